@@ -3,134 +3,116 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use futures::StreamExt as FuturesStreamExt;
-use futures_util::SinkExt;
 use reqwest::multipart;
 use std::path::Path;
+use std::sync::OnceLock;
 use streamkit_api::{
-    AudioAsset, BatchOperation, MessageType, PermissionsInfo, Request, RequestPayload, Response,
-    ResponsePayload, SamplePipeline, SavePipelineRequest,
+    AudioAsset, BatchOperation, PermissionsInfo, RequestPayload, ResponsePayload, SamplePipeline,
+    SavePipelineRequest,
 };
+use streamkit_client_sdk::url::http_base_url;
+use streamkit_client_sdk::{Client, ClientOptions, ReconnectPolicy, TlsOptions};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_util::io::ReaderStream;
 use tracing::{debug, error, info};
-use url::Url;
-
-fn http_base_url(server_url: &str) -> Result<Url, Box<dyn std::error::Error + Send + Sync>> {
-    let mut url = Url::parse(server_url)?;
-    match url.scheme() {
-        "http" | "https" => {},
-        "ws" => {
-            url.set_scheme("http")
-                .map_err(|()| "Failed to convert ws:// to http:// for server URL")?;
-        },
-        "wss" => {
-            url.set_scheme("https")
-                .map_err(|()| "Failed to convert wss:// to https:// for server URL")?;
-        },
-        _ => return Err("Server URL must be http(s) or ws(s)".into()),
+
+/// Read size used when streaming the oneshot input file to the server.
+const MEDIA_UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// TLS settings from `--cert`/`--key`/`--ca`, used by every connection to the server.
+static TLS: OnceLock<TlsOptions> = OnceLock::new();
+
+/// Sets the client certificate, key and extra CA certificate (all PEM files) presented to
+/// the server by every later HTTP and WebSocket connection.
+///
+/// # Errors
+///
+/// Returns an error if only one of `cert` and `key` is given, a file cannot be read, or the
+/// TLS backend rejects the certificates.
+pub fn configure_tls(
+    cert: Option<&Path>,
+    key: Option<&Path>,
+    ca: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if cert.is_some() != key.is_some() {
+        return Err("--cert and --key must be given together".into());
     }
-    url.set_path("");
-    url.set_query(None);
-    url.set_fragment(None);
-    Ok(url)
+    let read = |path: Option<&Path>| {
+        path.map(|path| {
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+        })
+        .transpose()
+    };
+    let tls = TlsOptions {
+        ca_certificate_pem: read(ca)?,
+        client_certificate_pem: read(cert)?,
+        client_key_pem: read(key)?,
+    };
+
+    // Reject unusable certificates up front rather than on the first request
+    build_http_client(&tls)?;
+    let _ = TLS.set(tls);
+    Ok(())
+}
+
+fn tls_options() -> TlsOptions {
+    TLS.get().cloned().unwrap_or_default()
 }
 
-fn control_ws_url(server_url: &str) -> Result<Url, Box<dyn std::error::Error + Send + Sync>> {
-    let mut ws_url = Url::parse(server_url)?;
-    match ws_url.scheme() {
-        "http" => ws_url
-            .set_scheme("ws")
-            .map_err(|()| "Failed to convert http:// to ws:// for server URL")?,
-        "https" => ws_url
-            .set_scheme("wss")
-            .map_err(|()| "Failed to convert https:// to wss:// for server URL")?,
-        "ws" | "wss" => {},
-        _ => return Err("Server URL must be http(s) or ws(s)".into()),
+fn build_http_client(tls: &TlsOptions) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(ca) = &tls.ca_certificate_pem {
+        builder = builder.add_root_certificate(reqwest::Certificate::from_pem(ca)?);
     }
-    ws_url.set_path("/api/v1/control");
-    ws_url.set_query(None);
-    ws_url.set_fragment(None);
-    Ok(ws_url)
+    if let (Some(cert), Some(key)) = (&tls.client_certificate_pem, &tls.client_key_pem) {
+        builder = builder.identity(reqwest::Identity::from_pkcs8_pem(cert, key)?);
+    }
+    builder.build()
 }
 
-async fn recv_response_ignoring_events(
-    ws_stream: &mut tokio_tungstenite::WebSocketStream<
-        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-    >,
-    expected_correlation_id: &str,
-) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
-    use serde_json::Value;
-    use tokio_tungstenite::tungstenite::protocol::Message as WsMessage;
+/// HTTP client presenting the certificates set by [`configure_tls`].
+///
+/// # Errors
+///
+/// Returns an error if the TLS backend cannot be initialized.
+pub fn http_client() -> Result<reqwest::Client, reqwest::Error> {
+    build_http_client(&tls_options())
+}
 
-    loop {
-        match ws_stream.next().await {
-            Some(Ok(WsMessage::Text(text))) => {
-                let v: Value = serde_json::from_str(&text)?;
-                if v.get("type").and_then(|t| t.as_str()) == Some("response") {
-                    let response: Response = serde_json::from_str(&text)?;
-                    if let Some(cid) = &response.correlation_id {
-                        if cid == expected_correlation_id {
-                            return Ok(response);
-                        }
-                        // Ignore responses for other requests - continue to next iteration
-                    } else {
-                        // No correlation id - accept it
-                        return Ok(response);
-                    }
-                }
-                // Ignore events and unknown message types - continue to next iteration
-            },
-            Some(Ok(_)) => {
-                // Non-text message; ignore and continue
-            },
-            Some(Err(e)) => return Err(e.into()),
-            None => return Err("WebSocket closed before receiving response".into()),
-        }
+/// Control connection options for a CLI command: no reconnects, configured TLS.
+pub(crate) fn control_options() -> ClientOptions {
+    ClientOptions {
+        reconnect: ReconnectPolicy::disabled(),
+        tls: tls_options(),
+        ..ClientOptions::default()
     }
 }
 
+/// Opens a short-lived control connection for a single CLI command.
+pub(crate) async fn control_client(
+    server_url: &str,
+) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(Client::connect_with(server_url, control_options()).await?)
+}
+
 async fn ws_request(
     server_url: &str,
     payload: RequestPayload,
 ) -> Result<ResponsePayload, Box<dyn std::error::Error + Send + Sync>> {
-    let ws_url = control_ws_url(server_url)?.to_string();
-    let (mut ws_stream, _) = connect_async(ws_url).await?;
-
-    let req = Request {
-        message_type: MessageType::Request,
-        correlation_id: Some(uuid::Uuid::new_v4().to_string()),
-        payload,
-    };
-    let req_json = serde_json::to_string(&req)?;
-    ws_stream.send(Message::Text(req_json.into())).await?;
-
-    #[allow(clippy::unwrap_used)] // correlation_id is always Some() as set above
-    let correlation_id = req.correlation_id.clone().unwrap();
-    let response = recv_response_ignoring_events(&mut ws_stream, &correlation_id).await?;
-    ws_stream.close(None).await?;
-
-    match response.payload {
-        ResponsePayload::Error { message } => Err(message.into()),
-        other => Ok(other),
-    }
+    let client = control_client(server_url).await?;
+    let response = client.request(payload).await;
+    client.close().await;
+    Ok(response?)
 }
 
 async fn ws_send_fire_and_forget(
     server_url: &str,
     payload: RequestPayload,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ws_url = control_ws_url(server_url)?.to_string();
-    let (mut ws_stream, _) = connect_async(ws_url).await?;
-
-    let req = Request {
-        message_type: MessageType::Request,
-        correlation_id: Some(uuid::Uuid::new_v4().to_string()),
-        payload,
-    };
-    let req_json = serde_json::to_string(&req)?;
-    ws_stream.send(Message::Text(req_json.into())).await?;
-    ws_stream.close(None).await?;
+    let client = control_client(server_url).await?;
+    client.send(payload).await?;
+    client.close().await;
     Ok(())
 }
 
@@ -171,7 +153,7 @@ pub async fn process_oneshot(
     output_path: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     process_oneshot_with_client(&client, pipeline_path, input_path, output_path, server_url).await
 }
 
@@ -216,9 +198,10 @@ pub async fn process_oneshot_with_client(
     debug!("Reading pipeline configuration from {pipeline_path}");
     let pipeline_content = fs::read_to_string(pipeline_path).await?;
 
-    // Read input media file
-    debug!("Reading input media file from {input_path}");
-    let media_data = fs::read(input_path).await?;
+    // Open input media file; it is streamed into the request rather than read into memory
+    debug!("Opening input media file {input_path}");
+    let media_file = fs::File::open(input_path).await?;
+    let media_len = media_file.metadata().await?.len();
 
     // Extract filename for the multipart form
     let input_filename = Path::new(input_path)
@@ -228,11 +211,15 @@ pub async fn process_oneshot_with_client(
         .to_string();
 
     // Create multipart form
-    let media_len = media_data.len();
     debug!("Creating multipart form with {media_len} bytes of media data");
-    let form = multipart::Form::new()
-        .text("config", pipeline_content)
-        .part("media", multipart::Part::bytes(media_data).file_name(input_filename));
+    let media_body = reqwest::Body::wrap_stream(ReaderStream::with_capacity(
+        media_file,
+        MEDIA_UPLOAD_CHUNK_SIZE,
+    ));
+    let form = multipart::Form::new().text("config", pipeline_content).part(
+        "media",
+        multipart::Part::stream_with_length(media_body, media_len).file_name(input_filename),
+    );
 
     // Send request to server
     let url = http_base_url(server_url)?.join("/api/v1/process")?;
@@ -320,7 +307,7 @@ pub async fn create_session(
     let request_body = CreateSessionRequest { name: name.clone(), yaml: pipeline_content };
 
     // Send HTTP POST request
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/sessions")?;
 
     info!("Sending HTTP POST request to {url}");
@@ -480,14 +467,17 @@ pub async fn tune_node(
 #[allow(clippy::cognitive_complexity)]
 pub async fn list_sessions(
     server_url: &str,
+    tags: Vec<String>,
+    search: Option<String>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!(
         server = %server_url,
         "Listing active sessions"
     );
 
-    match ws_request(server_url, RequestPayload::ListSessions).await? {
-        ResponsePayload::SessionsListed { sessions } => {
+    let query = streamkit_api::SessionListQuery { tags, search, ..Default::default() };
+    match ws_request(server_url, RequestPayload::ListSessions { query: Some(query) }).await? {
+        ResponsePayload::SessionsListed { sessions, .. } => {
             let count = sessions.len();
             info!("Successfully retrieved {count} sessions");
 
@@ -495,12 +485,18 @@ pub async fn list_sessions(
                 println!("No active sessions found.");
             } else {
                 println!("Active Sessions:");
-                println!("{:<20} {:<36} STATUS", "NAME", "SESSION ID");
-                println!("{}", "-".repeat(70));
+                println!("{:<20} {:<36} {:<8} TAGS", "NAME", "SESSION ID", "STATUS");
+                println!("{}", "-".repeat(80));
 
                 for session in sessions {
                     let name = session.name.as_deref().unwrap_or("<unnamed>");
-                    println!("{:<20} {:<36} Running", name, session.id);
+                    println!(
+                        "{:<20} {:<36} {:<8} {}",
+                        name,
+                        session.id,
+                        "Running",
+                        session.tags.join(",")
+                    );
                 }
             }
         },
@@ -563,6 +559,8 @@ pub async fn control_add_node(
     node_id: &str,
     kind: &str,
     params: Option<&str>,
+    label: Option<String>,
+    notes: Option<String>,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let params = match params {
@@ -577,6 +575,8 @@ pub async fn control_add_node(
             node_id: node_id.to_string(),
             kind: kind.to_string(),
             params,
+            label,
+            notes,
         },
     )
     .await?
@@ -806,7 +806,7 @@ struct FrontendConfig {
 /// Returns an error if the server URL is invalid, the request fails, the server returns a
 /// non-success status, or the response cannot be parsed.
 pub async fn get_config(server_url: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/config")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -834,7 +834,7 @@ struct PermissionsResponse {
 pub async fn get_permissions(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/permissions")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -856,7 +856,7 @@ pub async fn get_permissions(
 pub async fn list_node_schemas(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/schema/nodes")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -878,7 +878,7 @@ pub async fn list_node_schemas(
 pub async fn list_packet_schemas(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/schema/packets")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -902,7 +902,7 @@ pub async fn get_pipeline(
     session_id: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url =
         http_base_url(server_url)?.join(&format!("/api/v1/sessions/{session_id}/pipeline"))?;
     let response = client.get(url).send().await?;
@@ -942,7 +942,7 @@ struct PluginSummary {
 pub async fn list_plugins(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/plugins")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -965,7 +965,7 @@ pub async fn upload_plugin(
     path: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let base = http_base_url(server_url)?;
     let url = base.join("/api/v1/plugins")?;
 
@@ -1002,7 +1002,7 @@ pub async fn delete_plugin(
     keep_file: bool,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let mut url = http_base_url(server_url)?.join(&format!("/api/v1/plugins/{kind}"))?;
     if keep_file {
         url.query_pairs_mut().append_pair("keep_file", "true");
@@ -1028,7 +1028,7 @@ pub async fn delete_plugin(
 pub async fn list_samples_oneshot(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/samples/oneshot")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -1050,7 +1050,7 @@ pub async fn list_samples_oneshot(
 pub async fn list_samples_dynamic(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/samples/dynamic")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -1074,7 +1074,7 @@ pub async fn get_sample(
     yaml_only: bool,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join(&format!("/api/v1/samples/oneshot/{id}"))?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -1114,7 +1114,7 @@ pub async fn save_sample(
         is_fragment: fragment,
     };
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/samples/oneshot")?;
     let response = client.post(url).json(&req).send().await?;
     if !response.status().is_success() {
@@ -1137,7 +1137,7 @@ pub async fn delete_sample(
     id: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join(&format!("/api/v1/samples/oneshot/{id}"))?;
     let response = client.delete(url).send().await?;
     if response.status().is_success() {
@@ -1158,7 +1158,7 @@ pub async fn delete_sample(
 pub async fn list_audio_assets(
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/assets/audio")?;
     let response = client.get(url).send().await?;
     if !response.status().is_success() {
@@ -1181,7 +1181,7 @@ pub async fn upload_audio_asset(
     path: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join("/api/v1/assets/audio")?;
 
     let file_path = Path::new(path);
@@ -1216,7 +1216,7 @@ pub async fn delete_audio_asset(
     id: &str,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let url = http_base_url(server_url)?.join(&format!("/api/v1/assets/audio/{id}"))?;
     let response = client.delete(url).send().await?;
     if response.status().is_success() {
@@ -1241,8 +1241,8 @@ pub async fn watch_events(
     pretty: bool,
     server_url: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ws_url = control_ws_url(server_url)?.to_string();
-    let (mut ws_stream, _) = connect_async(ws_url).await?;
+    let client = control_client(server_url).await?;
+    let mut events = client.events();
 
     eprintln!("Watching events (Ctrl-C to stop)...");
 
@@ -1251,23 +1251,11 @@ pub async fn watch_events(
             _ = tokio::signal::ctrl_c() => {
                 break;
             }
-            msg = ws_stream.next() => {
-                let Some(msg) = msg else {
+            event = events.next() => {
+                let Some(event) = event else {
                     break;
                 };
-                let msg = msg?;
-                let Message::Text(text) = msg else {
-                    continue;
-                };
-
-                let v: serde_json::Value = match serde_json::from_str(&text) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-
-                if v.get("type").and_then(|t| t.as_str()) != Some("event") {
-                    continue;
-                }
+                let v = serde_json::to_value(&event)?;
 
                 if let Some(filter) = session_filter {
                     let sid = v
@@ -1282,12 +1270,12 @@ pub async fn watch_events(
                 if pretty {
                     println!("{}", serde_json::to_string_pretty(&v)?);
                 } else {
-                    println!("{text}");
+                    println!("{v}");
                 }
             }
         }
     }
 
-    ws_stream.close(None).await?;
+    client.close().await;
     Ok(())
 }
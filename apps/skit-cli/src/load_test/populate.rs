@@ -11,7 +11,7 @@ use tracing::{info, warn};
 use crate::load_test::config::LoadTestConfig;
 
 pub async fn populate_environment(config: &LoadTestConfig) -> Result<()> {
-    let client = reqwest::Client::new();
+    let client = crate::client::http_client()?;
 
     // Load native plugins
     for plugin_path in &config.populate.plugins_native {
@@ -26,7 +26,13 @@ pub async fn oneshot_worker(
 ) {
     debug!("OneShot worker {} started", worker_id);
 
-    let client = reqwest::Client::new();
+    let client = match crate::client::http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("OneShot worker {} failed to create HTTP client: {}", worker_id, e);
+            return;
+        },
+    };
 
     let pipeline_path = &config.oneshot.pipeline;
     let input_path = &config.oneshot.input_file;
@@ -278,7 +284,6 @@ async fn create_session_with_pipeline(
     server_url: &str,
     run_id: &str,
 ) -> Result<(String, Vec<String>), Box<dyn std::error::Error + Send + Sync>> {
-    use reqwest;
     use serde::{Deserialize, Serialize};
     use tokio::fs;
 
@@ -315,7 +320,7 @@ async fn create_session_with_pipeline(
     let request = CreateSessionRequest { yaml, name: Some(session_name.to_string()) };
 
     // Send HTTP POST to /api/v1/sessions
-    let client = reqwest::Client::new();
+    let client = crate::client::http_client()?;
     let url = format!("{server_url}/api/v1/sessions");
     let response = client.post(&url).json(&request).send().await?;
 
@@ -361,7 +366,7 @@ pub async fn create_broadcaster_session(
 
     let request = CreateSessionRequest { yaml, name: Some(session_name.to_string()) };
 
-    let client = reqwest::Client::new();
+    let client = crate::client::http_client()?;
     let url = format!("{server_url}/api/v1/sessions");
     let response = client.post(&url).json(&request).send().await?;
 
@@ -547,7 +552,7 @@ pub async fn session_tuner_worker(
                     if control_ws.is_none() {
                         control_ws = ControlWs::connect(&config.server.url).await.ok();
                     }
-                    match control_ws.as_mut() {
+                    match control_ws.as_ref() {
                         Some(ws) => {
                             if session.tunable_node_ids.is_empty() {
                                 Err("No tunable nodes found for this session".into())
@@ -623,7 +628,7 @@ pub async fn cleanup_sessions(
             if control_ws.is_none() {
                 control_ws = ControlWs::connect(server_url).await.ok();
             }
-            match control_ws.as_mut() {
+            match control_ws.as_ref() {
                 Some(ws) => ws.destroy_session(&session_id).await,
                 None => Err("Failed to connect to control WebSocket".into()),
             }
@@ -651,130 +656,42 @@ pub async fn cleanup_sessions(
     debug!("Session cleanup complete");
 }
 
-async fn recv_response_ignoring_events(
-    ws_stream: &mut tokio_tungstenite::WebSocketStream<
-        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-    >,
-    expected_correlation_id: &str,
-) -> Result<streamkit_api::Response, Box<dyn std::error::Error + Send + Sync>> {
-    use futures::StreamExt as FuturesStreamExt;
-    use serde_json::Value;
-    use tokio_tungstenite::tungstenite::protocol::Message as WsMessage;
-
-    loop {
-        match ws_stream.next().await {
-            Some(Ok(WsMessage::Text(text))) => {
-                let v: Value = serde_json::from_str(&text)?;
-                if v.get("type").and_then(|t| t.as_str()) == Some("response") {
-                    let response: streamkit_api::Response = serde_json::from_str(&text)?;
-                    if let Some(cid) = &response.correlation_id {
-                        if cid == expected_correlation_id {
-                            return Ok(response);
-                        }
-                    } else {
-                        return Ok(response);
-                    }
-                }
-            },
-            Some(Ok(_)) => {},
-            Some(Err(e)) => return Err(e.into()),
-            None => return Err("WebSocket closed before receiving response".into()),
-        }
-    }
-}
-
 struct ControlWs {
-    ws_stream: tokio_tungstenite::WebSocketStream<
-        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-    >,
+    client: streamkit_client_sdk::Client,
 }
 
 impl ControlWs {
     async fn connect(server_url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        use tokio_tungstenite::connect_async;
-        use url::Url;
-
-        let mut ws_url = Url::parse(server_url)?;
-        #[allow(clippy::unwrap_used)]
-        match ws_url.scheme() {
-            "http" => ws_url.set_scheme("ws").unwrap(),
-            "https" => ws_url.set_scheme("wss").unwrap(),
-            "ws" | "wss" => (),
-            _ => return Err("Server URL must be http(s) or ws(s)".into()),
-        }
-        ws_url.set_path("/api/v1/control");
-        let ws_url_str = ws_url.to_string();
-
-        let (ws_stream, _) = connect_async(ws_url_str).await?;
-        Ok(Self { ws_stream })
-    }
-
-    async fn send_request(
-        &mut self,
-        payload: streamkit_api::RequestPayload,
-    ) -> Result<streamkit_api::ResponsePayload, Box<dyn std::error::Error + Send + Sync>> {
-        use futures_util::SinkExt;
-        use streamkit_api::{MessageType, Request, ResponsePayload};
-        use tokio_tungstenite::tungstenite::protocol::Message;
-
-        let req = Request {
-            message_type: MessageType::Request,
-            correlation_id: Some(uuid::Uuid::new_v4().to_string()),
-            payload,
-        };
-
-        let req_json = serde_json::to_string(&req)?;
-        self.ws_stream.send(Message::Text(req_json.into())).await?;
-
-        #[allow(clippy::unwrap_used)]
-        let correlation_id = req.correlation_id.clone().unwrap();
-        let response = recv_response_ignoring_events(&mut self.ws_stream, &correlation_id).await?;
-        match response.payload {
-            ResponsePayload::Error { message } => Err(message.into()),
-            other => Ok(other),
-        }
+        // Workers reconnect themselves after a failure so each retry is measured.
+        let options = crate::client::control_options();
+        Ok(Self { client: streamkit_client_sdk::Client::connect_with(server_url, options).await? })
     }
 
     async fn destroy_session(
-        &mut self,
+        &self,
         session_id: &str,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use streamkit_api::{RequestPayload, ResponsePayload};
-
-        match self
-            .send_request(RequestPayload::DestroySession { session_id: session_id.to_string() })
-            .await?
-        {
-            ResponsePayload::SessionDestroyed { .. } | ResponsePayload::Success => Ok(()),
-            _ => Err("Unexpected response from server".into()),
-        }
+        Ok(self.client.destroy_session(session_id).await?)
     }
 
     async fn tune_node(
-        &mut self,
+        &self,
         session_id: &str,
         node_id: &str,
         param: &str,
         value: &str,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use streamkit_api::{NodeControlMessage, RequestPayload, ResponsePayload};
+        use streamkit_api::NodeControlMessage;
 
         let param_value: serde_json::Value = serde_saphyr::from_str(value)?;
         let mut params = serde_json::Map::new();
         params.insert(param.to_string(), param_value);
         let update_params = serde_json::Value::Object(params);
 
-        match self
-            .send_request(RequestPayload::TuneNode {
-                session_id: session_id.to_string(),
-                node_id: node_id.to_string(),
-                message: NodeControlMessage::UpdateParams(update_params),
-            })
-            .await?
-        {
-            ResponsePayload::Success => Ok(()),
-            _ => Err("Unexpected response from server".into()),
-        }
+        Ok(self
+            .client
+            .tune_node(session_id, node_id, NodeControlMessage::UpdateParams(update_params))
+            .await?)
     }
 }
 
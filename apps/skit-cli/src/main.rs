@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use tracing::{error, info};
 
 #[derive(Parser, Debug)]
@@ -10,6 +11,15 @@ use tracing::{error, info};
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Client certificate (PEM) for servers that require mutual TLS
+    #[arg(long, global = true, value_name = "PATH", requires = "key")]
+    cert: Option<PathBuf>,
+    /// Private key (PKCS#8 PEM) for --cert
+    #[arg(long, global = true, value_name = "PATH", requires = "cert")]
+    key: Option<PathBuf>,
+    /// Extra CA certificate (PEM) trusted for the server certificate
+    #[arg(long, global = true, value_name = "PATH")]
+    ca: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -65,6 +75,12 @@ enum Commands {
         /// Server URL (default: http://127.0.0.1:4545)
         #[arg(short, long, default_value = "http://127.0.0.1:4545")]
         server: String,
+        /// Only list sessions carrying this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Case-insensitive search on session id, name and tags
+        #[arg(long)]
+        search: Option<String>,
     },
     /// Start an interactive shell session
     Shell {
@@ -268,6 +284,12 @@ enum ControlCommands {
         /// Optional params as JSON or YAML (object)
         #[arg(long)]
         params: Option<String>,
+        /// Display name shown instead of the node ID
+        #[arg(long)]
+        label: Option<String>,
+        /// Free-form notes about the node
+        #[arg(long)]
+        notes: Option<String>,
     },
     /// Remove a node from a session (WS action: removenode)
     RemoveNode {
@@ -328,6 +350,15 @@ async fn main() {
 
     let cli = Cli::parse();
 
+    if let Err(e) = streamkit_client::client::configure_tls(
+        cli.cert.as_deref(),
+        cli.key.as_deref(),
+        cli.ca.as_deref(),
+    ) {
+        error!(error = %e, "Invalid TLS options");
+        std::process::exit(2);
+    }
+
     match cli.command {
         Commands::OneShot { pipeline, input, output, server } => {
             info!("Starting StreamKit client - oneshot processing");
@@ -369,10 +400,10 @@ async fn main() {
                 std::process::exit(1);
             }
         },
-        Commands::List { server } => {
+        Commands::List { server, tags, search } => {
             info!("Starting StreamKit client - listing sessions");
 
-            if let Err(e) = streamkit_client::list_sessions(&server).await {
+            if let Err(e) = streamkit_client::list_sessions(&server, tags, search).await {
                 // Error already logged via tracing above
                 error!(error = %e, "Failed to list sessions");
                 std::process::exit(1);
@@ -512,12 +543,14 @@ async fn main() {
                 ControlCommands::Pipeline { session_id } => {
                     streamkit_client::control_get_pipeline(&session_id, &server).await
                 },
-                ControlCommands::AddNode { session_id, node_id, kind, params } => {
+                ControlCommands::AddNode { session_id, node_id, kind, params, label, notes } => {
                     streamkit_client::control_add_node(
                         &session_id,
                         &node_id,
                         &kind,
                         params.as_deref(),
+                        label,
+                        notes,
                         &server,
                     )
                     .await
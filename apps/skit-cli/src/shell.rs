@@ -2,7 +2,6 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
-use futures_util::{SinkExt, StreamExt};
 use rustyline::completion::{Completer, FilenameCompleter, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::{CmdKind, Highlighter};
@@ -13,10 +12,8 @@ use rustyline::Helper;
 use rustyline::{Cmd, CompletionType, Config, EditMode, Editor, KeyEvent};
 use std::borrow::Cow::{self, Borrowed, Owned};
 use std::collections::HashSet;
-use streamkit_api::{MessageType, Request, RequestPayload, Response, ResponsePayload, SessionInfo};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use streamkit_api::SessionInfo;
 use tracing::{debug, warn};
-use url::Url;
 
 struct SkitHelper {
     completer: SkitCompleter,
@@ -230,28 +227,8 @@ impl Shell {
     /// - The server URL cannot be parsed
     /// - The URL scheme is not http(s) or ws(s)
     /// - Editor initialization fails
-    ///
-    /// # Panics
-    ///
-    /// Panics if URL scheme conversion fails (unreachable in practice as "ws" and "wss" are always valid)
     pub fn new(server_url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Parse server URL to convert to ws:// or wss://
-        let mut ws_url = Url::parse(server_url)?;
-        match ws_url.scheme() {
-            // set_scheme only fails for invalid schemes, but "ws" and "wss" are always valid
-            // Using expect is justified here: these are hardcoded valid schemes
-            #[allow(clippy::expect_used)]
-            "http" => {
-                ws_url.set_scheme("ws").expect("ws is a valid URL scheme");
-            },
-            #[allow(clippy::expect_used)]
-            "https" => {
-                ws_url.set_scheme("wss").expect("wss is a valid URL scheme");
-            },
-            "ws" | "wss" => (), // Scheme is already correct
-            _ => return Err("Server URL must be http(s) or ws(s)".into()),
-        }
-        ws_url.set_path("/api/v1/control");
+        let ws_url = streamkit_client_sdk::url::control_ws_url(server_url)?;
 
         let config = Config::builder()
             .history_ignore_space(true)
@@ -438,54 +415,10 @@ impl Shell {
     async fn fetch_sessions(
         &self,
     ) -> Result<Vec<SessionInfo>, Box<dyn std::error::Error + Send + Sync>> {
-        let (mut ws_stream, _) = connect_async(&self.ws_url).await?;
-
-        let list_sessions_req = Request {
-            message_type: MessageType::Request,
-            correlation_id: Some(uuid::Uuid::new_v4().to_string()),
-            payload: RequestPayload::ListSessions,
-        };
-        let req_json = serde_json::to_string(&list_sessions_req)?;
-        ws_stream.send(Message::Text(req_json.into())).await?;
-
-        // Read until we get a response (ignore any interleaved events)
-        let sessions = loop {
-            match ws_stream.next().await {
-                Some(Ok(Message::Text(res_text))) => {
-                    // Peek at message type
-                    let v: serde_json::Value = serde_json::from_str(&res_text)?;
-                    if v.get("type").and_then(|t| t.as_str()) == Some("response") {
-                        let response: Response = serde_json::from_str(&res_text)?;
-                        match response.payload {
-                            ResponsePayload::SessionsListed { sessions } => break sessions,
-                            ResponsePayload::Error { message } => {
-                                ws_stream.close(None).await?;
-                                return Err(message.into());
-                            },
-                            _ => {
-                                ws_stream.close(None).await?;
-                                return Err("Unexpected response from server".into());
-                            },
-                        }
-                    }
-                    // Ignore events and unknown message types while waiting for response
-                },
-                Some(Ok(_)) => {
-                    // Non-text message; ignore and continue
-                },
-                Some(Err(e)) => {
-                    ws_stream.close(None).await?;
-                    return Err(e.into());
-                },
-                None => {
-                    ws_stream.close(None).await?;
-                    return Err("Did not receive list sessions response".into());
-                },
-            }
-        };
-
-        ws_stream.close(None).await?;
-        Ok(sessions)
+        let client = crate::client::control_client(&self.ws_url).await?;
+        let page = client.list_sessions(None).await;
+        client.close().await;
+        Ok(page?.sessions)
     }
 
     async fn create_session(
@@ -3,10 +3,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, Path, State},
+    extract::{DefaultBodyLimit, Multipart, Path, Request, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
-    routing::{delete, get},
+    routing::get,
     Json, Router,
 };
 use std::path::PathBuf;
@@ -18,10 +18,10 @@ use tracing::{debug, error, info, warn};
 use crate::permissions::Permissions as RolePermissions;
 use crate::role_extractor::get_permissions;
 use crate::state::AppState;
+use crate::upload_limits::UploadLimitError;
 use streamkit_api::AudioAsset;
 
 // Security limits
-const MAX_AUDIO_FILE_SIZE: usize = 100 * 1024 * 1024; // 100MB
 const MAX_FILENAME_LENGTH: usize = 255;
 
 // Allowed audio formats
@@ -233,7 +233,9 @@ async fn list_assets(
     Ok(all_assets)
 }
 
-/// Stream an uploaded multipart field to disk with size enforcement.
+/// Stream an uploaded multipart field to disk.
+///
+/// Size and stall limits are enforced on the request body by the upload guard.
 async fn write_upload_stream_to_disk(
     mut field: axum::extract::multipart::Field<'_>,
     file_path: &std::path::Path,
@@ -253,10 +255,6 @@ async fn write_upload_stream_to_disk(
         match field.chunk().await {
             Ok(Some(chunk)) => {
                 total_bytes = total_bytes.saturating_add(chunk.len());
-                if total_bytes > MAX_AUDIO_FILE_SIZE {
-                    let _ = fs::remove_file(file_path).await;
-                    return Err(AssetsError::FileTooLarge(MAX_AUDIO_FILE_SIZE));
-                }
 
                 if let Err(e) = file.write_all(&chunk).await {
                     let _ = fs::remove_file(file_path).await;
@@ -266,9 +264,7 @@ async fn write_upload_stream_to_disk(
             Ok(None) => break,
             Err(e) => {
                 let _ = fs::remove_file(file_path).await;
-                return Err(AssetsError::InvalidRequest(format!(
-                    "Failed to read upload stream: {e}"
-                )));
+                return Err(multipart_read_error(&e, "Failed to read upload stream"));
             },
         }
     }
@@ -286,6 +282,17 @@ async fn write_upload_stream_to_disk(
     Ok(total_bytes)
 }
 
+/// Maps a multipart read failure to an upload limit error when a limit caused it.
+fn multipart_read_error(
+    err: &axum::extract::multipart::MultipartError,
+    context: &str,
+) -> AssetsError {
+    UploadLimitError::find(err).map_or_else(
+        || AssetsError::InvalidRequest(format!("{context}: {err}")),
+        |limit| AssetsError::UploadLimit(limit.clone()),
+    )
+}
+
 /// Build AudioAsset response for uploaded file
 fn build_upload_response(
     filename: &str,
@@ -352,10 +359,7 @@ pub async fn upload_asset_handler(
         Ok(None) => {
             return AssetsError::InvalidRequest("No file provided".to_string()).into_response()
         },
-        Err(e) => {
-            return AssetsError::InvalidRequest(format!("Failed to read multipart: {e}"))
-                .into_response()
-        },
+        Err(e) => return multipart_read_error(&e, "Failed to read multipart").into_response(),
     };
 
     let filename = match field.file_name() {
@@ -378,27 +382,67 @@ pub async fn upload_asset_handler(
     }
 }
 
-/// Validate that a file path is within the user directory (security check)
-fn validate_file_in_user_directory(
+/// Validate that a file path is within the given asset directory (security check)
+fn validate_file_in_directory(
     file_path: &std::path::Path,
-    user_dir: &std::path::Path,
+    dir: &std::path::Path,
 ) -> Result<(), AssetsError> {
     let canonical = file_path
         .canonicalize()
         .map_err(|e| AssetsError::IoError(format!("Failed to resolve file path: {e}")))?;
 
-    let canonical_user_dir = user_dir
+    let canonical_dir = dir
         .canonicalize()
-        .map_err(|_| AssetsError::IoError("Failed to resolve user directory".to_string()))?;
+        .map_err(|_| AssetsError::IoError("Failed to resolve asset directory".to_string()))?;
 
-    if !canonical.starts_with(&canonical_user_dir) {
-        error!("Attempt to delete non-user asset: {:?}", canonical);
+    if !canonical.starts_with(&canonical_dir) {
+        error!("Asset path escapes {:?}: {:?}", canonical_dir, canonical);
         return Err(AssetsError::Forbidden);
     }
 
     Ok(())
 }
 
+/// Download an audio asset (system or user).
+///
+/// Supports `Range` requests, so players can seek and interrupted downloads can resume.
+pub async fn download_asset_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    request: Request,
+) -> Response {
+    let perms = get_permissions(&headers, &app_state);
+
+    if let Err(e) = validate_audio_filename(&id) {
+        return e.into_response();
+    }
+
+    let base_path = PathBuf::from("samples/audio");
+    for source in ["system", "user"] {
+        let dir = base_path.join(source);
+        let file_path = dir.join(&id);
+        if !file_path.is_file() {
+            continue;
+        }
+
+        // Assets hidden from the listing are reported as missing rather than forbidden.
+        if !perms.is_asset_allowed(&format!("samples/audio/{source}/{id}")) {
+            debug!("Asset download filtered by permissions: {}/{}", source, id);
+            return AssetsError::NotFound(id).into_response();
+        }
+
+        if let Err(e) = validate_file_in_directory(&file_path, &dir) {
+            return e.into_response();
+        }
+
+        debug!("Serving audio asset: {}/{}", source, id);
+        return crate::downloads::serve_file(&file_path, None, request).await;
+    }
+
+    AssetsError::NotFound(id).into_response()
+}
+
 /// Delete audio file and its associated license file
 async fn delete_audio_files(
     file_path: &std::path::Path,
@@ -445,7 +489,7 @@ pub async fn delete_asset_handler(
         return AssetsError::NotFound(id).into_response();
     }
 
-    if let Err(e) = validate_file_in_user_directory(&file_path, &user_dir) {
+    if let Err(e) = validate_file_in_directory(&file_path, &user_dir) {
         return e.into_response();
     }
 
@@ -465,9 +509,13 @@ pub fn assets_router() -> Router<Arc<AppState>> {
             "/api/v1/assets/audio",
             get(list_assets_handler)
                 .post(upload_asset_handler)
-                .layer(DefaultBodyLimit::max(MAX_AUDIO_FILE_SIZE)),
+                // Size is enforced by the upload guard from `[server].max_body_size`.
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/api/v1/assets/audio/{id}",
+            get(download_asset_handler).delete(delete_asset_handler),
         )
-        .route("/api/v1/assets/audio/{id}", delete(delete_asset_handler))
 }
 
 // Error types
@@ -478,7 +526,7 @@ pub enum AssetsError {
     InvalidFilename(String),
     InvalidFormat(String),
     InvalidRequest(String),
-    FileTooLarge(usize),
+    UploadLimit(UploadLimitError),
     FileExists(String),
     NotFound(String),
     Forbidden,
@@ -491,10 +539,7 @@ impl IntoResponse for AssetsError {
             Self::InvalidFilename(msg) | Self::InvalidFormat(msg) | Self::InvalidRequest(msg) => {
                 (StatusCode::BAD_REQUEST, msg)
             },
-            Self::FileTooLarge(max) => (
-                StatusCode::PAYLOAD_TOO_LARGE,
-                format!("File too large. Maximum size: {max} bytes"),
-            ),
+            Self::UploadLimit(e) => return e.into_response(),
             Self::FileExists(filename) => {
                 (StatusCode::CONFLICT, format!("File already exists: {filename}"))
             },
@@ -513,7 +558,7 @@ impl std::fmt::Display for AssetsError {
             Self::InvalidFilename(msg) => write!(f, "Invalid filename: {msg}"),
             Self::InvalidFormat(msg) => write!(f, "Invalid format: {msg}"),
             Self::InvalidRequest(msg) => write!(f, "Invalid request: {msg}"),
-            Self::FileTooLarge(max) => write!(f, "File too large (max: {max} bytes)"),
+            Self::UploadLimit(e) => write!(f, "Upload rejected: {e}"),
             Self::FileExists(filename) => write!(f, "File exists: {filename}"),
             Self::NotFound(id) => write!(f, "Not found: {id}"),
             Self::Forbidden => write!(f, "Forbidden"),
@@ -125,6 +125,10 @@ fn add_synthetic_oneshot_nodes(defs: &mut Vec<NodeDefinition>) {
         }],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 
     defs.push(NodeDefinition {
@@ -143,6 +147,10 @@ fn add_synthetic_oneshot_nodes(defs: &mut Vec<NodeDefinition>) {
         outputs: vec![],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 }
 
@@ -630,6 +638,16 @@ fn render_node_page(def: &NodeDefinition) -> Result<String> {
 
     out.push_str(&format!("`kind`: `{}`\n\n", def.kind));
 
+    if def.deprecated {
+        out.push_str("> [!CAUTION]\n");
+        out.push_str("> This node kind is deprecated and may be removed in a future release.\n\n");
+    }
+
+    if !def.aliases.is_empty() {
+        let aliases: Vec<String> = def.aliases.iter().map(|a| format!("`{a}`")).collect();
+        out.push_str(&format!("Former names: {}\n\n", aliases.join(", ")));
+    }
+
     // Add description as prose if present
     if let Some(desc) = &def.description {
         out.push_str(desc);
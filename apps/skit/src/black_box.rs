@@ -0,0 +1,440 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Black-box recording of sessions, for postmortems.
+//!
+//! A recording captures what happened to one session, minus media: the control requests it
+//! received along with their responses, every event it emitted (node state transitions,
+//! stats, telemetry, pipeline changes) and any gaps where the recorder fell behind. Entries
+//! are gzip-compressed JSON Lines, written to `<session_id>.jsonl.gz.part` in the configured
+//! directory and renamed to `<session_id>.jsonl.gz` once the session is gone.
+//!
+//! The file is flushed every second, so after a crash the `.part` file still holds all but
+//! the last moments. Sensitive node params are masked before anything reaches disk.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, SystemTime};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use streamkit_api::{
+    BatchOperation, Event as ApiEvent, EventPayload, Pipeline, RequestPayload, ResponsePayload,
+};
+use streamkit_core::control::NodeControlMessage;
+use streamkit_core::redaction::{self, REDACTED};
+use streamkit_core::registry::NodeRegistry;
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+};
+use tokio::sync::{mpsc, Mutex};
+use tracing::{info, warn};
+
+use crate::config::BlackBoxConfig;
+use crate::session::system_time_to_rfc3339;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+/// Requests waiting to be written. When the recorder is this far behind, further requests
+/// are counted as a gap instead of stalling request handling.
+const REQUEST_QUEUE_CAPACITY: usize = 256;
+const EXTENSION: &str = ".jsonl.gz";
+const PART_EXTENSION: &str = ".jsonl.gz.part";
+
+/// Recordings currently being written; lets request handling skip the session lookup when
+/// nothing is recorded.
+static ACTIVE_RECORDINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns true while at least one session is being recorded.
+pub fn any_active() -> bool {
+    ACTIVE_RECORDINGS.load(Ordering::Relaxed) > 0
+}
+
+/// One line of a recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// When the entry was captured (RFC 3339)
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "lowercase")]
+pub enum Record {
+    /// The recorded session; always the first entry.
+    Start(Header),
+    /// A control request and its response (absent for fire-and-forget requests).
+    Request {
+        role: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+        request: RequestPayload,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        response: Option<ResponsePayload>,
+    },
+    /// An event concerning the session.
+    Event { event: EventPayload },
+    /// Entries lost because the recorder fell behind. `events` counts server-wide events,
+    /// so some of them may have concerned other sessions.
+    Gap { events: u64, requests: u64 },
+    /// The session is gone and the recording is complete; always the last entry.
+    End,
+}
+
+/// Identity of a recorded session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    pub session_id: String,
+    pub name: Option<String>,
+    /// Role that created the session; governs who may download the recording
+    pub created_by: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Handle to a session's recording, held by the session.
+///
+/// The recording is finalized once every handle is dropped, i.e. when the session has been
+/// destroyed and the last request touching it has been recorded.
+pub struct BlackBox {
+    session_id: String,
+    requests: mpsc::Sender<Entry>,
+    dropped_requests: Arc<AtomicU64>,
+}
+
+impl BlackBox {
+    /// Creates the recording file and starts the recorder task.
+    ///
+    /// The event subscription is taken before this returns, so every event broadcast
+    /// afterwards (starting with `SessionCreated`) is captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording directory or file cannot be created.
+    pub fn start(
+        config: &BlackBoxConfig,
+        header: Header,
+        registry: Arc<RwLock<NodeRegistry>>,
+        pipeline: Weak<Mutex<Pipeline>>,
+        event_tx: &broadcast::Sender<ApiEvent>,
+    ) -> io::Result<Self> {
+        let directory = Path::new(&config.directory);
+        fs::create_dir_all(directory)?;
+        prune(directory, config.max_recordings);
+
+        let session_id = header.session_id.clone();
+        let mut writer = Writer::create(directory, &session_id)?;
+        writer.write(&now(Record::Start(header)))?;
+
+        let (requests, requests_rx) = mpsc::channel(REQUEST_QUEUE_CAPACITY);
+        let dropped_requests = Arc::new(AtomicU64::new(0));
+        let recorder = Recorder {
+            session_id: session_id.clone(),
+            writer,
+            redactor: Redactor { registry, pipeline },
+            dropped_requests: Arc::clone(&dropped_requests),
+        };
+        ACTIVE_RECORDINGS.fetch_add(1, Ordering::Relaxed);
+        tokio::spawn(recorder.run(requests_rx, event_tx.subscribe()));
+
+        info!(session_id = %session_id, "Black-box recording started");
+        Ok(Self { session_id, requests, dropped_requests })
+    }
+
+    /// Queues a handled control request for the recording.
+    pub fn record_request(
+        &self,
+        role: &str,
+        correlation_id: Option<String>,
+        request: RequestPayload,
+        response: Option<ResponsePayload>,
+    ) {
+        let entry =
+            now(Record::Request { role: role.to_string(), correlation_id, request, response });
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.requests.try_send(entry) {
+            if self.dropped_requests.fetch_add(1, Ordering::Relaxed) == 0 {
+                warn!(
+                    session_id = %self.session_id,
+                    "Black-box recorder fell behind; dropping requests"
+                );
+            }
+        }
+    }
+}
+
+fn now(record: Record) -> Entry {
+    Entry { timestamp: system_time_to_rfc3339(SystemTime::now()), record }
+}
+
+/// Finds a session's recording. Returns its path and whether it is complete; an incomplete
+/// recording belongs to a session that is still running or was cut short by a crash.
+pub fn locate(config: &BlackBoxConfig, session_id: &str) -> Option<(PathBuf, bool)> {
+    // Session IDs are UUIDs; anything else could escape the recording directory.
+    uuid::Uuid::parse_str(session_id).ok()?;
+    let directory = Path::new(&config.directory);
+    let finished = directory.join(format!("{session_id}{EXTENSION}"));
+    if finished.is_file() {
+        return Some((finished, true));
+    }
+    let part = directory.join(format!("{session_id}{PART_EXTENSION}"));
+    part.is_file().then_some((part, false))
+}
+
+/// Reads the header from the start of a (compressed) recording.
+pub fn read_header(recording: impl io::Read) -> Option<Header> {
+    let mut line = String::new();
+    BufReader::new(GzDecoder::new(recording)).read_line(&mut line).ok()?;
+    match serde_json::from_str::<Entry>(&line).ok()?.record {
+        Record::Start(header) => Some(header),
+        _ => None,
+    }
+}
+
+/// Deletes the oldest finished recordings so that, counting the one about to start, at most
+/// `max_recordings` remain. Recordings in progress are never deleted.
+fn prune(directory: &Path, max_recordings: usize) {
+    if max_recordings == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    let mut finished: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(EXTENSION))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    if finished.len() < max_recordings {
+        return;
+    }
+    finished.sort_unstable();
+    let excess = finished.len() + 1 - max_recordings;
+    for (_, path) in finished.into_iter().take(excess) {
+        if let Err(e) = fs::remove_file(&path) {
+            warn!(path = %path.display(), error = %e, "Failed to delete old black-box recording");
+        }
+    }
+}
+
+struct Recorder {
+    session_id: String,
+    writer: Writer,
+    redactor: Redactor,
+    dropped_requests: Arc<AtomicU64>,
+}
+
+impl Recorder {
+    async fn run(
+        mut self,
+        mut requests: mpsc::Receiver<Entry>,
+        mut events: broadcast::Receiver<ApiEvent>,
+    ) {
+        let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+        flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut events_open = true;
+
+        let mut result = loop {
+            let result = tokio::select! {
+                entry = requests.recv() => match entry {
+                    Some(entry) => self.record_request(entry).await,
+                    // Every handle is gone, so is the session.
+                    None => break Ok(()),
+                },
+                event = events.recv(), if events_open => match event {
+                    Ok(event) => self.record_event(event).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        self.writer.write(&now(Record::Gap { events: missed, requests: 0 }))
+                    },
+                    Err(RecvError::Closed) => {
+                        events_open = false;
+                        Ok(())
+                    },
+                },
+                _ = flush.tick() => self.writer.flush(),
+            };
+            if result.is_err() {
+                break result;
+            }
+        };
+
+        // Pick up events broadcast while the session was torn down (cost report, destroyed).
+        while result.is_ok() {
+            result = match events.try_recv() {
+                Ok(event) => self.record_event(event).await,
+                Err(TryRecvError::Lagged(missed)) => {
+                    self.writer.write(&now(Record::Gap { events: missed, requests: 0 }))
+                },
+                Err(_) => break,
+            };
+        }
+
+        let result = result
+            .and_then(|()| self.writer.write(&now(Record::End)))
+            .and_then(|()| self.writer.finish());
+        ACTIVE_RECORDINGS.fetch_sub(1, Ordering::Relaxed);
+        match result {
+            Ok(path) => {
+                info!(
+                    session_id = %self.session_id,
+                    path = %path.display(),
+                    "Black-box recording saved"
+                );
+            },
+            Err(e) => {
+                warn!(session_id = %self.session_id, error = %e, "Black-box recording failed");
+            },
+        }
+    }
+
+    async fn record_request(&mut self, mut entry: Entry) -> io::Result<()> {
+        let dropped = self.dropped_requests.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            self.writer.write(&now(Record::Gap { events: 0, requests: dropped }))?;
+        }
+        if let Record::Request { request, response, .. } = &mut entry.record {
+            self.redactor.request(request).await;
+            if let Some(response) = response {
+                self.redactor.response(response);
+            }
+        }
+        self.writer.write(&entry)
+    }
+
+    async fn record_event(&mut self, event: ApiEvent) -> io::Result<()> {
+        let mut event = event.payload;
+        if event.session_id() != Some(self.session_id.as_str()) {
+            return Ok(());
+        }
+        self.redactor.event(&mut event).await;
+        self.writer.write(&now(Record::Event { event }))
+    }
+}
+
+/// Masks sensitive node params, looking node kinds up in the session's pipeline.
+struct Redactor {
+    registry: Arc<RwLock<NodeRegistry>>,
+    pipeline: Weak<Mutex<Pipeline>>,
+}
+
+impl Redactor {
+    fn params(&self, kind: &str, params: &mut Value) {
+        match self.registry.read() {
+            Ok(registry) => {
+                if let Some(schema) = registry.param_schema(kind) {
+                    redaction::redact_params(schema, params);
+                }
+            },
+            Err(_) => *params = Value::String(REDACTED.to_string()),
+        }
+    }
+
+    async fn node_params(&self, node_id: &str, params: &mut Value) {
+        let kind = match self.pipeline.upgrade() {
+            Some(pipeline) => pipeline.lock().await.nodes.get(node_id).map(|n| n.kind.clone()),
+            None => None,
+        };
+        match kind {
+            Some(kind) => self.params(&kind, params),
+            // Without the node's schema there is no telling what is sensitive
+            None => *params = Value::String(REDACTED.to_string()),
+        }
+    }
+
+    async fn request(&self, request: &mut RequestPayload) {
+        match request {
+            RequestPayload::AddNode { kind, params: Some(params), .. } => self.params(kind, params),
+            RequestPayload::TuneNode {
+                node_id,
+                message: NodeControlMessage::UpdateParams(params),
+                ..
+            }
+            | RequestPayload::TuneNodeAsync {
+                node_id,
+                message: NodeControlMessage::UpdateParams(params),
+                ..
+            } => self.node_params(node_id, params).await,
+            RequestPayload::ValidateBatch { operations, .. }
+            | RequestPayload::ApplyBatch { operations, .. } => {
+                for operation in operations {
+                    if let BatchOperation::AddNode { kind, params: Some(params), .. } = operation {
+                        self.params(kind, params);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn response(&self, response: &mut ResponsePayload) {
+        if let ResponsePayload::Pipeline { pipeline } = response {
+            for node in pipeline.nodes.values_mut() {
+                if let Some(params) = node.params.as_mut() {
+                    self.params(&node.kind, params);
+                }
+            }
+        }
+    }
+
+    async fn event(&self, event: &mut EventPayload) {
+        match event {
+            EventPayload::NodeAdded { kind, params: Some(params), .. } => {
+                self.params(kind, params);
+            },
+            EventPayload::NodeParamsChanged { node_id, params, .. } => {
+                self.node_params(node_id, params).await;
+            },
+            _ => {},
+        }
+    }
+}
+
+/// The compressed recording file.
+struct Writer {
+    encoder: GzEncoder<BufWriter<File>>,
+    part_path: PathBuf,
+    path: PathBuf,
+    /// Written since the last flush; idle recordings skip flushing (each flush costs bytes)
+    dirty: bool,
+}
+
+impl Writer {
+    fn create(directory: &Path, session_id: &str) -> io::Result<Self> {
+        let part_path = directory.join(format!("{session_id}{PART_EXTENSION}"));
+        let path = directory.join(format!("{session_id}{EXTENSION}"));
+        let file = File::create(&part_path)?;
+        Ok(Self {
+            encoder: GzEncoder::new(BufWriter::new(file), Compression::default()),
+            part_path,
+            path,
+            dirty: false,
+        })
+    }
+
+    fn write(&mut self, entry: &Entry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.encoder, entry)?;
+        self.encoder.write_all(b"\n")?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+        self.encoder.flush()
+    }
+
+    fn finish(self) -> io::Result<PathBuf> {
+        self.encoder.finish()?.flush()?;
+        fs::rename(&self.part_path, &self.path)?;
+        Ok(self.path)
+    }
+}
@@ -4,11 +4,16 @@
 
 use clap::{Parser, Subcommand};
 use schemars::schema_for;
+use std::future::Future;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
 use crate::config;
 
-type LogInitFn =
+/// How long in-flight pipeline tasks get to finish once the server has stopped.
+const DATA_PLANE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type LogInitFn =
     fn(
         &config::LogConfig,
         &config::TelemetryConfig,
@@ -33,6 +38,9 @@ pub enum Commands {
     /// Manage configuration
     #[command(subcommand)]
     Config(ConfigCommands),
+    /// Manage the Windows service
+    #[command(subcommand, hide = cfg!(not(windows)))]
+    Service(ServiceCommands),
 }
 
 #[derive(Subcommand, Debug)]
@@ -41,6 +49,18 @@ pub enum ConfigCommands {
     Default,
     /// Generate a JSON schema for the config and print it to stdout
     Schema,
+    /// Print the configuration after merging defaults, the config file and environment variables
+    PrintEffective,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ServiceCommands {
+    /// Register skit as an automatically started service using the given config
+    Install,
+    /// Stop and remove the service
+    Uninstall,
+    /// Run under the service control manager (used by the installed service)
+    Run,
 }
 
 /// Initialize telemetry (metrics) if enabled in configuration
@@ -74,15 +94,20 @@ fn log_startup_info(config: &config::Config) {
         console_level = ?config.log.console_level,
         file_level = ?config.log.file_level,
         file_path = %config.log.file_path,
+        dedicated_data_plane = config.runtime.dedicated_data_plane,
         "Starting skit server"
     );
 }
 
-/// Handle the "serve" command - start the server
+/// Loads the config and runs the server until `shutdown` completes
 /// Exits the process on error with status code 1
 // Allow eprintln before logging is initialized (CLI output)
 #[allow(clippy::disallowed_macros)]
-async fn handle_serve_command(config_path: &str, init_logging: LogInitFn) {
+pub fn run_server(
+    config_path: &str,
+    init_logging: LogInitFn,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
     let config_result = match config::load(config_path) {
         Ok(result) => result,
         Err(e) => {
@@ -91,6 +116,41 @@ async fn handle_serve_command(config_path: &str, init_logging: LogInitFn) {
         },
     };
 
+    // The runtime layout comes from the config, so the runtimes are built by hand here
+    // rather than by `#[tokio::main]`.
+    let api_runtime = match crate::runtime::build_api_runtime(&config_result.config.runtime) {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start API runtime: {e}");
+            std::process::exit(1);
+        },
+    };
+    let data_plane = match crate::runtime::build_data_plane_runtime(&config_result.config.runtime) {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start data-plane runtime: {e}");
+            std::process::exit(1);
+        },
+    };
+
+    let data_plane_handle = data_plane.as_ref().map(|runtime| runtime.handle().clone());
+    api_runtime.block_on(serve(config_result, data_plane_handle, init_logging, shutdown));
+
+    // Node tasks still winding down must not hold up process exit.
+    if let Some(data_plane) = data_plane {
+        data_plane.shutdown_timeout(DATA_PLANE_SHUTDOWN_TIMEOUT);
+    }
+}
+
+/// Runs the server on the API runtime: logging, telemetry, then the HTTP listener
+// Allow eprintln before logging is initialized (CLI output)
+#[allow(clippy::disallowed_macros)]
+async fn serve(
+    config_result: config::ConfigLoadResult,
+    data_plane: Option<tokio::runtime::Handle>,
+    init_logging: LogInitFn,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
     let _log_guard = match init_logging(&config_result.config.log, &config_result.config.telemetry)
     {
         Ok(guard) => guard,
@@ -112,7 +172,7 @@ async fn handle_serve_command(config_path: &str, init_logging: LogInitFn) {
         crate::telemetry::start_system_metrics();
     }
 
-    if let Err(e) = crate::server::start_server(&config_result.config).await {
+    if let Err(e) = crate::server::start_server(&config_result.config, data_plane, shutdown).await {
         error!(error = %e, "Failed to start server");
         std::process::exit(1);
     }
@@ -150,13 +210,76 @@ fn handle_config_schema_command() {
     }
 }
 
+/// Handle the "config print-effective" command - print the merged config to stdout
+// Allow println for CLI output to stdout (intentional)
+#[allow(clippy::disallowed_macros)]
+fn handle_config_print_effective_command(config_path: &str) {
+    let result = match config::load(config_path) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {e}");
+            std::process::exit(1);
+        },
+    };
+    match toml::to_string_pretty(&result.config) {
+        Ok(toml_string) => {
+            println!("# Effective skit configuration");
+            if result.file_missing.is_some() {
+                println!("# Config file: {config_path} (not found, using defaults)");
+            } else {
+                println!("# Config file: {config_path}");
+            }
+            for key in config::env_overrides() {
+                println!("# Overridden by environment: {key}");
+            }
+            println!("{toml_string}");
+        },
+        Err(e) => {
+            eprintln!("Failed to render configuration: {e}");
+            std::process::exit(1);
+        },
+    }
+}
+
+/// Handle the "service" commands
+// Allow eprintln for CLI output (logging is only initialized by `service run`)
+#[allow(clippy::disallowed_macros)]
+#[cfg(windows)]
+fn handle_service_command(config_path: &str, command: &ServiceCommands, init_logging: LogInitFn) {
+    let result = match command {
+        ServiceCommands::Install => crate::service::install(config_path),
+        ServiceCommands::Uninstall => crate::service::uninstall(),
+        ServiceCommands::Run => crate::service::run(config_path, init_logging),
+    };
+    if let Err(e) = result {
+        eprintln!("Service command failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Handle the "service" commands on platforms without a service manager integration
+// Allow eprintln for CLI output
+#[allow(clippy::disallowed_macros)]
+#[cfg(not(windows))]
+fn handle_service_command(
+    _config_path: &str,
+    _command: &ServiceCommands,
+    _init_logging: LogInitFn,
+) {
+    eprintln!(
+        "The service command is only available on Windows; \
+         see deploy/systemd or deploy/launchd for Linux and macOS"
+    );
+    std::process::exit(1);
+}
+
 /// Handle CLI commands
 // Allow eprintln/println before logging is initialized (for CLI output)
 #[allow(clippy::disallowed_macros)]
-pub async fn handle_command(cli: &Cli, init_logging: LogInitFn) {
+pub fn handle_command(cli: &Cli, init_logging: LogInitFn) {
     match cli.command.as_ref().unwrap_or(&Commands::Serve) {
         Commands::Serve => {
-            handle_serve_command(&cli.config, init_logging).await;
+            run_server(&cli.config, init_logging, crate::server::shutdown_signal());
         },
         Commands::Config(ConfigCommands::Default) => {
             handle_config_default_command();
@@ -164,5 +287,11 @@ pub async fn handle_command(cli: &Cli, init_logging: LogInitFn) {
         Commands::Config(ConfigCommands::Schema) => {
             handle_config_schema_command();
         },
+        Commands::Config(ConfigCommands::PrintEffective) => {
+            handle_config_print_effective_command(&cli.config);
+        },
+        Commands::Service(command) => {
+            handle_service_command(&cli.config, command, init_logging);
+        },
     }
 }
@@ -9,6 +9,7 @@ use figment::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use streamkit_core::TelemetryLimits;
 use tracing::Level;
 
 use crate::permissions::PermissionsConfig;
@@ -156,6 +157,33 @@ pub struct AdvancedBufferConfig {
     pub moq_peer_channel_capacity: Option<usize>,
 }
 
+/// Tokio runtime layout (`[runtime]`).
+///
+/// By default the API/HTTP server and all pipelines share one multi-thread runtime sized to
+/// the machine. Enabling `dedicated_data_plane` moves pipeline node tasks (dynamic sessions
+/// and oneshot runs) onto a second runtime, so heavy media work cannot delay request
+/// handling or WebSocket traffic. Either runtime can be pinned to a set of CPU cores (Linux).
+#[derive(Deserialize, Serialize, Debug, Clone, Default, JsonSchema)]
+pub struct RuntimeConfig {
+    /// Worker threads for the API/HTTP runtime (default: one per core, or one per pinned core)
+    pub api_worker_threads: Option<usize>,
+
+    /// CPU cores the API/HTTP runtime's threads are pinned to (Linux only; empty = no pinning)
+    #[serde(default)]
+    pub api_cores: Vec<usize>,
+
+    /// Run pipeline nodes on a dedicated runtime instead of the API/HTTP runtime
+    #[serde(default)]
+    pub dedicated_data_plane: bool,
+
+    /// Worker threads for the data-plane runtime (default: one per core, or one per pinned core)
+    pub data_plane_worker_threads: Option<usize>,
+
+    /// CPU cores the data-plane runtime's threads are pinned to (Linux only; empty = no pinning)
+    #[serde(default)]
+    pub data_plane_cores: Vec<usize>,
+}
+
 /// Log level for filtering messages.
 #[derive(Deserialize, Serialize, Debug, Clone, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -187,6 +215,26 @@ const fn default_max_body_size() -> usize {
     100 * 1024 * 1024
 }
 
+const fn default_websocket_max_message_bytes() -> usize {
+    1024 * 1024
+}
+
+const fn default_unix_socket_mode() -> u32 {
+    0o660
+}
+
+const fn default_max_uploads_per_ip() -> usize {
+    4
+}
+
+const fn default_upload_idle_timeout_ms() -> u64 {
+    30_000
+}
+
+const fn default_header_read_timeout_ms() -> u64 {
+    10_000
+}
+
 fn default_cors_allowed_origins() -> Vec<String> {
     vec![
         // Portless localhost (e.g., reverse proxy on 80/443)
@@ -236,6 +284,11 @@ pub struct TelemetryConfig {
     pub otlp_headers: HashMap<String, String>,
     #[serde(default)]
     pub tokio_console: bool,
+    /// Sampling and rate limits for node telemetry events (VAD, meters, ...), applied
+    /// before events reach the WebSocket bus. Nodes can override them with a `telemetry`
+    /// object in their params.
+    #[serde(default)]
+    pub event_limits: TelemetryLimits,
 }
 
 impl Default for TelemetryConfig {
@@ -247,6 +300,7 @@ impl Default for TelemetryConfig {
             otlp_traces_endpoint: None,
             otlp_headers: HashMap::new(),
             tokio_console: false,
+            event_limits: TelemetryLimits::default(),
         }
     }
 }
@@ -301,20 +355,43 @@ pub struct ServerConfig {
     pub cert_path: String,
     pub key_path: String,
     pub samples_dir: String,
-    /// Maximum request body size in bytes for multipart uploads (default: 100MB)
+    /// Maximum request body size in bytes for multipart uploads (default: 100MB).
+    /// Larger oneshot and asset uploads get `413 Payload Too Large`.
     #[serde(default = "default_max_body_size")]
     pub max_body_size: usize,
+    /// Largest control WebSocket message accepted from clients, in bytes (default: 1MB).
+    /// Larger messages close the connection.
+    #[serde(default = "default_websocket_max_message_bytes")]
+    pub websocket_max_message_bytes: usize,
+    /// Unix domain socket to serve the API on in addition to `address` (Unix only).
+    /// Prefix the name with `@` for a Linux abstract socket, which has no file permissions.
+    #[serde(default)]
+    pub unix_socket: Option<String>,
+    /// File mode applied to `unix_socket`; only local users it grants write access can connect
+    /// (default: 0o660)
+    #[serde(default = "default_unix_socket_mode")]
+    pub unix_socket_mode: u32,
     /// Base path for subpath deployments (e.g., "/s/session_xxx"). Used to inject <base> tag in HTML.
     /// If None, no <base> tag is injected (root deployment).
     pub base_path: Option<String>,
     /// CORS configuration for cross-origin requests
     #[serde(default)]
     pub cors: CorsConfig,
+    /// Mutual TLS for the HTTPS listener (requires `tls = true`)
+    #[serde(default)]
+    pub mtls: MtlsConfig,
+    /// Abuse protection for the oneshot and asset upload endpoints
+    #[serde(default)]
+    pub upload_limits: UploadLimitsConfig,
     #[cfg(feature = "moq")]
     pub moq_address: Option<String>,
     /// MoQ Gateway URL to use in the frontend (can be overridden via SK_SERVER__MOQ_GATEWAY_URL)
     #[cfg(feature = "moq")]
     pub moq_gateway_url: Option<String>,
+    /// Token authentication for MoQ publishers and subscribers
+    #[cfg(feature = "moq")]
+    #[serde(default)]
+    pub moq_auth: MoqAuthConfig,
 }
 
 impl Default for ServerConfig {
@@ -326,12 +403,129 @@ impl Default for ServerConfig {
             key_path: String::new(),
             samples_dir: "./samples/pipelines".to_string(),
             max_body_size: default_max_body_size(),
+            websocket_max_message_bytes: default_websocket_max_message_bytes(),
+            unix_socket: None,
+            unix_socket_mode: default_unix_socket_mode(),
             base_path: None,
             cors: CorsConfig::default(),
+            mtls: MtlsConfig::default(),
+            upload_limits: UploadLimitsConfig::default(),
             #[cfg(feature = "moq")]
             moq_address: Some("127.0.0.1:4545".to_string()),
             #[cfg(feature = "moq")]
             moq_gateway_url: None,
+            #[cfg(feature = "moq")]
+            moq_auth: MoqAuthConfig::default(),
+        }
+    }
+}
+
+/// Mutual TLS settings (`[server.mtls]`).
+///
+/// When enabled, the HTTPS listener asks clients for a certificate issued by `client_ca_path`.
+/// Certificates whose subject common name appears in `roles` act as that role, taking
+/// precedence over `permissions.role_header`; other clients get the usual role resolution.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct MtlsConfig {
+    /// Request client certificates on the HTTPS listener (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM bundle of the CA certificates that issue client certificates
+    #[serde(default)]
+    pub client_ca_path: String,
+    /// Reject handshakes that don't present a valid client certificate (default: true).
+    /// When false, clients without a certificate are still served.
+    #[serde(default = "default_true")]
+    pub require_client_cert: bool,
+    /// Client certificate subject common name -> role name
+    #[serde(default)]
+    pub roles: HashMap<String, String>,
+}
+
+impl Default for MtlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_ca_path: String::new(),
+            require_client_cert: true,
+            roles: HashMap::new(),
+        }
+    }
+}
+
+/// Limits on HTTP uploads (`[server.upload_limits]`).
+///
+/// Applied to `POST /api/v1/process` and `POST /api/v1/assets/audio` together with
+/// `max_body_size`, so a single client can't tie up disk space or sockets. Clients are
+/// told apart by peer IP address, so everything behind one reverse proxy shares a budget.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct UploadLimitsConfig {
+    /// Uploads a single IP address may have in flight at once (default: 4, 0 = unlimited).
+    /// Further uploads get `429 Too Many Requests`.
+    #[serde(default = "default_max_uploads_per_ip")]
+    pub max_concurrent_per_ip: usize,
+    /// Abort an upload when no body data arrives for this long (default: 30000, 0 = never).
+    /// Guards against slow-loris clients trickling a request body.
+    #[serde(default = "default_upload_idle_timeout_ms")]
+    pub idle_timeout_ms: u64,
+    /// Close HTTP/1 connections that don't finish sending request headers within this time
+    /// (default: 10000, 0 = never). Applies to the TCP listener, not the Unix socket.
+    #[serde(default = "default_header_read_timeout_ms")]
+    pub header_read_timeout_ms: u64,
+}
+
+impl Default for UploadLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_per_ip: default_max_uploads_per_ip(),
+            idle_timeout_ms: default_upload_idle_timeout_ms(),
+            header_read_timeout_ms: default_header_read_timeout_ms(),
+        }
+    }
+}
+
+/// Signed token format accepted by the MoQ gateway.
+#[cfg(feature = "moq")]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MoqTokenFormat {
+    /// HS256 JSON Web Token
+    #[default]
+    Jwt,
+    /// `base64url(claims).base64url(hmac_sha256(claims))`
+    Hmac,
+}
+
+/// MoQ connection authentication (`[server.moq_auth]`).
+///
+/// When enabled, every WebTransport connection must carry a `token` query parameter whose
+/// claims cover the requested broadcast path and direction.
+#[cfg(feature = "moq")]
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct MoqAuthConfig {
+    /// Require a signed token on MoQ connections (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Token format (default: jwt)
+    #[serde(default)]
+    pub format: MoqTokenFormat,
+    /// Environment variable holding the shared signing secret (default: SK_MOQ_AUTH_SECRET)
+    #[serde(default = "default_moq_auth_secret_env")]
+    pub secret_env: String,
+}
+
+#[cfg(feature = "moq")]
+fn default_moq_auth_secret_env() -> String {
+    "SK_MOQ_AUTH_SECRET".to_string()
+}
+
+#[cfg(feature = "moq")]
+impl Default for MoqAuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: MoqTokenFormat::default(),
+            secret_env: default_moq_auth_secret_env(),
         }
     }
 }
@@ -345,14 +539,67 @@ pub struct PluginConfig {
     /// Default is false to avoid accidental exposure when running without an auth layer.
     #[serde(default)]
     pub allow_http_management: bool,
+    /// CPU placement of native plugin instances that set no `_placement` param.
+    #[serde(default)]
+    pub native_placement: NativePlacementPolicy,
+    /// Per-instance working directories and environment filtering for native plugins.
+    #[serde(default)]
+    pub native_sandbox: NativeSandboxConfig,
 }
 
 impl Default for PluginConfig {
     fn default() -> Self {
-        Self { directory: ".plugins".to_string(), allow_http_management: false }
+        Self {
+            directory: ".plugins".to_string(),
+            allow_http_management: false,
+            native_placement: NativePlacementPolicy::default(),
+            native_sandbox: NativeSandboxConfig::default(),
+        }
+    }
+}
+
+/// Sandboxing of native plugin instances.
+///
+/// Native plugins share the server process, so this can't stop a plugin from reading the
+/// process environment directly; it gives plugins built with a current SDK a private
+/// directory and an environment that only contains the passthrough variables.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct NativeSandboxConfig {
+    /// Give each native plugin instance its own working directory and filtered environment.
+    pub enabled: bool,
+    /// Directory holding the instances' working directories (default: `<directory>/work`).
+    /// Each instance's directory is deleted when the instance is destroyed.
+    pub work_directory: Option<String>,
+    /// Environment variables plugins may see; a trailing `*` matches a prefix (e.g. `LC_*`).
+    pub env_passthrough: Vec<String>,
+}
+
+impl Default for NativeSandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            work_directory: None,
+            env_passthrough: streamkit_plugin_native::sandbox::DEFAULT_ENV_PASSTHROUGH
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
     }
 }
 
+/// Automatic CPU placement policy for native plugin instances.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NativePlacementPolicy {
+    /// Plugin calls run on the shared blocking thread pool
+    #[default]
+    None,
+    /// Each instance gets a worker thread pinned to the NUMA node running the fewest
+    /// instances (no effect on single-node machines)
+    NumaSpread,
+}
+
 const fn default_keep_models_loaded() -> bool {
     true
 }
@@ -372,6 +619,13 @@ pub struct PrewarmPluginConfig {
     /// Useful for GPU plugins that should fallback to CPU
     #[serde(default)]
     pub fallback_params: Option<serde_json::Value>,
+
+    /// Number of created instances to keep ready in a warm pool (default: 0, none).
+    /// Dynamic sessions adding a node of this kind with exactly the params that pre-warmed
+    /// successfully claim a ready instance instead of creating one; the pool refills in the
+    /// background.
+    #[serde(default)]
+    pub pool_size: usize,
 }
 
 /// Configuration for pre-warming plugins at startup.
@@ -386,6 +640,32 @@ pub struct PrewarmConfig {
     pub plugins: Vec<PrewarmPluginConfig>,
 }
 
+/// Declared resource needs of one instance of a node kind, used to estimate pipelines.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, JsonSchema)]
+pub struct NodeProfileConfig {
+    /// CPU cores an instance keeps busy. When unset, the average measured from past
+    /// instances is used, if any.
+    #[serde(default)]
+    pub cpu_cores: Option<f64>,
+
+    /// Memory an instance needs, in megabytes
+    #[serde(default)]
+    pub ram_mb: u64,
+
+    /// GPU memory an instance needs, in megabytes (implies `requires_gpu`)
+    #[serde(default)]
+    pub vram_mb: u64,
+
+    /// Whether an instance needs a GPU
+    #[serde(default)]
+    pub requires_gpu: bool,
+
+    /// Params holding the path of a model file or directory the node loads (e.g.
+    /// "model_path"); use dots for nested params
+    #[serde(default)]
+    pub model_params: Vec<String>,
+}
+
 /// Resource management configuration for ML models and shared resources.
 #[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
 pub struct ResourceConfig {
@@ -402,11 +682,20 @@ pub struct ResourceConfig {
     /// Pre-warming configuration for reducing first-use latency
     #[serde(default)]
     pub prewarm: PrewarmConfig,
+
+    /// Declared resource needs per node kind, for `POST /api/v1/estimate`
+    #[serde(default)]
+    pub profiles: HashMap<String, NodeProfileConfig>,
 }
 
 impl Default for ResourceConfig {
     fn default() -> Self {
-        Self { keep_models_loaded: true, max_memory_mb: None, prewarm: PrewarmConfig::default() }
+        Self {
+            keep_models_loaded: true,
+            max_memory_mb: None,
+            prewarm: PrewarmConfig::default(),
+            profiles: HashMap::new(),
+        }
     }
 }
 
@@ -496,6 +785,11 @@ pub struct ScriptConfig {
     /// and can be injected into HTTP headers via pipeline configuration
     #[serde(default)]
     pub secrets: HashMap<String, SecretConfig>,
+
+    /// Event hooks: sandboxed scripts that react to server events and issue control requests.
+    /// Configured as `[[script.hooks]]` entries.
+    #[serde(default)]
+    pub hooks: Vec<EventHookConfig>,
 }
 
 impl Default for ScriptConfig {
@@ -505,10 +799,51 @@ impl Default for ScriptConfig {
             default_memory_limit_mb: default_script_memory_limit_mb(),
             global_fetch_allowlist: Vec::new(),
             secrets: HashMap::new(),
+            hooks: Vec::new(),
         }
     }
 }
 
+fn default_hook_role() -> String {
+    "admin".to_string()
+}
+
+/// A server-side event hook script.
+///
+/// The script must define `onEvent(event)`, which receives each matching WebSocket event
+/// (same JSON shape clients see) and may return a WebSocket API request object, an array of
+/// them, or `null`. Requests run with the permissions of `role`.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct EventHookConfig {
+    /// Name used in logs
+    pub name: String,
+
+    /// Path to the JavaScript file defining `onEvent(event)`
+    pub script_path: String,
+
+    /// Event names to deliver (e.g., "sessioncreated", "nodestatechanged").
+    /// Empty = all events.
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Only deliver `nodetelemetry` events whose `data.event_type` matches one of these.
+    /// A trailing `*` matches by prefix (e.g., "vad.*"). Empty = no telemetry filtering.
+    #[serde(default)]
+    pub telemetry_types: Vec<String>,
+
+    /// Role whose permissions apply to requests returned by the hook
+    #[serde(default = "default_hook_role")]
+    pub role: String,
+
+    /// Per-event execution timeout (falls back to `script.default_timeout_ms`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// QuickJS memory limit (falls back to `script.default_memory_limit_mb`)
+    #[serde(default)]
+    pub memory_limit_mb: Option<usize>,
+}
+
 fn default_allowed_file_paths() -> Vec<String> {
     vec!["samples/**".to_string()]
 }
@@ -528,7 +863,7 @@ pub struct SecurityConfig {
     #[serde(default = "default_allowed_file_paths")]
     pub allowed_file_paths: Vec<String>,
 
-    /// Allowed file paths for file_writer nodes.
+    /// Allowed file paths for file_writer nodes and HLS packager output directories.
     ///
     /// Default: empty (deny all writes). This is intentional: arbitrary file writes from
     /// user-provided pipelines are a high-risk capability.
@@ -548,6 +883,102 @@ impl Default for SecurityConfig {
     }
 }
 
+/// What `CreateSession` does when a named pipeline already has a live session.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NamedPipelineConflict {
+    /// Fail the request (default)
+    #[default]
+    Reject,
+    /// Return the existing session instead of creating a new one
+    Attach,
+}
+
+/// A named pipeline that may have at most one live session at a time.
+///
+/// Intended for deployments bound to exclusive hardware (one camera, one microphone):
+/// creating a session with this name either joins the running one or fails.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, JsonSchema)]
+pub struct NamedPipelineConfig {
+    /// Behavior when a session with this name is already running
+    #[serde(default)]
+    pub on_conflict: NamedPipelineConflict,
+}
+
+fn default_black_box_directory() -> String {
+    "./black-box".to_string()
+}
+
+const fn default_black_box_max_recordings() -> usize {
+    100
+}
+
+/// Black-box recording of session control traffic and events, for postmortems.
+///
+/// Sessions opt in with `record: true` on `CreateSession`. Recordings hold control requests,
+/// events and node state transitions (no media), as gzip-compressed JSON Lines.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct BlackBoxConfig {
+    /// Directory recordings are written to, one `<session_id>.jsonl.gz` file per session
+    #[serde(default = "default_black_box_directory")]
+    pub directory: String,
+
+    /// Record every session, not only those that opt in
+    #[serde(default)]
+    pub record_all: bool,
+
+    /// Recordings to keep; the oldest are deleted when a new recording starts (0 = keep all)
+    #[serde(default = "default_black_box_max_recordings")]
+    pub max_recordings: usize,
+}
+
+impl Default for BlackBoxConfig {
+    fn default() -> Self {
+        Self {
+            directory: default_black_box_directory(),
+            record_all: false,
+            max_recordings: default_black_box_max_recordings(),
+        }
+    }
+}
+
+const fn default_usage_report_interval_secs() -> u64 {
+    3600
+}
+
+/// Per-kind node usage statistics (instances, lifetimes, error rates) across all sessions.
+///
+/// Always available to admins at `GET /api/v1/usage`; optionally also written to a file.
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct UsageConfig {
+    /// File the JSON usage report is periodically written to (disabled when unset)
+    #[serde(default)]
+    pub report_path: Option<String>,
+
+    /// Seconds between report file writes
+    #[serde(default = "default_usage_report_interval_secs")]
+    pub report_interval_secs: u64,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self { report_path: None, report_interval_secs: default_usage_report_interval_secs() }
+    }
+}
+
+/// Maintenance settings.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, JsonSchema)]
+pub struct MaintenanceConfig {
+    /// Start in read-only mode: queries and event streaming work, mutations are rejected.
+    /// Admins can toggle it at runtime (`setreadonly` or `PUT /api/v1/read-only`).
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Banner shown to clients while read-only
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
 /// Root configuration for the StreamKit server.
 #[derive(Deserialize, Serialize, Default, Debug, Clone, JsonSchema)]
 pub struct Config {
@@ -566,6 +997,9 @@ pub struct Config {
     #[serde(default)]
     pub engine: EngineConfig,
 
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+
     #[serde(default)]
     pub plugins: PluginConfig,
 
@@ -577,6 +1011,26 @@ pub struct Config {
 
     #[serde(default)]
     pub script: ScriptConfig,
+
+    /// Named singleton pipelines, keyed by session name.
+    #[serde(default)]
+    pub named_pipelines: HashMap<String, NamedPipelineConfig>,
+
+    #[serde(default)]
+    pub black_box: BlackBoxConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub usage: UsageConfig,
+}
+
+impl Config {
+    /// Returns true when `name` is a registered pipeline that attaches to its live session.
+    pub fn attaches_to_named_session(&self, name: &str) -> bool {
+        self.named_pipelines
+            .get(name)
+            .is_some_and(|p| p.on_conflict == NamedPipelineConflict::Attach)
+    }
 }
 
 #[derive(Debug)]
@@ -607,13 +1061,49 @@ pub fn load(config_path: &str) -> Result<ConfigLoadResult, Box<figment::Error>>
     }
 
     let mut config: Config =
-        figment.merge(Env::prefixed("SK_").split("__")).extract().map_err(Box::new)?;
+        figment.merge(legacy_env_provider()).merge(env_provider()).extract().map_err(Box::new)?;
 
     normalize_permissions_config(&mut config);
 
     Ok(ConfigLoadResult { config, file_missing })
 }
 
+/// Environment overlay: `SK_SECTION__KEY` (or `SK__SECTION__KEY`) sets `section.key`.
+///
+/// Nested tables and map entries use further `__` separators, e.g.
+/// `SK_PERMISSIONS__ROLES__VIEWER__CREATE_SESSIONS=false`. Values are parsed like TOML
+/// values, so arrays and inline tables can be given as `'["a", "b"]'`.
+fn env_provider() -> Env {
+    Env::prefixed("SK_").map(|key| key.as_str().trim_start_matches('_').into()).split("__")
+}
+
+/// Env-only settings from before the overlay covered everything; the canonical
+/// `SK_SECTION__KEY` form takes precedence when both are set.
+fn legacy_env_provider() -> Env {
+    Env::raw()
+        .only(&["SK_WEBSOCKET_MAX_MESSAGE_BYTES"])
+        .map(|_| "server.websocket_max_message_bytes".into())
+}
+
+/// Lists the configuration keys (dotted, e.g. `server.address`) currently overridden by
+/// environment variables.
+///
+/// Unrelated `SK_` variables such as `SK_ROLE` are not part of the config and are skipped.
+pub fn env_overrides() -> Vec<String> {
+    let Ok(toml::Value::Table(sections)) = toml::Value::try_from(Config::default()) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<String> = legacy_env_provider()
+        .iter()
+        .chain(env_provider().iter())
+        .map(|(key, _)| key.as_str().to_string())
+        .filter(|key| sections.contains_key(key.split('.').next().unwrap_or_default()))
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
 fn normalize_permissions_config(config: &mut Config) {
     for role in config.permissions.roles.values_mut() {
         normalize_allowed_samples(&config.server.samples_dir, role.allowed_samples.as_mut_slice());
@@ -0,0 +1,280 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Connection taps for live debugging.
+//!
+//! A tap clips onto a connection of a running session and sends summaries of the packets
+//! flowing over it (type, size, timing metadata and an optional payload preview) to the
+//! WebSocket client that opened it. Taps are sampled, truncated and always expire, so
+//! leaving one open cannot flood a client or slow the session: packets the client cannot
+//! keep up with are skipped.
+
+use std::fmt::Write;
+use std::time::{Duration, SystemTime};
+
+use streamkit_api::{Event as ApiEvent, EventPayload, MessageType, TapEndReason, TappedPacket};
+use streamkit_core::types::{Packet, PacketMetadata};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+const DEFAULT_MAX_PACKETS: u32 = 20;
+const MAX_MAX_PACKETS: u32 = 500;
+const DEFAULT_DURATION_SECS: u32 = 30;
+const MAX_DURATION_SECS: u32 = 300;
+/// Largest payload preview a client can ask for, also used when inspecting held packets.
+pub const MAX_PREVIEW_BYTES: u32 = 1024;
+
+/// Capacity of a client's tap event queue, shared by all its taps.
+pub const TAP_EVENT_CAPACITY: usize = 64;
+
+/// Limits a tap runs under, with the client's requested values clamped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TapLimits {
+    pub max_packets: u32,
+    pub duration_secs: u32,
+    pub sample_every: u32,
+    pub preview_bytes: u32,
+}
+
+impl TapLimits {
+    pub fn new(
+        max_packets: Option<u32>,
+        duration_secs: Option<u32>,
+        sample_every: Option<u32>,
+        preview_bytes: Option<u32>,
+    ) -> Self {
+        Self {
+            max_packets: max_packets.unwrap_or(DEFAULT_MAX_PACKETS).clamp(1, MAX_MAX_PACKETS),
+            duration_secs: duration_secs
+                .unwrap_or(DEFAULT_DURATION_SECS)
+                .clamp(1, MAX_DURATION_SECS),
+            sample_every: sample_every.unwrap_or(1).max(1),
+            preview_bytes: preview_bytes.unwrap_or(0).min(MAX_PREVIEW_BYTES),
+        }
+    }
+}
+
+fn truncate_text(text: &str, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+fn hex_preview(bytes: &[u8], max_bytes: usize) -> (String, bool) {
+    let shown = &bytes[..bytes.len().min(max_bytes)];
+    let hex = shown.iter().fold(String::with_capacity(shown.len() * 2), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+    (hex, shown.len() < bytes.len())
+}
+
+/// Summarizes a packet, with up to `preview_bytes` of its payload when non-zero.
+pub fn summarize(packet: &Packet, preview_bytes: usize) -> TappedPacket {
+    let (packet_type, content_type, metadata): (_, _, Option<&PacketMetadata>) = match packet {
+        Packet::Audio(frame) => (
+            "audio",
+            Some(format!("{}Hz/{}ch", frame.sample_rate, frame.channels)),
+            frame.metadata.as_ref(),
+        ),
+        Packet::Text(_) => ("text", None, None),
+        Packet::Transcription(t) => ("transcription", None, t.metadata.as_ref()),
+        Packet::Custom(c) => ("custom", Some(c.type_id.clone()), c.metadata.as_ref()),
+        Packet::Binary { content_type, metadata, .. } => {
+            ("binary", content_type.as_ref().map(ToString::to_string), metadata.as_ref())
+        },
+    };
+
+    let preview = (preview_bytes > 0).then(|| match packet {
+        Packet::Audio(frame) => {
+            // Samples as little-endian f32 bytes
+            let bytes: Vec<u8> =
+                frame.samples.iter().flat_map(|s| s.to_le_bytes()).take(preview_bytes).collect();
+            let (hex, _) = hex_preview(&bytes, preview_bytes);
+            (hex, bytes.len() < packet.payload_size())
+        },
+        Packet::Text(text) => truncate_text(text, preview_bytes),
+        Packet::Transcription(t) => truncate_text(&t.text, preview_bytes),
+        Packet::Custom(c) => truncate_text(&c.data.to_string(), preview_bytes),
+        Packet::Binary { data, .. } => hex_preview(data, preview_bytes),
+    });
+
+    TappedPacket {
+        packet_type: packet_type.to_string(),
+        content_type,
+        size_bytes: packet.payload_size() as u64,
+        metadata: metadata.cloned(),
+        truncated: preview.as_ref().is_some_and(|(_, truncated)| *truncated),
+        preview: preview.map(|(text, _)| text),
+    }
+}
+
+const fn tap_event(payload: EventPayload) -> ApiEvent {
+    ApiEvent { message_type: MessageType::Event, correlation_id: None, payload }
+}
+
+/// Forwards sampled summaries of `packets` to `sink` until the tap's limits are reached,
+/// the tapped output goes away or the client disconnects (`sink` closes).
+///
+/// Dropping `packets` when done removes the tap from the engine.
+pub fn spawn_tap(
+    session_id: String,
+    tap_id: String,
+    mut packets: mpsc::Receiver<Packet>,
+    limits: TapLimits,
+    sink: mpsc::Sender<ApiEvent>,
+) {
+    tokio::spawn(async move {
+        let expiry = tokio::time::sleep(Duration::from_secs(u64::from(limits.duration_secs)));
+        tokio::pin!(expiry);
+        let mut seen = 0u64;
+        let mut sent = 0u64;
+
+        let reason = loop {
+            tokio::select! {
+                () = &mut expiry => break TapEndReason::Expired,
+                () = sink.closed() => {
+                    debug!(%tap_id, "Connection tap client went away");
+                    return;
+                },
+                packet = packets.recv() => {
+                    let Some(packet) = packet else { break TapEndReason::Closed };
+                    seen += 1;
+                    if !(seen - 1).is_multiple_of(u64::from(limits.sample_every)) {
+                        continue;
+                    }
+                    let event = tap_event(EventPayload::ConnectionTapPacket {
+                        session_id: session_id.clone(),
+                        tap_id: tap_id.clone(),
+                        index: seen,
+                        packet: summarize(&packet, limits.preview_bytes as usize),
+                        timestamp: crate::session::system_time_to_rfc3339(SystemTime::now()),
+                    });
+                    match sink.try_send(event) {
+                        Ok(()) => sent += 1,
+                        // A slow client skips packets rather than holding up the tap
+                        Err(mpsc::error::TrySendError::Full(_)) => continue,
+                        Err(mpsc::error::TrySendError::Closed(_)) => return,
+                    }
+                    if sent >= u64::from(limits.max_packets) {
+                        break TapEndReason::PacketLimit;
+                    }
+                },
+            }
+        };
+
+        debug!(%tap_id, ?reason, sent, "Connection tap ended");
+        let _ = sink
+            .send(tap_event(EventPayload::ConnectionTapEnded {
+                session_id,
+                tap_id,
+                reason,
+                packets_sent: sent,
+            }))
+            .await;
+    });
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    fn binary(data: &'static [u8], sequence: u64) -> Packet {
+        Packet::Binary {
+            data: Bytes::from_static(data),
+            content_type: Some(Cow::Borrowed("audio/opus")),
+            metadata: Some(PacketMetadata {
+                timestamp_us: None,
+                duration_us: Some(20_000),
+                sequence: Some(sequence),
+            }),
+        }
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        assert_eq!(
+            TapLimits::new(None, None, None, None),
+            TapLimits { max_packets: 20, duration_secs: 30, sample_every: 1, preview_bytes: 0 }
+        );
+        assert_eq!(
+            TapLimits::new(Some(0), Some(100_000), Some(0), Some(1 << 20)),
+            TapLimits { max_packets: 1, duration_secs: 300, sample_every: 1, preview_bytes: 1024 }
+        );
+    }
+
+    #[test]
+    fn previews_are_truncated() {
+        let summary = summarize(&binary(&[0xde, 0xad, 0xbe, 0xef], 1), 2);
+        assert_eq!(summary.packet_type, "binary");
+        assert_eq!(summary.content_type.as_deref(), Some("audio/opus"));
+        assert_eq!(summary.size_bytes, 4);
+        assert_eq!(summary.metadata.unwrap().sequence, Some(1));
+        assert_eq!((summary.preview.as_deref(), summary.truncated), (Some("dead"), true));
+
+        // Text is cut on a character boundary
+        let summary = summarize(&Packet::Text(Arc::from("héllo")), 2);
+        assert_eq!((summary.preview.as_deref(), summary.truncated), (Some("h"), true));
+
+        let summary = summarize(&Packet::Text(Arc::from("hi")), 0);
+        assert_eq!((summary.preview, summary.truncated), (None, false));
+    }
+
+    #[tokio::test]
+    async fn tap_samples_and_stops_at_packet_limit() {
+        let (packet_tx, packet_rx) = mpsc::channel(16);
+        let (sink, mut events) = mpsc::channel(16);
+        let limits = TapLimits::new(Some(2), None, Some(2), None);
+        spawn_tap("s".into(), "t".into(), packet_rx, limits, sink);
+
+        for sequence in 1..=6 {
+            packet_tx.send(binary(b"x", sequence)).await.unwrap();
+        }
+
+        let mut indices = Vec::new();
+        let reason = loop {
+            match events.recv().await.unwrap().payload {
+                EventPayload::ConnectionTapPacket { index, .. } => indices.push(index),
+                EventPayload::ConnectionTapEnded { reason, packets_sent, .. } => {
+                    assert_eq!(packets_sent, 2);
+                    break reason;
+                },
+                other => panic!("unexpected event {other:?}"),
+            }
+        };
+        assert_eq!(indices, [1, 3]);
+        assert_eq!(reason, TapEndReason::PacketLimit);
+        // The tap let go of the output
+        assert!(packet_tx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn tap_expires() {
+        let (_packet_tx, packet_rx) = mpsc::channel(1);
+        let (sink, mut events) = mpsc::channel(1);
+        spawn_tap(
+            "s".into(),
+            "t".into(),
+            packet_rx,
+            TapLimits::new(None, Some(1), None, None),
+            sink,
+        );
+
+        match events.recv().await.unwrap().payload {
+            EventPayload::ConnectionTapEnded { reason, packets_sent, .. } => {
+                assert_eq!((reason, packets_sent), (TapEndReason::Expired, 0));
+            },
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+}
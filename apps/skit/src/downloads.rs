@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Transfer encoding for HTTP downloads.
+//!
+//! Oneshot results are compressed with gzip or zstd when the client's `Accept-Encoding` allows
+//! it and the content type benefits (text, JSON, uncompressed PCM); already-encoded media is
+//! passed through untouched. Files on disk, such as black-box recordings and audio assets, are
+//! served by [`serve_file`], which honors `Range` and conditional request headers so large
+//! downloads can be resumed or seeked into.
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, Extensions, HeaderMap, HeaderValue, StatusCode, Version};
+use axum::response::Response;
+use std::path::Path;
+use tower::ServiceExt;
+use tower_http::compression::predicate::{Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::services::ServeFile;
+
+/// Content types worth compressing besides `text/*` and `*+json`.
+const COMPRESSIBLE_CONTENT_TYPES: &[&str] = &[
+    "application/json",
+    "application/x-ndjson",
+    "application/xml",
+    "audio/wav",
+    "audio/wave",
+    "audio/x-wav",
+    "audio/l16",
+    "audio/pcm",
+];
+
+/// Compression for streamed outputs, negotiated from the request's `Accept-Encoding`.
+///
+/// Responses that carry a `Content-Range` or already have a `Content-Encoding` are left alone.
+pub fn compression_layer() -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new()
+        .no_br()
+        .no_deflate()
+        .compress_when(SizeAbove::default().and(is_compressible))
+}
+
+fn is_compressible(_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence.starts_with("text/")
+        || essence.ends_with("+json")
+        || COMPRESSIBLE_CONTENT_TYPES.contains(&essence.as_str())
+}
+
+/// Serves the file at `path`, answering `Range`, `If-Modified-Since` and `If-Unmodified-Since`.
+///
+/// `content_type` replaces the type guessed from the file extension on successful responses.
+pub async fn serve_file(path: &Path, content_type: Option<HeaderValue>, req: Request) -> Response {
+    let response = match ServeFile::new(path).oneshot(req).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    };
+    let mut response = response.map(Body::new);
+    if let Some(content_type) = content_type.filter(|_| response.status().is_success()) {
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    fn compressible(content_type: &str) -> bool {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+        is_compressible(StatusCode::OK, Version::HTTP_11, &headers, &Extensions::new())
+    }
+
+    #[test]
+    fn only_uncompressed_content_types_are_compressed() {
+        assert!(compressible("application/json"));
+        assert!(compressible("text/plain; charset=utf-8"));
+        assert!(compressible("audio/WAV"));
+        assert!(compressible("application/vnd.api+json"));
+        assert!(!compressible("audio/ogg"));
+        assert!(!compressible("audio/mpeg"));
+        assert!(!compressible("application/octet-stream"));
+        assert!(!compressible("application/gzip"));
+    }
+
+    #[tokio::test]
+    async fn serves_byte_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clip.wav");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let req =
+            Request::builder().header(header::RANGE, "bytes=2-5").body(Body::empty()).unwrap();
+        let response = serve_file(&path, Some(HeaderValue::from_static("audio/x-test")), req).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "audio/x-test");
+        assert_eq!(response.into_body().collect().await.unwrap().to_bytes(), "2345");
+    }
+
+    #[tokio::test]
+    async fn missing_file_keeps_error_content_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let response = serve_file(
+            &dir.path().join("missing.wav"),
+            Some(HeaderValue::from_static("audio/x-test")),
+            req,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().get(header::CONTENT_TYPE).is_none_or(|ct| ct != "audio/x-test"));
+    }
+}
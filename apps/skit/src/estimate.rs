@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Pipeline resource estimates.
+//!
+//! Sums the resource needs of a pipeline's nodes before it is instantiated, so clients can
+//! warn that a pipeline needs a GPU or a model download instead of failing at session start.
+//! Figures come from the operator's `[resources.profiles]`; kinds without a declared CPU load
+//! fall back to the average measured from past instances. Kinds with neither are reported
+//! as `unknown` and left out of the totals.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+use streamkit_api::{ModelRequirement, NodeEstimate, Pipeline, PipelineEstimate, ProfileSource};
+use streamkit_engine::usage::UsageReport;
+
+use crate::config::NodeProfileConfig;
+
+/// Looks up a param by dotted path (e.g. "gpu.model_path").
+fn param<'a>(params: Option<&'a Value>, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(params?, |value, key| value.get(key))
+}
+
+/// Estimates the resource needs of `pipeline` from declared `profiles` and measured `usage`.
+#[allow(clippy::implicit_hasher)] // Profiles always come from the deserialized config
+pub fn estimate_pipeline(
+    pipeline: &Pipeline,
+    profiles: &HashMap<String, NodeProfileConfig>,
+    usage: &UsageReport,
+) -> PipelineEstimate {
+    let measured_cpu: HashMap<&str, f64> = usage
+        .kinds
+        .iter()
+        .filter_map(|kind| Some((kind.kind.as_str(), kind.avg_cpu_cores?)))
+        .collect();
+
+    let mut models = Vec::new();
+    let nodes: Vec<NodeEstimate> = pipeline
+        .nodes
+        .iter()
+        .map(|(node_id, node)| {
+            let measured = measured_cpu.get(node.kind.as_str()).copied();
+            let Some(profile) = profiles.get(&node.kind) else {
+                return NodeEstimate {
+                    node_id: node_id.clone(),
+                    kind: node.kind.clone(),
+                    source: if measured.is_some() {
+                        ProfileSource::Measured
+                    } else {
+                        ProfileSource::Unknown
+                    },
+                    cpu_cores: measured.unwrap_or(0.0),
+                    ram_mb: 0,
+                    vram_mb: 0,
+                    requires_gpu: false,
+                };
+            };
+
+            for name in &profile.model_params {
+                if let Some(path) = param(node.params.as_ref(), name).and_then(Value::as_str) {
+                    models.push(ModelRequirement {
+                        node_id: node_id.clone(),
+                        path: path.to_string(),
+                        present: Path::new(path).exists(),
+                    });
+                }
+            }
+            NodeEstimate {
+                node_id: node_id.clone(),
+                kind: node.kind.clone(),
+                source: ProfileSource::Declared,
+                cpu_cores: profile.cpu_cores.or(measured).unwrap_or(0.0),
+                ram_mb: profile.ram_mb,
+                vram_mb: profile.vram_mb,
+                requires_gpu: profile.requires_gpu || profile.vram_mb > 0,
+            }
+        })
+        .collect();
+
+    PipelineEstimate {
+        cpu_cores: nodes.iter().map(|n| n.cpu_cores).sum(),
+        ram_mb: nodes.iter().map(|n| n.ram_mb).sum(),
+        vram_mb: nodes.iter().map(|n| n.vram_mb).sum(),
+        requires_gpu: nodes.iter().any(|n| n.requires_gpu),
+        models,
+        nodes,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use streamkit_engine::usage::KindUsage;
+
+    fn pipeline(model_path: &str) -> Pipeline {
+        let yaml = format!(
+            r"
+nodes:
+  stt:
+    kind: plugin::native::whisper
+    params:
+      model_path: {model_path}
+  gain:
+    kind: audio::gain
+  pacer:
+    kind: core::pacer
+"
+        );
+        let user_pipeline = serde_saphyr::from_str(&yaml).unwrap();
+        streamkit_api::yaml::compile(user_pipeline).unwrap()
+    }
+
+    fn measured(kind: &str, avg_cpu_cores: f64) -> KindUsage {
+        KindUsage {
+            kind: kind.to_string(),
+            instances: 1,
+            active: 0,
+            creation_failures: 0,
+            failed: 0,
+            error_rate: 0.0,
+            avg_lifetime_secs: Some(10.0),
+            avg_cpu_cores: Some(avg_cpu_cores),
+        }
+    }
+
+    #[test]
+    fn combines_declared_and_measured_profiles() {
+        let model = tempfile::NamedTempFile::new().unwrap();
+        let model_path = model.path().to_str().unwrap();
+        let profiles = HashMap::from([(
+            "plugin::native::whisper".to_string(),
+            NodeProfileConfig {
+                ram_mb: 600,
+                vram_mb: 1000,
+                model_params: vec!["model_path".to_string(), "vad.model_path".to_string()],
+                ..Default::default()
+            },
+        )]);
+        let usage = UsageReport {
+            kinds: vec![measured("plugin::native::whisper", 1.5), measured("audio::gain", 0.25)],
+        };
+
+        let estimate = estimate_pipeline(&pipeline(model_path), &profiles, &usage);
+        let sources: Vec<_> = estimate.nodes.iter().map(|n| n.source).collect();
+        assert_eq!(
+            sources,
+            [ProfileSource::Declared, ProfileSource::Measured, ProfileSource::Unknown]
+        );
+        // The declared profile leaves CPU to the measurement
+        assert!((estimate.cpu_cores - 1.75).abs() < f64::EPSILON);
+        assert_eq!((estimate.ram_mb, estimate.vram_mb), (600, 1000));
+        assert!(estimate.requires_gpu);
+        assert_eq!(
+            estimate.models,
+            [ModelRequirement {
+                node_id: "stt".to_string(),
+                path: model_path.to_string(),
+                present: true
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_missing_models() {
+        let profiles = HashMap::from([(
+            "plugin::native::whisper".to_string(),
+            NodeProfileConfig {
+                cpu_cores: Some(2.0),
+                model_params: vec!["model_path".to_string()],
+                ..Default::default()
+            },
+        )]);
+        let estimate = estimate_pipeline(
+            &pipeline("models/missing.bin"),
+            &profiles,
+            &UsageReport { kinds: vec![measured("plugin::native::whisper", 0.5)] },
+        );
+        assert!((estimate.cpu_cores - 2.0).abs() < f64::EPSILON);
+        assert!(!estimate.requires_gpu);
+        assert!(!estimate.models[0].present);
+    }
+}
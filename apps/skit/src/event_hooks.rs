@@ -0,0 +1,323 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Server-side event hooks.
+//!
+//! Each `[[script.hooks]]` entry runs a sandboxed QuickJS script that receives the same
+//! events WebSocket clients see. Its `onEvent(event)` function may answer with WebSocket
+//! API requests, which are executed through the regular request handler under the hook's
+//! role. This covers small automation policies ("restart the pipeline if STT fails twice")
+//! without running an external controller.
+//!
+//! Hooks run one event at a time and have no `fetch()` or timers; state kept in script
+//! globals persists across events for the lifetime of the server.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rquickjs::function::Func;
+use rquickjs::{AsyncContext, AsyncRuntime, Ctx};
+use serde_json::Value;
+use streamkit_api::{Event as ApiEvent, RequestPayload, ResponsePayload};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+
+use crate::config::EventHookConfig;
+use crate::permissions::Permissions;
+use crate::state::AppState;
+use crate::websocket_handlers::handle_request_payload;
+
+/// Starts one task per configured hook. Hooks whose script cannot be read are skipped.
+pub fn spawn_hooks(app_state: &Arc<AppState>) {
+    let script_config = &app_state.config.script;
+    for hook in &script_config.hooks {
+        let source = match std::fs::read_to_string(&hook.script_path) {
+            Ok(source) => source,
+            Err(e) => {
+                warn!(
+                    hook = %hook.name,
+                    path = %hook.script_path,
+                    error = %e,
+                    "Failed to read event hook script; hook disabled"
+                );
+                continue;
+            },
+        };
+
+        let runner = HookRunner {
+            hook: hook.clone(),
+            timeout: Duration::from_millis(
+                hook.timeout_ms.unwrap_or(script_config.default_timeout_ms),
+            ),
+            memory_limit_mb: hook.memory_limit_mb.unwrap_or(script_config.default_memory_limit_mb),
+            app_state: Arc::clone(app_state),
+        };
+        let events_rx = app_state.event_tx.subscribe();
+
+        tokio::spawn(async move {
+            if let Err(e) = runner.run(source, events_rx).await {
+                warn!(hook = %runner.hook.name, error = %e, "Event hook stopped");
+            }
+        });
+    }
+}
+
+struct HookRunner {
+    hook: EventHookConfig,
+    timeout: Duration,
+    memory_limit_mb: usize,
+    app_state: Arc<AppState>,
+}
+
+impl HookRunner {
+    async fn run(
+        &self,
+        source: String,
+        mut events_rx: broadcast::Receiver<ApiEvent>,
+    ) -> Result<(), String> {
+        let script = HookScript::load(&self.hook.name, source, self.timeout, self.memory_limit_mb)
+            .await
+            .map_err(|e| format!("Failed to load script: {e}"))?;
+
+        let perms = self.app_state.config.permissions.get_role(&self.hook.role);
+        info!(hook = %self.hook.name, role = %self.hook.role, "Event hook started");
+
+        loop {
+            let event = match events_rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(hook = %self.hook.name, skipped, "Event hook fell behind; events dropped");
+                    continue;
+                },
+                Err(RecvError::Closed) => return Ok(()),
+            };
+
+            let event_json = match serde_json::to_value(&event.payload) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!(hook = %self.hook.name, error = %e, "Failed to serialize event");
+                    continue;
+                },
+            };
+            if !matches_event(&self.hook, &event_json) {
+                continue;
+            }
+
+            let requests = match script
+                .on_event(&event_json)
+                .await
+                .and_then(|json| parse_requests(json.as_deref()))
+            {
+                Ok(requests) => requests,
+                Err(e) => {
+                    warn!(hook = %self.hook.name, error = %e, "Event hook failed");
+                    continue;
+                },
+            };
+
+            for request in requests {
+                self.execute(request, &perms).await;
+            }
+        }
+    }
+
+    async fn execute(&self, request: RequestPayload, perms: &Permissions) {
+        debug!(hook = %self.hook.name, ?request, "Executing event hook request");
+        let response =
+            handle_request_payload(request, &self.app_state, perms, &self.hook.role, None).await;
+        if let Some(ResponsePayload::Error { message }) = response {
+            warn!(hook = %self.hook.name, error = %message, "Event hook request failed");
+        }
+    }
+}
+
+/// A loaded hook script and the QuickJS runtime it lives in.
+struct HookScript {
+    // Kept alive for the context; dropping the runtime tears down the script.
+    _runtime: AsyncRuntime,
+    context: AsyncContext,
+    deadline: Arc<Mutex<Option<Instant>>>,
+    timeout: Duration,
+}
+
+impl HookScript {
+    async fn load(
+        name: &str,
+        source: String,
+        timeout: Duration,
+        memory_limit_mb: usize,
+    ) -> Result<Self, String> {
+        let runtime = AsyncRuntime::new().map_err(|e| format!("Failed to create runtime: {e}"))?;
+        runtime.set_memory_limit(memory_limit_mb * 1024 * 1024).await;
+
+        // QuickJS polls the interrupt handler while executing, so a runaway onEvent() is
+        // aborted once the per-event deadline passes instead of stalling the hook forever.
+        let deadline: Arc<Mutex<Option<Instant>>> = Arc::default();
+        let handler_deadline = Arc::clone(&deadline);
+        runtime
+            .set_interrupt_handler(Some(Box::new(move || {
+                handler_deadline.lock().ok().and_then(|d| *d).is_some_and(|d| Instant::now() >= d)
+            })))
+            .await;
+
+        let context = AsyncContext::full(&runtime)
+            .await
+            .map_err(|e| format!("Failed to create context: {e}"))?;
+
+        let script = Self { _runtime: runtime, context, deadline, timeout };
+        script.set_deadline(true);
+        let loaded = script
+            .context
+            .with(|ctx| {
+                register_console(&ctx, name).map_err(|e| js_error(&ctx, &e))?;
+                ctx.eval::<(), _>(source).map_err(|e| js_error(&ctx, &e))?;
+                ctx.globals()
+                    .get::<_, rquickjs::Function>("onEvent")
+                    .map(|_| ())
+                    .map_err(|_| "Script must define an 'onEvent(event)' function".to_string())
+            })
+            .await;
+        script.set_deadline(false);
+        loaded.map(|()| script)
+    }
+
+    /// Calls `onEvent(event)` and returns its result as JSON (`None` for `undefined`).
+    async fn on_event(&self, event: &Value) -> Result<Option<String>, String> {
+        self.set_deadline(true);
+        let output = self
+            .context
+            .with(|ctx| {
+                let on_event: rquickjs::Function = ctx
+                    .globals()
+                    .get("onEvent")
+                    .map_err(|_| "onEvent is no longer defined".to_string())?;
+                let arg = ctx.json_parse(event.to_string()).map_err(|e| js_error(&ctx, &e))?;
+                let result: rquickjs::Value =
+                    on_event.call((arg,)).map_err(|e| js_error(&ctx, &e))?;
+                let json = ctx.json_stringify(result).map_err(|e| js_error(&ctx, &e))?;
+                json.map(|s| s.to_string()).transpose().map_err(|e| js_error(&ctx, &e))
+            })
+            .await;
+        self.set_deadline(false);
+        output
+    }
+
+    fn set_deadline(&self, armed: bool) {
+        if let Ok(mut deadline) = self.deadline.lock() {
+            *deadline = armed.then(|| Instant::now() + self.timeout);
+        }
+    }
+}
+
+fn register_console(ctx: &Ctx<'_>, hook_name: &str) -> rquickjs::Result<()> {
+    let console = rquickjs::Object::new(ctx.clone())?;
+    for level in ["log", "warn", "error"] {
+        let hook = hook_name.to_string();
+        console.set(
+            level,
+            Func::from(move |msg: String| match level {
+                "error" => tracing::error!(target: "streamkit::hooks", hook = %hook, "{}", msg),
+                "warn" => tracing::warn!(target: "streamkit::hooks", hook = %hook, "{}", msg),
+                _ => tracing::info!(target: "streamkit::hooks", hook = %hook, "{}", msg),
+            }),
+        )?;
+    }
+    ctx.globals().set("console", console)
+}
+
+/// Turns a QuickJS error into a message, pulling the thrown value for exceptions.
+fn js_error(ctx: &Ctx<'_>, e: &rquickjs::Error) -> String {
+    if !matches!(e, rquickjs::Error::Exception) {
+        return e.to_string();
+    }
+    let thrown = ctx.catch();
+    thrown
+        .as_exception()
+        .and_then(rquickjs::Exception::message)
+        .or_else(|| thrown.as_string().and_then(|s| s.to_string().ok()))
+        .unwrap_or_else(|| "uncaught exception".to_string())
+}
+
+/// Whether a serialized `EventPayload` passes the hook's event and telemetry filters.
+fn matches_event(hook: &EventHookConfig, event: &Value) -> bool {
+    let name = event.get("event").and_then(Value::as_str).unwrap_or_default();
+    if !hook.events.is_empty() && !hook.events.iter().any(|e| e == name) {
+        return false;
+    }
+    if name == "nodetelemetry" && !hook.telemetry_types.is_empty() {
+        let event_type =
+            event.pointer("/data/event_type").and_then(Value::as_str).unwrap_or_default();
+        return hook.telemetry_types.iter().any(|pattern| {
+            pattern.strip_suffix('*').map_or(pattern == event_type, |p| event_type.starts_with(p))
+        });
+    }
+    true
+}
+
+/// Parses `onEvent`'s JSON result: `null`/`undefined`, one request, or an array of requests.
+fn parse_requests(json: Option<&str>) -> Result<Vec<RequestPayload>, String> {
+    let Some(json) = json else {
+        return Ok(Vec::new());
+    };
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let values = match value {
+        Value::Null => return Ok(Vec::new()),
+        Value::Array(values) => values,
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|v| serde_json::from_value(v).map_err(|e| format!("Invalid request from hook: {e}")))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Tests assert on known-good fixtures
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hook(events: &[&str], telemetry_types: &[&str]) -> EventHookConfig {
+        EventHookConfig {
+            name: "test".to_string(),
+            script_path: String::new(),
+            events: events.iter().map(ToString::to_string).collect(),
+            telemetry_types: telemetry_types.iter().map(ToString::to_string).collect(),
+            role: "admin".to_string(),
+            timeout_ms: None,
+            memory_limit_mb: None,
+        }
+    }
+
+    #[test]
+    fn test_event_filters() {
+        let created = json!({"event": "sessioncreated", "session_id": "s1"});
+        let vad = json!({"event": "nodetelemetry", "data": {"event_type": "vad.start"}});
+        let stt = json!({"event": "nodetelemetry", "data": {"event_type": "stt.result"}});
+
+        assert!(matches_event(&hook(&[], &[]), &created));
+        assert!(!matches_event(&hook(&["nodestatechanged"], &[]), &created));
+        assert!(matches_event(&hook(&["nodetelemetry"], &["vad.*"]), &vad));
+        assert!(!matches_event(&hook(&["nodetelemetry"], &["vad.*"]), &stt));
+        assert!(matches_event(&hook(&[], &["stt.result"]), &stt));
+        assert!(matches_event(&hook(&[], &["stt.result"]), &created));
+    }
+
+    #[test]
+    fn test_parse_requests() {
+        assert!(parse_requests(None).unwrap().is_empty());
+        assert!(parse_requests(Some("null")).unwrap().is_empty());
+
+        let single = parse_requests(Some(r#"{"action":"listnodes"}"#)).unwrap();
+        assert!(matches!(single.as_slice(), [RequestPayload::ListNodes]));
+
+        let many = parse_requests(Some(
+            r#"[{"action":"listnodes"},{"action":"destroysession","session_id":"s1"}]"#,
+        ))
+        .unwrap();
+        assert_eq!(many.len(), 2);
+
+        assert!(parse_requests(Some(r#"{"action":"nope"}"#)).is_err());
+    }
+}
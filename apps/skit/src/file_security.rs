@@ -143,6 +143,80 @@ pub fn validate_write_path(path: &str, security_config: &SecurityConfig) -> Resu
     Ok(())
 }
 
+/// Upper bound on files offered by [`list_readable_files`], to keep node listings small.
+const MAX_LISTED_FILES: usize = 500;
+
+/// Directory depth searched below each allowed pattern's base directory.
+const MAX_LISTED_DEPTH: usize = 6;
+
+/// Lists files that `validate_file_path` would accept, as options for file_reader paths.
+///
+/// Each pattern in `security.allowed_file_paths` is searched from its literal base directory
+/// (e.g. `samples` for `samples/**`). The catch-all `**` pattern is skipped rather than
+/// walking the whole filesystem. Paths under the working directory are listed relative to it.
+pub fn list_readable_files(security_config: &SecurityConfig) -> Vec<String> {
+    use std::path::{Component, PathBuf};
+
+    let Ok(cwd) = std::env::current_dir() else {
+        return Vec::new();
+    };
+
+    let mut files = std::collections::BTreeSet::new();
+    for pattern in &security_config.allowed_file_paths {
+        let base: PathBuf = std::path::Path::new(pattern)
+            .components()
+            .take_while(|c| {
+                !matches!(c, Component::Normal(part)
+                    if part.to_string_lossy().contains(['*', '?', '[']))
+            })
+            .collect();
+        if base.as_os_str().is_empty() {
+            continue;
+        }
+
+        let mut stack = vec![(cwd.join(base), 0)];
+        while let Some((dir, depth)) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    if depth < MAX_LISTED_DEPTH {
+                        stack.push((path, depth + 1));
+                    }
+                    continue;
+                }
+                let display =
+                    path.strip_prefix(&cwd).unwrap_or(&path).to_string_lossy().to_string();
+                if validate_file_path(&display, security_config).is_ok() {
+                    files.insert(display);
+                    if files.len() >= MAX_LISTED_FILES {
+                        return files.into_iter().collect();
+                    }
+                }
+            }
+        }
+    }
+    files.into_iter().collect()
+}
+
+/// Returns the param holding the write target of node kinds that write to the filesystem.
+///
+/// Every place that creates a node checks this param with [`validate_write_path`]; the param
+/// must not be tunable so it cannot be redirected after creation.
+pub fn write_path_param(kind: &str) -> Option<&'static str> {
+    match kind {
+        "core::file_writer" => Some("path"),
+        // The packager only writes plain file names inside this directory.
+        "transport::hls::packager" => Some("directory"),
+        _ => None,
+    }
+}
+
 /// Check if a canonical path is allowed by the configured patterns.
 ///
 /// Patterns can be:
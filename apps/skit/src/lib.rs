@@ -3,21 +3,39 @@
 // SPDX-License-Identifier: MPL-2.0
 
 pub mod assets;
+pub mod black_box;
 pub mod cli;
 pub mod config;
+pub mod connection_tap;
+pub mod downloads;
+pub mod estimate;
+#[cfg(feature = "script")]
+pub mod event_hooks;
 pub mod file_security;
 pub mod logging;
 #[cfg(feature = "moq")]
+pub mod moq_auth;
+#[cfg(feature = "moq")]
 pub mod moq_gateway;
+pub mod mtls;
+pub mod param_validation;
 pub mod permissions;
 pub mod plugins;
 pub mod profiling;
+pub mod recordings;
 pub mod role_extractor;
+pub mod runtime;
 pub mod samples;
 pub mod server;
+#[cfg(windows)]
+pub mod service;
 pub mod session;
+pub mod session_bridge;
 pub mod state;
 pub mod telemetry;
+pub mod upload_limits;
+#[cfg(feature = "webrtc")]
+pub mod webrtc_gateway;
 pub mod websocket;
 pub mod websocket_handlers;
 
@@ -31,26 +31,41 @@ static ALLOC: dhat::Alloc = dhat::Alloc;
 use clap::Parser;
 
 mod assets;
+mod black_box;
 mod cli;
 mod config;
+mod connection_tap;
+mod downloads;
+mod estimate;
+#[cfg(feature = "script")]
+mod event_hooks;
 mod file_security;
 mod logging;
 #[cfg(feature = "moq")]
 mod moq_gateway;
+mod mtls;
+mod param_validation;
 mod permissions;
 mod plugins;
 mod profiling;
+mod recordings;
 mod role_extractor;
+mod runtime;
 mod samples;
 mod server;
+#[cfg(windows)]
+mod service;
 mod session;
+mod session_bridge;
 mod state;
 mod telemetry;
+mod upload_limits;
+#[cfg(feature = "webrtc")]
+mod webrtc_gateway;
 mod websocket;
 mod websocket_handlers;
 
-#[tokio::main]
-async fn main() {
+fn main() {
     // Start DHAT profiler if enabled - must be created before any allocations we want to track
     // The profiler writes output to dhat-heap.json when dropped (on graceful shutdown)
     #[cfg(feature = "dhat-heap")]
@@ -63,8 +78,7 @@ async fn main() {
     let cli = cli::Cli::parse();
     cli::handle_command(&cli, |log_config, telemetry_config| {
         logging::init_logging(log_config, telemetry_config)
-    })
-    .await;
+    });
 
     // DHAT profiler is dropped here, writing dhat-heap.json
 }
@@ -0,0 +1,315 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Token authentication for MoQ WebTransport connections.
+//!
+//! With `[server.moq_auth]` enabled, clients pass a `token` query parameter signed with a
+//! shared secret. The token names the broadcast path it is valid for and whether it may
+//! publish, subscribe or both; the gateway checks it before the connection reaches a node.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use ring::hmac;
+use serde::Deserialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{MoqAuthConfig, MoqTokenFormat};
+
+/// What a connection wants to do, derived from the path it connects to.
+///
+/// `moq_peer` routes publishers to `{gateway_path}/input` and subscribers to
+/// `{gateway_path}/output`; the base path carries both directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoqAccess {
+    Publish,
+    Subscribe,
+    PublishSubscribe,
+}
+
+impl MoqAccess {
+    pub fn for_path(path: &str) -> Self {
+        match path.trim_end_matches('/').rsplit('/').next() {
+            Some("input") => Self::Publish,
+            Some("output") => Self::Subscribe,
+            _ => Self::PublishSubscribe,
+        }
+    }
+}
+
+/// Claims carried by a token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenClaims {
+    /// Broadcast path the token is valid for, e.g. `/moq/room-1`; paths below it match too
+    pub path: String,
+    #[serde(default)]
+    pub publish: bool,
+    #[serde(default)]
+    pub subscribe: bool,
+    /// Expiry as Unix seconds; tokens without one don't expire
+    #[serde(default)]
+    pub exp: Option<u64>,
+}
+
+impl TokenClaims {
+    /// Checks that the claims allow `access` on `path` at `now` (Unix seconds).
+    ///
+    /// # Errors
+    ///
+    /// Returns why the connection must be refused.
+    pub fn authorize(&self, path: &str, access: MoqAccess, now: u64) -> Result<(), AuthError> {
+        if self.exp.is_some_and(|exp| now >= exp) {
+            return Err(AuthError::Expired);
+        }
+
+        let scope = self.path.trim_end_matches('/');
+        let in_scope = scope.is_empty()
+            || path == scope
+            || path.strip_prefix(scope).is_some_and(|rest| rest.starts_with('/'));
+        if !in_scope {
+            return Err(AuthError::PathNotAllowed);
+        }
+
+        let allowed = match access {
+            MoqAccess::Publish => self.publish,
+            MoqAccess::Subscribe => self.subscribe,
+            MoqAccess::PublishSubscribe => self.publish && self.subscribe,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(AuthError::AccessDenied)
+        }
+    }
+}
+
+/// Why a connection was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    MissingToken,
+    Malformed,
+    BadSignature,
+    Expired,
+    PathNotAllowed,
+    AccessDenied,
+}
+
+impl AuthError {
+    /// Short label for logs and the rejection counter.
+    pub const fn reason(self) -> &'static str {
+        match self {
+            Self::MissingToken => "missing_token",
+            Self::Malformed => "malformed",
+            Self::BadSignature => "bad_signature",
+            Self::Expired => "expired",
+            Self::PathNotAllowed => "path_not_allowed",
+            Self::AccessDenied => "access_denied",
+        }
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::MissingToken => "no token query parameter",
+            Self::Malformed => "token is malformed",
+            Self::BadSignature => "token signature is invalid",
+            Self::Expired => "token has expired",
+            Self::PathNotAllowed => "token is not valid for this path",
+            Self::AccessDenied => "token does not grant this access",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Checks a token's signature and decodes its claims.
+///
+/// Implement this to plug in another token scheme; see [`MoqGateway::set_verifier`].
+///
+/// [`MoqGateway::set_verifier`]: crate::moq_gateway::MoqGateway::set_verifier
+pub trait TokenVerifier: Send + Sync {
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed or its signature doesn't verify.
+    fn verify(&self, token: &str) -> Result<TokenClaims, AuthError>;
+}
+
+/// Compact HMAC-SHA256 tokens: `base64url(claims JSON) "." base64url(signature)`, where
+/// the signature covers the first part.
+pub struct HmacVerifier {
+    key: hmac::Key,
+}
+
+impl HmacVerifier {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { key: hmac::Key::new(hmac::HMAC_SHA256, secret) }
+    }
+}
+
+impl TokenVerifier for HmacVerifier {
+    fn verify(&self, token: &str) -> Result<TokenClaims, AuthError> {
+        let (claims, signature) = token.split_once('.').ok_or(AuthError::Malformed)?;
+        verify_signature(&self.key, claims, signature)?;
+        decode_json(claims)
+    }
+}
+
+/// HS256 JSON Web Tokens.
+pub struct JwtVerifier {
+    key: hmac::Key,
+}
+
+impl JwtVerifier {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { key: hmac::Key::new(hmac::HMAC_SHA256, secret) }
+    }
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+impl TokenVerifier for JwtVerifier {
+    fn verify(&self, token: &str) -> Result<TokenClaims, AuthError> {
+        let (signed, signature) = token.rsplit_once('.').ok_or(AuthError::Malformed)?;
+        let (header, claims) = signed.split_once('.').ok_or(AuthError::Malformed)?;
+        // Only HS256 is accepted, so "none" or asymmetric algorithms can't be substituted.
+        let header: JwtHeader = decode_json(header)?;
+        if header.alg != "HS256" {
+            return Err(AuthError::Malformed);
+        }
+        verify_signature(&self.key, signed, signature)?;
+        decode_json(claims)
+    }
+}
+
+fn verify_signature(key: &hmac::Key, signed: &str, signature: &str) -> Result<(), AuthError> {
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| AuthError::Malformed)?;
+    hmac::verify(key, signed.as_bytes(), &signature).map_err(|_| AuthError::BadSignature)
+}
+
+fn decode_json<T: for<'de> Deserialize<'de>>(part: &str) -> Result<T, AuthError> {
+    let bytes = URL_SAFE_NO_PAD.decode(part).map_err(|_| AuthError::Malformed)?;
+    serde_json::from_slice(&bytes).map_err(|_| AuthError::Malformed)
+}
+
+/// Builds the verifier configured in `[server.moq_auth]`, or `None` when auth is disabled.
+///
+/// # Errors
+///
+/// Returns an error if auth is enabled but the secret's environment variable is unset or empty.
+pub fn verifier_from_config(
+    config: &MoqAuthConfig,
+) -> Result<Option<Arc<dyn TokenVerifier>>, String> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let secret = std::env::var(&config.secret_env).unwrap_or_default();
+    if secret.is_empty() {
+        return Err(format!(
+            "[server.moq_auth] is enabled but the secret variable '{}' is not set",
+            config.secret_env
+        ));
+    }
+    let verifier: Arc<dyn TokenVerifier> = match config.format {
+        MoqTokenFormat::Jwt => Arc::new(JwtVerifier::new(secret.as_bytes())),
+        MoqTokenFormat::Hmac => Arc::new(HmacVerifier::new(secret.as_bytes())),
+    };
+    Ok(Some(verifier))
+}
+
+/// Authorizes a connection to `path` with the `token` from its URL query.
+///
+/// # Errors
+///
+/// Returns why the connection must be refused.
+pub fn authorize(
+    verifier: &dyn TokenVerifier,
+    path: &str,
+    query: Option<&str>,
+) -> Result<TokenClaims, AuthError> {
+    let token = query
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty())
+        .ok_or(AuthError::MissingToken)?;
+    let claims = verifier.verify(token)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    claims.authorize(path, MoqAccess::for_path(path), now)?;
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn sign(signed: &str) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, SECRET);
+        URL_SAFE_NO_PAD.encode(hmac::sign(&key, signed.as_bytes()))
+    }
+
+    fn encode(value: &serde_json::Value) -> String {
+        URL_SAFE_NO_PAD.encode(value.to_string())
+    }
+
+    fn hmac_token(claims: &serde_json::Value) -> String {
+        let claims = encode(claims);
+        format!("{claims}.{}", sign(&claims))
+    }
+
+    fn jwt(alg: &str, claims: &serde_json::Value) -> String {
+        let signed = format!("{}.{}", encode(&json!({"alg": alg, "typ": "JWT"})), encode(claims));
+        format!("{signed}.{}", sign(&signed))
+    }
+
+    #[test]
+    fn test_hmac_token_scoped_to_path_and_direction() {
+        let verifier = HmacVerifier::new(SECRET);
+        let token = hmac_token(&json!({"path": "/moq/room-1", "publish": true}));
+        let query = format!("token={token}");
+
+        assert!(authorize(&verifier, "/moq/room-1/input", Some(&query)).is_ok());
+        assert_eq!(
+            authorize(&verifier, "/moq/room-1/output", Some(&query)).unwrap_err(),
+            AuthError::AccessDenied
+        );
+        assert_eq!(
+            authorize(&verifier, "/moq/room-10/input", Some(&query)).unwrap_err(),
+            AuthError::PathNotAllowed
+        );
+        assert_eq!(
+            authorize(&verifier, "/moq/room-1/input", None).unwrap_err(),
+            AuthError::MissingToken
+        );
+    }
+
+    #[test]
+    fn test_jwt_signature_algorithm_and_expiry() {
+        let verifier = JwtVerifier::new(SECRET);
+        let claims = json!({"path": "/moq", "publish": true, "subscribe": true, "exp": 2000});
+        let token = jwt("HS256", &claims);
+        let decoded = verifier.verify(&token).unwrap();
+        assert!(decoded.authorize("/moq", MoqAccess::PublishSubscribe, 1000).is_ok());
+        assert_eq!(
+            decoded.authorize("/moq", MoqAccess::PublishSubscribe, 2000).unwrap_err(),
+            AuthError::Expired
+        );
+
+        let tampered = format!("{}x", &token[..token.len() - 1]);
+        assert!(verifier.verify(&tampered).is_err());
+        assert_eq!(verifier.verify(&jwt("none", &claims)).unwrap_err(), AuthError::Malformed);
+        assert_eq!(
+            HmacVerifier::new(b"other").verify(&hmac_token(&claims)).unwrap_err(),
+            AuthError::BadSignature
+        );
+    }
+}
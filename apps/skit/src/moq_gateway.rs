@@ -8,6 +8,7 @@
 //! to the appropriate session's moq_peer node based on URL path matching.
 
 use async_trait::async_trait;
+use opentelemetry::{global, metrics::Counter, KeyValue};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -15,6 +16,8 @@ use streamkit_core::moq_gateway::{MoqConnection, MoqConnectionResult, MoqGateway
 use tokio::sync::{mpsc, oneshot, Notify, RwLock};
 use tracing::{debug, error, info, warn};
 
+use crate::moq_auth::{self, AuthError, TokenVerifier};
+
 /// A route registration from a path pattern to a connection receiver
 struct Route {
     /// The session ID that owns this route
@@ -40,6 +43,12 @@ pub struct MoqGateway {
     /// Certificate fingerprints for local development (served via HTTP)
     #[cfg(feature = "moq")]
     fingerprints: Arc<RwLock<Vec<String>>>,
+
+    /// Verifies connection tokens; `None` accepts every connection
+    verifier: Arc<RwLock<Option<Arc<dyn TokenVerifier>>>>,
+
+    /// Connections refused by the verifier, by reason
+    auth_rejections: Counter<u64>,
 }
 
 impl MoqGateway {
@@ -50,6 +59,40 @@ impl MoqGateway {
             route_notify: Arc::new(Notify::new()),
             #[cfg(feature = "moq")]
             fingerprints: Arc::new(RwLock::new(Vec::new())),
+            verifier: Arc::new(RwLock::new(None)),
+            auth_rejections: global::meter("skit_moq")
+                .u64_counter("moq.auth.rejections")
+                .with_description("MoQ connections rejected by token authentication")
+                .build(),
+        }
+    }
+
+    /// Require connections to present a token accepted by `verifier`
+    pub async fn set_verifier(&self, verifier: Arc<dyn TokenVerifier>) {
+        *self.verifier.write().await = Some(verifier);
+    }
+
+    /// Check a connection's token before it is accepted.
+    ///
+    /// Always succeeds when no verifier is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the connection was refused; the rejection is logged and counted.
+    pub async fn authorize(&self, path: &str, query: Option<&str>) -> Result<(), AuthError> {
+        let Some(verifier) = self.verifier.read().await.clone() else {
+            return Ok(());
+        };
+        match moq_auth::authorize(verifier.as_ref(), path, query) {
+            Ok(claims) => {
+                debug!(path = %path, scope = %claims.path, "MoQ connection authorized");
+                Ok(())
+            },
+            Err(e) => {
+                warn!(path = %path, reason = e.reason(), "Rejected MoQ connection: {e}");
+                self.auth_rejections.add(1, &[KeyValue::new("reason", e.reason())]);
+                Err(e)
+            },
         }
     }
 
@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Mutual TLS for the HTTPS listener.
+//!
+//! [`server_config`] builds a rustls config that verifies client certificates against
+//! `[server.mtls].client_ca_path`. [`ClientCertAcceptor`] attaches the verified certificate's
+//! identity to every request on the connection, and [`client_cert_role_middleware`] turns it
+//! into the role that [`crate::role_extractor`] resolves.
+
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::{AddExtension, Next};
+use axum::response::Response;
+use axum::Extension;
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use futures::future::BoxFuture;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::RootCertStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::server::TlsStream;
+use tower::Layer;
+use tracing::debug;
+
+use crate::config::ServerConfig;
+
+/// Internal header carrying the role mapped from a verified client certificate.
+///
+/// Set only by [`client_cert_role_middleware`], which strips any value sent by the client.
+pub const CLIENT_CERT_ROLE_HEADER: &str = "x-streamkit-client-cert-role";
+
+/// Identity taken from a verified client certificate.
+#[derive(Debug, Clone, Default)]
+pub struct ClientCertIdentity {
+    /// Subject common name, if the certificate has one
+    pub common_name: Option<String>,
+    /// Role mapped from `common_name` via `[server.mtls].roles`
+    pub role: Option<String>,
+}
+
+/// Builds the rustls server config for a listener that verifies client certificates.
+///
+/// # Errors
+///
+/// Returns an error if a certificate, key or CA file cannot be read or parsed, or the CA
+/// bundle contains no usable certificate.
+pub fn server_config(config: &ServerConfig) -> Result<RustlsConfig, Box<dyn std::error::Error>> {
+    let certs = CertificateDer::pem_file_iter(&config.cert_path)
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .map_err(|e| format!("Failed to read TLS certificate '{}': {e}", config.cert_path))?;
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)
+        .map_err(|e| format!("Failed to read TLS key '{}': {e}", config.key_path))?;
+
+    let ca_path = &config.mtls.client_ca_path;
+    let mut roots = RootCertStore::empty();
+    for ca in CertificateDer::pem_file_iter(ca_path)
+        .map_err(|e| format!("Failed to read client CA bundle '{ca_path}': {e}"))?
+    {
+        let ca = ca.map_err(|e| format!("Failed to parse client CA bundle '{ca_path}': {e}"))?;
+        roots.add(ca).map_err(|e| format!("Invalid client CA in '{ca_path}': {e}"))?;
+    }
+    if roots.is_empty() {
+        return Err(format!("Client CA bundle '{ca_path}' contains no certificates").into());
+    }
+
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots));
+    let verifier = if config.mtls.require_client_cert {
+        verifier.build()?
+    } else {
+        verifier.allow_unauthenticated().build()?
+    };
+
+    let mut tls = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(certs, key)?;
+    tls.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(RustlsConfig::from_config(Arc::new(tls)))
+}
+
+/// Rustls acceptor that records the peer's client certificate identity on the connection.
+#[derive(Clone)]
+pub struct ClientCertAcceptor {
+    inner: RustlsAcceptor,
+    roles: Arc<HashMap<String, String>>,
+}
+
+impl ClientCertAcceptor {
+    pub fn new(config: RustlsConfig, roles: HashMap<String, String>) -> Self {
+        Self { inner: RustlsAcceptor::new(config), roles: Arc::new(roles) }
+    }
+}
+
+impl<I, S> Accept<I, S> for ClientCertAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = TlsStream<I>;
+    type Service = AddExtension<S, ClientCertIdentity>;
+    type Future = BoxFuture<'static, std::io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let acceptor = self.inner.clone();
+        let roles = Arc::clone(&self.roles);
+
+        Box::pin(async move {
+            let (stream, service) = acceptor.accept(stream, service).await?;
+            let identity = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(<[CertificateDer<'_>]>::first)
+                .map(|leaf| identify(leaf, &roles))
+                .unwrap_or_default();
+            debug!(
+                common_name = ?identity.common_name,
+                role = ?identity.role,
+                "Accepted TLS connection"
+            );
+            Ok((stream, Extension(identity).layer(service)))
+        })
+    }
+}
+
+fn identify(cert: &CertificateDer<'_>, roles: &HashMap<String, String>) -> ClientCertIdentity {
+    let common_name =
+        x509_parser::parse_x509_certificate(cert.as_ref()).ok().and_then(|(_, cert)| {
+            cert.subject()
+                .iter_common_name()
+                .next()
+                .and_then(|cn| cn.as_str().ok())
+                .map(str::to_string)
+        });
+    let role = common_name.as_ref().and_then(|cn| roles.get(cn)).cloned();
+    ClientCertIdentity { common_name, role }
+}
+
+/// Replaces [`CLIENT_CERT_ROLE_HEADER`] with the role of the connection's client certificate.
+///
+/// Runs on every listener so the header can't be forged over plain HTTP or the Unix socket.
+pub async fn client_cert_role_middleware(mut req: Request, next: Next) -> Response {
+    req.headers_mut().remove(CLIENT_CERT_ROLE_HEADER);
+    let role = req
+        .extensions()
+        .get::<ClientCertIdentity>()
+        .and_then(|identity| identity.role.as_deref())
+        .and_then(|role| HeaderValue::from_str(role).ok());
+    if let Some(role) = role {
+        req.headers_mut().insert(CLIENT_CERT_ROLE_HEADER, role);
+    }
+    next.run(req).await
+}
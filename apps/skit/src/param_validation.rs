@@ -0,0 +1,212 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! JSON Schema validation of node parameters on the control plane.
+//!
+//! Nodes deserialize their params with serde, which silently fills defaults for
+//! misspelled or mistyped fields. Validating against the registered param schema
+//! first lets the server reject such requests with the offending path instead.
+
+use serde_json::Value;
+use std::fmt;
+
+/// A single schema violation, addressed by JSON Pointer into the params object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamError {
+    /// JSON Pointer to the offending value (empty for the params object itself)
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// Validates `params` against a node's parameter schema.
+///
+/// With `partial` set (runtime tuning), top-level `required` is ignored because
+/// `UpdateParams` commonly carries only the fields being changed.
+///
+/// Schemas the validator cannot compile (e.g. from third-party plugins) are logged
+/// and treated as permissive rather than blocking the node.
+///
+/// # Errors
+///
+/// Returns every violation found, in schema evaluation order.
+pub fn validate_params(
+    schema: &Value,
+    params: &Value,
+    partial: bool,
+) -> Result<(), Vec<ParamError>> {
+    let mut schema = schema.clone();
+    if partial {
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("required");
+        }
+    }
+
+    let validator = match jsonschema::validator_for(&schema) {
+        Ok(validator) => validator,
+        Err(e) => {
+            tracing::warn!(error = %e, "Skipping param validation: schema failed to compile");
+            return Ok(());
+        },
+    };
+
+    let errors: Vec<ParamError> = validator
+        .iter_errors(params)
+        .map(|e| ParamError { path: e.instance_path().to_string(), message: e.to_string() })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that a runtime update only changes fields marked `"tunable": true`.
+///
+/// Schemas that never mention `tunable` predate the metadata, so their nodes keep
+/// deciding for themselves. Construct-only fields are accepted when their value matches
+/// `current`, letting clients resend the whole params object.
+///
+/// # Errors
+///
+/// Returns one error per construct-only field the update would change.
+pub fn check_tunable(
+    schema: &Value,
+    params: &Value,
+    current: Option<&Value>,
+) -> Result<(), Vec<ParamError>> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    if !properties.values().any(|prop| prop.get("tunable").is_some()) {
+        return Ok(());
+    }
+    let Some(params) = params.as_object() else {
+        return Ok(());
+    };
+
+    let errors: Vec<ParamError> = params
+        .iter()
+        .filter(|(key, value)| {
+            let tunable = properties
+                .get(key.as_str())
+                .and_then(|prop| prop.get("tunable"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            !tunable && current.and_then(|c| c.get(key.as_str())) != Some(*value)
+        })
+        .map(|(key, _)| ParamError {
+            path: format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            message: "cannot be changed at runtime; recreate the node to apply it".to_string(),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Formats validation errors for a node into a single client-facing message.
+pub fn describe_errors(node_id: &str, errors: &[ParamError]) -> String {
+    let details: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    format!("Invalid params for node '{node_id}': {}", details.join("; "))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Tests assert on expected validation failures
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn gain_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "gain": { "type": "number", "minimum": 0.0, "maximum": 4.0 },
+                "mode": { "type": "string", "enum": ["linear", "db"] }
+            },
+            "required": ["gain"]
+        })
+    }
+
+    #[test]
+    fn test_valid_params_pass() {
+        assert!(validate_params(&gain_schema(), &json!({"gain": 1.5}), false).is_ok());
+        assert!(validate_params(&gain_schema(), &json!({"gain": 2, "mode": "db"}), false).is_ok());
+    }
+
+    #[test]
+    fn test_errors_carry_json_pointer_paths() {
+        let errors =
+            validate_params(&gain_schema(), &json!({"gain": "loud", "mode": "log"}), false)
+                .unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert!(paths.contains(&"/gain"));
+        assert!(paths.contains(&"/mode"));
+
+        let errors = validate_params(&gain_schema(), &json!({"gain": 9.0}), false).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/gain");
+    }
+
+    #[test]
+    fn test_partial_updates_ignore_required() {
+        assert!(validate_params(&gain_schema(), &json!({"mode": "db"}), false).is_err());
+        assert!(validate_params(&gain_schema(), &json!({"mode": "db"}), true).is_ok());
+        assert!(validate_params(&gain_schema(), &json!({"gain": -1.0}), true).is_err());
+    }
+
+    #[test]
+    fn test_construct_only_fields_rejected_when_changed() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "gain": { "type": "number", "tunable": true },
+                "channels": { "type": "integer", "tunable": false },
+                "label": { "type": "string" }
+            }
+        });
+        let current = json!({"gain": 1.0, "channels": 2});
+
+        assert!(check_tunable(&schema, &json!({"gain": 0.5}), Some(&current)).is_ok());
+        assert!(
+            check_tunable(&schema, &json!({"gain": 0.5, "channels": 2}), Some(&current)).is_ok()
+        );
+
+        let errors = check_tunable(&schema, &json!({"channels": 1, "label": "x"}), Some(&current))
+            .unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/channels", "/label"]);
+    }
+
+    #[test]
+    fn test_schemas_without_tunable_metadata_are_unchecked() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "model_path": { "type": "string" } }
+        });
+        assert!(check_tunable(&schema, &json!({"model_path": "/m.bin"}), None).is_ok());
+    }
+
+    #[test]
+    fn test_describe_errors() {
+        let errors = vec![
+            ParamError { path: "/gain".to_string(), message: "too large".to_string() },
+            ParamError { path: String::new(), message: "not an object".to_string() },
+        ];
+        assert_eq!(
+            describe_errors("g1", &errors),
+            "Invalid params for node 'g1': /gain: too large; /: not an object"
+        );
+    }
+}
@@ -80,6 +80,14 @@ pub struct Permissions {
     #[serde(default)]
     pub allowed_nodes: Vec<String>,
 
+    /// Denied node types (e.g., "core::script", "core::file_*").
+    ///
+    /// Evaluated after `allowed_nodes` and always takes precedence, so a role can be
+    /// granted a broad namespace (`"core::*"`) while still excluding specific kinds.
+    /// Globally disabled kinds (`[permissions].disabled_nodes`) are merged in here.
+    #[serde(default)]
+    pub denied_nodes: Vec<String>,
+
     /// Allowed plugin node kinds (e.g., "plugin::native::whisper", "plugin::wasm::gain", "plugin::*")
     /// Empty list means no plugins are allowed (deny by default).
     /// Use `["*"]` to allow everything.
@@ -90,6 +98,12 @@ pub struct Permissions {
     #[serde(default)]
     pub access_all_sessions: bool,
 
+    /// Can see node params marked sensitive (URLs with tokens, file paths).
+    ///
+    /// Without it, such values are masked in pipelines and param change events.
+    #[serde(default)]
+    pub view_secrets: bool,
+
     /// Can upload audio assets
     #[serde(default)]
     pub upload_assets: bool,
@@ -123,8 +137,10 @@ impl Permissions {
             delete_samples: true,
             allowed_samples: vec!["*".to_string()], // Wildcard = allow all
             allowed_nodes: vec!["*".to_string()],   // Wildcard = allow all
+            denied_nodes: Vec::new(),
             allowed_plugins: vec!["*".to_string()], // Wildcard = allow all
             access_all_sessions: true,
+            view_secrets: true,
             upload_assets: true,
             delete_assets: true,
             allowed_assets: vec!["*".to_string()], // Wildcard = allow all
@@ -171,10 +187,13 @@ impl Permissions {
                 "core::telemetry_tap".to_string(),
                 "core::telemetry_out".to_string(),
                 "core::sink".to_string(),
+                "core::turn_manager".to_string(),
+                // core::llm is left out: it posts to any endpoint (SSRF risk)
                 // Plugins are represented as node kinds too (e.g. plugin::native::whisper).
                 // This must be aligned with allowed_plugins for RBAC to work as expected.
                 "plugin::*".to_string(),
             ],
+            denied_nodes: Vec::new(),
             allowed_plugins: vec![
                 // Users can use already-loaded plugins.
                 //
@@ -188,6 +207,7 @@ impl Permissions {
                 "plugin::*".to_string(),
             ],
             access_all_sessions: false, // Can only access own sessions
+            view_secrets: false,        // Sensitive params are masked
             upload_assets: true,
             delete_assets: true,
             allowed_assets: vec![
@@ -214,6 +234,7 @@ impl Permissions {
             write_samples: self.write_samples,
             delete_samples: self.delete_samples,
             access_all_sessions: self.access_all_sessions,
+            view_secrets: self.view_secrets,
             upload_assets: self.upload_assets,
             delete_assets: self.delete_assets,
         }
@@ -241,6 +262,15 @@ impl Permissions {
             return false;
         }
 
+        // Deny patterns win over allow patterns
+        if self
+            .denied_nodes
+            .iter()
+            .any(|pattern| Pattern::new(pattern).ok().is_some_and(|p| p.matches(node_type)))
+        {
+            return false;
+        }
+
         // Check against patterns (supports wildcards like "audio::*")
         self.allowed_nodes
             .iter()
@@ -310,6 +340,13 @@ pub struct PermissionsConfig {
     #[serde(default = "default_roles")]
     pub roles: HashMap<String, Permissions>,
 
+    /// Node types disabled for every role (supports globs like "core::file_*").
+    ///
+    /// Disabled kinds are hidden from node listings and rejected when adding nodes,
+    /// regardless of each role's `allowed_nodes`.
+    #[serde(default)]
+    pub disabled_nodes: Vec<String>,
+
     /// Maximum concurrent dynamic sessions (global limit, applies to all users)
     /// None = unlimited
     #[serde(default)]
@@ -328,6 +365,7 @@ impl Default for PermissionsConfig {
             role_header: None,
             allow_insecure_no_auth: false,
             roles: default_roles(),
+            disabled_nodes: Vec::new(),
             max_concurrent_sessions: None,
             max_concurrent_oneshots: None,
         }
@@ -346,8 +384,16 @@ fn default_roles() -> HashMap<String, Permissions> {
 }
 
 impl PermissionsConfig {
-    /// Get permissions for a role, falling back to default if not found
+    /// Get permissions for a role, falling back to default if not found.
+    ///
+    /// Globally disabled node types are folded into the returned `denied_nodes`.
     pub fn get_role(&self, role_name: &str) -> Permissions {
+        let mut perms = self.lookup_role(role_name);
+        perms.denied_nodes.extend(self.disabled_nodes.iter().cloned());
+        perms
+    }
+
+    fn lookup_role(&self, role_name: &str) -> Permissions {
         use tracing::warn;
 
         self.roles.get(role_name).map_or_else(
@@ -372,7 +418,6 @@ impl PermissionsConfig {
     }
 
     /// Get the default role permissions
-    #[allow(dead_code)]
     pub fn get_default(&self) -> Permissions {
         self.get_role(&self.default_role)
     }
@@ -432,6 +477,40 @@ mod tests {
         assert!(!perms.is_node_allowed("transport::http::client"));
     }
 
+    #[test]
+    fn test_denied_nodes_take_precedence() {
+        let perms = Permissions {
+            allowed_nodes: vec!["core::*".to_string(), "audio::*".to_string()],
+            denied_nodes: vec!["core::script".to_string(), "core::file_*".to_string()],
+            ..Default::default()
+        };
+
+        assert!(perms.is_node_allowed("core::passthrough"));
+        assert!(perms.is_node_allowed("audio::gain"));
+        assert!(!perms.is_node_allowed("core::script"));
+        assert!(!perms.is_node_allowed("core::file_reader"));
+        assert!(!perms.is_node_allowed("core::file_writer"));
+    }
+
+    #[test]
+    fn test_globally_disabled_nodes_apply_to_all_roles() {
+        let config = PermissionsConfig {
+            disabled_nodes: vec!["core::script".to_string()],
+            ..Default::default()
+        };
+
+        let admin = config.get_role("admin");
+        assert!(!admin.is_node_allowed("core::script"));
+        assert!(admin.is_node_allowed("core::file_writer"));
+
+        let user = config.get_role("user");
+        assert!(!user.is_node_allowed("core::script"));
+        assert!(user.is_node_allowed("core::passthrough"));
+
+        // Unknown roles fall back to the default role and still honor the global list
+        assert!(!config.get_role("nobody").is_node_allowed("core::script"));
+    }
+
     #[test]
     fn test_plugin_matching() {
         let perms = Permissions {
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
@@ -13,13 +13,19 @@ use anyhow::{anyhow, Context, Result};
 use opentelemetry::{global, KeyValue};
 use serde::Serialize;
 use streamkit_engine::Engine;
+use streamkit_plugin_native::placement::{ComputePool, PlacementPolicy};
+use streamkit_plugin_native::requirements::PluginRequirements;
+use streamkit_plugin_native::sandbox::SandboxPool;
 use streamkit_plugin_native::LoadedNativePlugin;
 use streamkit_plugin_wasm::{
     namespaced_kind as wasm_namespaced_kind, LoadedPlugin as WasmLoadedPlugin, PluginRuntime,
+    PluginRuntimeConfig, COMPILATION_CACHE_DIR_NAME, METADATA_CACHE_FILE_NAME,
 };
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
+use crate::config::{NativePlacementPolicy, NativeSandboxConfig};
+
 /// The type of plugin
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -39,6 +45,24 @@ pub struct PluginSummary {
     pub plugin_type: PluginType,
 }
 
+/// A plugin file found on disk that failed to load, exposed via the HTTP API.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginLoadFailure {
+    pub file_name: String,
+    pub plugin_type: PluginType,
+    /// The full error chain, e.g. the missing shared library or host feature
+    pub error: String,
+    pub failed_at_ms: u128,
+}
+
+impl PluginLoadFailure {
+    fn new(file_name: String, plugin_type: PluginType, error: &anyhow::Error) -> Self {
+        let failed_at_ms =
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+        Self { file_name, plugin_type, error: format!("{error:#}"), failed_at_ms }
+    }
+}
+
 impl PluginSummary {
     fn from_entry(kind: String, entry: &ManagedPlugin) -> Self {
         let loaded_at_ms = entry.loaded_at.duration_since(UNIX_EPOCH).map_or_else(
@@ -81,6 +105,8 @@ struct ManagedPlugin {
     loaded_at: SystemTime,
     original_kind: String,
     plugin_type: PluginType,
+    /// Always empty for WASM plugins
+    requirements: PluginRequirements,
 }
 
 impl ManagedPlugin {
@@ -97,6 +123,7 @@ impl ManagedPlugin {
             loaded_at: SystemTime::now(),
             original_kind,
             plugin_type: PluginType::Wasm,
+            requirements: PluginRequirements::default(),
         }
     }
 
@@ -106,6 +133,7 @@ impl ManagedPlugin {
         categories: Vec<String>,
         file_path: PathBuf,
     ) -> Self {
+        let requirements = plugin.metadata().requirements.clone();
         Self {
             plugin: LoadedPluginInner::Native(Arc::new(plugin)),
             categories,
@@ -113,6 +141,7 @@ impl ManagedPlugin {
             loaded_at: SystemTime::now(),
             original_kind,
             plugin_type: PluginType::Native,
+            requirements,
         }
     }
 }
@@ -121,8 +150,14 @@ impl ManagedPlugin {
 pub struct UnifiedPluginManager {
     wasm_runtime: PluginRuntime,
     plugins: HashMap<String, ManagedPlugin>,
+    /// Plugin files in the plugin directories that failed to load, by file name
+    load_failures: BTreeMap<String, PluginLoadFailure>,
     wasm_directory: PathBuf,
     native_directory: PathBuf,
+    /// Shared by every native plugin so automatic placement balances across all of them.
+    compute_pool: Arc<ComputePool>,
+    /// Working directories and environments of native plugin instances, if enabled.
+    sandboxes: Option<Arc<SandboxPool>>,
     engine: Arc<Engine>,
     #[allow(dead_code)] // Will be used when plugins are migrated to new resource system
     resource_manager: Arc<streamkit_core::ResourceManager>,
@@ -157,15 +192,23 @@ impl UnifiedPluginManager {
             })?;
         }
 
-        let wasm_runtime =
-            PluginRuntime::new(streamkit_plugin_wasm::PluginRuntimeConfig::default())?;
+        // Unchanged plugins are registered from cached metadata instead of being compiled
+        // and instantiated at every startup, and compiled code is reused across restarts.
+        let wasm_runtime = PluginRuntime::new(PluginRuntimeConfig {
+            compilation_cache_dir: Some(wasm_directory.join(COMPILATION_CACHE_DIR_NAME)),
+            ..PluginRuntimeConfig::default()
+        })?
+        .with_metadata_cache(wasm_directory.join(METADATA_CACHE_FILE_NAME));
 
         let meter = global::meter("skit_plugins");
         Ok(Self {
             wasm_runtime,
             plugins: HashMap::new(),
+            load_failures: BTreeMap::new(),
             wasm_directory,
             native_directory,
+            compute_pool: Arc::new(ComputePool::default()),
+            sandboxes: None,
             engine,
             resource_manager,
             plugins_loaded_gauge: meter
@@ -179,6 +222,36 @@ impl UnifiedPluginManager {
         })
     }
 
+    /// Sets the automatic CPU placement policy for native plugin instances.
+    #[must_use]
+    pub fn with_native_placement(mut self, policy: NativePlacementPolicy) -> Self {
+        let policy = match policy {
+            NativePlacementPolicy::None => PlacementPolicy::None,
+            NativePlacementPolicy::NumaSpread => PlacementPolicy::NumaSpread,
+        };
+        self.compute_pool = Arc::new(ComputePool::new(policy));
+        self
+    }
+
+    /// Sandboxes native plugin instances as configured. `plugin_base_dir` holds the default
+    /// work directory.
+    #[must_use]
+    pub fn with_native_sandbox(
+        mut self,
+        config: &NativeSandboxConfig,
+        plugin_base_dir: &Path,
+    ) -> Self {
+        self.sandboxes = config.enabled.then(|| {
+            let root = config
+                .work_directory
+                .as_ref()
+                .map_or_else(|| plugin_base_dir.join("work"), PathBuf::from);
+            info!(work_directory = %root.display(), "Sandboxing native plugin instances");
+            Arc::new(SandboxPool::new(root, &config.env_passthrough))
+        });
+        self
+    }
+
     /// Load all native plugins from the native directory
     fn load_native_plugins_from_dir(&mut self) -> Result<Vec<PluginSummary>> {
         let mut summaries = Vec::new();
@@ -204,7 +277,8 @@ impl UnifiedPluginManager {
                     summaries.push(summary);
                 },
                 Err(err) => {
-                    warn!(error = %err, file = ?path, "Failed to load native plugin from disk");
+                    warn!(error = %format!("{err:#}"), file = ?path, "Failed to load native plugin from disk");
+                    self.record_load_failure(&path, PluginType::Native, &err);
                 },
             }
         }
@@ -233,7 +307,8 @@ impl UnifiedPluginManager {
                     summaries.push(summary);
                 },
                 Err(err) => {
-                    warn!(error = %err, file = ?path, "Failed to load WASM plugin from disk");
+                    warn!(error = %format!("{err:#}"), file = ?path, "Failed to load WASM plugin from disk");
+                    self.record_load_failure(&path, PluginType::Wasm, &err);
                 },
             }
         }
@@ -257,6 +332,9 @@ impl UnifiedPluginManager {
     /// Pre-warms a plugin by creating a dummy node instance to trigger model loading.
     /// This reduces latency for the first real usage of the plugin.
     ///
+    /// With a non-zero `pool_size` the instances are kept in the engine's warm pool
+    /// instead, so sessions can also skip creating them.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -266,13 +344,25 @@ impl UnifiedPluginManager {
         engine: Arc<Engine>,
         kind: &str,
         params: Option<serde_json::Value>,
+        pool_size: usize,
     ) -> Result<()> {
         debug!(
             plugin = %kind,
             params_present = params.is_some(),
+            pool_size,
             "Pre-warming plugin (creating instance to load models)"
         );
 
+        if pool_size > 0 {
+            engine
+                .warm_pool
+                .fill(engine.registry.clone(), kind, params, pool_size)
+                .await
+                .with_context(|| format!("Failed to fill warm pool for plugin '{kind}'"))?;
+            info!(plugin = %kind, pool_size, "Pre-warming completed successfully");
+            return Ok(());
+        }
+
         // Use spawn_blocking for safety - GPU initialization might throw C++ exceptions
         let kind = kind.to_string();
         let kind_clone = kind.clone(); // Clone for error message after move
@@ -346,6 +436,7 @@ impl UnifiedPluginManager {
                                 engine.clone(),
                                 &plugin_config.kind,
                                 plugin_config.params.clone(),
+                                plugin_config.pool_size,
                             )
                             .await
                             {
@@ -363,6 +454,7 @@ impl UnifiedPluginManager {
                                             engine.clone(),
                                             &plugin_config.kind,
                                             Some(fallback_params.clone()),
+                                            plugin_config.pool_size,
                                         )
                                         .await
                                         {
@@ -421,6 +513,14 @@ impl UnifiedPluginManager {
             ));
         }
 
+        if let Some(other_kind) = self.plugins.iter().find_map(|(other_kind, other)| {
+            other.requirements.conflicts_with_kind(&kind, &original_kind).then_some(other_kind)
+        }) {
+            return Err(anyhow!(
+                "Plugin '{kind}' conflicts with loaded plugin '{other_kind}'; unload '{other_kind}' first"
+            ));
+        }
+
         let param_schema: serde_json::Value = serde_json::from_str(&metadata.param_schema)
             .with_context(|| format!("Plugin '{kind}' provided invalid param_schema JSON"))?;
         let categories = metadata.categories;
@@ -463,6 +563,7 @@ impl UnifiedPluginManager {
         }
 
         let summary = PluginSummary::from_entry(kind.clone(), &managed);
+        self.load_failures.remove(&summary.file_name);
         self.plugins.insert(kind, managed);
 
         // Update metrics
@@ -486,7 +587,12 @@ impl UnifiedPluginManager {
                 tracing::error!(error = %e, path = ?path, "Detailed native plugin load error");
                 e
             })
-            .with_context(|| format!("failed to load native plugin {}", path.to_string_lossy()))?;
+            .with_context(|| format!("failed to load native plugin {}", path.to_string_lossy()))?
+            .with_compute_pool(Arc::clone(&self.compute_pool));
+        let plugin = match &self.sandboxes {
+            Some(sandboxes) => plugin.with_sandboxes(Arc::clone(sandboxes)),
+            None => plugin,
+        };
 
         let metadata = plugin.metadata();
         let original_kind = metadata.kind.clone();
@@ -500,6 +606,18 @@ impl UnifiedPluginManager {
             ));
         }
 
+        // Conflicts count whichever of the two plugins declared them
+        let requirements = &metadata.requirements;
+        if let Some(other_kind) = self.plugins.iter().find_map(|(other_kind, other)| {
+            (requirements.conflicts_with_kind(other_kind, &other.original_kind)
+                || other.requirements.conflicts_with_kind(&kind, &original_kind))
+            .then_some(other_kind)
+        }) {
+            return Err(anyhow!(
+                "Plugin '{kind}' conflicts with loaded plugin '{other_kind}'; unload '{other_kind}' first"
+            ));
+        }
+
         // Ensure we don't override an existing node definition
         {
             let registry =
@@ -524,6 +642,7 @@ impl UnifiedPluginManager {
             ManagedPlugin::new_native(plugin, original_kind, categories, path.to_path_buf());
 
         let summary = PluginSummary::from_entry(kind.clone(), &managed);
+        self.load_failures.remove(&summary.file_name);
         self.plugins.insert(kind, managed);
 
         // Update metrics
@@ -558,6 +677,8 @@ impl UnifiedPluginManager {
                 );
             }
         }
+        // Pooled instances hold the plugin library open too
+        self.engine.warm_pool.evict_kind(kind);
 
         let plugin_type = match managed.plugin_type {
             PluginType::Wasm => "wasm",
@@ -603,6 +724,20 @@ impl UnifiedPluginManager {
             .collect()
     }
 
+    /// Returns the plugin files that failed to load from the plugin directories.
+    pub fn load_failures(&self) -> Vec<PluginLoadFailure> {
+        self.load_failures.values().cloned().collect()
+    }
+
+    fn record_load_failure(&mut self, path: &Path, plugin_type: PluginType, err: &anyhow::Error) {
+        let file_name = path.file_name().map_or_else(
+            || path.to_string_lossy().into_owned(),
+            |f| f.to_string_lossy().into_owned(),
+        );
+        self.load_failures
+            .insert(file_name.clone(), PluginLoadFailure::new(file_name, plugin_type, err));
+    }
+
     /// Helper method to update the loaded plugins gauge by counting each type
     fn update_loaded_gauge(&self) {
         let wasm_count =
@@ -0,0 +1,376 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Retrieval of the recordings written by a live session's `core::file_writer` nodes.
+//!
+//! `GET /api/v1/sessions/{id}/recordings` lists them; `GET .../recordings/{node_id}` downloads
+//! one. Without a time range the file is served as it is, with `Range` support. With
+//! `start`/`end` or `last` (seconds), Ogg/Opus recordings are cut server-side by a synthesized
+//! oneshot pipeline (`file_reader → ogg demuxer → trim → ogg muxer`), so pulling "the last
+//! five minutes" of a long recording does not mean downloading all of it.
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::session::Session;
+use crate::state::AppState;
+use streamkit_api::yaml::{compile, Step, UserPipeline};
+use streamkit_api::{EngineMode, Pipeline};
+
+const RECORDER_KIND: &str = "core::file_writer";
+
+/// How far into a recording to look for the `OpusHead` packet.
+const OPUS_HEAD_SEARCH_BYTES: u64 = 4096;
+
+/// A recording written by one of a session's nodes.
+#[derive(Debug, Serialize)]
+pub struct RecordingInfo {
+    /// Id of the `core::file_writer` node writing the recording
+    pub node_id: String,
+    pub content_type: &'static str,
+    /// Current size on disk; grows while the session runs
+    pub size_bytes: u64,
+    /// Last write time (RFC 3339), if the file exists yet
+    pub modified: Option<String>,
+    /// Whether `start`/`end`/`last` time ranges can be extracted
+    pub supports_time_range: bool,
+}
+
+/// Time range for a recording download, in seconds from the start of the recording.
+#[derive(Debug, Default, Deserialize)]
+pub struct RecordingRange {
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    /// The final `last` seconds of the recording; exclusive with `start`/`end`
+    pub last: Option<f64>,
+}
+
+impl RecordingRange {
+    const fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none() && self.last.is_none()
+    }
+
+    /// Converts the range into `core::trim` params.
+    fn trim_params(&self) -> Result<serde_json::Value, String> {
+        let to_ms = |name: &str, seconds: Option<f64>| -> Result<Option<u64>, String> {
+            match seconds {
+                None => Ok(None),
+                Some(s) if s.is_finite() && s >= 0.0 => {
+                    // Bounded by the check above; sub-millisecond precision is not needed.
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let ms = (s * 1000.0).round() as u64;
+                    Ok(Some(ms))
+                },
+                Some(_) => Err(format!("'{name}' must be a non-negative number of seconds")),
+            }
+        };
+        let start_ms = to_ms("start", self.start)?;
+        let end_ms = to_ms("end", self.end)?;
+        let last_ms = to_ms("last", self.last)?;
+
+        if last_ms.is_some() && (start_ms.is_some() || end_ms.is_some()) {
+            return Err("'last' cannot be combined with 'start' or 'end'".to_string());
+        }
+        if last_ms == Some(0) {
+            return Err("'last' must be greater than 0".to_string());
+        }
+        if let Some(end_ms) = end_ms {
+            if end_ms <= start_ms.unwrap_or(0) {
+                return Err("'end' must be after 'start'".to_string());
+            }
+        }
+        Ok(serde_json::json!({
+            "start_ms": start_ms.unwrap_or(0),
+            "end_ms": end_ms,
+            "last_ms": last_ms,
+        }))
+    }
+}
+
+/// Content type of a recording, from its file extension.
+fn recording_content_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("ogg" | "opus") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("flac") => "audio/flac",
+        Some("mp3") => "audio/mpeg",
+        Some("webm") => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// `Content-Disposition` header offering a recording as `<session>-<node>.<extension>`.
+///
+/// Session and node ids are user-chosen, so anything outside `[A-Za-z0-9._-]` is replaced
+/// with `_` to keep the quoted filename well-formed.
+fn recording_disposition(session_id: &str, node_id: &str, extension: &str) -> Option<HeaderValue> {
+    let filename: String = format!("{session_id}-{node_id}.{extension}")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '_' })
+        .collect();
+    HeaderValue::from_str(&format!("attachment; filename=\"{filename}\"")).ok()
+}
+
+/// Reads the channel count from the `OpusHead` packet near the start of an Ogg/Opus file.
+fn read_opus_channels(file: impl Read) -> Option<u8> {
+    let mut head = Vec::new();
+    file.take(OPUS_HEAD_SEARCH_BYTES).read_to_end(&mut head).ok()?;
+    let at = head.windows(8).position(|w| w == b"OpusHead")?;
+    // OpusHead: magic (8 bytes), version (1), channel count (1), ...
+    head.get(at + 9).copied().filter(|&channels| channels > 0)
+}
+
+/// Builds the oneshot pipeline that cuts `range` out of the Ogg/Opus file at `path`.
+fn build_cut_pipeline(
+    path: &str,
+    channels: u8,
+    range: &RecordingRange,
+) -> Result<Pipeline, String> {
+    let step = |kind: &str, params: serde_json::Value| Step {
+        kind: kind.to_string(),
+        params: Some(params),
+        label: None,
+        notes: None,
+        ui_metadata: None,
+    };
+    compile(UserPipeline::Steps {
+        name: Some("recording-cut".to_string()),
+        description: None,
+        mode: EngineMode::OneShot,
+        steps: vec![
+            step("core::file_reader", serde_json::json!({ "path": path })),
+            step("containers::ogg::demuxer", serde_json::json!({})),
+            step("core::trim", range.trim_params()?),
+            step("containers::ogg::muxer", serde_json::json!({ "channels": channels })),
+            step("streamkit::http_output", serde_json::json!({})),
+        ],
+    })
+}
+
+/// Looks up the session and checks that the caller may read it.
+async fn authorized_session(
+    app_state: &Arc<AppState>,
+    headers: &HeaderMap,
+    session_id: &str,
+) -> Result<Session, Response> {
+    let (role_name, perms) = crate::role_extractor::get_role_and_permissions(headers, app_state);
+    if !perms.list_sessions {
+        return Err(
+            (StatusCode::FORBIDDEN, "Permission denied: cannot list sessions").into_response()
+        );
+    }
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(session_id)
+    };
+    let Some(session) = session else {
+        return Err(
+            (StatusCode::NOT_FOUND, format!("Session '{session_id}' not found")).into_response()
+        );
+    };
+    if !perms.access_all_sessions && session.created_by.as_ref().is_some_and(|c| c != &role_name) {
+        return Err((StatusCode::FORBIDDEN, "Permission denied: you do not own this session")
+            .into_response());
+    }
+    Ok(session)
+}
+
+/// Paths of the session's recorder nodes, keyed by node id.
+async fn recorder_paths(session: &Session) -> Vec<(String, String)> {
+    let pipeline = session.pipeline.lock().await;
+    pipeline
+        .nodes
+        .iter()
+        .filter(|(_, node)| node.kind == RECORDER_KIND)
+        .filter_map(|(id, node)| {
+            let path = node.params.as_ref()?.get("path")?.as_str()?;
+            Some((id.clone(), path.to_string()))
+        })
+        .collect()
+}
+
+/// Axum handler listing the recordings of a live session.
+async fn list_recordings_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Response {
+    let session = match authorized_session(&app_state, &headers, &session_id).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+
+    let mut recordings = Vec::new();
+    for (node_id, path) in recorder_paths(&session).await {
+        let path = std::path::PathBuf::from(path);
+        let metadata = tokio::fs::metadata(&path).await.ok();
+        let content_type = recording_content_type(&path);
+        recordings.push(RecordingInfo {
+            node_id,
+            content_type,
+            size_bytes: metadata.as_ref().map_or(0, std::fs::Metadata::len),
+            modified: metadata
+                .and_then(|m| m.modified().ok())
+                .map(crate::session::system_time_to_rfc3339),
+            supports_time_range: content_type == "audio/ogg",
+        });
+    }
+    Json(recordings).into_response()
+}
+
+/// Axum handler downloading a recording, optionally cut to a time range.
+async fn download_recording_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, node_id)): Path<(String, String)>,
+    Query(range): Query<RecordingRange>,
+    request: Request,
+) -> Response {
+    let session = match authorized_session(&app_state, &headers, &session_id).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+    let Some((_, path)) = recorder_paths(&session).await.into_iter().find(|(id, _)| *id == node_id)
+    else {
+        return (StatusCode::NOT_FOUND, format!("No recording from node '{node_id}'"))
+            .into_response();
+    };
+    let path_buf = std::path::PathBuf::from(&path);
+    let content_type = recording_content_type(&path_buf);
+    let extension = path_buf.extension().and_then(|e| e.to_str()).unwrap_or("bin").to_string();
+    let disposition = recording_disposition(&session.id, &node_id, &extension);
+
+    let mut response = if range.is_empty() {
+        info!(session_id = %session.id, node_id = %node_id, "Serving recording via HTTP");
+        crate::downloads::serve_file(
+            &path_buf,
+            Some(HeaderValue::from_static(content_type)),
+            request,
+        )
+        .await
+    } else {
+        if content_type != "audio/ogg" {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Time ranges are only supported for Ogg/Opus recordings",
+            )
+                .into_response();
+        }
+        let head_path = path_buf.clone();
+        let channels = tokio::task::spawn_blocking(move || {
+            std::fs::File::open(head_path).ok().and_then(read_opus_channels)
+        })
+        .await
+        .ok()
+        .flatten();
+        let Some(channels) = channels else {
+            return (StatusCode::CONFLICT, "Recording has no Opus header yet").into_response();
+        };
+        let pipeline = match build_cut_pipeline(&path, channels, &range) {
+            Ok(pipeline) => pipeline,
+            Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+        };
+
+        info!(session_id = %session.id, node_id = %node_id, ?range, "Cutting recording via HTTP");
+        match crate::server::run_internal_oneshot_pipeline(&app_state, pipeline).await {
+            Ok(response) => response,
+            Err(e) => {
+                error!(error = %e, "Failed to cut recording");
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to cut recording")
+                    .into_response();
+            },
+        }
+    };
+
+    if response.status().is_success() {
+        if let Some(disposition) = disposition {
+            response.headers_mut().insert(header::CONTENT_DISPOSITION, disposition);
+        }
+    } else {
+        warn!(status = %response.status(), path = %path, "Recording download failed");
+    }
+    response
+}
+
+/// Routes for session recordings, merged into the main app router.
+pub fn recordings_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/sessions/{id}/recordings", get(list_recordings_handler))
+        .route("/api/v1/sessions/{id}/recordings/{node_id}", get(download_recording_handler))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_maps_to_trim_params() {
+        let range = RecordingRange { start: Some(1.5), end: Some(3.0), last: None };
+        let params = range.trim_params().unwrap();
+        assert_eq!(params["start_ms"], 1500);
+        assert_eq!(params["end_ms"], 3000);
+        assert!(params["last_ms"].is_null());
+
+        let range = RecordingRange { last: Some(300.0), ..RecordingRange::default() };
+        assert_eq!(range.trim_params().unwrap()["last_ms"], 300_000);
+    }
+
+    #[test]
+    fn invalid_ranges_are_rejected() {
+        for range in [
+            RecordingRange { start: Some(-1.0), ..RecordingRange::default() },
+            RecordingRange { start: Some(5.0), end: Some(5.0), last: None },
+            RecordingRange { start: Some(1.0), end: None, last: Some(10.0) },
+            RecordingRange { last: Some(0.0), ..RecordingRange::default() },
+            RecordingRange { end: Some(f64::NAN), ..RecordingRange::default() },
+        ] {
+            assert!(range.trim_params().is_err(), "{range:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn disposition_filename_is_sanitized() {
+        let header = recording_disposition("live", "rec\"; x=\u{7}1", "ogg").unwrap();
+        assert_eq!(header, "attachment; filename=\"live-rec___x__1.ogg\"");
+        let header = recording_disposition("s1", "mic.main", "webm").unwrap();
+        assert_eq!(header, "attachment; filename=\"s1-mic.main.webm\"");
+    }
+
+    #[test]
+    fn reads_opus_channel_count() {
+        let mut page = b"OggS\0\x02".to_vec();
+        page.extend_from_slice(&[0; 22]);
+        page.extend_from_slice(b"OpusHead\x01\x02\x38\x01");
+        assert_eq!(read_opus_channels(page.as_slice()), Some(2));
+        assert_eq!(read_opus_channels(&b"RIFF....WAVE"[..]), None);
+    }
+
+    #[test]
+    fn cut_pipeline_chains_trim_between_demuxer_and_muxer() {
+        let range = RecordingRange { last: Some(60.0), ..RecordingRange::default() };
+        let pipeline = build_cut_pipeline("/rec/a.ogg", 2, &range).unwrap();
+        let kinds: Vec<_> = pipeline.nodes.values().map(|n| n.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "core::file_reader",
+                "containers::ogg::demuxer",
+                "core::trim",
+                "containers::ogg::muxer",
+                "streamkit::http_output"
+            ]
+        );
+        assert_eq!(pipeline.connections.len(), 4);
+    }
+}
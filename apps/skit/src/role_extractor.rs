@@ -6,30 +6,37 @@ use axum::http::HeaderMap;
 use std::sync::Arc;
 use tracing::debug;
 
+use crate::mtls::CLIENT_CERT_ROLE_HEADER;
 use crate::{permissions::Permissions, state::AppState};
 
-/// Helper function to extract permissions from headers and state
+/// Resolves the role name for a request.
 ///
 /// For now, this reads from:
-/// 1. A configured trusted role header (set by launcher or auth layer)
-/// 2. SK_ROLE environment variable (fallback)
-/// 3. Config default_role (final fallback)
-pub fn get_permissions(headers: &HeaderMap, app_state: &Arc<AppState>) -> Permissions {
+/// 1. The role mapped from a verified mTLS client certificate (`[server.mtls].roles`)
+/// 2. A configured trusted role header (set by launcher or auth layer)
+/// 3. SK_ROLE environment variable (fallback)
+/// 4. Config default_role (final fallback)
+fn resolve_role_name(headers: &HeaderMap, app_state: &Arc<AppState>) -> String {
     let trusted_header = app_state.config.permissions.role_header.as_deref().map(|h| {
         // Normalize for HeaderMap lookups.
         h.trim().to_ascii_lowercase()
     });
 
-    // Try to get role from the configured trusted header first (if enabled)
-    let role_name = trusted_header
-        .as_deref()
-        .and_then(|header_name| headers.get(header_name))
+    // Only `client_cert_role_middleware` sets this header, so it can be trusted
+    std::iter::once(CLIENT_CERT_ROLE_HEADER)
+        .chain(trusted_header.as_deref())
+        .find_map(|header_name| headers.get(header_name))
         .and_then(|v| v.to_str().ok())
         .map(std::string::ToString::to_string)
         // Fallback to environment variable
         .or_else(|| std::env::var("SK_ROLE").ok())
         // Fallback to default role from config
-        .unwrap_or_else(|| app_state.config.permissions.default_role.clone());
+        .unwrap_or_else(|| app_state.config.permissions.default_role.clone())
+}
+
+/// Helper function to extract permissions from headers and state (see [`resolve_role_name`])
+pub fn get_permissions(headers: &HeaderMap, app_state: &Arc<AppState>) -> Permissions {
+    let role_name = resolve_role_name(headers, app_state);
 
     let perms = app_state.config.permissions.get_role(&role_name);
     debug!(
@@ -53,21 +60,7 @@ pub fn get_role_and_permissions(
     headers: &HeaderMap,
     app_state: &Arc<AppState>,
 ) -> (String, Permissions) {
-    let trusted_header = app_state.config.permissions.role_header.as_deref().map(|h| {
-        // Normalize for HeaderMap lookups.
-        h.trim().to_ascii_lowercase()
-    });
-
-    // Try to get role from the configured trusted header first (if enabled)
-    let role_name = trusted_header
-        .as_deref()
-        .and_then(|header_name| headers.get(header_name))
-        .and_then(|v| v.to_str().ok())
-        .map(std::string::ToString::to_string)
-        // Fallback to environment variable
-        .or_else(|| std::env::var("SK_ROLE").ok())
-        // Fallback to default role from config
-        .unwrap_or_else(|| app_state.config.permissions.default_role.clone());
+    let role_name = resolve_role_name(headers, app_state);
 
     let perms = app_state.config.permissions.get_role(&role_name);
     debug!(
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Tokio runtime construction for the API/HTTP server and the optional data-plane runtime.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use streamkit_plugin_native::placement::pin_current_thread;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::config::RuntimeConfig;
+
+/// Builds the runtime that serves HTTP, WebSocket and control traffic.
+///
+/// # Errors
+///
+/// Returns an error if the worker count is zero, a listed core is unavailable, or the
+/// runtime cannot be created.
+pub fn build_api_runtime(config: &RuntimeConfig) -> io::Result<Runtime> {
+    build_runtime("skit-api", config.api_worker_threads, &config.api_cores)
+}
+
+/// Builds the dedicated pipeline runtime, if `dedicated_data_plane` is enabled.
+///
+/// # Errors
+///
+/// Same as [`build_api_runtime`], for the data-plane settings.
+pub fn build_data_plane_runtime(config: &RuntimeConfig) -> io::Result<Option<Runtime>> {
+    if !config.dedicated_data_plane {
+        return Ok(None);
+    }
+    build_runtime("skit-data", config.data_plane_worker_threads, &config.data_plane_cores).map(Some)
+}
+
+fn build_runtime(
+    name: &str,
+    worker_threads: Option<usize>,
+    cores: &[usize],
+) -> io::Result<Runtime> {
+    let mut builder = Builder::new_multi_thread();
+    builder.enable_all().thread_name(name);
+
+    match worker_threads {
+        Some(0) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{name}: worker thread count must be greater than 0"),
+            ));
+        },
+        Some(threads) => {
+            builder.worker_threads(threads);
+        },
+        None if !cores.is_empty() => {
+            builder.worker_threads(cores.len());
+        },
+        None => {},
+    }
+
+    if !cores.is_empty() {
+        check_cores(name, cores)?;
+        // Worker and blocking-pool threads take the listed cores round-robin.
+        let cores: Arc<[usize]> = cores.into();
+        let next = AtomicUsize::new(0);
+        let thread_name = name.to_string();
+        builder.on_thread_start(move || {
+            let core = cores[next.fetch_add(1, Ordering::Relaxed) % cores.len()];
+            if let Err(e) = pin_current_thread(&[core]) {
+                tracing::warn!(runtime = %thread_name, core, error = %e, "Failed to pin thread");
+            }
+        });
+    }
+
+    builder.build()
+}
+
+/// Rejects cores this process may not run on (offline, out of range, or outside its cpuset).
+#[cfg(target_os = "linux")]
+fn check_cores(name: &str, cores: &[usize]) -> io::Result<()> {
+    use nix::sched::{sched_getaffinity, CpuSet};
+    use nix::unistd::Pid;
+
+    let allowed = sched_getaffinity(Pid::from_raw(0)).map_err(io::Error::from)?;
+    for &core in cores {
+        if core >= CpuSet::count() || !allowed.is_set(core).unwrap_or(false) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{name}: core {core} is not available to this process"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_cores(name: &str, _cores: &[usize]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{name}: core pinning is only supported on Linux"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_plane_runtime_is_opt_in() {
+        let config = RuntimeConfig::default();
+        assert!(matches!(build_data_plane_runtime(&config), Ok(None)));
+
+        let config = RuntimeConfig {
+            dedicated_data_plane: true,
+            data_plane_worker_threads: Some(0),
+            ..RuntimeConfig::default()
+        };
+        assert!(build_data_plane_runtime(&config).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pinned_runtime_threads_run_on_listed_cores() {
+        use nix::sched::{sched_getaffinity, CpuSet};
+        use nix::unistd::Pid;
+
+        let Ok(allowed) = sched_getaffinity(Pid::from_raw(0)) else {
+            panic!("failed to read process affinity");
+        };
+        let Some(core) = (0..CpuSet::count()).find(|&c| allowed.is_set(c) == Ok(true)) else {
+            panic!("process has no usable cores");
+        };
+
+        let config = RuntimeConfig {
+            dedicated_data_plane: true,
+            data_plane_cores: vec![core],
+            ..RuntimeConfig::default()
+        };
+        let Ok(Some(runtime)) = build_data_plane_runtime(&config) else {
+            panic!("failed to build pinned runtime");
+        };
+        let affinity = runtime
+            .block_on(async { tokio::spawn(async { sched_getaffinity(Pid::from_raw(0)) }).await });
+        let Ok(Ok(affinity)) = affinity else { panic!("failed to read thread affinity") };
+        let pinned: Vec<usize> =
+            (0..CpuSet::count()).filter(|&c| affinity.is_set(c) == Ok(true)).collect();
+        assert_eq!(pinned, vec![core]);
+
+        let config = RuntimeConfig { api_cores: vec![usize::MAX], ..RuntimeConfig::default() };
+        assert!(build_api_runtime(&config).is_err());
+    }
+}
@@ -11,7 +11,7 @@ use axum::{
     http::{header, HeaderMap, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use bytes::Bytes;
@@ -44,6 +44,7 @@ use streamkit_api::Pipeline;
 use streamkit_api::{ApiPipeline, Event as ApiEvent, EventPayload, MessageType};
 use streamkit_core::control::EngineControlMessage;
 use streamkit_core::error::StreamKitError;
+use streamkit_engine::oneshot::ONESHOT_SOURCE_KINDS;
 use streamkit_engine::{Engine, OneshotEngineConfig};
 
 use crate::session::SessionManager;
@@ -52,7 +53,7 @@ use crate::config::Config;
 use tokio_stream::wrappers::ReceiverStream;
 
 use anyhow::Error as AnyhowError;
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::io::AsyncWriteExt;
 
@@ -220,6 +221,46 @@ async fn origin_guard_middleware(
     next.run(req).await
 }
 
+/// Rejects mutating API calls while the server is read-only.
+///
+/// Oneshot processing and pipeline estimates leave no state behind and stay available, as
+/// does the read-only toggle itself.
+async fn read_only_middleware(
+    State(app_state): State<Arc<AppState>>,
+    req: axum::http::Request<Body>,
+    next: Next,
+) -> Response {
+    use axum::http::Method;
+
+    let path = req.uri().path();
+    // Match past any `[server].base_path` prefix.
+    let api_path = path.find("/api/").map(|i| &path[i..]);
+    let is_mutating =
+        matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+
+    if let Some(api_path) = api_path {
+        let exempt =
+            matches!(api_path, "/api/v1/process" | "/api/v1/read-only" | "/api/v1/estimate");
+        if is_mutating && !exempt {
+            let read_only = app_state.read_only.read().await.clone();
+            if let Some(mode) = read_only {
+                debug!(method = %req.method(), path = %path, "Rejected request: read-only mode");
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(serde_json::json!({
+                        "error": "read_only",
+                        "message": crate::state::READ_ONLY_MESSAGE,
+                        "banner": mode.message,
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    next.run(req).await
+}
+
 fn create_cors_layer(config: &crate::config::CorsConfig) -> CorsLayer {
     use axum::http::{HeaderValue, Method};
 
@@ -302,7 +343,10 @@ async fn list_node_definitions_handler(
 
     let perms = crate::role_extractor::get_permissions(&headers, &app_state);
 
-    let mut definitions = read_registry(&app_state)?.definitions();
+    let (mut definitions, enum_resolver) = {
+        let registry = read_registry(&app_state)?;
+        (registry.definitions(), registry.enum_resolver())
+    };
 
     // Add synthetic node definitions for oneshot-only nodes
     // These are virtual markers that get replaced at runtime in oneshot pipelines
@@ -323,6 +367,10 @@ async fn list_node_definitions_handler(
         }],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: std::collections::BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 
     definitions.push(NodeDefinition {
@@ -341,6 +389,10 @@ async fn list_node_definitions_handler(
         outputs: vec![],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: std::collections::BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 
     definitions.retain(|def| {
@@ -353,6 +405,18 @@ async fn list_node_definitions_handler(
         true
     });
 
+    // Providers may touch hardware or the filesystem, so resolve off the async workers.
+    let reveal_sensitive = perms.view_secrets;
+    let definitions = tokio::task::spawn_blocking(move || {
+        enum_resolver.resolve(&mut definitions, reveal_sensitive);
+        definitions
+    })
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Resolving node param options failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
     info!(
         "Listed {} available node definitions via HTTP (including synthetic oneshot nodes)",
         definitions.len()
@@ -385,21 +449,131 @@ struct FrontendConfig {
     #[cfg(feature = "moq")]
     #[serde(skip_serializing_if = "Option::is_none")]
     moq_gateway_url: Option<String>,
+    /// Whether the server is in read-only maintenance mode
+    read_only: bool,
+    /// Operator message to display while read-only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    banner: Option<String>,
 }
 
 /// Axum handler to get frontend configuration
 async fn get_config_handler(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
-    #[cfg(not(feature = "moq"))]
-    let _ = &app_state;
+    let read_only = app_state.read_only.read().await.clone();
 
     let config = FrontendConfig {
         #[cfg(feature = "moq")]
         moq_gateway_url: app_state.config.server.moq_gateway_url.clone(),
+        read_only: read_only.is_some(),
+        banner: read_only.and_then(|mode| mode.message),
     };
 
     Json(config)
 }
 
+#[derive(Deserialize)]
+struct SetReadOnlyRequest {
+    enabled: bool,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Axum handler to enter or leave read-only mode (admin only)
+async fn set_read_only_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<SetReadOnlyRequest>,
+) -> Response {
+    let (role_name, perms) = crate::role_extractor::get_role_and_permissions(&headers, &app_state);
+    if !perms.access_all_sessions {
+        warn!(role = %role_name, "Blocked attempt to toggle read-only mode via HTTP");
+        return (StatusCode::FORBIDDEN, "Permission denied: cannot toggle read-only mode")
+            .into_response();
+    }
+
+    let mode = req.enabled.then_some(crate::state::ReadOnlyMode { message: req.message });
+    info!(role = %role_name, enabled = req.enabled, "Read-only mode toggled via HTTP");
+    app_state.set_read_only(mode).await;
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// Axum handler returning per-kind node usage statistics across all sessions (admin only)
+async fn get_usage_handler(State(app_state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let (role_name, perms) = crate::role_extractor::get_role_and_permissions(&headers, &app_state);
+    if !perms.access_all_sessions {
+        warn!(role = %role_name, "Blocked attempt to read usage statistics via HTTP");
+        return (StatusCode::FORBIDDEN, "Permission denied: cannot read usage statistics")
+            .into_response();
+    }
+
+    Json(app_state.engine.usage.report()).into_response()
+}
+
+/// Request body for estimating a pipeline's resource needs
+#[derive(Debug, Deserialize)]
+struct EstimatePipelineRequest {
+    yaml: String,
+}
+
+/// Axum handler estimating the resource needs of a pipeline before it is created
+async fn estimate_pipeline_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<EstimatePipelineRequest>,
+) -> Result<Json<streamkit_api::PipelineEstimate>, (StatusCode, String)> {
+    let perms = crate::role_extractor::get_permissions(&headers, &app_state);
+    if !perms.create_sessions {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Permission denied: cannot create sessions".to_string(),
+        ));
+    }
+
+    let user_pipeline: UserPipeline = serde_saphyr::from_str(&req.yaml)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid YAML: {e}")))?;
+    let mut pipeline = compile(user_pipeline)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid pipeline: {e}")))?;
+    resolve_pipeline_kinds(&app_state, &mut pipeline);
+
+    Ok(Json(crate::estimate::estimate_pipeline(
+        &pipeline,
+        &app_state.config.resources.profiles,
+        &app_state.engine.usage.report(),
+    )))
+}
+
+/// Periodically writes the usage report to `[usage].report_path`, if configured.
+fn spawn_usage_report(app_state: &Arc<AppState>) {
+    let Some(path) = app_state.config.usage.report_path.clone() else {
+        return;
+    };
+    let interval_secs = app_state.config.usage.report_interval_secs.max(1);
+    let usage = Arc::clone(&app_state.engine.usage);
+    info!(path = %path, interval_secs, "Writing node usage report periodically");
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let report = usage.report();
+            let path = path.clone();
+            let written = tokio::task::spawn_blocking(move || {
+                // Write next to the target and rename, so readers never see a partial report
+                let tmp = format!("{path}.tmp");
+                let json = serde_json::to_vec_pretty(&report).map_err(std::io::Error::other)?;
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, &path)
+            })
+            .await;
+            match written {
+                Ok(Ok(())) => debug!("Wrote node usage report"),
+                Ok(Err(e)) => warn!(error = %e, "Failed to write node usage report"),
+                Err(e) => warn!(error = %e, "Node usage report task failed"),
+            }
+        }
+    });
+}
+
 async fn list_plugins_handler(
     State(app_state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -414,6 +588,23 @@ async fn list_plugins_handler(
     Json(plugins)
 }
 
+async fn list_plugin_failures_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, PluginHttpError> {
+    let perms = crate::role_extractor::get_permissions(&headers, &app_state);
+
+    // Errors can include file system paths, so only show them to plugin operators
+    if !perms.load_plugins {
+        return Err(PluginHttpError::Forbidden(
+            "Permission denied: cannot load plugins".to_string(),
+        ));
+    }
+
+    let failures = app_state.plugin_manager.lock().await.load_failures();
+    Ok(Json(failures))
+}
+
 async fn upload_plugin_handler(
     State(app_state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -629,6 +820,11 @@ async fn get_certificate_sha256_handler(
 struct CreateSessionRequest {
     name: Option<String>,
     yaml: String,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    /// Keep a black-box recording of the session
+    #[serde(default)]
+    record: bool,
 }
 
 /// Response body for creating a session
@@ -651,7 +847,10 @@ async fn populate_session_pipeline(session: &crate::session::Session, engine_pip
             streamkit_api::Node {
                 kind: node_spec.kind.clone(),
                 params: node_spec.params.clone(),
+                label: node_spec.label.clone(),
+                notes: node_spec.notes.clone(),
                 state: None,
+                ui_metadata: node_spec.ui_metadata.clone(),
             },
         );
     }
@@ -664,6 +863,7 @@ async fn populate_session_pipeline(session: &crate::session::Session, engine_pip
             to_node: c.to_node.clone(),
             to_pin: c.to_pin.clone(),
             mode: c.mode,
+            ui_metadata: c.ui_metadata.clone(),
         }
     }));
 }
@@ -691,6 +891,9 @@ async fn send_pipeline_to_engine(session: &crate::session::Session, engine_pipel
             streamkit_api::ConnectionMode::BestEffort => {
                 streamkit_core::control::ConnectionMode::BestEffort
             },
+            streamkit_api::ConnectionMode::Feedback => {
+                streamkit_core::control::ConnectionMode::Feedback
+            },
         };
         session
             .send_control_message(EngineControlMessage::Connect {
@@ -704,6 +907,30 @@ async fn send_pipeline_to_engine(session: &crate::session::Session, engine_pipel
     }
 }
 
+/// Responds with an already-running named session instead of creating a new one.
+///
+/// The request's pipeline YAML is ignored; the live session keeps its current graph.
+fn attach_to_named_session(
+    session: &crate::session::Session,
+    role_name: &str,
+    perms: &crate::permissions::Permissions,
+) -> Result<Json<CreateSessionResponse>, (StatusCode, String)> {
+    if !perms.access_all_sessions && session.created_by.as_deref().is_some_and(|c| c != role_name) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Permission denied: you do not own this session".to_string(),
+        ));
+    }
+
+    info!(session_id = %session.id, name = ?session.name, "Attached to existing named session via HTTP");
+
+    Ok(Json(CreateSessionResponse {
+        session_id: session.id.clone(),
+        name: session.name.clone(),
+        created_at: crate::session::system_time_to_rfc3339(session.created_at),
+    }))
+}
+
 /// Axum handler to create a new session with a pipeline from YAML.
 async fn create_session_handler(
     State(app_state): State<Arc<AppState>>,
@@ -719,14 +946,21 @@ async fn create_session_handler(
         ));
     }
 
+    let tags = crate::session::normalize_session_tags(req.tags.clone())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
     // Global session limit
-    let (current_count, name_taken) = {
+    let (current_count, name_taken, existing) = {
         let session_manager = app_state.session_manager.lock().await;
         let current_count = session_manager.session_count();
         let name_taken = req.name.as_deref().is_some_and(|n| session_manager.is_name_taken(n));
+        let existing = session_manager.attachable_session(req.name.as_deref(), &app_state.config);
         drop(session_manager);
-        (current_count, name_taken)
+        (current_count, name_taken, existing)
     };
+    if let Some(existing) = existing {
+        return attach_to_named_session(&existing, &role_name, &perms);
+    }
     if let Some(ref session_name) = req.name {
         if name_taken {
             return Err((
@@ -748,8 +982,9 @@ async fn create_session_handler(
     let user_pipeline: UserPipeline = serde_saphyr::from_str(&req.yaml)
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid YAML: {e}")))?;
 
-    let engine_pipeline = compile(user_pipeline)
+    let mut engine_pipeline = compile(user_pipeline)
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid pipeline: {e}")))?;
+    let deprecations = resolve_pipeline_kinds(&app_state, &mut engine_pipeline);
 
     // Validate the pipeline has at least one node
     if engine_pipeline.nodes.is_empty() {
@@ -801,6 +1036,7 @@ async fn create_session_handler(
                 (StatusCode::INTERNAL_SERVER_ERROR, format!("Pipeline execution error: {err}"))
             },
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::UploadLimit(err) => (StatusCode::BAD_REQUEST, err.to_string()),
         },
     )?;
 
@@ -820,6 +1056,7 @@ async fn create_session_handler(
                 (StatusCode::INTERNAL_SERVER_ERROR, format!("Pipeline execution error: {err}"))
             },
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::UploadLimit(err) => (StatusCode::BAD_REQUEST, err.to_string()),
         },
     )?;
 
@@ -838,6 +1075,7 @@ async fn create_session_handler(
             (StatusCode::INTERNAL_SERVER_ERROR, format!("Pipeline execution error: {err}"))
         },
         AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+        AppError::UploadLimit(err) => (StatusCode::BAD_REQUEST, err.to_string()),
     })?;
 
     // Create the session without holding the session manager lock.
@@ -847,20 +1085,31 @@ async fn create_session_handler(
         req.name.clone(),
         app_state.event_tx.clone(),
         Some(role_name.clone()),
+        tags,
+        req.record,
     )
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create session: {e}")))?;
 
     // Insert the session with short lock hold and re-check limits to avoid races.
+    // A concurrent request may have started the same named pipeline meanwhile.
     let insert_result = {
         let mut session_manager = app_state.session_manager.lock().await;
         let current_count = session_manager.session_count();
-        if app_state.config.permissions.can_accept_session(current_count) {
-            session_manager.add_session(session.clone())
+        let existing =
+            session_manager.attachable_session(session.name.as_deref(), &app_state.config);
+        if existing.is_some() {
+            Ok(existing)
+        } else if app_state.config.permissions.can_accept_session(current_count) {
+            session_manager.add_session(session.clone()).map(|()| None)
         } else {
             Err("Maximum concurrent sessions limit reached".to_string())
         }
     };
+    if let Ok(Some(existing)) = insert_result {
+        let _ = session.shutdown_and_wait().await;
+        return attach_to_named_session(&existing, &role_name, &perms);
+    }
     if let Err(error_msg) = insert_result {
         let _ = session.shutdown_and_wait().await;
         if error_msg == "Maximum concurrent sessions limit reached" {
@@ -905,14 +1154,74 @@ async fn create_session_handler(
     if app_state.event_tx.send(event).is_err() {
         debug!("No WebSocket clients connected to receive SessionCreated event");
     }
+    for (node_id, deprecation) in deprecations {
+        session.notify_kind_deprecation(&node_id, deprecation, &app_state.event_tx);
+    }
+    session.publish_pipeline_change(&app_state.event_tx).await;
 
     Ok(Json(CreateSessionResponse { session_id, name: session_name, created_at: created_at_str }))
 }
 
+/// Rewrites renamed node kinds in place so validation and instantiation see current kinds.
+///
+/// Returns the deprecation notices for the affected nodes, keyed by node id.
+fn resolve_pipeline_kinds(
+    app_state: &AppState,
+    pipeline: &mut Pipeline,
+) -> Vec<(String, streamkit_core::KindDeprecation)> {
+    let mut deprecations = Vec::new();
+    for (node_id, node) in &mut pipeline.nodes {
+        let (kind, deprecation) = app_state.resolve_node_kind(&node.kind);
+        node.kind = kind;
+        if let Some(deprecation) = deprecation {
+            deprecations.push((node_id.clone(), deprecation));
+        }
+    }
+    deprecations
+}
+
+/// Query parameters for `GET /api/v1/sessions`.
+///
+/// Mirrors `SessionListQuery`, with `tags` given as a comma-separated list.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ListSessionsParams {
+    tags: Option<String>,
+    search: Option<String>,
+    sort: streamkit_api::SessionSortKey,
+    order: streamkit_api::SortOrder,
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+impl From<ListSessionsParams> for streamkit_api::SessionListQuery {
+    fn from(params: ListSessionsParams) -> Self {
+        Self {
+            tags: params
+                .tags
+                .iter()
+                .flat_map(|tags| tags.split(','))
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            search: params.search,
+            sort: params.sort,
+            order: params.order,
+            limit: params.limit,
+            cursor: params.cursor,
+        }
+    }
+}
+
 /// Axum handler to get the list of active sessions.
+///
+/// Returns a JSON array; when more results are available the `x-next-cursor` response header
+/// carries the cursor for the next page.
 async fn list_sessions_handler(
     State(app_state): State<Arc<AppState>>,
     headers: HeaderMap,
+    Query(params): Query<ListSessionsParams>,
 ) -> Response {
     let (role_name, perms) = crate::role_extractor::get_role_and_permissions(&headers, &app_state);
 
@@ -921,23 +1230,26 @@ async fn list_sessions_handler(
             .into_response();
     }
 
-    let sessions = app_state.session_manager.lock().await.list_sessions();
-    let session_infos: Vec<streamkit_api::SessionInfo> = sessions
-        .into_iter()
-        .filter(|session| {
-            if perms.access_all_sessions {
-                return true;
-            }
-            session.created_by.as_ref().is_none_or(|creator| creator == &role_name)
-        })
-        .map(|session| streamkit_api::SessionInfo {
-            id: session.id,
-            name: session.name,
-            created_at: crate::session::system_time_to_rfc3339(session.created_at),
-        })
-        .collect();
+    let query = streamkit_api::SessionListQuery::from(params);
+    let result = app_state.session_manager.lock().await.query_sessions(&query, |session| {
+        if perms.access_all_sessions {
+            return true;
+        }
+        session.created_by.as_ref().is_none_or(|creator| creator == &role_name)
+    });
+    let (sessions, next_cursor) = match result {
+        Ok(page) => page,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let session_infos: Vec<streamkit_api::SessionInfo> =
+        sessions.iter().map(crate::session::Session::info).collect();
     info!("Listed {} active sessions via HTTP", session_infos.len());
-    Json(session_infos).into_response()
+
+    let mut response = Json(session_infos).into_response();
+    if let Some(cursor) = next_cursor.and_then(|c| header::HeaderValue::from_str(&c).ok()) {
+        response.headers_mut().insert("x-next-cursor", cursor);
+    }
+    response
 }
 
 /// Axum handler to destroy a session.
@@ -998,7 +1310,18 @@ async fn destroy_session_handler(
 
     info!(session_id = %destroyed_id, "Session destroyed successfully via HTTP");
 
-    // Broadcast event to all WebSocket clients
+    // Broadcast events to all WebSocket clients
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::SessionCostReport {
+            session_id: destroyed_id.clone(),
+            report: session.cost_report(),
+        },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast SessionCostReport event: {}", e);
+    }
     let event = ApiEvent {
         message_type: MessageType::Event,
         correlation_id: None,
@@ -1048,11 +1371,91 @@ async fn get_pipeline_handler(
     for (id, node) in &mut api_pipeline.nodes {
         node.state = node_states.get(id).cloned();
     }
+    if !perms.view_secrets {
+        app_state.redact_pipeline(&mut api_pipeline);
+    }
 
     info!("Fetched pipeline with states for session '{}' via HTTP", session_id);
     Ok(Json(api_pipeline))
 }
 
+/// Axum handler to download a session's black-box recording (gzip-compressed JSON Lines).
+///
+/// Recordings become available once the session ends. One left unfinished by a server crash
+/// is served as it is, marked with `x-recording-complete: false`.
+async fn get_black_box_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    request: axum::extract::Request,
+) -> Response {
+    let (role_name, perms) = crate::role_extractor::get_role_and_permissions(&headers, &app_state);
+
+    if !perms.list_sessions {
+        return (StatusCode::FORBIDDEN, "Permission denied: cannot list sessions").into_response();
+    }
+    let can_access = |created_by: Option<&String>| {
+        perms.access_all_sessions || created_by.is_none_or(|creator| creator == &role_name)
+    };
+
+    let live_session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+    if let Some(session) = live_session {
+        if !can_access(session.created_by.as_ref()) {
+            return (StatusCode::FORBIDDEN, "Permission denied: you do not own this session")
+                .into_response();
+        }
+        return if session.is_recording() {
+            (StatusCode::CONFLICT, "Recording in progress; available once the session ends")
+        } else {
+            (StatusCode::NOT_FOUND, "Session is not being recorded")
+        }
+        .into_response();
+    }
+
+    let Some((path, complete)) = crate::black_box::locate(&app_state.config.black_box, &session_id)
+    else {
+        return (StatusCode::NOT_FOUND, format!("No recording for session '{session_id}'"))
+            .into_response();
+    };
+    let header_path = path.clone();
+    let header = tokio::task::spawn_blocking(move || {
+        std::fs::File::open(header_path).ok().and_then(crate::black_box::read_header)
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some(header) = header else {
+        error!(path = %path.display(), "Black-box recording has no valid header");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if !can_access(header.created_by.as_ref()) {
+        return (StatusCode::FORBIDDEN, "Permission denied: you do not own this session")
+            .into_response();
+    }
+
+    info!(session_id = %session_id, complete, "Serving black-box recording via HTTP");
+    // Already gzip-compressed; served as-is with Range support so large recordings can resume.
+    let mut response = crate::downloads::serve_file(
+        &path,
+        Some(header::HeaderValue::from_static("application/gzip")),
+        request,
+    )
+    .await;
+    if let Ok(disposition) =
+        header::HeaderValue::from_str(&format!("attachment; filename=\"{session_id}.jsonl.gz\""))
+    {
+        response.headers_mut().insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    response.headers_mut().insert(
+        header::HeaderName::from_static("x-recording-complete"),
+        header::HeaderValue::from_static(if complete { "true" } else { "false" }),
+    );
+    response
+}
+
 /// Result of parsing multipart request with config and optional media stream
 struct MultipartParseResult {
     user_pipeline: UserPipeline,
@@ -1081,7 +1484,7 @@ async fn parse_config_field(
     let first_field = multipart
         .next_field()
         .await
-        .map_err(|e| AppError::BadRequest(format!("Multipart error: {e}")))?
+        .map_err(|e| multipart_read_error(&e, "Multipart error"))?
         .ok_or_else(|| AppError::BadRequest("Empty multipart payload".to_string()))?;
     let first_name = first_field.name().map(std::string::ToString::to_string).unwrap_or_default();
     if first_name != "config" {
@@ -1093,10 +1496,18 @@ async fn parse_config_field(
     let config_bytes = first_field
         .bytes()
         .await
-        .map_err(|e| AppError::BadRequest(format!("Failed to read config field: {e}")))?;
+        .map_err(|e| multipart_read_error(&e, "Failed to read config field"))?;
     serde_saphyr::from_slice(&config_bytes).map_err(Into::into)
 }
 
+/// Maps a multipart read failure to an upload limit response when a limit caused it.
+fn multipart_read_error(err: &raw_multer::Error, context: &str) -> AppError {
+    crate::upload_limits::UploadLimitError::find(err).map_or_else(
+        || AppError::BadRequest(format!("{context}: {err}")),
+        |limit| AppError::UploadLimit(limit.clone()),
+    )
+}
+
 /// Parse the multipart request and extract config and media stream.
 /// Returns the parsed pipeline config, media stream (possibly empty), content type, and whether media was provided.
 ///
@@ -1203,7 +1614,8 @@ fn validate_pipeline_nodes(
         pipeline_def.nodes.values().any(|node| node.kind == "streamkit::http_input");
     let has_http_output =
         pipeline_def.nodes.values().any(|node| node.kind == "streamkit::http_output");
-    let has_file_read = pipeline_def.nodes.values().any(|node| node.kind == "core::file_reader");
+    let has_file_read =
+        pipeline_def.nodes.values().any(|node| ONESHOT_SOURCE_KINDS.contains(&node.kind.as_str()));
 
     // Validate entry point based on whether media was provided
     if has_media {
@@ -1215,7 +1627,7 @@ fn validate_pipeline_nodes(
             ));
         }
     } else {
-        // File-based mode: require file_read, disallow http_input
+        // File-based mode: require a source node, disallow http_input
         if has_http_input {
             return Err(AppError::BadRequest(
                 "Pipeline cannot contain 'streamkit::http_input' node when no media is provided"
@@ -1223,10 +1635,10 @@ fn validate_pipeline_nodes(
             ));
         }
         if !has_file_read {
-            return Err(AppError::BadRequest(
-                "Pipeline must contain at least one 'core::file_reader' node when no media is provided"
-                    .to_string(),
-            ));
+            return Err(AppError::BadRequest(format!(
+                "Pipeline must contain at least one source node ({}) when no media is provided",
+                ONESHOT_SOURCE_KINDS.join(", ")
+            )));
         }
     }
 
@@ -1267,29 +1679,27 @@ fn validate_file_reader_paths(
     Ok(())
 }
 
-/// Validate write paths in all file_writer nodes to prevent arbitrary file writes.
+/// Validate write paths in all file-writing nodes to prevent arbitrary file writes.
 fn validate_file_writer_paths(
     pipeline_def: &Pipeline,
     security_config: &crate::config::SecurityConfig,
 ) -> Result<(), AppError> {
     for (node_id, node_def) in &pipeline_def.nodes {
-        if node_def.kind == "core::file_writer" {
-            let Some(params) = &node_def.params else {
-                return Err(AppError::BadRequest(format!(
-                    "Invalid file_writer params in node '{node_id}': expected params.path"
-                )));
-            };
-
-            let Some(path_str) = params.get("path").and_then(serde_json::Value::as_str) else {
-                return Err(AppError::BadRequest(format!(
-                    "Invalid file_writer params in node '{node_id}': expected params.path to be a string"
-                )));
-            };
-
-            crate::file_security::validate_write_path(path_str, security_config).map_err(|e| {
-                AppError::BadRequest(format!("Invalid write path in node '{node_id}': {e}"))
-            })?;
-        }
+        let Some(param) = crate::file_security::write_path_param(&node_def.kind) else {
+            continue;
+        };
+        let Some(path_str) =
+            node_def.params.as_ref().and_then(|p| p.get(param)).and_then(serde_json::Value::as_str)
+        else {
+            return Err(AppError::BadRequest(format!(
+                "Invalid {} params in node '{node_id}': expected params.{param} to be a string",
+                node_def.kind
+            )));
+        };
+
+        crate::file_security::validate_write_path(path_str, security_config).map_err(|e| {
+            AppError::BadRequest(format!("Invalid write path in node '{node_id}': {e}"))
+        })?;
     }
     Ok(())
 }
@@ -1481,8 +1891,11 @@ async fn process_oneshot_pipeline_handler(
 
     // Compile pipeline definition
     tracing::debug!("Compiling user pipeline definition");
-    let pipeline_def: Pipeline = compile(parse_result.user_pipeline)?;
+    let mut pipeline_def: Pipeline = compile(parse_result.user_pipeline)?;
     tracing::debug!("Pipeline compilation completed");
+    for (node_id, deprecation) in resolve_pipeline_kinds(&app_state, &mut pipeline_def) {
+        warn!(node_id = %node_id, kind = %deprecation.kind, "{}", deprecation.message);
+    }
 
     // Validate pipeline structure
     let (has_http_input, has_file_read, has_http_output) =
@@ -1529,6 +1942,40 @@ async fn process_oneshot_pipeline_handler(
     );
     tracing::info!(role = %role_name, "Executing oneshot pipeline for role");
 
+    let response = execute_oneshot_pipeline(
+        &app_state,
+        pipeline_def,
+        parse_result.media_stream,
+        parse_result.media_content_type,
+        parse_result.has_media,
+    )
+    .await?;
+    Ok(response)
+}
+
+/// Runs a server-built, file-based oneshot pipeline and streams its output.
+///
+/// Used for pipelines synthesized by the server itself (such as recording cuts), which skip
+/// the request-level validation applied to user-submitted pipelines.
+///
+/// # Errors
+///
+/// Returns an error if the engine fails to build or start the pipeline.
+pub async fn run_internal_oneshot_pipeline(
+    app_state: &AppState,
+    pipeline_def: Pipeline,
+) -> Result<Response, StreamKitError> {
+    let no_media: MediaStream = Box::new(futures::stream::empty());
+    execute_oneshot_pipeline(app_state, pipeline_def, no_media, None, false).await
+}
+
+async fn execute_oneshot_pipeline(
+    app_state: &AppState,
+    pipeline_def: Pipeline,
+    media_stream: MediaStream,
+    media_content_type: Option<String>,
+    has_media: bool,
+) -> Result<Response, StreamKitError> {
     // Execute oneshot pipeline
     tracing::info!("Starting oneshot pipeline execution");
     let oneshot_start_time = Instant::now();
@@ -1561,9 +2008,9 @@ async fn process_oneshot_pipeline_handler(
         .engine
         .run_oneshot_pipeline(
             pipeline_def,
-            parse_result.media_stream,
-            parse_result.media_content_type,
-            parse_result.has_media,
+            media_stream,
+            media_content_type,
+            has_media,
             Some(oneshot_config),
         )
         .await
@@ -1575,7 +2022,7 @@ async fn process_oneshot_pipeline_handler(
         Err(e) => {
             let labels = [KeyValue::new("status", "error")];
             oneshot_duration_histogram.record(oneshot_start_time.elapsed().as_secs_f64(), &labels);
-            return Err(e.into());
+            return Err(e);
         },
     };
 
@@ -1607,7 +2054,22 @@ async fn websocket_handler(
 
     // Extract role name and permissions from headers
     let (role_name, perms) = crate::role_extractor::get_role_and_permissions(&headers, &app_state);
-    ws.on_upgrade(move |socket| websocket::handle_websocket(socket, app_state, perms, role_name))
+
+    // Wire format negotiation: echo the first subprotocol we understand, default to JSON.
+    let requested = headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','));
+    let negotiated = streamkit_api::WireFormat::negotiate(requested);
+    let ws = match negotiated {
+        Some(format) => ws.protocols([format.subprotocol()]),
+        None => ws,
+    };
+    let format = negotiated.unwrap_or_default();
+    ws.on_upgrade(move |socket| {
+        websocket::handle_websocket(socket, app_state, perms, role_name, format)
+    })
 }
 
 async fn static_handler(
@@ -1736,7 +2198,20 @@ async fn metrics_middleware(req: axum::http::Request<Body>, next: Next) -> Respo
 ///
 /// Since this occurs during application initialization, a panic here is acceptable
 /// as the server cannot function without plugin support.
+#[allow(dead_code)] // The binary starts through `create_app_with_runtime`; tests use this
 pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
+    create_app_with_runtime(config, None)
+}
+
+/// Like [`create_app`], but runs pipelines on `data_plane` instead of the caller's runtime.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`create_app`].
+pub fn create_app_with_runtime(
+    config: Config,
+    data_plane: Option<tokio::runtime::Handle>,
+) -> (Router, Arc<AppState>) {
     // --- Create the shared application state ---
     let (event_tx, _) = tokio::sync::broadcast::channel(128);
 
@@ -1772,6 +2247,7 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
             .unwrap_or(streamkit_engine::constants::DEFAULT_MOQ_PEER_CHANNEL_CAPACITY),
     };
     streamkit_core::set_node_buffer_config(node_buffer_config);
+    streamkit_core::set_default_telemetry_limits(config.telemetry.event_limits.clone());
 
     // Create engine with resource management support
     let plugin_base_dir = std::path::PathBuf::from(&config.plugins.directory);
@@ -1780,7 +2256,7 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
 
     // Create engine with script configuration if feature is enabled
     #[cfg(feature = "script")]
-    let engine = {
+    let mut engine = {
         // Convert server config AllowlistRule to nodes AllowlistRule
         let global_script_allowlist = if config.script.global_fetch_allowlist.is_empty() {
             None
@@ -1801,15 +2277,29 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
         // Load secrets from environment variables
         let secrets = load_script_secrets(&config.script.secrets);
 
-        Arc::new(Engine::with_resource_manager_and_script_config(
+        Engine::with_resource_manager_and_script_config(
             resource_manager.clone(),
             global_script_allowlist,
             secrets,
-        ))
+        )
     };
 
     #[cfg(not(feature = "script"))]
-    let engine = Arc::new(Engine::with_resource_manager(resource_manager.clone()));
+    let mut engine = Engine::with_resource_manager(resource_manager.clone());
+
+    engine.runtime = data_plane;
+
+    // Offer the files file_reader nodes may open as options for their `path` param.
+    if let Ok(mut registry) = engine.registry.write() {
+        let security = config.security.clone();
+        registry.register_enum_provider("readable_files", move || {
+            crate::file_security::list_readable_files(&security)
+                .into_iter()
+                .map(streamkit_core::EnumOption::new)
+                .collect()
+        });
+    }
+    let engine = Arc::new(engine);
 
     // Initialize plugin manager - panic on failure since we can't proceed without it
     // This expect is justified and documented in the function's # Panics section
@@ -1820,7 +2310,9 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
         wasm_plugin_dir,
         native_plugin_dir,
     )
-    .expect("Failed to initialize unified plugin manager");
+    .expect("Failed to initialize unified plugin manager")
+    .with_native_placement(config.plugins.native_placement)
+    .with_native_sandbox(&config.plugins.native_sandbox, &plugin_base_dir);
     let plugin_manager = Arc::new(tokio::sync::Mutex::new(plugin_manager));
 
     // Spawn background task to load plugins asynchronously to avoid blocking startup
@@ -1838,24 +2330,68 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
         Some(gateway)
     };
 
+    #[cfg(feature = "webrtc")]
+    let webrtc_gateway = {
+        let gateway = Arc::new(crate::webrtc_gateway::WebRtcGateway::new());
+        let trait_obj: Arc<dyn streamkit_core::webrtc_gateway::WebRtcGatewayTrait> =
+            gateway.clone();
+        streamkit_core::webrtc_gateway::init_webrtc_gateway(trait_obj);
+        gateway
+    };
+
+    let session_manager = Arc::new(tokio::sync::Mutex::new(SessionManager::default()));
+    {
+        let bridge = crate::session_bridge::SessionBridge::new(
+            Arc::clone(&session_manager),
+            config.permissions.clone(),
+        );
+        streamkit_core::session_bridge::init_session_bridge(Arc::new(bridge));
+    }
+
+    let read_only = config.maintenance.read_only.then(|| {
+        warn!("Starting in read-only mode ([maintenance].read_only); mutations are rejected");
+        crate::state::ReadOnlyMode { message: config.maintenance.message.clone() }
+    });
+
     let app_state = Arc::new(AppState {
         engine,
-        session_manager: Arc::new(tokio::sync::Mutex::new(SessionManager::default())),
+        session_manager,
         config: Arc::new(config),
         event_tx,
         plugin_manager,
+        read_only: Arc::new(tokio::sync::RwLock::new(read_only)),
         #[cfg(feature = "moq")]
         moq_gateway,
+        #[cfg(feature = "webrtc")]
+        webrtc_gateway,
     });
 
+    spawn_usage_report(&app_state);
+
+    #[cfg(feature = "script")]
+    crate::event_hooks::spawn_hooks(&app_state);
+    #[cfg(not(feature = "script"))]
+    if !app_state.config.script.hooks.is_empty() {
+        warn!("script.hooks configured but the server was built without the 'script' feature");
+    }
+
+    let upload_guard = middleware::from_fn_with_state(
+        crate::upload_limits::UploadGuard::new(&app_state.config.server),
+        crate::upload_limits::upload_guard_middleware,
+    );
+
     let mut oneshot_route = post(process_oneshot_pipeline_handler)
         // Use configurable body limit for oneshot processing
         .layer(DefaultBodyLimit::max(app_state.config.server.max_body_size));
+    // gzip/zstd for text and PCM outputs, negotiated from Accept-Encoding
+    oneshot_route = oneshot_route.layer(crate::downloads::compression_layer());
     if let Some(max) = app_state.config.permissions.max_concurrent_oneshots {
         oneshot_route = oneshot_route.layer(ConcurrencyLimitLayer::new(max));
     }
+    // Outside the concurrency limit so over-quota clients are turned away instead of queued
+    let oneshot_route = oneshot_route.layer(upload_guard.clone());
 
-    #[cfg_attr(not(feature = "moq"), allow(unused_mut))]
+    #[cfg_attr(not(any(feature = "moq", feature = "webrtc")), allow(unused_mut))]
     let mut router = Router::new()
         .route("/healthz", get(health_handler))
         .route("/health", get(health_handler))
@@ -1867,15 +2403,20 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
                 // Plugin uploads are multipart; raise default body limit for realistic artifacts.
                 .layer(DefaultBodyLimit::max(app_state.config.server.max_body_size)),
         )
+        .route("/api/v1/plugins/failures", get(list_plugin_failures_handler))
         .route("/api/v1/plugins/{kind}", delete(delete_plugin_handler))
         .route("/api/v1/control", get(websocket_handler))
         .route("/api/v1/permissions", get(get_permissions_handler))
         .route("/api/v1/config", get(get_config_handler))
+        .route("/api/v1/read-only", put(set_read_only_handler))
+        .route("/api/v1/usage", get(get_usage_handler))
+        .route("/api/v1/estimate", post(estimate_pipeline_handler))
         .route("/api/v1/schema/nodes", get(list_node_definitions_handler))
         .route("/api/v1/schema/packets", get(list_packet_types_handler))
         .route("/api/v1/sessions", get(list_sessions_handler).post(create_session_handler))
         .route("/api/v1/sessions/{id}", delete(destroy_session_handler))
         .route("/api/v1/sessions/{id}/pipeline", get(get_pipeline_handler))
+        .route("/api/v1/sessions/{id}/blackbox", get(get_black_box_handler))
         .route(
             "/api/v1/profile/cpu",
             get({
@@ -1902,8 +2443,9 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
                 }
             }),
         )
+        .merge(crate::recordings::recordings_router())
         .merge(crate::samples::samples_router())
-        .merge(crate::assets::assets_router());
+        .merge(crate::assets::assets_router().route_layer(upload_guard));
 
     // Add MoQ routes if feature is enabled
     #[cfg(feature = "moq")]
@@ -1912,6 +2454,11 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
         router = router.route("/certificate.sha256", get(get_certificate_sha256_handler));
     }
 
+    #[cfg(feature = "webrtc")]
+    {
+        router = router.merge(crate::webrtc_gateway::webrtc_router());
+    }
+
     let cors_layer = create_cors_layer(&app_state.config.server.cors);
 
     let router = router.fallback(static_handler);
@@ -1938,6 +2485,7 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
 
     let router = router
         .with_state(Arc::clone(&app_state))
+        .layer(middleware::from_fn_with_state(Arc::clone(&app_state), read_only_middleware))
         .layer(middleware::from_fn_with_state(Arc::clone(&app_state), origin_guard_middleware))
         .layer(ServiceBuilder::new().layer(
             TraceLayer::new_for_http()
@@ -1965,7 +2513,9 @@ pub fn create_app(config: Config) -> (Router, Arc<AppState>) {
         header::X_FRAME_OPTIONS,
         header::HeaderValue::from_static("SAMEORIGIN"),
     ))
-    .layer(cors_layer);
+    .layer(cors_layer)
+    // Outermost so every layer and handler sees the verified client certificate role
+    .layer(middleware::from_fn(crate::mtls::client_cert_role_middleware));
 
     (router, app_state)
 }
@@ -1976,6 +2526,7 @@ fn start_moq_webtransport_acceptor(
     app_state: &Arc<AppState>,
     config: &Config,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::moq_auth::AuthError;
     use moq_native::{ServerConfig as MoqServerConfig, ServerTlsConfig};
 
     let gateway = if let Some(gw) = &app_state.moq_gateway {
@@ -1985,6 +2536,12 @@ fn start_moq_webtransport_acceptor(
         return Ok(());
     };
 
+    // A missing secret must stop startup rather than leave the port open.
+    let verifier = crate::moq_auth::verifier_from_config(&config.server.moq_auth)?;
+    if verifier.is_some() {
+        info!(format = ?config.server.moq_auth.format, "MoQ token authentication enabled");
+    }
+
     // Parse address for WebTransport (UDP will use the same port as HTTP/HTTPS)
     let addr: SocketAddr = config.server.address.parse()?;
 
@@ -2016,6 +2573,10 @@ fn start_moq_webtransport_acceptor(
     );
 
     tokio::spawn(async move {
+        if let Some(verifier) = verifier {
+            gateway.set_verifier(verifier).await;
+        }
+
         match moq_config.init() {
             Ok(mut server) => {
                 // Store fingerprints in gateway for HTTP endpoint
@@ -2039,6 +2600,24 @@ fn start_moq_webtransport_acceptor(
                                 let path = wt_request.url().path().to_string();
                                 debug!(path = %path, "Received WebTransport connection request");
 
+                                if let Err(e) =
+                                    gateway.authorize(&path, wt_request.url().query()).await
+                                {
+                                    let status = match e {
+                                        AuthError::MissingToken
+                                        | AuthError::Malformed
+                                        | AuthError::BadSignature
+                                        | AuthError::Expired => StatusCode::UNAUTHORIZED,
+                                        AuthError::PathNotAllowed | AuthError::AccessDenied => {
+                                            StatusCode::FORBIDDEN
+                                        },
+                                    };
+                                    if let Err(e) = wt_request.close(status).await {
+                                        debug!(path = %path, error = %e, "Failed to reject WebTransport session");
+                                    }
+                                    return;
+                                }
+
                                 match wt_request.ok().await {
                                     Ok(session) => {
                                         if let Err(e) =
@@ -2070,29 +2649,83 @@ fn start_moq_webtransport_acceptor(
     Ok(())
 }
 
-/// Starts the HTTP/HTTPS server and optional MoQ WebTransport acceptor.
+/// Waits for Ctrl+C, SIGTERM on Unix or a console close event on Windows.
+///
+/// # Panics
+///
+/// Panics if the signal handlers cannot be installed (critical OS failure).
+// These expect() calls are justified and documented in the function's # Panics section
+#[allow(clippy::expect_used)]
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    // Closing the console window or logging off sends CTRL_CLOSE_EVENT, the closest thing
+    // Windows has to SIGTERM for console processes.
+    #[cfg(windows)]
+    let terminate = async {
+        tokio::signal::windows::ctrl_close()
+            .expect("failed to install console close handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(any(unix, windows)))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {
+            info!("Received CTRL-C signal, initiating graceful shutdown");
+        },
+        () = terminate => {
+            info!("Received termination signal, initiating graceful shutdown");
+        },
+    }
+}
+
+/// Starts the HTTP/HTTPS server and optional MoQ WebTransport acceptor, shutting down
+/// gracefully once `shutdown` completes.
+///
+/// Pipelines run on `data_plane` when one is given, otherwise on the current runtime.
+/// `shutdown` is normally [`shutdown_signal()`]; the Windows service passes its
+/// service control manager stop request instead.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The server address cannot be parsed
 /// - TLS is enabled but certificates cannot be loaded
+/// - Mutual TLS is enabled without TLS, or its client CA bundle cannot be loaded
 /// - The server fails to bind to the specified address
+/// - `server.unix_socket` is set but cannot be bound
 /// - The server encounters a runtime error
 ///
 /// # Panics
 ///
-/// Panics if:
-/// - The Ctrl+C signal handler cannot be installed (critical OS failure)
-/// - The SIGTERM signal handler cannot be installed on Unix systems (critical OS failure)
-/// - The plugin manager fails to initialize (via `create_app`)
-pub async fn start_server(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    let (app, app_state) = create_app(config.clone());
+/// Panics if the plugin manager fails to initialize (via `create_app`).
+pub async fn start_server(
+    config: &Config,
+    data_plane: Option<tokio::runtime::Handle>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (app, app_state) = create_app_with_runtime(config.clone(), data_plane);
     #[cfg(not(feature = "moq"))]
     let _ = &app_state;
 
     let addr: SocketAddr = config.server.address.parse()?;
-    if !addr.ip().is_loopback() && config.permissions.role_header.is_none() {
+    // Required client certificates authenticate every connection, like a trusted auth proxy
+    let mtls_required =
+        config.server.tls && config.server.mtls.enabled && config.server.mtls.require_client_cert;
+    if !addr.ip().is_loopback() && config.permissions.role_header.is_none() && !mtls_required {
         if !config.permissions.allow_insecure_no_auth {
             return Err(format!(
                 "Refusing to start: server.address is '{addr}' (non-loopback) but permissions.role_header is not set. \
@@ -2115,47 +2748,70 @@ pub async fn start_server(config: &Config) -> Result<(), Box<dyn std::error::Err
     #[cfg(feature = "moq")]
     start_moq_webtransport_acceptor(&app_state, config)?;
 
-    // Set up graceful shutdown signal handler
-    // These expect() calls are justified and documented in the function's # Panics section
-    #[allow(clippy::expect_used)]
-    let shutdown_signal = async {
-        let ctrl_c = async {
-            tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
-        };
+    // Both listeners stop on the same signal
+    let shutdown = shutdown.shared();
 
+    if let Some(path) = &config.server.unix_socket {
         #[cfg(unix)]
-        let terminate = async {
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-                .expect("failed to install signal handler")
-                .recv()
-                .await;
-        };
-
+        serve_unix_socket(path, config.server.unix_socket_mode, app.clone(), shutdown.clone())?;
         #[cfg(not(unix))]
-        let terminate = std::future::pending::<()>();
+        return Err(format!(
+            "server.unix_socket is set to '{path}' but Unix sockets are not supported on this platform"
+        )
+        .into());
+    }
 
-        tokio::select! {
-            () = ctrl_c => {
-                info!("Received CTRL-C signal, initiating graceful shutdown");
-            },
-            () = terminate => {
-                info!("Received SIGTERM signal, initiating graceful shutdown");
-            },
-        }
-    };
+    if config.server.mtls.enabled && !config.server.tls {
+        return Err("Mutual TLS is enabled but server.tls is false".into());
+    }
 
     if config.server.tls {
         if config.server.cert_path.is_empty() || config.server.key_path.is_empty() {
             return Err("TLS is enabled but cert_path or key_path is not configured".into());
         }
 
+        if config.server.mtls.enabled && config.server.mtls.client_ca_path.is_empty() {
+            return Err(
+                "Mutual TLS is enabled but server.mtls.client_ca_path is not configured".into()
+            );
+        }
+
         info!(
             address = %addr,
             cert_path = %config.server.cert_path,
             key_path = %config.server.key_path,
+            mtls = config.server.mtls.enabled,
             "Starting HTTPS API server"
         );
 
+        if config.server.mtls.enabled {
+            let tls_config = crate::mtls::server_config(&config.server).map_err(|e| {
+                error!(error = %e, "Failed to load mutual TLS configuration");
+                e
+            })?;
+            let acceptor =
+                crate::mtls::ClientCertAcceptor::new(tls_config, config.server.mtls.roles.clone());
+
+            let handle = axum_server::Handle::new();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    shutdown.await;
+                    handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
+                }
+            });
+
+            let mut server = axum_server::bind(addr).acceptor(acceptor).handle(handle);
+            configure_http_builder(server.http_builder(), &config.server);
+            return server
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                .await
+                .map_err(|e| {
+                    error!(error = %e, "API server error");
+                    e.into()
+                });
+        }
+
         let tls_config = axum_server::tls_rustls::RustlsConfig::from_pem_file(
             &config.server.cert_path,
             &config.server.key_path,
@@ -2177,19 +2833,17 @@ pub async fn start_server(config: &Config) -> Result<(), Box<dyn std::error::Err
         tokio::spawn({
             let handle = handle.clone();
             async move {
-                shutdown_signal.await;
+                shutdown.await;
                 handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
             }
         });
 
-        axum_server::bind_rustls(addr, tls_config)
-            .handle(handle)
-            .serve(app.into_make_service())
-            .await
-            .map_err(|e| {
-                error!(error = %e, "API server error");
-                e.into()
-            })
+        let mut server = axum_server::bind_rustls(addr, tls_config).handle(handle);
+        configure_http_builder(server.http_builder(), &config.server);
+        server.serve(app.into_make_service_with_connect_info::<SocketAddr>()).await.map_err(|e| {
+            error!(error = %e, "API server error");
+            e.into()
+        })
     } else {
         info!(address = %addr, "Starting HTTP API server");
 
@@ -2199,18 +2853,115 @@ pub async fn start_server(config: &Config) -> Result<(), Box<dyn std::error::Err
         tokio::spawn({
             let handle = handle.clone();
             async move {
-                shutdown_signal.await;
+                shutdown.await;
                 handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
             }
         });
 
-        axum_server::bind(addr).handle(handle).serve(app.into_make_service()).await.map_err(|e| {
+        let mut server = axum_server::bind(addr).handle(handle);
+        configure_http_builder(server.http_builder(), &config.server);
+        server.serve(app.into_make_service_with_connect_info::<SocketAddr>()).await.map_err(|e| {
             error!(error = %e, "API server error");
             e.into()
         })
     }
 }
 
+/// Applies `[server.upload_limits].header_read_timeout_ms` to a TCP listener's connections.
+fn configure_http_builder(
+    builder: &mut hyper_util::server::conn::auto::Builder<hyper_util::rt::TokioExecutor>,
+    config: &crate::config::ServerConfig,
+) {
+    let timeout_ms = config.upload_limits.header_read_timeout_ms;
+    let timeout = (timeout_ms > 0).then(|| std::time::Duration::from_millis(timeout_ms));
+    builder.http1().timer(hyper_util::rt::TokioTimer::new()).header_read_timeout(timeout);
+}
+
+/// Serves `app` as plain HTTP on a Unix domain socket until `shutdown` completes.
+///
+/// Requests arriving on the socket go through the same role resolution as TCP requests;
+/// who may connect at all is controlled by the socket file's `mode`.
+#[cfg(unix)]
+fn serve_unix_socket(
+    path: &str,
+    mode: u32,
+    app: Router,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = bind_unix_socket(path, mode).map_err(|e| {
+        error!(error = %e, path = %path, "Failed to bind Unix socket");
+        format!("Failed to bind Unix socket '{path}': {e}")
+    })?;
+    info!(path = %path, mode = %format_args!("{mode:o}"), "Starting HTTP API server on Unix socket");
+
+    // Abstract sockets vanish with the process; socket files have to be removed
+    let socket_file = (!path.starts_with('@')).then(|| path.to_string());
+    tokio::spawn(async move {
+        if let Err(e) =
+            axum::serve(listener, app.into_make_service()).with_graceful_shutdown(shutdown).await
+        {
+            error!(error = %e, "Unix socket API server error");
+        }
+        if let Some(path) = socket_file {
+            let _ = std::fs::remove_file(path);
+        }
+    });
+    Ok(())
+}
+
+/// Binds a Unix socket listener at `path`, or in the abstract namespace if it starts with `@`.
+///
+/// A socket file left behind by a previous run is replaced, but only if nothing is accepting
+/// connections on it. Any other existing file is an error.
+#[cfg(unix)]
+fn bind_unix_socket(path: &str, mode: u32) -> std::io::Result<tokio::net::UnixListener> {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if let Some(name) = path.strip_prefix('@') {
+        return bind_abstract_unix_socket(name);
+    }
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(Error::new(
+                    ErrorKind::AddrInUse,
+                    "another server is listening on this socket",
+                ));
+            }
+            std::fs::remove_file(path)?;
+        },
+        Ok(_) => {
+            return Err(Error::new(ErrorKind::AlreadyExists, "path exists and is not a socket"));
+        },
+        Err(e) if e.kind() == ErrorKind::NotFound => {},
+        Err(e) => return Err(e),
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(listener)
+}
+
+#[cfg(target_os = "linux")]
+fn bind_abstract_unix_socket(name: &str) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::linux::net::SocketAddrExt;
+
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+    let listener = std::os::unix::net::UnixListener::bind_addr(&addr)?;
+    listener.set_nonblocking(true)?;
+    tokio::net::UnixListener::from_std(listener)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn bind_abstract_unix_socket(_name: &str) -> std::io::Result<tokio::net::UnixListener> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "abstract Unix sockets are only supported on Linux",
+    ))
+}
+
 // --- A simple error type for the Axum handler ---
 #[derive(Debug)]
 enum AppError {
@@ -2220,11 +2971,13 @@ enum AppError {
     PipelineCompilation(String),
     BadRequest(String),
     Forbidden(String),
+    UploadLimit(crate::upload_limits::UploadLimitError),
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, msg) = match self {
+            Self::UploadLimit(e) => return e.into_response(),
             Self::Engine(e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, format!("Pipeline execution error: {e}"))
             },
@@ -2283,8 +3036,11 @@ impl IntoResponse for PluginHttpError {
                     .into_response()
             },
             Self::Manager(err) => {
-                error!(error = %err, "Plugin manager error");
-                (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()).into_response()
+                // Include the whole chain: the root cause (e.g. a missing shared library) is
+                // what callers need to act on
+                let message = format!("{err:#}");
+                error!(error = %message, "Plugin manager error");
+                (StatusCode::UNPROCESSABLE_ENTITY, message).into_response()
             },
         }
     }
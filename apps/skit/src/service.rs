@@ -0,0 +1,183 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Windows service integration.
+//!
+//! `skit service install` registers the current executable with the service control manager
+//! (SCM), which then launches it as `skit --config <path> service run`. In that mode the
+//! process hands its main thread to the SCM dispatcher, and the server runs until the SCM
+//! sends a stop or shutdown control.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tracing::{error, info};
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+
+use crate::cli::LogInitFn;
+
+/// Name the service is registered under.
+pub const SERVICE_NAME: &str = "StreamKit";
+const SERVICE_DISPLAY_NAME: &str = "StreamKit Server";
+const SERVICE_DESCRIPTION: &str = "StreamKit real-time media processing server";
+const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
+
+/// How long the SCM should wait for the server to drain before considering it hung.
+const STOP_WAIT_HINT: Duration = Duration::from_secs(15);
+
+/// Arguments for `service_main`, which the SCM invokes without any of ours.
+static SERVICE_ARGS: OnceLock<(String, LogInitFn)> = OnceLock::new();
+
+/// Registers skit as an automatically started service using `config_path`.
+///
+/// # Errors
+///
+/// Returns an error if the config path cannot be resolved, or if the SCM refuses the
+/// registration (usually because the caller is not an administrator or the service exists).
+pub fn install(config_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    // Services start in System32, so the config path must not depend on the current directory
+    let config_path = std::path::absolute(config_path)?;
+    let executable_path = std::env::current_exe()?;
+
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )?;
+    let service_info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from(SERVICE_DISPLAY_NAME),
+        service_type: SERVICE_TYPE,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path,
+        launch_arguments: vec![
+            OsString::from("--config"),
+            config_path.clone().into_os_string(),
+            OsString::from("service"),
+            OsString::from("run"),
+        ],
+        dependencies: vec![],
+        account_name: None,
+        account_password: None,
+    };
+    let service = manager.create_service(&service_info, ServiceAccess::CHANGE_CONFIG)?;
+    service.set_description(SERVICE_DESCRIPTION)?;
+
+    info!(service = SERVICE_NAME, config_path = %config_path.display(), "Service installed");
+    Ok(())
+}
+
+/// Stops the service if it is running and removes its registration.
+///
+/// # Errors
+///
+/// Returns an error if the service does not exist or the caller lacks the rights to delete it.
+pub fn uninstall() -> Result<(), Box<dyn std::error::Error>> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
+    let service = manager.open_service(
+        SERVICE_NAME,
+        ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+    )?;
+
+    // Deletion is deferred by the SCM until the service has stopped and all handles are closed
+    service.delete()?;
+    if service.query_status()?.current_state != ServiceState::Stopped {
+        service.stop()?;
+    }
+
+    info!(service = SERVICE_NAME, "Service marked for deletion");
+    Ok(())
+}
+
+/// Runs the server under the service control manager. Blocks until the service stops.
+///
+/// # Errors
+///
+/// Returns an error if the process was not started by the SCM.
+pub fn run(config_path: &str, init_logging: LogInitFn) -> Result<(), Box<dyn std::error::Error>> {
+    SERVICE_ARGS
+        .set((config_path.to_string(), init_logging))
+        .map_err(|_| "service dispatcher already started")?;
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)?;
+    Ok(())
+}
+
+define_windows_service!(ffi_service_main, service_main);
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = run_service() {
+        error!(error = %e, "Windows service failed");
+    }
+}
+
+fn status(current_state: ServiceState) -> ServiceStatus {
+    ServiceStatus {
+        service_type: SERVICE_TYPE,
+        current_state,
+        controls_accepted: if current_state == ServiceState::Running {
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+        } else {
+            ServiceControlAccept::empty()
+        },
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint: 0,
+        wait_hint: if current_state == ServiceState::StopPending {
+            STOP_WAIT_HINT
+        } else {
+            Duration::default()
+        },
+        process_id: None,
+    }
+}
+
+fn run_service() -> windows_service::Result<()> {
+    let Some((config_path, init_logging)) = SERVICE_ARGS.get() else {
+        return Ok(());
+    };
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let mut shutdown_tx = Some(shutdown_tx);
+    let status_handle =
+        service_control_handler::register(SERVICE_NAME, move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                if let Some(tx) = shutdown_tx.take() {
+                    let _ = tx.send(());
+                }
+                ServiceControlHandlerResult::NoError
+            },
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })?;
+
+    // Relative paths in the config (plugins, logs, samples) resolve against its directory,
+    // as they would when running skit from there.
+    if let Some(dir) = Path::new(config_path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Err(e) = std::env::set_current_dir(dir) {
+            error!(error = %e, dir = %dir.display(), "Failed to change to the config directory");
+        }
+    }
+
+    status_handle.set_service_status(status(ServiceState::Running))?;
+
+    let stop_requested = async move {
+        let _ = shutdown_rx.await;
+        info!("Received service stop request, initiating graceful shutdown");
+        // Tells the SCM the drain may take a while rather than that the service hung
+        if let Err(e) = status_handle.set_service_status(status(ServiceState::StopPending)) {
+            error!(error = %e, "Failed to report service stop pending");
+        }
+    };
+    crate::cli::run_server(config_path, *init_logging, stop_requested);
+
+    status_handle.set_service_status(status(ServiceState::Stopped))?;
+    Ok(())
+}
@@ -4,19 +4,28 @@
 
 //! server/src/session.rs: Manages live, dynamic pipeline sessions.
 
+use crate::black_box::{self, BlackBox};
 use crate::config::Config;
 use opentelemetry::global;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use streamkit_api::{Event as ApiEvent, EventPayload, MessageType, Pipeline};
+use streamkit_api::{
+    Event as ApiEvent, EventPayload, MessageType, Pipeline, RequestPayload, ResponsePayload,
+    SessionFilter, SessionInfo, SessionListQuery, SessionSortKey, SortOrder,
+};
 use streamkit_core::control::EngineControlMessage;
+use streamkit_core::cost::CostReport;
 use streamkit_core::state::NodeState;
 use streamkit_core::stats::NodeStats;
 use streamkit_core::telemetry::TelemetryEvent;
+use streamkit_core::types::Packet;
+use streamkit_core::KindDeprecation;
+use streamkit_engine::breakpoint::{DebugCommand, DebugReply};
 use streamkit_engine::{DynamicEngineConfig, DynamicEngineHandle, Engine};
 use time::format_description::well_known::Rfc3339;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use uuid::Uuid;
 
 /// Convert SystemTime to ISO 8601 / RFC3339 format string using the time crate
@@ -32,6 +41,38 @@ fn normalize_optional_name(name: Option<String>) -> Option<String> {
     })
 }
 
+/// Maximum number of tags a session may carry.
+const MAX_SESSION_TAGS: usize = 16;
+/// Maximum length of a single session tag, in characters.
+const MAX_SESSION_TAG_CHARS: usize = 64;
+
+/// Trims, de-duplicates and validates session tags supplied by a client.
+///
+/// # Errors
+///
+/// Returns an error if there are too many tags or a tag is too long.
+pub fn normalize_session_tags(tags: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.unwrap_or_default() {
+        let tag = tag.trim();
+        if tag.is_empty() || normalized.iter().any(|t| t == tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_SESSION_TAG_CHARS {
+            return Err(format!("Session tag '{tag}' exceeds {MAX_SESSION_TAG_CHARS} characters"));
+        }
+        normalized.push(tag.to_string());
+    }
+    if normalized.len() > MAX_SESSION_TAGS {
+        return Err(format!("Sessions may have at most {MAX_SESSION_TAGS} tags"));
+    }
+    Ok(normalized)
+}
+
+fn unix_micros(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| u64::try_from(d.as_micros()).unwrap_or(u64::MAX))
+}
+
 fn fnv1a_64(input: &str) -> u64 {
     const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
     const FNV_PRIME: u64 = 0x0100_0000_01b3;
@@ -44,6 +85,54 @@ fn fnv1a_64(input: &str) -> u64 {
     hash
 }
 
+/// How often each session re-broadcasts its pipeline snapshot, even without changes.
+///
+/// This lets clients that missed structural events notice drift without polling.
+const PIPELINE_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Computes a stable hash of the pipeline structure (nodes, params and connections).
+///
+/// Node and connection order do not affect the result; runtime state is excluded.
+pub fn pipeline_structure_hash(pipeline: &Pipeline) -> String {
+    let mut nodes: Vec<String> = pipeline
+        .nodes
+        .iter()
+        .map(|(id, node)| {
+            let params = node.params.as_ref().map(ToString::to_string).unwrap_or_default();
+            format!("{id}\0{}\0{params}", node.kind)
+        })
+        .collect();
+    nodes.sort_unstable();
+
+    let mut connections: Vec<String> = pipeline
+        .connections
+        .iter()
+        .map(|c| format!("{}.{}>{}.{}:{:?}", c.from_node, c.from_pin, c.to_node, c.to_pin, c.mode))
+        .collect();
+    connections.sort_unstable();
+
+    let canonical = format!("{}\n--\n{}", nodes.join("\n"), connections.join("\n"));
+    format!("{:016x}", fnv1a_64(&canonical))
+}
+
+fn create_pipeline_snapshot_event(
+    session_id: &str,
+    revision: u64,
+    pipeline: &Pipeline,
+) -> ApiEvent {
+    ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::PipelineSnapshot {
+            session_id: session_id.to_string(),
+            revision,
+            hash: pipeline_structure_hash(pipeline),
+            node_count: pipeline.nodes.len(),
+            connection_count: pipeline.connections.len(),
+        },
+    }
+}
+
 fn generate_session_name(session_id: &str) -> String {
     // Deterministic "docker-style" name derived from the session id.
     // This keeps names consistent across clients without requiring additional storage or APIs.
@@ -139,20 +228,124 @@ pub struct Session {
     /// The handle to send control messages to the running DynamicEngine actor.
     engine_handle: Arc<DynamicEngineHandle>,
     pub pipeline: Arc<Mutex<Pipeline>>,
+    /// Incremented on every structural change to `pipeline`; carried in snapshot events.
+    pipeline_revision: Arc<AtomicU64>,
     /// Timestamp when the session was created
     pub created_at: SystemTime,
     /// User/role who created this session (for permission filtering)
     pub created_by: Option<String>,
+    /// Free-form labels used to filter and search sessions
+    pub tags: Vec<String>,
+    /// Unix time (microseconds) of the last pipeline change or control message
+    last_activity: Arc<AtomicU64>,
+    /// Black-box recorder, when the session is recorded
+    black_box: Option<Arc<BlackBox>>,
 }
 
 impl Session {
     /// Forwards a control message to this session's specific engine actor.
     pub async fn send_control_message(&self, msg: EngineControlMessage) {
+        self.touch();
         if let Err(e) = self.engine_handle.send_control(msg).await {
             tracing::error!(session_id = %self.id, error = %e, "Failed to send control message");
         }
     }
 
+    /// Records a change to the pipeline model and broadcasts a fresh snapshot.
+    ///
+    /// Call after mutating `pipeline`, once the lock has been released.
+    pub async fn publish_pipeline_change(&self, event_tx: &broadcast::Sender<ApiEvent>) {
+        self.touch();
+        let revision = self.pipeline_revision.fetch_add(1, Ordering::AcqRel) + 1;
+        let event = {
+            let pipeline = self.pipeline.lock().await;
+            create_pipeline_snapshot_event(&self.id, revision, &pipeline)
+        };
+        // No receivers is fine; clients pick up the next snapshot when they connect.
+        let _ = event_tx.send(event);
+    }
+
+    /// Warns subscribers that a node in this session was added using a deprecated kind.
+    pub fn notify_kind_deprecation(
+        &self,
+        node_id: &str,
+        deprecation: KindDeprecation,
+        event_tx: &broadcast::Sender<ApiEvent>,
+    ) {
+        tracing::warn!(
+            session_id = %self.id,
+            node_id = %node_id,
+            kind = %deprecation.kind,
+            "{}", deprecation.message
+        );
+        let event = ApiEvent {
+            message_type: MessageType::Event,
+            correlation_id: None,
+            payload: EventPayload::NodeKindDeprecated {
+                session_id: self.id.clone(),
+                node_id: node_id.to_string(),
+                kind: deprecation.kind,
+                replacement: deprecation.replacement,
+                message: deprecation.message,
+            },
+        };
+        let _ = event_tx.send(event);
+    }
+
+    fn touch(&self) {
+        self.last_activity.store(unix_micros(SystemTime::now()), Ordering::Relaxed);
+    }
+
+    /// Returns the time of the last pipeline change or control message.
+    pub fn last_activity_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_micros(self.last_activity.load(Ordering::Relaxed))
+    }
+
+    /// Builds the API summary of this session.
+    pub fn info(&self) -> SessionInfo {
+        SessionInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            created_at: system_time_to_rfc3339(self.created_at),
+            tags: self.tags.clone(),
+            last_activity_at: system_time_to_rfc3339(self.last_activity_at()),
+        }
+    }
+
+    fn matches_query(&self, query: &SessionListQuery) -> bool {
+        if !query.tags.iter().all(|tag| self.tags.contains(tag)) {
+            return false;
+        }
+        let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+            return true;
+        };
+        let needle = search.to_lowercase();
+        self.id.to_lowercase().contains(&needle)
+            || self.name.as_deref().is_some_and(|n| n.to_lowercase().contains(&needle))
+            || self.tags.iter().any(|t| t.to_lowercase().contains(&needle))
+    }
+
+    fn matches_filter(
+        &self,
+        filter: &SessionFilter,
+        name_pattern: Option<&glob::Pattern>,
+        now: SystemTime,
+    ) -> bool {
+        filter.tags.iter().all(|tag| self.tags.contains(tag))
+            && name_pattern
+                .is_none_or(|pattern| self.name.as_deref().is_some_and(|n| pattern.matches(n)))
+            && filter.older_than_secs.is_none_or(|secs| {
+                now.duration_since(self.created_at).unwrap_or_default().as_secs() >= secs
+            })
+    }
+
+    fn sort_value(&self, key: SessionSortKey) -> u64 {
+        match key {
+            SessionSortKey::CreatedAt => unix_micros(self.created_at),
+            SessionSortKey::Activity => self.last_activity.load(Ordering::Relaxed),
+        }
+    }
+
     /// Shuts down the session's engine actor and waits for it to complete.
     ///
     /// # Errors
@@ -162,6 +355,29 @@ impl Session {
         self.engine_handle.shutdown_and_wait().await
     }
 
+    /// Returns true if this session keeps a black-box recording.
+    pub const fn is_recording(&self) -> bool {
+        self.black_box.is_some()
+    }
+
+    /// Adds a handled control request to the session's black-box recording, if it has one.
+    pub fn record_request(
+        &self,
+        role: &str,
+        correlation_id: Option<String>,
+        request: RequestPayload,
+        response: Option<ResponsePayload>,
+    ) {
+        if let Some(black_box) = &self.black_box {
+            black_box.record_request(role, correlation_id, request, response);
+        }
+    }
+
+    /// Resource usage accumulated by this session's nodes so far.
+    pub fn cost_report(&self) -> CostReport {
+        self.engine_handle.cost_report()
+    }
+
     /// Creates a new session by starting a dynamic engine actor and spawning forwarding tasks.
     ///
     /// This does not register the session with `SessionManager`. Callers should insert the
@@ -176,6 +392,8 @@ impl Session {
         name: Option<String>,
         event_tx: broadcast::Sender<ApiEvent>,
         created_by: Option<String>,
+        tags: Vec<String>,
+        record: bool,
     ) -> Result<Self, String> {
         let session_id = Uuid::new_v4().to_string();
         let name =
@@ -291,13 +509,59 @@ impl Session {
             );
         });
 
+        let created_at = SystemTime::now();
+        let pipeline = Arc::new(Mutex::new(Pipeline::default()));
+        let pipeline_revision = Arc::new(AtomicU64::new(0));
+
+        // A recording that fails to start doesn't keep the session from running.
+        let black_box = if record || config.black_box.record_all {
+            let header = black_box::Header {
+                session_id: session_id.clone(),
+                name: name.clone(),
+                created_by: created_by.clone(),
+                tags: tags.clone(),
+            };
+            match BlackBox::start(
+                &config.black_box,
+                header,
+                Arc::clone(&engine.registry),
+                Arc::downgrade(&pipeline),
+                &event_tx,
+            ) {
+                Ok(black_box) => Some(Arc::new(black_box)),
+                Err(e) => {
+                    tracing::warn!(
+                        session_id = %session_id,
+                        error = %e,
+                        "Failed to start black-box recording"
+                    );
+                    None
+                },
+            }
+        } else {
+            None
+        };
+
+        // Spawn task to periodically re-broadcast the pipeline snapshot so clients can
+        // detect drift. Holds only a weak reference so it ends with the session.
+        spawn_pipeline_snapshot_task(
+            session_id.clone(),
+            Arc::downgrade(&pipeline),
+            Arc::clone(&pipeline_revision),
+            event_tx,
+        );
+
         Ok(Self {
             id: session_id,
             name,
             engine_handle: Arc::new(engine_handle),
-            pipeline: Arc::new(Mutex::new(Pipeline::default())),
-            created_at: SystemTime::now(),
+            pipeline,
+            pipeline_revision,
+            created_at,
             created_by,
+            tags,
+            last_activity: Arc::new(AtomicU64::new(unix_micros(created_at))),
+            black_box,
         })
     }
 
@@ -321,6 +585,70 @@ impl Session {
     pub async fn get_node_stats(&self) -> Result<HashMap<String, NodeStats>, String> {
         self.engine_handle.get_node_stats().await
     }
+
+    /// Taps an output pin of a node in this session's pipeline, for bridging into another
+    /// session. The tap is best-effort and never slows this session down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output does not exist or the engine actor has stopped.
+    pub async fn tap_output(
+        &self,
+        node_id: &str,
+        pin: &str,
+    ) -> Result<mpsc::Receiver<Packet>, String> {
+        self.engine_handle.tap_output(node_id, pin).await
+    }
+
+    /// Runs a pipeline debugger command on this session's engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the engine refuses the command or has stopped.
+    pub async fn debug(&self, command: DebugCommand) -> Result<DebugReply, String> {
+        self.engine_handle.debug(command).await
+    }
+
+    /// Subscribes to this session's telemetry events, for bridging into another session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the engine actor has stopped.
+    pub async fn subscribe_telemetry(&self) -> Result<mpsc::Receiver<TelemetryEvent>, String> {
+        self.engine_handle.subscribe_telemetry().await
+    }
+}
+
+fn spawn_pipeline_snapshot_task(
+    session_id: String,
+    pipeline: Weak<Mutex<Pipeline>>,
+    revision: Arc<AtomicU64>,
+    event_tx: broadcast::Sender<ApiEvent>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + PIPELINE_SNAPSHOT_INTERVAL,
+            PIPELINE_SNAPSHOT_INTERVAL,
+        );
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let Some(pipeline) = pipeline.upgrade() else {
+                break;
+            };
+            let event = {
+                let pipeline = pipeline.lock().await;
+                create_pipeline_snapshot_event(
+                    &session_id,
+                    revision.load(Ordering::Acquire),
+                    &pipeline,
+                )
+            };
+            drop(pipeline);
+            let _ = event_tx.send(event);
+        }
+        tracing::debug!(session_id = %session_id, "Pipeline snapshot task ended");
+    });
 }
 
 /// A thread-safe manager for all active sessions.
@@ -392,6 +720,18 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Finds the live session a create request for `name` should attach to.
+    ///
+    /// Only names registered under `named_pipelines` with `on_conflict = "attach"` attach;
+    /// all other names keep the usual unique-name semantics.
+    pub fn attachable_session(&self, name: Option<&str>, config: &Config) -> Option<Session> {
+        let name = name?.trim();
+        if !config.attaches_to_named_session(name) {
+            return None;
+        }
+        self.sessions.values().find(|session| session.name.as_deref() == Some(name)).cloned()
+    }
+
     /// Find session by ID or name
     pub fn get_session_by_name_or_id(&self, identifier: &str) -> Option<Session> {
         // First try by ID
@@ -424,4 +764,84 @@ impl SessionManager {
     pub fn list_sessions(&self) -> Vec<Session> {
         self.sessions.values().cloned().collect()
     }
+
+    /// Returns every visible session matching a bulk-operation filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name_pattern` is not a valid glob.
+    pub fn filter_sessions(
+        &self,
+        filter: &SessionFilter,
+        visible: impl Fn(&Session) -> bool,
+    ) -> Result<Vec<Session>, String> {
+        let name_pattern = filter
+            .name_pattern
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| format!("Invalid name_pattern: {e}"))?;
+        let now = SystemTime::now();
+        Ok(self
+            .sessions
+            .values()
+            .filter(|session| {
+                visible(session) && session.matches_filter(filter, name_pattern.as_ref(), now)
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Returns one page of visible sessions matching `query`, plus the cursor for the next page.
+    ///
+    /// Sessions are ordered by the requested key with the session id as a tie-breaker, so a
+    /// cursor (`"<sort value>.<session id>"`) identifies a stable position in that order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cursor is malformed.
+    pub fn query_sessions(
+        &self,
+        query: &SessionListQuery,
+        visible: impl Fn(&Session) -> bool,
+    ) -> Result<(Vec<Session>, Option<String>), String> {
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|cursor| {
+                cursor
+                    .split_once('.')
+                    .and_then(|(value, id)| Some((value.parse::<u64>().ok()?, id)))
+                    .ok_or_else(|| format!("Invalid session cursor '{cursor}'"))
+            })
+            .transpose()?;
+
+        let mut entries: Vec<(u64, &Session)> = self
+            .sessions
+            .values()
+            .filter(|session| visible(session) && session.matches_query(query))
+            .map(|session| (session.sort_value(query.sort), session))
+            .collect();
+        entries.sort_by(|(a, sa), (b, sb)| (a, &sa.id).cmp(&(b, &sb.id)));
+        if query.order == SortOrder::Desc {
+            entries.reverse();
+        }
+
+        if let Some((value, id)) = after {
+            entries.retain(|(v, session)| {
+                let position = (*v, session.id.as_str()).cmp(&(value, id));
+                match query.order {
+                    SortOrder::Asc => position.is_gt(),
+                    SortOrder::Desc => position.is_lt(),
+                }
+            });
+        }
+
+        let limit = query.limit.unwrap_or(usize::MAX).max(1);
+        let next_cursor = (entries.len() > limit)
+            .then(|| entries.get(limit - 1).map(|(v, session)| format!("{v}.{}", session.id)))
+            .flatten();
+        let page = entries.into_iter().take(limit).map(|(_, session)| session.clone()).collect();
+        Ok((page, next_cursor))
+    }
 }
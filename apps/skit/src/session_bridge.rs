@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Session bridge: lets bridge nodes in one session tap the outputs and telemetry of another.
+//!
+//! A tap is only granted if the role that created the tapping session could access the
+//! target session through the API: it owns the target, or it has `access_all_sessions`.
+//! Taps are read-only and best-effort, so the target's graph and timing are unaffected.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use streamkit_core::session_bridge::SessionBridgeTrait;
+use streamkit_core::telemetry::TelemetryEvent;
+use streamkit_core::types::Packet;
+use tokio::sync::{mpsc, Mutex};
+use tracing::info;
+
+use crate::permissions::{Permissions, PermissionsConfig};
+use crate::session::{Session, SessionManager};
+
+/// Resolves sessions and checks permissions for bridge nodes
+pub struct SessionBridge {
+    session_manager: Arc<Mutex<SessionManager>>,
+    permissions: PermissionsConfig,
+}
+
+impl SessionBridge {
+    /// Create a bridge over the server's sessions
+    pub const fn new(
+        session_manager: Arc<Mutex<SessionManager>>,
+        permissions: PermissionsConfig,
+    ) -> Self {
+        Self { session_manager, permissions }
+    }
+
+    /// Look up the target session and check that the tapping session may access it
+    async fn authorize(&self, session_id: &str, target: &str) -> Result<Session, String> {
+        let (source, target_session) = {
+            let session_manager = self.session_manager.lock().await;
+            (
+                session_manager.get_session_by_name_or_id(session_id),
+                session_manager.get_session_by_name_or_id(target),
+            )
+        };
+        let Some(source) = source else {
+            return Err(format!("Session '{session_id}' not found"));
+        };
+        let Some(target_session) = target_session else {
+            return Err(format!("Session '{target}' not found"));
+        };
+        if source.id == target_session.id {
+            return Err("A session cannot tap itself; connect the nodes directly".to_string());
+        }
+
+        let perms = source
+            .created_by
+            .as_deref()
+            .map_or_else(|| self.permissions.get_default(), |role| self.permissions.get_role(role));
+        if !can_tap(source.created_by.as_deref(), target_session.created_by.as_deref(), &perms) {
+            return Err(format!("Permission denied: session '{target}' belongs to another role"));
+        }
+        Ok(target_session)
+    }
+}
+
+/// Whether a session created by `source_owner` may tap one created by `target_owner`.
+///
+/// Mirrors the API's ownership rule: sessions without an owner are accessible to everyone.
+fn can_tap(source_owner: Option<&str>, target_owner: Option<&str>, perms: &Permissions) -> bool {
+    perms.access_all_sessions || target_owner.is_none_or(|owner| source_owner == Some(owner))
+}
+
+#[async_trait]
+impl SessionBridgeTrait for SessionBridge {
+    async fn tap_output(
+        &self,
+        session_id: &str,
+        target: &str,
+        node_id: &str,
+        pin: &str,
+    ) -> Result<mpsc::Receiver<Packet>, String> {
+        let target_session = self.authorize(session_id, target).await?;
+        let rx = target_session.tap_output(node_id, pin).await?;
+        info!(
+            session_id = %session_id,
+            target = %target_session.id,
+            node_id = %node_id,
+            pin = %pin,
+            "Bridged session output"
+        );
+        Ok(rx)
+    }
+
+    async fn tap_telemetry(
+        &self,
+        session_id: &str,
+        target: &str,
+    ) -> Result<mpsc::Receiver<TelemetryEvent>, String> {
+        let target_session = self.authorize(session_id, target).await?;
+        let rx = target_session.subscribe_telemetry().await?;
+        info!(session_id = %session_id, target = %target_session.id, "Bridged session telemetry");
+        Ok(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_owners_and_admins_can_tap() {
+        let user = Permissions::user();
+        assert!(can_tap(Some("user"), Some("user"), &user));
+        assert!(!can_tap(Some("user"), Some("admin"), &user));
+        assert!(can_tap(Some("user"), None, &user));
+
+        let admin = Permissions::admin();
+        assert!(can_tap(Some("admin"), Some("user"), &admin));
+        assert!(can_tap(None, Some("user"), &admin));
+    }
+}
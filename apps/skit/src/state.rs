@@ -3,17 +3,34 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use std::sync::Arc;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, Mutex, RwLock};
 
-use streamkit_api::Event as ApiEvent;
+use streamkit_api::{
+    Event as ApiEvent, EventPayload, MessageType, Pipeline as ApiPipeline, ResponsePayload,
+};
+use streamkit_core::redaction;
+use streamkit_core::KindDeprecation;
 use streamkit_engine::Engine;
 
 use crate::config::Config;
+use crate::param_validation::{self, ParamError};
 use crate::plugins::SharedUnifiedPluginManager;
 use crate::session::SessionManager;
 
 #[cfg(feature = "moq")]
 use crate::moq_gateway::MoqGateway;
+#[cfg(feature = "webrtc")]
+use crate::webrtc_gateway::WebRtcGateway;
+
+/// Why mutations are refused while read-only; the operator's banner is sent alongside.
+pub const READ_ONLY_MESSAGE: &str = "Server is in read-only mode; changes are disabled";
+
+/// Read-only maintenance mode, entered from config or at runtime by an admin.
+#[derive(Debug, Clone)]
+pub struct ReadOnlyMode {
+    /// Banner shown to clients
+    pub message: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct AppState {
@@ -22,6 +39,115 @@ pub struct AppState {
     pub config: Arc<Config>,
     pub event_tx: broadcast::Sender<ApiEvent>,
     pub plugin_manager: SharedUnifiedPluginManager,
+    /// `Some` while the server rejects mutations
+    pub read_only: Arc<RwLock<Option<ReadOnlyMode>>>,
     #[cfg(feature = "moq")]
     pub moq_gateway: Option<Arc<MoqGateway>>,
+    #[cfg(feature = "webrtc")]
+    pub webrtc_gateway: Arc<WebRtcGateway>,
+}
+
+impl AppState {
+    /// Resolves a renamed node kind to its current name, along with any deprecation notice.
+    ///
+    /// Callers must validate the returned kind rather than the requested one, so permission
+    /// and path checks apply to the node that will actually be instantiated.
+    pub fn resolve_node_kind(&self, kind: &str) -> (String, Option<KindDeprecation>) {
+        match self.engine.registry.read() {
+            Ok(registry) => (registry.canonical_kind(kind).to_string(), registry.deprecation(kind)),
+            Err(e) => {
+                tracing::error!("Engine registry poisoned: {}", e);
+                (kind.to_string(), None)
+            },
+        }
+    }
+
+    /// Validates node params against the param schema registered for `kind`.
+    ///
+    /// Kinds without a registered schema (e.g. oneshot-only markers) pass unchecked.
+    /// Set `partial` for runtime updates, which may omit required fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns the schema violations found in `params`.
+    pub fn validate_node_params(
+        &self,
+        kind: &str,
+        params: Option<&serde_json::Value>,
+        partial: bool,
+    ) -> Result<(), Vec<ParamError>> {
+        let Some(params) = params else {
+            return Ok(());
+        };
+        self.param_schema(kind)
+            .map_or(Ok(()), |schema| param_validation::validate_params(&schema, params, partial))
+    }
+
+    /// Rejects runtime updates to fields the node's schema does not mark as tunable.
+    ///
+    /// `current` is the node's params in the pipeline model; unchanged construct-only
+    /// values are allowed through.
+    ///
+    /// # Errors
+    ///
+    /// Returns one error per construct-only field that `params` would change.
+    pub fn check_tunable_params(
+        &self,
+        kind: &str,
+        params: &serde_json::Value,
+        current: Option<&serde_json::Value>,
+    ) -> Result<(), Vec<ParamError>> {
+        self.param_schema(kind)
+            .map_or(Ok(()), |schema| param_validation::check_tunable(&schema, params, current))
+    }
+
+    /// Masks the params the node's schema marks sensitive. Callers decide whether the
+    /// requesting role needs this (i.e. lacks `view_secrets`).
+    pub fn redact_node_params(&self, kind: &str, params: &mut serde_json::Value) {
+        if let Some(schema) = self.param_schema(kind) {
+            redaction::redact_params(&schema, params);
+        }
+    }
+
+    /// Masks sensitive params on every node of `pipeline`.
+    pub fn redact_pipeline(&self, pipeline: &mut ApiPipeline) {
+        for node in pipeline.nodes.values_mut() {
+            if let Some(params) = node.params.as_mut() {
+                self.redact_node_params(&node.kind, params);
+            }
+        }
+    }
+
+    /// The answer to a mutating request while read-only, or `None` if mutations are allowed.
+    pub async fn read_only_rejection(&self) -> Option<ResponsePayload> {
+        let mode = self.read_only.read().await.clone()?;
+        Some(ResponsePayload::ReadOnly {
+            message: READ_ONLY_MESSAGE.to_string(),
+            banner: mode.message,
+        })
+    }
+
+    /// Enters (`Some`) or leaves (`None`) read-only mode and tells every client.
+    pub async fn set_read_only(&self, mode: Option<ReadOnlyMode>) {
+        let payload = EventPayload::ReadOnlyChanged {
+            enabled: mode.is_some(),
+            message: mode.as_ref().and_then(|mode| mode.message.clone()),
+        };
+        *self.read_only.write().await = mode;
+
+        let event = ApiEvent { message_type: MessageType::Event, correlation_id: None, payload };
+        if self.event_tx.send(event).is_err() {
+            tracing::debug!("No WebSocket clients connected to receive ReadOnlyChanged event");
+        }
+    }
+
+    fn param_schema(&self, kind: &str) -> Option<serde_json::Value> {
+        match self.engine.registry.read() {
+            Ok(registry) => registry.param_schema(kind).cloned(),
+            Err(e) => {
+                tracing::error!("Engine registry poisoned: {}", e);
+                None
+            },
+        }
+    }
 }
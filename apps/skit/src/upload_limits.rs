@@ -0,0 +1,298 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Abuse protection for the HTTP upload endpoints.
+//!
+//! [`upload_guard_middleware`] sits in front of `POST /api/v1/process` and
+//! `POST /api/v1/assets/audio`. It rejects bodies larger than `[server].max_body_size`, caps
+//! concurrent uploads per client IP and aborts uploads that stall, as configured in
+//! `[server.upload_limits]`. Violations are reported as JSON bodies of the form
+//! `{"error": "...", "message": "...", "limit": ...}`.
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::config::ServerConfig;
+
+/// Shared state for [`upload_guard_middleware`].
+#[derive(Debug)]
+pub struct UploadGuard {
+    max_body_size: usize,
+    max_per_ip: usize,
+    idle_timeout: Option<Duration>,
+    active: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl UploadGuard {
+    pub fn new(config: &ServerConfig) -> Arc<Self> {
+        let limits = &config.upload_limits;
+        Arc::new(Self {
+            max_body_size: config.max_body_size,
+            max_per_ip: limits.max_concurrent_per_ip,
+            idle_timeout: (limits.idle_timeout_ms > 0)
+                .then(|| Duration::from_millis(limits.idle_timeout_ms)),
+            active: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Reserves an upload slot for `ip`, or returns `None` if it already has the maximum.
+    fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<UploadPermit> {
+        let mut active = self.active.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let count = active.entry(ip).or_default();
+        if *count >= self.max_per_ip {
+            return None;
+        }
+        *count += 1;
+        drop(active);
+        Some(UploadPermit { guard: Arc::clone(self), ip })
+    }
+
+    /// Wraps `body` so it fails once it exceeds the size limit or stalls, and holds `permit`
+    /// until the body is finished or dropped.
+    fn limit_body(&self, body: Body, permit: Option<UploadPermit>) -> Body {
+        let max_body_size = self.max_body_size;
+        let idle_timeout = self.idle_timeout;
+        let stream = futures::stream::try_unfold(
+            (body.into_data_stream(), 0usize, permit),
+            move |(mut data, received, permit)| async move {
+                let next = match idle_timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, data.next())
+                        .await
+                        .map_err(|_| UploadLimitError::Stalled { timeout })?,
+                    None => data.next().await,
+                };
+                let Some(chunk) = next.transpose()? else {
+                    return Ok::<_, axum::BoxError>(None);
+                };
+                let received = received.saturating_add(chunk.len());
+                if received > max_body_size {
+                    return Err(UploadLimitError::TooLarge { limit: max_body_size }.into());
+                }
+                Ok(Some((chunk, (data, received, permit))))
+            },
+        );
+        Body::from_stream(stream)
+    }
+}
+
+/// An in-flight upload counted against its client's IP address.
+#[derive(Debug)]
+struct UploadPermit {
+    guard: Arc<UploadGuard>,
+    ip: IpAddr,
+}
+
+impl Drop for UploadPermit {
+    fn drop(&mut self) {
+        let mut active =
+            self.guard.active.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(count) = active.get_mut(&self.ip) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                active.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// An upload rejected by [`upload_guard_middleware`].
+#[derive(Debug, Clone)]
+pub enum UploadLimitError {
+    /// The body is larger than `[server].max_body_size`
+    TooLarge { limit: usize },
+    /// The client IP already has `max_concurrent_per_ip` uploads in flight
+    TooManyUploads { limit: usize },
+    /// No body data arrived within `idle_timeout_ms`
+    Stalled { timeout: Duration },
+}
+
+impl UploadLimitError {
+    /// Finds an upload limit violation in `err` or its chain of sources.
+    ///
+    /// Handlers only see these wrapped in body or multipart errors; this lets them answer with
+    /// the limit's status instead of a generic bad request.
+    pub fn find<'a>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a Self> {
+        let mut current = Some(err);
+        while let Some(err) = current {
+            if let Some(limit) = err.downcast_ref::<Self>() {
+                return Some(limit);
+            }
+            current = err.source();
+        }
+        None
+    }
+
+    const fn status(&self) -> StatusCode {
+        match self {
+            Self::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyUploads { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Stalled { .. } => StatusCode::REQUEST_TIMEOUT,
+        }
+    }
+
+    const fn code(&self) -> &'static str {
+        match self {
+            Self::TooLarge { .. } => "payload_too_large",
+            Self::TooManyUploads { .. } => "too_many_uploads",
+            Self::Stalled { .. } => "upload_timeout",
+        }
+    }
+
+    fn limit(&self) -> u64 {
+        match self {
+            Self::TooLarge { limit } | Self::TooManyUploads { limit } => *limit as u64,
+            Self::Stalled { timeout } => u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl std::fmt::Display for UploadLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge { limit } => {
+                write!(f, "Request body exceeds the maximum size of {limit} bytes")
+            },
+            Self::TooManyUploads { limit } => {
+                write!(f, "Too many concurrent uploads from this address (limit: {limit})")
+            },
+            Self::Stalled { timeout } => {
+                write!(f, "Upload stalled: no data received for {}ms", timeout.as_millis())
+            },
+        }
+    }
+}
+
+impl std::error::Error for UploadLimitError {}
+
+impl IntoResponse for UploadLimitError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": self.code(),
+            "message": self.to_string(),
+            "limit": self.limit(),
+        });
+        let mut response = (self.status(), Json(body)).into_response();
+        if matches!(self, Self::TooManyUploads { .. }) {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, header::HeaderValue::from_static("1"));
+        }
+        response
+    }
+}
+
+/// Enforces `[server.upload_limits]` and `max_body_size` on upload requests.
+///
+/// Only `POST` requests are checked. Oversized uploads that declare a `Content-Length` are
+/// rejected before any of the body is read; chunked ones fail once they cross the limit.
+/// Requests without a peer address (the Unix socket) skip the per-IP limit.
+pub async fn upload_guard_middleware(
+    State(guard): State<Arc<UploadGuard>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+
+    let declared_len = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_len.is_some_and(|len| len > guard.max_body_size as u64) {
+        debug!(content_length = ?declared_len, "Rejected upload: body too large");
+        return UploadLimitError::TooLarge { limit: guard.max_body_size }.into_response();
+    }
+
+    let peer_ip = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+    let permit = match peer_ip {
+        Some(ip) if guard.max_per_ip > 0 => {
+            let Some(permit) = guard.try_acquire(ip) else {
+                warn!(peer_ip = %ip, "Rejected upload: too many concurrent uploads");
+                return UploadLimitError::TooManyUploads { limit: guard.max_per_ip }
+                    .into_response();
+            };
+            Some(permit)
+        },
+        _ => None,
+    };
+
+    let req = req.map(|body| guard.limit_body(body, permit));
+    next.run(req).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    fn guard(max_body_size: usize, max_per_ip: usize, idle_timeout_ms: u64) -> Arc<UploadGuard> {
+        let mut config = ServerConfig { max_body_size, ..ServerConfig::default() };
+        config.upload_limits.max_concurrent_per_ip = max_per_ip;
+        config.upload_limits.idle_timeout_ms = idle_timeout_ms;
+        UploadGuard::new(&config)
+    }
+
+    #[test]
+    fn permits_are_counted_per_ip() {
+        let guard = guard(1024, 2, 0);
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        let first = guard.try_acquire(a).unwrap();
+        let _second = guard.try_acquire(a).unwrap();
+        assert!(guard.try_acquire(a).is_none());
+        assert!(guard.try_acquire(b).is_some());
+
+        drop(first);
+        assert!(guard.try_acquire(a).is_some());
+    }
+
+    #[test]
+    fn released_ips_are_forgotten() {
+        let guard = guard(1024, 1, 0);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        drop(guard.try_acquire(ip).unwrap());
+        assert!(guard.active.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn body_over_limit_fails_with_limit_error() {
+        let guard = guard(4, 1, 0);
+        let body = guard.limit_body(Body::from("too long"), None);
+        let err = body.collect().await.unwrap_err();
+        assert!(matches!(
+            UploadLimitError::find(&err),
+            Some(UploadLimitError::TooLarge { limit: 4 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn stalled_body_times_out() {
+        let guard = guard(1024, 1, 20);
+        let stream = futures::stream::pending::<Result<bytes::Bytes, std::io::Error>>();
+        let body = guard.limit_body(Body::from_stream(stream), None);
+        let err = body.collect().await.unwrap_err();
+        assert!(matches!(UploadLimitError::find(&err), Some(UploadLimitError::Stalled { .. })));
+    }
+
+    #[tokio::test]
+    async fn body_within_limit_passes_through() {
+        let guard = guard(8, 1, 1000);
+        let body = guard.limit_body(Body::from("fits"), None);
+        assert_eq!(body.collect().await.unwrap().to_bytes(), "fits");
+    }
+}
@@ -0,0 +1,353 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! WebRTC Gateway: WHIP/WHEP signaling endpoints for the WebRTC transport nodes.
+//!
+//! Browsers and encoders POST an SDP offer to `/api/v1/sessions/{id}/whip/{node}` (publish)
+//! or `/api/v1/sessions/{id}/whep/{node}` (play). The gateway forwards the offer to the node,
+//! which negotiates the peer connection and returns the SDP answer. Media then flows directly
+//! between the client and the node over ICE/DTLS-SRTP; the server only carries signaling.
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, post},
+    Router,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::webrtc_gateway::{
+    WebRtcEndpointKind, WebRtcGatewayTrait, WebRtcRejection, WebRtcRequest,
+};
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// How long a node gets to answer an offer, ICE gathering included
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Largest SDP offer we accept; real offers are a few KB
+const MAX_OFFER_SIZE: usize = 64 * 1024;
+
+/// A registered node endpoint
+struct Endpoint {
+    kind: WebRtcEndpointKind,
+
+    /// Channel to send signaling requests to the node
+    request_tx: mpsc::UnboundedSender<WebRtcRequest>,
+}
+
+/// Routes WHIP/WHEP requests to WebRTC nodes by session and node ID
+pub struct WebRtcGateway {
+    /// Endpoints keyed by `(session_id, node_id)`
+    endpoints: RwLock<HashMap<(String, String), Endpoint>>,
+}
+
+impl WebRtcGateway {
+    /// Create a new WebRTC gateway
+    pub fn new() -> Self {
+        Self { endpoints: RwLock::new(HashMap::new()) }
+    }
+
+    /// Look up the request channel of a node, if it serves `kind`
+    async fn endpoint(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        kind: WebRtcEndpointKind,
+    ) -> Option<mpsc::UnboundedSender<WebRtcRequest>> {
+        let endpoints = self.endpoints.read().await;
+        endpoints
+            .get(&(session_id.to_string(), node_id.to_string()))
+            .filter(|endpoint| endpoint.kind == kind)
+            .map(|endpoint| endpoint.request_tx.clone())
+    }
+
+    /// Get the number of registered endpoints
+    #[cfg(test)]
+    pub async fn endpoint_count(&self) -> usize {
+        self.endpoints.read().await.len()
+    }
+}
+
+impl Default for WebRtcGateway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebRtcGatewayTrait for WebRtcGateway {
+    async fn register_endpoint(
+        &self,
+        session_id: String,
+        node_id: String,
+        kind: WebRtcEndpointKind,
+    ) -> Result<mpsc::UnboundedReceiver<WebRtcRequest>, String> {
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
+
+        {
+            let mut endpoints = self.endpoints.write().await;
+            let key = (session_id.clone(), node_id.clone());
+            if endpoints.contains_key(&key) {
+                return Err(format!(
+                    "Node '{node_id}' of session '{session_id}' already has a WebRTC endpoint"
+                ));
+            }
+            endpoints.insert(key, Endpoint { kind, request_tx });
+        }
+
+        info!(session_id = %session_id, node_id = %node_id, ?kind, "Registered WebRTC endpoint");
+        Ok(request_rx)
+    }
+
+    async fn unregister_endpoint(&self, session_id: &str, node_id: &str) {
+        let mut endpoints = self.endpoints.write().await;
+        if endpoints.remove(&(session_id.to_string(), node_id.to_string())).is_some() {
+            info!(session_id = %session_id, node_id = %node_id, "Unregistered WebRTC endpoint");
+        }
+    }
+}
+
+/// WHIP/WHEP routes, mounted by the server
+pub fn webrtc_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/v1/sessions/{id}/whip/{node}", post(whip_offer_handler))
+        .route("/api/v1/sessions/{id}/whep/{node}", post(whep_offer_handler))
+        .route("/api/v1/sessions/{id}/whip/{node}/{resource}", delete(whip_delete_handler))
+        .route("/api/v1/sessions/{id}/whep/{node}/{resource}", delete(whep_delete_handler))
+}
+
+async fn whip_offer_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, node_id)): Path<(String, String)>,
+    body: String,
+) -> Response {
+    offer(&app_state, &headers, WebRtcEndpointKind::Whip, &session_id, &node_id, body).await
+}
+
+async fn whep_offer_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, node_id)): Path<(String, String)>,
+    body: String,
+) -> Response {
+    offer(&app_state, &headers, WebRtcEndpointKind::Whep, &session_id, &node_id, body).await
+}
+
+async fn whip_delete_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, node_id, resource_id)): Path<(String, String, String)>,
+) -> Response {
+    terminate(&app_state, &headers, WebRtcEndpointKind::Whip, &session_id, &node_id, resource_id)
+        .await
+}
+
+async fn whep_delete_handler(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, node_id, resource_id)): Path<(String, String, String)>,
+) -> Response {
+    terminate(&app_state, &headers, WebRtcEndpointKind::Whep, &session_id, &node_id, resource_id)
+        .await
+}
+
+/// URL path segment of each endpoint kind
+const fn path_segment(kind: WebRtcEndpointKind) -> &'static str {
+    match kind {
+        WebRtcEndpointKind::Whip => "whip",
+        WebRtcEndpointKind::Whep => "whep",
+    }
+}
+
+/// Check permissions and resolve the session name or ID to the node's request channel.
+///
+/// Publishing changes what a session processes, so WHIP needs `modify_sessions`; playing
+/// only needs `list_sessions`, like reading a session's pipeline.
+async fn resolve_endpoint(
+    app_state: &Arc<AppState>,
+    headers: &HeaderMap,
+    kind: WebRtcEndpointKind,
+    session_id: &str,
+    node_id: &str,
+) -> Result<mpsc::UnboundedSender<WebRtcRequest>, Response> {
+    let (role_name, perms) = crate::role_extractor::get_role_and_permissions(headers, app_state);
+    let allowed = match kind {
+        WebRtcEndpointKind::Whip => perms.modify_sessions,
+        WebRtcEndpointKind::Whep => perms.list_sessions,
+    };
+    if !allowed {
+        return Err((StatusCode::FORBIDDEN, "Permission denied").into_response());
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(session_id)
+    };
+    let Some(session) = session else {
+        return Err(
+            (StatusCode::NOT_FOUND, format!("Session '{session_id}' not found")).into_response()
+        );
+    };
+    let owns = session.created_by.as_ref().is_none_or(|creator| creator == &role_name);
+    if !perms.access_all_sessions && !owns {
+        return Err((StatusCode::FORBIDDEN, "Permission denied: you do not own this session")
+            .into_response());
+    }
+
+    app_state.webrtc_gateway.endpoint(&session.id, node_id, kind).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!(
+                "Node '{node_id}' has no {} endpoint in session '{session_id}'",
+                path_segment(kind).to_uppercase()
+            ),
+        )
+            .into_response()
+    })
+}
+
+async fn offer(
+    app_state: &Arc<AppState>,
+    headers: &HeaderMap,
+    kind: WebRtcEndpointKind,
+    session_id: &str,
+    node_id: &str,
+    sdp: String,
+) -> Response {
+    let is_sdp = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().starts_with("application/sdp"));
+    if !is_sdp {
+        return (StatusCode::UNSUPPORTED_MEDIA_TYPE, "Expected Content-Type: application/sdp")
+            .into_response();
+    }
+    if sdp.len() > MAX_OFFER_SIZE {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    }
+
+    let request_tx = match resolve_endpoint(app_state, headers, kind, session_id, node_id).await {
+        Ok(request_tx) => request_tx,
+        Err(response) => return response,
+    };
+
+    let (response_tx, response_rx) = oneshot::channel();
+    if request_tx.send(WebRtcRequest::Offer { sdp, response_tx }).is_err() {
+        return (StatusCode::NOT_FOUND, "Node is shutting down").into_response();
+    }
+
+    let answer = match tokio::time::timeout(NEGOTIATION_TIMEOUT, response_rx).await {
+        Ok(Ok(Ok(answer))) => answer,
+        Ok(Ok(Err(rejection))) => {
+            warn!(session_id = %session_id, node_id = %node_id, %rejection, "WebRTC offer rejected");
+            let status = match rejection {
+                WebRtcRejection::InvalidOffer(_) => StatusCode::BAD_REQUEST,
+                WebRtcRejection::Busy(_) => StatusCode::CONFLICT,
+                WebRtcRejection::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, rejection.to_string()).into_response();
+        },
+        Ok(Err(_)) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Node dropped the offer").into_response();
+        },
+        Err(_) => {
+            warn!(session_id = %session_id, node_id = %node_id, "WebRTC negotiation timed out");
+            return (StatusCode::GATEWAY_TIMEOUT, "Negotiation timed out").into_response();
+        },
+    };
+
+    info!(
+        session_id = %session_id,
+        node_id = %node_id,
+        resource_id = %answer.resource_id,
+        ?kind,
+        "WebRTC resource created"
+    );
+    let location = format!(
+        "/api/v1/sessions/{session_id}/{}/{node_id}/{}",
+        path_segment(kind),
+        answer.resource_id
+    );
+    (
+        StatusCode::CREATED,
+        [(header::CONTENT_TYPE, "application/sdp".to_string()), (header::LOCATION, location)],
+        answer.sdp,
+    )
+        .into_response()
+}
+
+async fn terminate(
+    app_state: &Arc<AppState>,
+    headers: &HeaderMap,
+    kind: WebRtcEndpointKind,
+    session_id: &str,
+    node_id: &str,
+    resource_id: String,
+) -> Response {
+    let request_tx = match resolve_endpoint(app_state, headers, kind, session_id, node_id).await {
+        Ok(request_tx) => request_tx,
+        Err(response) => return response,
+    };
+
+    let (response_tx, response_rx) = oneshot::channel();
+    if request_tx.send(WebRtcRequest::Terminate { resource_id, response_tx }).is_err() {
+        return (StatusCode::NOT_FOUND, "Node is shutting down").into_response();
+    }
+    match response_rx.await {
+        Ok(true) => StatusCode::OK.into_response(),
+        Ok(false) | Err(_) => (StatusCode::NOT_FOUND, "Unknown resource").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[allow(clippy::unwrap_used)]
+    async fn test_endpoint_registration() {
+        let gateway = WebRtcGateway::new();
+
+        let _rx = gateway
+            .register_endpoint("session-1".into(), "ingest".into(), WebRtcEndpointKind::Whip)
+            .await
+            .unwrap();
+        assert_eq!(gateway.endpoint_count().await, 1);
+
+        // Same node twice is refused, the same node name in another session is not
+        let duplicate = gateway
+            .register_endpoint("session-1".into(), "ingest".into(), WebRtcEndpointKind::Whep)
+            .await;
+        assert!(duplicate.is_err());
+        let _rx2 = gateway
+            .register_endpoint("session-2".into(), "ingest".into(), WebRtcEndpointKind::Whip)
+            .await
+            .unwrap();
+        assert_eq!(gateway.endpoint_count().await, 2);
+
+        gateway.unregister_endpoint("session-1", "ingest").await;
+        assert_eq!(gateway.endpoint_count().await, 1);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::unwrap_used)]
+    async fn test_endpoint_lookup_matches_kind() {
+        let gateway = WebRtcGateway::new();
+        let _rx = gateway
+            .register_endpoint("session-1".into(), "ingest".into(), WebRtcEndpointKind::Whip)
+            .await
+            .unwrap();
+
+        assert!(gateway.endpoint("session-1", "ingest", WebRtcEndpointKind::Whip).await.is_some());
+        assert!(gateway.endpoint("session-1", "ingest", WebRtcEndpointKind::Whep).await.is_none());
+        assert!(gateway.endpoint("session-1", "other", WebRtcEndpointKind::Whip).await.is_none());
+    }
+}
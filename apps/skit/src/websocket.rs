@@ -10,43 +10,45 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
 use streamkit_api::{
-    EventPayload, MessageType, Request as ApiRequest, Response as ApiResponse, ResponsePayload,
+    Connection, ConnectionMode, Event as ApiEvent, EventPayload, MessageType,
+    Request as ApiRequest, RequestPayload, Response as ApiResponse, ResponsePayload, WireFormat,
 };
+use streamkit_core::redaction::REDACTED;
 
+use crate::connection_tap::TapLimits;
 use crate::permissions::Permissions;
 use crate::state::AppState;
 
 static ACTIVE_CONNECTIONS: AtomicU64 = AtomicU64::new(0);
-const DEFAULT_MAX_WS_MESSAGE_BYTES: usize = 1024 * 1024; // 1 MiB
-
-fn max_ws_message_bytes() -> usize {
-    static MAX: OnceLock<usize> = OnceLock::new();
-    *MAX.get_or_init(|| {
-        std::env::var("SK_WEBSOCKET_MAX_MESSAGE_BYTES")
-            .ok()
-            .and_then(|v| v.parse::<usize>().ok())
-            .filter(|v| *v > 0)
-            .unwrap_or(DEFAULT_MAX_WS_MESSAGE_BYTES)
-    })
-}
-
-/// Helper function to send a JSON message over WebSocket with consistent error handling.
+/// Helper function to send a message over WebSocket in the connection's wire format, with
+/// consistent error handling. JSON goes out as text frames, MessagePack as binary frames.
 /// Returns `Ok(())` if the message was sent successfully, `Err(())` if serialization
 /// or sending failed (indicating the connection should be closed).
 ///
 /// The `Sync` bound on `T` is required because the message reference crosses an `.await` point,
 /// and the future must be `Send` to work with Tokio's multi-threaded runtime.
-async fn send_json_message<T: Serialize + Sync>(
+async fn send_message<T: Serialize + Sync>(
     socket: &mut WebSocket,
+    format: WireFormat,
     message: &T,
     message_type: &str,
 ) -> Result<(), ()> {
-    match serde_json::to_string(message) {
-        Ok(json) => {
-            if socket.send(axum::extract::ws::Message::Text(json.into())).await.is_err() {
+    let frame = match format {
+        WireFormat::Json => serde_json::to_string(message)
+            .map(|json| axum::extract::ws::Message::Text(json.into()))
+            .map_err(|e| e.to_string()),
+        // Named (map) encoding keeps the message shape identical to the JSON form.
+        WireFormat::Msgpack => rmp_serde::to_vec_named(message)
+            .map(|bytes| axum::extract::ws::Message::Binary(bytes.into()))
+            .map_err(|e| e.to_string()),
+    };
+    match frame {
+        Ok(frame) => {
+            if socket.send(frame).await.is_err() {
                 warn!("Failed to send WebSocket {}", message_type);
                 Err(())
             } else {
@@ -60,6 +62,23 @@ async fn send_json_message<T: Serialize + Sync>(
     }
 }
 
+/// Decodes a client request. `encoding` is the frame's encoding: text frames are always JSON,
+/// binary frames are MessagePack.
+fn decode_request(payload: &[u8], encoding: WireFormat) -> Result<ApiRequest, String> {
+    let result = match encoding {
+        WireFormat::Json => {
+            serde_json::from_slice(payload).map_err(|e| format!("Invalid JSON: {e}"))
+        },
+        WireFormat::Msgpack => {
+            rmp_serde::from_slice(payload).map_err(|e| format!("Invalid MessagePack: {e}"))
+        },
+    };
+    if let Err(e) = &result {
+        warn!(error = %e, message_len = payload.len(), "Failed to parse WebSocket message");
+    }
+    result
+}
+
 /// Metrics for WebSocket connection handling
 #[derive(Clone)]
 struct WebSocketMetrics {
@@ -93,39 +112,40 @@ impl WebSocketMetrics {
     }
 }
 
-/// Handle a text message received from the WebSocket client.
+/// Handle a decoded request from the WebSocket client; decode errors are reported back to it.
 /// Returns true if the connection should continue, false if it should break.
+#[allow(clippy::too_many_arguments)]
 async fn handle_client_message(
     socket: &mut WebSocket,
-    text: String,
+    format: WireFormat,
+    request: Result<ApiRequest, String>,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
+    tap_tx: &mpsc::Sender<ApiEvent>,
     metrics: &WebSocketMetrics,
 ) -> bool {
     metrics.messages_counter.add(1, &[KeyValue::new("direction", "inbound")]);
 
-    // Parse the incoming request
-    let request: ApiRequest = match serde_json::from_str(&text) {
+    let request = match request {
         Ok(req) => req,
-        Err(e) => {
-            warn!(error = %e, message_len = text.len(), "Failed to parse WebSocket message");
+        Err(message) => {
             metrics.errors_counter.add(1, &[KeyValue::new("error_type", "parse_error")]);
             let error_response = ApiResponse {
                 message_type: MessageType::Response,
                 correlation_id: None,
-                payload: ResponsePayload::Error { message: format!("Invalid JSON: {e}") },
+                payload: ResponsePayload::Error { message },
             };
-            let _ = send_json_message(socket, &error_response, "error response").await;
+            let _ = send_message(socket, format, &error_response, "error response").await;
             return true; // Continue processing
         },
     };
 
     // Handle the request and generate a response
-    if let Some(response) = handle_api_request(request, app_state, perms, role_name).await {
+    if let Some(response) = handle_api_request(request, app_state, perms, role_name, tap_tx).await {
         // Send the response back
         metrics.messages_counter.add(1, &[KeyValue::new("direction", "outbound")]);
-        if send_json_message(socket, &response, "response").await.is_err() {
+        if send_message(socket, format, &response, "response").await.is_err() {
             metrics.errors_counter.add(1, &[KeyValue::new("error_type", "send_error")]);
             return false; // Break loop
         }
@@ -141,14 +161,17 @@ pub async fn handle_websocket(
     app_state: Arc<AppState>,
     perms: Permissions,
     role_name: String,
+    format: WireFormat,
 ) {
-    info!("WebSocket connection established");
+    info!(?format, "WebSocket connection established");
 
     let metrics = WebSocketMetrics::shared();
     let active = ACTIVE_CONNECTIONS.fetch_add(1, Ordering::Relaxed) + 1;
     metrics.connections_gauge.record(active, &[]);
 
     let mut event_rx = app_state.event_tx.subscribe();
+    // Events for this client only (connection taps). Taps stop once the receiver drops.
+    let (tap_tx, mut tap_rx) = mpsc::channel(crate::connection_tap::TAP_EVENT_CAPACITY);
 
     let mut visible_session_ids: HashSet<String> = if perms.access_all_sessions {
         HashSet::new()
@@ -170,7 +193,7 @@ pub async fn handle_websocket(
             Some(msg) = socket.recv() => {
                 match msg {
                     Ok(axum::extract::ws::Message::Text(text)) => {
-                        let max_len = max_ws_message_bytes();
+                        let max_len = app_state.config.server.websocket_max_message_bytes;
                         if text.len() > max_len {
                             warn!(
                                 message_len = text.len(),
@@ -190,18 +213,18 @@ pub async fn handle_websocket(
                                     ),
                                 },
                             };
-                            let _ = send_json_message(&mut socket, &error_response, "error response")
+                            let _ = send_message(&mut socket, format, &error_response, "error response")
                                 .await;
                             let _ = socket.send(axum::extract::ws::Message::Close(None)).await;
                             break;
                         }
 
-                        if !handle_client_message(&mut socket, text.to_string(), &app_state, &perms, &role_name, &metrics).await {
+                        if !handle_client_message(&mut socket, format, decode_request(text.as_bytes(), WireFormat::Json), &app_state, &perms, &role_name, &tap_tx, &metrics).await {
                             break;
                         }
                     }
                     Ok(axum::extract::ws::Message::Binary(data)) => {
-                        let max_len = max_ws_message_bytes();
+                        let max_len = app_state.config.server.websocket_max_message_bytes;
                         if data.len() > max_len {
                             warn!(
                                 message_len = data.len(),
@@ -214,6 +237,13 @@ pub async fn handle_websocket(
                             let _ = socket.send(axum::extract::ws::Message::Close(None)).await;
                             break;
                         }
+
+                        // Binary frames only carry requests on MessagePack connections.
+                        if format == WireFormat::Msgpack
+                            && !handle_client_message(&mut socket, format, decode_request(&data, WireFormat::Msgpack), &app_state, &perms, &role_name, &tap_tx, &metrics).await
+                        {
+                            break;
+                        }
                     }
                     Ok(axum::extract::ws::Message::Close(_)) => {
                         info!("WebSocket connection closed");
@@ -228,6 +258,15 @@ pub async fn handle_websocket(
                 }
             },
 
+            // An event for this client only
+            Some(event) = tap_rx.recv() => {
+                metrics.messages_counter.add(1, &[KeyValue::new("direction", "outbound")]);
+                if send_message(&mut socket, format, &event, "event").await.is_err() {
+                    metrics.errors_counter.add(1, &[KeyValue::new("error_type", "send_error")]);
+                    break;
+                }
+            }
+
             // A broadcast event was received
             event_result = event_rx.recv() => {
                 let event = match event_result {
@@ -274,15 +313,26 @@ pub async fn handle_websocket(
                         | EventPayload::NodeRemoved { session_id, .. }
                         | EventPayload::ConnectionAdded { session_id, .. }
                         | EventPayload::ConnectionRemoved { session_id, .. }
+                        | EventPayload::PinAdded { session_id, .. }
+                        | EventPayload::PinRemoved { session_id, .. }
+                        | EventPayload::UiMetadataChanged { session_id, .. }
+                        | EventPayload::NodeKindDeprecated { session_id, .. }
+                        | EventPayload::PipelineSnapshot { session_id, .. }
+                        | EventPayload::SessionCostReport { session_id, .. }
+                        | EventPayload::ConnectionTapPacket { session_id, .. }
+                        | EventPayload::ConnectionTapEnded { session_id, .. }
                         | EventPayload::NodeTelemetry { session_id, .. } => {
                             visible_session_ids.contains(session_id)
                         }
+                        EventPayload::ReadOnlyChanged { .. } => true,
                     }
                 };
 
                 if should_send {
+                    let event =
+                        if perms.view_secrets { event } else { redact_event(&app_state, event).await };
                     metrics.messages_counter.add(1, &[KeyValue::new("direction", "outbound")]);
-                    if send_json_message(&mut socket, &event, "event").await.is_err() {
+                    if send_message(&mut socket, format, &event, "event").await.is_err() {
                         metrics.errors_counter.add(1, &[KeyValue::new("error_type", "send_error")]);
                         break;
                     }
@@ -298,15 +348,80 @@ pub async fn handle_websocket(
     info!("WebSocket connection terminated");
 }
 
+/// Masks sensitive node params in events that carry them, for roles without `view_secrets`.
+async fn redact_event(app_state: &AppState, mut event: ApiEvent) -> ApiEvent {
+    match &mut event.payload {
+        EventPayload::NodeAdded { kind, params: Some(params), .. } => {
+            app_state.redact_node_params(kind, params);
+        },
+        EventPayload::NodeParamsChanged { session_id, node_id, params } => {
+            let session = {
+                let session_manager = app_state.session_manager.lock().await;
+                session_manager.get_session_by_name_or_id(session_id)
+            };
+            let kind = match session {
+                Some(session) => session
+                    .pipeline
+                    .lock()
+                    .await
+                    .nodes
+                    .get(node_id.as_str())
+                    .map(|n| n.kind.clone()),
+                None => None,
+            };
+            match kind {
+                Some(kind) => app_state.redact_node_params(&kind, params),
+                // Without the node's schema there is no telling what is sensitive
+                None => *params = serde_json::Value::String(REDACTED.to_string()),
+            }
+        },
+        _ => {},
+    }
+    event
+}
+
 /// Main API request handler that delegates to specific handlers in websocket_handlers module.
 async fn handle_api_request(
     request: ApiRequest,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
+    tap_tx: &mpsc::Sender<ApiEvent>,
 ) -> Option<ApiResponse> {
     let correlation_id = request.correlation_id.clone();
 
+    if let RequestPayload::TapConnection {
+        session_id,
+        from_node,
+        from_pin,
+        to_node,
+        to_pin,
+        max_packets,
+        duration_secs,
+        sample_every,
+        preview_bytes,
+    } = request.payload
+    {
+        let payload = crate::websocket_handlers::handle_tap_connection(
+            session_id,
+            Connection {
+                from_node,
+                from_pin,
+                to_node,
+                to_pin,
+                mode: ConnectionMode::default(),
+                ui_metadata: None,
+            },
+            TapLimits::new(max_packets, duration_secs, sample_every, preview_bytes),
+            app_state,
+            perms,
+            role_name,
+            tap_tx.clone(),
+        )
+        .await;
+        return Some(ApiResponse { message_type: MessageType::Response, correlation_id, payload });
+    }
+
     let payload = crate::websocket_handlers::handle_request_payload(
         request.payload,
         app_state,
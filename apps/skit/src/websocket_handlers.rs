@@ -7,19 +7,29 @@
 //! This module contains handler functions for each API request type, extracted from
 //! the main `handle_api_request` function.
 
+use crate::connection_tap::{self, TapLimits};
 use crate::file_security;
+use crate::param_validation;
 use crate::permissions::Permissions;
 use crate::session::Session;
-use crate::state::AppState;
+use crate::state::{AppState, ReadOnlyMode};
+use std::time::Duration;
 use streamkit_api::{
-    Event as ApiEvent, EventPayload, MessageType, RequestPayload, ResponsePayload,
+    Event as ApiEvent, EventPayload, MessageType, QueuedPacketSummary, RequestPayload,
+    ResponsePayload, UiMetadataTarget,
 };
 use streamkit_core::control::{EngineControlMessage, NodeControlMessage};
 use streamkit_core::registry::NodeDefinition;
 use streamkit_core::types::PacketType;
 use streamkit_core::{InputPin, OutputPin, PinCardinality};
+use streamkit_engine::breakpoint::{DebugCommand, DebugReply};
+use streamkit_engine::ConnectionId;
 use tracing::{debug, error, info, warn};
 
+/// Upper bound on a single `ui_metadata` blob (serialized JSON). Layout data is small;
+/// this keeps the in-memory pipeline from being used as general-purpose storage.
+const MAX_UI_METADATA_BYTES: usize = 64 * 1024;
+
 /// Check if the user has access to modify/destroy a session.
 ///
 /// Returns true if:
@@ -40,17 +50,89 @@ pub async fn handle_request_payload(
     role_name: &str,
     correlation_id: Option<String>,
 ) -> Option<ResponsePayload> {
+    let creates_session = matches!(payload, RequestPayload::CreateSession { .. });
+    if !creates_session && !crate::black_box::any_active() {
+        return dispatch_request(payload, app_state, perms, role_name, correlation_id).await;
+    }
+
+    // Requests are recorded once handled, so the entry carries the outcome. A new session
+    // is only known from the response.
+    let target = match payload.session_id() {
+        Some(session_id) => {
+            app_state.session_manager.lock().await.get_session_by_name_or_id(session_id)
+        },
+        None => None,
+    };
+    let recorded = if creates_session || target.as_ref().is_some_and(Session::is_recording) {
+        // Copied through the wire form: `RequestPayload` isn't `Clone` because of the node
+        // query variant, which never arrives over the wire.
+        serde_json::to_value(&payload)
+            .and_then(serde_json::from_value::<RequestPayload>)
+            .ok()
+            .map(|request| (request, correlation_id.clone()))
+    } else {
+        None
+    };
+
+    let response = dispatch_request(payload, app_state, perms, role_name, correlation_id).await;
+
+    if let Some((request, correlation_id)) = recorded {
+        let session = match (target, &response) {
+            (Some(session), _) => Some(session),
+            (None, Some(ResponsePayload::SessionCreated { session_id, .. })) => {
+                app_state.session_manager.lock().await.get_session_by_name_or_id(session_id)
+            },
+            (None, _) => None,
+        };
+        if let Some(session) = session {
+            session.record_request(role_name, correlation_id, request, response.clone());
+        }
+    }
+    response
+}
+
+async fn dispatch_request(
+    payload: RequestPayload,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+    correlation_id: Option<String>,
+) -> Option<ResponsePayload> {
+    if payload.is_mutation() {
+        if let Some(rejection) = app_state.read_only_rejection().await {
+            debug!(role = %role_name, "Rejected request: read-only mode");
+            return Some(rejection);
+        }
+    }
+
     match payload {
-        RequestPayload::CreateSession { name } => {
-            handle_create_session(name, app_state, perms, role_name, correlation_id).await
+        RequestPayload::CreateSession { name, tags, record } => {
+            handle_create_session(
+                name,
+                tags,
+                record.unwrap_or(false),
+                app_state,
+                perms,
+                role_name,
+                correlation_id,
+            )
+            .await
         },
         RequestPayload::DestroySession { session_id } => {
             handle_destroy_session(session_id, app_state, perms, role_name, correlation_id).await
         },
-        RequestPayload::ListSessions => handle_list_sessions(app_state, perms, role_name).await,
-        RequestPayload::ListNodes => Some(handle_list_nodes(app_state, perms)),
-        RequestPayload::AddNode { session_id, node_id, kind, params } => {
-            handle_add_node(session_id, node_id, kind, params, app_state, perms, role_name).await
+        RequestPayload::DestroySessions { filter, dry_run } => {
+            handle_destroy_sessions(filter, dry_run, app_state, perms, role_name).await
+        },
+        RequestPayload::ListSessions { query } => {
+            handle_list_sessions(query.unwrap_or_default(), app_state, perms, role_name).await
+        },
+        RequestPayload::ListNodes => Some(handle_list_nodes(app_state, perms).await),
+        RequestPayload::AddNode { session_id, node_id, kind, params, label, notes } => {
+            handle_add_node(
+                session_id, node_id, kind, params, label, notes, app_state, perms, role_name,
+            )
+            .await
         },
         RequestPayload::RemoveNode { session_id, node_id } => {
             handle_remove_node(session_id, node_id, app_state, perms, role_name).await
@@ -67,15 +149,97 @@ pub async fn handle_request_payload(
             )
             .await
         },
+        RequestPayload::AddPin { session_id, node_id, pin_name } => {
+            handle_add_pin(session_id, node_id, pin_name, app_state, perms, role_name).await
+        },
+        RequestPayload::RemovePin { session_id, node_id, pin_name } => {
+            handle_remove_pin(session_id, node_id, pin_name, app_state, perms, role_name).await
+        },
         RequestPayload::TuneNode { session_id, node_id, message } => {
             handle_tune_node(session_id, node_id, message, app_state, perms, role_name).await
         },
         RequestPayload::TuneNodeAsync { session_id, node_id, message } => {
             handle_tune_node_async(session_id, node_id, message, app_state, perms, role_name).await
         },
+        RequestPayload::QueryNode { session_id, node_id, kind, args } => {
+            handle_query_node(session_id, node_id, kind, args, app_state, perms, role_name).await
+        },
+        RequestPayload::TuneNodesByKind { kind, params, filter, dry_run } => {
+            handle_tune_nodes_by_kind(kind, params, filter, dry_run, app_state, perms, role_name)
+                .await
+        },
+        RequestPayload::SetUiMetadata { session_id, target, ui_metadata } => {
+            handle_set_ui_metadata(session_id, target, ui_metadata, app_state, perms, role_name)
+                .await
+        },
         RequestPayload::GetPipeline { session_id } => {
             handle_get_pipeline(session_id, app_state, perms, role_name).await
         },
+        RequestPayload::GetSessionCost { session_id } => {
+            handle_get_session_cost(session_id, app_state, perms, role_name).await
+        },
+        // Taps stream to the requesting client, so the WebSocket handler serves them directly
+        RequestPayload::TapConnection { .. } => Some(ResponsePayload::Error {
+            message: "TapConnection is only available over a WebSocket connection".to_string(),
+        }),
+        RequestPayload::SetDebugMode { session_id, enabled } => {
+            let command = DebugCommand::SetEnabled(enabled);
+            handle_debug(session_id, command, 0, app_state, perms, role_name).await
+        },
+        RequestPayload::SetBreakpoint {
+            session_id,
+            from_node,
+            from_pin,
+            to_node,
+            to_pin,
+            paused,
+        } => {
+            let connection = ConnectionId::new(from_node, from_pin, to_node, to_pin);
+            let command = if paused {
+                DebugCommand::Pause(connection)
+            } else {
+                DebugCommand::Resume(connection)
+            };
+            handle_debug(session_id, command, 0, app_state, perms, role_name).await
+        },
+        RequestPayload::StepConnection {
+            session_id,
+            from_node,
+            from_pin,
+            to_node,
+            to_pin,
+            count,
+        } => {
+            let connection = ConnectionId::new(from_node, from_pin, to_node, to_pin);
+            let command = DebugCommand::Step { connection, count: count.unwrap_or(1) };
+            handle_debug(session_id, command, 0, app_state, perms, role_name).await
+        },
+        RequestPayload::InspectConnection {
+            session_id,
+            from_node,
+            from_pin,
+            to_node,
+            to_pin,
+            preview_bytes,
+        } => {
+            let connection = ConnectionId::new(from_node, from_pin, to_node, to_pin);
+            let preview_bytes = preview_bytes.unwrap_or(0).min(connection_tap::MAX_PREVIEW_BYTES);
+            let command = DebugCommand::Inspect(connection);
+            handle_debug(session_id, command, preview_bytes, app_state, perms, role_name).await
+        },
+        RequestPayload::EditQueuedPacket {
+            session_id,
+            from_node,
+            from_pin,
+            to_node,
+            to_pin,
+            packet_id,
+            content,
+        } => {
+            let connection = ConnectionId::new(from_node, from_pin, to_node, to_pin);
+            let command = DebugCommand::Edit { connection, packet_id, content };
+            handle_debug(session_id, command, 0, app_state, perms, role_name).await
+        },
         RequestPayload::ValidateBatch { session_id: _, operations } => {
             Some(handle_validate_batch(&operations, app_state, perms))
         },
@@ -83,11 +247,16 @@ pub async fn handle_request_payload(
             handle_apply_batch(session_id, operations, app_state, perms, role_name).await
         },
         RequestPayload::GetPermissions => Some(handle_get_permissions(perms, role_name)),
+        RequestPayload::SetReadOnly { enabled, message } => {
+            Some(handle_set_read_only(enabled, message, app_state, perms, role_name).await)
+        },
     }
 }
 
 async fn handle_create_session(
     name: Option<String>,
+    tags: Option<Vec<String>>,
+    record: bool,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
@@ -100,14 +269,23 @@ async fn handle_create_session(
         });
     }
 
+    let tags = match crate::session::normalize_session_tags(tags) {
+        Ok(tags) => tags,
+        Err(message) => return Some(ResponsePayload::Error { message }),
+    };
+
     // Check global session limits + duplicate names with short lock hold.
-    let (current_count, name_taken) = {
+    let (current_count, name_taken, existing) = {
         let session_manager = app_state.session_manager.lock().await;
         let current_count = session_manager.session_count();
         let name_taken = name.as_deref().is_some_and(|n| session_manager.is_name_taken(n));
+        let existing = session_manager.attachable_session(name.as_deref(), &app_state.config);
         drop(session_manager);
-        (current_count, name_taken)
+        (current_count, name_taken, existing)
     };
+    if let Some(existing) = existing {
+        return Some(attach_to_named_session(&existing, role_name, perms));
+    }
     if let Some(ref session_name) = name {
         if name_taken {
             return Some(ResponsePayload::Error {
@@ -127,6 +305,8 @@ async fn handle_create_session(
         name.clone(),
         app_state.event_tx.clone(),
         Some(role_name.to_string()),
+        tags,
+        record,
     )
     .await
     {
@@ -135,18 +315,30 @@ async fn handle_create_session(
     };
 
     // Insert session with short lock hold, re-checking limits to avoid races.
+    // A concurrent request may have started the same named pipeline meanwhile.
     let insert_result = {
         let mut session_manager = app_state.session_manager.lock().await;
         let current_count = session_manager.session_count();
-        if app_state.config.permissions.can_accept_session(current_count) {
-            session_manager.add_session(session.clone())
+        let existing =
+            session_manager.attachable_session(session.name.as_deref(), &app_state.config);
+        if existing.is_some() {
+            Ok(existing)
+        } else if app_state.config.permissions.can_accept_session(current_count) {
+            session_manager.add_session(session.clone()).map(|()| None)
         } else {
             Err("Maximum concurrent sessions limit reached".to_string())
         }
     };
-    if let Err(error_msg) = insert_result {
-        let _ = session.shutdown_and_wait().await;
-        return Some(ResponsePayload::Error { message: error_msg });
+    match insert_result {
+        Ok(None) => {},
+        Ok(Some(existing)) => {
+            let _ = session.shutdown_and_wait().await;
+            return Some(attach_to_named_session(&existing, role_name, perms));
+        },
+        Err(error_msg) => {
+            let _ = session.shutdown_and_wait().await;
+            return Some(ResponsePayload::Error { message: error_msg });
+        },
     }
 
     info!(session_id = %session.id, name = ?session.name, "Created new session");
@@ -173,6 +365,27 @@ async fn handle_create_session(
     })
 }
 
+/// Answers a CreateSession that targets a named pipeline which already has a live session.
+fn attach_to_named_session(
+    session: &Session,
+    role_name: &str,
+    perms: &Permissions,
+) -> ResponsePayload {
+    if !can_access_session(session, role_name, perms) {
+        return ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        };
+    }
+
+    info!(session_id = %session.id, name = ?session.name, "Attached to existing named session");
+
+    ResponsePayload::SessionCreated {
+        session_id: session.id.clone(),
+        name: session.name.clone(),
+        created_at: crate::session::system_time_to_rfc3339(session.created_at),
+    }
+}
+
 async fn handle_destroy_session(
     session_id: String,
     app_state: &AppState,
@@ -221,6 +434,13 @@ async fn handle_destroy_session(
             message: format!("Session '{session_id}' not found"),
         });
     };
+    teardown_session(&session, app_state).await;
+
+    Some(ResponsePayload::SessionDestroyed { session_id: session.id })
+}
+
+/// Shuts down a session already removed from the manager and announces its end.
+async fn teardown_session(session: &Session, app_state: &AppState) {
     let destroyed_id = session.id.clone();
 
     if let Err(e) = session.shutdown_and_wait().await {
@@ -230,6 +450,17 @@ async fn handle_destroy_session(
     info!(session_id = %destroyed_id, "Session destroyed successfully");
 
     // Broadcast event to all clients
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::SessionCostReport {
+            session_id: destroyed_id.clone(),
+            report: session.cost_report(),
+        },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast SessionCostReport event: {}", e);
+    }
     let event = ApiEvent {
         message_type: MessageType::Event,
         correlation_id: None,
@@ -238,11 +469,64 @@ async fn handle_destroy_session(
     if let Err(e) = app_state.event_tx.send(event) {
         error!("Failed to broadcast SessionDestroyed event: {}", e);
     }
+}
+
+async fn handle_destroy_sessions(
+    filter: streamkit_api::SessionFilter,
+    dry_run: bool,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    if !perms.destroy_sessions || !perms.access_all_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: bulk destroy requires destroy_sessions and \
+                      access_all_sessions"
+                .to_string(),
+        });
+    }
+    // An empty filter would match every session; make that an explicit choice per criterion.
+    if filter.is_empty() {
+        return Some(ResponsePayload::Error {
+            message: "Refusing to destroy sessions with an empty filter".to_string(),
+        });
+    }
+
+    let mut session_manager = app_state.session_manager.lock().await;
+    let matched = match session_manager.filter_sessions(&filter, |_| true) {
+        Ok(matched) => matched,
+        Err(message) => return Some(ResponsePayload::Error { message }),
+    };
+    let removed: Vec<Session> = if dry_run {
+        matched
+    } else {
+        matched
+            .iter()
+            .filter_map(|session| session_manager.remove_session_by_id(&session.id))
+            .collect()
+    };
+    drop(session_manager);
+
+    let session_ids: Vec<String> = removed.iter().map(|session| session.id.clone()).collect();
+    info!(
+        role = %role_name,
+        dry_run,
+        count = session_ids.len(),
+        ?filter,
+        "Bulk destroy matched sessions"
+    );
+    if !dry_run {
+        futures::future::join_all(
+            removed.iter().map(|session| teardown_session(session, app_state)),
+        )
+        .await;
+    }
 
-    Some(ResponsePayload::SessionDestroyed { session_id: destroyed_id })
+    Some(ResponsePayload::SessionsDestroyed { session_ids, dry_run })
 }
 
 async fn handle_list_sessions(
+    query: streamkit_api::SessionListQuery,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
@@ -254,36 +538,33 @@ async fn handle_list_sessions(
         });
     }
 
-    let sessions = app_state.session_manager.lock().await.list_sessions();
-
-    // Filter sessions based on ownership and permissions
-    let session_infos: Vec<streamkit_api::SessionInfo> = sessions
-        .into_iter()
-        .filter(|session| {
-            // Admin with access_all_sessions can see all sessions
-            if perms.access_all_sessions {
-                return true;
-            }
-            // Otherwise, only see sessions you created
-            session.created_by.as_ref().is_none_or(|creator| creator == role_name)
-        })
-        .map(|session| streamkit_api::SessionInfo {
-            id: session.id,
-            name: session.name,
-            created_at: crate::session::system_time_to_rfc3339(session.created_at),
-        })
-        .collect();
+    // Filter sessions based on ownership and permissions, then apply the query
+    let result = app_state.session_manager.lock().await.query_sessions(&query, |session| {
+        // Admin with access_all_sessions can see all sessions
+        if perms.access_all_sessions {
+            return true;
+        }
+        // Otherwise, only see sessions you created
+        session.created_by.as_ref().is_none_or(|creator| creator == role_name)
+    });
+    let (sessions, next_cursor) = match result {
+        Ok(page) => page,
+        Err(message) => return Some(ResponsePayload::Error { message }),
+    };
+    let session_infos: Vec<streamkit_api::SessionInfo> =
+        sessions.iter().map(Session::info).collect();
 
     info!(
         role = %role_name,
         access_all = perms.access_all_sessions,
         filtered_sessions = session_infos.len(),
+        has_more = next_cursor.is_some(),
         "Listed sessions with filtering"
     );
-    Some(ResponsePayload::SessionsListed { sessions: session_infos })
+    Some(ResponsePayload::SessionsListed { sessions: session_infos, next_cursor })
 }
 
-fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePayload {
+async fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePayload {
     // Check permission
     if !perms.list_nodes {
         return ResponsePayload::Error {
@@ -291,7 +572,7 @@ fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePaylo
         };
     }
 
-    let mut definitions = {
+    let (mut definitions, enum_resolver) = {
         let registry = match app_state.engine.registry.read() {
             Ok(reg) => reg,
             Err(e) => {
@@ -301,7 +582,7 @@ fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePaylo
                 };
             },
         };
-        registry.definitions()
+        (registry.definitions(), registry.enum_resolver())
     };
 
     // Add synthetic node definitions for oneshot-only nodes
@@ -323,6 +604,10 @@ fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePaylo
         }],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: std::collections::BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 
     definitions.push(NodeDefinition {
@@ -341,6 +626,10 @@ fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePaylo
         outputs: vec![],
         categories: vec!["transport".to_string(), "oneshot".to_string()],
         bidirectional: false,
+        deprecated: false,
+        aliases: Vec::new(),
+        enum_providers: std::collections::BTreeMap::new(),
+        custom_types: Vec::new(),
     });
 
     // Filter nodes based on allowed_nodes permission.
@@ -355,6 +644,25 @@ fn handle_list_nodes(app_state: &AppState, perms: &Permissions) -> ResponsePaylo
         true
     });
 
+    // Fill in deployment-specific options (devices, voices, models) after the registry
+    // lock is released; providers may touch hardware or the filesystem, so they run on a
+    // blocking thread.
+    let reveal_sensitive = perms.view_secrets;
+    let definitions = match tokio::task::spawn_blocking(move || {
+        enum_resolver.resolve(&mut definitions, reveal_sensitive);
+        definitions
+    })
+    .await
+    {
+        Ok(definitions) => definitions,
+        Err(e) => {
+            error!(error = %e, "Resolving node param options failed");
+            return ResponsePayload::Error {
+                message: "Service temporarily unavailable".to_string(),
+            };
+        },
+    };
+
     info!(
         "Listed {} available node definitions (including synthetic oneshot nodes)",
         definitions.len()
@@ -368,6 +676,8 @@ async fn handle_add_node(
     node_id: String,
     kind: String,
     params: Option<serde_json::Value>,
+    label: Option<String>,
+    notes: Option<String>,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
@@ -379,6 +689,9 @@ async fn handle_add_node(
         });
     }
 
+    // Resolve renamed kinds up front so every check below sees the kind that gets created.
+    let (kind, deprecation) = app_state.resolve_node_kind(&kind);
+
     // Reject oneshot-only marker nodes on the dynamic control plane.
     if kind == "streamkit::http_input" || kind == "streamkit::http_output" {
         return Some(ResponsePayload::Error {
@@ -417,14 +730,13 @@ async fn handle_add_node(
         }
     }
 
-    // Security: validate file_writer paths on the control plane too (avoid arbitrary file writes).
-    if kind == "core::file_writer" {
+    // Security: validate write paths on the control plane too (avoid arbitrary file writes).
+    if let Some(param) = file_security::write_path_param(&kind) {
         let Some(path) =
-            params.as_ref().and_then(|p| p.get("path")).and_then(serde_json::Value::as_str)
+            params.as_ref().and_then(|p| p.get(param)).and_then(serde_json::Value::as_str)
         else {
             return Some(ResponsePayload::Error {
-                message: "Invalid file_writer params: expected params.path to be a string"
-                    .to_string(),
+                message: format!("Invalid {kind} params: expected params.{param} to be a string"),
             });
         };
         if let Err(e) = file_security::validate_write_path(path, &app_state.config.security) {
@@ -448,6 +760,12 @@ async fn handle_add_node(
         }
     }
 
+    if let Err(errors) = app_state.validate_node_params(&kind, params.as_ref(), false) {
+        return Some(ResponsePayload::Error {
+            message: param_validation::describe_errors(&node_id, &errors),
+        });
+    }
+
     // Get session with SHORT lock hold to avoid blocking other operations
     let session = {
         let session_manager = app_state.session_manager.lock().await;
@@ -471,7 +789,14 @@ async fn handle_add_node(
         let mut pipeline = session.pipeline.lock().await;
         pipeline.nodes.insert(
             node_id.clone(),
-            streamkit_api::Node { kind: kind.clone(), params: params.clone(), state: None },
+            streamkit_api::Node {
+                kind: kind.clone(),
+                params: params.clone(),
+                label: label.clone(),
+                notes: notes.clone(),
+                state: None,
+                ui_metadata: None,
+            },
         );
     } // Lock released here
 
@@ -484,11 +809,17 @@ async fn handle_add_node(
             node_id: node_id.clone(),
             kind: kind.clone(),
             params: params.clone(),
+            label,
+            notes,
         },
     };
     if let Err(e) = app_state.event_tx.send(event) {
         error!("Failed to broadcast NodeAdded event: {}", e);
     }
+    if let Some(deprecation) = deprecation {
+        session.notify_kind_deprecation(&node_id, deprecation, &app_state.event_tx);
+    }
+    session.publish_pipeline_change(&app_state.event_tx).await;
 
     // Now safe to do async operations without holding session_manager lock
     let control_msg = EngineControlMessage::AddNode { node_id, kind, params };
@@ -547,6 +878,7 @@ async fn handle_remove_node(
     if let Err(e) = app_state.event_tx.send(event) {
         error!("Failed to broadcast NodeRemoved event: {}", e);
     }
+    session.publish_pipeline_change(&app_state.event_tx).await;
 
     // Now safe to do async operations without holding session_manager lock
     let control_msg = EngineControlMessage::RemoveNode { node_id };
@@ -600,6 +932,7 @@ async fn handle_connect(
             to_node: to_node.clone(),
             to_pin: to_pin.clone(),
             mode,
+            ui_metadata: None,
         });
     }
 
@@ -618,6 +951,7 @@ async fn handle_connect(
     if let Err(e) = app_state.event_tx.send(event) {
         error!("Failed to broadcast ConnectionAdded event: {}", e);
     }
+    session.publish_pipeline_change(&app_state.event_tx).await;
 
     // Now safe to do async operations without holding session_manager lock
     // Convert API ConnectionMode to core ConnectionMode
@@ -628,6 +962,9 @@ async fn handle_connect(
         streamkit_api::ConnectionMode::BestEffort => {
             streamkit_core::control::ConnectionMode::BestEffort
         },
+        streamkit_api::ConnectionMode::Feedback => {
+            streamkit_core::control::ConnectionMode::Feedback
+        },
     };
     let control_msg =
         EngineControlMessage::Connect { from_node, from_pin, to_node, to_pin, mode: core_mode };
@@ -697,6 +1034,7 @@ async fn handle_disconnect(
     if let Err(e) = app_state.event_tx.send(event) {
         error!("Failed to broadcast ConnectionRemoved event: {}", e);
     }
+    session.publish_pipeline_change(&app_state.event_tx).await;
 
     // Now safe to do async operations without holding session_manager lock
     let control_msg = EngineControlMessage::Disconnect { from_node, from_pin, to_node, to_pin };
@@ -704,132 +1042,168 @@ async fn handle_disconnect(
     Some(ResponsePayload::Success)
 }
 
-async fn handle_tune_node(
-    session_id: String,
-    node_id: String,
-    message: NodeControlMessage,
+/// Looks up a session for a pin request and checks the node exists in it.
+async fn session_for_pin_request(
+    session_id: &str,
+    node_id: &str,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
-) -> Option<ResponsePayload> {
-    // Check permission to tune nodes
-    if !perms.tune_nodes {
-        return Some(ResponsePayload::Error {
-            message: "Permission denied: cannot tune nodes".to_string(),
+) -> Result<Session, ResponsePayload> {
+    if !perms.modify_sessions {
+        return Err(ResponsePayload::Error {
+            message: "Permission denied: cannot modify sessions".to_string(),
         });
     }
 
-    // Get session with SHORT lock hold to avoid blocking other operations
     let session = {
         let session_manager = app_state.session_manager.lock().await;
-        session_manager.get_session_by_name_or_id(&session_id)
-    }; // Session manager lock released here
-
+        session_manager.get_session_by_name_or_id(session_id)
+    };
     let Some(session) = session else {
-        return Some(ResponsePayload::Error {
+        return Err(ResponsePayload::Error {
             message: format!("Session '{session_id}' not found"),
         });
     };
 
-    // Check ownership (session is cloned, doesn't need lock)
     if !can_access_session(&session, role_name, perms) {
-        return Some(ResponsePayload::Error {
+        return Err(ResponsePayload::Error {
             message: "Permission denied: you do not own this session".to_string(),
         });
     }
 
-    // Handle UpdateParams specially for event broadcasting (and validate file paths)
-    if let NodeControlMessage::UpdateParams(ref params) = message {
-        let (kind, file_path, script_path) = {
-            let pipeline = session.pipeline.lock().await;
-            let kind = pipeline.nodes.get(&node_id).map(|n| n.kind.clone());
-            let file_path =
-                params.get("path").and_then(serde_json::Value::as_str).map(str::to_string);
-            let script_path =
-                params.get("script_path").and_then(serde_json::Value::as_str).map(str::to_string);
-            drop(pipeline);
-            (kind, file_path, script_path)
-        };
+    if !session.pipeline.lock().await.nodes.contains_key(node_id) {
+        return Err(ResponsePayload::Error {
+            message: format!("Node '{node_id}' not found in session '{session_id}'"),
+        });
+    }
+    Ok(session)
+}
 
-        let file_path = file_path.as_deref();
-        let script_path = script_path.as_deref();
+/// Sends a pin request to the engine and waits for the affected pin name.
+async fn send_pin_request(
+    session: &Session,
+    build: impl FnOnce(streamkit_core::control::PinReply) -> EngineControlMessage,
+) -> Result<String, ResponsePayload> {
+    let (reply, reply_rx) = tokio::sync::oneshot::channel();
+    session.send_control_message(build(reply)).await;
+    match tokio::time::timeout(QUERY_TIMEOUT, reply_rx).await {
+        Ok(Ok(Ok(pin_name))) => Ok(pin_name),
+        Ok(Ok(Err(message))) => Err(ResponsePayload::Error { message }),
+        Ok(Err(_)) => Err(ResponsePayload::Error {
+            message: "The session stopped before the pin request completed".to_string(),
+        }),
+        Err(_) => Err(ResponsePayload::Error {
+            message: "The pin request did not complete in time".to_string(),
+        }),
+    }
+}
 
-        if kind.as_deref() == Some("core::file_reader") {
-            let Some(path) = file_path else {
-                return Some(ResponsePayload::Error {
-                    message: "Invalid file_reader params: expected params.path to be a string"
-                        .to_string(),
-                });
-            };
-            if let Err(e) = file_security::validate_file_path(path, &app_state.config.security) {
-                return Some(ResponsePayload::Error { message: format!("Invalid file path: {e}") });
-            }
-        }
+async fn handle_add_pin(
+    session_id: String,
+    node_id: String,
+    pin_name: Option<String>,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    let session =
+        match session_for_pin_request(&session_id, &node_id, app_state, perms, role_name).await {
+            Ok(session) => session,
+            Err(response) => return Some(response),
+        };
 
-        if kind.as_deref() == Some("core::file_writer") {
-            if let Some(path) = file_path {
-                if let Err(e) = file_security::validate_write_path(path, &app_state.config.security)
-                {
-                    return Some(ResponsePayload::Error {
-                        message: format!("Invalid write path: {e}"),
-                    });
-                }
-            }
-        }
+    let pin_name = match send_pin_request(&session, |reply| EngineControlMessage::AddPin {
+        node_id: node_id.clone(),
+        pin_name,
+        reply: Some(reply),
+    })
+    .await
+    {
+        Ok(pin_name) => pin_name,
+        Err(response) => return Some(response),
+    };
 
-        if kind.as_deref() == Some("core::script") {
-            if let Some(path) = script_path {
-                if !path.trim().is_empty() {
-                    if let Err(e) =
-                        file_security::validate_file_path(path, &app_state.config.security)
-                    {
-                        return Some(ResponsePayload::Error {
-                            message: format!("Invalid script_path: {e}"),
-                        });
-                    }
-                }
-            }
-        }
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::PinAdded {
+            session_id: session.id.clone(),
+            node_id: node_id.clone(),
+            pin_name: pin_name.clone(),
+        },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast PinAdded event: {}", e);
+    }
+    Some(ResponsePayload::PinAdded { node_id, pin_name })
+}
 
-        {
-            let mut pipeline = session.pipeline.lock().await;
-            if let Some(node) = pipeline.nodes.get_mut(&node_id) {
-                node.params = Some(params.clone());
-            } else {
-                warn!(
-                    node_id = %node_id,
-                    "Attempted to tune params for non-existent node in pipeline model"
-                );
-            }
-        } // Lock released here
+async fn handle_remove_pin(
+    session_id: String,
+    node_id: String,
+    pin_name: String,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    let session =
+        match session_for_pin_request(&session_id, &node_id, app_state, perms, role_name).await {
+            Ok(session) => session,
+            Err(response) => return Some(response),
+        };
+
+    if let Err(response) = send_pin_request(&session, |reply| EngineControlMessage::RemovePin {
+        node_id: node_id.clone(),
+        pin_name: pin_name.clone(),
+        reply: Some(reply),
+    })
+    .await
+    {
+        return Some(response);
+    }
 
-        // Broadcast event to all clients
+    // Connections into the removed pin are gone from the engine; drop them from the model too
+    let removed: Vec<streamkit_api::Connection> = {
+        let mut pipeline = session.pipeline.lock().await;
+        let (removed, kept) = std::mem::take(&mut pipeline.connections)
+            .into_iter()
+            .partition(|conn| conn.to_node == node_id && conn.to_pin == pin_name);
+        pipeline.connections = kept;
+        removed
+    };
+    for conn in &removed {
         let event = ApiEvent {
             message_type: MessageType::Event,
             correlation_id: None,
-            payload: EventPayload::NodeParamsChanged {
+            payload: EventPayload::ConnectionRemoved {
                 session_id: session.id.clone(),
-                node_id: node_id.clone(),
-                params: params.clone(),
+                from_node: conn.from_node.clone(),
+                from_pin: conn.from_pin.clone(),
+                to_node: conn.to_node.clone(),
+                to_pin: conn.to_pin.clone(),
             },
         };
         if let Err(e) = app_state.event_tx.send(event) {
-            error!("Failed to broadcast NodeParamsChanged event: {}", e);
+            error!("Failed to broadcast ConnectionRemoved event: {}", e);
         }
     }
 
-    // Now safe to do async operations without holding session_manager lock
-    let control_msg = EngineControlMessage::TuneNode { node_id, message };
-    session.send_control_message(control_msg).await;
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::PinRemoved { session_id: session.id.clone(), node_id, pin_name },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast PinRemoved event: {}", e);
+    }
+    if !removed.is_empty() {
+        session.publish_pipeline_change(&app_state.event_tx).await;
+    }
     Some(ResponsePayload::Success)
 }
 
-/// Handle async node tuning (fire-and-forget).
-///
-/// Complexity (37/30) is due to: permission check, session lookup, conditional UpdateParams
-/// handling with pipeline update + event broadcast, engine message sending.
-#[allow(clippy::cognitive_complexity)]
-async fn handle_tune_node_async(
+async fn handle_tune_node(
     session_id: String,
     node_id: String,
     message: NodeControlMessage,
@@ -839,32 +1213,326 @@ async fn handle_tune_node_async(
 ) -> Option<ResponsePayload> {
     // Check permission to tune nodes
     if !perms.tune_nodes {
-        // For async operations, we don't send a response but we should still log
-        warn!("Permission denied: attempted to tune node without permission via TuneNodeAsync");
-        return None;
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot tune nodes".to_string(),
+        });
     }
 
+    // Get session with SHORT lock hold to avoid blocking other operations
     let session = {
         let session_manager = app_state.session_manager.lock().await;
         session_manager.get_session_by_name_or_id(&session_id)
     }; // Session manager lock released here
 
-    if let Some(session) = session {
-        // Check ownership
-        if !can_access_session(&session, role_name, perms) {
-            warn!(
-                session_id = %session_id,
-                role = %role_name,
-                "Permission denied: attempted to tune node in session not owned via TuneNodeAsync"
-            );
-            return None;
+    let Some(session) = session else {
+        return Some(ResponsePayload::Error {
+            message: format!("Session '{session_id}' not found"),
+        });
+    };
+
+    // Check ownership (session is cloned, doesn't need lock)
+    if !can_access_session(&session, role_name, perms) {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        });
+    }
+
+    // Handle UpdateParams specially for event broadcasting (and validate file paths)
+    if let NodeControlMessage::UpdateParams(ref params) = message {
+        if let Err(message) = validate_tuned_params(&session, &node_id, params, app_state).await {
+            return Some(ResponsePayload::Error { message });
+        }
+        store_tuned_params(&session, &node_id, params, app_state).await;
+    }
+
+    // Now safe to do async operations without holding session_manager lock
+    let control_msg = EngineControlMessage::TuneNode { node_id, message };
+    session.send_control_message(control_msg).await;
+    Some(ResponsePayload::Success)
+}
+
+/// Checks an `UpdateParams` payload for one node: file and script paths against the security
+/// config, then the node kind's param schema and tunability.
+async fn validate_tuned_params(
+    session: &Session,
+    node_id: &str,
+    params: &serde_json::Value,
+    app_state: &AppState,
+) -> Result<(), String> {
+    let (kind, current_params, file_path, script_path) = {
+        let pipeline = session.pipeline.lock().await;
+        let node = pipeline.nodes.get(node_id);
+        let kind = node.map(|n| n.kind.clone());
+        let current_params = node.and_then(|n| n.params.clone());
+        let file_path = params.get("path").and_then(serde_json::Value::as_str).map(str::to_string);
+        let script_path =
+            params.get("script_path").and_then(serde_json::Value::as_str).map(str::to_string);
+        drop(pipeline);
+        (kind, current_params, file_path, script_path)
+    };
+
+    let file_path = file_path.as_deref();
+    let script_path = script_path.as_deref();
+
+    if kind.as_deref() == Some("core::file_reader") {
+        let Some(path) = file_path else {
+            return Err(
+                "Invalid file_reader params: expected params.path to be a string".to_string()
+            );
+        };
+        if let Err(e) = file_security::validate_file_path(path, &app_state.config.security) {
+            return Err(format!("Invalid file path: {e}"));
+        }
+    }
+
+    if kind.as_deref() == Some("core::file_writer") {
+        if let Some(path) = file_path {
+            if let Err(e) = file_security::validate_write_path(path, &app_state.config.security) {
+                return Err(format!("Invalid write path: {e}"));
+            }
+        }
+    }
+
+    if kind.as_deref() == Some("core::script") {
+        if let Some(path) = script_path {
+            if !path.trim().is_empty() {
+                if let Err(e) = file_security::validate_file_path(path, &app_state.config.security)
+                {
+                    return Err(format!("Invalid script_path: {e}"));
+                }
+            }
+        }
+    }
+
+    if let Some(kind) = kind.as_deref() {
+        if let Err(errors) = app_state
+            .validate_node_params(kind, Some(params), true)
+            .and_then(|()| app_state.check_tunable_params(kind, params, current_params.as_ref()))
+        {
+            return Err(param_validation::describe_errors(node_id, &errors));
+        }
+    }
+
+    Ok(())
+}
+
+/// Stores tuned params in the session's pipeline model and broadcasts `NodeParamsChanged`.
+async fn store_tuned_params(
+    session: &Session,
+    node_id: &str,
+    params: &serde_json::Value,
+    app_state: &AppState,
+) {
+    {
+        let mut pipeline = session.pipeline.lock().await;
+        if let Some(node) = pipeline.nodes.get_mut(node_id) {
+            node.params = Some(params.clone());
+        } else {
+            warn!(
+                node_id = %node_id,
+                "Attempted to tune params for non-existent node in pipeline model"
+            );
+        }
+    } // Lock released here
+
+    // Broadcast event to all clients
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::NodeParamsChanged {
+            session_id: session.id.clone(),
+            node_id: node_id.to_string(),
+            params: params.clone(),
+        },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast NodeParamsChanged event: {}", e);
+    }
+    session.publish_pipeline_change(&app_state.event_tx).await;
+}
+
+async fn handle_tune_nodes_by_kind(
+    kind: String,
+    params: serde_json::Value,
+    filter: streamkit_api::SessionFilter,
+    dry_run: bool,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    if !perms.tune_nodes || !perms.access_all_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: bulk tuning requires tune_nodes and access_all_sessions"
+                .to_string(),
+        });
+    }
+
+    let (kind, _) = app_state.resolve_node_kind(&kind);
+    let matched = app_state.session_manager.lock().await.filter_sessions(&filter, |_| true);
+    let mut sessions = match matched {
+        Ok(sessions) => sessions,
+        Err(message) => return Some(ResponsePayload::Error { message }),
+    };
+    sessions.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // Validate every target first so a bad value leaves all nodes untouched.
+    let mut targets = Vec::new();
+    for session in &sessions {
+        let node_ids: Vec<String> = {
+            let pipeline = session.pipeline.lock().await;
+            pipeline
+                .nodes
+                .iter()
+                // Nodes created under a renamed kind still match its current name.
+                .filter(|(_, node)| app_state.resolve_node_kind(&node.kind).0 == kind)
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for node_id in node_ids {
+            if let Err(message) = validate_tuned_params(session, &node_id, &params, app_state).await
+            {
+                return Some(ResponsePayload::Error {
+                    message: format!("Session '{}': {message}", session.id),
+                });
+            }
+            targets.push((session, node_id));
+        }
+    }
+
+    if !dry_run {
+        for (session, node_id) in &targets {
+            store_tuned_params(session, node_id, &params, app_state).await;
+            let control_msg = EngineControlMessage::TuneNode {
+                node_id: node_id.clone(),
+                message: NodeControlMessage::UpdateParams(params.clone()),
+            };
+            session.send_control_message(control_msg).await;
+        }
+    }
+
+    info!(
+        role = %role_name,
+        kind = %kind,
+        dry_run,
+        sessions = sessions.len(),
+        nodes = targets.len(),
+        "Bulk tuned nodes by kind"
+    );
+    let nodes = targets
+        .into_iter()
+        .map(|(session, node_id)| streamkit_api::SessionNode {
+            session_id: session.id.clone(),
+            node_id,
+        })
+        .collect();
+    Some(ResponsePayload::NodesTuned { nodes, dry_run })
+}
+
+/// How long a node has to answer a `QueryNode` request.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn handle_query_node(
+    session_id: String,
+    node_id: String,
+    kind: String,
+    args: Option<serde_json::Value>,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    if !perms.tune_nodes {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot query nodes".to_string(),
+        });
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+
+    let Some(session) = session else {
+        return Some(ResponsePayload::Error {
+            message: format!("Session '{session_id}' not found"),
+        });
+    };
+
+    if !can_access_session(&session, role_name, perms) {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        });
+    }
+
+    if !session.pipeline.lock().await.nodes.contains_key(&node_id) {
+        return Some(ResponsePayload::Error {
+            message: format!("Node '{node_id}' not found in session '{session_id}'"),
+        });
+    }
+
+    let (message, reply_rx) =
+        NodeControlMessage::query(kind.clone(), args.unwrap_or(serde_json::Value::Null));
+    session
+        .send_control_message(EngineControlMessage::TuneNode { node_id: node_id.clone(), message })
+        .await;
+
+    match tokio::time::timeout(QUERY_TIMEOUT, reply_rx).await {
+        Ok(Ok(Ok(result))) => Some(ResponsePayload::QueryResult { node_id, kind, result }),
+        Ok(Ok(Err(message))) => Some(ResponsePayload::Error {
+            message: format!("Query '{kind}' failed on node '{node_id}': {message}"),
+        }),
+        // The reply was dropped: the node ignores queries or is no longer running
+        Ok(Err(_)) => Some(ResponsePayload::Error {
+            message: format!("Node '{node_id}' did not answer query '{kind}' (unsupported)"),
+        }),
+        Err(_) => Some(ResponsePayload::Error {
+            message: format!("Node '{node_id}' did not answer query '{kind}' in time"),
+        }),
+    }
+}
+
+/// Handle async node tuning (fire-and-forget).
+///
+/// Complexity (37/30) is due to: permission check, session lookup, conditional UpdateParams
+/// handling with pipeline update + event broadcast, engine message sending.
+#[allow(clippy::cognitive_complexity)]
+async fn handle_tune_node_async(
+    session_id: String,
+    node_id: String,
+    message: NodeControlMessage,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    // Check permission to tune nodes
+    if !perms.tune_nodes {
+        // For async operations, we don't send a response but we should still log
+        warn!("Permission denied: attempted to tune node without permission via TuneNodeAsync");
+        return None;
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    }; // Session manager lock released here
+
+    if let Some(session) = session {
+        // Check ownership
+        if !can_access_session(&session, role_name, perms) {
+            warn!(
+                session_id = %session_id,
+                role = %role_name,
+                "Permission denied: attempted to tune node in session not owned via TuneNodeAsync"
+            );
+            return None;
         }
 
         // Handle UpdateParams specially for pipeline model updates and event broadcasting
         if let NodeControlMessage::UpdateParams(ref params) = message {
-            let (kind, file_path, script_path) = {
+            let (kind, current_params, file_path, script_path) = {
                 let pipeline = session.pipeline.lock().await;
-                let kind = pipeline.nodes.get(&node_id).map(|n| n.kind.clone());
+                let node = pipeline.nodes.get(&node_id);
+                let kind = node.map(|n| n.kind.clone());
+                let current_params = node.and_then(|n| n.params.clone());
                 let file_path =
                     params.get("path").and_then(serde_json::Value::as_str).map(str::to_string);
                 let script_path = params
@@ -872,7 +1540,7 @@ async fn handle_tune_node_async(
                     .and_then(serde_json::Value::as_str)
                     .map(str::to_string);
                 drop(pipeline);
-                (kind, file_path, script_path)
+                (kind, current_params, file_path, script_path)
             };
 
             let file_path = file_path.as_deref();
@@ -914,6 +1582,17 @@ async fn handle_tune_node_async(
                 }
             }
 
+            if let Some(kind) = kind.as_deref() {
+                if let Err(errors) =
+                    app_state.validate_node_params(kind, Some(params), true).and_then(|()| {
+                        app_state.check_tunable_params(kind, params, current_params.as_ref())
+                    })
+                {
+                    warn!("{}", param_validation::describe_errors(&node_id, &errors));
+                    return None;
+                }
+            }
+
             {
                 let mut pipeline = session.pipeline.lock().await;
                 if let Some(node) = pipeline.nodes.get_mut(&node_id) {
@@ -939,6 +1618,7 @@ async fn handle_tune_node_async(
             if let Err(e) = app_state.event_tx.send(event) {
                 error!("Failed to broadcast NodeParamsChanged event: {}", e);
             }
+            session.publish_pipeline_change(&app_state.event_tx).await;
         }
 
         let control_msg = EngineControlMessage::TuneNode { node_id, message };
@@ -949,6 +1629,94 @@ async fn handle_tune_node_async(
     None // Do not send a response
 }
 
+async fn handle_set_ui_metadata(
+    session_id: String,
+    target: UiMetadataTarget,
+    ui_metadata: Option<serde_json::Value>,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    if !perms.modify_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot modify sessions".to_string(),
+        });
+    }
+
+    let size = ui_metadata.as_ref().map_or(0, |value| value.to_string().len());
+    if size > MAX_UI_METADATA_BYTES {
+        return Some(ResponsePayload::Error {
+            message: format!(
+                "ui_metadata is {size} bytes; the limit is {MAX_UI_METADATA_BYTES} bytes"
+            ),
+        });
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+
+    let Some(session) = session else {
+        return Some(ResponsePayload::Error {
+            message: format!("Session '{session_id}' not found"),
+        });
+    };
+
+    if !can_access_session(&session, role_name, perms) {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        });
+    }
+
+    {
+        let mut pipeline = session.pipeline.lock().await;
+        let slot = match &target {
+            UiMetadataTarget::Node { node_id } => {
+                pipeline.nodes.get_mut(node_id).map(|node| &mut node.ui_metadata)
+            },
+            UiMetadataTarget::Connection { from_node, from_pin, to_node, to_pin } => pipeline
+                .connections
+                .iter_mut()
+                .find(|conn| {
+                    &conn.from_node == from_node
+                        && &conn.from_pin == from_pin
+                        && &conn.to_node == to_node
+                        && &conn.to_pin == to_pin
+                })
+                .map(|conn| &mut conn.ui_metadata),
+        };
+        let Some(slot) = slot else {
+            let message = match &target {
+                UiMetadataTarget::Node { node_id } => {
+                    format!("Node '{node_id}' not found in session '{session_id}'")
+                },
+                UiMetadataTarget::Connection { from_node, from_pin, to_node, to_pin } => format!(
+                    "Connection {from_node}.{from_pin} -> {to_node}.{to_pin} not found in session '{session_id}'"
+                ),
+            };
+            return Some(ResponsePayload::Error { message });
+        };
+        slot.clone_from(&ui_metadata);
+    }
+
+    // Layout is not part of the structure hash, so no pipeline snapshot is published.
+    let event = ApiEvent {
+        message_type: MessageType::Event,
+        correlation_id: None,
+        payload: EventPayload::UiMetadataChanged {
+            session_id: session.id.clone(),
+            target,
+            ui_metadata,
+        },
+    };
+    if let Err(e) = app_state.event_tx.send(event) {
+        error!("Failed to broadcast UiMetadataChanged event: {}", e);
+    }
+
+    Some(ResponsePayload::Success)
+}
+
 async fn handle_get_pipeline(
     session_id: String,
     app_state: &AppState,
@@ -991,6 +1759,9 @@ async fn handle_get_pipeline(
     for (id, node) in &mut api_pipeline.nodes {
         node.state = node_states.get(id).cloned();
     }
+    if !perms.view_secrets {
+        app_state.redact_pipeline(&mut api_pipeline);
+    }
 
     info!(
         session_id = %session_id,
@@ -1002,6 +1773,183 @@ async fn handle_get_pipeline(
     Some(ResponsePayload::Pipeline { pipeline: api_pipeline })
 }
 
+async fn handle_get_session_cost(
+    session_id: String,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    if !perms.list_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot view sessions".to_string(),
+        });
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+
+    let Some(session) = session else {
+        return Some(ResponsePayload::Error {
+            message: format!("Session '{session_id}' not found"),
+        });
+    };
+
+    if !can_access_session(&session, role_name, perms) {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        });
+    }
+
+    Some(ResponsePayload::SessionCost {
+        session_id: session.id.clone(),
+        report: session.cost_report(),
+    })
+}
+
+/// Opens a connection tap that streams to `sink`, the requesting client's event queue.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_tap_connection(
+    session_id: String,
+    connection: streamkit_api::Connection,
+    limits: TapLimits,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+    sink: tokio::sync::mpsc::Sender<ApiEvent>,
+) -> ResponsePayload {
+    if !perms.list_sessions {
+        return ResponsePayload::Error {
+            message: "Permission denied: cannot view pipelines".to_string(),
+        };
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+    let Some(session) = session else {
+        return ResponsePayload::Error { message: format!("Session '{session_id}' not found") };
+    };
+    if !can_access_session(&session, role_name, perms) {
+        return ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        };
+    }
+
+    let exists = session.pipeline.lock().await.connections.iter().any(|conn| {
+        conn.from_node == connection.from_node
+            && conn.from_pin == connection.from_pin
+            && conn.to_node == connection.to_node
+            && conn.to_pin == connection.to_pin
+    });
+    if !exists {
+        return ResponsePayload::Error {
+            message: format!(
+                "Connection {}.{} -> {}.{} not found",
+                connection.from_node, connection.from_pin, connection.to_node, connection.to_pin
+            ),
+        };
+    }
+
+    // A connection carries everything its source pin sends, so tapping the pin sees the
+    // same packets without touching the connection itself.
+    let packets = match session.tap_output(&connection.from_node, &connection.from_pin).await {
+        Ok(packets) => packets,
+        Err(e) => {
+            return ResponsePayload::Error { message: format!("Failed to tap connection: {e}") }
+        },
+    };
+
+    let tap_id = uuid::Uuid::new_v4().to_string();
+    info!(
+        session_id = %session.id,
+        %tap_id,
+        from = %format!("{}.{}", connection.from_node, connection.from_pin),
+        to = %format!("{}.{}", connection.to_node, connection.to_pin),
+        ?limits,
+        "Opened connection tap"
+    );
+    crate::connection_tap::spawn_tap(session.id.clone(), tap_id.clone(), packets, limits, sink);
+
+    ResponsePayload::ConnectionTapped {
+        tap_id,
+        max_packets: limits.max_packets,
+        duration_secs: limits.duration_secs,
+        sample_every: limits.sample_every,
+        preview_bytes: limits.preview_bytes,
+    }
+}
+
+/// Runs a pipeline debugger command. Inspecting needs the same access as tapping a
+/// connection; everything else changes how packets flow, so it needs `modify_sessions`.
+async fn handle_debug(
+    session_id: String,
+    command: DebugCommand,
+    preview_bytes: u32,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> Option<ResponsePayload> {
+    let inspecting = matches!(command, DebugCommand::Inspect(_));
+    if inspecting && !perms.list_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot view pipelines".to_string(),
+        });
+    }
+    if !inspecting && !perms.modify_sessions {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: cannot modify sessions".to_string(),
+        });
+    }
+
+    let session = {
+        let session_manager = app_state.session_manager.lock().await;
+        session_manager.get_session_by_name_or_id(&session_id)
+    };
+    let Some(session) = session else {
+        return Some(ResponsePayload::Error {
+            message: format!("Session '{session_id}' not found"),
+        });
+    };
+    if !can_access_session(&session, role_name, perms) {
+        return Some(ResponsePayload::Error {
+            message: "Permission denied: you do not own this session".to_string(),
+        });
+    }
+
+    if let Some(connection) = command.connection() {
+        let exists = session.pipeline.lock().await.connections.iter().any(|conn| {
+            *conn.from_node == *connection.from_node
+                && *conn.from_pin == *connection.from_pin
+                && *conn.to_node == *connection.to_node
+                && *conn.to_pin == *connection.to_pin
+        });
+        if !exists {
+            return Some(ResponsePayload::Error {
+                message: format!("Connection {connection} not found"),
+            });
+        }
+    }
+
+    debug!(session_id = %session.id, ?command, "Running debugger command");
+    match session.debug(command).await {
+        Ok(DebugReply::Done) => Some(ResponsePayload::Success),
+        Ok(DebugReply::Queue { paused, packets }) => Some(ResponsePayload::ConnectionQueue {
+            paused,
+            packets: packets
+                .iter()
+                .map(|queued| QueuedPacketSummary {
+                    packet_id: queued.id,
+                    packet: connection_tap::summarize(&queued.packet, preview_bytes as usize),
+                })
+                .collect(),
+        }),
+        Err(e) => Some(ResponsePayload::Error { message: e }),
+    }
+}
+
 fn handle_validate_batch(
     operations: &[streamkit_api::BatchOperation],
     app_state: &AppState,
@@ -1015,8 +1963,11 @@ fn handle_validate_batch(
     }
 
     // Basic validation: check that all referenced node types are allowed
+    let mut errors = Vec::new();
     for op in operations {
-        if let streamkit_api::BatchOperation::AddNode { kind, params, .. } = op {
+        if let streamkit_api::BatchOperation::AddNode { node_id, kind, params, .. } = op {
+            let (kind, _) = app_state.resolve_node_kind(kind);
+            let kind = kind.as_str();
             if !perms.is_node_allowed(kind) {
                 return ResponsePayload::Error {
                     message: format!("Permission denied: node type '{kind}' not allowed"),
@@ -1038,13 +1989,14 @@ fn handle_validate_batch(
                 }
             }
 
-            if kind == "core::file_writer" {
+            if let Some(param) = file_security::write_path_param(kind) {
                 let path =
-                    params.as_ref().and_then(|p| p.get("path")).and_then(serde_json::Value::as_str);
+                    params.as_ref().and_then(|p| p.get(param)).and_then(serde_json::Value::as_str);
                 let Some(path) = path else {
                     return ResponsePayload::Error {
-                        message: "Invalid file_writer params: expected params.path to be a string"
-                            .to_string(),
+                        message: format!(
+                            "Invalid {kind} params: expected params.{param} to be a string"
+                        ),
                     };
                 };
                 if let Err(e) = file_security::validate_write_path(path, &app_state.config.security)
@@ -1070,17 +2022,32 @@ fn handle_validate_batch(
                     }
                 }
             }
+
+            if let Err(param_errors) = app_state.validate_node_params(kind, params.as_ref(), false)
+            {
+                errors.extend(param_errors.into_iter().map(|e| streamkit_api::ValidationError {
+                    error_type: streamkit_api::ValidationErrorType::Error,
+                    message: e.message,
+                    node_id: Some(node_id.clone()),
+                    connection_id: None,
+                    path: Some(e.path),
+                }));
+            }
         }
     }
 
-    info!(operation_count = operations.len(), "Validated batch operations");
-    ResponsePayload::ValidationResult { errors: Vec::new() }
+    info!(
+        operation_count = operations.len(),
+        error_count = errors.len(),
+        "Validated batch operations"
+    );
+    ResponsePayload::ValidationResult { errors }
 }
 
 #[allow(clippy::significant_drop_tightening)]
 async fn handle_apply_batch(
     session_id: String,
-    operations: Vec<streamkit_api::BatchOperation>,
+    mut operations: Vec<streamkit_api::BatchOperation>,
     app_state: &AppState,
     perms: &Permissions,
     role_name: &str,
@@ -1111,9 +2078,21 @@ async fn handle_apply_batch(
         });
     }
 
+    // Resolve renamed kinds before validation so checks apply to the kinds that get created.
+    let mut deprecations = Vec::new();
+    for op in &mut operations {
+        if let streamkit_api::BatchOperation::AddNode { node_id, kind, .. } = op {
+            let (resolved, deprecation) = app_state.resolve_node_kind(kind);
+            *kind = resolved;
+            if let Some(deprecation) = deprecation {
+                deprecations.push((node_id.clone(), deprecation));
+            }
+        }
+    }
+
     // Validate permissions for all operations
     for op in &operations {
-        if let streamkit_api::BatchOperation::AddNode { kind, params, .. } = op {
+        if let streamkit_api::BatchOperation::AddNode { node_id, kind, params, .. } = op {
             if !perms.is_node_allowed(kind) {
                 return Some(ResponsePayload::Error {
                     message: format!("Permission denied: node type '{kind}' not allowed"),
@@ -1137,13 +2116,14 @@ async fn handle_apply_batch(
                 }
             }
 
-            if kind == "core::file_writer" {
+            if let Some(param) = file_security::write_path_param(kind) {
                 let path =
-                    params.as_ref().and_then(|p| p.get("path")).and_then(serde_json::Value::as_str);
+                    params.as_ref().and_then(|p| p.get(param)).and_then(serde_json::Value::as_str);
                 let Some(path) = path else {
                     return Some(ResponsePayload::Error {
-                        message: "Invalid file_writer params: expected params.path to be a string"
-                            .to_string(),
+                        message: format!(
+                            "Invalid {kind} params: expected params.{param} to be a string"
+                        ),
                     });
                 };
                 if let Err(e) = file_security::validate_write_path(path, &app_state.config.security)
@@ -1171,6 +2151,12 @@ async fn handle_apply_batch(
                     }
                 }
             }
+
+            if let Err(errors) = app_state.validate_node_params(kind, params.as_ref(), false) {
+                return Some(ResponsePayload::Error {
+                    message: param_validation::describe_errors(node_id, &errors),
+                });
+            }
         }
     }
 
@@ -1182,13 +2168,16 @@ async fn handle_apply_batch(
 
         for op in operations {
             match op {
-                streamkit_api::BatchOperation::AddNode { node_id, kind, params } => {
+                streamkit_api::BatchOperation::AddNode { node_id, kind, params, label, notes } => {
                     pipeline.nodes.insert(
                         node_id.clone(),
                         streamkit_api::Node {
                             kind: kind.clone(),
                             params: params.clone(),
+                            label,
+                            notes,
                             state: None,
+                            ui_metadata: None,
                         },
                     );
                     engine_operations.push(EngineControlMessage::AddNode { node_id, kind, params });
@@ -1213,6 +2202,7 @@ async fn handle_apply_batch(
                         to_node: to_node.clone(),
                         to_pin: to_pin.clone(),
                         mode,
+                        ui_metadata: None,
                     });
                     let core_mode = match mode {
                         streamkit_api::ConnectionMode::Reliable => {
@@ -1221,6 +2211,9 @@ async fn handle_apply_batch(
                         streamkit_api::ConnectionMode::BestEffort => {
                             streamkit_core::control::ConnectionMode::BestEffort
                         },
+                        streamkit_api::ConnectionMode::Feedback => {
+                            streamkit_core::control::ConnectionMode::Feedback
+                        },
                     };
                     engine_operations.push(EngineControlMessage::Connect {
                         from_node,
@@ -1254,6 +2247,12 @@ async fn handle_apply_batch(
         drop(pipeline);
     } // Release pipeline lock
 
+    // Batches don't emit per-operation structure events; the snapshot lets clients resync.
+    for (node_id, deprecation) in deprecations {
+        session.notify_kind_deprecation(&node_id, deprecation, &app_state.event_tx);
+    }
+    session.publish_pipeline_change(&app_state.event_tx).await;
+
     // Now safe to do async operations without holding session_manager lock
     for msg in engine_operations {
         session.send_control_message(msg).await;
@@ -1271,3 +2270,22 @@ fn handle_get_permissions(perms: &Permissions, role_name: &str) -> ResponsePaylo
     info!(role = %role_name, "Returning permissions for role");
     ResponsePayload::Permissions { role: role_name.to_string(), permissions: perms.to_info() }
 }
+
+async fn handle_set_read_only(
+    enabled: bool,
+    message: Option<String>,
+    app_state: &AppState,
+    perms: &Permissions,
+    role_name: &str,
+) -> ResponsePayload {
+    if !perms.access_all_sessions {
+        return ResponsePayload::Error {
+            message: "Permission denied: toggling read-only mode requires access_all_sessions"
+                .to_string(),
+        };
+    }
+
+    info!(role = %role_name, enabled, "Read-only mode toggled");
+    app_state.set_read_only(enabled.then_some(ReadOnlyMode { message })).await;
+    ResponsePayload::Success
+}
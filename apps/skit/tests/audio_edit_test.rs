@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use reqwest::multipart;
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use streamkit_server::Config;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, timeout, Duration};
+
+async fn start_test_server(config: Config) -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let (app, _state) = streamkit_server::server::create_app(config);
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+
+    sleep(Duration::from_millis(50)).await;
+    Some((addr, server_handle))
+}
+
+/// Decodes an Ogg/Opus stream and returns the number of mono samples at 48 kHz.
+fn decoded_samples(ogg: &[u8]) -> usize {
+    let mut reader = ogg::PacketReader::new(Cursor::new(ogg));
+    let mut decoder = opus::Decoder::new(48000, opus::Channels::Mono).unwrap();
+    let mut output = vec![0i16; 5760];
+    let mut total = 0;
+    while let Some(packet) = reader.read_packet().unwrap() {
+        if packet.data.starts_with(b"OpusHead") || packet.data.starts_with(b"OpusTags") {
+            continue;
+        }
+        total += decoder.decode(&packet.data, &mut output, false).unwrap();
+    }
+    total
+}
+
+#[tokio::test]
+async fn podcast_edit_sample_joins_intro_episode_and_outro() {
+    let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..").canonicalize().unwrap();
+    let system_audio = repo_root.join("samples/audio/system");
+
+    let mut config = Config::default();
+    config.security.allowed_file_paths = vec![format!("{}/**", system_audio.display())];
+    let Some((addr, server_handle)) = start_test_server(config).await else {
+        return;
+    };
+
+    // The sample refers to the music relative to the repo root; the test runs elsewhere.
+    let yaml =
+        std::fs::read_to_string(repo_root.join("samples/pipelines/oneshot/podcast_edit.yml"))
+            .unwrap()
+            .replace("samples/audio/system", &system_audio.display().to_string());
+    let episode = std::fs::read(system_audio.join("sample.ogg")).unwrap();
+    let process = |yaml: String| {
+        let form = multipart::Form::new()
+            .text("config", yaml)
+            .part("media", multipart::Part::bytes(episode.clone()).file_name("sample.ogg"));
+        async move {
+            let res = timeout(
+                Duration::from_secs(60),
+                reqwest::Client::new()
+                    .post(format!("http://{addr}/api/v1/process"))
+                    .multipart(form)
+                    .send(),
+            )
+            .await
+            .expect("request timed out")
+            .unwrap();
+            assert_eq!(res.status(), reqwest::StatusCode::OK);
+            assert_eq!(res.headers()["content-type"], "audio/ogg");
+            decoded_samples(&res.bytes().await.unwrap())
+        }
+    };
+
+    // The episode as the server decodes and re-encodes it, without any editing.
+    let episode_samples = process(
+        "mode: oneshot\nsteps:\n  - kind: streamkit::http_input\n  \
+         - kind: containers::ogg::demuxer\n  - kind: audio::opus::decoder\n  \
+         - kind: audio::opus::encoder\n  - kind: containers::ogg::muxer\n    params:\n      \
+         channels: 1\n  - kind: streamkit::http_output\n"
+            .to_string(),
+    )
+    .await;
+    let samples = process(yaml).await;
+
+    // 8 s intro + the episode - 2 s of crossfade + 10 s outro
+    let expected = episode_samples + 16 * 48_000;
+    assert!(
+        samples.abs_diff(expected) < 48_000 / 10,
+        "decoded {samples} samples, expected about {expected}"
+    );
+
+    server_handle.abort();
+}
@@ -197,3 +197,58 @@ async fn test_missing_media_field() {
     // Should return 400 Bad Request
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_oneshot_output_compression_is_negotiated() {
+    use std::io::Read;
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping end-to-end tests: local TCP bind not permitted");
+        return;
+    };
+
+    // Keep reqwest from transparently decoding the response
+    let client = reqwest::Client::builder().no_gzip().no_zstd().build().unwrap();
+    let url = format!("http://{addr}/api/v1/process");
+    let process = |config: &'static str, accept_encoding: &'static str| {
+        let form = multipart::Form::new()
+            .text("config", config)
+            .part("media", multipart::Part::bytes(vec![7u8; 4096]).file_name("data.bin"));
+        client.post(&url).header("Accept-Encoding", accept_encoding).multipart(form).send()
+    };
+
+    // JSON output is compressed when the client accepts gzip
+    let json_pipeline = "mode: oneshot
+steps:
+  - kind: streamkit::http_input
+  - kind: core::json_serialize
+  - kind: streamkit::http_output
+    params:
+      content_type: application/json
+";
+    let response = process(json_pipeline, "gzip").await.expect("Failed to send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    let compressed = response.bytes().await.unwrap();
+    let mut json = String::new();
+    flate2::read::GzDecoder::new(compressed.as_ref()).read_to_string(&mut json).unwrap();
+    assert!(json.len() > compressed.len(), "JSON output should shrink when compressed");
+
+    // ...and sent as-is when it doesn't
+    let response = process(json_pipeline, "identity").await.expect("Failed to send request");
+    assert!(response.headers().get("content-encoding").is_none());
+
+    // Opaque binary output is never compressed
+    let passthrough_pipeline = "mode: oneshot
+steps:
+  - kind: streamkit::http_input
+  - kind: core::passthrough
+  - kind: streamkit::http_output
+";
+    let response =
+        process(passthrough_pipeline, "gzip, zstd").await.expect("Failed to send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("content-encoding").is_none());
+    assert_eq!(response.bytes().await.unwrap().as_ref(), [7u8; 4096]);
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use rcgen::{
+    BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    KeyUsagePurpose,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use streamkit_server::server::start_server;
+use streamkit_server::Config;
+use tokio::sync::oneshot;
+use tokio::time::{sleep, Duration};
+
+struct Pki {
+    ca_pem: String,
+    ca: rcgen::Certificate,
+    ca_key: KeyPair,
+}
+
+impl Pki {
+    fn new() -> Self {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, "streamkit test ca");
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let ca = params.self_signed(&ca_key).unwrap();
+        Self { ca_pem: ca.pem(), ca, ca_key }
+    }
+
+    /// Issues a certificate and returns `(cert_pem, pkcs8_key_pem)`.
+    fn issue(&self, common_name: &str, purpose: ExtendedKeyUsagePurpose) -> (String, String) {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+        params.distinguished_name.push(DnType::CommonName, common_name);
+        params.extended_key_usages = vec![purpose];
+        let cert = params.signed_by(&key, &self.ca, &self.ca_key).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+fn mtls_config(dir: &Path, pki: &Pki, port: u16) -> Config {
+    let (server_cert, server_key) = pki.issue("localhost", ExtendedKeyUsagePurpose::ServerAuth);
+    std::fs::write(dir.join("server.pem"), server_cert).unwrap();
+    std::fs::write(dir.join("server.key"), server_key).unwrap();
+    std::fs::write(dir.join("ca.pem"), &pki.ca_pem).unwrap();
+
+    let mut config = Config::default();
+    config.server.address = format!("127.0.0.1:{port}");
+    config.server.tls = true;
+    config.server.cert_path = dir.join("server.pem").to_string_lossy().into_owned();
+    config.server.key_path = dir.join("server.key").to_string_lossy().into_owned();
+    config.server.mtls.enabled = true;
+    config.server.mtls.client_ca_path = dir.join("ca.pem").to_string_lossy().into_owned();
+    config.server.mtls.roles = HashMap::from([("ops-cli".to_string(), "user".to_string())]);
+    config
+}
+
+fn https_client(pki: &Pki, identity: Option<(String, String)>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(pki.ca_pem.as_bytes()).unwrap());
+    if let Some((cert, key)) = identity {
+        builder = builder
+            .identity(reqwest::Identity::from_pkcs8_pem(cert.as_bytes(), key.as_bytes()).unwrap());
+    }
+    builder.build().unwrap()
+}
+
+async fn role_of(client: &reqwest::Client, port: u16) -> reqwest::Result<String> {
+    let url = format!("https://localhost:{port}/api/v1/permissions");
+    for _ in 0..100 {
+        match client.get(&url).send().await {
+            Ok(response) => {
+                let body: serde_json::Value = response.json().await?;
+                return Ok(body["role"].as_str().unwrap().to_string());
+            },
+            Err(e) if e.is_connect() && e.to_string().contains("onnection refused") => {
+                sleep(Duration::from_millis(20)).await;
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    panic!("server never started listening on port {port}");
+}
+
+#[tokio::test]
+async fn client_certificate_maps_to_role() {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let dir = tempfile::tempdir().unwrap();
+    let pki = Pki::new();
+    let port = free_port();
+    let config = mtls_config(dir.path(), &pki, port);
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        start_server(&config, None, async {
+            let _ = shutdown_rx.await;
+        })
+        .await
+        .map_err(|e| e.to_string())
+    });
+
+    // Mapped certificate: the role comes from `[server.mtls].roles`, even with a forged header
+    let mapped =
+        https_client(&pki, Some(pki.issue("ops-cli", ExtendedKeyUsagePurpose::ClientAuth)));
+    assert_eq!(role_of(&mapped, port).await.unwrap(), "user");
+    let forged = mapped
+        .get(format!("https://localhost:{port}/api/v1/permissions"))
+        .header("x-streamkit-client-cert-role", "admin")
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = forged.json().await.unwrap();
+    assert_eq!(body["role"], "user");
+
+    // Unmapped certificate: falls back to the default role
+    let unmapped =
+        https_client(&pki, Some(pki.issue("stranger", ExtendedKeyUsagePurpose::ClientAuth)));
+    assert_eq!(role_of(&unmapped, port).await.unwrap(), "admin");
+
+    // No certificate: the handshake is rejected
+    let anonymous = https_client(&pki, None);
+    assert!(role_of(&anonymous, port).await.is_err());
+
+    shutdown_tx.send(()).unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn mtls_requires_tls() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = mtls_config(dir.path(), &Pki::new(), free_port());
+    config.server.tls = false;
+
+    let err = start_server(&config, None, std::future::pending()).await.unwrap_err();
+    assert!(err.to_string().contains("server.tls is false"), "{err}");
+}
@@ -264,7 +264,11 @@ async fn test_native_plugin_in_pipeline() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("create-session".to_string()),
-        payload: RequestPayload::CreateSession { name: Some("Plugin Test".to_string()) },
+        payload: RequestPayload::CreateSession {
+            name: Some("Plugin Test".to_string()),
+            tags: None,
+            record: None,
+        },
     };
 
     write
@@ -288,6 +292,8 @@ async fn test_native_plugin_in_pipeline() {
             node_id: "gain_plugin".to_string(),
             kind: "plugin::native::gain".to_string(),
             params: Some(json!({"gain": 2.0})),
+            label: None,
+            notes: None,
         },
     };
 
@@ -577,7 +583,11 @@ async fn test_unload_plugin_after_pipeline_use() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("create".to_string()),
-        payload: RequestPayload::CreateSession { name: Some("Unload Test".to_string()) },
+        payload: RequestPayload::CreateSession {
+            name: Some("Unload Test".to_string()),
+            tags: None,
+            record: None,
+        },
     };
     write
         .send(WsMessage::Text(serde_json::to_string(&create_request).unwrap().into()))
@@ -599,6 +609,8 @@ async fn test_unload_plugin_after_pipeline_use() {
             node_id: "gain".to_string(),
             kind: "plugin::native::gain".to_string(),
             params: Some(json!({"gain": 1.5})),
+            label: None,
+            notes: None,
         },
     };
     write.send(WsMessage::Text(serde_json::to_string(&add_node).unwrap().into())).await.unwrap();
@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use streamkit_server::Config;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, Duration, Instant};
+
+async fn start_test_server(config: Config) -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let (app, _state) = streamkit_server::server::create_app(config);
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+
+    sleep(Duration::from_millis(50)).await;
+    Some((addr, server_handle))
+}
+
+fn sample_ogg() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../samples/audio/system/sample.ogg")
+        .canonicalize()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn recordings_are_listed_and_cut_by_time_range() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let out_dir_path = out_dir.path().canonicalize().unwrap();
+    let recording = out_dir_path.join("recording.ogg");
+    let sample = sample_ogg();
+
+    let mut config = Config::default();
+    config.security.allowed_file_paths = vec![sample.display().to_string()];
+    config.security.allowed_write_paths = vec![format!("{}/**", out_dir_path.display())];
+    let Some((addr, server_handle)) = start_test_server(config).await else {
+        return;
+    };
+
+    // A session that "records" the sample by copying it through a file writer. The input of a
+    // dynamic session never closes, so write unbuffered to get the whole file on disk.
+    let yaml = format!(
+        "mode: dynamic\nsteps:\n  - kind: core::file_reader\n    params:\n      path: {}\n  \
+         - kind: core::file_writer\n    params:\n      path: {}\n      chunk_size: 1\n",
+        sample.display(),
+        recording.display()
+    );
+    let client = reqwest::Client::new();
+    let res = client
+        .post(format!("http://{addr}/api/v1/sessions"))
+        .json(&serde_json::json!({ "name": "recorded", "yaml": yaml }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK, "{}", res.text().await.unwrap());
+
+    let sample_bytes = std::fs::read(&sample).unwrap();
+    let list_url = format!("http://{addr}/api/v1/sessions/recorded/recordings");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let listing = loop {
+        let listing: serde_json::Value =
+            client.get(&list_url).send().await.unwrap().json().await.unwrap();
+        if listing[0]["size_bytes"] == sample_bytes.len() {
+            break listing;
+        }
+        assert!(Instant::now() < deadline, "recording never completed: {listing}");
+        sleep(Duration::from_millis(50)).await;
+    };
+    assert_eq!(listing.as_array().unwrap().len(), 1);
+    assert_eq!(listing[0]["content_type"], "audio/ogg");
+    assert_eq!(listing[0]["supports_time_range"], true);
+    let node_id = listing[0]["node_id"].as_str().unwrap().to_string();
+    let recording_url = format!("http://{addr}/api/v1/sessions/recorded/recordings/{node_id}");
+
+    // Without a range the file is served untouched.
+    let res = client.get(&recording_url).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "audio/ogg");
+    assert!(res.headers()["content-disposition"].to_str().unwrap().contains(".ogg"));
+    assert_eq!(res.bytes().await.unwrap(), sample_bytes);
+
+    // The last second is cut server-side into a new, shorter Ogg/Opus stream.
+    let res = client.get(format!("{recording_url}?last=1")).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "audio/ogg");
+    let cut = res.bytes().await.unwrap();
+    assert!(cut.starts_with(b"OggS"));
+    assert!(cut.windows(8).any(|w| w == b"OpusHead"));
+    assert!(cut.len() < sample_bytes.len() / 2, "cut is {} bytes", cut.len());
+
+    let res = client.get(format!("{recording_url}?start=2&last=1")).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let res = client
+        .get(format!("http://{addr}/api/v1/sessions/recorded/recordings/missing"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+
+    server_handle.abort();
+}
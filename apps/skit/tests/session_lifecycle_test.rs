@@ -15,8 +15,12 @@ use serde_json::json;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use streamkit_api::{MessageType, Request, RequestPayload, Response, ResponsePayload};
+use streamkit_api::{
+    BatchOperation, ConnectionMode, MessageType, Request, RequestPayload, Response,
+    ResponsePayload, SessionFilter, SessionListQuery, SessionSortKey, SortOrder, UiMetadataTarget,
+};
 use streamkit_core::control::NodeControlMessage;
+use streamkit_server::config::{NamedPipelineConfig, NamedPipelineConflict};
 use streamkit_server::Config;
 use tokio::net::TcpListener;
 use tokio::time::{timeout, Duration, Instant};
@@ -61,6 +65,12 @@ async fn read_response(
 }
 
 async fn start_test_server() -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    start_test_server_with_config(Config::default()).await
+}
+
+async fn start_test_server_with_config(
+    config: Config,
+) -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
     // Find an available port by binding to port 0
     let listener = match TcpListener::bind("127.0.0.1:0").await {
         Ok(listener) => listener,
@@ -71,7 +81,7 @@ async fn start_test_server() -> Option<(SocketAddr, tokio::task::JoinHandle<()>)
 
     // Start server in background using the existing listener
     let server_handle = tokio::spawn(async move {
-        let (app, _state) = streamkit_server::server::create_app(Config::default());
+        let (app, _state) = streamkit_server::server::create_app(config);
         axum::serve(listener, app.into_make_service()).await.unwrap();
     });
 
@@ -100,7 +110,11 @@ async fn test_create_and_destroy_session() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("test-1".to_string()),
-        payload: RequestPayload::CreateSession { name: Some("Test Session".to_string()) },
+        payload: RequestPayload::CreateSession {
+            name: Some("Test Session".to_string()),
+            tags: None,
+            record: None,
+        },
     };
 
     let msg = serde_json::to_string(&create_request).unwrap();
@@ -125,7 +139,7 @@ async fn test_create_and_destroy_session() {
     let list_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("test-2".to_string()),
-        payload: RequestPayload::ListSessions,
+        payload: RequestPayload::ListSessions { query: None },
     };
 
     let msg = serde_json::to_string(&list_request).unwrap();
@@ -134,7 +148,7 @@ async fn test_create_and_destroy_session() {
     let response = read_response(&mut read, "test-2").await;
 
     match response.payload {
-        ResponsePayload::SessionsListed { sessions } => {
+        ResponsePayload::SessionsListed { sessions, .. } => {
             assert_eq!(sessions.len(), 1);
             assert_eq!(sessions[0].id, session_id);
             assert_eq!(sessions[0].name, Some("Test Session".to_string()));
@@ -169,7 +183,7 @@ async fn test_create_and_destroy_session() {
     let list_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("test-4".to_string()),
-        payload: RequestPayload::ListSessions,
+        payload: RequestPayload::ListSessions { query: None },
     };
 
     let msg = serde_json::to_string(&list_request).unwrap();
@@ -178,7 +192,7 @@ async fn test_create_and_destroy_session() {
     let response = read_response(&mut read, "test-4").await;
 
     match response.payload {
-        ResponsePayload::SessionsListed { sessions } => {
+        ResponsePayload::SessionsListed { sessions, .. } => {
             assert_eq!(sessions.len(), 0);
         },
         _ => panic!("Expected SessionsListed response"),
@@ -207,7 +221,11 @@ async fn test_multiple_sessions() {
         let create_request = Request {
             message_type: MessageType::Request,
             correlation_id: Some(format!("create-{}", i)),
-            payload: RequestPayload::CreateSession { name: Some(format!("Session {}", i)) },
+            payload: RequestPayload::CreateSession {
+                name: Some(format!("Session {}", i)),
+                tags: None,
+                record: None,
+            },
         };
 
         write
@@ -229,7 +247,7 @@ async fn test_multiple_sessions() {
     let list_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("list".to_string()),
-        payload: RequestPayload::ListSessions,
+        payload: RequestPayload::ListSessions { query: None },
     };
 
     write
@@ -240,7 +258,7 @@ async fn test_multiple_sessions() {
     let response = read_response(&mut read, "list").await;
 
     match response.payload {
-        ResponsePayload::SessionsListed { sessions } => {
+        ResponsePayload::SessionsListed { sessions, .. } => {
             assert_eq!(sessions.len(), 3);
         },
         _ => panic!("Expected SessionsListed"),
@@ -284,7 +302,7 @@ async fn test_add_and_remove_nodes() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("create".to_string()),
-        payload: RequestPayload::CreateSession { name: None },
+        payload: RequestPayload::CreateSession { name: None, tags: None, record: None },
     };
 
     write
@@ -307,6 +325,8 @@ async fn test_add_and_remove_nodes() {
             node_id: "gain1".to_string(),
             kind: "gain".to_string(),
             params: Some(json!({"gain": 2.0})),
+            label: None,
+            notes: None,
         },
     };
 
@@ -394,6 +414,504 @@ async fn test_add_and_remove_nodes() {
     println!("✅ Pipeline is empty after node removal");
 }
 
+/// Helper to read messages until a `pipelinesnapshot` event with the given revision arrives
+async fn read_pipeline_snapshot(
+    read: &mut futures_util::stream::SplitStream<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    >,
+    session_id: &str,
+    revision: u64,
+) -> serde_json::Value {
+    loop {
+        let message = timeout(Duration::from_secs(5), read.next())
+            .await
+            .expect("Timeout waiting for pipeline snapshot")
+            .expect("No message received")
+            .expect("Failed to read message");
+
+        let value: serde_json::Value =
+            serde_json::from_str(message.to_text().expect("Expected text message"))
+                .expect("Failed to parse message");
+        let payload = &value["payload"];
+        if value["type"] == "event"
+            && payload["event"] == "pipelinesnapshot"
+            && payload["session_id"] == session_id
+            && payload["revision"] == revision
+        {
+            return payload.clone();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_pipeline_snapshot_events() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let create_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("create".to_string()),
+        payload: RequestPayload::CreateSession { name: None, tags: None, record: None },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&create_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    let response = read_response(&mut read, "create").await;
+    let session_id = match response.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    // Batches emit no per-operation structure events, only a snapshot
+    let batch_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("batch".to_string()),
+        payload: RequestPayload::ApplyBatch {
+            session_id: session_id.clone(),
+            operations: vec![
+                BatchOperation::AddNode {
+                    node_id: "gain1".to_string(),
+                    kind: "audio::gain".to_string(),
+                    params: Some(json!({"gain": 1.0})),
+                    label: None,
+                    notes: None,
+                },
+                BatchOperation::AddNode {
+                    node_id: "gain2".to_string(),
+                    kind: "audio::gain".to_string(),
+                    params: None,
+                    label: None,
+                    notes: None,
+                },
+                BatchOperation::Connect {
+                    from_node: "gain1".to_string(),
+                    from_pin: "out".to_string(),
+                    to_node: "gain2".to_string(),
+                    to_pin: "in".to_string(),
+                    mode: streamkit_api::ConnectionMode::Reliable,
+                },
+            ],
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&batch_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    let snapshot = read_pipeline_snapshot(&mut read, &session_id, 1).await;
+    assert_eq!(snapshot["node_count"], 2);
+    assert_eq!(snapshot["connection_count"], 1);
+    let batch_hash = snapshot["hash"].as_str().unwrap().to_string();
+
+    println!("✅ Snapshot received after batch (hash {})", batch_hash);
+
+    let remove_node_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("remove-node".to_string()),
+        payload: RequestPayload::RemoveNode {
+            session_id: session_id.clone(),
+            node_id: "gain2".to_string(),
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&remove_node_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    let snapshot = read_pipeline_snapshot(&mut read, &session_id, 2).await;
+    assert_eq!(snapshot["node_count"], 1);
+    assert_eq!(snapshot["connection_count"], 0);
+    assert_ne!(snapshot["hash"].as_str().unwrap(), batch_hash);
+
+    println!("✅ Snapshot revision and hash advance on removal");
+}
+
+async fn send_create_session<S>(write: &mut S, correlation_id: &str, name: &str)
+where
+    S: SinkExt<WsMessage> + Unpin,
+    S::Error: std::fmt::Debug,
+{
+    let request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(correlation_id.to_string()),
+        payload: RequestPayload::CreateSession {
+            name: Some(name.to_string()),
+            tags: None,
+            record: None,
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&request).unwrap().into()))
+        .await
+        .expect("Failed to send create session request");
+}
+
+#[tokio::test]
+async fn test_named_pipeline_singleton_sessions() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mut config = Config::default();
+    config.named_pipelines.insert(
+        "camera".to_string(),
+        NamedPipelineConfig { on_conflict: NamedPipelineConflict::Attach },
+    );
+    config.named_pipelines.insert("mic".to_string(), NamedPipelineConfig::default());
+
+    let Some((addr, _server_handle)) = start_test_server_with_config(config).await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "camera-1", "camera").await;
+    let first = match read_response(&mut read, "camera-1").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        other => panic!("Expected SessionCreated, got: {:?}", other),
+    };
+
+    send_create_session(&mut write, "camera-2", "camera").await;
+    match read_response(&mut read, "camera-2").await.payload {
+        ResponsePayload::SessionCreated { session_id, name, .. } => {
+            assert_eq!(session_id, first, "second create should attach to the live session");
+            assert_eq!(name.as_deref(), Some("camera"));
+        },
+        other => panic!("Expected SessionCreated, got: {:?}", other),
+    }
+
+    println!("✅ Attach policy returned the existing session");
+
+    send_create_session(&mut write, "mic-1", "mic").await;
+    assert!(matches!(
+        read_response(&mut read, "mic-1").await.payload,
+        ResponsePayload::SessionCreated { .. }
+    ));
+
+    send_create_session(&mut write, "mic-2", "mic").await;
+    match read_response(&mut read, "mic-2").await.payload {
+        ResponsePayload::Error { message } => assert!(message.contains("already exists")),
+        other => panic!("Expected Error, got: {:?}", other),
+    }
+
+    println!("✅ Reject policy refused a second live session");
+}
+
+async fn list_sessions_with_query(
+    write: &mut futures_util::stream::SplitSink<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        WsMessage,
+    >,
+    read: &mut futures_util::stream::SplitStream<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    >,
+    correlation_id: &str,
+    query: SessionListQuery,
+) -> (Vec<String>, Option<String>) {
+    let request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(correlation_id.to_string()),
+        payload: RequestPayload::ListSessions { query: Some(query) },
+    };
+    write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+
+    match read_response(read, correlation_id).await.payload {
+        ResponsePayload::SessionsListed { sessions, next_cursor } => {
+            (sessions.into_iter().filter_map(|s| s.name).collect(), next_cursor)
+        },
+        other => panic!("Expected SessionsListed, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_list_sessions_filter_search_and_paginate() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let sessions: [(&str, &[&str]); 3] =
+        [("alpha", &["studio", "cam"]), ("beta", &["studio"]), ("gamma", &[])];
+    for (name, tags) in sessions {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(name.to_string()),
+            payload: RequestPayload::CreateSession {
+                name: Some(name.to_string()),
+                tags: Some(tags.iter().map(ToString::to_string).collect()),
+                record: None,
+            },
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+        assert!(matches!(
+            read_response(&mut read, name).await.payload,
+            ResponsePayload::SessionCreated { .. }
+        ));
+        // Distinct creation timestamps keep the created_at ordering deterministic
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let query = SessionListQuery { tags: vec!["studio".to_string()], ..Default::default() };
+    let (mut names, _) = list_sessions_with_query(&mut write, &mut read, "tags", query).await;
+    names.sort();
+    assert_eq!(names, vec!["alpha", "beta"]);
+
+    let query = SessionListQuery { search: Some("GAM".to_string()), ..Default::default() };
+    let (names, _) = list_sessions_with_query(&mut write, &mut read, "search", query).await;
+    assert_eq!(names, vec!["gamma"]);
+
+    println!("✅ Tag filter and search narrowed the listing");
+
+    let mut cursor = None;
+    let mut pages = Vec::new();
+    for page in 0..4 {
+        let query = SessionListQuery {
+            sort: SessionSortKey::CreatedAt,
+            order: SortOrder::Asc,
+            limit: Some(1),
+            cursor: cursor.take(),
+            ..Default::default()
+        };
+        let (names, next) =
+            list_sessions_with_query(&mut write, &mut read, &format!("page-{page}"), query).await;
+        pages.extend(names);
+        if next.is_none() {
+            break;
+        }
+        cursor = next;
+    }
+    assert_eq!(pages, vec!["alpha", "beta", "gamma"]);
+
+    println!("✅ Cursor pagination walked every session once, in order");
+}
+
+async fn round_trip(
+    write: &mut futures_util::stream::SplitSink<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        WsMessage,
+    >,
+    read: &mut futures_util::stream::SplitStream<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    >,
+    correlation_id: &str,
+    payload: RequestPayload,
+) -> ResponsePayload {
+    let request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(correlation_id.to_string()),
+        payload,
+    };
+    write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    read_response(read, correlation_id).await.payload
+}
+
+#[tokio::test]
+async fn test_bulk_tune_and_destroy() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let sessions: [(&str, &[&str]); 3] =
+        [("load-test-1", &["load"]), ("load-test-2", &["load"]), ("keeper", &[])];
+    let mut ids = Vec::new();
+    for (name, tags) in sessions {
+        let create = RequestPayload::CreateSession {
+            name: Some(name.to_string()),
+            tags: Some(tags.iter().map(ToString::to_string).collect()),
+            record: None,
+        };
+        let session_id = match round_trip(&mut write, &mut read, name, create).await {
+            ResponsePayload::SessionCreated { session_id, .. } => session_id,
+            other => panic!("Expected SessionCreated, got {other:?}"),
+        };
+        let add = RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: "gain".to_string(),
+            kind: "audio::gain".to_string(),
+            params: Some(json!({"gain": 2.0})),
+            label: None,
+            notes: None,
+        };
+        let response = round_trip(&mut write, &mut read, "add", add).await;
+        assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+        ids.push(session_id);
+    }
+    let mut load_ids = ids[..2].to_vec();
+    load_ids.sort();
+
+    let tune = |params: serde_json::Value, filter: SessionFilter, dry_run| {
+        RequestPayload::TuneNodesByKind { kind: "audio::gain".to_string(), params, filter, dry_run }
+    };
+    let by_tag = SessionFilter { tags: vec!["load".to_string()], ..Default::default() };
+    let by_name =
+        SessionFilter { name_pattern: Some("load-test-*".to_string()), ..Default::default() };
+
+    // A dry run reports the matching nodes; an invalid value is rejected before any is tuned
+    match round_trip(&mut write, &mut read, "dry", tune(json!({"gain": 0.5}), by_tag, true)).await {
+        ResponsePayload::NodesTuned { nodes, dry_run } => {
+            assert!(dry_run);
+            let tuned: Vec<&str> = nodes.iter().map(|n| n.session_id.as_str()).collect();
+            assert_eq!(tuned, load_ids);
+        },
+        other => panic!("Expected NodesTuned, got {other:?}"),
+    }
+    let invalid = tune(json!({"gain": "loud"}), by_name.clone(), false);
+    let response = round_trip(&mut write, &mut read, "invalid", invalid).await;
+    assert!(matches!(response, ResponsePayload::Error { .. }), "{response:?}");
+
+    let apply = tune(json!({"gain": 0.5}), by_name.clone(), false);
+    match round_trip(&mut write, &mut read, "tune", apply).await {
+        ResponsePayload::NodesTuned { nodes, dry_run } => {
+            assert!(!dry_run);
+            assert_eq!(nodes.len(), 2);
+        },
+        other => panic!("Expected NodesTuned, got {other:?}"),
+    }
+    for (session_id, gain) in [(&ids[0], 0.5), (&ids[2], 2.0)] {
+        let get = RequestPayload::GetPipeline { session_id: session_id.clone() };
+        match round_trip(&mut write, &mut read, "get", get).await {
+            ResponsePayload::Pipeline { pipeline } => {
+                assert_eq!(pipeline.nodes["gain"].params, Some(json!({"gain": gain})));
+            },
+            other => panic!("Expected Pipeline, got {other:?}"),
+        }
+    }
+
+    // Bulk destroy refuses an empty filter and leaves everything in place on a dry run
+    let destroy = |filter, dry_run| RequestPayload::DestroySessions { filter, dry_run };
+    let response =
+        round_trip(&mut write, &mut read, "empty", destroy(SessionFilter::default(), false)).await;
+    assert!(matches!(response, ResponsePayload::Error { .. }), "{response:?}");
+
+    for dry_run in [true, false] {
+        match round_trip(&mut write, &mut read, "destroy", destroy(by_name.clone(), dry_run)).await
+        {
+            ResponsePayload::SessionsDestroyed { mut session_ids, .. } => {
+                session_ids.sort();
+                assert_eq!(session_ids, load_ids);
+            },
+            other => panic!("Expected SessionsDestroyed, got {other:?}"),
+        }
+    }
+    let (names, _) =
+        list_sessions_with_query(&mut write, &mut read, "list", SessionListQuery::default()).await;
+    assert_eq!(names, vec!["keeper"]);
+
+    println!("✅ Bulk tune and destroy honour filters and dry runs");
+}
+
+#[tokio::test]
+async fn test_read_only_mode() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+    let (observer, _) = connect_async(&ws_url).await.unwrap();
+    let (_observer_write, mut observer_read) = observer.split();
+
+    let create = |name: &str| RequestPayload::CreateSession {
+        name: Some(name.to_string()),
+        tags: None,
+        record: None,
+    };
+    let session_id = match round_trip(&mut write, &mut read, "create", create("before")).await {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        other => panic!("Expected SessionCreated, got {other:?}"),
+    };
+
+    let enable =
+        RequestPayload::SetReadOnly { enabled: true, message: Some("Upgrading".to_string()) };
+    let response = round_trip(&mut write, &mut read, "enable", enable).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    let event = read_event(&mut observer_read, "readonlychanged").await;
+    assert_eq!(event["enabled"], true);
+    assert_eq!(event["message"], "Upgrading");
+
+    // Mutations are refused with the banner; queries and dry runs still work
+    match round_trip(&mut write, &mut read, "refused", create("during")).await {
+        ResponsePayload::ReadOnly { banner, .. } => {
+            assert_eq!(banner.as_deref(), Some("Upgrading"));
+        },
+        other => panic!("Expected ReadOnly, got {other:?}"),
+    }
+    let list = RequestPayload::ListSessions { query: None };
+    let response = round_trip(&mut write, &mut read, "list", list).await;
+    assert!(matches!(response, ResponsePayload::SessionsListed { .. }), "{response:?}");
+    let filter = SessionFilter { name_pattern: Some("before".to_string()), ..Default::default() };
+    let dry_run = RequestPayload::DestroySessions { filter, dry_run: true };
+    match round_trip(&mut write, &mut read, "dry", dry_run).await {
+        ResponsePayload::SessionsDestroyed { session_ids, .. } => {
+            assert_eq!(session_ids, vec![session_id.clone()]);
+        },
+        other => panic!("Expected SessionsDestroyed, got {other:?}"),
+    }
+
+    let client = reqwest::Client::new();
+    let config: serde_json::Value = client
+        .get(format!("http://{addr}/api/v1/config"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(config["read_only"], true);
+    assert_eq!(config["banner"], "Upgrading");
+    let response =
+        client.delete(format!("http://{addr}/api/v1/sessions/{session_id}")).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "read_only");
+
+    let disable = RequestPayload::SetReadOnly { enabled: false, message: None };
+    let response = round_trip(&mut write, &mut read, "disable", disable).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    let response = round_trip(&mut write, &mut read, "after", create("after")).await;
+    assert!(matches!(response, ResponsePayload::SessionCreated { .. }), "{response:?}");
+
+    println!("✅ Read-only mode refuses mutations and keeps queries working");
+}
+
 #[tokio::test]
 async fn test_session_not_found() {
     let Some((addr, _server_handle)) = start_test_server().await else {
@@ -442,7 +960,11 @@ async fn test_session_destroy_shuts_down_pipeline() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("create".to_string()),
-        payload: RequestPayload::CreateSession { name: Some("Pipeline Test".to_string()) },
+        payload: RequestPayload::CreateSession {
+            name: Some("Pipeline Test".to_string()),
+            tags: None,
+            record: None,
+        },
     };
 
     write
@@ -471,6 +993,8 @@ async fn test_session_destroy_shuts_down_pipeline() {
                 "sample_rate": 48000,
                 "channels": 2
             })),
+            label: None,
+            notes: None,
         },
     };
 
@@ -497,6 +1021,8 @@ async fn test_session_destroy_shuts_down_pipeline() {
             node_id: "gain".to_string(),
             kind: "gain".to_string(),
             params: Some(json!({"gain": 1.0})),
+            label: None,
+            notes: None,
         },
     };
 
@@ -628,7 +1154,7 @@ async fn test_session_destroy_shuts_down_pipeline() {
     let list_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("list".to_string()),
-        payload: RequestPayload::ListSessions,
+        payload: RequestPayload::ListSessions { query: None },
     };
 
     write
@@ -638,7 +1164,7 @@ async fn test_session_destroy_shuts_down_pipeline() {
 
     let response = read_response(&mut read, "list").await;
     match response.payload {
-        ResponsePayload::SessionsListed { sessions } => {
+        ResponsePayload::SessionsListed { sessions, .. } => {
             assert_eq!(sessions.len(), 0, "Session should be completely removed");
         },
         _ => panic!("Expected SessionsListed response"),
@@ -675,7 +1201,11 @@ async fn test_concurrent_operations_no_lock_contention() {
     let create_request = Request {
         message_type: MessageType::Request,
         correlation_id: Some("setup-create".to_string()),
-        payload: RequestPayload::CreateSession { name: Some("Contention Test".to_string()) },
+        payload: RequestPayload::CreateSession {
+            name: Some("Contention Test".to_string()),
+            tags: None,
+            record: None,
+        },
     };
 
     write
@@ -698,6 +1228,8 @@ async fn test_concurrent_operations_no_lock_contention() {
             node_id: "gain".to_string(),
             kind: "gain".to_string(),
             params: Some(json!({"gain": 1.0})),
+            label: None,
+            notes: None,
         },
     };
 
@@ -759,7 +1291,7 @@ async fn test_concurrent_operations_no_lock_contention() {
                     _ => Request {
                         message_type: MessageType::Request,
                         correlation_id: Some(correlation_id.clone()),
-                        payload: RequestPayload::ListSessions,
+                        payload: RequestPayload::ListSessions { query: None },
                     },
                 };
 
@@ -831,3 +1363,1170 @@ async fn test_concurrent_operations_no_lock_contention() {
 
     println!("✅ Test passed: No lock contention detected");
 }
+
+async fn read_event(
+    read: &mut futures_util::stream::SplitStream<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    >,
+    event: &str,
+) -> serde_json::Value {
+    loop {
+        let message = timeout(Duration::from_secs(5), read.next())
+            .await
+            .unwrap_or_else(|_| panic!("Timeout waiting for {event} event"))
+            .expect("No message received")
+            .expect("Failed to read message");
+
+        let value: serde_json::Value =
+            serde_json::from_str(message.to_text().expect("Expected text message"))
+                .expect("Failed to parse message");
+        if value["type"] == "event" && value["payload"]["event"] == event {
+            return value["payload"].clone();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_node_kind_alias_resolution() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+            return;
+        },
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let (app, state) = streamkit_server::server::create_app(Config::default());
+    state.engine.registry.write().unwrap().register_alias("audio::volume", "audio::gain");
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "alias-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add_node_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("add-node".to_string()),
+        payload: RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: "gain".to_string(),
+            kind: "audio::volume".to_string(),
+            params: None,
+            label: None,
+            notes: None,
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&add_node_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    // The node is created under its current kind, followed by a deprecation warning
+    let added = read_event(&mut read, "nodeadded").await;
+    assert_eq!(added["kind"], "audio::gain");
+
+    let deprecated = read_event(&mut read, "nodekinddeprecated").await;
+    assert_eq!(deprecated["session_id"], session_id);
+    assert_eq!(deprecated["node_id"], "gain");
+    assert_eq!(deprecated["kind"], "audio::volume");
+    assert_eq!(deprecated["replacement"], "audio::gain");
+
+    println!("✅ Alias resolved to current kind with deprecation event");
+}
+
+/// Lists nodes over the control websocket with a stubbed `readable_files` provider and
+/// returns the resolved schema of `core::file_reader`'s `path` param.
+async fn list_file_reader_path_schema(config: Config) -> Option<serde_json::Value> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let (app, state) = streamkit_server::server::create_app(config);
+    // Replace the filesystem-backed provider so the options don't depend on the test's cwd.
+    state.engine.registry.write().unwrap().register_enum_provider("readable_files", || {
+        vec![
+            streamkit_core::EnumOption::new("samples/a.ogg"),
+            streamkit_core::EnumOption::labeled("samples/b.ogg", "B"),
+        ]
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("list-nodes".to_string()),
+        payload: RequestPayload::ListNodes,
+    };
+    write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+
+    let ResponsePayload::NodesListed { nodes } =
+        read_response(&mut read, "list-nodes").await.payload
+    else {
+        panic!("Expected NodesListed");
+    };
+    let reader = nodes.iter().find(|def| def.kind == "core::file_reader").unwrap();
+    assert_eq!(reader.enum_providers.get("path").map(String::as_str), Some("readable_files"));
+    Some(reader.param_schema["properties"]["path"].clone())
+}
+
+#[tokio::test]
+async fn test_list_nodes_resolves_enum_providers() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some(path) = list_file_reader_path_schema(Config::default()).await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+    assert_eq!(
+        path["enum_options"],
+        serde_json::json!([{ "value": "samples/a.ogg" }, { "value": "samples/b.ogg", "label": "B" }])
+    );
+    assert!(path.get("enum").is_none(), "options must not constrain validation");
+
+    // `path` is sensitive: roles that get it masked must not receive the file list either.
+    let mut config = Config::default();
+    config.permissions.default_role = "user".to_string();
+    let path = list_file_reader_path_schema(config).await.unwrap();
+    assert!(path.get("enum_options").is_none());
+
+    println!("✅ Enum provider options resolved into listed node schemas");
+}
+
+#[tokio::test]
+async fn test_node_params_validated_against_schema() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "params-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let requests = [
+        (
+            "add-bad",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "gain".to_string(),
+                kind: "audio::gain".to_string(),
+                params: Some(json!({"gain": "loud"})),
+                label: None,
+                notes: None,
+            },
+        ),
+        (
+            "add-good",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "gain".to_string(),
+                kind: "audio::gain".to_string(),
+                params: Some(json!({"gain": 0.5})),
+                label: None,
+                notes: None,
+            },
+        ),
+        (
+            "tune-bad",
+            RequestPayload::TuneNode {
+                session_id: session_id.clone(),
+                node_id: "gain".to_string(),
+                message: NodeControlMessage::UpdateParams(json!({"gain": 10.0})),
+            },
+        ),
+        (
+            "validate",
+            RequestPayload::ValidateBatch {
+                session_id: session_id.clone(),
+                operations: vec![BatchOperation::AddNode {
+                    node_id: "gain2".to_string(),
+                    kind: "audio::gain".to_string(),
+                    params: Some(json!({"gain": -1.0})),
+                    label: None,
+                    notes: None,
+                }],
+            },
+        ),
+    ];
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    }
+
+    match read_response(&mut read, "add-bad").await.payload {
+        ResponsePayload::Error { message } => {
+            assert!(message.contains("node 'gain'"), "unexpected message: {message}");
+            assert!(message.contains("/gain"), "unexpected message: {message}");
+        },
+        other => panic!("Expected Error for invalid params, got {other:?}"),
+    }
+    assert!(matches!(read_response(&mut read, "add-good").await.payload, ResponsePayload::Success));
+    match read_response(&mut read, "tune-bad").await.payload {
+        ResponsePayload::Error { message } => {
+            assert!(message.contains("/gain"), "unexpected message: {message}");
+        },
+        other => panic!("Expected Error for out-of-range tune, got {other:?}"),
+    }
+    match read_response(&mut read, "validate").await.payload {
+        ResponsePayload::ValidationResult { errors } => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].node_id.as_deref(), Some("gain2"));
+            assert_eq!(errors[0].path.as_deref(), Some("/gain"));
+        },
+        other => panic!("Expected ValidationResult, got {other:?}"),
+    }
+
+    println!("✅ Invalid params rejected with JSON Pointer paths");
+}
+
+#[tokio::test]
+async fn test_tune_rejects_construct_only_params() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "tunable-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let tune = |params: serde_json::Value| RequestPayload::TuneNode {
+        session_id: session_id.clone(),
+        node_id: "pacer".to_string(),
+        message: NodeControlMessage::UpdateParams(params),
+    };
+    let requests = [
+        (
+            "add",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "pacer".to_string(),
+                kind: "core::pacer".to_string(),
+                params: Some(json!({"speed": 1.0, "buffer_size": 16})),
+                label: None,
+                notes: None,
+            },
+        ),
+        ("tune-speed", tune(json!({"speed": 2.0, "buffer_size": 16}))),
+        ("tune-buffer", tune(json!({"buffer_size": 4}))),
+    ];
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    }
+
+    assert!(matches!(read_response(&mut read, "add").await.payload, ResponsePayload::Success));
+    assert!(matches!(
+        read_response(&mut read, "tune-speed").await.payload,
+        ResponsePayload::Success
+    ));
+    match read_response(&mut read, "tune-buffer").await.payload {
+        ResponsePayload::Error { message } => {
+            assert!(message.contains("/buffer_size"), "unexpected message: {message}");
+            assert!(message.contains("recreate the node"), "unexpected message: {message}");
+        },
+        other => panic!("Expected Error for construct-only tune, got {other:?}"),
+    }
+
+    println!("✅ Construct-only params rejected at runtime");
+}
+
+#[tokio::test]
+async fn test_query_node_runtime_info() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "query-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let query = |kind: &str| RequestPayload::QueryNode {
+        session_id: session_id.clone(),
+        node_id: "pacer".to_string(),
+        kind: kind.to_string(),
+        args: None,
+    };
+    let requests = [
+        (
+            "add",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "pacer".to_string(),
+                kind: "core::pacer".to_string(),
+                params: Some(json!({"buffer_size": 8})),
+                label: None,
+                notes: None,
+            },
+        ),
+        ("query-fill", query("buffer_fill")),
+        ("query-unknown", query("model_info")),
+    ];
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    }
+
+    assert!(matches!(read_response(&mut read, "add").await.payload, ResponsePayload::Success));
+    match read_response(&mut read, "query-fill").await.payload {
+        ResponsePayload::QueryResult { node_id, kind, result } => {
+            assert_eq!(node_id, "pacer");
+            assert_eq!(kind, "buffer_fill");
+            assert_eq!(result, json!({"queued": 0, "capacity": 8}));
+        },
+        other => panic!("Expected QueryResult, got {other:?}"),
+    }
+    match read_response(&mut read, "query-unknown").await.payload {
+        ResponsePayload::Error { message } => {
+            assert!(message.contains("Unsupported query 'model_info'"), "unexpected: {message}");
+        },
+        other => panic!("Expected Error for unknown query, got {other:?}"),
+    }
+
+    println!("✅ Node answered runtime query");
+}
+
+#[tokio::test]
+async fn test_add_and_remove_dynamic_pins() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "pins-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add_pin = |pin_name: Option<&str>| RequestPayload::AddPin {
+        session_id: session_id.clone(),
+        node_id: "mixer".to_string(),
+        pin_name: pin_name.map(str::to_string),
+    };
+    let remove_pin = |pin_name: &str| RequestPayload::RemovePin {
+        session_id: session_id.clone(),
+        node_id: "mixer".to_string(),
+        pin_name: pin_name.to_string(),
+    };
+    let requests = [
+        (
+            "add",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "mixer".to_string(),
+                kind: "audio::mixer".to_string(),
+                params: None,
+                label: None,
+                notes: None,
+            },
+        ),
+        ("pin-auto", add_pin(None)),
+        ("pin-named", add_pin(Some("in_guest"))),
+        ("remove", remove_pin("in_guest")),
+        ("remove-again", remove_pin("in_guest")),
+    ];
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    }
+
+    assert!(matches!(read_response(&mut read, "add").await.payload, ResponsePayload::Success));
+    match read_response(&mut read, "pin-auto").await.payload {
+        ResponsePayload::PinAdded { node_id, pin_name } => {
+            assert_eq!(node_id, "mixer");
+            assert!(pin_name.starts_with("in_"), "unexpected pin name: {pin_name}");
+        },
+        other => panic!("Expected PinAdded, got {other:?}"),
+    }
+    match read_response(&mut read, "pin-named").await.payload {
+        ResponsePayload::PinAdded { pin_name, .. } => assert_eq!(pin_name, "in_guest"),
+        other => panic!("Expected PinAdded, got {other:?}"),
+    }
+    assert!(matches!(read_response(&mut read, "remove").await.payload, ResponsePayload::Success));
+    match read_response(&mut read, "remove-again").await.payload {
+        ResponsePayload::Error { message } => {
+            assert!(message.contains("not found"), "unexpected message: {message}");
+        },
+        other => panic!("Expected Error for removed pin, got {other:?}"),
+    }
+
+    println!("✅ Dynamic pins added and removed on request");
+}
+
+#[tokio::test]
+async fn test_node_label_and_notes() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "label-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add = RequestPayload::AddNode {
+        session_id: session_id.clone(),
+        node_id: "nllb_3".to_string(),
+        kind: "core::passthrough".to_string(),
+        params: None,
+        label: Some("Spanish caption branch".to_string()),
+        notes: Some("Feeds the ES subtitle track".to_string()),
+    };
+    let get = RequestPayload::GetPipeline { session_id: session_id.clone() };
+    let request = |correlation_id: &str, payload| Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(correlation_id.to_string()),
+        payload,
+    };
+
+    let add = request("add", add);
+    write.send(WsMessage::Text(serde_json::to_string(&add).unwrap().into())).await.unwrap();
+    let added = read_event(&mut read, "nodeadded").await;
+    assert_eq!(added["label"], "Spanish caption branch");
+    assert_eq!(added["notes"], "Feeds the ES subtitle track");
+
+    let get = request("get", get);
+    write.send(WsMessage::Text(serde_json::to_string(&get).unwrap().into())).await.unwrap();
+    match read_response(&mut read, "get").await.payload {
+        ResponsePayload::Pipeline { pipeline } => {
+            let node = &pipeline.nodes["nllb_3"];
+            assert_eq!(node.label.as_deref(), Some("Spanish caption branch"));
+            assert_eq!(node.notes.as_deref(), Some("Feeds the ES subtitle track"));
+        },
+        other => panic!("Expected Pipeline, got {other:?}"),
+    }
+
+    println!("✅ Node label and notes returned in events and pipeline");
+}
+
+#[tokio::test]
+async fn test_ui_metadata_round_trip() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "ui-metadata-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add = |node_id: &str| RequestPayload::AddNode {
+        session_id: session_id.clone(),
+        node_id: node_id.to_string(),
+        kind: "core::passthrough".to_string(),
+        params: None,
+        label: None,
+        notes: None,
+    };
+    let connection = UiMetadataTarget::Connection {
+        from_node: "a".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "b".to_string(),
+        to_pin: "in".to_string(),
+    };
+    let set = |target: UiMetadataTarget, ui_metadata| RequestPayload::SetUiMetadata {
+        session_id: session_id.clone(),
+        target,
+        ui_metadata,
+    };
+    let requests = [
+        ("add-a", add("a")),
+        ("add-b", add("b")),
+        (
+            "connect",
+            RequestPayload::Connect {
+                session_id: session_id.clone(),
+                from_node: "a".to_string(),
+                from_pin: "out".to_string(),
+                to_node: "b".to_string(),
+                to_pin: "in".to_string(),
+                mode: ConnectionMode::default(),
+            },
+        ),
+        (
+            "set-node",
+            set(UiMetadataTarget::Node { node_id: "a".to_string() }, Some(json!({"x": 1, "y": 2}))),
+        ),
+        ("set-connection", set(connection.clone(), Some(json!({"label": "main"})))),
+        ("clear-connection", set(connection, None)),
+        ("set-missing", set(UiMetadataTarget::Node { node_id: "nope".to_string() }, None)),
+        (
+            "set-oversized",
+            set(
+                UiMetadataTarget::Node { node_id: "b".to_string() },
+                Some(json!("x".repeat(70_000))),
+            ),
+        ),
+        ("get", RequestPayload::GetPipeline { session_id: session_id.clone() }),
+    ];
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+    }
+
+    for correlation_id in
+        ["add-a", "add-b", "connect", "set-node", "set-connection", "clear-connection"]
+    {
+        let payload = read_response(&mut read, correlation_id).await.payload;
+        assert!(matches!(payload, ResponsePayload::Success), "{correlation_id}: {payload:?}");
+    }
+    for correlation_id in ["set-missing", "set-oversized"] {
+        let payload = read_response(&mut read, correlation_id).await.payload;
+        assert!(matches!(payload, ResponsePayload::Error { .. }), "{correlation_id}: {payload:?}");
+    }
+    match read_response(&mut read, "get").await.payload {
+        ResponsePayload::Pipeline { pipeline } => {
+            assert_eq!(pipeline.nodes["a"].ui_metadata, Some(json!({"x": 1, "y": 2})));
+            assert_eq!(pipeline.nodes["b"].ui_metadata, None);
+            assert_eq!(pipeline.connections[0].ui_metadata, None);
+        },
+        other => panic!("Expected Pipeline, got {other:?}"),
+    }
+
+    println!("✅ ui_metadata stored and returned with the pipeline");
+}
+
+#[tokio::test]
+async fn test_session_cost_report() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "cost-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add_node_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("add".to_string()),
+        payload: RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: "gain".to_string(),
+            kind: "audio::gain".to_string(),
+            params: None,
+            label: None,
+            notes: None,
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&add_node_request).unwrap().into()))
+        .await
+        .unwrap();
+    assert!(matches!(read_response(&mut read, "add").await.payload, ResponsePayload::Success));
+    // Nodes are metered once the engine has started them
+    read_event(&mut read, "nodestatechanged").await;
+
+    let cost_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("cost".to_string()),
+        payload: RequestPayload::GetSessionCost { session_id: session_id.clone() },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&cost_request).unwrap().into()))
+        .await
+        .unwrap();
+    match read_response(&mut read, "cost").await.payload {
+        ResponsePayload::SessionCost { session_id: id, report } => {
+            assert_eq!(id, session_id);
+            assert_eq!(report.nodes.len(), 1);
+            assert_eq!(report.nodes[0].node_id, "gain");
+            assert_eq!(report.by_kind[0].kind, "audio::gain");
+        },
+        other => panic!("Expected SessionCost, got {other:?}"),
+    }
+
+    // Destroying the session publishes the final report
+    let destroy_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("destroy".to_string()),
+        payload: RequestPayload::DestroySession { session_id: session_id.clone() },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&destroy_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    let report = read_event(&mut read, "sessioncostreport").await;
+    assert_eq!(report["session_id"], session_id);
+    assert_eq!(report["report"]["by_kind"][0]["instances"], 1);
+    read_event(&mut read, "sessiondestroyed").await;
+
+    println!("✅ Session cost reported on demand and at destroy");
+}
+
+#[tokio::test]
+async fn test_sensitive_params_redacted_without_view_secrets() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // The built-in `user` role lacks `view_secrets`
+    let mut config = Config::default();
+    config.permissions.default_role = "user".to_string();
+    config.security.allowed_file_paths = vec!["**".to_string()];
+
+    let Some((addr, _server_handle)) = start_test_server_with_config(config).await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "redaction-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    let add_node_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("add".to_string()),
+        payload: RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: "reader".to_string(),
+            kind: "core::file_reader".to_string(),
+            params: Some(json!({"path": "Cargo.toml", "chunk_size": 1024})),
+            label: None,
+            notes: None,
+        },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&add_node_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    let added = read_event(&mut read, "nodeadded").await;
+    assert_eq!(added["params"], json!({"path": "[redacted]", "chunk_size": 1024}));
+
+    let get_pipeline_request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some("get-pipeline".to_string()),
+        payload: RequestPayload::GetPipeline { session_id: session_id.clone() },
+    };
+    write
+        .send(WsMessage::Text(serde_json::to_string(&get_pipeline_request).unwrap().into()))
+        .await
+        .unwrap();
+
+    match read_response(&mut read, "get-pipeline").await.payload {
+        ResponsePayload::Pipeline { pipeline } => {
+            let params = pipeline.nodes["reader"].params.clone().unwrap();
+            assert_eq!(params, json!({"path": "[redacted]", "chunk_size": 1024}));
+        },
+        other => panic!("Expected Pipeline response, got {other:?}"),
+    }
+
+    println!("✅ Sensitive params masked for roles without view_secrets");
+}
+
+#[cfg(feature = "script")]
+#[tokio::test]
+async fn test_event_hook_issues_control_requests() {
+    use streamkit_server::config::EventHookConfig;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let script_path = temp_dir.path().join("hook.js");
+    std::fs::write(
+        &script_path,
+        r#"
+        let seen = 0;
+        function onEvent(event) {
+            seen += 1;
+            return {
+                action: "addnode",
+                session_id: event.session_id,
+                node_id: "auto_" + seen,
+                kind: "core::passthrough",
+            };
+        }
+        "#,
+    )
+    .unwrap();
+
+    let mut config = Config::default();
+    config.script.hooks.push(EventHookConfig {
+        name: "auto-node".to_string(),
+        script_path: script_path.to_string_lossy().into_owned(),
+        events: vec!["sessioncreated".to_string()],
+        telemetry_types: Vec::new(),
+        role: "admin".to_string(),
+        timeout_ms: None,
+        memory_limit_mb: None,
+    });
+
+    let Some((addr, _server_handle)) = start_test_server_with_config(config).await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "hook-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    // The hook reacts to sessioncreated by adding a node; its own nodeadded event is filtered out
+    let added = read_event(&mut read, "nodeadded").await;
+    assert_eq!(added["session_id"], session_id);
+    assert_eq!(added["node_id"], "auto_1");
+    assert_eq!(added["kind"], "core::passthrough");
+
+    println!("✅ Event hook issued control request");
+}
+
+#[tokio::test]
+async fn test_black_box_recording() {
+    use std::io::BufRead;
+
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.black_box.directory = temp_dir.path().to_string_lossy().into_owned();
+    config.security.allowed_file_paths = vec!["**".to_string()];
+
+    let Some((addr, _server_handle)) = start_test_server_with_config(config).await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let requests = [
+        ("create", RequestPayload::CreateSession { name: None, tags: None, record: Some(true) }),
+        ("unrecorded", RequestPayload::CreateSession { name: None, tags: None, record: None }),
+    ];
+    let mut session_ids = Vec::new();
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+        match read_response(&mut read, correlation_id).await.payload {
+            ResponsePayload::SessionCreated { session_id, .. } => session_ids.push(session_id),
+            other => panic!("Expected SessionCreated, got {other:?}"),
+        }
+    }
+    let (session_id, unrecorded_id) = (session_ids[0].clone(), session_ids[1].clone());
+
+    let requests = [
+        (
+            "add",
+            RequestPayload::AddNode {
+                session_id: session_id.clone(),
+                node_id: "reader".to_string(),
+                kind: "core::file_reader".to_string(),
+                params: Some(json!({"path": "Cargo.toml", "chunk_size": 1024})),
+                label: None,
+                notes: None,
+            },
+        ),
+        ("destroy", RequestPayload::DestroySession { session_id: session_id.clone() }),
+    ];
+    let client = reqwest::Client::new();
+    let url = |id: &str| format!("http://{addr}/api/v1/sessions/{id}/blackbox");
+
+    let response = client.get(url(&session_id)).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::CONFLICT, "still recording");
+    let response = client.get(url(&unrecorded_id)).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    for (correlation_id, payload) in requests {
+        let request = Request {
+            message_type: MessageType::Request,
+            correlation_id: Some(correlation_id.to_string()),
+            payload,
+        };
+        write.send(WsMessage::Text(serde_json::to_string(&request).unwrap().into())).await.unwrap();
+        let response = read_response(&mut read, correlation_id).await;
+        assert!(!matches!(response.payload, ResponsePayload::Error { .. }), "{response:?}");
+        if correlation_id == "add" {
+            // Nodes are built off the actor loop; let this one start before the session goes.
+            read_event(&mut read, "nodestatechanged").await;
+        }
+    }
+
+    // The recording is finalized in the background once the session is gone.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let response = loop {
+        let response = client.get(url(&session_id)).send().await.unwrap();
+        if response.status() == reqwest::StatusCode::OK
+            && response.headers()["x-recording-complete"] == "true"
+        {
+            break response;
+        }
+        assert!(Instant::now() < deadline, "recording was not finalized in time");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    let bytes = response.bytes().await.unwrap();
+
+    // Interrupted downloads can resume from where they stopped.
+    let response = client.get(url(&session_id)).header("Range", "bytes=10-").send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        response.headers()["content-range"],
+        format!("bytes 10-{}/{}", bytes.len() - 1, bytes.len()).as_str()
+    );
+    assert_eq!(response.headers()["content-type"], "application/gzip");
+    assert_eq!(response.bytes().await.unwrap(), bytes[10..]);
+
+    let entries: Vec<serde_json::Value> =
+        std::io::BufReader::new(flate2::read::GzDecoder::new(&bytes[..]))
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+
+    assert_eq!(entries[0]["record"], "start");
+    assert_eq!(entries[0]["session_id"], session_id);
+    assert_eq!(entries.last().unwrap()["record"], "end");
+
+    let requests: Vec<&str> = entries
+        .iter()
+        .filter(|e| e["record"] == "request")
+        .map(|e| e["request"]["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(requests, ["createsession", "addnode", "destroysession"]);
+    let add = entries.iter().find(|e| e["request"]["action"] == "addnode").unwrap();
+    assert_eq!(add["request"]["params"]["path"], "[redacted]", "secrets stay off disk");
+    assert_eq!(add["response"]["action"], "success");
+
+    let events: Vec<&str> = entries
+        .iter()
+        .filter(|e| e["record"] == "event")
+        .map(|e| e["event"]["event"].as_str().unwrap())
+        .collect();
+    for expected in ["sessioncreated", "nodeadded", "nodestatechanged", "sessiondestroyed"] {
+        assert!(events.contains(&expected), "missing {expected} in {events:?}");
+    }
+    assert!(entries.iter().all(|e| e["event"]["session_id"] != unrecorded_id.as_str()));
+
+    println!("✅ Black-box recording captured requests and events");
+}
+
+#[tokio::test]
+async fn test_tap_connection() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "tap-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    for (correlation_id, node_id, kind, params) in [
+        ("add-tone", "tone", "audio::tone", Some(json!({"frequency_hz": 440}))),
+        ("add-gain", "gain", "audio::gain", None),
+    ] {
+        let payload = RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            params,
+            label: None,
+            notes: None,
+        };
+        let response = round_trip(&mut write, &mut read, correlation_id, payload).await;
+        assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    }
+    let tap = |to_pin: &str| RequestPayload::TapConnection {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: to_pin.to_string(),
+        max_packets: Some(3),
+        duration_secs: None,
+        sample_every: Some(2),
+        preview_bytes: Some(8),
+    };
+
+    // Only existing connections can be tapped
+    let response = round_trip(&mut write, &mut read, "tap-missing", tap("in")).await;
+    assert!(matches!(response, ResponsePayload::Error { .. }), "{response:?}");
+
+    let connect = RequestPayload::Connect {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        mode: ConnectionMode::Reliable,
+    };
+    let response = round_trip(&mut write, &mut read, "connect", connect).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+
+    let tap_id = match round_trip(&mut write, &mut read, "tap", tap("in")).await {
+        ResponsePayload::ConnectionTapped { tap_id, max_packets, duration_secs, .. } => {
+            assert_eq!((max_packets, duration_secs), (3, 30));
+            tap_id
+        },
+        other => panic!("Expected ConnectionTapped, got {other:?}"),
+    };
+
+    for expected_index in [1, 3, 5] {
+        let event = read_event(&mut read, "connectiontappacket").await;
+        assert_eq!(event["tap_id"], tap_id.as_str());
+        assert_eq!(event["index"], expected_index);
+        assert_eq!(event["packet"]["packet_type"], "audio");
+        assert_eq!(event["packet"]["preview"].as_str().unwrap().len(), 16);
+        assert_eq!(event["packet"]["truncated"], true);
+    }
+    let ended = read_event(&mut read, "connectiontapended").await;
+    assert_eq!(ended["tap_id"], tap_id.as_str());
+    assert_eq!(ended["reason"], "packet_limit");
+    assert_eq!(ended["packets_sent"], 3);
+
+    println!("✅ Connection tap sampled packets and expired");
+}
+
+#[tokio::test]
+async fn test_connection_breakpoints() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let Some((addr, _server_handle)) = start_test_server().await else {
+        eprintln!("Skipping session lifecycle tests: local TCP bind not permitted");
+        return;
+    };
+
+    let ws_url = format!("ws://{}/api/v1/control", addr);
+    let (ws_stream, _) = connect_async(&ws_url).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    send_create_session(&mut write, "create", "debug-test").await;
+    let session_id = match read_response(&mut read, "create").await.payload {
+        ResponsePayload::SessionCreated { session_id, .. } => session_id,
+        _ => panic!("Expected SessionCreated"),
+    };
+
+    for (correlation_id, node_id, kind, params) in [
+        ("add-tone", "tone", "audio::tone", Some(json!({"frequency_hz": 440}))),
+        ("add-gain", "gain", "audio::gain", None),
+    ] {
+        let payload = RequestPayload::AddNode {
+            session_id: session_id.clone(),
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            params,
+            label: None,
+            notes: None,
+        };
+        let response = round_trip(&mut write, &mut read, correlation_id, payload).await;
+        assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    }
+    let connect = RequestPayload::Connect {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        mode: ConnectionMode::Reliable,
+    };
+    let response = round_trip(&mut write, &mut read, "connect", connect).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+
+    let breakpoint = |paused: bool| RequestPayload::SetBreakpoint {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        paused,
+    };
+    let inspect = || RequestPayload::InspectConnection {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        preview_bytes: None,
+    };
+    let debug_mode =
+        |enabled: bool| RequestPayload::SetDebugMode { session_id: session_id.clone(), enabled };
+
+    // Connections can only be paused in debug mode
+    let response = round_trip(&mut write, &mut read, "pause-early", breakpoint(true)).await;
+    assert!(matches!(response, ResponsePayload::Error { .. }), "{response:?}");
+
+    let response = round_trip(&mut write, &mut read, "debug-on", debug_mode(true)).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    let response = round_trip(&mut write, &mut read, "pause", breakpoint(true)).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let held = match round_trip(&mut write, &mut read, "inspect", inspect()).await {
+        ResponsePayload::ConnectionQueue { paused, packets } => {
+            assert!(paused);
+            assert!(!packets.is_empty(), "a paused connection holds packets");
+            assert!(packets.iter().all(|p| p.packet.packet_type == "audio"));
+            packets.iter().map(|p| p.packet_id).collect::<Vec<_>>()
+        },
+        other => panic!("Expected ConnectionQueue, got {other:?}"),
+    };
+    assert!(held.windows(2).all(|w| w[1] == w[0] + 1), "packets are held in order: {held:?}");
+
+    // Only text and custom packets can be edited
+    let edit = RequestPayload::EditQueuedPacket {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        packet_id: held[0],
+        content: json!("hello"),
+    };
+    let response = round_trip(&mut write, &mut read, "edit", edit).await;
+    assert!(matches!(response, ResponsePayload::Error { .. }), "{response:?}");
+
+    let step = RequestPayload::StepConnection {
+        session_id: session_id.clone(),
+        from_node: "tone".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "gain".to_string(),
+        to_pin: "in".to_string(),
+        count: Some(1),
+    };
+    let response = round_trip(&mut write, &mut read, "step", step).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    match round_trip(&mut write, &mut read, "inspect-stepped", inspect()).await {
+        ResponsePayload::ConnectionQueue { packets, .. } => {
+            assert_eq!(packets[0].packet_id, held[0] + 1, "stepping released the oldest packet");
+        },
+        other => panic!("Expected ConnectionQueue, got {other:?}"),
+    }
+
+    // Leaving debug mode lets everything through again
+    let response = round_trip(&mut write, &mut read, "debug-off", debug_mode(false)).await;
+    assert!(matches!(response, ResponsePayload::Success), "{response:?}");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    match round_trip(&mut write, &mut read, "inspect-resumed", inspect()).await {
+        ResponsePayload::ConnectionQueue { paused, packets } => {
+            assert!(!paused);
+            assert!(packets.len() <= 1, "resumed connection drained: {}", packets.len());
+        },
+        other => panic!("Expected ConnectionQueue, got {other:?}"),
+    }
+
+    println!("✅ Connection breakpoints paused, stepped and resumed");
+}
@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use reqwest::multipart;
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use streamkit_server::Config;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, timeout, Duration};
+
+async fn start_test_server(config: Config) -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let (app, _state) = streamkit_server::server::create_app(config);
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+
+    sleep(Duration::from_millis(50)).await;
+    Some((addr, server_handle))
+}
+
+/// Decodes an Ogg/Opus stream and returns the number of mono samples at 48 kHz.
+fn decoded_samples(ogg: &[u8]) -> usize {
+    let mut reader = ogg::PacketReader::new(Cursor::new(ogg));
+    let mut decoder = opus::Decoder::new(48000, opus::Channels::Mono).unwrap();
+    let mut output = vec![0i16; 5760];
+    let mut total = 0;
+    while let Some(packet) = reader.read_packet().unwrap() {
+        // Skip the headers and the empty end-of-stream packet, which would decode as a gap
+        if packet.data.is_empty()
+            || packet.data.starts_with(b"OpusHead")
+            || packet.data.starts_with(b"OpusTags")
+        {
+            continue;
+        }
+        total += decoder.decode(&packet.data, &mut output, false).unwrap();
+    }
+    total
+}
+
+#[tokio::test]
+async fn oneshot_pipeline_renders_tone_without_media() {
+    let Some((addr, server_handle)) = start_test_server(Config::default()).await else {
+        return;
+    };
+
+    let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..").canonicalize().unwrap();
+    let yaml =
+        std::fs::read_to_string(repo_root.join("samples/pipelines/oneshot/test_tone.yml")).unwrap();
+    let form = multipart::Form::new().text("config", yaml);
+    let res = timeout(
+        Duration::from_secs(30),
+        reqwest::Client::new().post(format!("http://{addr}/api/v1/process")).multipart(form).send(),
+    )
+    .await
+    .expect("request timed out")
+    .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    // Unpaced, so two seconds of audio render well within the timeout
+    let samples = decoded_samples(&res.bytes().await.unwrap());
+    assert!(samples.abs_diff(2 * 48_000) < 48_000 / 50, "decoded {samples} samples");
+
+    server_handle.abort();
+}
@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![cfg(unix)]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use streamkit_server::server::start_server;
+use streamkit_server::Config;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::sync::oneshot;
+use tokio::time::{sleep, Duration};
+
+fn unix_socket_config(path: &Path) -> Config {
+    let mut config = Config::default();
+    config.server.address = "127.0.0.1:0".to_string();
+    config.server.unix_socket = Some(path.to_string_lossy().into_owned());
+    config.server.unix_socket_mode = 0o600;
+    config
+}
+
+async fn connect_with_retry(path: &Path) -> UnixStream {
+    for _ in 0..200 {
+        if let Ok(stream) = UnixStream::connect(path).await {
+            return stream;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    panic!("server never listened on {}", path.display());
+}
+
+#[tokio::test]
+async fn serves_api_on_unix_socket_with_configured_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("skit.sock");
+    let config = unix_socket_config(&socket_path);
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        start_server(&config, None, async {
+            let _ = shutdown_rx.await;
+        })
+        .await
+        .map_err(|e| e.to_string())
+    });
+
+    let mut stream = connect_with_retry(&socket_path).await;
+    stream
+        .write_all(b"GET /healthz HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "unexpected response: {response}");
+
+    let mode = std::fs::metadata(&socket_path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+
+    shutdown_tx.send(()).unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn refuses_to_replace_a_regular_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket_path = dir.path().join("not-a-socket");
+    std::fs::write(&socket_path, b"keep me").unwrap();
+    let config = unix_socket_config(&socket_path);
+
+    let result = start_server(&config, None, std::future::pending()).await;
+    assert!(result.unwrap_err().to_string().contains("not a socket"));
+    assert_eq!(std::fs::read(&socket_path).unwrap(), b"keep me");
+}
@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::net::SocketAddr;
+use streamkit_server::Config;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{sleep, Duration};
+
+async fn start_test_server(config: Config) -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let (app, _state) = streamkit_server::server::create_app(config);
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .unwrap();
+    });
+
+    sleep(Duration::from_millis(50)).await;
+    Some((addr, server_handle))
+}
+
+/// Starts a oneshot upload that announces more body than it sends, then stalls.
+async fn start_stalled_upload(addr: SocketAddr) -> TcpStream {
+    let body = "--b\r\nContent-Disposition: form-data; name=\"config\"\r\n\r\nname: stalled\n";
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!(
+                "POST /api/v1/process HTTP/1.1\r\nHost: localhost\r\n\
+                 Content-Type: multipart/form-data; boundary=b\r\n\
+                 Content-Length: 4096\r\n\r\n{body}"
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    stream
+}
+
+#[tokio::test]
+async fn oversized_upload_is_rejected_with_413() {
+    let mut config = Config::default();
+    config.server.max_body_size = 1024;
+    let Some((addr, server_handle)) = start_test_server(config).await else {
+        return;
+    };
+
+    let res = reqwest::Client::new()
+        .post(format!("http://{addr}/api/v1/process"))
+        .header("Content-Type", "multipart/form-data; boundary=b")
+        .body(vec![b'x'; 4096])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "payload_too_large");
+    assert_eq!(body["limit"], 1024);
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn concurrent_and_stalled_uploads_are_limited() {
+    let mut config = Config::default();
+    config.server.upload_limits.max_concurrent_per_ip = 1;
+    config.server.upload_limits.idle_timeout_ms = 500;
+    let Some((addr, server_handle)) = start_test_server(config).await else {
+        return;
+    };
+
+    let mut stalled = start_stalled_upload(addr).await;
+    sleep(Duration::from_millis(100)).await;
+
+    // The stalled upload holds this address's only slot.
+    let res = reqwest::Client::new()
+        .post(format!("http://{addr}/api/v1/assets/audio"))
+        .header("Content-Type", "multipart/form-data; boundary=b")
+        .body("--b--\r\n")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "too_many_uploads");
+
+    // Once the idle timeout passes the stalled upload is answered and its slot released.
+    let mut response = vec![0u8; 1024];
+    let n = tokio::time::timeout(Duration::from_secs(5), stalled.read(&mut response))
+        .await
+        .expect("stalled upload was never aborted")
+        .unwrap();
+    let response = String::from_utf8_lossy(&response[..n]);
+    assert!(response.starts_with("HTTP/1.1 408"), "unexpected response: {response}");
+    assert!(response.contains("upload_timeout"));
+    drop(stalled);
+
+    sleep(Duration::from_millis(50)).await;
+    let res = reqwest::Client::new()
+        .post(format!("http://{addr}/api/v1/assets/audio"))
+        .header("Content-Type", "multipart/form-data; boundary=b")
+        .body("--b--\r\n")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    server_handle.abort();
+}
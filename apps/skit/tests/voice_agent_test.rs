@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Checks the reference voice agent pipelines against the nodes they use.
+//!
+//! The full loop needs the Whisper, VAD and Kokoro plugins plus an LLM endpoint, which CI
+//! doesn't have; this keeps the samples compiling and wired to pins that exist.
+
+#![cfg(all(feature = "nodes", feature = "script"))]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use streamkit_api::{ConnectionMode, Pipeline};
+use streamkit_nodes::core::llm::LlmNode;
+use streamkit_nodes::core::script::ScriptSecret;
+use streamkit_nodes::core::turn_manager::TurnManagerNode;
+
+fn compile_sample(path: &str) -> Pipeline {
+    let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..").canonicalize().unwrap();
+    let yaml = std::fs::read_to_string(repo_root.join(path)).unwrap();
+    let user_pipeline = serde_saphyr::from_str(&yaml).unwrap();
+    streamkit_api::yaml::compile(user_pipeline).unwrap()
+}
+
+fn check_voice_agent(path: &str) {
+    let pipeline = compile_sample(path);
+
+    // The core nodes accept the sample's parameters
+    let turn_manager = (TurnManagerNode::factory())(pipeline.nodes["turn_manager"].params.as_ref())
+        .unwrap_or_else(|e| panic!("{path}: turn_manager params rejected: {e}"));
+    let secret = ScriptSecret { value: "sk-test".to_string(), allowed_fetch_urls: vec![] };
+    let secrets = Arc::new(HashMap::from([("openai_key".to_string(), secret)]));
+    (LlmNode::factory(secrets))(pipeline.nodes["llm"].params.as_ref())
+        .unwrap_or_else(|e| panic!("{path}: llm params rejected: {e}"));
+
+    // Every connection touching the turn manager uses one of its pins
+    let inputs: Vec<String> = turn_manager.input_pins().into_iter().map(|p| p.name).collect();
+    let outputs: Vec<String> = turn_manager.output_pins().into_iter().map(|p| p.name).collect();
+    for conn in &pipeline.connections {
+        if conn.to_node == "turn_manager" {
+            assert!(inputs.contains(&conn.to_pin), "{path}: no input pin {}", conn.to_pin);
+        }
+        if conn.from_node == "turn_manager" {
+            assert!(outputs.contains(&conn.from_pin), "{path}: no output pin {}", conn.from_pin);
+        }
+    }
+
+    let agent_speech = pipeline
+        .connections
+        .iter()
+        .find(|c| c.to_node == "turn_manager" && c.to_pin == "in_2")
+        .expect("agent speech should feed back into the turn manager");
+    assert_eq!(agent_speech.mode, ConnectionMode::Feedback);
+
+    let gated = pipeline
+        .connections
+        .iter()
+        .find(|c| c.from_node == "turn_manager" && c.from_pin == "audio")
+        .expect("gated agent speech should leave through the audio pin");
+    assert_ne!(gated.to_node, "llm");
+}
+
+#[test]
+fn reference_voice_agent_sample_is_wired() {
+    check_voice_agent("samples/pipelines/dynamic/voice-agent.yaml");
+}
+
+#[test]
+fn voice_agent_loadtest_pipeline_is_wired() {
+    check_voice_agent("samples/loadtest/pipelines/voice_agent.yml");
+}
@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use streamkit_api::{MessageType, Request, RequestPayload, Response, ResponsePayload, WireFormat};
+use streamkit_server::Config;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+async fn start_test_server() -> Option<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => listener,
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+        Err(e) => panic!("Failed to bind test server listener: {e}"),
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let (app, _state) = streamkit_server::server::create_app(Config::default());
+        axum::serve(listener, app.into_make_service()).await.unwrap();
+    });
+
+    sleep(Duration::from_millis(50)).await;
+    Some((addr, server_handle))
+}
+
+fn create_session_request(correlation_id: &str) -> Request {
+    Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(correlation_id.to_string()),
+        payload: RequestPayload::CreateSession { name: None, tags: None, record: None },
+    }
+}
+
+#[tokio::test]
+async fn websocket_negotiates_msgpack() {
+    let Some((addr, server_handle)) = start_test_server().await else {
+        return;
+    };
+
+    let mut req = format!("ws://{addr}/api/v1/control").into_client_request().unwrap();
+    req.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        format!("unknown.v9, {}", WireFormat::Msgpack.subprotocol()).parse().unwrap(),
+    );
+    let (mut ws, response) = tokio_tungstenite::connect_async(req).await.unwrap();
+    assert_eq!(
+        response.headers().get("Sec-WebSocket-Protocol").unwrap(),
+        WireFormat::Msgpack.subprotocol()
+    );
+
+    // Requests may be MessagePack or JSON; replies are always MessagePack.
+    let binary = rmp_serde::to_vec_named(&create_session_request("mp-1")).unwrap();
+    ws.send(WsMessage::Binary(binary.into())).await.unwrap();
+    let text = serde_json::to_string(&create_session_request("mp-2")).unwrap();
+    ws.send(WsMessage::Text(text.into())).await.unwrap();
+
+    let mut seen = Vec::new();
+    while seen.len() < 2 {
+        let msg = timeout(Duration::from_secs(5), ws.next()).await.unwrap().unwrap().unwrap();
+        let WsMessage::Binary(data) = msg else {
+            panic!("Expected a binary frame, got: {msg:?}");
+        };
+        let value: serde_json::Value = rmp_serde::from_slice(&data).unwrap();
+        if value["type"] == "event" {
+            continue;
+        }
+        let response: Response = rmp_serde::from_slice(&data).unwrap();
+        assert!(
+            matches!(response.payload, ResponsePayload::SessionCreated { .. }),
+            "unexpected response: {:?}",
+            response.payload
+        );
+        seen.push(response.correlation_id.unwrap());
+    }
+    seen.sort();
+    assert_eq!(seen, ["mp-1", "mp-2"]);
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn websocket_defaults_to_json() {
+    let Some((addr, server_handle)) = start_test_server().await else {
+        return;
+    };
+
+    let (mut ws, response) =
+        tokio_tungstenite::connect_async(format!("ws://{addr}/api/v1/control")).await.unwrap();
+    assert!(response.headers().get("Sec-WebSocket-Protocol").is_none());
+
+    // Binary frames are not requests on a JSON connection; only the text request is answered.
+    let binary = rmp_serde::to_vec_named(&create_session_request("ignored")).unwrap();
+    ws.send(WsMessage::Binary(binary.into())).await.unwrap();
+    let text = serde_json::to_string(&create_session_request("json-1")).unwrap();
+    ws.send(WsMessage::Text(text.into())).await.unwrap();
+
+    loop {
+        let msg = timeout(Duration::from_secs(5), ws.next()).await.unwrap().unwrap().unwrap();
+        let text = msg.to_text().expect("Expected a text frame");
+        let value: serde_json::Value = serde_json::from_str(text).unwrap();
+        if value["type"] == "response" {
+            assert_eq!(value["correlation_id"], "json-1");
+            break;
+        }
+    }
+
+    server_handle.abort();
+}
@@ -0,0 +1,691 @@
+// This file is auto-generated. Do not edit it manually.
+
+// Keep loose to allow schema usage in UI
+export type JsonValue = unknown;
+
+// streamkit-core
+export type SampleFormat = "F32" | "S16Le";
+
+export type AudioFormat = { sample_rate: number, channels: number, sample_format: SampleFormat, };
+
+export type PacketMetadata = { 
+/**
+ * Absolute timestamp in microseconds (presentation time)
+ */
+timestamp_us: bigint | null, 
+/**
+ * Duration of this packet/frame in microseconds
+ */
+duration_us: bigint | null, 
+/**
+ * Sequence number for ordering and detecting loss
+ */
+sequence: bigint | null, };
+
+export type TranscriptionSegment = { 
+/**
+ * The transcribed text for this segment
+ */
+text: string, 
+/**
+ * Start time in milliseconds
+ */
+start_time_ms: bigint, 
+/**
+ * End time in milliseconds
+ */
+end_time_ms: bigint, 
+/**
+ * Confidence score (0.0 - 1.0), if available
+ */
+confidence: number | null, };
+
+export type TranscriptionData = { 
+/**
+ * The full transcribed text (concatenation of all segments)
+ */
+text: string, 
+/**
+ * Individual segments with timing information
+ */
+segments: Array<TranscriptionSegment>, 
+/**
+ * Detected or specified language code (e.g., "en", "es", "fr")
+ */
+language: string | null, 
+/**
+ * Optional timing metadata for the entire transcription
+ */
+metadata: PacketMetadata | null, };
+
+export type PacketType = { "RawAudio": AudioFormat } | "OpusAudio" | "Text" | "Transcription" | { "Custom": { type_id: string, } } | "Binary" | "Any" | "Passthrough";
+
+export type PinCardinality = "One" | "Broadcast" | { "Dynamic": { prefix: string, } };
+
+export type InputPin = { name: string, accepts_types: Array<PacketType>, cardinality: PinCardinality, };
+
+export type OutputPin = { name: string, produces_type: PacketType, cardinality: PinCardinality, };
+
+export type NodeDefinition = { kind: string, 
+/**
+ * Human-readable description of what this node does.
+ * This is separate from the param_schema description which describes the config struct.
+ */
+description?: string | null, param_schema: JsonValue, inputs: Array<InputPin>, outputs: Array<OutputPin>, 
+/**
+ * Hierarchical categories for UI grouping (e.g., `["audio", "filters"]`)
+ */
+categories: Array<string>, 
+/**
+ * Whether this node is bidirectional (has both input and output for the same data flow)
+ */
+bidirectional: boolean, 
+/**
+ * Whether this node kind is deprecated and should not be used in new pipelines
+ */
+deprecated: boolean, 
+/**
+ * Former kind names that still resolve to this node
+ */
+aliases: Array<string>, 
+/**
+ * Parameters whose options come from a named enum provider, mapped to the provider name.
+ * Declared in the config struct with `#[schemars(extend("enum_provider" = "<name>"))]`.
+ */
+enum_providers: { [key in string]?: string }, };
+
+export type StopReason = "completed" | "input_closed" | "output_closed" | "shutdown" | "no_inputs" | "unknown";
+
+export type NodeState = "Initializing" | "Ready" | "Running" | { "Recovering": { reason: string, details: JsonValue, } } | { "Degraded": { reason: string, details: JsonValue, } } | { "Failed": { reason: string, } } | { "Stopped": { reason: StopReason, } };
+
+export type NodeStats = { 
+/**
+ * Total packets received on all input pins
+ */
+received: bigint, 
+/**
+ * Total packets successfully sent on all output pins
+ */
+sent: bigint, 
+/**
+ * Total packets discarded (e.g., due to backpressure, invalid data)
+ */
+discarded: bigint, 
+/**
+ * Total processing errors that didn't crash the node
+ */
+errored: bigint, 
+/**
+ * Duration in seconds since the node started processing (for rate calculation)
+ */
+duration_secs: number, };
+
+export type NodeCost = { node_id: string, kind: string, cpu_secs: number, gpu_secs: number, bytes_in: bigint, bytes_out: bigint, };
+
+export type KindCost = { kind: string, 
+/**
+ * Number of node instances of this kind over the session's lifetime
+ */
+instances: number, cpu_secs: number, gpu_secs: number, bytes_in: bigint, bytes_out: bigint, };
+
+export type CostReport = { 
+/**
+ * Wall-clock seconds since the session's engine started
+ */
+wall_secs: number, cpu_secs: number, gpu_secs: number, bytes_in: bigint, bytes_out: bigint, 
+/**
+ * Usage per node kind, sorted by kind
+ */
+by_kind: Array<KindCost>, 
+/**
+ * Usage per node instance, in the order nodes were started. Nodes removed from the
+ * pipeline are kept, so a node ID may appear more than once if it was re-added.
+ */
+nodes: Array<NodeCost>, };
+
+export type NodeControlMessage = { "UpdateParams": JsonValue } | "Start" | "Shutdown";
+
+export type FieldRule = { name: string, wildcard_value: JsonValue | null, };
+
+export type Compatibility = { "kind": "any" } | { "kind": "exact" } | { "kind": "structfieldwildcard", fields: Array<FieldRule>, };
+
+export type PacketTypeMeta = { 
+/**
+ * Variant identifier (e.g., "RawAudio", "OpusAudio", "Binary", "Any").
+ */
+id: string, 
+/**
+ * Human-friendly default label.
+ */
+label: string, 
+/**
+ * Hex color to use in UIs.
+ */
+color: string, 
+/**
+ * Optional display template for struct payloads. Placeholders are field names,
+ * optionally with "|*" to indicate wildcard-display (handled on the client).
+ * Example: "Raw Audio ({sample_rate|*}Hz, {channels|*}ch, {sample_format})"
+ */
+display_template: string | null, 
+/**
+ * Compatibility strategy for this type.
+ */
+compatibility: Compatibility, };
+
+
+// streamkit-api
+export type MessageType = "request" | "response" | "event";
+
+export type WireFormat = "json" | "msgpack";
+
+/** `Sec-WebSocket-Protocol` values that select each wire format */
+export const WIRE_FORMAT_SUBPROTOCOLS: Record<WireFormat, string> = { json: "streamkit.json", msgpack: "streamkit.msgpack" };
+
+export type RequestPayload = { "action": "createsession", 
+/**
+ * Optional session name for identification
+ */
+name: string | null, 
+/**
+ * Optional tags for filtering sessions in `ListSessions`
+ */
+tags?: Array<string>, 
+/**
+ * Keep a black-box recording of the session's control requests and events,
+ * retrievable after it ends. Defaults to false.
+ */
+record?: boolean, } | { "action": "destroysession", 
+/**
+ * The session ID to destroy
+ */
+session_id: string, } | { "action": "destroysessions", filter: SessionFilter, 
+/**
+ * Only report which sessions would be destroyed
+ */
+dry_run?: boolean, } | { "action": "listsessions", query?: SessionListQuery, } | { "action": "listnodes" } | { "action": "addnode", 
+/**
+ * The session ID to add the node to
+ */
+session_id: string, 
+/**
+ * Unique identifier for this node instance
+ */
+node_id: string, 
+/**
+ * Node type (e.g., "audio::gain", "plugin::native::whisper")
+ */
+kind: string, 
+/**
+ * Optional JSON configuration parameters for the node
+ */
+params: JsonValue, 
+/**
+ * Human-friendly display name (e.g. "Spanish caption branch")
+ */
+label?: string, 
+/**
+ * Free-form operator notes about this node instance
+ */
+notes?: string, } | { "action": "removenode", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to remove
+ */
+node_id: string, } | { "action": "connect", 
+/**
+ * The session ID containing the nodes
+ */
+session_id: string, 
+/**
+ * Source node ID
+ */
+from_node: string, 
+/**
+ * Source output pin name
+ */
+from_pin: string, 
+/**
+ * Destination node ID
+ */
+to_node: string, 
+/**
+ * Destination input pin name
+ */
+to_pin: string, 
+/**
+ * Connection mode (reliable, best-effort or feedback). Defaults to Reliable.
+ */
+mode: ConnectionMode, } | { "action": "disconnect", 
+/**
+ * The session ID containing the nodes
+ */
+session_id: string, 
+/**
+ * Source node ID
+ */
+from_node: string, 
+/**
+ * Source output pin name
+ */
+from_pin: string, 
+/**
+ * Destination node ID
+ */
+to_node: string, 
+/**
+ * Destination input pin name
+ */
+to_pin: string, } | { "action": "addpin", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to add the pin to
+ */
+node_id: string, 
+/**
+ * Requested pin name; the node picks the next free name when absent
+ */
+pin_name?: string, } | { "action": "removepin", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to remove the pin from
+ */
+node_id: string, 
+/**
+ * The input pin to remove
+ */
+pin_name: string, } | { "action": "tunenode", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to send the message to
+ */
+node_id: string, 
+/**
+ * The control message (UpdateParams, Start, or Shutdown)
+ */
+message: NodeControlMessage, } | { "action": "tunenodeasync", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to send the message to
+ */
+node_id: string, 
+/**
+ * The control message (typically UpdateParams)
+ */
+message: NodeControlMessage, } | { "action": "querynode", 
+/**
+ * The session ID containing the node
+ */
+session_id: string, 
+/**
+ * The node ID to query
+ */
+node_id: string, 
+/**
+ * Query name understood by the node (e.g. "buffer_fill")
+ */
+kind: string, 
+/**
+ * Optional query-specific arguments
+ */
+args?: JsonValue, } | { "action": "tunenodesbykind", 
+/**
+ * Node type to tune (e.g. "audio::opus::encoder")
+ */
+kind: string, 
+/**
+ * Params to apply, as with `UpdateParams`
+ */
+params: JsonValue, filter?: SessionFilter, 
+/**
+ * Only report which nodes would be tuned
+ */
+dry_run?: boolean, } | { "action": "setuimetadata", 
+/**
+ * The session ID containing the node or connection
+ */
+session_id: string, 
+/**
+ * The node or connection to annotate
+ */
+target: UiMetadataTarget, ui_metadata: JsonValue, } | { "action": "getpipeline", 
+/**
+ * The session ID to query
+ */
+session_id: string, } | { "action": "getsessioncost", 
+/**
+ * The session ID to query
+ */
+session_id: string, } | { "action": "tapconnection", 
+/**
+ * The session ID containing the connection
+ */
+session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, 
+/**
+ * Stop after sending this many packets (default 20, max 500)
+ */
+max_packets?: number, 
+/**
+ * Stop after this many seconds (default 30, max 300)
+ */
+duration_secs?: number, 
+/**
+ * Send one packet out of every `sample_every` (default 1, every packet)
+ */
+sample_every?: number, 
+/**
+ * Include up to this many leading payload bytes in each event (default 0, max 1024)
+ */
+preview_bytes?: number, } | { "action": "setdebugmode", session_id: string, enabled: boolean, } | { "action": "setbreakpoint", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, paused: boolean, } | { "action": "stepconnection", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, 
+/**
+ * Number of packets to deliver (default 1)
+ */
+count?: number, } | { "action": "inspectconnection", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, 
+/**
+ * Include up to this many leading payload bytes of each packet (default 0, max 1024)
+ */
+preview_bytes?: number, } | { "action": "editqueuedpacket", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, 
+/**
+ * `packet_id` from `InspectConnection`
+ */
+packet_id: bigint, content: JsonValue, } | { "action": "validatebatch", 
+/**
+ * The session ID to validate operations against
+ */
+session_id: string, 
+/**
+ * List of operations to validate
+ */
+operations: Array<BatchOperation>, } | { "action": "applybatch", 
+/**
+ * The session ID to apply operations to
+ */
+session_id: string, 
+/**
+ * List of operations to apply atomically
+ */
+operations: Array<BatchOperation>, } | { "action": "getpermissions" } | { "action": "setreadonly", enabled: boolean, 
+/**
+ * Banner shown to clients while read-only (e.g. "Upgrading, back at 14:00 UTC")
+ */
+message?: string, };
+
+export type ResponsePayload = { "action": "sessioncreated", session_id: string, name: string | null, 
+/**
+ * ISO 8601 formatted timestamp when the session was created
+ */
+created_at: string, } | { "action": "sessiondestroyed", session_id: string, } | { "action": "sessionsdestroyed", session_ids: Array<string>, dry_run: boolean, } | { "action": "sessionslisted", sessions: Array<SessionInfo>, 
+/**
+ * Cursor for the next page; absent when this is the last page
+ */
+next_cursor?: string, } | { "action": "nodeslisted", nodes: Array<NodeDefinition>, } | { "action": "pipeline", pipeline: Pipeline, } | { "action": "sessioncost", session_id: string, report: CostReport, } | { "action": "connectiontapped", tap_id: string, max_packets: number, duration_secs: number, sample_every: number, preview_bytes: number, } | { "action": "connectionqueue", paused: boolean, packets: Array<QueuedPacketSummary>, } | { "action": "validationresult", errors: Array<ValidationError>, } | { "action": "batchapplied", success: boolean, errors: Array<string>, } | { "action": "queryresult", node_id: string, kind: string, result: JsonValue, } | { "action": "pinadded", node_id: string, pin_name: string, } | { "action": "nodestuned", nodes: Array<SessionNode>, dry_run: boolean, } | { "action": "permissions", role: string, permissions: PermissionsInfo, } | { "action": "success" } | { "action": "error", message: string, } | { "action": "readonly", message: string, 
+/**
+ * The operator's banner, if one was set
+ */
+banner?: string, };
+
+export type EventPayload = { "event": "nodestatechanged", session_id: string, node_id: string, state: NodeState, 
+/**
+ * ISO 8601 formatted timestamp
+ */
+timestamp: string, } | { "event": "nodestatsupdated", session_id: string, node_id: string, stats: NodeStats, 
+/**
+ * ISO 8601 formatted timestamp
+ */
+timestamp: string, } | { "event": "nodeparamschanged", session_id: string, node_id: string, params: JsonValue, } | { "event": "sessioncreated", session_id: string, name: string | null, 
+/**
+ * ISO 8601 formatted timestamp when the session was created
+ */
+created_at: string, } | { "event": "sessiondestroyed", session_id: string, } | { "event": "sessioncostreport", session_id: string, report: CostReport, } | { "event": "nodeadded", session_id: string, node_id: string, kind: string, params: JsonValue, label?: string, notes?: string, } | { "event": "noderemoved", session_id: string, node_id: string, } | { "event": "connectionadded", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, } | { "event": "connectionremoved", session_id: string, from_node: string, from_pin: string, to_node: string, to_pin: string, } | { "event": "pinadded", session_id: string, node_id: string, pin_name: string, } | { "event": "pinremoved", session_id: string, node_id: string, pin_name: string, } | { "event": "uimetadatachanged", session_id: string, target: UiMetadataTarget, ui_metadata: JsonValue, } | { "event": "nodekinddeprecated", session_id: string, node_id: string, 
+/**
+ * The kind as sent by the client
+ */
+kind: string, 
+/**
+ * The kind the node was created as, if `kind` is an alias
+ */
+replacement?: string, message: string, } | { "event": "readonlychanged", enabled: boolean, message?: string, } | { "event": "pipelinesnapshot", session_id: string, 
+/**
+ * Incremented on every structural change; a gap means events were missed
+ */
+revision: bigint, 
+/**
+ * Order-independent hash of nodes, params and connections (hex-encoded)
+ */
+hash: string, node_count: number, connection_count: number, } | { "event": "connectiontappacket", session_id: string, tap_id: string, 
+/**
+ * 1-based position of the packet among all packets seen since the tap opened
+ */
+index: bigint, packet: TappedPacket, 
+/**
+ * RFC 3339 formatted time the packet was seen
+ */
+timestamp: string, } | { "event": "connectiontapended", session_id: string, tap_id: string, reason: TapEndReason, 
+/**
+ * Packet events sent by the tap
+ */
+packets_sent: bigint, } | { "event": "nodetelemetry", 
+/**
+ * The session this event belongs to
+ */
+session_id: string, 
+/**
+ * The node that emitted this event
+ */
+node_id: string, 
+/**
+ * Packet type identifier (e.g., "core::telemetry/event@1")
+ */
+type_id: string, 
+/**
+ * Event payload containing event_type, correlation_id, turn_id, and event-specific data
+ */
+data: JsonValue, 
+/**
+ * Microsecond timestamp from the packet metadata (if available)
+ */
+timestamp_us: bigint | null, 
+/**
+ * RFC 3339 formatted timestamp for convenience
+ */
+timestamp: string, };
+
+export type SessionInfo = { id: string, name: string | null, 
+/**
+ * ISO 8601 formatted timestamp when the session was created
+ */
+created_at: string, tags?: Array<string>, 
+/**
+ * ISO 8601 formatted timestamp of the last pipeline change or control message
+ */
+last_activity_at: string, };
+
+export type SessionSortKey = "created_at" | "activity";
+
+export type SortOrder = "asc" | "desc";
+
+export type SessionListQuery = { 
+/**
+ * Only sessions carrying all of these tags
+ */
+tags?: Array<string>, 
+/**
+ * Case-insensitive substring match on session id, name and tags
+ */
+search?: string, sort?: SessionSortKey, order?: SortOrder, 
+/**
+ * Maximum number of sessions per page (unlimited when absent)
+ */
+limit?: number, 
+/**
+ * Opaque cursor from a previous `SessionsListed` response
+ */
+cursor?: string, };
+
+export type SessionFilter = { 
+/**
+ * Only sessions carrying all of these tags
+ */
+tags?: Array<string>, 
+/**
+ * Glob matched against the session name (e.g. "load-test-*")
+ */
+name_pattern?: string, 
+/**
+ * Only sessions created at least this many seconds ago
+ */
+older_than_secs?: bigint, };
+
+export type SessionNode = { session_id: string, node_id: string, };
+
+export type EngineMode = "oneshot" | "dynamic";
+
+export type ConnectionMode = "reliable" | "best_effort" | "feedback";
+
+export type Connection = { from_node: string, from_pin: string, to_node: string, to_pin: string, 
+/**
+ * How this connection handles backpressure. Defaults to `Reliable`.
+ */
+mode?: ConnectionMode, 
+/**
+ * Opaque client data (e.g. edge routing in a graph editor), stored but never interpreted
+ */
+ui_metadata?: JsonValue, };
+
+export type Node = { kind: string, params: JsonValue, 
+/**
+ * Human-friendly display name, distinct from the node id
+ */
+label?: string, 
+/**
+ * Free-form operator notes about this node instance
+ */
+notes?: string, 
+/**
+ * Runtime state (only populated in API responses)
+ */
+state: NodeState | null, 
+/**
+ * Opaque client data (e.g. position in a graph editor), stored but never interpreted
+ */
+ui_metadata?: JsonValue, };
+
+export type UiMetadataTarget = { "type": "node", node_id: string, } | { "type": "connection", from_node: string, from_pin: string, to_node: string, to_pin: string, };
+
+export type Pipeline = { name: string | null, description: string | null, mode: EngineMode, nodes: Record<string, Node>, connections: Array<Connection>, };
+
+export type SamplePipeline = { id: string, name: string, description: string, yaml: string, is_system: boolean, mode: string, 
+/**
+ * Whether this is a reusable fragment (partial pipeline) vs a complete pipeline
+ */
+is_fragment: boolean, };
+
+export type SavePipelineRequest = { name: string, description: string, yaml: string, overwrite: boolean, 
+/**
+ * Whether this is a fragment (partial pipeline) vs a complete pipeline
+ */
+is_fragment: boolean, };
+
+export type AudioAsset = { 
+/**
+ * Unique identifier (filename, including extension)
+ */
+id: string, 
+/**
+ * Display name
+ */
+name: string, 
+/**
+ * Server-relative path suitable for `core::file_reader` (e.g., `samples/audio/system/foo.wav`)
+ */
+path: string, 
+/**
+ * File extension/format (opus, ogg, flac, mp3, wav)
+ */
+format: string, 
+/**
+ * File size in bytes
+ */
+size_bytes: bigint, 
+/**
+ * License information from .license file
+ */
+license: string | null, 
+/**
+ * Whether this is a system asset (true) or user asset (false)
+ */
+is_system: boolean, };
+
+export type BatchOperation = { "action": "addnode", node_id: string, kind: string, params: JsonValue, label?: string, notes?: string, } | { "action": "removenode", node_id: string, } | { "action": "connect", from_node: string, from_pin: string, to_node: string, to_pin: string, mode: ConnectionMode, } | { "action": "disconnect", from_node: string, from_pin: string, to_node: string, to_pin: string, };
+
+export type ValidationError = { error_type: ValidationErrorType, message: string, node_id: string | null, connection_id: string | null, 
+/**
+ * JSON Pointer into the node's params, for param schema violations
+ */
+path?: string, };
+
+export type ValidationErrorType = "error" | "warning";
+
+export type PermissionsInfo = { create_sessions: boolean, destroy_sessions: boolean, list_sessions: boolean, modify_sessions: boolean, tune_nodes: boolean, load_plugins: boolean, delete_plugins: boolean, list_nodes: boolean, list_samples: boolean, read_samples: boolean, write_samples: boolean, delete_samples: boolean, access_all_sessions: boolean, view_secrets: boolean, upload_assets: boolean, delete_assets: boolean, };
+
+export type ProfileSource = "declared" | "measured" | "unknown";
+
+export type NodeEstimate = { node_id: string, kind: string, source: ProfileSource, cpu_cores: number, ram_mb: bigint, vram_mb: bigint, requires_gpu: boolean, };
+
+export type ModelRequirement = { node_id: string, path: string, 
+/**
+ * Whether the path exists on the server; missing models have to be downloaded first
+ */
+present: boolean, };
+
+export type PipelineEstimate = { cpu_cores: number, ram_mb: bigint, vram_mb: bigint, requires_gpu: boolean, models: Array<ModelRequirement>, 
+/**
+ * Per-node figures, in pipeline order
+ */
+nodes: Array<NodeEstimate>, };
+
+export type TappedPacket = { 
+/**
+ * Packet variant: `audio`, `text`, `transcription`, `custom` or `binary`
+ */
+packet_type: string, 
+/**
+ * Binary content type, custom type ID, or audio format (e.g. "48000Hz/2ch")
+ */
+content_type?: string, 
+/**
+ * Approximate payload size in bytes
+ */
+size_bytes: bigint, metadata: PacketMetadata | null, 
+/**
+ * Leading payload bytes when requested: text for text-like packets, lowercase hex otherwise
+ */
+preview?: string, 
+/**
+ * Whether the preview stops short of the full payload
+ */
+truncated: boolean, };
+
+export type QueuedPacketSummary = { 
+/**
+ * Identifies the packet for `StepConnection` and `EditQueuedPacket`; counts the
+ * packets the connection has carried since it was first paused
+ */
+packet_id: bigint, packet: TappedPacket, };
+
+export type TapEndReason = "packet_limit" | "expired" | "closed";
\ No newline at end of file
@@ -0,0 +1,340 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+import type { EventPayload, RequestPayload, ResponsePayload } from './api-types.js';
+import {
+  isResponseFor,
+  type Event,
+  type EventName,
+  type EventOf,
+  type FireAndForgetAction,
+  type Request,
+  type RequestAction,
+  type Response,
+  type ResponseFor,
+} from './protocol.js';
+
+/** Path of the WebSocket control endpoint */
+export const CONTROL_PATH = '/api/v1/control';
+
+export interface ReconnectOptions {
+  /** Delay before the first reconnect attempt (default 1000 ms) */
+  initialDelayMs?: number;
+  /** Upper bound for the doubling delay (default 30000 ms) */
+  maxDelayMs?: number;
+  /** Attempts before giving up (default unlimited; 0 disables reconnection) */
+  maxAttempts?: number;
+}
+
+export interface ClientOptions {
+  /** How long a request waits for its response (default 30000 ms) */
+  requestTimeoutMs?: number;
+  reconnect?: ReconnectOptions;
+  /** Creates the socket; defaults to the global `WebSocket` (browsers, Bun, Node 22+) */
+  createWebSocket?: (url: string) => WebSocket;
+  /** Generates correlation IDs; defaults to `crypto.randomUUID()` */
+  correlationId?: () => string;
+}
+
+export type ConnectionState =
+  | { state: 'connecting' }
+  | { state: 'connected' }
+  | { state: 'reconnecting'; attempt: number; delayMs: number }
+  | { state: 'closed' };
+
+/** Raised for error responses, timeouts and lost connections */
+export class StreamKitError extends Error {
+  /** `server`: error response; `read_only`: a change refused in read-only mode; `timeout`:
+   * no reply in time; `disconnected`: the connection dropped with the request in flight;
+   * `closed`: the client was closed; `unexpected`: the reply didn't match the request */
+  readonly kind: 'server' | 'read_only' | 'timeout' | 'disconnected' | 'closed' | 'unexpected';
+  /** The operator's banner, for `read_only` errors */
+  readonly banner?: string;
+
+  constructor(message: string, kind: StreamKitError['kind'], banner?: string) {
+    super(message);
+    this.name = 'StreamKitError';
+    this.kind = kind;
+    this.banner = banner;
+  }
+}
+
+interface Pending {
+  action: RequestAction;
+  resolve: (payload: ResponsePayload) => void;
+  reject: (error: StreamKitError) => void;
+  timer: ReturnType<typeof setTimeout>;
+}
+
+type Listener<T> = (value: T) => void;
+
+/**
+ * Client for the StreamKit control WebSocket.
+ *
+ * Requests are matched to responses by correlation ID and typed per action. Events are
+ * delivered to listeners registered with {@link StreamKitClient.on}. When the connection
+ * drops the client reconnects with exponential backoff; requests made meanwhile are queued,
+ * while requests already in flight are rejected because the server may or may not have
+ * applied them.
+ */
+export class StreamKitClient {
+  private readonly url: string;
+  private readonly options: Required<Omit<ClientOptions, 'reconnect'>> & {
+    reconnect: Required<ReconnectOptions>;
+  };
+  private ws: WebSocket | null = null;
+  private closed = false;
+  private attempt = 0;
+  private reconnectTimer: ReturnType<typeof setTimeout> | null = null;
+  private readonly pending = new Map<string, Pending>();
+  private readonly queue: Request[] = [];
+  private readonly eventListeners = new Map<string, Set<Listener<EventPayload>>>();
+  private readonly stateListeners = new Set<Listener<ConnectionState>>();
+  private currentState: ConnectionState = { state: 'connecting' };
+
+  /**
+   * @param serverUrl The server's `http(s)://` or `ws(s)://` address; the control path is
+   * filled in.
+   */
+  constructor(serverUrl: string, options: ClientOptions = {}) {
+    this.url = controlUrl(serverUrl);
+    this.options = {
+      requestTimeoutMs: options.requestTimeoutMs ?? 30_000,
+      createWebSocket: options.createWebSocket ?? ((url) => new WebSocket(url)),
+      correlationId: options.correlationId ?? (() => crypto.randomUUID()),
+      reconnect: {
+        initialDelayMs: options.reconnect?.initialDelayMs ?? 1_000,
+        maxDelayMs: options.reconnect?.maxDelayMs ?? 30_000,
+        maxAttempts: options.reconnect?.maxAttempts ?? Infinity,
+      },
+    };
+  }
+
+  get state(): ConnectionState {
+    return this.currentState;
+  }
+
+  /**
+   * Opens the connection. Resolves once connected; rejects if the first attempt fails, in
+   * which case the client keeps retrying according to its reconnect options.
+   */
+  connect(): Promise<void> {
+    this.closed = false;
+    return new Promise((resolve, reject) => {
+      const off = this.onStateChange((state) => {
+        if (state.state === 'connected') {
+          off();
+          resolve();
+        } else if (state.state === 'reconnecting' || state.state === 'closed') {
+          off();
+          reject(new StreamKitError(`Failed to connect to ${this.url}`, 'disconnected'));
+        }
+      });
+      this.open();
+    });
+  }
+
+  /** Closes the connection and rejects every outstanding request. */
+  close(): void {
+    this.closed = true;
+    if (this.reconnectTimer !== null) {
+      clearTimeout(this.reconnectTimer);
+      this.reconnectTimer = null;
+    }
+    this.ws?.close();
+    this.ws = null;
+    this.failPending('closed', 'Client closed');
+    this.queue.length = 0;
+    this.setState({ state: 'closed' });
+  }
+
+  /**
+   * Sends a request and resolves with its successful response, narrowed to the response
+   * type of the request's action. Error responses reject with a `server` {@link StreamKitError},
+   * read-only rejections with a `read_only` one.
+   */
+  request<P extends Exclude<RequestPayload, { action: FireAndForgetAction }>>(
+    payload: P
+  ): Promise<ResponseFor<P['action']>> {
+    if (this.closed) {
+      return Promise.reject(new StreamKitError('Client closed', 'closed'));
+    }
+    const correlationId = this.options.correlationId();
+    return new Promise((resolve, reject) => {
+      const timer = setTimeout(() => {
+        this.pending.delete(correlationId);
+        this.dequeue(correlationId);
+        reject(new StreamKitError(`Request '${payload.action}' timed out`, 'timeout'));
+      }, this.options.requestTimeoutMs);
+      this.pending.set(correlationId, {
+        action: payload.action,
+        resolve: resolve as (payload: ResponsePayload) => void,
+        reject,
+        timer,
+      });
+      this.transmit({ type: 'request', correlation_id: correlationId, payload });
+    });
+  }
+
+  /** Sends a request without waiting for a reply (e.g. `tunenodeasync`). */
+  send(payload: RequestPayload): void {
+    if (this.closed) {
+      throw new StreamKitError('Client closed', 'closed');
+    }
+    this.transmit({ type: 'request', correlation_id: this.options.correlationId(), payload });
+  }
+
+  /**
+   * Registers a listener for one event type, or for every event with `'*'`.
+   * Returns a function that removes the listener.
+   */
+  on<E extends EventName>(event: E, listener: Listener<EventOf<E>>): () => void;
+  on(event: '*', listener: Listener<EventPayload>): () => void;
+  on(event: string, listener: Listener<never>): () => void {
+    const listeners = this.eventListeners.get(event) ?? new Set();
+    this.eventListeners.set(event, listeners);
+    const entry = listener as Listener<EventPayload>;
+    listeners.add(entry);
+    return () => listeners.delete(entry);
+  }
+
+  /** Registers a connection state listener. Returns a function that removes it. */
+  onStateChange(listener: Listener<ConnectionState>): () => void {
+    this.stateListeners.add(listener);
+    return () => this.stateListeners.delete(listener);
+  }
+
+  private open(): void {
+    let ws: WebSocket;
+    try {
+      ws = this.options.createWebSocket(this.url);
+    } catch {
+      this.scheduleReconnect();
+      return;
+    }
+    this.ws = ws;
+
+    ws.onopen = () => {
+      this.attempt = 0;
+      this.setState({ state: 'connected' });
+      for (const request of this.queue.splice(0)) {
+        ws.send(JSON.stringify(request));
+      }
+    };
+    ws.onmessage = (message: MessageEvent) => {
+      if (typeof message.data === 'string') {
+        this.dispatch(message.data);
+      }
+    };
+    ws.onclose = () => {
+      if (this.ws !== ws) {
+        return;
+      }
+      this.ws = null;
+      this.failPending('disconnected', 'Connection lost before a response was received');
+      if (!this.closed) {
+        this.scheduleReconnect();
+      }
+    };
+  }
+
+  private scheduleReconnect(): void {
+    const { initialDelayMs, maxDelayMs, maxAttempts } = this.options.reconnect;
+    if (this.attempt >= maxAttempts) {
+      this.close();
+      return;
+    }
+    const delayMs = Math.min(initialDelayMs * 2 ** this.attempt, maxDelayMs);
+    this.attempt += 1;
+    this.setState({ state: 'reconnecting', attempt: this.attempt, delayMs });
+    this.reconnectTimer = setTimeout(() => {
+      this.reconnectTimer = null;
+      this.open();
+    }, delayMs);
+  }
+
+  private transmit(request: Request): void {
+    if (this.ws?.readyState === WebSocket.OPEN) {
+      this.ws.send(JSON.stringify(request));
+    } else {
+      this.queue.push(request);
+    }
+  }
+
+  private dequeue(correlationId: string): void {
+    const index = this.queue.findIndex((r) => r.correlation_id === correlationId);
+    if (index >= 0) {
+      this.queue.splice(index, 1);
+    }
+  }
+
+  private dispatch(data: string): void {
+    let message: Response | Event;
+    try {
+      message = JSON.parse(data) as Response | Event;
+    } catch {
+      return;
+    }
+
+    if (message.type === 'event') {
+      const payload = (message as Event).payload;
+      for (const key of [payload.event, '*']) {
+        this.eventListeners.get(key)?.forEach((listener) => listener(payload));
+      }
+      return;
+    }
+
+    const pending = message.correlation_id ? this.pending.get(message.correlation_id) : undefined;
+    if (!pending || !message.correlation_id) {
+      return;
+    }
+    this.pending.delete(message.correlation_id);
+    clearTimeout(pending.timer);
+
+    const payload = (message as Response).payload;
+    if (payload.action === 'error') {
+      pending.reject(new StreamKitError(payload.message, 'server'));
+    } else if (payload.action === 'readonly') {
+      pending.reject(new StreamKitError(payload.message, 'read_only', payload.banner));
+    } else if (isResponseFor(pending.action, payload)) {
+      pending.resolve(payload);
+    } else {
+      pending.reject(
+        new StreamKitError(
+          `Unexpected '${payload.action}' response to '${pending.action}'`,
+          'unexpected'
+        )
+      );
+    }
+  }
+
+  private failPending(kind: StreamKitError['kind'], message: string): void {
+    for (const pending of this.pending.values()) {
+      clearTimeout(pending.timer);
+      pending.reject(new StreamKitError(message, kind));
+    }
+    this.pending.clear();
+  }
+
+  private setState(state: ConnectionState): void {
+    this.currentState = state;
+    this.stateListeners.forEach((listener) => listener(state));
+  }
+}
+
+/** Maps a server address to its control WebSocket URL. */
+export function controlUrl(serverUrl: string): string {
+  const url = new URL(serverUrl);
+  if (url.protocol === 'http:') {
+    url.protocol = 'ws:';
+  } else if (url.protocol === 'https:') {
+    url.protocol = 'wss:';
+  } else if (url.protocol !== 'ws:' && url.protocol !== 'wss:') {
+    throw new Error('Server URL must be http(s) or ws(s)');
+  }
+  url.pathname = CONTROL_PATH;
+  url.search = '';
+  url.hash = '';
+  return url.toString();
+}
@@ -0,0 +1,7 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+export * from './api-types.js';
+export * from './protocol.js';
+export * from './client.js';
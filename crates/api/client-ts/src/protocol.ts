@@ -0,0 +1,172 @@
+// This file is auto-generated. Do not edit it manually.
+
+import type { EventPayload, MessageType, RequestPayload, ResponsePayload } from './api-types.js';
+
+/** Envelope of every control-plane message */
+export type Message<T> = {
+  type: MessageType;
+  /** Present on requests and their responses, absent on events */
+  correlation_id?: string;
+  payload: T;
+};
+export type Request = Message<RequestPayload>;
+export type Response = Message<ResponsePayload>;
+export type Event = Message<EventPayload>;
+
+/** Request `action` values */
+export type RequestAction = RequestPayload['action'];
+/** The request payload for one action */
+export type RequestOf<A extends RequestAction> = Extract<RequestPayload, { action: A }>;
+/** The fields of a request, without its `action` tag */
+export type RequestArgs<A extends RequestAction> = Omit<RequestOf<A>, 'action'>;
+/** Event names (`event` tag values) */
+export type EventName = EventPayload['event'];
+/** The event payload for one event name */
+export type EventOf<E extends EventName> = Extract<EventPayload, { event: E }>;
+
+/**
+ * Response actions a successful reply carries, per request action. Any request may also be
+ * answered with `error`, and mutating requests with `readonly`; requests with no entries are
+ * fire-and-forget.
+ */
+export const RESPONSE_ACTIONS = {
+  createsession: ['sessioncreated'],
+  destroysession: ['sessiondestroyed'],
+  destroysessions: ['sessionsdestroyed'],
+  listsessions: ['sessionslisted'],
+  listnodes: ['nodeslisted'],
+  addnode: ['success'],
+  removenode: ['success'],
+  connect: ['success'],
+  disconnect: ['success'],
+  addpin: ['pinadded'],
+  removepin: ['success'],
+  tunenode: ['success'],
+  tunenodeasync: [],
+  querynode: ['queryresult'],
+  tunenodesbykind: ['nodestuned'],
+  setuimetadata: ['success'],
+  getpipeline: ['pipeline'],
+  getsessioncost: ['sessioncost'],
+  tapconnection: ['connectiontapped'],
+  setdebugmode: ['success'],
+  setbreakpoint: ['success'],
+  stepconnection: ['success'],
+  inspectconnection: ['connectionqueue'],
+  editqueuedpacket: ['success'],
+  validatebatch: ['validationresult'],
+  applybatch: ['batchapplied'],
+  getpermissions: ['permissions'],
+  setreadonly: ['success'],
+} as const satisfies Record<RequestAction, readonly ResponsePayload['action'][]>;
+
+/** The successful response payload for a request action */
+export type ResponseFor<A extends RequestAction> = Extract<
+  ResponsePayload,
+  { action: (typeof RESPONSE_ACTIONS)[A][number] }
+>;
+
+/** Request actions the server never answers */
+export type FireAndForgetAction = {
+  [A in RequestAction]: (typeof RESPONSE_ACTIONS)[A] extends readonly [] ? A : never;
+}[RequestAction];
+
+/** Whether `payload` is a successful reply to a request with this action */
+export function isResponseFor<A extends RequestAction>(
+  action: A,
+  payload: ResponsePayload
+): payload is ResponseFor<A> {
+  return (RESPONSE_ACTIONS[action] as readonly string[]).includes(payload.action);
+}
+
+/** Builder arguments; optional for requests without fields */
+type ArgsTuple<A extends RequestAction> =
+  Record<string, never> extends RequestArgs<A> ? [args?: RequestArgs<A>] : [args: RequestArgs<A>];
+
+/** Request payload builders, one per action */
+export const requests = {
+  /** Builds the `createsession` request payload */
+  createSession: (...[args]: ArgsTuple<'createsession'>): RequestOf<'createsession'> =>
+    ({ ...args, action: 'createsession' }) as RequestOf<'createsession'>,
+  /** Builds the `destroysession` request payload */
+  destroySession: (...[args]: ArgsTuple<'destroysession'>): RequestOf<'destroysession'> =>
+    ({ ...args, action: 'destroysession' }) as RequestOf<'destroysession'>,
+  /** Builds the `destroysessions` request payload */
+  destroySessions: (...[args]: ArgsTuple<'destroysessions'>): RequestOf<'destroysessions'> =>
+    ({ ...args, action: 'destroysessions' }) as RequestOf<'destroysessions'>,
+  /** Builds the `listsessions` request payload */
+  listSessions: (...[args]: ArgsTuple<'listsessions'>): RequestOf<'listsessions'> =>
+    ({ ...args, action: 'listsessions' }) as RequestOf<'listsessions'>,
+  /** Builds the `listnodes` request payload */
+  listNodes: (...[args]: ArgsTuple<'listnodes'>): RequestOf<'listnodes'> =>
+    ({ ...args, action: 'listnodes' }) as RequestOf<'listnodes'>,
+  /** Builds the `addnode` request payload */
+  addNode: (...[args]: ArgsTuple<'addnode'>): RequestOf<'addnode'> =>
+    ({ ...args, action: 'addnode' }) as RequestOf<'addnode'>,
+  /** Builds the `removenode` request payload */
+  removeNode: (...[args]: ArgsTuple<'removenode'>): RequestOf<'removenode'> =>
+    ({ ...args, action: 'removenode' }) as RequestOf<'removenode'>,
+  /** Builds the `connect` request payload */
+  connect: (...[args]: ArgsTuple<'connect'>): RequestOf<'connect'> =>
+    ({ ...args, action: 'connect' }) as RequestOf<'connect'>,
+  /** Builds the `disconnect` request payload */
+  disconnect: (...[args]: ArgsTuple<'disconnect'>): RequestOf<'disconnect'> =>
+    ({ ...args, action: 'disconnect' }) as RequestOf<'disconnect'>,
+  /** Builds the `addpin` request payload */
+  addPin: (...[args]: ArgsTuple<'addpin'>): RequestOf<'addpin'> =>
+    ({ ...args, action: 'addpin' }) as RequestOf<'addpin'>,
+  /** Builds the `removepin` request payload */
+  removePin: (...[args]: ArgsTuple<'removepin'>): RequestOf<'removepin'> =>
+    ({ ...args, action: 'removepin' }) as RequestOf<'removepin'>,
+  /** Builds the `tunenode` request payload */
+  tuneNode: (...[args]: ArgsTuple<'tunenode'>): RequestOf<'tunenode'> =>
+    ({ ...args, action: 'tunenode' }) as RequestOf<'tunenode'>,
+  /** Builds the `tunenodeasync` request payload */
+  tuneNodeAsync: (...[args]: ArgsTuple<'tunenodeasync'>): RequestOf<'tunenodeasync'> =>
+    ({ ...args, action: 'tunenodeasync' }) as RequestOf<'tunenodeasync'>,
+  /** Builds the `querynode` request payload */
+  queryNode: (...[args]: ArgsTuple<'querynode'>): RequestOf<'querynode'> =>
+    ({ ...args, action: 'querynode' }) as RequestOf<'querynode'>,
+  /** Builds the `tunenodesbykind` request payload */
+  tuneNodesByKind: (...[args]: ArgsTuple<'tunenodesbykind'>): RequestOf<'tunenodesbykind'> =>
+    ({ ...args, action: 'tunenodesbykind' }) as RequestOf<'tunenodesbykind'>,
+  /** Builds the `setuimetadata` request payload */
+  setUiMetadata: (...[args]: ArgsTuple<'setuimetadata'>): RequestOf<'setuimetadata'> =>
+    ({ ...args, action: 'setuimetadata' }) as RequestOf<'setuimetadata'>,
+  /** Builds the `getpipeline` request payload */
+  getPipeline: (...[args]: ArgsTuple<'getpipeline'>): RequestOf<'getpipeline'> =>
+    ({ ...args, action: 'getpipeline' }) as RequestOf<'getpipeline'>,
+  /** Builds the `getsessioncost` request payload */
+  getSessionCost: (...[args]: ArgsTuple<'getsessioncost'>): RequestOf<'getsessioncost'> =>
+    ({ ...args, action: 'getsessioncost' }) as RequestOf<'getsessioncost'>,
+  /** Builds the `tapconnection` request payload */
+  tapConnection: (...[args]: ArgsTuple<'tapconnection'>): RequestOf<'tapconnection'> =>
+    ({ ...args, action: 'tapconnection' }) as RequestOf<'tapconnection'>,
+  /** Builds the `setdebugmode` request payload */
+  setDebugMode: (...[args]: ArgsTuple<'setdebugmode'>): RequestOf<'setdebugmode'> =>
+    ({ ...args, action: 'setdebugmode' }) as RequestOf<'setdebugmode'>,
+  /** Builds the `setbreakpoint` request payload */
+  setBreakpoint: (...[args]: ArgsTuple<'setbreakpoint'>): RequestOf<'setbreakpoint'> =>
+    ({ ...args, action: 'setbreakpoint' }) as RequestOf<'setbreakpoint'>,
+  /** Builds the `stepconnection` request payload */
+  stepConnection: (...[args]: ArgsTuple<'stepconnection'>): RequestOf<'stepconnection'> =>
+    ({ ...args, action: 'stepconnection' }) as RequestOf<'stepconnection'>,
+  /** Builds the `inspectconnection` request payload */
+  inspectConnection: (...[args]: ArgsTuple<'inspectconnection'>): RequestOf<'inspectconnection'> =>
+    ({ ...args, action: 'inspectconnection' }) as RequestOf<'inspectconnection'>,
+  /** Builds the `editqueuedpacket` request payload */
+  editQueuedPacket: (...[args]: ArgsTuple<'editqueuedpacket'>): RequestOf<'editqueuedpacket'> =>
+    ({ ...args, action: 'editqueuedpacket' }) as RequestOf<'editqueuedpacket'>,
+  /** Builds the `validatebatch` request payload */
+  validateBatch: (...[args]: ArgsTuple<'validatebatch'>): RequestOf<'validatebatch'> =>
+    ({ ...args, action: 'validatebatch' }) as RequestOf<'validatebatch'>,
+  /** Builds the `applybatch` request payload */
+  applyBatch: (...[args]: ArgsTuple<'applybatch'>): RequestOf<'applybatch'> =>
+    ({ ...args, action: 'applybatch' }) as RequestOf<'applybatch'>,
+  /** Builds the `getpermissions` request payload */
+  getPermissions: (...[args]: ArgsTuple<'getpermissions'>): RequestOf<'getpermissions'> =>
+    ({ ...args, action: 'getpermissions' }) as RequestOf<'getpermissions'>,
+  /** Builds the `setreadonly` request payload */
+  setReadOnly: (...[args]: ArgsTuple<'setreadonly'>): RequestOf<'setreadonly'> =>
+    ({ ...args, action: 'setreadonly' }) as RequestOf<'setreadonly'>,
+};
@@ -8,6 +8,7 @@
 
 use std::fs;
 use std::path::Path;
+use streamkit_api::REQUEST_RESPONSES;
 use streamkit_core::control::NodeControlMessage;
 use streamkit_core::types::{
     AudioFormat, PacketMetadata, PacketType, SampleFormat, TranscriptionData, TranscriptionSegment,
@@ -27,9 +28,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         format!("export {}", streamkit_core::InputPin::decl()),
         format!("export {}", streamkit_core::OutputPin::decl()),
         format!("export {}", streamkit_core::NodeDefinition::decl()),
+        format!("export {}", streamkit_core::CustomTypeSchema::decl()),
         format!("export {}", streamkit_core::StopReason::decl()),
         format!("export {}", streamkit_core::NodeState::decl()),
         format!("export {}", streamkit_core::NodeStats::decl()),
+        format!("export {}", streamkit_core::NodeCost::decl()),
+        format!("export {}", streamkit_core::KindCost::decl()),
+        format!("export {}", streamkit_core::CostReport::decl()),
         format!("export {}", NodeControlMessage::decl()),
         // packet type registry metadata (server-driven UI)
         format!("export {}", streamkit_core::packet_meta::FieldRule::decl()),
@@ -37,14 +42,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         format!("export {}", streamkit_core::packet_meta::PacketTypeMeta::decl()),
         // streamkit-api types
         format!("\n// streamkit-api\nexport {}", streamkit_api::MessageType::decl()),
+        format!("export {}", streamkit_api::WireFormat::decl()),
+        format!(
+            "/** `Sec-WebSocket-Protocol` values that select each wire format */\nexport const WIRE_FORMAT_SUBPROTOCOLS: Record<WireFormat, string> = {{ json: \"{}\", msgpack: \"{}\" }};",
+            streamkit_api::WireFormat::Json.subprotocol(),
+            streamkit_api::WireFormat::Msgpack.subprotocol(),
+        ),
         format!("export {}", streamkit_api::RequestPayload::decl()),
         format!("export {}", streamkit_api::ResponsePayload::decl()),
         format!("export {}", streamkit_api::EventPayload::decl()),
         format!("export {}", streamkit_api::SessionInfo::decl()),
+        format!("export {}", streamkit_api::SessionSortKey::decl()),
+        format!("export {}", streamkit_api::SortOrder::decl()),
+        format!("export {}", streamkit_api::SessionListQuery::decl()),
+        format!("export {}", streamkit_api::SessionFilter::decl()),
+        format!("export {}", streamkit_api::SessionNode::decl()),
         format!("export {}", streamkit_api::EngineMode::decl()),
         format!("export {}", streamkit_api::ConnectionMode::decl()),
         format!("export {}", streamkit_api::Connection::decl()),
         format!("export {}", streamkit_api::Node::decl()),
+        format!("export {}", streamkit_api::UiMetadataTarget::decl()),
         format!("export {}", streamkit_api::Pipeline::decl()),
         format!("export {}", streamkit_api::SamplePipeline::decl()),
         format!("export {}", streamkit_api::SavePipelineRequest::decl()),
@@ -53,6 +70,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         format!("export {}", streamkit_api::ValidationError::decl()),
         format!("export {}", streamkit_api::ValidationErrorType::decl()),
         format!("export {}", streamkit_api::PermissionsInfo::decl()),
+        format!("export {}", streamkit_api::ProfileSource::decl()),
+        format!("export {}", streamkit_api::NodeEstimate::decl()),
+        format!("export {}", streamkit_api::ModelRequirement::decl()),
+        format!("export {}", streamkit_api::PipelineEstimate::decl()),
+        format!("export {}", streamkit_api::TappedPacket::decl()),
+        format!("export {}", streamkit_api::QueuedPacketSummary::decl()),
+        format!("export {}", streamkit_api::TapEndReason::decl()),
     ];
 
     let output = declarations.join("\n\n");
@@ -65,13 +89,115 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parent()
         .and_then(|parent| parent.parent())
         .ok_or("Failed to find workspace root from CARGO_MANIFEST_DIR")?;
-    let output_path = workspace_root.join("ui/src/types/generated/api-types.ts");
-
-    println!("Writing TypeScript bindings to: {}", output_path.display());
+    let client_dir = manifest_dir.join("client-ts/src");
+    let outputs = [
+        (workspace_root.join("ui/src/types/generated/api-types.ts"), content.clone()),
+        (client_dir.join("api-types.ts"), content),
+        (client_dir.join("protocol.ts"), protocol_module()),
+    ];
 
-    fs::write(&output_path, content)?;
+    for (path, content) in outputs {
+        println!("Writing TypeScript bindings to: {}", path.display());
+        fs::write(&path, content)?;
+    }
 
     println!("✅ TypeScript bindings generated successfully.");
 
     Ok(())
 }
+
+/// Builds `protocol.ts` for the client package: message envelopes, request builders and
+/// per-request response narrowing, all derived from `REQUEST_RESPONSES`.
+fn protocol_module() -> String {
+    let response_actions: Vec<String> = REQUEST_RESPONSES
+        .iter()
+        .map(|(variant, replies)| {
+            let replies: Vec<String> = replies.iter().map(|r| format!("'{r}'")).collect();
+            format!("  {}: [{}],", variant.to_lowercase(), replies.join(", "))
+        })
+        .collect();
+    let builders: Vec<String> = REQUEST_RESPONSES
+        .iter()
+        .map(|(variant, _)| {
+            let action = variant.to_lowercase();
+            let name = lower_camel(variant);
+            format!(
+                "  /** Builds the `{action}` request payload */\n  {name}: (...[args]: ArgsTuple<'{action}'>): RequestOf<'{action}'> =>\n    ({{ ...args, action: '{action}' }}) as RequestOf<'{action}'>,"
+            )
+        })
+        .collect();
+
+    format!(
+        r"// This file is auto-generated. Do not edit it manually.
+
+import type {{ EventPayload, MessageType, RequestPayload, ResponsePayload }} from './api-types.js';
+
+/** Envelope of every control-plane message */
+export type Message<T> = {{
+  type: MessageType;
+  /** Present on requests and their responses, absent on events */
+  correlation_id?: string;
+  payload: T;
+}};
+export type Request = Message<RequestPayload>;
+export type Response = Message<ResponsePayload>;
+export type Event = Message<EventPayload>;
+
+/** Request `action` values */
+export type RequestAction = RequestPayload['action'];
+/** The request payload for one action */
+export type RequestOf<A extends RequestAction> = Extract<RequestPayload, {{ action: A }}>;
+/** The fields of a request, without its `action` tag */
+export type RequestArgs<A extends RequestAction> = Omit<RequestOf<A>, 'action'>;
+/** Event names (`event` tag values) */
+export type EventName = EventPayload['event'];
+/** The event payload for one event name */
+export type EventOf<E extends EventName> = Extract<EventPayload, {{ event: E }}>;
+
+/**
+ * Response actions a successful reply carries, per request action. Any request may also be
+ * answered with `error`, and mutating requests with `readonly`; requests with no entries are
+ * fire-and-forget.
+ */
+export const RESPONSE_ACTIONS = {{
+{}
+}} as const satisfies Record<RequestAction, readonly ResponsePayload['action'][]>;
+
+/** The successful response payload for a request action */
+export type ResponseFor<A extends RequestAction> = Extract<
+  ResponsePayload,
+  {{ action: (typeof RESPONSE_ACTIONS)[A][number] }}
+>;
+
+/** Request actions the server never answers */
+export type FireAndForgetAction = {{
+  [A in RequestAction]: (typeof RESPONSE_ACTIONS)[A] extends readonly [] ? A : never;
+}}[RequestAction];
+
+/** Whether `payload` is a successful reply to a request with this action */
+export function isResponseFor<A extends RequestAction>(
+  action: A,
+  payload: ResponsePayload
+): payload is ResponseFor<A> {{
+  return (RESPONSE_ACTIONS[action] as readonly string[]).includes(payload.action);
+}}
+
+/** Builder arguments; optional for requests without fields */
+type ArgsTuple<A extends RequestAction> =
+  Record<string, never> extends RequestArgs<A> ? [args?: RequestArgs<A>] : [args: RequestArgs<A>];
+
+/** Request payload builders, one per action */
+export const requests = {{
+{}
+}};
+",
+        response_actions.join("\n"),
+        builders.join("\n"),
+    )
+}
+
+/// `CreateSession` -> `createSession`
+fn lower_camel(variant: &str) -> String {
+    let mut chars = variant.chars();
+    chars.next().map_or_else(String::new, |first| first.to_lowercase().chain(chars).collect())
+}
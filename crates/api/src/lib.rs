@@ -16,7 +16,7 @@ pub mod yaml;
 
 // Re-export types so client crates can use them
 pub use streamkit_core::control::{ConnectionMode, NodeControlMessage};
-pub use streamkit_core::{NodeDefinition, NodeState, NodeStats};
+pub use streamkit_core::{CostReport, NodeDefinition, NodeState, NodeStats};
 
 // --- Message Types ---
 
@@ -38,6 +38,46 @@ pub enum MessageType {
     Event,
 }
 
+/// Encoding of WebSocket control-plane messages, negotiated per connection.
+///
+/// Clients pick a format by offering its subprotocol in `Sec-WebSocket-Protocol` when
+/// connecting; the server echoes the one it accepted. Without a recognized subprotocol the
+/// connection uses JSON.
+///
+/// With MessagePack, the server sends responses and events as binary frames. Structs are
+/// encoded as maps with the same field names as the JSON form, so both carry the same
+/// message shape. Requests may be sent as MessagePack binary frames or as JSON text frames.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum WireFormat {
+    /// JSON text frames (default)
+    #[default]
+    Json,
+    /// MessagePack binary frames
+    Msgpack,
+}
+
+impl WireFormat {
+    /// Every supported format.
+    pub const ALL: [Self; 2] = [Self::Json, Self::Msgpack];
+
+    /// The `Sec-WebSocket-Protocol` value that selects this format.
+    pub const fn subprotocol(self) -> &'static str {
+        match self {
+            Self::Json => "streamkit.json",
+            Self::Msgpack => "streamkit.msgpack",
+        }
+    }
+
+    /// Picks the first of the client's requested subprotocols that names a format.
+    pub fn negotiate<'a>(requested: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        requested.into_iter().find_map(|protocol| {
+            Self::ALL.into_iter().find(|format| format.subprotocol() == protocol.trim())
+        })
+    }
+}
+
 // --- Base Message ---
 
 /// Generic WebSocket message container for requests, responses, and events.
@@ -102,15 +142,19 @@ pub struct Message<T> {
 /// # Session Management
 /// - `CreateSession`: Create a new dynamic pipeline session
 /// - `DestroySession`: Destroy an existing session
-/// - `ListSessions`: List all sessions visible to the current role
+/// - `ListSessions`: List sessions visible to the current role (filter, search, paginate)
+/// - `DestroySessions`: Destroy every session matching a filter (admin)
 ///
 /// # Pipeline Manipulation
 /// - `AddNode`: Add a node to a session's pipeline
 /// - `RemoveNode`: Remove a node from a session's pipeline
 /// - `Connect`: Connect two nodes in a session's pipeline
 /// - `Disconnect`: Disconnect two nodes in a session's pipeline
+/// - `AddPin` / `RemovePin`: Create or remove an input pin on a node with dynamic pins
 /// - `TuneNode`: Send control message to a node (with response)
 /// - `TuneNodeAsync`: Send control message to a node (fire-and-forget)
+/// - `SetUiMetadata`: Store editor layout/hints on a node or connection
+/// - `TuneNodesByKind`: Update the params of every node of one kind across sessions (admin)
 ///
 /// # Batch Operations
 /// - `ValidateBatch`: Validate multiple operations without applying
@@ -119,7 +163,19 @@ pub struct Message<T> {
 /// # Discovery
 /// - `ListNodes`: List all available node types
 /// - `GetPipeline`: Get current pipeline state for a session
+/// - `GetSessionCost`: Get the resource usage accumulated by a session so far
+/// - `TapConnection`: Stream a sampled view of the packets flowing over a connection
+///
+/// # Debugging
+/// - `SetDebugMode`: Allow or stop pausing connections in a session
+/// - `SetBreakpoint`: Pause or resume a connection
+/// - `StepConnection`: Release packets held on a paused connection one at a time
+/// - `InspectConnection`: List the packets held on a paused connection
+/// - `EditQueuedPacket`: Rewrite a held text or custom packet before it is delivered
 /// - `GetPermissions`: Get current user's permissions
+///
+/// # Administration
+/// - `SetReadOnly`: Enter or leave read-only maintenance mode (admin)
 #[derive(Serialize, Deserialize, Debug, TS)]
 #[ts(export)]
 #[serde(tag = "action")]
@@ -130,14 +186,37 @@ pub enum RequestPayload {
         /// Optional session name for identification
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        /// Optional tags for filtering sessions in `ListSessions`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        tags: Option<Vec<String>>,
+        /// Keep a black-box recording of the session's control requests and events,
+        /// retrievable after it ends. Defaults to false.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        record: Option<bool>,
     },
     /// Destroy an existing session and clean up resources
     DestroySession {
         /// The session ID to destroy
         session_id: String,
     },
-    /// List all sessions visible to the current user/role
-    ListSessions,
+    /// Destroy every session matching `filter`. Requires `destroy_sessions` and
+    /// `access_all_sessions`; answered with `SessionsDestroyed`.
+    DestroySessions {
+        filter: SessionFilter,
+        /// Only report which sessions would be destroyed
+        #[serde(default)]
+        #[ts(optional, as = "Option<bool>")]
+        dry_run: bool,
+    },
+    /// List sessions visible to the current user/role.
+    /// Without a query, every visible session is returned in one response.
+    ListSessions {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        query: Option<SessionListQuery>,
+    },
     /// List all available node types and their schemas
     ListNodes,
     /// Add a node to a session's pipeline
@@ -152,6 +231,14 @@ pub enum RequestPayload {
         #[serde(skip_serializing_if = "Option::is_none")]
         #[ts(type = "JsonValue")]
         params: Option<serde_json::Value>,
+        /// Human-friendly display name (e.g. "Spanish caption branch")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        label: Option<String>,
+        /// Free-form operator notes about this node instance
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        notes: Option<String>,
     },
     /// Remove a node from a session's pipeline
     RemoveNode {
@@ -172,7 +259,7 @@ pub enum RequestPayload {
         to_node: String,
         /// Destination input pin name
         to_pin: String,
-        /// Connection mode (reliable or best-effort). Defaults to Reliable.
+        /// Connection mode (reliable, best-effort or feedback). Defaults to Reliable.
         #[serde(default)]
         mode: ConnectionMode,
     },
@@ -189,6 +276,28 @@ pub enum RequestPayload {
         /// Destination input pin name
         to_pin: String,
     },
+    /// Create an input pin on a node that declares a dynamic pin family (e.g. `audio::mixer`)
+    /// before connecting to it. Answered with `PinAdded` carrying the pin name.
+    /// Connecting to a pin that does not exist yet creates it implicitly.
+    AddPin {
+        /// The session ID containing the node
+        session_id: String,
+        /// The node ID to add the pin to
+        node_id: String,
+        /// Requested pin name; the node picks the next free name when absent
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        pin_name: Option<String>,
+    },
+    /// Remove an input pin from a node with dynamic pins, closing any connections to it
+    RemovePin {
+        /// The session ID containing the node
+        session_id: String,
+        /// The node ID to remove the pin from
+        node_id: String,
+        /// The input pin to remove
+        pin_name: String,
+    },
     /// Send a control message to a node and wait for response
     TuneNode {
         /// The session ID containing the node
@@ -208,11 +317,136 @@ pub enum RequestPayload {
         /// The control message (typically UpdateParams)
         message: NodeControlMessage,
     },
+    /// Ask a running node for runtime information (e.g. buffer fill, model info).
+    /// Answered with `QueryResult`, or `Error` if the node cannot answer.
+    QueryNode {
+        /// The session ID containing the node
+        session_id: String,
+        /// The node ID to query
+        node_id: String,
+        /// Query name understood by the node (e.g. "buffer_fill")
+        kind: String,
+        /// Optional query-specific arguments
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional, type = "JsonValue")]
+        args: Option<serde_json::Value>,
+    },
+    /// Send the same `UpdateParams` to every node of `kind` in the sessions matching
+    /// `filter` (all sessions when absent), e.g. to lower Opus bitrates during an incident.
+    /// Requires `tune_nodes` and `access_all_sessions`; answered with `NodesTuned`.
+    /// Params are validated for every node before any is tuned.
+    TuneNodesByKind {
+        /// Node type to tune (e.g. "audio::opus::encoder")
+        kind: String,
+        /// Params to apply, as with `UpdateParams`
+        #[ts(type = "JsonValue")]
+        params: serde_json::Value,
+        #[serde(default)]
+        #[ts(optional, as = "Option<SessionFilter>")]
+        filter: SessionFilter,
+        /// Only report which nodes would be tuned
+        #[serde(default)]
+        #[ts(optional, as = "Option<bool>")]
+        dry_run: bool,
+    },
+    /// Replace the opaque `ui_metadata` of a node or connection (e.g. editor positions).
+    /// The server stores it with the pipeline and returns it from `GetPipeline`; `null` clears it.
+    SetUiMetadata {
+        /// The session ID containing the node or connection
+        session_id: String,
+        /// The node or connection to annotate
+        target: UiMetadataTarget,
+        #[ts(type = "JsonValue")]
+        ui_metadata: Option<serde_json::Value>,
+    },
     /// Get the current pipeline state for a session
     GetPipeline {
         /// The session ID to query
         session_id: String,
     },
+    /// Get the cumulative resource usage (CPU/GPU time, bytes moved) of a session
+    GetSessionCost {
+        /// The session ID to query
+        session_id: String,
+    },
+    /// Stream a sampled, truncated view of the packets flowing over a connection to the
+    /// requesting client as `connectiontappacket` events, until the tap expires.
+    /// Only available over WebSocket; the tap also ends when the client disconnects.
+    TapConnection {
+        /// The session ID containing the connection
+        session_id: String,
+        from_node: String,
+        from_pin: String,
+        to_node: String,
+        to_pin: String,
+        /// Stop after sending this many packets (default 20, max 500)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        max_packets: Option<u32>,
+        /// Stop after this many seconds (default 30, max 300)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        duration_secs: Option<u32>,
+        /// Send one packet out of every `sample_every` (default 1, every packet)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        sample_every: Option<u32>,
+        /// Include up to this many leading payload bytes in each event (default 0, max 1024)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        preview_bytes: Option<u32>,
+    },
+    /// Turn a session's debug mode on or off. Connections can only be paused in debug
+    /// mode; turning it off resumes every paused connection.
+    SetDebugMode { session_id: String, enabled: bool },
+    /// Pause a connection, holding the packets sent over it, or resume it, delivering the
+    /// held packets in order. Requires debug mode.
+    SetBreakpoint {
+        session_id: String,
+        from_node: String,
+        from_pin: String,
+        to_node: String,
+        to_pin: String,
+        paused: bool,
+    },
+    /// Deliver the next packets of a paused connection, whether already held or still to
+    /// arrive, then keep holding. Requires debug mode.
+    StepConnection {
+        session_id: String,
+        from_node: String,
+        from_pin: String,
+        to_node: String,
+        to_pin: String,
+        /// Number of packets to deliver (default 1)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        count: Option<u32>,
+    },
+    /// List the packets held on a connection, oldest first
+    InspectConnection {
+        session_id: String,
+        from_node: String,
+        from_pin: String,
+        to_node: String,
+        to_pin: String,
+        /// Include up to this many leading payload bytes of each packet (default 0, max 1024)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        preview_bytes: Option<u32>,
+    },
+    /// Replace the content of a packet held on a paused connection: the text of a text
+    /// packet (a JSON string) or the data of a custom packet. Requires debug mode.
+    EditQueuedPacket {
+        session_id: String,
+        from_node: String,
+        from_pin: String,
+        to_node: String,
+        to_pin: String,
+        /// `packet_id` from `InspectConnection`
+        packet_id: u64,
+        #[ts(type = "JsonValue")]
+        content: serde_json::Value,
+    },
     /// Validate a batch of operations without applying them.
     /// Returns validation errors if any operations would fail.
     ValidateBatch {
@@ -231,6 +465,89 @@ pub enum RequestPayload {
     },
     /// Get current user's permissions based on their role
     GetPermissions,
+    /// Enter or leave read-only mode. While enabled, queries and event streaming keep
+    /// working and every mutating request is answered with `readonly`.
+    SetReadOnly {
+        enabled: bool,
+        /// Banner shown to clients while read-only (e.g. "Upgrading, back at 14:00 UTC")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        message: Option<String>,
+    },
+}
+
+impl RequestPayload {
+    /// The session this request targets, if any.
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Self::DestroySession { session_id }
+            | Self::AddNode { session_id, .. }
+            | Self::RemoveNode { session_id, .. }
+            | Self::Connect { session_id, .. }
+            | Self::Disconnect { session_id, .. }
+            | Self::AddPin { session_id, .. }
+            | Self::RemovePin { session_id, .. }
+            | Self::TuneNode { session_id, .. }
+            | Self::TuneNodeAsync { session_id, .. }
+            | Self::QueryNode { session_id, .. }
+            | Self::SetUiMetadata { session_id, .. }
+            | Self::GetPipeline { session_id }
+            | Self::GetSessionCost { session_id }
+            | Self::TapConnection { session_id, .. }
+            | Self::SetDebugMode { session_id, .. }
+            | Self::SetBreakpoint { session_id, .. }
+            | Self::StepConnection { session_id, .. }
+            | Self::InspectConnection { session_id, .. }
+            | Self::EditQueuedPacket { session_id, .. }
+            | Self::ValidateBatch { session_id, .. }
+            | Self::ApplyBatch { session_id, .. } => Some(session_id),
+            Self::CreateSession { .. }
+            | Self::DestroySessions { .. }
+            | Self::TuneNodesByKind { .. }
+            | Self::ListSessions { .. }
+            | Self::ListNodes
+            | Self::GetPermissions
+            | Self::SetReadOnly { .. } => None,
+        }
+    }
+
+    /// Whether the request changes server state, and so is refused in read-only mode.
+    ///
+    /// Dry runs and validation count as queries. `SetReadOnly` is not a mutation, so
+    /// read-only mode can always be left.
+    pub const fn is_mutation(&self) -> bool {
+        match self {
+            Self::CreateSession { .. }
+            | Self::DestroySession { .. }
+            | Self::AddNode { .. }
+            | Self::RemoveNode { .. }
+            | Self::Connect { .. }
+            | Self::Disconnect { .. }
+            | Self::AddPin { .. }
+            | Self::RemovePin { .. }
+            | Self::TuneNode { .. }
+            | Self::TuneNodeAsync { .. }
+            | Self::SetUiMetadata { .. }
+            | Self::SetDebugMode { .. }
+            | Self::SetBreakpoint { .. }
+            | Self::StepConnection { .. }
+            | Self::EditQueuedPacket { .. }
+            | Self::ApplyBatch { .. } => true,
+            Self::DestroySessions { dry_run, .. } | Self::TuneNodesByKind { dry_run, .. } => {
+                !*dry_run
+            },
+            Self::ListSessions { .. }
+            | Self::ListNodes
+            | Self::QueryNode { .. }
+            | Self::GetPipeline { .. }
+            | Self::GetSessionCost { .. }
+            | Self::TapConnection { .. }
+            | Self::InspectConnection { .. }
+            | Self::ValidateBatch { .. }
+            | Self::GetPermissions
+            | Self::SetReadOnly { .. } => false,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
@@ -244,6 +561,12 @@ pub enum BatchOperation {
         #[serde(skip_serializing_if = "Option::is_none")]
         #[ts(type = "JsonValue")]
         params: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        label: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        notes: Option<String>,
     },
     RemoveNode {
         node_id: String,
@@ -266,6 +589,43 @@ pub enum BatchOperation {
 
 pub type Request = Message<RequestPayload>;
 
+/// Response actions a successful reply to each request carries, keyed by `RequestPayload`
+/// variant name (the request action is its lowercase form). Any request may also be
+/// answered with `error`, and mutating requests with `readonly`; fire-and-forget requests
+/// map to no response at all.
+///
+/// Generated clients use this to narrow response types per request.
+pub const REQUEST_RESPONSES: &[(&str, &[&str])] = &[
+    ("CreateSession", &["sessioncreated"]),
+    ("DestroySession", &["sessiondestroyed"]),
+    ("DestroySessions", &["sessionsdestroyed"]),
+    ("ListSessions", &["sessionslisted"]),
+    ("ListNodes", &["nodeslisted"]),
+    ("AddNode", &["success"]),
+    ("RemoveNode", &["success"]),
+    ("Connect", &["success"]),
+    ("Disconnect", &["success"]),
+    ("AddPin", &["pinadded"]),
+    ("RemovePin", &["success"]),
+    ("TuneNode", &["success"]),
+    ("TuneNodeAsync", &[]),
+    ("QueryNode", &["queryresult"]),
+    ("TuneNodesByKind", &["nodestuned"]),
+    ("SetUiMetadata", &["success"]),
+    ("GetPipeline", &["pipeline"]),
+    ("GetSessionCost", &["sessioncost"]),
+    ("TapConnection", &["connectiontapped"]),
+    ("SetDebugMode", &["success"]),
+    ("SetBreakpoint", &["success"]),
+    ("StepConnection", &["success"]),
+    ("InspectConnection", &["connectionqueue"]),
+    ("EditQueuedPacket", &["success"]),
+    ("ValidateBatch", &["validationresult"]),
+    ("ApplyBatch", &["batchapplied"]),
+    ("GetPermissions", &["permissions"]),
+    ("SetReadOnly", &["success"]),
+];
+
 // --- Server-to-Client Payloads (Responses & Events) ---
 
 // Allowed: This is an API contract where explicit boolean fields provide clarity
@@ -287,11 +647,12 @@ pub struct PermissionsInfo {
     pub write_samples: bool,
     pub delete_samples: bool,
     pub access_all_sessions: bool,
+    pub view_secrets: bool,
     pub upload_assets: bool,
     pub delete_assets: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, TS)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 #[ts(export)]
 #[serde(tag = "action")]
 #[serde(rename_all = "lowercase")]
@@ -306,8 +667,17 @@ pub enum ResponsePayload {
     SessionDestroyed {
         session_id: String,
     },
+    /// Sessions destroyed (or, for a dry run, matched) by `DestroySessions`
+    SessionsDestroyed {
+        session_ids: Vec<String>,
+        dry_run: bool,
+    },
     SessionsListed {
         sessions: Vec<SessionInfo>,
+        /// Cursor for the next page; absent when this is the last page
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        next_cursor: Option<String>,
     },
     NodesListed {
         nodes: Vec<NodeDefinition>,
@@ -315,6 +685,23 @@ pub enum ResponsePayload {
     Pipeline {
         pipeline: ApiPipeline,
     },
+    SessionCost {
+        session_id: String,
+        report: CostReport,
+    },
+    /// A connection tap opened by `TapConnection`, with the limits it runs under
+    ConnectionTapped {
+        tap_id: String,
+        max_packets: u32,
+        duration_secs: u32,
+        sample_every: u32,
+        preview_bytes: u32,
+    },
+    /// Packets held on a connection, from `InspectConnection`
+    ConnectionQueue {
+        paused: bool,
+        packets: Vec<QueuedPacketSummary>,
+    },
     ValidationResult {
         errors: Vec<ValidationError>,
     },
@@ -322,6 +709,22 @@ pub enum ResponsePayload {
         success: bool,
         errors: Vec<String>,
     },
+    QueryResult {
+        node_id: String,
+        kind: String,
+        #[ts(type = "JsonValue")]
+        result: serde_json::Value,
+    },
+    /// The input pin created by `AddPin`
+    PinAdded {
+        node_id: String,
+        pin_name: String,
+    },
+    /// Nodes tuned (or, for a dry run, matched) by `TuneNodesByKind`
+    NodesTuned {
+        nodes: Vec<SessionNode>,
+        dry_run: bool,
+    },
     Permissions {
         role: String,
         permissions: PermissionsInfo,
@@ -330,6 +733,14 @@ pub enum ResponsePayload {
     Error {
         message: String,
     },
+    /// The request would change server state while the server is in read-only mode
+    ReadOnly {
+        message: String,
+        /// The operator's banner, if one was set
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        banner: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
@@ -339,6 +750,10 @@ pub struct ValidationError {
     pub message: String,
     pub node_id: Option<String>,
     pub connection_id: Option<String>,
+    /// JSON Pointer into the node's params, for param schema violations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub path: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
@@ -357,6 +772,97 @@ pub struct SessionInfo {
     pub name: Option<String>,
     /// ISO 8601 formatted timestamp when the session was created
     pub created_at: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[ts(optional, as = "Option<Vec<String>>")]
+    pub tags: Vec<String>,
+    /// ISO 8601 formatted timestamp of the last pipeline change or control message
+    pub last_activity_at: String,
+}
+
+/// Sort key for `ListSessions`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSortKey {
+    /// Session creation time
+    #[default]
+    CreatedAt,
+    /// Time of the last pipeline change or control message
+    Activity,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filtering, sorting and pagination options for `ListSessions`.
+///
+/// Pagination is keyset-based: pass the previous response's `next_cursor` back as `cursor`
+/// with the same filters and sort. Cursors stay valid while sessions come and go, though
+/// sorting by activity may move a session across pages between requests.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SessionListQuery {
+    /// Only sessions carrying all of these tags
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[ts(optional, as = "Option<Vec<String>>")]
+    pub tags: Vec<String>,
+    /// Case-insensitive substring match on session id, name and tags
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub search: Option<String>,
+    #[ts(optional, as = "Option<SessionSortKey>")]
+    pub sort: SessionSortKey,
+    #[ts(optional, as = "Option<SortOrder>")]
+    pub order: SortOrder,
+    /// Maximum number of sessions per page (unlimited when absent)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub limit: Option<usize>,
+    /// Opaque cursor from a previous `SessionsListed` response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cursor: Option<String>,
+}
+
+/// Selects sessions for bulk operations. Every criterion that is set must match.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SessionFilter {
+    /// Only sessions carrying all of these tags
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[ts(optional, as = "Option<Vec<String>>")]
+    pub tags: Vec<String>,
+    /// Glob matched against the session name (e.g. "load-test-*")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name_pattern: Option<String>,
+    /// Only sessions created at least this many seconds ago
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub older_than_secs: Option<u64>,
+}
+
+impl SessionFilter {
+    /// True when no criterion is set, i.e. the filter matches every session.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.name_pattern.is_none() && self.older_than_secs.is_none()
+    }
+}
+
+/// A node within a session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, TS)]
+#[ts(export)]
+pub struct SessionNode {
+    pub session_id: String,
+    pub node_id: String,
 }
 
 pub type Response = Message<ResponsePayload>;
@@ -407,6 +913,11 @@ pub enum EventPayload {
     SessionDestroyed {
         session_id: String,
     },
+    /// Final resource usage of a session, sent right before `SessionDestroyed`.
+    SessionCostReport {
+        session_id: String,
+        report: CostReport,
+    },
     // --- Pipeline Structure Events ---
     NodeAdded {
         session_id: String,
@@ -414,6 +925,12 @@ pub enum EventPayload {
         kind: String,
         #[ts(type = "JsonValue")]
         params: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        label: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        notes: Option<String>,
     },
     NodeRemoved {
         session_id: String,
@@ -433,6 +950,79 @@ pub enum EventPayload {
         to_node: String,
         to_pin: String,
     },
+    /// An input pin was created on a node with dynamic pins through `AddPin`.
+    /// Pins created implicitly by `Connect` are only reported by `ConnectionAdded`.
+    PinAdded {
+        session_id: String,
+        node_id: String,
+        pin_name: String,
+    },
+    /// An input pin was removed from a node with dynamic pins.
+    PinRemoved {
+        session_id: String,
+        node_id: String,
+        pin_name: String,
+    },
+    /// The `ui_metadata` of a node or connection was replaced.
+    UiMetadataChanged {
+        session_id: String,
+        target: UiMetadataTarget,
+        #[ts(type = "JsonValue")]
+        ui_metadata: Option<serde_json::Value>,
+    },
+    /// A node was added using a deprecated or renamed kind.
+    /// Renamed kinds are resolved to their replacement before the node is created, so the
+    /// preceding NodeAdded event already carries the current kind.
+    NodeKindDeprecated {
+        session_id: String,
+        node_id: String,
+        /// The kind as sent by the client
+        kind: String,
+        /// The kind the node was created as, if `kind` is an alias
+        #[ts(optional)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        replacement: Option<String>,
+        message: String,
+    },
+    /// The server entered or left read-only mode. Sent to every client.
+    ReadOnlyChanged {
+        enabled: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        message: Option<String>,
+    },
+    /// Compact summary of a session's pipeline structure.
+    /// Sent after every structural change and periodically, so clients can detect drift
+    /// (e.g. missed NodeAdded/ConnectionAdded events) and re-fetch the full pipeline only
+    /// when their local view no longer matches.
+    PipelineSnapshot {
+        session_id: String,
+        /// Incremented on every structural change; a gap means events were missed
+        revision: u64,
+        /// Order-independent hash of nodes, params and connections (hex-encoded)
+        hash: String,
+        node_count: usize,
+        connection_count: usize,
+    },
+    // --- Connection Tap Events ---
+    /// A packet seen by a connection tap. Sent only to the client that opened the tap.
+    ConnectionTapPacket {
+        session_id: String,
+        tap_id: String,
+        /// 1-based position of the packet among all packets seen since the tap opened
+        index: u64,
+        packet: TappedPacket,
+        /// RFC 3339 formatted time the packet was seen
+        timestamp: String,
+    },
+    /// A connection tap ended. Sent only to the client that opened the tap.
+    ConnectionTapEnded {
+        session_id: String,
+        tap_id: String,
+        reason: TapEndReason,
+        /// Packet events sent by the tap
+        packets_sent: u64,
+    },
     // --- Telemetry Events ---
     /// Telemetry event from a node (transcription results, VAD events, LLM responses, etc.).
     /// The data payload contains event-specific fields including event_type for filtering.
@@ -455,6 +1045,77 @@ pub enum EventPayload {
     },
 }
 
+/// Summary of a packet seen by a connection tap.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct TappedPacket {
+    /// Packet variant: `audio`, `text`, `transcription`, `custom` or `binary`
+    pub packet_type: String,
+    /// Binary content type, custom type ID, or audio format (e.g. "48000Hz/2ch")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub content_type: Option<String>,
+    /// Approximate payload size in bytes
+    pub size_bytes: u64,
+    pub metadata: Option<streamkit_core::types::PacketMetadata>,
+    /// Leading payload bytes when requested: text for text-like packets, lowercase hex otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub preview: Option<String>,
+    /// Whether the preview stops short of the full payload
+    pub truncated: bool,
+}
+
+/// A packet held on a paused connection.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct QueuedPacketSummary {
+    /// Identifies the packet for `StepConnection` and `EditQueuedPacket`; counts the
+    /// packets the connection has carried since it was first paused
+    pub packet_id: u64,
+    pub packet: TappedPacket,
+}
+
+/// Why a connection tap ended.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TapEndReason {
+    /// The tap sent its maximum number of packets
+    PacketLimit,
+    /// The tap's duration elapsed
+    Expired,
+    /// The tapped output went away (node removed or session destroyed)
+    Closed,
+}
+
+impl EventPayload {
+    /// The session this event belongs to; `None` for server-wide events.
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Self::NodeStateChanged { session_id, .. }
+            | Self::NodeStatsUpdated { session_id, .. }
+            | Self::NodeParamsChanged { session_id, .. }
+            | Self::SessionCreated { session_id, .. }
+            | Self::SessionDestroyed { session_id }
+            | Self::SessionCostReport { session_id, .. }
+            | Self::NodeAdded { session_id, .. }
+            | Self::NodeRemoved { session_id, .. }
+            | Self::ConnectionAdded { session_id, .. }
+            | Self::ConnectionRemoved { session_id, .. }
+            | Self::PinAdded { session_id, .. }
+            | Self::PinRemoved { session_id, .. }
+            | Self::UiMetadataChanged { session_id, .. }
+            | Self::NodeKindDeprecated { session_id, .. }
+            | Self::PipelineSnapshot { session_id, .. }
+            | Self::ConnectionTapPacket { session_id, .. }
+            | Self::ConnectionTapEnded { session_id, .. }
+            | Self::NodeTelemetry { session_id, .. } => Some(session_id),
+            Self::ReadOnlyChanged { .. } => None,
+        }
+    }
+}
+
 pub type Event = Message<EventPayload>;
 
 // --- Pipeline Types (merged from pipeline crate) ---
@@ -483,6 +1144,10 @@ pub struct Connection {
     /// How this connection handles backpressure. Defaults to `Reliable`.
     #[serde(default, skip_serializing_if = "is_default_mode")]
     pub mode: ConnectionMode,
+    /// Opaque client data (e.g. edge routing in a graph editor), stored but never interpreted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "JsonValue")]
+    pub ui_metadata: Option<serde_json::Value>,
 }
 
 #[allow(clippy::trivially_copy_pass_by_ref)] // serde skip_serializing_if requires reference
@@ -497,9 +1162,30 @@ pub struct Node {
     pub kind: String,
     #[ts(type = "JsonValue")]
     pub params: Option<serde_json::Value>,
+    /// Human-friendly display name, distinct from the node id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub label: Option<String>,
+    /// Free-form operator notes about this node instance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub notes: Option<String>,
     /// Runtime state (only populated in API responses)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<NodeState>,
+    /// Opaque client data (e.g. position in a graph editor), stored but never interpreted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "JsonValue")]
+    pub ui_metadata: Option<serde_json::Value>,
+}
+
+/// A pipeline element that carries `ui_metadata`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum UiMetadataTarget {
+    Node { node_id: String },
+    Connection { from_node: String, from_pin: String, to_node: String, to_pin: String },
 }
 
 /// The top-level structure for a pipeline definition, used by the engine and API.
@@ -572,3 +1258,82 @@ pub struct AudioAsset {
     /// Whether this is a system asset (true) or user asset (false)
     pub is_system: bool,
 }
+
+// --- Pipeline Estimates ---
+
+/// Where the resource figures of a node come from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileSource {
+    /// Declared by the operator in `[resources.profiles]`
+    Declared,
+    /// Measured from past instances of the kind (CPU only)
+    Measured,
+    /// No profile; the node's needs are not included in the totals
+    Unknown,
+}
+
+/// Estimated resource needs of one node.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct NodeEstimate {
+    pub node_id: String,
+    pub kind: String,
+    pub source: ProfileSource,
+    pub cpu_cores: f64,
+    pub ram_mb: u64,
+    pub vram_mb: u64,
+    pub requires_gpu: bool,
+}
+
+/// A model file or directory a node loads.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, TS)]
+#[ts(export)]
+pub struct ModelRequirement {
+    pub node_id: String,
+    pub path: String,
+    /// Whether the path exists on the server; missing models have to be downloaded first
+    pub present: bool,
+}
+
+/// Resource needs of a pipeline, estimated before it is instantiated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct PipelineEstimate {
+    pub cpu_cores: f64,
+    pub ram_mb: u64,
+    pub vram_mb: u64,
+    pub requires_gpu: bool,
+    pub models: Vec<ModelRequirement>,
+    /// Per-node figures, in pipeline order
+    pub nodes: Vec<NodeEstimate>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn actions(decl: &str) -> BTreeSet<String> {
+        decl.split("\"action\": \"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_request_responses_cover_the_protocol() {
+        let requests: BTreeSet<String> =
+            REQUEST_RESPONSES.iter().map(|(variant, _)| variant.to_lowercase()).collect();
+        assert_eq!(requests, actions(&RequestPayload::decl()));
+
+        let responses = actions(&ResponsePayload::decl());
+        for (variant, replies) in REQUEST_RESPONSES {
+            for reply in *replies {
+                assert!(responses.contains(*reply), "{variant} maps to unknown response '{reply}'");
+            }
+        }
+    }
+}
@@ -18,6 +18,12 @@ use serde::Deserialize;
 pub struct Step {
     pub kind: String,
     pub params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub ui_metadata: Option<serde_json::Value>,
 }
 
 /// Represents a single node in a user-facing DAG pipeline definition.
@@ -27,6 +33,12 @@ pub struct UserNode {
     pub params: Option<serde_json::Value>,
     #[serde(default)]
     pub needs: Needs,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub ui_metadata: Option<serde_json::Value>,
 }
 
 /// A single dependency with optional connection mode.
@@ -35,11 +47,16 @@ pub struct UserNode {
 pub enum NeedsDependency {
     /// Simple string: just the node name (mode defaults to Reliable)
     Simple(String),
-    /// Object with node name and optional mode
+    /// Object with node name, optional output pin, mode and connection `ui_metadata`
     WithMode {
         node: String,
+        /// Output pin on `node` to read from (defaults to `out`)
+        #[serde(default)]
+        from_pin: Option<String>,
         #[serde(default)]
         mode: ConnectionMode,
+        #[serde(default)]
+        ui_metadata: Option<serde_json::Value>,
     },
 }
 
@@ -51,12 +68,26 @@ impl NeedsDependency {
         }
     }
 
+    fn source_pin(&self) -> &str {
+        match self {
+            Self::WithMode { from_pin: Some(pin), .. } => pin,
+            _ => "out",
+        }
+    }
+
     fn mode(&self) -> ConnectionMode {
         match self {
             Self::Simple(_) => ConnectionMode::default(),
             Self::WithMode { mode, .. } => *mode,
         }
     }
+
+    fn ui_metadata(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::Simple(_) => None,
+            Self::WithMode { ui_metadata, .. } => ui_metadata.clone(),
+        }
+    }
 }
 
 /// Represents the `needs` field for DAG nodes.
@@ -132,10 +163,21 @@ fn compile_steps(
                 to_node: node_name.clone(),
                 to_pin: "in".to_string(),
                 mode: ConnectionMode::default(),
+                ui_metadata: None,
             });
         }
 
-        nodes.insert(node_name, Node { kind: step.kind, params: step.params, state: None });
+        nodes.insert(
+            node_name,
+            Node {
+                kind: step.kind,
+                params: step.params,
+                label: step.label,
+                notes: step.notes,
+                state: None,
+                ui_metadata: step.ui_metadata,
+            },
+        );
     }
 
     Pipeline { name, description, mode, nodes, connections }
@@ -155,7 +197,8 @@ fn is_bidirectional_kind(kind: &str) -> bool {
 ///
 /// Returns an error message describing the cycle if one is found.
 /// Cycles that involve bidirectional nodes (like `transport::moq::peer`) are allowed,
-/// as these nodes have separate input/output data paths.
+/// as these nodes have separate input/output data paths. Dependencies declared with
+/// `mode: feedback` are left out of the graph, so cycles they close are allowed too.
 fn detect_cycles(user_nodes: &IndexMap<String, UserNode>) -> Result<(), String> {
     use std::collections::HashSet;
 
@@ -213,13 +256,18 @@ fn detect_cycles(user_nodes: &IndexMap<String, UserNode>) -> Result<(), String>
     for (node_name, node_def) in user_nodes {
         adjacency.entry(node_name).or_default();
 
-        let dependencies: Vec<&str> = match &node_def.needs {
+        let dependencies: Vec<&NeedsDependency> = match &node_def.needs {
             Needs::None => vec![],
-            Needs::Single(dep) => vec![dep.node()],
-            Needs::Multiple(deps) => deps.iter().map(NeedsDependency::node).collect(),
+            Needs::Single(dep) => vec![dep],
+            Needs::Multiple(deps) => deps.iter().collect(),
         };
 
-        for dep_name in dependencies {
+        // Feedback edges close cycles on purpose; the engine buffers them so they can't deadlock.
+        for dep_name in dependencies
+            .into_iter()
+            .filter(|dep| dep.mode() != ConnectionMode::Feedback)
+            .map(NeedsDependency::node)
+        {
             // Edge: dep_name -> node_name (data flows from dep to node)
             // We need to find the key in user_nodes to get a reference with the right lifetime
             if let Some((key, _)) = user_nodes.get_key_value(dep_name) {
@@ -289,10 +337,11 @@ fn compile_dag(
 
             connections.push(Connection {
                 from_node: dep_name.to_string(),
-                from_pin: "out".to_string(),
+                from_pin: dep.source_pin().to_string(),
                 to_node: node_name.clone(),
                 to_pin,
                 mode: dep.mode(),
+                ui_metadata: dep.ui_metadata(),
             });
         }
     }
@@ -338,7 +387,15 @@ fn compile_dag(
                 }
             }
 
-            (name, Node { kind: def.kind, params, state: None })
+            let node = Node {
+                kind: def.kind,
+                params,
+                label: def.label,
+                notes: def.notes,
+                state: None,
+                ui_metadata: def.ui_metadata,
+            };
+            (name, node)
         })
         .collect();
 
@@ -400,6 +457,38 @@ nodes:
         );
     }
 
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::expect_used)]
+    fn test_feedback_needs_allows_cycle() {
+        let yaml = r"
+mode: dynamic
+nodes:
+  mic:
+    kind: test_source
+  aec:
+    kind: test_aec
+    needs:
+      - mic
+      - node: tts
+        mode: feedback
+  tts:
+    kind: test_tts
+    needs: aec
+";
+
+        let user_pipeline: UserPipeline = serde_saphyr::from_str(yaml).unwrap();
+        let pipeline = compile(user_pipeline).unwrap();
+
+        let feedback = pipeline
+            .connections
+            .iter()
+            .find(|c| c.from_node == "tts")
+            .expect("Should have feedback connection from tts");
+        assert_eq!(feedback.to_node, "aec");
+        assert_eq!(feedback.to_pin, "in_1");
+        assert_eq!(feedback.mode, ConnectionMode::Feedback);
+    }
+
     #[test]
     #[allow(clippy::unwrap_used)]
     fn test_invalid_needs_reference() {
@@ -829,4 +918,73 @@ nodes:
         assert_eq!(conn_b.mode, ConnectionMode::BestEffort);
         assert_eq!(conn_b.to_pin, "in_1");
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::expect_used)]
+    fn test_from_pin_in_needs() {
+        let yaml = r"
+mode: dynamic
+nodes:
+  splitter:
+    kind: test_splitter
+  main:
+    kind: test_sink
+    needs: splitter
+  side:
+    kind: test_sink
+    needs:
+      node: splitter
+      from_pin: audio
+";
+
+        let user_pipeline: UserPipeline = serde_saphyr::from_str(yaml).unwrap();
+        let pipeline = compile(user_pipeline).unwrap();
+
+        let main_conn = pipeline
+            .connections
+            .iter()
+            .find(|c| c.to_node == "main")
+            .expect("Should have connection to main");
+        assert_eq!(main_conn.from_pin, "out");
+
+        let side_conn = pipeline
+            .connections
+            .iter()
+            .find(|c| c.to_node == "side")
+            .expect("Should have connection to side");
+        assert_eq!(side_conn.from_pin, "audio");
+        assert_eq!(side_conn.to_pin, "in");
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::expect_used)]
+    fn test_ui_metadata_and_label_preserved() {
+        let yaml = r"
+nodes:
+  source:
+    kind: test_source
+    label: Microphone
+    ui_metadata:
+      position: [10, 20]
+  sink:
+    kind: test_sink
+    needs:
+      node: source
+      ui_metadata:
+        color: red
+";
+
+        let user_pipeline: UserPipeline = serde_saphyr::from_str(yaml).unwrap();
+        let pipeline = compile(user_pipeline).unwrap();
+
+        assert_eq!(
+            pipeline.nodes["source"].ui_metadata,
+            Some(serde_json::json!({"position": [10, 20]}))
+        );
+        assert_eq!(pipeline.nodes["sink"].ui_metadata, None);
+        assert_eq!(pipeline.nodes["source"].label.as_deref(), Some("Microphone"));
+        let conn = pipeline.connections.first().expect("Should have one connection");
+        assert_eq!(conn.ui_metadata, Some(serde_json::json!({"color": "red"})));
+        assert_eq!(conn.mode, ConnectionMode::Reliable);
+    }
 }
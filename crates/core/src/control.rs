@@ -14,6 +14,13 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+/// Reply channel carried by [`NodeControlMessage::Query`].
+pub type QueryReply = tokio::sync::oneshot::Sender<Result<serde_json::Value, String>>;
+
+/// Reply channel carried by [`EngineControlMessage::AddPin`] and
+/// [`EngineControlMessage::RemovePin`], answered with the affected pin name.
+pub type PinReply = tokio::sync::oneshot::Sender<Result<String, String>>;
+
 /// A message sent to a specific, running node to tune its parameters or control its lifecycle.
 #[derive(Debug, Deserialize, Serialize, TS)]
 #[ts(export)]
@@ -25,6 +32,35 @@ pub enum NodeControlMessage {
     /// Shutdown signal for graceful termination.
     /// Nodes should clean up resources and exit their run loop when receiving this.
     Shutdown,
+    /// Asks the node for runtime information, such as buffer fill or model details.
+    ///
+    /// `kind` selects what to report and is defined by each node. Nodes answer through
+    /// `reply`; unsupported kinds should get an `Err`, and dropping `reply` unanswered
+    /// is reported to the caller as "not supported". The reply channel cannot cross the
+    /// wire, so clients use the `querynode` request instead of sending this directly.
+    #[serde(skip)]
+    #[ts(skip)]
+    Query {
+        kind: String,
+        args: serde_json::Value,
+        reply: QueryReply,
+    },
+}
+
+impl NodeControlMessage {
+    /// Builds a [`NodeControlMessage::Query`] along with the receiver for its answer.
+    pub fn query(
+        kind: impl Into<String>,
+        args: serde_json::Value,
+    ) -> (Self, tokio::sync::oneshot::Receiver<Result<serde_json::Value, String>>) {
+        let (reply, rx) = tokio::sync::oneshot::channel();
+        (Self::Query { kind: kind.into(), args, reply }, rx)
+    }
+}
+
+/// Answers a query the node does not recognise.
+pub fn reject_query(kind: &str, reply: QueryReply) {
+    let _ = reply.send(Err(format!("Unsupported query '{kind}'")));
 }
 
 /// Specifies how a connection handles backpressure from slow consumers.
@@ -42,6 +78,14 @@ pub enum ConnectionMode {
     /// Useful for observer outputs (metrics, UI, debug taps) that shouldn't stall
     /// the main data flow. Dropped packets are logged and counted in metrics.
     BestEffort,
+
+    /// Loopback edge that deliberately closes a cycle in the graph (e.g. an echo-canceller
+    /// reference fed from the TTS output, or TTS state gating STT upstream).
+    /// The producer never waits on it: the connection holds at most one undelivered packet,
+    /// which is delivered before the next one and replaced by newer packets while the consumer
+    /// is busy. This one-packet delay is what keeps a cycle from deadlocking under backpressure.
+    /// Feedback edges are exempt from cycle detection and only supported in dynamic pipelines.
+    Feedback,
 }
 
 /// A message sent to the central Engine actor to modify the pipeline graph itself.
@@ -72,5 +116,19 @@ pub enum EngineControlMessage {
         node_id: String,
         message: NodeControlMessage,
     },
+    /// Creates an input pin on a node that declares a `Dynamic` pin family, before anything
+    /// is connected to it. `pin_name` lets the node pick a name when absent; `reply`
+    /// receives the name of the created pin.
+    AddPin {
+        node_id: String,
+        pin_name: Option<String>,
+        reply: Option<PinReply>,
+    },
+    /// Removes an input pin created at runtime, closing its channel.
+    RemovePin {
+        node_id: String,
+        pin_name: String,
+        reply: Option<PinReply>,
+    },
     Shutdown,
 }
@@ -0,0 +1,236 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Per-session resource accounting for chargeback.
+//!
+//! The engine keeps one [`CostLedger`] per session and hands every node a [`NodeMeter`].
+//! Compute time and bytes moved are accumulated with relaxed atomics so metering stays off
+//! the hot path's critical section; a [`CostReport`] aggregates them per node and per kind.
+//!
+//! What gets measured:
+//! - **CPU time**: time the node's task spends being polled on the async runtime, plus any
+//!   blocking work the node reports through [`NodeMeter::add_cpu_time`].
+//! - **GPU time**: only what nodes report through [`NodeMeter::add_gpu_time`]. Native plugins
+//!   report it with `OutputSender::report_gpu_time`, which the plugin host forwards here.
+//! - **Bytes in/out**: [`Packet::payload_size`](crate::types::Packet::payload_size) of
+//!   packets delivered to / produced by the node over its connections.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+/// Usage counters for a single node instance.
+#[derive(Debug, Default)]
+pub struct NodeMeter {
+    cpu_nanos: AtomicU64,
+    gpu_nanos: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl NodeMeter {
+    /// Charges compute time to the node (e.g. work done in `spawn_blocking`).
+    pub fn add_cpu_time(&self, elapsed: Duration) {
+        self.cpu_nanos.fetch_add(duration_nanos(elapsed), Ordering::Relaxed);
+    }
+
+    /// Charges GPU time to the node. Nodes that offload work to a GPU report it here.
+    pub fn add_gpu_time(&self, elapsed: Duration) {
+        self.gpu_nanos.fetch_add(duration_nanos(elapsed), Ordering::Relaxed);
+    }
+
+    /// Compute time charged to the node so far.
+    pub fn cpu_time(&self) -> Duration {
+        Duration::from_nanos(self.cpu_nanos.load(Ordering::Relaxed))
+    }
+
+    /// GPU time charged to the node so far.
+    pub fn gpu_time(&self) -> Duration {
+        Duration::from_nanos(self.gpu_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Records bytes delivered to the node's inputs.
+    pub fn add_bytes_in(&self, bytes: u64) {
+        self.bytes_in.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Records bytes produced on the node's outputs.
+    pub fn add_bytes_out(&self, bytes: u64) {
+        self.bytes_out.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, node_id: &str, kind: &str) -> NodeCost {
+        NodeCost {
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            cpu_secs: nanos_to_secs(self.cpu_nanos.load(Ordering::Relaxed)),
+            gpu_secs: nanos_to_secs(self.gpu_nanos.load(Ordering::Relaxed)),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn duration_nanos(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[allow(clippy::cast_precision_loss)] // Nanosecond totals stay well within f64's exact range
+fn nanos_to_secs(nanos: u64) -> f64 {
+    nanos as f64 / 1e9
+}
+
+/// Resource usage of one node instance over the session.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NodeCost {
+    pub node_id: String,
+    pub kind: String,
+    pub cpu_secs: f64,
+    pub gpu_secs: f64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// Resource usage summed over all nodes of one kind.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct KindCost {
+    pub kind: String,
+    /// Number of node instances of this kind over the session's lifetime
+    pub instances: u32,
+    pub cpu_secs: f64,
+    pub gpu_secs: f64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// Cumulative resource usage of a session.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CostReport {
+    /// Wall-clock seconds since the session's engine started
+    pub wall_secs: f64,
+    pub cpu_secs: f64,
+    pub gpu_secs: f64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// Usage per node kind, sorted by kind
+    pub by_kind: Vec<KindCost>,
+    /// Usage per node instance, in the order nodes were started. Nodes removed from the
+    /// pipeline are kept, so a node ID may appear more than once if it was re-added.
+    pub nodes: Vec<NodeCost>,
+}
+
+/// Collects the meters of every node started in a session.
+#[derive(Debug)]
+pub struct CostLedger {
+    started_at: Instant,
+    nodes: Mutex<Vec<(String, String, Arc<NodeMeter>)>>,
+}
+
+impl Default for CostLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostLedger {
+    pub fn new() -> Self {
+        Self { started_at: Instant::now(), nodes: Mutex::new(Vec::new()) }
+    }
+
+    /// Creates the meter for a newly started node.
+    pub fn register(&self, node_id: &str, kind: &str) -> Arc<NodeMeter> {
+        let meter = Arc::new(NodeMeter::default());
+        if let Ok(mut nodes) = self.nodes.lock() {
+            nodes.push((node_id.to_string(), kind.to_string(), Arc::clone(&meter)));
+        }
+        meter
+    }
+
+    /// Returns the meter of the most recently started node with this ID.
+    pub fn meter(&self, node_id: &str) -> Option<Arc<NodeMeter>> {
+        let nodes = self.nodes.lock().ok()?;
+        nodes.iter().rev().find(|(id, _, _)| id == node_id).map(|(_, _, meter)| Arc::clone(meter))
+    }
+
+    /// Builds a report of everything metered so far.
+    pub fn report(&self) -> CostReport {
+        let nodes: Vec<NodeCost> = self
+            .nodes
+            .lock()
+            .map(|nodes| nodes.iter().map(|(id, kind, meter)| meter.snapshot(id, kind)).collect())
+            .unwrap_or_default();
+
+        let mut by_kind: BTreeMap<&str, KindCost> = BTreeMap::new();
+        for node in &nodes {
+            let entry = by_kind
+                .entry(&node.kind)
+                .or_insert_with(|| KindCost { kind: node.kind.clone(), ..KindCost::default() });
+            entry.instances += 1;
+            entry.cpu_secs += node.cpu_secs;
+            entry.gpu_secs += node.gpu_secs;
+            entry.bytes_in += node.bytes_in;
+            entry.bytes_out += node.bytes_out;
+        }
+        let by_kind: Vec<KindCost> = by_kind.into_values().collect();
+
+        CostReport {
+            wall_secs: self.started_at.elapsed().as_secs_f64(),
+            cpu_secs: by_kind.iter().map(|k| k.cpu_secs).sum(),
+            gpu_secs: by_kind.iter().map(|k| k.gpu_secs).sum(),
+            bytes_in: by_kind.iter().map(|k| k.bytes_in).sum(),
+            bytes_out: by_kind.iter().map(|k| k.bytes_out).sum(),
+            by_kind,
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_aggregates_by_kind() {
+        let ledger = CostLedger::new();
+        let stt_a = ledger.register("stt_a", "plugin::native::whisper");
+        let stt_b = ledger.register("stt_b", "plugin::native::whisper");
+        let gain = ledger.register("gain", "audio::gain");
+
+        stt_a.add_cpu_time(Duration::from_millis(1500));
+        stt_b.add_cpu_time(Duration::from_millis(500));
+        stt_b.add_gpu_time(Duration::from_secs(2));
+        gain.add_bytes_in(3840);
+        gain.add_bytes_out(3840);
+
+        let report = ledger.report();
+        assert_eq!(report.nodes.len(), 3);
+        assert_eq!(report.by_kind.len(), 2);
+        assert_eq!(report.by_kind[0].kind, "audio::gain");
+        let whisper = &report.by_kind[1];
+        assert_eq!(whisper.instances, 2);
+        assert!((whisper.cpu_secs - 2.0).abs() < 1e-9);
+        assert!((report.gpu_secs - 2.0).abs() < 1e-9);
+        assert_eq!(report.bytes_in, 3840);
+    }
+
+    #[test]
+    fn test_meter_lookup_prefers_latest_instance() {
+        let ledger = CostLedger::new();
+        let first = ledger.register("node", "core::passthrough");
+        first.add_bytes_out(10);
+        let second = ledger.register("node", "core::passthrough");
+
+        let found = ledger.meter("node").expect("registered");
+        assert!(Arc::ptr_eq(&found, &second));
+        assert!(ledger.meter("missing").is_none());
+        // The removed instance still counts towards the session total.
+        assert_eq!(ledger.report().bytes_out, 10);
+    }
+}
@@ -14,7 +14,8 @@ use crate::types::Packet;
 /// Helper functions for parsing node configuration from JSON values.
 pub mod config_helpers {
     use super::StreamKitError;
-    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
 
     /// Parses configuration from an optional JSON value, using defaults if not provided.
     /// This is the preferred approach for nodes with sensible defaults.
@@ -70,6 +71,24 @@ pub mod config_helpers {
             },
         )
     }
+
+    /// Overlays the fields present in `patch` onto `current`, for applying partial
+    /// `UpdateParams` payloads. Fields missing from `patch` keep their current values.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the failure if the merged value does not deserialize back
+    /// into `T` (e.g. a patched field has the wrong type).
+    pub fn merge_params<T>(current: &T, patch: &serde_json::Value) -> Result<T, String>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut merged = serde_json::to_value(current).map_err(|e| e.to_string())?;
+        if let (Some(target), Some(patch)) = (merged.as_object_mut(), patch.as_object()) {
+            target.extend(patch.clone());
+        }
+        serde_json::from_value(merged).map_err(|e| e.to_string())
+    }
 }
 
 /// Helper functions for common packet processing patterns.
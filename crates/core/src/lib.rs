@@ -15,11 +15,18 @@
 //! - [`state`]: Node state machine and lifecycle tracking
 //! - [`stats`]: Node statistics collection and reporting
 //! - [`telemetry`]: Telemetry event emission for observability
+//! - [`cost`]: Per-session compute and traffic accounting
 //! - [`control`]: Control messages for node and engine management
 //! - [`error`]: Error types and handling
 //! - [`resource_manager`]: Shared resource management (ML models, GPU contexts)
 //! - [`packet_meta`]: Packet type metadata and compatibility checking
+//! - [`redaction`]: Masking of sensitive node parameters
+//! - [`seed`]: Seed convention for reproducible stochastic nodes
+//! - [`media_clock`]: Per-session media clock for cross-track A/V sync
+//! - [`lookahead`]: Engine-provided delayed and early-peek views of an input
 //! - [`moq_gateway`]: MoQ WebTransport routing infrastructure
+//! - [`webrtc_gateway`]: WHIP/WHEP signaling routing infrastructure
+//! - [`session_bridge`]: Tapping the outputs and telemetry of other sessions
 //! - [`helpers`]: Utility functions for configuration and packet processing
 //!
 //! ## Quick Start
@@ -50,20 +57,27 @@ pub use async_trait::async_trait;
 
 // Module declarations
 pub mod control;
+pub mod cost;
 pub mod error;
 pub mod frame_pool;
 pub mod helpers;
+pub mod lookahead;
+pub mod media_clock;
 pub mod moq_gateway;
 pub mod node;
 pub mod node_config;
 pub mod packet_meta;
 pub mod pins;
+pub mod redaction;
 pub mod registry;
 pub mod resource_manager;
+pub mod seed;
+pub mod session_bridge;
 pub mod state;
 pub mod stats;
 pub mod telemetry;
 pub mod types;
+pub mod webrtc_gateway;
 
 // Convenience re-exports for commonly used types
 // These are the most frequently used types in node implementations
@@ -77,7 +91,10 @@ pub use node::{
 };
 
 // Registry and factory
-pub use registry::{NodeDefinition, NodeRegistry};
+pub use registry::{
+    CustomTypeSchema, EnumOption, EnumProvider, EnumResolver, KindDeprecation, NodeDefinition,
+    NodeRegistry,
+};
 
 // Resource management
 pub use resource_manager::{Resource, ResourceError, ResourceKey, ResourceManager, ResourcePolicy};
@@ -89,7 +106,10 @@ pub use state::{NodeState, NodeStateUpdate, StopReason};
 pub use stats::{NodeStats, NodeStatsUpdate};
 
 // Telemetry
-pub use telemetry::{TelemetryConfig, TelemetryEmitter, TelemetryEvent};
+pub use telemetry::{
+    set_default_telemetry_limits, EventTypeLimit, TelemetryConfig, TelemetryEmitter,
+    TelemetryEvent, TelemetryLimits,
+};
 
 // Pin definitions
 pub use pins::{InputPin, OutputPin, PinCardinality};
@@ -102,6 +122,15 @@ pub use telemetry::telemetry_helpers;
 // Frame pooling (optional hot-path optimization)
 pub use frame_pool::{AudioFramePool, FramePool, PooledFrameData, PooledSamples};
 
+// Usage accounting
+pub use cost::{CostLedger, CostReport, KindCost, NodeCost, NodeMeter};
+
+// Session media clock
+pub use media_clock::{MediaClock, TrackClock, TrackKind, TrackSync};
+
+// Lookahead inputs
+pub use lookahead::{LookaheadEvent, LookaheadSpec};
+
 // Node buffer configuration
 pub use node_config::{
     get_codec_channel_capacity, get_demuxer_buffer_size, get_moq_peer_channel_capacity,
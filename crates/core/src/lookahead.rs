@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Bounded lookahead for nodes that decide on a packet only after seeing what follows it.
+//!
+//! Loudness normalization, chaptering and scene detection all want to look a few seconds
+//! ahead of the packet they are about to emit. Rather than each node keeping its own pair of
+//! buffers, a node declares a [`LookaheadSpec`] for one input pin through
+//! [`ProcessorNode::lookahead`](crate::node::ProcessorNode::lookahead). The engine then feeds
+//! that pin through a [`LookaheadBuffer`] and hands the node a single ordered stream of
+//! [`LookaheadEvent`]s in [`NodeContext::lookahead`](crate::node::NodeContext::lookahead)
+//! instead of a receiver in `inputs`:
+//!
+//! - every packet arrives as [`LookaheadEvent::Peek`] as soon as it reaches the node, and
+//! - again as [`LookaheadEvent::Delayed`] once `window` worth of media has arrived after it
+//!   (or the input closed).
+//!
+//! Both views come from one channel, so when a `Delayed` packet is received the node has
+//! already been shown every packet up to `window` past it; there is nothing to synchronize.
+//!
+//! The window is measured in media time: audio frames count their sample duration, other
+//! packets their `metadata.duration_us`. Packets without a duration (text, most custom
+//! events) ride along with the media around them. `max_packets` bounds the buffer for
+//! streams that carry no durations at all.
+
+use crate::types::Packet;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default bound on the number of packets held back, whatever their duration.
+pub const DEFAULT_LOOKAHEAD_MAX_PACKETS: usize = 4096;
+
+/// A node's request for lookahead on one of its input pins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookaheadSpec {
+    /// Input pin to delay; it is delivered through `NodeContext::lookahead` instead of `inputs`
+    pub pin: String,
+    /// Media time a packet is held back before it is delivered as [`LookaheadEvent::Delayed`]
+    pub window: Duration,
+    /// Packets are released early once more than this many are held back
+    pub max_packets: usize,
+}
+
+impl LookaheadSpec {
+    pub fn new(pin: impl Into<String>, window: Duration) -> Self {
+        Self { pin: pin.into(), window, max_packets: DEFAULT_LOOKAHEAD_MAX_PACKETS }
+    }
+
+    #[must_use]
+    pub const fn with_max_packets(mut self, max_packets: usize) -> Self {
+        self.max_packets = max_packets;
+        self
+    }
+}
+
+/// One step of a lookahead stream; see the [module docs](self).
+#[derive(Debug, Clone)]
+pub enum LookaheadEvent {
+    /// A packet that just arrived, `window` ahead of the delayed stream
+    Peek(Packet),
+    /// A packet released after `window` of media followed it
+    Delayed(Packet),
+}
+
+/// Media duration of a packet in microseconds, if it has one.
+pub fn packet_duration_us(packet: &Packet) -> Option<u64> {
+    match packet {
+        Packet::Audio(frame) => frame.duration_us(),
+        Packet::Custom(custom) => custom.metadata.as_ref().and_then(|m| m.duration_us),
+        Packet::Transcription(transcription) => {
+            transcription.metadata.as_ref().and_then(|m| m.duration_us)
+        },
+        Packet::Binary { metadata, .. } => metadata.as_ref().and_then(|m| m.duration_us),
+        Packet::Text(_) => None,
+    }
+}
+
+/// The delay line behind a lookahead stream.
+///
+/// The engine runs one per declared [`LookaheadSpec`]; it is public so nodes can be tested
+/// with the same event sequence the engine would produce.
+#[derive(Debug)]
+pub struct LookaheadBuffer {
+    window_us: u64,
+    max_packets: usize,
+    held: VecDeque<(Packet, u64)>,
+    held_us: u64,
+}
+
+impl LookaheadBuffer {
+    pub fn new(spec: &LookaheadSpec) -> Self {
+        Self {
+            window_us: u64::try_from(spec.window.as_micros()).unwrap_or(u64::MAX),
+            max_packets: spec.max_packets.max(1),
+            held: VecDeque::new(),
+            held_us: 0,
+        }
+    }
+
+    /// Accepts the next input packet and appends the events it causes to `out`: its `Peek`,
+    /// followed by any packets it pushes out of the window.
+    pub fn push(&mut self, packet: Packet, out: &mut Vec<LookaheadEvent>) {
+        let duration_us = packet_duration_us(&packet).unwrap_or(0);
+        out.push(LookaheadEvent::Peek(packet.clone()));
+        self.held.push_back((packet, duration_us));
+        self.held_us = self.held_us.saturating_add(duration_us);
+
+        while let Some(&(_, front_us)) = self.held.front() {
+            let after_front_us = self.held_us - front_us;
+            if after_front_us < self.window_us && self.held.len() <= self.max_packets {
+                break;
+            }
+            self.release_front(out);
+        }
+    }
+
+    /// Releases everything still held back, for when the input has closed.
+    pub fn finish(&mut self, out: &mut Vec<LookaheadEvent>) {
+        while !self.held.is_empty() {
+            self.release_front(out);
+        }
+    }
+
+    /// Media time currently held back.
+    pub const fn held(&self) -> Duration {
+        Duration::from_micros(self.held_us)
+    }
+
+    fn release_front(&mut self, out: &mut Vec<LookaheadEvent>) {
+        if let Some((packet, duration_us)) = self.held.pop_front() {
+            self.held_us -= duration_us;
+            out.push(LookaheadEvent::Delayed(packet));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AudioFrame;
+
+    /// 10 ms of mono audio at 1 kHz whose single sample identifies it.
+    fn audio(id: u16) -> Packet {
+        let mut samples = vec![0.0; 10];
+        samples[0] = f32::from(id);
+        Packet::Audio(AudioFrame::new(1000, 1, samples))
+    }
+
+    fn describe(events: &[LookaheadEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| {
+                let (kind, packet) = match event {
+                    LookaheadEvent::Peek(p) => ("peek", p),
+                    LookaheadEvent::Delayed(p) => ("delayed", p),
+                };
+                let id = match packet {
+                    Packet::Audio(frame) => frame.samples()[0].to_string(),
+                    Packet::Text(text) => text.to_string(),
+                    _ => "?".to_string(),
+                };
+                format!("{kind} {id}")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delays_by_media_time() {
+        let mut buffer = LookaheadBuffer::new(&LookaheadSpec::new("in", Duration::from_millis(20)));
+        let mut events = Vec::new();
+        for id in 0..4 {
+            buffer.push(audio(id), &mut events);
+        }
+        assert_eq!(buffer.held(), Duration::from_millis(20));
+        buffer.finish(&mut events);
+        assert_eq!(
+            describe(&events),
+            [
+                "peek 0",
+                "peek 1",
+                "peek 2",
+                "delayed 0",
+                "peek 3",
+                "delayed 1",
+                "delayed 2",
+                "delayed 3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_untimed_packets_ride_along() {
+        let mut buffer = LookaheadBuffer::new(&LookaheadSpec::new("in", Duration::from_millis(10)));
+        let mut events = Vec::new();
+        buffer.push(audio(0), &mut events);
+        buffer.push(Packet::Text("note".into()), &mut events);
+        buffer.push(audio(1), &mut events);
+        assert_eq!(
+            describe(&events),
+            ["peek 0", "peek note", "peek 1", "delayed 0", "delayed note"]
+        );
+
+        // Zero window passes every packet straight through, in order.
+        let mut passthrough = LookaheadBuffer::new(&LookaheadSpec::new("in", Duration::ZERO));
+        events.clear();
+        passthrough.push(Packet::Text("a".into()), &mut events);
+        assert_eq!(describe(&events), ["peek a", "delayed a"]);
+    }
+
+    #[test]
+    fn test_max_packets_bounds_untimed_streams() {
+        let spec = LookaheadSpec::new("in", Duration::from_secs(5)).with_max_packets(2);
+        let mut buffer = LookaheadBuffer::new(&spec);
+        let mut events = Vec::new();
+        for text in ["a", "b", "c"] {
+            buffer.push(Packet::Text(text.into()), &mut events);
+        }
+        assert_eq!(describe(&events), ["peek a", "peek b", "peek c", "delayed a"]);
+    }
+}
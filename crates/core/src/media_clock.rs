@@ -0,0 +1,279 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Per-session media clock for aligning tracks.
+//!
+//! Every pipeline gets one [`MediaClock`]. Nodes that care about cross-track timing (muxers,
+//! compositors, sync monitors) register a [`TrackClock`] per track and feed it the packet
+//! timestamps they see. The clock tracks each track's offset against session time, smoothing
+//! out arrival jitter while following slow drift, so two tracks produced by independent
+//! sources can be mapped onto one shared timeline instead of each node guessing from its
+//! own timestamps.
+//!
+//! Session time is microseconds since the clock was created (when the pipeline started).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Weight of a new observation in the smoothed offset. Small enough that per-packet jitter
+/// averages out, large enough that drift of a few hundred ppm is followed within seconds.
+const OFFSET_SMOOTHING: f64 = 1.0 / 64.0;
+
+/// Observations before the smoothed offset has settled; drift is measured from this point.
+const WARMUP_OBSERVATIONS: u64 = 64;
+
+/// What kind of media a track carries; used to report audio/video skew.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackKind {
+    Audio,
+    Video,
+    Other,
+}
+
+/// Point-in-time view of a registered track, for telemetry and queries.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackSync {
+    pub track_id: String,
+    pub kind: TrackKind,
+    /// Current smoothed offset from media timestamps to session time, in microseconds
+    pub offset_us: i64,
+    /// Estimated clock drift of the track relative to session time, in parts per million
+    pub drift_ppm: f64,
+    /// Number of timestamps observed so far
+    pub observations: u64,
+}
+
+#[derive(Debug)]
+struct TrackState {
+    kind: TrackKind,
+    baseline_pts_us: u64,
+    baseline_offset_us: f64,
+    offset_us: f64,
+    last_pts_us: u64,
+    last_mapped_us: u64,
+    observations: u64,
+}
+
+impl TrackState {
+    fn new(kind: TrackKind) -> Self {
+        Self {
+            kind,
+            baseline_pts_us: 0,
+            baseline_offset_us: 0.0,
+            offset_us: 0.0,
+            last_pts_us: 0,
+            last_mapped_us: 0,
+            observations: 0,
+        }
+    }
+
+    // Microsecond values stay far below 2^52 for any realistic session, so the f64 math
+    // below is exact where it matters.
+    #[allow(clippy::cast_precision_loss)]
+    fn observe(&mut self, pts_us: u64, now_us: u64) -> u64 {
+        let offset = now_us as f64 - pts_us as f64;
+        self.observations += 1;
+        // Plain running mean until warmed up, so the estimate doesn't lean on the first packet.
+        let weight = (1.0 / self.observations as f64).max(OFFSET_SMOOTHING);
+        self.offset_us += (offset - self.offset_us) * weight;
+        if self.observations == WARMUP_OBSERVATIONS {
+            self.baseline_pts_us = pts_us;
+            self.baseline_offset_us = self.offset_us;
+        }
+        self.last_pts_us = pts_us;
+
+        // Offset corrections must never move a track backwards on the session timeline.
+        let mapped = self.map(pts_us).max(self.last_mapped_us);
+        self.last_mapped_us = mapped;
+        mapped
+    }
+
+    // See `observe` for why the conversions are safe.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    fn map(&self, pts_us: u64) -> u64 {
+        (pts_us as f64 + self.offset_us).round().max(0.0) as u64
+    }
+
+    #[allow(clippy::cast_precision_loss)] // See `observe`
+    fn drift_ppm(&self) -> f64 {
+        let span = self.last_pts_us.saturating_sub(self.baseline_pts_us);
+        if self.observations <= WARMUP_OBSERVATIONS || span == 0 {
+            return 0.0;
+        }
+        (self.offset_us - self.baseline_offset_us) / span as f64 * 1_000_000.0
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // See `observe`
+    fn snapshot(&self, track_id: &str) -> TrackSync {
+        TrackSync {
+            track_id: track_id.to_string(),
+            kind: self.kind,
+            offset_us: self.offset_us.round() as i64,
+            drift_ppm: self.drift_ppm(),
+            observations: self.observations,
+        }
+    }
+}
+
+/// Shared session clock. Cheap to clone via `Arc`; all methods take `&self`.
+#[derive(Debug)]
+pub struct MediaClock {
+    epoch: Instant,
+    tracks: Mutex<HashMap<String, TrackState>>,
+}
+
+impl Default for MediaClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaClock {
+    /// Creates a clock whose session time starts now.
+    pub fn new() -> Self {
+        Self { epoch: Instant::now(), tracks: Mutex::new(HashMap::new()) }
+    }
+
+    /// Current session time in microseconds.
+    pub fn now_us(&self) -> u64 {
+        u64::try_from(self.epoch.elapsed().as_micros()).unwrap_or(u64::MAX)
+    }
+
+    /// Registers a track and returns its handle. The track is unregistered when the
+    /// handle is dropped; registering an existing `track_id` starts it over.
+    pub fn register_track(
+        self: &Arc<Self>,
+        track_id: impl Into<String>,
+        kind: TrackKind,
+    ) -> TrackClock {
+        let track_id = track_id.into();
+        if let Ok(mut tracks) = self.tracks.lock() {
+            tracks.insert(track_id.clone(), TrackState::new(kind));
+        }
+        TrackClock { clock: Arc::clone(self), track_id }
+    }
+
+    /// Snapshot of all registered tracks, sorted by track ID.
+    pub fn tracks(&self) -> Vec<TrackSync> {
+        let Ok(tracks) = self.tracks.lock() else {
+            return Vec::new();
+        };
+        let mut snapshot: Vec<TrackSync> =
+            tracks.iter().map(|(id, track)| track.snapshot(id)).collect();
+        snapshot.sort_by(|a, b| a.track_id.cmp(&b.track_id));
+        snapshot
+    }
+
+    /// How far the first video track lags the first audio track, in microseconds
+    /// (negative if video leads). `None` until both have been observed.
+    pub fn av_skew_us(&self) -> Option<i64> {
+        let tracks = self.tracks();
+        let offset_of = |kind| {
+            tracks.iter().find(|t| t.kind == kind && t.observations > 0).map(|t| t.offset_us)
+        };
+        Some(offset_of(TrackKind::Video)? - offset_of(TrackKind::Audio)?)
+    }
+
+    fn observe_at(&self, track_id: &str, pts_us: u64, now_us: u64) -> u64 {
+        let Ok(mut tracks) = self.tracks.lock() else {
+            return pts_us;
+        };
+        tracks.get_mut(track_id).map_or(pts_us, |track| track.observe(pts_us, now_us))
+    }
+}
+
+/// A track's registration on the session [`MediaClock`].
+#[derive(Debug)]
+pub struct TrackClock {
+    clock: Arc<MediaClock>,
+    track_id: String,
+}
+
+impl TrackClock {
+    /// Records that a packet with media timestamp `pts_us` is being handled now, and
+    /// returns that timestamp mapped onto session time. Mapped times never go backwards.
+    pub fn observe(&self, pts_us: u64) -> u64 {
+        self.clock.observe_at(&self.track_id, pts_us, self.clock.now_us())
+    }
+
+    /// Maps a media timestamp onto session time using the current offset, without
+    /// recording an observation. Returns `None` before the first observation.
+    pub fn to_session_time(&self, pts_us: u64) -> Option<u64> {
+        let tracks = self.clock.tracks.lock().ok()?;
+        let track = tracks.get(&self.track_id).filter(|t| t.observations > 0)?;
+        Some(track.map(pts_us))
+    }
+
+    /// Current sync state of this track.
+    pub fn sync(&self) -> Option<TrackSync> {
+        let tracks = self.clock.tracks.lock().ok()?;
+        tracks.get(&self.track_id).map(|t| t.snapshot(&self.track_id))
+    }
+
+    /// The session clock this track is registered on.
+    pub fn clock(&self) -> &Arc<MediaClock> {
+        &self.clock
+    }
+}
+
+impl Drop for TrackClock {
+    fn drop(&mut self) {
+        if let Ok(mut tracks) = self.clock.tracks.lock() {
+            tracks.remove(&self.track_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_absorbs_jitter_and_stays_monotonic() {
+        let clock = Arc::new(MediaClock::new());
+        let track = clock.register_track("audio", TrackKind::Audio);
+
+        // 20 ms packets whose arrival alternates between 2 ms early and 2 ms late.
+        let mut last = 0;
+        for i in 0..200u64 {
+            let pts = i * 20_000;
+            let now = 1_000_000 + pts + if i % 2 == 0 { 0 } else { 4_000 };
+            let mapped = clock.observe_at("audio", pts, now);
+            assert!(mapped >= last, "mapped time went backwards at packet {i}");
+            last = mapped;
+        }
+
+        let sync = track.sync().expect("track registered");
+        assert!((1_000_000..=1_004_000).contains(&sync.offset_us), "offset {}", sync.offset_us);
+        assert!(sync.drift_ppm.abs() < 100.0, "drift {}", sync.drift_ppm);
+    }
+
+    #[test]
+    fn test_drift_and_av_skew() {
+        let clock = Arc::new(MediaClock::new());
+        let audio = clock.register_track("audio", TrackKind::Audio);
+        let _video = clock.register_track("video", TrackKind::Video);
+        assert_eq!(clock.av_skew_us(), None);
+
+        // Video timestamps run 0.1% slow relative to session time and start 40 ms later.
+        for i in 0..2_000u64 {
+            let now = i * 10_000;
+            clock.observe_at("audio", now, now);
+            clock.observe_at("video", now * 999 / 1000, now + 40_000);
+        }
+
+        let tracks = clock.tracks();
+        assert_eq!(tracks.len(), 2);
+        let video = &tracks[1];
+        assert!((500.0..1_100.0).contains(&video.drift_ppm), "drift {}", video.drift_ppm);
+        let skew = clock.av_skew_us().expect("both tracks observed");
+        assert!(skew > 40_000, "skew {skew}");
+
+        drop(audio);
+        assert_eq!(clock.tracks().len(), 1);
+    }
+}
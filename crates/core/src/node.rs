@@ -11,11 +11,14 @@
 //! - [`OutputSender`]: Handle for sending packets to downstream nodes
 
 use crate::control::NodeControlMessage;
+use crate::cost::NodeMeter;
 use crate::error::StreamKitError;
+use crate::lookahead::{LookaheadEvent, LookaheadSpec};
+use crate::media_clock::MediaClock;
 use crate::pins::{InputPin, OutputPin, PinManagementMessage, PinUpdate};
 use crate::state::NodeStateUpdate;
 use crate::stats::NodeStatsUpdate;
-use crate::telemetry::TelemetryEvent;
+use crate::telemetry::{TelemetryEmitter, TelemetryEvent, TelemetryLimits};
 use crate::types::Packet;
 use crate::AudioFramePool;
 use async_trait::async_trait;
@@ -43,9 +46,40 @@ pub enum OutputRouting {
 pub struct OutputSender {
     /// Node name as Arc<str> to avoid cloning allocations
     node_name: Arc<str>,
-    routing: OutputRouting,
-    /// Cached pin names as Arc<str> to avoid repeated allocations
-    pin_name_cache: HashMap<String, Arc<str>>,
+    routes: RouteTable,
+    /// Index of the route used by the previous send; nodes usually send on one pin.
+    last_route: usize,
+}
+
+/// Output pins resolved once at construction, so a send is a short scan over a few pins
+/// (usually hitting `last_route` first) instead of hashing the pin name every packet.
+#[derive(Clone)]
+enum RouteTable {
+    Direct(Vec<(Arc<str>, mpsc::Sender<Packet>)>),
+    /// Pin names are interned on first use and reused for every later message.
+    Routed {
+        engine_tx: mpsc::Sender<RoutedPacketMessage>,
+        pins: Vec<Arc<str>>,
+    },
+}
+
+impl RouteTable {
+    fn pin(&self, index: usize) -> Option<&str> {
+        match self {
+            Self::Direct(routes) => routes.get(index).map(|(pin, _)| pin.as_ref()),
+            Self::Routed { pins, .. } => pins.get(index).map(AsRef::as_ref),
+        }
+    }
+
+    fn find(&self, pin_name: &str, hint: usize) -> Option<usize> {
+        if self.pin(hint) == Some(pin_name) {
+            return Some(hint);
+        }
+        match self {
+            Self::Direct(routes) => routes.iter().position(|(pin, _)| pin.as_ref() == pin_name),
+            Self::Routed { pins, .. } => pins.iter().position(|pin| pin.as_ref() == pin_name),
+        }
+    }
 }
 
 /// Error returned by [`OutputSender::send`] when a packet cannot be delivered.
@@ -64,7 +98,13 @@ impl OutputSender {
     /// Creates a new OutputSender.
     /// Note: The node_name String is converted to Arc<str> for efficient cloning on the hot path.
     pub fn new(node_name: String, routing: OutputRouting) -> Self {
-        Self { node_name: Arc::from(node_name), routing, pin_name_cache: HashMap::new() }
+        let routes = match routing {
+            OutputRouting::Direct(senders) => RouteTable::Direct(
+                senders.into_iter().map(|(pin, tx)| (Arc::from(pin), tx)).collect(),
+            ),
+            OutputRouting::Routed(engine_tx) => RouteTable::Routed { engine_tx, pins: Vec::new() },
+        };
+        Self { node_name: Arc::from(node_name), routes, last_route: 0 }
     }
 
     /// Returns the node's name.
@@ -73,14 +113,10 @@ impl OutputSender {
         &self.node_name
     }
 
-    /// Get or cache the pin name as Arc<str> to avoid repeated allocations.
-    fn get_cached_pin_name(&mut self, pin_name: &str) -> Arc<str> {
-        if let Some(cached) = self.pin_name_cache.get(pin_name) {
-            cached.clone() // O(1) Arc clone
-        } else {
-            let arc_name: Arc<str> = Arc::from(pin_name);
-            self.pin_name_cache.insert(pin_name.to_string(), arc_name.clone());
-            arc_name
+    fn closed_error(&self, pin_name: &str) -> OutputSendError {
+        OutputSendError::ChannelClosed {
+            node_name: self.node_name.to_string(),
+            pin_name: pin_name.to_string(),
         }
     }
 
@@ -97,77 +133,68 @@ impl OutputSender {
     pub async fn send(&mut self, pin_name: &str, packet: Packet) -> Result<(), OutputSendError> {
         use tokio::sync::mpsc::error::TrySendError;
 
-        match &self.routing {
-            OutputRouting::Direct(senders) => {
-                if let Some(sender) = senders.get(pin_name) {
-                    // Fast path: avoid allocating/awaiting a future if the channel has capacity.
-                    match sender.try_send(packet) {
-                        Ok(()) => {},
-                        Err(TrySendError::Full(packet)) => {
-                            if sender.send(packet).await.is_err() {
-                                // This is expected during cancellation/shutdown, so use debug level
-                                tracing::debug!(
-                                    "Directly connected channel for pin '{}' is closed.",
-                                    pin_name
-                                );
-                                return Err(OutputSendError::ChannelClosed {
-                                    node_name: self.node_name.to_string(),
-                                    pin_name: pin_name.to_string(),
-                                });
-                            }
-                        },
-                        Err(TrySendError::Closed(_packet)) => {
-                            // This is expected during cancellation/shutdown, so use debug level
-                            tracing::debug!(
-                                "Directly connected channel for pin '{}' is closed.",
-                                pin_name
-                            );
-                            return Err(OutputSendError::ChannelClosed {
-                                node_name: self.node_name.to_string(),
-                                pin_name: pin_name.to_string(),
-                            });
-                        },
-                    }
-                } else {
+        let found = self.routes.find(pin_name, self.last_route);
+        match &mut self.routes {
+            RouteTable::Direct(routes) => {
+                let Some(index) = found else {
                     // Pin not found - this is a programming error, log warning and return error
                     tracing::warn!(
                         "OutputSender::send() called with unknown pin '{}' on node '{}'. \
                          Available pins: {:?}. Packet dropped.",
                         pin_name,
                         self.node_name,
-                        senders.keys().collect::<Vec<_>>()
+                        routes.iter().map(|(pin, _)| pin.as_ref()).collect::<Vec<_>>()
                     );
                     return Err(OutputSendError::PinNotFound {
                         node_name: self.node_name.to_string(),
                         pin_name: pin_name.to_string(),
                     });
+                };
+                self.last_route = index;
+                let sender = &routes[index].1;
+
+                // Fast path: avoid allocating/awaiting a future if the channel has capacity.
+                match sender.try_send(packet) {
+                    Ok(()) => {},
+                    Err(TrySendError::Full(packet)) => {
+                        if sender.send(packet).await.is_err() {
+                            // This is expected during cancellation/shutdown, so use debug level
+                            tracing::debug!(
+                                "Directly connected channel for pin '{}' is closed.",
+                                pin_name
+                            );
+                            return Err(self.closed_error(pin_name));
+                        }
+                    },
+                    Err(TrySendError::Closed(_packet)) => {
+                        // This is expected during cancellation/shutdown, so use debug level
+                        tracing::debug!(
+                            "Directly connected channel for pin '{}' is closed.",
+                            pin_name
+                        );
+                        return Err(self.closed_error(pin_name));
+                    },
                 }
             },
-            OutputRouting::Routed(engine_tx) => {
-                // Clone engine_tx first to release the immutable borrow on self,
-                // allowing us to call get_cached_pin_name() which needs &mut self
-                let engine_tx = engine_tx.clone();
-
-                // Use cached Arc<str> for node and pin names to avoid heap allocations
-                let cached_pin = self.get_cached_pin_name(pin_name);
-                let message = (self.node_name.clone(), cached_pin, packet);
+            RouteTable::Routed { engine_tx, pins } => {
+                // Use interned Arc<str> for node and pin names to avoid heap allocations
+                let index = found.unwrap_or_else(|| {
+                    pins.push(Arc::from(pin_name));
+                    pins.len() - 1
+                });
+                self.last_route = index;
+                let message = (self.node_name.clone(), pins[index].clone(), packet);
                 match engine_tx.try_send(message) {
                     Ok(()) => {},
                     Err(TrySendError::Full(message)) => {
                         if engine_tx.send(message).await.is_err() {
                             tracing::warn!("Engine channel is closed. Cannot send packet.");
-                            return Err(OutputSendError::ChannelClosed {
-                                node_name: self.node_name.to_string(),
-                                pin_name: pin_name.to_string(),
-                            });
+                            return Err(self.closed_error(pin_name));
                         }
                     },
                     Err(TrySendError::Closed(_message)) => {
                         tracing::warn!("Engine channel is closed. Cannot send packet.");
-                        return Err(OutputSendError::ChannelClosed {
-                            node_name: self.node_name.to_string(),
-                            pin_name: pin_name.to_string(),
-                        });
+                        return Err(self.closed_error(pin_name));
                     },
                 }
             },
@@ -223,6 +250,25 @@ pub struct NodeContext {
     /// Nodes that produce audio frames (decoders, resamplers, mixers) may use this to
     /// amortize `Vec<f32>` allocations. If `None`, nodes should fall back to allocating.
     pub audio_pool: Option<Arc<AudioFramePool>>,
+    /// Media clock shared by every node in the pipeline.
+    ///
+    /// Nodes that align several tracks (muxers, compositors) register their tracks here
+    /// instead of each inferring timing from packet timestamps on its own. `None` in
+    /// test contexts.
+    pub media_clock: Option<Arc<MediaClock>>,
+    /// Usage meter for this node in the session's cost report.
+    ///
+    /// The engine already charges time spent polling the node's task and bytes moved over
+    /// its connections. Nodes that offload work (`spawn_blocking`, GPU) should add that time
+    /// here. `None` when the pipeline isn't metered.
+    pub cost_meter: Option<Arc<NodeMeter>>,
+    /// Telemetry sampling and rate limits for this node: the server defaults merged with
+    /// the `telemetry` entry of its params. `None` uses the server defaults as-is.
+    pub telemetry_limits: Option<Arc<TelemetryLimits>>,
+    /// Peek and delayed views of the pin named by [`ProcessorNode::lookahead`], which is
+    /// then absent from `inputs`. `None` when the node declares no lookahead or the pin is
+    /// not connected.
+    pub lookahead: Option<mpsc::Receiver<LookaheadEvent>>,
 }
 
 impl NodeContext {
@@ -238,6 +284,17 @@ impl NodeContext {
         })
     }
 
+    /// Takes the lookahead stream declared through [`ProcessorNode::lookahead`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `StreamKitError::Runtime` if the engine did not provide one.
+    pub fn take_lookahead(&mut self) -> Result<mpsc::Receiver<LookaheadEvent>, StreamKitError> {
+        self.lookahead.take().ok_or_else(|| {
+            StreamKitError::Runtime("Engine did not provide a lookahead receiver".to_string())
+        })
+    }
+
     /// Receives a packet from the given receiver, respecting the cancellation token if present.
     /// Returns None if cancelled or if the channel is closed.
     ///
@@ -253,6 +310,19 @@ impl NodeContext {
             rx.recv().await
         }
     }
+
+    /// Creates a [`TelemetryEmitter`] for this node that applies its telemetry limits.
+    pub fn telemetry_emitter(&self) -> TelemetryEmitter {
+        let emitter = TelemetryEmitter::new(
+            self.output_sender.node_name().to_string(),
+            self.session_id.clone(),
+            self.telemetry_tx.clone(),
+        );
+        match &self.telemetry_limits {
+            Some(limits) => emitter.with_limits(Arc::clone(limits)),
+            None => emitter,
+        }
+    }
 }
 
 /// The fundamental trait for any processing node, designed as an actor.
@@ -305,6 +375,18 @@ pub trait ProcessorNode: Send + Sync {
         false
     }
 
+    /// Bounded lookahead on one input pin.
+    ///
+    /// When this returns a spec, the engine delays that pin by `window` of media time and
+    /// delivers it as an ordered stream of peek and delayed events in
+    /// [`NodeContext::lookahead`] instead of in `inputs`. See [`crate::lookahead`].
+    ///
+    /// Queried once, after [`initialize`](Self::initialize). Default implementation returns
+    /// `None`.
+    fn lookahead(&self) -> Option<LookaheadSpec> {
+        None
+    }
+
     /// The main actor loop for the node. The engine will spawn this method as a task.
     async fn run(self: Box<Self>, context: NodeContext) -> Result<(), StreamKitError>;
 }
@@ -322,3 +404,48 @@ pub type NodeFactory = Arc<
 /// Given parameters, returns a deterministic hash string used as part of the ResourceKey.
 /// Plugins should hash only the parameters that affect resource initialization (e.g., model path, GPU settings).
 pub type ResourceKeyHasher = Arc<dyn Fn(Option<&serde_json::Value>) -> String + Send + Sync>;
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn output_sender_routes_each_pin_to_its_channel() {
+        let (audio_tx, mut audio_rx) = mpsc::channel(4);
+        let (text_tx, mut text_rx) = mpsc::channel(4);
+        let senders =
+            HashMap::from([("audio".to_string(), audio_tx), ("text".to_string(), text_tx)]);
+        let mut sender = OutputSender::new("node".to_string(), OutputRouting::Direct(senders));
+
+        for text in ["a", "b"] {
+            sender.send("text", Packet::Text(text.into())).await.unwrap();
+            sender.send("audio", Packet::Text(format!("audio-{text}").into())).await.unwrap();
+        }
+        let err = sender.send("missing", Packet::Text("x".into())).await.unwrap_err();
+        assert!(matches!(err, OutputSendError::PinNotFound { .. }));
+
+        for expected in ["a", "b"] {
+            assert!(
+                matches!(text_rx.recv().await, Some(Packet::Text(s)) if s.as_ref() == expected)
+            );
+            let audio = format!("audio-{expected}");
+            assert!(matches!(audio_rx.recv().await, Some(Packet::Text(s)) if *s == *audio));
+        }
+    }
+
+    #[tokio::test]
+    async fn output_sender_reuses_pin_names_when_routed() {
+        let (engine_tx, mut engine_rx) = mpsc::channel(4);
+        let mut sender = OutputSender::new("node".to_string(), OutputRouting::Routed(engine_tx));
+
+        sender.send("out", Packet::Text("1".into())).await.unwrap();
+        sender.send("out", Packet::Text("2".into())).await.unwrap();
+
+        let (node, first_pin, _) = engine_rx.recv().await.unwrap();
+        let (_, second_pin, _) = engine_rx.recv().await.unwrap();
+        assert_eq!(node.as_ref(), "node");
+        assert_eq!(first_pin.as_ref(), "out");
+        assert!(Arc::ptr_eq(&first_pin, &second_pin));
+    }
+}
@@ -0,0 +1,146 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Masking of sensitive node parameters.
+//!
+//! Node configs mark fields that may carry credentials or private locations (URLs with
+//! tokens, file paths) with `#[schemars(extend("sensitive" = true))]`. The server uses
+//! [`redact_params`] to mask those fields before showing params to roles that may not see
+//! them, and nodes use [`redact_url`] when logging endpoints.
+
+use serde_json::Value;
+
+/// Placeholder that replaces redacted values.
+pub const REDACTED: &str = "[redacted]";
+
+/// Nesting limit when following `properties` and `$ref`s; guards against recursive schemas.
+const MAX_DEPTH: usize = 16;
+
+/// Replaces every value in `params` whose schema property is marked `"sensitive": true`
+/// with [`REDACTED`]. Nested objects are followed through `properties` and local `$ref`s.
+///
+/// Returns `true` if anything was masked.
+pub fn redact_params(schema: &Value, params: &mut Value) -> bool {
+    redact_object(schema, schema, params, 0)
+}
+
+fn redact_object(root: &Value, schema: &Value, params: &mut Value, depth: usize) -> bool {
+    if depth > MAX_DEPTH {
+        return false;
+    }
+    let schema = resolve_ref(root, schema);
+    let (Some(properties), Some(params)) =
+        (schema.get("properties").and_then(Value::as_object), params.as_object_mut())
+    else {
+        return false;
+    };
+
+    let mut redacted = false;
+    for (key, value) in params.iter_mut() {
+        let Some(property) = properties.get(key) else {
+            continue;
+        };
+        if is_sensitive(property) || is_sensitive(resolve_ref(root, property)) {
+            if !value.is_null() {
+                *value = Value::String(REDACTED.to_string());
+                redacted = true;
+            }
+        } else {
+            redacted |= redact_object(root, property, value, depth + 1);
+        }
+    }
+    redacted
+}
+
+fn is_sensitive(property: &Value) -> bool {
+    property.get("sensitive").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Follows a local `$ref` (e.g. `#/$defs/Auth`); anything else is returned unchanged.
+fn resolve_ref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+/// Strips credentials from a URL for logging: user info and the query string (where
+/// tokens usually live) are replaced with [`REDACTED`]. Scheme, host and path are kept.
+pub fn redact_url(url: &str) -> String {
+    let (scheme, rest) = url.split_once("://").map_or(("", url), |(s, r)| (s, r));
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(authority_end);
+
+    let mut redacted = String::with_capacity(url.len());
+    if !scheme.is_empty() {
+        redacted.push_str(scheme);
+        redacted.push_str("://");
+    }
+    match authority.rsplit_once('@') {
+        Some((_, host)) => {
+            redacted.push_str(REDACTED);
+            redacted.push('@');
+            redacted.push_str(host);
+        },
+        None => redacted.push_str(authority),
+    }
+
+    let path_end = tail.find(['?', '#']).unwrap_or(tail.len());
+    redacted.push_str(&tail[..path_end]);
+    if tail[path_end..].starts_with('?') {
+        redacted.push('?');
+        redacted.push_str(REDACTED);
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_params_masks_marked_fields() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string", "sensitive": true },
+                "broadcast": { "type": "string" },
+                "auth": { "$ref": "#/$defs/Auth" }
+            },
+            "$defs": {
+                "Auth": {
+                    "type": "object",
+                    "properties": { "token": { "type": "string", "sensitive": true } }
+                }
+            }
+        });
+        let mut params = json!({
+            "url": "https://relay.example.com/?jwt=abc",
+            "broadcast": "input",
+            "auth": { "token": "abc" }
+        });
+
+        assert!(redact_params(&schema, &mut params));
+        assert_eq!(
+            params,
+            json!({ "url": REDACTED, "broadcast": "input", "auth": { "token": REDACTED } })
+        );
+
+        let mut params = json!({ "broadcast": "input" });
+        assert!(!redact_params(&schema, &mut params));
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("https://user:pw@relay.example.com:4443/anon?jwt=abc#frag"),
+            "https://[redacted]@relay.example.com:4443/anon?[redacted]"
+        );
+        assert_eq!(redact_url("http://localhost:4545/moq"), "http://localhost:4545/moq");
+        assert_eq!(redact_url("relay.example.com?token=1"), "relay.example.com?[redacted]");
+    }
+}
@@ -13,9 +13,10 @@ use crate::error::StreamKitError;
 use crate::node::{NodeFactory, ProcessorNode, ResourceKeyHasher};
 use crate::pins::{InputPin, OutputPin};
 use crate::resource_manager::{Resource, ResourceError, ResourceKey, ResourceManager};
+use crate::types::PacketType;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use ts_rs::TS;
 
@@ -47,6 +48,73 @@ pub struct NodeDefinition {
     /// Whether this node is bidirectional (has both input and output for the same data flow)
     #[serde(default)]
     pub bidirectional: bool,
+    /// Whether this node kind is deprecated and should not be used in new pipelines
+    #[serde(default)]
+    pub deprecated: bool,
+    /// Former kind names that still resolve to this node
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Parameters whose options come from a named enum provider, mapped to the provider name.
+    /// Declared in the config struct with `#[schemars(extend("enum_provider" = "<name>"))]`.
+    #[serde(default)]
+    pub enum_providers: BTreeMap<String, String>,
+    /// Declared payload schemas of the custom packet types used by this node's pins
+    #[serde(default)]
+    pub custom_types: Vec<CustomTypeSchema>,
+}
+
+/// JSON Schema declared for the `data` of a custom packet type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct CustomTypeSchema {
+    /// Namespaced, versioned type id (e.g., `plugin::wasm::detector/event@1`)
+    pub type_id: String,
+    /// JSON Schema of the packet payload
+    pub schema: serde_json::Value,
+}
+
+/// A single option offered by an enum provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct EnumOption {
+    /// The parameter value to use when this option is selected
+    pub value: String,
+    /// Human-readable label, if different from the value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl EnumOption {
+    /// Creates an option whose label is the value itself.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self { value: value.into(), label: None }
+    }
+
+    /// Creates an option with a separate display label.
+    pub fn labeled(value: impl Into<String>, label: impl Into<String>) -> Self {
+        Self { value: value.into(), label: Some(label.into()) }
+    }
+}
+
+/// Lists the currently available options for a parameter (e.g. audio devices, TTS voices,
+/// installed models). Called on every node listing, so it should be reasonably cheap.
+pub type EnumProvider = Arc<dyn Fn() -> Vec<EnumOption> + Send + Sync>;
+
+/// Schema extension key naming the enum provider for a parameter.
+const ENUM_PROVIDER_KEY: &str = "enum_provider";
+
+/// Schema key that [`EnumResolver::resolve`] fills with a provider's current options.
+const ENUM_OPTIONS_KEY: &str = "enum_options";
+
+/// Deprecation notice for a node kind referenced by a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KindDeprecation {
+    /// The kind as referenced by the caller.
+    pub kind: String,
+    /// The kind to use instead, if the referenced kind is an alias.
+    pub replacement: Option<String>,
+    /// Human-readable explanation suitable for surfacing to users.
+    pub message: String,
 }
 
 /// Static pin configuration for nodes with fixed pins.
@@ -70,15 +138,72 @@ pub(crate) struct NodeInfo {
     pub resource_factory: Option<AsyncResourceFactory>,
     /// Optional key hasher for computing resource cache keys from parameters
     pub resource_key_hasher: Option<ResourceKeyHasher>,
+    /// Deprecation note, set via [`NodeRegistry::deprecate`]
+    pub deprecation: Option<String>,
 }
 
 /// The NodeRegistry holds all available node types that the engine can construct.
 #[derive(Clone, Default)]
 pub struct NodeRegistry {
     info: HashMap<String, NodeInfo>,
+    /// Former kind names mapped to their current kind
+    aliases: HashMap<String, String>,
     /// Optional resource manager for shared resources (e.g., ML models)
     #[allow(clippy::type_complexity)]
     resource_manager: Option<Arc<ResourceManager>>,
+    /// Named providers of deployment-specific parameter options
+    enum_providers: HashMap<String, EnumProvider>,
+    /// Payload schemas of custom packet types, keyed by type id
+    custom_type_schemas: HashMap<String, serde_json::Value>,
+}
+
+/// A snapshot of the registry's enum providers, used to fill in parameter options
+/// without holding the registry lock while providers run.
+#[derive(Clone, Default)]
+pub struct EnumResolver {
+    providers: HashMap<String, EnumProvider>,
+}
+
+impl EnumResolver {
+    /// Writes the current options of each provider-backed parameter into the definitions'
+    /// parameter schemas as `enum_options` (a list of [`EnumOption`]s).
+    ///
+    /// The options are a hint for UI forms and are not enforced, so they stay out of the
+    /// standard `enum` keyword: a value outside the list (e.g. an unplugged device) or `null`
+    /// for an unset optional field must still validate. Parameters marked `"sensitive": true`
+    /// are only resolved when `reveal_sensitive` is set, and parameters naming an unknown
+    /// provider are left untouched.
+    ///
+    /// Providers may enumerate hardware or walk the filesystem, so async callers should run
+    /// this on a blocking thread.
+    pub fn resolve(&self, definitions: &mut [NodeDefinition], reveal_sensitive: bool) {
+        let mut cache: HashMap<&str, serde_json::Value> = HashMap::new();
+        for def in definitions {
+            for (param, provider_name) in &def.enum_providers {
+                let Some(provider) = self.providers.get(provider_name) else {
+                    tracing::debug!(kind = %def.kind, provider = %provider_name, "Unknown enum provider");
+                    continue;
+                };
+                let Some(property) = def
+                    .param_schema
+                    .get_mut("properties")
+                    .and_then(|props| props.get_mut(param))
+                    .and_then(serde_json::Value::as_object_mut)
+                else {
+                    continue;
+                };
+                let sensitive =
+                    property.get("sensitive").and_then(serde_json::Value::as_bool).unwrap_or(false);
+                if sensitive && !reveal_sensitive {
+                    continue;
+                }
+                let options = cache.entry(provider_name.as_str()).or_insert_with(|| {
+                    serde_json::to_value(provider()).unwrap_or_else(|_| serde_json::json!([]))
+                });
+                property.insert(ENUM_OPTIONS_KEY.to_string(), options.clone());
+            }
+        }
+    }
 }
 
 impl NodeRegistry {
@@ -89,7 +214,13 @@ impl NodeRegistry {
 
     /// Creates a new registry with resource management support.
     pub fn with_resource_manager(resource_manager: Arc<ResourceManager>) -> Self {
-        Self { info: HashMap::new(), resource_manager: Some(resource_manager) }
+        Self {
+            info: HashMap::new(),
+            aliases: HashMap::new(),
+            resource_manager: Some(resource_manager),
+            enum_providers: HashMap::new(),
+            custom_type_schemas: HashMap::new(),
+        }
     }
 
     /// Sets or updates the resource manager for this registry.
@@ -124,6 +255,7 @@ impl NodeRegistry {
                 description: None,
                 resource_factory: None,
                 resource_key_hasher: None,
+                deprecation: None,
             },
         );
     }
@@ -156,6 +288,7 @@ impl NodeRegistry {
                 description: Some(description.into()),
                 resource_factory: None,
                 resource_key_hasher: None,
+                deprecation: None,
             },
         );
     }
@@ -187,6 +320,7 @@ impl NodeRegistry {
                 description: None,
                 resource_factory: None,
                 resource_key_hasher: None,
+                deprecation: None,
             },
         );
     }
@@ -217,6 +351,7 @@ impl NodeRegistry {
                 description: Some(description.into()),
                 resource_factory: None,
                 resource_key_hasher: None,
+                deprecation: None,
             },
         );
     }
@@ -262,6 +397,7 @@ impl NodeRegistry {
                 description: None,
                 resource_factory: Some(resource_factory),
                 resource_key_hasher: Some(resource_key_hasher),
+                deprecation: None,
             },
         );
     }
@@ -294,6 +430,7 @@ impl NodeRegistry {
                 description: None,
                 resource_factory: Some(resource_factory),
                 resource_key_hasher: Some(resource_key_hasher),
+                deprecation: None,
             },
         );
     }
@@ -314,6 +451,7 @@ impl NodeRegistry {
         name: &str,
         params: Option<&serde_json::Value>,
     ) -> Result<Box<dyn ProcessorNode>, StreamKitError> {
+        let name = self.canonical_kind(name);
         self.info.get(name).map_or_else(
             || Err(StreamKitError::Runtime(format!("Node type '{name}' not found in registry"))),
             |info| (info.factory)(params),
@@ -334,6 +472,7 @@ impl NodeRegistry {
         name: &str,
         params: Option<&serde_json::Value>,
     ) -> Result<Box<dyn ProcessorNode>, StreamKitError> {
+        let name = self.canonical_kind(name);
         let info = self.info.get(name).ok_or_else(|| {
             StreamKitError::Runtime(format!("Node type '{name}' not found in registry"))
         })?;
@@ -385,6 +524,7 @@ impl NodeRegistry {
                 },
             };
 
+            let custom_types = self.custom_types_of(&inputs, &outputs);
             defs.push(NodeDefinition {
                 kind: kind.clone(),
                 description: info.description.clone(),
@@ -393,6 +533,10 @@ impl NodeRegistry {
                 outputs,
                 categories: info.categories.clone(),
                 bidirectional: info.bidirectional,
+                deprecated: info.deprecation.is_some(),
+                aliases: self.aliases_of(kind),
+                enum_providers: enum_provider_params(&info.param_schema),
+                custom_types,
             });
         }
         defs
@@ -405,7 +549,284 @@ impl NodeRegistry {
     }
 
     /// Checks whether a node definition exists in the registry.
+    /// Aliases count as present if their target is registered.
     pub fn contains(&self, name: &str) -> bool {
-        self.info.contains_key(name)
+        self.info.contains_key(self.canonical_kind(name))
+    }
+
+    /// Returns the parameter JSON Schema for a node kind, resolving aliases.
+    pub fn param_schema(&self, name: &str) -> Option<&serde_json::Value> {
+        self.info.get(self.canonical_kind(name)).map(|info| &info.param_schema)
+    }
+
+    /// Registers `alias` as a former name of `target`.
+    ///
+    /// Pipelines referencing the alias keep working: node creation resolves it to `target`,
+    /// and [`NodeRegistry::deprecation`] reports it so callers can warn users to migrate.
+    /// Aliases do not chain; `target` should be a registered kind, not another alias.
+    pub fn register_alias(&mut self, alias: &str, target: &str) {
+        self.aliases.insert(alias.to_string(), target.to_string());
+    }
+
+    /// Registers (or replaces) a named provider of parameter options.
+    ///
+    /// Node configs reference it with `#[schemars(extend("enum_provider" = "<name>"))]` on a
+    /// field; [`EnumResolver::resolve`] then lists the provider's options in that field's schema.
+    pub fn register_enum_provider<F>(&mut self, name: &str, provider: F)
+    where
+        F: Fn() -> Vec<EnumOption> + Send + Sync + 'static,
+    {
+        self.enum_providers.insert(name.to_string(), Arc::new(provider));
+    }
+
+    /// Returns a resolver holding the currently registered enum providers.
+    pub fn enum_resolver(&self) -> EnumResolver {
+        EnumResolver { providers: self.enum_providers.clone() }
+    }
+
+    /// Registers (or replaces) the JSON Schema of a custom packet type's payload.
+    ///
+    /// Node definitions list the schemas of the custom types their pins produce or accept,
+    /// so clients can render and validate those packets.
+    pub fn register_custom_type_schema(&mut self, type_id: &str, schema: serde_json::Value) {
+        if self.custom_type_schemas.get(type_id).is_some_and(|previous| *previous != schema) {
+            tracing::warn!(type_id = %type_id, "Replacing the schema of a custom packet type");
+        }
+        self.custom_type_schemas.insert(type_id.to_string(), schema);
+    }
+
+    /// Returns the registered payload schema of a custom packet type.
+    pub fn custom_type_schema(&self, type_id: &str) -> Option<&serde_json::Value> {
+        self.custom_type_schemas.get(type_id)
+    }
+
+    /// Marks a registered node kind as deprecated.
+    /// Returns false if no definition with the provided name is registered.
+    pub fn deprecate(&mut self, name: &str, note: impl Into<String>) -> bool {
+        self.info.get_mut(name).map(|info| info.deprecation = Some(note.into())).is_some()
+    }
+
+    /// Resolves an alias to its current kind. Non-alias names are returned unchanged.
+    pub fn canonical_kind<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// Returns a deprecation notice if `name` is an alias or a deprecated kind.
+    pub fn deprecation(&self, name: &str) -> Option<KindDeprecation> {
+        if let Some(target) = self.aliases.get(name) {
+            return Some(KindDeprecation {
+                kind: name.to_string(),
+                replacement: Some(target.clone()),
+                message: format!("Node kind '{name}' has been renamed to '{target}'"),
+            });
+        }
+
+        let note = self.info.get(name)?.deprecation.as_ref()?;
+        Some(KindDeprecation {
+            kind: name.to_string(),
+            replacement: None,
+            message: format!("Node kind '{name}' is deprecated: {note}"),
+        })
+    }
+
+    /// Collects the registered schemas of the custom packet types used by a node's pins.
+    fn custom_types_of(&self, inputs: &[InputPin], outputs: &[OutputPin]) -> Vec<CustomTypeSchema> {
+        let type_ids: std::collections::BTreeSet<&str> = inputs
+            .iter()
+            .flat_map(|pin| &pin.accepts_types)
+            .chain(outputs.iter().map(|pin| &pin.produces_type))
+            .filter_map(|packet_type| match packet_type {
+                PacketType::Custom { type_id } => Some(type_id.as_str()),
+                _ => None,
+            })
+            .collect();
+        type_ids
+            .into_iter()
+            .filter_map(|type_id| {
+                let schema = self.custom_type_schemas.get(type_id)?;
+                Some(CustomTypeSchema { type_id: type_id.to_string(), schema: schema.clone() })
+            })
+            .collect()
+    }
+
+    fn aliases_of(&self, kind: &str) -> Vec<String> {
+        let mut aliases: Vec<String> = self
+            .aliases
+            .iter()
+            .filter(|(_, target)| target.as_str() == kind)
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        aliases.sort();
+        aliases
+    }
+}
+
+/// Collects top-level parameters that declare an enum provider in their schema.
+fn enum_provider_params(param_schema: &serde_json::Value) -> BTreeMap<String, String> {
+    param_schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+        .map(|props| {
+            props
+                .iter()
+                .filter_map(|(param, schema)| {
+                    let provider = schema.get(ENUM_PROVIDER_KEY)?.as_str()?;
+                    Some((param.clone(), provider.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry_with_gain() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register_static(
+            "audio::gain",
+            |_| Err(StreamKitError::Configuration("gain factory called".to_string())),
+            serde_json::json!({}),
+            StaticPins { inputs: vec![], outputs: vec![] },
+            vec!["audio".to_string()],
+            false,
+        );
+        registry
+    }
+
+    #[test]
+    fn alias_resolves_to_target() {
+        let mut registry = registry_with_gain();
+        registry.register_alias("audio::volume", "audio::gain");
+
+        assert_eq!(registry.canonical_kind("audio::volume"), "audio::gain");
+        assert_eq!(registry.canonical_kind("audio::gain"), "audio::gain");
+        assert!(registry.contains("audio::volume"));
+
+        // The target's factory runs rather than a "not found" error.
+        let Err(err) = registry.create_node("audio::volume", None) else {
+            panic!("expected factory error");
+        };
+        assert!(matches!(err, StreamKitError::Configuration(_)));
+
+        let deprecation = registry.deprecation("audio::volume").expect("alias is deprecated");
+        assert_eq!(deprecation.replacement.as_deref(), Some("audio::gain"));
+        assert!(registry.deprecation("audio::gain").is_none());
+
+        let defs = registry.definitions();
+        assert_eq!(defs.len(), 1);
+        assert_eq!(defs[0].aliases, vec!["audio::volume".to_string()]);
+        assert!(!defs[0].deprecated);
+    }
+
+    #[test]
+    fn deprecated_kind_is_flagged() {
+        let mut registry = registry_with_gain();
+        assert!(!registry.deprecate("audio::missing", "gone"));
+        assert!(registry.deprecate("audio::gain", "use audio::mixer instead"));
+
+        let deprecation = registry.deprecation("audio::gain").expect("kind is deprecated");
+        assert_eq!(deprecation.replacement, None);
+        assert!(deprecation.message.contains("use audio::mixer instead"));
+        assert!(registry.definitions()[0].deprecated);
+    }
+
+    #[test]
+    fn custom_type_schemas_are_listed_for_pins_using_them() {
+        let mut registry = NodeRegistry::new();
+        let event = PacketType::Custom { type_id: "plugin::wasm::detector/event@1".to_string() };
+        registry.register_static(
+            "plugin::wasm::detector",
+            |_| Err(StreamKitError::Configuration("detector factory called".to_string())),
+            serde_json::json!({}),
+            StaticPins {
+                inputs: vec![],
+                outputs: vec![OutputPin {
+                    name: "out".to_string(),
+                    produces_type: event,
+                    cardinality: crate::pins::PinCardinality::Broadcast,
+                }],
+            },
+            vec!["ml".to_string()],
+            false,
+        );
+        let schema = serde_json::json!({ "type": "object", "required": ["label"] });
+        registry.register_custom_type_schema("plugin::wasm::detector/event@1", schema.clone());
+        registry.register_custom_type_schema("plugin::wasm::other/event@1", serde_json::json!({}));
+
+        let defs = registry.definitions();
+        assert_eq!(
+            defs[0].custom_types,
+            vec![CustomTypeSchema {
+                type_id: "plugin::wasm::detector/event@1".to_string(),
+                schema: schema.clone()
+            }]
+        );
+        assert_eq!(registry.custom_type_schema("plugin::wasm::detector/event@1"), Some(&schema));
+    }
+
+    #[test]
+    fn enum_provider_options_are_resolved_into_schema() {
+        let mut registry = NodeRegistry::new();
+        registry.register_static(
+            "audio::capture",
+            |_| Err(StreamKitError::Configuration("capture factory called".to_string())),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "device": { "type": ["string", "null"], "enum_provider": "audio_inputs" },
+                    "voice": { "type": "string", "enum_provider": "missing" },
+                    "path": {
+                        "type": "string",
+                        "sensitive": true,
+                        "enum_provider": "audio_inputs"
+                    },
+                    "gain": { "type": "number" }
+                }
+            }),
+            StaticPins { inputs: vec![], outputs: vec![] },
+            vec!["audio".to_string()],
+            false,
+        );
+        registry.register_enum_provider("audio_inputs", || {
+            vec![EnumOption::labeled("hw:0", "Built-in Microphone"), EnumOption::new("hw:1")]
+        });
+
+        let mut defs = registry.definitions();
+        assert_eq!(
+            defs[0].enum_providers,
+            BTreeMap::from([
+                ("device".to_string(), "audio_inputs".to_string()),
+                ("path".to_string(), "audio_inputs".to_string()),
+                ("voice".to_string(), "missing".to_string()),
+            ])
+        );
+
+        let resolver = registry.enum_resolver();
+        let mut revealed = defs.clone();
+        resolver.resolve(&mut defs, false);
+        let props = &defs[0].param_schema["properties"];
+        assert_eq!(
+            props["device"]["enum_options"],
+            serde_json::json!([{ "value": "hw:0", "label": "Built-in Microphone" }, { "value": "hw:1" }])
+        );
+        // Options never become a validating `enum`, so the nullable field still accepts null.
+        assert!(props["device"].get("enum").is_none());
+        assert_eq!(props["device"]["type"], serde_json::json!(["string", "null"]));
+        assert!(props["voice"].get("enum_options").is_none());
+        assert!(props["gain"].get("enum_options").is_none());
+        // Sensitive params are only resolved for callers allowed to see their values.
+        assert!(props["path"].get("enum_options").is_none());
+
+        resolver.resolve(&mut revealed, true);
+        assert_eq!(
+            revealed[0].param_schema["properties"]["path"]["enum_options"],
+            props["device"]["enum_options"]
+        );
+
+        // The registry's own schema (used for parameter validation) is unchanged.
+        let schema = registry.param_schema("audio::capture").expect("registered");
+        assert!(schema["properties"]["device"].get("enum_options").is_none());
     }
 }
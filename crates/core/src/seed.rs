@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Seed convention for nodes with stochastic output.
+//!
+//! Nodes whose output depends on random sampling (LLM decoding, sampled translation,
+//! noisy TTS) declare an optional integer `seed` param in their config schema. The engine
+//! makes every run of such a node reproducible: when `seed` is unset it picks one and
+//! passes it in, and either way it reports the effective seed as a [`SEED_EVENT_TYPE`]
+//! telemetry event. Re-running the pipeline with that seed set reproduces the output, as
+//! far as the backend is deterministic.
+//!
+//! Nodes that don't declare `seed` are left alone, so the param never reaches a node
+//! that would reject it as unknown.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::hash::{BuildHasher, Hasher};
+
+/// Node param holding the seed.
+pub const SEED_PARAM: &str = "seed";
+
+/// Telemetry event type reporting the seed a node was created with.
+pub const SEED_EVENT_TYPE: &str = "node.seed";
+
+/// Generated seeds stay below 2^53 so they survive a round trip through JavaScript numbers
+/// in the UI and client SDKs.
+const MAX_GENERATED_SEED: u64 = (1 << 53) - 1;
+
+/// The seed a node was created with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EffectiveSeed {
+    pub seed: u64,
+    /// Whether the engine picked the seed because none was set
+    pub generated: bool,
+}
+
+/// Whether a node config schema declares the [`SEED_PARAM`] param.
+pub fn accepts_seed(schema: &Value) -> bool {
+    schema.get("properties").and_then(|p| p.get(SEED_PARAM)).is_some()
+}
+
+/// Reads the requested seed from node params; `null` and a missing param mean "unset".
+///
+/// # Errors
+///
+/// Returns an error if `seed` is present but not a non-negative integer.
+pub fn requested_seed(params: Option<&Value>) -> Result<Option<u64>, String> {
+    match params.and_then(|p| p.get(SEED_PARAM)) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("'{SEED_PARAM}' must be a non-negative integer, got {value}")),
+    }
+}
+
+/// Picks a fresh random seed.
+pub fn generate_seed() -> u64 {
+    // RandomState is keyed from the OS RNG, which is all the randomness a seed needs.
+    std::collections::hash_map::RandomState::new().build_hasher().finish() & MAX_GENERATED_SEED
+}
+
+/// Fills in the seed of a node whose schema declares [`SEED_PARAM`].
+///
+/// Returns the params to create the node with and its effective seed. Params of nodes that
+/// don't accept a seed (or that aren't an object) are returned unchanged, with no seed.
+///
+/// # Errors
+///
+/// Returns an error if a requested seed is invalid.
+pub fn resolve_seed(
+    schema: Option<&Value>,
+    params: Option<Value>,
+) -> Result<(Option<Value>, Option<EffectiveSeed>), String> {
+    if !schema.is_some_and(accepts_seed) {
+        return Ok((params, None));
+    }
+    if let Some(seed) = requested_seed(params.as_ref())? {
+        return Ok((params, Some(EffectiveSeed { seed, generated: false })));
+    }
+
+    let mut params = params.unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+    let Some(obj) = params.as_object_mut() else {
+        return Ok((Some(params), None));
+    };
+    let seed = generate_seed();
+    obj.insert(SEED_PARAM.to_string(), Value::from(seed));
+    Ok((Some(params), Some(EffectiveSeed { seed, generated: true })))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({ "properties": { "seed": { "type": ["integer", "null"] } } })
+    }
+
+    #[test]
+    fn test_requested_seed_is_kept() {
+        let (params, seed) =
+            resolve_seed(Some(&schema()), Some(json!({ "seed": 42, "top_p": 0.9 }))).unwrap();
+        assert_eq!(params, Some(json!({ "seed": 42, "top_p": 0.9 })));
+        assert_eq!(seed, Some(EffectiveSeed { seed: 42, generated: false }));
+
+        assert!(resolve_seed(Some(&schema()), Some(json!({ "seed": -1 }))).is_err());
+        assert!(resolve_seed(Some(&schema()), Some(json!({ "seed": "42" }))).is_err());
+    }
+
+    #[test]
+    fn test_missing_seed_is_generated_and_injected() {
+        let (params, seed) = resolve_seed(Some(&schema()), None).unwrap();
+        let seed = seed.unwrap();
+        assert!(seed.generated);
+        assert!(seed.seed <= MAX_GENERATED_SEED);
+        assert_eq!(params, Some(json!({ "seed": seed.seed })));
+
+        let (params, seed) = resolve_seed(Some(&schema()), Some(json!({ "seed": null }))).unwrap();
+        assert_eq!(requested_seed(params.as_ref()).unwrap(), seed.map(|s| s.seed));
+    }
+
+    #[test]
+    fn test_nodes_without_seed_are_untouched() {
+        let schema = json!({ "properties": { "gain": { "type": "number" } } });
+        let params = Some(json!({ "gain": 2.0 }));
+        assert_eq!(resolve_seed(Some(&schema), params.clone()).unwrap(), (params, None));
+        assert_eq!(resolve_seed(None, None).unwrap(), (None, None));
+    }
+}
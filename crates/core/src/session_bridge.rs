@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Gateway trait for bridging packets between sessions
+//!
+//! Bridge nodes let one session (e.g. a monitoring console) subscribe to the output pins or
+//! the telemetry of another session running on the same server, without touching the other
+//! session's graph. The server resolves sessions and checks that the owner of the tapping
+//! session may access the target; as with [`crate::moq_gateway`], only the interface is
+//! defined in core.
+
+use crate::telemetry::TelemetryEvent;
+use crate::types::Packet;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Gateway interface that bridge nodes use to tap other sessions
+#[async_trait]
+pub trait SessionBridgeTrait: Send + Sync {
+    /// Subscribe to the packets `node_id` of session `target` sends on output `pin`, on
+    /// behalf of session `session_id`
+    ///
+    /// `target` is a session name or ID. The subscription is best-effort: when the receiver
+    /// falls behind, packets are dropped rather than slowing the target down. The receiver
+    /// closes when the node or the target session goes away.
+    async fn tap_output(
+        &self,
+        session_id: &str,
+        target: &str,
+        node_id: &str,
+        pin: &str,
+    ) -> Result<mpsc::Receiver<Packet>, String>;
+
+    /// Subscribe to the telemetry events of session `target`, on behalf of session
+    /// `session_id`
+    ///
+    /// The receiver closes when the target session goes away.
+    async fn tap_telemetry(
+        &self,
+        session_id: &str,
+        target: &str,
+    ) -> Result<mpsc::Receiver<TelemetryEvent>, String>;
+}
+
+/// Global gateway registry - nodes call this to get the gateway
+static GATEWAY: std::sync::OnceLock<Arc<dyn SessionBridgeTrait>> = std::sync::OnceLock::new();
+
+/// Initialize the global session bridge (called by server)
+pub fn init_session_bridge(gateway: Arc<dyn SessionBridgeTrait>) {
+    if GATEWAY.set(gateway).is_err() {
+        tracing::warn!("Session bridge already initialized");
+    }
+}
+
+/// Get the global session bridge (called by nodes)
+pub fn get_session_bridge() -> Option<Arc<dyn SessionBridgeTrait>> {
+    GATEWAY.get().cloned()
+}
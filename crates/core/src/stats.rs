@@ -26,11 +26,22 @@ pub struct NodeStats {
     pub errored: u64,
     /// Duration in seconds since the node started processing (for rate calculation)
     pub duration_secs: f64,
+    /// Delay the node adds to the media passing through it, in microseconds, for nodes
+    /// that buffer or filter audio (e.g. resampler chunking and filter delay)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_us: Option<u64>,
 }
 
 impl Default for NodeStats {
     fn default() -> Self {
-        Self { received: 0, sent: 0, discarded: 0, errored: 0, duration_secs: 0.0 }
+        Self {
+            received: 0,
+            sent: 0,
+            discarded: 0,
+            errored: 0,
+            duration_secs: 0.0,
+            latency_us: None,
+        }
     }
 }
 
@@ -126,6 +137,12 @@ impl NodeStatsTracker {
         self.stats.errored += count;
     }
 
+    /// Record the latency the node currently adds, reported with every update
+    #[inline]
+    pub const fn set_latency_us(&mut self, latency_us: u64) {
+        self.stats.latency_us = Some(latency_us);
+    }
+
     /// Automatically send stats if threshold is met (every 2s or 1000 packets).
     /// Call this after processing a batch of packets.
     pub fn maybe_send(&mut self) {
@@ -12,6 +12,7 @@
 //! - **Best-effort delivery**: Telemetry never blocks audio processing
 //! - **Wire-compatible**: Events wrap `CustomPacketData` for future "telemetry as track" support
 //! - **Rate-limited**: Emitters automatically throttle high-frequency events
+//! - **Sampled**: Chatty event types can be thinned to every Nth event (see [`TelemetryLimits`])
 //! - **Drop accounting**: Track dropped events for health monitoring
 //!
 //! ## Event Type Convention
@@ -47,9 +48,12 @@
 //! ```
 
 use crate::types::{CustomEncoding, CustomPacketData, PacketMetadata};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use ts_rs::TS;
@@ -57,6 +61,15 @@ use ts_rs::TS;
 /// The standard type_id for all telemetry events.
 pub const TELEMETRY_TYPE_ID: &str = "core::telemetry/event@1";
 
+/// Node params key holding per-node [`TelemetryLimits`] overrides.
+pub const TELEMETRY_PARAMS_KEY: &str = "telemetry";
+
+/// Default per-event-type rate limit when nothing is configured.
+const DEFAULT_MAX_EVENTS_PER_SEC: u32 = 100;
+
+/// Event type of the drop report; exempt from limits so drops are always visible.
+const HEALTH_EVENT_TYPE: &str = "telemetry.health";
+
 /// A telemetry event emitted by a node.
 ///
 /// This wraps `CustomPacketData` to maintain wire-compatibility with the packet system,
@@ -148,15 +161,122 @@ impl Default for TelemetryConfig {
     }
 }
 
+/// Rate limit and sampling for one event type (or a `prefix.*` family of types).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct EventTypeLimit {
+    /// Maximum events of this type per second. Falls back to `max_events_per_sec`.
+    pub max_per_sec: Option<u32>,
+    /// Forward only every Nth event of this type (1 forwards all). Sampling runs before
+    /// the rate limit.
+    #[schemars(range(min = 1))]
+    pub sample_every: Option<u32>,
+}
+
+/// Sampling and rate limits applied to node telemetry events.
+///
+/// The server sets the defaults at startup (`set_default_telemetry_limits`); a node can
+/// override them with a `telemetry` object in its params, which is merged over the
+/// defaults key by key.
+///
+/// Keys of `event_types` are exact event types (`vad.level`) or prefixes ending in `.*`
+/// (`vad.*`). Exact matches win over prefixes, longer prefixes over shorter ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TelemetryLimits {
+    /// Maximum events per second for each event type without its own limit (default: 100).
+    pub max_events_per_sec: Option<u32>,
+    /// Per-event-type overrides.
+    pub event_types: BTreeMap<String, EventTypeLimit>,
+}
+
+impl TelemetryLimits {
+    /// Returns these limits with `overrides` applied on top.
+    #[must_use]
+    pub fn merged(&self, overrides: &Self) -> Self {
+        let mut event_types = self.event_types.clone();
+        for (event_type, limit) in &overrides.event_types {
+            let entry = event_types.entry(event_type.clone()).or_default();
+            entry.max_per_sec = limit.max_per_sec.or(entry.max_per_sec);
+            entry.sample_every = limit.sample_every.or(entry.sample_every);
+        }
+        Self {
+            max_events_per_sec: overrides.max_events_per_sec.or(self.max_events_per_sec),
+            event_types,
+        }
+    }
+
+    /// Resolves the limits for a node: the server defaults merged with the `telemetry`
+    /// object in its params, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `telemetry` params entry is malformed.
+    pub fn for_node_params(params: Option<&JsonValue>) -> Result<Arc<Self>, String> {
+        let defaults = default_telemetry_limits();
+        let Some(overrides) = params.and_then(|p| p.get(TELEMETRY_PARAMS_KEY)) else {
+            return Ok(Arc::new(defaults.clone()));
+        };
+        let overrides: Self = serde_json::from_value(overrides.clone())
+            .map_err(|e| format!("invalid '{TELEMETRY_PARAMS_KEY}' params: {e}"))?;
+        Ok(Arc::new(defaults.merged(&overrides)))
+    }
+
+    /// The type-specific `(max_per_sec, sample_every)` for an event type; `max_per_sec` is
+    /// `None` when the type falls back to `max_events_per_sec`.
+    fn resolve(&self, event_type: &str) -> (Option<u32>, u32) {
+        let limit = self.event_types.get(event_type).or_else(|| {
+            self.event_types
+                .iter()
+                .filter_map(|(key, limit)| {
+                    let prefix = key.strip_suffix('*')?;
+                    (prefix.ends_with('.') && event_type.starts_with(prefix))
+                        .then_some((prefix.len(), limit))
+                })
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, limit)| limit)
+        });
+        (limit.and_then(|l| l.max_per_sec), limit.and_then(|l| l.sample_every).unwrap_or(1).max(1))
+    }
+}
+
+/// Server-wide default limits.
+static DEFAULT_TELEMETRY_LIMITS: OnceLock<TelemetryLimits> = OnceLock::new();
+
+/// Sets the server-wide default telemetry limits.
+///
+/// Called once at server startup, before any sessions are created. Subsequent calls are
+/// ignored (the first configuration wins).
+pub fn set_default_telemetry_limits(limits: TelemetryLimits) {
+    if DEFAULT_TELEMETRY_LIMITS.set(limits).is_err() {
+        tracing::warn!("Default telemetry limits already set, ignoring new configuration");
+    }
+}
+
+/// Returns the server-wide default telemetry limits (empty if never set).
+pub fn default_telemetry_limits() -> &'static TelemetryLimits {
+    static EMPTY: OnceLock<TelemetryLimits> = OnceLock::new();
+    DEFAULT_TELEMETRY_LIMITS.get().unwrap_or_else(|| EMPTY.get_or_init(TelemetryLimits::default))
+}
+
 /// Helper for emitting telemetry events from nodes.
 ///
-/// Provides best-effort, non-blocking emission with automatic rate limiting
+/// Provides best-effort, non-blocking emission with per-event-type sampling, rate limiting
 /// and drop accounting. Uses `try_send()` to never block audio processing.
 ///
+/// ## Sampling and Rate Limits
+///
+/// Limits come from [`TelemetryLimits`]: the server defaults unless the emitter is built
+/// with [`TelemetryEmitter::with_limits`] (the engine resolves per-node overrides into
+/// `NodeContext::telemetry_limits`). Events forwarded from a sampled type carry a
+/// `sample_every` field so consumers can scale counts back up.
+///
 /// ## Drop Accounting
 ///
-/// Dropped events are tracked and can be reported via health telemetry.
-/// Call `emit_health()` periodically to report dropped event counts.
+/// Dropped events are counted per reason and per event type. While there are drops, the
+/// emitter reports them in a `telemetry.health` event at most every 5 seconds, piggybacking
+/// on the next emitted event; `maybe_emit_health()` can be called to report without
+/// waiting for one.
 pub struct TelemetryEmitter {
     node_id: String,
     session_id: Option<String>,
@@ -165,37 +285,47 @@ pub struct TelemetryEmitter {
     dropped_full: AtomicU64,
     /// Events dropped due to rate limiting
     dropped_rate_limit: AtomicU64,
-    /// Last health emission time for throttling
-    last_health_emit: Instant,
-    /// Rate limiter state: (event_type_hash, last_emit_time, count_in_window)
-    rate_limit_state: std::sync::Mutex<RateLimitState>,
+    /// Events dropped by sampling
+    dropped_sampling: AtomicU64,
+    limits: Arc<TelemetryLimits>,
+    /// Rate limiter, sampler and health reporting state
+    limiter: std::sync::Mutex<LimiterState>,
 }
 
 /// Internal rate limiting state
-struct RateLimitState {
-    /// Per-event-type tracking: event_type -> (last_emit_instant, count_in_window)
-    per_type: std::collections::HashMap<String, (Instant, u32)>,
+struct LimiterState {
+    /// Per-event-type tracking, resolved from the limits on first use
+    per_type: HashMap<String, TypeState>,
     /// Window duration for rate limiting
     window: std::time::Duration,
-    /// Max events per window per event_type
+    /// Rate limit set through `set_rate_limit`, replacing the configured default
+    max_override: Option<u32>,
+    /// Last health emission time for throttling
+    last_health_emit: Instant,
+}
+
+struct TypeState {
     max_per_window: u32,
+    sample_every: u32,
+    window_start: Instant,
+    count_in_window: u32,
+    /// Events seen so far, for picking every Nth one
+    seen: u64,
+    /// Sampled or rate-limited events since the last health report
+    dropped: u64,
 }
 
-impl Default for RateLimitState {
-    fn default() -> Self {
-        Self {
-            per_type: std::collections::HashMap::new(),
-            window: std::time::Duration::from_secs(1),
-            max_per_window: 100, // 100 events/sec per event_type by default
-        }
-    }
+enum Admission {
+    Send { sample_every: u32 },
+    Sampled,
+    RateLimited,
 }
 
 impl TelemetryEmitter {
     /// Health emission interval (5 seconds)
     const HEALTH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 
-    /// Create a new telemetry emitter for a node.
+    /// Create a new telemetry emitter for a node, using the server-wide default limits.
     pub fn new(
         node_id: String,
         session_id: Option<String>,
@@ -207,51 +337,91 @@ impl TelemetryEmitter {
             tx,
             dropped_full: AtomicU64::new(0),
             dropped_rate_limit: AtomicU64::new(0),
-            last_health_emit: Instant::now(),
-            rate_limit_state: std::sync::Mutex::new(RateLimitState::default()),
+            dropped_sampling: AtomicU64::new(0),
+            limits: Arc::new(default_telemetry_limits().clone()),
+            limiter: std::sync::Mutex::new(LimiterState {
+                per_type: HashMap::new(),
+                window: std::time::Duration::from_secs(1),
+                max_override: None,
+                last_health_emit: Instant::now(),
+            }),
         }
     }
 
+    /// Replaces the limits this emitter applies (e.g. the node's resolved limits).
+    #[must_use]
+    pub fn with_limits(mut self, limits: Arc<TelemetryLimits>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Whether events go anywhere (the node was given a telemetry channel).
+    pub const fn is_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+
     /// Get current timestamp in microseconds since UNIX epoch.
     #[allow(clippy::cast_possible_truncation)] // u64 microseconds covers ~500,000 years
     fn now_us() -> u64 {
         SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0)
     }
 
-    /// Check if an event should be rate-limited.
+    /// Decides whether an event is sent, sampled out or rate limited.
     #[allow(clippy::expect_used)] // Mutex poisoning indicates a serious bug, panic is appropriate
-    fn should_rate_limit(&self, event_type: &str) -> bool {
-        let mut state = self.rate_limit_state.lock().expect("rate limit mutex poisoned");
+    fn admit(&self, event_type: &str) -> Admission {
+        let mut guard = self.limiter.lock().expect("rate limit mutex poisoned");
+        let state = &mut *guard;
         let now = Instant::now();
         let window = state.window;
-        let max_per_window = state.max_per_window;
 
-        let entry = state.per_type.entry(event_type.to_string()).or_insert((now, 0));
+        let entry = state.per_type.entry(event_type.to_string()).or_insert_with(|| {
+            let (max_per_sec, sample_every) = self.limits.resolve(event_type);
+            TypeState {
+                max_per_window: max_per_sec
+                    .or(state.max_override)
+                    .or(self.limits.max_events_per_sec)
+                    .unwrap_or(DEFAULT_MAX_EVENTS_PER_SEC),
+                sample_every,
+                window_start: now,
+                count_in_window: 0,
+                seen: 0,
+                dropped: 0,
+            }
+        });
+
+        entry.seen += 1;
+        if !(entry.seen - 1).is_multiple_of(u64::from(entry.sample_every)) {
+            entry.dropped += 1;
+            return Admission::Sampled;
+        }
 
         // Reset window if expired
-        if now.duration_since(entry.0) >= window {
-            entry.0 = now;
-            entry.1 = 1;
-            drop(state);
-            return false;
+        if now.duration_since(entry.window_start) >= window {
+            entry.window_start = now;
+            entry.count_in_window = 0;
         }
 
         // Check if we're over the limit
-        if entry.1 >= max_per_window {
-            drop(state);
-            return true;
+        if entry.count_in_window >= entry.max_per_window {
+            entry.dropped += 1;
+            return Admission::RateLimited;
         }
 
-        entry.1 += 1;
-        drop(state);
-        false
+        entry.count_in_window += 1;
+        Admission::Send { sample_every: entry.sample_every }
     }
 
     /// Best-effort emit a telemetry event. Never blocks.
     ///
     /// Returns `true` if the event was sent (or queued), `false` if dropped.
     pub fn emit(&self, event_type: &str, data: JsonValue) -> bool {
-        self.emit_internal(event_type, None, None, data)
+        self.emit_internal(event_type, None, None, None, data)
+    }
+
+    /// Emit an event with an explicit timestamp (microseconds since UNIX epoch), e.g. one
+    /// reported by a plugin.
+    pub fn emit_at(&self, event_type: &str, timestamp_us: u64, data: JsonValue) -> bool {
+        self.emit_internal(event_type, None, None, Some(timestamp_us), data)
     }
 
     /// Emit an event with a correlation ID for grouping related events.
@@ -261,12 +431,12 @@ impl TelemetryEmitter {
         correlation_id: &str,
         data: JsonValue,
     ) -> bool {
-        self.emit_internal(event_type, Some(correlation_id), None, data)
+        self.emit_internal(event_type, Some(correlation_id), None, None, data)
     }
 
     /// Emit an event with a turn ID for voice agent conversation grouping.
     pub fn emit_with_turn(&self, event_type: &str, turn_id: &str, data: JsonValue) -> bool {
-        self.emit_internal(event_type, None, Some(turn_id), data)
+        self.emit_internal(event_type, None, Some(turn_id), None, data)
     }
 
     /// Emit an event with both correlation and turn IDs.
@@ -277,7 +447,7 @@ impl TelemetryEmitter {
         turn_id: &str,
         data: JsonValue,
     ) -> bool {
-        self.emit_internal(event_type, Some(correlation_id), Some(turn_id), data)
+        self.emit_internal(event_type, Some(correlation_id), Some(turn_id), None, data)
     }
 
     /// Internal emit implementation.
@@ -286,18 +456,25 @@ impl TelemetryEmitter {
         event_type: &str,
         correlation_id: Option<&str>,
         turn_id: Option<&str>,
+        timestamp_us: Option<u64>,
         mut data: JsonValue,
     ) -> bool {
-        let Some(ref tx) = self.tx else {
-            return false;
-        };
-
-        // Rate limiting check
-        if self.should_rate_limit(event_type) {
-            self.dropped_rate_limit.fetch_add(1, Ordering::Relaxed);
+        if self.tx.is_none() {
             return false;
         }
 
+        let sample_every = match self.admit(event_type) {
+            Admission::Send { sample_every } => sample_every,
+            Admission::Sampled => {
+                self.dropped_sampling.fetch_add(1, Ordering::Relaxed);
+                return false;
+            },
+            Admission::RateLimited => {
+                self.dropped_rate_limit.fetch_add(1, Ordering::Relaxed);
+                return false;
+            },
+        };
+
         // Ensure data is an object and add standard fields
         if let Some(obj) = data.as_object_mut() {
             obj.insert("event_type".to_string(), JsonValue::String(event_type.to_string()));
@@ -316,15 +493,27 @@ impl TelemetryEmitter {
                 "value": data,
             });
         }
+        if sample_every > 1 {
+            if let Some(obj) = data.as_object_mut() {
+                obj.insert("sample_every".to_string(), JsonValue::from(sample_every));
+            }
+        }
 
-        let event = TelemetryEvent::new(
-            self.session_id.clone(),
-            self.node_id.clone(),
-            data,
-            Self::now_us(),
-        );
+        let sent = self.send(data, timestamp_us.unwrap_or_else(Self::now_us));
+        if sent {
+            self.report_drops(false);
+        }
+        sent
+    }
+
+    /// Best-effort send - never block
+    fn send(&self, data: JsonValue, timestamp_us: u64) -> bool {
+        let Some(ref tx) = self.tx else {
+            return false;
+        };
+        let event =
+            TelemetryEvent::new(self.session_id.clone(), self.node_id.clone(), data, timestamp_us);
 
-        // Best-effort send - never block
         match tx.try_send(event) {
             Ok(()) => true,
             Err(mpsc::error::TrySendError::Full(_)) => {
@@ -335,59 +524,93 @@ impl TelemetryEmitter {
         }
     }
 
-    /// Get the current dropped event counts.
+    /// Get the current dropped event counts: `(channel full, rate limited)`.
     pub fn dropped_counts(&self) -> (u64, u64) {
         (self.dropped_full.load(Ordering::Relaxed), self.dropped_rate_limit.load(Ordering::Relaxed))
     }
 
-    /// Emit a health event if the interval has passed or if there are dropped events.
+    /// Get the number of events dropped by sampling.
+    pub fn dropped_by_sampling(&self) -> u64 {
+        self.dropped_sampling.load(Ordering::Relaxed)
+    }
+
+    /// Emit a health event if the interval has passed and there are dropped events.
     ///
     /// Returns `true` if a health event was emitted.
-    pub fn maybe_emit_health(&mut self) -> bool {
+    pub fn maybe_emit_health(&self) -> bool {
+        self.report_drops(false)
+    }
+
+    /// Sends a `telemetry.health` event with the drop counters, bypassing sampling and
+    /// rate limits, then resets them. Unless `force` is set, reports at most once per
+    /// [`Self::HEALTH_INTERVAL`].
+    fn report_drops(&self, force: bool) -> bool {
         let (dropped_full, dropped_rate_limit) = self.dropped_counts();
-        let has_drops = dropped_full > 0 || dropped_rate_limit > 0;
-        let interval_passed = self.last_health_emit.elapsed() >= Self::HEALTH_INTERVAL;
+        let dropped_sampling = self.dropped_by_sampling();
+        if dropped_full == 0 && dropped_rate_limit == 0 && dropped_sampling == 0 {
+            return false;
+        }
 
-        if !has_drops && !interval_passed {
+        // Also runs from `Drop`, so a poisoned mutex skips the report instead of panicking.
+        let Ok(mut state) = self.limiter.lock() else {
+            return false;
+        };
+        if !force && state.last_health_emit.elapsed() < Self::HEALTH_INTERVAL {
             return false;
         }
+        state.last_health_emit = Instant::now();
+        let by_type: serde_json::Map<String, JsonValue> = state
+            .per_type
+            .iter()
+            .filter(|(_, t)| t.dropped > 0)
+            .map(|(event_type, t)| (event_type.clone(), JsonValue::from(t.dropped)))
+            .collect();
+
+        let emitted = self.send(
+            serde_json::json!({
+                "event_type": HEALTH_EVENT_TYPE,
+                "dropped_due_to_full": dropped_full,
+                "dropped_due_to_rate_limit": dropped_rate_limit,
+                "dropped_due_to_sampling": dropped_sampling,
+                "dropped_by_event_type": by_type,
+            }),
+            Self::now_us(),
+        );
 
-        if has_drops || interval_passed {
-            self.last_health_emit = Instant::now();
-
-            // Only emit if there's something to report
-            if has_drops {
-                let emitted = self.emit(
-                    "telemetry.health",
-                    serde_json::json!({
-                        "dropped_due_to_full": dropped_full,
-                        "dropped_due_to_rate_limit": dropped_rate_limit,
-                    }),
-                );
-
-                // Reset counters after emission
-                if emitted {
-                    self.dropped_full.store(0, Ordering::Relaxed);
-                    self.dropped_rate_limit.store(0, Ordering::Relaxed);
-                }
-
-                return emitted;
+        // Reset counters after emission
+        if emitted {
+            self.dropped_full.fetch_sub(dropped_full, Ordering::Relaxed);
+            self.dropped_rate_limit.fetch_sub(dropped_rate_limit, Ordering::Relaxed);
+            self.dropped_sampling.fetch_sub(dropped_sampling, Ordering::Relaxed);
+            for type_state in state.per_type.values_mut() {
+                type_state.dropped = 0;
             }
         }
-
-        false
+        drop(state);
+        emitted
     }
 
     /// Configure rate limiting for this emitter.
     ///
+    /// Replaces the default per-event-type limit; event types with their own configured
+    /// `max_per_sec` keep it.
+    ///
     /// # Panics
     ///
     /// Panics if the internal rate limit mutex is poisoned (indicates a prior panic).
     #[allow(clippy::expect_used)] // Mutex poisoning indicates a serious bug, panic is appropriate
     pub fn set_rate_limit(&self, max_per_second: u32) {
-        let mut state = self.rate_limit_state.lock().expect("rate limit mutex poisoned");
-        state.max_per_window = max_per_second;
+        let mut state = self.limiter.lock().expect("rate limit mutex poisoned");
+        state.max_override = Some(max_per_second);
         state.window = std::time::Duration::from_secs(1);
+        state.per_type.clear();
+    }
+}
+
+impl Drop for TelemetryEmitter {
+    fn drop(&mut self) {
+        // Don't lose the tail of the drop counts when a node shuts down.
+        self.report_drops(true);
     }
 }
 
@@ -496,6 +719,66 @@ mod tests {
         assert_eq!(dropped_full, 1);
     }
 
+    #[test]
+    fn test_limits_merge_and_resolve() {
+        let defaults: TelemetryLimits = serde_json::from_value(serde_json::json!({
+            "max_events_per_sec": 50,
+            "event_types": { "vad.*": { "sample_every": 4 }, "vad.level": { "max_per_sec": 10 } }
+        }))
+        .unwrap();
+        let node: TelemetryLimits = serde_json::from_value(serde_json::json!({
+            "event_types": { "vad.level": { "sample_every": 2 } }
+        }))
+        .unwrap();
+        let limits = defaults.merged(&node);
+
+        assert_eq!(limits.max_events_per_sec, Some(50));
+        assert_eq!(limits.resolve("vad.level"), (Some(10), 2));
+        assert_eq!(limits.resolve("vad.start"), (None, 4));
+        assert_eq!(limits.resolve("vadx.start"), (None, 1));
+        assert_eq!(limits.resolve("stt.result"), (None, 1));
+
+        let params = serde_json::json!({ "telemetry": { "max_events_per_sec": "many" } });
+        assert!(TelemetryLimits::for_node_params(Some(&params)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_emitter_sampling_and_rate_limit() {
+        let (tx, mut rx) = mpsc::channel(100);
+        let limits: TelemetryLimits = serde_json::from_value(serde_json::json!({
+            "event_types": {
+                "vad.level": { "sample_every": 3 },
+                "meter.peak": { "max_per_sec": 2 }
+            }
+        }))
+        .unwrap();
+        let emitter = TelemetryEmitter::new("node-1".to_string(), None, Some(tx))
+            .with_limits(Arc::new(limits));
+
+        let sent = (0..9).filter(|_| emitter.emit("vad.level", serde_json::json!({}))).count();
+        assert_eq!(sent, 3);
+        assert_eq!(emitter.dropped_by_sampling(), 6);
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.packet.data.get("sample_every").and_then(JsonValue::as_u64), Some(3));
+
+        let sent = (0..5).filter(|_| emitter.emit("meter.peak", serde_json::json!({}))).count();
+        assert_eq!(sent, 2);
+        assert_eq!(emitter.dropped_counts(), (0, 3));
+
+        // Shutting the emitter down reports the outstanding drops.
+        drop(emitter);
+        let mut health = None;
+        while let Ok(event) = rx.try_recv() {
+            if event.event_type() == Some(HEALTH_EVENT_TYPE) {
+                health = Some(event.packet.data);
+            }
+        }
+        let health = health.expect("health event on drop");
+        assert_eq!(health["dropped_due_to_sampling"], 6);
+        assert_eq!(health["dropped_due_to_rate_limit"], 3);
+        assert_eq!(health["dropped_by_event_type"]["meter.peak"], 3);
+    }
+
     #[test]
     fn test_emitter_no_tx() {
         let emitter = TelemetryEmitter::new("node-1".to_string(), None, None);
@@ -112,6 +112,22 @@ pub enum Packet {
     },
 }
 
+impl Packet {
+    /// Approximate payload size in bytes, used for usage accounting.
+    ///
+    /// Audio counts its `f32` samples, text and binary their byte length, and structured
+    /// payloads (transcriptions, custom) their text or JSON size. Metadata is not counted.
+    pub fn payload_size(&self) -> usize {
+        match self {
+            Self::Audio(frame) => frame.len() * std::mem::size_of::<f32>(),
+            Self::Text(text) => text.len(),
+            Self::Transcription(transcription) => transcription.text.len(),
+            Self::Custom(custom) => serde_json::to_vec(&custom.data).map_or(0, |json| json.len()),
+            Self::Binary { data, .. } => data.len(),
+        }
+    }
+}
+
 /// Encoding for [`Packet::Custom`] payloads.
 ///
 /// This is intentionally extensible. For now we keep things user-friendly and debuggable.
@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Gateway trait for WHIP/WHEP signaling
+//!
+//! The server exposes the WHIP (ingest) and WHEP (egress) HTTP endpoints; WebRTC nodes
+//! register here to receive the SDP offers addressed to them. As with [`crate::moq_gateway`],
+//! the implementation lives in the server crate and only the interface is defined in core.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+
+/// Which signaling protocol an endpoint answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebRtcEndpointKind {
+    /// WebRTC-HTTP Ingestion Protocol (RFC 9725): clients publish media into the session
+    Whip,
+    /// WebRTC-HTTP Egress Protocol: clients play media out of the session
+    Whep,
+}
+
+/// Answer to an accepted offer
+#[derive(Debug, Clone)]
+pub struct WebRtcAnswer {
+    /// SDP answer, including all ICE candidates (no trickle)
+    pub sdp: String,
+    /// Identifies the new WHIP/WHEP resource in later requests (e.g. DELETE)
+    pub resource_id: String,
+}
+
+/// Why an offer was not accepted
+#[derive(Debug, Clone)]
+pub enum WebRtcRejection {
+    /// The offer could not be parsed or has nothing the node can use
+    InvalidOffer(String),
+    /// The node is at capacity (a publisher is already connected, too many viewers)
+    Busy(String),
+    /// Negotiation failed on the node's side
+    Failed(String),
+}
+
+impl std::fmt::Display for WebRtcRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidOffer(reason) | Self::Busy(reason) | Self::Failed(reason) => {
+                f.write_str(reason)
+            },
+        }
+    }
+}
+
+/// A signaling request routed to a WebRTC node
+pub enum WebRtcRequest {
+    /// A client sent an SDP offer to start a new resource
+    Offer {
+        /// SDP offer from the client
+        sdp: String,
+        /// Channel to send the answer (or rejection) back to the gateway
+        response_tx: oneshot::Sender<Result<WebRtcAnswer, WebRtcRejection>>,
+    },
+    /// A client deleted its resource
+    Terminate {
+        /// Resource to tear down
+        resource_id: String,
+        /// Receives `false` if the node does not know the resource
+        response_tx: oneshot::Sender<bool>,
+    },
+}
+
+/// Gateway interface that WebRTC nodes use to register their endpoints
+#[async_trait]
+pub trait WebRtcGatewayTrait: Send + Sync {
+    /// Register the endpoint of a session's node
+    ///
+    /// Returns a receiver for the signaling requests addressed to it.
+    async fn register_endpoint(
+        &self,
+        session_id: String,
+        node_id: String,
+        kind: WebRtcEndpointKind,
+    ) -> Result<mpsc::UnboundedReceiver<WebRtcRequest>, String>;
+
+    /// Unregister a node's endpoint
+    async fn unregister_endpoint(&self, session_id: &str, node_id: &str);
+}
+
+/// Global gateway registry - nodes call this to get the gateway
+static GATEWAY: std::sync::OnceLock<Arc<dyn WebRtcGatewayTrait>> = std::sync::OnceLock::new();
+
+/// Initialize the global WebRTC gateway (called by server)
+pub fn init_webrtc_gateway(gateway: Arc<dyn WebRtcGatewayTrait>) {
+    if GATEWAY.set(gateway).is_err() {
+        tracing::warn!("WebRTC gateway already initialized");
+    }
+}
+
+/// Get the global WebRTC gateway (called by nodes)
+pub fn get_webrtc_gateway() -> Option<Arc<dyn WebRtcGatewayTrait>> {
+    GATEWAY.get().cloned()
+}
@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Control-plane latency of dynamic sessions while one of them is flooded.
+//!
+//! Run with `cargo bench -p streamkit-engine --bench session_isolation`. Times a node-state
+//! query round trip on an idle engine, on a quiet session next to a flooded one, on the
+//! flooded session itself, and on a flooded session with an unread output tap. Without
+//! per-session actors and per-pin distributors the flooded cases stall for seconds.
+
+#[path = "../tests/support/mod.rs"]
+mod support;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use streamkit_engine::DynamicEngineHandle;
+use support::{add_node, session_config, start_flooding_session, test_engine};
+
+fn bench_queries(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    runtime: &tokio::runtime::Runtime,
+    name: &str,
+    handle: &DynamicEngineHandle,
+) {
+    group.bench_function(name, |b| {
+        b.iter(|| runtime.block_on(handle.get_node_states()));
+    });
+}
+
+// Setup failures should abort the bench. The group stays open while the flood and the tap
+// are started between benchmarks.
+#[allow(clippy::expect_used, clippy::significant_drop_tightening)]
+fn session_isolation(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .enable_all()
+        .build()
+        .expect("tokio runtime");
+    let engine = test_engine();
+
+    let mut group = c.benchmark_group("session_isolation");
+    group.sample_size(50);
+
+    let idle = runtime.block_on(async {
+        let handle = engine.start_dynamic_actor(session_config("idle"));
+        add_node(&handle, "drain", "test::drain").await;
+        handle
+    });
+    bench_queries(&mut group, &runtime, "idle_session_query", &idle);
+
+    let flooded = runtime.block_on(start_flooding_session(&engine));
+    bench_queries(&mut group, &runtime, "quiet_session_query", &idle);
+    bench_queries(&mut group, &runtime, "flooded_session_query", &flooded);
+
+    // The tap is never read, so its buffer fills and packets are dropped for it alone.
+    let _tap = runtime.block_on(flooded.tap_output("flood", "out")).expect("tap should attach");
+    bench_queries(&mut group, &runtime, "tapped_session_query", &flooded);
+    group.finish();
+
+    runtime.block_on(async {
+        flooded.shutdown_and_wait().await.expect("flooded engine shutdown");
+        idle.shutdown_and_wait().await.expect("idle engine shutdown");
+    });
+}
+
+criterion_group!(benches, session_isolation);
+criterion_main!(benches);
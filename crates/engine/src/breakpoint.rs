@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Connection breakpoints for pipeline debugging.
+//!
+//! Pausing a connection reroutes it through a gate task sitting between the source's pin
+//! distributor and the destination input. While paused, packets queue in the gate, where
+//! they can be inspected, edited and released one at a time. Once installed, a gate stays
+//! on its connection as a pass-through until the connection or one of its nodes goes away,
+//! so resuming never reorders or drops packets.
+//!
+//! A full gate stops reading, so a paused reliable connection backpressures its source
+//! exactly like a stalled consumer would.
+
+use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::types::{CustomPacketData, Packet};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::dynamic_messages::ConnectionId;
+
+/// Packets a paused connection holds before it stops reading from its source.
+pub const GATE_QUEUE_LIMIT: usize = 256;
+
+/// A debugger request for a session's engine.
+#[derive(Debug)]
+pub enum DebugCommand {
+    /// Turns debug mode on or off. Turning it off resumes every paused connection.
+    SetEnabled(bool),
+    /// Holds packets on a connection from now on
+    Pause(ConnectionId),
+    /// Releases the held packets and lets new ones through
+    Resume(ConnectionId),
+    /// Releases the next `count` packets of a paused connection, queued or still to come
+    Step { connection: ConnectionId, count: u32 },
+    /// Lists the packets held on a connection
+    Inspect(ConnectionId),
+    /// Replaces the content of a held packet: the text of a text packet (a JSON string) or
+    /// the data of a custom packet, keeping its type ID and metadata
+    Edit { connection: ConnectionId, packet_id: u64, content: JsonValue },
+}
+
+impl DebugCommand {
+    /// Whether the command needs debug mode to be on.
+    pub const fn requires_debug_mode(&self) -> bool {
+        !matches!(self, Self::SetEnabled(_) | Self::Inspect(_))
+    }
+
+    /// The connection the command targets, if any.
+    pub const fn connection(&self) -> Option<&ConnectionId> {
+        match self {
+            Self::SetEnabled(_) => None,
+            Self::Pause(connection)
+            | Self::Resume(connection)
+            | Self::Inspect(connection)
+            | Self::Step { connection, .. }
+            | Self::Edit { connection, .. } => Some(connection),
+        }
+    }
+}
+
+/// A packet held on a paused connection.
+#[derive(Debug, Clone)]
+pub struct QueuedPacket {
+    /// Position of the packet among all packets the gate has seen, starting at 1
+    pub id: u64,
+    pub packet: Packet,
+}
+
+/// Reply to a [`DebugCommand`].
+#[derive(Debug)]
+pub enum DebugReply {
+    Done,
+    Queue { paused: bool, packets: Vec<QueuedPacket> },
+}
+
+/// Control messages from the engine to a gate.
+pub(crate) enum GateCommand {
+    Pause,
+    Resume,
+    Step(u32),
+    Inspect(oneshot::Sender<DebugReply>),
+    Edit { packet_id: u64, content: JsonValue, reply: oneshot::Sender<Result<(), String>> },
+}
+
+/// Engine-side handle to a gate.
+pub(crate) struct Gate {
+    pub commands: mpsc::Sender<GateCommand>,
+    /// Whether the gate is holding packets, as last commanded
+    pub paused: bool,
+}
+
+impl Gate {
+    /// Spawns a paused gate forwarding `input` to `dest`, for `connection`.
+    pub(crate) fn spawn(
+        connection: ConnectionId,
+        input: mpsc::Receiver<Packet>,
+        dest: mpsc::Sender<Packet>,
+    ) -> Self {
+        let (commands, command_rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            run_gate(input, dest, command_rx).await;
+            tracing::debug!("Breakpoint gate for {} finished", connection);
+        });
+        Self { commands, paused: true }
+    }
+}
+
+fn edit(
+    queue: &mut VecDeque<QueuedPacket>,
+    packet_id: u64,
+    content: JsonValue,
+) -> Result<(), String> {
+    let Some(queued) = queue.iter_mut().find(|q| q.id == packet_id) else {
+        return Err(format!("Packet {packet_id} is not queued"));
+    };
+    queued.packet = match (&queued.packet, content) {
+        (Packet::Text(_), JsonValue::String(text)) => Packet::Text(Arc::from(text)),
+        (Packet::Text(_), _) => {
+            return Err(format!(
+                "Packet {packet_id} is a text packet; its content must be a string"
+            ))
+        },
+        (Packet::Custom(custom), data) => Packet::Custom(Arc::new(CustomPacketData {
+            type_id: custom.type_id.clone(),
+            encoding: custom.encoding,
+            data,
+            metadata: custom.metadata.clone(),
+        })),
+        _ => return Err(format!("Packet {packet_id} is not a text or custom packet")),
+    };
+    Ok(())
+}
+
+async fn run_gate(
+    mut input: mpsc::Receiver<Packet>,
+    dest: mpsc::Sender<Packet>,
+    mut commands: mpsc::Receiver<GateCommand>,
+) {
+    let mut queue: VecDeque<QueuedPacket> = VecDeque::new();
+    let mut paused = true;
+    let mut steps = 0u32;
+    let mut next_id = 0u64;
+    let mut input_open = true;
+
+    loop {
+        while !paused || steps > 0 {
+            let Some(queued) = queue.pop_front() else { break };
+            if dest.send(queued.packet).await.is_err() {
+                return;
+            }
+            if paused {
+                steps -= 1;
+            }
+        }
+        if !input_open && queue.is_empty() {
+            return;
+        }
+
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(GateCommand::Pause) => paused = true,
+                Some(GateCommand::Resume) => {
+                    paused = false;
+                    steps = 0;
+                },
+                Some(GateCommand::Step(count)) => {
+                    if paused {
+                        steps = steps.saturating_add(count);
+                    }
+                },
+                Some(GateCommand::Inspect(reply)) => {
+                    let packets = queue.iter().cloned().collect();
+                    let _ = reply.send(DebugReply::Queue { paused, packets });
+                },
+                Some(GateCommand::Edit { packet_id, content, reply }) => {
+                    let _ = reply.send(edit(&mut queue, packet_id, content));
+                },
+                // The connection or the engine went away; held packets go with it
+                None => return,
+            },
+            packet = input.recv(), if input_open && queue.len() < GATE_QUEUE_LIMIT => match packet {
+                Some(packet) => {
+                    next_id += 1;
+                    queue.push_back(QueuedPacket { id: next_id, packet });
+                },
+                None => input_open = false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Packet {
+        Packet::Text(Arc::from(s))
+    }
+
+    fn as_text(packet: &Packet) -> &str {
+        match packet {
+            Packet::Text(text) => text,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    async fn inspect(gate: &Gate) -> (bool, Vec<QueuedPacket>) {
+        let (reply, rx) = oneshot::channel();
+        gate.commands.send(GateCommand::Inspect(reply)).await.unwrap();
+        match rx.await.unwrap() {
+            DebugReply::Queue { paused, packets } => (paused, packets),
+            DebugReply::Done => panic!("expected a queue"),
+        }
+    }
+
+    #[tokio::test]
+    async fn gate_holds_steps_edits_and_resumes_in_order() {
+        let (input_tx, input_rx) = mpsc::channel(8);
+        let (dest_tx, mut dest_rx) = mpsc::channel(8);
+        let id = ConnectionId::new("a".into(), "out".into(), "b".into(), "in".into());
+        let gate = Gate::spawn(id, input_rx, dest_tx);
+
+        for s in ["one", "two", "three"] {
+            input_tx.send(text(s)).await.unwrap();
+        }
+        tokio::task::yield_now().await;
+        let (paused, packets) = inspect(&gate).await;
+        assert!(paused);
+        assert_eq!(packets.iter().map(|q| q.id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(dest_rx.try_recv().is_err());
+
+        gate.commands.send(GateCommand::Step(1)).await.unwrap();
+        assert_eq!(as_text(&dest_rx.recv().await.unwrap()), "one");
+
+        let (reply, rx) = oneshot::channel();
+        gate.commands
+            .send(GateCommand::Edit { packet_id: 2, content: "edited".into(), reply })
+            .await
+            .unwrap();
+        rx.await.unwrap().unwrap();
+        let (reply, rx) = oneshot::channel();
+        let content = serde_json::json!({ "text": "not a string" });
+        gate.commands.send(GateCommand::Edit { packet_id: 3, content, reply }).await.unwrap();
+        assert!(rx.await.unwrap().is_err());
+
+        gate.commands.send(GateCommand::Resume).await.unwrap();
+        input_tx.send(text("four")).await.unwrap();
+        for expected in ["edited", "three", "four"] {
+            assert_eq!(as_text(&dest_rx.recv().await.unwrap()), expected);
+        }
+
+        // Removing the connection closes the gate once it has nothing left to deliver
+        drop(input_tx);
+        assert!(dest_rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn full_gate_backpressures_its_source() {
+        let (input_tx, input_rx) = mpsc::channel(1);
+        let (dest_tx, _dest_rx) = mpsc::channel(1);
+        let id = ConnectionId::new("a".into(), "out".into(), "b".into(), "in".into());
+        let gate = Gate::spawn(id, input_rx, dest_tx);
+
+        for _ in 0..=GATE_QUEUE_LIMIT {
+            input_tx.send(text("x")).await.unwrap();
+        }
+        tokio::task::yield_now().await;
+        assert!(input_tx.try_send(text("x")).is_err());
+        assert_eq!(inspect(&gate).await.1.len(), GATE_QUEUE_LIMIT);
+    }
+}
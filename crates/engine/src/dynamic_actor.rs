@@ -9,24 +9,31 @@
 //! reconfiguration of the running pipeline.
 
 use crate::{
+    breakpoint::{DebugCommand, DebugReply, Gate, GateCommand},
     constants::DEFAULT_SUBSCRIBER_CHANNEL_CAPACITY,
     dynamic_config::CONTROL_CAPACITY,
-    dynamic_messages::{PinConfigMsg, QueryMessage},
+    dynamic_messages::{ConnectionId, NodeInitResult, PinConfigMsg, QueryMessage},
     dynamic_pin_distributor::PinDistributorActor,
     graph_builder,
+    lookahead::attach_lookahead,
+    usage::{UsageStats, UsageTicket},
+    warm_pool::WarmPool,
 };
 use opentelemetry::KeyValue;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use streamkit_core::control::{EngineControlMessage, NodeControlMessage};
-use streamkit_core::error::StreamKitError;
+use streamkit_core::cost::{CostLedger, NodeMeter};
 use streamkit_core::frame_pool::AudioFramePool;
+use streamkit_core::media_clock::MediaClock;
 use streamkit_core::node::{InitContext, NodeContext, OutputRouting, OutputSender};
 use streamkit_core::pins::PinUpdate;
 use streamkit_core::registry::NodeRegistry;
+use streamkit_core::seed::{self, EffectiveSeed, SEED_EVENT_TYPE};
 use streamkit_core::state::{NodeState, NodeStateUpdate};
 use streamkit_core::stats::{NodeStats, NodeStatsUpdate};
-use streamkit_core::telemetry::TelemetryEvent;
-use streamkit_core::PinCardinality;
+use streamkit_core::telemetry::{TelemetryEmitter, TelemetryEvent, TelemetryLimits};
+use streamkit_core::{PinCardinality, StreamKitError};
 use tokio::sync::mpsc;
 use tracing::Instrument;
 
@@ -37,12 +44,45 @@ pub struct NodePinMetadata {
     pub output_pins: Vec<streamkit_core::OutputPin>,
 }
 
+/// Senders owned by the actor loop and handed out to nodes and construction workers.
+struct ActorChannels {
+    state: mpsc::Sender<NodeStateUpdate>,
+    stats: mpsc::Sender<NodeStatsUpdate>,
+    telemetry: mpsc::Sender<TelemetryEvent>,
+    node_init: mpsc::Sender<NodeInitResult>,
+}
+
+/// Wraps a node's run future and charges the time spent polling it to the node's meter.
+struct MeteredTask<F> {
+    inner: F,
+    meter: Arc<NodeMeter>,
+}
+
+impl<F: std::future::Future + Unpin> std::future::Future for MeteredTask<F> {
+    type Output = F::Output;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let started = std::time::Instant::now();
+        let result = std::pin::Pin::new(&mut self.inner).poll(cx);
+        self.meter.add_cpu_time(started.elapsed());
+        result
+    }
+}
+
 /// The state for the long-running, dynamic engine actor (Control Plane).
 pub struct DynamicEngine {
-    pub(super) registry: NodeRegistry,
+    /// Shared with node construction workers, which run off the actor loop.
+    pub(super) registry: Arc<NodeRegistry>,
     pub(super) control_rx: mpsc::Receiver<EngineControlMessage>,
     pub(super) query_rx: mpsc::Receiver<QueryMessage>,
     pub(super) live_nodes: HashMap<String, graph_builder::LiveNode>,
+    /// Nodes being constructed on a worker task: NodeId -> worker handle
+    pub(super) pending_nodes: HashMap<String, tokio::task::JoinHandle<()>>,
+    /// Control messages touching a pending node, replayed in order once it completes
+    pub(super) deferred_control: VecDeque<EngineControlMessage>,
     /// Map of input Senders: (NodeId, PinName) -> Sender (used when connecting)
     pub(super) node_inputs: HashMap<(String, String), mpsc::Sender<streamkit_core::types::Packet>>,
     /// Map of Pin Distributor configuration Senders: (NodeId, PinName) -> Config Sender
@@ -57,6 +97,16 @@ pub struct DynamicEngine {
     pub(super) session_id: Option<String>,
     /// Per-pipeline audio buffer pool for hot paths (e.g., Opus decode).
     pub(super) audio_pool: std::sync::Arc<AudioFramePool>,
+    /// Engine-wide pre-created nodes, claimed before constructing a node.
+    pub(super) warm_pool: std::sync::Arc<WarmPool>,
+    /// Session media clock shared by all nodes for cross-track sync.
+    pub(super) media_clock: std::sync::Arc<MediaClock>,
+    /// Per-session usage accounting, shared with the engine handle.
+    pub(super) cost_ledger: std::sync::Arc<CostLedger>,
+    /// Engine-wide per-kind usage statistics.
+    pub(super) usage: std::sync::Arc<UsageStats>,
+    /// Usage of each started node, ended when the node stops, fails or is removed.
+    pub(super) usage_tickets: HashMap<String, UsageTicket>,
     /// Buffer capacity for node input channels
     pub(super) node_input_capacity: usize,
     /// Buffer capacity for pin distributor channels
@@ -71,6 +121,15 @@ pub struct DynamicEngine {
     pub(super) stats_subscribers: Vec<mpsc::Sender<NodeStatsUpdate>>,
     /// Subscribers that want to receive telemetry events
     pub(super) telemetry_subscribers: Vec<mpsc::Sender<TelemetryEvent>>,
+    /// Number of output taps handed out so far, used to name their connections
+    pub(super) next_tap_id: u64,
+    /// Whether connections may be paused and stepped (see [`crate::breakpoint`])
+    pub(super) debug_mode: bool,
+    /// Breakpoint gates installed on connections, kept until the connection goes away
+    pub(super) gates: HashMap<ConnectionId, Gate>,
+    /// Input pins `connect_nodes` created on demand, with the connections feeding them.
+    /// Such a pin is removed again once its last connection goes away.
+    pub(super) on_demand_pins: HashMap<(String, String), HashSet<ConnectionId>>,
     // Metrics
     pub(super) nodes_active_gauge: opentelemetry::metrics::Gauge<u64>,
     pub(super) node_state_transitions_counter: opentelemetry::metrics::Counter<u64>,
@@ -97,19 +156,42 @@ impl DynamicEngine {
     }
 
     /// The main actor loop for the dynamic engine (Control Plane).
+    ///
+    /// Each session runs its own actor task, and every output pin gets its own
+    /// `PinDistributorActor` task, so packet fan-out is already sharded per pin and
+    /// never runs on this loop. The select is `biased` so that control and query
+    /// messages are always serviced before the high-frequency state/stats/telemetry
+    /// streams: a node flooding stats cannot push graph operations behind its updates.
+    ///
+    /// Node construction never runs on this loop either: `AddNode` hands the factory and
+    /// `initialize()` call to a worker task, which reports back via a [`NodeInitResult`].
     pub(super) async fn run(mut self) {
         tracing::info!("Dynamic Engine actor started (Per-Pin Distributor Architecture).");
         let (state_tx, mut state_rx) = mpsc::channel(DEFAULT_SUBSCRIBER_CHANNEL_CAPACITY);
         let (stats_tx, mut stats_rx) = mpsc::channel(DEFAULT_SUBSCRIBER_CHANNEL_CAPACITY);
         let (telemetry_tx, mut telemetry_rx) = mpsc::channel(DEFAULT_SUBSCRIBER_CHANNEL_CAPACITY);
+        let (init_tx, mut init_rx) = mpsc::channel(CONTROL_CAPACITY);
+        let channels = ActorChannels {
+            state: state_tx,
+            stats: stats_tx,
+            telemetry: telemetry_tx,
+            node_init: init_tx,
+        };
 
         loop {
             tokio::select! {
+                biased;
+
                 Some(control_msg) = self.control_rx.recv() => {
-                    if !self.handle_engine_control(control_msg, &state_tx, &stats_tx, &telemetry_tx).await {
+                    if !self.handle_engine_control(control_msg, &channels).await {
                         break; // Shutdown requested
                     }
                 },
+                Some(init_result) = init_rx.recv() => {
+                    if !self.handle_node_init_result(init_result, &channels).await {
+                        break; // Shutdown requested by a replayed control message
+                    }
+                },
                 Some(query_msg) = self.query_rx.recv() => {
                     self.handle_query(query_msg).await;
                 },
@@ -153,9 +235,190 @@ impl DynamicEngine {
                 self.telemetry_subscribers.push(tx);
                 let _ = response_tx.send(rx).await;
             },
+            QueryMessage::TapOutput { node_id, pin, response_tx } => {
+                let _ = response_tx.send(self.tap_output(node_id, pin).await).await;
+            },
+            QueryMessage::Debug { command, response_tx } => {
+                self.handle_debug(command, response_tx).await;
+            },
         }
     }
 
+    /// Handles a debugger command.
+    ///
+    /// Inspecting and editing wait for the gate on a spawned task, since a gate stepping
+    /// packets into a full input only answers once that input drains.
+    async fn handle_debug(
+        &mut self,
+        command: DebugCommand,
+        response_tx: mpsc::Sender<Result<DebugReply, String>>,
+    ) {
+        if command.requires_debug_mode() && !self.debug_mode {
+            let _ = response_tx
+                .send(Err("Debug mode is not enabled for this session".to_string()))
+                .await;
+            return;
+        }
+
+        let result = match command {
+            DebugCommand::SetEnabled(enabled) => {
+                self.debug_mode = enabled;
+                if !enabled {
+                    for (id, gate) in &mut self.gates {
+                        if gate.paused && gate.commands.send(GateCommand::Resume).await.is_ok() {
+                            tracing::info!("Resumed {} on leaving debug mode", id);
+                        }
+                        gate.paused = false;
+                    }
+                }
+                tracing::info!("Debug mode {}", if enabled { "enabled" } else { "disabled" });
+                Ok(DebugReply::Done)
+            },
+            DebugCommand::Pause(id) => self.pause_connection(id).await.map(|()| DebugReply::Done),
+            DebugCommand::Resume(id) => {
+                self.send_to_gate(&id, GateCommand::Resume, false).await.map(|()| DebugReply::Done)
+            },
+            DebugCommand::Step { connection, count } => self
+                .send_to_gate(&connection, GateCommand::Step(count), true)
+                .await
+                .map(|()| DebugReply::Done),
+            DebugCommand::Inspect(id) => {
+                let Some(gate) = self.gates.get(&id) else {
+                    let queue = DebugReply::Queue { paused: false, packets: Vec::new() };
+                    let _ = response_tx.send(Ok(queue)).await;
+                    return;
+                };
+                let commands = gate.commands.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = tokio::sync::oneshot::channel();
+                    let result = match commands.send(GateCommand::Inspect(reply)).await {
+                        Ok(()) => rx.await.map_err(|_| format!("Connection {id} has gone away")),
+                        Err(_) => Err(format!("Connection {id} has gone away")),
+                    };
+                    let _ = response_tx.send(result).await;
+                });
+                return;
+            },
+            DebugCommand::Edit { connection, packet_id, content } => {
+                let Some(gate) = self.gates.get(&connection).filter(|gate| gate.paused) else {
+                    let _ = response_tx
+                        .send(Err(format!("Connection {connection} is not paused")))
+                        .await;
+                    return;
+                };
+                let commands = gate.commands.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = tokio::sync::oneshot::channel();
+                    let command = GateCommand::Edit { packet_id, content, reply };
+                    let result = match commands.send(command).await {
+                        Ok(()) => rx
+                            .await
+                            .map_err(|_| format!("Connection {connection} has gone away"))
+                            .and_then(|result| result.map(|()| DebugReply::Done)),
+                        Err(_) => Err(format!("Connection {connection} has gone away")),
+                    };
+                    let _ = response_tx.send(result).await;
+                });
+                return;
+            },
+        };
+        let _ = response_tx.send(result).await;
+    }
+
+    /// Pauses a connection, installing a gate on it the first time.
+    async fn pause_connection(&mut self, id: ConnectionId) -> Result<(), String> {
+        if self.gates.contains_key(&id) {
+            return self.send_to_gate(&id, GateCommand::Pause, false).await.map(|()| {
+                if let Some(gate) = self.gates.get_mut(&id) {
+                    gate.paused = true;
+                }
+            });
+        }
+
+        let dest_key = (id.to_node.to_string(), id.to_pin.to_string());
+        let source_key = (id.from_node.to_string(), id.from_pin.to_string());
+        let (Some(dest_tx), Some(config_tx)) =
+            (self.node_inputs.get(&dest_key), self.pin_distributors.get(&source_key))
+        else {
+            return Err(format!("Connection {id} not found"));
+        };
+
+        // The gate feeds the destination input directly, and the source's pin distributor
+        // feeds the gate instead of that input.
+        let (tx, rx) = mpsc::channel(self.node_input_capacity);
+        let gate = Gate::spawn(id.clone(), rx, dest_tx.clone());
+        if config_tx.send(PinConfigMsg::RetargetConnection { id: id.clone(), tx }).await.is_err() {
+            return Err(format!("Output '{}.{}' has stopped", id.from_node, id.from_pin));
+        }
+        tracing::info!("Paused {}", id);
+        self.gates.insert(id, gate);
+        Ok(())
+    }
+
+    /// Sends a command to a connection's gate. Connections without a gate were never paused,
+    /// which is only an error when the command needs a paused connection.
+    async fn send_to_gate(
+        &mut self,
+        id: &ConnectionId,
+        command: GateCommand,
+        needs_paused: bool,
+    ) -> Result<(), String> {
+        let resumes = matches!(command, GateCommand::Resume);
+        let Some(gate) = self.gates.get_mut(id) else {
+            return if needs_paused {
+                Err(format!("Connection {id} is not paused"))
+            } else {
+                Ok(())
+            };
+        };
+        if needs_paused && !gate.paused {
+            return Err(format!("Connection {id} is not paused"));
+        }
+        if gate.commands.send(command).await.is_err() {
+            // The gate stops once its connection is gone
+            self.gates.remove(id);
+            return Err(format!("Connection {id} not found"));
+        }
+        if resumes {
+            gate.paused = false;
+            tracing::info!("Resumed {}", id);
+        }
+        Ok(())
+    }
+
+    /// Attaches a best-effort observer connection to a node's output pin.
+    ///
+    /// The tap is not part of the graph: it has no destination node, never applies
+    /// backpressure, and is removed by the pin distributor once the receiver is dropped.
+    async fn tap_output(
+        &mut self,
+        node_id: String,
+        pin: String,
+    ) -> Result<mpsc::Receiver<streamkit_core::types::Packet>, String> {
+        let Some(config_tx) = self.pin_distributors.get(&(node_id.clone(), pin.clone())) else {
+            return Err(format!("Output '{node_id}.{pin}' not found"));
+        };
+
+        self.next_tap_id += 1;
+        let (tx, rx) = mpsc::channel(self.node_input_capacity);
+        let id = crate::dynamic_messages::ConnectionId::new(
+            node_id.clone(),
+            pin.clone(),
+            format!("tap#{}", self.next_tap_id),
+            "in".to_string(),
+        );
+        let msg = PinConfigMsg::AddConnection {
+            id,
+            tx,
+            mode: streamkit_core::control::ConnectionMode::BestEffort,
+        };
+        if config_tx.send(msg).await.is_err() {
+            return Err(format!("Output '{node_id}.{pin}' has stopped"));
+        }
+        tracing::info!("Tapped output {}.{}", node_id, pin);
+        Ok(rx)
+    }
+
     /// Checks if all nodes in the pipeline are Ready or Running.
     /// If all nodes are ready, sends Start signal to nodes in Ready state.
     /// This ensures that source nodes don't start producing packets until the entire
@@ -294,6 +557,19 @@ impl DynamicEngine {
             &[KeyValue::new("node_id", update.node_id.clone()), KeyValue::new("state", state_name)],
         );
 
+        // A node that stopped or failed on its own has ended its lifetime
+        match &update.state {
+            NodeState::Failed { .. } => {
+                if let Some(ticket) = self.usage_tickets.remove(&update.node_id) {
+                    ticket.fail();
+                }
+            },
+            NodeState::Stopped { .. } => {
+                self.usage_tickets.remove(&update.node_id);
+            },
+            _ => {},
+        }
+
         // Store the current state
         self.node_states.insert(update.node_id.clone(), update.state.clone());
 
@@ -386,31 +662,193 @@ impl DynamicEngine {
         });
     }
 
-    /// Helper function to initialize a node and its I/O actors (Pin Distributors).
+    /// Hands node construction (factory + `initialize()`) to a worker task.
     ///
-    /// Takes node_id, kind, state_tx, stats_tx, and telemetry_tx by reference since they're cloned
-    /// multiple times internally (for channels, metrics, etc.)
-    async fn initialize_node(
+    /// The node is tracked as `Initializing` right away so the pipeline is not activated
+    /// before it is ready; the worker reports back with a [`NodeInitResult`].
+    fn spawn_node_construction(
         &mut self,
-        node: Box<dyn streamkit_core::ProcessorNode>,
-        node_id: &str,
-        kind: &str,
-        state_tx: &mpsc::Sender<NodeStateUpdate>,
-        stats_tx: &mpsc::Sender<NodeStatsUpdate>,
-        telemetry_tx: &mpsc::Sender<TelemetryEvent>,
-    ) -> Result<(), StreamKitError> {
-        let mut node = node;
-
-        // Tier 1: Initialization-time discovery (dynamic pins, probing external resources, etc.)
-        let init_ctx = InitContext { node_id: node_id.to_string(), state_tx: state_tx.clone() };
-        match node.initialize(&init_ctx).await {
-            Ok(PinUpdate::NoChange | PinUpdate::Updated { .. }) => {},
+        node_id: String,
+        kind: String,
+        params: Option<serde_json::Value>,
+        channels: &ActorChannels,
+    ) {
+        self.node_states.insert(node_id.clone(), NodeState::Initializing);
+
+        let registry = self.registry.clone();
+        let warm_pool = Arc::clone(&self.warm_pool);
+        let state_tx = channels.state.clone();
+        let init_tx = channels.node_init.clone();
+        let span = tracing::info_span!(
+            "node_construct",
+            session.id = %self.session_id.as_deref().unwrap_or("<unknown>"),
+            node.name = %node_id,
+            node.kind = %kind
+        );
+        let worker_node_id = node_id.clone();
+        let worker = tokio::spawn(
+            async move {
+                let node_id = worker_node_id;
+                let warm = warm_pool.claim(&kind, params.as_ref());
+                let (seed, telemetry_limits, created) = if let Some(warm) = warm {
+                    // Created ahead of time from the same params, with a seed of its own
+                    match TelemetryLimits::for_node_params(params.as_ref()) {
+                        Ok(limits) => (warm.seed, Some(limits), Ok(warm.node)),
+                        Err(e) => (None, None, Err(StreamKitError::Configuration(e))),
+                    }
+                } else {
+                    // Nodes that accept a seed always get one, so every run can be reproduced
+                    let resolved = seed::resolve_seed(registry.param_schema(&kind), params)
+                        .and_then(|(params, seed)| {
+                            let limits = TelemetryLimits::for_node_params(params.as_ref())?;
+                            Ok((params, seed, limits))
+                        });
+                    match resolved {
+                        Ok((params, seed, limits)) => {
+                            // Factories may load models or plugins synchronously; run them on
+                            // the blocking pool so they don't hold up a runtime worker.
+                            let (registry, factory_kind) = (Arc::clone(&registry), kind.clone());
+                            let created = tokio::task::spawn_blocking(move || {
+                                registry.create_node(&factory_kind, params.as_ref())
+                            })
+                            .await
+                            .unwrap_or_else(|e| {
+                                Err(StreamKitError::Runtime(format!(
+                                    "Node construction task failed: {e}"
+                                )))
+                            });
+                            (seed, Some(limits), created)
+                        },
+                        Err(e) => (None, None, Err(StreamKitError::Configuration(e))),
+                    }
+                };
+                let result = match created {
+                    Ok(mut node) => {
+                        // Tier 1: Initialization-time discovery (dynamic pins, probing external
+                        // resources, etc.)
+                        let init_ctx = InitContext { node_id: node_id.clone(), state_tx };
+                        let init_result = node.initialize(&init_ctx).await;
+                        match init_result {
+                            Ok(PinUpdate::NoChange | PinUpdate::Updated { .. }) => Ok(node),
+                            Err(e) => Err(e),
+                        }
+                    },
+                    Err(e) => Err(e),
+                };
+                // The actor may have shut down meanwhile; nothing left to report to.
+                let _ = init_tx
+                    .send(NodeInitResult { node_id, kind, telemetry_limits, seed, result })
+                    .await;
+            }
+            .instrument(span),
+        );
+        self.pending_nodes.insert(node_id, worker);
+    }
+
+    /// Handles a completed node construction and replays control messages that were
+    /// waiting on it. Returns false if a replayed message requested shutdown.
+    async fn handle_node_init_result(
+        &mut self,
+        init_result: NodeInitResult,
+        channels: &ActorChannels,
+    ) -> bool {
+        let NodeInitResult { node_id, kind, telemetry_limits, seed, result } = init_result;
+
+        if self.pending_nodes.remove(&node_id).is_none() {
+            // Construction was abandoned (engine shutting down); drop the node.
+            tracing::debug!(node_id = %node_id, "Discarding node whose construction was abandoned");
+            return true;
+        }
+
+        match result {
+            Ok(node) => {
+                self.start_node(node, &node_id, &kind, telemetry_limits, channels);
+                if let Some(seed) = seed {
+                    self.report_seed(&node_id, seed, channels);
+                }
+            },
             Err(e) => {
-                return Err(e);
+                tracing::error!(
+                    node_id = %node_id,
+                    kind = %kind,
+                    error = %e,
+                    "Failed to initialize node"
+                );
+                self.node_states.remove(&node_id);
+                self.usage.record_creation_failure(&kind);
+                // The failed node no longer holds back activation of the rest of the graph.
+                self.check_and_activate_pipeline();
             },
         }
 
+        let deferred = std::mem::take(&mut self.deferred_control);
+        for msg in deferred {
+            if !self.handle_engine_control(msg, channels).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns true if the message touches a node that is still being constructed, or a
+    /// node with earlier deferred messages (so per-node ordering is preserved).
+    fn must_defer(&self, msg: &EngineControlMessage) -> bool {
+        fn touched_nodes(msg: &EngineControlMessage) -> Vec<&str> {
+            match msg {
+                EngineControlMessage::AddNode { node_id, .. }
+                | EngineControlMessage::RemoveNode { node_id }
+                | EngineControlMessage::TuneNode { node_id, .. }
+                | EngineControlMessage::AddPin { node_id, .. }
+                | EngineControlMessage::RemovePin { node_id, .. } => vec![node_id.as_str()],
+                EngineControlMessage::Connect { from_node, to_node, .. }
+                | EngineControlMessage::Disconnect { from_node, to_node, .. } => {
+                    vec![from_node.as_str(), to_node.as_str()]
+                },
+                EngineControlMessage::Shutdown => Vec::new(),
+            }
+        }
+
+        if self.pending_nodes.is_empty() {
+            return false;
+        }
+        let nodes = touched_nodes(msg);
+        nodes.iter().any(|node_id| {
+            self.pending_nodes.contains_key(*node_id)
+                || self
+                    .deferred_control
+                    .iter()
+                    .any(|deferred| touched_nodes(deferred).contains(node_id))
+        })
+    }
+
+    /// Records the seed a node was created with on the session's telemetry bus.
+    fn report_seed(&self, node_id: &str, seed: EffectiveSeed, channels: &ActorChannels) {
+        tracing::info!(
+            node_id = %node_id,
+            seed = seed.seed,
+            generated = seed.generated,
+            "Node created with seed"
+        );
+        let telemetry = TelemetryEmitter::new(
+            node_id.to_string(),
+            self.session_id.clone(),
+            Some(channels.telemetry.clone()),
+        );
+        telemetry.emit(SEED_EVENT_TYPE, serde_json::json!(seed));
+    }
+
+    /// Helper function to wire up a constructed node and its I/O actors (Pin Distributors)
+    /// and spawn its run task.
+    fn start_node(
+        &mut self,
+        node: Box<dyn streamkit_core::ProcessorNode>,
+        node_id: &str,
+        kind: &str,
+        telemetry_limits: Option<Arc<TelemetryLimits>>,
+        channels: &ActorChannels,
+    ) {
         let (control_tx, control_rx) = mpsc::channel(CONTROL_CAPACITY);
+        let cost_meter = self.cost_ledger.register(node_id, kind);
 
         // 0. Capture pin metadata for runtime type validation
         let input_pins = node.input_pins();
@@ -422,12 +860,25 @@ impl DynamicEngine {
 
         // 1. Setup Inputs
         let mut node_inputs_map = HashMap::new();
-        for pin in input_pins {
+        for pin in &input_pins {
             let (tx, rx) = mpsc::channel(self.node_input_capacity);
             // Store the Sender so the engine can provide it to upstream PinDistributors.
             self.node_inputs.insert((node_id.to_string(), pin.name.clone()), tx);
-            node_inputs_map.insert(pin.name, rx);
+            node_inputs_map.insert(pin.name.clone(), rx);
         }
+        // A bad spec is the node's own bug; it then fails on `take_lookahead` with the pin
+        // left in `inputs`, rather than taking the session down here.
+        let lookahead = attach_lookahead(
+            node_id,
+            node.lookahead(),
+            &input_pins,
+            &mut node_inputs_map,
+            self.node_input_capacity,
+        )
+        .unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            None
+        });
 
         // 2. Setup Outputs (Spawn Pin Distributors)
         let mut node_outputs_map = HashMap::new();
@@ -438,8 +889,14 @@ impl DynamicEngine {
 
             // Spawn the PinDistributorActor
             let distributor =
-                PinDistributorActor::new(data_rx, config_rx, node_id.to_string(), pin.name.clone());
-            tokio::spawn(distributor.run());
+                PinDistributorActor::new(data_rx, config_rx, node_id.to_string(), pin.name.clone())
+                    .with_cost_ledger(self.cost_ledger.clone());
+            tokio::spawn(distributor.run().instrument(tracing::debug_span!(
+                "pin_distributor",
+                session.id = %self.session_id.as_deref().unwrap_or("<unknown>"),
+                node.name = %node_id,
+                pin.name = %pin.name
+            )));
 
             // Store the configuration sender in the engine state
             self.pin_distributors.insert((node_id.to_string(), pin.name.clone()), config_tx);
@@ -472,17 +929,23 @@ impl DynamicEngine {
                 OutputRouting::Direct(node_outputs_map),
             ),
             batch_size: self.batch_size,
-            state_tx: state_tx.clone(),
-            stats_tx: Some(stats_tx.clone()),
-            telemetry_tx: Some(telemetry_tx.clone()),
+            state_tx: channels.state.clone(),
+            stats_tx: Some(channels.stats.clone()),
+            telemetry_tx: Some(channels.telemetry.clone()),
             session_id: self.session_id.clone(),
             cancellation_token: None, // Dynamic pipelines don't use cancellation tokens
             pin_management_rx,
             audio_pool: Some(self.audio_pool.clone()),
+            media_clock: Some(self.media_clock.clone()),
+            cost_meter: Some(cost_meter.clone()),
+            telemetry_limits,
+            lookahead,
         };
 
         // 5. Spawn Node
-        let task_handle = tokio::spawn(node.run(context).instrument(tracing::info_span!(
+        let usage_ticket = self.usage.start(kind, Arc::clone(&cost_meter));
+        let run = MeteredTask { inner: node.run(context), meter: cost_meter };
+        let task_handle = tokio::spawn(run.instrument(tracing::info_span!(
             "node_run",
             session.id = %self.session_id.as_deref().unwrap_or("<unknown>"),
             node.name = %node_id,
@@ -490,8 +953,8 @@ impl DynamicEngine {
         )));
         self.live_nodes
             .insert(node_id.to_string(), graph_builder::LiveNode { control_tx, task_handle });
+        self.usage_tickets.insert(node_id.to_string(), usage_ticket);
         self.nodes_active_gauge.record(self.live_nodes.len() as u64, &[]);
-        Ok(())
     }
 
     /// Validates type compatibility between source and destination pins.
@@ -626,7 +1089,7 @@ impl DynamicEngine {
     ///
     /// May create dynamic pins on-demand if the destination node supports them.
     #[allow(clippy::cognitive_complexity)] // Dynamic pin creation inherently complex
-    async fn connect_nodes(
+    pub(crate) async fn connect_nodes(
         &mut self,
         from_node: String,
         from_pin: String,
@@ -658,71 +1121,26 @@ impl DynamicEngine {
 
         // 1. Find the destination input Sender
         // If the pin doesn't exist and the node supports dynamic pins, create it first
-        let dest_tx = if let Some(tx) = self.node_inputs.get(&(to_node.clone(), to_pin.clone())) {
+        let to_key = (to_node.clone(), to_pin.clone());
+        let dest_tx = if let Some(tx) = self.node_inputs.get(&to_key) {
             tx.clone()
-        } else if let Some(pin_mgmt_tx) = self.pin_management_txs.get(&to_node) {
+        } else if self.pin_management_txs.contains_key(&to_node) {
             // Node supports dynamic pins - create the pin on-demand
             tracing::info!(
                 "Dynamically creating input pin '{}.{}' for connection",
                 to_node,
                 to_pin
             );
-
-            // Request pin creation
-            let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-            let msg = streamkit_core::pins::PinManagementMessage::RequestAddInputPin {
-                suggested_name: Some(to_pin.clone()),
-                response_tx,
-            };
-
-            if pin_mgmt_tx.send(msg).await.is_err() {
-                tracing::error!(
-                    "Failed to send pin creation request to node '{}'. It may have stopped.",
-                    to_node
-                );
-                return;
-            }
-
-            // Wait for the pin to be created
-            let pin = match response_rx.await {
-                Ok(Ok(pin)) => pin,
-                Ok(Err(e)) => {
-                    tracing::error!("Node '{}' rejected pin creation: {}", to_node, e);
-                    return;
+            match self.add_dynamic_input_pin(&to_node, Some(to_pin.clone())).await {
+                Ok((_, tx)) => {
+                    self.on_demand_pins.insert(to_key.clone(), HashSet::new());
+                    tx
                 },
-                Err(_) => {
-                    tracing::error!("Node '{}' did not respond to pin creation request", to_node);
+                Err(e) => {
+                    tracing::error!("Cannot connect to '{}.{}': {}", to_node, to_pin, e);
                     return;
                 },
-            };
-
-            // Create the channel for this new pin
-            let (tx, rx) = mpsc::channel(self.node_input_capacity);
-            self.node_inputs.insert((to_node.clone(), pin.name.clone()), tx.clone());
-
-            // Update our pin metadata so future validations can resolve this pin by name.
-            let meta = self.node_pin_metadata.entry(to_node.clone()).or_insert_with(|| {
-                NodePinMetadata { input_pins: Vec::new(), output_pins: Vec::new() }
-            });
-            if !meta.input_pins.iter().any(|p| p.name == pin.name) {
-                meta.input_pins.push(pin.clone());
-            }
-
-            // Notify the node that the pin is ready with its channel
-            let msg = streamkit_core::pins::PinManagementMessage::AddedInputPin {
-                pin: pin.clone(),
-                channel: rx,
-            };
-
-            if pin_mgmt_tx.send(msg).await.is_err() {
-                tracing::error!(
-                    "Failed to send pin activation message to node '{}'. It may have stopped.",
-                    to_node
-                );
-                return;
             }
-
-            tx
         } else {
             tracing::error!(
                 "Cannot connect: Destination input '{}.{}' not found and node doesn't support dynamic pins.",
@@ -751,6 +1169,11 @@ impl DynamicEngine {
             to_node.clone(),
             to_pin.clone(),
         );
+        // Reconnecting replaces the connection, along with any breakpoint gate it had
+        self.gates.remove(&connection_id);
+        if let Some(connections) = self.on_demand_pins.get_mut(&to_key) {
+            connections.insert(connection_id.clone());
+        }
         let msg = PinConfigMsg::AddConnection { id: connection_id, tx: dest_tx, mode };
 
         if config_tx.send(msg).await.is_err() {
@@ -762,11 +1185,105 @@ impl DynamicEngine {
         }
     }
 
+    /// Asks a node with dynamic pins to create an input pin, then wires up its channel.
+    ///
+    /// Returns the name the node chose and the sender feeding the new pin.
+    pub(crate) async fn add_dynamic_input_pin(
+        &mut self,
+        node_id: &str,
+        suggested_name: Option<String>,
+    ) -> Result<(String, mpsc::Sender<streamkit_core::types::Packet>), String> {
+        let Some(pin_mgmt_tx) = self.pin_management_txs.get(node_id) else {
+            return Err(format!("Node '{node_id}' does not support dynamic pins"));
+        };
+
+        // Request pin creation
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let msg = streamkit_core::pins::PinManagementMessage::RequestAddInputPin {
+            suggested_name,
+            response_tx,
+        };
+        if pin_mgmt_tx.send(msg).await.is_err() {
+            return Err(format!("Node '{node_id}' stopped before the pin could be created"));
+        }
+
+        // Wait for the pin to be created
+        let pin = match response_rx.await {
+            Ok(Ok(pin)) => pin,
+            Ok(Err(e)) => return Err(format!("Node '{node_id}' rejected pin creation: {e}")),
+            Err(_) => return Err(format!("Node '{node_id}' did not respond to pin creation")),
+        };
+        if self.node_inputs.contains_key(&(node_id.to_string(), pin.name.clone())) {
+            return Err(format!("Pin '{node_id}.{}' already exists", pin.name));
+        }
+
+        // Create the channel for this new pin
+        let (tx, rx) = mpsc::channel(self.node_input_capacity);
+
+        // Notify the node that the pin is ready with its channel
+        let msg = streamkit_core::pins::PinManagementMessage::AddedInputPin {
+            pin: pin.clone(),
+            channel: rx,
+        };
+        if pin_mgmt_tx.send(msg).await.is_err() {
+            return Err(format!("Node '{node_id}' stopped before the pin could be activated"));
+        }
+        self.node_inputs.insert((node_id.to_string(), pin.name.clone()), tx.clone());
+
+        // Update our pin metadata so future validations can resolve this pin by name.
+        let meta = self
+            .node_pin_metadata
+            .entry(node_id.to_string())
+            .or_insert_with(|| NodePinMetadata { input_pins: Vec::new(), output_pins: Vec::new() });
+        if !meta.input_pins.iter().any(|p| p.name == pin.name) {
+            meta.input_pins.push(pin.clone());
+        }
+
+        Ok((pin.name, tx))
+    }
+
+    /// Removes an input pin from a node with dynamic pins.
+    ///
+    /// The node drops its receiver, so upstream Pin Distributors still feeding the pin see
+    /// the channel close and drop those connections. Pin family templates (`Dynamic`
+    /// cardinality) are not pins themselves and cannot be removed.
+    pub(crate) async fn remove_dynamic_input_pin(
+        &mut self,
+        node_id: &str,
+        pin_name: &str,
+    ) -> Result<(), String> {
+        let Some(pin_mgmt_tx) = self.pin_management_txs.get(node_id) else {
+            return Err(format!("Node '{node_id}' does not support dynamic pins"));
+        };
+        let key = (node_id.to_string(), pin_name.to_string());
+        let removable = self.node_pin_metadata.get(node_id).is_some_and(|meta| {
+            meta.input_pins.iter().any(|p| {
+                p.name == pin_name && !matches!(p.cardinality, PinCardinality::Dynamic { .. })
+            })
+        });
+        if !removable || !self.node_inputs.contains_key(&key) {
+            return Err(format!("Input pin '{node_id}.{pin_name}' not found"));
+        }
+
+        let msg = streamkit_core::pins::PinManagementMessage::RemoveInputPin {
+            pin_name: pin_name.to_string(),
+        };
+        if pin_mgmt_tx.send(msg).await.is_err() {
+            return Err(format!("Node '{node_id}' stopped before the pin could be removed"));
+        }
+        self.node_inputs.remove(&key);
+        self.on_demand_pins.remove(&key);
+        if let Some(meta) = self.node_pin_metadata.get_mut(node_id) {
+            meta.input_pins.retain(|p| p.name != pin_name);
+        }
+        Ok(())
+    }
+
     /// Helper function to disconnect nodes.
     ///
-    /// Takes `&self` not `&mut self` because it only reads from HashMaps and sends messages
-    async fn disconnect_nodes(
-        &self,
+    /// Packets held by a breakpoint on the connection are dropped with it.
+    pub(crate) async fn disconnect_nodes(
+        &mut self,
         from_node: String,
         from_pin: String,
         to_node: String,
@@ -794,7 +1311,8 @@ impl DynamicEngine {
             to_node.clone(),
             to_pin.clone(),
         );
-        let msg = PinConfigMsg::RemoveConnection { id: connection_id };
+        self.gates.remove(&connection_id);
+        let msg = PinConfigMsg::RemoveConnection { id: connection_id.clone() };
 
         if config_tx.send(msg).await.is_err() {
             tracing::warn!(
@@ -803,6 +1321,31 @@ impl DynamicEngine {
                 from_pin
             );
         }
+
+        // 3. Remove the destination pin if connecting created it and nothing else feeds it
+        self.release_on_demand_pin(&connection_id).await;
+    }
+
+    /// Forgets a connection feeding a pin created on demand, removing the pin with its
+    /// last connection (e.g. a mixer input whose participant left).
+    async fn release_on_demand_pin(&mut self, id: &ConnectionId) {
+        let key = (id.to_node.to_string(), id.to_pin.to_string());
+        let Some(connections) = self.on_demand_pins.get_mut(&key) else {
+            return;
+        };
+        if !connections.remove(id) || !connections.is_empty() {
+            return;
+        }
+
+        self.on_demand_pins.remove(&key);
+        match self.remove_dynamic_input_pin(&key.0, &key.1).await {
+            Ok(()) => tracing::info!(
+                "Removed input pin '{}.{}' after its last connection was removed",
+                key.0,
+                key.1
+            ),
+            Err(e) => tracing::debug!("Could not remove on-demand input pin: {}", e),
+        }
     }
 
     /// Helper function to gracefully shut down a node and its associated actors.
@@ -817,6 +1360,8 @@ impl DynamicEngine {
             );
         }
 
+        self.usage_tickets.remove(node_id);
+
         // 1. Stop the node task gracefully
         if let Some(live_node) = self.live_nodes.remove(node_id) {
             // First, try graceful shutdown by sending a control message
@@ -843,8 +1388,19 @@ impl DynamicEngine {
             }
         }
 
-        // 2. Clean up inputs
+        // 2. Clean up inputs, and pins created on demand that only this node was feeding
         self.node_inputs.retain(|(name, _), _| name != node_id);
+        self.on_demand_pins.retain(|(name, _), _| name != node_id);
+        let fed_by_node: Vec<ConnectionId> = self
+            .on_demand_pins
+            .values()
+            .flatten()
+            .filter(|id| &*id.from_node == node_id)
+            .cloned()
+            .collect();
+        for id in fed_by_node {
+            self.release_on_demand_pin(&id).await;
+        }
 
         // 3. Stop and clean up Pin Distributors
         let distributors_to_remove: Vec<(String, String)> =
@@ -862,48 +1418,31 @@ impl DynamicEngine {
         self.node_stats.remove(node_id);
         self.node_pin_metadata.remove(node_id);
         self.pin_management_txs.remove(node_id);
+        self.gates.retain(|id, _| &*id.from_node != node_id && &*id.to_node != node_id);
         self.nodes_active_gauge.record(self.live_nodes.len() as u64, &[]);
     }
 
     /// Handles a single control message sent to the engine.
     /// Returns true if the engine should continue running, false if it should shut down.
+    ///
+    /// Messages touching a node that is still being constructed are deferred and replayed
+    /// once construction completes, so `AddNode` followed by `Connect` keeps working.
     #[allow(clippy::cognitive_complexity)]
     async fn handle_engine_control(
         &mut self,
         msg: EngineControlMessage,
-        state_tx: &mpsc::Sender<NodeStateUpdate>,
-        stats_tx: &mpsc::Sender<NodeStatsUpdate>,
-        telemetry_tx: &mpsc::Sender<TelemetryEvent>,
+        channels: &ActorChannels,
     ) -> bool {
+        if self.must_defer(&msg) {
+            self.deferred_control.push_back(msg);
+            return true;
+        }
+
         match msg {
             EngineControlMessage::AddNode { node_id, kind, params } => {
                 self.engine_operations_counter.add(1, &[KeyValue::new("operation", "add_node")]);
                 tracing::info!(name = %node_id, kind = %kind, "Adding node to graph");
-                match self.registry.create_node(&kind, params.as_ref()) {
-                    Ok(node) => {
-                        // Delegate initialization to helper function
-                        // Pass by reference to avoid unnecessary clones
-                        if let Err(e) = self
-                            .initialize_node(
-                                node,
-                                &node_id,
-                                &kind,
-                                state_tx,
-                                stats_tx,
-                                telemetry_tx,
-                            )
-                            .await
-                        {
-                            tracing::error!(
-                                node_id = %node_id,
-                                kind = %kind,
-                                error = %e,
-                                "Failed to initialize node"
-                            );
-                        }
-                    },
-                    Err(e) => tracing::error!("Failed to create node '{}': {}", node_id, e),
-                }
+                self.spawn_node_construction(node_id, kind, params, channels);
             },
             EngineControlMessage::RemoveNode { node_id } => {
                 self.engine_operations_counter.add(1, &[KeyValue::new("operation", "remove_node")]);
@@ -936,9 +1475,39 @@ impl DynamicEngine {
                     tracing::warn!("Could not tune non-existent node '{}'", node_id);
                 }
             },
+            EngineControlMessage::AddPin { node_id, pin_name, reply } => {
+                self.engine_operations_counter.add(1, &[KeyValue::new("operation", "add_pin")]);
+                let result =
+                    self.add_dynamic_input_pin(&node_id, pin_name).await.map(|(name, _)| name);
+                match &result {
+                    Ok(name) => tracing::info!("Added input pin '{}.{}'", node_id, name),
+                    Err(e) => tracing::warn!("Could not add input pin: {}", e),
+                }
+                if let Some(reply) = reply {
+                    let _ = reply.send(result);
+                }
+            },
+            EngineControlMessage::RemovePin { node_id, pin_name, reply } => {
+                self.engine_operations_counter.add(1, &[KeyValue::new("operation", "remove_pin")]);
+                let result =
+                    self.remove_dynamic_input_pin(&node_id, &pin_name).await.map(|()| pin_name);
+                match &result {
+                    Ok(name) => tracing::info!("Removed input pin '{}.{}'", node_id, name),
+                    Err(e) => tracing::warn!("Could not remove input pin: {}", e),
+                }
+                if let Some(reply) = reply {
+                    let _ = reply.send(result);
+                }
+            },
             EngineControlMessage::Shutdown => {
                 tracing::info!("Received shutdown signal, stopping all nodes");
 
+                // Step 0: Abandon in-flight node construction
+                for (_, worker) in self.pending_nodes.drain() {
+                    worker.abort();
+                }
+                self.deferred_control.clear();
+
                 // Step 1: Close all input channels so nodes blocked on recv() will exit
                 // This ensures nodes that don't check control_rx will still shut down
                 self.node_inputs.clear();
@@ -4,13 +4,16 @@
 
 //! Public client handle for controlling a running dynamic engine.
 
+use crate::breakpoint::{DebugCommand, DebugReply};
 use crate::dynamic_messages::QueryMessage;
 use std::collections::HashMap;
 use std::sync::Arc;
 use streamkit_core::control::EngineControlMessage;
+use streamkit_core::cost::{CostLedger, CostReport};
 use streamkit_core::state::{NodeState, NodeStateUpdate};
 use streamkit_core::stats::{NodeStats, NodeStatsUpdate};
 use streamkit_core::telemetry::TelemetryEvent;
+use streamkit_core::types::Packet;
 use tokio::sync::mpsc;
 
 /// A handle to communicate with a running dynamic engine actor.
@@ -18,6 +21,7 @@ pub struct DynamicEngineHandle {
     control_tx: mpsc::Sender<EngineControlMessage>,
     query_tx: mpsc::Sender<QueryMessage>,
     engine_task: Arc<tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    cost_ledger: Arc<CostLedger>,
 }
 
 impl DynamicEngineHandle {
@@ -26,14 +30,23 @@ impl DynamicEngineHandle {
         control_tx: mpsc::Sender<EngineControlMessage>,
         query_tx: mpsc::Sender<QueryMessage>,
         engine_task: tokio::task::JoinHandle<()>,
+        cost_ledger: Arc<CostLedger>,
     ) -> Self {
         Self {
             control_tx,
             query_tx,
             engine_task: Arc::new(tokio::sync::Mutex::new(Some(engine_task))),
+            cost_ledger,
         }
     }
 
+    /// Returns the session's cumulative resource usage so far.
+    ///
+    /// Reads the shared ledger directly, so it keeps working after the engine has shut down.
+    pub fn cost_report(&self) -> CostReport {
+        self.cost_ledger.report()
+    }
+
     /// Sends a control message to the engine.
     ///
     /// # Errors
@@ -121,6 +134,57 @@ impl DynamicEngineHandle {
         response_rx.recv().await.ok_or_else(|| "Failed to receive response from engine".to_string())
     }
 
+    /// Taps an output pin of a running node.
+    /// Returns a receiver that gets a copy of every subsequent packet the node sends on `pin`.
+    ///
+    /// The tap is best-effort: packets are dropped when the receiver falls behind, so an
+    /// observer never slows the pipeline down. Dropping the receiver removes the tap; the
+    /// receiver closes when the node is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output does not exist or the engine actor has shut down.
+    pub async fn tap_output(
+        &self,
+        node_id: &str,
+        pin: &str,
+    ) -> Result<mpsc::Receiver<Packet>, String> {
+        let (response_tx, mut response_rx) = mpsc::channel(1);
+        self.query_tx
+            .send(QueryMessage::TapOutput {
+                node_id: node_id.to_string(),
+                pin: pin.to_string(),
+                response_tx,
+            })
+            .await
+            .map_err(|_| "Engine actor has shut down".to_string())?;
+
+        response_rx
+            .recv()
+            .await
+            .ok_or_else(|| "Failed to receive response from engine".to_string())?
+    }
+
+    /// Runs a debugger command: toggles debug mode, or pauses, steps, inspects or edits
+    /// the packets on a connection (see [`crate::breakpoint`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command is refused (debug mode off, connection not found or
+    /// not paused, packet not editable) or the engine actor has shut down.
+    pub async fn debug(&self, command: DebugCommand) -> Result<DebugReply, String> {
+        let (response_tx, mut response_rx) = mpsc::channel(1);
+        self.query_tx
+            .send(QueryMessage::Debug { command, response_tx })
+            .await
+            .map_err(|_| "Engine actor has shut down".to_string())?;
+
+        response_rx
+            .recv()
+            .await
+            .ok_or_else(|| "Failed to receive response from engine".to_string())?
+    }
+
     /// Sends a shutdown signal to the engine and waits for it to complete.
     /// This ensures all nodes are properly stopped before returning.
     /// Can only be called once - subsequent calls will return an error.
@@ -6,11 +6,16 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use streamkit_core::seed::EffectiveSeed;
 use streamkit_core::state::{NodeState, NodeStateUpdate};
 use streamkit_core::stats::{NodeStats, NodeStatsUpdate};
-use streamkit_core::telemetry::TelemetryEvent;
+use streamkit_core::telemetry::{TelemetryEvent, TelemetryLimits};
+use streamkit_core::types::Packet;
+use streamkit_core::{ProcessorNode, StreamKitError};
 use tokio::sync::mpsc;
 
+use crate::breakpoint::{DebugCommand, DebugReply};
+
 /// Unique identifier for a connection (FromNode, FromPin, ToNode, ToPin).
 ///
 /// This is carried on the control plane (connect/disconnect) and also used as the
@@ -46,11 +51,44 @@ impl std::fmt::Display for ConnectionId {
 
 /// Query messages for retrieving information from the engine without modifying state.
 pub enum QueryMessage {
-    GetNodeStates { response_tx: mpsc::Sender<HashMap<String, NodeState>> },
-    GetNodeStats { response_tx: mpsc::Sender<HashMap<String, NodeStats>> },
-    SubscribeState { response_tx: mpsc::Sender<mpsc::Receiver<NodeStateUpdate>> },
-    SubscribeStats { response_tx: mpsc::Sender<mpsc::Receiver<NodeStatsUpdate>> },
-    SubscribeTelemetry { response_tx: mpsc::Sender<mpsc::Receiver<TelemetryEvent>> },
+    GetNodeStates {
+        response_tx: mpsc::Sender<HashMap<String, NodeState>>,
+    },
+    GetNodeStats {
+        response_tx: mpsc::Sender<HashMap<String, NodeStats>>,
+    },
+    SubscribeState {
+        response_tx: mpsc::Sender<mpsc::Receiver<NodeStateUpdate>>,
+    },
+    SubscribeStats {
+        response_tx: mpsc::Sender<mpsc::Receiver<NodeStatsUpdate>>,
+    },
+    SubscribeTelemetry {
+        response_tx: mpsc::Sender<mpsc::Receiver<TelemetryEvent>>,
+    },
+    TapOutput {
+        node_id: String,
+        pin: String,
+        response_tx: mpsc::Sender<Result<mpsc::Receiver<Packet>, String>>,
+    },
+    Debug {
+        command: DebugCommand,
+        response_tx: mpsc::Sender<Result<DebugReply, String>>,
+    },
+}
+
+/// Completion message sent back to the actor by a node construction worker.
+///
+/// Creating a node (factory + `initialize()`) may be slow, e.g. plugins loading models
+/// or probing devices, so it runs on a worker task instead of the actor loop.
+pub struct NodeInitResult {
+    pub node_id: String,
+    pub kind: String,
+    /// Resolved telemetry limits (server defaults plus the node's `telemetry` params)
+    pub telemetry_limits: Option<Arc<TelemetryLimits>>,
+    /// Seed the node was created with, if it accepts one
+    pub seed: Option<EffectiveSeed>,
+    pub result: Result<Box<dyn ProcessorNode>, StreamKitError>,
 }
 
 // Re-export ConnectionMode from core for use by pin distributor
@@ -60,11 +98,17 @@ pub use streamkit_core::control::ConnectionMode;
 pub enum PinConfigMsg {
     AddConnection {
         id: ConnectionId,
-        tx: mpsc::Sender<streamkit_core::types::Packet>,
+        tx: mpsc::Sender<Packet>,
         mode: ConnectionMode,
     },
     RemoveConnection {
         id: ConnectionId,
     },
+    /// Points an existing connection at a new sender, keeping its mode and any packet it
+    /// is holding. Ignored if the connection does not exist.
+    RetargetConnection {
+        id: ConnectionId,
+        tx: mpsc::Sender<Packet>,
+    },
     Shutdown,
 }
@@ -5,22 +5,76 @@
 //! Pin distributor actor for the data plane.
 //!
 //! The PinDistributorActor is responsible for distributing packets from a single
-//! output pin to multiple downstream input pins. Supports two connection modes:
+//! output pin to multiple downstream input pins. Supports three connection modes:
 //!
 //! - **Reliable**: Synchronized backpressure - waits for slow consumers
 //! - **BestEffort**: Avoids backpressure; keeps the newest packet when downstream is congested
+//! - **Feedback**: Cycle-closing edge with a one-packet-delay buffer; never waits
 
 use crate::dynamic_messages::{ConnectionId, ConnectionMode, PinConfigMsg};
-use std::collections::HashMap;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::sync::Arc;
 use std::time::Instant;
+use streamkit_core::cost::{CostLedger, NodeMeter};
 use streamkit_core::types::Packet;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 
 /// Information about a downstream connection.
 struct OutputConnection {
+    id: ConnectionId,
     tx: mpsc::Sender<Packet>,
     mode: ConnectionMode,
-    pending_best_effort: Option<Packet>,
+    /// Newest packet that could not be delivered yet (best-effort and feedback connections).
+    pending: Option<Packet>,
+    /// Packet a full reliable connection is waiting to accept, while the other outputs are served
+    blocked: Option<Packet>,
+    /// Cost meter of the downstream node, charged with bytes delivered to it
+    meter: Option<Arc<NodeMeter>>,
+    /// The consumer went away; the connection is dropped at the end of the current packet.
+    closed: bool,
+}
+
+/// Result of a non-blocking send on a feedback connection.
+struct FeedbackSend {
+    delivered: u64,
+    /// An older held packet was overwritten by this one.
+    replaced: bool,
+}
+
+impl OutputConnection {
+    /// Sends on a feedback connection without ever awaiting.
+    ///
+    /// The held packet (if any) goes first so feedback stays in order; if the consumer is
+    /// still busy the new packet takes its place. Returns `None` once the consumer is gone.
+    fn send_feedback(&mut self, packet: Packet) -> Option<FeedbackSend> {
+        let mut delivered = 0;
+        if let Some(held) = self.pending.take() {
+            match self.tx.try_send(held) {
+                Ok(()) => delivered += 1,
+                Err(TrySendError::Full(held)) => self.pending = Some(held),
+                Err(TrySendError::Closed(_)) => return None,
+            }
+        }
+
+        let replaced = self.pending.is_some();
+        if replaced {
+            self.pending = Some(packet);
+        } else {
+            match self.tx.try_send(packet) {
+                Ok(()) => delivered += 1,
+                Err(TrySendError::Full(packet)) => self.pending = Some(packet),
+                Err(TrySendError::Closed(_)) => return None,
+            }
+        }
+        Some(FeedbackSend { delivered, replaced })
+    }
+
+    fn charge(&self, bytes: u64, deliveries: u64) {
+        if let Some(meter) = &self.meter {
+            meter.add_bytes_in(bytes * deliveries);
+        }
+    }
 }
 
 /// Actor responsible for distributing packets from a single output pin (Data Plane).
@@ -29,8 +83,9 @@ pub struct PinDistributorActor {
     data_rx: mpsc::Receiver<streamkit_core::types::Packet>,
     /// Input from the control plane
     config_rx: mpsc::Receiver<PinConfigMsg>,
-    /// Map of active downstream connections with their modes
-    outputs: HashMap<ConnectionId, OutputConnection>,
+    /// Active downstream connections, resolved once when connections change and walked
+    /// in order for every packet
+    outputs: Vec<OutputConnection>,
     /// Metadata for logging
     node_id: String,
     pin_name: String,
@@ -40,12 +95,18 @@ pub struct PinDistributorActor {
     packets_dropped_counter: opentelemetry::metrics::Counter<u64>,
     /// Telemetry: packets dropped due to best-effort backpressure
     best_effort_drops_counter: opentelemetry::metrics::Counter<u64>,
+    /// Telemetry: held feedback packets overwritten before the consumer caught up
+    feedback_drops_counter: opentelemetry::metrics::Counter<u64>,
     /// Telemetry: number of active outputs
     outputs_active_gauge: opentelemetry::metrics::Gauge<u64>,
     /// Telemetry: time spent blocked on downstream backpressure (send().await)
     send_wait_histogram: opentelemetry::metrics::Histogram<f64>,
     /// Pre-built metric labels - allocated once in new(), reused on every packet
     metric_labels: [opentelemetry::KeyValue; 2],
+    /// Session cost ledger, used to look up the meters of connected nodes
+    cost_ledger: Option<Arc<CostLedger>>,
+    /// Cost meter of the node owning this pin, charged with bytes produced
+    source_meter: Option<Arc<NodeMeter>>,
 }
 
 impl PinDistributorActor {
@@ -73,6 +134,12 @@ impl PinDistributorActor {
                 "Number of packets dropped/overwritten on best-effort connections due to backpressure",
             )
             .build();
+        let feedback_drops_counter = meter
+            .u64_counter("pin_distributor.feedback_drops")
+            .with_description(
+                "Number of held packets overwritten on feedback connections because the consumer was busy",
+            )
+            .build();
         let outputs_active_gauge = meter
             .u64_gauge("pin_distributor.outputs_active")
             .with_description("Number of active downstream outputs for a pin")
@@ -92,18 +159,28 @@ impl PinDistributorActor {
         Self {
             data_rx,
             config_rx,
-            outputs: HashMap::new(),
+            outputs: Vec::new(),
             node_id,
             pin_name,
             packets_distributed_counter,
             packets_dropped_counter,
             best_effort_drops_counter,
+            feedback_drops_counter,
             outputs_active_gauge,
             send_wait_histogram,
             metric_labels,
+            cost_ledger: None,
+            source_meter: None,
         }
     }
 
+    /// Enables byte accounting against the session's cost ledger.
+    pub(super) fn with_cost_ledger(mut self, ledger: Arc<CostLedger>) -> Self {
+        self.source_meter = ledger.meter(&self.node_id);
+        self.cost_ledger = Some(ledger);
+        self
+    }
+
     /// The main loop for the Pin Distributor. Handles configuration and packet fan-out with backpressure.
     #[allow(clippy::cognitive_complexity)]
     pub(super) async fn run(mut self) {
@@ -161,10 +238,29 @@ impl PinDistributorActor {
     fn handle_config(&mut self, msg: PinConfigMsg) -> bool {
         match msg {
             PinConfigMsg::AddConnection { id, tx, mode } => {
-                self.outputs.insert(id, OutputConnection { tx, mode, pending_best_effort: None });
+                let meter = self.cost_ledger.as_ref().and_then(|ledger| ledger.meter(&id.to_node));
+                let conn = OutputConnection {
+                    id,
+                    tx,
+                    mode,
+                    pending: None,
+                    blocked: None,
+                    meter,
+                    closed: false,
+                };
+                if let Some(existing) = self.outputs.iter_mut().find(|c| c.id == conn.id) {
+                    *existing = conn;
+                } else {
+                    self.outputs.push(conn);
+                }
             },
             PinConfigMsg::RemoveConnection { id } => {
-                self.outputs.remove(&id);
+                self.outputs.retain(|c| c.id != id);
+            },
+            PinConfigMsg::RetargetConnection { id, tx } => {
+                if let Some(conn) = self.outputs.iter_mut().find(|c| c.id == id) {
+                    conn.tx = tx;
+                }
             },
             PinConfigMsg::Shutdown => {
                 return false;
@@ -178,11 +274,12 @@ impl PinDistributorActor {
     ///
     /// For `Reliable` connections: synchronized backpressure - waits for slow consumers.
     /// For `BestEffort` connections: drops packets when buffer is full (no waiting).
-    #[allow(clippy::cognitive_complexity)] // Fan-out with mode handling requires multiple paths
+    /// For `Feedback` connections: holds the newest undelivered packet (no waiting).
+    ///
+    /// This runs once per packet, so it avoids allocating: outputs live in a dense table,
+    /// blocked and closed ones are tracked in place, and the last output receives the packet
+    /// itself rather than a clone. Only waiting on full reliable outputs allocates.
     async fn distribute_packet(&mut self, packet: Packet) {
-        use futures::stream::{FuturesUnordered, StreamExt};
-        use tokio::sync::mpsc::error::TrySendError;
-
         if self.outputs.is_empty() {
             // No outputs configured - drop packet and record metric
             // Use pre-built labels - no allocation on hot path
@@ -190,169 +287,99 @@ impl PinDistributorActor {
             return;
         }
 
-        // Optimization: Handle the common case of a single destination without cloning.
-        if self.outputs.len() == 1 {
-            // Best-effort needs a small per-output buffer (pending_best_effort), but does not await.
-            if matches!(
-                self.outputs.values().next().map(|c| &c.mode),
-                Some(ConnectionMode::BestEffort)
-            ) {
-                // Use let-else pattern for safety instead of unwrap
-                let Some((id, conn)) = self.outputs.iter_mut().next() else {
-                    tracing::error!(
-                        "{}.{}: Outputs unexpectedly empty despite len() == 1",
-                        self.node_id,
-                        self.pin_name
-                    );
-                    return;
-                };
+        // Sizing custom packets means serializing them, so only do it when metering.
+        let bytes = self.source_meter.as_ref().map_or(0, |meter| {
+            let bytes = packet.payload_size() as u64;
+            meter.add_bytes_out(bytes);
+            bytes
+        });
 
-                // Optimization: try_send first, only store on Full (avoids store-then-take in common case)
-                match conn.tx.try_send(packet) {
-                    Ok(()) => {
-                        self.packets_distributed_counter.add(1, &self.metric_labels);
-                    },
-                    Err(TrySendError::Full(packet)) => {
-                        // Channel full - store packet for later (drop-old semantics)
-                        if conn.pending_best_effort.is_some() {
-                            self.best_effort_drops_counter.add(1, &self.metric_labels);
-                        }
-                        conn.pending_best_effort = Some(packet);
-                    },
-                    Err(TrySendError::Closed(_packet)) => {
-                        let id = id.clone();
-                        tracing::warn!(
-                            "{}.{}: Downstream connection {} closed.",
-                            self.node_id,
-                            self.pin_name,
-                            id
-                        );
-                        self.outputs.remove(&id);
-                    },
-                }
-            } else {
-                // Reliable: preserve synchronized backpressure semantics.
-                let Some((id, conn)) = self.outputs.iter().next() else {
-                    tracing::error!(
-                        "{}.{}: Outputs unexpectedly empty despite len() == 1",
-                        self.node_id,
-                        self.pin_name
-                    );
-                    return;
-                };
-
-                let id = id.clone();
-                let tx = conn.tx.clone();
-
-                match tx.try_send(packet) {
-                    Ok(()) => {
-                        self.packets_distributed_counter.add(1, &self.metric_labels);
-                    },
-                    Err(TrySendError::Full(packet)) => {
-                        let start = Instant::now();
-                        let result = tx.send(packet).await;
-                        self.send_wait_histogram
-                            .record(start.elapsed().as_secs_f64(), &self.metric_labels);
-                        if result.is_err() {
-                            tracing::warn!(
-                                "{}.{}: Downstream connection {} closed.",
-                                self.node_id,
-                                self.pin_name,
-                                id
-                            );
-                            self.outputs.remove(&id);
-                        } else {
-                            self.packets_distributed_counter.add(1, &self.metric_labels);
-                        }
-                    },
-                    Err(TrySendError::Closed(_packet)) => {
-                        tracing::warn!(
-                            "{}.{}: Downstream connection {} closed.",
-                            self.node_id,
-                            self.pin_name,
-                            id
-                        );
-                        self.outputs.remove(&id);
-                    },
-                }
-            }
-            return;
-        }
-
-        // Fan-out to multiple outputs.
-        //
-        // Strategy:
-        // - For Reliable connections: fall back to `send().await` if channel is full.
-        // - For BestEffort connections: keep newest packet in a 1-slot buffer and try_send it.
         let mut successes = 0u64;
         let mut best_effort_drops = 0u64;
-        let mut to_remove: Vec<ConnectionId> = Vec::new();
-        // Let Rust infer future type - avoids Box::pin allocation per future
-        let mut pending = FuturesUnordered::new();
+        let mut feedback_drops = 0u64;
+        let mut any_closed = false;
+        let mut any_blocked = false;
+
+        let last = self.outputs.len() - 1;
+        let mut packet = Some(packet);
+        for (index, conn) in self.outputs.iter_mut().enumerate() {
+            let packet = if index == last { packet.take() } else { packet.clone() };
+            let Some(packet) = packet else { break };
 
-        for (id, conn) in &mut self.outputs {
             match conn.mode {
                 ConnectionMode::BestEffort => {
                     // Optimization: try_send first, only store on Full (avoids store-then-take in common case)
-                    match conn.tx.try_send(packet.clone()) {
+                    match conn.tx.try_send(packet) {
                         Ok(()) => {
+                            conn.charge(bytes, 1);
                             successes += 1;
                         },
-                        Err(TrySendError::Full(packet_clone)) => {
+                        Err(TrySendError::Full(packet)) => {
                             // Channel full - store packet for later (drop-old semantics)
-                            if conn.pending_best_effort.is_some() {
+                            if conn.pending.is_some() {
                                 best_effort_drops += 1;
                             }
-                            conn.pending_best_effort = Some(packet_clone);
-                        },
-                        Err(TrySendError::Closed(_packet_clone)) => {
-                            to_remove.push(id.clone());
+                            conn.pending = Some(packet);
                         },
+                        Err(TrySendError::Closed(_packet)) => conn.closed = true,
                     }
                 },
-                ConnectionMode::Reliable => {
-                    let packet_clone = packet.clone();
-                    match conn.tx.try_send(packet_clone) {
-                        Ok(()) => {
-                            successes += 1;
-                        },
-                        Err(TrySendError::Full(packet_clone)) => {
-                            let id = id.clone();
-                            let tx = conn.tx.clone();
-                            // Push async block directly - no Box::pin allocation
-                            pending.push(async move {
-                                let start = Instant::now();
-                                let result = tx.send(packet_clone).await;
-                                (id, start.elapsed().as_secs_f64(), result)
-                            });
-                        },
-                        Err(TrySendError::Closed(_packet_clone)) => {
-                            to_remove.push(id.clone());
-                        },
-                    }
+                ConnectionMode::Feedback => match conn.send_feedback(packet) {
+                    Some(sent) => {
+                        conn.charge(bytes, sent.delivered);
+                        successes += sent.delivered;
+                        feedback_drops += u64::from(sent.replaced);
+                    },
+                    None => conn.closed = true,
+                },
+                ConnectionMode::Reliable => match conn.tx.try_send(packet) {
+                    Ok(()) => {
+                        conn.charge(bytes, 1);
+                        successes += 1;
+                    },
+                    Err(TrySendError::Full(packet)) => {
+                        conn.blocked = Some(packet);
+                        any_blocked = true;
+                    },
+                    Err(TrySendError::Closed(_packet)) => conn.closed = true,
                 },
             }
+            any_closed |= conn.closed;
         }
 
-        // Wait for all pending reliable sends to complete
-        while let Some((id, waited_secs, result)) = pending.next().await {
-            self.send_wait_histogram.record(waited_secs, &self.metric_labels);
-            if result.is_err() {
-                to_remove.push(id);
-            } else {
-                successes += 1;
+        // Wait for the full reliable outputs only after every other output was served, and
+        // wait on all of them at once so one slow consumer doesn't hold back the rest. The
+        // futures are only built here, when the distributor is about to wait anyway.
+        if any_blocked {
+            let mut waits: FuturesUnordered<_> = self
+                .outputs
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(index, conn)| {
+                    let packet = conn.blocked.take()?;
+                    let tx = conn.tx.clone();
+                    Some(async move {
+                        let start = Instant::now();
+                        let result = tx.send(packet).await;
+                        (index, start.elapsed().as_secs_f64(), result.is_ok())
+                    })
+                })
+                .collect();
+            while let Some((index, waited, delivered)) = waits.next().await {
+                self.send_wait_histogram.record(waited, &self.metric_labels);
+                let Some(conn) = self.outputs.get_mut(index) else { continue };
+                if delivered {
+                    conn.charge(bytes, 1);
+                    successes += 1;
+                } else {
+                    conn.closed = true;
+                    any_closed = true;
+                }
             }
         }
 
-        // Remove closed connections
-        for id in to_remove {
-            tracing::warn!(
-                "{}.{}: Downstream connection {} closed during fan-out.",
-                self.node_id,
-                self.pin_name,
-                id
-            );
-            self.outputs.remove(&id);
+        if any_closed {
+            self.remove_closed_outputs();
         }
 
         // Record metrics
@@ -362,5 +389,23 @@ impl PinDistributorActor {
         if best_effort_drops > 0 {
             self.best_effort_drops_counter.add(best_effort_drops, &self.metric_labels);
         }
+        if feedback_drops > 0 {
+            self.feedback_drops_counter.add(feedback_drops, &self.metric_labels);
+        }
+    }
+
+    fn remove_closed_outputs(&mut self) {
+        self.outputs.retain(|conn| {
+            if conn.closed {
+                tracing::warn!(
+                    "{}.{}: Downstream connection {} closed.",
+                    self.node_id,
+                    self.pin_name,
+                    conn.id
+                );
+            }
+            !conn.closed
+        });
+        self.outputs_active_gauge.record(self.outputs.len() as u64, &self.metric_labels);
     }
 }
@@ -6,9 +6,10 @@ use opentelemetry::{global, KeyValue};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
-use streamkit_core::control::NodeControlMessage;
+use streamkit_core::control::{ConnectionMode, NodeControlMessage};
 use streamkit_core::error::StreamKitError;
 use streamkit_core::frame_pool::AudioFramePool;
+use streamkit_core::media_clock::MediaClock;
 use streamkit_core::node::{InitContext, NodeContext, OutputRouting, OutputSender, ProcessorNode};
 use streamkit_core::packet_meta::{can_connect, packet_type_registry};
 use streamkit_core::pins::PinUpdate;
@@ -20,6 +21,7 @@ use tokio::task::JoinHandle;
 use tracing::Instrument;
 
 use crate::constants::{DEFAULT_ONESHOT_CONTROL_CAPACITY, DEFAULT_STATE_CHANNEL_CAPACITY};
+use crate::lookahead::attach_lookahead;
 
 /// A handle to a live, running node.
 pub struct LiveNode {
@@ -84,6 +86,14 @@ pub async fn wire_and_spawn_graph(
         )));
     }
 
+    // Feedback edges close cycles, which a linear oneshot pipeline cannot have.
+    if let Some(conn) = connections.iter().find(|c| c.mode == ConnectionMode::Feedback) {
+        return Err(StreamKitError::Configuration(format!(
+            "Oneshot pipelines do not support feedback connections: {}.{} -> {}.{}",
+            conn.from_node, conn.from_pin, conn.to_node, conn.to_pin
+        )));
+    }
+
     // --- 1. Initialize nodes (allows Tier 1 dynamic pin discovery) ---
     // Create a dummy state channel for initialization if no state_tx provided
     let (init_state_tx, _init_state_rx) = mpsc::channel(DEFAULT_STATE_CHANNEL_CAPACITY);
@@ -311,6 +321,7 @@ pub async fn wire_and_spawn_graph(
     // --- 3. Spawn each node as a separate actor task ---
     let mut live_nodes = HashMap::new();
     let node_names: Vec<String> = nodes.keys().cloned().collect();
+    let media_clock = Arc::new(MediaClock::new());
 
     for name in node_names {
         tracing::debug!("Spawning node '{}'", name);
@@ -321,14 +332,21 @@ pub async fn wire_and_spawn_graph(
         let input_pins = node.input_pins();
         tracing::debug!("Node '{}' has {} input pins", name, input_pins.len());
 
-        for pin in input_pins {
+        for pin in &input_pins {
             if let Some(rx) = input_rxs.remove(&(name.clone(), pin.name.clone())) {
                 tracing::debug!("Connected input pin '{}.{}'", name, pin.name);
-                node_inputs.insert(pin.name, rx);
+                node_inputs.insert(pin.name.clone(), rx);
             } else {
                 tracing::debug!("Input pin '{}.{}' not connected", name, pin.name);
             }
         }
+        let lookahead = attach_lookahead(
+            &name,
+            node.lookahead(),
+            &input_pins,
+            &mut node_inputs,
+            media_channel_capacity,
+        )?;
 
         let mut direct_outputs = HashMap::new();
         let output_pins = node.output_pins();
@@ -362,6 +380,10 @@ pub async fn wire_and_spawn_graph(
             cancellation_token: cancellation_token.clone(),
             pin_management_rx: None, // Stateless pipelines don't support dynamic pins
             audio_pool: audio_pool.clone(),
+            media_clock: Some(media_clock.clone()),
+            cost_meter: None, // Stateless pipelines aren't metered
+            telemetry_limits: None,
+            lookahead,
         };
 
         tracing::debug!("Starting task for node '{}'", name);
@@ -16,10 +16,15 @@ use tokio::sync::mpsc;
 
 pub mod constants;
 pub mod graph_builder;
+mod lookahead;
 pub mod oneshot;
+pub mod usage;
+pub mod warm_pool;
 
 // Dynamic engine modules (gated by feature flag)
 #[cfg(feature = "dynamic")]
+pub mod breakpoint;
+#[cfg(feature = "dynamic")]
 mod dynamic_actor;
 #[cfg(feature = "dynamic")]
 mod dynamic_config;
@@ -35,6 +40,8 @@ mod dynamic_pin_distributor;
 pub use dynamic_config::DynamicEngineConfig;
 #[cfg(feature = "dynamic")]
 pub use dynamic_handle::DynamicEngineHandle;
+#[cfg(feature = "dynamic")]
+pub use dynamic_messages::ConnectionId;
 pub use oneshot::{OneshotEngineConfig, OneshotPipelineResult};
 
 // Import constants and types (within dynamic module)
@@ -53,6 +60,13 @@ use dynamic_actor::DynamicEngine;
 pub struct Engine {
     pub registry: Arc<RwLock<NodeRegistry>>,
     pub audio_pool: Arc<streamkit_core::AudioFramePool>,
+    /// Runtime that pipeline tasks are spawned on. `None` uses the caller's runtime; the
+    /// server sets this to keep media processing off the API/HTTP runtime.
+    pub runtime: Option<tokio::runtime::Handle>,
+    /// Pre-created node instances that dynamic sessions claim instead of constructing nodes.
+    pub warm_pool: Arc<warm_pool::WarmPool>,
+    /// Per-kind node usage statistics across all dynamic sessions.
+    pub usage: Arc<usage::UsageStats>,
 }
 impl Default for Engine {
     fn default() -> Self {
@@ -145,7 +159,7 @@ impl Engine {
         streamkit_nodes::register_nodes(&mut registry, global_script_allowlist, secrets);
 
         #[cfg(not(feature = "script"))]
-        streamkit_nodes::register_nodes(&mut registry, None, Default::default());
+        streamkit_nodes::register_nodes(&mut registry);
 
         if load_plugins {
             // Load WASM plugins if feature is enabled
@@ -156,6 +170,9 @@ impl Engine {
         Self {
             registry: Arc::new(RwLock::new(registry)),
             audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+            runtime: None,
+            warm_pool: Arc::default(),
+            usage: Arc::default(),
         }
     }
 
@@ -225,12 +242,15 @@ impl Engine {
             guard.clone()
         };
 
+        let cost_ledger = Arc::new(streamkit_core::CostLedger::new());
         let meter = global::meter("skit_engine");
         let dynamic_engine = DynamicEngine {
-            registry: registry_snapshot,
+            registry: Arc::new(registry_snapshot),
             control_rx,
             query_rx,
             live_nodes: HashMap::new(),
+            pending_nodes: HashMap::new(),
+            deferred_control: std::collections::VecDeque::new(),
             node_inputs: HashMap::new(),
             pin_distributors: HashMap::new(),
             pin_management_txs: HashMap::new(),
@@ -238,6 +258,11 @@ impl Engine {
             batch_size: config.packet_batch_size,
             session_id: config.session_id,
             audio_pool: self.audio_pool.clone(),
+            warm_pool: Arc::clone(&self.warm_pool),
+            media_clock: Arc::new(streamkit_core::MediaClock::new()),
+            cost_ledger: Arc::clone(&cost_ledger),
+            usage: Arc::clone(&self.usage),
+            usage_tickets: HashMap::new(),
             node_input_capacity,
             pin_distributor_capacity,
             node_states: HashMap::new(),
@@ -245,6 +270,10 @@ impl Engine {
             node_stats: HashMap::new(),
             stats_subscribers: Vec::new(),
             telemetry_subscribers: Vec::new(),
+            next_tap_id: 0,
+            debug_mode: false,
+            gates: HashMap::new(),
+            on_demand_pins: HashMap::new(),
             nodes_active_gauge: meter
                 .u64_gauge("engine.nodes.active")
                 .with_description("Number of active nodes in the pipeline")
@@ -279,9 +308,13 @@ impl Engine {
                 .build(),
         };
 
-        let engine_task = tokio::spawn(dynamic_engine.run());
+        // Node tasks are spawned from within the actor, so they follow it onto this runtime.
+        let engine_task = match &self.runtime {
+            Some(runtime) => runtime.spawn(dynamic_engine.run()),
+            None => tokio::spawn(dynamic_engine.run()),
+        };
 
-        DynamicEngineHandle::new(control_tx, query_tx, engine_task)
+        DynamicEngineHandle::new(control_tx, query_tx, engine_task, cost_ledger)
     }
 }
 
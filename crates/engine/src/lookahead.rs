@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Wiring for nodes that declare a [`LookaheadSpec`].
+//!
+//! The declared pin's receiver is taken out of the node's inputs and handed to a small task
+//! that runs it through a [`LookaheadBuffer`]; the node gets the task's event stream as
+//! `NodeContext::lookahead`. Backpressure is preserved: when the node stops draining
+//! events, the task blocks and the pin's channel fills up as it would without lookahead.
+
+use std::collections::HashMap;
+use streamkit_core::lookahead::{LookaheadBuffer, LookaheadEvent, LookaheadSpec};
+use streamkit_core::types::Packet;
+use streamkit_core::{InputPin, StreamKitError};
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+/// Replaces the declared pin's receiver in `inputs` with a lookahead event stream.
+///
+/// Returns `Ok(None)` when the node declares no lookahead or the pin is not connected.
+///
+/// # Errors
+///
+/// Returns `StreamKitError::Configuration` if the spec names a pin the node doesn't have.
+pub fn attach_lookahead(
+    node_id: &str,
+    spec: Option<LookaheadSpec>,
+    input_pins: &[InputPin],
+    inputs: &mut HashMap<String, mpsc::Receiver<Packet>>,
+    capacity: usize,
+) -> Result<Option<mpsc::Receiver<LookaheadEvent>>, StreamKitError> {
+    let Some(spec) = spec else {
+        return Ok(None);
+    };
+    if !input_pins.iter().any(|pin| pin.name == spec.pin) {
+        return Err(StreamKitError::Configuration(format!(
+            "Node '{node_id}' declares lookahead on unknown input pin '{}'",
+            spec.pin
+        )));
+    }
+    let Some(input) = inputs.remove(&spec.pin) else {
+        return Ok(None);
+    };
+
+    tracing::debug!("Node '{}' looks ahead {:?} on input pin '{}'", node_id, spec.window, spec.pin);
+    let (tx, rx) = mpsc::channel(capacity);
+    let span = tracing::debug_span!("lookahead", node.name = %node_id, pin.name = %spec.pin);
+    tokio::spawn(run_lookahead(LookaheadBuffer::new(&spec), input, tx).instrument(span));
+    Ok(Some(rx))
+}
+
+async fn run_lookahead(
+    mut buffer: LookaheadBuffer,
+    mut input: mpsc::Receiver<Packet>,
+    tx: mpsc::Sender<LookaheadEvent>,
+) {
+    let mut events = Vec::new();
+    while let Some(packet) = input.recv().await {
+        buffer.push(packet, &mut events);
+        for event in std::mem::take(&mut events) {
+            if tx.send(event).await.is_err() {
+                // The node has finished with its input.
+                return;
+            }
+        }
+    }
+    buffer.finish(&mut events);
+    for event in events {
+        if tx.send(event).await.is_err() {
+            return;
+        }
+    }
+}
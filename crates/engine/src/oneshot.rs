@@ -31,12 +31,18 @@ use crate::{graph_builder, Engine};
 use bytes::Bytes;
 use futures::Stream;
 use std::collections::HashMap;
+use std::sync::Arc;
 use streamkit_api::Pipeline;
 use streamkit_core::control::NodeControlMessage;
 use streamkit_core::error::StreamKitError;
 use streamkit_core::node::ProcessorNode;
+use streamkit_core::seed;
 use tokio::sync::mpsc;
 
+/// Node kinds that produce data on their own and wait for a Start signal. A pipeline
+/// without media input needs at least one of them.
+pub const ONESHOT_SOURCE_KINDS: &[&str] = &["core::file_reader", "audio::silence", "audio::tone"];
+
 /// Configuration for oneshot pipeline execution.
 #[derive(Debug, Clone)]
 pub struct OneshotEngineConfig {
@@ -71,21 +77,74 @@ impl Engine {
     /// - HTTP streaming mode (`has_http_input=true`): Uses http_input node with media stream
     /// - File-based mode (`has_http_input=false`): Uses file_read nodes reading from disk
     ///
+    /// When the engine has a dedicated [`runtime`](Engine::runtime), the pipeline's tasks are
+    /// spawned there.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Pipeline compilation fails
     /// - Nodes cannot be created or wired
     /// - The pipeline structure is invalid for oneshot execution
+    /// - The setup task on the engine's runtime panics or is cancelled
     ///
     /// # Panics
     ///
     /// Panics if the engine's registry lock is poisoned (only possible if a thread panicked
     /// while holding the lock).
-    #[allow(clippy::cognitive_complexity)]
     pub async fn run_oneshot_pipeline<S, E>(
         &self,
         definition: Pipeline,
+        input_stream: S,
+        input_content_type: Option<String>,
+        has_http_input: bool,
+        config: Option<OneshotEngineConfig>,
+    ) -> Result<OneshotPipelineResult, StreamKitError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let Some(runtime) = &self.runtime else {
+            return self
+                .run_oneshot_on_current_runtime(
+                    definition,
+                    input_stream,
+                    input_content_type,
+                    has_http_input,
+                    config,
+                )
+                .await;
+        };
+
+        // Drive the whole setup on the engine's runtime so the node and pump tasks it spawns
+        // land there; only the output receiver comes back to the caller.
+        let engine = Self {
+            registry: Arc::clone(&self.registry),
+            audio_pool: Arc::clone(&self.audio_pool),
+            runtime: None,
+            warm_pool: Arc::clone(&self.warm_pool),
+            usage: Arc::clone(&self.usage),
+        };
+        runtime
+            .spawn(async move {
+                engine
+                    .run_oneshot_on_current_runtime(
+                        definition,
+                        input_stream,
+                        input_content_type,
+                        has_http_input,
+                        config,
+                    )
+                    .await
+            })
+            .await
+            .map_err(|e| StreamKitError::Runtime(format!("Oneshot pipeline setup failed: {e}")))?
+    }
+
+    #[allow(clippy::cognitive_complexity)]
+    async fn run_oneshot_on_current_runtime<S, E>(
+        &self,
+        mut definition: Pipeline,
         mut input_stream: S,
         input_content_type: Option<String>,
         has_http_input: bool,
@@ -103,7 +162,7 @@ impl Engine {
             definition.connections.len()
         );
 
-        // expect is documented in #[doc] Panics section above
+        // expect is documented in the Panics section of `run_oneshot_pipeline`
         #[allow(clippy::expect_used)]
         let registry = {
             let guard = self
@@ -113,6 +172,22 @@ impl Engine {
             guard.clone()
         };
 
+        // Nodes that accept a seed always get one, so a run can be reproduced from the logs
+        for (name, def) in &mut definition.nodes {
+            let (params, seed) =
+                seed::resolve_seed(registry.param_schema(&def.kind), def.params.take())
+                    .map_err(|e| StreamKitError::Configuration(format!("Node '{name}': {e}")))?;
+            def.params = params;
+            if let Some(seed) = seed {
+                tracing::info!(
+                    node_id = %name,
+                    seed = seed.seed,
+                    generated = seed.generated,
+                    "Node created with seed"
+                );
+            }
+        }
+
         // --- 1. Find the special namespaced input and output nodes ---
         let mut input_node_id: Option<String> = None;
         let mut output_node_id: Option<String> = None;
@@ -126,7 +201,7 @@ impl Engine {
             if def.kind == "streamkit::http_output" {
                 output_node_id = Some(name.clone());
             }
-            if def.kind == "core::file_reader" {
+            if ONESHOT_SOURCE_KINDS.contains(&def.kind.as_str()) {
                 source_node_ids.push(name.clone());
             }
         }
@@ -152,11 +227,11 @@ impl Engine {
         } else {
             // File-based mode: ensure we have source nodes
             if source_node_ids.is_empty() {
-                tracing::error!("Pipeline validation failed: no file_reader nodes found");
-                return Err(StreamKitError::Configuration(
-                    "File-based pipelines must contain at least one 'core::file_reader' node."
-                        .to_string(),
-                ));
+                tracing::error!("Pipeline validation failed: no source nodes found");
+                return Err(StreamKitError::Configuration(format!(
+                    "File-based pipelines must contain at least one source node ({}).",
+                    ONESHOT_SOURCE_KINDS.join(", ")
+                )));
             }
             tracing::info!(
                 "File-based mode: {} source node(s), output='{}'",
@@ -291,14 +366,11 @@ impl Engine {
         .await?;
         tracing::info!("Pipeline graph successfully spawned");
 
-        // --- 5.5. Send Start signals to file_reader nodes ---
+        // --- 5.5. Send Start signals to source nodes ---
         // Note: file_reader nodes need Start signals even in HTTP streaming mode
         // (e.g., for mixing scenarios where you have both http_input and file_reader)
         if !source_node_ids.is_empty() {
-            tracing::info!(
-                "Sending Start signals to {} file_reader node(s)",
-                source_node_ids.len()
-            );
+            tracing::info!("Sending Start signals to {} source node(s)", source_node_ids.len());
             for source_id in &source_node_ids {
                 if let Some(node_handle) = live_nodes.get(source_id) {
                     tracing::debug!("Sending Start signal to source node '{}'", source_id);
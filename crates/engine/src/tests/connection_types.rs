@@ -12,7 +12,7 @@ use tokio::sync::mpsc;
 
 /// Helper to create a minimal DynamicEngine for testing
 #[allow(clippy::unwrap_used)] // Tests use unwrap for assertions
-fn create_test_engine() -> DynamicEngine {
+pub(super) fn create_test_engine() -> DynamicEngine {
     let (control_tx, control_rx) = mpsc::channel(32);
     let (query_tx, query_rx) = mpsc::channel(32);
     drop(control_tx);
@@ -20,10 +20,12 @@ fn create_test_engine() -> DynamicEngine {
 
     let meter = opentelemetry::global::meter("test");
     DynamicEngine {
-        registry: NodeRegistry::new(),
+        registry: std::sync::Arc::new(NodeRegistry::new()),
         control_rx,
         query_rx,
         live_nodes: HashMap::new(),
+        pending_nodes: HashMap::new(),
+        deferred_control: std::collections::VecDeque::new(),
         node_inputs: HashMap::new(),
         pin_distributors: HashMap::new(),
         pin_management_txs: HashMap::new(),
@@ -31,6 +33,11 @@ fn create_test_engine() -> DynamicEngine {
         batch_size: 32,
         session_id: None,
         audio_pool: std::sync::Arc::new(streamkit_core::FramePool::<f32>::audio_default()),
+        warm_pool: std::sync::Arc::default(),
+        media_clock: std::sync::Arc::new(streamkit_core::MediaClock::new()),
+        cost_ledger: std::sync::Arc::new(streamkit_core::CostLedger::new()),
+        usage: std::sync::Arc::default(),
+        usage_tickets: HashMap::new(),
         node_input_capacity: 128,
         pin_distributor_capacity: 64,
         node_states: HashMap::new(),
@@ -38,6 +45,10 @@ fn create_test_engine() -> DynamicEngine {
         node_stats: HashMap::new(),
         stats_subscribers: Vec::new(),
         telemetry_subscribers: Vec::new(),
+        next_tap_id: 0,
+        debug_mode: false,
+        gates: HashMap::new(),
+        on_demand_pins: HashMap::new(),
         nodes_active_gauge: meter.u64_gauge("test.nodes").build(),
         node_state_transitions_counter: meter.u64_counter("test.transitions").build(),
         engine_operations_counter: meter.u64_counter("test.operations").build(),
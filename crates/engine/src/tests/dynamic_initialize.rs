@@ -41,7 +41,8 @@ impl ProcessorNode for InitCalledNode {
                 Some(streamkit_core::control::NodeControlMessage::Shutdown) | None => return Ok(()),
                 Some(
                     streamkit_core::control::NodeControlMessage::Start
-                    | streamkit_core::control::NodeControlMessage::UpdateParams(_),
+                    | streamkit_core::control::NodeControlMessage::UpdateParams(_)
+                    | streamkit_core::control::NodeControlMessage::Query { .. },
                 ) => {},
             }
         }
@@ -67,6 +68,9 @@ async fn test_dynamic_engine_calls_initialize() {
     let engine = Engine {
         registry: Arc::new(std::sync::RwLock::new(registry)),
         audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: None,
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
     };
     let handle = engine.start_dynamic_actor(DynamicEngineConfig::default());
 
@@ -97,3 +101,165 @@ async fn test_dynamic_engine_calls_initialize() {
         panic!("failed to shutdown engine: {e}");
     }
 }
+
+struct SlowInitNode {
+    init_delay: std::time::Duration,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for SlowInitNode {
+    fn input_pins(&self) -> Vec<streamkit_core::InputPin> {
+        Vec::new()
+    }
+
+    fn output_pins(&self) -> Vec<streamkit_core::OutputPin> {
+        Vec::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        _ctx: &streamkit_core::InitContext,
+    ) -> Result<streamkit_core::pins::PinUpdate, StreamKitError> {
+        tokio::time::sleep(self.init_delay).await;
+        Ok(streamkit_core::pins::PinUpdate::NoChange)
+    }
+
+    async fn run(
+        self: Box<Self>,
+        mut context: streamkit_core::NodeContext,
+    ) -> Result<(), StreamKitError> {
+        while let Some(msg) = context.control_rx.recv().await {
+            if matches!(msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_slow_initialize_does_not_block_actor() {
+    let mut registry = NodeRegistry::new();
+    registry.register_dynamic(
+        "test::slow_init",
+        |_params| Ok(Box::new(SlowInitNode { init_delay: std::time::Duration::from_millis(300) })),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+
+    let engine = Engine {
+        registry: Arc::new(std::sync::RwLock::new(registry)),
+        audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: None,
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
+    };
+    let handle = engine.start_dynamic_actor(DynamicEngineConfig::default());
+
+    for node_id in ["slow", "doomed"] {
+        if let Err(e) = handle
+            .send_control(streamkit_core::control::EngineControlMessage::AddNode {
+                node_id: node_id.to_string(),
+                kind: "test::slow_init".to_string(),
+                params: None,
+            })
+            .await
+        {
+            panic!("failed to add node: {e}");
+        }
+    }
+    // Removal of a node still under construction is deferred until construction completes.
+    if let Err(e) = handle
+        .send_control(streamkit_core::control::EngineControlMessage::RemoveNode {
+            node_id: "doomed".to_string(),
+        })
+        .await
+    {
+        panic!("failed to remove node: {e}");
+    }
+
+    // Queries are answered while construction is still in flight.
+    let started = std::time::Instant::now();
+    let states = match handle.get_node_states().await {
+        Ok(states) => states,
+        Err(e) => panic!("failed to query states: {e}"),
+    };
+    assert!(
+        started.elapsed() < std::time::Duration::from_millis(200),
+        "query blocked on node construction"
+    );
+    assert!(matches!(states.get("slow"), Some(streamkit_core::NodeState::Initializing)));
+
+    // Once construction completes the deferred RemoveNode is replayed.
+    let mut waited = 0u32;
+    loop {
+        let states = match handle.get_node_states().await {
+            Ok(states) => states,
+            Err(e) => panic!("failed to query states: {e}"),
+        };
+        if !states.contains_key("doomed") && states.contains_key("slow") {
+            break;
+        }
+        assert!(waited < 200, "deferred RemoveNode was not applied");
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        waited += 1;
+    }
+
+    if let Err(e) = handle.shutdown_and_wait().await {
+        panic!("failed to shutdown engine: {e}");
+    }
+}
+
+#[tokio::test]
+async fn test_blocking_factory_does_not_stall_runtime() {
+    let mut registry = NodeRegistry::new();
+    registry.register_dynamic(
+        "test::slow_factory",
+        |_params| {
+            // Stands in for a synchronous model or plugin load
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            Ok(Box::new(SlowInitNode { init_delay: std::time::Duration::ZERO }))
+        },
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+
+    let engine = Engine {
+        registry: Arc::new(std::sync::RwLock::new(registry)),
+        audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: None,
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
+    };
+    // The default test runtime has a single thread, so a factory running on it would stall
+    // the actor until it returned.
+    let handle = engine.start_dynamic_actor(DynamicEngineConfig::default());
+    if let Err(e) = handle
+        .send_control(streamkit_core::control::EngineControlMessage::AddNode {
+            node_id: "slow".to_string(),
+            kind: "test::slow_factory".to_string(),
+            params: None,
+        })
+        .await
+    {
+        panic!("failed to add node: {e}");
+    }
+    // Give the construction task a chance to start before querying.
+    let started = std::time::Instant::now();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    let states = match handle.get_node_states().await {
+        Ok(states) => states,
+        Err(e) => panic!("failed to query states: {e}"),
+    };
+    assert!(
+        started.elapsed() < std::time::Duration::from_millis(200),
+        "query blocked on the node factory"
+    );
+    assert!(matches!(states.get("slow"), Some(streamkit_core::NodeState::Initializing)));
+
+    if let Err(e) = handle.shutdown_and_wait().await {
+        panic!("failed to shutdown engine: {e}");
+    }
+}
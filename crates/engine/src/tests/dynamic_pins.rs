@@ -0,0 +1,156 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Unit tests for explicit dynamic pin creation and removal.
+
+use super::connection_types::create_test_engine;
+use crate::dynamic_actor::NodePinMetadata;
+use crate::dynamic_messages::ConnectionMode;
+use streamkit_core::pins::PinManagementMessage;
+use streamkit_core::types::PacketType;
+use streamkit_core::{InputPin, OutputPin, PinCardinality};
+use tokio::sync::mpsc;
+
+/// Stands in for a node with a dynamic `in` pin family, naming pins `in_<n>` when the
+/// engine does not suggest one. Returns every message it saw once the engine lets go.
+fn spawn_dynamic_node() -> (mpsc::Sender<PinManagementMessage>, tokio::task::JoinHandle<Vec<String>>)
+{
+    let (tx, mut rx) = mpsc::channel(8);
+    let handle = tokio::spawn(async move {
+        let mut seen = Vec::new();
+        let mut next_id = 0;
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                PinManagementMessage::RequestAddInputPin { suggested_name, response_tx } => {
+                    let name = suggested_name.unwrap_or_else(|| {
+                        next_id += 1;
+                        format!("in_{}", next_id - 1)
+                    });
+                    let _ = response_tx.send(Ok(InputPin {
+                        name,
+                        accepts_types: vec![PacketType::Any],
+                        cardinality: PinCardinality::One,
+                    }));
+                },
+                PinManagementMessage::AddedInputPin { pin, .. } => {
+                    seen.push(format!("added {}", pin.name));
+                },
+                PinManagementMessage::RemoveInputPin { pin_name } => {
+                    seen.push(format!("removed {pin_name}"));
+                },
+                _ => {},
+            }
+        }
+        seen
+    });
+    (tx, handle)
+}
+
+#[tokio::test]
+#[allow(clippy::unwrap_used)] // Tests use unwrap for assertions
+async fn test_add_and_remove_dynamic_input_pin() {
+    let mut engine = create_test_engine();
+    let (tx, node) = spawn_dynamic_node();
+    engine.pin_management_txs.insert("mixer".to_string(), tx);
+    engine.node_pin_metadata.insert(
+        "mixer".to_string(),
+        NodePinMetadata {
+            input_pins: vec![InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::Dynamic { prefix: "in".to_string() },
+            }],
+            output_pins: vec![],
+        },
+    );
+
+    let (name, _) = engine.add_dynamic_input_pin("mixer", None).await.unwrap();
+    assert_eq!(name, "in_0");
+    let (name, _) = engine.add_dynamic_input_pin("mixer", Some("in_guest".into())).await.unwrap();
+    assert_eq!(name, "in_guest");
+    assert!(engine.node_inputs.contains_key(&("mixer".to_string(), "in_guest".to_string())));
+    assert!(engine.add_dynamic_input_pin("mixer", Some("in_guest".into())).await.is_err());
+
+    engine.remove_dynamic_input_pin("mixer", "in_0").await.unwrap();
+    assert!(!engine.node_inputs.contains_key(&("mixer".to_string(), "in_0".to_string())));
+    // Neither the pin family template nor an unknown pin can be removed
+    assert!(engine.remove_dynamic_input_pin("mixer", "in").await.is_err());
+    assert!(engine.remove_dynamic_input_pin("mixer", "in_0").await.is_err());
+    let pins = &engine.node_pin_metadata["mixer"].input_pins;
+    assert_eq!(pins.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["in", "in_guest"]);
+
+    engine.pin_management_txs.clear();
+    let seen = node.await.unwrap();
+    assert_eq!(seen, ["added in_0", "added in_guest", "removed in_0"]);
+}
+
+#[tokio::test]
+async fn test_add_pin_requires_dynamic_pin_support() {
+    let mut engine = create_test_engine();
+    let result = engine.add_dynamic_input_pin("gain", None).await;
+    assert!(result.is_err_and(|e| e.contains("does not support dynamic pins")));
+}
+
+#[tokio::test]
+#[allow(clippy::unwrap_used)] // Tests use unwrap for assertions
+async fn test_pins_created_by_connect_are_removed_with_their_last_connection() {
+    let mut engine = create_test_engine();
+    let (tx, node) = spawn_dynamic_node();
+    engine.pin_management_txs.insert("mixer".to_string(), tx);
+    engine.node_pin_metadata.insert(
+        "mixer".to_string(),
+        NodePinMetadata {
+            input_pins: vec![InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::Dynamic { prefix: "in".to_string() },
+            }],
+            output_pins: vec![],
+        },
+    );
+    // Keeps the distributor config channels open while the engine sends to them
+    let _distributors: Vec<_> = ["alice", "bob"]
+        .into_iter()
+        .map(|source| {
+            engine.node_pin_metadata.insert(
+                source.to_string(),
+                NodePinMetadata {
+                    input_pins: vec![],
+                    output_pins: vec![OutputPin {
+                        name: "out".to_string(),
+                        produces_type: PacketType::Any,
+                        cardinality: PinCardinality::Broadcast,
+                    }],
+                },
+            );
+            let (config_tx, config_rx) = mpsc::channel(8);
+            engine.pin_distributors.insert((source.to_string(), "out".to_string()), config_tx);
+            config_rx
+        })
+        .collect();
+    let pin = ("mixer".to_string(), "in_room".to_string());
+
+    for source in ["alice", "bob"] {
+        engine
+            .connect_nodes(
+                source.into(),
+                "out".into(),
+                "mixer".into(),
+                "in_room".into(),
+                ConnectionMode::Reliable,
+            )
+            .await;
+    }
+    assert!(engine.node_inputs.contains_key(&pin));
+
+    engine.disconnect_nodes("alice".into(), "out".into(), "mixer".into(), "in_room".into()).await;
+    assert!(engine.node_inputs.contains_key(&pin), "bob still feeds the pin");
+    engine.disconnect_nodes("bob".into(), "out".into(), "mixer".into(), "in_room".into()).await;
+    assert!(!engine.node_inputs.contains_key(&pin));
+    assert!(engine.on_demand_pins.is_empty());
+
+    engine.pin_management_txs.clear();
+    let seen = node.await.unwrap();
+    assert_eq!(seen, ["added in_room", "removed in_room"]);
+}
@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Unit tests for engine-provided lookahead inputs.
+
+use super::super::*;
+use crate::constants::DEFAULT_ONESHOT_MEDIA_CAPACITY;
+use std::time::Duration;
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    InputPin, LookaheadEvent, LookaheadSpec, NodeContext, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+
+fn audio_type() -> PacketType {
+    PacketType::RawAudio(AudioFormat {
+        sample_rate: 1000,
+        channels: 1,
+        sample_format: SampleFormat::F32,
+    })
+}
+
+/// Emits `count` frames of 10 ms, each tagged with its index in the first sample.
+struct FrameSource {
+    count: u16,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for FrameSource {
+    fn input_pins(&self) -> Vec<InputPin> {
+        Vec::new()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: audio_type(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        for id in 0..self.count {
+            let mut samples = vec![0.0; 10];
+            samples[0] = f32::from(id);
+            let frame = AudioFrame::new(1000, 1, samples);
+            if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the lookahead events it sees as `peek <id>` / `delayed <id>`.
+struct LookaheadSink {
+    pin: &'static str,
+    seen: mpsc::UnboundedSender<String>,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for LookaheadSink {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        Vec::new()
+    }
+
+    fn lookahead(&self) -> Option<LookaheadSpec> {
+        Some(LookaheadSpec::new(self.pin, Duration::from_millis(20)))
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        assert!(!context.inputs.contains_key("in"), "lookahead pin must not be in inputs");
+        let mut events = context.take_lookahead()?;
+        while let Some(event) = events.recv().await {
+            let (kind, packet) = match event {
+                LookaheadEvent::Peek(packet) => ("peek", packet),
+                LookaheadEvent::Delayed(packet) => ("delayed", packet),
+            };
+            if let Packet::Audio(frame) = packet {
+                let _ = self.seen.send(format!("{kind} {}", frame.samples()[0]));
+            }
+        }
+        Ok(())
+    }
+}
+
+type TestGraph =
+    (HashMap<String, Box<dyn ProcessorNode>>, Vec<Connection>, HashMap<String, String>);
+
+fn graph(sink: LookaheadSink) -> TestGraph {
+    let mut nodes: HashMap<String, Box<dyn ProcessorNode>> = HashMap::new();
+    nodes.insert("src".to_string(), Box::new(FrameSource { count: 4 }));
+    nodes.insert("sink".to_string(), Box::new(sink));
+    let connections = vec![Connection {
+        from_node: "src".to_string(),
+        from_pin: "out".to_string(),
+        to_node: "sink".to_string(),
+        to_pin: "in".to_string(),
+        mode: streamkit_api::ConnectionMode::Reliable,
+        ui_metadata: None,
+    }];
+    let node_kinds = [
+        ("src".to_string(), "test::source".to_string()),
+        ("sink".to_string(), "test::lookahead".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    (nodes, connections, node_kinds)
+}
+
+#[tokio::test]
+#[allow(clippy::unwrap_used)] // Tests use unwrap for assertions
+async fn test_lookahead_delays_declared_pin() {
+    let (seen_tx, mut seen_rx) = mpsc::unbounded_channel();
+    let (nodes, connections, node_kinds) = graph(LookaheadSink { pin: "in", seen: seen_tx });
+
+    let live = graph_builder::wire_and_spawn_graph(
+        nodes,
+        &connections,
+        &node_kinds,
+        1,
+        DEFAULT_ONESHOT_MEDIA_CAPACITY,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    for (_, node) in live {
+        node.task_handle.await.unwrap().unwrap();
+    }
+
+    let mut seen = Vec::new();
+    while let Ok(event) = seen_rx.try_recv() {
+        seen.push(event);
+    }
+    assert_eq!(
+        seen,
+        [
+            "peek 0",
+            "peek 1",
+            "peek 2",
+            "delayed 0",
+            "peek 3",
+            "delayed 1",
+            "delayed 2",
+            "delayed 3"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_lookahead_on_unknown_pin_is_rejected() {
+    let (seen_tx, _seen_rx) = mpsc::unbounded_channel();
+    let (nodes, connections, node_kinds) = graph(LookaheadSink { pin: "missing", seen: seen_tx });
+
+    let Err(err) = graph_builder::wire_and_spawn_graph(
+        nodes,
+        &connections,
+        &node_kinds,
+        1,
+        DEFAULT_ONESHOT_MEDIA_CAPACITY,
+        None,
+        None,
+        None,
+    )
+    .await
+    else {
+        panic!("expected lookahead on an unknown pin to be rejected");
+    };
+
+    match err {
+        StreamKitError::Configuration(msg) => assert!(msg.contains("unknown input pin 'missing'")),
+        other => panic!("expected configuration error, got: {other:?}"),
+    }
+}
@@ -8,6 +8,11 @@
 mod connection_types;
 #[cfg(feature = "dynamic")]
 mod dynamic_initialize;
+#[cfg(feature = "dynamic")]
+mod dynamic_pins;
+mod lookahead;
 mod oneshot_linear;
 #[cfg(feature = "dynamic")]
 mod pin_distributor;
+#[cfg(feature = "dynamic")]
+mod runtime_placement;
@@ -45,6 +45,7 @@ async fn test_oneshot_rejects_fanout() {
             to_node: "a".to_string(),
             to_pin: "in".to_string(),
             mode: streamkit_api::ConnectionMode::Reliable,
+            ui_metadata: None,
         },
         Connection {
             from_node: "src".to_string(),
@@ -52,6 +53,7 @@ async fn test_oneshot_rejects_fanout() {
             to_node: "b".to_string(),
             to_pin: "in".to_string(),
             mode: streamkit_api::ConnectionMode::Reliable,
+            ui_metadata: None,
         },
     ];
 
@@ -152,3 +152,253 @@ async fn pin_distributor_removes_closed_outputs() {
 
     let _ = tokio::time::timeout(std::time::Duration::from_secs(1), actor_handle).await;
 }
+
+#[tokio::test]
+async fn pin_distributor_feedback_never_blocks_and_keeps_newest() {
+    let (data_tx, data_rx) = mpsc::channel(8);
+    let (config_tx, config_rx) = mpsc::channel(8);
+
+    let actor =
+        PinDistributorActor::new(data_rx, config_rx, "node_a".to_string(), "out".to_string());
+    let actor_handle = tokio::spawn(actor.run());
+
+    let (main_tx, mut main_rx) = mpsc::channel(8);
+    // Feedback consumer with room for a single packet that is not read until later.
+    let (feedback_tx, mut feedback_rx) = mpsc::channel(1);
+
+    let main_id = ConnectionId::new(
+        "node_a".to_string(),
+        "out".to_string(),
+        "node_b".to_string(),
+        "in".to_string(),
+    );
+    let feedback_id = ConnectionId::new(
+        "node_a".to_string(),
+        "out".to_string(),
+        "node_upstream".to_string(),
+        "reference".to_string(),
+    );
+
+    for (id, tx, mode) in [
+        (main_id, main_tx, crate::dynamic_messages::ConnectionMode::Reliable),
+        (feedback_id, feedback_tx, crate::dynamic_messages::ConnectionMode::Feedback),
+    ] {
+        if let Err(e) = config_tx.send(PinConfigMsg::AddConnection { id, tx, mode }).await {
+            panic!("failed to add connection: {e}");
+        }
+    }
+
+    let recv_text = |pkt: Option<Packet>| match pkt {
+        Some(Packet::Text(s)) => s.to_string(),
+        other => panic!("unexpected packet: {other:?}"),
+    };
+
+    // The stalled feedback consumer must not hold back the reliable output.
+    for text in ["one", "two", "three"] {
+        if let Err(e) = data_tx.send(Packet::Text(text.into())).await {
+            panic!("failed to send packet: {e}");
+        }
+    }
+    for expected in ["one", "two", "three"] {
+        let pkt = tokio::time::timeout(std::time::Duration::from_secs(1), main_rx.recv()).await;
+        let Ok(pkt) = pkt else {
+            panic!("reliable output stalled behind feedback connection");
+        };
+        assert_eq!(recv_text(pkt), expected);
+    }
+
+    // "one" was delivered immediately; "two" was held, then replaced by "three".
+    assert_eq!(recv_text(feedback_rx.recv().await), "one");
+
+    // The next packet flushes the held one first, keeping feedback in order.
+    if let Err(e) = data_tx.send(Packet::Text("four".into())).await {
+        panic!("failed to send packet: {e}");
+    }
+    assert_eq!(recv_text(main_rx.recv().await), "four");
+    assert_eq!(recv_text(feedback_rx.recv().await), "three");
+
+    drop(data_tx);
+    drop(config_tx);
+
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(1), actor_handle).await;
+}
+
+#[tokio::test]
+async fn pin_distributor_charges_bytes_to_cost_meters() {
+    use streamkit_core::CostLedger;
+
+    let ledger = std::sync::Arc::new(CostLedger::new());
+    for node in ["node_a", "node_b", "node_c"] {
+        ledger.register(node, "core::passthrough");
+    }
+
+    let (data_tx, data_rx) = mpsc::channel(8);
+    let (config_tx, config_rx) = mpsc::channel(8);
+    let actor =
+        PinDistributorActor::new(data_rx, config_rx, "node_a".to_string(), "out".to_string())
+            .with_cost_ledger(std::sync::Arc::clone(&ledger));
+    let actor_handle = tokio::spawn(actor.run());
+
+    let mut receivers = Vec::new();
+    for to_node in ["node_b", "node_c"] {
+        let (tx, rx) = mpsc::channel(8);
+        let id = ConnectionId::new(
+            "node_a".to_string(),
+            "out".to_string(),
+            to_node.to_string(),
+            "in".to_string(),
+        );
+        let mode = crate::dynamic_messages::ConnectionMode::Reliable;
+        if let Err(e) = config_tx.send(PinConfigMsg::AddConnection { id, tx, mode }).await {
+            panic!("failed to add connection to {to_node}: {e}");
+        }
+        receivers.push(rx);
+    }
+
+    for _ in 0..3 {
+        if let Err(e) = data_tx.send(Packet::Text("hello".into())).await {
+            panic!("failed to send packet to distributor: {e}");
+        }
+    }
+    for rx in &mut receivers {
+        for _ in 0..3 {
+            assert!(rx.recv().await.is_some(), "output channel closed unexpectedly");
+        }
+    }
+
+    let report = ledger.report();
+    let bytes = |node: &str| {
+        let Some(cost) = report.nodes.iter().find(|n| n.node_id == node) else {
+            panic!("node {node} was not metered");
+        };
+        (cost.bytes_in, cost.bytes_out)
+    };
+    // Produced once, delivered to each consumer
+    assert_eq!(bytes("node_a"), (0, 15));
+    assert_eq!(bytes("node_b"), (15, 0));
+    assert_eq!(bytes("node_c"), (15, 0));
+
+    drop(data_tx);
+    drop(config_tx);
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(1), actor_handle).await;
+}
+
+#[tokio::test]
+async fn pin_distributor_serves_other_outputs_while_reliable_output_is_full() {
+    use crate::dynamic_messages::ConnectionMode;
+
+    let (data_tx, data_rx) = mpsc::channel(8);
+    let (config_tx, config_rx) = mpsc::channel(8);
+
+    let actor =
+        PinDistributorActor::new(data_rx, config_rx, "node_a".to_string(), "out".to_string());
+    let actor_handle = tokio::spawn(actor.run());
+
+    let connection = |to_node: &str| {
+        ConnectionId::new(
+            "node_a".to_string(),
+            "out".to_string(),
+            to_node.to_string(),
+            "in".to_string(),
+        )
+    };
+    let (slow_tx, mut slow_rx) = mpsc::channel(1);
+    let (fast_tx, mut fast_rx) = mpsc::channel(8);
+    let (stale_tx, mut stale_rx) = mpsc::channel(8);
+
+    // The reliable output comes first, so a blocked send on it must not delay the others.
+    // "node_fast" is added twice; the second registration replaces the first.
+    for (id, tx, mode) in [
+        (connection("node_slow"), slow_tx, ConnectionMode::Reliable),
+        (connection("node_fast"), stale_tx, ConnectionMode::BestEffort),
+        (connection("node_fast"), fast_tx, ConnectionMode::BestEffort),
+    ] {
+        if let Err(e) = config_tx.send(PinConfigMsg::AddConnection { id, tx, mode }).await {
+            panic!("failed to add connection: {e}");
+        }
+    }
+
+    for text in ["one", "two"] {
+        if let Err(e) = data_tx.send(Packet::Text(text.into())).await {
+            panic!("failed to send packet {text}: {e}");
+        }
+    }
+
+    // "two" reaches the best-effort output while the reliable one still holds "one".
+    for expected in ["one", "two"] {
+        let packet = tokio::time::timeout(std::time::Duration::from_secs(1), fast_rx.recv()).await;
+        match packet {
+            Ok(Some(Packet::Text(s))) => assert_eq!(s.as_ref(), expected),
+            other => panic!("unexpected best-effort delivery: {other:?}"),
+        }
+    }
+    assert!(stale_rx.try_recv().is_err(), "replaced connection should receive nothing");
+
+    for expected in ["one", "two"] {
+        match slow_rx.recv().await {
+            Some(Packet::Text(s)) => assert_eq!(s.as_ref(), expected),
+            other => panic!("unexpected reliable delivery: {other:?}"),
+        }
+    }
+
+    drop(data_tx);
+    drop(config_tx);
+
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(1), actor_handle).await;
+}
+
+#[tokio::test]
+async fn pin_distributor_waits_on_full_reliable_outputs_concurrently() {
+    use crate::dynamic_messages::ConnectionMode;
+
+    let (data_tx, data_rx) = mpsc::channel(8);
+    let (config_tx, config_rx) = mpsc::channel(8);
+
+    let actor =
+        PinDistributorActor::new(data_rx, config_rx, "node_a".to_string(), "out".to_string());
+    let actor_handle = tokio::spawn(actor.run());
+
+    let (stalled_tx, mut stalled_rx) = mpsc::channel(1);
+    let (draining_tx, mut draining_rx) = mpsc::channel(1);
+    for (to_node, tx) in [("node_stalled", stalled_tx), ("node_draining", draining_tx)] {
+        let id = ConnectionId::new(
+            "node_a".to_string(),
+            "out".to_string(),
+            to_node.to_string(),
+            "in".to_string(),
+        );
+        let mode = ConnectionMode::Reliable;
+        if let Err(e) = config_tx.send(PinConfigMsg::AddConnection { id, tx, mode }).await {
+            panic!("failed to add connection to {to_node}: {e}");
+        }
+    }
+
+    for text in ["one", "two"] {
+        if let Err(e) = data_tx.send(Packet::Text(text.into())).await {
+            panic!("failed to send packet {text}: {e}");
+        }
+    }
+
+    // Both outputs are full while "two" is distributed; draining the second one must not
+    // wait for the first, which nobody reads yet.
+    for expected in ["one", "two"] {
+        let packet =
+            tokio::time::timeout(std::time::Duration::from_secs(1), draining_rx.recv()).await;
+        match packet {
+            Ok(Some(Packet::Text(s))) => assert_eq!(s.as_ref(), expected),
+            other => panic!("draining output stalled behind the full one: {other:?}"),
+        }
+    }
+
+    for expected in ["one", "two"] {
+        match stalled_rx.recv().await {
+            Some(Packet::Text(s)) => assert_eq!(s.as_ref(), expected),
+            other => panic!("unexpected reliable delivery: {other:?}"),
+        }
+    }
+
+    drop(data_tx);
+    drop(config_tx);
+
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(1), actor_handle).await;
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use super::super::*;
+use std::sync::Arc;
+use streamkit_core::{NodeRegistry, ProcessorNode, StreamKitError};
+
+/// Reports the name of the thread its `run` executes on, then idles until shutdown.
+struct ThreadProbeNode {
+    thread_tx: mpsc::UnboundedSender<Option<String>>,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for ThreadProbeNode {
+    fn input_pins(&self) -> Vec<streamkit_core::InputPin> {
+        Vec::new()
+    }
+
+    fn output_pins(&self) -> Vec<streamkit_core::OutputPin> {
+        Vec::new()
+    }
+
+    async fn run(
+        self: Box<Self>,
+        mut context: streamkit_core::NodeContext,
+    ) -> Result<(), StreamKitError> {
+        let _ = self.thread_tx.send(std::thread::current().name().map(str::to_string));
+        while let Some(msg) = context.control_rx.recv().await {
+            if matches!(msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_dynamic_engine_runs_nodes_on_configured_runtime() {
+    let data_plane = match tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("test-data-plane")
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => panic!("failed to build data-plane runtime: {e}"),
+    };
+
+    let (thread_tx, mut thread_rx) = mpsc::unbounded_channel();
+    let mut registry = NodeRegistry::new();
+    registry.register_dynamic(
+        "test::thread_probe",
+        move |_params| Ok(Box::new(ThreadProbeNode { thread_tx: thread_tx.clone() })),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+
+    let engine = Engine {
+        registry: Arc::new(std::sync::RwLock::new(registry)),
+        audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: Some(data_plane.handle().clone()),
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
+    };
+    let handle = engine.start_dynamic_actor(DynamicEngineConfig::default());
+
+    if let Err(e) = handle
+        .send_control(streamkit_core::control::EngineControlMessage::AddNode {
+            node_id: "probe".to_string(),
+            kind: "test::thread_probe".to_string(),
+            params: None,
+        })
+        .await
+    {
+        panic!("failed to add node: {e}");
+    }
+
+    let Ok(Some(thread_name)) =
+        tokio::time::timeout(std::time::Duration::from_secs(5), thread_rx.recv()).await
+    else {
+        panic!("node never started");
+    };
+    assert_eq!(thread_name.as_deref(), Some("test-data-plane"));
+
+    if let Err(e) = handle.shutdown_and_wait().await {
+        panic!("failed to shutdown engine: {e}");
+    }
+    data_plane.shutdown_background();
+}
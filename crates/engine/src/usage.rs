@@ -0,0 +1,197 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Per-kind node usage statistics.
+//!
+//! Counts how often each node kind is instantiated by dynamic sessions, how long instances
+//! live, how much CPU they keep busy and how often they fail, across every session of an
+//! engine. Only kinds are recorded (no session IDs, node names or params), so the report can
+//! be shared to help decide which nodes deserve optimization work. The pipeline estimator
+//! also uses the measured CPU load for kinds without a declared profile.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use streamkit_core::cost::NodeMeter;
+
+#[derive(Debug, Default)]
+struct KindCounters {
+    instances: u64,
+    active: u64,
+    creation_failures: u64,
+    failed: u64,
+    finished: u64,
+    total_lifetime: Duration,
+    total_cpu: Duration,
+}
+
+/// Usage of one node kind since the engine started.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KindUsage {
+    pub kind: String,
+    /// Instances started
+    pub instances: u64,
+    /// Instances currently running
+    pub active: u64,
+    /// Attempts to create an instance that failed before it started
+    pub creation_failures: u64,
+    /// Started instances that ended in the failed state
+    pub failed: u64,
+    /// Failed creations and runs over all attempts, from 0 to 1
+    pub error_rate: f64,
+    /// Average lifetime of the instances that have ended
+    pub avg_lifetime_secs: Option<f64>,
+    /// CPU cores the instances that have ended kept busy on average over their lifetime
+    pub avg_cpu_cores: Option<f64>,
+}
+
+/// Usage of every node kind seen since the engine started, most instantiated first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub kinds: Vec<KindUsage>,
+}
+
+/// Engine-wide usage statistics, shared by every session.
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    kinds: Mutex<BTreeMap<String, KindCounters>>,
+}
+
+/// A started instance, counted as ended (successfully, unless marked failed) when dropped.
+#[derive(Debug)]
+pub struct UsageTicket {
+    stats: Arc<UsageStats>,
+    kind: String,
+    meter: Arc<NodeMeter>,
+    started: Instant,
+    failed: bool,
+}
+
+impl UsageTicket {
+    /// Ends the instance as failed.
+    pub fn fail(mut self) {
+        self.failed = true;
+    }
+}
+
+impl Drop for UsageTicket {
+    fn drop(&mut self) {
+        self.stats.with_kind(&self.kind, |c| {
+            c.active = c.active.saturating_sub(1);
+            c.finished += 1;
+            c.total_lifetime += self.started.elapsed();
+            c.total_cpu += self.meter.cpu_time();
+            if self.failed {
+                c.failed += 1;
+            }
+        });
+    }
+}
+
+impl UsageStats {
+    /// Counts a started instance of `kind`, whose CPU time is charged to `meter`; it is
+    /// counted as ended when the ticket drops.
+    pub fn start(self: &Arc<Self>, kind: &str, meter: Arc<NodeMeter>) -> UsageTicket {
+        self.with_kind(kind, |c| {
+            c.instances += 1;
+            c.active += 1;
+        });
+        UsageTicket {
+            stats: Arc::clone(self),
+            kind: kind.to_string(),
+            meter,
+            started: Instant::now(),
+            failed: false,
+        }
+    }
+
+    /// Counts an instance of `kind` that could not be created.
+    pub fn record_creation_failure(&self, kind: &str) {
+        self.with_kind(kind, |c| c.creation_failures += 1);
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Counts stay far below 2^52.
+    pub fn report(&self) -> UsageReport {
+        let mut kinds: Vec<_> = self
+            .lock()
+            .iter()
+            .map(|(kind, c)| {
+                let attempts = c.instances + c.creation_failures;
+                let errors = c.failed + c.creation_failures;
+                KindUsage {
+                    kind: kind.clone(),
+                    instances: c.instances,
+                    active: c.active,
+                    creation_failures: c.creation_failures,
+                    failed: c.failed,
+                    error_rate: if attempts == 0 { 0.0 } else { errors as f64 / attempts as f64 },
+                    avg_lifetime_secs: (c.finished > 0)
+                        .then(|| c.total_lifetime.as_secs_f64() / c.finished as f64),
+                    avg_cpu_cores: (!c.total_lifetime.is_zero())
+                        .then(|| c.total_cpu.as_secs_f64() / c.total_lifetime.as_secs_f64()),
+                }
+            })
+            .collect();
+        kinds.sort_by(|a, b| b.instances.cmp(&a.instances).then_with(|| a.kind.cmp(&b.kind)));
+        UsageReport { kinds }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, KindCounters>> {
+        // Counters are plain data, so they are consistent even if a holder panicked.
+        self.kinds.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn with_kind(&self, kind: &str, f: impl FnOnce(&mut KindCounters)) {
+        let mut kinds = self.lock();
+        if let Some(counters) = kinds.get_mut(kind) {
+            f(counters);
+        } else {
+            f(kinds.entry(kind.to_string()).or_default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_instances_lifetimes_and_errors() {
+        let stats = Arc::new(UsageStats::default());
+        let gain = stats.start("audio::gain", Arc::default());
+        let meter = Arc::new(NodeMeter::default());
+        meter.add_cpu_time(Duration::from_secs(3600));
+        let first = stats.start("audio::mixer", meter);
+        let second = stats.start("audio::mixer", Arc::default());
+        stats.record_creation_failure("audio::mixer");
+        drop(first);
+        second.fail();
+
+        let report = stats.report();
+        assert_eq!(report.kinds.len(), 2);
+        let mixer = &report.kinds[0];
+        assert_eq!(mixer.kind, "audio::mixer");
+        assert_eq!((mixer.instances, mixer.active), (2, 0));
+        assert_eq!((mixer.creation_failures, mixer.failed), (1, 1));
+        assert!((mixer.error_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert!(mixer.avg_lifetime_secs.is_some());
+        assert!(mixer.avg_cpu_cores.is_some_and(|cores| cores > 1.0));
+
+        let gain_usage = &report.kinds[1];
+        assert_eq!((gain_usage.instances, gain_usage.active), (1, 1));
+        assert_eq!((gain_usage.avg_lifetime_secs, gain_usage.avg_cpu_cores), (None, None));
+        drop(gain);
+        assert_eq!(stats.report().kinds[1].active, 0);
+    }
+
+    #[test]
+    fn creation_failures_alone_are_all_errors() {
+        let stats = UsageStats::default();
+        stats.record_creation_failure("plugin::native::whisper");
+        let kind = &stats.report().kinds[0];
+        assert_eq!(kind.instances, 0);
+        assert!((kind.error_rate - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,324 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Warm pool of pre-created node instances.
+//!
+//! Creating some nodes takes seconds (e.g. a Whisper or TTS plugin allocating its inference
+//! state), which shows up as session spin-up latency. The server can keep a few instances of
+//! such kinds ready, created ahead of time with a fixed set of params; when a dynamic session
+//! adds a node with exactly that kind and params, it claims a ready instance instead of
+//! constructing one, and the pool refills in the background.
+//!
+//! Only construction is moved ahead of time: a claimed node is still initialized and run by
+//! the session like any other. Nodes that accept a `seed` are created with their own
+//! generated seed, which is reported when the node is claimed.
+
+use opentelemetry::{global, KeyValue};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use streamkit_core::registry::NodeRegistry;
+use streamkit_core::seed::{self, EffectiveSeed};
+use streamkit_core::{ProcessorNode, StreamKitError};
+
+/// A pre-created node, ready to be claimed.
+pub struct WarmNode {
+    pub node: Box<dyn ProcessorNode>,
+    /// Seed the node was created with, if it accepts one
+    pub seed: Option<EffectiveSeed>,
+}
+
+/// Number of instances kept ready for one kind and set of params.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmPoolStatus {
+    pub kind: String,
+    pub size: usize,
+    pub ready: usize,
+}
+
+/// Instances of one kind and set of params.
+struct Slot {
+    registry: Arc<RwLock<NodeRegistry>>,
+    kind: String,
+    params: Option<Value>,
+    size: usize,
+    ready: Vec<WarmNode>,
+    /// Whether a background task is creating instances for this slot
+    filling: bool,
+}
+
+/// Pre-created node instances, shared by every session of an engine.
+pub struct WarmPool {
+    slots: Mutex<HashMap<String, Slot>>,
+    claims_counter: opentelemetry::metrics::Counter<u64>,
+}
+
+impl Default for WarmPool {
+    fn default() -> Self {
+        let meter = global::meter("skit_engine");
+        Self {
+            slots: Mutex::new(HashMap::new()),
+            claims_counter: meter
+                .u64_counter("engine.warm_pool.claims")
+                .with_description(
+                    "Node creations served from (hit) or missing (miss) the warm pool",
+                )
+                .build(),
+        }
+    }
+}
+
+/// Pool key of a kind and its params; missing params and an empty object are the same.
+fn slot_key(kind: &str, params: Option<&Value>) -> String {
+    params.map_or_else(|| format!("{kind}\n{{}}"), |params| format!("{kind}\n{params}"))
+}
+
+fn normalized(params: Option<Value>) -> Option<Value> {
+    params.filter(|p| p.as_object().is_none_or(|o| !o.is_empty()))
+}
+
+/// Creates one instance the way a session would, resolving its seed first.
+fn create(
+    registry: &RwLock<NodeRegistry>,
+    kind: &str,
+    params: Option<&Value>,
+) -> Result<WarmNode, StreamKitError> {
+    let registry = registry
+        .read()
+        .map_err(|e| StreamKitError::Runtime(format!("Registry lock poisoned: {e}")))?;
+    let (params, seed) = seed::resolve_seed(registry.param_schema(kind), params.cloned())
+        .map_err(StreamKitError::Configuration)?;
+    let node = registry.create_node(kind, params.as_ref());
+    drop(registry);
+    Ok(WarmNode { node: node?, seed })
+}
+
+impl WarmPool {
+    /// Keeps `size` instances of `kind` created with `params` ready.
+    ///
+    /// The first instance is created before returning, so a kind or params that can't be
+    /// instantiated fail here; the rest are created in the background. Calling it again for
+    /// the same kind and params changes the size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the first instance can't be created; the pool is left unchanged.
+    pub async fn fill(
+        self: &Arc<Self>,
+        registry: Arc<RwLock<NodeRegistry>>,
+        kind: &str,
+        params: Option<Value>,
+        size: usize,
+    ) -> Result<(), StreamKitError> {
+        let params = normalized(params);
+        let key = slot_key(kind, params.as_ref());
+        if size == 0 {
+            self.lock().remove(&key);
+            return Ok(());
+        }
+
+        let first = {
+            let (registry, kind, params) =
+                (Arc::clone(&registry), kind.to_string(), params.clone());
+            tokio::task::spawn_blocking(move || create(&registry, &kind, params.as_ref()))
+                .await
+                .map_err(|e| StreamKitError::Runtime(format!("Warm pool task failed: {e}")))??
+        };
+
+        let mut slots = self.lock();
+        let slot = slots.entry(key.clone()).or_insert_with(|| Slot {
+            registry,
+            kind: kind.to_string(),
+            params,
+            size,
+            ready: Vec::new(),
+            filling: false,
+        });
+        slot.size = size;
+        slot.ready.push(first);
+        slot.ready.truncate(size);
+        drop(slots);
+        tracing::info!(kind, size, "Filling warm pool");
+        self.refill(&key);
+        Ok(())
+    }
+
+    /// Takes a ready instance of `kind` created with exactly `params`, if there is one.
+    pub fn claim(self: &Arc<Self>, kind: &str, params: Option<&Value>) -> Option<WarmNode> {
+        let params = normalized(params.cloned());
+        let key = slot_key(kind, params.as_ref());
+        let claimed = self.with_slot(&key, |slot| slot.ready.pop())?;
+        let outcome = if claimed.is_some() { "hit" } else { "miss" };
+        self.claims_counter
+            .add(1, &[KeyValue::new("kind", kind.to_string()), KeyValue::new("outcome", outcome)]);
+        tracing::debug!(kind, outcome, "Warm pool claim");
+        self.refill(&key);
+        claimed
+    }
+
+    /// Drops the ready instances of `kind`, e.g. because its plugin is being unloaded.
+    ///
+    /// The pool keeps its size and refills from the registry on the next claim.
+    pub fn evict_kind(&self, kind: &str) {
+        for slot in self.lock().values_mut().filter(|slot| slot.kind == kind) {
+            slot.ready.clear();
+        }
+    }
+
+    /// Size and ready instances of every pooled kind and set of params.
+    pub fn status(&self) -> Vec<WarmPoolStatus> {
+        let mut status: Vec<_> = self
+            .lock()
+            .values()
+            .map(|slot| WarmPoolStatus {
+                kind: slot.kind.clone(),
+                size: slot.size,
+                ready: slot.ready.len(),
+            })
+            .collect();
+        status.sort_by(|a, b| a.kind.cmp(&b.kind));
+        status
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Slot>> {
+        // Slots only hold plain data, so they are consistent even if a holder panicked.
+        self.slots.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Runs `f` on the slot under `key`, if it still exists.
+    fn with_slot<R>(&self, key: &str, f: impl FnOnce(&mut Slot) -> R) -> Option<R> {
+        self.lock().get_mut(key).map(f)
+    }
+
+    /// Starts a background task topping up the slot, unless one is running or it is full.
+    fn refill(self: &Arc<Self>, key: &str) {
+        let started = self.with_slot(key, |slot| {
+            if slot.filling || slot.ready.len() >= slot.size {
+                return None;
+            }
+            slot.filling = true;
+            Some((Arc::clone(&slot.registry), slot.kind.clone(), slot.params.clone()))
+        });
+        let Some((registry, kind, params)) = started.flatten() else { return };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(kind, "No runtime to refill the warm pool on");
+            self.with_slot(key, |slot| slot.filling = false);
+            return;
+        };
+
+        let pool = Arc::clone(self);
+        let key = key.to_string();
+        runtime.spawn_blocking(move || loop {
+            let created = create(&registry, &kind, params.as_ref());
+            let done = pool.with_slot(&key, |slot| {
+                match created {
+                    Ok(node) if slot.ready.len() < slot.size => slot.ready.push(node),
+                    Ok(_) => {},
+                    Err(e) => {
+                        // Don't retry in a loop; the next claim tries again.
+                        tracing::warn!(kind, error = %e, "Failed to create warm pool instance");
+                        slot.filling = false;
+                        return true;
+                    },
+                }
+                let full = slot.ready.len() >= slot.size;
+                slot.filling = !full;
+                full
+            });
+            // The slot was removed meanwhile, or is full
+            if done != Some(false) {
+                return;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use streamkit_core::{InputPin, NodeContext, OutputPin};
+
+    struct NoopNode;
+
+    #[streamkit_core::async_trait]
+    impl ProcessorNode for NoopNode {
+        fn input_pins(&self) -> Vec<InputPin> {
+            Vec::new()
+        }
+
+        fn output_pins(&self) -> Vec<OutputPin> {
+            Vec::new()
+        }
+
+        async fn run(self: Box<Self>, _context: NodeContext) -> Result<(), StreamKitError> {
+            Ok(())
+        }
+    }
+
+    fn registry(created: &Arc<AtomicUsize>) -> Arc<RwLock<NodeRegistry>> {
+        let mut registry = NodeRegistry::new();
+        let created = Arc::clone(created);
+        registry.register_dynamic(
+            "test::slow",
+            move |params| {
+                if params.and_then(|p| p.get("fail")).is_some() {
+                    return Err(StreamKitError::Configuration("fail".to_string()));
+                }
+                created.fetch_add(1, Ordering::SeqCst);
+                Ok(Box::new(NoopNode))
+            },
+            serde_json::json!({ "properties": { "seed": {} } }),
+            vec!["test".to_string()],
+            false,
+        );
+        Arc::new(RwLock::new(registry))
+    }
+
+    async fn wait_ready(pool: &WarmPool, ready: usize) {
+        for _ in 0..100 {
+            if pool.status().first().is_some_and(|s| s.ready == ready) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("warm pool never reached {ready} ready instances: {:?}", pool.status());
+    }
+
+    #[tokio::test]
+    async fn test_claims_matching_params_and_refills() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let pool = Arc::new(WarmPool::default());
+        let params = serde_json::json!({ "model": "base" });
+        pool.fill(registry(&created), "test::slow", Some(params.clone()), 2).await.unwrap();
+        wait_ready(&pool, 2).await;
+
+        // Only the exact kind and params match; an explicit seed is different params.
+        assert!(pool.claim("test::slow", None).is_none());
+        assert!(pool.claim("test::slow", Some(&serde_json::json!({ "model": "tiny" }))).is_none());
+        let claimed = pool.claim("test::slow", Some(&params)).unwrap();
+        assert!(claimed.seed.unwrap().generated);
+
+        wait_ready(&pool, 2).await;
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+
+        pool.evict_kind("test::slow");
+        assert_eq!(pool.status()[0].ready, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fill_fails_for_params_that_cant_be_created() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let pool = Arc::new(WarmPool::default());
+        let params = Some(serde_json::json!({ "fail": true }));
+        assert!(pool.fill(registry(&created), "test::slow", params, 1).await.is_err());
+        assert!(pool.status().is_empty());
+
+        // No params and an empty object are the same pool entry
+        pool.fill(registry(&created), "test::slow", Some(serde_json::json!({})), 1).await.unwrap();
+        assert!(pool.claim("test::slow", None).is_some());
+    }
+}
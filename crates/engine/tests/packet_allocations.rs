@@ -0,0 +1,257 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Heap allocations per packet on the dynamic engine's data path.
+//!
+//! Runs a paced source through its pin distributor into one and three consumers under a
+//! dhat testing profiler and counts the heap blocks allocated while a fixed number of
+//! packets flow. Run with `cargo test -p streamkit-engine --test packet_allocations --
+//! --nocapture` to see the figures.
+//!
+//! Before output routes were resolved up front, fanning out to three consumers allocated
+//! about two blocks per packet (the distributor built a `FuturesUnordered` for every packet);
+//! it now allocates none, only the engine's own background work shows up as a small
+//! fraction of a block per packet. Packets travel by value through tokio channels, whose
+//! block storage is recycled, so there is no per-packet envelope left to pool in a slab.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{ConnectionMode, EngineControlMessage, NodeControlMessage};
+use streamkit_core::state::NodeState;
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use streamkit_engine::{DynamicEngineConfig, DynamicEngineHandle, Engine};
+use tokio::sync::Notify;
+
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+const WARMUP_PACKETS: u64 = 1_000;
+const MEASURED_PACKETS: u64 = 10_000;
+
+#[derive(Default)]
+struct Shared {
+    /// Released once to start the warm-up, then again to start the measured run.
+    gate: Notify,
+    received: AtomicU64,
+}
+
+/// Sends packets one at a time, yielding after each so consumers keep up and no output
+/// backpressures.
+struct PacedSource {
+    shared: Arc<Shared>,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for PacedSource {
+    fn input_pins(&self) -> Vec<InputPin> {
+        Vec::new()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        state_helpers::emit_running(&context.state_tx, context.output_sender.node_name());
+        self.shared.gate.notified().await;
+        for sent in 0..WARMUP_PACKETS + MEASURED_PACKETS {
+            if sent == WARMUP_PACKETS {
+                self.shared.gate.notified().await;
+            }
+            let packet = Packet::Binary {
+                data: bytes::Bytes::from_static(&[0u8; 64]),
+                content_type: None,
+                metadata: None,
+            };
+            if context.output_sender.send("out", packet).await.is_err() {
+                return Ok(());
+            }
+            tokio::task::yield_now().await;
+        }
+        while let Some(msg) = context.control_rx.recv().await {
+            if matches!(msg, NodeControlMessage::Shutdown) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Counts every packet it receives.
+struct CountingSink {
+    shared: Arc<Shared>,
+}
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for CountingSink {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        Vec::new()
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let Some(mut rx) = context.inputs.remove("in") else {
+            return Err(StreamKitError::Runtime("missing input pin".to_string()));
+        };
+        state_helpers::emit_running(&context.state_tx, context.output_sender.node_name());
+        loop {
+            tokio::select! {
+                msg = context.control_rx.recv() => {
+                    if matches!(msg, Some(NodeControlMessage::Shutdown) | None) {
+                        return Ok(());
+                    }
+                },
+                packet = rx.recv() => {
+                    if packet.is_none() {
+                        return Ok(());
+                    }
+                    self.shared.received.fetch_add(1, Ordering::Relaxed);
+                },
+            }
+        }
+    }
+}
+
+fn counting_engine(shared: &Arc<Shared>) -> Engine {
+    let mut registry = NodeRegistry::new();
+    let source_shared = Arc::clone(shared);
+    registry.register_dynamic(
+        "test::paced_source",
+        move |_params| Ok(Box::new(PacedSource { shared: Arc::clone(&source_shared) })),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+    let sink_shared = Arc::clone(shared);
+    registry.register_dynamic(
+        "test::counting_sink",
+        move |_params| Ok(Box::new(CountingSink { shared: Arc::clone(&sink_shared) })),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+    Engine {
+        registry: Arc::new(std::sync::RwLock::new(registry)),
+        audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: None,
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
+    }
+}
+
+#[allow(clippy::expect_used)]
+async fn add_node(handle: &DynamicEngineHandle, node_id: &str, kind: &str) {
+    handle
+        .send_control(EngineControlMessage::AddNode {
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            params: None,
+        })
+        .await
+        .expect("failed to add node");
+}
+
+async fn wait_for_packets(shared: &Shared, expected: u64) {
+    let waited = tokio::time::timeout(Duration::from_secs(30), async {
+        while shared.received.load(Ordering::Relaxed) < expected {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await;
+    if waited.is_err() {
+        let received = shared.received.load(Ordering::Relaxed);
+        panic!("consumers received {received} of {expected} packets");
+    }
+}
+
+/// Waits for every node to run. Connections to a node are applied once it is constructed,
+/// before it starts, so no packet is sent into an unconnected pin.
+async fn wait_until_running(handle: &DynamicEngineHandle, nodes: usize) {
+    let waited = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(states) = handle.get_node_states().await {
+                let running =
+                    states.values().filter(|state| matches!(state, NodeState::Running)).count();
+                if running == nodes {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await;
+    assert!(waited.is_ok(), "nodes did not start");
+}
+
+/// Heap blocks allocated per packet while the source fans out to `modes.len()` consumers.
+#[allow(clippy::expect_used, clippy::cast_precision_loss)]
+async fn blocks_per_packet(modes: &[ConnectionMode]) -> f64 {
+    let shared = Arc::new(Shared::default());
+    let engine = counting_engine(&shared);
+    let handle = engine.start_dynamic_actor(DynamicEngineConfig::default());
+
+    add_node(&handle, "source", "test::paced_source").await;
+    for (index, mode) in modes.iter().enumerate() {
+        let sink = format!("sink_{index}");
+        add_node(&handle, &sink, "test::counting_sink").await;
+        handle
+            .send_control(EngineControlMessage::Connect {
+                from_node: "source".to_string(),
+                from_pin: "out".to_string(),
+                to_node: sink,
+                to_pin: "in".to_string(),
+                mode: *mode,
+            })
+            .await
+            .expect("failed to connect");
+    }
+    wait_until_running(&handle, modes.len() + 1).await;
+
+    let consumers = modes.len() as u64;
+    shared.gate.notify_one();
+    wait_for_packets(&shared, WARMUP_PACKETS * consumers).await;
+
+    let before = dhat::HeapStats::get().total_blocks;
+    shared.gate.notify_one();
+    wait_for_packets(&shared, (WARMUP_PACKETS + MEASURED_PACKETS) * consumers).await;
+    let allocated = dhat::HeapStats::get().total_blocks - before;
+
+    handle.shutdown_and_wait().await.expect("engine shutdown");
+    allocated as f64 / MEASURED_PACKETS as f64
+}
+
+// A single test, as only one dhat profiler may run per process.
+#[allow(clippy::disallowed_macros)] // Report the figures with --nocapture.
+#[tokio::test]
+async fn data_path_does_not_allocate_per_packet() {
+    let _profiler = dhat::Profiler::builder().testing().build();
+
+    let single = blocks_per_packet(&[ConnectionMode::Reliable]).await;
+    let fan_out = blocks_per_packet(&[
+        ConnectionMode::Reliable,
+        ConnectionMode::Reliable,
+        ConnectionMode::BestEffort,
+    ])
+    .await;
+    println!("heap blocks per packet: single output {single:.3}, three outputs {fan_out:.3}");
+
+    assert!(single < 0.5, "single output allocates {single:.3} blocks per packet");
+    assert!(fan_out < 0.5, "fan-out allocates {fan_out:.3} blocks per packet");
+}
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Isolation tests for the dynamic engine.
+//!
+//! Each session runs its own engine actor, and each output pin its own distributor task.
+//! These tests flood one session (packets + stats updates as fast as possible) and check
+//! that the quiet session and the flooded session itself keep answering control messages
+//! and queries. They only assert liveness; the latency numbers live in the
+//! `session_isolation` bench (`cargo bench -p streamkit-engine --bench session_isolation`).
+
+mod support;
+
+use std::time::Duration;
+use streamkit_core::state::NodeState;
+use support::{add_node, session_config, start_flooding_session, test_engine};
+
+const SAMPLES: usize = 200;
+/// Only catches a wedged actor: a healthy one answers in milliseconds even under the flood.
+const LIVENESS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Polls the node states until `node_id` leaves `Initializing`.
+async fn wait_until_running(handle: &streamkit_engine::DynamicEngineHandle, node_id: &str) {
+    let waited = tokio::time::timeout(LIVENESS_TIMEOUT, async {
+        loop {
+            if let Ok(states) = handle.get_node_states().await {
+                if states
+                    .get(node_id)
+                    .is_some_and(|state| !matches!(state, NodeState::Initializing))
+                {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await;
+    assert!(waited.is_ok(), "{node_id} did not start");
+}
+
+/// Sends `SAMPLES` queries and asserts that every one of them is answered.
+#[allow(clippy::expect_used)]
+async fn assert_answers_queries(handle: &streamkit_engine::DynamicEngineHandle, session: &str) {
+    for i in 0..SAMPLES {
+        tokio::time::timeout(LIVENESS_TIMEOUT, handle.get_node_states())
+            .await
+            .unwrap_or_else(|_| panic!("{session} session did not answer query {i}"))
+            .expect("engine should answer queries");
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[allow(clippy::expect_used)]
+async fn test_flooding_session_does_not_delay_other_sessions() {
+    let engine = test_engine();
+    let flooded = start_flooding_session(&engine).await;
+
+    let quiet = engine.start_dynamic_actor(session_config("quiet"));
+    add_node(&quiet, "drain", "test::drain").await;
+
+    assert_answers_queries(&quiet, "quiet").await;
+
+    flooded.shutdown_and_wait().await.expect("flooded engine shutdown");
+    quiet.shutdown_and_wait().await.expect("quiet engine shutdown");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[allow(clippy::expect_used)]
+async fn test_flooding_node_does_not_delay_own_control_plane() {
+    let engine = test_engine();
+    let flooded = start_flooding_session(&engine).await;
+
+    assert_answers_queries(&flooded, "flooded").await;
+
+    // Graph operations must also go through while the flood continues.
+    add_node(&flooded, "late", "test::drain").await;
+    let applied = async {
+        loop {
+            let states = flooded.get_node_states().await.expect("engine should answer queries");
+            if states.contains_key("late") {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+    };
+    assert!(
+        tokio::time::timeout(LIVENESS_TIMEOUT, applied).await.is_ok(),
+        "AddNode was never applied on the flooded session"
+    );
+
+    flooded.shutdown_and_wait().await.expect("flooded engine shutdown");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[allow(clippy::expect_used)]
+async fn test_unread_tap_does_not_stall_tapped_session() {
+    let engine = test_engine();
+    let flooded = start_flooding_session(&engine).await;
+
+    assert!(flooded.tap_output("missing", "out").await.is_err());
+    assert!(flooded.tap_output("flood", "missing").await.is_err());
+
+    let mut tap = flooded.tap_output("flood", "out").await.expect("tap should attach");
+    assert!(tap.recv().await.is_some(), "tap should receive the node's packets");
+
+    // Leave the tap unread: its buffer fills up and packets are dropped for it alone.
+    assert_answers_queries(&flooded, "tapped").await;
+
+    flooded.shutdown_and_wait().await.expect("flooded engine shutdown");
+    assert!(
+        tokio::time::timeout(Duration::from_secs(5), async { while tap.recv().await.is_some() {} })
+            .await
+            .is_ok(),
+        "tap should close when the session shuts down"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[allow(clippy::expect_used)]
+async fn test_usage_is_counted_across_sessions() {
+    let engine = test_engine();
+    let first = engine.start_dynamic_actor(session_config("first"));
+    let second = engine.start_dynamic_actor(session_config("second"));
+    add_node(&first, "drain", "test::drain").await;
+    add_node(&second, "drain", "test::drain").await;
+    // Nodes are built off the actor loop; usage is counted once they start.
+    wait_until_running(&first, "drain").await;
+    wait_until_running(&second, "drain").await;
+
+    let report = engine.usage.report();
+    let drain = report.kinds.first().expect("drain usage should be reported");
+    assert_eq!((drain.kind.as_str(), drain.instances, drain.active), ("test::drain", 2, 2));
+
+    first.shutdown_and_wait().await.expect("first engine shutdown");
+    second.shutdown_and_wait().await.expect("second engine shutdown");
+
+    let report = engine.usage.report();
+    let drain = report.kinds.first().expect("drain usage should be reported");
+    assert_eq!((drain.instances, drain.active, drain.failed), (2, 0, 0));
+    assert!(drain.avg_lifetime_secs.is_some());
+}
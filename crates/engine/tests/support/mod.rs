@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Flooding and draining test nodes shared by the session isolation tests and bench.
+//!
+//! Included with `#[path]` from `benches/`, so it stays free of test-only attributes.
+
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{EngineControlMessage, NodeControlMessage};
+use streamkit_core::stats::{NodeStats, NodeStatsUpdate};
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use streamkit_engine::{DynamicEngineConfig, DynamicEngineHandle, Engine};
+
+/// Emits binary packets and stats updates in a tight loop until shut down.
+pub struct FloodNode;
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for FloodNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        Vec::new()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_id = "flood".to_string();
+        let mut sent = 0u64;
+        loop {
+            match context.control_rx.try_recv() {
+                Ok(NodeControlMessage::Shutdown)
+                | Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => return Ok(()),
+                Ok(_) | Err(tokio::sync::mpsc::error::TryRecvError::Empty) => {},
+            }
+
+            let packet = Packet::Binary {
+                data: bytes::Bytes::from_static(&[0u8; 64]),
+                content_type: None,
+                metadata: None,
+            };
+            if context.output_sender.send("out", packet).await.is_err() {
+                return Ok(());
+            }
+            sent += 1;
+
+            if let Some(stats_tx) = &context.stats_tx {
+                let update = NodeStatsUpdate {
+                    node_id: node_id.clone(),
+                    stats: NodeStats { sent, ..NodeStats::default() },
+                    timestamp: std::time::SystemTime::now(),
+                };
+                // Blocking send keeps the actor's stats channel saturated.
+                if stats_tx.send(update).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Consumes packets as fast as they arrive.
+pub struct DrainNode;
+
+#[streamkit_core::async_trait]
+impl ProcessorNode for DrainNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        Vec::new()
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let Some(mut rx) = context.inputs.remove("in") else {
+            return Err(StreamKitError::Runtime("missing input pin".to_string()));
+        };
+        state_helpers::emit_running(&context.state_tx, context.output_sender.node_name());
+        loop {
+            tokio::select! {
+                msg = context.control_rx.recv() => {
+                    if matches!(msg, Some(NodeControlMessage::Shutdown) | None) {
+                        return Ok(());
+                    }
+                },
+                packet = rx.recv() => {
+                    if packet.is_none() {
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub fn test_engine() -> Engine {
+    let mut registry = NodeRegistry::new();
+    registry.register_dynamic(
+        "test::flood",
+        |_params| Ok(Box::new(FloodNode)),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+    registry.register_dynamic(
+        "test::drain",
+        |_params| Ok(Box::new(DrainNode)),
+        serde_json::json!({}),
+        vec!["test".to_string()],
+        false,
+    );
+    Engine {
+        registry: Arc::new(std::sync::RwLock::new(registry)),
+        audio_pool: Arc::new(streamkit_core::AudioFramePool::audio_default()),
+        runtime: None,
+        warm_pool: Arc::default(),
+        usage: Arc::default(),
+    }
+}
+
+pub fn session_config(id: &str) -> DynamicEngineConfig {
+    DynamicEngineConfig { session_id: Some(id.to_string()), ..DynamicEngineConfig::default() }
+}
+
+#[allow(clippy::expect_used)]
+pub async fn add_node(handle: &DynamicEngineHandle, node_id: &str, kind: &str) {
+    handle
+        .send_control(EngineControlMessage::AddNode {
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            params: None,
+        })
+        .await
+        .expect("failed to add node");
+}
+
+#[allow(clippy::expect_used)]
+pub async fn start_flooding_session(engine: &Engine) -> DynamicEngineHandle {
+    let handle = engine.start_dynamic_actor(session_config("flooded"));
+    add_node(&handle, "flood", "test::flood").await;
+    add_node(&handle, "drain", "test::drain").await;
+    handle
+        .send_control(EngineControlMessage::Connect {
+            from_node: "flood".to_string(),
+            from_pin: "out".to_string(),
+            to_node: "drain".to_string(),
+            to_pin: "in".to_string(),
+            mode: streamkit_core::control::ConnectionMode::Reliable,
+        })
+        .await
+        .expect("failed to connect");
+    // Let the flood ramp up and saturate the stats channel.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    handle
+}
@@ -0,0 +1,36 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Read-only file mappings for the file reader's `mmap` backend.
+//!
+//! The mapping is handed out as `Bytes`, so chunks cut from it are views of the page cache
+//! and the file stays mapped until the last of them is dropped.
+
+use bytes::Bytes;
+use std::fs::File;
+use std::io;
+
+/// Maps `file` read-only, or returns `None` if it is empty (a zero-length mapping is
+/// invalid).
+///
+/// The file must not be truncated while the mapping is alive: touching pages past the new
+/// end raises SIGBUS, which kills the process.
+///
+/// # Errors
+///
+/// Returns the error from reading the file's metadata or from `mmap(2)`.
+pub fn map(file: &File) -> io::Result<Option<Bytes>> {
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+    // SAFETY: a fresh private read-only mapping aliases no Rust memory. Its contents can
+    // still change if another process writes the file, which only affects the bytes read;
+    // truncation raises SIGBUS, as documented above.
+    let mapping = unsafe { memmap2::Mmap::map(file) }?;
+    #[cfg(unix)]
+    if let Err(e) = mapping.advise(memmap2::Advice::Sequential) {
+        tracing::debug!("could not set sequential access hint on mapping: {}", e);
+    }
+    Ok(Some(Bytes::from_owner(mapping)))
+}
@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Opus encode throughput across complexity settings.
+//!
+//! Run with `cargo bench -p streamkit-nodes --bench opus_encode`. Encodes a minute of 48 kHz
+//! audio in the 20 ms frames `audio::opus::encoder` uses, with an encoder configured the same
+//! way as the node's. Opus frames depend on the encoder state of the previous frame, so unlike
+//! `flac_encode` there is no parallel variant. There is no MP3 benchmark: the tree only has an
+//! MP3 decoder.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use streamkit_nodes::audio::codecs::opus::{configured_encoder, OpusEncoderConfig};
+
+const SAMPLE_RATE: usize = 48_000;
+const SECONDS: usize = 60;
+const FRAME_LEN: usize = SAMPLE_RATE / 50;
+// Large enough for any Opus packet, as in the node
+const MAX_PACKET: usize = 4000;
+
+// Synthetic program material; precision loss in the phase math is irrelevant here.
+#[allow(clippy::cast_precision_loss)]
+fn signal(channels: usize) -> Vec<f32> {
+    let mut state = 1u32;
+    (0..SECONDS * SAMPLE_RATE)
+        .flat_map(|i| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let noise = (state >> 8) as f32 / (1 << 24) as f32 - 0.5;
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let tone = (t * 440.0 * std::f32::consts::TAU).sin() * 0.4;
+            [tone + noise * 0.05, tone * 0.7 + noise * 0.1].into_iter().take(channels)
+        })
+        .collect()
+}
+
+/// Encodes `samples` frame by frame, returning the total encoded size.
+#[allow(clippy::expect_used)] // A failing encode invalidates the benchmark
+fn encode(config: &OpusEncoderConfig, channels: u16, samples: &[f32]) -> usize {
+    let encoder = configured_encoder(config, channels).expect("encoder should be created");
+    let mut packet = vec![0u8; MAX_PACKET];
+    samples
+        .chunks_exact(FRAME_LEN * usize::from(channels))
+        .map(|frame| encoder.encode_float(frame, &mut packet).expect("frame should encode"))
+        .sum()
+}
+
+fn opus_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("opus_encode");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((SECONDS * SAMPLE_RATE) as u64));
+
+    for (name, channels, bitrate) in [("voice_mono", 1u16, 32_000), ("music_stereo", 2, 128_000)] {
+        let samples = signal(usize::from(channels));
+        for complexity in [0u8, 5, 10] {
+            let config = OpusEncoderConfig {
+                bitrate,
+                complexity: Some(complexity),
+                ..OpusEncoderConfig::default()
+            };
+            group.bench_with_input(BenchmarkId::new(name, complexity), &config, |b, config| {
+                b.iter(|| encode(config, channels, &samples));
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, opus_encode);
+criterion_main!(benches);
@@ -0,0 +1,699 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Chapter detector node - Emits chapter markers from pauses, topic shifts and time limits
+//!
+//! Pauses in the audio are boundary candidates. A candidate becomes a chapter when the
+//! transcript around it changes topic, when the pause is long enough on its own, or when the
+//! current chapter has grown past its maximum length.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, CustomEncoding, CustomPacketData, Packet, PacketMetadata, PacketType,
+    SampleFormat, TranscriptionData,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+pub use super::CHAPTER_MARKER_TYPE_ID;
+
+/// Level measurement block length.
+const BLOCK_MS: u64 = 20;
+
+/// Dimension of the hashed bag-of-words transcript embedding.
+const EMBEDDING_DIM: usize = 256;
+
+/// Frequent words that say nothing about the topic. English only: other languages keep their
+/// function words in the embedding, which blurs topic shifts.
+const STOPWORDS: &[&str] = &[
+    "about", "also", "and", "are", "because", "been", "but", "can", "could", "did", "does", "for",
+    "from", "had", "has", "have", "her", "him", "his", "how", "into", "its", "just", "like",
+    "more", "not", "now", "our", "out", "really", "she", "should", "some", "that", "the", "their",
+    "them", "then", "there", "these", "they", "this", "was", "were", "what", "when", "which",
+    "who", "will", "with", "would", "yeah", "you", "your",
+];
+
+/// Configuration for the ChapterDetectNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ChapterDetectConfig {
+    /// Minimum chapter length in seconds; no boundary is placed closer to the previous one
+    #[schemars(range(min = 1))]
+    pub min_chapter_s: u32,
+    /// Maximum chapter length in seconds, after which a boundary is forced at the longest
+    /// pause seen (0 disables)
+    pub max_chapter_s: u32,
+    /// Level below which audio counts as a pause, in dBFS
+    #[schemars(range(max = 0.0))]
+    pub silence_threshold_db: f32,
+    /// Minimum pause length for a boundary candidate, in milliseconds
+    #[schemars(range(min = 100))]
+    pub min_silence_ms: u32,
+    /// Pause length that starts a chapter even without a topic shift, in milliseconds
+    /// (0 disables)
+    pub long_silence_ms: u32,
+    /// Length of transcript compared before and after a candidate, in seconds
+    #[schemars(range(min = 5))]
+    pub topic_window_s: u32,
+    /// Cosine distance between the hashed bag-of-words vectors of the transcript windows that
+    /// counts as a topic shift
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub topic_shift_threshold: f32,
+    /// How long to wait for transcripts covering a candidate, in milliseconds of audio
+    pub transcript_delay_ms: u32,
+    /// Number of keywords reported with each chapter
+    #[schemars(range(max = 10))]
+    pub keywords: usize,
+}
+
+impl Default for ChapterDetectConfig {
+    fn default() -> Self {
+        Self {
+            min_chapter_s: 60,
+            max_chapter_s: 900,
+            silence_threshold_db: -45.0,
+            min_silence_ms: 1000,
+            long_silence_ms: 5000,
+            topic_window_s: 45,
+            topic_shift_threshold: 0.7,
+            transcript_delay_ms: 10_000,
+            keywords: 3,
+        }
+    }
+}
+
+/// Why a chapter starts where it does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChapterReason {
+    /// The first chapter, at the start of the stream
+    Start,
+    TopicShift,
+    Silence,
+    MaxDuration,
+}
+
+/// A chapter boundary decided by the detector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterMarker {
+    /// 1-based chapter number
+    pub index: u32,
+    /// Chapter start, in milliseconds from the start of the audio stream
+    pub start_ms: u64,
+    pub reason: ChapterReason,
+    /// Most frequent topic words in the transcript following `start_ms`
+    pub keywords: Vec<String>,
+    /// Transcript distance across the boundary, when both sides had text
+    pub topic_distance: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pause {
+    start_ms: u64,
+    end_ms: u64,
+}
+
+impl Pause {
+    const fn position_ms(self) -> u64 {
+        self.start_ms.midpoint(self.end_ms)
+    }
+
+    const fn len_ms(self) -> u64 {
+        self.end_ms - self.start_ms
+    }
+}
+
+struct Segment {
+    start_ms: u64,
+    end_ms: u64,
+    text: String,
+}
+
+/// Chapter boundary logic, separated from I/O.
+///
+/// Times are milliseconds from the start of the audio stream, which is also the time base of
+/// the STT nodes' segment timestamps. Markers are decided after the fact: a candidate is only
+/// judged once the transcript `topic_window_s` past it has arrived (or `transcript_delay_ms`
+/// more audio has gone by), so `start_ms` usually lies in the past when a marker is emitted.
+pub struct ChapterDetector {
+    config: ChapterDetectConfig,
+    silence_power: f32,
+    /// Block accumulation at the current sample rate
+    sample_rate: u32,
+    block_power: f32,
+    block_samples: u64,
+    /// Audio time at the start of the current block, in microseconds
+    block_start_us: u64,
+    now_ms: u64,
+    silence_start_ms: Option<u64>,
+    /// Pauses still waiting for a topic decision, oldest first
+    candidates: VecDeque<Pause>,
+    /// Every pause in the current chapter, for the max-duration fallback
+    pauses: Vec<Pause>,
+    segments: VecDeque<Segment>,
+    transcript_end_ms: u64,
+    /// Start of the current chapter; `None` until audio arrives
+    chapter_start_ms: Option<u64>,
+    chapters: u32,
+}
+
+impl ChapterDetector {
+    pub fn new(config: ChapterDetectConfig) -> Self {
+        Self {
+            silence_power: 10f32.powf(config.silence_threshold_db / 10.0),
+            config,
+            sample_rate: 0,
+            block_power: 0.0,
+            block_samples: 0,
+            block_start_us: 0,
+            now_ms: 0,
+            silence_start_ms: None,
+            candidates: VecDeque::new(),
+            pauses: Vec::new(),
+            segments: VecDeque::new(),
+            transcript_end_ms: 0,
+            chapter_start_ms: None,
+            chapters: 0,
+        }
+    }
+
+    pub const fn chapters(&self) -> u32 {
+        self.chapters
+    }
+
+    /// Feeds interleaved audio, appending any decided chapters to `out`.
+    pub fn push_audio(
+        &mut self,
+        sample_rate: u32,
+        channels: u16,
+        samples: &[f32],
+        out: &mut Vec<ChapterMarker>,
+    ) {
+        if sample_rate == 0 {
+            return;
+        }
+        if self.chapter_start_ms.is_none() {
+            self.start_chapter(0, ChapterReason::Start, None, out);
+        }
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.block_start_us += self.block_samples * 1_000_000 / u64::from(sample_rate);
+            self.block_power = 0.0;
+            self.block_samples = 0;
+        }
+
+        let block_len = (u64::from(sample_rate) * BLOCK_MS / 1000).max(1);
+        let channels = usize::from(channels.max(1));
+        let scale = 1.0 / f32::from(u16::try_from(channels).unwrap_or(u16::MAX));
+        for frame in samples.chunks_exact(channels) {
+            let sample = frame.iter().sum::<f32>() * scale;
+            self.block_power += sample * sample;
+            self.block_samples += 1;
+            if self.block_samples == block_len {
+                // Block lengths are far below f32's exact integer range.
+                #[allow(clippy::cast_precision_loss)]
+                let silent = self.block_power / (block_len as f32) < self.silence_power;
+                self.block_start_us += block_len * 1_000_000 / u64::from(sample_rate);
+                self.block_power = 0.0;
+                self.block_samples = 0;
+                self.end_block(silent, out);
+            }
+        }
+    }
+
+    fn end_block(&mut self, silent: bool, out: &mut Vec<ChapterMarker>) {
+        let block_start_ms = self.now_ms;
+        self.now_ms = self.block_start_us / 1000;
+        match (silent, self.silence_start_ms) {
+            (true, None) => self.silence_start_ms = Some(block_start_ms),
+            (false, Some(start_ms)) => {
+                self.silence_start_ms = None;
+                let pause = Pause { start_ms, end_ms: block_start_ms };
+                if pause.len_ms() >= u64::from(self.config.min_silence_ms) {
+                    self.candidates.push_back(pause);
+                    self.pauses.push(pause);
+                }
+            },
+            _ => {},
+        }
+        self.evaluate(false, out);
+    }
+
+    /// Adds transcript segments, appending any chapters they allow deciding to `out`.
+    pub fn push_transcript(
+        &mut self,
+        transcript: &TranscriptionData,
+        out: &mut Vec<ChapterMarker>,
+    ) {
+        if transcript.segments.is_empty() {
+            // Without segment timings, place the text at the current audio position.
+            let start_ms = transcript
+                .metadata
+                .as_ref()
+                .and_then(|m| m.timestamp_us)
+                .map_or(self.now_ms, |us| us / 1000);
+            self.add_segment(start_ms, start_ms, &transcript.text);
+        }
+        for segment in &transcript.segments {
+            self.add_segment(segment.start_time_ms, segment.end_time_ms, &segment.text);
+        }
+        self.evaluate(false, out);
+    }
+
+    fn add_segment(&mut self, start_ms: u64, end_ms: u64, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        self.transcript_end_ms = self.transcript_end_ms.max(end_ms);
+        self.segments.push_back(Segment { start_ms, end_ms, text: text.to_string() });
+    }
+
+    /// Decides all remaining candidates with whatever transcript has arrived.
+    pub fn finish(&mut self, out: &mut Vec<ChapterMarker>) {
+        self.evaluate(true, out);
+    }
+
+    fn evaluate(&mut self, force: bool, out: &mut Vec<ChapterMarker>) {
+        let window_ms = u64::from(self.config.topic_window_s) * 1000;
+        let min_ms = u64::from(self.config.min_chapter_s) * 1000;
+
+        while let (Some(&pause), Some(chapter_start)) =
+            (self.candidates.front(), self.chapter_start_ms)
+        {
+            let position = pause.position_ms();
+            if position < chapter_start + min_ms {
+                self.candidates.pop_front();
+                continue;
+            }
+            let covered = self.transcript_end_ms >= position + window_ms
+                || self.now_ms >= position + window_ms + u64::from(self.config.transcript_delay_ms);
+            if !covered && !force {
+                break;
+            }
+            self.candidates.pop_front();
+
+            let before = self.embedding(position.saturating_sub(window_ms), position);
+            let after = self.embedding(position, position + window_ms);
+            let distance = before.zip(after).map(|(b, a)| cosine_distance(&b, &a));
+            let long_silence = self.config.long_silence_ms > 0
+                && pause.len_ms() >= u64::from(self.config.long_silence_ms);
+            if distance.is_some_and(|d| d >= self.config.topic_shift_threshold) {
+                self.start_chapter(position, ChapterReason::TopicShift, distance, out);
+            } else if long_silence {
+                self.start_chapter(position, ChapterReason::Silence, distance, out);
+            }
+        }
+
+        let Some(chapter_start) = self.chapter_start_ms else { return };
+        let max_ms = u64::from(self.config.max_chapter_s) * 1000;
+        let limit = chapter_start + max_ms;
+        if max_ms > 0 && self.now_ms >= limit {
+            let position = self
+                .pauses
+                .iter()
+                .filter(|p| p.position_ms() >= chapter_start + min_ms)
+                .max_by_key(|p| p.len_ms())
+                .map_or(limit, |p| p.position_ms());
+            self.start_chapter(position, ChapterReason::MaxDuration, None, out);
+        }
+
+        // Keep what later decisions can still look at.
+        let oldest = self.candidates.front().map_or(self.now_ms, |p| p.position_ms());
+        let keep_from = oldest.min(self.chapter_start_ms.unwrap_or(0)).saturating_sub(window_ms);
+        while self.segments.front().is_some_and(|s| s.end_ms < keep_from) {
+            self.segments.pop_front();
+        }
+    }
+
+    fn start_chapter(
+        &mut self,
+        start_ms: u64,
+        reason: ChapterReason,
+        topic_distance: Option<f32>,
+        out: &mut Vec<ChapterMarker>,
+    ) {
+        self.chapters += 1;
+        self.chapter_start_ms = Some(start_ms);
+        self.candidates.retain(|p| p.position_ms() > start_ms);
+        self.pauses.retain(|p| p.position_ms() > start_ms);
+        let window_ms = u64::from(self.config.topic_window_s) * 1000;
+        out.push(ChapterMarker {
+            index: self.chapters,
+            start_ms,
+            reason,
+            keywords: self.keywords(start_ms, start_ms + window_ms),
+            topic_distance,
+        });
+    }
+
+    /// Topic words of the transcript segments that start within `[from_ms, to_ms)`.
+    fn words(&self, from_ms: u64, to_ms: u64) -> impl Iterator<Item = String> + '_ {
+        self.segments
+            .iter()
+            .filter(move |s| s.start_ms >= from_ms && s.start_ms < to_ms)
+            .flat_map(|s| topic_words(&s.text))
+    }
+
+    fn embedding(&self, from_ms: u64, to_ms: u64) -> Option<Vec<f32>> {
+        let mut vector = vec![0.0; EMBEDDING_DIM];
+        let mut any = false;
+        for word in self.words(from_ms, to_ms) {
+            vector[fnv1a(&word) % EMBEDDING_DIM] += 1.0;
+            any = true;
+        }
+        any.then_some(vector)
+    }
+
+    fn keywords(&self, from_ms: u64, to_ms: u64) -> Vec<String> {
+        let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+        for (order, word) in self.words(from_ms, to_ms).enumerate() {
+            counts.entry(word).or_insert((0, order)).0 += 1;
+        }
+        let mut ranked: Vec<_> = counts.into_iter().collect();
+        // Most frequent first, ties broken by first appearance.
+        ranked.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.1 .1.cmp(&b.1 .1)));
+        ranked.into_iter().take(self.config.keywords).map(|(word, _)| word).collect()
+    }
+}
+
+/// Lowercased words of at least three letters, minus stopwords.
+fn topic_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+}
+
+fn fnv1a(word: &str) -> usize {
+    let hash = word
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3));
+    // Only the low bits are used as a bucket index.
+    #[allow(clippy::cast_possible_truncation)]
+    let bucket = hash as usize;
+    bucket
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm =
+        a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+    if norm <= f32::EPSILON {
+        return 1.0;
+    }
+    1.0 - dot / norm
+}
+
+/// A node that emits one `audio::chapter/marker@1` Custom packet per chapter start, with
+/// `index`, `start_ms`, `title`, `reason`, `keywords` and `topic_distance`.
+///
+/// Listens to audio on `in` for pauses and to STT output on `transcript` for topic shifts.
+/// The `transcript` pin may be left unconnected, in which case only long pauses and the
+/// maximum chapter length place boundaries. The first chapter starts at 0 as soon as audio
+/// arrives. Container and tagging nodes can consume the markers to write chapter lists.
+pub struct ChapterDetectNode {
+    config: ChapterDetectConfig,
+}
+
+impl ChapterDetectNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: ChapterDetectConfig = config_helpers::parse_config_optional(params)?;
+            if config.max_chapter_s != 0 && config.max_chapter_s < config.min_chapter_s {
+                return Err(StreamKitError::Configuration(
+                    "max_chapter_s must be 0 or at least min_chapter_s".to_string(),
+                ));
+            }
+            if !config.silence_threshold_db.is_finite() {
+                return Err(StreamKitError::Configuration(
+                    "silence_threshold_db must be finite".to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn marker_packet(marker: &ChapterMarker) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: CHAPTER_MARKER_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({
+                "index": marker.index,
+                "start_ms": marker.start_ms,
+                "title": format!("Chapter {}", marker.index),
+                "reason": marker.reason,
+                "keywords": marker.keywords,
+                "topic_distance": marker.topic_distance,
+            }),
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(marker.start_ms * 1000),
+                duration_us: None,
+                sequence: Some(u64::from(marker.index)),
+            }),
+        }))
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for ChapterDetectNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0, // Wildcard
+                    channels: 0,    // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "transcript".to_string(),
+                accepts_types: vec![PacketType::Transcription],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Custom { type_id: CHAPTER_MARKER_TYPE_ID.to_string() },
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        // The transcript pin is optional; without it only pauses and time limits apply.
+        let mut transcript_rx = context.inputs.remove("transcript");
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut detector = ChapterDetector::new(self.config.clone());
+        let mut markers = Vec::new();
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    detector.push_audio(frame.sample_rate, frame.channels, frame.samples(), &mut markers);
+                }
+                maybe_transcript = async { transcript_rx.as_mut()?.recv().await }, if transcript_rx.is_some() => {
+                    match maybe_transcript {
+                        Some(Packet::Transcription(transcript)) => {
+                            detector.push_transcript(&transcript, &mut markers);
+                        }
+                        Some(_) => {}
+                        None => transcript_rx = None,
+                    }
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+
+            for marker in std::mem::take(&mut markers) {
+                tracing::info!(index = marker.index, start_ms = marker.start_ms, reason = ?marker.reason, "Chapter detected");
+                if context.output_sender.send("out", Self::marker_packet(&marker)).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                    return Ok(());
+                }
+                stats.sent();
+            }
+            stats.maybe_send();
+        }
+
+        // Late transcripts for the tail of the stream may still be queued.
+        if let Some(rx) = transcript_rx.as_mut() {
+            while let Ok(packet) = rx.try_recv() {
+                if let Packet::Transcription(transcript) = packet {
+                    detector.push_transcript(&transcript, &mut markers);
+                }
+            }
+        }
+        detector.finish(&mut markers);
+        for marker in markers {
+            if context.output_sender.send("out", Self::marker_packet(&marker)).await.is_err() {
+                break;
+            }
+            stats.sent();
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use streamkit_core::types::TranscriptionSegment;
+
+    const RATE: u32 = 8000;
+
+    fn config() -> ChapterDetectConfig {
+        ChapterDetectConfig {
+            min_chapter_s: 20,
+            max_chapter_s: 0,
+            long_silence_ms: 0,
+            topic_window_s: 15,
+            ..Default::default()
+        }
+    }
+
+    /// Speech-level noise (`speech = true`) or digital silence.
+    fn audio(detector: &mut ChapterDetector, ms: u64, speech: bool, out: &mut Vec<ChapterMarker>) {
+        let samples: Vec<f32> = (0..u64::from(RATE) * ms / 1000)
+            .map(|n| {
+                if speech {
+                    0.1 * (f32::from(u8::try_from(n * 7919 % 200).unwrap()) / 100.0 - 1.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        // 20 ms packets, like a decoder would produce
+        for chunk in samples.chunks(160) {
+            detector.push_audio(RATE, 1, chunk, out);
+        }
+    }
+
+    fn say(
+        detector: &mut ChapterDetector,
+        start_ms: u64,
+        end_ms: u64,
+        text: &str,
+        out: &mut Vec<ChapterMarker>,
+    ) {
+        let transcript = TranscriptionData {
+            text: text.to_string(),
+            segments: vec![TranscriptionSegment {
+                text: text.to_string(),
+                start_time_ms: start_ms,
+                end_time_ms: end_ms,
+                confidence: None,
+            }],
+            language: None,
+            metadata: None,
+        };
+        detector.push_transcript(&transcript, out);
+    }
+
+    fn summary(markers: &[ChapterMarker]) -> Vec<(u64, ChapterReason)> {
+        markers.iter().map(|m| (m.start_ms, m.reason)).collect()
+    }
+
+    /// 30 s of speech about one topic, a 2 s pause, 30 s about `second_topic`.
+    fn two_topics(config: ChapterDetectConfig, second_topic: &str) -> Vec<ChapterMarker> {
+        let mut detector = ChapterDetector::new(config);
+        let mut out = Vec::new();
+        // Each 5 s of speech is transcribed once it has been heard, as an STT node would.
+        let first_topic = "the quarterly budget forecast and revenue targets";
+        for (start, text) in [(0, first_topic), (32_000, second_topic)] {
+            if start > 0 {
+                audio(&mut detector, 2_000, false, &mut out);
+            }
+            for t in (start..start + 30_000).step_by(5_000) {
+                audio(&mut detector, 5_000, true, &mut out);
+                say(&mut detector, t, t + 4_000, text, &mut out);
+            }
+        }
+        detector.finish(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_topic_shift_at_pause_starts_chapter() {
+        let out = two_topics(config(), "hiring plans for the engineering team onboarding");
+        assert_eq!(
+            summary(&out),
+            vec![(0, ChapterReason::Start), (31_000, ChapterReason::TopicShift)]
+        );
+        assert_eq!(out[1].index, 2);
+        assert_eq!(out[1].keywords, vec!["hiring", "plans", "engineering"]);
+        assert!(out[1].topic_distance.unwrap() > 0.9);
+    }
+
+    #[test]
+    fn test_same_topic_pause_is_not_a_chapter() {
+        let out = two_topics(config(), "budget forecast revenue targets for the quarterly review");
+        assert_eq!(summary(&out), vec![(0, ChapterReason::Start)]);
+
+        // Unless the pause alone is long enough
+        let lenient = ChapterDetectConfig { long_silence_ms: 2_000, ..config() };
+        let out = two_topics(lenient, "budget forecast revenue targets for the quarterly review");
+        assert_eq!(
+            summary(&out),
+            vec![(0, ChapterReason::Start), (31_000, ChapterReason::Silence)]
+        );
+    }
+
+    #[test]
+    fn test_min_and_max_chapter_length() {
+        // A pause 10 s in is too close to the start for a 20 s minimum.
+        let strict = ChapterDetectConfig { min_chapter_s: 20, long_silence_ms: 1_000, ..config() };
+        let mut detector = ChapterDetector::new(strict);
+        let mut out = Vec::new();
+        audio(&mut detector, 10_000, true, &mut out);
+        audio(&mut detector, 3_000, false, &mut out);
+        audio(&mut detector, 20_000, true, &mut out);
+        detector.finish(&mut out);
+        assert_eq!(summary(&out), vec![(0, ChapterReason::Start)]);
+
+        // Without topic information, the max length splits at the longest eligible pause.
+        let capped = ChapterDetectConfig { max_chapter_s: 60, ..config() };
+        let mut detector = ChapterDetector::new(capped);
+        let mut out = Vec::new();
+        audio(&mut detector, 25_000, true, &mut out);
+        audio(&mut detector, 1_000, false, &mut out);
+        audio(&mut detector, 14_000, true, &mut out);
+        audio(&mut detector, 1_600, false, &mut out);
+        audio(&mut detector, 30_000, true, &mut out);
+        assert_eq!(
+            summary(&out),
+            vec![(0, ChapterReason::Start), (40_800, ChapterReason::MaxDuration)]
+        );
+        assert_eq!(detector.chapters(), 2);
+    }
+}
@@ -0,0 +1,394 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! DTMF detector node - Emits Custom events for telephone keypad digits heard in audio
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, CustomEncoding, CustomPacketData, Packet, PacketMetadata, PacketType,
+    SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+use crate::audio::generators::dtmf::{COL_FREQS, DTMF_DIGIT_TYPE_ID, KEYPAD, ROW_FREQS};
+
+/// Analysis block length. 20 ms gives ~50 Hz resolution, enough to separate the closest
+/// DTMF tones (697/770 Hz) while keeping the minimum 40 ms digit at two blocks.
+const BLOCK_MS: u32 = 20;
+
+/// Share of the block's energy the two tones must carry; rejects speech and music.
+const MIN_TONE_ENERGY_RATIO: f32 = 0.5;
+
+/// The strongest tone in each group must be this far (power ratio, 6 dB) above the others.
+const MIN_PEAK_RATIO: f32 = 4.0;
+
+/// Configuration for the DtmfDetectNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DtmfDetectConfig {
+    /// Minimum level of each tone in dBFS
+    #[schemars(range(max = 0.0))]
+    pub min_level_db: f32,
+    /// Maximum level difference between the two tones in dB
+    #[schemars(range(min = 0.0))]
+    pub max_twist_db: f32,
+    /// Minimum tone duration before a digit is reported, in milliseconds
+    #[schemars(range(min = 20))]
+    pub min_duration_ms: u32,
+}
+
+impl Default for DtmfDetectConfig {
+    fn default() -> Self {
+        Self { min_level_db: -30.0, max_twist_db: 8.0, min_duration_ms: 40 }
+    }
+}
+
+/// A digit confirmed by the detector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtmfEvent {
+    pub digit: char,
+    /// Stream position where the tone started
+    pub position_ms: u64,
+    /// Level of the weaker of the two tones in dBFS
+    pub level_db: f32,
+}
+
+/// Goertzel-based DTMF detector over mono samples.
+///
+/// Each digit is reported once, after it has been held for `min_duration_ms`; it must drop
+/// out for at least one block before the same digit is reported again.
+pub struct DtmfDetector {
+    sample_rate: u32,
+    block_len: usize,
+    block: Vec<f32>,
+    row_coeffs: [f32; 4],
+    col_coeffs: [f32; 4],
+    min_amplitude: f32,
+    max_twist_db: f32,
+    min_blocks: u32,
+    /// Digit seen in consecutive blocks, how many, and where the run started
+    run: Option<(char, u32, u64)>,
+    reported: bool,
+    samples_seen: u64,
+}
+
+impl DtmfDetector {
+    // Sample rates and block lengths are far below f32's exact integer range.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(sample_rate: u32, config: &DtmfDetectConfig) -> Self {
+        let block_len = (sample_rate * BLOCK_MS / 1000).max(1) as usize;
+        let coeff = |freq: f32| 2.0 * (TAU * freq / sample_rate as f32).cos();
+        Self {
+            sample_rate,
+            block_len,
+            block: Vec::with_capacity(block_len),
+            row_coeffs: ROW_FREQS.map(coeff),
+            col_coeffs: COL_FREQS.map(coeff),
+            min_amplitude: 10f32.powf(config.min_level_db / 20.0),
+            max_twist_db: config.max_twist_db,
+            min_blocks: config.min_duration_ms.div_ceil(BLOCK_MS).max(1),
+            run: None,
+            reported: false,
+            samples_seen: 0,
+        }
+    }
+
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Feeds mono samples, appending any newly confirmed digits to `events`.
+    pub fn push(&mut self, samples: impl IntoIterator<Item = f32>, events: &mut Vec<DtmfEvent>) {
+        for sample in samples {
+            self.block.push(sample);
+            if self.block.len() == self.block_len {
+                let block_start = self.samples_seen;
+                self.samples_seen += self.block_len as u64;
+                let detected = self.classify_block();
+                self.block.clear();
+                self.advance(detected, block_start, events);
+            }
+        }
+    }
+
+    fn advance(
+        &mut self,
+        detected: Option<(char, f32)>,
+        block_start: u64,
+        out: &mut Vec<DtmfEvent>,
+    ) {
+        let Some((digit, level_db)) = detected else {
+            self.run = None;
+            self.reported = false;
+            return;
+        };
+        let (count, start) = match self.run {
+            Some((current, count, start)) if current == digit => (count + 1, start),
+            _ => {
+                self.reported = false;
+                (1, block_start)
+            },
+        };
+        self.run = Some((digit, count, start));
+        if count >= self.min_blocks && !self.reported {
+            self.reported = true;
+            out.push(DtmfEvent {
+                digit,
+                position_ms: start * 1000 / u64::from(self.sample_rate),
+                level_db,
+            });
+        }
+    }
+
+    /// Returns the digit in the current block and its weaker tone's level, if any.
+    // Block lengths are far below f32's exact integer range.
+    #[allow(clippy::cast_precision_loss)]
+    fn classify_block(&self) -> Option<(char, f32)> {
+        let n = self.block.len() as f32;
+        let energy = self.block.iter().map(|s| s * s).sum::<f32>() / n;
+        if energy <= f32::EPSILON {
+            return None;
+        }
+
+        let rows = self.row_coeffs.map(|c| goertzel_power(&self.block, c));
+        let cols = self.col_coeffs.map(|c| goertzel_power(&self.block, c));
+        let (row, row_power) = strongest(&rows)?;
+        let (col, col_power) = strongest(&cols)?;
+
+        // |X(f)| ≈ A·N/2 for a sinusoid of amplitude A at f
+        let row_amp = 2.0 * row_power.sqrt() / n;
+        let col_amp = 2.0 * col_power.sqrt() / n;
+        let weaker = row_amp.min(col_amp);
+        if weaker < self.min_amplitude {
+            return None;
+        }
+        let twist_db = 20.0 * (row_amp / col_amp).log10();
+        if twist_db.abs() > self.max_twist_db {
+            return None;
+        }
+        let tone_energy = row_amp.mul_add(row_amp, col_amp * col_amp) / 2.0;
+        if tone_energy < MIN_TONE_ENERGY_RATIO * energy {
+            return None;
+        }
+        Some((KEYPAD[row][col], 20.0 * weaker.log10()))
+    }
+}
+
+/// Goertzel power at the frequency whose coefficient is `coeff`.
+fn goertzel_power(samples: &[f32], coeff: f32) -> f32 {
+    let (mut prev, mut prev2) = (0.0f32, 0.0f32);
+    for &x in samples {
+        let s = coeff.mul_add(prev, x) - prev2;
+        prev2 = prev;
+        prev = s;
+    }
+    coeff.mul_add(-prev * prev2, prev.mul_add(prev, prev2 * prev2))
+}
+
+/// Index and power of the strongest tone, if it clearly dominates the rest of its group.
+fn strongest(powers: &[f32; 4]) -> Option<(usize, f32)> {
+    let (index, &peak) = powers.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+    let dominant =
+        powers.iter().enumerate().all(|(i, &p)| i == index || p * MIN_PEAK_RATIO <= peak);
+    dominant.then_some((index, peak))
+}
+
+/// Downmixes an interleaved frame to mono by averaging channels.
+fn mono_samples(frame: &AudioFrame) -> impl Iterator<Item = f32> + '_ {
+    let channels = usize::from(frame.channels.max(1));
+    let scale = 1.0 / f32::from(frame.channels.max(1));
+    frame.samples().chunks(channels).map(move |c| c.iter().sum::<f32>() * scale)
+}
+
+/// A node that listens for DTMF tones and emits one `audio::dtmf/digit@1` Custom packet per
+/// key press, with `digit`, `position_ms` (stream position of the tone start) and `level_db`.
+///
+/// Accepts any sample rate and channel count; channels are averaged before detection.
+pub struct DtmfDetectNode {
+    config: DtmfDetectConfig,
+}
+
+impl DtmfDetectNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: DtmfDetectConfig = config_helpers::parse_config_optional(params)?;
+            if !config.min_level_db.is_finite() || !config.max_twist_db.is_finite() {
+                return Err(StreamKitError::Configuration(
+                    "min_level_db and max_twist_db must be finite".to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn digit_packet(event: &DtmfEvent) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: DTMF_DIGIT_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({
+                "digit": event.digit.to_string(),
+                "position_ms": event.position_ms,
+                "level_db": event.level_db,
+            }),
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(event.position_ms * 1000),
+                duration_us: None,
+                sequence: None,
+            }),
+        }))
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for DtmfDetectNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Custom { type_id: DTMF_DIGIT_TYPE_ID.to_string() },
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut detector: Option<DtmfDetector> = None;
+        let mut events = Vec::new();
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+
+                    let detector = match &mut detector {
+                        Some(d) if d.sample_rate() == frame.sample_rate => d,
+                        slot => slot.insert(DtmfDetector::new(frame.sample_rate, &self.config)),
+                    };
+                    detector.push(mono_samples(&frame), &mut events);
+
+                    for event in std::mem::take(&mut events) {
+                        tracing::debug!(digit = %event.digit, position_ms = event.position_ms, "DTMF digit detected");
+                        if context.output_sender.send("out", Self::digit_packet(&event)).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                        stats.sent();
+                    }
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::audio::generators::dtmf::DtmfGeneratorConfig;
+
+    fn detect(sample_rate: u32, samples: &[f32]) -> Vec<DtmfEvent> {
+        let mut detector = DtmfDetector::new(sample_rate, &DtmfDetectConfig::default());
+        let mut events = Vec::new();
+        // Feed in uneven chunks to exercise block boundaries
+        for chunk in samples.chunks(333) {
+            detector.push(chunk.iter().copied(), &mut events);
+        }
+        events
+    }
+
+    fn digits(events: &[DtmfEvent]) -> String {
+        events.iter().map(|e| e.digit).collect()
+    }
+
+    #[test]
+    fn test_detects_generated_sequence() {
+        for sample_rate in [8000, 16000, 48000] {
+            let config = DtmfGeneratorConfig { sample_rate, ..Default::default() };
+            let (samples, _) = config.synthesize("0123456789*#ABCD");
+            let events = detect(sample_rate, &samples);
+            assert_eq!(digits(&events), "0123456789*#ABCD", "at {sample_rate} Hz");
+            // Second digit starts after one tone + gap (160 ms), within a block
+            assert!(events[1].position_ms.abs_diff(160) <= 20, "{:?}", events[1]);
+        }
+    }
+
+    #[test]
+    fn test_repeated_digit_and_short_tones() {
+        let config = DtmfGeneratorConfig {
+            sample_rate: 8000,
+            tone_ms: 60,
+            gap_ms: 50,
+            ..Default::default()
+        };
+        let (samples, _) = config.synthesize("55");
+        assert_eq!(digits(&detect(8000, &samples)), "55");
+
+        // 20 ms blips are shorter than the 40 ms minimum
+        let blips = DtmfGeneratorConfig { sample_rate: 8000, tone_ms: 20, ..Default::default() };
+        let (samples, _) = blips.synthesize("123");
+        assert!(detect(8000, &samples).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_single_tone_twist_and_quiet_input() {
+        // A single 1 kHz tone is not a digit
+        let tone: Vec<f32> =
+            (0..8000u16).map(|n| 0.5 * (TAU * 1000.0 * f32::from(n) / 8000.0).sin()).collect();
+        assert!(detect(8000, &tone).is_empty());
+
+        // Row tone 12 dB louder than the column tone exceeds the default twist
+        let twisted: Vec<f32> = (0..8000u16)
+            .map(|n| {
+                let t = f32::from(n) / 8000.0;
+                0.4f32.mul_add((TAU * 697.0 * t).sin(), 0.1 * (TAU * 1209.0 * t).sin())
+            })
+            .collect();
+        assert!(detect(8000, &twisted).is_empty());
+
+        let quiet =
+            DtmfGeneratorConfig { sample_rate: 8000, level_db: -45.0, ..Default::default() };
+        let (samples, _) = quiet.synthesize("1");
+        assert!(detect(8000, &samples).is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio analysis nodes, which inspect audio and emit events instead of audio.
+
+use streamkit_core::NodeRegistry;
+
+/// Type id of the Custom packets announcing a chapter start.
+///
+/// Defined here rather than in `chapter_detect` so consumers such as the MP4 muxer don't
+/// depend on the detector being built.
+pub const CHAPTER_MARKER_TYPE_ID: &str = "audio::chapter/marker@1";
+
+#[cfg(feature = "chapter_detect")]
+pub mod chapter_detect;
+#[cfg(feature = "dtmf")]
+pub mod dtmf_detect;
+
+/// Registers all available audio analysis nodes with the engine's registry.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+#[cfg_attr(
+    not(any(feature = "dtmf", feature = "chapter_detect")),
+    allow(unused_variables, clippy::missing_const_for_fn)
+)]
+pub fn register_audio_analysis(registry: &mut NodeRegistry) {
+    #[cfg(feature = "dtmf")]
+    {
+        use dtmf_detect::{DtmfDetectConfig, DtmfDetectNode};
+        use schemars::schema_for;
+        let factory = DtmfDetectNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::analysis::dtmf_detect",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(DtmfDetectConfig))
+                .expect("DtmfDetectConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "analysis".to_string()],
+            false,
+            "Detects DTMF telephone keypad tones and emits one audio::dtmf/digit@1 event per \
+             key press with the digit, its stream position and level. Use it to drive IVR \
+             menus from caller input.",
+        );
+    }
+
+    #[cfg(feature = "chapter_detect")]
+    {
+        use chapter_detect::{ChapterDetectConfig, ChapterDetectNode};
+        use schemars::schema_for;
+        let factory = ChapterDetectNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::analysis::chapter_detect",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(ChapterDetectConfig))
+                .expect("ChapterDetectConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "analysis".to_string()],
+            false,
+            "Emits audio::chapter/marker@1 events where a new chapter starts, combining pauses \
+             in the audio, topic shifts in the transcript and minimum/maximum chapter lengths. \
+             Feed it audio and, optionally, STT output to chapter podcasts and recordings. \
+             Topic shifts are the cosine distance between hashed bag-of-words vectors of the \
+             transcript, with an English-only stopword list, so other languages chapter less \
+             reliably. Connect the markers to the MP4 muxer's chapters pin to embed them.",
+        );
+    }
+}
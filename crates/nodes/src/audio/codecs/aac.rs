@@ -0,0 +1,403 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! AAC decoder node.
+//!
+//! Decodes AAC-LC carried in an ADTS stream (`.aac` files, HLS audio segments) using
+//! symphonia's pure-Rust decoder. The matching encoder is `audio::aac::encoder` in
+//! [`super::aac_encoder`].
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use opentelemetry::{global, KeyValue};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::time::Instant;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    get_stream_channel_capacity, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions, ReadOnlySource};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tokio::sync::mpsc;
+
+use crate::streaming_utils::StreamingReader;
+
+// --- AAC Decoder Constants ---
+
+/// Channel buffer size for decoder pipeline communication
+const DECODER_CHANNEL_CAPACITY: usize = 32;
+
+/// Output frame size - 20ms at 48kHz stereo (960 samples per channel * 2 = 1920 total)
+/// This matches Opus encoder expectations
+const OUTPUT_FRAME_SIZE: usize = 1920;
+
+// --- AAC Decoder ---
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
+#[serde(default)]
+pub struct AacDecoderConfig {}
+
+/// A node that decodes ADTS-framed AAC audio to raw PCM audio frames.
+pub struct AacDecoderNode {
+    _config: AacDecoderConfig,
+}
+
+impl AacDecoderNode {
+    /// Creates a new AAC decoder node.
+    ///
+    /// # Errors
+    /// Currently returns `Ok` in all cases, but the `Result` type is kept for future extensibility.
+    pub const fn new(config: AacDecoderConfig) -> Result<Self, StreamKitError> {
+        Ok(Self { _config: config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AacDecoderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Binary],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 48000, // Will be updated based on actual format
+                channels: 2,        // Will be updated based on actual format
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        Some("audio/aac".to_string())
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        tracing::info!("AacDecoderNode starting");
+        let mut input_rx = context.take_input("in")?;
+
+        let meter = global::meter("skit_nodes");
+        let packets_processed_counter = meter.u64_counter("aac_packets_processed").build();
+        let decode_duration_histogram = meter.f64_histogram("aac_decode_duration").build();
+
+        // Bounded so a slow consumer backpressures the upstream reader.
+        let (stream_tx, stream_rx) = mpsc::channel::<Bytes>(get_stream_channel_capacity());
+        let (result_tx, mut result_rx) = mpsc::channel::<DecodeResult>(DECODER_CHANNEL_CAPACITY);
+
+        let decode_task = tokio::task::spawn_blocking(move || {
+            let decode_start_time = Instant::now();
+            let reader = StreamingReader::new(stream_rx);
+
+            let result = decode_aac_streaming_incremental(reader, &result_tx);
+
+            decode_duration_histogram.record(decode_start_time.elapsed().as_secs_f64(), &[]);
+
+            if let Err(e) = result {
+                tracing::error!("AAC decode failed: {}", e);
+                let _ = result_tx.blocking_send(Err(e));
+            }
+        });
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        // Feed input from a separate task so decode results keep draining while the stream
+        // channel is full.
+        let mut input_task = tokio::spawn(async move {
+            let stream_tx = stream_tx;
+            while let Some(packet) = input_rx.recv().await {
+                if let Packet::Binary { data, .. } = packet {
+                    if stream_tx.send(data).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        let mut input_done = false;
+
+        loop {
+            tokio::select! {
+                maybe_result = result_rx.recv() => {
+                    match maybe_result {
+                        Some(Ok((samples, sample_rate, channels))) => {
+                            packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
+                            stats_tracker.received();
+
+                            if !samples.is_empty() {
+                                let output_frame =
+                                    AudioFrame::new(sample_rate, channels, samples);
+                                if context
+                                    .output_sender
+                                    .send("out", Packet::Audio(output_frame))
+                                    .await
+                                    .is_err()
+                                {
+                                    tracing::debug!("Output channel closed, stopping node");
+                                    break;
+                                }
+                                stats_tracker.sent();
+                            }
+                            stats_tracker.maybe_send();
+                        }
+                        Some(Err(e)) => {
+                            packets_processed_counter.add(1, &[KeyValue::new("status", "error")]);
+                            stats_tracker.received();
+                            stats_tracker.errored();
+                            stats_tracker.force_send();
+                            input_task.abort();
+                            let err_msg = format!("AAC decode error: {e}");
+                            state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                            return Err(StreamKitError::Runtime(err_msg));
+                        }
+                        None => {
+                            // Result channel closed, blocking task is done
+                            break;
+                        }
+                    }
+                }
+                Some(control_msg) = context.control_rx.recv() => {
+                    if matches!(control_msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                        tracing::info!("AacDecoderNode received shutdown signal");
+                        input_task.abort();
+                        break;
+                    }
+                }
+                _ = &mut input_task, if !input_done => {
+                    // Input finished; keep draining until the decoder closes the result channel.
+                    input_done = true;
+                }
+            }
+        }
+
+        let _ = decode_task.await;
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+
+        tracing::info!("AacDecoderNode finished");
+        Ok(())
+    }
+}
+
+type DecodeResult = Result<(Vec<f32>, u32, u16), String>;
+
+/// Decodes ADTS AAC from a streaming reader, emitting fixed-size frames as packets decode.
+fn decode_aac_streaming_incremental(
+    reader: StreamingReader,
+    result_tx: &mpsc::Sender<DecodeResult>,
+) -> Result<(), String> {
+    let source = ReadOnlySource::new(reader);
+    let mss = MediaSourceStream::new(Box::new(source), MediaSourceStreamOptions::default());
+
+    let mut hint = Hint::new();
+    hint.with_extension("aac");
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to probe AAC stream: {e}"))?;
+    let mut format_reader = probed.format;
+
+    let track =
+        format_reader.default_track().ok_or_else(|| "No default track found in AAC".to_string())?;
+    let codec_params = &track.codec_params;
+    let sample_rate =
+        codec_params.sample_rate.ok_or_else(|| "No sample rate found in AAC".to_string())?;
+    let channel_count =
+        codec_params.channels.ok_or_else(|| "No channel info found in AAC".to_string())?.count();
+    let channels = u16::try_from(channel_count)
+        .map_err(|_| format!("Channel count {channel_count} exceeds u16::MAX"))?;
+
+    tracing::info!(
+        "Detected AAC audio: {} Hz, {} channels (streaming mode)",
+        sample_rate,
+        channels
+    );
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create AAC decoder: {e}"))?;
+    let track_id = track.id;
+
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+    let mut rechunk_buffer: VecDeque<f32> = VecDeque::new();
+    let mut frame_count = 0usize;
+
+    loop {
+        // Blocks waiting for more data from the stream
+        let packet = match format_reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                tracing::debug!("Reached end of AAC stream after {} frames", frame_count);
+                break;
+            },
+            Err(e) => {
+                tracing::warn!("Error reading AAC packet: {}", e);
+                break;
+            },
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(audio_buf) => {
+                let buf = sample_buf.get_or_insert_with(|| {
+                    SampleBuffer::<f32>::new(audio_buf.capacity() as u64, *audio_buf.spec())
+                });
+                buf.copy_interleaved_ref(audio_buf);
+                rechunk_buffer.extend(buf.samples().iter().copied());
+
+                while rechunk_buffer.len() >= OUTPUT_FRAME_SIZE {
+                    let chunk: Vec<f32> = rechunk_buffer.drain(..OUTPUT_FRAME_SIZE).collect();
+                    if result_tx.blocking_send(Ok((chunk, sample_rate, channels))).is_err() {
+                        tracing::info!(
+                            "Result channel closed after sending {} frames. Stopping decode.",
+                            frame_count
+                        );
+                        return Ok(());
+                    }
+                    frame_count += 1;
+                }
+            },
+            Err(Error::DecodeError(err)) => {
+                // A corrupt frame is dropped; ADTS resynchronizes on the next header.
+                tracing::warn!("AAC decode error (continuing): {}", err);
+            },
+            Err(e) => {
+                return Err(format!("Failed to decode AAC packet: {e}"));
+            },
+        }
+    }
+
+    if !rechunk_buffer.is_empty() {
+        let final_chunk: Vec<f32> = rechunk_buffer.into_iter().collect();
+        if result_tx.blocking_send(Ok((final_chunk, sample_rate, channels))).is_err() {
+            return Err("Result channel closed".to_string());
+        }
+        frame_count += 1;
+    }
+
+    tracing::info!("AAC streaming decode complete: {} frames sent", frame_count);
+    Ok(())
+}
+
+use schemars::schema_for;
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the AAC decoder node.
+///
+/// # Panics
+///
+/// Panics if the default AAC decoder cannot be created (should never happen)
+/// or if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+pub fn register_aac_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "symphonia")]
+    {
+        let default_decoder = AacDecoderNode::new(AacDecoderConfig::default())
+            .expect("default AAC decoder config should be valid");
+        registry.register_static_with_description(
+            "audio::aac::decoder",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(AacDecoderNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(AacDecoderConfig))
+                .expect("AacDecoderConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_decoder.input_pins(),
+                outputs: default_decoder.output_pins(),
+            },
+            vec!["audio".to_string(), "codecs".to_string(), "aac".to_string()],
+            false,
+            "Decodes AAC-LC audio in ADTS framing to raw PCM samples. \
+             Accepts binary ADTS data and outputs f32 audio at the stream's rate and channel count.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_binary_packet, create_test_context,
+    };
+    use std::collections::HashMap;
+
+    /// ADTS stream of `frames` silent AAC-LC mono frames at 48 kHz.
+    ///
+    /// Each frame is one single channel element with no scale factor bands, which decodes to
+    /// 1024 zero samples.
+    fn silent_adts(frames: usize) -> Vec<u8> {
+        const RAW_SILENT_SCE: [u8; 4] = [0x01, 0x40, 0x20, 0x07];
+        let frame_len = 7 + RAW_SILENT_SCE.len();
+        let mut out = Vec::with_capacity(frames * frame_len);
+        for _ in 0..frames {
+            // syncword, MPEG-4, layer 0, no CRC | LC profile, 48 kHz (index 3), mono
+            out.extend_from_slice(&[0xFF, 0xF1, 0x4C, 0x40]);
+            // 13-bit frame length, buffer fullness 0x7FF (VBR), one raw data block
+            out.push(u8::try_from(frame_len >> 3).unwrap());
+            out.push(u8::try_from((frame_len & 0x07) << 5).unwrap() | 0x1F);
+            out.push(0xFC);
+            out.extend_from_slice(&RAW_SILENT_SCE);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_aac_decode_split_input() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AacDecoderNode::new(AacDecoderConfig::default()).unwrap();
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // Split mid-frame to exercise resumption across input packets.
+        let stream = silent_adts(10);
+        let (head, tail) = stream.split_at(stream.len() / 2 + 3);
+        input_tx.send(create_test_binary_packet(head.to_vec())).await.unwrap();
+        input_tx.send(create_test_binary_packet(tail.to_vec())).await.unwrap();
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let output_packets = mock_sender.get_packets_for_pin("out").await;
+        let mut total = 0;
+        for packet in &output_packets {
+            let Packet::Audio(frame) = packet else { panic!("expected audio output") };
+            assert_eq!((frame.sample_rate, frame.channels), (48000, 1));
+            assert!(frame.samples.iter().all(|s| s.abs() < 1e-6));
+            total += frame.samples.len();
+        }
+        assert_eq!(total, 10 * 1024);
+    }
+}
@@ -0,0 +1,934 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! AAC-LC encoder.
+//!
+//! A small pure-Rust encoder that produces ADTS frames any AAC-LC decoder accepts. It uses
+//! long windows only, one scalefactor per channel and frame, and escape-codebook Huffman
+//! coding. There is no psychoacoustic model: each frame gets the finest global quantizer
+//! that fits its share of the bitrate, and the spectrum above `bandwidth_hz` is dropped.
+//! That is well below what a tuned encoder achieves per bit, but clean at 96 kbps per channel
+//! and up, which is what it is meant for: getting HLS and MP4 audio out of a pipeline without
+//! an external tool.
+//!
+//! Decoders start with one frame of priming, so the decoded audio lags the input by 1024
+//! samples. Packet timestamps count encoded frames and don't compensate for it.
+//!
+//! The Huffman and scalefactor band tables are the ones from ISO/IEC 14496-3, as published
+//! in symphonia's AAC decoder.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use streamkit_core::control::NodeControlMessage;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{AudioFormat, Packet, PacketMetadata, PacketType, SampleFormat};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, OutputPin, OutputSender, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+
+use super::mdct::Mdct;
+use crate::containers::adts;
+
+/// Samples per channel in one AAC-LC frame.
+const FRAME_LEN: usize = 1024;
+
+/// Decoder input buffer per channel, which caps the bits one channel can use in a frame.
+const MAX_CHANNEL_BITS: usize = 6144;
+
+/// Largest quantized magnitude the escape codebook can carry.
+const MAX_QUANT: u16 = 8191;
+
+/// Syntactic element IDs of a raw data block.
+const ID_SCE: u32 = 0;
+const ID_CPE: u32 = 1;
+const ID_END: u32 = 7;
+
+/// Escape codebook (ESC_HCB).
+const ESC_CODEBOOK: u8 = 11;
+
+/// Codebook of bands with no nonzero coefficients (ZERO_HCB).
+const ZERO_CODEBOOK: u8 = 0;
+
+/// Bitrate the node uses unless configured otherwise.
+pub const DEFAULT_BITRATE: u32 = 128_000;
+
+const MIN_BITRATE: u32 = 16_000;
+const MAX_BITRATE: u32 = 512_000;
+
+#[rustfmt::skip]
+const SWB_OFFSET_48K_LONG: [usize; 50] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 48, 56, 64, 72, 80, 88, 96, 108, 120, 132, 144, 160,
+    176, 196, 216, 240, 264, 292, 320, 352, 384, 416, 448, 480, 512, 544, 576, 608, 640, 672, 704,
+    736, 768, 800, 832, 864, 896, 928, 1024,
+];
+
+#[rustfmt::skip]
+const SWB_OFFSET_32K_LONG: [usize; 52] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 48, 56, 64, 72, 80, 88, 96, 108, 120, 132, 144, 160,
+    176, 196, 216, 240, 264, 292, 320, 352, 384, 416, 448, 480, 512, 544, 576, 608, 640, 672, 704,
+    736, 768, 800, 832, 864, 896, 928, 960, 992, 1024,
+];
+
+#[rustfmt::skip]
+const SWB_OFFSET_24K_LONG: [usize; 48] = [
+    0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 44, 52, 60, 68, 76, 84, 92, 100, 108, 116, 124, 136,
+    148, 160, 172, 188, 204, 220, 240, 260, 284, 308, 336, 364, 396, 432, 468, 508, 552, 600, 652,
+    704, 768, 832, 896, 960, 1024,
+];
+
+#[rustfmt::skip]
+const SWB_OFFSET_16K_LONG: [usize; 44] = [
+    0, 8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 100, 112, 124, 136, 148, 160, 172, 184, 196, 212,
+    228, 244, 260, 280, 300, 320, 344, 368, 396, 424, 456, 492, 532, 572, 616, 664, 716, 772, 832,
+    896, 960, 1024,
+];
+
+#[rustfmt::skip]
+const SWB_OFFSET_8K_LONG: [usize; 41] = [
+    0, 12, 24, 36, 48, 60, 72, 84, 96, 108, 120, 132, 144, 156, 172, 188, 204, 220, 236, 252, 268,
+    288, 308, 328, 348, 372, 396, 420, 448, 476, 508, 544, 580, 620, 664, 712, 764, 820, 880, 944,
+    1024,
+];
+
+/// Code lengths of the escape codebook, indexed by `17 * |y| + |z|`.
+#[rustfmt::skip]
+const ESC_LENS: [u8; 289] = [
+     4,  5,  6,  7,  8,  8,  9, 10, 10, 10, 11, 11, 12, 11, 12, 12,
+    10,  5,  4,  5,  6,  7,  7,  8,  8,  9,  9,  9, 10, 10, 10, 10,
+    11,  8,  6,  5,  5,  6,  7,  7,  8,  8,  8,  9,  9,  9, 10, 10,
+    10, 10,  8,  7,  6,  6,  6,  7,  7,  8,  8,  8,  9,  9,  9, 10,
+    10, 10, 10,  8,  8,  7,  7,  7,  7,  8,  8,  8,  8,  9,  9,  9,
+    10, 10, 10, 10,  8,  8,  7,  7,  7,  7,  8,  8,  8,  9,  9,  9,
+     9, 10, 10, 10, 10,  8,  9,  8,  8,  8,  8,  8,  8,  8,  9,  9,
+     9, 10, 10, 10, 10, 10,  8,  9,  8,  8,  8,  8,  8,  8,  9,  9,
+     9, 10, 10, 10, 10, 10, 10,  8, 10,  9,  8,  8,  9,  9,  9,  9,
+     9, 10, 10, 10, 10, 10, 10, 11,  8, 10,  9,  9,  9,  9,  9,  9,
+     9, 10, 10, 10, 10, 10, 10, 11, 11,  8, 11,  9,  9,  9,  9,  9,
+     9, 10, 10, 10, 10, 10, 11, 10, 11, 11,  8, 11, 10,  9,  9, 10,
+     9, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11,  8, 11, 10, 10, 10,
+    10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11,  9, 11, 10,  9,
+     9, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11,  9, 11, 10,
+    10, 10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11,  9, 12,
+    10, 10, 10, 10, 10, 10, 10, 11, 11, 11, 11, 11, 11, 12, 12,  9,
+     9,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  8,  9,
+     5,
+];
+
+/// Codewords of the escape codebook, indexed like [`ESC_LENS`].
+#[rustfmt::skip]
+const ESC_CODES: [u16; 289] = [
+    0x000, 0x006, 0x019, 0x03d, 0x09c, 0x0c6, 0x1a7, 0x390,
+    0x3c2, 0x3df, 0x7e6, 0x7f3, 0xffb, 0x7ec, 0xffa, 0xffe,
+    0x38e, 0x005, 0x001, 0x008, 0x014, 0x037, 0x042, 0x092,
+    0x0af, 0x191, 0x1a5, 0x1b5, 0x39e, 0x3c0, 0x3a2, 0x3cd,
+    0x7d6, 0x0ae, 0x017, 0x007, 0x009, 0x018, 0x039, 0x040,
+    0x08e, 0x0a3, 0x0b8, 0x199, 0x1ac, 0x1c1, 0x3b1, 0x396,
+    0x3be, 0x3ca, 0x09d, 0x03c, 0x015, 0x016, 0x01a, 0x03b,
+    0x044, 0x091, 0x0a5, 0x0be, 0x196, 0x1ae, 0x1b9, 0x3a1,
+    0x391, 0x3a5, 0x3d5, 0x094, 0x09a, 0x036, 0x038, 0x03a,
+    0x041, 0x08c, 0x09b, 0x0b0, 0x0c3, 0x19e, 0x1ab, 0x1bc,
+    0x39f, 0x38f, 0x3a9, 0x3cf, 0x093, 0x0bf, 0x03e, 0x03f,
+    0x043, 0x045, 0x09e, 0x0a7, 0x0b9, 0x194, 0x1a2, 0x1ba,
+    0x1c3, 0x3a6, 0x3a7, 0x3bb, 0x3d4, 0x09f, 0x1a0, 0x08f,
+    0x08d, 0x090, 0x098, 0x0a6, 0x0b6, 0x0c4, 0x19f, 0x1af,
+    0x1bf, 0x399, 0x3bf, 0x3b4, 0x3c9, 0x3e7, 0x0a8, 0x1b6,
+    0x0ab, 0x0a4, 0x0aa, 0x0b2, 0x0c2, 0x0c5, 0x198, 0x1a4,
+    0x1b8, 0x38c, 0x3a4, 0x3c4, 0x3c6, 0x3dd, 0x3e8, 0x0ad,
+    0x3af, 0x192, 0x0bd, 0x0bc, 0x18e, 0x197, 0x19a, 0x1a3,
+    0x1b1, 0x38d, 0x398, 0x3b7, 0x3d3, 0x3d1, 0x3db, 0x7dd,
+    0x0b4, 0x3de, 0x1a9, 0x19b, 0x19c, 0x1a1, 0x1aa, 0x1ad,
+    0x1b3, 0x38b, 0x3b2, 0x3b8, 0x3ce, 0x3e1, 0x3e0, 0x7d2,
+    0x7e5, 0x0b7, 0x7e3, 0x1bb, 0x1a8, 0x1a6, 0x1b0, 0x1b2,
+    0x1b7, 0x39b, 0x39a, 0x3ba, 0x3b5, 0x3d6, 0x7d7, 0x3e4,
+    0x7d8, 0x7ea, 0x0ba, 0x7e8, 0x3a0, 0x1bd, 0x1b4, 0x38a,
+    0x1c4, 0x392, 0x3aa, 0x3b0, 0x3bc, 0x3d7, 0x7d4, 0x7dc,
+    0x7db, 0x7d5, 0x7f0, 0x0c1, 0x7fb, 0x3c8, 0x3a3, 0x395,
+    0x39d, 0x3ac, 0x3ae, 0x3c5, 0x3d8, 0x3e2, 0x3e6, 0x7e4,
+    0x7e7, 0x7e0, 0x7e9, 0x7f7, 0x190, 0x7f2, 0x393, 0x1be,
+    0x1c0, 0x394, 0x397, 0x3ad, 0x3c3, 0x3c1, 0x3d2, 0x7da,
+    0x7d9, 0x7df, 0x7eb, 0x7f4, 0x7fa, 0x195, 0x7f8, 0x3bd,
+    0x39c, 0x3ab, 0x3a8, 0x3b3, 0x3b9, 0x3d0, 0x3e3, 0x3e5,
+    0x7e2, 0x7de, 0x7ed, 0x7f1, 0x7f9, 0x7fc, 0x193, 0xffd,
+    0x3dc, 0x3b6, 0x3c7, 0x3cc, 0x3cb, 0x3d9, 0x3da, 0x7d3,
+    0x7e1, 0x7ee, 0x7ef, 0x7f5, 0x7f6, 0xffc, 0xfff, 0x19d,
+    0x1c2, 0x0b5, 0x0a1, 0x096, 0x097, 0x095, 0x099, 0x0a0,
+    0x0a2, 0x0ac, 0x0a9, 0x0b1, 0x0b3, 0x0bb, 0x0c0, 0x18f,
+    0x004,
+];
+
+/// Long-window scalefactor band offsets for a sampling rate, or `None` if unsupported.
+const fn long_bands(sample_rate: u32) -> Option<&'static [usize]> {
+    match sample_rate {
+        48_000 | 44_100 => Some(&SWB_OFFSET_48K_LONG),
+        32_000 => Some(&SWB_OFFSET_32K_LONG),
+        24_000 | 22_050 => Some(&SWB_OFFSET_24K_LONG),
+        16_000 | 12_000 | 11_025 => Some(&SWB_OFFSET_16K_LONG),
+        8_000 => Some(&SWB_OFFSET_8K_LONG),
+        _ => None,
+    }
+}
+
+/// Audio bandwidth worth spending bits on at a per-channel bitrate.
+const fn auto_bandwidth(bitrate_per_channel: u32) -> u32 {
+    match bitrate_per_channel {
+        96_000.. => 20_000,
+        64_000.. => 17_000,
+        48_000.. => 14_000,
+        32_000.. => 11_000,
+        _ => 8_000,
+    }
+}
+
+/// Sine window over a 2048-sample block.
+// Table setup in f64 over indices far below 2^52.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn sine_window() -> Vec<f32> {
+    let n = 2.0 * FRAME_LEN as f64;
+    (0..2 * FRAME_LEN).map(|i| (std::f64::consts::PI * (i as f64 + 0.5) / n).sin() as f32).collect()
+}
+
+/// MSB-first bit packer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    /// Appends the low `n` (at most 24) bits of `value`.
+    fn write(&mut self, value: u32, n: u32) {
+        debug_assert!(n <= 24);
+        self.acc = (self.acc << n) | (value & ((1 << n) - 1));
+        self.pending_bits += n;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            // Truncation to the byte just completed is intended.
+            #[allow(clippy::cast_possible_truncation)]
+            self.bytes.push((self.acc >> self.pending_bits) as u8);
+        }
+        self.acc &= (1 << self.pending_bits) - 1;
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+        self.bytes
+    }
+}
+
+/// Bits of the escape sequence for a magnitude of 16 or more.
+const fn escape_bits(value: u32) -> u32 {
+    // 2^(n + 4) <= value < 2^(n + 5)
+    let n = value.ilog2() - 4;
+    2 * n + 5
+}
+
+fn write_escape(w: &mut BitWriter, value: u32) {
+    let n = value.ilog2() - 4;
+    w.write((1 << n) - 1, n);
+    w.write(0, 1);
+    w.write(value - (1 << (n + 4)), n + 4);
+}
+
+/// Codeword index and sign/escape bits of one coefficient pair in the escape codebook.
+fn pair_bits(y: i32, z: i32) -> u32 {
+    let magnitudes = [y.unsigned_abs(), z.unsigned_abs()];
+    let index = 17 * magnitudes[0].min(16) + magnitudes[1].min(16);
+    let mut bits = u32::from(ESC_LENS[index as usize]);
+    for a in magnitudes {
+        if a != 0 {
+            bits += 1;
+        }
+        if a >= 16 {
+            bits += escape_bits(a);
+        }
+    }
+    bits
+}
+
+fn write_pair(w: &mut BitWriter, y: i32, z: i32) {
+    let magnitudes = [y.unsigned_abs(), z.unsigned_abs()];
+    let index = (17 * magnitudes[0].min(16) + magnitudes[1].min(16)) as usize;
+    w.write(u32::from(ESC_CODES[index]), u32::from(ESC_LENS[index]));
+    for v in [y, z] {
+        if v != 0 {
+            w.write(u32::from(v < 0), 1);
+        }
+    }
+    for a in magnitudes {
+        if a >= 16 {
+            write_escape(w, a);
+        }
+    }
+}
+
+/// One channel's spectrum quantized with a single scalefactor.
+struct QuantizedChannel {
+    global_gain: u8,
+    max_sfb: usize,
+    /// Codebook per band below `max_sfb`
+    codebooks: Vec<u8>,
+    coefficients: [i32; FRAME_LEN],
+}
+
+impl QuantizedChannel {
+    /// Quantizes `spectrum` with scalefactor `gain`, or returns `None` if a coefficient
+    /// would overflow the escape codebook.
+    fn new(spectrum: &[f32], bands: &[usize], max_sfb: usize, gain: u8) -> Option<Self> {
+        // x_quant = int((|X| * 2^(-(sf - 100) / 4))^(3/4) + 0.4054)
+        let step = (-0.25 * (f32::from(gain) - 100.0)).exp2();
+        let mut coefficients = [0i32; FRAME_LEN];
+        for (q, &x) in coefficients[..bands[max_sfb]].iter_mut().zip(spectrum) {
+            let magnitude = (x.abs() * step).powf(0.75) + 0.4054;
+            if magnitude > f32::from(MAX_QUANT) {
+                return None;
+            }
+            // Bounded by MAX_QUANT above.
+            #[allow(clippy::cast_possible_truncation)]
+            let magnitude = magnitude as i32;
+            *q = if x < 0.0 { -magnitude } else { magnitude };
+        }
+        let codebooks: Vec<u8> = bands
+            .windows(2)
+            .take(max_sfb)
+            .map(|band| {
+                if coefficients[band[0]..band[1]].iter().all(|&q| q == 0) {
+                    ZERO_CODEBOOK
+                } else {
+                    ESC_CODEBOOK
+                }
+            })
+            .collect();
+        // Trailing silent bands don't need to be sent at all.
+        let max_sfb = codebooks.iter().rposition(|&cb| cb != ZERO_CODEBOOK).map_or(0, |b| b + 1);
+        Some(Self { global_gain: gain, max_sfb, codebooks, coefficients })
+    }
+
+    /// Runs of equal codebooks as (codebook, bands).
+    fn sections(&self) -> Vec<(u8, usize)> {
+        let mut sections: Vec<(u8, usize)> = Vec::new();
+        for &cb in &self.codebooks[..self.max_sfb] {
+            match sections.last_mut() {
+                Some((last, len)) if *last == cb => *len += 1,
+                _ => sections.push((cb, 1)),
+            }
+        }
+        sections
+    }
+
+    /// Size of the individual channel stream this would write.
+    fn bits(&self, bands: &[usize]) -> usize {
+        // global_gain, ics_info, and the pulse/TNS/gain control flags
+        let mut bits = 8 + 11 + 3;
+        for (_, len) in self.sections() {
+            bits += 4 + 5 * (len / 31 + 1);
+        }
+        for (sfb, &cb) in self.codebooks[..self.max_sfb].iter().enumerate() {
+            if cb == ESC_CODEBOOK {
+                // One-bit scalefactor delta of zero
+                bits += 1;
+                let band = &self.coefficients[bands[sfb]..bands[sfb + 1]];
+                bits += band.chunks_exact(2).map(|p| pair_bits(p[0], p[1]) as usize).sum::<usize>();
+            }
+        }
+        bits
+    }
+
+    /// Writes the individual channel stream.
+    fn write(&self, w: &mut BitWriter, bands: &[usize]) {
+        w.write(u32::from(self.global_gain), 8);
+        // ics_reserved_bit, ONLY_LONG_SEQUENCE, sine window
+        w.write(0, 1 + 2 + 1);
+        #[allow(clippy::cast_possible_truncation)] // At most 51 bands
+        w.write(self.max_sfb as u32, 6);
+        // predictor_data_present
+        w.write(0, 1);
+
+        for (cb, len) in self.sections() {
+            w.write(u32::from(cb), 4);
+            let mut remaining = len;
+            while remaining >= 31 {
+                w.write(31, 5);
+                remaining -= 31;
+            }
+            #[allow(clippy::cast_possible_truncation)] // Less than 31
+            w.write(remaining as u32, 5);
+        }
+        // Every band uses global_gain, so each scalefactor delta is zero (index 60, code "0").
+        for &cb in &self.codebooks[..self.max_sfb] {
+            if cb == ESC_CODEBOOK {
+                w.write(0, 1);
+            }
+        }
+        // pulse_data_present, tns_data_present, gain_control_data_present
+        w.write(0, 3);
+
+        for (sfb, &cb) in self.codebooks[..self.max_sfb].iter().enumerate() {
+            if cb == ESC_CODEBOOK {
+                for pair in self.coefficients[bands[sfb]..bands[sfb + 1]].chunks_exact(2) {
+                    write_pair(w, pair[0], pair[1]);
+                }
+            }
+        }
+    }
+}
+
+/// Encoder state for one stream: format, band layout and the previous block per channel.
+struct AacEncoder {
+    channels: usize,
+    sample_rate: u32,
+    asc: [u8; 2],
+    bands: &'static [usize],
+    /// Bands below the configured bandwidth
+    max_sfb: usize,
+    channel_bits: usize,
+    mdct: Mdct,
+    /// Last frame of input per channel, the first half of the next MDCT block
+    history: Vec<Vec<f32>>,
+    block: Vec<f32>,
+    spectrum: Vec<f32>,
+}
+
+impl AacEncoder {
+    fn new(
+        sample_rate: u32,
+        channels: u16,
+        bitrate: u32,
+        bandwidth_hz: Option<u32>,
+    ) -> Result<Self, String> {
+        let bands = long_bands(sample_rate).ok_or_else(|| {
+            format!(
+                "AAC encoder supports 8, 11.025, 12, 16, 22.05, 24, 32, 44.1 and 48 kHz, \
+                 got {sample_rate} Hz"
+            )
+        })?;
+        let sample_rate_index = adts::aac_sample_rate_index(sample_rate)
+            .ok_or_else(|| format!("{sample_rate} Hz has no AAC sampling frequency index"))?;
+        if !(1..=2).contains(&channels) {
+            return Err(format!("AAC encoder supports mono and stereo, got {channels} channels"));
+        }
+        let bandwidth = bandwidth_hz
+            .unwrap_or_else(|| auto_bandwidth(bitrate / u32::from(channels)))
+            .min(sample_rate / 2);
+        // Band b starts at offset * (sample_rate / 2) / 1024 Hz.
+        let max_sfb = bands[..bands.len() - 1]
+            .iter()
+            .take_while(|&&offset| {
+                (offset as u64) * u64::from(sample_rate) < u64::from(bandwidth) * 2048
+            })
+            .count();
+        #[allow(clippy::cast_possible_truncation)] // Channel count checked above
+        let channel_config = channels as u8;
+        let mut encoder = Self {
+            channels: usize::from(channels),
+            sample_rate,
+            asc: adts::audio_specific_config(2, sample_rate_index, channel_config),
+            bands,
+            max_sfb,
+            channel_bits: 0,
+            mdct: Mdct::new(sine_window(), 2.0),
+            history: vec![vec![0.0; FRAME_LEN]; usize::from(channels)],
+            block: vec![0.0; 2 * FRAME_LEN],
+            spectrum: vec![0.0; FRAME_LEN],
+        };
+        encoder.set_bitrate(bitrate);
+        Ok(encoder)
+    }
+
+    fn set_bitrate(&mut self, bitrate: u32) {
+        // ADTS header and element framing are paid out of the frame's share.
+        let frame_bits = u64::from(bitrate) * FRAME_LEN as u64 / u64::from(self.sample_rate);
+        let frame_bits = usize::try_from(frame_bits).unwrap_or(usize::MAX).saturating_sub(56 + 16);
+        self.channel_bits = (frame_bits / self.channels).min(MAX_CHANNEL_BITS - 16);
+    }
+
+    /// Encodes one frame of interleaved samples (1024 per channel; shorter input is padded
+    /// with silence) into an ADTS frame.
+    fn encode_frame(&mut self, samples: &[f32]) -> Result<Vec<u8>, String> {
+        let mut w = BitWriter::default();
+        // SCE for mono, CPE without a common window for stereo; instance tag 0
+        w.write(if self.channels == 1 { ID_SCE } else { ID_CPE }, 3);
+        w.write(0, 4);
+        if self.channels == 2 {
+            w.write(0, 1);
+        }
+        for ch in 0..self.channels {
+            let quantized = self.analyze(samples, ch);
+            quantized.write(&mut w, self.bands);
+        }
+        w.write(ID_END, 3);
+        let payload = w.into_bytes();
+
+        let mut frame = adts::adts_header(&self.asc, payload.len())?.to_vec();
+        frame.extend_from_slice(&payload);
+        Ok(frame)
+    }
+
+    /// Transforms channel `ch` of the frame and picks the finest quantizer that fits the
+    /// channel's bit budget.
+    fn analyze(&mut self, samples: &[f32], ch: usize) -> QuantizedChannel {
+        let (past, current) = self.block.split_at_mut(FRAME_LEN);
+        past.copy_from_slice(&self.history[ch]);
+        current.fill(0.0);
+        // Decoders output 16-bit scale; input is normalized to ±1.0.
+        for (dst, frame) in current.iter_mut().zip(samples.chunks(self.channels)) {
+            *dst = frame[ch] * 32768.0;
+        }
+        self.history[ch].copy_from_slice(current);
+        self.mdct.forward(&self.block, &mut self.spectrum);
+
+        // Coarser quantizers (higher gain) never need more bits, so bisect for the lowest
+        // gain that fits. 255 keeps the whole spectrum within range.
+        let fits = |gain: u8| {
+            QuantizedChannel::new(&self.spectrum, self.bands, self.max_sfb, gain)
+                .filter(|q| q.bits(self.bands) <= self.channel_bits)
+        };
+        let (mut lo, mut hi) = (0u8, 255u8);
+        let mut best = None;
+        while lo <= hi {
+            let mid = lo + (hi - lo) / 2;
+            if let Some(q) = fits(mid) {
+                best = Some(q);
+                if mid == 0 {
+                    break;
+                }
+                hi = mid - 1;
+            } else if mid == 255 {
+                break;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        best.unwrap_or_else(|| {
+            // Not even the coarsest quantizer fits: send silence for this frame.
+            QuantizedChannel {
+                global_gain: 255,
+                max_sfb: 0,
+                codebooks: Vec::new(),
+                coefficients: [0; FRAME_LEN],
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+#[serde(default)]
+pub struct AacEncoderConfig {
+    /// Target bitrate in bits per second for the whole stream (16000-512000)
+    #[schemars(range(min = 16_000, max = 512_000), extend("tunable" = true))]
+    pub bitrate: u32,
+    /// Highest frequency to encode. Defaults to a cutoff chosen from the per-channel bitrate;
+    /// always capped at half the sample rate.
+    pub bandwidth_hz: Option<u32>,
+}
+
+impl Default for AacEncoderConfig {
+    fn default() -> Self {
+        Self { bitrate: DEFAULT_BITRATE, bandwidth_hz: None }
+    }
+}
+
+/// Params that can be changed while the encoder runs.
+#[derive(Deserialize, Debug, Default)]
+struct AacEncoderUpdate {
+    bitrate: Option<u32>,
+}
+
+fn validate_bitrate(bitrate: u32) -> Result<(), String> {
+    if !(MIN_BITRATE..=MAX_BITRATE).contains(&bitrate) {
+        return Err(format!("bitrate must be {MIN_BITRATE}-{MAX_BITRATE}, got {bitrate}"));
+    }
+    Ok(())
+}
+
+impl AacEncoderConfig {
+    fn validate(&self) -> Result<(), StreamKitError> {
+        validate_bitrate(self.bitrate).map_err(StreamKitError::Configuration)?;
+        if self.bandwidth_hz.is_some_and(|hz| hz < 1000) {
+            return Err(StreamKitError::Configuration(
+                "bandwidth_hz must be at least 1000".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A node that encodes raw audio frames into an ADTS AAC-LC stream.
+pub struct AacEncoderNode {
+    config: AacEncoderConfig,
+}
+
+impl AacEncoderNode {
+    /// Creates a new AAC encoder node.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StreamKitError::Configuration` for an out-of-range bitrate or bandwidth.
+    pub fn new(config: AacEncoderConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
+
+async fn send_aac(
+    output_sender: &mut OutputSender,
+    bytes: Vec<u8>,
+    sample_rate: u32,
+    frame_number: u64,
+) -> bool {
+    let to_us = |samples: u64| samples * 1_000_000 / u64::from(sample_rate);
+    let packet = Packet::Binary {
+        data: Bytes::from(bytes),
+        content_type: Some(Cow::Borrowed("audio/aac")),
+        metadata: Some(PacketMetadata {
+            timestamp_us: Some(to_us(frame_number * FRAME_LEN as u64)),
+            duration_us: Some(to_us(FRAME_LEN as u64)),
+            sequence: Some(frame_number),
+        }),
+    };
+    output_sender.send("out", packet).await.is_ok()
+}
+
+#[async_trait]
+impl ProcessorNode for AacEncoderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        Some("audio/aac".to_string())
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let mut bitrate = self.config.bitrate;
+        tracing::info!("AacEncoderNode starting ({} bps)", bitrate);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut encoder: Option<AacEncoder> = None;
+        let mut pending: Vec<f32> = Vec::new();
+        let mut next_frame = 0u64;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    let Packet::Audio(frame) = packet else { continue };
+                    stats_tracker.received();
+
+                    if encoder.is_none() {
+                        match AacEncoder::new(
+                            frame.sample_rate,
+                            frame.channels,
+                            bitrate,
+                            self.config.bandwidth_hz,
+                        ) {
+                            Ok(enc) => encoder = Some(enc),
+                            Err(err_msg) => {
+                                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                                return Err(StreamKitError::Runtime(err_msg));
+                            },
+                        }
+                    }
+                    let Some(enc) = encoder.as_mut() else { continue };
+                    if enc.sample_rate != frame.sample_rate || enc.channels != usize::from(frame.channels) {
+                        let err_msg = format!(
+                            "AAC input format changed from {} Hz/{} ch to {} Hz/{} ch",
+                            enc.sample_rate, enc.channels, frame.sample_rate, frame.channels
+                        );
+                        state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                        return Err(StreamKitError::Runtime(err_msg));
+                    }
+
+                    pending.extend_from_slice(frame.samples());
+                    let frame_len = FRAME_LEN * enc.channels;
+                    while pending.len() >= frame_len {
+                        let bytes = enc.encode_frame(&pending[..frame_len]).map_err(StreamKitError::Runtime)?;
+                        pending.drain(..frame_len);
+                        if !send_aac(&mut context.output_sender, bytes, enc.sample_rate, next_frame).await {
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                        next_frame += 1;
+                        stats_tracker.sent();
+                    }
+                    stats_tracker.maybe_send();
+                }
+                Some(control_msg) = context.control_rx.recv() => {
+                    match control_msg {
+                        NodeControlMessage::Shutdown => {
+                            tracing::info!("AacEncoderNode received shutdown signal");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                            return Ok(());
+                        }
+                        NodeControlMessage::UpdateParams(params) => {
+                            let update = serde_json::from_value::<AacEncoderUpdate>(params)
+                                .map_err(|e| e.to_string())
+                                .and_then(|update| {
+                                    let rate = update.bitrate.unwrap_or(bitrate);
+                                    validate_bitrate(rate).map(|()| rate)
+                                });
+                            match update {
+                                // Takes effect from the next frame
+                                Ok(rate) => {
+                                    bitrate = rate;
+                                    if let Some(enc) = encoder.as_mut() {
+                                        enc.set_bitrate(rate);
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Rejected AAC encoder update: {}", e);
+                                    stats_tracker.errored();
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // The final partial frame, then one of silence so decoders can finish the overlap.
+        if let Some(enc) = encoder.as_mut() {
+            let mut tail = Vec::new();
+            if !pending.is_empty() {
+                tail.push(std::mem::take(&mut pending));
+            }
+            tail.push(Vec::new());
+            for samples in tail {
+                let bytes = enc.encode_frame(&samples).map_err(StreamKitError::Runtime)?;
+                if !send_aac(&mut context.output_sender, bytes, enc.sample_rate, next_frame).await {
+                    break;
+                }
+                next_frame += 1;
+                stats_tracker.sent();
+            }
+        }
+        stats_tracker.force_send();
+
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        tracing::info!("AacEncoderNode finished ({} frames)", next_frame);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "aac_encoder")]
+use schemars::schema_for;
+use streamkit_core::NodeRegistry;
+#[cfg(feature = "aac_encoder")]
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the AAC encoder node.
+///
+/// # Panics
+///
+/// Panics if the default AAC encoder cannot be created (should never happen)
+/// or if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+#[cfg_attr(not(feature = "aac_encoder"), allow(unused_variables, clippy::missing_const_for_fn))]
+pub fn register_aac_encoder_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "aac_encoder")]
+    {
+        let default_encoder = AacEncoderNode::new(AacEncoderConfig::default())
+            .expect("default AAC encoder config should be valid");
+        registry.register_static_with_description(
+            "audio::aac::encoder",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(AacEncoderNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(AacEncoderConfig))
+                .expect("AacEncoderConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_encoder.input_pins(),
+                outputs: default_encoder.output_pins(),
+            },
+            vec!["audio".to_string(), "codecs".to_string(), "aac".to_string()],
+            false,
+            "Encodes mono or stereo raw audio at 8-48 kHz into AAC-LC in ADTS framing, \
+             one frame per packet with timing metadata. A simple encoder without a \
+             psychoacoustic model: each frame gets the finest quantizer that fits the bitrate. \
+             Use 96 kbps per channel or more for music. bitrate is tunable while running.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation
+)]
+mod tests {
+    use super::*;
+    use crate::audio::codecs::aac::{AacDecoderConfig, AacDecoderNode};
+    use crate::test_utils::{create_test_binary_packet, create_test_context};
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    async fn run_node<N: ProcessorNode + 'static>(node: N, packets: Vec<Packet>) -> Vec<Packet> {
+        let (input_tx, input_rx) = mpsc::channel(packets.len().max(1));
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        for packet in packets {
+            input_tx.send(packet).await.unwrap();
+        }
+        drop(input_tx);
+        handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    /// Interleaved test tones: 440 Hz on the left, 1 kHz on the right.
+    fn test_signal(sample_rate: u32, channels: u16, frames: usize) -> Vec<f32> {
+        let mut samples = Vec::with_capacity(frames * usize::from(channels));
+        for i in 0..frames {
+            let t = i as f64 / f64::from(sample_rate);
+            for ch in 0..channels {
+                let freq = if ch == 0 { 440.0 } else { 1000.0 };
+                samples.push(((2.0 * std::f64::consts::PI * freq * t).sin() * 0.5) as f32);
+            }
+        }
+        samples
+    }
+
+    async fn round_trip(
+        config: AacEncoderConfig,
+        sample_rate: u32,
+        channels: u16,
+        samples: &[f32],
+    ) -> (Vec<Packet>, Vec<f32>) {
+        let packets = samples
+            .chunks(960 * usize::from(channels))
+            .map(|chunk| Packet::Audio(AudioFrame::new(sample_rate, channels, chunk.to_vec())))
+            .collect();
+        let encoded = run_node(AacEncoderNode::new(config).unwrap(), packets).await;
+        let adts: Vec<u8> = encoded
+            .iter()
+            .flat_map(|packet| match packet {
+                Packet::Binary { data, .. } => data.to_vec(),
+                _ => panic!("expected binary output"),
+            })
+            .collect();
+        let aac_decoder = AacDecoderNode::new(AacDecoderConfig::default()).unwrap();
+        let decoded = run_node(aac_decoder, vec![create_test_binary_packet(adts)])
+            .await
+            .into_iter()
+            .flat_map(|packet| match packet {
+                Packet::Audio(frame) => {
+                    assert_eq!((frame.sample_rate, frame.channels), (sample_rate, channels));
+                    frame.samples().to_vec()
+                },
+                _ => Vec::new(),
+            })
+            .collect();
+        (encoded, decoded)
+    }
+
+    /// Signal-to-noise ratio in dB of `decoded` against `reference`.
+    fn snr_db(reference: &[f32], decoded: &[f32]) -> f64 {
+        let (mut signal, mut noise) = (0.0f64, 0.0f64);
+        for (&r, &d) in reference.iter().zip(decoded) {
+            signal += f64::from(r) * f64::from(r);
+            noise += f64::from(r - d) * f64::from(r - d);
+        }
+        10.0 * (signal / noise.max(f64::MIN_POSITIVE)).log10()
+    }
+
+    #[tokio::test]
+    async fn test_aac_encoder_round_trip_stereo() {
+        let samples = test_signal(48_000, 2, 48_000);
+        let (packets, decoded) = round_trip(AacEncoderConfig::default(), 48_000, 2, &samples).await;
+
+        // 46 full frames and the 896-sample remainder, then a frame of silence
+        assert_eq!(packets.len(), 48);
+        for (i, packet) in packets.iter().enumerate() {
+            let Packet::Binary { data, metadata, .. } = packet else { unreachable!() };
+            let header = adts::parse_adts_header(data).unwrap();
+            assert_eq!((header.object_type, header.channel_config), (2, 2));
+            assert_eq!(header.frame_len, data.len());
+            let metadata = metadata.as_ref().unwrap();
+            assert_eq!(metadata.sequence, Some(i as u64));
+            assert_eq!(metadata.timestamp_us, Some(i as u64 * 1024 * 1_000_000 / 48_000));
+        }
+        let bytes: usize = packets
+            .iter()
+            .map(|p| match p {
+                Packet::Binary { data, .. } => data.len(),
+                _ => 0,
+            })
+            .sum();
+        let kbps = bytes as f64 * 8.0 / (48.0 * 1024.0 / 48_000.0) / 1000.0;
+        assert!(kbps <= 130.0, "{kbps:.1} kbps for a 128 kbps target");
+
+        // Decoded audio lags by the 1024-sample priming frame.
+        assert_eq!(decoded.len(), 48 * 1024 * 2);
+        let delay = FRAME_LEN * 2;
+        let snr =
+            snr_db(&samples[..samples.len() - 2048], &decoded[delay..delay + samples.len() - 2048]);
+        assert!(snr > 40.0, "SNR {snr:.1} dB");
+    }
+
+    #[tokio::test]
+    async fn test_aac_encoder_round_trip_mono_low_rate() {
+        let samples = test_signal(16_000, 1, 16_000);
+        let config = AacEncoderConfig { bitrate: 32_000, ..AacEncoderConfig::default() };
+        let (_, decoded) = round_trip(config, 16_000, 1, &samples).await;
+        let snr = snr_db(&samples[..14_000], &decoded[FRAME_LEN..FRAME_LEN + 14_000]);
+        assert!(snr > 30.0, "SNR {snr:.1} dB");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_formats_fail() {
+        for (sample_rate, channels) in [(96_000, 2), (48_000, 6)] {
+            let (input_tx, input_rx) = mpsc::channel(1);
+            let inputs = HashMap::from([("in".to_string(), input_rx)]);
+            let (context, _mock_sender, _state_rx) = create_test_context(inputs, 10);
+            let node = AacEncoderNode::new(AacEncoderConfig::default()).unwrap();
+            let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+            let samples = vec![0.0; 1024 * usize::from(channels)];
+            input_tx
+                .send(Packet::Audio(AudioFrame::new(sample_rate, channels, samples)))
+                .await
+                .unwrap();
+            assert!(handle.await.unwrap().is_err(), "{sample_rate} Hz/{channels} ch");
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = |json| serde_json::from_value::<AacEncoderConfig>(json).unwrap();
+        assert!(AacEncoderNode::new(config(serde_json::json!({"bitrate": 64000}))).is_ok());
+        assert!(AacEncoderNode::new(config(serde_json::json!({"bitrate": 8000}))).is_err());
+        assert!(AacEncoderNode::new(config(serde_json::json!({"bandwidth_hz": 500}))).is_err());
+    }
+}
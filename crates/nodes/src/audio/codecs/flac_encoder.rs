@@ -0,0 +1,1068 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! FLAC encoder.
+//!
+//! A small pure-Rust encoder: fixed-blocksize frames, constant/verbatim/fixed-predictor
+//! subframes with partitioned Rice residuals, and stereo decorrelation. That is most of the
+//! compression of the reference encoder at its fast presets.
+//!
+//! FLAC frames are independent of each other, so a long stream can be split on block
+//! boundaries, the pieces encoded on separate threads, and the results concatenated in
+//! order. The node does this when `threads` is greater than one, which is meant for file
+//! conversion in oneshot pipelines where latency doesn't matter.
+//!
+//! The STREAMINFO header goes out first with the stream length left unknown, so the output
+//! never needs a final rewrite and can be written to a pipe. In `streaming` mode every packet
+//! is exactly one frame with timing metadata, for live streaming.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::{FuturesOrdered, StreamExt};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use streamkit_core::control::NodeControlMessage;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{AudioFormat, Packet, PacketMetadata, PacketType, SampleFormat};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, OutputPin, OutputSender, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+
+/// Largest sample rate a FLAC stream can declare.
+const MAX_SAMPLE_RATE: u32 = 655_350;
+
+/// Largest Rice parameter with the 4-bit parameter encoding (15 is the escape code).
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Partition orders beyond this rarely pay for their parameter bits.
+const MAX_PARTITION_ORDER: u32 = 8;
+
+/// Highest `compression_level`, as in the reference encoder.
+const MAX_COMPRESSION_LEVEL: u8 = 8;
+
+/// Compression level the node uses unless configured otherwise.
+pub const DEFAULT_COMPRESSION_LEVEL: u8 = 5;
+
+/// How hard the encoder searches for a compact coding at one compression level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Effort {
+    /// Highest fixed predictor order tried
+    max_order: usize,
+    max_partition_order: u32,
+    /// Try left/side, right/side and mid/side coding for stereo
+    stereo: bool,
+}
+
+impl Effort {
+    /// Levels 5 and up search everything this encoder knows; there is nothing more to try.
+    const fn for_level(level: u8) -> Self {
+        let (max_order, max_partition_order, stereo) = match level {
+            0 => (2, 3, false),
+            1 => (2, 3, true),
+            2 => (3, 4, true),
+            3 => (4, 4, true),
+            4 => (4, 6, true),
+            _ => (4, MAX_PARTITION_ORDER, true),
+        };
+        Self { max_order, max_partition_order, stereo }
+    }
+}
+
+// --- Bitstream ---
+
+/// Format of an encoded stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlacStreamInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 16 or 24
+    pub bits_per_sample: u8,
+    /// Samples per channel in every frame but the last
+    pub block_size: usize,
+}
+
+impl FlacStreamInfo {
+    /// Checks that the format can be expressed in a FLAC stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first unsupported value.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sample_rate == 0 || self.sample_rate > MAX_SAMPLE_RATE {
+            return Err(format!("Unsupported FLAC sample rate {} Hz", self.sample_rate));
+        }
+        if !(1..=8).contains(&self.channels) {
+            return Err(format!("FLAC supports 1-8 channels, got {}", self.channels));
+        }
+        if !matches!(self.bits_per_sample, 16 | 24) {
+            return Err(format!("bits_per_sample must be 16 or 24, got {}", self.bits_per_sample));
+        }
+        if !(16..=65535).contains(&self.block_size) {
+            return Err(format!("block_size must be 16-65535, got {}", self.block_size));
+        }
+        Ok(())
+    }
+
+    /// `fLaC` marker and STREAMINFO block. Sizes, total length and MD5 are left as unknown,
+    /// since the header goes out before any audio.
+    pub fn header(&self) -> Vec<u8> {
+        let mut w = BitWriter::default();
+        w.write(u64::from(u32::from_be_bytes(*b"fLaC")), 32);
+        // Last metadata block, type 0 (STREAMINFO), 34 bytes
+        w.write(1, 1);
+        w.write(0, 7);
+        w.write(34, 24);
+        w.write(self.block_size as u64, 16);
+        w.write(self.block_size as u64, 16);
+        w.write(0, 24); // min frame size
+        w.write(0, 24); // max frame size
+        w.write(u64::from(self.sample_rate), 20);
+        w.write(u64::from(self.channels - 1), 3);
+        w.write(u64::from(self.bits_per_sample - 1), 5);
+        w.write(0, 36); // total samples
+        w.write(0, 64); // MD5
+        w.write(0, 64);
+        w.into_bytes()
+    }
+
+    /// Interleaved samples in a block of `block_size` frames.
+    pub const fn block_len(&self) -> usize {
+        self.block_size * self.channels as usize
+    }
+}
+
+/// Encodes interleaved `samples` as consecutive frames numbered from `first_frame`, searching
+/// as hard as `compression_level` (0-8) asks. Any level decodes to the same samples.
+///
+/// Every block but the last must be full; a short final block ends the stream.
+pub fn encode_frames(
+    info: &FlacStreamInfo,
+    compression_level: u8,
+    samples: &[f32],
+    first_frame: u64,
+    out: &mut Vec<u8>,
+) {
+    let effort = Effort::for_level(compression_level);
+    let channels = usize::from(info.channels);
+    let scale = f64::from(1u32 << (info.bits_per_sample - 1));
+    let mut planes = vec![Vec::with_capacity(info.block_size); channels];
+    for (i, block) in samples.chunks(info.block_len()).enumerate() {
+        for plane in &mut planes {
+            plane.clear();
+        }
+        for frame in block.chunks_exact(channels) {
+            for (plane, &sample) in planes.iter_mut().zip(frame) {
+                plane.push(quantize(sample, scale));
+            }
+        }
+        encode_frame(info, effort, &planes, first_frame + i as u64, out);
+    }
+}
+
+// Clamped to the sample range first, so the cast cannot overflow.
+#[allow(clippy::cast_possible_truncation)]
+fn quantize(sample: f32, scale: f64) -> i64 {
+    (f64::from(sample) * scale).round().clamp(-scale, scale - 1.0) as i64
+}
+
+/// Channels as coded in a frame, with the bits per sample of each.
+type CodedChannels<'a> = Vec<(Cow<'a, [i64]>, u32)>;
+
+fn encode_frame(
+    info: &FlacStreamInfo,
+    effort: Effort,
+    planes: &[Vec<i64>],
+    frame_number: u64,
+    out: &mut Vec<u8>,
+) {
+    let block_size = planes[0].len();
+    let bps = u32::from(info.bits_per_sample);
+
+    // Stereo may be coded as left/side, right/side or mid/side, whichever is smallest.
+    let mut residual = Vec::with_capacity(block_size);
+    let (assignment, coded): (u64, CodedChannels<'_>) = if planes.len() == 2 && effort.stereo {
+        let (left, right) = (&planes[0], &planes[1]);
+        let side: Vec<i64> = left.iter().zip(right).map(|(l, r)| l - r).collect();
+        let mid: Vec<i64> = left.iter().zip(right).map(|(l, r)| (l + r) >> 1).collect();
+        let cost = |x: &[i64], sbps: u32, residual: &mut Vec<i64>| {
+            plan_subframe(x, sbps, effort, residual).bits
+        };
+        let (l, r) = (cost(left, bps, &mut residual), cost(right, bps, &mut residual));
+        let (s, m) = (cost(&side, bps + 1, &mut residual), cost(&mid, bps, &mut residual));
+        let options = [(0b0001, l + r), (0b1000, l + s), (0b1001, s + r), (0b1010, m + s)];
+        let best = options.iter().min_by_key(|(_, bits)| *bits).map_or(0b0001, |(a, _)| *a);
+        let coded = match best {
+            0b1000 => vec![(Cow::Borrowed(&left[..]), bps), (Cow::Owned(side), bps + 1)],
+            0b1001 => vec![(Cow::Owned(side), bps + 1), (Cow::Borrowed(&right[..]), bps)],
+            0b1010 => vec![(Cow::Owned(mid), bps), (Cow::Owned(side), bps + 1)],
+            _ => vec![(Cow::Borrowed(&left[..]), bps), (Cow::Borrowed(&right[..]), bps)],
+        };
+        (best, coded)
+    } else {
+        let coded = planes.iter().map(|p| (Cow::Borrowed(&p[..]), bps)).collect();
+        ((planes.len() - 1) as u64, coded)
+    };
+
+    let mut w = BitWriter::default();
+    w.write(0xFFF8, 16); // sync code, fixed blocksize
+    let (rate_code, rate_extra) = sample_rate_code(info.sample_rate);
+    w.write(0b0111, 4); // 16-bit (blocksize - 1) follows the frame number
+    w.write(rate_code, 4);
+    w.write(assignment, 4);
+    w.write(if bps == 24 { 0b110 } else { 0b100 }, 3);
+    w.write(0, 1);
+    write_utf8_number(&mut w, frame_number);
+    w.write(block_size as u64 - 1, 16);
+    if let Some((value, bits)) = rate_extra {
+        w.write(value, bits);
+    }
+    let header_crc = crc8(w.bytes());
+    w.write(u64::from(header_crc), 8);
+
+    for (samples, sbps) in &coded {
+        let plan = plan_subframe(samples, *sbps, effort, &mut residual);
+        write_subframe(&mut w, samples, *sbps, &plan, &mut residual);
+    }
+    w.align();
+    let frame_crc = crc16(w.bytes());
+    w.write(u64::from(frame_crc), 16);
+    out.extend_from_slice(w.bytes());
+}
+
+fn sample_rate_code(rate: u32) -> (u64, Option<(u64, u32)>) {
+    match rate {
+        88_200 => (0b0001, None),
+        176_400 => (0b0010, None),
+        192_000 => (0b0011, None),
+        8_000 => (0b0100, None),
+        16_000 => (0b0101, None),
+        22_050 => (0b0110, None),
+        24_000 => (0b0111, None),
+        32_000 => (0b1000, None),
+        44_100 => (0b1001, None),
+        48_000 => (0b1010, None),
+        96_000 => (0b1011, None),
+        r if r % 1000 == 0 && r / 1000 <= 255 => (0b1100, Some((u64::from(r / 1000), 8))),
+        r if r <= 65_535 => (0b1101, Some((u64::from(r), 16))),
+        r if r % 10 == 0 && r / 10 <= 65_535 => (0b1110, Some((u64::from(r / 10), 16))),
+        _ => (0b0000, None),
+    }
+}
+
+/// Frame numbers use the extended UTF-8 style coding of the FLAC spec.
+fn write_utf8_number(w: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        w.write(value, 8);
+        return;
+    }
+    let len: u32 = match value {
+        0x80..0x800 => 2,
+        0x800..0x1_0000 => 3,
+        0x1_0000..0x20_0000 => 4,
+        0x20_0000..0x400_0000 => 5,
+        0x400_0000..0x8000_0000 => 6,
+        _ => 7,
+    };
+    let lead_marker = (0xFF00u64 >> len) & 0xFF;
+    w.write(lead_marker | (value >> (6 * (len - 1))), 8);
+    for i in (0..len - 1).rev() {
+        w.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+enum SubframeKind {
+    Constant,
+    Verbatim,
+    Fixed { order: usize, partition_order: u32, params: Vec<u32> },
+}
+
+struct SubframePlan {
+    kind: SubframeKind,
+    bits: u64,
+}
+
+/// Picks the cheapest coding for one channel of a block. Leaves the chosen fixed
+/// predictor's residual in `residual`.
+fn plan_subframe(x: &[i64], sbps: u32, effort: Effort, residual: &mut Vec<i64>) -> SubframePlan {
+    let header_bits = 8;
+    if x.iter().all(|&s| s == x[0]) {
+        return SubframePlan { kind: SubframeKind::Constant, bits: header_bits + u64::from(sbps) };
+    }
+    let verbatim = SubframePlan {
+        kind: SubframeKind::Verbatim,
+        bits: header_bits + x.len() as u64 * u64::from(sbps),
+    };
+
+    // Order with the smallest residual magnitude; the Rice cost tracks it closely.
+    let max_order = effort.max_order.min(x.len() - 1);
+    let order = (0..=max_order)
+        .min_by_key(|&order| {
+            fixed_residual(x, order, residual);
+            residual.iter().map(|r| r.unsigned_abs()).sum::<u64>()
+        })
+        .unwrap_or(0);
+    fixed_residual(x, order, residual);
+    let (partition_order, params, rice_bits) =
+        plan_rice(residual, x.len(), order, effort.max_partition_order);
+    let bits = header_bits + order as u64 * u64::from(sbps) + 6 + rice_bits;
+    if bits < verbatim.bits {
+        SubframePlan { kind: SubframeKind::Fixed { order, partition_order, params }, bits }
+    } else {
+        verbatim
+    }
+}
+
+fn write_subframe(
+    w: &mut BitWriter,
+    x: &[i64],
+    sbps: u32,
+    plan: &SubframePlan,
+    residual: &mut Vec<i64>,
+) {
+    match &plan.kind {
+        SubframeKind::Constant => {
+            w.write(0b0000_0000, 8);
+            w.write_signed(x[0], sbps);
+        },
+        SubframeKind::Verbatim => {
+            w.write(0b0000_0010, 8);
+            for &s in x {
+                w.write_signed(s, sbps);
+            }
+        },
+        SubframeKind::Fixed { order, partition_order, params } => {
+            w.write((0b00_1000 | *order as u64) << 1, 8);
+            for &s in &x[..*order] {
+                w.write_signed(s, sbps);
+            }
+            fixed_residual(x, *order, residual);
+            w.write(0b00, 2); // Rice coding with 4-bit parameters
+            w.write(u64::from(*partition_order), 4);
+            let partition_len = x.len() >> partition_order;
+            let mut start = 0;
+            for (j, &k) in params.iter().enumerate() {
+                let n = if j == 0 { partition_len - order } else { partition_len };
+                w.write(u64::from(k), 4);
+                for &r in &residual[start..start + n] {
+                    let u = zigzag(r);
+                    w.write_unary(u >> k);
+                    w.write(u & ((1 << k) - 1), k);
+                }
+                start += n;
+            }
+        },
+    }
+}
+
+fn fixed_residual(x: &[i64], order: usize, out: &mut Vec<i64>) {
+    out.clear();
+    out.extend((order..x.len()).map(|i| match order {
+        0 => x[i],
+        1 => x[i] - x[i - 1],
+        2 => x[i] - 2 * x[i - 1] + x[i - 2],
+        3 => x[i] - 3 * x[i - 1] + 3 * x[i - 2] - x[i - 3],
+        _ => x[i] - 4 * x[i - 1] + 6 * x[i - 2] - 4 * x[i - 3] + x[i - 4],
+    }));
+}
+
+/// Chooses the partition order and per-partition Rice parameters; returns them with the
+/// number of bits the partitions take.
+fn plan_rice(
+    residual: &[i64],
+    block_size: usize,
+    order: usize,
+    max_partition_order: u32,
+) -> (u32, Vec<u32>, u64) {
+    let mut max_order = 0;
+    while max_order < max_partition_order
+        && block_size.is_multiple_of(1 << (max_order + 1))
+        && (block_size >> (max_order + 1)) > order
+    {
+        max_order += 1;
+    }
+
+    let mut best: Option<(u32, Vec<u32>, u64)> = None;
+    for partition_order in 0..=max_order {
+        let partition_len = block_size >> partition_order;
+        let mut params = Vec::with_capacity(1 << partition_order);
+        let mut bits = 0;
+        let mut start = 0;
+        for j in 0..1usize << partition_order {
+            let n = if j == 0 { partition_len - order } else { partition_len };
+            let (k, cost) = rice_param(&residual[start..start + n]);
+            params.push(k);
+            bits += 4 + cost;
+            start += n;
+        }
+        if best.as_ref().is_none_or(|(_, _, b)| bits < *b) {
+            best = Some((partition_order, params, bits));
+        }
+    }
+    best.unwrap_or_default()
+}
+
+/// Best Rice parameter for a partition and its cost in bits.
+fn rice_param(residual: &[i64]) -> (u32, u64) {
+    if residual.is_empty() {
+        return (0, 0);
+    }
+    let n = residual.len() as u64;
+    let cost = |k: u32| residual.iter().map(|&r| 1 + u64::from(k) + (zigzag(r) >> k)).sum::<u64>();
+    let mean = residual.iter().map(|&r| zigzag(r)).sum::<u64>() / n;
+    // The optimum is log2 of the mean or one above it.
+    let estimate = mean.max(1).ilog2().min(MAX_RICE_PARAM);
+    let (k, bits) = (estimate, cost(estimate));
+    if k < MAX_RICE_PARAM {
+        let above = cost(k + 1);
+        if above < bits {
+            return (k + 1, above);
+        }
+    }
+    (k, bits)
+}
+
+// Maps signed residuals onto unsigned codes: 0, -1, 1, -2, 2, ...
+#[allow(clippy::cast_sign_loss)] // Bit reinterpretation is the point
+const fn zigzag(r: i64) -> u64 {
+    ((r << 1) ^ (r >> 63)) as u64
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &b| {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 == 0 { crc << 1 } else { (crc << 1) ^ 0x07 };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &b| {
+        crc ^= u16::from(b) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 { crc << 1 } else { (crc << 1) ^ 0x8005 };
+        }
+        crc
+    })
+}
+
+/// MSB-first bit packer.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    /// Appends the low `n` bits of `value`.
+    fn write(&mut self, value: u64, n: u32) {
+        let mut n = n;
+        while n > 32 {
+            n -= 32;
+            self.write((value >> n) & 0xFFFF_FFFF, 32);
+        }
+        if n == 0 {
+            return;
+        }
+        self.acc = (self.acc << n) | (value & ((1 << n) - 1));
+        self.pending_bits += n;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            // Truncation to the byte just completed is intended.
+            #[allow(clippy::cast_possible_truncation)]
+            self.bytes.push((self.acc >> self.pending_bits) as u8);
+        }
+        self.acc &= (1 << self.pending_bits) - 1;
+    }
+
+    // Two's complement in `n` bits; callers size `n` to the sample range.
+    #[allow(clippy::cast_sign_loss)]
+    fn write_signed(&mut self, value: i64, n: u32) {
+        self.write(value as u64, n);
+    }
+
+    /// `q` zero bits followed by a one.
+    fn write_unary(&mut self, q: u64) {
+        let mut q = q;
+        while q >= 32 {
+            self.write(0, 32);
+            q -= 32;
+        }
+        // q < 32 here
+        #[allow(clippy::cast_possible_truncation)]
+        self.write(1, q as u32 + 1);
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+
+    /// Bytes completed so far.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+// --- Node ---
+
+#[derive(Deserialize, Debug, JsonSchema)]
+#[serde(default)]
+pub struct FlacEncoderConfig {
+    /// Bits per encoded sample (16 or 24)
+    pub bits_per_sample: u8,
+    /// Samples per channel in each FLAC frame (16-65535)
+    pub block_size: usize,
+    /// Encoder threads. 1 encodes each block as soon as it is complete. Higher values
+    /// collect `chunk_blocks` blocks at a time and encode chunks in parallel, trading
+    /// latency for throughput on long files; 0 uses every available core.
+    pub threads: usize,
+    /// Blocks per chunk when encoding on several threads
+    pub chunk_blocks: usize,
+    /// Encoder effort from 0 (fastest) to 8 (smallest output); levels above 5 currently
+    /// match 5. Every level is lossless.
+    #[schemars(range(min = 0, max = 8), extend("tunable" = true))]
+    pub compression_level: u8,
+    /// Emit exactly one FLAC frame per packet, with timestamp, duration and frame number
+    /// metadata, for live streaming. Requires `threads` = 1.
+    pub streaming: bool,
+}
+
+impl Default for FlacEncoderConfig {
+    fn default() -> Self {
+        Self {
+            bits_per_sample: 16,
+            block_size: 4096,
+            threads: 1,
+            chunk_blocks: 64,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            streaming: false,
+        }
+    }
+}
+
+/// Params that can be changed while the encoder runs.
+#[derive(Deserialize, Debug, Default)]
+struct FlacEncoderUpdate {
+    compression_level: Option<u8>,
+}
+
+fn validate_compression_level(level: u8) -> Result<(), String> {
+    if level > MAX_COMPRESSION_LEVEL {
+        return Err(format!("compression_level must be 0-{MAX_COMPRESSION_LEVEL}, got {level}"));
+    }
+    Ok(())
+}
+
+impl FlacEncoderConfig {
+    fn validate(&self) -> Result<(), StreamKitError> {
+        // Placeholder rate and channels; only the encoder settings are checked here.
+        let info = FlacStreamInfo {
+            sample_rate: 48_000,
+            channels: 2,
+            bits_per_sample: self.bits_per_sample,
+            block_size: self.block_size,
+        };
+        info.validate().map_err(StreamKitError::Configuration)?;
+        if self.chunk_blocks == 0 {
+            return Err(StreamKitError::Configuration(
+                "chunk_blocks must be greater than 0".to_string(),
+            ));
+        }
+        validate_compression_level(self.compression_level)
+            .map_err(StreamKitError::Configuration)?;
+        if self.streaming && self.threads != 1 {
+            return Err(StreamKitError::Configuration(
+                "streaming mode encodes frame by frame and requires threads = 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn resolved_threads(&self) -> usize {
+        if self.threads == 0 {
+            std::thread::available_parallelism().map_or(1, std::num::NonZero::get)
+        } else {
+            self.threads
+        }
+    }
+}
+
+/// A node that encodes raw audio frames into a FLAC stream.
+pub struct FlacEncoderNode {
+    config: FlacEncoderConfig,
+}
+
+impl FlacEncoderNode {
+    /// Creates a new FLAC encoder node.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StreamKitError::Configuration` for unsupported bit depths or block sizes.
+    pub fn new(config: FlacEncoderConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
+
+async fn send_flac(
+    output_sender: &mut OutputSender,
+    bytes: Vec<u8>,
+    metadata: Option<PacketMetadata>,
+) -> bool {
+    let packet = Packet::Binary {
+        data: Bytes::from(bytes),
+        content_type: Some(Cow::Borrowed("audio/flac")),
+        metadata,
+    };
+    output_sender.send("out", packet).await.is_ok()
+}
+
+/// Timing of a frame in streaming mode, from its number and its samples per channel.
+fn frame_metadata(info: &FlacStreamInfo, frame_number: u64, frames: usize) -> PacketMetadata {
+    let to_us = |samples: u64| samples * 1_000_000 / u64::from(info.sample_rate);
+    PacketMetadata {
+        timestamp_us: Some(to_us(frame_number * info.block_size as u64)),
+        duration_us: Some(to_us(frames as u64)),
+        sequence: Some(frame_number),
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for FlacEncoderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        Some("audio/flac".to_string())
+    }
+
+    // Encoder loop: input, parallel chunk results and control share one select.
+    #[allow(clippy::too_many_lines)]
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let threads = self.config.resolved_threads();
+        let chunk_blocks = if threads > 1 { self.config.chunk_blocks } else { 1 };
+        let streaming = self.config.streaming;
+        let mut compression_level = self.config.compression_level;
+        tracing::info!(
+            "FlacEncoderNode starting ({} bits, {} samples per block, level {}, {} threads{})",
+            self.config.bits_per_sample,
+            self.config.block_size,
+            compression_level,
+            threads,
+            if streaming { ", streaming" } else { "" }
+        );
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut stream: Option<FlacStreamInfo> = None;
+        let mut pending: Vec<f32> = Vec::new();
+        let mut next_frame = 0u64;
+        let mut in_flight: FuturesOrdered<tokio::task::JoinHandle<Vec<u8>>> = FuturesOrdered::new();
+
+        loop {
+            tokio::select! {
+                Some(encoded) = in_flight.next(), if !in_flight.is_empty() => {
+                    let bytes = encoded.map_err(|e| {
+                        StreamKitError::Runtime(format!("FLAC encode task failed: {e}"))
+                    })?;
+                    if !send_flac(&mut context.output_sender, bytes, None).await {
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                }
+                maybe_packet = input_rx.recv(), if in_flight.len() < threads => {
+                    let Some(packet) = maybe_packet else { break };
+                    let Packet::Audio(frame) = packet else { continue };
+                    stats_tracker.received();
+
+                    let info = match &stream {
+                        Some(info)
+                            if info.sample_rate == frame.sample_rate
+                                && info.channels == frame.channels => info.clone(),
+                        Some(info) => {
+                            let err_msg = format!(
+                                "FLAC input format changed from {} Hz/{} ch to {} Hz/{} ch",
+                                info.sample_rate, info.channels, frame.sample_rate, frame.channels
+                            );
+                            state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                            return Err(StreamKitError::Runtime(err_msg));
+                        },
+                        None => {
+                            let info = FlacStreamInfo {
+                                sample_rate: frame.sample_rate,
+                                channels: frame.channels,
+                                bits_per_sample: self.config.bits_per_sample,
+                                block_size: self.config.block_size,
+                            };
+                            if let Err(err_msg) = info.validate() {
+                                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                                return Err(StreamKitError::Runtime(err_msg));
+                            }
+                            if !send_flac(&mut context.output_sender, info.header(), None).await {
+                                state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                                return Ok(());
+                            }
+                            stream = Some(info.clone());
+                            info
+                        },
+                    };
+
+                    pending.extend_from_slice(frame.samples());
+                    let chunk_len = info.block_len() * chunk_blocks;
+                    while pending.len() >= chunk_len {
+                        let rest = pending.split_off(chunk_len);
+                        let chunk = std::mem::replace(&mut pending, rest);
+                        if threads == 1 {
+                            let mut bytes = Vec::new();
+                            encode_frames(&info, compression_level, &chunk, next_frame, &mut bytes);
+                            let metadata =
+                                streaming.then(|| frame_metadata(&info, next_frame, info.block_size));
+                            if !send_flac(&mut context.output_sender, bytes, metadata).await {
+                                state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                                return Ok(());
+                            }
+                            stats_tracker.sent();
+                        } else {
+                            let (info, first_frame) = (info.clone(), next_frame);
+                            in_flight.push_back(tokio::task::spawn_blocking(move || {
+                                let mut bytes = Vec::new();
+                                encode_frames(&info, compression_level, &chunk, first_frame, &mut bytes);
+                                bytes
+                            }));
+                        }
+                        next_frame += chunk_blocks as u64;
+                    }
+                    stats_tracker.maybe_send();
+                }
+                Some(control_msg) = context.control_rx.recv() => {
+                    match control_msg {
+                        NodeControlMessage::Shutdown => {
+                            tracing::info!("FlacEncoderNode received shutdown signal");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                            return Ok(());
+                        }
+                        NodeControlMessage::UpdateParams(params) => {
+                            let update = serde_json::from_value::<FlacEncoderUpdate>(params)
+                                .map_err(|e| e.to_string())
+                                .and_then(|update| {
+                                    let level = update.compression_level.unwrap_or(compression_level);
+                                    validate_compression_level(level).map(|()| level)
+                                });
+                            match update {
+                                // Takes effect from the next block; frames stay compatible
+                                Ok(level) => compression_level = level,
+                                Err(e) => {
+                                    tracing::warn!("Rejected FLAC encoder update: {}", e);
+                                    stats_tracker.errored();
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // Chunks already dispatched come first, then the final (possibly short) block.
+        let mut output_open = true;
+        while let Some(encoded) = in_flight.next().await {
+            let bytes = encoded
+                .map_err(|e| StreamKitError::Runtime(format!("FLAC encode task failed: {e}")))?;
+            output_open = send_flac(&mut context.output_sender, bytes, None).await;
+            if !output_open {
+                break;
+            }
+            stats_tracker.sent();
+        }
+        if let Some(info) = stream.as_ref().filter(|_| output_open && !pending.is_empty()) {
+            let mut bytes = Vec::new();
+            encode_frames(info, compression_level, &pending, next_frame, &mut bytes);
+            let frames = pending.len() / usize::from(info.channels);
+            let metadata = streaming.then(|| frame_metadata(info, next_frame, frames));
+            if send_flac(&mut context.output_sender, bytes, metadata).await {
+                stats_tracker.sent();
+            }
+        }
+        stats_tracker.force_send();
+
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        tracing::info!("FlacEncoderNode finished");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "flac_encoder")]
+use schemars::schema_for;
+use streamkit_core::NodeRegistry;
+#[cfg(feature = "flac_encoder")]
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the FLAC encoder node.
+///
+/// # Panics
+///
+/// Panics if the default FLAC encoder cannot be created (should never happen)
+/// or if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+#[cfg_attr(not(feature = "flac_encoder"), allow(unused_variables, clippy::missing_const_for_fn))]
+pub fn register_flac_encoder_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "flac_encoder")]
+    {
+        let default_encoder = FlacEncoderNode::new(FlacEncoderConfig::default())
+            .expect("default FLAC encoder config should be valid");
+        registry.register_static_with_description(
+            "audio::flac::encoder",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(FlacEncoderNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(FlacEncoderConfig))
+                .expect("FlacEncoderConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_encoder.input_pins(),
+                outputs: default_encoder.output_pins(),
+            },
+            vec!["audio".to_string(), "codecs".to_string(), "flac".to_string()],
+            false,
+            "Encodes raw audio into a lossless FLAC stream at 16 or 24 bits. \
+             With threads > 1, long inputs are split on block boundaries and encoded in \
+             parallel, for faster file conversion at the cost of latency. Streaming mode \
+             sends one frame per packet with timing metadata, for live streams and pipes. \
+             compression_level is tunable while running.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation
+)]
+mod tests {
+    use super::*;
+    use crate::audio::codecs::flac::{FlacDecoderConfig, FlacDecoderNode};
+    use crate::test_utils::{create_test_binary_packet, create_test_context};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    /// Stereo test signal on the 16-bit grid, so it survives a lossless round trip exactly:
+    /// a tone on the left, a correlated mix on the right, and a stretch of silence.
+    fn test_signal(frames: usize) -> Vec<f32> {
+        let mut state = 7u32;
+        let mut samples = Vec::with_capacity(frames * 2);
+        for i in 0..frames {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let noise = f64::from((state >> 16) % 200) - 100.0;
+            let tone = (i as f64 * 0.031).sin() * 12_000.0;
+            let (left, right) =
+                if (20_000..24_000).contains(&i) { (0.0, 0.0) } else { (tone, tone * 0.5 + noise) };
+            samples.push((left.round() / 32768.0) as f32);
+            samples.push((right.round() / 32768.0) as f32);
+        }
+        samples
+    }
+
+    async fn run_node<N: ProcessorNode + 'static>(node: N, packets: Vec<Packet>) -> Vec<Packet> {
+        run_node_with_updates(node, Vec::new(), packets).await
+    }
+
+    /// Runs `node`, applying param `updates` before any packet is sent.
+    async fn run_node_with_updates<N: ProcessorNode + 'static>(
+        node: N,
+        updates: Vec<serde_json::Value>,
+        packets: Vec<Packet>,
+    ) -> Vec<Packet> {
+        let (input_tx, input_rx) = mpsc::channel(packets.len().max(1));
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        for update in updates {
+            control_tx.send(NodeControlMessage::UpdateParams(update)).await.unwrap();
+        }
+        // Let the node apply the updates before audio arrives
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        for packet in packets {
+            input_tx.send(packet).await.unwrap();
+        }
+        drop(input_tx);
+        handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    /// Encodes `samples` and returns the output packets, applying `updates` first.
+    async fn encode_packets(
+        config: FlacEncoderConfig,
+        updates: Vec<serde_json::Value>,
+        samples: &[f32],
+    ) -> Vec<(Bytes, Option<PacketMetadata>)> {
+        // 20 ms frames, as a decoder or resampler upstream would deliver them
+        let packets = samples
+            .chunks(960 * 2)
+            .map(|chunk| Packet::Audio(AudioFrame::new(48_000, 2, chunk.to_vec())))
+            .collect();
+        let node = FlacEncoderNode::new(config).unwrap();
+        run_node_with_updates(node, updates, packets)
+            .await
+            .into_iter()
+            .map(|packet| match packet {
+                Packet::Binary { data, metadata, .. } => (data, metadata),
+                _ => panic!("expected binary output"),
+            })
+            .collect()
+    }
+
+    async fn encode(config: FlacEncoderConfig, samples: &[f32]) -> Vec<u8> {
+        encode_packets(config, Vec::new(), samples)
+            .await
+            .into_iter()
+            .flat_map(|(data, _)| data.to_vec())
+            .collect()
+    }
+
+    async fn decode(flac: Vec<u8>) -> Vec<f32> {
+        let flac_decoder = FlacDecoderNode::new(FlacDecoderConfig::default()).unwrap();
+        run_node(flac_decoder, vec![create_test_binary_packet(flac)])
+            .await
+            .into_iter()
+            .flat_map(|packet| match packet {
+                Packet::Audio(frame) => frame.samples().to_vec(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_flac_encoder_round_trip_is_lossless() {
+        let samples = test_signal(48_000);
+        let flac = encode(FlacEncoderConfig::default(), &samples).await;
+        assert!(flac.starts_with(b"fLaC"));
+        assert!(flac.len() < samples.len() * 2, "expected compression, got {} bytes", flac.len());
+
+        let decoded = decode(flac).await;
+        assert_eq!(decoded.len(), samples.len());
+        assert!(decoded == samples, "decoded samples differ from the input");
+    }
+
+    #[tokio::test]
+    async fn test_compression_levels_are_lossless() {
+        let samples = test_signal(30_000);
+        let mut sizes = Vec::new();
+        for compression_level in [0, 2, 4, 8] {
+            let config = FlacEncoderConfig { compression_level, ..FlacEncoderConfig::default() };
+            let flac = encode(config, &samples).await;
+            sizes.push(flac.len());
+            assert!(decode(flac).await == samples, "level {compression_level} is not lossless");
+        }
+        assert!(sizes.windows(2).all(|w| w[0] >= w[1]), "sizes by level: {sizes:?}");
+        assert!(sizes[0] > sizes[3]);
+    }
+
+    #[tokio::test]
+    async fn test_compression_level_is_tunable() {
+        let samples = test_signal(10_000);
+        let fast = FlacEncoderConfig { compression_level: 0, ..FlacEncoderConfig::default() };
+        let expected = encode(fast, &samples).await;
+
+        let updates = vec![json!({"compression_level": 9}), json!({"compression_level": 0})];
+        let tuned: Vec<u8> = encode_packets(FlacEncoderConfig::default(), updates, &samples)
+            .await
+            .into_iter()
+            .flat_map(|(data, _)| data.to_vec())
+            .collect();
+        assert_eq!(tuned, expected);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_mode_sends_one_frame_per_packet() {
+        let samples = test_signal(10_000);
+        let config =
+            FlacEncoderConfig { block_size: 960, streaming: true, ..FlacEncoderConfig::default() };
+        let packets = encode_packets(config, Vec::new(), &samples).await;
+
+        // Header, 10 full frames of 20 ms and a final frame of 400 samples
+        assert_eq!(packets.len(), 12);
+        assert!(packets[0].0.starts_with(b"fLaC") && packets[0].1.is_none());
+        for (i, (data, metadata)) in packets[1..].iter().enumerate() {
+            assert_eq!(&data[..2], [0xFF, 0xF8], "packet {i} does not start a frame");
+            let metadata = metadata.as_ref().unwrap();
+            assert_eq!(metadata.sequence, Some(i as u64));
+            assert_eq!(metadata.timestamp_us, Some(i as u64 * 20_000));
+            let expected_duration = if i == 10 { 8333 } else { 20_000 };
+            assert_eq!(metadata.duration_us, Some(expected_duration));
+        }
+
+        let flac: Vec<u8> = packets.iter().flat_map(|(data, _)| data.to_vec()).collect();
+        assert!(decode(flac).await == samples);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = |json| serde_json::from_value::<FlacEncoderConfig>(json).unwrap();
+        assert!(FlacEncoderNode::new(config(json!({"compression_level": 8}))).is_ok());
+        assert!(FlacEncoderNode::new(config(json!({"compression_level": 9}))).is_err());
+        assert!(FlacEncoderNode::new(config(json!({"streaming": true}))).is_ok());
+        assert!(FlacEncoderNode::new(config(json!({"streaming": true, "threads": 4}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_encode_matches_serial() {
+        let samples = test_signal(100_000);
+        let serial = encode(FlacEncoderConfig::default(), &samples).await;
+        let parallel = encode(
+            FlacEncoderConfig { threads: 4, chunk_blocks: 3, ..FlacEncoderConfig::default() },
+            &samples,
+        )
+        .await;
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_frame_numbers_use_utf8_coding() {
+        let mut w = BitWriter::default();
+        write_utf8_number(&mut w, 0x7F);
+        write_utf8_number(&mut w, 0x80);
+        write_utf8_number(&mut w, 0x1_0000);
+        assert_eq!(w.into_bytes(), [0x7F, 0xC2, 0x80, 0xF0, 0x90, 0x80, 0x80]);
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Forward MDCT shared by the AAC and Vorbis encoders.
+
+use symphonia::core::dsp::complex::Complex;
+use symphonia::core::dsp::fft::Fft;
+
+/// Forward MDCT of a windowed `2N`-sample block to `N` coefficients, computed as a DCT-IV
+/// through an `N/2`-point complex FFT.
+pub(super) struct Mdct {
+    len: usize,
+    fft: Fft,
+    /// Pre-rotation `exp(-iπn/N)`
+    pre: Vec<Complex>,
+    /// Post-rotation `exp(-iπ(k + 1/4)/N)`
+    post: Vec<Complex>,
+    /// Analysis window over the whole block
+    window: Vec<f32>,
+    /// Output scale
+    scale: f32,
+    scratch: Vec<Complex>,
+}
+
+impl Mdct {
+    /// Creates a transform with `window.len() / 2` coefficients whose output is multiplied by
+    /// `scale`. With a scale of 1 it computes `X[k] = sum(w[n] x[n] cos(2π/2N (n + n0)(k + 1/2)))`,
+    /// `n0 = N/2 + 1/2`.
+    // Table setup in f64 over indices far below 2^52.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(super) fn new(window: Vec<f32>, scale: f32) -> Self {
+        let len = window.len() / 2;
+        let n = len as f64;
+        let rotation = |angle: f64| Complex::new(angle.cos() as f32, angle.sin() as f32);
+        Self {
+            len,
+            fft: Fft::new(len / 2),
+            pre: (0..len / 2).map(|i| rotation(-std::f64::consts::PI * i as f64 / n)).collect(),
+            post: (0..len / 2)
+                .map(|k| rotation(-std::f64::consts::PI * (k as f64 + 0.25) / n))
+                .collect(),
+            window,
+            scale,
+            scratch: vec![Complex::default(); len / 2],
+        }
+    }
+
+    /// Windows `block` (`2N` samples) and writes its MDCT to `out` (`N` coefficients).
+    pub(super) fn forward(&mut self, block: &[f32], out: &mut [f32]) {
+        let len = self.len;
+        let q = len / 2;
+        let x = |i: usize| block[i] * self.window[i];
+        // Fold the four quarters (a, b, c, d) into the DCT-IV input (-c_r - d, a - b_r).
+        let folded = |n: usize| {
+            if n < q {
+                -x(3 * q - 1 - n) - x(3 * q + n)
+            } else {
+                let n = n - q;
+                x(n) - x(2 * q - 1 - n)
+            }
+        };
+        for (i, z) in self.scratch.iter_mut().enumerate() {
+            let v = Complex::new(folded(2 * i), folded(len - 1 - 2 * i));
+            *z = v * self.pre[i];
+        }
+        self.fft.fft_inplace(&mut self.scratch);
+        for (k, z) in self.scratch.iter().enumerate() {
+            let y = *z * self.post[k];
+            out[2 * k] = self.scale * y.re;
+            out[len - 1 - 2 * k] = -self.scale * y.im;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mdct_matches_direct_transform() {
+        for len in [128, 1024] {
+            let block: Vec<f32> =
+                (0..2 * len).map(|i| ((i * 7919) % 2003) as f32 / 1000.0 - 1.0).collect();
+            let window: Vec<f32> =
+                (0..2 * len).map(|i| ((i as f32 + 0.5) / (2 * len) as f32).sqrt()).collect();
+            let mut fast = vec![0.0; len];
+            Mdct::new(window.clone(), 2.0).forward(&block, &mut fast);
+
+            let n = 2.0 * len as f64;
+            let n0 = len as f64 / 2.0 + 0.5;
+            for k in [0, 1, len / 8, len / 2 - 1, len / 2, len - 1] {
+                let direct: f64 = (0..2 * len)
+                    .map(|i| {
+                        let phase =
+                            2.0 * std::f64::consts::PI / n * (i as f64 + n0) * (k as f64 + 0.5);
+                        2.0 * f64::from(window[i]) * f64::from(block[i]) * phase.cos()
+                    })
+                    .sum();
+                assert!(
+                    (f64::from(fast[k]) - direct).abs() < 1e-2 * direct.abs().max(1.0),
+                    "N = {len}, bin {k}"
+                );
+            }
+        }
+    }
+}
@@ -7,9 +7,15 @@
 use streamkit_core::NodeRegistry;
 
 // Declare the submodules for each codec.
+pub mod aac;
+pub mod aac_encoder;
 pub mod flac;
+pub mod flac_encoder;
+mod mdct;
 pub mod mp3;
 pub mod opus;
+pub mod vorbis;
+pub mod vorbis_encoder;
 
 /// Registers all available audio codec nodes with the engine's registry.
 pub fn register_audio_codecs(registry: &mut NodeRegistry) {
@@ -17,4 +23,9 @@ pub fn register_audio_codecs(registry: &mut NodeRegistry) {
     opus::register_opus_nodes(registry);
     mp3::register_mp3_nodes(registry);
     flac::register_flac_nodes(registry);
+    aac::register_aac_nodes(registry);
+    aac_encoder::register_aac_encoder_nodes(registry);
+    vorbis::register_vorbis_nodes(registry);
+    vorbis_encoder::register_vorbis_encoder_nodes(registry);
+    flac_encoder::register_flac_encoder_nodes(registry);
 }
@@ -2,13 +2,15 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
+use crate::audio::layout::Remix;
 use async_trait::async_trait;
 use bytes::Bytes;
 use opentelemetry::{global, KeyValue};
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
+use streamkit_core::config_helpers::merge_params;
 use streamkit_core::stats::NodeStatsTracker;
 use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
 use streamkit_core::{
@@ -31,13 +33,123 @@ const OPUS_OUTPUT_BUFFER_SIZE: usize = 4000;
 
 // --- Opus Decoder ---
 
-#[derive(Deserialize, Debug, Default, JsonSchema)]
+/// Telemetry event type of a concealed gap.
+pub const CONCEALMENT_EVENT_TYPE: &str = "audio.concealment";
+
+/// Sequence numbers this far behind the last packet are late arrivals; further back, the
+/// stream is assumed to have restarted.
+const LATE_WINDOW: u64 = 64;
+
+#[derive(Deserialize, Debug, JsonSchema)]
 #[serde(default)]
-pub struct OpusDecoderConfig {}
+pub struct OpusDecoderConfig {
+    /// Output channels: 1 (mono) or 2 (stereo). Stereo streams are downmixed when decoding to
+    /// mono, and mono streams duplicated when decoding to stereo.
+    #[schemars(range(min = 1, max = 2))]
+    pub channels: u16,
+    /// Synthesize audio for lost packets (detected from sequence number or timestamp gaps)
+    /// with Opus packet-loss concealment, recovering the last lost frame from in-band FEC
+    /// when the stream carries it.
+    pub plc: bool,
+    /// Longest gap that is concealed, in milliseconds; longer gaps are treated as a
+    /// discontinuity and left as is.
+    #[schemars(range(min = 0, max = 2000))]
+    pub max_concealed_ms: u64,
+}
+
+impl Default for OpusDecoderConfig {
+    fn default() -> Self {
+        Self { channels: 1, plc: true, max_concealed_ms: 200 }
+    }
+}
+
+/// Where an arriving packet falls relative to the ones before it.
+#[derive(Debug, PartialEq, Eq)]
+enum Arrival {
+    /// Arrived after its slot was already played or concealed
+    Late,
+    /// In order, after `lost` missing frames
+    InOrder { lost: u64 },
+}
+
+/// Detects lost and late packets from the sequence numbers and timestamps of arrivals.
+///
+/// Sequence gaps count as losses. Timestamp gaps count too (some sources number their output
+/// contiguously), but only while timestamps advance by one frame per packet: containers like
+/// Ogg stamp every packet of a page with the page's time, and those jumps aren't losses.
+#[derive(Debug, Default)]
+struct GapDetector {
+    last_sequence: Option<u64>,
+    /// Timestamp the next packet is expected at
+    next_timestamp_us: Option<u64>,
+    /// Timestamps stood still or went back since they last arrived on time
+    timestamps_unreliable: bool,
+}
+
+impl GapDetector {
+    /// Records a packet carrying `frame_us` of audio and reports how it arrived.
+    fn arrive(
+        &mut self,
+        metadata: Option<&streamkit_core::types::PacketMetadata>,
+        frame_us: u64,
+    ) -> Arrival {
+        let sequence = metadata.and_then(|m| m.sequence);
+        let timestamp_us = metadata.and_then(|m| m.timestamp_us);
+
+        let mut lost = match (sequence, self.last_sequence) {
+            (Some(sequence), Some(last)) if sequence <= last && last - sequence < LATE_WINDOW => {
+                return Arrival::Late;
+            },
+            (Some(sequence), Some(last)) => sequence.saturating_sub(last + 1),
+            _ => 0,
+        };
+        if sequence.is_some() {
+            self.last_sequence = sequence;
+        }
+
+        if let (Some(timestamp), Some(expected)) = (timestamp_us, self.next_timestamp_us) {
+            let tolerance = frame_us / 2;
+            if timestamp > expected + tolerance {
+                if !self.timestamps_unreliable && frame_us > 0 {
+                    lost = lost.max((timestamp - expected + tolerance) / frame_us);
+                }
+            } else {
+                self.timestamps_unreliable = timestamp + tolerance < expected;
+            }
+        }
+        self.next_timestamp_us = timestamp_us.map(|timestamp| timestamp + frame_us);
+
+        Arrival::InOrder { lost }
+    }
+}
+
+/// Duration of `samples` per channel at the Opus sample rate, in microseconds.
+fn samples_to_us(samples: usize) -> u64 {
+    samples as u64 * 1_000_000 / u64::from(OPUS_SAMPLE_RATE)
+}
+
+/// Copies decoded samples into a pooled buffer when a pool is available.
+fn to_pooled(audio_pool: Option<&Arc<AudioFramePool>>, decoded: &[f32]) -> PooledSamples {
+    audio_pool.map_or_else(
+        || PooledSamples::from_vec(decoded.to_vec()),
+        |pool| {
+            let mut samples = pool.get(decoded.len());
+            samples.as_mut_slice().copy_from_slice(decoded);
+            samples
+        },
+    )
+}
+
+/// A frame decoded from a packet or concealed in place of a lost one, or a decode error.
+struct DecodedFrame {
+    samples: Result<PooledSamples, String>,
+    metadata: Option<streamkit_core::types::PacketMetadata>,
+    concealed: bool,
+}
 
 /// A node that decodes Opus packets into raw audio frames.
 pub struct OpusDecoderNode {
-    _config: OpusDecoderConfig,
+    config: OpusDecoderConfig,
 }
 
 impl OpusDecoderNode {
@@ -45,10 +157,21 @@ impl OpusDecoderNode {
     ///
     /// # Errors
     ///
-    /// Currently always returns `Ok`, but the signature allows for future error cases
-    /// (e.g., if decoder initialization fails).
-    pub const fn new(config: OpusDecoderConfig) -> Result<Self, StreamKitError> {
-        Ok(Self { _config: config })
+    /// Returns an error if `channels` is neither 1 nor 2.
+    pub fn new(config: OpusDecoderConfig) -> Result<Self, StreamKitError> {
+        if !(1..=2).contains(&config.channels) {
+            return Err(StreamKitError::Configuration(format!(
+                "Opus decoder channels must be 1 or 2, got {}",
+                config.channels
+            )));
+        }
+        if config.max_concealed_ms > 2000 {
+            return Err(StreamKitError::Configuration(format!(
+                "Opus decoder max_concealed_ms must be at most 2000, got {}",
+                config.max_concealed_ms
+            )));
+        }
+        Ok(Self { config })
     }
 }
 
@@ -67,7 +190,7 @@ impl ProcessorNode for OpusDecoderNode {
             name: "out".to_string(),
             produces_type: PacketType::RawAudio(AudioFormat {
                 sample_rate: OPUS_SAMPLE_RATE,
-                channels: 1,
+                channels: self.config.channels,
                 sample_format: SampleFormat::F32,
             }),
             cardinality: PinCardinality::Broadcast,
@@ -85,6 +208,15 @@ impl ProcessorNode for OpusDecoderNode {
         let meter = global::meter("skit_nodes");
         let packets_processed_counter = meter.u64_counter("opus_packets_processed").build();
         let decode_duration_histogram = meter.f64_histogram("opus_decode_duration").build();
+        let concealed_frames_counter = meter
+            .u64_counter("opus_concealed_frames")
+            .with_description("Frames synthesized by the Opus decoder in place of lost packets")
+            .build();
+        let late_packets_counter = meter
+            .u64_counter("opus_late_packets")
+            .with_description("Packets dropped by the Opus decoder for arriving out of order")
+            .build();
+        let telemetry = context.telemetry_emitter();
 
         // Create channels for communication with the blocking task
         // Now includes metadata with each packet
@@ -92,15 +224,18 @@ impl ProcessorNode for OpusDecoderNode {
             Bytes,
             Option<streamkit_core::types::PacketMetadata>,
         )>(get_codec_channel_capacity());
-        let (result_tx, mut result_rx) = mpsc::channel::<(
-            Result<PooledSamples, String>,
-            Option<streamkit_core::types::PacketMetadata>,
-        )>(get_codec_channel_capacity());
+        let (result_tx, mut result_rx) =
+            mpsc::channel::<DecodedFrame>(get_codec_channel_capacity());
 
         // Spawn a single blocking task that will handle all decode operations
         // Uses blocking_recv/blocking_send for efficiency - no need for block_on
+        let channels = self.config.channels;
+        let plc = self.config.plc;
+        let max_concealed_us = self.config.max_concealed_ms * 1000;
         let decode_task = tokio::task::spawn_blocking(move || {
-            let mut decoder = match opus::Decoder::new(OPUS_SAMPLE_RATE, opus::Channels::Mono) {
+            let opus_channels =
+                if channels == 1 { opus::Channels::Mono } else { opus::Channels::Stereo };
+            let mut decoder = match opus::Decoder::new(OPUS_SAMPLE_RATE, opus_channels) {
                 Ok(d) => d,
                 Err(e) => {
                     tracing::error!("Failed to create Opus decoder: {}", e);
@@ -110,34 +245,90 @@ impl ProcessorNode for OpusDecoderNode {
 
             // Reusable decode buffer - avoids allocation per frame (~7.5KB savings per decode)
             // This buffer lives for the lifetime of the decode task
-            let mut decode_buffer = vec![0f32; OPUS_MAX_FRAME_SIZE];
+            let mut decode_buffer = vec![0f32; OPUS_MAX_FRAME_SIZE * usize::from(channels)];
+            let mut gaps = GapDetector::default();
+            let mut concealed_total = 0u64;
 
             // Use blocking_recv - efficient for spawn_blocking context
             while let Some((data, metadata)) = decode_rx.blocking_recv() {
                 let decode_start_time = Instant::now();
 
-                let result = {
-                    // Note: No need to zero the buffer - opus writes to it and we only
-                    // copy out decoded_len samples, so stale data is never read.
-                    match decoder.decode_float(&data, &mut decode_buffer, false) {
-                        Ok(decoded_len) => audio_pool.as_ref().map_or_else(
-                            || Ok(PooledSamples::from_vec(decode_buffer[..decoded_len].to_vec())),
-                            |pool| {
-                                let mut samples = pool.get(decoded_len);
-                                samples
-                                    .as_mut_slice()
-                                    .copy_from_slice(&decode_buffer[..decoded_len]);
-                                Ok(samples)
-                            },
-                        ),
-                        Err(e) => Err(e.to_string()),
-                    }
+                // Packets the decoder can't parse are left to fail in decode_float below
+                let frame_samples =
+                    decoder.get_nb_samples(&data).ok().map(|n| n.min(OPUS_MAX_FRAME_SIZE));
+                let frame_us = frame_samples.map_or(0, samples_to_us);
+                let lost = match frame_samples.map(|_| gaps.arrive(metadata.as_ref(), frame_us)) {
+                    Some(Arrival::Late) => {
+                        late_packets_counter.add(1, &[]);
+                        tracing::debug!(?metadata, "Dropping late Opus packet");
+                        continue;
+                    },
+                    Some(Arrival::InOrder { lost }) => lost,
+                    None => 0,
                 };
 
+                let concealable = frame_us > 0 && lost.saturating_mul(frame_us) <= max_concealed_us;
+                if plc && lost > 0 && !concealable {
+                    tracing::debug!(lost, "Gap too long to conceal, leaving a discontinuity");
+                } else if plc && lost > 0 {
+                    // PLC for all but the last lost frame, which may be recoverable from the
+                    // FEC data carried by this packet (libopus falls back to PLC otherwise)
+                    let frame_len = frame_samples.unwrap_or(0) * usize::from(channels);
+                    for remaining in (1..=lost).rev() {
+                        let fec = remaining == 1;
+                        let input: &[u8] = if fec { &data } else { &[] };
+                        let samples = decoder
+                            .decode_float(input, &mut decode_buffer[..frame_len], fec)
+                            .map(|per_channel| {
+                                let decoded_len = per_channel * usize::from(channels);
+                                to_pooled(audio_pool.as_ref(), &decode_buffer[..decoded_len])
+                            })
+                            .map_err(|e| e.to_string());
+                        let concealed_metadata = streamkit_core::types::PacketMetadata {
+                            timestamp_us: metadata
+                                .as_ref()
+                                .and_then(|m| m.timestamp_us)
+                                .map(|ts| ts.saturating_sub(remaining * frame_us)),
+                            duration_us: Some(frame_us),
+                            sequence: None,
+                        };
+                        let frame = DecodedFrame {
+                            samples,
+                            metadata: Some(concealed_metadata),
+                            concealed: true,
+                        };
+                        if result_tx.blocking_send(frame).is_err() {
+                            return; // Main task has shut down
+                        }
+                    }
+                    concealed_total += lost;
+                    concealed_frames_counter.add(lost, &[]);
+                    telemetry.emit(
+                        CONCEALMENT_EVENT_TYPE,
+                        serde_json::json!({
+                            "frames": lost,
+                            "duration_ms": lost * frame_us / 1000,
+                            "total_frames": concealed_total,
+                        }),
+                    );
+                }
+
+                // Note: No need to zero the buffer - opus writes to it and we only
+                // copy out decoded_len samples, so stale data is never read.
+                // Opus reports samples per channel.
+                let samples = decoder
+                    .decode_float(&data, &mut decode_buffer, false)
+                    .map(|per_channel| {
+                        let decoded_len = per_channel * usize::from(channels);
+                        to_pooled(audio_pool.as_ref(), &decode_buffer[..decoded_len])
+                    })
+                    .map_err(|e| e.to_string());
+
                 decode_duration_histogram.record(decode_start_time.elapsed().as_secs_f64(), &[]);
 
                 // Use blocking_send - efficient for spawn_blocking context
-                if result_tx.blocking_send((result, metadata)).is_err() {
+                let frame = DecodedFrame { samples, metadata, concealed: false };
+                if result_tx.blocking_send(frame).is_err() {
                     break; // Main task has shut down
                 }
             }
@@ -208,16 +399,18 @@ impl ProcessorNode for OpusDecoderNode {
             tokio::select! {
                 maybe_result = result_rx.recv() => {
                     match maybe_result {
-                        Some((Ok(decoded_samples), metadata)) => {
-                            packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
-                            stats_tracker.received();
+                        Some(DecodedFrame { samples: Ok(decoded_samples), metadata, concealed }) => {
+                            if !concealed {
+                                packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
+                                stats_tracker.received();
+                            }
 
                             if !decoded_samples.is_empty() {
                                 audio_packet_count += 1;
 
                                 let output_frame = AudioFrame::from_pooled(
                                     OPUS_SAMPLE_RATE,
-                                    1,
+                                    channels,
                                     decoded_samples,
                                     metadata, // Propagate metadata from input packet
                                 );
@@ -234,7 +427,7 @@ impl ProcessorNode for OpusDecoderNode {
                             }
                             stats_tracker.maybe_send();
                         }
-                        Some((Err(e), _metadata)) => {
+                        Some(DecodedFrame { samples: Err(e), .. }) => {
                             packets_processed_counter.add(1, &[KeyValue::new("status", "error")]);
                             stats_tracker.received();
                             stats_tracker.errored();
@@ -271,16 +464,18 @@ impl ProcessorNode for OpusDecoderNode {
                         tokio::select! {
                             maybe_result = result_rx.recv() => {
                                 match maybe_result {
-                                    Some((Ok(decoded_samples), metadata)) => {
-                                        packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
-                                        stats_tracker.received();
+                                    Some(DecodedFrame { samples: Ok(decoded_samples), metadata, concealed }) => {
+                                        if !concealed {
+                                            packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
+                                            stats_tracker.received();
+                                        }
 
                                         if !decoded_samples.is_empty() {
                                             audio_packet_count += 1;
 
                                             let output_frame = AudioFrame::from_pooled(
                                                 OPUS_SAMPLE_RATE,
-                                                1,
+                                                channels,
                                                 decoded_samples,
                                                 metadata, // Propagate metadata
                                             );
@@ -297,7 +492,7 @@ impl ProcessorNode for OpusDecoderNode {
                                         }
                                         stats_tracker.maybe_send();
                                     }
-                                    Some((Err(e), _metadata)) => {
+                                    Some(DecodedFrame { samples: Err(e), .. }) => {
                                         packets_processed_counter.add(1, &[KeyValue::new("status", "error")]);
                                         stats_tracker.received();
                                         stats_tracker.errored();
@@ -363,25 +558,129 @@ fn bitrate_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
         "maximum": 510_000,  // 510 kbps max bitrate
         "multipleOf": 1000,
         "default": 64000,
-        "tunable": false
+        "tunable": true
     })
 }
 
-#[derive(Deserialize, Debug, JsonSchema)]
+/// How the encoder spends its bitrate over time.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OpusBitrateMode {
+    /// Unconstrained variable bitrate: best quality for the average bitrate
+    Vbr,
+    /// Constrained VBR: varies per packet but never exceeds the bitrate over a short window
+    #[default]
+    Cvbr,
+    /// Constant bitrate: every packet has the same size
+    Cbr,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(default)]
 pub struct OpusEncoderConfig {
     #[schemars(schema_with = "bitrate_schema")]
     pub bitrate: i32,
+    /// Variable, constrained variable or constant bitrate
+    #[schemars(extend("tunable" = true))]
+    pub bitrate_mode: OpusBitrateMode,
+    /// Encoder complexity, trading CPU for quality (0-10). Uses the libopus default when unset.
+    #[schemars(range(min = 0, max = 10), extend("tunable" = true))]
+    pub complexity: Option<u8>,
+    /// In-band forward error correction: packets carry a low-bitrate copy of the previous
+    /// frame, so a decoder can recover a single lost packet. Only takes effect with a
+    /// non-zero `packet_loss_percent`.
+    #[schemars(extend("tunable" = true))]
+    pub fec: bool,
+    /// Discontinuous transmission: during silence the encoder emits 1-2 byte packets
+    /// instead of full frames, cutting bandwidth when nobody speaks
+    #[schemars(extend("tunable" = true))]
+    pub dtx: bool,
+    /// Expected packet loss on the network (0-100). Higher values make the encoder more
+    /// loss resilient (and FEC stronger) at the cost of quality when nothing is lost.
+    #[schemars(range(min = 0, max = 100), extend("tunable" = true))]
+    pub packet_loss_percent: u8,
 }
 
 impl Default for OpusEncoderConfig {
     fn default() -> Self {
         Self {
             bitrate: 64000, // 64 kbps - good balance for voice
+            bitrate_mode: OpusBitrateMode::Cvbr,
+            complexity: None,
+            fec: false,
+            dtx: false,
+            packet_loss_percent: 0,
         }
     }
 }
 
+impl OpusEncoderConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(6000..=510_000).contains(&self.bitrate) {
+            return Err(format!("bitrate must be within 6000-510000, got {}", self.bitrate));
+        }
+        if self.complexity.is_some_and(|c| c > 10) {
+            return Err("complexity must be within 0-10".to_string());
+        }
+        if self.packet_loss_percent > 100 {
+            return Err(format!(
+                "packet_loss_percent must be within 0-100, got {}",
+                self.packet_loss_percent
+            ));
+        }
+        Ok(())
+    }
+
+    /// Applies every setting to `encoder`.
+    fn apply(&self, encoder: &mut audiopus::coder::Encoder) -> audiopus::Result<()> {
+        encoder.set_bitrate(audiopus::Bitrate::BitsPerSecond(self.bitrate))?;
+        encoder.set_vbr(self.bitrate_mode != OpusBitrateMode::Cbr)?;
+        encoder.set_vbr_constraint(self.bitrate_mode == OpusBitrateMode::Cvbr)?;
+        if let Some(complexity) = self.complexity {
+            encoder.set_complexity(complexity)?;
+        }
+        encoder.set_inband_fec(self.fec)?;
+        encoder.set_packet_loss_perc(self.packet_loss_percent)?;
+        encoder.set_dtx(self.dtx)
+    }
+}
+
+/// Creates a 48 kHz libopus encoder for `channels` (1 or 2) with every setting of `config`
+/// applied, as `audio::opus::encoder` does when its input format is known.
+///
+/// # Errors
+///
+/// Returns an error if libopus rejects the encoder or one of the settings.
+pub fn configured_encoder(
+    config: &OpusEncoderConfig,
+    channels: u16,
+) -> audiopus::Result<audiopus::coder::Encoder> {
+    let channels =
+        if channels == 1 { audiopus::Channels::Mono } else { audiopus::Channels::Stereo };
+    let mut encoder = audiopus::coder::Encoder::new(
+        audiopus::SampleRate::Hz48000,
+        channels,
+        audiopus::Application::Audio,
+    )?;
+    config.apply(&mut encoder)?;
+    Ok(encoder)
+}
+
+/// Merges a runtime update into `config` and hands the result to the encode task.
+///
+/// Invalid updates are rejected and leave the encoder on its current settings.
+fn apply_update(
+    config: &mut OpusEncoderConfig,
+    params: &serde_json::Value,
+    settings_tx: &tokio::sync::watch::Sender<OpusEncoderConfig>,
+) -> Result<(), String> {
+    let updated: OpusEncoderConfig = merge_params(config, params)?;
+    updated.validate()?;
+    *config = updated;
+    settings_tx.send_replace(config.clone());
+    Ok(())
+}
+
 /// A node that encodes raw audio frames into Opus packets.
 pub struct OpusEncoderNode {
     config: OpusEncoderConfig,
@@ -392,9 +691,11 @@ impl OpusEncoderNode {
     ///
     /// # Errors
     ///
-    /// Currently always returns `Ok`, but the signature allows for future error cases
-    /// (e.g., if encoder initialization fails or config validation is added).
-    pub const fn new(config: OpusEncoderConfig) -> Result<Self, StreamKitError> {
+    /// Returns an error if a setting is out of range.
+    pub fn new(config: OpusEncoderConfig) -> Result<Self, StreamKitError> {
+        config.validate().map_err(|e| {
+            StreamKitError::Configuration(format!("Invalid Opus encoder configuration: {e}"))
+        })?;
         Ok(Self { config })
     }
 }
@@ -406,7 +707,7 @@ impl ProcessorNode for OpusEncoderNode {
             name: "in".to_string(),
             accepts_types: vec![PacketType::RawAudio(AudioFormat {
                 sample_rate: OPUS_SAMPLE_RATE,
-                channels: 1,
+                channels: 0, // Mono and stereo are encoded as is, surround is downmixed
                 sample_format: SampleFormat::F32,
             })],
             cardinality: PinCardinality::One,
@@ -446,12 +747,14 @@ impl ProcessorNode for OpusEncoderNode {
         let (result_tx, mut result_rx) =
             mpsc::channel::<Result<Vec<u8>, String>>(get_codec_channel_capacity());
 
-        let target_bitrate = self.config.bitrate;
+        // Shared with the blocking task so `UpdateParams` can retune a running encoder.
+        let mut config = self.config;
+        let (settings_tx, mut settings_rx) = tokio::sync::watch::channel(config.clone());
 
         // Spawn a single blocking task that will handle all encode operations
         // Uses blocking_recv/blocking_send for efficiency - no need for block_on
         let encode_task = tokio::task::spawn_blocking(move || {
-            let mut encoder: Option<opus::Encoder> = None;
+            let mut encoder: Option<audiopus::coder::Encoder> = None;
             let mut current_channels: Option<u16> = None;
 
             // Reusable encode buffer - avoids 4KB allocation per frame
@@ -465,25 +768,13 @@ impl ProcessorNode for OpusEncoderNode {
 
                 // Initialize or recreate encoder if channel count changed
                 if current_channels != Some(channels) {
-                    let opus_channels =
-                        if channels == 1 { opus::Channels::Mono } else { opus::Channels::Stereo };
-
-                    encoder = match opus::Encoder::new(
-                        OPUS_SAMPLE_RATE,
-                        opus_channels,
-                        opus::Application::Audio,
-                    ) {
-                        Ok(mut e) => {
-                            // Set the configured bitrate
-                            if let Err(err) = e.set_bitrate(opus::Bitrate::Bits(target_bitrate)) {
-                                tracing::error!("Failed to set Opus bitrate: {}", err);
-                                let _ = result_tx.blocking_send(Err(err.to_string()));
-                                return;
-                            }
+                    let settings = settings_rx.borrow_and_update().clone();
+                    encoder = match configured_encoder(&settings, channels) {
+                        Ok(e) => {
                             tracing::info!(
-                                "Created Opus encoder for {} channels with bitrate {} bps",
+                                "Created Opus encoder for {} channels with {:?}",
                                 channels,
-                                target_bitrate
+                                settings
                             );
                             current_channels = Some(channels);
                             Some(e)
@@ -505,6 +796,16 @@ impl ProcessorNode for OpusEncoderNode {
                         continue;
                     };
 
+                    if settings_rx.has_changed().unwrap_or(false) {
+                        let settings = settings_rx.borrow_and_update().clone();
+                        match settings.apply(enc) {
+                            Ok(()) => tracing::info!(?settings, "Updated Opus encoder settings"),
+                            Err(err) => {
+                                tracing::warn!("Failed to update Opus encoder settings: {}", err);
+                            },
+                        }
+                    }
+
                     // Pad undersized frames with silence to meet Opus requirements
                     // Opus expects exact frame sizes (e.g., 960 samples for 20ms at 48kHz)
                     let expected_samples =
@@ -561,8 +862,16 @@ impl ProcessorNode for OpusEncoderNode {
                     if let Packet::Audio(frame) = packet {
                         frame_count += 1;
 
+                        // Opus without multistream mapping carries at most two channels.
+                        let (samples, channels) = if frame.channels > 2 {
+                            let stereo = Remix::new(frame.channels, 2).apply(&frame.samples);
+                            (Arc::new(PooledSamples::from_vec(stereo)), 2)
+                        } else {
+                            (frame.samples, frame.channels)
+                        };
+
                         // Send to blocking task for encoding with channel count
-                        if encode_tx_clone.send((frame.samples, frame.channels)).await.is_err() {
+                        if encode_tx_clone.send((samples, channels)).await.is_err() {
                             tracing::error!("Encode task has shut down unexpectedly");
                             return;
                         }
@@ -622,16 +931,25 @@ impl ProcessorNode for OpusEncoderNode {
                     }
                 }
                 Some(control_msg) = context.control_rx.recv() => {
-                    if matches!(control_msg, streamkit_core::control::NodeControlMessage::Shutdown) {
-                        tracing::info!("OpusEncoderNode received shutdown signal");
-                        // Abort input task
-                        input_task.abort();
-                        // Signal blocking task to shut down
-                        drop(encode_tx);
-                        // Break out of main loop
-                        break;
+                    match control_msg {
+                        streamkit_core::control::NodeControlMessage::Shutdown => {
+                            tracing::info!("OpusEncoderNode received shutdown signal");
+                            // Abort input task
+                            input_task.abort();
+                            // Signal blocking task to shut down
+                            drop(encode_tx);
+                            // Break out of main loop
+                            break;
+                        }
+                        streamkit_core::control::NodeControlMessage::UpdateParams(params) => {
+                            if let Err(e) = apply_update(&mut config, &params, &settings_tx) {
+                                tracing::warn!("Rejected Opus encoder update: {}", e);
+                                stats_tracker.errored();
+                            }
+                        }
+                        // Ignore other control messages
+                        _ => {}
                     }
-                    // Ignore other control messages
                 }
                 _ = &mut input_task => {
                     // Input task finished, signal blocking task to shut down
@@ -738,8 +1056,8 @@ pub fn register_opus_nodes(registry: &mut NodeRegistry) {
             vec!["audio".to_string(), "codecs".to_string(), "opus".to_string()],
             false,
             "Encodes raw PCM audio into Opus-compressed packets. \
-             Configurable bitrate, application mode (VoIP/audio), and complexity settings. \
-             Ideal for streaming and real-time communication.",
+             Bitrate, VBR/CVBR/CBR mode, complexity, in-band FEC, DTX and expected packet \
+             loss are all tunable while running, for real-time streaming over lossy networks.",
         );
     }
 }
@@ -809,7 +1127,7 @@ mod tests {
         let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
 
         // Create Opus encoder with higher bitrate for stereo
-        let config = OpusEncoderConfig { bitrate: 128_000 };
+        let config = OpusEncoderConfig { bitrate: 128_000, ..Default::default() };
         let node = OpusEncoderNode::new(config).unwrap();
 
         let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
@@ -902,7 +1220,7 @@ mod tests {
 
         let (enc_context, enc_mock_sender, mut enc_state_rx) = create_test_context(enc_inputs, 10);
 
-        let enc_config = OpusEncoderConfig { bitrate: 96000 };
+        let enc_config = OpusEncoderConfig { bitrate: 96000, ..Default::default() };
         let enc_node = OpusEncoderNode::new(enc_config).unwrap();
 
         let enc_handle = tokio::spawn(async move { Box::new(enc_node).run(enc_context).await });
@@ -962,13 +1280,12 @@ mod tests {
         println!("✅ Decoded {} Opus packets back to audio", decoded_packets.len());
 
         // Verify decoded audio has correct format
-        // Note: Current decoder implementation decodes to mono, even if input was stereo
+        // Note: The default decoder configuration decodes to mono, even if input was stereo
         for (i, packet) in decoded_packets.iter().enumerate() {
             match packet {
                 Packet::Audio(frame) => {
                     assert_eq!(frame.sample_rate, 48_000, "Frame {i} should have 48kHz");
-                    // Decoder is mono-only currently
-                    assert_eq!(frame.channels, 1, "Frame {i} should be mono (decoder limitation)");
+                    assert_eq!(frame.channels, 1, "Frame {i} should be mono (default channels)");
                     assert_eq!(
                         frame.samples.len(),
                         960,
@@ -982,6 +1299,58 @@ mod tests {
         println!("✅ Opus roundtrip complete: audio → Opus → audio");
     }
 
+    #[tokio::test]
+    async fn test_opus_surround_downmix_and_stereo_decode() {
+        assert!(
+            OpusDecoderNode::new(OpusDecoderConfig { channels: 6, ..Default::default() }).is_err()
+        );
+
+        // 5.1 input is downmixed to stereo by the encoder...
+        let (enc_input_tx, enc_input_rx) = mpsc::channel(10);
+        let enc_inputs = HashMap::from([("in".to_string(), enc_input_rx)]);
+        let (enc_context, enc_mock_sender, mut enc_state_rx) = create_test_context(enc_inputs, 10);
+        let enc_node = OpusEncoderNode::new(OpusEncoderConfig::default()).unwrap();
+        let enc_handle = tokio::spawn(async move { Box::new(enc_node).run(enc_context).await });
+        assert_state_initializing(&mut enc_state_rx).await;
+        assert_state_running(&mut enc_state_rx).await;
+        for _ in 0..3 {
+            enc_input_tx.send(create_test_audio_packet(48000, 6, 960, 0.2)).await.unwrap();
+        }
+        drop(enc_input_tx);
+        assert_state_stopped(&mut enc_state_rx).await;
+        enc_handle.await.unwrap().unwrap();
+        let encoded_packets = enc_mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(encoded_packets.len(), 3);
+
+        // ...and decodes to stereo when asked for it.
+        let (dec_input_tx, dec_input_rx) = mpsc::channel(10);
+        let dec_inputs = HashMap::from([("in".to_string(), dec_input_rx)]);
+        let (dec_context, dec_mock_sender, mut dec_state_rx) = create_test_context(dec_inputs, 10);
+        let dec_node =
+            OpusDecoderNode::new(OpusDecoderConfig { channels: 2, ..Default::default() }).unwrap();
+        assert!(matches!(
+            dec_node.output_pins()[0].produces_type,
+            PacketType::RawAudio(AudioFormat { channels: 2, .. })
+        ));
+        let dec_handle = tokio::spawn(async move { Box::new(dec_node).run(dec_context).await });
+        assert_state_initializing(&mut dec_state_rx).await;
+        assert_state_running(&mut dec_state_rx).await;
+        for packet in encoded_packets {
+            dec_input_tx.send(packet).await.unwrap();
+        }
+        drop(dec_input_tx);
+        assert_state_stopped(&mut dec_state_rx).await;
+        dec_handle.await.unwrap().unwrap();
+
+        let decoded_packets = dec_mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(decoded_packets.len(), 3);
+        for packet in &decoded_packets {
+            let Packet::Audio(frame) = packet else { panic!("Expected Audio packet") };
+            assert_eq!(frame.channels, 2);
+            assert_eq!(frame.samples.len(), 1920, "20ms of stereo at 48kHz");
+        }
+    }
+
     #[tokio::test]
     async fn test_opus_encoder_channel_switching() {
         // Test that encoder handles mono → stereo transitions
@@ -1069,7 +1438,7 @@ mod tests {
 
             let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
 
-            let config = OpusEncoderConfig { bitrate };
+            let config = OpusEncoderConfig { bitrate, ..Default::default() };
             let node = OpusEncoderNode::new(config).unwrap();
 
             let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
@@ -1092,4 +1461,190 @@ mod tests {
 
         println!("✅ Opus encoder tested with multiple bitrates");
     }
+
+    #[test]
+    fn test_opus_encoder_config_validation() {
+        let invalid = [
+            OpusEncoderConfig { bitrate: 1000, ..Default::default() },
+            OpusEncoderConfig { complexity: Some(11), ..Default::default() },
+            OpusEncoderConfig { packet_loss_percent: 101, ..Default::default() },
+        ];
+        for config in invalid {
+            assert!(OpusEncoderNode::new(config).is_err());
+        }
+
+        let config: OpusEncoderConfig = serde_json::from_value(serde_json::json!({
+            "bitrate_mode": "cbr", "complexity": 5, "fec": true, "dtx": true,
+            "packet_loss_percent": 15
+        }))
+        .unwrap();
+        assert_eq!(config.bitrate, 64000);
+        assert_eq!(config.bitrate_mode, OpusBitrateMode::Cbr);
+        assert!(OpusEncoderNode::new(config).is_ok());
+    }
+
+    /// Encodes `frames` frames of mono audio at `level` with `config`, applying `updates`
+    /// before any audio is sent.
+    async fn encode_with_updates(
+        config: OpusEncoderConfig,
+        updates: Vec<serde_json::Value>,
+        frames: usize,
+        level: f32,
+    ) -> Vec<Bytes> {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let node = OpusEncoderNode::new(config).unwrap();
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        for update in updates {
+            control_tx
+                .send(streamkit_core::control::NodeControlMessage::UpdateParams(update))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        for _ in 0..frames {
+            input_tx.send(create_test_audio_packet(48000, 1, 960, level)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        mock_sender
+            .get_packets_for_pin("out")
+            .await
+            .into_iter()
+            .map(|packet| match packet {
+                Packet::Binary { data, .. } => data,
+                _ => panic!("Expected Binary packet from Opus encoder"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_opus_encoder_runtime_cbr_update() {
+        // Switching to CBR keeps the other settings; the out-of-range update is ignored.
+        let updates = vec![
+            serde_json::json!({ "bitrate_mode": "cbr", "bitrate": 32000 }),
+            serde_json::json!({ "packet_loss_percent": 200 }),
+        ];
+        let packets = encode_with_updates(OpusEncoderConfig::default(), updates, 10, 0.5).await;
+        assert_eq!(packets.len(), 10);
+        // 32 kbps over 20 ms frames
+        assert!(packets.iter().all(|p| p.len() == 80), "sizes: {:?}", packets_sizes(&packets));
+    }
+
+    #[tokio::test]
+    async fn test_opus_encoder_dtx_shrinks_silence() {
+        let config = OpusEncoderConfig {
+            dtx: true,
+            fec: true,
+            packet_loss_percent: 10,
+            ..Default::default()
+        };
+        let packets = encode_with_updates(config, Vec::new(), 50, 0.0).await;
+        assert_eq!(packets.len(), 50);
+        // DTX kicks in after a few hundred milliseconds of silence
+        assert!(packets[40..].iter().all(|p| p.len() <= 3), "sizes: {:?}", packets_sizes(&packets));
+    }
+
+    fn packets_sizes(packets: &[Bytes]) -> Vec<usize> {
+        packets.iter().map(Bytes::len).collect()
+    }
+
+    fn at(
+        sequence: Option<u64>,
+        timestamp_us: Option<u64>,
+    ) -> streamkit_core::types::PacketMetadata {
+        streamkit_core::types::PacketMetadata { timestamp_us, duration_us: None, sequence }
+    }
+
+    #[test]
+    fn test_gap_detector_sequences() {
+        let mut gaps = GapDetector::default();
+        assert_eq!(gaps.arrive(Some(&at(Some(10), None)), 20_000), Arrival::InOrder { lost: 0 });
+        assert_eq!(gaps.arrive(Some(&at(Some(13), None)), 20_000), Arrival::InOrder { lost: 2 });
+        assert_eq!(gaps.arrive(Some(&at(Some(12), None)), 20_000), Arrival::Late);
+        // A stream that starts over is not late
+        assert_eq!(
+            gaps.arrive(Some(&at(Some(13 + LATE_WINDOW), None)), 20_000),
+            Arrival::InOrder { lost: LATE_WINDOW - 1 }
+        );
+        assert_eq!(gaps.arrive(Some(&at(Some(0), None)), 20_000), Arrival::InOrder { lost: 0 });
+    }
+
+    #[test]
+    fn test_gap_detector_timestamps() {
+        // Contiguously numbered packets with a timestamp gap (e.g. RTP ingest)
+        let mut gaps = GapDetector::default();
+        for (sequence, timestamp, lost) in
+            [(0, 0, 0), (1, 20_000, 0), (2, 80_000, 2), (3, 100_000, 0)]
+        {
+            let arrival = gaps.arrive(Some(&at(Some(sequence), Some(timestamp))), 20_000);
+            assert_eq!(arrival, Arrival::InOrder { lost });
+        }
+
+        // Page-level timestamps, as from the Ogg demuxer, are not losses
+        let mut gaps = GapDetector::default();
+        for (sequence, timestamp) in
+            [(0, 60_000), (1, 60_000), (2, 60_000), (3, 120_000), (4, 120_000)]
+        {
+            let arrival = gaps.arrive(Some(&at(Some(sequence), Some(timestamp))), 20_000);
+            assert_eq!(arrival, Arrival::InOrder { lost: 0 });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opus_decoder_conceals_lost_packets() {
+        let config = OpusEncoderConfig { fec: true, packet_loss_percent: 20, ..Default::default() };
+        let packets = encode_with_updates(config, Vec::new(), 8, 0.3).await;
+        assert_eq!(packets.len(), 8);
+
+        let (input_tx, input_rx) = mpsc::channel(20);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (telemetry_tx, mut telemetry_rx) = mpsc::channel(10);
+        context.telemetry_tx = Some(telemetry_tx);
+        let node = OpusDecoderNode::new(OpusDecoderConfig::default()).unwrap();
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // Packets 2 and 3 are lost, packet 1 is delivered again late
+        for sequence in [0u64, 1, 4, 1, 5, 6, 7] {
+            let packet = Packet::Binary {
+                data: packets[usize::try_from(sequence).unwrap()].clone(),
+                content_type: None,
+                metadata: Some(at(Some(sequence), None)),
+            };
+            input_tx.send(packet).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let frames: Vec<_> = mock_sender
+            .get_packets_for_pin("out")
+            .await
+            .into_iter()
+            .map(|packet| match packet {
+                Packet::Audio(frame) => frame,
+                _ => panic!("Expected Audio packet from Opus decoder"),
+            })
+            .collect();
+        assert_eq!(frames.len(), 8);
+        assert!(frames.iter().all(|frame| frame.samples.len() == 960));
+        let sequences: Vec<_> =
+            frames.iter().map(|f| f.metadata.as_ref().and_then(|m| m.sequence)).collect();
+        assert_eq!(sequences, [Some(0), Some(1), None, None, Some(4), Some(5), Some(6), Some(7)]);
+
+        let event = telemetry_rx.recv().await.unwrap();
+        assert_eq!(event.event_type(), Some(CONCEALMENT_EVENT_TYPE));
+    }
 }
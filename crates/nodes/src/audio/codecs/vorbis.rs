@@ -0,0 +1,413 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Vorbis decoder node.
+//!
+//! Decodes Vorbis packets with symphonia's Vorbis decoder. Its input is one Vorbis packet
+//! per binary packet, as `containers::ogg::demuxer` (with `codec: vorbis`) and
+//! `audio::vorbis::encoder` produce them. The three header packets come first: the
+//! identification and setup headers configure the decoder, the comment header is skipped.
+//! Each audio packet then decodes to one frame carrying the packet's timing metadata. A
+//! packet whose duration is shorter than its decoded audio, the end of a stream, is cut to
+//! that duration.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use opentelemetry::{global, KeyValue};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::time::Instant;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    get_codec_channel_capacity, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecParameters, Decoder, DecoderOptions, CODEC_TYPE_VORBIS};
+use symphonia::core::errors::Error;
+use tokio::sync::mpsc;
+
+// --- Vorbis Decoder Constants ---
+
+/// Header packet types (section 4.2.1 of the Vorbis I specification)
+const IDENTIFICATION_HEADER: u8 = 1;
+const COMMENT_HEADER: u8 = 3;
+const SETUP_HEADER: u8 = 5;
+
+/// Length of the identification header
+const IDENTIFICATION_LEN: usize = 30;
+
+// --- Vorbis Decoder ---
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
+#[serde(default)]
+pub struct VorbisDecoderConfig {}
+
+/// A node that decodes Vorbis packets to raw PCM audio frames.
+pub struct VorbisDecoderNode {
+    _config: VorbisDecoderConfig,
+}
+
+impl VorbisDecoderNode {
+    /// Creates a new Vorbis decoder node.
+    ///
+    /// # Errors
+    /// Currently returns `Ok` in all cases, but the `Result` type is kept for future extensibility.
+    pub const fn new(config: VorbisDecoderConfig) -> Result<Self, StreamKitError> {
+        Ok(Self { _config: config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for VorbisDecoderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Binary],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 48000, // Will be updated based on actual format
+                channels: 2,        // Will be updated based on actual format
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        tracing::info!("VorbisDecoderNode starting");
+        let mut input_rx = context.take_input("in")?;
+
+        let meter = global::meter("skit_nodes");
+        let packets_processed_counter = meter.u64_counter("vorbis_packets_processed").build();
+        let decode_duration_histogram = meter.f64_histogram("vorbis_decode_duration").build();
+
+        let (decode_tx, mut decode_rx) =
+            mpsc::channel::<(Bytes, Option<PacketMetadata>)>(get_codec_channel_capacity());
+        let (result_tx, mut result_rx) =
+            mpsc::channel::<DecodeResult>(get_codec_channel_capacity());
+
+        let decode_task = tokio::task::spawn_blocking(move || {
+            let mut decoder = PacketDecoder::default();
+            while let Some((data, metadata)) = decode_rx.blocking_recv() {
+                let decode_start_time = Instant::now();
+                let result = decoder.decode(&data, metadata);
+                decode_duration_histogram.record(decode_start_time.elapsed().as_secs_f64(), &[]);
+                let failed = result.is_err();
+                if result_tx.blocking_send(result).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        // Feed input from a separate task so decode results keep draining while the decode
+        // channel is full.
+        let mut input_task = tokio::spawn(async move {
+            while let Some(packet) = input_rx.recv().await {
+                if let Packet::Binary { data, metadata, .. } = packet {
+                    if decode_tx.send((data, metadata)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        let mut input_done = false;
+
+        loop {
+            tokio::select! {
+                maybe_result = result_rx.recv() => {
+                    match maybe_result {
+                        Some(Ok(decoded)) => {
+                            packets_processed_counter.add(1, &[KeyValue::new("status", "ok")]);
+                            stats_tracker.received();
+
+                            if let Some(output_frame) = decoded {
+                                if context
+                                    .output_sender
+                                    .send("out", Packet::Audio(output_frame))
+                                    .await
+                                    .is_err()
+                                {
+                                    tracing::debug!("Output channel closed, stopping node");
+                                    break;
+                                }
+                                stats_tracker.sent();
+                            }
+                            stats_tracker.maybe_send();
+                        }
+                        Some(Err(e)) => {
+                            packets_processed_counter.add(1, &[KeyValue::new("status", "error")]);
+                            stats_tracker.received();
+                            stats_tracker.errored();
+                            stats_tracker.force_send();
+                            input_task.abort();
+                            let err_msg = format!("Vorbis decode error: {e}");
+                            state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                            return Err(StreamKitError::Runtime(err_msg));
+                        }
+                        None => {
+                            // Result channel closed, blocking task is done
+                            break;
+                        }
+                    }
+                }
+                Some(control_msg) = context.control_rx.recv() => {
+                    if matches!(control_msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                        tracing::info!("VorbisDecoderNode received shutdown signal");
+                        input_task.abort();
+                        break;
+                    }
+                }
+                _ = &mut input_task, if !input_done => {
+                    // Input finished; keep draining until the decoder closes the result channel.
+                    input_done = true;
+                }
+            }
+        }
+
+        let _ = decode_task.await;
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+
+        tracing::info!("VorbisDecoderNode finished");
+        Ok(())
+    }
+}
+
+/// A decoded frame, or `None` for packets without audio (headers, the first audio packet).
+type DecodeResult = Result<Option<AudioFrame>, String>;
+
+/// Header type of a Vorbis header packet, or `None` for audio packets.
+fn header_type(data: &[u8]) -> Option<u8> {
+    // Header packets have an odd type byte followed by the codec name.
+    match data {
+        [kind, name @ ..] if kind & 1 == 1 && name.starts_with(b"vorbis") => Some(*kind),
+        _ => None,
+    }
+}
+
+/// Vorbis decoder state across the packets of one stream.
+#[derive(Default)]
+struct PacketDecoder {
+    identification: Option<Vec<u8>>,
+    decoder: Option<Box<dyn Decoder>>,
+    sample_rate: u32,
+    channels: u16,
+    sample_buf: Option<SampleBuffer<f32>>,
+}
+
+impl PacketDecoder {
+    fn decode(&mut self, data: &[u8], metadata: Option<PacketMetadata>) -> DecodeResult {
+        match header_type(data) {
+            Some(IDENTIFICATION_HEADER) => {
+                if data.len() < IDENTIFICATION_LEN {
+                    return Err("Truncated Vorbis identification header".to_string());
+                }
+                self.channels = u16::from(data[11]);
+                self.sample_rate = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+                self.identification = Some(data[..IDENTIFICATION_LEN].to_vec());
+                Ok(None)
+            },
+            Some(COMMENT_HEADER) => Ok(None),
+            Some(SETUP_HEADER) => {
+                let identification = self.identification.as_ref().ok_or_else(|| {
+                    "Vorbis setup header before the identification header".to_string()
+                })?;
+                let mut params = CodecParameters::new();
+                params
+                    .for_codec(CODEC_TYPE_VORBIS)
+                    .with_sample_rate(self.sample_rate)
+                    .with_extra_data([identification.as_slice(), data].concat().into_boxed_slice());
+                let decoder = symphonia::default::get_codecs()
+                    .make(&params, &DecoderOptions::default())
+                    .map_err(|e| format!("Failed to create Vorbis decoder: {e}"))?;
+                tracing::info!(
+                    "Detected Vorbis audio: {} Hz, {} channels",
+                    self.sample_rate,
+                    self.channels
+                );
+                self.decoder = Some(decoder);
+                self.sample_buf = None;
+                Ok(None)
+            },
+            Some(other) => Err(format!("Unknown Vorbis header packet type {other}")),
+            // Empty packets carry no audio; symphonia reports them as an I/O error.
+            None if data.is_empty() => Ok(None),
+            None => self.decode_audio(data, metadata),
+        }
+    }
+
+    fn decode_audio(&mut self, data: &[u8], metadata: Option<PacketMetadata>) -> DecodeResult {
+        let decoder = self
+            .decoder
+            .as_mut()
+            .ok_or_else(|| "Vorbis audio packet before the setup header".to_string())?;
+        let audio_buf = match decoder
+            .decode(&symphonia::core::formats::Packet::new_from_slice(0, 0, 0, data))
+        {
+            Ok(audio_buf) => audio_buf,
+            Err(Error::DecodeError(err)) => {
+                // A corrupt packet is dropped; the next one decodes independently.
+                tracing::warn!("Vorbis decode error (continuing): {}", err);
+                return Ok(None);
+            },
+            Err(e) => return Err(format!("Failed to decode Vorbis packet: {e}")),
+        };
+        let buf = self.sample_buf.get_or_insert_with(|| {
+            SampleBuffer::<f32>::new(audio_buf.capacity() as u64, *audio_buf.spec())
+        });
+        buf.copy_interleaved_ref(audio_buf);
+
+        let channels = usize::from(self.channels.max(1));
+        let mut len = buf.samples().len();
+        // Packet durations end the stream short of the last block.
+        if let Some(duration_us) = metadata.as_ref().and_then(|m| m.duration_us) {
+            let frames =
+                (u128::from(duration_us) * u128::from(self.sample_rate) + 500_000) / 1_000_000;
+            len = len.min(usize::try_from(frames).unwrap_or(usize::MAX).saturating_mul(channels));
+        }
+        if len == 0 {
+            return Ok(None);
+        }
+        Ok(Some(AudioFrame::with_metadata(
+            self.sample_rate,
+            self.channels,
+            buf.samples()[..len].to_vec(),
+            metadata,
+        )))
+    }
+}
+
+use schemars::schema_for;
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the Vorbis decoder node.
+///
+/// # Panics
+///
+/// Panics if the default Vorbis decoder cannot be created (should never happen)
+/// or if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+pub fn register_vorbis_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "symphonia")]
+    {
+        let default_decoder = VorbisDecoderNode::new(VorbisDecoderConfig::default())
+            .expect("default Vorbis decoder config should be valid");
+        registry.register_static_with_description(
+            "audio::vorbis::decoder",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(VorbisDecoderNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(VorbisDecoderConfig))
+                .expect("VorbisDecoderConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_decoder.input_pins(),
+                outputs: default_decoder.output_pins(),
+            },
+            vec!["audio".to_string(), "codecs".to_string(), "vorbis".to_string()],
+            false,
+            "Decodes Vorbis packets to raw PCM samples. Takes one packet per input, headers \
+             first, as the Ogg demuxer (codec vorbis) and the Vorbis encoder produce them, and \
+             outputs f32 audio at the stream's rate and channel count with the packet timing.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+mod tests {
+    use super::*;
+    use crate::audio::codecs::vorbis_encoder::{VorbisEncoderConfig, VorbisEncoderNode};
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_binary_packet, create_test_context,
+    };
+    use std::collections::HashMap;
+
+    async fn run_node<N: ProcessorNode + 'static>(
+        node: N,
+        packets: Vec<Packet>,
+    ) -> Result<Vec<Packet>, StreamKitError> {
+        let (input_tx, input_rx) = mpsc::channel(packets.len().max(1));
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        for packet in packets {
+            input_tx.send(packet).await.unwrap();
+        }
+        drop(input_tx);
+        handle.await.unwrap()?;
+        Ok(mock_sender.get_packets_for_pin("out").await)
+    }
+
+    #[tokio::test]
+    async fn test_vorbis_decode() {
+        // 0.9 s of a 440 Hz tone, which ends partway into a block
+        let samples: Vec<f32> = (0..43_200)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48_000.0).sin() * 0.5)
+            .collect();
+        let frames = samples
+            .chunks(960)
+            .map(|chunk| Packet::Audio(AudioFrame::new(48_000, 1, chunk.to_vec())))
+            .collect();
+        let encoder = VorbisEncoderNode::new(VorbisEncoderConfig::default()).unwrap();
+        let packets = run_node(encoder, frames).await.unwrap();
+
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let node = VorbisDecoderNode::new(VorbisDecoderConfig::default()).unwrap();
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for packet in packets {
+            input_tx.send(packet).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let mut decoded = Vec::new();
+        let mut next_us = 0;
+        for packet in mock_sender.get_packets_for_pin("out").await {
+            let Packet::Audio(frame) = packet else { panic!("expected audio output") };
+            assert_eq!((frame.sample_rate, frame.channels), (48_000, 1));
+            // One frame per packet, contiguous in time
+            let metadata = frame.metadata.clone().unwrap();
+            assert_eq!(metadata.timestamp_us, Some(next_us));
+            next_us += metadata.duration_us.unwrap();
+            decoded.extend_from_slice(frame.samples());
+        }
+        assert_eq!(decoded.len(), samples.len());
+        let noise: f32 = samples.iter().zip(&decoded).map(|(a, b)| (a - b) * (a - b)).sum();
+        let signal: f32 = samples.iter().map(|a| a * a).sum();
+        assert!(signal / noise > 100.0, "SNR {:.1} dB", 10.0 * (signal / noise).log10());
+    }
+
+    #[tokio::test]
+    async fn test_audio_before_headers_fails() {
+        let node = VorbisDecoderNode::new(VorbisDecoderConfig::default()).unwrap();
+        let result = run_node(node, vec![create_test_binary_packet(vec![0x06])]).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,1348 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Vorbis encoder.
+//!
+//! A small pure-Rust encoder that produces Vorbis I packets any Vorbis decoder accepts. It
+//! uses long blocks only (2048 samples), one floor 1 curve and one residue 1 setup with fixed
+//! codebooks, and codes channels independently. The floor follows the spectral envelope, and
+//! `quality` sets how finely the residue is quantized against it; there is no
+//! psychoacoustic model or bitrate target. Like the AAC encoder, it exists to get audio into
+//! a format without an external tool, not to compete with libvorbis per bit.
+//!
+//! The node emits the three header packets (identification, comment, setup) first, without
+//! timing metadata, then one audio packet per 1024 input samples. An audio packet's timing
+//! is the span of output the decoder completes with it: the first packet only primes the
+//! decoder, and the last one is cut to the input length, so containers can derive granule
+//! positions from it.
+//!
+//! The floor 1 dB table is the one from the Vorbis I specification, as published in
+//! symphonia's Vorbis decoder.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use streamkit_core::control::NodeControlMessage;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{AudioFormat, Packet, PacketMetadata, PacketType, SampleFormat};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, OutputPin, OutputSender, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+
+use super::mdct::Mdct;
+
+/// Long block size; blocks overlap by half, so each packet completes this many samples.
+const HALF_BLOCK: usize = 1024;
+
+/// `blocksize_0` and `blocksize_1` exponents announced in the identification header. Short
+/// blocks are declared, as the format requires, but never used.
+const SHORT_BLOCK_EXP: u8 = 8;
+const LONG_BLOCK_EXP: u8 = 11;
+
+/// Floor 1 posts between 0 and `HALF_BLOCK`, in seven partitions of four.
+const FLOOR_POSTS: [u32; 28] = [
+    2, 4, 6, 8, 12, 16, 20, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    448, 512, 640, 768, 896,
+];
+const FLOOR_PARTITION_DIM: usize = 4;
+const FLOOR_MULTIPLIER: i32 = 2;
+/// Post amplitude range for a multiplier of 2.
+const FLOOR_RANGE: i32 = 128;
+/// Bits of the two end posts, `ilog(FLOOR_RANGE - 1)`.
+const FLOOR_END_BITS: u32 = 7;
+const FLOOR_RANGE_BITS: u32 = 10;
+
+/// Residue partition size and the classword width (partitions per classbook entry).
+const PARTITION_LEN: usize = 32;
+const PARTITIONS_PER_CLASSWORD: usize = 4;
+
+/// Residue classes: silent, fine values only, and coarse plus fine values.
+const CLASS_SILENT: u8 = 0;
+const CLASS_FINE: u8 = 1;
+const CLASS_COARSE: u8 = 2;
+const CLASSES: u8 = 3;
+
+/// Largest residue magnitude the fine book carries, and the spacing of the coarse book.
+const FINE_MAX: i32 = 4;
+const COARSE_STEP: i32 = 9;
+const COARSE_MAX: i32 = 8;
+/// Largest residue magnitude the two passes carry together.
+const RESIDUE_MAX: i32 = COARSE_STEP * COARSE_MAX + FINE_MAX;
+
+/// Codebook numbers. Residue books can't be book 0, so the floor book comes first.
+const FLOOR_BOOK: u32 = 0;
+const CLASS_BOOK: u32 = 1;
+const FINE_BOOK: u32 = 2;
+const COARSE_BOOK: u32 = 3;
+
+/// Floor amplitudes below this are not worth coding (about -100 dBFS per bin).
+const MIN_FLOOR: f32 = 1e-5;
+
+/// Quality the node uses unless configured otherwise.
+pub const DEFAULT_QUALITY: f32 = 5.0;
+
+/// Vendor string of the comment header.
+const VENDOR: &str = "streamkit";
+
+/// As defined in section 10.1 of the Vorbis I specification.
+#[allow(clippy::unreadable_literal, clippy::excessive_precision)]
+#[rustfmt::skip]
+const FLOOR1_INVERSE_DB_TABLE: [f32; 256] = [
+    1.0649863e-07, 1.1341951e-07, 1.2079015e-07, 1.2863978e-07,
+    1.3699951e-07, 1.4590251e-07, 1.5538408e-07, 1.6548181e-07,
+    1.7623575e-07, 1.8768855e-07, 1.9988561e-07, 2.1287530e-07,
+    2.2670913e-07, 2.4144197e-07, 2.5713223e-07, 2.7384213e-07,
+    2.9163793e-07, 3.1059021e-07, 3.3077411e-07, 3.5226968e-07,
+    3.7516214e-07, 3.9954229e-07, 4.2550680e-07, 4.5315863e-07,
+    4.8260743e-07, 5.1396998e-07, 5.4737065e-07, 5.8294187e-07,
+    6.2082472e-07, 6.6116941e-07, 7.0413592e-07, 7.4989464e-07,
+    7.9862701e-07, 8.5052630e-07, 9.0579828e-07, 9.6466216e-07,
+    1.0273513e-06, 1.0941144e-06, 1.1652161e-06, 1.2409384e-06,
+    1.3215816e-06, 1.4074654e-06, 1.4989305e-06, 1.5963394e-06,
+    1.7000785e-06, 1.8105592e-06, 1.9282195e-06, 2.0535261e-06,
+    2.1869758e-06, 2.3290978e-06, 2.4804557e-06, 2.6416497e-06,
+    2.8133190e-06, 2.9961443e-06, 3.1908506e-06, 3.3982101e-06,
+    3.6190449e-06, 3.8542308e-06, 4.1047004e-06, 4.3714470e-06,
+    4.6555282e-06, 4.9580707e-06, 5.2802740e-06, 5.6234160e-06,
+    5.9888572e-06, 6.3780469e-06, 6.7925283e-06, 7.2339451e-06,
+    7.7040476e-06, 8.2047000e-06, 8.7378876e-06, 9.3057248e-06,
+    9.9104632e-06, 1.0554501e-05, 1.1240392e-05, 1.1970856e-05,
+    1.2748789e-05, 1.3577278e-05, 1.4459606e-05, 1.5399272e-05,
+    1.6400004e-05, 1.7465768e-05, 1.8600792e-05, 1.9809576e-05,
+    2.1096914e-05, 2.2467911e-05, 2.3928002e-05, 2.5482978e-05,
+    2.7139006e-05, 2.8902651e-05, 3.0780908e-05, 3.2781225e-05,
+    3.4911534e-05, 3.7180282e-05, 3.9596466e-05, 4.2169667e-05,
+    4.4910090e-05, 4.7828601e-05, 5.0936773e-05, 5.4246931e-05,
+    5.7772202e-05, 6.1526565e-05, 6.5524908e-05, 6.9783085e-05,
+    7.4317983e-05, 7.9147585e-05, 8.4291040e-05, 8.9768747e-05,
+    9.5602426e-05, 0.00010181521, 0.00010843174, 0.00011547824,
+    0.00012298267, 0.00013097477, 0.00013948625, 0.00014855085,
+    0.00015820453, 0.00016848555, 0.00017943469, 0.00019109536,
+    0.00020351382, 0.00021673929, 0.00023082423, 0.00024582449,
+    0.00026179955, 0.00027881276, 0.00029693158, 0.00031622787,
+    0.00033677814, 0.00035866388, 0.00038197188, 0.00040679456,
+    0.00043323036, 0.00046138411, 0.00049136745, 0.00052329927,
+    0.00055730621, 0.00059352311, 0.00063209358, 0.00067317058,
+    0.00071691700, 0.00076350630, 0.00081312324, 0.00086596457,
+    0.00092223983, 0.00098217216, 0.0010459992,  0.0011139742,
+    0.0011863665,  0.0012634633,  0.0013455702,  0.0014330129,
+    0.0015261382,  0.0016253153,  0.0017309374,  0.0018434235,
+    0.0019632195,  0.0020908006,  0.0022266726,  0.0023713743,
+    0.0025254795,  0.0026895994,  0.0028643847,  0.0030505286,
+    0.0032487691,  0.0034598925,  0.0036847358,  0.0039241906,
+    0.0041792066,  0.0044507950,  0.0047400328,  0.0050480668,
+    0.0053761186,  0.0057254891,  0.0060975636,  0.0064938176,
+    0.0069158225,  0.0073652516,  0.0078438871,  0.0083536271,
+    0.0088964928,  0.009474637,   0.010090352,   0.010746080,
+    0.011444421,   0.012188144,   0.012980198,   0.013823725,
+    0.014722068,   0.015678791,   0.016697687,   0.017782797,
+    0.018938423,   0.020169149,   0.021479854,   0.022875735,
+    0.024362330,   0.025945531,   0.027631618,   0.029427276,
+    0.031339626,   0.033376252,   0.035545228,   0.037855157,
+    0.040315199,   0.042935108,   0.045725273,   0.048696758,
+    0.051861348,   0.055231591,   0.058820850,   0.062643361,
+    0.066714279,   0.071049749,   0.075666962,   0.080584227,
+    0.085821044,   0.091398179,   0.097337747,   0.10366330,
+    0.11039993,    0.11757434,    0.12521498,    0.13335215,
+    0.14201813,    0.15124727,    0.16107617,    0.17154380,
+    0.18269168,    0.19456402,    0.20720788,    0.22067342,
+    0.23501402,    0.25028656,    0.26655159,    0.28387361,
+    0.30232132,    0.32196786,    0.34289114,    0.36517414,
+    0.38890521,    0.41417847,    0.44109412,    0.46975890,
+    0.50028648,    0.53279791,    0.56742212,    0.60429640,
+    0.64356699,    0.68538959,    0.72993007,    0.77736504,
+    0.82788260,    0.88168307,    0.9389798,     1.0,
+];
+
+/// LSB-first bit packer, the bit order of Vorbis packets.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        debug_assert!(bits <= 32);
+        self.acc |= (u64::from(value) & ((1u64 << bits) - 1)) << self.bits;
+        self.bits += bits;
+        while self.bits >= 8 {
+            #[allow(clippy::cast_possible_truncation)] // Low byte
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write(u32::from(byte), 8);
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            #[allow(clippy::cast_possible_truncation)] // Low byte
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Huffman code lengths for entries of the given relative frequencies.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn huffman_lengths(weights: &[f64]) -> Vec<u8> {
+    let max = weights.iter().copied().fold(f64::MIN_POSITIVE, f64::max);
+    // Keeping the weights within 2^16 of each other keeps the codes far below 32 bits.
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, &w)| Reverse((((w / max) * 65536.0).max(1.0) as u64, i)))
+        .collect();
+    let mut parent = vec![usize::MAX; 2 * weights.len()];
+    let mut next = weights.len();
+    while let (Some(Reverse((w1, a))), Some(Reverse((w2, b)))) = (heap.pop(), heap.pop()) {
+        parent[a] = next;
+        parent[b] = next;
+        heap.push(Reverse((w1 + w2, next)));
+        next += 1;
+    }
+    (0..weights.len())
+        .map(|leaf| {
+            let (mut node, mut depth) = (leaf, 0u8);
+            while parent[node] != usize::MAX {
+                node = parent[node];
+                depth += 1;
+            }
+            depth
+        })
+        .collect()
+}
+
+/// Codewords a decoder assigns to entries of the given lengths (section 3.2.1 of the
+/// specification: each entry takes the lowest free codeword of its length, in entry order).
+fn synthesize_codewords(lengths: &[u8]) -> Vec<u32> {
+    let mut marker = [0u32; 33];
+    lengths
+        .iter()
+        .map(|&len| {
+            let len = usize::from(len);
+            let entry = marker[len];
+            // Step past the entry, carrying into shorter lengths when a branch fills up.
+            for j in (1..=len).rev() {
+                if marker[j] & 1 == 1 {
+                    marker[j] = if j == 1 { marker[1] + 1 } else { marker[j - 1] << 1 };
+                    break;
+                }
+                marker[j] += 1;
+            }
+            // Longer codewords hung off the entry just taken move to the next free branch.
+            let mut taken = entry;
+            for j in len + 1..33 {
+                if marker[j] >> 1 != taken {
+                    break;
+                }
+                taken = marker[j];
+                marker[j] = marker[j - 1] << 1;
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Packs an integer as a Vorbis float32: `mantissa * 2^(exponent - 788)`.
+#[allow(clippy::cast_sign_loss)]
+const fn float32_pack(value: i32) -> u32 {
+    let sign = if value < 0 { 1 << 31 } else { 0 };
+    sign | (788 << 21) | value.unsigned_abs()
+}
+
+/// A lookup type 1 vector table: `min + delta * m` for multiplicands `0..values`.
+struct Lookup {
+    min: i32,
+    delta: i32,
+    values: u32,
+    value_bits: u32,
+}
+
+struct Codebook {
+    dimensions: u16,
+    lengths: Vec<u8>,
+    codewords: Vec<u32>,
+    lookup: Option<Lookup>,
+}
+
+impl Codebook {
+    fn new(dimensions: u16, weights: &[f64], lookup: Option<Lookup>) -> Self {
+        let lengths = huffman_lengths(weights);
+        let codewords = synthesize_codewords(&lengths);
+        Self { dimensions, lengths, codewords, lookup }
+    }
+
+    /// A book of `dimensions`-value vectors over `values` multiplicands each, with entry
+    /// frequencies the product of `weight` over the vector's multiplicands.
+    #[allow(clippy::cast_possible_truncation)]
+    fn vq(
+        dimensions: u16,
+        values: u32,
+        weight: impl Fn(u32) -> f64,
+        lookup: Option<Lookup>,
+    ) -> Self {
+        let entries = values.pow(u32::from(dimensions));
+        let weights: Vec<f64> = (0..entries)
+            .map(|entry| {
+                (0..dimensions).map(|d| weight(entry / values.pow(u32::from(d)) % values)).product()
+            })
+            .collect();
+        Self::new(dimensions, &weights, lookup)
+    }
+
+    fn write_entry(&self, w: &mut BitWriter, entry: usize) {
+        let len = u32::from(self.lengths[entry]);
+        // Codewords are read MSB first from the LSB-first stream.
+        w.write(self.codewords[entry].reverse_bits() >> (32 - len), len);
+    }
+
+    /// Writes the book's setup header description.
+    fn write_setup(&self, w: &mut BitWriter) {
+        w.write(0x56_43_42, 24);
+        w.write(u32::from(self.dimensions), 16);
+        #[allow(clippy::cast_possible_truncation)] // At most a few hundred entries
+        w.write(self.lengths.len() as u32, 24);
+        // Not length-ordered, not sparse
+        w.write(0, 1);
+        w.write(0, 1);
+        for &len in &self.lengths {
+            w.write(u32::from(len) - 1, 5);
+        }
+        match &self.lookup {
+            None => w.write(0, 4),
+            Some(lookup) => {
+                w.write(1, 4);
+                w.write(float32_pack(lookup.min), 32);
+                w.write(float32_pack(lookup.delta), 32);
+                w.write(lookup.value_bits - 1, 4);
+                w.write(0, 1); // sequence_p
+                for m in 0..lookup.values {
+                    w.write(m, lookup.value_bits);
+                }
+            },
+        }
+    }
+}
+
+/// The encoder's four codebooks, numbered as in the setup header.
+struct Codebooks([Codebook; 4]);
+
+impl Codebooks {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_wrap,
+        clippy::cast_possible_truncation
+    )]
+    fn new() -> Self {
+        let laplace =
+            |base: f64, center: u32| move |m: u32| base.powi((m as i32 - center as i32).abs());
+        let fine_values = 2 * FINE_MAX.unsigned_abs() + 1;
+        let coarse_values = 2 * COARSE_MAX.unsigned_abs() + 1;
+        Self([
+            // Floor post values: small corrections are the common case.
+            Codebook::new(
+                1,
+                &(0..FLOOR_RANGE).map(|v| 1.0 / f64::from(v + 1).powi(2)).collect::<Vec<_>>(),
+                None,
+            ),
+            // Classwords of four partitions
+            Codebook::vq(
+                PARTITIONS_PER_CLASSWORD as u16,
+                u32::from(CLASSES),
+                |class| [0.4, 0.35, 0.25][class as usize],
+                None,
+            ),
+            Codebook::vq(
+                2,
+                fine_values,
+                laplace(0.5, FINE_MAX.unsigned_abs()),
+                Some(Lookup { min: -FINE_MAX, delta: 1, values: fine_values, value_bits: 4 }),
+            ),
+            Codebook::vq(
+                2,
+                coarse_values,
+                laplace(0.6, COARSE_MAX.unsigned_abs()),
+                Some(Lookup {
+                    min: -COARSE_STEP * COARSE_MAX,
+                    delta: COARSE_STEP,
+                    values: coarse_values,
+                    value_bits: 5,
+                }),
+            ),
+        ])
+    }
+
+    const fn book(&self, number: u32) -> &Codebook {
+        &self.0[number as usize]
+    }
+}
+
+/// Floor 1 prediction of the post at `x` from the posts at `x0` and `x1` (section 9.2.6).
+#[allow(clippy::cast_possible_wrap)]
+const fn render_point(x0: u32, y0: i32, x1: u32, y1: i32, x: u32) -> i32 {
+    let dy = y1 - y0;
+    let off = (dy.unsigned_abs() * (x - x0) / (x1 - x0)) as i32;
+    if dy < 0 {
+        y0 - off
+    } else {
+        y0 + off
+    }
+}
+
+/// Floor 1 line between two posts, as floor amplitudes over `[x0, x1)` (section 9.2.7).
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn render_line(x0: u32, y0: i32, x1: u32, y1: i32, floor: &mut [f32]) {
+    let dy = y1 - y0;
+    let adx = (x1 - x0) as i32;
+    let base = dy / adx;
+    let sy = if dy < 0 { base - 1 } else { base + 1 };
+    let ady = dy.abs() - base.abs() * adx;
+    let mut y = y0;
+    let mut err = 0;
+    floor[x0 as usize] = FLOOR1_INVERSE_DB_TABLE[y as usize];
+    for v in &mut floor[x0 as usize + 1..x1 as usize] {
+        err += ady;
+        if err >= adx {
+            err -= adx;
+            y += sy;
+        } else {
+            y += base;
+        }
+        *v = FLOOR1_INVERSE_DB_TABLE[y as usize];
+    }
+}
+
+/// The floor 1 configuration, with the post order and neighbours a decoder derives from it.
+struct Floor {
+    /// Post positions in bitstream order: the two ends, then `FLOOR_POSTS`
+    x_list: Vec<u32>,
+    /// Post indices by position
+    sort_order: Vec<usize>,
+    /// Closest earlier posts below and above each post
+    neighbors: Vec<(usize, usize)>,
+}
+
+impl Floor {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new() -> Self {
+        let mut x_list = vec![0, HALF_BLOCK as u32];
+        x_list.extend_from_slice(&FLOOR_POSTS);
+        let mut sort_order: Vec<usize> = (0..x_list.len()).collect();
+        sort_order.sort_by_key(|&i| x_list[i]);
+        let neighbors = (0..x_list.len())
+            .map(|i| {
+                let x = x_list[i];
+                let earlier = || x_list[..i].iter().enumerate();
+                let low = earlier().filter(|&(_, &v)| v < x).max_by_key(|&(_, &v)| v);
+                let high = earlier().filter(|&(_, &v)| v > x).min_by_key(|&(_, &v)| v);
+                (low.map_or(0, |(n, _)| n), high.map_or(0, |(n, _)| n))
+            })
+            .collect();
+        Self { x_list, sort_order, neighbors }
+    }
+
+    fn write_setup(&self, w: &mut BitWriter) {
+        w.write(1, 16); // Floor type 1
+        let partitions = FLOOR_POSTS.len() / FLOOR_PARTITION_DIM;
+        #[allow(clippy::cast_possible_truncation)]
+        w.write(partitions as u32, 5);
+        for _ in 0..partitions {
+            w.write(0, 4); // All partitions use class 0.
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        w.write(FLOOR_PARTITION_DIM as u32 - 1, 3);
+        w.write(0, 2); // No subclasses, so no master book
+        w.write(FLOOR_BOOK + 1, 8);
+        #[allow(clippy::cast_sign_loss)]
+        w.write(FLOOR_MULTIPLIER as u32 - 1, 2);
+        w.write(FLOOR_RANGE_BITS, 4);
+        for &x in &self.x_list[2..] {
+            w.write(x, FLOOR_RANGE_BITS);
+        }
+    }
+
+    /// Picks the post amplitudes for `spectrum`, renders the curve a decoder will build from
+    /// them into `curve` and returns the values to code.
+    fn fit(&self, spectrum: &[f32], resolution: f32, curve: &mut [f32]) -> Vec<u32> {
+        let posts = self.x_list.len();
+        // Each post covers the bins out to its neighbours, so every bin between two posts
+        // bounds both.
+        let mut target = vec![0i32; posts];
+        for (s, &i) in self.sort_order.iter().enumerate() {
+            let lo = if s == 0 { 0 } else { self.x_list[self.sort_order[s - 1]] as usize };
+            let hi = self.sort_order.get(s + 1).map_or(HALF_BLOCK, |&n| self.x_list[n] as usize);
+            let band = &spectrum[lo..hi.max(lo + 1).min(HALF_BLOCK)];
+            let (energy, peak) =
+                band.iter().fold((0.0f32, 0.0f32), |(e, p), &v| (e + v * v, p.max(v.abs())));
+            #[allow(clippy::cast_precision_loss)]
+            let rms = (energy / band.len() as f32).sqrt();
+            // The peak bound keeps residues within what the books carry.
+            let amplitude = (rms / resolution).max(peak / 60.0).max(MIN_FLOOR);
+            target[i] = post_amplitude(amplitude);
+        }
+
+        let mut values = vec![0u32; posts];
+        let mut final_y = vec![0i32; posts];
+        let mut used = vec![false; posts];
+        for i in 0..2 {
+            final_y[i] = target[i];
+            #[allow(clippy::cast_sign_loss)]
+            {
+                values[i] = target[i] as u32;
+            }
+            used[i] = true;
+        }
+        for i in 2..posts {
+            let (low, high) = self.neighbors[i];
+            let predicted = render_point(
+                self.x_list[low],
+                final_y[low],
+                self.x_list[high],
+                final_y[high],
+                self.x_list[i],
+            );
+            let y = target[i];
+            let (high_room, low_room) = (FLOOR_RANGE - predicted, predicted);
+            let room = 2 * high_room.min(low_room);
+            let delta = y - predicted;
+            let value = if delta == 0 {
+                0
+            } else {
+                let near = if delta > 0 { 2 * delta } else { -2 * delta - 1 };
+                if near < room {
+                    near
+                } else if high_room > low_room {
+                    y
+                } else {
+                    FLOOR_RANGE - 1 - y
+                }
+            };
+            #[allow(clippy::cast_sign_loss)]
+            {
+                values[i] = value as u32;
+            }
+            final_y[i] = y;
+            if value == 0 {
+                used[i] = false;
+            } else {
+                used[low] = true;
+                used[high] = true;
+                used[i] = true;
+            }
+        }
+
+        // Curve synthesis over the posts still in use
+        let (mut lx, mut ly) = (0, final_y[self.sort_order[0]] * FLOOR_MULTIPLIER);
+        for &i in &self.sort_order[1..] {
+            if used[i] {
+                let (hx, hy) = (self.x_list[i], final_y[i] * FLOOR_MULTIPLIER);
+                render_line(lx, ly, hx, hy, curve);
+                (lx, ly) = (hx, hy);
+            }
+        }
+        values
+    }
+}
+
+/// Closest post amplitude (table index over the multiplier) to a linear amplitude.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn post_amplitude(amplitude: f32) -> i32 {
+    let at = |y: usize| FLOOR1_INVERSE_DB_TABLE[y * FLOOR_MULTIPLIER as usize];
+    let above = (0..FLOOR_RANGE as usize).find(|&y| at(y) >= amplitude);
+    match above {
+        None => FLOOR_RANGE - 1,
+        Some(0) => 0,
+        // Nearest on a log scale
+        Some(y) if at(y) / amplitude > amplitude / at(y - 1) => y as i32 - 1,
+        Some(y) => y as i32,
+    }
+}
+
+/// Residue class of a partition from its largest magnitude.
+const fn classify(peak: i32) -> u8 {
+    match peak {
+        0 => CLASS_SILENT,
+        1..=FINE_MAX => CLASS_FINE,
+        _ => CLASS_COARSE,
+    }
+}
+
+/// Fine book entry of a pair of values within `±FINE_MAX`.
+#[allow(clippy::cast_sign_loss)]
+const fn fine_entry(a: i32, b: i32) -> usize {
+    ((a + FINE_MAX) + (2 * FINE_MAX + 1) * (b + FINE_MAX)) as usize
+}
+
+/// Splits a residue into a coarse step count and a fine remainder within `±FINE_MAX`.
+const fn split_residue(r: i32) -> (i32, i32) {
+    let coarse = (r + FINE_MAX).div_euclid(COARSE_STEP);
+    (coarse, r - COARSE_STEP * coarse)
+}
+
+/// One channel of an audio packet.
+struct ChannelBlock {
+    /// Floor values, or `None` when every residue is zero
+    floor: Option<Vec<u32>>,
+    residue: Vec<i32>,
+    classes: Vec<u8>,
+}
+
+/// Builds the three Vorbis header packets.
+struct Headers {
+    identification: Vec<u8>,
+    comment: Vec<u8>,
+    setup: Vec<u8>,
+}
+
+struct VorbisEncoder {
+    channels: usize,
+    sample_rate: u32,
+    resolution: f32,
+    /// Bins below the configured bandwidth
+    max_bin: usize,
+    books: Codebooks,
+    floor: Floor,
+    mdct: Mdct,
+    /// Last half block of input per channel, the first half of the next MDCT block
+    history: Vec<Vec<f32>>,
+    block: Vec<f32>,
+    spectrum: Vec<f32>,
+    curve: Vec<f32>,
+}
+
+impl VorbisEncoder {
+    #[allow(clippy::cast_precision_loss)] // HALF_BLOCK is exact in f32
+    fn new(
+        sample_rate: u32,
+        channels: u16,
+        quality: f32,
+        bandwidth_hz: Option<u32>,
+    ) -> Result<Self, String> {
+        if !(1..=2).contains(&channels) {
+            return Err(format!(
+                "Vorbis encoder supports mono and stereo, got {channels} channels"
+            ));
+        }
+        if !(8_000..=192_000).contains(&sample_rate) {
+            return Err(format!("Vorbis encoder supports 8-192 kHz, got {sample_rate} Hz"));
+        }
+        let bandwidth =
+            bandwidth_hz.unwrap_or_else(|| auto_bandwidth(quality)).min(sample_rate / 2);
+        // Bin k is centered on (k + 1/2) * sample_rate / 2048 Hz.
+        let max_bin = (0..HALF_BLOCK)
+            .take_while(|&k| {
+                (2 * k as u64 + 1) * u64::from(sample_rate) < u64::from(bandwidth) * 4096
+            })
+            .count();
+        let mut encoder = Self {
+            channels: usize::from(channels),
+            sample_rate,
+            resolution: 0.0,
+            max_bin,
+            books: Codebooks::new(),
+            floor: Floor::new(),
+            // Scaled so the decoder's unscaled inverse transform restores the input level
+            mdct: Mdct::new(vorbis_window(), 2.0 / HALF_BLOCK as f32),
+            history: vec![vec![0.0; HALF_BLOCK]; usize::from(channels)],
+            block: vec![0.0; 2 * HALF_BLOCK],
+            spectrum: vec![0.0; HALF_BLOCK],
+            curve: vec![0.0; HALF_BLOCK],
+        };
+        encoder.set_quality(quality);
+        Ok(encoder)
+    }
+
+    fn set_quality(&mut self, quality: f32) {
+        // Steps of 1.5 dB: about 17 dB SNR per band at quality 0 and 41 dB at 10
+        self.resolution = 2.0f32.powf(1.0 + quality / 2.5);
+    }
+
+    fn headers(&self) -> Headers {
+        let mut identification = vec![1];
+        identification.extend_from_slice(b"vorbis");
+        identification.extend_from_slice(&0u32.to_le_bytes());
+        #[allow(clippy::cast_possible_truncation)] // Mono or stereo
+        identification.push(self.channels as u8);
+        identification.extend_from_slice(&self.sample_rate.to_le_bytes());
+        // No bitrate hints
+        identification.extend_from_slice(&[0; 12]);
+        identification.push((LONG_BLOCK_EXP << 4) | SHORT_BLOCK_EXP);
+        identification.push(1);
+
+        let mut comment = vec![3];
+        comment.extend_from_slice(b"vorbis");
+        #[allow(clippy::cast_possible_truncation)]
+        comment.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+        comment.extend_from_slice(VENDOR.as_bytes());
+        comment.extend_from_slice(&0u32.to_le_bytes());
+        comment.push(1);
+
+        let mut w = BitWriter::default();
+        w.write(5, 8);
+        w.write_bytes(b"vorbis");
+        w.write(3, 8); // Four codebooks
+        for book in &self.books.0 {
+            book.write_setup(&mut w);
+        }
+        // One placeholder time-domain transform
+        w.write(0, 6);
+        w.write(0, 16);
+        // One floor
+        w.write(0, 6);
+        self.floor.write_setup(&mut w);
+        // One residue: type 1, over the whole half block
+        w.write(0, 6);
+        w.write(1, 16);
+        w.write(0, 24);
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            w.write(HALF_BLOCK as u32, 24);
+            w.write(PARTITION_LEN as u32 - 1, 24);
+        }
+        w.write(u32::from(CLASSES) - 1, 6);
+        w.write(CLASS_BOOK, 8);
+        // Passes per class: none, fine, coarse then fine
+        for passes in [0b00, 0b01, 0b11] {
+            w.write(passes, 3);
+            w.write(0, 1);
+        }
+        w.write(FINE_BOOK, 8);
+        w.write(COARSE_BOOK, 8);
+        w.write(FINE_BOOK, 8);
+        // One mapping: a single submap, no coupling
+        w.write(0, 6);
+        w.write(0, 16);
+        w.write(0, 1);
+        w.write(0, 1);
+        w.write(0, 2);
+        w.write(0, 8);
+        w.write(0, 8); // Floor 0
+        w.write(0, 8); // Residue 0
+                       // One mode: long blocks through mapping 0
+        w.write(0, 6);
+        w.write(1, 1);
+        w.write(0, 16);
+        w.write(0, 16);
+        w.write(0, 8);
+        w.write(1, 1); // Framing
+        Headers { identification, comment, setup: w.into_bytes() }
+    }
+
+    /// Encodes one half block of interleaved samples (1024 per channel; shorter input is
+    /// padded with silence) into an audio packet.
+    fn encode_block(&mut self, samples: &[f32]) -> Vec<u8> {
+        let blocks: Vec<ChannelBlock> =
+            (0..self.channels).map(|ch| self.analyze(samples, ch)).collect();
+
+        let mut w = BitWriter::default();
+        w.write(0, 1); // Audio packet; the only mode takes no bits.
+        w.write(1, 1); // Previous and next windows are long.
+        w.write(1, 1);
+        for block in &blocks {
+            match &block.floor {
+                Some(values) => self.write_floor(&mut w, values),
+                None => w.write(0, 1),
+            }
+        }
+        self.write_residue(&mut w, &blocks);
+        w.into_bytes()
+    }
+
+    fn analyze(&mut self, samples: &[f32], ch: usize) -> ChannelBlock {
+        let (past, current) = self.block.split_at_mut(HALF_BLOCK);
+        past.copy_from_slice(&self.history[ch]);
+        current.fill(0.0);
+        for (dst, frame) in current.iter_mut().zip(samples.chunks(self.channels)) {
+            *dst = frame[ch];
+        }
+        self.history[ch].copy_from_slice(current);
+        self.mdct.forward(&self.block, &mut self.spectrum);
+        self.spectrum[self.max_bin..].fill(0.0);
+
+        let values = self.floor.fit(&self.spectrum, self.resolution, &mut self.curve);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)] // Clamped
+        let residue: Vec<i32> = self
+            .spectrum
+            .iter()
+            .zip(&self.curve)
+            .map(|(&x, &f)| (x / f).round().clamp(-RESIDUE_MAX as f32, RESIDUE_MAX as f32) as i32)
+            .collect();
+        let classes: Vec<u8> = residue
+            .chunks(PARTITION_LEN)
+            .map(|part| classify(part.iter().map(|r| r.abs()).max().unwrap_or(0)))
+            .collect();
+        let floor = classes.iter().any(|&c| c != CLASS_SILENT).then_some(values);
+        ChannelBlock { floor, residue, classes }
+    }
+
+    fn write_floor(&self, w: &mut BitWriter, values: &[u32]) {
+        w.write(1, 1); // Floor in use
+        w.write(values[0], FLOOR_END_BITS);
+        w.write(values[1], FLOOR_END_BITS);
+        for &value in &values[2..] {
+            self.books.book(FLOOR_BOOK).write_entry(w, value as usize);
+        }
+    }
+
+    /// Writes the residue of the channels with a floor, interleaved by partition as residue
+    /// type 1 decodes it.
+    fn write_residue(&self, w: &mut BitWriter, blocks: &[ChannelBlock]) {
+        let coded: Vec<&ChannelBlock> = blocks.iter().filter(|b| b.floor.is_some()).collect();
+        if coded.is_empty() {
+            return;
+        }
+        let partitions = HALF_BLOCK / PARTITION_LEN;
+        for pass in 0..2 {
+            for first in (0..partitions).step_by(PARTITIONS_PER_CLASSWORD) {
+                let group = first..first + PARTITIONS_PER_CLASSWORD;
+                if pass == 0 {
+                    for block in &coded {
+                        // The first partition is the most significant digit.
+                        let word = block.classes[group.clone()]
+                            .iter()
+                            .fold(0usize, |word, &c| word * usize::from(CLASSES) + usize::from(c));
+                        self.books.book(CLASS_BOOK).write_entry(w, word);
+                    }
+                }
+                for part in group {
+                    for block in &coded {
+                        let values =
+                            &block.residue[part * PARTITION_LEN..(part + 1) * PARTITION_LEN];
+                        self.write_partition(w, block.classes[part], pass, values);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_partition(&self, w: &mut BitWriter, class: u8, pass: usize, values: &[i32]) {
+        for pair in values.chunks_exact(2) {
+            let (a, b) = (pair[0], pair[1]);
+            match (class, pass) {
+                (CLASS_FINE, 0) => self.books.book(FINE_BOOK).write_entry(w, fine_entry(a, b)),
+                (CLASS_COARSE, 0) => {
+                    let ((ca, _), (cb, _)) = (split_residue(a), split_residue(b));
+                    #[allow(clippy::cast_sign_loss)]
+                    let entry =
+                        ((ca + COARSE_MAX) + (2 * COARSE_MAX + 1) * (cb + COARSE_MAX)) as usize;
+                    self.books.book(COARSE_BOOK).write_entry(w, entry);
+                },
+                (CLASS_COARSE, 1) => {
+                    let ((_, fa), (_, fb)) = (split_residue(a), split_residue(b));
+                    self.books.book(FINE_BOOK).write_entry(w, fine_entry(fa, fb));
+                },
+                _ => return,
+            }
+        }
+    }
+}
+
+/// Vorbis power-sine window over a long block.
+// Table setup in f64 over indices far below 2^52.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn vorbis_window() -> Vec<f32> {
+    let n = 2.0 * HALF_BLOCK as f64;
+    (0..2 * HALF_BLOCK)
+        .map(|i| {
+            let s = (std::f64::consts::PI * (i as f64 + 0.5) / n).sin();
+            (std::f64::consts::FRAC_PI_2 * s * s).sin() as f32
+        })
+        .collect()
+}
+
+/// Audio bandwidth worth spending bits on at a quality.
+fn auto_bandwidth(quality: f32) -> u32 {
+    match quality {
+        q if q >= 6.0 => 20_000,
+        q if q >= 4.0 => 17_000,
+        q if q >= 2.0 => 14_000,
+        _ => 11_000,
+    }
+}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+#[serde(default)]
+pub struct VorbisEncoderConfig {
+    /// Quality from 0 (smallest) to 10 (best). Sets how finely the spectrum is quantized
+    /// against its envelope; the bitrate follows the material.
+    #[schemars(range(min = 0.0, max = 10.0), extend("tunable" = true))]
+    pub quality: f32,
+    /// Highest frequency to encode. Defaults to a cutoff chosen from the quality; always
+    /// capped at half the sample rate.
+    pub bandwidth_hz: Option<u32>,
+}
+
+impl Default for VorbisEncoderConfig {
+    fn default() -> Self {
+        Self { quality: DEFAULT_QUALITY, bandwidth_hz: None }
+    }
+}
+
+/// Params that can be changed while the encoder runs.
+#[derive(Deserialize, Debug, Default)]
+struct VorbisEncoderUpdate {
+    quality: Option<f32>,
+}
+
+fn validate_quality(quality: f32) -> Result<(), String> {
+    if !(0.0..=10.0).contains(&quality) {
+        return Err(format!("quality must be 0-10, got {quality}"));
+    }
+    Ok(())
+}
+
+impl VorbisEncoderConfig {
+    fn validate(&self) -> Result<(), StreamKitError> {
+        validate_quality(self.quality).map_err(StreamKitError::Configuration)?;
+        if self.bandwidth_hz.is_some_and(|hz| hz < 1000) {
+            return Err(StreamKitError::Configuration(
+                "bandwidth_hz must be at least 1000".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A node that encodes raw audio frames into Vorbis packets.
+pub struct VorbisEncoderNode {
+    config: VorbisEncoderConfig,
+}
+
+impl VorbisEncoderNode {
+    /// Creates a new Vorbis encoder node.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StreamKitError::Configuration` for an out-of-range quality or bandwidth.
+    pub fn new(config: VorbisEncoderConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
+
+/// Timing of audio packet `index`, which completes output samples
+/// `[(index - 1) * 1024, index * 1024)` cut to `input_len`; the first packet completes none.
+fn packet_metadata(index: u64, input_len: u64, sample_rate: u32) -> PacketMetadata {
+    let half = HALF_BLOCK as u64;
+    let end = (index * half).min(input_len);
+    let start = (index.saturating_sub(1) * half).min(end);
+    let to_us = |samples: u64| samples * 1_000_000 / u64::from(sample_rate);
+    PacketMetadata {
+        timestamp_us: Some(to_us(start)),
+        duration_us: Some(to_us(end) - to_us(start)),
+        sequence: Some(index),
+    }
+}
+
+async fn send_vorbis(
+    output_sender: &mut OutputSender,
+    bytes: Vec<u8>,
+    metadata: Option<PacketMetadata>,
+) -> bool {
+    let packet = Packet::Binary { data: Bytes::from(bytes), content_type: None, metadata };
+    output_sender.send("out", packet).await.is_ok()
+}
+
+#[async_trait]
+impl ProcessorNode for VorbisEncoderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        // Bare Vorbis packets; the Ogg muxer labels its output audio/ogg.
+        Some("audio/vorbis".to_string())
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let mut quality = self.config.quality;
+        tracing::info!("VorbisEncoderNode starting (quality {})", quality);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut encoder: Option<VorbisEncoder> = None;
+        let mut pending: Vec<f32> = Vec::new();
+        let mut input_len = 0u64;
+        let mut next_packet = 0u64;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    let Packet::Audio(frame) = packet else { continue };
+                    stats_tracker.received();
+
+                    if encoder.is_none() {
+                        let enc = match VorbisEncoder::new(
+                            frame.sample_rate,
+                            frame.channels,
+                            quality,
+                            self.config.bandwidth_hz,
+                        ) {
+                            Ok(enc) => enc,
+                            Err(err_msg) => {
+                                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                                return Err(StreamKitError::Runtime(err_msg));
+                            },
+                        };
+                        let headers = enc.headers();
+                        for header in [headers.identification, headers.comment, headers.setup] {
+                            if !send_vorbis(&mut context.output_sender, header, None).await {
+                                state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                                return Ok(());
+                            }
+                        }
+                        encoder = Some(enc);
+                    }
+                    let Some(enc) = encoder.as_mut() else { continue };
+                    if enc.sample_rate != frame.sample_rate || enc.channels != usize::from(frame.channels) {
+                        let err_msg = format!(
+                            "Vorbis input format changed from {} Hz/{} ch to {} Hz/{} ch",
+                            enc.sample_rate, enc.channels, frame.sample_rate, frame.channels
+                        );
+                        state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                        return Err(StreamKitError::Runtime(err_msg));
+                    }
+
+                    pending.extend_from_slice(frame.samples());
+                    input_len += (frame.samples().len() / enc.channels) as u64;
+                    let block_len = HALF_BLOCK * enc.channels;
+                    while pending.len() >= block_len {
+                        let bytes = enc.encode_block(&pending[..block_len]);
+                        pending.drain(..block_len);
+                        let metadata = packet_metadata(next_packet, input_len, enc.sample_rate);
+                        if !send_vorbis(&mut context.output_sender, bytes, Some(metadata)).await {
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                        next_packet += 1;
+                        stats_tracker.sent();
+                    }
+                    stats_tracker.maybe_send();
+                }
+                Some(control_msg) = context.control_rx.recv() => {
+                    match control_msg {
+                        NodeControlMessage::Shutdown => {
+                            tracing::info!("VorbisEncoderNode received shutdown signal");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                            return Ok(());
+                        }
+                        NodeControlMessage::UpdateParams(params) => {
+                            let update = serde_json::from_value::<VorbisEncoderUpdate>(params)
+                                .map_err(|e| e.to_string())
+                                .and_then(|update| {
+                                    let q = update.quality.unwrap_or(quality);
+                                    validate_quality(q).map(|()| q)
+                                });
+                            match update {
+                                // Takes effect from the next packet
+                                Ok(q) => {
+                                    quality = q;
+                                    if let Some(enc) = encoder.as_mut() {
+                                        enc.set_quality(q);
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Rejected Vorbis encoder update: {}", e);
+                                    stats_tracker.errored();
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // The final partial block, then one of silence to complete the last overlap.
+        if let Some(enc) = encoder.as_mut() {
+            let mut tail = Vec::new();
+            if !pending.is_empty() {
+                tail.push(std::mem::take(&mut pending));
+            }
+            tail.push(Vec::new());
+            for samples in tail {
+                let bytes = enc.encode_block(&samples);
+                let metadata = packet_metadata(next_packet, input_len, enc.sample_rate);
+                if !send_vorbis(&mut context.output_sender, bytes, Some(metadata)).await {
+                    break;
+                }
+                next_packet += 1;
+                stats_tracker.sent();
+            }
+        }
+        stats_tracker.force_send();
+
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        tracing::info!("VorbisEncoderNode finished ({} packets)", next_packet);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "vorbis_encoder")]
+use schemars::schema_for;
+use streamkit_core::NodeRegistry;
+#[cfg(feature = "vorbis_encoder")]
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the Vorbis encoder node.
+///
+/// # Panics
+///
+/// Panics if the default Vorbis encoder cannot be created (should never happen)
+/// or if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+#[cfg_attr(not(feature = "vorbis_encoder"), allow(unused_variables, clippy::missing_const_for_fn))]
+pub fn register_vorbis_encoder_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "vorbis_encoder")]
+    {
+        let default_encoder = VorbisEncoderNode::new(VorbisEncoderConfig::default())
+            .expect("default Vorbis encoder config should be valid");
+        registry.register_static_with_description(
+            "audio::vorbis::encoder",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(VorbisEncoderNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(VorbisEncoderConfig))
+                .expect("VorbisEncoderConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_encoder.input_pins(),
+                outputs: default_encoder.output_pins(),
+            },
+            vec!["audio".to_string(), "codecs".to_string(), "vorbis".to_string()],
+            false,
+            "Encodes mono or stereo raw audio into Vorbis packets: the three headers first, \
+             then one packet per 1024 samples with timing metadata. Feed it to the Ogg muxer \
+             with codec vorbis. A simple encoder without a psychoacoustic model; quality \
+             (0-10) sets the quantization and is tunable while running.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation
+)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_context;
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_VORBIS};
+    use tokio::sync::mpsc;
+
+    async fn encode(config: VorbisEncoderConfig, frames: Vec<AudioFrame>) -> Vec<Packet> {
+        let (input_tx, input_rx) = mpsc::channel(frames.len().max(1));
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let node = VorbisEncoderNode::new(config).unwrap();
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        for frame in frames {
+            input_tx.send(Packet::Audio(frame)).await.unwrap();
+        }
+        drop(input_tx);
+        handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    /// Decodes encoder output with symphonia's Vorbis decoder, cutting each packet's audio to
+    /// its duration metadata.
+    fn decode(packets: &[Packet], sample_rate: u32) -> Vec<f32> {
+        let data: Vec<&[u8]> = packets
+            .iter()
+            .map(|packet| match packet {
+                Packet::Binary { data, .. } => data.as_ref(),
+                _ => panic!("expected binary output"),
+            })
+            .collect();
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_VORBIS).with_extra_data([data[0], data[2]].concat().into());
+        let mut decoder =
+            symphonia::default::get_codecs().make(&params, &DecoderOptions::default()).unwrap();
+        let mut out = Vec::new();
+        for (packet, bytes) in packets.iter().zip(&data).skip(3) {
+            let packet_in = symphonia::core::formats::Packet::new_from_slice(0, 0, 0, bytes);
+            let audio = decoder.decode(&packet_in).unwrap();
+            let channels = audio.spec().channels.count();
+            let mut buf = SampleBuffer::<f32>::new(audio.capacity() as u64, *audio.spec());
+            buf.copy_interleaved_ref(audio);
+            let Packet::Binary { metadata: Some(metadata), .. } = packet else { unreachable!() };
+            let frames =
+                (metadata.duration_us.unwrap() * u64::from(sample_rate) + 500_000) / 1_000_000;
+            let keep = (frames as usize * channels).min(buf.samples().len());
+            out.extend_from_slice(&buf.samples()[..keep]);
+        }
+        out
+    }
+
+    /// Interleaved test tones: 440 Hz on the left, 1 kHz on the right.
+    fn test_signal(sample_rate: u32, channels: u16, frames: usize) -> Vec<f32> {
+        let mut samples = Vec::with_capacity(frames * usize::from(channels));
+        for i in 0..frames {
+            let t = i as f64 / f64::from(sample_rate);
+            for ch in 0..channels {
+                let freq = if ch == 0 { 440.0 } else { 1000.0 };
+                samples.push(((2.0 * std::f64::consts::PI * freq * t).sin() * 0.5) as f32);
+            }
+        }
+        samples
+    }
+
+    /// Signal-to-noise ratio in dB of `decoded` against `reference`.
+    fn snr_db(reference: &[f32], decoded: &[f32]) -> f64 {
+        let (mut signal, mut noise) = (0.0f64, 0.0f64);
+        for (&r, &d) in reference.iter().zip(decoded) {
+            signal += f64::from(r) * f64::from(r);
+            noise += f64::from(r - d) * f64::from(r - d);
+        }
+        10.0 * (signal / noise.max(f64::MIN_POSITIVE)).log10()
+    }
+
+    fn frames(sample_rate: u32, channels: u16, samples: &[f32]) -> Vec<AudioFrame> {
+        samples
+            .chunks(960 * usize::from(channels))
+            .map(|chunk| AudioFrame::new(sample_rate, channels, chunk.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_codewords_match_specification_example() {
+        assert_eq!(synthesize_codewords(&[2, 4, 4, 4, 4, 2, 3, 3]), [0, 4, 5, 6, 7, 2, 6, 7]);
+    }
+
+    #[test]
+    fn test_codebooks_are_complete() {
+        for book in &Codebooks::new().0 {
+            assert!(book.lengths.iter().all(|&len| (1..=32).contains(&len)));
+            // Kraft sum of exactly one: every bit pattern decodes.
+            let kraft: f64 = book.lengths.iter().map(|&len| 0.5f64.powi(i32::from(len))).sum();
+            assert!((kraft - 1.0).abs() < 1e-12, "Kraft sum {kraft}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vorbis_encoder_round_trip_stereo() {
+        let samples = test_signal(48_000, 2, 48_000);
+        let packets = encode(VorbisEncoderConfig::default(), frames(48_000, 2, &samples)).await;
+
+        // Three headers, 46 full blocks and the 896-sample remainder, then a block of silence
+        assert_eq!(packets.len(), 3 + 48);
+        for (i, packet) in packets.iter().enumerate() {
+            let Packet::Binary { metadata, .. } = packet else { unreachable!() };
+            if i < 3 {
+                assert!(metadata.is_none());
+                continue;
+            }
+            let metadata = metadata.as_ref().unwrap();
+            let index = i as u64 - 3;
+            assert_eq!(metadata.sequence, Some(index));
+            let start = index.saturating_sub(1) * 1024;
+            assert_eq!(metadata.timestamp_us, Some(start * 1_000_000 / 48_000));
+        }
+
+        let decoded = decode(&packets, 48_000);
+        assert_eq!(decoded.len(), samples.len());
+        let snr = snr_db(&samples, &decoded);
+        assert!(snr > 25.0, "SNR {snr:.1} dB");
+    }
+
+    #[tokio::test]
+    async fn test_vorbis_encoder_quality_trades_size_for_snr() {
+        let samples = test_signal(44_100, 1, 44_100);
+        let mut results = Vec::new();
+        for quality in [1.0, 9.0] {
+            let config = VorbisEncoderConfig { quality, ..VorbisEncoderConfig::default() };
+            let packets = encode(config, frames(44_100, 1, &samples)).await;
+            let bytes: usize = packets
+                .iter()
+                .skip(3)
+                .map(|p| match p {
+                    Packet::Binary { data, .. } => data.len(),
+                    _ => 0,
+                })
+                .sum();
+            let decoded = decode(&packets, 44_100);
+            assert_eq!(decoded.len(), samples.len());
+            results.push((bytes, snr_db(&samples, &decoded)));
+        }
+        let ((low_bytes, low_snr), (high_bytes, high_snr)) = (results[0], results[1]);
+        assert!(low_bytes < high_bytes, "{low_bytes} vs {high_bytes} bytes");
+        assert!(low_snr + 10.0 < high_snr, "{low_snr:.1} vs {high_snr:.1} dB");
+        assert!(high_snr > 35.0, "SNR {high_snr:.1} dB");
+    }
+
+    #[tokio::test]
+    async fn test_silence_codes_unused_floors() {
+        let packets =
+            encode(VorbisEncoderConfig::default(), frames(48_000, 2, &vec![0.0; 2 * 4096])).await;
+        for packet in &packets[3..] {
+            let Packet::Binary { data, .. } = packet else { unreachable!() };
+            // Packet type, two window flags and two unused floors
+            assert_eq!(data.as_ref(), [0b0_0110]);
+        }
+        assert_eq!(decode(&packets, 48_000), vec![0.0; 2 * 4096]);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_formats_fail() {
+        for (sample_rate, channels) in [(4_000, 2), (48_000, 6)] {
+            let (input_tx, input_rx) = mpsc::channel(1);
+            let inputs = HashMap::from([("in".to_string(), input_rx)]);
+            let (context, _mock_sender, _state_rx) = create_test_context(inputs, 10);
+            let node = VorbisEncoderNode::new(VorbisEncoderConfig::default()).unwrap();
+            let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+            let samples = vec![0.0; 1024 * usize::from(channels)];
+            input_tx
+                .send(Packet::Audio(AudioFrame::new(sample_rate, channels, samples)))
+                .await
+                .unwrap();
+            assert!(handle.await.unwrap().is_err(), "{sample_rate} Hz/{channels} ch");
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = |json| serde_json::from_value::<VorbisEncoderConfig>(json).unwrap();
+        assert!(VorbisEncoderNode::new(config(serde_json::json!({"quality": 2.5}))).is_ok());
+        assert!(VorbisEncoderNode::new(config(serde_json::json!({"quality": 11}))).is_err());
+        assert!(VorbisEncoderNode::new(config(serde_json::json!({"bandwidth_hz": 500}))).is_err());
+    }
+}
@@ -0,0 +1,620 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio device nodes - Capture from and play to local sound devices
+//!
+//! Devices are opened through cpal, which uses WASAPI on Windows, CoreAudio on macOS and ALSA
+//! on Linux. The device runs its callback on its own thread: captured buffers are forwarded to
+//! the node task over a bounded channel, and playback pulls from a queue the node task fills,
+//! holding upstream back so the pipeline runs at the device clock.
+
+use async_trait::async_trait;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, StreamConfig, StreamError};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat as PacketSampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, EnumOption, InputPin, NodeContext,
+    NodeRegistry, OutputPin, PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::{mpsc, Notify};
+
+/// Captured buffers that may queue up before the device thread starts dropping them.
+const CAPTURE_QUEUE_DEPTH: usize = 64;
+
+/// Configuration for the AudioCaptureNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AudioCaptureConfig {
+    /// Device id (as reported by the host, e.g. `wasapi:...`) or a case-insensitive part of
+    /// the device name. Uses the system default input device when unset.
+    #[schemars(extend("enum_provider" = "audio_input_devices"))]
+    pub device: Option<String>,
+    /// Capture sample rate in Hz
+    #[schemars(range(min = 8000))]
+    pub sample_rate: u32,
+    /// Capture channel count
+    #[schemars(range(min = 1))]
+    pub channels: u16,
+    /// Output frame duration in milliseconds
+    #[schemars(range(min = 1))]
+    pub frame_ms: u32,
+}
+
+impl Default for AudioCaptureConfig {
+    fn default() -> Self {
+        Self { device: None, sample_rate: 48000, channels: 1, frame_ms: 20 }
+    }
+}
+
+/// Configuration for the AudioPlaybackNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AudioPlaybackConfig {
+    /// Device id (as reported by the host, e.g. `wasapi:...`) or a case-insensitive part of
+    /// the device name. Uses the system default output device when unset.
+    #[schemars(extend("enum_provider" = "audio_output_devices"))]
+    pub device: Option<String>,
+    /// Playback sample rate in Hz; input frames must match it
+    #[schemars(range(min = 8000))]
+    pub sample_rate: u32,
+    /// Playback channel count; input frames must match it
+    #[schemars(range(min = 1))]
+    pub channels: u16,
+    /// Audio queued for the device before upstream is held back, in milliseconds
+    #[schemars(range(min = 1))]
+    pub buffer_ms: u32,
+}
+
+impl Default for AudioPlaybackConfig {
+    fn default() -> Self {
+        Self { device: None, sample_rate: 48000, channels: 2, buffer_ms: 200 }
+    }
+}
+
+fn validate_format(sample_rate: u32, channels: u16, duration_ms: u32) -> Result<(), String> {
+    if sample_rate < 8000 {
+        return Err(format!("sample_rate must be at least 8000, got {sample_rate}"));
+    }
+    if channels == 0 || duration_ms == 0 {
+        return Err("channels and the buffer duration must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Number of interleaved samples in `ms` milliseconds of audio.
+fn samples_for_ms(sample_rate: u32, channels: u16, ms: u32) -> usize {
+    (u64::from(sample_rate) * u64::from(ms) / 1000) as usize * usize::from(channels)
+}
+
+/// Opens the device named by `selector`, or the default one.
+fn open_device(selector: Option<&str>, input: bool) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+    let kind = if input { "input" } else { "output" };
+    let Some(selector) = selector else {
+        let device = if input { host.default_input_device() } else { host.default_output_device() };
+        return device.ok_or_else(|| format!("No default audio {kind} device"));
+    };
+
+    if let Some(device) = selector.parse().ok().and_then(|id| host.device_by_id(&id)) {
+        return Ok(device);
+    }
+    let needle = selector.to_lowercase();
+    host.devices()
+        .map_err(|e| format!("Failed to list audio devices: {e}"))?
+        .filter(|device| if input { device.supports_input() } else { device.supports_output() })
+        .find(|device| {
+            device
+                .description()
+                .is_ok_and(|description| description.name().to_lowercase().contains(&needle))
+        })
+        .ok_or_else(|| format!("No audio {kind} device matches '{selector}'"))
+}
+
+/// Lists the host's input or output devices for the UI, keyed by device id.
+fn list_devices(input: bool) -> Vec<EnumOption> {
+    let devices = match cpal::default_host().devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            tracing::warn!("Failed to list audio devices: {e}");
+            return Vec::new();
+        },
+    };
+    devices
+        .filter(|device| if input { device.supports_input() } else { device.supports_output() })
+        .filter_map(|device| {
+            let id = device.id().ok()?.to_string();
+            Some(match device.description() {
+                Ok(description) => EnumOption::labeled(id, description.name()),
+                Err(_) => EnumOption::new(id),
+            })
+        })
+        .collect()
+}
+
+/// Forwards fatal stream errors to the node task; glitches are only logged.
+fn error_callback(
+    node: &'static str,
+    error_tx: mpsc::UnboundedSender<String>,
+) -> impl FnMut(StreamError) + Send + 'static {
+    move |err| match err {
+        StreamError::BufferUnderrun => tracing::debug!("{} device buffer under/overrun", node),
+        err => {
+            let _ = error_tx.send(err.to_string());
+        },
+    }
+}
+
+fn build_capture_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    samples_tx: mpsc::Sender<Vec<f32>>,
+    dropped: Arc<AtomicU64>,
+    error_tx: mpsc::UnboundedSender<String>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let samples = data.iter().map(|&s| s.to_sample::<f32>()).collect();
+            if samples_tx.try_send(samples).is_err() {
+                dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        },
+        error_callback("AudioCaptureNode", error_tx),
+        None,
+    )
+}
+
+/// Samples waiting for the output device, shared with its callback.
+#[derive(Default)]
+struct PlaybackQueue {
+    samples: Mutex<VecDeque<f32>>,
+    /// Signalled by the device callback each time it consumed samples
+    drained: Notify,
+}
+
+impl PlaybackQueue {
+    fn len(&self) -> usize {
+        self.samples.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+}
+
+fn build_playback_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    queue: Arc<PlaybackQueue>,
+    error_tx: mpsc::UnboundedSender<String>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            let mut samples = queue.samples.lock().unwrap_or_else(PoisonError::into_inner);
+            let available = samples.len().min(data.len());
+            for (out, sample) in data.iter_mut().zip(samples.drain(..available)) {
+                *out = T::from_sample(sample);
+            }
+            drop(samples);
+            // Underruns play silence
+            for out in &mut data[available..] {
+                *out = T::EQUILIBRIUM;
+            }
+            queue.drained.notify_one();
+        },
+        error_callback("AudioPlaybackNode", error_tx),
+        None,
+    )
+}
+
+/// Dispatches on the device's native sample format, which cpal does not convert.
+macro_rules! build_for_format {
+    ($format:expr, $build:ident($($arg:expr),*)) => {
+        match $format {
+            SampleFormat::F32 => $build::<f32>($($arg),*),
+            SampleFormat::I16 => $build::<i16>($($arg),*),
+            SampleFormat::I32 => $build::<i32>($($arg),*),
+            SampleFormat::U16 => $build::<u16>($($arg),*),
+            other => return Err(format!("Unsupported device sample format {other}")),
+        }
+    };
+}
+
+/// A source node that captures audio from a local input device.
+///
+/// Emits f32 frames of `frame_ms` at the configured rate and channel count, which the device
+/// must support. If the node falls behind the device, whole device buffers are dropped.
+pub struct AudioCaptureNode {
+    config: AudioCaptureConfig,
+}
+
+impl AudioCaptureNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioCaptureConfig = config_helpers::parse_config_optional(params)?;
+            validate_format(config.sample_rate, config.channels, config.frame_ms).map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid audio capture configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn open_stream(
+        &self,
+        samples_tx: mpsc::Sender<Vec<f32>>,
+        dropped: Arc<AtomicU64>,
+        error_tx: mpsc::UnboundedSender<String>,
+    ) -> Result<cpal::Stream, String> {
+        let device = open_device(self.config.device.as_deref(), true)?;
+        let format = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to query the input device: {e}"))?
+            .sample_format();
+        let config = StreamConfig {
+            channels: self.config.channels,
+            sample_rate: self.config.sample_rate,
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let stream = build_for_format!(
+            format,
+            build_capture_stream(&device, &config, samples_tx, dropped, error_tx)
+        )
+        .map_err(|e| {
+            format!(
+                "Failed to open input device at {} Hz, {} channels: {e}",
+                config.sample_rate, config.channels
+            )
+        })?;
+        stream.play().map_err(|e| format!("Failed to start audio capture: {e}"))?;
+        Ok(stream)
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioCaptureNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: self.config.sample_rate,
+                channels: self.config.channels,
+                sample_format: PacketSampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::UpdateParams(_)) => {},
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::Shutdown) | None => {
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                    return Ok(());
+                },
+            }
+        }
+
+        let (samples_tx, mut samples_rx) = mpsc::channel(CAPTURE_QUEUE_DEPTH);
+        let (error_tx, mut error_rx) = mpsc::unbounded_channel();
+        let dropped = Arc::new(AtomicU64::new(0));
+        let stream = match self.open_stream(samples_tx, dropped.clone(), error_tx) {
+            Ok(stream) => stream,
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let rate = u64::from(self.config.sample_rate);
+        let channels = usize::from(self.config.channels);
+        let frame_len =
+            samples_for_ms(self.config.sample_rate, self.config.channels, self.config.frame_ms)
+                .max(channels);
+        let frame_duration_us = (frame_len / channels) as u64 * 1_000_000 / rate;
+        let mut pending: Vec<f32> = Vec::with_capacity(2 * frame_len);
+        let mut sequence = 0u64;
+
+        let reason = loop {
+            tokio::select! {
+                Some(chunk) = samples_rx.recv() => {
+                    pending.extend(chunk);
+                    while pending.len() >= frame_len {
+                        let samples: Vec<f32> = pending.drain(..frame_len).collect();
+                        let metadata = PacketMetadata {
+                            timestamp_us: Some(sequence * frame_duration_us),
+                            duration_us: Some(frame_duration_us),
+                            sequence: Some(sequence),
+                        };
+                        sequence += 1;
+                        let frame = AudioFrame::with_metadata(
+                            self.config.sample_rate,
+                            self.config.channels,
+                            samples,
+                            Some(metadata),
+                        );
+                        if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            drop(stream);
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                        stats.sent();
+                    }
+                    let dropped = dropped.swap(0, Ordering::Relaxed);
+                    if dropped > 0 {
+                        tracing::warn!(dropped, "AudioCaptureNode fell behind the device, dropped buffers");
+                        for _ in 0..dropped {
+                            stats.discarded();
+                        }
+                    }
+                    stats.maybe_send();
+                }
+                Some(err_msg) = error_rx.recv() => {
+                    let err_msg = format!("Audio capture failed: {err_msg}");
+                    stats.force_send();
+                    state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                    return Err(StreamKitError::Runtime(err_msg));
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+        };
+
+        drop(stream);
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// A sink node that plays audio on a local output device.
+///
+/// Input frames must match the configured rate and channel count. Up to `buffer_ms` of audio
+/// is queued for the device; beyond that upstream is held back. Gaps play as silence.
+pub struct AudioPlaybackNode {
+    config: AudioPlaybackConfig,
+}
+
+impl AudioPlaybackNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioPlaybackConfig = config_helpers::parse_config_optional(params)?;
+            validate_format(config.sample_rate, config.channels, config.buffer_ms).map_err(
+                |e| {
+                    StreamKitError::Configuration(format!(
+                        "Invalid audio playback configuration: {e}"
+                    ))
+                },
+            )?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn open_stream(
+        &self,
+        queue: Arc<PlaybackQueue>,
+        error_tx: mpsc::UnboundedSender<String>,
+    ) -> Result<cpal::Stream, String> {
+        let device = open_device(self.config.device.as_deref(), false)?;
+        let format = device
+            .default_output_config()
+            .map_err(|e| format!("Failed to query the output device: {e}"))?
+            .sample_format();
+        let config = StreamConfig {
+            channels: self.config.channels,
+            sample_rate: self.config.sample_rate,
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let stream =
+            build_for_format!(format, build_playback_stream(&device, &config, queue, error_tx))
+                .map_err(|e| {
+                    format!(
+                        "Failed to open output device at {} Hz, {} channels: {e}",
+                        config.sample_rate, config.channels
+                    )
+                })?;
+        stream.play().map_err(|e| format!("Failed to start audio playback: {e}"))?;
+        Ok(stream)
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioPlaybackNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: self.config.sample_rate,
+                channels: self.config.channels,
+                sample_format: PacketSampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let queue = Arc::new(PlaybackQueue::default());
+        let (error_tx, mut error_rx) = mpsc::unbounded_channel();
+        let stream = match self.open_stream(queue.clone(), error_tx) {
+            Ok(stream) => stream,
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let max_queued =
+            samples_for_ms(self.config.sample_rate, self.config.channels, self.config.buffer_ms);
+        let mut input_closed = false;
+
+        let reason = loop {
+            let queued = queue.len();
+            // Once input ends, what is queued still plays out
+            if input_closed && queued == 0 {
+                break "input_closed";
+            }
+            let has_room = !input_closed && queued < max_queued;
+
+            tokio::select! {
+                maybe_packet = input_rx.recv(), if has_room => {
+                    let Some(packet) = maybe_packet else {
+                        input_closed = true;
+                        continue;
+                    };
+                    let Packet::Audio(frame) = packet else {
+                        tracing::warn!("AudioPlaybackNode received non-audio packet, ignoring");
+                        stats.discarded();
+                        continue;
+                    };
+                    stats.received();
+                    if frame.sample_rate != self.config.sample_rate
+                        || frame.channels != self.config.channels
+                    {
+                        tracing::warn!(
+                            sample_rate = frame.sample_rate,
+                            channels = frame.channels,
+                            "AudioPlaybackNode received a frame in the wrong format, ignoring"
+                        );
+                        stats.discarded();
+                        continue;
+                    }
+                    queue
+                        .samples
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .extend(frame.samples());
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                () = queue.drained.notified(), if !has_room => {}
+                Some(err_msg) = error_rx.recv() => {
+                    let err_msg = format!("Audio playback failed: {err_msg}");
+                    stats.force_send();
+                    state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                    return Err(StreamKitError::Runtime(err_msg));
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+        };
+
+        drop(stream);
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Registers the audio device nodes.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+pub fn register_audio_device_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let factory = AudioCaptureNode::factory();
+    registry.register_dynamic_with_description(
+        "audio::device::capture",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(AudioCaptureConfig))
+            .expect("AudioCaptureConfig schema should serialize to JSON"),
+        vec!["audio".to_string(), "device".to_string()],
+        false,
+        "Captures audio from a local input device (WASAPI on Windows, CoreAudio on macOS, \
+         ALSA on Linux) as raw f32 frames. Uses the system default device unless one is \
+         selected by id or name. Only in builds with the audio_device feature.",
+    );
+
+    let factory = AudioPlaybackNode::factory();
+    registry.register_dynamic_with_description(
+        "audio::device::playback",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(AudioPlaybackConfig))
+            .expect("AudioPlaybackConfig schema should serialize to JSON"),
+        vec!["audio".to_string(), "device".to_string()],
+        false,
+        "Plays raw audio on a local output device (WASAPI on Windows, CoreAudio on macOS, \
+         ALSA on Linux), pacing the pipeline to the device clock. Uses the system default \
+         device unless one is selected by id or name. Only in builds with the audio_device \
+         feature.",
+    );
+
+    registry.register_enum_provider("audio_input_devices", || list_devices(true));
+    registry.register_enum_provider("audio_output_devices", || list_devices(false));
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_validation() {
+        assert!(AudioCaptureNode::factory()(None).is_ok());
+        let params = serde_json::json!({ "sample_rate": 4000 });
+        assert!(AudioCaptureNode::factory()(Some(&params)).is_err());
+        let params = serde_json::json!({ "channels": 0 });
+        assert!(AudioPlaybackNode::factory()(Some(&params)).is_err());
+
+        let node = AudioPlaybackNode::factory()(None).unwrap();
+        assert_eq!(
+            node.input_pins()[0].accepts_types,
+            vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 48000,
+                channels: 2,
+                sample_format: PacketSampleFormat::F32,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_samples_for_ms() {
+        assert_eq!(samples_for_ms(48000, 2, 20), 1920);
+        assert_eq!(samples_for_ms(44100, 1, 10), 441);
+    }
+}
@@ -0,0 +1,199 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio concat node - Plays several raw audio inputs back to back
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::types::Packet;
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    ProcessorNode, StreamKitError,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{Format, Timeline};
+
+/// Configuration for the AudioConcatNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct AudioConcatConfig {
+    /// Number of inputs, named in_0, in_1, ..., in_{N-1} and played in that order
+    #[schemars(range(min = 1, max = 64))]
+    pub num_inputs: usize,
+}
+
+impl Default for AudioConcatConfig {
+    fn default() -> Self {
+        Self { num_inputs: 2 }
+    }
+}
+
+/// A node that outputs all of `in_0`, then all of `in_1`, and so on.
+///
+/// Each input is read to the end before the next is touched, so later inputs are held back by
+/// backpressure meanwhile. Inputs that aren't connected are skipped. The joined audio is
+/// re-stamped onto one timeline starting at zero. Non-audio packets are dropped.
+pub struct AudioConcatNode {
+    config: AudioConcatConfig,
+}
+
+impl AudioConcatNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioConcatConfig = config_helpers::parse_config_optional(params)?;
+            if !(1..=64).contains(&config.num_inputs) {
+                return Err(StreamKitError::Configuration(format!(
+                    "num_inputs must be between 1 and 64, got {}",
+                    config.num_inputs
+                )));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioConcatNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        (0..self.config.num_inputs).map(|i| super::audio_pin(format!("in_{i}"))).collect()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![super::audio_output_pin()]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let inputs: Vec<_> = (0..self.config.num_inputs)
+            .map(|i| {
+                let pin = format!("in_{i}");
+                let rx = context.inputs.remove(&pin);
+                (pin, rx)
+            })
+            .collect();
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut format = Format::default();
+        let mut timeline = Timeline::default();
+
+        for (pin, rx) in inputs {
+            let Some(mut rx) = rx else {
+                tracing::debug!(node = %node_name, %pin, "Input not connected, skipping");
+                continue;
+            };
+            while let Some(packet) = context.recv_with_cancellation(&mut rx).await {
+                stats.received();
+                let Packet::Audio(frame) = packet else {
+                    stats.discarded();
+                    continue;
+                };
+                if let Err(e) = format.check(&frame, &pin) {
+                    state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                    return Err(e);
+                }
+                let packet =
+                    timeline.frame(frame.sample_rate, frame.channels, frame.samples().to_vec());
+                if context.output_sender.send("out", packet).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                    return Ok(());
+                }
+                stats.sent();
+                stats.maybe_send();
+            }
+            if context.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                break;
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    fn frame(value: f32, len: usize) -> Packet {
+        Packet::Audio(AudioFrame::new(1000, 1, vec![value; len]))
+    }
+
+    #[tokio::test]
+    async fn test_plays_inputs_in_order() {
+        let (tx0, rx0) = mpsc::channel(10);
+        let (tx1, rx1) = mpsc::channel(10);
+        let (tx2, rx2) = mpsc::channel(10);
+        let inputs = HashMap::from([
+            ("in_0".to_string(), rx0),
+            ("in_1".to_string(), rx1),
+            ("in_2".to_string(), rx2),
+        ]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let params = serde_json::json!({"num_inputs": 3});
+        let node = AudioConcatNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // Later inputs arrive first; they must still come out after the earlier ones.
+        tx2.send(frame(3.0, 10)).await.unwrap();
+        tx1.send(frame(2.0, 20)).await.unwrap();
+        drop((tx1, tx2));
+        tx0.send(frame(1.0, 10)).await.unwrap();
+        tx0.send(frame(1.0, 10)).await.unwrap();
+        drop(tx0);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let firsts: Vec<f32> = packets.iter().map(|p| extract_audio_data(p).unwrap()[0]).collect();
+        assert_eq!(firsts, vec![1.0, 1.0, 2.0, 3.0]);
+        let timestamps: Vec<_> = packets
+            .iter()
+            .map(|p| match p {
+                Packet::Audio(frame) => frame.metadata.as_ref().unwrap().timestamp_us.unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(timestamps, vec![0, 10_000, 20_000, 40_000]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_mismatched_format() {
+        let (tx0, rx0) = mpsc::channel(10);
+        let (tx1, rx1) = mpsc::channel(10);
+        let inputs = HashMap::from([("in_0".to_string(), rx0), ("in_1".to_string(), rx1)]);
+        let (context, _mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let node = AudioConcatNode::factory()(None).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+
+        tx0.send(frame(1.0, 10)).await.unwrap();
+        drop(tx0);
+        tx1.send(Packet::Audio(AudioFrame::new(48_000, 2, vec![0.0; 20]))).await.unwrap();
+        assert!(handle.await.unwrap().is_err());
+    }
+
+    #[test]
+    fn test_num_inputs_validation() {
+        let factory = AudioConcatNode::factory();
+        assert!(factory(Some(&serde_json::json!({"num_inputs": 0}))).is_err());
+        let node = factory(Some(&serde_json::json!({"num_inputs": 3}))).unwrap();
+        assert_eq!(node.input_pins().len(), 3);
+    }
+}
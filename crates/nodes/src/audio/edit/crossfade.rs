@@ -0,0 +1,269 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio crossfade node - Joins two raw audio inputs with an overlapping fade
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::types::Packet;
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    ProcessorNode, StreamKitError,
+};
+
+use super::{ms_to_frames, progress, FadeCurve, Format, Tail, Timeline};
+
+/// Configuration for the AudioCrossfadeNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct AudioCrossfadeConfig {
+    /// Length of the overlap between the end of in_0 and the start of in_1, in milliseconds
+    #[schemars(range(min = 1, max = 60_000))]
+    pub duration_ms: u64,
+    /// Shape of the fades. `equal_power` keeps the loudness steady through the overlap.
+    pub fade_curve: FadeCurve,
+}
+
+impl Default for AudioCrossfadeConfig {
+    fn default() -> Self {
+        Self { duration_ms: 1000, fade_curve: FadeCurve::EqualPower }
+    }
+}
+
+/// A node that plays `in_0` and then `in_1`, overlapping the end of one with the start of
+/// the other.
+///
+/// The last `duration_ms` of `in_0` is held back until `in_0` ends and is then mixed into the
+/// start of `in_1`, fading out while `in_1` fades in. When `in_0` is shorter than the overlap
+/// the whole of it is crossfaded; when `in_1` is, the rest of `in_0` fades out alone. The
+/// output is one timeline starting at zero. Non-audio packets are dropped.
+pub struct AudioCrossfadeNode {
+    config: AudioCrossfadeConfig,
+}
+
+impl AudioCrossfadeNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioCrossfadeConfig = config_helpers::parse_config_optional(params)?;
+            if !(1..=60_000).contains(&config.duration_ms) {
+                return Err(StreamKitError::Configuration(format!(
+                    "duration_ms must be between 1 and 60000, got {}",
+                    config.duration_ms
+                )));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+/// The held-back end of `in_0`, mixed into `in_1` frame by frame.
+struct Overlap {
+    samples: VecDeque<f32>,
+    /// Length of the overlap in frames
+    frames: u64,
+    /// Frames mixed so far
+    done: u64,
+}
+
+impl Overlap {
+    /// Mixes the overlap into the start of `samples`, fading it out and `samples` in.
+    fn mix_into(&mut self, samples: &mut [f32], channels: usize, curve: FadeCurve) {
+        for frame in samples.chunks_mut(channels) {
+            if self.samples.is_empty() {
+                return;
+            }
+            let p = progress(self.done, self.frames);
+            let (gain_out, gain_in) = (curve.fade_out(p), curve.fade_in(p));
+            for sample in frame {
+                let held = self.samples.pop_front().unwrap_or(0.0);
+                *sample = *sample * gain_in + held * gain_out;
+            }
+            self.done += 1;
+        }
+    }
+
+    /// Fades out whatever the second input didn't overlap.
+    fn finish(mut self, channels: usize, curve: FadeCurve) -> Vec<f32> {
+        let mut rest: Vec<f32> = self.samples.drain(..).collect();
+        for frame in rest.chunks_mut(channels) {
+            let gain = curve.fade_out(progress(self.done, self.frames));
+            for sample in frame {
+                *sample *= gain;
+            }
+            self.done += 1;
+        }
+        rest
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioCrossfadeNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![super::audio_pin("in_0".to_string()), super::audio_pin("in_1".to_string())]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![super::audio_output_pin()]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut first_rx = context.take_input("in_0")?;
+        let mut second_rx = context.take_input("in_1")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let curve = self.config.fade_curve;
+        let mut format = Format::default();
+        let mut timeline = Timeline::default();
+        let mut tail: Option<Tail> = None;
+
+        while let Some(packet) = context.recv_with_cancellation(&mut first_rx).await {
+            stats.received();
+            let Packet::Audio(frame) = packet else {
+                stats.discarded();
+                continue;
+            };
+            if let Err(e) = format.check(&frame, "in_0") {
+                state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                return Err(e);
+            }
+            let (sample_rate, channels) = (frame.sample_rate, frame.channels);
+            let tail = tail.get_or_insert_with(|| {
+                Tail::new(ms_to_frames(self.config.duration_ms, sample_rate), channels)
+            });
+            let samples = tail.push(frame.samples());
+            if !samples.is_empty() {
+                let packet = timeline.frame(sample_rate, channels, samples);
+                if context.output_sender.send("out", packet).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                    return Ok(());
+                }
+                stats.sent();
+            }
+            stats.maybe_send();
+        }
+
+        let mut overlap = tail.map(|mut tail| {
+            let samples: VecDeque<f32> = tail.take().into();
+            let channels = format.0.map_or(1, |(_, channels)| usize::from(channels.max(1)));
+            Overlap { frames: (samples.len() / channels) as u64, samples, done: 0 }
+        });
+
+        while let Some(packet) = context.recv_with_cancellation(&mut second_rx).await {
+            stats.received();
+            let Packet::Audio(frame) = packet else {
+                stats.discarded();
+                continue;
+            };
+            if let Err(e) = format.check(&frame, "in_1") {
+                state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                return Err(e);
+            }
+            let (sample_rate, channels) = (frame.sample_rate, frame.channels);
+            let mut samples = frame.samples().to_vec();
+            if let Some(overlap) = overlap.as_mut() {
+                overlap.mix_into(&mut samples, usize::from(channels.max(1)), curve);
+            }
+            let packet = timeline.frame(sample_rate, channels, samples);
+            if context.output_sender.send("out", packet).await.is_err() {
+                tracing::debug!("Output channel closed, stopping node");
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                return Ok(());
+            }
+            stats.sent();
+            stats.maybe_send();
+        }
+
+        if let (Some(overlap), Some((sample_rate, channels))) = (overlap, format.0) {
+            let samples = overlap.finish(usize::from(channels.max(1)), curve);
+            if !samples.is_empty() {
+                let packet = timeline.frame(sample_rate, channels, samples);
+                if context.output_sender.send("out", packet).await.is_ok() {
+                    stats.sent();
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    async fn run_crossfade(
+        params: serde_json::Value,
+        first: Vec<Vec<f32>>,
+        second: Vec<Vec<f32>>,
+    ) -> Vec<f32> {
+        let (tx0, rx0) = mpsc::channel(16);
+        let (tx1, rx1) = mpsc::channel(16);
+        let inputs = HashMap::from([("in_0".to_string(), rx0), ("in_1".to_string(), rx1)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let node = AudioCrossfadeNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for samples in second {
+            tx1.send(Packet::Audio(AudioFrame::new(1000, 1, samples))).await.unwrap();
+        }
+        drop(tx1);
+        for samples in first {
+            tx0.send(Packet::Audio(AudioFrame::new(1000, 1, samples))).await.unwrap();
+        }
+        drop(tx0);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        packets.iter().flat_map(|p| extract_audio_data(p).unwrap().to_vec()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_overlaps_end_of_first_with_start_of_second() {
+        let params = serde_json::json!({"duration_ms": 10, "fade_curve": "linear"});
+        let first = vec![vec![1.0; 20], vec![1.0; 20]];
+        let second = vec![vec![2.0; 30]];
+        let out = run_crossfade(params, first, second).await;
+        // 40 + 30 frames with 10 of them overlapped
+        assert_eq!(out.len(), 60);
+        assert!((out[29] - 1.0).abs() < 1e-6);
+        // Halfway through the overlap: 0.5 * 1.0 + 0.5 * 2.0
+        assert!((out[35] - 1.5).abs() < 1e-6);
+        assert!((out[40] - 2.0).abs() < 1e-6);
+        assert!((out[59] - 2.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_short_second_input_fades_out_first() {
+        let params = serde_json::json!({"duration_ms": 10, "fade_curve": "linear"});
+        let out = run_crossfade(params, vec![vec![1.0; 20]], vec![vec![0.0; 4]]).await;
+        assert_eq!(out.len(), 20);
+        assert!((out[14] - 0.6).abs() < 1e-6);
+        assert!(out[19] < 0.15);
+    }
+
+    #[test]
+    fn test_duration_validation() {
+        let factory = AudioCrossfadeNode::factory();
+        assert!(factory(Some(&serde_json::json!({"duration_ms": 0}))).is_err());
+        assert!(factory(None).is_ok());
+    }
+}
@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio editing nodes for oneshot pipelines: sample-accurate trimming with fades,
+//! concatenation and crossfades.
+//!
+//! These nodes consume their inputs to the end and re-stamp their output onto a single
+//! timeline starting at zero, so an intro, an episode and an outro can be joined into one
+//! file. Every input of a node must share one sample rate and channel count; put an
+//! `audio::resampler` in front of inputs that don't.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use streamkit_core::types::SampleFormat;
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType};
+use streamkit_core::{InputPin, NodeRegistry, OutputPin, PinCardinality, StreamKitError};
+
+pub mod concat;
+pub mod crossfade;
+pub mod trim;
+
+/// Shape of a fade.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeCurve {
+    /// Gain changes linearly with time
+    #[default]
+    Linear,
+    /// Sine/cosine gains whose powers sum to one, keeping loudness steady through crossfades
+    EqualPower,
+}
+
+impl FadeCurve {
+    /// Gain of a fade-in at `progress` (0 = start, 1 = end).
+    fn fade_in(self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => progress,
+            Self::EqualPower => (progress * std::f32::consts::FRAC_PI_2).sin(),
+        }
+    }
+
+    /// Gain of a fade-out at `progress` (0 = start, 1 = end).
+    fn fade_out(self, progress: f32) -> f32 {
+        self.fade_in(1.0 - progress)
+    }
+}
+
+/// Converts milliseconds to frames at `sample_rate`.
+fn ms_to_frames(ms: u64, sample_rate: u32) -> u64 {
+    ms.saturating_mul(u64::from(sample_rate)) / 1000
+}
+
+/// Position of `index` within a span of `len` items, as a fraction.
+#[allow(clippy::cast_precision_loss)] // Fade lengths are far below f32's exact integer range
+fn progress(index: u64, len: u64) -> f32 {
+    if len == 0 {
+        1.0
+    } else {
+        index as f32 / len as f32
+    }
+}
+
+/// Scales each interleaved frame of `samples` by `gain(frame_index)`.
+fn apply_gain(samples: &mut [f32], channels: usize, mut gain: impl FnMut(u64) -> f32) {
+    for (index, frame) in samples.chunks_mut(channels.max(1)).enumerate() {
+        let gain = gain(index as u64);
+        for sample in frame {
+            *sample *= gain;
+        }
+    }
+}
+
+/// The sample rate and channel count every input must share.
+#[derive(Default)]
+struct Format(Option<(u32, u16)>);
+
+impl Format {
+    /// Records the first frame's format and rejects frames that differ from it.
+    fn check(&mut self, frame: &AudioFrame, pin: &str) -> Result<(), StreamKitError> {
+        let format = (frame.sample_rate, frame.channels);
+        match self.0 {
+            None => {
+                self.0 = Some(format);
+                Ok(())
+            },
+            Some(expected) if expected == format => Ok(()),
+            Some((rate, channels)) => Err(StreamKitError::Runtime(format!(
+                "Audio on '{pin}' is {} Hz/{} ch but earlier audio was {rate} Hz/{channels} ch; \
+                 resample inputs to a common format first",
+                frame.sample_rate, frame.channels
+            ))),
+        }
+    }
+}
+
+/// Stamps output audio onto one continuous timeline starting at zero.
+#[derive(Default)]
+struct Timeline {
+    frames: u64,
+    sequence: u64,
+}
+
+impl Timeline {
+    fn frame(&mut self, sample_rate: u32, channels: u16, samples: Vec<f32>) -> Packet {
+        let frames = (samples.len() / usize::from(channels.max(1))) as u64;
+        let rate = u64::from(sample_rate.max(1));
+        let metadata = PacketMetadata {
+            timestamp_us: Some(self.frames * 1_000_000 / rate),
+            duration_us: Some(frames * 1_000_000 / rate),
+            sequence: Some(self.sequence),
+        };
+        self.frames += frames;
+        self.sequence += 1;
+        Packet::Audio(AudioFrame::with_metadata(sample_rate, channels, samples, Some(metadata)))
+    }
+}
+
+/// Holds back the newest audio, up to a fixed number of frames.
+struct Tail {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl Tail {
+    fn new(frames: u64, channels: u16) -> Self {
+        let capacity =
+            usize::try_from(frames).unwrap_or(usize::MAX).saturating_mul(usize::from(channels));
+        Self { samples: VecDeque::new(), capacity }
+    }
+
+    /// Adds `samples` and returns the oldest audio that no longer fits.
+    fn push(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.samples.extend(samples);
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        self.samples.drain(..excess).collect()
+    }
+
+    fn take(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+}
+
+fn audio_pin(name: String) -> InputPin {
+    InputPin {
+        name,
+        accepts_types: vec![PacketType::RawAudio(AudioFormat {
+            sample_rate: 0, // Wildcard
+            channels: 0,    // Wildcard
+            sample_format: SampleFormat::F32,
+        })],
+        cardinality: PinCardinality::One,
+    }
+}
+
+fn audio_output_pin() -> OutputPin {
+    OutputPin {
+        name: "out".to_string(),
+        produces_type: PacketType::RawAudio(AudioFormat {
+            sample_rate: 0,
+            channels: 0,
+            sample_format: SampleFormat::F32,
+        }),
+        cardinality: PinCardinality::Broadcast,
+    }
+}
+
+/// Registers the audio editing nodes with the engine's registry.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+pub fn register_audio_edit(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let factory = trim::AudioTrimNode::factory();
+    registry.register_dynamic_with_description(
+        "audio::edit::trim",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(trim::AudioTrimConfig))
+            .expect("AudioTrimConfig schema should serialize to JSON"),
+        vec!["audio".to_string(), "edit".to_string()],
+        false,
+        "Cuts raw audio to a sample-accurate window, with optional fade-in and fade-out. \
+         Output timestamps start at zero.",
+    );
+
+    let factory = concat::AudioConcatNode::factory();
+    registry.register_dynamic_with_description(
+        "audio::edit::concat",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(concat::AudioConcatConfig))
+            .expect("AudioConcatConfig schema should serialize to JSON"),
+        vec!["audio".to_string(), "edit".to_string()],
+        false,
+        "Plays its inputs one after another (in_0, then in_1, ...) as a single stream, \
+         e.g. intro + episode + outro. Inputs must share sample rate and channel count.",
+    );
+
+    let factory = crossfade::AudioCrossfadeNode::factory();
+    registry.register_dynamic_with_description(
+        "audio::edit::crossfade",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(crossfade::AudioCrossfadeConfig))
+            .expect("AudioCrossfadeConfig schema should serialize to JSON"),
+        vec!["audio".to_string(), "edit".to_string()],
+        false,
+        "Joins in_1 onto the end of in_0, overlapping the two with a fade-out/fade-in of \
+         `duration_ms`. Chain crossfades to join more than two segments.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fade_curves() {
+        assert!(FadeCurve::Linear.fade_in(0.0).abs() < 1e-6);
+        assert!((FadeCurve::Linear.fade_out(0.25) - 0.75).abs() < 1e-6);
+        // Equal-power gains keep the summed power constant through the crossfade.
+        for p in [0.0, 0.3, 0.5, 0.9] {
+            let (a, b) = (FadeCurve::EqualPower.fade_out(p), FadeCurve::EqualPower.fade_in(p));
+            assert!((a.mul_add(a, b * b) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_tail_keeps_newest_frames() {
+        let mut tail = Tail::new(2, 2);
+        assert!(tail.push(&[1.0, 1.0, 2.0, 2.0]).is_empty());
+        assert_eq!(tail.push(&[3.0, 3.0]), vec![1.0, 1.0]);
+        assert_eq!(tail.take(), vec![2.0, 2.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_timeline_is_continuous() {
+        let mut timeline = Timeline::default();
+        let first = timeline.frame(48_000, 2, vec![0.0; 1920]);
+        let second = timeline.frame(48_000, 2, vec![0.0; 960]);
+        let meta = |p: &Packet| match p {
+            Packet::Audio(frame) => frame.metadata.clone().unwrap(),
+            _ => unreachable!(),
+        };
+        assert_eq!(meta(&first).timestamp_us, Some(0));
+        assert_eq!(meta(&first).duration_us, Some(20_000));
+        assert_eq!(meta(&second).timestamp_us, Some(20_000));
+        assert_eq!(meta(&second).sequence, Some(1));
+    }
+}
@@ -0,0 +1,287 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio trim node - Cuts raw audio to a sample-accurate window with optional fades
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::types::Packet;
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    ProcessorNode, StreamKitError,
+};
+
+use super::{apply_gain, ms_to_frames, progress, FadeCurve, Format, Tail, Timeline};
+
+/// Configuration for the AudioTrimNode
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct AudioTrimConfig {
+    /// Start of the kept audio in milliseconds from the start of the input
+    pub start_ms: u64,
+    /// End of the kept audio in milliseconds from the start of the input. Runs to the end of
+    /// the input when unset.
+    pub end_ms: Option<u64>,
+    /// Length of the fade-in at the start of the kept audio, in milliseconds
+    pub fade_in_ms: u64,
+    /// Length of the fade-out at the end of the kept audio, in milliseconds. Without `end_ms`
+    /// the last `fade_out_ms` of audio is held back until the input ends.
+    pub fade_out_ms: u64,
+    /// Shape of both fades
+    pub fade_curve: FadeCurve,
+}
+
+impl AudioTrimConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(end_ms) = self.end_ms {
+            if end_ms <= self.start_ms {
+                return Err(format!(
+                    "end_ms ({end_ms}) must be greater than start_ms ({})",
+                    self.start_ms
+                ));
+            }
+            let length_ms = end_ms - self.start_ms;
+            if self.fade_in_ms > length_ms || self.fade_out_ms > length_ms {
+                return Err(format!("fades must fit within the trimmed length of {length_ms} ms"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Window and fades in frames, fixed by the first frame's sample rate.
+struct Window {
+    start: u64,
+    end: Option<u64>,
+    fade_in: u64,
+    fade_out: u64,
+}
+
+impl Window {
+    fn new(config: &AudioTrimConfig, sample_rate: u32) -> Self {
+        Self {
+            start: ms_to_frames(config.start_ms, sample_rate),
+            end: config.end_ms.map(|ms| ms_to_frames(ms, sample_rate)),
+            fade_in: ms_to_frames(config.fade_in_ms, sample_rate),
+            fade_out: ms_to_frames(config.fade_out_ms, sample_rate),
+        }
+    }
+}
+
+/// A node that keeps a window of raw audio, cut at exact sample positions.
+///
+/// Frames that straddle the window edges are split. The kept audio is re-stamped to start at
+/// zero and can be faded in and out. Once `end_ms` has passed the node stops without reading
+/// the rest of its input. Non-audio packets are dropped.
+pub struct AudioTrimNode {
+    config: AudioTrimConfig,
+}
+
+impl AudioTrimNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioTrimConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid trim configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioTrimNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![super::audio_pin("in".to_string())]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![super::audio_output_pin()]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let curve = self.config.fade_curve;
+        let mut format = Format::default();
+        let mut timeline = Timeline::default();
+        let mut window: Option<Window> = None;
+        let mut tail: Option<Tail> = None;
+        // Frames read from the input so far
+        let mut position = 0u64;
+        let mut reason = "input_closed";
+
+        while let Some(packet) = context.recv_with_cancellation(&mut input_rx).await {
+            stats.received();
+            let Packet::Audio(frame) = packet else {
+                stats.discarded();
+                continue;
+            };
+            if let Err(e) = format.check(&frame, "in") {
+                state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                return Err(e);
+            }
+            let (sample_rate, channels) = (frame.sample_rate, frame.channels);
+            let window = window.get_or_insert_with(|| Window::new(&self.config, sample_rate));
+            if window.end.is_none() && window.fade_out > 0 && tail.is_none() {
+                tail = Some(Tail::new(window.fade_out, channels));
+            }
+
+            let ch = usize::from(channels.max(1));
+            let frames = (frame.samples().len() / ch) as u64;
+            let from = window.start.clamp(position, position + frames);
+            let to =
+                window.end.map_or(position + frames, |end| end.clamp(position, position + frames));
+            position += frames;
+            if from >= to {
+                stats.discarded();
+                if window.end.is_some_and(|end| position >= end) {
+                    reason = "end_reached";
+                    break;
+                }
+                continue;
+            }
+
+            let skip = usize::try_from(from + frames - position).unwrap_or(usize::MAX) * ch;
+            let keep = usize::try_from(to - from).unwrap_or(usize::MAX) * ch;
+            let mut samples = frame.samples()[skip..skip + keep].to_vec();
+            let offset = from - window.start;
+            let (fade_in, fade_out, end) = (window.fade_in, window.fade_out, window.end);
+            apply_gain(&mut samples, ch, |i| {
+                let at = offset + i;
+                let mut gain =
+                    if at < fade_in { curve.fade_in(progress(at, fade_in)) } else { 1.0 };
+                if let Some(end) = end {
+                    let remaining = end - window.start - at;
+                    if remaining <= fade_out {
+                        gain *= curve.fade_out(progress(fade_out - remaining, fade_out));
+                    }
+                }
+                gain
+            });
+
+            let samples = match tail.as_mut() {
+                Some(tail) => tail.push(&samples),
+                None => samples,
+            };
+            if !samples.is_empty() {
+                let packet = timeline.frame(sample_rate, channels, samples);
+                if context.output_sender.send("out", packet).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                    return Ok(());
+                }
+                stats.sent();
+            }
+            stats.maybe_send();
+
+            if window.end.is_some_and(|end| position >= end) {
+                reason = "end_reached";
+                break;
+            }
+        }
+
+        // Without an end the fade-out covers whatever audio was held back.
+        if let (Some(mut tail), Some((sample_rate, channels))) = (tail, format.0) {
+            let mut samples = tail.take();
+            let ch = usize::from(channels.max(1));
+            let frames = (samples.len() / ch) as u64;
+            apply_gain(&mut samples, ch, |i| curve.fade_out(progress(i + 1, frames)));
+            if !samples.is_empty() {
+                let packet = timeline.frame(sample_rate, channels, samples);
+                if context.output_sender.send("out", packet).await.is_ok() {
+                    stats.sent();
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    /// Mono 1 kHz frames whose samples count up from `first`, so positions are visible.
+    fn ramp_frame(first: usize, len: usize) -> Packet {
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (first..first + len).map(|i| i as f32).collect();
+        Packet::Audio(AudioFrame::new(1000, 1, samples))
+    }
+
+    async fn run_trim(params: serde_json::Value, packets: Vec<Packet>) -> Vec<f32> {
+        let (input_tx, input_rx) = mpsc::channel(packets.len() + 1);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let node = AudioTrimNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for packet in packets {
+            let _ = input_tx.send(packet).await;
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        packets.iter().flat_map(|p| extract_audio_data(p).unwrap().to_vec()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_cuts_at_exact_samples() {
+        let packets = (0..5).map(|i| ramp_frame(i * 20, 20)).collect();
+        let out = run_trim(serde_json::json!({"start_ms": 15, "end_ms": 47}), packets).await;
+        #[allow(clippy::cast_precision_loss)]
+        let expected: Vec<f32> = (15..47).map(|i| i as f32).collect();
+        assert_eq!(out, expected);
+    }
+
+    #[tokio::test]
+    async fn test_fades_with_known_end() {
+        let packets = vec![Packet::Audio(AudioFrame::new(1000, 1, vec![1.0; 100]))];
+        let params = serde_json::json!({"end_ms": 40, "fade_in_ms": 10, "fade_out_ms": 10});
+        let out = run_trim(params, packets).await;
+        assert_eq!(out.len(), 40);
+        assert!(out[0].abs() < 1e-6);
+        assert!((out[5] - 0.5).abs() < 1e-6);
+        assert!((out[20] - 1.0).abs() < 1e-6);
+        assert!(out[39] < 0.2);
+    }
+
+    #[tokio::test]
+    async fn test_fade_out_without_end_holds_back_tail() {
+        let packets =
+            (0..3).map(|_| Packet::Audio(AudioFrame::new(1000, 1, vec![1.0; 20]))).collect();
+        let out = run_trim(serde_json::json!({"fade_out_ms": 10}), packets).await;
+        assert_eq!(out.len(), 60);
+        assert!((out[49] - 1.0).abs() < 1e-6);
+        assert!(out[50] < 1.0 && out[50] > 0.8);
+        assert!(out[59].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_validation() {
+        let factory = AudioTrimNode::factory();
+        assert!(factory(Some(&serde_json::json!({"start_ms": 100, "end_ms": 50}))).is_err());
+        assert!(factory(Some(&serde_json::json!({"end_ms": 50, "fade_in_ms": 60}))).is_err());
+        assert!(factory(Some(&serde_json::json!({"fade_out_ms": 60}))).is_ok());
+    }
+}
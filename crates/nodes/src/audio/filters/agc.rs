@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! AGC node - Automatic gain control towards a target level
+//!
+//! The level of the input is measured over a sliding `window_ms`, either as RMS or as peak,
+//! across all channels together. The gain that would bring that level to `target_db` is
+//! limited to `max_gain_db` and approached in the dB domain: quickly (`attack_ms`) when the
+//! gain has to drop, slowly (`release_ms`) when it may rise. While the level is below
+//! `noise_floor_db` the gain is held, so pauses and background noise aren't pumped up. The
+//! applied gain is additionally capped so no sample exceeds full scale.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Levels below this are treated as silence by the level detector.
+const SILENCE_DB: f32 = -120.0;
+
+/// How the input level is measured.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AgcDetector {
+    /// Root mean square over the window; follows perceived loudness
+    #[default]
+    Rms,
+    /// Highest absolute sample in the window; keeps peaks at the target
+    Peak,
+}
+
+/// Configuration for the AudioAgcNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioAgcConfig {
+    /// Level the output is steered towards, in dBFS
+    #[schemars(range(min = -60.0, max = 0.0), extend("tunable" = true))]
+    pub target_db: f32,
+    /// Level measurement used to compare against the target
+    #[schemars(extend("tunable" = true))]
+    pub detector: AgcDetector,
+    /// Length of the window the level is measured over, in milliseconds
+    #[schemars(range(min = 10.0, max = 5000.0), extend("tunable" = true))]
+    pub window_ms: f32,
+    /// Largest boost applied to quiet input, in dB
+    #[schemars(range(min = 0.0, max = 60.0), extend("tunable" = true))]
+    pub max_gain_db: f32,
+    /// Time for the gain to come down when the input gets louder, in milliseconds
+    #[schemars(range(min = 1.0, max = 5000.0), extend("tunable" = true))]
+    pub attack_ms: f32,
+    /// Time for the gain to go up when the input gets quieter, in milliseconds
+    #[schemars(range(min = 10.0, max = 30000.0), extend("tunable" = true))]
+    pub release_ms: f32,
+    /// Level below which the gain is held rather than raised, in dBFS
+    #[schemars(range(min = -120.0, max = 0.0), extend("tunable" = true))]
+    pub noise_floor_db: f32,
+}
+
+impl Default for AudioAgcConfig {
+    fn default() -> Self {
+        Self {
+            target_db: -18.0,
+            detector: AgcDetector::Rms,
+            window_ms: 300.0,
+            max_gain_db: 30.0,
+            attack_ms: 50.0,
+            release_ms: 2000.0,
+            noise_floor_db: -60.0,
+        }
+    }
+}
+
+impl AudioAgcConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("target_db", self.target_db, -60.0, 0.0)?;
+        check("window_ms", self.window_ms, 10.0, 5000.0)?;
+        check("max_gain_db", self.max_gain_db, 0.0, 60.0)?;
+        check("attack_ms", self.attack_ms, 1.0, 5000.0)?;
+        check("release_ms", self.release_ms, 10.0, 30000.0)?;
+        check("noise_floor_db", self.noise_floor_db, -120.0, 0.0)
+    }
+}
+
+/// Converts a linear amplitude to dBFS.
+fn amplitude_db(amplitude: f32) -> f32 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(SILENCE_DB)
+    } else {
+        SILENCE_DB
+    }
+}
+
+/// Smoothing coefficient of a one-pole filter with time constant `ms` at `sample_rate`.
+#[allow(clippy::cast_precision_loss)] // Sample rates are far below f32's exact integer range
+fn time_coefficient(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms * 0.001 * sample_rate as f32)).exp()
+}
+
+/// Sliding-window level of a stream, updated one frame at a time.
+#[derive(Default)]
+struct LevelWindow {
+    /// Per-frame mean square (RMS) or peak, oldest first
+    frames: VecDeque<f32>,
+    len: usize,
+    /// Running sum of `frames` for RMS
+    sum: f64,
+    /// Indices into `frames` of decreasing peaks, for a sliding maximum
+    peaks: VecDeque<(u64, f32)>,
+    /// Index of the next frame pushed
+    next: u64,
+}
+
+impl LevelWindow {
+    fn new(len: usize) -> Self {
+        Self { len: len.max(1), ..Self::default() }
+    }
+
+    /// Adds a frame and returns the window's level as an amplitude.
+    fn push(&mut self, frame: &[f32], detector: AgcDetector) -> f32 {
+        match detector {
+            AgcDetector::Rms => {
+                #[allow(clippy::cast_precision_loss)] // Channel counts are tiny
+                let mean_square =
+                    frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32;
+                self.frames.push_back(mean_square);
+                self.sum += f64::from(mean_square);
+                if self.frames.len() > self.len {
+                    self.sum -= f64::from(self.frames.pop_front().unwrap_or(0.0));
+                }
+                #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+                let mean = (self.sum.max(0.0) / self.frames.len() as f64) as f32;
+                mean.sqrt()
+            },
+            AgcDetector::Peak => {
+                let peak = frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+                while self.peaks.back().is_some_and(|&(_, p)| p <= peak) {
+                    self.peaks.pop_back();
+                }
+                self.peaks.push_back((self.next, peak));
+                let oldest = self.next.saturating_sub(self.len as u64 - 1);
+                while self.peaks.front().is_some_and(|&(index, _)| index < oldest) {
+                    self.peaks.pop_front();
+                }
+                self.next += 1;
+                self.peaks.front().map_or(0.0, |&(_, p)| p)
+            },
+        }
+    }
+}
+
+/// Level detector, gain computer and smoothing state for one stream.
+struct Agc {
+    config: AudioAgcConfig,
+    sample_rate: u32,
+    attack: f32,
+    release: f32,
+    window: LevelWindow,
+    /// Smoothed gain in dB
+    gain_db: f32,
+}
+
+impl Agc {
+    fn new(config: AudioAgcConfig) -> Self {
+        Self {
+            config,
+            sample_rate: 0,
+            attack: 0.0,
+            release: 0.0,
+            window: LevelWindow::default(),
+            gain_db: 0.0,
+        }
+    }
+
+    /// Applies new parameters, keeping the current gain.
+    fn set_config(&mut self, config: AudioAgcConfig) {
+        self.config = config;
+        self.reset_detector();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.reset_detector();
+        }
+    }
+
+    fn reset_detector(&mut self) {
+        if self.sample_rate > 0 {
+            self.attack = time_coefficient(self.config.attack_ms, self.sample_rate);
+            self.release = time_coefficient(self.config.release_ms, self.sample_rate);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            #[allow(clippy::cast_precision_loss)]
+            let len = (self.config.window_ms * 0.001 * self.sample_rate as f32) as usize;
+            self.window = LevelWindow::new(len);
+        }
+    }
+
+    /// Adjusts the level of interleaved `samples` in place.
+    fn process(&mut self, samples: &mut [f32], channels: usize) {
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let level_db = amplitude_db(self.window.push(frame, self.config.detector));
+            if level_db >= self.config.noise_floor_db {
+                let target = (self.config.target_db - level_db).min(self.config.max_gain_db);
+                let coefficient = if target < self.gain_db { self.attack } else { self.release };
+                self.gain_db = target + coefficient * (self.gain_db - target);
+            }
+
+            let peak = frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            let mut gain = 10f32.powf(self.gain_db / 20.0);
+            if peak * gain > 1.0 {
+                gain = 1.0 / peak;
+            }
+            for sample in frame {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+/// A node that automatically adjusts the level of raw audio towards a target loudness.
+///
+/// Useful in front of a mixer or encoder when sources (e.g. participants' microphones) arrive
+/// at very different levels. The gain adapts continuously; all parameters can be tuned while
+/// running.
+pub struct AudioAgcNode {
+    config: AudioAgcConfig,
+}
+
+impl AudioAgcNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioAgcConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid AGC configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioAgcNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut agc = Agc::new(self.config);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        agc.set_sample_rate(frame.sample_rate);
+                        let channels = usize::from(frame.channels);
+                        // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                        agc.process(frame.make_samples_mut(), channels);
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&agc.config, &params).and_then(|config: AudioAgcConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => agc.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected AGC update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    /// `ms` milliseconds of a 1 kHz sine at `amplitude`, whose RMS is `amplitude / sqrt(2)`.
+    fn sine(amplitude: f32, ms: usize) -> Vec<f32> {
+        let len = RATE as usize * ms / 1000;
+        (0..len)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / RATE as f32).sin()
+            })
+            .collect()
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        let mean = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        amplitude_db(mean.sqrt())
+    }
+
+    fn agc(config: AudioAgcConfig) -> Agc {
+        let mut agc = Agc::new(config);
+        agc.set_sample_rate(RATE);
+        agc
+    }
+
+    #[test]
+    fn test_quiet_and_loud_input_converge_to_target() {
+        for amplitude in [0.02, 0.9] {
+            let mut agc = agc(AudioAgcConfig { release_ms: 200.0, ..AudioAgcConfig::default() });
+            let mut samples = sine(amplitude, 3000);
+            agc.process(&mut samples, 1);
+            let out = rms_db(&samples[samples.len() - RATE as usize / 2..]);
+            assert!((out + 18.0).abs() < 0.5, "{amplitude}: got {out} dB");
+        }
+    }
+
+    #[test]
+    fn test_gain_is_limited_by_max_gain() {
+        let mut agc = agc(AudioAgcConfig {
+            max_gain_db: 6.0,
+            release_ms: 100.0,
+            ..AudioAgcConfig::default()
+        });
+        // -40 dB RMS would need 22 dB of gain to reach the target.
+        let mut samples = sine(0.01 * std::f32::consts::SQRT_2, 2000);
+        agc.process(&mut samples, 1);
+        let out = rms_db(&samples[samples.len() - RATE as usize / 2..]);
+        assert!((out + 34.0).abs() < 0.5, "got {out} dB");
+    }
+
+    #[test]
+    fn test_gain_holds_below_noise_floor() {
+        let mut agc = agc(AudioAgcConfig { release_ms: 100.0, ..AudioAgcConfig::default() });
+        let mut speech = sine(0.1, 1000);
+        agc.process(&mut speech, 1);
+
+        // Once the window holds only near-silence, a long pause leaves the gain where it was.
+        let mut pause = sine(0.0001, 300);
+        agc.process(&mut pause, 1);
+        let gain_db = agc.gain_db;
+        let mut pause = sine(0.0001, 2000);
+        agc.process(&mut pause, 1);
+        assert!((agc.gain_db - gain_db).abs() < 0.01);
+        assert!(pause.iter().all(|s| s.abs() < 0.01));
+    }
+
+    #[test]
+    fn test_attack_is_faster_than_release() {
+        let config = AudioAgcConfig { attack_ms: 10.0, release_ms: 5000.0, ..Default::default() };
+        let mut agc = agc(config);
+        let mut quiet = sine(0.05, 1000);
+        agc.process(&mut quiet, 1);
+        let boosted = agc.gain_db;
+        assert!(boosted > 1.0, "got {boosted} dB");
+
+        // A loud burst pulls the gain down within the window plus a few attack times...
+        let mut loud = sine(0.9, 500);
+        agc.process(&mut loud, 1);
+        assert!(agc.gain_db < -1.0, "got {} dB", agc.gain_db);
+        // ...without clipping while it does.
+        assert!(loud.iter().all(|s| s.abs() <= 1.0));
+
+        // ...while a quiet stretch brings it back up only slowly.
+        let mut quiet = sine(0.05, 500);
+        agc.process(&mut quiet, 1);
+        assert!(agc.gain_db < boosted - 3.0, "got {} dB", agc.gain_db);
+    }
+
+    #[test]
+    fn test_peak_detector_tracks_window_maximum() {
+        let mut window = LevelWindow::new(3);
+        let levels: Vec<f32> = [0.1, 0.5, 0.2, 0.3, 0.1, 0.05]
+            .iter()
+            .map(|&s| window.push(&[s, -s], AgcDetector::Peak))
+            .collect();
+        assert_eq!(levels, vec![0.1, 0.5, 0.5, 0.5, 0.3, 0.3]);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioAgcConfig::default().validate().is_ok());
+        assert!(AudioAgcConfig { target_db: 3.0, ..Default::default() }.validate().is_err());
+
+        let updated: AudioAgcConfig =
+            merge_params(&AudioAgcConfig::default(), &serde_json::json!({"detector": "peak"}))
+                .unwrap();
+        assert_eq!(updated.detector, AgcDetector::Peak);
+        assert!((updated.target_db + 18.0).abs() < f32::EPSILON);
+
+        let factory = AudioAgcNode::factory();
+        assert!(factory(Some(&serde_json::json!({"window_ms": 1.0}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_raises_quiet_input() {
+        let (input_tx, input_rx) = mpsc::channel(200);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioAgcNode::factory()(Some(&serde_json::json!({
+            "target_db": -12.0, "release_ms": 100.0
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let samples = sine(0.05, 2000);
+        for chunk in samples.chunks(960) {
+            let frame = AudioFrame::new(RATE, 1, chunk.to_vec());
+            input_tx.send(Packet::Audio(frame)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 100);
+        let last = extract_audio_data(&packets[99]).unwrap();
+        assert!((rms_db(last) + 12.0).abs() < 0.5, "got {} dB", rms_db(last));
+    }
+}
@@ -0,0 +1,648 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Binaural node - Renders multichannel or object audio to headphone stereo
+//!
+//! Every input channel is a virtual source: by default a loudspeaker of the standard layout
+//! for the channel count (see [`crate::audio::layout`]), or an object placed with `objects`.
+//! Each source reaches each ear through a spherical-head HRTF model (Brown & Duda, 1998): an
+//! interaural time difference from the path around the head and a one-pole/one-zero head-shadow
+//! filter that brightens the near ear and darkens the far one. The model has no pinna cues, so
+//! elevation only matters through the lateral angle and front/back stay ambiguous.
+//!
+//! Object positions and the listener's head rotation can be changed while running, either
+//! through parameter updates or `audio::binaural/positions@1` packets on the `positions` pin;
+//! changes are ramped over one frame so moving sources don't click.
+
+use crate::audio::layout;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, CustomPacketData, Packet, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Type id of the packets that move objects and rotate the listener.
+pub const BINAURAL_POSITIONS_TYPE_ID: &str = "audio::binaural/positions@1";
+
+const SPEED_OF_SOUND: f32 = 343.0;
+/// Strongest head shadow of the model (at `SHADOW_MIN_ANGLE` from the ear), as a gain at high
+/// frequencies
+const SHADOW_MIN_ALPHA: f32 = 0.1;
+const SHADOW_MIN_ANGLE: f32 = 5.0 * PI / 6.0;
+/// Length of each source's delay line; comfortably above the largest interaural delay
+/// (about 0.85 ms for a 12 cm head radius) at 192 kHz.
+const DELAY_LINE_LEN: usize = 256;
+
+/// Position of the source carried by one input channel.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub struct ObjectPosition {
+    /// Input channel (0-based) the object's audio is on
+    pub channel: u16,
+    /// Horizontal angle in degrees clockwise from straight ahead (90 is hard right)
+    #[serde(default)]
+    pub azimuth: f32,
+    /// Vertical angle in degrees above the horizontal plane
+    #[serde(default)]
+    pub elevation: f32,
+}
+
+/// Configuration for the AudioBinauralNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioBinauralConfig {
+    /// Positions overriding the standard speaker layout for individual input channels
+    #[schemars(extend("tunable" = true))]
+    pub objects: Vec<ObjectPosition>,
+    /// Rotation of the listener's head in degrees clockwise, e.g. from a head tracker
+    #[schemars(range(min = -180.0, max = 180.0), extend("tunable" = true))]
+    pub head_yaw: f32,
+    /// Radius of the modelled head, in centimetres
+    #[schemars(range(min = 5.0, max = 12.0), extend("tunable" = true))]
+    pub head_radius_cm: f32,
+    /// Output gain in dB; the default leaves headroom for summing surround channels
+    #[schemars(range(min = -24.0, max = 12.0), extend("tunable" = true))]
+    pub gain_db: f32,
+}
+
+impl Default for AudioBinauralConfig {
+    fn default() -> Self {
+        Self { objects: Vec::new(), head_yaw: 0.0, head_radius_cm: 8.75, gain_db: -3.0 }
+    }
+}
+
+impl AudioBinauralConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("head_yaw", self.head_yaw, -180.0, 180.0)?;
+        check("head_radius_cm", self.head_radius_cm, 5.0, 12.0)?;
+        check("gain_db", self.gain_db, -24.0, 12.0)?;
+        for object in &self.objects {
+            check("azimuth", object.azimuth, -180.0, 180.0)?;
+            check("elevation", object.elevation, -90.0, 90.0)?;
+        }
+        Ok(())
+    }
+
+    /// Applies a positions packet: listed objects replace those on the same channel.
+    fn apply_positions(&mut self, data: &serde_json::Value) -> Result<(), String> {
+        #[derive(Deserialize)]
+        struct Positions {
+            #[serde(default)]
+            objects: Vec<ObjectPosition>,
+            head_yaw: Option<f32>,
+        }
+        let positions: Positions =
+            serde_json::from_value(data.clone()).map_err(|e| e.to_string())?;
+        let mut updated = self.clone();
+        for object in positions.objects {
+            updated.objects.retain(|o| o.channel != object.channel);
+            updated.objects.push(object);
+        }
+        if let Some(head_yaw) = positions.head_yaw {
+            updated.head_yaw = head_yaw;
+        }
+        updated.validate()?;
+        *self = updated;
+        Ok(())
+    }
+}
+
+/// Delay and head-shadow filter from one source to one ear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct EarPath {
+    /// Delay in samples
+    delay: f32,
+    /// Head-shadow filter `y = b0 x + b1 x[-1] - a1 y[-1]`; `a1` only depends on the head size
+    b0: f32,
+    b1: f32,
+}
+
+/// Where a source sits and how loud it is, before the head model is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Placement {
+    azimuth: f32,
+    elevation: f32,
+    /// LFE channels feed both ears equally, without a position
+    lfe: bool,
+}
+
+/// Placement of every input channel: the layout's speakers, overridden by configured objects.
+#[allow(clippy::cast_precision_loss)] // Channel counts are tiny
+fn placements(channels: u16, objects: &[ObjectPosition]) -> Vec<Placement> {
+    let mut placements: Vec<Placement> = layout::speakers(channels).map_or_else(
+        // Without a known layout, spread the channels evenly around the listener.
+        || {
+            (0..channels)
+                .map(|ch| Placement {
+                    azimuth: f32::from(ch) * 360.0 / f32::from(channels) - 180.0,
+                    elevation: 0.0,
+                    lfe: false,
+                })
+                .collect()
+        },
+        |speakers| {
+            speakers
+                .iter()
+                .map(|s| Placement { azimuth: s.azimuth, elevation: s.elevation, lfe: s.lfe })
+                .collect()
+        },
+    );
+    for object in objects {
+        if let Some(placement) = placements.get_mut(usize::from(object.channel)) {
+            *placement =
+                Placement { azimuth: object.azimuth, elevation: object.elevation, lfe: false };
+        }
+    }
+    placements
+}
+
+/// Spherical-head model for one head size at one sample rate.
+struct HeadModel {
+    /// Head radius over the speed of sound, in samples
+    radius_delay: f32,
+    /// `2 * w0` and `2 * fs` of the bilinear-transformed shadow filter
+    two_w0: f32,
+    two_fs: f32,
+    a1: f32,
+}
+
+impl HeadModel {
+    #[allow(clippy::cast_precision_loss)] // Sample rates are far below f32's exact integer range
+    fn new(sample_rate: u32, radius_cm: f32) -> Self {
+        let radius = radius_cm / 100.0;
+        let rate = sample_rate as f32;
+        let two_w0 = 2.0 * SPEED_OF_SOUND / radius;
+        let two_fs = 2.0 * rate;
+        Self {
+            radius_delay: radius / SPEED_OF_SOUND * rate,
+            two_w0,
+            two_fs,
+            a1: (two_w0 - two_fs) / (two_w0 + two_fs),
+        }
+    }
+
+    /// Path to the ear at `ear_x` (-1 left, 1 right) from a source at `placement`.
+    fn path(&self, placement: Placement, head_yaw: f32, ear_x: f32) -> EarPath {
+        if placement.lfe {
+            return self.filter(0.0, 1.0);
+        }
+        let azimuth = (placement.azimuth - head_yaw).to_radians();
+        let elevation = placement.elevation.to_radians();
+        // Angle between the source and the ear's axis
+        let lateral = azimuth.sin() * elevation.cos();
+        let angle = (lateral * ear_x).clamp(-1.0, 1.0).acos();
+
+        let delay = if angle < FRAC_PI_2 {
+            self.radius_delay * (1.0 - angle.cos())
+        } else {
+            self.radius_delay * (1.0 + angle - FRAC_PI_2)
+        };
+        let alpha = (1.0 + SHADOW_MIN_ALPHA / 2.0)
+            + (1.0 - SHADOW_MIN_ALPHA / 2.0) * (angle / SHADOW_MIN_ANGLE * PI).cos();
+        self.filter(delay, alpha)
+    }
+
+    /// Shadow filter `(alpha s + 2 w0) / (s + 2 w0)`: unity at DC, `alpha` at high frequencies.
+    fn filter(&self, delay: f32, alpha: f32) -> EarPath {
+        let norm = self.two_w0 + self.two_fs;
+        EarPath {
+            delay,
+            b0: (self.two_w0 + alpha * self.two_fs) / norm,
+            b1: (self.two_w0 - alpha * self.two_fs) / norm,
+        }
+    }
+}
+
+/// Per-source state: recent input and both ears' filters.
+struct Source {
+    placement: Placement,
+    history: [f32; DELAY_LINE_LEN],
+    /// Left and right paths in use, and their filter memories (`x[-1]`, `y[-1]`)
+    paths: [EarPath; 2],
+    memory: [(f32, f32); 2],
+}
+
+/// Renders interleaved multichannel audio to interleaved stereo.
+struct Renderer {
+    config: AudioBinauralConfig,
+    format: Option<(u32, u16)>,
+    model: HeadModel,
+    sources: Vec<Source>,
+    write: usize,
+    gain: f32,
+}
+
+impl Renderer {
+    fn new(config: AudioBinauralConfig) -> Self {
+        Self {
+            model: HeadModel::new(48_000, config.head_radius_cm),
+            gain: 10f32.powf(config.gain_db / 20.0),
+            config,
+            format: None,
+            sources: Vec::new(),
+            write: 0,
+        }
+    }
+
+    fn set_config(&mut self, config: AudioBinauralConfig) {
+        self.gain = 10f32.powf(config.gain_db / 20.0);
+        self.config = config;
+        if let Some((sample_rate, channels)) = self.format {
+            self.model = HeadModel::new(sample_rate, self.config.head_radius_cm);
+            let placements = placements(channels, &self.config.objects);
+            for (source, placement) in self.sources.iter_mut().zip(placements) {
+                source.placement = placement;
+            }
+        }
+    }
+
+    /// Sets up the sources for a stream format, keeping their state when it doesn't change.
+    fn set_format(&mut self, sample_rate: u32, channels: u16) {
+        if self.format == Some((sample_rate, channels)) {
+            return;
+        }
+        self.format = Some((sample_rate, channels));
+        self.model = HeadModel::new(sample_rate, self.config.head_radius_cm);
+        let (model, yaw) = (&self.model, self.config.head_yaw);
+        self.sources = placements(channels, &self.config.objects)
+            .into_iter()
+            .map(|placement| Source {
+                placement,
+                history: [0.0; DELAY_LINE_LEN],
+                paths: [model.path(placement, yaw, -1.0), model.path(placement, yaw, 1.0)],
+                memory: [(0.0, 0.0); 2],
+            })
+            .collect();
+        self.write = 0;
+        tracing::debug!(
+            "Rendering {} ({} channels) at {} Hz binaurally",
+            layout::layout_name(channels),
+            channels,
+            sample_rate
+        );
+    }
+
+    /// Renders `samples` (interleaved, in the current format) to interleaved stereo.
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn render(&mut self, samples: &[f32]) -> Vec<f32> {
+        let channels = self.sources.len().max(1);
+        let frames = samples.len() / channels;
+        let mut output = vec![0.0; frames * 2];
+        let (model, yaw) = (&self.model, self.config.head_yaw);
+
+        for (ch, source) in self.sources.iter_mut().enumerate() {
+            let gain = if source.placement.lfe { FRAC_1_SQRT_2 } else { 1.0 } * self.gain;
+            let targets =
+                [model.path(source.placement, yaw, -1.0), model.path(source.placement, yaw, 1.0)];
+            let mut write = self.write;
+            for frame in 0..frames {
+                source.history[write] = samples[frame * channels + ch];
+                // Ramp from the old to the new paths across the frame.
+                let t = (frame + 1) as f32 / frames as f32;
+                for ear in 0..2 {
+                    let (from, to) = (source.paths[ear], targets[ear]);
+                    let delay = from.delay + (to.delay - from.delay) * t;
+                    let b0 = from.b0 + (to.b0 - from.b0) * t;
+                    let b1 = from.b1 + (to.b1 - from.b1) * t;
+
+                    let whole = delay as usize;
+                    let fraction = delay - whole as f32;
+                    let newer = source.history[(write + DELAY_LINE_LEN - whole) % DELAY_LINE_LEN];
+                    let older =
+                        source.history[(write + DELAY_LINE_LEN - whole - 1) % DELAY_LINE_LEN];
+                    let x = newer + (older - newer) * fraction;
+
+                    let (x1, y1) = source.memory[ear];
+                    let y = b0 * x + b1 * x1 - model.a1 * y1;
+                    source.memory[ear] = (x, y);
+                    output[frame * 2 + ear] += gain * y;
+                }
+                write = (write + 1) % DELAY_LINE_LEN;
+            }
+            source.paths = targets;
+        }
+        self.write = (self.write + frames) % DELAY_LINE_LEN;
+        output
+    }
+}
+
+/// A node that renders multichannel or object-based audio to binaural headphone stereo.
+///
+/// Surround layouts are rendered as virtual loudspeakers; individual channels can instead be
+/// placed (and moved) as objects. Intended for monitoring immersive mixes on headphones.
+pub struct AudioBinauralNode {
+    config: AudioBinauralConfig,
+}
+
+impl AudioBinauralNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioBinauralConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid binaural configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+fn positions_event(event: &CustomPacketData) -> Option<&serde_json::Value> {
+    (event.type_id == BINAURAL_POSITIONS_TYPE_ID).then_some(&event.data)
+}
+
+#[async_trait]
+impl ProcessorNode for AudioBinauralNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0, // Wildcard
+                    channels: 0,    // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "positions".to_string(),
+                accepts_types: vec![PacketType::Custom {
+                    type_id: BINAURAL_POSITIONS_TYPE_ID.to_string(),
+                }],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 2,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut positions_rx = context.inputs.remove("positions");
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut renderer = Renderer::new(self.config);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    if frame.sample_rate == 0 || frame.channels == 0 {
+                        stats.discarded();
+                        continue;
+                    }
+                    renderer.set_format(frame.sample_rate, frame.channels);
+                    let samples = renderer.render(frame.samples());
+                    let output =
+                        AudioFrame::with_metadata(frame.sample_rate, 2, samples, frame.metadata);
+                    if context.output_sender.send("out", Packet::Audio(output)).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                maybe_positions = async { positions_rx.as_mut()?.recv().await }, if positions_rx.is_some() => {
+                    match maybe_positions {
+                        Some(Packet::Custom(event)) => {
+                            let Some(data) = positions_event(&event) else { continue };
+                            let mut config = renderer.config.clone();
+                            match config.apply_positions(data) {
+                                Ok(()) => renderer.set_config(config),
+                                Err(e) => {
+                                    tracing::warn!("Rejected binaural positions: {}", e);
+                                    stats.errored();
+                                }
+                            }
+                        }
+                        Some(_) => {}
+                        None => positions_rx = None,
+                    }
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&renderer.config, &params).and_then(|config: AudioBinauralConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => renderer.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected binaural update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::CustomEncoding;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    /// Renders an impulse on channel `ch` of `channels` and returns the left and right ears.
+    fn impulse_response(renderer: &mut Renderer, channels: u16, ch: usize) -> [Vec<f32>; 2] {
+        renderer.set_format(RATE, channels);
+        let mut input = vec![0.0; 128 * usize::from(channels)];
+        input[ch] = 1.0;
+        let output = renderer.render(&input);
+        [
+            output.iter().step_by(2).copied().collect(),
+            output.iter().skip(1).step_by(2).copied().collect(),
+        ]
+    }
+
+    fn peak_index(samples: &[f32]) -> usize {
+        (0..samples.len()).max_by(|&a, &b| samples[a].abs().total_cmp(&samples[b].abs())).unwrap()
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    fn object(channel: u16, azimuth: f32) -> ObjectPosition {
+        ObjectPosition { channel, azimuth, elevation: 0.0 }
+    }
+
+    #[test]
+    fn test_source_on_the_right_reaches_the_left_ear_later_and_darker() {
+        let config = AudioBinauralConfig { objects: vec![object(0, 90.0)], ..Default::default() };
+        let mut renderer = Renderer::new(config);
+        let [left, right] = impulse_response(&mut renderer, 1, 0);
+
+        // Around the head: 8.75 cm / 343 m/s * (1 + pi/2) is about 31.5 samples at 48 kHz.
+        assert_eq!(peak_index(&right), 0);
+        let delay = peak_index(&left);
+        assert!((30..=33).contains(&delay), "got {delay}");
+        assert!(energy(&right) > 2.0 * energy(&left));
+    }
+
+    #[test]
+    fn test_centre_and_lfe_are_identical_in_both_ears() {
+        let mut renderer = Renderer::new(AudioBinauralConfig::default());
+        let [left, right] = impulse_response(&mut renderer, 1, 0);
+        assert_eq!(left, right);
+
+        // 5.1: the LFE feeds both ears alike, the left surround favours the left ear.
+        let mut renderer = Renderer::new(AudioBinauralConfig::default());
+        let [left, right] = impulse_response(&mut renderer, 6, 3);
+        assert_eq!(left, right);
+        let mut renderer = Renderer::new(AudioBinauralConfig::default());
+        let [left, right] = impulse_response(&mut renderer, 6, 4);
+        assert!(energy(&left) > energy(&right));
+        assert!(peak_index(&left) < peak_index(&right));
+    }
+
+    #[test]
+    fn test_head_yaw_rotates_the_scene() {
+        // Turning 90 degrees to the left puts a frontal source on the right.
+        let config = AudioBinauralConfig { head_yaw: -90.0, ..Default::default() };
+        let mut renderer = Renderer::new(config);
+        let [left, right] = impulse_response(&mut renderer, 1, 0);
+        assert!(peak_index(&left) > peak_index(&right));
+    }
+
+    #[test]
+    fn test_positions_packets_merge_objects() {
+        let mut config =
+            AudioBinauralConfig { objects: vec![object(0, 10.0)], ..Default::default() };
+        config
+            .apply_positions(&serde_json::json!({
+                "objects": [{"channel": 0, "azimuth": -45.0}, {"channel": 1, "azimuth": 45.0}],
+                "head_yaw": 20.0
+            }))
+            .unwrap();
+        assert_eq!(config.objects, vec![object(0, -45.0), object(1, 45.0)]);
+        assert!((config.head_yaw - 20.0).abs() < f32::EPSILON);
+
+        // Invalid positions leave the configuration untouched.
+        let before = config.clone();
+        assert!(config
+            .apply_positions(&serde_json::json!({"objects": [{"channel": 0, "azimuth": 270.0}]}))
+            .is_err());
+        assert_eq!(config, before);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioBinauralConfig::default().validate().is_ok());
+        assert!(AudioBinauralConfig { head_radius_cm: 2.0, ..Default::default() }
+            .validate()
+            .is_err());
+        let updated: AudioBinauralConfig =
+            merge_params(&AudioBinauralConfig::default(), &serde_json::json!({"gain_db": 0.0}))
+                .unwrap();
+        assert!(updated.gain_db.abs() < f32::EPSILON);
+        assert!(
+            AudioBinauralNode::factory()(Some(&serde_json::json!({"head_yaw": 400.0}))).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_node_renders_stereo_and_follows_positions() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let (positions_tx, positions_rx) = mpsc::channel(10);
+        let inputs =
+            HashMap::from([("in".to_string(), input_rx), ("positions".to_string(), positions_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioBinauralNode::factory()(Some(&serde_json::json!({
+            "objects": [{"channel": 0, "azimuth": -90.0}]
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let tone: Vec<f32> =
+            (0..960).map(|i| (2.0 * PI * 2000.0 * i as f32 / RATE as f32).sin() * 0.5).collect();
+        input_tx.send(Packet::Audio(AudioFrame::new(RATE, 1, tone.clone()))).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        positions_tx
+            .send(Packet::Custom(Arc::new(CustomPacketData {
+                type_id: BINAURAL_POSITIONS_TYPE_ID.to_string(),
+                encoding: CustomEncoding::Json,
+                data: serde_json::json!({"objects": [{"channel": 0, "azimuth": 90.0}]}),
+                metadata: None,
+            })))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        // One frame ramps to the new position, the next is rendered there.
+        for _ in 0..2 {
+            input_tx.send(Packet::Audio(AudioFrame::new(RATE, 1, tone.clone()))).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 3);
+        let ears = |packet: &Packet| {
+            assert!(matches!(packet, Packet::Audio(frame) if frame.channels == 2));
+            let samples = extract_audio_data(packet).unwrap();
+            let left: Vec<f32> = samples.iter().step_by(2).copied().collect();
+            let right: Vec<f32> = samples.iter().skip(1).step_by(2).copied().collect();
+            (energy(&left), energy(&right))
+        };
+        let (left, right) = ears(&packets[0]);
+        assert!(left > 2.0 * right, "{left} vs {right}");
+        let (left, right) = ears(&packets[2]);
+        assert!(right > 2.0 * left, "{left} vs {right}");
+    }
+}
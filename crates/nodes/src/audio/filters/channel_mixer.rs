@@ -0,0 +1,349 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Channel mixer node - Up/downmixing and channel routing
+//!
+//! Without a `matrix` the input is converted to `channels` channels between the standard
+//! layouts implied by the channel counts (see [`crate::audio::layout`]): stereo to mono
+//! averages both sides, mono to stereo duplicates, 5.1 to stereo follows ITU-R BS.775.
+//! With a `matrix` every output channel is the weighted sum of the input channels given by
+//! its row, which covers swapping or picking channels, custom downmixes and phase flips.
+
+use crate::audio::layout::{layout_name, Remix};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Most channels accepted on either side of the mixer.
+const MAX_CHANNELS: usize = 32;
+
+/// Configuration for the AudioChannelMixerNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioChannelMixerConfig {
+    /// Number of output channels, up- or downmixed from whatever layout arrives.
+    /// Ignored when `matrix` is set.
+    #[schemars(range(min = 1, max = 32))]
+    pub channels: u16,
+    /// Mixing coefficients: one row per output channel, each with one linear gain per input
+    /// channel, e.g. `[[0.5, 0.5]]` for stereo to mono or `[[0, 1], [1, 0]]` to swap left and
+    /// right. Input with a different channel count than the rows is dropped. Can be changed
+    /// while running as long as the number of rows stays the same.
+    #[schemars(extend("tunable" = true))]
+    pub matrix: Option<Vec<Vec<f32>>>,
+}
+
+impl Default for AudioChannelMixerConfig {
+    fn default() -> Self {
+        Self { channels: 2, matrix: None }
+    }
+}
+
+impl AudioChannelMixerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_CHANNELS).contains(&usize::from(self.channels)) {
+            return Err(format!("channels must be within 1-{MAX_CHANNELS}, got {}", self.channels));
+        }
+        let Some(matrix) = &self.matrix else { return Ok(()) };
+        if matrix.is_empty() || matrix.len() > MAX_CHANNELS {
+            return Err(format!("matrix must have 1-{MAX_CHANNELS} rows, got {}", matrix.len()));
+        }
+        let inputs = matrix[0].len();
+        if inputs == 0 || inputs > MAX_CHANNELS {
+            return Err(format!("matrix rows must have 1-{MAX_CHANNELS} columns, got {inputs}"));
+        }
+        for (index, row) in matrix.iter().enumerate() {
+            if row.len() != inputs {
+                return Err(format!(
+                    "matrix row {index} has {} columns, expected {inputs}",
+                    row.len()
+                ));
+            }
+            if row.iter().any(|gain| !gain.is_finite()) {
+                return Err(format!("matrix row {index} contains a non-finite coefficient"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of channels the node produces.
+    #[allow(clippy::cast_possible_truncation)] // Validated to at most MAX_CHANNELS rows
+    fn output_channels(&self) -> u16 {
+        self.matrix.as_ref().map_or(self.channels, |matrix| matrix.len() as u16)
+    }
+
+    /// The remix for input with `input_channels` channels, or `None` if the matrix doesn't
+    /// fit it.
+    fn remix(&self, input_channels: u16) -> Option<Remix> {
+        self.matrix.as_ref().map_or_else(
+            || Some(Remix::new(input_channels, self.channels)),
+            |matrix| {
+                let remix = Remix::from_matrix(matrix);
+                (remix.inputs() == usize::from(input_channels)).then_some(remix)
+            },
+        )
+    }
+}
+
+/// A node that converts raw audio between channel layouts or routes channels through an
+/// explicit mixing matrix.
+///
+/// The output pin advertises the configured channel count, so e.g. a mono-only speech
+/// recognizer can be fed from a stereo source without going through the resampler.
+pub struct AudioChannelMixerNode {
+    config: AudioChannelMixerConfig,
+}
+
+impl AudioChannelMixerNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioChannelMixerConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid channel mixer configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioChannelMixerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: self.config.output_channels(),
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut config = self.config;
+        let output_channels = config.output_channels();
+        // Remix for the most recent input channel count; `None` while the matrix doesn't fit.
+        let mut current: Option<(u16, Option<Remix>)> = None;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    if frame.channels == 0 {
+                        stats.discarded();
+                        continue;
+                    }
+                    if current.as_ref().is_none_or(|(channels, _)| *channels != frame.channels) {
+                        let remix = config.remix(frame.channels);
+                        if remix.is_none() {
+                            tracing::warn!(
+                                "Dropping {} ({}-channel) audio: the matrix expects {} input channels",
+                                layout_name(frame.channels),
+                                frame.channels,
+                                config.matrix.as_ref().map_or(0, |m| m[0].len())
+                            );
+                        }
+                        current = Some((frame.channels, remix));
+                    }
+                    let Some((_, Some(remix))) = &current else {
+                        stats.discarded();
+                        continue;
+                    };
+
+                    let output = if remix.is_identity() {
+                        frame
+                    } else {
+                        let samples = remix.apply(frame.samples());
+                        AudioFrame::with_metadata(
+                            frame.sample_rate,
+                            output_channels,
+                            samples,
+                            frame.metadata,
+                        )
+                    };
+                    if context.output_sender.send("out", Packet::Audio(output)).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        let update = merge_params(&config, &params).and_then(|updated: AudioChannelMixerConfig| {
+                            updated.validate()?;
+                            if updated.output_channels() != output_channels {
+                                return Err(format!(
+                                    "the output channel count can't change while running ({output_channels} to {})",
+                                    updated.output_channels()
+                                ));
+                            }
+                            Ok(updated)
+                        });
+                        match update {
+                            Ok(updated) => {
+                                config = updated;
+                                current = None;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected channel mixer update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    fn config(params: serde_json::Value) -> AudioChannelMixerConfig {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn test_layout_conversions() {
+        let mono = config(serde_json::json!({"channels": 1}));
+        assert_eq!(mono.output_channels(), 1);
+        assert_eq!(mono.remix(2).unwrap().apply(&[0.2, 0.4, -1.0, 1.0]), vec![0.3, 0.0]);
+
+        let stereo = AudioChannelMixerConfig::default();
+        assert_eq!(stereo.remix(1).unwrap().apply(&[0.5]), vec![0.5, 0.5]);
+        assert!(stereo.remix(2).unwrap().is_identity());
+    }
+
+    #[test]
+    fn test_matrix_routing() {
+        let swap = config(serde_json::json!({"matrix": [[0.0, 1.0], [1.0, 0.0]]}));
+        assert_eq!(swap.output_channels(), 2);
+        assert_eq!(swap.remix(2).unwrap().apply(&[0.1, 0.2]), vec![0.2, 0.1]);
+        assert!(swap.remix(1).is_none());
+
+        // Only the first channel of a 5.1 stream, at half gain.
+        let pick = config(serde_json::json!({"channels": 6, "matrix": [[0.5, 0, 0, 0, 0, 0]]}));
+        assert_eq!(pick.output_channels(), 1);
+        assert_eq!(pick.remix(6).unwrap().apply(&[0.8, 1.0, 1.0, 1.0, 1.0, 1.0]), vec![0.4]);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioChannelMixerConfig::default().validate().is_ok());
+        assert!(config(serde_json::json!({"channels": 0})).validate().is_err());
+        assert!(config(serde_json::json!({"matrix": []})).validate().is_err());
+        assert!(config(serde_json::json!({"matrix": [[1.0, 0.0], [1.0]]})).validate().is_err());
+
+        let current = config(serde_json::json!({"matrix": [[1.0, 0.0]]}));
+        let updated: AudioChannelMixerConfig =
+            merge_params(&current, &serde_json::json!({"matrix": [[0.0, 1.0]]})).unwrap();
+        assert_eq!(updated.matrix, Some(vec![vec![0.0, 1.0]]));
+        assert_eq!(updated.channels, 2);
+
+        let factory = AudioChannelMixerNode::factory();
+        assert!(factory(Some(&serde_json::json!({"channels": 64}))).is_err());
+        let node = factory(Some(&serde_json::json!({"channels": 1}))).unwrap();
+        assert_eq!(
+            node.output_pins()[0].produces_type,
+            PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 1,
+                sample_format: SampleFormat::F32
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_node_downmixes_and_rejects_channel_count_changes() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let node =
+            AudioChannelMixerNode::factory()(Some(&serde_json::json!({"channels": 1}))).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let frame = AudioFrame::new(48_000, 2, vec![0.2, 0.4, 0.6, 0.8]);
+        input_tx.send(Packet::Audio(frame)).await.unwrap();
+        // Mono input already matches and passes through untouched.
+        input_tx.send(Packet::Audio(AudioFrame::new(48_000, 1, vec![0.7]))).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        // Switching to a matrix keeps mono output; going to stereo is refused.
+        let update = serde_json::json!({"matrix": [[0.0, 1.0]]});
+        control_tx.send(NodeControlMessage::UpdateParams(update)).await.unwrap();
+        let update = serde_json::json!({"channels": 2, "matrix": null});
+        control_tx.send(NodeControlMessage::UpdateParams(update)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let frame = AudioFrame::new(48_000, 2, vec![0.2, 0.4]);
+        input_tx.send(Packet::Audio(frame)).await.unwrap();
+        // Mono no longer fits the two-column matrix and is dropped.
+        input_tx.send(Packet::Audio(AudioFrame::new(48_000, 1, vec![0.7]))).await.unwrap();
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let outputs: Vec<Vec<f32>> =
+            packets.iter().map(|p| extract_audio_data(p).unwrap().to_vec()).collect();
+        assert_eq!(outputs.len(), 3);
+        assert!((outputs[0][0] - 0.3).abs() < 1e-6 && (outputs[0][1] - 0.7).abs() < 1e-6);
+        assert_eq!(outputs[1], vec![0.7]);
+        assert_eq!(outputs[2], vec![0.4]);
+    }
+}
@@ -0,0 +1,333 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compliance beep node - Mixes a periodic notification tone into audio
+//!
+//! Many jurisdictions require an audible tone at regular intervals while a call is being
+//! recorded (e.g. 1400 Hz every 15 seconds). The beep is scheduled on the stream's sample
+//! count, so its cadence is exact regardless of how frames are sized or delivered.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Fade applied to each end of the beep to avoid clicks.
+const RAMP_MS: u64 = 5;
+
+/// Configuration for the ComplianceBeepNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ComplianceBeepConfig {
+    /// Time between the start of consecutive beeps in milliseconds
+    #[schemars(range(min = 1000), extend("tunable" = true))]
+    pub interval_ms: u64,
+    /// Length of each beep in milliseconds
+    #[schemars(range(min = 20))]
+    pub duration_ms: u64,
+    /// Beep frequency in Hz
+    #[schemars(range(min = 100.0, max = 8000.0))]
+    pub frequency_hz: f32,
+    /// Beep level in dBFS
+    #[schemars(range(max = 0.0), extend("tunable" = true))]
+    pub level_db: f32,
+    /// Beep at the start of the stream instead of after the first interval
+    pub beep_at_start: bool,
+}
+
+impl Default for ComplianceBeepConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 15_000,
+            duration_ms: 200,
+            frequency_hz: 1400.0,
+            level_db: -20.0,
+            beep_at_start: true,
+        }
+    }
+}
+
+impl ComplianceBeepConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.interval_ms < 1000 {
+            return Err(format!("interval_ms must be at least 1000, got {}", self.interval_ms));
+        }
+        if self.duration_ms < 20 || self.duration_ms >= self.interval_ms {
+            return Err(format!(
+                "duration_ms must be at least 20 and shorter than interval_ms, got {}",
+                self.duration_ms
+            ));
+        }
+        if !(100.0..=8000.0).contains(&self.frequency_hz) {
+            return Err(format!("frequency_hz must be within 100-8000, got {}", self.frequency_hz));
+        }
+        if !self.level_db.is_finite() || self.level_db > 0.0 {
+            return Err(format!("level_db must be at most 0, got {}", self.level_db));
+        }
+        Ok(())
+    }
+}
+
+/// Beep schedule and synthesis over interleaved frames.
+struct BeepInjector {
+    config: ComplianceBeepConfig,
+    sample_rate: u32,
+    /// Frames processed since the stream started
+    position: u64,
+}
+
+impl BeepInjector {
+    const fn new(config: ComplianceBeepConfig, sample_rate: u32) -> Self {
+        Self { config, sample_rate, position: 0 }
+    }
+
+    fn frames_for_ms(&self, ms: u64) -> u64 {
+        ms * u64::from(self.sample_rate) / 1000
+    }
+
+    /// Keeps the stream position in time when the input sample rate changes.
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate && self.sample_rate > 0 {
+            self.position = self.position * u64::from(sample_rate) / u64::from(self.sample_rate);
+        }
+        self.sample_rate = sample_rate;
+    }
+
+    /// Offset into the beep at stream frame `position`, if a beep is sounding there.
+    fn beep_offset(&self, position: u64, interval: u64, length: u64) -> Option<u64> {
+        let first = if self.config.beep_at_start { 0 } else { interval };
+        let since_first = position.checked_sub(first)?;
+        let offset = since_first % interval;
+        (offset < length).then_some(offset)
+    }
+
+    /// Mixes the beep into `samples`, returning the stream positions (in ms) of beeps that
+    /// start within this block.
+    // Beep offsets are at most a few thousand frames, well within f32's exact integer range.
+    #[allow(clippy::cast_precision_loss)]
+    fn process(&mut self, samples: &mut [f32], channels: usize) -> Vec<u64> {
+        let interval = self.frames_for_ms(self.config.interval_ms).max(1);
+        let length = self.frames_for_ms(self.config.duration_ms);
+        let ramp = self.frames_for_ms(RAMP_MS).clamp(1, (length / 2).max(1));
+        let amplitude = 10f32.powf(self.config.level_db / 20.0);
+        let step = TAU * self.config.frequency_hz / self.sample_rate as f32;
+
+        let mut started = Vec::new();
+        for (i, frame) in samples.chunks_mut(channels.max(1)).enumerate() {
+            let position = self.position + i as u64;
+            let Some(offset) = self.beep_offset(position, interval, length) else {
+                continue;
+            };
+            if offset == 0 {
+                started.push(position * 1000 / u64::from(self.sample_rate));
+            }
+            let edge = offset.min(length - 1 - offset);
+            let envelope = if edge < ramp { edge as f32 / ramp as f32 } else { 1.0 };
+            let value = amplitude * envelope * (step * offset as f32).sin();
+            for sample in frame {
+                *sample = (*sample + value).clamp(-1.0, 1.0);
+            }
+        }
+        self.position += (samples.len() / channels.max(1)) as u64;
+        started
+    }
+}
+
+/// A node that mixes a periodic tone into passing audio to signal that a call is recorded.
+///
+/// Audio passes through unchanged apart from the beep, which is added on every channel every
+/// `interval_ms`, with short fades at each end. Each beep emits a `compliance.beep` telemetry
+/// event with its stream position, so recordings can be audited for the notification.
+/// `interval_ms` and `level_db` can be tuned while running.
+pub struct ComplianceBeepNode {
+    config: ComplianceBeepConfig,
+}
+
+impl ComplianceBeepNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: ComplianceBeepConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid compliance beep configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn apply_update(
+        config: &mut ComplianceBeepConfig,
+        params: &serde_json::Value,
+    ) -> Result<(), String> {
+        let mut updated = config.clone();
+        if let Some(interval) = params.get("interval_ms").and_then(serde_json::Value::as_u64) {
+            updated.interval_ms = interval;
+        }
+        if let Some(level) = params.get("level_db").and_then(serde_json::Value::as_f64) {
+            #[allow(clippy::cast_possible_truncation)] // dB levels fit comfortably in f32
+            let level = level as f32;
+            updated.level_db = level;
+        }
+        updated.validate()?;
+        *config = updated;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for ComplianceBeepNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut injector = BeepInjector::new(self.config, 0);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        injector.set_sample_rate(frame.sample_rate);
+                        let channels = usize::from(frame.channels);
+                        for position_ms in injector.process(frame.make_samples_mut(), channels) {
+                            telemetry.emit(
+                                "compliance.beep",
+                                serde_json::json!({ "position_ms": position_ms }),
+                            );
+                        }
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        if let Err(e) = Self::apply_update(&mut injector.config, &params) {
+                            tracing::warn!("Rejected compliance beep update: {}", e);
+                            stats.errored();
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test assertions
+mod tests {
+    use super::*;
+
+    fn run(config: ComplianceBeepConfig, seconds: usize) -> Vec<f32> {
+        let mut injector = BeepInjector::new(config, 8000);
+        let mut out = Vec::new();
+        // 20 ms stereo frames
+        for _ in 0..seconds * 50 {
+            let mut frame = vec![0.0; 320];
+            injector.process(&mut frame, 2);
+            out.extend(frame);
+        }
+        out
+    }
+
+    fn beeping(samples: &[f32], from_ms: usize, to_ms: usize) -> bool {
+        samples[from_ms * 16..to_ms * 16].iter().any(|s| s.abs() > 1e-4)
+    }
+
+    #[test]
+    fn test_beep_cadence() {
+        let config = ComplianceBeepConfig { interval_ms: 1000, ..Default::default() };
+        let out = run(config, 3);
+        for second in 0..3 {
+            let start = second * 1000;
+            assert!(beeping(&out, start, start + 200), "beep expected at {start} ms");
+            assert!(!beeping(&out, start + 200, start + 1000), "silence expected after {start} ms");
+        }
+        // Both channels carry the same tone
+        assert!(out.chunks(2).all(|f| (f[0] - f[1]).abs() < f32::EPSILON));
+
+        let delayed =
+            ComplianceBeepConfig { interval_ms: 1000, beep_at_start: false, ..Default::default() };
+        let out = run(delayed, 2);
+        assert!(!beeping(&out, 0, 1000));
+        assert!(beeping(&out, 1000, 1200));
+    }
+
+    #[test]
+    fn test_beep_is_mixed_and_reports_start() {
+        let mut injector = BeepInjector::new(ComplianceBeepConfig::default(), 8000);
+        let mut frame = vec![0.25; 1600];
+        assert_eq!(injector.process(&mut frame, 1), vec![0]);
+        // Faded in from the underlying signal, peaking at -20 dBFS above it
+        assert!((frame[0] - 0.25).abs() < f32::EPSILON);
+        let peak = frame.iter().map(|s| (s - 0.25).abs()).fold(0.0, f32::max);
+        assert!((peak - 0.1).abs() < 0.005, "peak {peak}");
+        assert!(injector.process(&mut frame, 1).is_empty());
+    }
+
+    #[test]
+    fn test_update_validation() {
+        let mut config = ComplianceBeepConfig::default();
+        ComplianceBeepNode::apply_update(&mut config, &serde_json::json!({"level_db": -12.0}))
+            .unwrap();
+        assert!((config.level_db + 12.0).abs() < f32::EPSILON);
+        assert!(ComplianceBeepNode::apply_update(
+            &mut config,
+            &serde_json::json!({"interval_ms": 100})
+        )
+        .is_err());
+        assert_eq!(config.interval_ms, 15_000);
+    }
+}
@@ -0,0 +1,517 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compressor node - Dynamic range compression with an optional sidechain
+//!
+//! A feed-forward, stereo-linked peak compressor. Levels above `threshold_db` are reduced by
+//! `ratio`, with gain reduction smoothed in the dB domain using separate attack and release
+//! times. When the `sidechain` pin is connected its level drives the gain reduction instead of
+//! the input's own, so one stream (e.g. a TTS voice) can duck another (e.g. background music).
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Most sidechain audio kept waiting for the input to catch up, in seconds.
+const MAX_SIDECHAIN_BACKLOG_S: usize = 5;
+/// Levels below this are treated as silence by the level detector.
+const SILENCE_DB: f32 = -120.0;
+
+/// Configuration for the AudioCompressorNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioCompressorConfig {
+    /// Level above which gain reduction starts, in dBFS
+    #[schemars(range(min = -60.0, max = 0.0), extend("tunable" = true))]
+    pub threshold_db: f32,
+    /// Input-to-output ratio above the threshold (e.g. 4 turns 8 dB over into 2 dB over)
+    #[schemars(range(min = 1.0, max = 20.0), extend("tunable" = true))]
+    pub ratio: f32,
+    /// Time for gain reduction to take effect, in milliseconds
+    #[schemars(range(min = 0.1, max = 500.0), extend("tunable" = true))]
+    pub attack_ms: f32,
+    /// Time for gain reduction to recover once the level drops, in milliseconds
+    #[schemars(range(min = 1.0, max = 5000.0), extend("tunable" = true))]
+    pub release_ms: f32,
+    /// Gain applied after compression, in dB
+    #[schemars(range(min = -24.0, max = 24.0), extend("tunable" = true))]
+    pub makeup_gain_db: f32,
+}
+
+impl Default for AudioCompressorConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: -20.0,
+            ratio: 4.0,
+            attack_ms: 10.0,
+            release_ms: 200.0,
+            makeup_gain_db: 0.0,
+        }
+    }
+}
+
+impl AudioCompressorConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("threshold_db", self.threshold_db, -60.0, 0.0)?;
+        check("ratio", self.ratio, 1.0, 20.0)?;
+        check("attack_ms", self.attack_ms, 0.1, 500.0)?;
+        check("release_ms", self.release_ms, 1.0, 5000.0)?;
+        check("makeup_gain_db", self.makeup_gain_db, -24.0, 24.0)
+    }
+}
+
+pub(super) fn level_db(peak: f32) -> f32 {
+    if peak > 0.0 {
+        (20.0 * peak.log10()).max(SILENCE_DB)
+    } else {
+        SILENCE_DB
+    }
+}
+
+/// Smoothing coefficient of a one-pole filter with time constant `ms` at `sample_rate`.
+#[allow(clippy::cast_precision_loss)] // Sample rates are far below f32's exact integer range
+pub(super) fn time_coefficient(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms * 0.001 * sample_rate as f32)).exp()
+}
+
+/// Sidechain peaks waiting to be matched with input frames.
+#[derive(Default)]
+pub(super) struct Sidechain {
+    /// Per-frame peak of the sidechain, oldest first
+    levels: VecDeque<f32>,
+    sample_rate: u32,
+    /// Sidechain frames owed to the input but not yet consumed, scaled by the input rate
+    owed: u64,
+    /// Level for the input frame being processed
+    current: f32,
+}
+
+impl Sidechain {
+    pub(super) fn push(&mut self, sample_rate: u32, channels: usize, samples: &[f32]) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.levels.clear();
+            self.owed = 0;
+        }
+        self.levels.extend(
+            samples
+                .chunks(channels.max(1))
+                .map(|frame| frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()))),
+        );
+        let max_backlog = MAX_SIDECHAIN_BACKLOG_S * sample_rate as usize;
+        if self.levels.len() > max_backlog {
+            let excess = self.levels.len() - max_backlog;
+            self.levels.drain(..excess);
+        }
+    }
+
+    /// Advances by one input frame at `sample_rate` and returns the sidechain level for it.
+    ///
+    /// Sidechain audio that hasn't arrived yet counts as silence.
+    pub(super) fn next(&mut self, sample_rate: u32) -> f32 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.owed += u64::from(self.sample_rate);
+        while self.owed >= u64::from(sample_rate) {
+            self.owed -= u64::from(sample_rate);
+            self.current = self.levels.pop_front().unwrap_or(0.0);
+        }
+        self.current
+    }
+}
+
+/// Gain computer and smoothing state for one stream.
+struct Compressor {
+    config: AudioCompressorConfig,
+    sample_rate: u32,
+    attack: f32,
+    release: f32,
+    /// Smoothed gain reduction in dB (zero or positive)
+    reduction_db: f32,
+}
+
+impl Compressor {
+    fn new(config: AudioCompressorConfig) -> Self {
+        let mut compressor =
+            Self { config, sample_rate: 0, attack: 0.0, release: 0.0, reduction_db: 0.0 };
+        compressor.update_coefficients();
+        compressor
+    }
+
+    fn set_config(&mut self, config: AudioCompressorConfig) {
+        self.config = config;
+        self.update_coefficients();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.update_coefficients();
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        if self.sample_rate > 0 {
+            self.attack = time_coefficient(self.config.attack_ms, self.sample_rate);
+            self.release = time_coefficient(self.config.release_ms, self.sample_rate);
+        }
+    }
+
+    /// Compresses interleaved `samples` in place. Without a `sidechain` the input's own
+    /// level drives the gain reduction.
+    fn process(
+        &mut self,
+        samples: &mut [f32],
+        channels: usize,
+        mut sidechain: Option<&mut Sidechain>,
+    ) {
+        let slope = 1.0 - 1.0 / self.config.ratio;
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let key = match sidechain.as_deref_mut() {
+                Some(sidechain) => sidechain.next(self.sample_rate),
+                None => frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs())),
+            };
+            let target = (level_db(key) - self.config.threshold_db).max(0.0) * slope;
+            let coefficient = if target > self.reduction_db { self.attack } else { self.release };
+            self.reduction_db = target + coefficient * (self.reduction_db - target);
+
+            let gain = 10f32.powf((self.config.makeup_gain_db - self.reduction_db) / 20.0);
+            for sample in frame {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+/// A node that compresses the dynamic range of raw audio, optionally keyed by a sidechain.
+///
+/// Audio on `in` is compressed and sent to `out`. The optional `sidechain` pin takes another
+/// audio stream whose level decides how much `in` is turned down, which is how music is ducked
+/// under a voice. Sidechain audio is consumed in step with `in` (one sidechain second per
+/// input second), so both streams should run at the same pace; sidechain audio that hasn't
+/// arrived yet counts as silence. All parameters can be tuned while running.
+pub struct AudioCompressorNode {
+    config: AudioCompressorConfig,
+}
+
+impl AudioCompressorNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioCompressorConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid compressor configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn audio_pin(name: &str) -> InputPin {
+        InputPin {
+            name: name.to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioCompressorNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![Self::audio_pin("in"), Self::audio_pin("sidechain")]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        // The sidechain pin is optional; without it the input compresses itself.
+        let mut sidechain_rx = context.inputs.remove("sidechain");
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut compressor = Compressor::new(self.config);
+        let mut sidechain = sidechain_rx.is_some().then(Sidechain::default);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        compressor.set_sample_rate(frame.sample_rate);
+                        let channels = usize::from(frame.channels);
+                        // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                        compressor.process(frame.make_samples_mut(), channels, sidechain.as_mut());
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                maybe_key = async { sidechain_rx.as_mut()?.recv().await }, if sidechain_rx.is_some() => {
+                    match (maybe_key, sidechain.as_mut()) {
+                        (Some(Packet::Audio(frame)), Some(sidechain)) if frame.sample_rate > 0 => {
+                            sidechain.push(frame.sample_rate, usize::from(frame.channels), frame.samples());
+                        }
+                        (Some(_), _) => {}
+                        // Once the sidechain ends, its remaining audio still keys the input.
+                        (None, _) => sidechain_rx = None,
+                    }
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&compressor.config, &params).and_then(|config: AudioCompressorConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => compressor.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected compressor update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    fn config(threshold_db: f32, ratio: f32) -> AudioCompressorConfig {
+        AudioCompressorConfig {
+            threshold_db,
+            ratio,
+            attack_ms: 1.0,
+            release_ms: 50.0,
+            makeup_gain_db: 0.0,
+        }
+    }
+
+    /// Peak of the last `tail` samples after running `level` DC through `compressor`.
+    fn settle(compressor: &mut Compressor, level: f32, sidechain: Option<&mut Sidechain>) -> f32 {
+        compressor.set_sample_rate(RATE);
+        let mut samples = vec![level; RATE as usize / 2];
+        compressor.process(&mut samples, 1, sidechain);
+        samples[samples.len() - 1]
+    }
+
+    #[test]
+    fn test_levels_above_threshold_are_reduced_by_ratio() {
+        let mut compressor = Compressor::new(config(-20.0, 4.0));
+        // 0 dBFS is 20 dB over; at 4:1 it should come out 5 dB over, at -15 dBFS.
+        let out = settle(&mut compressor, 1.0, None);
+        assert!((level_db(out) + 15.0).abs() < 0.1, "got {} dB", level_db(out));
+
+        // Below the threshold nothing changes once the reduction has released.
+        let out = settle(&mut compressor, 0.05, None);
+        assert!((out - 0.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_makeup_gain_and_unity_ratio() {
+        let mut compressor =
+            Compressor::new(AudioCompressorConfig { makeup_gain_db: 6.0, ..config(-20.0, 1.0) });
+        let out = settle(&mut compressor, 0.5, None);
+        assert!((out - 0.9975).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_attack_and_release_smooth_gain_changes() {
+        let mut compressor = Compressor::new(AudioCompressorConfig {
+            attack_ms: 10.0,
+            release_ms: 100.0,
+            ..config(-20.0, 20.0)
+        });
+        compressor.set_sample_rate(RATE);
+        let mut burst = vec![1.0_f32; 480]; // 10 ms
+        compressor.process(&mut burst, 1, None);
+        // After one attack time constant roughly 63% of the reduction is applied.
+        assert!(burst[0] > 0.9);
+        let reduced = level_db(burst[479]);
+        assert!(reduced < -8.0 && reduced > -16.0, "got {reduced} dB");
+
+        // A quiet tail recovers gradually rather than jumping back to unity.
+        let mut tail = vec![0.01_f32; 480];
+        compressor.process(&mut tail, 1, None);
+        assert!(tail[479] < 0.01 * 0.95);
+    }
+
+    #[test]
+    fn test_sidechain_ducks_input() {
+        let mut compressor = Compressor::new(config(-30.0, 10.0));
+        let mut sidechain = Sidechain::default();
+
+        // Quiet sidechain: music passes untouched even though it is above the threshold.
+        sidechain.push(RATE, 1, &vec![0.0; RATE as usize / 2]);
+        let out = settle(&mut compressor, 0.5, Some(&mut sidechain));
+        assert!((out - 0.5).abs() < 1e-4);
+
+        // Loud sidechain (at a different rate and channel count) ducks it.
+        sidechain.push(16_000, 2, &vec![0.5; 16_000]);
+        let out = settle(&mut compressor, 0.5, Some(&mut sidechain));
+        assert!(level_db(out) < level_db(0.5) - 20.0, "got {} dB", level_db(out));
+
+        // Once the sidechain runs dry the input recovers.
+        let out = settle(&mut compressor, 0.5, Some(&mut sidechain));
+        assert!((out - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sidechain_backlog_is_bounded() {
+        let mut sidechain = Sidechain::default();
+        sidechain.push(8_000, 1, &vec![0.1; 8_000 * (MAX_SIDECHAIN_BACKLOG_S + 2)]);
+        assert_eq!(sidechain.levels.len(), 8_000 * MAX_SIDECHAIN_BACKLOG_S);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioCompressorConfig::default().validate().is_ok());
+        assert!(config(-20.0, 0.5).validate().is_err());
+        assert!(config(10.0, 4.0).validate().is_err());
+
+        let updated: AudioCompressorConfig =
+            merge_params(&AudioCompressorConfig::default(), &serde_json::json!({"ratio": 8.0}))
+                .unwrap();
+        assert!((updated.ratio - 8.0).abs() < f32::EPSILON);
+        assert!((updated.threshold_db + 20.0).abs() < f32::EPSILON);
+
+        let factory = AudioCompressorNode::factory();
+        assert!(factory(Some(&serde_json::json!({"attack_ms": 0.0}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_without_sidechain_compresses_input() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let factory = AudioCompressorNode::factory();
+        let node = factory(Some(&serde_json::json!({
+            "threshold_db": -20.0, "ratio": 20.0, "attack_ms": 0.1
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.9)).await.unwrap();
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 1);
+        let samples = extract_audio_data(&packets[0]).unwrap();
+        assert_eq!(samples.len(), 1920);
+        assert!(samples[1919] < 0.15, "got {}", samples[1919]);
+    }
+
+    #[tokio::test]
+    async fn test_node_ducks_input_under_sidechain() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let (sidechain_tx, sidechain_rx) = mpsc::channel(10);
+        let inputs =
+            HashMap::from([("in".to_string(), input_rx), ("sidechain".to_string(), sidechain_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let factory = AudioCompressorNode::factory();
+        let node = factory(Some(&serde_json::json!({
+            "threshold_db": -40.0, "ratio": 10.0, "attack_ms": 0.1, "release_ms": 1.0
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // Voice arrives first, then the music it should duck.
+        sidechain_tx.send(create_test_audio_packet(RATE, 1, 960, 0.5)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.25)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // Turning the ratio back to 1:1 disables ducking.
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"ratio": 1.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        sidechain_tx.send(create_test_audio_packet(RATE, 1, 960, 0.5)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.25)).await.unwrap();
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 2);
+        let ducked = extract_audio_data(&packets[0]).unwrap();
+        assert!(ducked[1919] < 0.25 * 0.1, "got {}", ducked[1919]);
+        let untouched = extract_audio_data(&packets[1]).unwrap();
+        assert!((untouched[1919] - 0.25).abs() < 1e-4, "got {}", untouched[1919]);
+    }
+}
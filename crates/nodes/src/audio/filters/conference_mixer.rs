@@ -0,0 +1,737 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Conference mixer node - Mixes participants on a fixed clock with ducking and speaker events
+//!
+//! Unlike `audio::mixer`, which synchronises on its inputs, this mixer emits a frame every
+//! `frame_ms` whatever the participants send, so a late or silent participant never stalls
+//! the bridge. Each input has a small jitter buffer; inputs without a full frame on a tick
+//! are left out of that tick's mix.
+
+use crate::audio::simd;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, CustomEncoding, CustomPacketData, Packet, PacketMetadata, PacketType,
+    SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+/// Type id of the active-speaker events emitted on the `events` pin.
+pub const ACTIVE_SPEAKER_TYPE_ID: &str = "audio::conference/active_speaker@1";
+
+/// Level reported for an input with no audio in the current frame.
+const SILENCE_DB: f32 = -120.0;
+
+/// Per-participant mix settings, keyed by input pin name.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ParticipantConfig {
+    /// Linear gain applied to this participant
+    #[schemars(range(min = 0.0, max = 4.0))]
+    pub gain: f32,
+    /// Leave this participant out of the mix (still tracked for levels)
+    pub mute: bool,
+    /// When any participant is soloed, only soloed participants are mixed
+    pub solo: bool,
+    /// While a priority participant speaks, everyone else is ducked, even if speaking
+    pub priority: bool,
+}
+
+impl Default for ParticipantConfig {
+    fn default() -> Self {
+        Self { gain: 1.0, mute: false, solo: false, priority: false }
+    }
+}
+
+/// Automatic ducking of participants who are not speaking.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DuckingConfig {
+    /// Attenuate participants who are not speaking while someone else is
+    pub enabled: bool,
+    /// Attenuation applied to ducked participants in dB
+    #[schemars(range(max = 0.0))]
+    pub amount_db: f32,
+    /// Time to reach the ducked level in milliseconds
+    pub attack_ms: u32,
+    /// Time to recover once ducking ends in milliseconds
+    pub release_ms: u32,
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self { enabled: true, amount_db: -12.0, attack_ms: 20, release_ms: 400 }
+    }
+}
+
+/// Configuration for the ConferenceMixerNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ConferenceMixerConfig {
+    /// Number of inputs, named `in_0` through `in_{N-1}`
+    #[schemars(range(min = 1))]
+    pub num_inputs: usize,
+    /// Output sample rate in Hz; inputs must already match it
+    pub sample_rate: u32,
+    /// Output channel count; mono and stereo inputs are converted
+    #[schemars(range(min = 1, max = 2))]
+    pub channels: u16,
+    /// Mix interval and output frame duration in milliseconds
+    #[schemars(range(min = 5, max = 100))]
+    pub frame_ms: u32,
+    /// Frames buffered per input before the oldest is dropped
+    #[schemars(range(min = 1))]
+    pub jitter_buffer_frames: usize,
+    /// Settings per input pin, e.g. `{"in_0": {"mute": true}}`
+    #[schemars(extend("tunable" = true))]
+    pub participants: HashMap<String, ParticipantConfig>,
+    /// Ducking of participants who are not speaking
+    #[schemars(extend("tunable" = true))]
+    pub ducking: DuckingConfig,
+    /// Input level above which a participant counts as speaking, in dBFS
+    #[schemars(range(max = 0.0), extend("tunable" = true))]
+    pub speech_threshold_db: f32,
+    /// How long a participant keeps counting as speaking after dropping below the threshold
+    pub speech_hold_ms: u32,
+    /// How much louder another speaker must be to take over as active speaker, in dB
+    #[schemars(range(min = 0.0))]
+    pub switch_margin_db: f32,
+}
+
+impl Default for ConferenceMixerConfig {
+    fn default() -> Self {
+        Self {
+            num_inputs: 2,
+            sample_rate: 48_000,
+            channels: 1,
+            frame_ms: 20,
+            jitter_buffer_frames: 3,
+            participants: HashMap::new(),
+            ducking: DuckingConfig::default(),
+            speech_threshold_db: -45.0,
+            speech_hold_ms: 300,
+            switch_margin_db: 6.0,
+        }
+    }
+}
+
+impl ConferenceMixerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.num_inputs == 0 || self.jitter_buffer_frames == 0 {
+            return Err("num_inputs and jitter_buffer_frames must be greater than 0".to_string());
+        }
+        if !(1..=2).contains(&self.channels) {
+            return Err(format!("channels must be 1 or 2, got {}", self.channels));
+        }
+        if !(5..=100).contains(&self.frame_ms) || self.sample_rate == 0 {
+            return Err("frame_ms must be within 5-100 and sample_rate greater than 0".to_string());
+        }
+        if let Some((pin, _)) =
+            self.participants.iter().find(|(_, p)| !(0.0..=4.0).contains(&p.gain))
+        {
+            return Err(format!("gain for '{pin}' must be within 0.0-4.0"));
+        }
+        if !self.ducking.amount_db.is_finite() || self.ducking.amount_db > 0.0 {
+            return Err("ducking.amount_db must be at most 0".to_string());
+        }
+        Ok(())
+    }
+
+    const fn frame_len(&self) -> usize {
+        (self.sample_rate * self.frame_ms / 1000) as usize
+    }
+}
+
+/// Mixing state of one input.
+#[derive(Debug)]
+struct Participant {
+    pin: String,
+    /// Samples converted to the output channel count
+    buffer: VecDeque<f32>,
+    closed: bool,
+    level_db: f32,
+    /// Ticks left before the participant stops counting as speaking
+    hold: u32,
+    duck_gain: f32,
+}
+
+impl Participant {
+    const fn speaking(&self) -> bool {
+        self.hold > 0
+    }
+}
+
+/// The outcome of one mix tick.
+#[derive(Debug)]
+struct MixOutput {
+    samples: Vec<f32>,
+    /// Set when the active speaker changed: (new speaker pin, previous speaker pin)
+    speaker_change: Option<(Option<String>, Option<String>)>,
+}
+
+/// Clocked conference mix, separated from I/O.
+struct ConferenceMix {
+    config: ConferenceMixerConfig,
+    participants: Vec<Participant>,
+    active_speaker: Option<usize>,
+    frame_len: usize,
+}
+
+impl ConferenceMix {
+    fn new(config: ConferenceMixerConfig) -> Self {
+        let participants = (0..config.num_inputs)
+            .map(|i| Participant {
+                pin: format!("in_{i}"),
+                buffer: VecDeque::new(),
+                closed: false,
+                level_db: SILENCE_DB,
+                hold: 0,
+                duck_gain: 1.0,
+            })
+            .collect();
+        let frame_len = config.frame_len();
+        Self { config, participants, active_speaker: None, frame_len }
+    }
+
+    fn settings(&self, index: usize) -> ParticipantConfig {
+        self.config.participants.get(&self.participants[index].pin).cloned().unwrap_or_default()
+    }
+
+    /// Queues an input frame, converting it to the output channel count.
+    fn push(&mut self, index: usize, frame: &AudioFrame) -> Result<(), String> {
+        if frame.sample_rate != self.config.sample_rate {
+            return Err(format!(
+                "{} is {} Hz but the mixer runs at {} Hz; add an audio::resampler",
+                self.participants[index].pin, frame.sample_rate, self.config.sample_rate
+            ));
+        }
+        let in_channels = usize::from(frame.channels.max(1));
+        let downmix = 1.0 / f32::from(frame.channels.max(1));
+        let out_channels = usize::from(self.config.channels);
+        let capacity = self.frame_len * out_channels * self.config.jitter_buffer_frames;
+        let buffer = &mut self.participants[index].buffer;
+        for input in frame.samples().chunks(in_channels) {
+            match (in_channels, out_channels) {
+                (1, _) => buffer.extend(std::iter::repeat_n(input[0], out_channels)),
+                (_, 1) => buffer.push_back(input.iter().sum::<f32>() * downmix),
+                _ => buffer.extend((0..out_channels).map(|c| input[c.min(input.len() - 1)])),
+            }
+        }
+        // Overwrite-oldest keeps latency bounded when a participant bursts
+        let excess = buffer.len().saturating_sub(capacity);
+        buffer.drain(..excess);
+        Ok(())
+    }
+
+    fn close(&mut self, index: usize) {
+        self.participants[index].closed = true;
+    }
+
+    /// True once every input has closed and its buffered audio has been mixed.
+    fn finished(&self) -> bool {
+        let frame = self.frame_len * usize::from(self.config.channels);
+        self.participants.iter().all(|p| p.closed && p.buffer.len() < frame)
+    }
+
+    /// Mixes one frame from every participant with a full frame buffered.
+    // Frame lengths and hold counts are small, well within f32/u32 range.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn tick(&mut self) -> MixOutput {
+        let channels = usize::from(self.config.channels);
+        let frame = self.frame_len * channels;
+        let hold_ticks = self.config.speech_hold_ms.div_ceil(self.config.frame_ms);
+        let settings: Vec<ParticipantConfig> =
+            (0..self.participants.len()).map(|i| self.settings(i)).collect();
+
+        // Pull frames and update speech detection
+        let mut frames: Vec<Option<Vec<f32>>> = Vec::with_capacity(self.participants.len());
+        for participant in &mut self.participants {
+            if participant.buffer.len() < frame {
+                participant.level_db = SILENCE_DB;
+                participant.hold = participant.hold.saturating_sub(1);
+                frames.push(None);
+                continue;
+            }
+            let samples: Vec<f32> = participant.buffer.drain(..frame).collect();
+            let rms = (simd::sum_squares(&samples) / frame as f32).sqrt();
+            participant.level_db = 20.0 * rms.max(1e-6).log10();
+            if participant.level_db >= self.config.speech_threshold_db {
+                participant.hold = hold_ticks.max(1);
+            } else {
+                participant.hold = participant.hold.saturating_sub(1);
+            }
+            frames.push(Some(samples));
+        }
+
+        let audible = |i: usize| {
+            let any_solo = settings.iter().any(|s| s.solo);
+            !settings[i].mute && (!any_solo || settings[i].solo)
+        };
+        let speaking = |p: &Participant, i: usize| p.speaking() && audible(i);
+
+        // Ducking targets: idle participants duck under any speaker, everyone but the
+        // priority speakers ducks under a priority speaker.
+        let ducking = &self.config.ducking;
+        let anyone_speaking = self.participants.iter().enumerate().any(|(i, p)| speaking(p, i));
+        let priority_speaking = self
+            .participants
+            .iter()
+            .enumerate()
+            .any(|(i, p)| settings[i].priority && speaking(p, i));
+        let ducked_gain = 10f32.powf(ducking.amount_db / 20.0);
+        let smoothing = |ms: u32| {
+            if ms == 0 {
+                1.0
+            } else {
+                1.0 - (-(self.config.frame_ms as f32) / ms as f32).exp()
+            }
+        };
+        let (attack, release) = (smoothing(ducking.attack_ms), smoothing(ducking.release_ms));
+
+        let mut mix = vec![0.0f32; frame];
+        for (i, participant) in self.participants.iter_mut().enumerate() {
+            let duck = ducking.enabled
+                && ((anyone_speaking && !participant.speaking())
+                    || (priority_speaking && !settings[i].priority));
+            let target = if duck { ducked_gain } else { 1.0 };
+            let start_gain = participant.duck_gain;
+            let coeff = if target < start_gain { attack } else { release };
+            participant.duck_gain += (target - start_gain) * coeff;
+
+            let Some(samples) = &frames[i] else { continue };
+            if !audible(i) {
+                continue;
+            }
+            // Ramp the gain across the frame, reaching the new value on the last sample
+            let step = (participant.duck_gain - start_gain) / self.frame_len as f32;
+            for (n, chunk) in samples.chunks(channels).enumerate() {
+                let gain = settings[i].gain * (step.mul_add((n + 1) as f32, start_gain));
+                for (c, sample) in chunk.iter().enumerate() {
+                    mix[n * channels + c] += sample * gain;
+                }
+            }
+        }
+        simd::clamp_unit(&mut mix);
+
+        let speaker_change = self.update_active_speaker(&settings);
+        MixOutput { samples: mix, speaker_change }
+    }
+
+    fn update_active_speaker(
+        &mut self,
+        settings: &[ParticipantConfig],
+    ) -> Option<(Option<String>, Option<String>)> {
+        let any_solo = settings.iter().any(|s| s.solo);
+        let eligible = |i: usize, p: &Participant| {
+            p.speaking() && !settings[i].mute && (!any_solo || settings[i].solo)
+        };
+        let loudest = self
+            .participants
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| eligible(*i, p))
+            .max_by(|a, b| a.1.level_db.total_cmp(&b.1.level_db))
+            .map(|(i, _)| i);
+
+        let next = match (self.active_speaker, loudest) {
+            (Some(current), Some(candidate)) if eligible(current, &self.participants[current]) => {
+                let margin =
+                    self.participants[candidate].level_db - self.participants[current].level_db;
+                if margin >= self.config.switch_margin_db {
+                    Some(candidate)
+                } else {
+                    Some(current)
+                }
+            },
+            (_, candidate) => candidate,
+        };
+        if next == self.active_speaker {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.active_speaker, next);
+        let pin = |i: usize| self.participants[i].pin.clone();
+        Some((next.map(pin), previous.map(pin)))
+    }
+
+    /// Merges a runtime update. `participants` entries and `ducking` are merged field by field.
+    fn apply_update(&mut self, params: &serde_json::Value) -> Result<(), String> {
+        let mut updated = self.config.clone();
+        if let Some(participants) = params.get("participants").and_then(|p| p.as_object()) {
+            for (pin, patch) in participants {
+                let entry = updated.participants.entry(pin.clone()).or_default();
+                *entry = merge_params(entry, patch).map_err(|e| format!("{pin}: {e}"))?;
+            }
+        }
+        if let Some(patch) = params.get("ducking") {
+            updated.ducking =
+                merge_params(&updated.ducking, patch).map_err(|e| format!("ducking: {e}"))?;
+        }
+        if let Some(threshold) =
+            params.get("speech_threshold_db").and_then(serde_json::Value::as_f64)
+        {
+            #[allow(clippy::cast_possible_truncation)] // dB levels fit comfortably in f32
+            let threshold = threshold as f32;
+            updated.speech_threshold_db = threshold;
+        }
+        updated.validate()?;
+        self.config = updated;
+        Ok(())
+    }
+
+    fn status(&self) -> serde_json::Value {
+        let participants: Vec<serde_json::Value> = self
+            .participants
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let settings = self.settings(i);
+                serde_json::json!({
+                    "pin": p.pin,
+                    "level_db": p.level_db,
+                    "speaking": p.speaking(),
+                    "gain": settings.gain,
+                    "mute": settings.mute,
+                    "solo": settings.solo,
+                    "closed": p.closed,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "active_speaker": self.active_speaker.map(|i| self.participants[i].pin.clone()),
+            "participants": participants,
+        })
+    }
+}
+
+/// A node that mixes N participants on a fixed clock for conferencing bridges.
+///
+/// Every `frame_ms` one frame is taken from each input's jitter buffer and mixed with the
+/// participant's gain, mute and solo settings (all tunable at runtime through
+/// `participants`). A participant is speaking while its level stays above
+/// `speech_threshold_db`; with ducking enabled, participants who are not speaking are
+/// attenuated by `ducking.amount_db`, which keeps idle microphones from adding noise.
+///
+/// The loudest speaker becomes the active speaker, and a challenger must be
+/// `switch_margin_db` louder to take over. Each change is sent on `events` as an
+/// `audio::conference/active_speaker@1` Custom packet with `pin` (or `null` once everyone is
+/// silent) and `previous`. Answers the `"status"` query with per-participant levels.
+pub struct ConferenceMixerNode {
+    config: ConferenceMixerConfig,
+}
+
+impl ConferenceMixerNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: ConferenceMixerConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!(
+                    "Invalid conference mixer configuration: {e}"
+                ))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn speaker_event(pin: Option<&str>, previous: Option<&str>, timestamp_us: u64) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: ACTIVE_SPEAKER_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({ "pin": pin, "previous": previous }),
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(timestamp_us),
+                duration_us: None,
+                sequence: None,
+            }),
+        }))
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for ConferenceMixerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        (0..self.config.num_inputs)
+            .map(|i| InputPin {
+                name: format!("in_{i}"),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: self.config.sample_rate,
+                    channels: 0, // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            })
+            .collect()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![
+            OutputPin {
+                name: "out".to_string(),
+                produces_type: PacketType::RawAudio(AudioFormat {
+                    sample_rate: self.config.sample_rate,
+                    channels: self.config.channels,
+                    sample_format: SampleFormat::F32,
+                }),
+                cardinality: PinCardinality::Broadcast,
+            },
+            OutputPin {
+                name: "events".to_string(),
+                produces_type: PacketType::Custom { type_id: ACTIVE_SPEAKER_TYPE_ID.to_string() },
+                cardinality: PinCardinality::Broadcast,
+            },
+        ]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Forward every input into one channel tagged with its index; `None` marks closure.
+        let (merged_tx, mut merged_rx) = mpsc::channel::<(usize, Option<Packet>)>(
+            self.config.num_inputs * self.config.jitter_buffer_frames.max(4),
+        );
+        for i in 0..self.config.num_inputs {
+            let mut rx = context.take_input(&format!("in_{i}"))?;
+            let tx = merged_tx.clone();
+            tokio::spawn(async move {
+                while let Some(packet) = rx.recv().await {
+                    if tx.send((i, Some(packet))).await.is_err() {
+                        return;
+                    }
+                }
+                let _ = tx.send((i, None)).await;
+            });
+        }
+        drop(merged_tx);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let frame_duration = Duration::from_millis(u64::from(self.config.frame_ms));
+        let frame_duration_us = u64::from(self.config.frame_ms) * 1000;
+        let (sample_rate, channels) = (self.config.sample_rate, self.config.channels);
+        let mut mix = ConferenceMix::new(self.config);
+        let mut ticker = tokio::time::interval(frame_duration);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut started = false;
+        let mut inputs_open = true;
+        let mut sequence = 0u64;
+        let mut rate_warned = vec![false; mix.participants.len()];
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        loop {
+            tokio::select! {
+                item = merged_rx.recv(), if inputs_open => match item {
+                    Some((i, Some(Packet::Audio(frame)))) => {
+                        stats.received();
+                        if let Err(e) = mix.push(i, &frame) {
+                            if !rate_warned[i] {
+                                tracing::warn!("ConferenceMixerNode: {}", e);
+                                rate_warned[i] = true;
+                            }
+                            stats.discarded();
+                        } else if !started {
+                            // Start the clock with the first audio so output begins promptly
+                            started = true;
+                            ticker.reset_immediately();
+                        }
+                    }
+                    Some((_, Some(_))) => {
+                        stats.received();
+                        stats.discarded();
+                    }
+                    Some((i, None)) => mix.close(i),
+                    None => inputs_open = false,
+                },
+                _ = ticker.tick(), if started => {
+                    if mix.finished() {
+                        break;
+                    }
+                    let output = mix.tick();
+                    let timestamp_us = sequence * frame_duration_us;
+                    if let Some((pin, previous)) = output.speaker_change {
+                        tracing::debug!(?pin, ?previous, "Active speaker changed");
+                        let event = Self::speaker_event(pin.as_deref(), previous.as_deref(), timestamp_us);
+                        // The events pin may be unconnected; only the mix output is required
+                        let _ = context.output_sender.send("events", event).await;
+                    }
+                    let frame = AudioFrame::with_metadata(
+                        sample_rate,
+                        channels,
+                        output.samples,
+                        Some(PacketMetadata {
+                            timestamp_us: Some(timestamp_us),
+                            duration_us: Some(frame_duration_us),
+                            sequence: Some(sequence),
+                        }),
+                    );
+                    sequence += 1;
+                    if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        if let Err(e) = mix.apply_update(&params) {
+                            tracing::warn!("Rejected conference mixer update: {}", e);
+                            stats.errored();
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                        let _ = reply.send(Ok(mix.status()));
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+
+            // Inputs that close before sending any audio never start the clock
+            if !started && mix.finished() {
+                break;
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test assertions
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_test_audio_packet, create_test_context};
+
+    fn config(num_inputs: usize) -> ConferenceMixerConfig {
+        ConferenceMixerConfig {
+            num_inputs,
+            sample_rate: 8000,
+            ducking: DuckingConfig { attack_ms: 0, release_ms: 0, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    /// One 20 ms mono frame at 8 kHz with a constant value.
+    fn frame(value: f32) -> AudioFrame {
+        AudioFrame::new(8000, 1, vec![value; 160])
+    }
+
+    fn feed(mix: &mut ConferenceMix, values: &[f32]) -> MixOutput {
+        for (i, &v) in values.iter().enumerate() {
+            mix.push(i, &frame(v)).unwrap();
+        }
+        mix.tick()
+    }
+
+    #[test]
+    fn test_mixes_with_gain_mute_and_solo() {
+        let mut mix = ConferenceMix::new(ConferenceMixerConfig {
+            ducking: DuckingConfig { enabled: false, ..Default::default() },
+            ..config(3)
+        });
+        let out = feed(&mut mix, &[0.1, 0.2, 0.3]);
+        assert!((out.samples[0] - 0.6).abs() < 1e-6);
+
+        mix.apply_update(&serde_json::json!({
+            "participants": {"in_0": {"gain": 2.0}, "in_2": {"mute": true}}
+        }))
+        .unwrap();
+        let out = feed(&mut mix, &[0.1, 0.2, 0.3]);
+        assert!((out.samples[0] - 0.4).abs() < 1e-6);
+
+        mix.apply_update(&serde_json::json!({"participants": {"in_1": {"solo": true}}})).unwrap();
+        let out = feed(&mut mix, &[0.1, 0.2, 0.3]);
+        assert!((out.samples[0] - 0.2).abs() < 1e-6);
+        // Earlier fields survive a partial update
+        assert!((mix.settings(0).gain - 2.0).abs() < f32::EPSILON);
+
+        assert!(mix
+            .apply_update(&serde_json::json!({"participants": {"in_0": {"gain": 9.0}}}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_missing_input_is_left_out_and_jitter_buffer_is_bounded() {
+        let mut mix = ConferenceMix::new(config(2));
+        for _ in 0..5 {
+            mix.push(0, &frame(0.1)).unwrap();
+        }
+        assert_eq!(mix.participants[0].buffer.len(), 3 * 160);
+        let out = mix.tick();
+        assert_eq!(out.samples.len(), 160);
+        assert!(mix.push(1, &AudioFrame::new(16000, 1, vec![0.0; 320])).is_err());
+    }
+
+    #[test]
+    fn test_ducks_idle_participants_while_someone_speaks() {
+        let mut mix = ConferenceMix::new(ConferenceMixerConfig { speech_hold_ms: 0, ..config(2) });
+        // in_0 speaks at -20 dBFS, in_1 is background noise below the threshold
+        let out = feed(&mut mix, &[0.1, 0.001]);
+        let ducked = 0.001 * 10f32.powf(-12.0 / 20.0);
+        assert!((out.samples[159] - (0.1 + ducked)).abs() < 1e-6, "{}", out.samples[159]);
+        assert!((mix.participants[1].duck_gain - 10f32.powf(-0.6)).abs() < 1e-6);
+        assert!((mix.participants[0].duck_gain - 1.0).abs() < f32::EPSILON);
+
+        mix.apply_update(&serde_json::json!({"ducking": {"enabled": false}})).unwrap();
+        assert!((mix.config.ducking.amount_db + 12.0).abs() < f32::EPSILON);
+        let out = feed(&mut mix, &[0.1, 0.001]);
+        assert!((out.samples[159] - 0.101).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_active_speaker_events_with_margin() {
+        let mut mix = ConferenceMix::new(ConferenceMixerConfig { speech_hold_ms: 20, ..config(3) });
+        let change = feed(&mut mix, &[0.1, 0.0, 0.0]).speaker_change.unwrap();
+        assert_eq!(change, (Some("in_0".to_string()), None));
+
+        // in_1 is only ~3.5 dB louder: below the 6 dB margin
+        assert!(feed(&mut mix, &[0.1, 0.15, 0.0]).speaker_change.is_none());
+        let change = feed(&mut mix, &[0.1, 0.25, 0.0]).speaker_change.unwrap();
+        assert_eq!(change, (Some("in_1".to_string()), Some("in_0".to_string())));
+
+        // Everyone goes quiet
+        let change = feed(&mut mix, &[0.0, 0.0, 0.0]).speaker_change.unwrap();
+        assert_eq!(change, (None, Some("in_1".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_node_mixes_until_inputs_drain() {
+        let mut inputs = HashMap::new();
+        for (i, level) in [0.2, 0.0].into_iter().enumerate() {
+            let (tx, rx) = mpsc::channel(8);
+            for _ in 0..2 {
+                tx.send(create_test_audio_packet(48000, 1, 960, level)).await.unwrap();
+            }
+            inputs.insert(format!("in_{i}"), rx);
+        }
+        let (context, sender, _state_rx) = create_test_context(inputs, 10);
+        let node = (ConferenceMixerNode::factory())(None).unwrap();
+        node.run(context).await.unwrap();
+
+        let packets = sender.collect_packets().await;
+        assert_eq!(packets.iter().filter(|(_, pin, _)| pin == "out").count(), 2);
+        let Some((_, _, Packet::Custom(event))) =
+            packets.iter().find(|(_, pin, _)| pin == "events")
+        else {
+            panic!("expected speaker event")
+        };
+        assert_eq!(event.data["pin"], "in_0");
+    }
+}
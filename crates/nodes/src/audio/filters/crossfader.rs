@@ -0,0 +1,549 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Crossfader node - Blends between two sources on a fixed clock
+//!
+//! `position` picks the blend, from `0.0` (only `a`) to `1.0` (only `b`). Changing it at
+//! runtime glides the blend to the new value over `ramp_ms`, sample by sample, so switching
+//! between a live source and hold music or TTS never clicks. Like `audio::conference_mixer`,
+//! output runs on its own clock: an input without audio for a frame contributes silence
+//! rather than stalling the other.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f32::consts::FRAC_PI_2;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+/// Input pin names, in position order.
+const INPUTS: [&str; 2] = ["a", "b"];
+
+/// Gain law applied across the fade.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossfadeCurve {
+    /// Sine/cosine gains: constant power, so uncorrelated sources keep their loudness midway
+    #[default]
+    EqualPower,
+    /// Gains proportional to the position: dips by 6 dB midway for uncorrelated sources
+    Linear,
+}
+
+impl CrossfadeCurve {
+    /// Gains of `a` and `b` at `position`.
+    fn gains(self, position: f32) -> (f32, f32) {
+        match self {
+            Self::EqualPower => {
+                let angle = position * FRAC_PI_2;
+                (angle.cos(), angle.sin())
+            },
+            Self::Linear => (1.0 - position, position),
+        }
+    }
+}
+
+/// Configuration for the AudioCrossfaderNode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AudioCrossfaderConfig {
+    /// Blend between the inputs: `0.0` plays only `a`, `1.0` only `b`
+    #[schemars(range(min = 0.0, max = 1.0), extend("tunable" = true))]
+    pub position: f32,
+    /// Time to glide from one end to the other, in milliseconds. Shorter moves take
+    /// proportionally less time; `0` jumps at the next frame.
+    #[schemars(range(min = 0, max = 60_000), extend("tunable" = true))]
+    pub ramp_ms: u32,
+    /// Gain law applied across the fade
+    #[schemars(extend("tunable" = true))]
+    pub curve: CrossfadeCurve,
+    /// Output sample rate in Hz; inputs must already match it
+    #[schemars(range(min = 1))]
+    pub sample_rate: u32,
+    /// Output channel count; mono and stereo inputs are converted
+    #[schemars(range(min = 1, max = 2))]
+    pub channels: u16,
+    /// Output frame duration in milliseconds
+    #[schemars(range(min = 5, max = 100))]
+    pub frame_ms: u32,
+    /// Frames buffered per input before the oldest is dropped
+    #[schemars(range(min = 1))]
+    pub jitter_buffer_frames: usize,
+}
+
+impl Default for AudioCrossfaderConfig {
+    fn default() -> Self {
+        Self {
+            position: 0.0,
+            ramp_ms: 500,
+            curve: CrossfadeCurve::default(),
+            sample_rate: 48_000,
+            channels: 1,
+            frame_ms: 20,
+            jitter_buffer_frames: 3,
+        }
+    }
+}
+
+impl AudioCrossfaderConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.position) {
+            return Err(format!("position must be within 0.0-1.0, got {}", self.position));
+        }
+        if self.ramp_ms > 60_000 {
+            return Err(format!("ramp_ms must be at most 60000, got {}", self.ramp_ms));
+        }
+        if !(1..=2).contains(&self.channels) {
+            return Err(format!("channels must be 1 or 2, got {}", self.channels));
+        }
+        if !(5..=100).contains(&self.frame_ms) || self.sample_rate == 0 {
+            return Err("frame_ms must be within 5-100 and sample_rate greater than 0".to_string());
+        }
+        if self.jitter_buffer_frames == 0 {
+            return Err("jitter_buffer_frames must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    const fn frame_len(&self) -> usize {
+        (self.sample_rate * self.frame_ms / 1000) as usize
+    }
+}
+
+/// Buffered audio of one input.
+#[derive(Debug, Default)]
+struct Source {
+    /// Samples converted to the output channel count
+    buffer: VecDeque<f32>,
+    closed: bool,
+}
+
+/// Clocked crossfade, separated from I/O.
+struct Crossfade {
+    config: AudioCrossfaderConfig,
+    sources: [Source; 2],
+    /// Position actually applied, gliding towards `config.position`
+    position: f32,
+    frame_len: usize,
+}
+
+impl Crossfade {
+    fn new(config: AudioCrossfaderConfig) -> Self {
+        let frame_len = config.frame_len();
+        Self { position: config.position, sources: Default::default(), config, frame_len }
+    }
+
+    /// Queues an input frame, converting it to the output channel count.
+    fn push(&mut self, index: usize, frame: &AudioFrame) -> Result<(), String> {
+        if frame.sample_rate != self.config.sample_rate {
+            return Err(format!(
+                "input '{}' is {} Hz but the crossfader runs at {} Hz; add an audio::resampler",
+                INPUTS[index], frame.sample_rate, self.config.sample_rate
+            ));
+        }
+        let in_channels = usize::from(frame.channels.max(1));
+        let downmix = 1.0 / f32::from(frame.channels.max(1));
+        let out_channels = usize::from(self.config.channels);
+        let capacity = self.frame_len * out_channels * self.config.jitter_buffer_frames;
+        let buffer = &mut self.sources[index].buffer;
+        for input in frame.samples().chunks(in_channels) {
+            match (in_channels, out_channels) {
+                (1, _) => buffer.extend(std::iter::repeat_n(input[0], out_channels)),
+                (_, 1) => buffer.push_back(input.iter().sum::<f32>() * downmix),
+                _ => buffer.extend((0..out_channels).map(|c| input[c.min(input.len() - 1)])),
+            }
+        }
+        // Overwrite-oldest keeps latency bounded when a source bursts
+        let excess = buffer.len().saturating_sub(capacity);
+        buffer.drain(..excess);
+        Ok(())
+    }
+
+    const fn close(&mut self, index: usize) {
+        self.sources[index].closed = true;
+    }
+
+    /// True once both inputs have closed and their buffered audio has been played.
+    fn finished(&self) -> bool {
+        let frame = self.frame_len * usize::from(self.config.channels);
+        self.sources.iter().all(|s| s.closed && s.buffer.len() < frame)
+    }
+
+    /// How far the position moves per output sample frame.
+    // Sample counts of a ramp are far below f32's exact integer range
+    #[allow(clippy::cast_precision_loss)]
+    fn step(&self) -> f32 {
+        let ramp_frames =
+            u64::from(self.config.ramp_ms) * u64::from(self.config.sample_rate) / 1000;
+        if ramp_frames == 0 {
+            1.0
+        } else {
+            1.0 / ramp_frames as f32
+        }
+    }
+
+    /// Blends one frame, taking a full frame from each input that has one buffered.
+    fn tick(&mut self) -> Vec<f32> {
+        let channels = usize::from(self.config.channels);
+        let frame = self.frame_len * channels;
+        let frames: Vec<Option<Vec<f32>>> = self
+            .sources
+            .iter_mut()
+            .map(|s| (s.buffer.len() >= frame).then(|| s.buffer.drain(..frame).collect()))
+            .collect();
+
+        let target = self.config.position;
+        let step = self.step();
+        let mut mix = vec![0.0f32; frame];
+        for (n, out) in mix.chunks_mut(channels).enumerate() {
+            self.position = if self.position < target {
+                (self.position + step).min(target)
+            } else {
+                (self.position - step).max(target)
+            };
+            let gains = <[f32; 2]>::from(self.config.curve.gains(self.position));
+            for (samples, gain) in frames.iter().zip(gains) {
+                let Some(samples) = samples else { continue };
+                for (c, sample) in out.iter_mut().enumerate() {
+                    *sample = samples[n * channels + c].mul_add(gain, *sample);
+                }
+            }
+        }
+        mix
+    }
+
+    /// Merges a runtime update. Only the fade parameters can change while running.
+    fn apply_update(&mut self, params: &serde_json::Value) -> Result<(), String> {
+        let updated: AudioCrossfaderConfig = merge_params(&self.config, params)?;
+        updated.validate()?;
+        if (updated.sample_rate, updated.channels, updated.frame_ms)
+            != (self.config.sample_rate, self.config.channels, self.config.frame_ms)
+            || updated.jitter_buffer_frames != self.config.jitter_buffer_frames
+        {
+            return Err("only position, ramp_ms and curve can be tuned while running".to_string());
+        }
+        self.config = updated;
+        Ok(())
+    }
+
+    fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "position": self.position,
+            "target_position": self.config.position,
+            "ramping": (self.position - self.config.position).abs() > f32::EPSILON,
+        })
+    }
+}
+
+/// A node that crossfades between two audio sources, `a` and `b`.
+///
+/// Every `frame_ms` one frame is taken from each input's jitter buffer and the two are blended
+/// by `position` using the selected `curve`. Tuning `position` glides to the new value at a
+/// rate of one full sweep per `ramp_ms`, so a voice agent can switch from the caller's audio
+/// to hold music or TTS (and back) without a click. An input that falls behind is treated as
+/// silence for that frame. Answers the `"status"` query with the current and target
+/// positions.
+pub struct AudioCrossfaderNode {
+    config: AudioCrossfaderConfig,
+}
+
+impl AudioCrossfaderNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioCrossfaderConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid crossfader configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioCrossfaderNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        INPUTS
+            .iter()
+            .map(|name| InputPin {
+                name: (*name).to_string(),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: self.config.sample_rate,
+                    channels: 0, // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            })
+            .collect()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: self.config.sample_rate,
+                channels: self.config.channels,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Forward both inputs into one channel tagged with their index; `None` marks closure.
+        // An unconnected input simply stays silent.
+        let (merged_tx, mut merged_rx) =
+            mpsc::channel::<(usize, Option<Packet>)>(2 * self.config.jitter_buffer_frames.max(4));
+        for (i, name) in INPUTS.iter().enumerate() {
+            let Some(mut rx) = context.inputs.remove(*name) else {
+                let _ = merged_tx.send((i, None)).await;
+                continue;
+            };
+            let tx = merged_tx.clone();
+            tokio::spawn(async move {
+                while let Some(packet) = rx.recv().await {
+                    if tx.send((i, Some(packet))).await.is_err() {
+                        return;
+                    }
+                }
+                let _ = tx.send((i, None)).await;
+            });
+        }
+        drop(merged_tx);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let frame_duration = Duration::from_millis(u64::from(self.config.frame_ms));
+        let frame_duration_us = u64::from(self.config.frame_ms) * 1000;
+        let (sample_rate, channels) = (self.config.sample_rate, self.config.channels);
+        let mut fade = Crossfade::new(self.config);
+        let mut ticker = tokio::time::interval(frame_duration);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut started = false;
+        let mut inputs_open = true;
+        let mut sequence = 0u64;
+        let mut rate_warned = [false; 2];
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        loop {
+            tokio::select! {
+                item = merged_rx.recv(), if inputs_open => match item {
+                    Some((i, Some(Packet::Audio(frame)))) => {
+                        stats.received();
+                        if let Err(e) = fade.push(i, &frame) {
+                            if !rate_warned[i] {
+                                tracing::warn!("AudioCrossfaderNode: {}", e);
+                                rate_warned[i] = true;
+                            }
+                            stats.discarded();
+                        } else if !started {
+                            // Start the clock with the first audio so output begins promptly
+                            started = true;
+                            ticker.reset_immediately();
+                        }
+                    }
+                    Some((_, Some(_))) => {
+                        stats.received();
+                        stats.discarded();
+                    }
+                    Some((i, None)) => fade.close(i),
+                    None => inputs_open = false,
+                },
+                _ = ticker.tick(), if started => {
+                    if fade.finished() {
+                        break;
+                    }
+                    let samples = fade.tick();
+                    let frame = AudioFrame::with_metadata(
+                        sample_rate,
+                        channels,
+                        samples,
+                        Some(PacketMetadata {
+                            timestamp_us: Some(sequence * frame_duration_us),
+                            duration_us: Some(frame_duration_us),
+                            sequence: Some(sequence),
+                        }),
+                    );
+                    sequence += 1;
+                    if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        if let Err(e) = fade.apply_update(&params) {
+                            tracing::warn!("Rejected crossfader update: {}", e);
+                            stats.errored();
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                        let _ = reply.send(Ok(fade.status()));
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+
+            // Inputs that close before sending any audio never start the clock
+            if !started && fade.finished() {
+                break;
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)] // Test assertions
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+
+    fn config(position: f32, ramp_ms: u32) -> AudioCrossfaderConfig {
+        AudioCrossfaderConfig { position, ramp_ms, sample_rate: 8000, ..Default::default() }
+    }
+
+    /// One 20 ms mono frame at 8 kHz with a constant value.
+    fn frame(value: f32) -> AudioFrame {
+        AudioFrame::new(8000, 1, vec![value; 160])
+    }
+
+    fn feed(fade: &mut Crossfade, a: f32, b: f32) -> Vec<f32> {
+        fade.push(0, &frame(a)).unwrap();
+        fade.push(1, &frame(b)).unwrap();
+        fade.tick()
+    }
+
+    #[test]
+    fn test_curves_hold_the_ends_and_equal_power_keeps_power() {
+        for curve in [CrossfadeCurve::EqualPower, CrossfadeCurve::Linear] {
+            let (a, b) = curve.gains(0.0);
+            assert!((a - 1.0).abs() < 1e-6 && b.abs() < 1e-6);
+            let (a, b) = curve.gains(1.0);
+            assert!(a.abs() < 1e-6 && (b - 1.0).abs() < 1e-6);
+        }
+        let (a, b) = CrossfadeCurve::EqualPower.gains(0.5);
+        assert!((a.mul_add(a, b * b) - 1.0).abs() < 1e-6);
+        let (a, b) = CrossfadeCurve::Linear.gains(0.25);
+        assert!((a - 0.75).abs() < 1e-6 && (b - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_position_glides_over_ramp() {
+        // 40 ms ramp: two 20 ms frames for a full sweep
+        let mut fade = Crossfade::new(config(0.0, 40));
+        assert!(feed(&mut fade, 0.5, 0.25).iter().all(|s| (s - 0.5).abs() < 1e-6));
+
+        fade.apply_update(&serde_json::json!({"position": 1.0, "curve": "linear"})).unwrap();
+        let first = feed(&mut fade, 0.5, 0.25);
+        // Halfway through the sweep at the end of the first frame, with no jump at its start
+        assert!((first[0] - 0.5).abs() < 0.01, "got {}", first[0]);
+        assert!((first[159] - 0.375).abs() < 1e-3, "got {}", first[159]);
+        let second = feed(&mut fade, 0.5, 0.25);
+        assert!((second[159] - 0.25).abs() < 1e-4, "got {}", second[159]);
+
+        // Settled: only `b` plays
+        assert!(feed(&mut fade, 0.5, 0.25).iter().all(|s| (s - 0.25).abs() < 1e-6));
+        assert!((fade.position - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_missing_input_is_silent_and_channels_convert() {
+        let mut fade = Crossfade::new(AudioCrossfaderConfig { channels: 2, ..config(0.0, 0) });
+        fade.push(0, &frame(0.5)).unwrap();
+        let out = fade.tick();
+        assert_eq!(out.len(), 320);
+        assert!(out.iter().all(|s| (s - 0.5).abs() < 1e-6));
+
+        // Jumps straight to `b`, which has nothing buffered
+        fade.apply_update(&serde_json::json!({"position": 1.0})).unwrap();
+        fade.push(0, &frame(0.5)).unwrap();
+        assert!(fade.tick().iter().all(|s| s.abs() < 1e-6));
+
+        assert!(fade.push(1, &AudioFrame::new(16_000, 1, vec![0.0; 320])).is_err());
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioCrossfaderConfig::default().validate().is_ok());
+        assert!(AudioCrossfaderConfig { position: 1.5, ..Default::default() }.validate().is_err());
+        assert!(AudioCrossfaderConfig { channels: 3, ..Default::default() }.validate().is_err());
+
+        let mut fade = Crossfade::new(config(0.0, 100));
+        assert!(fade.apply_update(&serde_json::json!({"position": -0.1})).is_err());
+        assert!(fade.apply_update(&serde_json::json!({"sample_rate": 16_000})).is_err());
+        fade.apply_update(&serde_json::json!({"ramp_ms": 250})).unwrap();
+        assert_eq!(fade.config.ramp_ms, 250);
+
+        let factory = AudioCrossfaderNode::factory();
+        assert!(factory(Some(&serde_json::json!({"frame_ms": 1}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_switches_sources_when_tuned() {
+        let (a_tx, a_rx) = mpsc::channel(10);
+        let (b_tx, b_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("a".to_string(), a_rx), ("b".to_string(), b_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let params = serde_json::json!({"sample_rate": 8000, "ramp_ms": 0});
+        let node = AudioCrossfaderNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        a_tx.send(create_test_audio_packet(8000, 1, 160, 0.5)).await.unwrap();
+        b_tx.send(create_test_audio_packet(8000, 1, 160, 0.25)).await.unwrap();
+        let (_node, _pin, first) =
+            mock_sender.recv_timeout(Duration::from_secs(2)).await.expect("Expected a frame");
+
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"position": 1.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        // Frames mixed while waiting for the update carry silence; only check the switched one
+        while mock_sender.try_recv().await.is_some() {}
+        a_tx.send(create_test_audio_packet(8000, 1, 160, 0.5)).await.unwrap();
+        b_tx.send(create_test_audio_packet(8000, 1, 160, 0.25)).await.unwrap();
+        drop(a_tx);
+        drop(b_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        assert!(extract_audio_data(&first).unwrap().iter().all(|s| (s - 0.5).abs() < 1e-6));
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let switched = packets.last().unwrap();
+        assert!(extract_audio_data(switched).unwrap().iter().all(|s| (s - 0.25).abs() < 1e-6));
+    }
+}
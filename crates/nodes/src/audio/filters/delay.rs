@@ -0,0 +1,444 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Delay node - Sample-accurate audio delay for latency compensation
+//!
+//! Every sample leaves the node `delay_ms` later than it arrived, measured on the stream
+//! timeline: the output starts with that much silence and packet timestamps are left as they
+//! are, so downstream muxers and mixers see the audio shifted against other streams. Use it
+//! to line audio up with a video path, or with a translation that takes seconds to arrive.
+//!
+//! The delay can be changed while running. Lowering it skips the audio in between, raising
+//! it inserts silence; either way the node crossfades over `ramp_ms` to avoid clicks. When
+//! the input ends, the audio still held back is flushed unless `flush` is off.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Longest delay the node holds, in milliseconds.
+const MAX_DELAY_MS: f32 = 10_000.0;
+
+/// Frame size used to flush the delay line when no input frame size is known yet.
+const FLUSH_CHUNK_MS: f32 = 20.0;
+
+/// Configuration for the AudioDelayNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioDelayConfig {
+    /// How long the audio is held back, in milliseconds
+    #[schemars(range(min = 0.0, max = 10000.0), extend("tunable" = true))]
+    pub delay_ms: f32,
+    /// Crossfade applied when the delay changes while running, in milliseconds
+    #[schemars(range(min = 0.0, max = 1000.0), extend("tunable" = true))]
+    pub ramp_ms: f32,
+    /// Emit the audio still held back when the input ends, instead of dropping it
+    #[schemars(extend("tunable" = true))]
+    pub flush: bool,
+}
+
+impl Default for AudioDelayConfig {
+    fn default() -> Self {
+        Self { delay_ms: 0.0, ramp_ms: 20.0, flush: true }
+    }
+}
+
+impl AudioDelayConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=MAX_DELAY_MS).contains(&self.delay_ms) {
+            return Err(format!("delay_ms must be within 0-{MAX_DELAY_MS}, got {}", self.delay_ms));
+        }
+        if !(0.0..=1000.0).contains(&self.ramp_ms) {
+            return Err(format!("ramp_ms must be within 0-1000, got {}", self.ramp_ms));
+        }
+        Ok(())
+    }
+}
+
+/// Number of frames `ms` milliseconds last at `sample_rate`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Validated to at most 10 s
+fn ms_to_frames(ms: f32, sample_rate: u32) -> usize {
+    (f64::from(ms) * f64::from(sample_rate) / 1000.0).round() as usize
+}
+
+/// Delay line for one stream of interleaved audio.
+struct DelayLine {
+    sample_rate: u32,
+    channels: usize,
+    /// Recent input, interleaved, newest last
+    history: VecDeque<f32>,
+    /// Delay in frames
+    delay: usize,
+    /// Delay being faded out and the frames left of the crossfade
+    fade: Option<(usize, usize)>,
+    fade_len: usize,
+}
+
+impl DelayLine {
+    fn new(sample_rate: u32, channels: usize, config: &AudioDelayConfig) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            history: VecDeque::new(),
+            delay: ms_to_frames(config.delay_ms, sample_rate),
+            fade: None,
+            fade_len: ms_to_frames(config.ramp_ms, sample_rate),
+        }
+    }
+
+    /// Switches to the delay and ramp of `config`, crossfading from the current delay.
+    fn set_config(&mut self, config: &AudioDelayConfig) {
+        let delay = ms_to_frames(config.delay_ms, self.sample_rate);
+        self.fade_len = ms_to_frames(config.ramp_ms, self.sample_rate);
+        if delay != self.delay {
+            self.fade = (self.fade_len > 0).then_some((self.delay, self.fade_len));
+            self.delay = delay;
+        }
+    }
+
+    /// Sample of `channel` from `delay` frames before the newest one; silence before the start.
+    fn read(&self, delay: usize, channel: usize) -> f32 {
+        let frames = self.history.len() / self.channels;
+        if delay < frames {
+            self.history[(frames - 1 - delay) * self.channels + channel]
+        } else {
+            0.0
+        }
+    }
+
+    /// Delays interleaved `samples` in place.
+    #[allow(clippy::cast_precision_loss)]
+    fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_exact_mut(self.channels) {
+            self.history.extend(frame.iter().copied());
+            match self.fade {
+                Some((old, left)) => {
+                    let mix = 1.0 - left as f32 / self.fade_len as f32;
+                    for (channel, sample) in frame.iter_mut().enumerate() {
+                        let from = self.read(old, channel);
+                        *sample = from + (self.read(self.delay, channel) - from) * mix;
+                    }
+                    self.fade = (left > 1).then_some((old, left - 1));
+                },
+                None => {
+                    for (channel, sample) in frame.iter_mut().enumerate() {
+                        *sample = self.read(self.delay, channel);
+                    }
+                },
+            }
+
+            // Only keep what hasn't been played yet, so raising the delay never repeats audio.
+            let excess = self.history.len().saturating_sub(self.held() * self.channels);
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Frames of input not yet played out.
+    fn held(&self) -> usize {
+        self.delay.max(self.fade.map_or(0, |(old, _)| old))
+    }
+
+    /// The audio still held back, as if the input continued with silence.
+    fn drain(&mut self) -> Vec<f32> {
+        let mut samples = vec![0.0; self.held() * self.channels];
+        self.process(&mut samples);
+        self.history.clear();
+        samples
+    }
+}
+
+/// A node that delays raw audio by a configurable, runtime-tunable amount.
+///
+/// Typical uses are lip-sync alignment between audio and video paths, and holding back the
+/// original audio until a translation or caption pipeline has caught up.
+pub struct AudioDelayNode {
+    config: AudioDelayConfig,
+}
+
+impl AudioDelayNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioDelayConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid delay configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+/// Sends the audio held in `line` as frames of `chunk_frames`, continuing the timestamps.
+async fn flush(
+    context: &mut NodeContext,
+    stats: &mut NodeStatsTracker,
+    line: &mut DelayLine,
+    chunk_frames: usize,
+    mut next_timestamp_us: Option<u64>,
+) -> bool {
+    let samples = line.drain();
+    #[allow(clippy::cast_possible_truncation)] // Channel counts come from a u16
+    let channels = line.channels as u16;
+    for chunk in samples.chunks(chunk_frames.max(1) * line.channels) {
+        let mut frame = AudioFrame::new(line.sample_rate, channels, chunk.to_vec());
+        let duration_us = frame.duration_us();
+        frame.metadata = next_timestamp_us.map(|timestamp_us| PacketMetadata {
+            timestamp_us: Some(timestamp_us),
+            duration_us,
+            sequence: None,
+        });
+        next_timestamp_us = next_timestamp_us.zip(duration_us).map(|(ts, d)| ts + d);
+        if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+            return false;
+        }
+        stats.sent();
+    }
+    true
+}
+
+#[async_trait]
+impl ProcessorNode for AudioDelayNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut config = self.config;
+        let mut line: Option<DelayLine> = None;
+        // Frame size and end timestamp of the last input, for flushing.
+        let mut last_frames = 0;
+        let mut next_timestamp_us: Option<u64> = None;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 || frame.channels == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        let channels = usize::from(frame.channels);
+                        let line = match &mut line {
+                            Some(line)
+                                if line.sample_rate == frame.sample_rate
+                                    && line.channels == channels => line,
+                            _ => {
+                                if line.is_some() {
+                                    tracing::info!(
+                                        "Audio format changed to {} Hz, {} channels; restarting the delay",
+                                        frame.sample_rate,
+                                        channels
+                                    );
+                                }
+                                line.insert(DelayLine::new(frame.sample_rate, channels, &config))
+                            },
+                        };
+                        last_frames = frame.num_frames();
+                        next_timestamp_us = frame
+                            .metadata
+                            .as_ref()
+                            .and_then(|m| m.timestamp_us)
+                            .zip(frame.duration_us())
+                            .map(|(ts, duration)| ts + duration);
+                        // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                        line.process(frame.make_samples_mut());
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&config, &params).and_then(|updated: AudioDelayConfig| {
+                            updated.validate()?;
+                            Ok(updated)
+                        }) {
+                            Ok(updated) => {
+                                if let Some(line) = &mut line {
+                                    line.set_config(&updated);
+                                }
+                                config = updated;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected delay update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => {
+                        line = None;
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Some(line) = line.as_mut().filter(|_| config.flush) {
+            let chunk_frames = if last_frames > 0 {
+                last_frames
+            } else {
+                ms_to_frames(FLUSH_CHUNK_MS, line.sample_rate)
+            };
+            if !flush(&mut context, &mut stats, line, chunk_frames, next_timestamp_us).await {
+                tracing::debug!("Output channel closed while flushing the delay");
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss, clippy::float_cmp)] // Delayed samples are exact copies
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 1000;
+
+    fn config(delay_ms: f32, ramp_ms: f32) -> AudioDelayConfig {
+        AudioDelayConfig { delay_ms, ramp_ms, flush: true }
+    }
+
+    #[test]
+    fn test_delays_by_whole_frames_across_packets() {
+        let mut line = DelayLine::new(RATE, 2, &config(3.0, 0.0));
+        let mut first = vec![1.0, -1.0, 2.0, -2.0];
+        let mut second = vec![3.0, -3.0, 4.0, -4.0, 5.0, -5.0];
+        line.process(&mut first);
+        line.process(&mut second);
+        assert_eq!(first, vec![0.0; 4]);
+        assert_eq!(second, vec![0.0, 0.0, 1.0, -1.0, 2.0, -2.0]);
+        assert_eq!(line.drain(), vec![3.0, -3.0, 4.0, -4.0, 5.0, -5.0]);
+    }
+
+    #[test]
+    fn test_runtime_changes_crossfade() {
+        let ramp: Vec<f32> = (1..=20).map(|i| i as f32).collect();
+        let mut line = DelayLine::new(RATE, 1, &config(2.0, 0.0));
+        let mut samples = ramp[..10].to_vec();
+        line.process(&mut samples);
+        assert_eq!(samples[9], 8.0);
+
+        // Dropping to no delay fades from two frames back to the current frame over 4 frames.
+        line.set_config(&config(0.0, 4.0));
+        let mut samples = ramp[10..].to_vec();
+        line.process(&mut samples);
+        assert_eq!(&samples[..4], &[9.0, 10.5, 12.0, 13.5]);
+        assert_eq!(&samples[4..], &ramp[14..]);
+
+        // Raising it again inserts silence rather than replaying audio.
+        line.set_config(&config(5.0, 0.0));
+        let mut samples = vec![21.0, 22.0, 23.0, 24.0, 25.0, 26.0];
+        line.process(&mut samples);
+        assert_eq!(samples, vec![0.0, 0.0, 0.0, 0.0, 0.0, 21.0]);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioDelayConfig::default().validate().is_ok());
+        assert!(config(-1.0, 20.0).validate().is_err());
+        assert!(config(20_000.0, 20.0).validate().is_err());
+        assert!(config(100.0, 5000.0).validate().is_err());
+        assert!(AudioDelayNode::factory()(Some(&serde_json::json!({"delay_ms": 250}))).is_ok());
+
+        let updated: AudioDelayConfig =
+            merge_params(&config(100.0, 20.0), &serde_json::json!({"delay_ms": 40.0})).unwrap();
+        assert_eq!(updated, config(40.0, 20.0));
+    }
+
+    #[tokio::test]
+    async fn test_node_delays_and_flushes_with_timestamps() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let params = serde_json::json!({"delay_ms": 15.0});
+        let node = AudioDelayNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        for i in 0..2u64 {
+            let metadata = PacketMetadata {
+                timestamp_us: Some(i * 10_000),
+                duration_us: Some(10_000),
+                sequence: Some(i),
+            };
+            let frame = AudioFrame::with_metadata(RATE, 1, vec![1.0; 10], Some(metadata));
+            input_tx.send(Packet::Audio(frame)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let samples: Vec<f32> =
+            packets.iter().flat_map(|p| extract_audio_data(p).unwrap().to_vec()).collect();
+        assert_eq!(samples.len(), 35);
+        assert!(samples[..15].iter().all(|s| *s == 0.0));
+        assert!(samples[15..].iter().all(|s| *s == 1.0));
+
+        let timestamps: Vec<Option<u64>> = packets
+            .iter()
+            .map(|p| match p {
+                Packet::Audio(frame) => frame.metadata.as_ref().and_then(|m| m.timestamp_us),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(timestamps, vec![Some(0), Some(10_000), Some(20_000), Some(30_000)]);
+    }
+}
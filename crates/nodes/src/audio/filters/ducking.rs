@@ -0,0 +1,451 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Ducking node - Lowers a program stream while a voice is active on the sidechain
+//!
+//! Unlike the compressor's proportional gain reduction, ducking is a two-state effect: while
+//! the sidechain is active the program is turned down by a fixed `depth_db`, otherwise it
+//! plays at unity. Activity is either the sidechain's audio level crossing `threshold_db`,
+//! or VAD `speech_start`/`speech_end` events. The gain moves between the two states in the dB
+//! domain over `attack_ms` and `release_ms`, and `hold_ms` bridges short pauses in speech.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, CustomPacketData, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+use super::compressor::{level_db, time_coefficient, Sidechain};
+
+/// Custom packet type emitted by the VAD plugin in `events` mode.
+pub(super) const VAD_EVENT_TYPE_ID: &str = "plugin::native::vad/vad-event@1";
+
+/// Configuration for the AudioDuckingNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioDuckingConfig {
+    /// How far the program is turned down while the sidechain is active, in dB
+    #[schemars(range(min = 0.0, max = 60.0), extend("tunable" = true))]
+    pub depth_db: f32,
+    /// Sidechain audio level at or above which it counts as active, in dBFS
+    #[schemars(range(min = -96.0, max = 0.0), extend("tunable" = true))]
+    pub threshold_db: f32,
+    /// Time for the program to duck once the sidechain becomes active, in milliseconds
+    #[schemars(range(min = 1.0, max = 2000.0), extend("tunable" = true))]
+    pub attack_ms: f32,
+    /// Time the program stays ducked after the sidechain goes quiet, in milliseconds
+    #[schemars(range(min = 0.0, max = 5000.0), extend("tunable" = true))]
+    pub hold_ms: f32,
+    /// Time for the program to come back up once the hold has passed, in milliseconds
+    #[schemars(range(min = 10.0, max = 10000.0), extend("tunable" = true))]
+    pub release_ms: f32,
+}
+
+impl Default for AudioDuckingConfig {
+    fn default() -> Self {
+        Self {
+            depth_db: 18.0,
+            threshold_db: -40.0,
+            attack_ms: 50.0,
+            hold_ms: 300.0,
+            release_ms: 500.0,
+        }
+    }
+}
+
+impl AudioDuckingConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("depth_db", self.depth_db, 0.0, 60.0)?;
+        check("threshold_db", self.threshold_db, -96.0, 0.0)?;
+        check("attack_ms", self.attack_ms, 1.0, 2000.0)?;
+        check("hold_ms", self.hold_ms, 0.0, 5000.0)?;
+        check("release_ms", self.release_ms, 10.0, 10000.0)
+    }
+}
+
+/// Reads a VAD event, returning whether speech started (`true`) or ended (`false`).
+pub(super) fn vad_speech(event: &CustomPacketData) -> Option<bool> {
+    if event.type_id != VAD_EVENT_TYPE_ID {
+        return None;
+    }
+    match event.data.get("event_type")?.as_str()? {
+        "speech_start" => Some(true),
+        "speech_end" => Some(false),
+        _ => None,
+    }
+}
+
+/// Activity tracking and gain smoothing for one program stream.
+struct Ducker {
+    config: AudioDuckingConfig,
+    sample_rate: u32,
+    attack: f32,
+    release: f32,
+    hold_frames: u64,
+    /// Program frames since the sidechain was last active
+    since_active: u64,
+    /// Whether the latest VAD event was `speech_start`
+    speaking: bool,
+    /// Smoothed program gain in dB (zero or negative)
+    gain_db: f32,
+}
+
+impl Ducker {
+    fn new(config: AudioDuckingConfig) -> Self {
+        let mut ducker = Self {
+            config,
+            sample_rate: 0,
+            attack: 0.0,
+            release: 0.0,
+            hold_frames: 0,
+            since_active: u64::MAX,
+            speaking: false,
+            gain_db: 0.0,
+        };
+        ducker.update_coefficients();
+        ducker
+    }
+
+    fn set_config(&mut self, config: AudioDuckingConfig) {
+        self.config = config;
+        self.update_coefficients();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.update_coefficients();
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        if self.sample_rate > 0 {
+            self.attack = time_coefficient(self.config.attack_ms, self.sample_rate);
+            self.release = time_coefficient(self.config.release_ms, self.sample_rate);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            #[allow(clippy::cast_precision_loss)]
+            let hold_frames = (self.config.hold_ms * 0.001 * self.sample_rate as f32) as u64;
+            self.hold_frames = hold_frames;
+        }
+    }
+
+    /// Ducks interleaved program `samples` in place, keyed by the sidechain's audio level
+    /// and the latest VAD event.
+    fn process(
+        &mut self,
+        samples: &mut [f32],
+        channels: usize,
+        mut sidechain: Option<&mut Sidechain>,
+    ) {
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let loud = sidechain.as_deref_mut().is_some_and(|sidechain| {
+                level_db(sidechain.next(self.sample_rate)) >= self.config.threshold_db
+            });
+            if loud || self.speaking {
+                self.since_active = 0;
+            } else {
+                self.since_active = self.since_active.saturating_add(1);
+            }
+
+            let target =
+                if self.since_active <= self.hold_frames { -self.config.depth_db } else { 0.0 };
+            let coefficient = if target < self.gain_db { self.attack } else { self.release };
+            self.gain_db = target + coefficient * (self.gain_db - target);
+
+            let gain = 10f32.powf(self.gain_db / 20.0);
+            for sample in frame {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+/// A node that turns the program on `in` down while the `sidechain` is active.
+///
+/// The sidechain takes either raw audio (e.g. the voiceover itself), which is active while
+/// its level is at or above `threshold_db`, or the `plugin::native::vad/vad-event@1` events
+/// of a VAD in `events` mode, which are active between `speech_start` and `speech_end`. Audio
+/// on the sidechain is consumed in step with the program, as for `audio::compressor`; VAD
+/// events take effect as they arrive. Without a sidechain the program passes unchanged. All
+/// parameters can be tuned while running.
+pub struct AudioDuckingNode {
+    config: AudioDuckingConfig,
+}
+
+impl AudioDuckingNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioDuckingConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid ducking configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+const fn raw_audio() -> PacketType {
+    PacketType::RawAudio(AudioFormat {
+        sample_rate: 0, // Wildcard
+        channels: 0,    // Wildcard
+        sample_format: SampleFormat::F32,
+    })
+}
+
+#[async_trait]
+impl ProcessorNode for AudioDuckingNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![raw_audio()],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "sidechain".to_string(),
+                accepts_types: vec![
+                    raw_audio(),
+                    PacketType::Custom { type_id: VAD_EVENT_TYPE_ID.to_string() },
+                ],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: raw_audio(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut sidechain_rx = context.inputs.remove("sidechain");
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut ducker = Ducker::new(self.config);
+        // Only created once sidechain audio arrives, so VAD-only sidechains don't read as silence.
+        let mut sidechain: Option<Sidechain> = None;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        ducker.set_sample_rate(frame.sample_rate);
+                        let channels = usize::from(frame.channels);
+                        // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                        ducker.process(frame.make_samples_mut(), channels, sidechain.as_mut());
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                maybe_key = async { sidechain_rx.as_mut()?.recv().await }, if sidechain_rx.is_some() => {
+                    match maybe_key {
+                        Some(Packet::Audio(frame)) if frame.sample_rate > 0 => {
+                            sidechain.get_or_insert_with(Sidechain::default).push(
+                                frame.sample_rate,
+                                usize::from(frame.channels),
+                                frame.samples(),
+                            );
+                        }
+                        Some(Packet::Custom(event)) => {
+                            if let Some(speaking) = vad_speech(&event) {
+                                ducker.speaking = speaking;
+                            }
+                        }
+                        Some(_) => {}
+                        // Once the sidechain ends, its remaining audio still keys the program.
+                        None => sidechain_rx = None,
+                    }
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&ducker.config, &params).and_then(|config: AudioDuckingConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => ducker.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected ducking update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::CustomEncoding;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    fn config() -> AudioDuckingConfig {
+        AudioDuckingConfig {
+            attack_ms: 5.0,
+            hold_ms: 100.0,
+            release_ms: 20.0,
+            ..Default::default()
+        }
+    }
+
+    fn ducker() -> Ducker {
+        let mut ducker = Ducker::new(config());
+        ducker.set_sample_rate(RATE);
+        ducker
+    }
+
+    /// Last sample after running `ms` of DC at 0.5 through `ducker`.
+    fn run(ducker: &mut Ducker, ms: usize, sidechain: Option<&mut Sidechain>) -> f32 {
+        let mut samples = vec![0.5; RATE as usize * ms / 1000];
+        ducker.process(&mut samples, 1, sidechain);
+        samples[samples.len() - 1]
+    }
+
+    fn vad_event(event_type: &str) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: VAD_EVENT_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({ "event_type": event_type, "timestamp_ms": 0 }),
+            metadata: None,
+        }))
+    }
+
+    #[test]
+    fn test_level_sidechain_ducks_by_depth_then_releases_after_hold() {
+        let mut ducker = ducker();
+        let mut sidechain = Sidechain::default();
+
+        // Quiet sidechain below the threshold leaves the program alone.
+        sidechain.push(RATE, 1, &vec![0.001; RATE as usize / 10]);
+        assert!((run(&mut ducker, 100, Some(&mut sidechain)) - 0.5).abs() < 1e-4);
+
+        // A voice at -6 dBFS ducks the program by 18 dB.
+        sidechain.push(RATE, 1, &vec![0.5; RATE as usize / 5]);
+        let low = run(&mut ducker, 200, Some(&mut sidechain));
+        assert!((level_db(low) - (level_db(0.5) - 18.0)).abs() < 0.1, "got {low}");
+
+        // During the hold the program stays down, afterwards it recovers.
+        let held = run(&mut ducker, 80, Some(&mut sidechain));
+        assert!((held - low).abs() < 1e-4);
+        assert!((run(&mut ducker, 300, Some(&mut sidechain)) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_vad_events_drive_ducking() {
+        let mut ducker = ducker();
+        let Packet::Custom(start) = vad_event("speech_start") else { unreachable!() };
+        let Packet::Custom(end) = vad_event("speech_end") else { unreachable!() };
+        assert_eq!(vad_speech(&start), Some(true));
+        assert_eq!(vad_speech(&end), Some(false));
+
+        ducker.speaking = true;
+        assert!(run(&mut ducker, 100, None) < 0.5 * 0.15);
+        ducker.speaking = false;
+        assert!((run(&mut ducker, 500, None) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioDuckingConfig::default().validate().is_ok());
+        assert!(AudioDuckingConfig { depth_db: -3.0, ..Default::default() }.validate().is_err());
+
+        let updated: AudioDuckingConfig =
+            merge_params(&AudioDuckingConfig::default(), &serde_json::json!({"depth_db": 6.0}))
+                .unwrap();
+        assert!((updated.depth_db - 6.0).abs() < f32::EPSILON);
+        assert!((updated.threshold_db + 40.0).abs() < f32::EPSILON);
+
+        let factory = AudioDuckingNode::factory();
+        assert!(factory(Some(&serde_json::json!({"release_ms": 0.0}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_ducks_program_on_vad_events() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let (sidechain_tx, sidechain_rx) = mpsc::channel(10);
+        let inputs =
+            HashMap::from([("in".to_string(), input_rx), ("sidechain".to_string(), sidechain_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioDuckingNode::factory()(Some(&serde_json::json!({
+            "attack_ms": 1.0, "hold_ms": 0.0, "release_ms": 10.0
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.5)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        sidechain_tx.send(vad_event("speech_start")).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.5)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        sidechain_tx.send(vad_event("speech_end")).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        for _ in 0..5 {
+            input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.5)).await.unwrap();
+        }
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 7);
+        let last = |i: usize| extract_audio_data(&packets[i]).unwrap()[1919];
+        assert!((last(0) - 0.5).abs() < 1e-4);
+        assert!(last(1) < 0.5 * 0.15, "got {}", last(1));
+        assert!((last(6) - 0.5).abs() < 1e-3, "got {}", last(6));
+    }
+}
@@ -0,0 +1,595 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Parametric equalizer node - Shapes the spectrum with a chain of biquad filters
+//!
+//! Each band is an RBJ "Audio EQ Cookbook" biquad (peaking, shelf, pass or notch) run in
+//! direct form I with 64-bit state per channel. Coefficients are recomputed when the sample
+//! rate or a band changes; filter state is kept across updates so live tuning doesn't click.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Most bands a single equalizer may have.
+const MAX_BANDS: usize = 16;
+/// Band gains and the output gain are limited to +/- this many dB.
+const MAX_GAIN_DB: f32 = 24.0;
+
+/// Filter shape of an equalizer band.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EqBandType {
+    /// Boost or cut around `frequency_hz`
+    #[default]
+    Peaking,
+    /// Boost or cut below `frequency_hz`
+    LowShelf,
+    /// Boost or cut above `frequency_hz`
+    HighShelf,
+    /// Remove content above `frequency_hz` (`gain_db` is ignored)
+    LowPass,
+    /// Remove content below `frequency_hz` (`gain_db` is ignored)
+    HighPass,
+    /// Remove a narrow band around `frequency_hz` (`gain_db` is ignored)
+    Notch,
+}
+
+/// One band of the equalizer.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct EqBand {
+    /// Filter shape
+    #[serde(rename = "type")]
+    pub band_type: EqBandType,
+    /// Center (peaking, notch) or corner (shelf, pass) frequency in Hz
+    #[schemars(range(min = 20.0, max = 20000.0))]
+    pub frequency_hz: f32,
+    /// Quality factor; higher values make the band narrower
+    #[schemars(range(min = 0.1, max = 20.0))]
+    pub q: f32,
+    /// Boost (positive) or cut (negative) in dB for peaking and shelf bands
+    #[schemars(range(min = -24.0, max = 24.0))]
+    pub gain_db: f32,
+    /// Set to false to bypass the band without removing it
+    pub enabled: bool,
+}
+
+impl Default for EqBand {
+    fn default() -> Self {
+        Self {
+            band_type: EqBandType::Peaking,
+            frequency_hz: 1000.0,
+            q: 0.75,
+            gain_db: 0.0,
+            enabled: true,
+        }
+    }
+}
+
+impl EqBand {
+    fn validate(&self) -> Result<(), String> {
+        if !(20.0..=20_000.0).contains(&self.frequency_hz) {
+            return Err(format!("frequency_hz must be within 20-20000, got {}", self.frequency_hz));
+        }
+        if !(0.1..=20.0).contains(&self.q) {
+            return Err(format!("q must be within 0.1-20, got {}", self.q));
+        }
+        if !(-MAX_GAIN_DB..=MAX_GAIN_DB).contains(&self.gain_db) {
+            return Err(format!(
+                "gain_db must be within -{MAX_GAIN_DB}-{MAX_GAIN_DB}, got {}",
+                self.gain_db
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Configuration for the AudioEqualizerNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioEqualizerConfig {
+    /// Bands applied in order (at most 16). Tunable: an array replaces every band, while an
+    /// object keyed by band index (e.g. `{"1": {"gain_db": 3.0}}`) patches individual bands.
+    #[schemars(extend("tunable" = true))]
+    pub bands: Vec<EqBand>,
+    /// Gain applied after all bands, in dB
+    #[schemars(range(min = -24.0, max = 24.0), extend("tunable" = true))]
+    pub output_gain_db: f32,
+}
+
+impl Default for AudioEqualizerConfig {
+    /// A flat three-band layout: low shelf, mid peak and high shelf.
+    fn default() -> Self {
+        Self {
+            bands: vec![
+                EqBand {
+                    band_type: EqBandType::LowShelf,
+                    frequency_hz: 100.0,
+                    ..EqBand::default()
+                },
+                EqBand { frequency_hz: 1000.0, q: 1.0, ..EqBand::default() },
+                EqBand {
+                    band_type: EqBandType::HighShelf,
+                    frequency_hz: 8000.0,
+                    ..EqBand::default()
+                },
+            ],
+            output_gain_db: 0.0,
+        }
+    }
+}
+
+impl AudioEqualizerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.bands.len() > MAX_BANDS {
+            return Err(format!(
+                "at most {MAX_BANDS} bands are supported, got {}",
+                self.bands.len()
+            ));
+        }
+        for (i, band) in self.bands.iter().enumerate() {
+            band.validate().map_err(|e| format!("band {i}: {e}"))?;
+        }
+        if !(-MAX_GAIN_DB..=MAX_GAIN_DB).contains(&self.output_gain_db) {
+            return Err(format!(
+                "output_gain_db must be within -{MAX_GAIN_DB}-{MAX_GAIN_DB}, got {}",
+                self.output_gain_db
+            ));
+        }
+        Ok(())
+    }
+
+    /// Applies a `TuneNode` params object on top of this config.
+    fn patched(&self, params: &serde_json::Value) -> Result<Self, String> {
+        let mut updated = self.clone();
+        match params.get("bands") {
+            Some(serde_json::Value::Array(_)) => {
+                updated.bands = serde_json::from_value(params["bands"].clone())
+                    .map_err(|e| format!("bands: {e}"))?;
+            },
+            Some(serde_json::Value::Object(patches)) => {
+                for (index, patch) in patches {
+                    let band = index
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| updated.bands.get_mut(i))
+                        .ok_or_else(|| format!("bands: no band at index '{index}'"))?;
+                    *band = merge_params(band, patch).map_err(|e| format!("band {index}: {e}"))?;
+                }
+            },
+            Some(other) => return Err(format!("bands must be an array or object, got {other}")),
+            None => {},
+        }
+        if let Some(gain) = params.get("output_gain_db").and_then(serde_json::Value::as_f64) {
+            #[allow(clippy::cast_possible_truncation)] // dB levels fit comfortably in f32
+            let gain = gain as f32;
+            updated.output_gain_db = gain;
+        }
+        updated.validate()?;
+        Ok(updated)
+    }
+}
+
+/// Normalized biquad coefficients (`a0` = 1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Biquad {
+    /// RBJ cookbook coefficients for `band` at `sample_rate`.
+    // Written as in the cookbook rather than with `mul_add` so it can be checked against it.
+    fn design(band: &EqBand, sample_rate: u32) -> Self {
+        let fs = f64::from(sample_rate);
+        // Keep the corner below Nyquist for low sample rates (e.g. an 8 kHz shelf at 16 kHz)
+        let frequency = f64::from(band.frequency_hz).min(fs * 0.49);
+        let w0 = TAU * frequency / fs;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * f64::from(band.q));
+        let a = 10f64.powf(f64::from(band.gain_db) / 40.0);
+
+        let (b0, b1, b2, a0, a1, a2) = match band.band_type {
+            EqBandType::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            EqBandType::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                )
+            },
+            EqBandType::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                )
+            },
+            EqBandType::LowPass => {
+                let b = (1.0 - cos) * 0.5;
+                (b, 1.0 - cos, b, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+            },
+            EqBandType::HighPass => {
+                let b = (1.0 + cos) * 0.5;
+                (b, -(1.0 + cos), b, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+            },
+            EqBandType::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+        };
+
+        Self { b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0 }
+    }
+}
+
+/// Direct form I history for one channel of one band.
+#[derive(Debug, Clone, Copy, Default)]
+struct BiquadState {
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+impl BiquadState {
+    #[inline]
+    fn process(&mut self, f: &Biquad, x: f64) -> f64 {
+        let y = f.b0 * x + f.b1 * self.x1 + f.b2 * self.x2 - f.a1 * self.y1 - f.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// The filter chain for one stream.
+struct Equalizer {
+    config: AudioEqualizerConfig,
+    sample_rate: u32,
+    channels: usize,
+    /// Coefficients of the enabled bands, in order
+    filters: Vec<Biquad>,
+    /// `filters.len() * channels` states, band-major
+    states: Vec<BiquadState>,
+    output_gain: f32,
+}
+
+impl Equalizer {
+    fn new(config: AudioEqualizerConfig) -> Self {
+        let mut eq = Self {
+            config,
+            sample_rate: 0,
+            channels: 0,
+            filters: Vec::new(),
+            states: Vec::new(),
+            output_gain: 1.0,
+        };
+        eq.redesign();
+        eq
+    }
+
+    /// Replaces the config, keeping filter history when the band layout is unchanged.
+    fn set_config(&mut self, config: AudioEqualizerConfig) {
+        self.config = config;
+        self.redesign();
+    }
+
+    /// Adapts to the stream format, resetting history when it changes.
+    fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        if sample_rate != self.sample_rate || channels != self.channels {
+            self.sample_rate = sample_rate;
+            self.channels = channels;
+            self.states.clear();
+            self.redesign();
+        }
+    }
+
+    fn redesign(&mut self) {
+        self.output_gain = 10f32.powf(self.config.output_gain_db / 20.0);
+        if self.sample_rate == 0 {
+            return;
+        }
+        self.filters = self
+            .config
+            .bands
+            .iter()
+            .filter(|band| band.enabled)
+            .map(|band| Biquad::design(band, self.sample_rate))
+            .collect();
+        let len = self.filters.len() * self.channels;
+        if self.states.len() != len {
+            self.states = vec![BiquadState::default(); len];
+        }
+    }
+
+    /// True when processing would leave the samples unchanged.
+    fn is_flat(&self) -> bool {
+        let neutral = |band: &EqBand| {
+            !band.enabled
+                || (band.gain_db.abs() < f32::EPSILON
+                    && matches!(
+                        band.band_type,
+                        EqBandType::Peaking | EqBandType::LowShelf | EqBandType::HighShelf
+                    ))
+        };
+        self.config.output_gain_db.abs() < f32::EPSILON && self.config.bands.iter().all(neutral)
+    }
+
+    // Filters run in f64 to keep low-frequency bands stable; the results are narrowed back.
+    #[allow(clippy::cast_possible_truncation)]
+    fn process(&mut self, samples: &mut [f32]) {
+        let channels = self.channels.max(1);
+        for frame in samples.chunks_mut(channels) {
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                let mut x = f64::from(*sample);
+                for (band, filter) in self.filters.iter().enumerate() {
+                    x = self.states[band * channels + ch].process(filter, x);
+                }
+                *sample = x as f32 * self.output_gain;
+            }
+        }
+    }
+}
+
+/// A node that applies a multi-band parametric equalizer to raw audio.
+///
+/// Bands are processed in order on every channel, in place on the incoming frame (copied
+/// only if the frame is shared). Both the bands and `output_gain_db` can be tuned while
+/// running; a flat configuration passes frames through untouched.
+pub struct AudioEqualizerNode {
+    config: AudioEqualizerConfig,
+}
+
+impl AudioEqualizerNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioEqualizerConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid equalizer configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioEqualizerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut eq = Equalizer::new(self.config);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        eq.set_format(frame.sample_rate, usize::from(frame.channels));
+                        if !eq.is_flat() {
+                            // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                            eq.process(frame.make_samples_mut());
+                        }
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => match eq.config.patched(&params) {
+                        Ok(config) => eq.set_config(config),
+                        Err(e) => {
+                            tracing::warn!("Rejected equalizer update: {}", e);
+                            stats.errored();
+                        }
+                    },
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    fn band(band_type: EqBandType, frequency_hz: f32, gain_db: f32) -> EqBand {
+        EqBand { band_type, frequency_hz, gain_db, ..EqBand::default() }
+    }
+
+    fn config(bands: Vec<EqBand>) -> AudioEqualizerConfig {
+        AudioEqualizerConfig { bands, output_gain_db: 0.0 }
+    }
+
+    /// Steady-state peak of a mono sine at `frequency` after one second through `eq`.
+    fn response(eq: &mut Equalizer, frequency: f32) -> f32 {
+        eq.set_format(RATE, 1);
+        let step = std::f32::consts::TAU * frequency / RATE as f32;
+        let mut samples: Vec<f32> = (0..RATE).map(|i| 0.25 * (step * i as f32).sin()).collect();
+        eq.process(&mut samples);
+        samples[RATE as usize / 2..].iter().fold(0.0_f32, |peak, s| peak.max(s.abs())) / 0.25
+    }
+
+    #[test]
+    fn test_peaking_band_boosts_its_frequency_only() {
+        let mut eq = Equalizer::new(config(vec![band(EqBandType::Peaking, 1000.0, 12.0)]));
+        assert!((response(&mut eq, 1000.0) - 3.98).abs() < 0.05);
+        assert!((response(&mut eq, 100.0) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_shelves_and_pass_filters() {
+        let mut low_cut = Equalizer::new(config(vec![band(EqBandType::LowShelf, 200.0, -12.0)]));
+        assert!(response(&mut low_cut, 50.0) < 0.3);
+        assert!((response(&mut low_cut, 5000.0) - 1.0).abs() < 0.05);
+
+        let mut lowpass = Equalizer::new(config(vec![band(EqBandType::LowPass, 1000.0, 0.0)]));
+        assert!(response(&mut lowpass, 10_000.0) < 0.02);
+        assert!((response(&mut lowpass, 100.0) - 1.0).abs() < 0.05);
+
+        let mut notch =
+            Equalizer::new(config(vec![EqBand { q: 5.0, ..band(EqBandType::Notch, 1000.0, 0.0) }]));
+        assert!(response(&mut notch, 1000.0) < 0.02);
+    }
+
+    #[test]
+    fn test_flat_config_is_bypassed() {
+        let eq = Equalizer::new(AudioEqualizerConfig::default());
+        assert!(eq.is_flat());
+        let eq = Equalizer::new(config(vec![band(EqBandType::HighPass, 80.0, 0.0)]));
+        assert!(!eq.is_flat());
+    }
+
+    #[test]
+    fn test_update_patches_and_replaces_bands() {
+        let base = AudioEqualizerConfig::default();
+        let patched =
+            base.patched(&serde_json::json!({"bands": {"1": {"gain_db": -6.0}}})).unwrap();
+        assert!((patched.bands[1].gain_db + 6.0).abs() < f32::EPSILON);
+        assert_eq!(patched.bands[0], base.bands[0]);
+
+        let replaced = base
+            .patched(&serde_json::json!({
+                "bands": [{"type": "high_pass", "frequency_hz": 80.0}],
+                "output_gain_db": 3.0
+            }))
+            .unwrap();
+        assert_eq!(replaced.bands.len(), 1);
+        assert_eq!(replaced.bands[0].band_type, EqBandType::HighPass);
+        assert!((replaced.output_gain_db - 3.0).abs() < f32::EPSILON);
+
+        assert!(base.patched(&serde_json::json!({"bands": {"7": {"gain_db": 1.0}}})).is_err());
+        assert!(base.patched(&serde_json::json!({"bands": {"0": {"q": 0.0}}})).is_err());
+        assert!(base.patched(&serde_json::json!({"output_gain_db": 40.0})).is_err());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioEqualizerConfig::default().validate().is_ok());
+        assert!(config(vec![band(EqBandType::Peaking, 10.0, 0.0)]).validate().is_err());
+        assert!(config(vec![band(EqBandType::Peaking, 1000.0, 30.0)]).validate().is_err());
+        assert!(config(vec![EqBand::default(); MAX_BANDS + 1]).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_filters_frames_and_applies_updates() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let factory = AudioEqualizerNode::factory();
+        let node = factory(Some(&serde_json::json!({"bands": [], "output_gain_db": 6.0}))).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        input_tx.send(create_test_audio_packet(RATE, 2, 10, 0.25)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"output_gain_db": 0.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 2, 10, 0.25)).await.unwrap();
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 2);
+        let boosted = extract_audio_data(&packets[0]).unwrap();
+        assert_eq!(boosted.len(), 20);
+        assert!(boosted.iter().all(|s| (s - 0.25 * 1.995).abs() < 0.001));
+        assert!(extract_audio_data(&packets[1]).unwrap().iter().all(|s| (s - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_factory_rejects_invalid_config() {
+        let factory = AudioEqualizerNode::factory();
+        assert!(factory(Some(&serde_json::json!({"bands": [{"q": 100.0}]}))).is_err());
+    }
+}
@@ -2,10 +2,11 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
+use crate::audio::simd;
 use async_trait::async_trait;
 use schemars::JsonSchema;
 use serde::Deserialize;
-use streamkit_core::control::NodeControlMessage;
+use streamkit_core::control::{reject_query, NodeControlMessage};
 use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
 use streamkit_core::{
     packet_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
@@ -178,15 +179,16 @@ impl ProcessorNode for AudioGainNode {
                                         tracing::info!("AudioGainNode received shutdown signal");
                                         return Ok(());
                                     },
+                                    NodeControlMessage::Query { kind, reply, .. } => {
+                                        reject_query(&kind, reply);
+                                    },
                                 }
                             }
 
                             if let Packet::Audio(ref mut frame) = packet {
                                 // The internal format is guaranteed to be f32, so we can operate directly.
                                 // Copy-on-write: clones only if Arc is shared, mutates in place if unique
-                                for sample in frame.make_samples_mut() {
-                                    *sample *= self.config.gain;
-                                }
+                                simd::scale(frame.make_samples_mut(), self.config.gain);
                             }
                             if context.output_sender.send("out", packet).await.is_err() {
                                 tracing::debug!("Output channel closed, stopping node");
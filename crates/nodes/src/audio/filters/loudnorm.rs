@@ -0,0 +1,1043 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Loudnorm node - Loudness normalization to EBU R128 / LUFS targets
+//!
+//! Loudness is measured as in ITU-R BS.1770: K-weighted mean square over 400 ms blocks every
+//! 100 ms, gated at -70 LUFS and then 10 LU below the ungated level for the integrated
+//! loudness, with a 4x oversampled true-peak estimate. Loudness range follows EBU Tech 3342.
+//!
+//! In `two_pass` mode the whole input is buffered and measured, then played out with the one
+//! gain that hits `target_lufs`, lowered if needed so the true peak stays under
+//! `true_peak_db`. This is exact but only fits oneshot pipelines. In `single_pass` mode the gain
+//! follows the integrated loudness measured so far and a sample-peak limiter enforces the
+//! ceiling. The input arrives through the engine's lookahead (see
+//! [`streamkit_core::lookahead`]): audio is measured as it is peeked and normalized once it
+//! comes out of the delay line, so the gain has already seen `lookahead_ms` of what follows.
+//! With the default of 0 audio flows through without delay, at the cost of settling during the
+//! first seconds; a few seconds of lookahead start the output at a measured gain instead.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::lookahead::{LookaheadEvent, LookaheadSpec};
+use streamkit_core::telemetry::TelemetryEmitter;
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Blocks quieter than this never count towards the integrated loudness or loudness range.
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Lowest and highest loudness tracked by the gating histograms, and their resolution.
+const HISTOGRAM_MIN_LUFS: f64 = ABSOLUTE_GATE_LUFS;
+const HISTOGRAM_MAX_LUFS: f64 = 10.0;
+const HISTOGRAM_STEP_LU: f64 = 0.1;
+/// Taps of each phase of the true-peak interpolation filter.
+const TRUE_PEAK_TAPS: usize = 12;
+/// Time constant of the single-pass gain as it follows the measured loudness.
+const SMOOTHING_MS: f32 = 1000.0;
+/// Time for the single-pass limiter to recover after catching a peak.
+const LIMITER_RELEASE_MS: f32 = 100.0;
+
+/// How the input is measured and normalized.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoudnormMode {
+    /// Buffer the whole input, measure it, then apply one constant gain (oneshot only)
+    #[default]
+    TwoPass,
+    /// Adjust the gain continuously from the loudness measured so far
+    SinglePass,
+}
+
+/// Configuration for the AudioLoudnormNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioLoudnormConfig {
+    /// Measurement and normalization strategy
+    pub mode: LoudnormMode,
+    /// Integrated loudness of the output, in LUFS (-23 for EBU R128 broadcast, -16 for podcasts)
+    #[schemars(range(min = -70.0, max = -5.0))]
+    pub target_lufs: f32,
+    /// Highest true peak allowed in the output, in dBTP
+    #[schemars(range(min = -9.0, max = 0.0))]
+    pub true_peak_db: f32,
+    /// Largest boost applied to quiet input, in dB
+    #[schemars(range(min = 0.0, max = 40.0))]
+    pub max_gain_db: f32,
+    /// Longest input buffered in `two_pass` mode, in seconds
+    #[schemars(range(min = 1.0, max = 14400.0))]
+    pub max_buffer_s: f32,
+    /// Interval between loudness telemetry events in `single_pass` mode, in milliseconds of audio
+    #[schemars(range(min = 100.0, max = 60000.0))]
+    pub stats_interval_ms: f32,
+    /// How far `single_pass` mode measures ahead of the audio it outputs, in milliseconds.
+    /// Adds the same latency.
+    #[schemars(range(min = 0.0, max = 30000.0))]
+    pub lookahead_ms: f32,
+}
+
+impl Default for AudioLoudnormConfig {
+    fn default() -> Self {
+        Self {
+            mode: LoudnormMode::TwoPass,
+            target_lufs: -23.0,
+            true_peak_db: -1.0,
+            max_gain_db: 20.0,
+            max_buffer_s: 3600.0,
+            stats_interval_ms: 1000.0,
+            lookahead_ms: 0.0,
+        }
+    }
+}
+
+impl AudioLoudnormConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("target_lufs", self.target_lufs, -70.0, -5.0)?;
+        check("true_peak_db", self.true_peak_db, -9.0, 0.0)?;
+        check("max_gain_db", self.max_gain_db, 0.0, 40.0)?;
+        check("max_buffer_s", self.max_buffer_s, 1.0, 14400.0)?;
+        check("stats_interval_ms", self.stats_interval_ms, 100.0, 60000.0)?;
+        check("lookahead_ms", self.lookahead_ms, 0.0, 30000.0)
+    }
+}
+
+/// Converts a mean square (already K-weighted and channel-weighted) to LUFS.
+fn loudness(energy: f64) -> f64 {
+    if energy > 0.0 {
+        -0.691 + 10.0 * energy.log10()
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Converts a linear amplitude to dB, `-inf` for silence.
+fn amplitude_db(amplitude: f32) -> f64 {
+    20.0 * f64::from(amplitude).log10()
+}
+
+/// Second-order IIR section (transposed direct form II).
+#[derive(Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// The two stages of the BS.1770 K-weighting filter, derived for any sample rate.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = f64::from(sample_rate);
+
+    // High shelf modelling the acoustic effect of the head
+    let (f0, gain_db, q) = (1_681.974_450_955_533, 3.999_843_853_973_347, 0.707_175_236_955_419_6);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    // High pass (RLB weighting)
+    let (f0, q) = (38.135_470_876_024_44, 0.500_327_037_323_877_3);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+/// Weight of each channel in the loudness sum; surround channels of a 5.1 layout count more
+/// and the LFE not at all.
+fn channel_weights(channels: usize) -> Vec<f64> {
+    if channels == 6 {
+        vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41]
+    } else {
+        vec![1.0; channels]
+    }
+}
+
+/// Estimates the true (inter-sample) peak by oversampling with a windowed-sinc interpolator.
+struct TruePeak {
+    /// Interpolation coefficients for each fractional position between samples
+    phases: Vec<[f32; TRUE_PEAK_TAPS]>,
+    /// Most recent samples of each channel, oldest first
+    history: Vec<[f32; TRUE_PEAK_TAPS]>,
+    peak: f32,
+}
+
+impl TruePeak {
+    #[allow(clippy::cast_precision_loss)] // Tap indices and factors are tiny
+    fn new(sample_rate: u32, channels: usize) -> Self {
+        // Oversample up to roughly 192 kHz, where the sample peak is close enough.
+        let factor = match sample_rate {
+            0..96_000 => 4,
+            96_000..192_000 => 2,
+            _ => 1,
+        };
+        let half = (TRUE_PEAK_TAPS / 2) as f32;
+        let phases = (1..factor)
+            .map(|phase| {
+                let mut taps = [0.0; TRUE_PEAK_TAPS];
+                for (j, tap) in taps.iter_mut().enumerate() {
+                    let d = half - 1.0 - j as f32 + phase as f32 / factor as f32;
+                    let x = std::f32::consts::PI * d;
+                    let sinc = if d == 0.0 { 1.0 } else { x.sin() / x };
+                    let window = 0.5 * (1.0 + (x / half).cos());
+                    *tap = sinc * window;
+                }
+                let sum: f32 = taps.iter().sum();
+                taps.map(|tap| tap / sum)
+            })
+            .collect();
+        Self { phases, history: vec![[0.0; TRUE_PEAK_TAPS]; channels], peak: 0.0 }
+    }
+
+    fn push(&mut self, frame: &[f32]) {
+        for (history, &sample) in self.history.iter_mut().zip(frame) {
+            history.copy_within(1.., 0);
+            history[TRUE_PEAK_TAPS - 1] = sample;
+            self.peak = self.peak.max(sample.abs());
+            for taps in &self.phases {
+                let value: f32 = taps.iter().zip(history.iter()).map(|(t, s)| t * s).sum();
+                self.peak = self.peak.max(value.abs());
+            }
+        }
+    }
+}
+
+/// Counts loudness values in fine bins, so gated means and percentiles over arbitrarily long
+/// input take constant memory and time.
+struct Histogram {
+    counts: Vec<u64>,
+    /// Sum of the mean squares that fell in each bin
+    energies: Vec<f64>,
+}
+
+impl Histogram {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn new() -> Self {
+        let bins = ((HISTOGRAM_MAX_LUFS - HISTOGRAM_MIN_LUFS) / HISTOGRAM_STEP_LU) as usize;
+        Self { counts: vec![0; bins], energies: vec![0.0; bins] }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn bin(&self, lufs: f64) -> usize {
+        let bin = ((lufs - HISTOGRAM_MIN_LUFS) / HISTOGRAM_STEP_LU).max(0.0) as usize;
+        bin.min(self.counts.len() - 1)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn bin_lufs(bin: usize) -> f64 {
+        HISTOGRAM_MIN_LUFS + (bin as f64 + 0.5) * HISTOGRAM_STEP_LU
+    }
+
+    /// Records a block, unless it falls under the absolute gate.
+    fn add(&mut self, energy: f64) {
+        let lufs = loudness(energy);
+        if lufs > ABSOLUTE_GATE_LUFS {
+            let bin = self.bin(lufs);
+            self.counts[bin] += 1;
+            self.energies[bin] += energy;
+        }
+    }
+
+    /// Mean square of the blocks at or above `gate` LUFS, or 0 if there are none.
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_above(&self, gate: f64) -> f64 {
+        let from = self.bin(gate);
+        let count: u64 = self.counts[from..].iter().sum();
+        if count == 0 {
+            return 0.0;
+        }
+        self.energies[from..].iter().sum::<f64>() / count as f64
+    }
+
+    /// Loudness at or above `gate` that `fraction` of the counted blocks fall below.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn percentile_above(&self, gate: f64, fraction: f64) -> f64 {
+        let from = self.bin(gate);
+        let count: u64 = self.counts[from..].iter().sum();
+        let rank = ((count.saturating_sub(1)) as f64 * fraction).round() as u64;
+        let mut seen = 0;
+        for (bin, &n) in self.counts.iter().enumerate().skip(from) {
+            seen += n;
+            if seen > rank {
+                return Self::bin_lufs(bin);
+            }
+        }
+        f64::NEG_INFINITY
+    }
+}
+
+/// Loudness measurement of an interleaved stream with a fixed format.
+struct LoudnessMeter {
+    sample_rate: u32,
+    channels: usize,
+    weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+    /// Frames per 100 ms hop, and how many of them the current hop holds
+    hop_len: usize,
+    hop_pos: usize,
+    hop_energy: f64,
+    /// Mean squares of the last 30 hops (3 s), oldest first
+    hops: VecDeque<f64>,
+    /// 400 ms blocks for the integrated loudness
+    blocks: Histogram,
+    /// 3 s blocks for the loudness range
+    short_terms: Histogram,
+    true_peak: TruePeak,
+    frames: u64,
+}
+
+impl LoudnessMeter {
+    fn new(sample_rate: u32, channels: usize) -> Self {
+        let channels = channels.max(1);
+        let filters = vec![k_weighting(sample_rate); channels];
+        Self {
+            sample_rate,
+            channels,
+            weights: channel_weights(channels),
+            filters,
+            hop_len: (sample_rate as usize / 10).max(1),
+            hop_pos: 0,
+            hop_energy: 0.0,
+            hops: VecDeque::with_capacity(30),
+            blocks: Histogram::new(),
+            short_terms: Histogram::new(),
+            true_peak: TruePeak::new(sample_rate, channels),
+            frames: 0,
+        }
+    }
+
+    /// Fails if `frame` doesn't have the format this meter was created for.
+    fn check(&self, frame: &AudioFrame) -> Result<(), StreamKitError> {
+        if frame.sample_rate == self.sample_rate && usize::from(frame.channels) == self.channels {
+            Ok(())
+        } else {
+            Err(StreamKitError::Runtime(format!(
+                "Audio changed from {} Hz/{} ch to {} Hz/{} ch mid-stream; resample it to one \
+                 format before normalizing",
+                self.sample_rate, self.channels, frame.sample_rate, frame.channels
+            )))
+        }
+    }
+
+    /// Measures one frame (a sample per channel); returns true when it completes a 100 ms hop.
+    #[allow(clippy::cast_precision_loss)] // Hop lengths are far below f64's exact integer range
+    fn push(&mut self, frame: &[f32]) -> bool {
+        for ((filters, weight), &sample) in self.filters.iter_mut().zip(&self.weights).zip(frame) {
+            let [head_shelf, high_pass] = filters;
+            let y = high_pass.process(head_shelf.process(f64::from(sample)));
+            self.hop_energy += weight * y * y;
+        }
+        self.true_peak.push(frame);
+        self.frames += 1;
+        self.hop_pos += 1;
+        if self.hop_pos < self.hop_len {
+            return false;
+        }
+
+        if self.hops.len() == 30 {
+            self.hops.pop_front();
+        }
+        self.hops.push_back(self.hop_energy / self.hop_len as f64);
+        self.hop_pos = 0;
+        self.hop_energy = 0.0;
+        if self.hops.len() >= 4 {
+            self.blocks.add(self.mean_of_last(4));
+        }
+        if self.hops.len() == 30 {
+            self.short_terms.add(self.mean_of_last(30));
+        }
+        true
+    }
+
+    fn push_samples(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(self.channels) {
+            self.push(frame);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_of_last(&self, hops: usize) -> f64 {
+        self.hops.iter().rev().take(hops).sum::<f64>() / hops as f64
+    }
+
+    /// Loudness of the last 400 ms.
+    fn momentary(&self) -> f64 {
+        if self.hops.len() >= 4 {
+            loudness(self.mean_of_last(4))
+        } else {
+            f64::NEG_INFINITY
+        }
+    }
+
+    /// Loudness of the last 3 s.
+    fn short_term(&self) -> f64 {
+        if self.hops.len() == 30 {
+            loudness(self.mean_of_last(30))
+        } else {
+            f64::NEG_INFINITY
+        }
+    }
+
+    /// Gated loudness of everything measured so far.
+    fn integrated(&self) -> f64 {
+        let ungated = loudness(self.blocks.mean_above(ABSOLUTE_GATE_LUFS));
+        loudness(self.blocks.mean_above(ungated - 10.0))
+    }
+
+    /// Spread between the quiet and loud parts (10th to 95th percentile of short-term
+    /// loudness), in LU.
+    fn loudness_range(&self) -> f64 {
+        let ungated = loudness(self.short_terms.mean_above(ABSOLUTE_GATE_LUFS));
+        if ungated.is_infinite() {
+            return 0.0;
+        }
+        let gate = ungated - 20.0;
+        self.short_terms.percentile_above(gate, 0.95) - self.short_terms.percentile_above(gate, 0.1)
+    }
+
+    fn true_peak_db(&self) -> f64 {
+        amplitude_db(self.true_peak.peak)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn duration_s(&self) -> f64 {
+        self.frames as f64 / f64::from(self.sample_rate)
+    }
+
+    /// Summary used in telemetry; unmeasurable values (e.g. silence) serialize as null.
+    fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "integrated_lufs": self.integrated(),
+            "true_peak_dbtp": self.true_peak_db(),
+            "loudness_range_lu": self.loudness_range(),
+        })
+    }
+}
+
+/// Gain for two-pass normalization of a measured input, in dB.
+#[allow(clippy::cast_possible_truncation)] // Gains are small
+fn two_pass_gain_db(config: &AudioLoudnormConfig, meter: &LoudnessMeter) -> f32 {
+    let integrated = meter.integrated();
+    if !integrated.is_finite() {
+        // Silence (or nothing above the gate) can't be normalized.
+        return 0.0;
+    }
+    let mut gain = (f64::from(config.target_lufs) - integrated).min(f64::from(config.max_gain_db));
+    let true_peak = meter.true_peak_db();
+    if true_peak.is_finite() && true_peak + gain > f64::from(config.true_peak_db) {
+        gain = f64::from(config.true_peak_db) - true_peak;
+    }
+    gain as f32
+}
+
+/// Continuously steers the gain towards the target from the loudness measured so far.
+struct SinglePass {
+    target_lufs: f64,
+    max_gain_db: f64,
+    ceiling: f32,
+    gain_db: f32,
+    desired_db: f32,
+    /// Whether any audio has been normalized yet
+    started: bool,
+    /// Gain reduction of the peak limiter, 1 when idle
+    limit: f32,
+    smoothing: f32,
+    release: f32,
+}
+
+impl SinglePass {
+    #[allow(clippy::cast_precision_loss)] // Sample rates are far below f32's exact integer range
+    fn new(config: &AudioLoudnormConfig, sample_rate: u32) -> Self {
+        let coefficient = |ms: f32| (-1.0 / (ms * 0.001 * sample_rate as f32)).exp();
+        Self {
+            target_lufs: f64::from(config.target_lufs),
+            max_gain_db: f64::from(config.max_gain_db),
+            ceiling: db_to_gain(config.true_peak_db),
+            gain_db: 0.0,
+            desired_db: 0.0,
+            started: false,
+            limit: 1.0,
+            smoothing: coefficient(SMOOTHING_MS),
+            release: coefficient(LIMITER_RELEASE_MS),
+        }
+    }
+
+    /// Measures interleaved `samples` with `meter`, retargeting the gain at every new block.
+    #[allow(clippy::cast_possible_truncation)]
+    fn measure(&mut self, samples: &[f32], meter: &mut LoudnessMeter) {
+        for frame in samples.chunks_exact(meter.channels) {
+            if meter.push(frame) {
+                let integrated = meter.integrated();
+                if integrated.is_finite() {
+                    self.desired_db = (self.target_lufs - integrated).min(self.max_gain_db) as f32;
+                }
+            }
+        }
+    }
+
+    /// Normalizes interleaved `samples` in place towards the gain measured so far.
+    ///
+    /// The first call starts at the measured gain, so lookahead spares the output the settling
+    /// time.
+    fn apply(&mut self, samples: &mut [f32], channels: usize) {
+        if !self.started {
+            self.started = true;
+            self.gain_db = self.desired_db;
+        }
+        for frame in samples.chunks_exact_mut(channels) {
+            self.gain_db = self.desired_db + self.smoothing * (self.gain_db - self.desired_db);
+            self.limit = 1.0 - self.release * (1.0 - self.limit);
+
+            let gain = db_to_gain(self.gain_db);
+            let peak = frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            if peak * gain * self.limit > self.ceiling {
+                self.limit = self.ceiling / (peak * gain);
+            }
+            let gain = gain * self.limit;
+            for sample in frame {
+                *sample *= gain;
+            }
+        }
+    }
+}
+
+/// A node that normalizes the loudness of raw audio to an integrated-loudness (LUFS) and
+/// true-peak target, as used for podcast and broadcast delivery.
+///
+/// Emits `loudness.stats` telemetry while running in `single_pass` mode and a
+/// `loudness.summary` event with the input and output measurements when the input ends.
+pub struct AudioLoudnormNode {
+    config: AudioLoudnormConfig,
+}
+
+/// Outcome of one mode's processing loop.
+#[allow(clippy::large_enum_variant)] // Returned once per run
+enum Finish {
+    /// The input ended; the measurements and the last gain go into the summary
+    Done { input: Option<LoudnessMeter>, output: Option<LoudnessMeter>, gain_db: f32 },
+    /// The node stopped early with this reason
+    Stopped(&'static str),
+}
+
+impl AudioLoudnormNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioLoudnormConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid loudnorm configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioLoudnormNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn lookahead(&self) -> Option<LookaheadSpec> {
+        (self.config.mode == LoudnormMode::SinglePass).then(|| {
+            LookaheadSpec::new("in", Duration::from_secs_f32(self.config.lookahead_ms / 1000.0))
+        })
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let config = self.config;
+
+        let finish = match config.mode {
+            LoudnormMode::TwoPass => {
+                let input_rx = context.take_input("in")?;
+                state_helpers::emit_running(&context.state_tx, &node_name);
+                run_two_pass(&config, &mut context, input_rx, &mut stats).await
+            },
+            LoudnormMode::SinglePass => {
+                let events_rx = context.take_lookahead()?;
+                state_helpers::emit_running(&context.state_tx, &node_name);
+                run_single_pass(&config, &mut context, events_rx, &mut stats, &telemetry).await
+            },
+        };
+        let (input, output, gain_db) = match finish {
+            Ok(Finish::Done { input, output, gain_db }) => (input, output, gain_db),
+            Ok(Finish::Stopped(reason)) => {
+                stats.force_send();
+                state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+                return Ok(());
+            },
+            Err(e) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                return Err(e);
+            },
+        };
+
+        if let (Some(input), Some(output)) = (&input, &output) {
+            tracing::info!(
+                "Normalized {:.1} LUFS / {:.1} dBTP to {:.1} LUFS / {:.1} dBTP",
+                input.integrated(),
+                input.true_peak_db(),
+                output.integrated(),
+                output.true_peak_db()
+            );
+            telemetry.emit(
+                "loudness.summary",
+                serde_json::json!({
+                    "mode": config.mode,
+                    "input": input.summary(),
+                    "output": output.summary(),
+                    "gain_db": gain_db,
+                }),
+            );
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+/// Starts measuring with the first frame's format and checks later frames against it.
+///
+/// Returns `Ok(false)` for frames without a sample rate, which are skipped.
+fn meter_input(
+    meter: &mut Option<LoudnessMeter>,
+    frame: &AudioFrame,
+) -> Result<bool, StreamKitError> {
+    if frame.sample_rate == 0 {
+        return Ok(false);
+    }
+    meter
+        .get_or_insert_with(|| LoudnessMeter::new(frame.sample_rate, usize::from(frame.channels)))
+        .check(frame)?;
+    Ok(true)
+}
+
+/// Buffers and measures the whole input, then plays it out with one gain.
+async fn run_two_pass(
+    config: &AudioLoudnormConfig,
+    context: &mut NodeContext,
+    mut input_rx: mpsc::Receiver<Packet>,
+    stats: &mut NodeStatsTracker,
+) -> Result<Finish, StreamKitError> {
+    let mut input: Option<LoudnessMeter> = None;
+    let mut buffered: Vec<AudioFrame> = Vec::new();
+
+    while let Some(packet) = context.recv_with_cancellation(&mut input_rx).await {
+        stats.received();
+        let Packet::Audio(frame) = packet else {
+            stats.discarded();
+            continue;
+        };
+        if !meter_input(&mut input, &frame)? {
+            stats.discarded();
+            continue;
+        }
+        let Some(meter) = input.as_mut() else { continue };
+        meter.push_samples(frame.samples());
+        if meter.duration_s() > f64::from(config.max_buffer_s) {
+            return Err(StreamKitError::Runtime(format!(
+                "Input is longer than max_buffer_s ({} s); raise it or use single_pass mode",
+                config.max_buffer_s
+            )));
+        }
+        buffered.push(frame);
+        stats.maybe_send();
+    }
+    if context.cancellation_token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+        return Ok(Finish::Stopped("cancelled"));
+    }
+
+    let Some(meter) = input else {
+        return Ok(Finish::Done { input: None, output: None, gain_db: 0.0 });
+    };
+    let gain_db = two_pass_gain_db(config, &meter);
+    let gain = db_to_gain(gain_db);
+    let mut output = LoudnessMeter::new(meter.sample_rate, meter.channels);
+    for mut frame in buffered {
+        for sample in frame.make_samples_mut() {
+            *sample *= gain;
+        }
+        output.push_samples(frame.samples());
+        if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+            tracing::debug!("Output channel closed, stopping node");
+            return Ok(Finish::Stopped("output_closed"));
+        }
+        stats.sent();
+        stats.maybe_send();
+    }
+    Ok(Finish::Done { input: Some(meter), output: Some(output), gain_db })
+}
+
+/// Measures audio as it is peeked and normalizes it as it leaves the lookahead window.
+async fn run_single_pass(
+    config: &AudioLoudnormConfig,
+    context: &mut NodeContext,
+    mut events_rx: mpsc::Receiver<LookaheadEvent>,
+    stats: &mut NodeStatsTracker,
+    telemetry: &TelemetryEmitter,
+) -> Result<Finish, StreamKitError> {
+    let mut input: Option<LoudnessMeter> = None;
+    let mut output: Option<LoudnessMeter> = None;
+    let mut normalizer: Option<SinglePass> = None;
+    let mut next_stats_s = f64::from(config.stats_interval_ms) / 1000.0;
+
+    loop {
+        let event = if let Some(token) = &context.cancellation_token {
+            tokio::select! {
+                () = token.cancelled() => return Ok(Finish::Stopped("cancelled")),
+                event = events_rx.recv() => event,
+            }
+        } else {
+            events_rx.recv().await
+        };
+        let Some(event) = event else { break };
+
+        match event {
+            LookaheadEvent::Peek(packet) => {
+                stats.received();
+                let Packet::Audio(frame) = packet else { continue };
+                if !meter_input(&mut input, &frame)? {
+                    continue;
+                }
+                let Some(meter) = input.as_mut() else { continue };
+                let normalizer =
+                    normalizer.get_or_insert_with(|| SinglePass::new(config, frame.sample_rate));
+                normalizer.measure(frame.samples(), meter);
+                if meter.duration_s() >= next_stats_s {
+                    next_stats_s += f64::from(config.stats_interval_ms) / 1000.0;
+                    telemetry.emit(
+                        "loudness.stats",
+                        serde_json::json!({
+                            "position_ms": (meter.duration_s() * 1000.0).round(),
+                            "momentary_lufs": meter.momentary(),
+                            "short_term_lufs": meter.short_term(),
+                            "integrated_lufs": meter.integrated(),
+                            "gain_db": normalizer.desired_db,
+                        }),
+                    );
+                }
+            },
+            LookaheadEvent::Delayed(packet) => {
+                // Frames rejected when peeked are dropped here.
+                let (Packet::Audio(mut frame), Some(normalizer)) = (packet, normalizer.as_mut())
+                else {
+                    stats.discarded();
+                    continue;
+                };
+                if frame.sample_rate == 0 {
+                    stats.discarded();
+                    continue;
+                }
+                // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                let channels = usize::from(frame.channels);
+                normalizer.apply(frame.make_samples_mut(), channels);
+                output
+                    .get_or_insert_with(|| {
+                        LoudnessMeter::new(frame.sample_rate, usize::from(frame.channels))
+                    })
+                    .push_samples(frame.samples());
+                if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    return Ok(Finish::Stopped("output_closed"));
+                }
+                stats.sent();
+                stats.maybe_send();
+            },
+        }
+    }
+
+    let gain_db = normalizer.as_ref().map_or(0.0, |normalizer| normalizer.gain_db);
+    Ok(Finish::Done { input, output, gain_db })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)] // Test assertions and signal math
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::lookahead::LookaheadBuffer;
+
+    const RATE: u32 = 48_000;
+
+    /// `ms` milliseconds of a sine at `frequency` and `amplitude`, starting at `phase`.
+    fn sine(frequency: f32, amplitude: f32, ms: usize, phase: f32) -> Vec<f32> {
+        let len = RATE as usize * ms / 1000;
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / RATE as f32;
+                amplitude * (2.0 * std::f32::consts::PI * frequency).mul_add(t, phase).sin()
+            })
+            .collect()
+    }
+
+    fn measure(samples: &[f32]) -> LoudnessMeter {
+        let mut meter = LoudnessMeter::new(RATE, 1);
+        meter.push_samples(samples);
+        meter
+    }
+
+    #[test]
+    fn test_sine_reads_at_reference_loudness() {
+        // A 1 kHz sine peaking at -20 dBFS reads 3 dB below its peak on one channel.
+        let meter = measure(&sine(1000.0, 0.1, 5000, 0.0));
+        assert!((meter.integrated() + 23.01).abs() < 0.1, "got {}", meter.integrated());
+        assert!((meter.momentary() + 23.01).abs() < 0.1);
+        assert!((meter.short_term() + 23.01).abs() < 0.1);
+
+        // The same signal on both channels of a stereo stream adds up.
+        let stereo: Vec<f32> = sine(1000.0, 0.1, 2000, 0.0).iter().flat_map(|&s| [s, s]).collect();
+        let mut meter = LoudnessMeter::new(RATE, 2);
+        meter.push_samples(&stereo);
+        assert!((meter.integrated() + 20.0).abs() < 0.1, "got {}", meter.integrated());
+    }
+
+    #[test]
+    fn test_gates_exclude_silence_and_quiet_passages() {
+        let mut samples = sine(1000.0, 0.1, 3000, 0.0);
+        samples.extend(vec![0.0; RATE as usize * 3]);
+        // 30 dB down: below the relative gate
+        samples.extend(sine(1000.0, 0.003_162, 3000, 0.0));
+        // Only the 400 ms blocks straddling the changes pull the result down slightly.
+        let meter = measure(&samples);
+        assert!((meter.integrated() + 23.01).abs() < 0.3, "got {}", meter.integrated());
+        assert!(measure(&vec![0.0; RATE as usize]).integrated().is_infinite());
+    }
+
+    #[test]
+    fn test_loudness_range_spans_quiet_and_loud_parts() {
+        let mut samples = sine(1000.0, 0.1, 6000, 0.0);
+        samples.extend(sine(1000.0, 0.031_62, 6000, 0.0));
+        let meter = measure(&samples);
+        assert!((meter.loudness_range() - 10.0).abs() < 0.5, "got {}", meter.loudness_range());
+        assert!(measure(&sine(1000.0, 0.1, 5000, 0.0)).loudness_range() < 0.2);
+    }
+
+    #[test]
+    fn test_true_peak_finds_peaks_between_samples() {
+        // At a quarter of the sample rate and 45 degrees off, every sample lands at 0.707.
+        let samples = sine(12_000.0, 1.0, 100, std::f32::consts::FRAC_PI_4);
+        assert!(samples.iter().all(|s| s.abs() < 0.71));
+        let meter = measure(&samples);
+        assert!(meter.true_peak_db().abs() < 0.5, "got {}", meter.true_peak_db());
+    }
+
+    #[test]
+    fn test_two_pass_gain_respects_true_peak_and_max_gain() {
+        let meter = measure(&sine(1000.0, 0.1, 2000, 0.0));
+        let config = AudioLoudnormConfig { target_lufs: -16.0, ..Default::default() };
+        assert!((two_pass_gain_db(&config, &meter) - 7.0).abs() < 0.1);
+
+        // +18 dB would put the -20 dB peak above the -3 dBTP ceiling.
+        let config =
+            AudioLoudnormConfig { target_lufs: -5.0, true_peak_db: -3.0, ..Default::default() };
+        assert!((two_pass_gain_db(&config, &meter) - 17.0).abs() < 0.1);
+
+        let meter = measure(&sine(1000.0, 0.001, 2000, 0.0));
+        let config = AudioLoudnormConfig { max_gain_db: 10.0, ..Default::default() };
+        assert!((two_pass_gain_db(&config, &meter) - 10.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_single_pass_converges_without_exceeding_ceiling() {
+        let config = AudioLoudnormConfig {
+            mode: LoudnormMode::SinglePass,
+            target_lufs: -16.0,
+            ..Default::default()
+        };
+        let mut normalizer = SinglePass::new(&config, RATE);
+        let mut meter = LoudnessMeter::new(RATE, 1);
+        let mut samples = sine(1000.0, 0.05, 10_000, 0.0);
+        normalizer.measure(&samples, &mut meter);
+        normalizer.apply(&mut samples, 1);
+
+        let ceiling = db_to_gain(config.true_peak_db);
+        assert!(samples.iter().all(|s| s.abs() <= ceiling + 1e-6));
+        let tail = measure(&samples[samples.len() - RATE as usize * 2..]);
+        assert!((tail.integrated() + 16.0).abs() < 0.5, "got {}", tail.integrated());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioLoudnormConfig::default().validate().is_ok());
+        assert!(AudioLoudnormConfig { target_lufs: 0.0, ..Default::default() }.validate().is_err());
+        assert!(AudioLoudnormConfig { true_peak_db: 1.0, ..Default::default() }
+            .validate()
+            .is_err());
+
+        let factory = AudioLoudnormNode::factory();
+        assert!(factory(Some(&serde_json::json!({"mode": "single_pass"}))).is_ok());
+        assert!(factory(Some(&serde_json::json!({"stats_interval_ms": 10.0}))).is_err());
+    }
+
+    async fn run_node(
+        params: serde_json::Value,
+        samples: &[f32],
+    ) -> (Result<(), StreamKitError>, Vec<f32>, usize) {
+        let (input_tx, mut input_rx) = mpsc::channel(1000);
+        let node = AudioLoudnormNode::factory()(Some(&params)).unwrap();
+        let (context, mock_sender, mut state_rx) = if let Some(spec) = node.lookahead() {
+            // Stand in for the engine's lookahead task.
+            let (events_tx, events_rx) = mpsc::channel(1000);
+            tokio::spawn(async move {
+                let mut buffer = LookaheadBuffer::new(&spec);
+                let mut events = Vec::new();
+                while let Some(packet) = input_rx.recv().await {
+                    buffer.push(packet, &mut events);
+                }
+                buffer.finish(&mut events);
+                for event in events {
+                    events_tx.send(event).await.unwrap();
+                }
+            });
+            let (mut context, mock_sender, state_rx) = create_test_context(HashMap::new(), 10);
+            context.lookahead = Some(events_rx);
+            (context, mock_sender, state_rx)
+        } else {
+            create_test_context(HashMap::from([("in".to_string(), input_rx)]), 10)
+        };
+
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        for chunk in samples.chunks(960) {
+            let frame = AudioFrame::new(RATE, 1, chunk.to_vec());
+            input_tx.send(Packet::Audio(frame)).await.unwrap();
+        }
+        drop(input_tx);
+        let result = handle.await.unwrap();
+        if result.is_ok() {
+            assert_state_stopped(&mut state_rx).await;
+        }
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let output = packets
+            .iter()
+            .flat_map(|packet| extract_audio_data(packet).unwrap().to_vec())
+            .collect();
+        (result, output, packets.len())
+    }
+
+    #[tokio::test]
+    async fn test_node_normalizes_in_two_passes() {
+        let samples = sine(1000.0, 0.02, 3000, 0.0);
+        let (result, output, packets) =
+            run_node(serde_json::json!({"target_lufs": -16.0, "max_gain_db": 30.0}), &samples)
+                .await;
+        result.unwrap();
+        assert_eq!(packets, 150);
+        assert_eq!(output.len(), samples.len());
+        // One constant gain from the very first sample
+        let meter = measure(&output);
+        assert!((meter.integrated() + 16.0).abs() < 0.1, "got {}", meter.integrated());
+        assert!((output[12] / samples[12] - db_to_gain(20.99)).abs() < 0.05);
+    }
+
+    #[tokio::test]
+    async fn test_node_rejects_input_longer_than_buffer() {
+        let samples = sine(1000.0, 0.02, 2000, 0.0);
+        let (result, output, _) =
+            run_node(serde_json::json!({"max_buffer_s": 1.0}), &samples).await;
+        assert!(result.is_err());
+        assert!(output.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_node_single_pass_lookahead_starts_at_measured_gain() {
+        let samples = sine(1000.0, 0.02, 4000, 0.0);
+        let params = |lookahead_ms: f32| {
+            serde_json::json!({
+                "mode": "single_pass",
+                "target_lufs": -16.0,
+                "max_gain_db": 30.0,
+                "lookahead_ms": lookahead_ms,
+            })
+        };
+
+        // Without lookahead the gain starts flat and settles later.
+        let (result, output, _) = run_node(params(0.0), &samples).await;
+        result.unwrap();
+        assert_eq!(output.len(), samples.len());
+        assert!((output[12] / samples[12] - 1.0).abs() < 0.01);
+
+        // Two seconds ahead, the first sample already gets the ~21 dB boost.
+        let (result, output, packets) = run_node(params(2000.0), &samples).await;
+        result.unwrap();
+        assert_eq!(packets, 200);
+        assert_eq!(output.len(), samples.len());
+        assert!((output[12] / samples[12] - db_to_gain(20.99)).abs() < 0.2);
+        let meter = measure(&output);
+        assert!((meter.integrated() + 16.0).abs() < 0.5, "got {}", meter.integrated());
+    }
+}
@@ -2,12 +2,17 @@
 //
 // SPDX-License-Identifier: MPL-2.0
 
+use crate::audio::filters::resampler::{AudioResamplerConfig, ResamplerChain, ResamplerQuality};
+use crate::audio::layout::Remix;
+use crate::audio::simd;
 use async_trait::async_trait;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::sync::{atomic::AtomicBool, atomic::Ordering, Mutex};
+use std::sync::{atomic::AtomicBool, atomic::AtomicU32, atomic::Ordering, Mutex};
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::NodeControlMessage;
 use streamkit_core::pins::PinManagementMessage;
 use streamkit_core::types::PacketMetadata;
 use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
@@ -21,7 +26,7 @@ use tokio::sync::mpsc;
 #[derive(Deserialize, Debug, Clone, JsonSchema)]
 #[serde(default)]
 pub struct ClockedMixerConfig {
-    /// Output sample rate (Hz). Inputs are expected to already match this.
+    /// Output sample rate (Hz). Inputs at other rates are resampled to it.
     pub sample_rate: u32,
 
     /// Fixed frame size (samples per channel) for the clocked mixer.
@@ -54,6 +59,40 @@ impl Default for ClockedMixerConfig {
     }
 }
 
+/// Per-input mix settings, keyed by input pin name.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+#[serde(default)]
+pub struct MixerInputConfig {
+    /// Linear gain applied to this input
+    #[schemars(range(min = 0.0, max = 4.0))]
+    pub gain: f32,
+    /// Leave this input out of the mix. It still counts towards input synchronization.
+    pub mute: bool,
+}
+
+impl Default for MixerInputConfig {
+    fn default() -> Self {
+        Self { gain: 1.0, mute: false }
+    }
+}
+
+impl MixerInputConfig {
+    fn validate(self) -> Result<(), String> {
+        if !(0.0..=4.0).contains(&self.gain) {
+            return Err(format!("gain must be within 0.0-4.0, got {}", self.gain));
+        }
+        Ok(())
+    }
+
+    const fn effective_gain(self) -> f32 {
+        if self.mute {
+            0.0
+        } else {
+            self.gain
+        }
+    }
+}
+
 /// Configuration for the AudioMixerNode.
 #[derive(Deserialize, Debug, Clone, JsonSchema)]
 #[serde(default)]
@@ -67,7 +106,8 @@ pub struct AudioMixerConfig {
 
     /// Number of input pins to pre-create.
     /// Required for stateless/oneshot pipelines where pins must exist before graph building.
-    /// Optional for dynamic pipelines where pins are created on-demand.
+    /// Not needed for dynamic pipelines, where pins are created on connect and removed on
+    /// disconnect.
     /// If specified, pins will be named in_0, in_1, ..., in_{N-1}.
     pub num_inputs: Option<usize>,
 
@@ -76,6 +116,24 @@ pub struct AudioMixerConfig {
     /// When enabled, the mixer emits frames on a fixed cadence determined by
     /// `sample_rate` and `frame_samples_per_channel`.
     pub clocked: Option<ClockedMixerConfig>,
+
+    /// Output sample rate (Hz). Inputs at other rates are resampled to it.
+    /// If not specified, the first input's rate is used. Clocked mode always runs at
+    /// `clocked.sample_rate`, so this must match it when both are set.
+    #[schemars(range(min = 1))]
+    pub sample_rate: Option<u32>,
+
+    /// Output channel count. Inputs are up- or downmixed to it.
+    /// If not specified, the output follows the highest channel count seen so far.
+    #[schemars(range(min = 1, max = 8))]
+    pub channels: Option<u16>,
+
+    /// Quality preset of the per-input sample rate conversion
+    pub resample_quality: ResamplerQuality,
+
+    /// Settings per input pin, e.g. `{"in_0": {"gain": 0.5}, "in_1": {"mute": true}}`
+    #[schemars(extend("tunable" = true))]
+    pub inputs: HashMap<String, MixerInputConfig>,
 }
 
 impl Default for AudioMixerConfig {
@@ -84,7 +142,49 @@ impl Default for AudioMixerConfig {
         // This provides tolerance for timing jitter, GC pauses, and network variation
         // while still catching truly slow/stuck inputs quickly enough
         // Tests use 100ms and it works well in practice
-        Self { sync_timeout_ms: Some(100), num_inputs: None, clocked: None }
+        Self {
+            sync_timeout_ms: Some(100),
+            num_inputs: None,
+            clocked: None,
+            sample_rate: None,
+            channels: None,
+            resample_quality: ResamplerQuality::default(),
+            inputs: HashMap::new(),
+        }
+    }
+}
+
+impl AudioMixerConfig {
+    /// Validates the output format and the per-input settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sample_rate` is zero, `channels` is outside 1-8, the clocked rate
+    /// disagrees with `sample_rate`, or an input's gain is out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sample_rate == Some(0) {
+            return Err("sample_rate must be greater than 0".to_string());
+        }
+        if let Some(channels) = self.channels {
+            if !(1..=8).contains(&channels) {
+                return Err(format!("channels must be within 1-8, got {channels}"));
+            }
+        }
+        if let (Some(rate), Some(clocked)) = (self.sample_rate, &self.clocked) {
+            if rate != clocked.sample_rate {
+                return Err(format!(
+                    "sample_rate ({rate} Hz) must match clocked.sample_rate ({} Hz)",
+                    clocked.sample_rate
+                ));
+            }
+        }
+        if self.clocked.as_ref().is_some_and(|c| c.sample_rate == 0) {
+            return Err("clocked.sample_rate must be greater than 0".to_string());
+        }
+        for (pin, input) in &self.inputs {
+            input.validate().map_err(|e| format!("inputs.{pin}: {e}"))?;
+        }
+        Ok(())
     }
 }
 
@@ -92,16 +192,27 @@ impl Default for AudioMixerConfig {
 /// This node operates on 32-bit floating-point audio.
 ///
 /// The mixer operates in dynamic mode, supporting runtime pin creation and removal.
+/// In a dynamic session, connecting to an `in_*` pin that doesn't exist yet creates it,
+/// and disconnecting its last source removes it again, so participants can join and leave
+/// a conference without a fixed input count. Once the last input is removed the mixer keeps
+/// running and waits for the next one.
+///
 /// It implements broadcast synchronization: waits for all active pins to provide frames
 /// before mixing (with optional timeout for slow inputs).
 ///
 /// **EOF Handling**: When a pin receives EOF (e.g., file playback completes), it is
 /// automatically removed from the active pin set, and mixing continues with remaining pins.
 ///
-/// **Output Format Stability**: The mixer tracks the maximum channel count it has observed
-/// and never decreases output channels thereafter. This avoids downstream glitches when a
-/// higher-channel input ends and only lower-channel inputs remain (e.g., continued speech
-/// after a stereo music track completes). Mono inputs are upmixed when output is stereo.
+/// **Input Formats**: Inputs may differ in sample rate and channel count. Each input is
+/// resampled to the output rate (`sample_rate`, or the first input's rate) and remixed to the
+/// output channel count, so participants don't each need their own `audio::resampler`.
+/// Per-input `gain` and `mute` are tunable at runtime by pin name.
+///
+/// **Output Format Stability**: Unless `channels` is set, the mixer tracks the maximum channel
+/// count it has observed and never decreases output channels thereafter. This avoids
+/// downstream glitches when a higher-channel input ends and only lower-channel inputs remain
+/// (e.g., continued speech after a stereo music track completes). Mono inputs are upmixed
+/// when output is stereo.
 pub struct AudioMixerNode {
     config: AudioMixerConfig,
     /// Current input pins (may grow dynamically)
@@ -116,6 +227,153 @@ struct InputSlot {
     has_sent: bool,
     slow: bool,
     frame: Option<AudioFrame>,
+    converter: InputConverter,
+}
+
+impl InputSlot {
+    const fn new(name: Arc<str>, rx: mpsc::Receiver<Packet>, quality: ResamplerQuality) -> Self {
+        Self {
+            name,
+            rx,
+            has_sent: false,
+            slow: false,
+            frame: None,
+            converter: InputConverter::new(quality, None),
+        }
+    }
+}
+
+/// Brings one input's frames to the mixer's output rate and applies its gain.
+///
+/// Frames already at the output rate pass through. Otherwise a resampler is built for the
+/// input's rate and channel count, and rebuilt if either changes mid-stream. With
+/// `frame_samples_per_channel` set, resampled audio is re-chunked to that size, since the
+/// clocked mixer expects each input frame to fill one tick.
+struct InputConverter {
+    quality: ResamplerQuality,
+    frame_samples_per_channel: Option<usize>,
+    resampler: Option<InputResampler>,
+}
+
+struct InputResampler {
+    chain: ResamplerChain,
+    input_rate: u32,
+    output_rate: u32,
+    channels: u16,
+    /// Interleaved output not yet emitted as a frame
+    pending: Vec<f32>,
+}
+
+impl InputConverter {
+    const fn new(quality: ResamplerQuality, frame_samples_per_channel: Option<usize>) -> Self {
+        Self { quality, frame_samples_per_channel, resampler: None }
+    }
+
+    /// Converts `frame` to `output_rate`, passing each resulting frame to `emit`. A resampled
+    /// input may yield no frame until enough audio is buffered.
+    fn convert(
+        &mut self,
+        frame: AudioFrame,
+        output_rate: u32,
+        gain: f32,
+        mut emit: impl FnMut(AudioFrame),
+    ) -> Result<(), String> {
+        if frame.sample_rate == output_rate || frame.channels == 0 {
+            self.resampler = None;
+            emit(apply_gain(frame, gain));
+            return Ok(());
+        }
+
+        let channels = usize::from(frame.channels);
+        let reusable = self.resampler.as_ref().is_some_and(|r| {
+            r.input_rate == frame.sample_rate
+                && r.output_rate == output_rate
+                && r.channels == frame.channels
+        });
+        if !reusable {
+            let config = AudioResamplerConfig {
+                target_sample_rate: output_rate,
+                quality: self.quality,
+                window: None,
+                sinc_len: None,
+                chunk_frames: None,
+                output_frame_size: 0,
+            };
+            let chunk_frames =
+                config.resolve_chunk_frames(frame.sample_rate, frame.samples.len() / channels);
+            let chain = ResamplerChain::new(&config, frame.sample_rate, chunk_frames, channels)
+                .map_err(|e| format!("Failed to create resampler: {e}"))?;
+            tracing::debug!(
+                "Mixer: resampling input from {} Hz to {} Hz ({} channels)",
+                frame.sample_rate,
+                output_rate,
+                channels
+            );
+            self.resampler = Some(InputResampler {
+                chain,
+                input_rate: frame.sample_rate,
+                output_rate,
+                channels: frame.channels,
+                pending: Vec::new(),
+            });
+        }
+        let Some(resampler) = self.resampler.as_mut() else { return Ok(()) };
+
+        let planar: Vec<Vec<f32>> = (0..channels)
+            .map(|ch| frame.samples.as_slice().iter().skip(ch).step_by(channels).copied().collect())
+            .collect();
+        let converted =
+            resampler.chain.process(&planar).map_err(|e| format!("Resampling failed: {e}"))?;
+        let output_frames = converted.first().map_or(0, Vec::len);
+        resampler.pending.reserve(output_frames * channels);
+        for i in 0..output_frames {
+            resampler.pending.extend(converted.iter().map(|ch| ch[i]));
+        }
+
+        let chunk_len =
+            self.frame_samples_per_channel.map_or(resampler.pending.len(), |n| n * channels);
+        if chunk_len == 0 {
+            return Ok(());
+        }
+        while resampler.pending.len() >= chunk_len {
+            let samples: Vec<f32> = resampler.pending.drain(..chunk_len).collect();
+            let duration_us = (chunk_len / channels) as u64 * 1_000_000 / u64::from(output_rate);
+            let metadata = frame
+                .metadata
+                .clone()
+                .map(|m| PacketMetadata { duration_us: Some(duration_us), ..m });
+            let output = AudioFrame::with_metadata(output_rate, frame.channels, samples, metadata);
+            emit(apply_gain(output, gain));
+        }
+        Ok(())
+    }
+}
+
+/// Scales a frame by `gain`, leaving unity-gain frames (and their shared buffers) untouched.
+fn apply_gain(mut frame: AudioFrame, gain: f32) -> AudioFrame {
+    if gain <= 0.0 {
+        frame.make_samples_mut().fill(0.0);
+    } else if (gain - 1.0).abs() > f32::EPSILON {
+        simd::scale(frame.make_samples_mut(), gain);
+    }
+    frame
+}
+
+/// Gain of one clocked input, shared with its drainer task so TuneNode updates apply live.
+struct InputLevel(AtomicU32);
+
+impl InputLevel {
+    const fn new(gain: f32) -> Self {
+        Self(AtomicU32::new(gain.to_bits()))
+    }
+
+    fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, gain: f32) {
+        self.0.store(gain.to_bits(), Ordering::Relaxed);
+    }
 }
 
 impl AudioMixerNode {
@@ -171,6 +429,55 @@ impl AudioMixerNode {
 
         (inputs, outputs)
     }
+
+    /// Gain for the input on `pin`, zero when it is muted.
+    fn input_gain(&self, pin: &str) -> f32 {
+        self.config.inputs.get(pin).copied().map_or(1.0, MixerInputConfig::effective_gain)
+    }
+
+    /// Applies a TuneNode update. Only the per-input settings can change at runtime.
+    fn apply_update(&mut self, params: &serde_json::Value) -> Result<(), String> {
+        let Some(inputs) = params.get("inputs").and_then(|p| p.as_object()) else {
+            return Ok(());
+        };
+        let mut updated = self.config.inputs.clone();
+        for (pin, patch) in inputs {
+            let entry = updated.entry(pin.clone()).or_default();
+            *entry = merge_params(entry, patch).map_err(|e| format!("{pin}: {e}"))?;
+            entry.validate().map_err(|e| format!("{pin}: {e}"))?;
+        }
+        self.config.inputs = updated;
+        Ok(())
+    }
+
+    /// Creates an input pin on behalf of the engine, named `in_<n>` unless it suggests a name.
+    fn create_input_pin(
+        &mut self,
+        suggested_name: Option<String>,
+    ) -> Result<InputPin, StreamKitError> {
+        let pin_name = suggested_name.unwrap_or_else(|| {
+            let name = format!("in_{}", self.next_input_id);
+            self.next_input_id += 1;
+            name
+        });
+        if self.input_pins.iter().any(|p| p.name == pin_name) {
+            return Err(StreamKitError::Configuration(format!(
+                "Mixer input pin '{pin_name}' already exists"
+            )));
+        }
+
+        let pin = InputPin {
+            name: pin_name,
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        };
+        self.input_pins.push(pin.clone());
+        Ok(pin)
+    }
 }
 
 #[async_trait]
@@ -238,8 +545,13 @@ impl AudioMixerNode {
         let cancellation_token = context.cancellation_token.clone();
 
         let frame_samples_per_channel = clocked.frame_samples_per_channel.max(1);
-        let jitter_buffer_frames = clocked.jitter_buffer_frames.max(1);
         let clocked_sample_rate = clocked.sample_rate;
+        let drainer_format = DrainerFormat {
+            jitter_buffer_frames: clocked.jitter_buffer_frames.max(1),
+            sample_rate: clocked_sample_rate,
+            frame_samples_per_channel,
+            quality: self.config.resample_quality,
+        };
         let clocked_generate_silence = clocked.generate_silence;
         let tick_duration = {
             let nanos_per_sec = 1_000_000_000u64;
@@ -251,7 +563,7 @@ impl AudioMixerNode {
         let output_mailbox = Arc::new(OutputMailbox::new());
         let (audio_cmd_tx, audio_cmd_rx) = std::sync::mpsc::channel::<AudioThreadCommand>();
 
-        // Drainers (and the clocked audio thread) report events back to this management loop.
+        // Drainers report events back to this management loop.
         let (input_event_tx, mut input_event_rx) = mpsc::channel::<InputEvent>(32);
 
         // Audio thread drives mixing and writes frames to output_mailbox.
@@ -260,9 +572,9 @@ impl AudioMixerNode {
         let stop_flag = Arc::new(AtomicBool::new(false));
         let stop_flag_thread = stop_flag.clone();
         let output_mailbox_thread = output_mailbox.clone();
-        let input_event_tx_thread = input_event_tx.clone();
 
         let sync_timeout = self.config.sync_timeout_ms.map(std::time::Duration::from_millis);
+        let output_channels = self.config.channels;
 
         let node_name_thread = node_name.clone();
         let audio_thread = std::thread::Builder::new()
@@ -271,13 +583,13 @@ impl AudioMixerNode {
                 let config = ClockedThreadConfig {
                     node_name: node_name_thread,
                     sample_rate: clocked_sample_rate,
+                    channels: output_channels,
                     frame_samples_per_channel,
                     tick_duration,
                     generate_silence: clocked_generate_silence,
                     sync_timeout,
                     audio_pool,
                     state_tx,
-                    input_event_tx: input_event_tx_thread,
                     output_mailbox: output_mailbox_thread,
                     cmd_rx: audio_cmd_rx,
                     stop_flag: stop_flag_thread,
@@ -313,19 +625,22 @@ impl AudioMixerNode {
         });
 
         let mut drainers: HashMap<Arc<str>, tokio::task::JoinHandle<()>> = HashMap::new();
+        let mut levels: HashMap<Arc<str>, Arc<InputLevel>> = HashMap::new();
         let mut stop_reason: &'static str = "shutdown";
 
         for (pin_name, rx) in context.inputs {
+            let level = Arc::new(InputLevel::new(self.input_gain(&pin_name)));
             let (name, handle) = start_clocked_input_drainer(
                 &audio_cmd_tx,
                 &input_event_tx,
                 cancellation_token.clone(),
-                jitter_buffer_frames,
-                clocked_sample_rate,
+                drainer_format,
                 pin_name,
                 rx,
+                level.clone(),
             );
-            drainers.insert(name, handle);
+            drainers.insert(name.clone(), handle);
+            levels.insert(name, level);
         }
 
         // Main control loop: pin management, shutdown, EOF removals.
@@ -339,37 +654,22 @@ impl AudioMixerNode {
                 } => {
                     match msg {
                         PinManagementMessage::RequestAddInputPin { suggested_name, response_tx } => {
-                            let pin_name = suggested_name.unwrap_or_else(|| {
-                                let name = format!("in_{}", self.next_input_id);
-                                self.next_input_id += 1;
-                                name
-                            });
-
-                            let pin = InputPin {
-                                name: pin_name.clone(),
-                                accepts_types: vec![PacketType::RawAudio(AudioFormat {
-                                    sample_rate: 0,
-                                    channels: 0,
-                                    sample_format: SampleFormat::F32,
-                                })],
-                                cardinality: PinCardinality::One,
-                            };
-
-                            self.input_pins.push(pin.clone());
-                            let _ = response_tx.send(Ok(pin));
+                            let _ = response_tx.send(self.create_input_pin(suggested_name));
                         }
                         PinManagementMessage::AddedInputPin { pin, channel } => {
                             tracing::info!("Mixer (clocked): Activated input pin {}", pin.name);
+                            let level = Arc::new(InputLevel::new(self.input_gain(&pin.name)));
                             let (name, handle) = start_clocked_input_drainer(
                                 &audio_cmd_tx,
                                 &input_event_tx,
                                 cancellation_token.clone(),
-                                jitter_buffer_frames,
-                                clocked_sample_rate,
+                                drainer_format,
                                 pin.name,
                                 channel,
+                                level.clone(),
                             );
-                            drainers.insert(name, handle);
+                            drainers.insert(name.clone(), handle);
+                            levels.insert(name, level);
                         }
                         PinManagementMessage::RemoveInputPin { pin_name } => {
                             tracing::info!("Mixer (clocked): Removed input pin {}", pin_name);
@@ -377,55 +677,46 @@ impl AudioMixerNode {
                             if let Some(handle) = drainers.remove(&key) {
                                 handle.abort();
                             }
+                            levels.remove(&key);
                             let _ = audio_cmd_tx.send(AudioThreadCommand::RemoveInput { name: key });
                             self.input_pins.retain(|p| p.name != pin_name);
-
-                            if drainers.is_empty() {
-                                stop_reason = "all_inputs_closed";
-                                break;
-                            }
+                            // Keep running with no inputs: pins are only removed by the
+                            // engine, which can add new ones later (e.g. a participant joining).
                         }
                         _ => {}
                     }
                 }
 
-                Some(control_msg) = context.control_rx.recv() => {
-                    if matches!(control_msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                Some(control_msg) = context.control_rx.recv() => match control_msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        if let Err(e) = self.apply_update(&params) {
+                            tracing::warn!("Rejected audio mixer update: {}", e);
+                            stats_tracker.errored();
+                            continue;
+                        }
+                        for (name, level) in &levels {
+                            level.set(self.input_gain(name));
+                        }
+                    }
+                    NodeControlMessage::Shutdown => {
                         tracing::info!("AudioMixerNode shutting down (shutdown requested)");
                         break;
                     }
-                }
+                    _ => {}
+                },
 
                 Some(event) = input_event_rx.recv() => {
                     match event {
                         InputEvent::Eof(name) => {
                             tracing::info!("Mixer (clocked): Input {} reached EOF", name);
                             let _ = drainers.remove(&name);
+                            levels.remove(&name);
                             let _ = audio_cmd_tx.send(AudioThreadCommand::RemoveInput { name });
                             if drainers.is_empty() {
                                 stop_reason = "all_inputs_closed";
                                 break;
                             }
                         }
-                        InputEvent::SampleRateMismatch { pin_name, expected, got } => {
-                            let err_msg = format!(
-                                "Sample rate mismatch on pin '{pin_name}': expected {expected} Hz, got {got} Hz"
-                            );
-                            tracing::error!("Mixer (clocked): {}", err_msg);
-                            stats_tracker.errored();
-                            stats_tracker.force_send();
-                            state_helpers::emit_failed(&context.state_tx, &node_name, err_msg.clone());
-                            // Don't set stop_reason - we're failing, not stopping normally
-                            for (_name, handle) in drainers.drain() {
-                                handle.abort();
-                            }
-                            stop_flag.store(true, Ordering::Relaxed);
-                            let _ = audio_cmd_tx.send(AudioThreadCommand::Shutdown);
-                            output_mailbox.shutdown();
-                            let _ = audio_thread.join();
-                            output_forwarder.abort();
-                            return Err(StreamKitError::Runtime(err_msg));
-                        }
                     }
                 }
             }
@@ -491,19 +782,17 @@ impl AudioMixerNode {
         }
 
         // Start with inputs from context (if any were pre-connected)
+        let quality = self.config.resample_quality;
         for (name, rx) in context.inputs {
-            slots.push(InputSlot {
-                name: Arc::from(name),
-                rx,
-                has_sent: false,
-                slow: false,
-                frame: None,
-            });
+            slots.push(InputSlot::new(Arc::from(name), rx, quality));
         }
 
         // Track the maximum observed channel count; never decreases (format stability).
         let mut max_output_channels_seen: u16 = 0;
 
+        // Inputs are converted to this rate; without a configured one the first input sets it.
+        let mut output_rate = self.config.sample_rate;
+
         // Track last mix time for timeout detection
         let mut waiting_since: Option<std::time::Instant> = None;
         let mut has_warned_slow = false;
@@ -554,6 +843,8 @@ impl AudioMixerNode {
         loop {
             // Determine if we have a timeout configured
             let sync_timeout = self.config.sync_timeout_ms.map(tokio::time::Duration::from_millis);
+            // Checked up front: the pin management branch borrows the receiver during select!
+            let accepts_new_pins = pin_mgmt_rx.as_ref().is_some_and(|rx| !rx.is_closed());
 
             tokio::select! {
                 // Handle pin management messages (only in fully dynamic mode)
@@ -565,38 +856,17 @@ impl AudioMixerNode {
                 } => {
                     match msg {
                         PinManagementMessage::RequestAddInputPin { suggested_name, response_tx } => {
-                            // Create new input pin
-                            let pin_name = suggested_name.unwrap_or_else(|| {
-                                let name = format!("in_{}", self.next_input_id);
-                                self.next_input_id += 1;
-                                name
-                            });
-
-                            let pin = InputPin {
-                                name: pin_name.clone(),
-                                accepts_types: vec![PacketType::RawAudio(AudioFormat {
-                                    sample_rate: 0,
-                                    channels: 0,
-                                    sample_format: SampleFormat::F32,
-                                })],
-                                cardinality: PinCardinality::One,
-                            };
-
-                            self.input_pins.push(pin.clone());
-                            tracing::info!("Mixer: Created input pin {}", pin_name);
-                            let _ = response_tx.send(Ok(pin));
+                            let result = self.create_input_pin(suggested_name);
+                            if let Ok(pin) = &result {
+                                tracing::info!("Mixer: Created input pin {}", pin.name);
+                            }
+                            let _ = response_tx.send(result);
                         }
 
                         PinManagementMessage::AddedInputPin { pin, channel } => {
                             // Engine has created the channel, start receiving
                             tracing::info!("Mixer: Activated input pin {}", pin.name);
-                            slots.push(InputSlot {
-                                name: Arc::from(pin.name),
-                                rx: channel,
-                                has_sent: false,
-                                slow: false,
-                                frame: None,
-                            });
+                            slots.push(InputSlot::new(Arc::from(pin.name), channel, quality));
                         }
 
                         PinManagementMessage::RemoveInputPin { pin_name } => {
@@ -625,15 +895,22 @@ impl AudioMixerNode {
                     }
                     }
 
-                    // Support explicit shutdown via control message.
-                    Some(control_msg) = context.control_rx.recv() => {
-                        if matches!(control_msg, streamkit_core::control::NodeControlMessage::Shutdown) {
+                    // Per-input gain/mute updates and explicit shutdown via control message.
+                    Some(control_msg) = context.control_rx.recv() => match control_msg {
+                        NodeControlMessage::UpdateParams(params) => {
+                            if let Err(e) = self.apply_update(&params) {
+                                tracing::warn!("Rejected audio mixer update: {}", e);
+                                stats_tracker.errored();
+                            }
+                        }
+                        NodeControlMessage::Shutdown => {
                             state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
                             tracing::info!("AudioMixerNode shutting down (shutdown requested)");
                             stats_tracker.force_send();
                             return Ok(());
                         }
-                    }
+                        _ => {}
+                    },
 
                     // Timeout-based mixing with silence, even when no new packets arrive.
                     () = async {
@@ -709,7 +986,12 @@ impl AudioMixerNode {
                     }
 
                     // Receive from any input (we can't select! over a dynamic list of receivers)
-                    result = Self::recv_from_any(&mut slots, &mut round_robin_idx, cancellation_token.as_ref()) => {
+                    result = Self::recv_from_any(
+                        &mut slots,
+                        &mut round_robin_idx,
+                        cancellation_token.as_ref(),
+                        accepts_new_pins,
+                    ) => {
                         match result {
                             RecvResult::Audio(slot_idx, frame) => {
                                 if slot_idx >= slots.len() {
@@ -718,6 +1000,28 @@ impl AudioMixerNode {
 
                                 stats_tracker.received();
 
+                                let rate = *output_rate.get_or_insert(frame.sample_rate);
+                                let gain = self.input_gain(&slots[slot_idx].name);
+                                let mut converted = None;
+                                if let Err(e) = slots[slot_idx].converter.convert(
+                                    frame,
+                                    rate,
+                                    gain,
+                                    |f| converted = Some(f),
+                                ) {
+                                    tracing::warn!(
+                                        "Mixer: dropping frame from pin {}: {}",
+                                        slots[slot_idx].name,
+                                        e
+                                    );
+                                    stats_tracker.errored();
+                                    continue;
+                                }
+                                // The resampler may still be filling its first chunk.
+                                let Some(frame) = converted else {
+                                    continue;
+                                };
+
                                 // Track maximum output channels; never decreases.
                                 max_output_channels_seen = max_output_channels_seen.max(frame.channels);
 
@@ -945,7 +1249,11 @@ impl AudioMixerNode {
         // Output channels never decrease across the lifetime of the node, to avoid downstream
         // format flips when a higher-channel input ends.
         let current_max = mix_frames.iter().map(|f| f.channels).max().unwrap_or(1);
-        let output_channels = max_output_channels_seen.max(current_max).max(1);
+        let output_channels = self
+            .config
+            .channels
+            .unwrap_or_else(|| max_output_channels_seen.max(current_max))
+            .max(1);
         let sample_rate = mix_frames.first().map(|f| f.sample_rate).unwrap_or_default();
 
         // Calculate output frame size based on the longest input after channel conversion
@@ -1022,7 +1330,7 @@ impl AudioMixerNode {
     /// Supports:
     /// - Mono (1ch) -> Stereo (2ch): Duplicate mono signal to both channels
     /// - Stereo (2ch) -> Stereo (2ch): Direct mixing
-    /// - Other configurations: Basic channel mapping
+    /// - Other configurations: Standard speaker-layout remix (e.g. 5.1 -> Stereo per ITU-R BS.775)
     #[allow(clippy::needless_range_loop)]
     fn mix_frame_with_channel_conversion(
         output: &mut [f32],
@@ -1039,11 +1347,7 @@ impl AudioMixerNode {
         if source_channels == output_channels {
             // Same channel count: direct sample-wise mixing
             let mix_len = mix_samples_per_channel * output_channels as usize;
-            for (out_sample, src_sample) in
-                output.iter_mut().zip(source.samples.iter()).take(mix_len)
-            {
-                *out_sample += src_sample;
-            }
+            simd::accumulate(&mut output[..mix_len], &source.samples[..mix_len]);
         } else if source_channels == 1 && output_channels == 2 {
             // Mono to stereo: duplicate mono signal to both L and R channels
             for i in 0..mix_samples_per_channel {
@@ -1060,20 +1364,10 @@ impl AudioMixerNode {
                 output[i] += (left + right) * 0.5;
             }
         } else {
-            // Generic fallback: map channels cyclically
-            tracing::warn!(
-                "Mixing {} channels into {} channels using generic fallback",
-                source_channels,
-                output_channels
-            );
-            for i in 0..mix_samples_per_channel {
-                for ch in 0..(output_channels as usize) {
-                    let source_ch = ch % source_channels as usize;
-                    let source_idx = i * source_channels as usize + source_ch;
-                    let output_idx = i * output_channels as usize + ch;
-                    output[output_idx] += source.samples[source_idx];
-                }
-            }
+            // Other layouts: standard surround up/downmix (see `audio::layout`)
+            let mix_len = mix_samples_per_channel * source_channels as usize;
+            Remix::new(source_channels, output_channels)
+                .accumulate(&source.samples[..mix_len], output);
         }
     }
 
@@ -1093,11 +1387,20 @@ impl AudioMixerNode {
         slots: &mut [InputSlot],
         round_robin_idx: &mut usize,
         cancellation_token: Option<&tokio_util::sync::CancellationToken>,
+        accepts_new_pins: bool,
     ) -> RecvResult {
         if slots.is_empty() {
-            // No receivers, wait indefinitely (will be woken by pin management)
-            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
-            return RecvResult::AllClosed;
+            if !accepts_new_pins {
+                return RecvResult::AllClosed;
+            }
+            // No receivers yet (or everyone left): wait for pin management to add one
+            return match cancellation_token {
+                Some(token) => {
+                    token.cancelled().await;
+                    RecvResult::Cancelled
+                },
+                None => std::future::pending().await,
+            };
         }
 
         let num_receivers = slots.len();
@@ -1213,19 +1516,28 @@ enum AudioThreadCommand {
 
 enum InputEvent {
     Eof(Arc<str>),
-    SampleRateMismatch { pin_name: Arc<str>, expected: u32, got: u32 },
+}
+
+/// Output format every clocked input drainer converts its frames to.
+#[derive(Clone, Copy)]
+struct DrainerFormat {
+    jitter_buffer_frames: usize,
+    sample_rate: u32,
+    frame_samples_per_channel: usize,
+    quality: ResamplerQuality,
 }
 
 struct ClockedThreadConfig {
     node_name: String,
     sample_rate: u32,
+    /// Fixed output channel count; `None` follows the highest count seen
+    channels: Option<u16>,
     frame_samples_per_channel: usize,
     tick_duration: std::time::Duration,
     generate_silence: bool,
     sync_timeout: Option<std::time::Duration>,
     audio_pool: Option<Arc<AudioFramePool>>,
     state_tx: tokio::sync::mpsc::Sender<streamkit_core::state::NodeStateUpdate>,
-    input_event_tx: mpsc::Sender<InputEvent>,
     output_mailbox: Arc<OutputMailbox>,
     cmd_rx: std::sync::mpsc::Receiver<AudioThreadCommand>,
     stop_flag: Arc<AtomicBool>,
@@ -1323,23 +1635,6 @@ fn run_clocked_audio_thread(config: &ClockedThreadConfig) {
                 for input in &mut inputs {
                     let frame = input.ring.pop();
                     if let Some(frame) = frame {
-                        if frame.sample_rate != config.sample_rate {
-                            tracing::error!(
-                                "Clocked mixer input '{}' sample_rate mismatch: got {}, expected {} (fatal)",
-                                input.name,
-                                frame.sample_rate,
-                                config.sample_rate
-                            );
-                            let _ = config.input_event_tx.blocking_send(
-                                InputEvent::SampleRateMismatch {
-                                    pin_name: input.name.clone(),
-                                    expected: config.sample_rate,
-                                    got: frame.sample_rate,
-                                },
-                            );
-                            return;
-                        }
-
                         max_output_channels_seen = max_output_channels_seen.max(frame.channels);
                         input.has_ever_sent = true;
                         input.missing_since = None;
@@ -1402,12 +1697,16 @@ fn run_clocked_audio_thread(config: &ClockedThreadConfig) {
                 }
 
                 // If we've never observed channels yet, we can't size the output buffer.
-                if max_output_channels_seen == 0 && frames.is_empty() {
+                if config.channels.is_none() && max_output_channels_seen == 0 && frames.is_empty() {
                     continue;
                 }
 
-                let output_channels = max_output_channels_seen
-                    .max(frames.iter().map(|f| f.channels).max().unwrap_or(1))
+                let output_channels = config
+                    .channels
+                    .unwrap_or_else(|| {
+                        max_output_channels_seen
+                            .max(frames.iter().map(|f| f.channels).max().unwrap_or(1))
+                    })
                     .max(1);
 
                 let metadata =
@@ -1497,8 +1796,10 @@ async fn run_input_drainer(
     ring: Arc<InputRingBuffer>,
     cancellation_token: Option<tokio_util::sync::CancellationToken>,
     input_event_tx: mpsc::Sender<InputEvent>,
-    expected_sample_rate: u32,
+    format: DrainerFormat,
+    level: Arc<InputLevel>,
 ) {
+    let mut converter = InputConverter::new(format.quality, Some(format.frame_samples_per_channel));
     loop {
         let packet = if let Some(token) = &cancellation_token {
             tokio::select! {
@@ -1515,46 +1816,21 @@ async fn run_input_drainer(
         };
 
         if let Packet::Audio(frame) = packet {
-            if frame.sample_rate != expected_sample_rate {
-                tracing::error!(
-                    "Clocked mixer input '{}' sample_rate mismatch: got {}, expected {} (fatal)",
-                    name,
-                    frame.sample_rate,
-                    expected_sample_rate
-                );
-                let _ = input_event_tx
-                    .send(InputEvent::SampleRateMismatch {
-                        pin_name: name,
-                        expected: expected_sample_rate,
-                        got: frame.sample_rate,
-                    })
-                    .await;
-                return;
-            }
-            ring.push(frame);
+            push_converted(&mut converter, &ring, &name, frame, format.sample_rate, level.get());
         }
 
         // Drain bursty backlogs quickly.
         loop {
             match rx.try_recv() {
                 Ok(Packet::Audio(frame)) => {
-                    if frame.sample_rate != expected_sample_rate {
-                        tracing::error!(
-                            "Clocked mixer input '{}' sample_rate mismatch: got {}, expected {} (fatal)",
-                            name,
-                            frame.sample_rate,
-                            expected_sample_rate
-                        );
-                        let _ = input_event_tx
-                            .send(InputEvent::SampleRateMismatch {
-                                pin_name: name,
-                                expected: expected_sample_rate,
-                                got: frame.sample_rate,
-                            })
-                            .await;
-                        return;
-                    }
-                    ring.push(frame);
+                    push_converted(
+                        &mut converter,
+                        &ring,
+                        &name,
+                        frame,
+                        format.sample_rate,
+                        level.get(),
+                    );
                 },
                 Ok(_other) => {},
                 Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
@@ -1567,17 +1843,31 @@ async fn run_input_drainer(
     }
 }
 
+/// Converts a clocked input frame to the mixer's format and queues the result.
+fn push_converted(
+    converter: &mut InputConverter,
+    ring: &InputRingBuffer,
+    name: &str,
+    frame: AudioFrame,
+    sample_rate: u32,
+    gain: f32,
+) {
+    if let Err(e) = converter.convert(frame, sample_rate, gain, |f| ring.push(f)) {
+        tracing::warn!("Clocked mixer input '{}': dropping frame: {}", name, e);
+    }
+}
+
 fn start_clocked_input_drainer(
     audio_cmd_tx: &std::sync::mpsc::Sender<AudioThreadCommand>,
     input_event_tx: &mpsc::Sender<InputEvent>,
     cancellation_token: Option<tokio_util::sync::CancellationToken>,
-    jitter_buffer_frames: usize,
-    expected_sample_rate: u32,
+    format: DrainerFormat,
     pin_name: String,
     rx: mpsc::Receiver<Packet>,
+    level: Arc<InputLevel>,
 ) -> (Arc<str>, tokio::task::JoinHandle<()>) {
     let name: Arc<str> = Arc::from(pin_name);
-    let ring = Arc::new(InputRingBuffer::new(jitter_buffer_frames));
+    let ring = Arc::new(InputRingBuffer::new(format.jitter_buffer_frames));
     let _ =
         audio_cmd_tx.send(AudioThreadCommand::AddInput { name: name.clone(), ring: ring.clone() });
 
@@ -1590,7 +1880,8 @@ fn start_clocked_input_drainer(
             ring,
             cancellation_token,
             input_event_tx,
-            expected_sample_rate,
+            format,
+            level,
         )
         .await;
     });
@@ -1701,6 +1992,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mixer_rejects_duplicate_input_pins() {
+        let mut node = AudioMixerNode::new(AudioMixerConfig::default());
+        assert_eq!(node.create_input_pin(None).unwrap().name, "in_0");
+        assert_eq!(node.create_input_pin(Some("in_guest".into())).unwrap().name, "in_guest");
+        assert!(node.create_input_pin(Some("in_0".into())).is_err());
+        assert_eq!(node.create_input_pin(None).unwrap().name, "in_1");
+    }
+
+    #[tokio::test]
+    async fn test_mixer_keeps_running_when_inputs_leave_and_join() {
+        let (first_tx, first_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in_0".to_string(), first_rx);
+
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (pin_mgmt_tx, pin_mgmt_rx) = mpsc::channel(10);
+        context.pin_management_rx = Some(pin_mgmt_rx);
+
+        let node = AudioMixerNode::new(AudioMixerConfig::default());
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // The only participant leaves; the mixer waits for the next one instead of stopping
+        pin_mgmt_tx
+            .send(PinManagementMessage::RemoveInputPin { pin_name: "in_0".to_string() })
+            .await
+            .unwrap();
+
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        pin_mgmt_tx
+            .send(PinManagementMessage::RequestAddInputPin {
+                suggested_name: Some("in_guest".to_string()),
+                response_tx,
+            })
+            .await
+            .unwrap();
+        let pin = response_rx.await.unwrap().unwrap();
+        let (guest_tx, guest_rx) = mpsc::channel(10);
+        pin_mgmt_tx
+            .send(PinManagementMessage::AddedInputPin { pin, channel: guest_rx })
+            .await
+            .unwrap();
+
+        guest_tx.send(create_test_audio_packet(48000, 1, 10, 0.25)).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let output_packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(output_packets.len(), 1);
+        let audio_data = extract_audio_data(&output_packets[0]).expect("Should be audio");
+        assert!(audio_data.iter().all(|&s| (s - 0.25).abs() < 0.001));
+
+        drop(first_tx);
+        drop(guest_tx);
+        assert_state_stopped_eventually(&mut state_rx, std::time::Duration::from_secs(1)).await;
+        node_handle.await.unwrap().unwrap();
+    }
+
     #[tokio::test]
     async fn test_mixer_continues_after_eof_with_sticky_channels() {
         let (stereo_tx, stereo_rx) = mpsc::channel(10);
@@ -1716,7 +2065,7 @@ mod tests {
         let node = AudioMixerNode::new(AudioMixerConfig {
             sync_timeout_ms: Some(100),
             num_inputs: Some(2),
-            clocked: None,
+            ..Default::default()
         });
 
         let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
@@ -2108,4 +2457,193 @@ mod tests {
         assert_state_stopped_eventually(&mut state_rx, std::time::Duration::from_secs(2)).await;
         node_handle.await.unwrap().unwrap();
     }
+
+    #[tokio::test]
+    async fn test_mixer_resamples_and_remixes_mismatched_inputs() {
+        let (input1_tx, input1_rx) = mpsc::channel(10);
+        let (input2_tx, input2_rx) = mpsc::channel(10);
+
+        let mut inputs = HashMap::new();
+        inputs.insert("in_0".to_string(), input1_rx);
+        inputs.insert("in_1".to_string(), input2_rx);
+
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioMixerNode::new(AudioMixerConfig {
+            sync_timeout_ms: None,
+            sample_rate: Some(48_000),
+            ..Default::default()
+        });
+
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 20ms of 48 kHz stereo against 20ms of 24 kHz mono
+        let mut frames = Vec::new();
+        for _ in 0..3 {
+            input1_tx.send(create_test_audio_packet(48_000, 2, 960, 0.5)).await.unwrap();
+            input2_tx.send(create_test_audio_packet(24_000, 1, 480, 0.25)).await.unwrap();
+            let (_node, _pin, packet) = mock_sender
+                .recv_timeout(std::time::Duration::from_secs(2))
+                .await
+                .expect("Expected a mixed packet");
+            let Packet::Audio(frame) = packet else { panic!("Expected audio packet") };
+            frames.push(frame);
+        }
+
+        drop(input1_tx);
+        drop(input2_tx);
+        assert_state_stopped_eventually(&mut state_rx, std::time::Duration::from_secs(2)).await;
+        node_handle.await.unwrap().unwrap();
+
+        for frame in &frames {
+            assert_eq!(frame.sample_rate, 48_000);
+            assert_eq!(frame.channels, 2);
+            assert_eq!(frame.samples.len(), 1920);
+        }
+        // Once the resampler has settled the mono input lands on both channels
+        let last = frames.last().unwrap().samples.as_slice();
+        for &sample in &last[200..1800] {
+            assert!((sample - 0.75).abs() < 0.01, "Expected ~0.75, got {}", sample);
+        }
+    }
+
+    /// Sends 0.4 to `in_0` and 0.2 to `in_1` and checks the mix comes out at `expected`.
+    async fn mix_levels(
+        senders: [&mpsc::Sender<Packet>; 2],
+        mock_sender: &crate::test_utils::MockOutputSender,
+        expected: f32,
+    ) {
+        senders[0].send(create_test_audio_packet(48_000, 2, 10, 0.4)).await.unwrap();
+        senders[1].send(create_test_audio_packet(48_000, 2, 10, 0.2)).await.unwrap();
+        let (_node, _pin, packet) = mock_sender
+            .recv_timeout(std::time::Duration::from_secs(2))
+            .await
+            .expect("Expected a mixed packet");
+        for &sample in extract_audio_data(&packet).unwrap() {
+            assert!((sample - expected).abs() < 0.001, "Expected ~{}, got {}", expected, sample);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mixer_tunes_input_gain_and_mute_by_pin_name() {
+        let (input1_tx, input1_rx) = mpsc::channel(10);
+        let (input2_tx, input2_rx) = mpsc::channel(10);
+
+        let mut inputs = HashMap::new();
+        inputs.insert("in_0".to_string(), input1_rx);
+        inputs.insert("in_1".to_string(), input2_rx);
+
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let node = AudioMixerNode::new(AudioMixerConfig {
+            sync_timeout_ms: None,
+            inputs: HashMap::from([(
+                "in_0".to_string(),
+                MixerInputConfig { gain: 0.5, mute: false },
+            )]),
+            ..Default::default()
+        });
+
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let senders = [&input1_tx, &input2_tx];
+        // 0.4 * 0.5 + 0.2
+        mix_levels(senders, &mock_sender, 0.4).await;
+
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({
+                "inputs": {"in_1": {"mute": true}}
+            })))
+            .await
+            .unwrap();
+        // Out of range: rejected without touching the current settings
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({
+                "inputs": {"in_0": {"gain": 10.0}}
+            })))
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        mix_levels(senders, &mock_sender, 0.2).await;
+
+        drop(input1_tx);
+        drop(input2_tx);
+        assert_state_stopped_eventually(&mut state_rx, std::time::Duration::from_secs(2)).await;
+        node_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_clocked_mixer_resamples_to_tick_frames() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+
+        let mut inputs = HashMap::new();
+        inputs.insert("in_0".to_string(), input_rx);
+
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioMixerNode::new(AudioMixerConfig {
+            sync_timeout_ms: Some(50),
+            clocked: Some(ClockedMixerConfig {
+                sample_rate: 48_000,
+                frame_samples_per_channel: 480,
+                jitter_buffer_frames: 4,
+                generate_silence: false,
+            }),
+            ..Default::default()
+        });
+
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 16 kHz audio: the mixer used to fail on any rate other than its own. Send a few
+        // 10ms packets, as the resampler's delay holds back part of the first one.
+        for _ in 0..4 {
+            input_tx.send(create_test_audio_packet(16_000, 1, 160, 0.5)).await.unwrap();
+        }
+
+        let (_node, _pin, packet) = mock_sender
+            .recv_timeout(std::time::Duration::from_secs(2))
+            .await
+            .expect("Expected a mixed packet");
+        let Packet::Audio(frame) = packet else { panic!("Expected audio packet") };
+        assert_eq!(frame.sample_rate, 48_000);
+        assert_eq!(frame.channels, 1);
+        assert_eq!(frame.samples.len(), 480);
+
+        drop(input_tx);
+        assert_state_stopped_eventually(&mut state_rx, std::time::Duration::from_secs(2)).await;
+        node_handle.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_mixer_config_validation() {
+        assert!(AudioMixerConfig::default().validate().is_ok());
+        assert!(AudioMixerConfig { channels: Some(0), ..Default::default() }.validate().is_err());
+        assert!(AudioMixerConfig {
+            sample_rate: Some(16_000),
+            clocked: Some(ClockedMixerConfig::default()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(AudioMixerConfig {
+            inputs: HashMap::from([(
+                "in_0".to_string(),
+                MixerInputConfig { gain: -1.0, mute: false },
+            )]),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
 }
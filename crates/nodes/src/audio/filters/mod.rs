@@ -7,12 +7,69 @@ use streamkit_core::{
     config_helpers, registry::StaticPins, NodeRegistry, ProcessorNode, StreamKitError,
 };
 
+pub mod agc;
+#[cfg(feature = "audio_agc")]
+use agc::{AudioAgcConfig, AudioAgcNode};
+pub mod binaural;
+#[cfg(feature = "audio_binaural")]
+use binaural::{AudioBinauralConfig, AudioBinauralNode};
+pub mod channel_mixer;
+#[cfg(feature = "audio_channel_mixer")]
+use channel_mixer::{AudioChannelMixerConfig, AudioChannelMixerNode};
+pub mod compliance_beep;
+#[cfg(feature = "audio_compliance_beep")]
+use compliance_beep::{ComplianceBeepConfig, ComplianceBeepNode};
+pub mod compressor;
+#[cfg(feature = "audio_compressor")]
+use compressor::{AudioCompressorConfig, AudioCompressorNode};
+pub mod conference_mixer;
+#[cfg(feature = "audio_conference_mixer")]
+use conference_mixer::{ConferenceMixerConfig, ConferenceMixerNode};
+pub mod crossfader;
+#[cfg(feature = "audio_crossfader")]
+use crossfader::{AudioCrossfaderConfig, AudioCrossfaderNode};
+pub mod delay;
+#[cfg(feature = "audio_delay")]
+use delay::{AudioDelayConfig, AudioDelayNode};
+pub mod ducking;
+#[cfg(feature = "audio_ducking")]
+use ducking::{AudioDuckingConfig, AudioDuckingNode};
+pub mod equalizer;
+#[cfg(feature = "audio_equalizer")]
+use equalizer::{AudioEqualizerConfig, AudioEqualizerNode};
 pub mod gain;
+#[cfg(feature = "audio_gain")]
 use gain::{AudioGainConfig, AudioGainNode};
+pub mod loudnorm;
+#[cfg(feature = "audio_loudnorm")]
+use loudnorm::{AudioLoudnormConfig, AudioLoudnormNode};
 pub mod mixer;
+#[cfg(feature = "audio_mixer")]
 use mixer::{AudioMixerConfig, AudioMixerNode};
+pub mod noise_gate;
+#[cfg(feature = "audio_noise_gate")]
+use noise_gate::{AudioNoiseGateConfig, AudioNoiseGateNode};
+pub mod pitch;
+#[cfg(feature = "audio_pitch_shift")]
+use pitch::{AudioPitchShiftConfig, AudioPitchShiftNode};
+pub mod redact;
+#[cfg(feature = "audio_redact")]
+use redact::{AudioRedactConfig, AudioRedactNode};
 pub mod resampler;
+#[cfg(feature = "audio_resampler")]
 use resampler::{AudioResamplerConfig, AudioResamplerNode};
+pub mod speech_gate;
+#[cfg(feature = "audio_speech_gate")]
+use speech_gate::{AudioSpeechGateConfig, AudioSpeechGateNode};
+pub mod speed;
+#[cfg(feature = "audio_speed")]
+use speed::{AudioSpeedConfig, AudioSpeedNode};
+pub mod splitter;
+#[cfg(feature = "audio_splitter")]
+use splitter::{AudioSplitterConfig, AudioSplitterNode};
+pub mod vu_meter;
+#[cfg(feature = "audio_vu_meter")]
+use vu_meter::{AudioVuMeterConfig, AudioVuMeterNode};
 
 use schemars::schema_for;
 
@@ -24,6 +81,7 @@ use schemars::schema_for;
 ///
 /// Panics if config schemas cannot be serialized to JSON (should never happen).
 #[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+#[allow(clippy::too_many_lines)] // One registration block per filter
 pub fn register_audio_filters(registry: &mut NodeRegistry) {
     // --- Register AudioGainNode ---
     #[cfg(feature = "audio_gain")]
@@ -64,6 +122,11 @@ pub fn register_audio_filters(registry: &mut NodeRegistry) {
                     })?,
                     None => AudioMixerConfig::default(), // Use default config
                 };
+                config.validate().map_err(|e| {
+                    StreamKitError::Configuration(format!(
+                        "Invalid audio::mixer configuration: {e}"
+                    ))
+                })?;
                 Ok(Box::new(AudioMixerNode::new(config)))
             },
             serde_json::to_value(schema_for!(AudioMixerConfig))
@@ -72,7 +135,8 @@ pub fn register_audio_filters(registry: &mut NodeRegistry) {
             vec!["audio".to_string(), "filters".to_string()],
             false,
             "Combines multiple audio streams into a single output by summing samples. \
-             Supports configurable number of input channels with per-channel gain control.",
+             Inputs are resampled and remixed to a common output format, \
+             with per-input gain and mute tunable by pin name.",
         );
     }
 
@@ -91,4 +155,314 @@ pub fn register_audio_filters(registry: &mut NodeRegistry) {
              Essential for connecting nodes that operate at different sample rates.",
         );
     }
+
+    // --- Register AudioEqualizerNode ---
+    #[cfg(feature = "audio_equalizer")]
+    {
+        let factory = AudioEqualizerNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::equalizer",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioEqualizerConfig))
+                .expect("AudioEqualizerConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Multi-band parametric equalizer with peaking, shelf, pass and notch bands. \
+             Frequency, Q and gain of every band can be tuned while running, e.g. to add \
+             presence to voice or cut rumble before encoding.",
+        );
+    }
+
+    // --- Register AudioCompressorNode ---
+    #[cfg(feature = "audio_compressor")]
+    {
+        let factory = AudioCompressorNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::compressor",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioCompressorConfig))
+                .expect("AudioCompressorConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Dynamic range compressor with threshold, ratio, attack, release and makeup gain. \
+             Connect a second stream to the optional `sidechain` pin to duck the input under \
+             it, e.g. lowering background music while a TTS voice speaks.",
+        );
+    }
+
+    // --- Register AudioDuckingNode ---
+    #[cfg(feature = "audio_ducking")]
+    {
+        let factory = AudioDuckingNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::ducking",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioDuckingConfig))
+                .expect("AudioDuckingConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Turns the program on `in` down by a fixed depth while the `sidechain` is active, \
+             with attack, hold and release. The sidechain is either a voice (level-based) or \
+             VAD events, as in voiceover-over-music pipelines.",
+        );
+    }
+
+    // --- Register AudioSpeechGateNode ---
+    #[cfg(feature = "audio_speech_gate")]
+    {
+        let factory = AudioSpeechGateNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::speech_gate",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioSpeechGateConfig))
+                .expect("AudioSpeechGateConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Passes audio to `out` only while VAD events on the `vad` pin report speech, with \
+             hangover after speech ends and pre-roll before it starts. Audio outside speech \
+             goes to `closed`, so the node can route a stream as well as gate it.",
+        );
+    }
+
+    // --- Register AudioNoiseGateNode ---
+    #[cfg(feature = "audio_noise_gate")]
+    {
+        let factory = AudioNoiseGateNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::noise_gate",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioNoiseGateConfig))
+                .expect("AudioNoiseGateConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Noise gate with threshold, attack, hold and release. Silences low-level background \
+             noise between utterances, e.g. in front of speech-to-text to avoid hallucinated \
+             words and wasted inference.",
+        );
+    }
+
+    // --- Register AudioAgcNode ---
+    #[cfg(feature = "audio_agc")]
+    {
+        let factory = AudioAgcNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::agc",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioAgcConfig))
+                .expect("AudioAgcConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Automatic gain control. Measures the RMS or peak level over a sliding window and \
+             steers it towards a target (e.g. -18 dBFS), limited by a maximum gain, with \
+             attack/release smoothing. Evens out microphones that arrive at very different levels.",
+        );
+    }
+
+    // --- Register AudioLoudnormNode ---
+    #[cfg(feature = "audio_loudnorm")]
+    {
+        let factory = AudioLoudnormNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::loudnorm",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioLoudnormConfig))
+                .expect("AudioLoudnormConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Loudness normalization per EBU R128 / ITU-R BS.1770. Brings the integrated \
+             loudness to a LUFS target (e.g. -23 for broadcast, -16 for podcasts) while keeping \
+             the true peak under a ceiling. Two-pass mode buffers the whole file for one exact \
+             gain; single-pass mode adapts while streaming, optionally measuring lookahead_ms \
+             ahead of its output. Reports loudness stats as telemetry.",
+        );
+    }
+
+    // --- Register AudioBinauralNode ---
+    #[cfg(feature = "audio_binaural")]
+    {
+        let factory = AudioBinauralNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::binaural",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioBinauralConfig))
+                .expect("AudioBinauralConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Binaural renderer for headphone monitoring. Places each input channel as a virtual \
+             loudspeaker of its surround layout (5.1, 7.1, ...) or as a positioned object, and \
+             renders it to stereo through a spherical-head HRTF model. Objects and head rotation \
+             can be moved live via parameters or positions packets.",
+        );
+    }
+
+    // --- Register AudioChannelMixerNode ---
+    #[cfg(feature = "audio_channel_mixer")]
+    {
+        let factory = AudioChannelMixerNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::channel_mixer",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioChannelMixerConfig))
+                .expect("AudioChannelMixerConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Converts raw audio between channel layouts (stereo to mono, mono to stereo, 5.1 \
+             to stereo, ...) or routes channels through an explicit mixing matrix with one \
+             coefficient per input and output channel. Use it to match the channel count a \
+             downstream node expects, e.g. mono for speech recognition.",
+        );
+    }
+
+    // --- Register AudioDelayNode ---
+    #[cfg(feature = "audio_delay")]
+    {
+        let factory = AudioDelayNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::delay",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioDelayConfig))
+                .expect("AudioDelayConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Delays raw audio by a fixed number of milliseconds, tunable while running with a \
+             short crossfade. Use it for lip-sync alignment between audio and video paths, or \
+             to hold the original audio back until a translation or caption pipeline catches up.",
+        );
+    }
+
+    // --- Register AudioSpeedNode ---
+    #[cfg(feature = "audio_speed")]
+    {
+        let factory = AudioSpeedNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::speed",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioSpeedConfig))
+                .expect("AudioSpeedConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Changes the tempo of raw audio without shifting its pitch (WSOLA time-stretching). \
+             The rate is tunable while running, so TTS output can be sped up or slowed down \
+             on request, and recordings can be played back faster in conversion pipelines.",
+        );
+    }
+
+    // --- Register AudioPitchShiftNode ---
+    #[cfg(feature = "audio_pitch_shift")]
+    {
+        let factory = AudioPitchShiftNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::pitch_shift",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioPitchShiftConfig))
+                .expect("AudioPitchShiftConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Shifts the pitch of raw audio by a number of semitones without changing its tempo \
+             (time-stretching followed by resampling). The shift is tunable while running, for \
+             voice anonymization or character voices in voice agents.",
+        );
+    }
+
+    // --- Register ComplianceBeepNode ---
+    #[cfg(feature = "audio_compliance_beep")]
+    {
+        let factory = ComplianceBeepNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::compliance_beep",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(ComplianceBeepConfig))
+                .expect("ComplianceBeepConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Mixes a periodic notification tone (1400 Hz every 15 seconds by default) into \
+             passing audio to signal that a call is being recorded, as required for \
+             contact-center compliance. Each beep is reported as telemetry for auditing.",
+        );
+    }
+
+    // --- Register ConferenceMixerNode ---
+    #[cfg(feature = "audio_conference_mixer")]
+    {
+        let factory = ConferenceMixerNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::conference_mixer",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(ConferenceMixerConfig))
+                .expect("ConferenceMixerConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Mixes conference participants on a fixed clock with per-participant gain, mute \
+             and solo, automatic ducking of idle microphones, and active-speaker events on \
+             the events pin. Late or silent participants never stall the mix.",
+        );
+    }
+
+    // --- Register AudioCrossfaderNode ---
+    #[cfg(feature = "audio_crossfader")]
+    {
+        let factory = AudioCrossfaderNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::crossfader",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioCrossfaderConfig))
+                .expect("AudioCrossfaderConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Crossfades between two sources, a and b, with a tunable position that glides \
+             to new values over a configurable ramp. Switches between live audio and hold \
+             music or TTS without clicks.",
+        );
+    }
+
+    // --- Register AudioRedactNode ---
+    #[cfg(feature = "audio_redact")]
+    {
+        let factory = AudioRedactNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::redact",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioRedactConfig))
+                .expect("AudioRedactConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Delays audio and bleeps or silences time ranges reported on the ranges pin, \
+             e.g. PII flagged by a transcript analyser using word timestamps. Place it before \
+             recorders or publishers so sensitive speech never leaves the pipeline.",
+        );
+    }
+
+    // --- Register AudioVuMeterNode ---
+    #[cfg(feature = "audio_vu_meter")]
+    {
+        let factory = AudioVuMeterNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::vu_meter",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioVuMeterConfig))
+                .expect("AudioVuMeterConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Passes audio through unchanged while reporting per-channel peak and RMS levels as \
+             throttled meter.level telemetry, and optionally as Custom packets on a levels pin. \
+             Insert it after any audio node to drive live level meters in a UI.",
+        );
+    }
+
+    // --- Register AudioSplitterNode ---
+    #[cfg(feature = "audio_splitter")]
+    {
+        let factory = AudioSplitterNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::splitter",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(AudioSplitterConfig))
+                .expect("AudioSplitterConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "filters".to_string()],
+            false,
+            "Duplicates one audio stream to several outputs, each resampled and up- or \
+             downmixed to its own format, e.g. 48 kHz stereo for an Opus encoder and 16 kHz \
+             mono for speech-to-text from the same microphone.",
+        );
+    }
 }
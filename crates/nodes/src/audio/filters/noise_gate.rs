@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Noise gate node - Silences audio while its level stays below a threshold
+//!
+//! A stereo-linked peak gate. The gate opens as soon as any channel reaches `threshold_db`,
+//! stays open for `hold_ms` after the level last did, then closes. Opening and closing are
+//! linear gain ramps over `attack_ms` and `release_ms` so the gate doesn't click.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Configuration for the AudioNoiseGateNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioNoiseGateConfig {
+    /// Level at or above which the gate opens, in dBFS
+    #[schemars(range(min = -96.0, max = 0.0), extend("tunable" = true))]
+    pub threshold_db: f32,
+    /// Time for the gate to open fully, in milliseconds
+    #[schemars(range(min = 0.1, max = 100.0), extend("tunable" = true))]
+    pub attack_ms: f32,
+    /// Time the gate stays open after the level drops below the threshold, in milliseconds
+    #[schemars(range(min = 0.0, max = 2000.0), extend("tunable" = true))]
+    pub hold_ms: f32,
+    /// Time for the gate to close fully once the hold has passed, in milliseconds
+    #[schemars(range(min = 1.0, max = 5000.0), extend("tunable" = true))]
+    pub release_ms: f32,
+}
+
+impl Default for AudioNoiseGateConfig {
+    fn default() -> Self {
+        Self { threshold_db: -50.0, attack_ms: 1.0, hold_ms: 100.0, release_ms: 150.0 }
+    }
+}
+
+impl AudioNoiseGateConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("threshold_db", self.threshold_db, -96.0, 0.0)?;
+        check("attack_ms", self.attack_ms, 0.1, 100.0)?;
+        check("hold_ms", self.hold_ms, 0.0, 2000.0)?;
+        check("release_ms", self.release_ms, 1.0, 5000.0)
+    }
+}
+
+/// Gate state for one stream.
+struct Gate {
+    config: AudioNoiseGateConfig,
+    sample_rate: u32,
+    threshold: f32,
+    /// Gain change per frame while opening and closing
+    attack_step: f32,
+    release_step: f32,
+    hold_frames: u64,
+    /// Frames since the level was last at or above the threshold
+    since_open: u64,
+    gain: f32,
+}
+
+impl Gate {
+    fn new(config: AudioNoiseGateConfig) -> Self {
+        let mut gate = Self {
+            config,
+            sample_rate: 0,
+            threshold: 0.0,
+            attack_step: 1.0,
+            release_step: 1.0,
+            hold_frames: 0,
+            // Start closed, as if the hold had long passed.
+            since_open: u64::MAX,
+            gain: 0.0,
+        };
+        gate.update_coefficients();
+        gate
+    }
+
+    fn set_config(&mut self, config: AudioNoiseGateConfig) {
+        self.config = config;
+        self.update_coefficients();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.update_coefficients();
+        }
+    }
+
+    // Sample rates are far below f32's exact integer range; hold lengths are non-negative.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn update_coefficients(&mut self) {
+        self.threshold = 10f32.powf(self.config.threshold_db / 20.0);
+        if self.sample_rate > 0 {
+            let frames_per_ms = self.sample_rate as f32 / 1000.0;
+            self.attack_step = 1.0 / (self.config.attack_ms * frames_per_ms).max(1.0);
+            self.release_step = 1.0 / (self.config.release_ms * frames_per_ms).max(1.0);
+            self.hold_frames = (self.config.hold_ms * frames_per_ms).round() as u64;
+        }
+    }
+
+    /// Gates interleaved `samples` in place.
+    fn process(&mut self, samples: &mut [f32], channels: usize) {
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let peak = frame.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            if peak >= self.threshold {
+                self.since_open = 0;
+            } else {
+                self.since_open = self.since_open.saturating_add(1);
+            }
+
+            if self.since_open <= self.hold_frames {
+                self.gain = (self.gain + self.attack_step).min(1.0);
+            } else {
+                self.gain = (self.gain - self.release_step).max(0.0);
+            }
+            for sample in frame {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+/// A node that silences raw audio while it stays below a level threshold.
+///
+/// Useful in front of speech-to-text, where low-level background noise between utterances
+/// would otherwise be transcribed as hallucinated words and waste inference. Non-audio packets
+/// pass through unchanged. All parameters can be tuned while running.
+pub struct AudioNoiseGateNode {
+    config: AudioNoiseGateConfig,
+}
+
+impl AudioNoiseGateNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioNoiseGateConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid noise gate configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioNoiseGateNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut gate = Gate::new(self.config);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(mut packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref mut frame) = packet {
+                        if frame.sample_rate == 0 {
+                            stats.discarded();
+                            continue;
+                        }
+                        gate.set_sample_rate(frame.sample_rate);
+                        let channels = usize::from(frame.channels);
+                        // Copy-on-write: clones only if Arc is shared, mutates in place if unique
+                        gate.process(frame.make_samples_mut(), channels);
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&gate.config, &params).and_then(|config: AudioNoiseGateConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => gate.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected noise gate update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    fn gate(threshold_db: f32, attack_ms: f32, hold_ms: f32, release_ms: f32) -> Gate {
+        let mut gate =
+            Gate::new(AudioNoiseGateConfig { threshold_db, attack_ms, hold_ms, release_ms });
+        gate.set_sample_rate(RATE);
+        gate
+    }
+
+    /// Runs `ms` milliseconds of mono DC at `level` through the gate.
+    fn run(gate: &mut Gate, level: f32, ms: usize) -> Vec<f32> {
+        let mut samples = vec![level; RATE as usize / 1000 * ms];
+        gate.process(&mut samples, 1);
+        samples
+    }
+
+    #[test]
+    fn test_quiet_audio_is_silenced_and_loud_audio_passes() {
+        let mut gate = gate(-40.0, 1.0, 10.0, 10.0);
+        // -46 dBFS noise never opens the gate.
+        assert!(run(&mut gate, 0.005, 50).iter().all(|s| *s == 0.0));
+        // -6 dBFS speech passes at unity once the attack ramp is done.
+        let out = run(&mut gate, 0.5, 50);
+        assert!((out[out.len() - 1] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_attack_ramps_gain_up() {
+        let mut gate = gate(-40.0, 10.0, 10.0, 10.0);
+        let out = run(&mut gate, 0.5, 10);
+        assert!(out[0] < 0.01);
+        assert!((out[239] - 0.25).abs() < 0.01, "got {}", out[239]);
+        assert!((out[479] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_hold_keeps_gate_open_before_release() {
+        let mut gate = gate(-40.0, 0.1, 50.0, 20.0);
+        run(&mut gate, 0.5, 10);
+
+        // Within the hold time quiet audio still passes untouched...
+        let held = run(&mut gate, 0.005, 40);
+        assert!((held[held.len() - 1] - 0.005).abs() < 1e-6);
+        // ...then it fades out over the release time and stays closed.
+        let released = run(&mut gate, 0.005, 40);
+        assert!(released[480] > 0.0 && released[480] < 0.005);
+        assert!(released[released.len() - 1] == 0.0);
+    }
+
+    #[test]
+    fn test_any_channel_opens_the_gate() {
+        let mut gate = gate(-40.0, 0.1, 10.0, 10.0);
+        // Left is silent, right is loud: both channels are let through.
+        let mut samples: Vec<f32> = (0..960).flat_map(|_| [0.001, 0.5]).collect();
+        gate.process(&mut samples, 2);
+        assert!((samples[1918] - 0.001).abs() < 1e-6);
+        assert!((samples[1919] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioNoiseGateConfig::default().validate().is_ok());
+        let factory = AudioNoiseGateNode::factory();
+        assert!(factory(Some(&serde_json::json!({"threshold_db": 6.0}))).is_err());
+        assert!(factory(Some(&serde_json::json!({"release_ms": 0.0}))).is_err());
+
+        let updated: AudioNoiseGateConfig =
+            merge_params(&AudioNoiseGateConfig::default(), &serde_json::json!({"hold_ms": 250.0}))
+                .unwrap();
+        assert!((updated.hold_ms - 250.0).abs() < f32::EPSILON);
+        assert!((updated.threshold_db + 50.0).abs() < f32::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_node_gates_audio_and_applies_updates() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let factory = AudioNoiseGateNode::factory();
+        let node = factory(Some(&serde_json::json!({"threshold_db": -30.0}))).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // -40 dBFS is below the threshold and gated.
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.01)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // Lowering the threshold lets the same level through.
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"threshold_db": -60.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 2, 960, 0.01)).await.unwrap();
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        assert_eq!(packets.len(), 2);
+        assert!(extract_audio_data(&packets[0]).unwrap().iter().all(|s| *s == 0.0));
+        let opened = extract_audio_data(&packets[1]).unwrap();
+        assert!((opened[1919] - 0.01).abs() < 1e-6);
+    }
+}
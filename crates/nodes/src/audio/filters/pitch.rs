@@ -0,0 +1,509 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Pitch shift node - Pitch change without tempo change
+//!
+//! Raises or lowers raw audio by `semitones` while keeping its duration. The audio is first
+//! time-stretched with the WSOLA stretcher of the speed node to `2^(semitones / 12)` times
+//! its length, then resampled back to the original length, which scales every frequency by
+//! that ratio. Resampling interpolates linearly, which is fine for voices (anonymization,
+//! character effects) but lets some aliasing through when shifting music far up.
+//!
+//! The shift can be changed while running. Output frames have the size of the input frames
+//! and timestamps continue from the first input timestamp. The node holds about one window
+//! of audio, which is flushed when the input ends.
+
+use super::speed::{AudioSpeedConfig, Stretcher};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+const MAX_SEMITONES: f32 = 12.0;
+
+/// Configuration for the AudioPitchShiftNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioPitchShiftConfig {
+    /// Pitch change in semitones: 12 is an octave up, -12 an octave down, 0 leaves the pitch
+    /// as is. Fractional values are allowed.
+    #[schemars(range(min = -12.0, max = 12.0), extend("tunable" = true))]
+    pub semitones: f32,
+    /// Length of the overlapping segments, in milliseconds. Longer windows suit music,
+    /// shorter ones keep speech crisper.
+    #[schemars(range(min = 10.0, max = 100.0))]
+    pub window_ms: f32,
+    /// How far a segment may be moved to line up with the previous one, in milliseconds
+    #[schemars(range(min = 0.0, max = 30.0))]
+    pub search_ms: f32,
+}
+
+impl Default for AudioPitchShiftConfig {
+    fn default() -> Self {
+        Self { semitones: 0.0, window_ms: 30.0, search_ms: 10.0 }
+    }
+}
+
+impl AudioPitchShiftConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(-MAX_SEMITONES..=MAX_SEMITONES).contains(&self.semitones) {
+            return Err(format!(
+                "semitones must be within -{MAX_SEMITONES}-{MAX_SEMITONES}, got {}",
+                self.semitones
+            ));
+        }
+        self.stretch_config().validate()
+    }
+
+    /// Frequency ratio of the shift.
+    fn ratio(&self) -> f64 {
+        (f64::from(self.semitones) / 12.0).exp2()
+    }
+
+    /// Stretcher settings that lengthen the audio by the shift ratio.
+    #[allow(clippy::cast_possible_truncation)] // The ratio is within 0.5-2
+    fn stretch_config(&self) -> AudioSpeedConfig {
+        AudioSpeedConfig {
+            rate: (1.0 / self.ratio()) as f32,
+            window_ms: self.window_ms,
+            search_ms: self.search_ms,
+        }
+    }
+}
+
+/// Time-stretches by the shift ratio, then resamples back to the original duration.
+struct PitchShifter {
+    stretcher: Stretcher,
+    /// Frames of stretched audio advanced per output frame
+    ratio: f64,
+    /// Stretched audio not yet resampled, interleaved
+    pending: Vec<f32>,
+    /// Read position within `pending`, in frames
+    position: f64,
+    /// Finished output, interleaved
+    output: Vec<f32>,
+}
+
+impl PitchShifter {
+    fn new(sample_rate: u32, channels: usize, config: &AudioPitchShiftConfig) -> Self {
+        Self {
+            stretcher: Stretcher::new(sample_rate, channels, &config.stretch_config()),
+            ratio: config.ratio(),
+            pending: Vec::new(),
+            position: 0.0,
+            output: Vec::new(),
+        }
+    }
+
+    const fn sample_rate(&self) -> u32 {
+        self.stretcher.sample_rate
+    }
+
+    const fn channels(&self) -> usize {
+        self.stretcher.channels
+    }
+
+    fn set_config(&mut self, config: &AudioPitchShiftConfig) {
+        self.stretcher.set_rate(config.stretch_config().rate);
+        self.ratio = config.ratio();
+    }
+
+    /// Resamples stretched audio while a frame and its successor are available.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    fn resample(&mut self) {
+        let channels = self.channels();
+        let frames = self.pending.len() / channels;
+        while (self.position as usize) + 1 < frames {
+            let index = self.position as usize;
+            let frac = (self.position - index as f64) as f32;
+            let current = &self.pending[index * channels..(index + 1) * channels];
+            let next = &self.pending[(index + 1) * channels..(index + 2) * channels];
+            self.output.extend(current.iter().zip(next).map(|(a, b)| (b - a).mul_add(frac, *a)));
+            self.position += self.ratio;
+        }
+        let consumed = (self.position as usize).min(frames);
+        self.pending.drain(..consumed * channels);
+        self.position -= consumed as f64;
+    }
+
+    /// Shifts interleaved `samples`, adding what is finished to the output.
+    fn process(&mut self, samples: &[f32]) {
+        self.stretcher.process(samples);
+        self.pending.extend(self.stretcher.take_all());
+        self.resample();
+    }
+
+    /// Takes `frames` frames of finished output, if there are that many.
+    fn take(&mut self, frames: usize) -> Option<Vec<f32>> {
+        let samples = frames * self.channels();
+        (frames > 0 && self.output.len() >= samples).then(|| self.output.drain(..samples).collect())
+    }
+
+    /// Finishes the stream: the rest of the output, as if the input continued with silence.
+    fn drain(&mut self) -> Vec<f32> {
+        self.pending.extend(self.stretcher.drain());
+        // A silent frame to interpolate the last stretched frame against
+        self.pending.extend(std::iter::repeat_n(0.0, self.channels()));
+        self.resample();
+        std::mem::take(&mut self.output)
+    }
+}
+
+/// A node that changes the pitch of raw audio without changing its tempo.
+///
+/// Typical uses are voice anonymization and character voices for voice agents.
+pub struct AudioPitchShiftNode {
+    config: AudioPitchShiftConfig,
+}
+
+impl AudioPitchShiftNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioPitchShiftConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid pitch shift configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+/// Timestamps output on a timeline starting at the first input timestamp.
+struct OutputClock {
+    start_us: Option<u64>,
+    frames: u64,
+}
+
+impl OutputClock {
+    fn metadata(&mut self, frame: &AudioFrame) -> Option<PacketMetadata> {
+        let start_us = self.start_us?;
+        let timestamp_us = start_us + self.frames * 1_000_000 / u64::from(frame.sample_rate);
+        self.frames += frame.num_frames() as u64;
+        Some(PacketMetadata {
+            timestamp_us: Some(timestamp_us),
+            duration_us: frame.duration_us(),
+            sequence: None,
+        })
+    }
+}
+
+/// Sends interleaved `samples` as one frame.
+async fn send_audio(
+    context: &mut NodeContext,
+    stats: &mut NodeStatsTracker,
+    shifter: &PitchShifter,
+    clock: &mut OutputClock,
+    samples: Vec<f32>,
+) -> bool {
+    #[allow(clippy::cast_possible_truncation)] // Channel counts come from a u16
+    let channels = shifter.channels() as u16;
+    let mut frame = AudioFrame::new(shifter.sample_rate(), channels, samples);
+    frame.metadata = clock.metadata(&frame);
+    if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+        return false;
+    }
+    stats.sent();
+    true
+}
+
+#[async_trait]
+impl ProcessorNode for AudioPitchShiftNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut config = self.config;
+        let mut shifter: Option<PitchShifter> = None;
+        let mut clock = OutputClock { start_us: None, frames: 0 };
+        // Output frames match the size of the last input frame
+        let mut chunk_frames = 0;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    if frame.sample_rate == 0 || frame.channels == 0 {
+                        stats.discarded();
+                        continue;
+                    }
+                    let channels = usize::from(frame.channels);
+                    let current = match &mut shifter {
+                        Some(current)
+                            if current.sample_rate() == frame.sample_rate
+                                && current.channels() == channels => current,
+                        _ => {
+                            if shifter.is_some() {
+                                tracing::info!(
+                                    "Audio format changed to {} Hz, {} channels; restarting the pitch shifter",
+                                    frame.sample_rate,
+                                    channels
+                                );
+                            }
+                            clock = OutputClock {
+                                start_us: frame.metadata.as_ref().and_then(|m| m.timestamp_us),
+                                frames: 0,
+                            };
+                            shifter.insert(PitchShifter::new(frame.sample_rate, channels, &config))
+                        },
+                    };
+                    chunk_frames = frame.num_frames();
+                    current.process(&frame.samples);
+                    while let Some(samples) = current.take(chunk_frames) {
+                        if !send_audio(&mut context, &mut stats, current, &mut clock, samples).await {
+                            tracing::debug!("Output channel closed, stopping node");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                    }
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&config, &params).and_then(|updated: AudioPitchShiftConfig| {
+                            updated.validate()?;
+                            // Everything but the shift is fixed once running
+                            if (AudioPitchShiftConfig { semitones: config.semitones, ..updated }) != config {
+                                return Err(
+                                    "window_ms and search_ms cannot be changed while running"
+                                        .to_string(),
+                                );
+                            }
+                            Ok(updated)
+                        }) {
+                            Ok(updated) => {
+                                if let Some(shifter) = &mut shifter {
+                                    shifter.set_config(&updated);
+                                }
+                                config = updated;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected pitch shift update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => {
+                        shifter = None;
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Some(shifter) = shifter.as_mut() {
+            let samples = shifter.drain();
+            for chunk in samples.chunks(chunk_frames.max(1) * shifter.channels()) {
+                if !send_audio(&mut context, &mut stats, shifter, &mut clock, chunk.to_vec()).await
+                {
+                    tracing::debug!("Output channel closed while flushing the pitch shifter");
+                    break;
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 8000;
+
+    fn config(semitones: f32) -> AudioPitchShiftConfig {
+        AudioPitchShiftConfig { semitones, ..AudioPitchShiftConfig::default() }
+    }
+
+    fn tone(frequency: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn shift(semitones: f32, input: &[f32], chunk: usize) -> Vec<f32> {
+        let mut shifter = PitchShifter::new(RATE, 1, &config(semitones));
+        let mut output = Vec::new();
+        for samples in input.chunks(chunk) {
+            shifter.process(samples);
+            while let Some(ready) = shifter.take(chunk) {
+                output.extend(ready);
+            }
+        }
+        output.extend(shifter.drain());
+        output
+    }
+
+    /// Zero crossings per second, a rough pitch estimate for a pure tone.
+    fn crossings_per_second(samples: &[f32]) -> f32 {
+        let crossings = samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+        crossings as f32 * RATE as f32 / samples.len() as f32
+    }
+
+    #[test]
+    fn test_zero_shift_is_transparent() {
+        let input = tone(440.0, 4000);
+        let output = shift(0.0, &input, 160);
+        assert_eq!(output.len(), input.len());
+        for (out, inp) in output.iter().zip(&input) {
+            assert!((out - inp).abs() < 1e-5, "{out} != {inp}");
+        }
+    }
+
+    #[test]
+    fn test_shift_changes_pitch_but_not_duration() {
+        let input = tone(440.0, 16_000);
+        for (semitones, expected) in [(12.0, 880.0), (-12.0, 220.0), (7.0, 659.3)] {
+            let output = shift(semitones, &input, 160);
+            assert!(
+                output.len().abs_diff(input.len()) <= 2,
+                "{semitones} semitones: {} frames",
+                output.len()
+            );
+            // Skip the edges, where the first and last windows fade against silence
+            let body = &output[800..output.len() - 800];
+            let pitch = crossings_per_second(body) / 2.0;
+            assert!(
+                (pitch - expected).abs() < expected * 0.02,
+                "{semitones} semitones: {pitch} Hz, expected {expected} Hz"
+            );
+            let peak = body.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!(peak > 0.9 && peak < 1.1, "{semitones} semitones: peak {peak}");
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioPitchShiftConfig::default().validate().is_ok());
+        assert!(config(12.5).validate().is_err());
+        assert!(config(-13.0).validate().is_err());
+        assert!(AudioPitchShiftConfig { window_ms: 5.0, ..config(0.0) }.validate().is_err());
+        assert!(
+            AudioPitchShiftNode::factory()(Some(&serde_json::json!({"semitones": -3.5}))).is_ok()
+        );
+
+        let updated: AudioPitchShiftConfig =
+            merge_params(&config(0.0), &serde_json::json!({"semitones": 4.0})).unwrap();
+        assert_eq!(updated, config(4.0));
+    }
+
+    #[tokio::test]
+    async fn test_node_shifts_with_timestamps_and_tunable_semitones() {
+        let (input_tx, input_rx) = mpsc::channel(100);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 100);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let params = serde_json::json!({"semitones": 12.0});
+        let node = AudioPitchShiftNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let input = tone(440.0, 16_000);
+        let send = |i: usize| {
+            let metadata = PacketMetadata {
+                timestamp_us: Some(1_000_000 + i as u64 * 20_000),
+                duration_us: Some(20_000),
+                sequence: Some(i as u64),
+            };
+            let samples = input[i * 160..(i + 1) * 160].to_vec();
+            Packet::Audio(AudioFrame::with_metadata(RATE, 1, samples, Some(metadata)))
+        };
+        for i in 0..50 {
+            input_tx.send(send(i)).await.unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        // Window sizes are fixed once running; the shift is not
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"search_ms": 5.0})))
+            .await
+            .unwrap();
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"semitones": -12.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        for i in 50..100 {
+            input_tx.send(send(i)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let output: Vec<f32> =
+            packets.iter().flat_map(|p| extract_audio_data(p).unwrap().to_vec()).collect();
+        assert!(output.len().abs_diff(input.len()) <= 2, "{} frames", output.len());
+        // An octave up for the first second, an octave down for the second
+        let first = crossings_per_second(&output[1600..6400]) / 2.0;
+        let second = crossings_per_second(&output[9600..14_400]) / 2.0;
+        assert!((first - 880.0).abs() < 20.0, "first second at {first} Hz");
+        assert!((second - 220.0).abs() < 10.0, "second second at {second} Hz");
+
+        let timestamps: Vec<u64> = packets
+            .iter()
+            .map(|p| match p {
+                Packet::Audio(frame) => frame.metadata.as_ref().unwrap().timestamp_us.unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(timestamps[0], 1_000_000);
+        assert!(timestamps.windows(2).all(|w| w[1] - w[0] == 20_000));
+    }
+}
@@ -0,0 +1,430 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio redaction node - Silences or bleeps time ranges flagged by transcript analysis
+//!
+//! PII detectors work on transcripts, so they only know a range is sensitive after the words
+//! were spoken and recognised. The node holds audio back for `delay_ms` to give them time to
+//! report ranges, then redacts whatever was flagged before the audio leaves the node.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Type id of the Custom packets carrying ranges to redact.
+pub const REDACTION_RANGE_TYPE_ID: &str = "audio::redact/range@1";
+
+/// How redacted audio is replaced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    /// Replace with a sine tone
+    #[default]
+    Bleep,
+    /// Replace with silence
+    Silence,
+}
+
+/// Configuration for the AudioRedactNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AudioRedactConfig {
+    /// How long audio is held back waiting for redaction ranges, in milliseconds.
+    /// Must cover the transcription and PII detection latency.
+    #[schemars(range(min = 0))]
+    pub delay_ms: u64,
+    /// Extra time redacted before and after each range, in milliseconds
+    pub padding_ms: u64,
+    /// Replacement for redacted audio
+    pub mode: RedactionMode,
+    /// Bleep frequency in Hz
+    #[schemars(range(min = 100.0, max = 8000.0))]
+    pub bleep_frequency_hz: f32,
+    /// Bleep level in dBFS
+    #[schemars(range(max = 0.0))]
+    pub bleep_level_db: f32,
+}
+
+impl Default for AudioRedactConfig {
+    fn default() -> Self {
+        Self {
+            delay_ms: 3000,
+            padding_ms: 50,
+            mode: RedactionMode::Bleep,
+            bleep_frequency_hz: 1000.0,
+            bleep_level_db: -18.0,
+        }
+    }
+}
+
+/// A range to redact, in milliseconds from the start of the audio stream.
+///
+/// This is the time base of the segment timestamps produced by the STT nodes, so a detector
+/// can forward word timings unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RedactionRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl RedactionRange {
+    /// Parses a range event: either `{"start_ms", "end_ms"}` or `{"ranges": [...]}`.
+    fn parse_event(data: &serde_json::Value) -> Option<Vec<Self>> {
+        if let Some(ranges) = data.get("ranges") {
+            return serde_json::from_value(ranges.clone()).ok();
+        }
+        serde_json::from_value(data.clone()).ok().map(|range| vec![range])
+    }
+}
+
+/// Delay line that applies redaction ranges to frames as they are released.
+struct Redactor {
+    config: AudioRedactConfig,
+    /// Held frames with their start position in microseconds
+    queue: VecDeque<(u64, AudioFrame)>,
+    buffered_us: u64,
+    /// Stream position of the next frame to arrive
+    next_us: u64,
+    /// Stream position up to which audio has been released
+    released_us: u64,
+    /// Pending ranges in microseconds, padding applied
+    ranges: Vec<(u64, u64)>,
+}
+
+impl Redactor {
+    const fn new(config: AudioRedactConfig) -> Self {
+        Self {
+            config,
+            queue: VecDeque::new(),
+            buffered_us: 0,
+            next_us: 0,
+            released_us: 0,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Registers a range, returning `false` if part of it was already released unredacted.
+    fn add_range(&mut self, range: RedactionRange) -> bool {
+        let padding = self.config.padding_ms * 1000;
+        let start = (range.start_ms * 1000).saturating_sub(padding);
+        let end = range.end_ms.saturating_mul(1000).saturating_add(padding);
+        if end > start && end > self.released_us {
+            self.ranges.push((start, end));
+        }
+        start >= self.released_us
+    }
+
+    /// Buffers a frame and returns the frames whose delay has elapsed, redacted.
+    fn push(&mut self, frame: AudioFrame) -> Vec<AudioFrame> {
+        let duration = frame.duration_us().unwrap_or(0);
+        self.queue.push_back((self.next_us, frame));
+        self.next_us += duration;
+        self.buffered_us += duration;
+
+        let delay = self.config.delay_ms * 1000;
+        let mut released = Vec::new();
+        while let Some((_, front)) = self.queue.front() {
+            let front_duration = front.duration_us().unwrap_or(0);
+            if self.buffered_us - front_duration < delay {
+                break;
+            }
+            released.extend(self.release_front());
+        }
+        released
+    }
+
+    /// Releases everything still held, e.g. when the input ends.
+    fn flush(&mut self) -> Vec<AudioFrame> {
+        std::iter::from_fn(|| self.release_front()).collect()
+    }
+
+    fn release_front(&mut self) -> Option<AudioFrame> {
+        let (start_us, mut frame) = self.queue.pop_front()?;
+        let duration = frame.duration_us().unwrap_or(0);
+        self.buffered_us -= duration;
+        self.released_us = start_us + duration;
+        self.apply(start_us, &mut frame);
+        self.ranges.retain(|&(_, end)| end > self.released_us);
+        Some(frame)
+    }
+
+    // Stream positions stay far below f64's exact integer range.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn apply(&self, start_us: u64, frame: &mut AudioFrame) {
+        let end_us = start_us + frame.duration_us().unwrap_or(0);
+        if frame.sample_rate == 0
+            || !self.ranges.iter().any(|&(start, end)| start < end_us && end > start_us)
+        {
+            return;
+        }
+
+        let rate = u64::from(frame.sample_rate);
+        let sample_rate = f64::from(frame.sample_rate);
+        let channels = usize::from(frame.channels.max(1));
+        let amplitude = 10f64.powf(f64::from(self.config.bleep_level_db) / 20.0);
+        let frequency = f64::from(self.config.bleep_frequency_hz);
+        for (i, samples) in frame.make_samples_mut().chunks_mut(channels).enumerate() {
+            let t_us = start_us + (i as u64 * 1_000_000) / rate;
+            if !self.ranges.iter().any(|&(start, end)| (start..end).contains(&t_us)) {
+                continue;
+            }
+            let value = match self.config.mode {
+                RedactionMode::Silence => 0.0,
+                // Phase follows the stream clock so the tone is continuous across frames
+                RedactionMode::Bleep => {
+                    let t = start_us as f64 / 1e6 + i as f64 / sample_rate;
+                    (amplitude * (TAU * frequency * t).sin()) as f32
+                },
+            };
+            samples.fill(value);
+        }
+    }
+}
+
+/// A node that redacts flagged time ranges from a delayed copy of its audio input.
+///
+/// Ranges arrive on the `ranges` pin as `audio::redact/range@1` Custom packets whose data is
+/// `{"start_ms": .., "end_ms": ..}` or `{"ranges": [..]}`, measured from the start of the
+/// audio stream. Audio is held for `delay_ms` and every sample inside a range (widened by
+/// `padding_ms`) is replaced with a bleep or silence before it is forwarded. Ranges are
+/// processed before queued audio.
+///
+/// A range that arrives after part of it was already forwarded is applied to whatever is
+/// still held and reported with a `redaction.late` telemetry event, so misses are auditable.
+/// When the input ends, the remaining audio is flushed.
+pub struct AudioRedactNode {
+    config: AudioRedactConfig,
+}
+
+impl AudioRedactNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioRedactConfig = config_helpers::parse_config_optional(params)?;
+            if !(100.0..=8000.0).contains(&config.bleep_frequency_hz)
+                || !config.bleep_level_db.is_finite()
+                || config.bleep_level_db > 0.0
+            {
+                return Err(StreamKitError::Configuration(
+                    "bleep_frequency_hz must be within 100-8000 and bleep_level_db at most 0"
+                        .to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioRedactNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0, // Wildcard
+                    channels: 0,    // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "ranges".to_string(),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut ranges_rx = context.take_input("ranges")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut redactor = Redactor::new(self.config);
+        let mut ranges_open = true;
+
+        loop {
+            let released = tokio::select! {
+                biased;
+
+                maybe_event = ranges_rx.recv(), if ranges_open => {
+                    let Some(packet) = maybe_event else {
+                        ranges_open = false;
+                        continue;
+                    };
+                    let parsed = match &packet {
+                        Packet::Custom(event) if event.type_id == REDACTION_RANGE_TYPE_ID => {
+                            RedactionRange::parse_event(&event.data)
+                        }
+                        _ => None,
+                    };
+                    let Some(ranges) = parsed else {
+                        tracing::warn!("AudioRedactNode ignoring unrecognised packet on ranges pin");
+                        stats.errored();
+                        continue;
+                    };
+                    for range in ranges {
+                        if !redactor.add_range(range) {
+                            tracing::warn!(?range, "Redaction range arrived after audio was released");
+                            telemetry.emit(
+                                "redaction.late",
+                                serde_json::json!({
+                                    "start_ms": range.start_ms,
+                                    "end_ms": range.end_ms,
+                                    "released_ms": redactor.released_us / 1000,
+                                }),
+                            );
+                        }
+                    }
+                    continue;
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => {
+                        reject_query(&kind, reply);
+                        continue;
+                    }
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => continue,
+                    NodeControlMessage::Shutdown => break,
+                },
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    redactor.push(frame)
+                }
+            };
+
+            for frame in released {
+                if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                    return Ok(());
+                }
+                stats.sent();
+            }
+            stats.maybe_send();
+        }
+
+        for frame in redactor.flush() {
+            if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                break;
+            }
+            stats.sent();
+        }
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test assertions
+mod tests {
+    use super::*;
+
+    /// 20 ms mono frames at 8 kHz filled with 0.5
+    fn frame() -> AudioFrame {
+        AudioFrame::new(8000, 1, vec![0.5; 160])
+    }
+
+    fn config(mode: RedactionMode) -> AudioRedactConfig {
+        AudioRedactConfig { delay_ms: 100, padding_ms: 0, mode, ..Default::default() }
+    }
+
+    fn samples(frames: &[AudioFrame]) -> Vec<f32> {
+        frames.iter().flat_map(|f| f.samples().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_delay_holds_audio_then_releases() {
+        let mut redactor = Redactor::new(config(RedactionMode::Silence));
+        for _ in 0..5 {
+            assert!(redactor.push(frame()).is_empty());
+        }
+        assert_eq!(redactor.push(frame()).len(), 1);
+        assert_eq!(redactor.released_us, 20_000);
+        assert_eq!(redactor.flush().len(), 5);
+    }
+
+    #[test]
+    fn test_silences_range_across_frames() {
+        let mut redactor = Redactor::new(config(RedactionMode::Silence));
+        let mut out = Vec::new();
+        for _ in 0..3 {
+            out.extend(redactor.push(frame()));
+        }
+        // 30-50 ms spans the second and third frames
+        assert!(redactor.add_range(RedactionRange { start_ms: 30, end_ms: 50 }));
+        for _ in 0..7 {
+            out.extend(redactor.push(frame()));
+        }
+        out.extend(redactor.flush());
+
+        let s = samples(&out);
+        assert_eq!(s.len(), 1600);
+        assert!(s[..240].iter().all(|&v| (v - 0.5).abs() < f32::EPSILON));
+        assert!(s[240..400].iter().all(|&v| v == 0.0));
+        assert!(s[400..].iter().all(|&v| (v - 0.5).abs() < f32::EPSILON));
+    }
+
+    #[test]
+    fn test_bleep_and_late_range() {
+        let mut redactor = Redactor::new(config(RedactionMode::Bleep));
+        let mut out = Vec::new();
+        for _ in 0..8 {
+            out.extend(redactor.push(frame()));
+        }
+        // 60 ms has already been released; only the held part can still be redacted
+        assert!(!redactor.add_range(RedactionRange { start_ms: 20, end_ms: 80 }));
+        out.extend(redactor.flush());
+
+        let s = samples(&out);
+        assert!(s[..480].iter().all(|&v| (v - 0.5).abs() < f32::EPSILON));
+        let bleep = &s[480..640];
+        assert!(bleep.iter().all(|v| v.abs() <= 0.126));
+        assert!(bleep.iter().any(|v| v.abs() > 0.1));
+        assert!(s[640..].iter().all(|&v| (v - 0.5).abs() < f32::EPSILON));
+    }
+
+    #[test]
+    fn test_parse_range_events() {
+        let single = serde_json::json!({"start_ms": 10, "end_ms": 20});
+        assert_eq!(
+            RedactionRange::parse_event(&single).unwrap(),
+            vec![RedactionRange { start_ms: 10, end_ms: 20 }]
+        );
+        let many = serde_json::json!({"ranges": [{"start_ms": 1, "end_ms": 2}, {"start_ms": 3, "end_ms": 4}]});
+        assert_eq!(RedactionRange::parse_event(&many).unwrap().len(), 2);
+        assert!(RedactionRange::parse_event(&serde_json::json!({"start_ms": 1})).is_none());
+    }
+}
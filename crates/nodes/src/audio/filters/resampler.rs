@@ -3,9 +3,21 @@
 // SPDX-License-Identifier: MPL-2.0
 
 //! Audio resampler node - Changes playback speed by resampling audio data
+//!
+//! Conversion runs through one or two rubato stages, picked by the `quality` preset: `fast`
+//! interpolates linearly, `medium` and `high` use windowed-sinc filters. A sinc filter of
+//! fixed length gets a wider transition band the further it downsamples, so with the sinc
+//! presets a downsampling ratio beyond 4x is split into two stages through an intermediate
+//! rate.
+//!
+//! The delay this adds (input chunking plus filter delay) is reported as `latency_us` in
+//! the node stats once the input rate is known.
 
 use async_trait::async_trait;
-use rubato::{FastFixedIn, Resampler};
+use rubato::{
+    FastFixedIn, Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType,
+    WindowFunction,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -17,6 +29,51 @@ use streamkit_core::{
     OutputPin, PinCardinality, PooledSamples, ProcessorNode, StreamKitError,
 };
 
+/// Largest ratio a single sinc stage converts by before the chain splits it in two
+const MAX_STAGE_RATIO: f64 = 4.0;
+
+/// Bounds for the chunk size picked from the first packet, in milliseconds of input
+const AUTO_CHUNK_MIN_MS: usize = 5;
+const AUTO_CHUNK_MAX_MS: usize = 100;
+
+/// Resampling quality preset, trading CPU load against aliasing and imaging artifacts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResamplerQuality {
+    /// Linear interpolation: cheapest, but aliases audibly when downsampling
+    #[default]
+    Fast,
+    /// 64-tap windowed sinc: clean speech at a moderate CPU cost
+    Medium,
+    /// 256-tap windowed sinc with cubic interpolation, for music
+    High,
+}
+
+/// Window function shaping the sinc filter of the `medium` and `high` presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SincWindow {
+    Blackman,
+    Blackman2,
+    BlackmanHarris,
+    BlackmanHarris2,
+    Hann,
+    Hann2,
+}
+
+impl From<SincWindow> for WindowFunction {
+    fn from(window: SincWindow) -> Self {
+        match window {
+            SincWindow::Blackman => Self::Blackman,
+            SincWindow::Blackman2 => Self::Blackman2,
+            SincWindow::BlackmanHarris => Self::BlackmanHarris,
+            SincWindow::BlackmanHarris2 => Self::BlackmanHarris2,
+            SincWindow::Hann => Self::Hann,
+            SincWindow::Hann2 => Self::Hann2,
+        }
+    }
+}
+
 /// Configuration for the AudioResamplerNode
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AudioResamplerConfig {
@@ -25,11 +82,24 @@ pub struct AudioResamplerConfig {
     /// Must be greater than 0
     #[schemars(range(min = 1))]
     pub target_sample_rate: u32,
-    /// Fixed chunk size for resampler (default: 960 frames = 20ms at 48kHz)
+    /// Quality preset: `fast` (linear), `medium` (64-tap sinc) or `high` (256-tap sinc)
+    #[serde(default)]
+    pub quality: ResamplerQuality,
+    /// Window function of the sinc filter, overriding the preset's
+    /// (`blackman2` for `medium`, `blackman_harris2` for `high`). Sinc presets only.
+    #[serde(default)]
+    pub window: Option<SincWindow>,
+    /// Sinc filter length in taps, overriding the preset's. Must be a multiple of 8.
+    /// Longer filters give a steeper cutoff at the cost of CPU and latency. Sinc presets only.
+    #[serde(default)]
+    #[schemars(range(min = 16, max = 1024))]
+    pub sinc_len: Option<usize>,
+    /// Fixed chunk size for resampler, in input frames
     /// Larger values = better efficiency but more latency
-    #[serde(default = "default_chunk_frames")]
+    /// When unset, matches the first packet's size (clamped to 5-100 ms of input)
+    #[serde(default)]
     #[schemars(range(min = 1))]
-    pub chunk_frames: usize,
+    pub chunk_frames: Option<usize>,
     /// Output frame size - packets will be buffered to this exact size (default: 960 = 20ms at 48kHz)
     /// Must be a valid Opus frame size: 120, 240, 480, 960, 1920, or 2880 samples
     /// Set to 0 to disable output buffering (variable frame sizes)
@@ -37,17 +107,297 @@ pub struct AudioResamplerConfig {
     pub output_frame_size: usize,
 }
 
-const fn default_chunk_frames() -> usize {
-    960 // 20ms at 48kHz, typical Opus frame size
-}
-
 const fn default_output_frame_size() -> usize {
     960 // 20ms at 48kHz - matches Opus default
 }
 
+impl AudioResamplerConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.target_sample_rate == 0 {
+            return Err("target_sample_rate must be greater than 0".to_string());
+        }
+
+        if self.chunk_frames == Some(0) {
+            return Err("chunk_frames must be greater than 0".to_string());
+        }
+
+        if self.quality == ResamplerQuality::Fast
+            && (self.window.is_some() || self.sinc_len.is_some())
+        {
+            return Err(
+                "window and sinc_len only apply to the medium and high quality presets".to_string()
+            );
+        }
+        if let Some(sinc_len) = self.sinc_len {
+            if !(16..=1024).contains(&sinc_len) || !sinc_len.is_multiple_of(8) {
+                return Err(format!(
+                    "sinc_len must be a multiple of 8 within 16-1024, got {sinc_len}"
+                ));
+            }
+        }
+
+        // Validate output_frame_size is a valid Opus frame size (or 0 for disabled)
+        if self.output_frame_size != 0 {
+            let valid_sizes = [120, 240, 480, 960, 1920, 2880];
+            if !valid_sizes.contains(&self.output_frame_size) {
+                return Err(format!(
+                    "output_frame_size must be 0 (disabled) or a valid Opus frame size: {valid_sizes:?}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sinc filter parameters for the configured preset, or `None` for `fast`.
+    fn sinc_parameters(&self) -> Option<SincInterpolationParameters> {
+        let (sinc_len, interpolation, oversampling_factor, window) = match self.quality {
+            ResamplerQuality::Fast => return None,
+            ResamplerQuality::Medium => {
+                (64, SincInterpolationType::Linear, 128, WindowFunction::Blackman2)
+            },
+            ResamplerQuality::High => {
+                (256, SincInterpolationType::Cubic, 256, WindowFunction::BlackmanHarris2)
+            },
+        };
+        let sinc_len = self.sinc_len.unwrap_or(sinc_len);
+        let window = self.window.map_or(window, WindowFunction::from);
+        Some(SincInterpolationParameters {
+            sinc_len,
+            f_cutoff: rubato::calculate_cutoff(sinc_len, window),
+            interpolation,
+            oversampling_factor,
+            window,
+        })
+    }
+
+    /// Chunk size in input frames: the configured one, or the first packet's frame count
+    /// kept within sensible bounds for the input rate.
+    pub(crate) fn resolve_chunk_frames(
+        &self,
+        input_rate: u32,
+        first_packet_frames: usize,
+    ) -> usize {
+        self.chunk_frames.unwrap_or_else(|| {
+            let ms = input_rate as usize / 1000;
+            let min = (ms * AUTO_CHUNK_MIN_MS).max(1);
+            let max = (ms * AUTO_CHUNK_MAX_MS).max(min);
+            let frames = if first_packet_frames == 0 {
+                ms * 20
+            } else {
+                first_packet_frames.clamp(min, max)
+            };
+            frames.max(1)
+        })
+    }
+}
+
+/// One rubato resampler in the chain.
+enum StageResampler {
+    Fast(FastFixedIn<f32>),
+    Sinc(Box<SincFixedIn<f32>>),
+}
+
+impl StageResampler {
+    fn input_frames_next(&self) -> usize {
+        match self {
+            Self::Fast(r) => r.input_frames_next(),
+            Self::Sinc(r) => r.input_frames_next(),
+        }
+    }
+
+    fn output_delay(&self) -> usize {
+        match self {
+            Self::Fast(r) => r.output_delay(),
+            Self::Sinc(r) => r.output_delay(),
+        }
+    }
+
+    fn process(&mut self, input: &[&[f32]]) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        match self {
+            Self::Fast(r) => r.process(input, None),
+            Self::Sinc(r) => r.process(input, None),
+        }
+    }
+
+    fn process_partial(
+        &mut self,
+        input: &[&[f32]],
+    ) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        match self {
+            Self::Fast(r) => r.process_partial(Some(input), None),
+            Self::Sinc(r) => r.process_partial(Some(input), None),
+        }
+    }
+}
+
+struct Stage {
+    resampler: StageResampler,
+    ratio: f64,
+    output_rate: f64,
+    /// Planar input waiting for a full chunk (filled when input arrives in other sizes, as
+    /// it does for later stages or for mixer inputs with uneven packets)
+    pending: Vec<Vec<f32>>,
+}
+
+impl Stage {
+    fn feed(&mut self, input: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        let needed = self.resampler.input_frames_next();
+        if self.pending[0].is_empty() && input[0].len() == needed {
+            let chunk: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+            return self.resampler.process(&chunk);
+        }
+
+        for (pending, samples) in self.pending.iter_mut().zip(input) {
+            pending.extend_from_slice(samples);
+        }
+        let mut output = vec![Vec::new(); self.pending.len()];
+        loop {
+            let needed = self.resampler.input_frames_next();
+            if self.pending[0].len() < needed {
+                return Ok(output);
+            }
+            let chunk: Vec<&[f32]> = self.pending.iter().map(|ch| &ch[..needed]).collect();
+            let processed = self.resampler.process(&chunk)?;
+            for (out, samples) in output.iter_mut().zip(processed) {
+                out.extend_from_slice(&samples);
+            }
+            for ch in &mut self.pending {
+                ch.drain(..needed);
+            }
+        }
+    }
+
+    /// Processes whatever is pending plus `input` as a final, zero-padded chunk and trims
+    /// the output to the length the real input accounts for.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    fn finish(&mut self, input: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        let mut output = self.feed(input)?;
+        let remaining = self.pending[0].len();
+        if remaining == 0 {
+            return Ok(output);
+        }
+        let chunk: Vec<&[f32]> = self.pending.iter().map(Vec::as_slice).collect();
+        let tail = self.resampler.process_partial(&chunk)?;
+        let keep = (remaining as f64 * self.ratio).ceil() as usize;
+        for (out, samples) in output.iter_mut().zip(tail) {
+            out.extend_from_slice(&samples[..keep.min(samples.len())]);
+        }
+        for ch in &mut self.pending {
+            ch.clear();
+        }
+        Ok(output)
+    }
+}
+
+/// Resamplers converting one stream, run one after another.
+pub(crate) struct ResamplerChain {
+    stages: Vec<Stage>,
+    chunk_frames: usize,
+    input_rate: u32,
+}
+
+impl ResamplerChain {
+    pub(crate) fn new(
+        config: &AudioResamplerConfig,
+        input_rate: u32,
+        chunk_frames: usize,
+        channels: usize,
+    ) -> Result<Self, rubato::ResamplerConstructionError> {
+        let output_rate = f64::from(config.target_sample_rate);
+        let input = f64::from(input_rate);
+        let sinc = config.quality != ResamplerQuality::Fast;
+
+        // Only sinc filters gain from splitting; linear interpolation has no filter to widen.
+        let rates = if sinc && input / output_rate > MAX_STAGE_RATIO {
+            vec![(input * output_rate).sqrt().round(), output_rate]
+        } else {
+            vec![output_rate]
+        };
+
+        let mut stages = Vec::with_capacity(rates.len());
+        let mut stage_input = input;
+        let mut stage_chunk = chunk_frames;
+        for rate in rates {
+            let ratio = rate / stage_input;
+            let resampler = match config.sinc_parameters() {
+                Some(parameters) => StageResampler::Sinc(Box::new(SincFixedIn::<f32>::new(
+                    ratio,
+                    1.0, // Fixed ratio, no relative changes
+                    parameters,
+                    stage_chunk,
+                    channels,
+                )?)),
+                None => StageResampler::Fast(FastFixedIn::<f32>::new(
+                    ratio,
+                    1.0, // Maximum relative ratio change (not used for FastFixedIn)
+                    rubato::PolynomialDegree::Linear, // Fast linear interpolation
+                    stage_chunk,
+                    channels,
+                )?),
+            };
+            stages.push(Stage {
+                resampler,
+                ratio,
+                output_rate: rate,
+                pending: vec![Vec::new(); channels],
+            });
+            // Size later stages for what one chunk of the previous stage yields
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let next_chunk = ((stage_chunk as f64 * ratio).ceil() as usize).max(1);
+            stage_chunk = next_chunk;
+            stage_input = rate;
+        }
+
+        Ok(Self { stages, chunk_frames, input_rate })
+    }
+
+    /// Resamples planar input, usually one chunk of `chunk_frames` frames. Input of another
+    /// size, and whatever later stages need to complete a chunk of their own, is held back.
+    pub(crate) fn process(
+        &mut self,
+        input: &[Vec<f32>],
+    ) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        let mut data = self.stages[0].feed(input)?;
+        for stage in &mut self.stages[1..] {
+            data = stage.feed(&data)?;
+        }
+        Ok(data)
+    }
+
+    /// Resamples a final partial chunk and drains every stage.
+    fn finish(&mut self, input: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, rubato::ResampleError> {
+        let mut data = input.to_vec();
+        for stage in &mut self.stages {
+            data = stage.finish(&data)?;
+        }
+        Ok(data)
+    }
+
+    /// Delay from input to output: waiting for a full input chunk plus each stage's filter.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    fn latency_us(&self) -> u64 {
+        let chunk_secs = self.chunk_frames as f64 / f64::from(self.input_rate);
+        let filter_secs: f64 = self
+            .stages
+            .iter()
+            .map(|stage| stage.resampler.output_delay() as f64 / stage.output_rate)
+            .sum();
+        ((chunk_secs + filter_secs) * 1_000_000.0).round() as u64
+    }
+
+    const fn stage_count(&self) -> usize {
+        self.stages.len()
+    }
+}
+
 /// A node that resamples audio to convert between different sample rates.
 ///
-/// This node uses rubato's FastFixedIn resampler for efficient, good-quality resampling.
+/// The `quality` preset selects rubato's FastFixedIn (linear) or SincFixedIn resamplers.
 /// Common use cases:
 /// - Converting 48kHz to 24kHz (downsampling)
 /// - Converting 16kHz to 48kHz (upsampling)
@@ -73,33 +423,15 @@ impl AudioResamplerNode {
                 // Default config for schema generation
                 None => AudioResamplerConfig {
                     target_sample_rate: 48000, // Default to 48kHz
-                    chunk_frames: default_chunk_frames(),
+                    quality: ResamplerQuality::default(),
+                    window: None,
+                    sinc_len: None,
+                    chunk_frames: None,
                     output_frame_size: default_output_frame_size(),
                 },
             };
 
-            // Validate target_sample_rate
-            if config.target_sample_rate == 0 {
-                return Err(StreamKitError::Configuration(
-                    "target_sample_rate must be greater than 0".to_string(),
-                ));
-            }
-
-            if config.chunk_frames == 0 {
-                return Err(StreamKitError::Configuration(
-                    "chunk_frames must be greater than 0".to_string(),
-                ));
-            }
-
-            // Validate output_frame_size is a valid Opus frame size (or 0 for disabled)
-            if config.output_frame_size != 0 {
-                let valid_sizes = [120, 240, 480, 960, 1920, 2880];
-                if !valid_sizes.contains(&config.output_frame_size) {
-                    return Err(StreamKitError::Configuration(format!(
-                        "output_frame_size must be 0 (disabled) or a valid Opus frame size: {valid_sizes:?}"
-                    )));
-                }
-            }
+            config.validate().map_err(StreamKitError::Configuration)?;
 
             Ok(Box::new(Self { config }))
         })
@@ -150,9 +482,10 @@ impl ProcessorNode for AudioResamplerNode {
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
         tracing::info!(
-            "AudioResamplerNode starting with target_sample_rate: {}Hz (chunk_frames: {}, using rubato FastFixedIn)",
+            "AudioResamplerNode starting with target_sample_rate: {}Hz (quality: {:?}, chunk_frames: {})",
             self.config.target_sample_rate,
-            self.config.chunk_frames
+            self.config.quality,
+            self.config.chunk_frames.map_or_else(|| "auto".to_string(), |n| n.to_string())
         );
 
         state_helpers::emit_running(&context.state_tx, &node_name);
@@ -165,7 +498,8 @@ impl ProcessorNode for AudioResamplerNode {
         let mut total_output_samples = 0u64;
 
         // State variables for resampler (initialized on first audio packet)
-        let mut resampler: Option<FastFixedIn<f32>> = None;
+        let mut resampler: Option<ResamplerChain> = None;
+        let mut chunk_frames: usize = 0;
         let mut needs_resample: Option<bool> = None;
         let mut sample_rate: Option<u32> = None;
         let mut channels: Option<u16> = None;
@@ -217,35 +551,40 @@ impl ProcessorNode for AudioResamplerNode {
                             let num_channels = frame.channels as usize;
                             let input_rate = frame.sample_rate;
                             let output_rate = self.config.target_sample_rate;
+                            chunk_frames = self.config.resolve_chunk_frames(
+                                input_rate,
+                                frame.samples.len() / num_channels.max(1),
+                            );
+
+                            // Create resampler once with fixed chunk size
+                            let chain = ResamplerChain::new(
+                                &self.config,
+                                input_rate,
+                                chunk_frames,
+                                num_channels,
+                            )
+                            .map_err(|e| {
+                                StreamKitError::Runtime(format!("Failed to create resampler: {e}"))
+                            })?;
 
                             tracing::debug!(
-                                "Creating resampler: {}→{} Hz, ratio: {:.4}, chunk_frames: {}, channels: {}",
+                                "Creating resampler: {}→{} Hz, ratio: {:.4}, chunk_frames: {}, channels: {}, stages: {}, latency: {}us",
                                 input_rate,
                                 output_rate,
                                 f64::from(output_rate) / f64::from(input_rate),
-                                self.config.chunk_frames,
-                                num_channels
-                            );
-
-                            // Create resampler once with fixed chunk size
-                            resampler = Some(
-                                FastFixedIn::<f32>::new(
-                                    f64::from(output_rate) / f64::from(input_rate),
-                                    1.0, // Maximum relative ratio change (not used for FastFixedIn)
-                                    rubato::PolynomialDegree::Linear, // Fast linear interpolation
-                                    self.config.chunk_frames,
-                                    num_channels,
-                                )
-                                .map_err(|e| {
-                                    StreamKitError::Runtime(format!(
-                                        "Failed to create resampler: {e}"
-                                    ))
-                                })?,
+                                chunk_frames,
+                                num_channels,
+                                chain.stage_count(),
+                                chain.latency_us()
                             );
+                            stats_tracker.set_latency_us(chain.latency_us());
+                            resampler = Some(chain);
 
                             // Pre-allocate planar buffers
                             planar_input_buffer =
-                                vec![Vec::with_capacity(self.config.chunk_frames); num_channels];
+                                vec![Vec::with_capacity(chunk_frames); num_channels];
+                        } else {
+                            stats_tracker.set_latency_us(0);
                         }
                     }
 
@@ -379,7 +718,7 @@ impl ProcessorNode for AudioResamplerNode {
                     // Safe unwrap: resampler is Some when needs_resample is true
                     #[allow(clippy::unwrap_used)]
                     let resampler_ref = resampler.as_mut().unwrap();
-                    let chunk_size_samples = self.config.chunk_frames * num_channels;
+                    let chunk_size_samples = chunk_frames * num_channels;
 
                     while sample_buffer.len().saturating_sub(sample_buffer_offset)
                         >= chunk_size_samples
@@ -394,7 +733,7 @@ impl ProcessorNode for AudioResamplerNode {
                         }
 
                         // Convert chunk to planar format
-                        for frame_idx in 0..self.config.chunk_frames {
+                        for frame_idx in 0..chunk_frames {
                             for ch in 0..num_channels {
                                 planar_input_buffer[ch].push(chunk[frame_idx * num_channels + ch]);
                             }
@@ -402,12 +741,19 @@ impl ProcessorNode for AudioResamplerNode {
 
                         // Resample
                         let planar_output =
-                            resampler_ref.process(&planar_input_buffer, None).map_err(|e| {
+                            resampler_ref.process(&planar_input_buffer).map_err(|e| {
                                 StreamKitError::Runtime(format!("Resampling failed: {e}"))
                             })?;
 
+                        // Mark processed samples as consumed (compaction happens opportunistically).
+                        sample_buffer_offset += chunk_size_samples;
+
                         // Convert planar output back to interleaved format
                         let output_frames = planar_output[0].len();
+                        if output_frames == 0 {
+                            // A later stage is still filling its chunk
+                            continue;
+                        }
                         if self.config.output_frame_size > 0 {
                             output_buffer.reserve(output_frames * num_channels);
                             for frame_idx in 0..output_frames {
@@ -508,9 +854,6 @@ impl ProcessorNode for AudioResamplerNode {
 
                             stats_tracker.sent();
                         }
-
-                        // Mark processed samples as consumed (compaction happens opportunistically).
-                        sample_buffer_offset += chunk_size_samples;
                     }
 
                     if sample_buffer_offset == sample_buffer.len() {
@@ -540,8 +883,7 @@ impl ProcessorNode for AudioResamplerNode {
         }
 
         // Process any remaining buffered samples (resampling path only)
-        if sample_buffer.len() > sample_buffer_offset && needs_resample == Some(true) {
-            // Safe unwraps: channels and sample_rate are Some when resampler is Some
+        if needs_resample == Some(true) {
             let Some(channels_u16) = channels else {
                 return Err(StreamKitError::Runtime(
                     "Resampler ended with pending samples but no channel count".to_string(),
@@ -553,43 +895,32 @@ impl ProcessorNode for AudioResamplerNode {
 
             tracing::debug!("Processing {} remaining frames", remaining_frames);
 
-            // For remaining samples, we need to create a new resampler with the exact size
-            // or pad to chunk_frames
-            if remaining_frames > 0 {
-                #[allow(clippy::unwrap_used)]
-                let input_rate = sample_rate.unwrap();
-                let output_rate = self.config.target_sample_rate;
-
-                // Create a temporary resampler for the remainder
-                let mut remainder_resampler = FastFixedIn::<f32>::new(
-                    f64::from(output_rate) / f64::from(input_rate),
-                    1.0,
-                    rubato::PolynomialDegree::Linear,
-                    remaining_frames,
-                    num_channels,
-                )
-                .map_err(|e| {
-                    StreamKitError::Runtime(format!("Failed to create remainder resampler: {e}"))
-                })?;
-
-                // Convert remaining samples to planar
-                let mut planar_remainder: Vec<Vec<f32>> =
-                    vec![Vec::with_capacity(remaining_frames); num_channels];
-                let remainder_samples = &sample_buffer[sample_buffer_offset..];
-                for frame_idx in 0..remaining_frames {
-                    for ch in 0..num_channels {
-                        planar_remainder[ch].push(remainder_samples[frame_idx * num_channels + ch]);
-                    }
+            // Run the remainder through the same resampler as a zero-padded final chunk,
+            // keeping filter state, and drain what later stages still hold
+            let Some(remainder_resampler) = resampler.as_mut() else {
+                return Err(StreamKitError::Runtime(
+                    "Resampler ended with pending samples but no resampler".to_string(),
+                ));
+            };
+
+            // Convert remaining samples to planar
+            let mut planar_remainder: Vec<Vec<f32>> =
+                vec![Vec::with_capacity(remaining_frames); num_channels];
+            let remainder_samples = &sample_buffer[sample_buffer_offset..];
+            for frame_idx in 0..remaining_frames {
+                for ch in 0..num_channels {
+                    planar_remainder[ch].push(remainder_samples[frame_idx * num_channels + ch]);
                 }
+            }
 
-                // Resample remainder
-                let planar_output =
-                    remainder_resampler.process(&planar_remainder, None).map_err(|e| {
-                        StreamKitError::Runtime(format!("Resampling remainder failed: {e}"))
-                    })?;
+            // Resample remainder
+            let planar_output = remainder_resampler.finish(&planar_remainder).map_err(|e| {
+                StreamKitError::Runtime(format!("Resampling remainder failed: {e}"))
+            })?;
 
-                // Convert to interleaved
-                let output_frames = planar_output[0].len();
+            // Convert to interleaved
+            let output_frames = planar_output[0].len();
+            if output_frames > 0 {
                 let mut interleaved_output = Vec::with_capacity(output_frames * num_channels);
                 for frame_idx in 0..output_frames {
                     for channel_data in planar_output.iter().take(num_channels) {
@@ -756,7 +1087,10 @@ mod tests {
     async fn test_audio_resampler_structure() {
         let config = AudioResamplerConfig {
             target_sample_rate: 24000,
-            chunk_frames: 960,
+            quality: ResamplerQuality::Fast,
+            window: None,
+            sinc_len: None,
+            chunk_frames: Some(960),
             output_frame_size: 0, // Disabled for this test
         };
         let node = Box::new(AudioResamplerNode { config });
@@ -797,12 +1131,19 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create node that downsamples from 48kHz to 24kHz
         let config = AudioResamplerConfig {
             target_sample_rate: 24000,
-            chunk_frames: 960,
+            quality: ResamplerQuality::Fast,
+            window: None,
+            sinc_len: None,
+            chunk_frames: Some(960),
             output_frame_size: 0, // Disabled for this test
         };
         let node = Box::new(AudioResamplerNode { config });
@@ -873,12 +1214,19 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         let config = AudioResamplerConfig {
             target_sample_rate: 24000,
-            chunk_frames: 960,    // Chunk size
-            output_frame_size: 0, // Disabled for this test
+            quality: ResamplerQuality::Fast,
+            window: None,
+            sinc_len: None,
+            chunk_frames: Some(960), // Chunk size
+            output_frame_size: 0,    // Disabled for this test
         };
         let node = Box::new(AudioResamplerNode { config });
 
@@ -921,4 +1269,78 @@ mod tests {
         let result = factory(Some(&params));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_audio_resampler_rejects_sinc_options_for_fast() {
+        let factory = AudioResamplerNode::factory();
+        let params = serde_json::json!({ "target_sample_rate": 16000, "window": "hann" });
+        assert!(factory(Some(&params)).is_err());
+
+        let params = serde_json::json!({
+            "target_sample_rate": 16000,
+            "quality": "high",
+            "window": "hann",
+            "sinc_len": 100
+        });
+        assert!(factory(Some(&params)).is_err());
+
+        let params = serde_json::json!({
+            "target_sample_rate": 16000,
+            "quality": "high",
+            "window": "hann",
+            "sinc_len": 128
+        });
+        assert!(factory(Some(&params)).is_ok());
+    }
+
+    #[test]
+    fn test_audio_resampler_auto_chunk_frames() {
+        let config: AudioResamplerConfig =
+            serde_json::from_value(serde_json::json!({ "target_sample_rate": 16000 })).unwrap();
+        // Follows the packet size within 5-100 ms of input
+        assert_eq!(config.resolve_chunk_frames(48000, 960), 960);
+        assert_eq!(config.resolve_chunk_frames(48000, 10), 240);
+        assert_eq!(config.resolve_chunk_frames(48000, 48000), 4800);
+
+        let config = AudioResamplerConfig { chunk_frames: Some(512), ..config };
+        assert_eq!(config.resolve_chunk_frames(48000, 960), 512);
+    }
+
+    #[test]
+    fn test_audio_resampler_chain_stages_and_latency() {
+        let mut config: AudioResamplerConfig =
+            serde_json::from_value(serde_json::json!({ "target_sample_rate": 8000 })).unwrap();
+
+        // Linear interpolation always runs in one stage
+        let fast = ResamplerChain::new(&config, 48000, 960, 1).unwrap();
+        assert_eq!(fast.stage_count(), 1);
+
+        // A 6x sinc downsample is split in two, and costs more latency than a 2x one
+        config.quality = ResamplerQuality::High;
+        let high = ResamplerChain::new(&config, 48000, 960, 1).unwrap();
+        assert_eq!(high.stage_count(), 2);
+        let two_x = ResamplerChain::new(&config, 16000, 320, 1).unwrap();
+        assert_eq!(two_x.stage_count(), 1);
+        assert!(high.latency_us() > 20_000, "latency {}", high.latency_us());
+    }
+
+    #[test]
+    fn test_audio_resampler_two_stage_output_length() {
+        let config: AudioResamplerConfig = serde_json::from_value(serde_json::json!({
+            "target_sample_rate": 8000,
+            "quality": "medium"
+        }))
+        .unwrap();
+        let mut chain = ResamplerChain::new(&config, 48000, 960, 2).unwrap();
+
+        let chunk = vec![vec![0.25f32; 960]; 2];
+        let mut frames = 0;
+        for _ in 0..50 {
+            frames += chain.process(&chunk).unwrap()[0].len();
+        }
+        frames += chain.finish(&[vec![0.25f32; 480], vec![0.25f32; 480]]).unwrap()[0].len();
+
+        // 50.5 chunks of 20 ms at 48 kHz is 8080 frames at 8 kHz
+        assert!((8000..=8100).contains(&frames), "got {frames} frames");
+    }
 }
@@ -0,0 +1,428 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Speech gate node - Routes audio by the speech state reported by a VAD
+//!
+//! The gate opens on a VAD `speech_start` event and closes `hangover_ms` of audio after the
+//! matching `speech_end`, so short pauses between words don't chop the stream. While open,
+//! audio goes to `out`; while closed, it goes to `closed`. VAD events trail the audio they
+//! describe, so the last `pre_roll_ms` of closed audio is kept back and sent on `out` ahead of
+//! the first open packet, which keeps the onset of each utterance.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+use super::ducking::{vad_speech, VAD_EVENT_TYPE_ID};
+
+/// Configuration for the AudioSpeechGateNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioSpeechGateConfig {
+    /// How long the gate stays open after speech ends, in milliseconds of audio
+    #[schemars(range(min = 0.0, max = 5000.0), extend("tunable" = true))]
+    pub hangover_ms: f32,
+    /// Closed audio sent on `out` ahead of each utterance to cover VAD latency, in milliseconds
+    #[schemars(range(min = 0.0, max = 2000.0), extend("tunable" = true))]
+    pub pre_roll_ms: f32,
+}
+
+impl Default for AudioSpeechGateConfig {
+    fn default() -> Self {
+        Self { hangover_ms: 300.0, pre_roll_ms: 200.0 }
+    }
+}
+
+impl AudioSpeechGateConfig {
+    fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{name} must be within {min}-{max}, got {value}"))
+            }
+        };
+        check("hangover_ms", self.hangover_ms, 0.0, 5000.0)?;
+        check("pre_roll_ms", self.pre_roll_ms, 0.0, 2000.0)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn hangover_us(&self) -> u64 {
+        (self.hangover_ms * 1000.0) as u64
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn pre_roll_us(&self) -> u64 {
+        (self.pre_roll_ms * 1000.0) as u64
+    }
+}
+
+/// Where a packet of audio leaves the gate.
+enum Route {
+    /// The gate is open: any pre-roll, then the packet itself, go to `out`.
+    Open(Vec<AudioFrame>),
+    /// The gate is closed: the packet goes to `closed`.
+    Closed(AudioFrame),
+}
+
+/// Speech state, hangover and pre-roll for one audio stream.
+struct Gate {
+    config: AudioSpeechGateConfig,
+    /// Whether the latest VAD event was `speech_start`
+    speaking: bool,
+    /// Audio since the last `speech_end`, in microseconds
+    since_speech_us: u64,
+    /// The most recent closed audio, at most `pre_roll_ms` of it
+    pre_roll: VecDeque<AudioFrame>,
+    pre_roll_us: u64,
+}
+
+impl Gate {
+    const fn new(config: AudioSpeechGateConfig) -> Self {
+        Self {
+            config,
+            speaking: false,
+            since_speech_us: u64::MAX,
+            pre_roll: VecDeque::new(),
+            pre_roll_us: 0,
+        }
+    }
+
+    fn set_config(&mut self, config: AudioSpeechGateConfig) {
+        self.config = config;
+        self.trim_pre_roll();
+    }
+
+    const fn set_speaking(&mut self, speaking: bool) {
+        if self.speaking && !speaking {
+            self.since_speech_us = 0;
+        }
+        self.speaking = speaking;
+    }
+
+    fn is_open(&self) -> bool {
+        self.speaking || self.since_speech_us < self.config.hangover_us()
+    }
+
+    fn route(&mut self, frame: AudioFrame) -> Route {
+        let duration_us = frame.duration_us().unwrap_or(0);
+        let open = self.is_open();
+        if !self.speaking {
+            self.since_speech_us = self.since_speech_us.saturating_add(duration_us);
+        }
+
+        if open {
+            let mut frames: Vec<AudioFrame> = self.pre_roll.drain(..).collect();
+            self.pre_roll_us = 0;
+            frames.push(frame);
+            Route::Open(frames)
+        } else {
+            if self.config.pre_roll_ms > 0.0 {
+                self.pre_roll.push_back(frame.clone());
+                self.pre_roll_us += duration_us;
+                self.trim_pre_roll();
+            }
+            Route::Closed(frame)
+        }
+    }
+
+    fn trim_pre_roll(&mut self) {
+        let limit = self.config.pre_roll_us();
+        while self.pre_roll_us > limit {
+            let Some(oldest) = self.pre_roll.pop_front() else { break };
+            self.pre_roll_us -= oldest.duration_us().unwrap_or(0);
+        }
+    }
+}
+
+/// A node that passes audio on `in` to `out` only while the `vad` input reports speech.
+///
+/// The `vad` pin takes the `plugin::native::vad/vad-event@1` events of a VAD in `events`
+/// mode; the gate opens on `speech_start` and closes once `hangover_ms` of audio has passed
+/// after `speech_end`. Audio arriving while the gate is closed goes to `closed` instead, so the
+/// node can split a stream as well as gate it; leave `closed` unconnected to drop it. Pre-roll
+/// audio is sent on both pins. Until the first event the gate is closed. All parameters can be
+/// tuned while running.
+pub struct AudioSpeechGateNode {
+    config: AudioSpeechGateConfig,
+}
+
+impl AudioSpeechGateNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioSpeechGateConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid speech gate configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+const fn raw_audio() -> PacketType {
+    PacketType::RawAudio(AudioFormat {
+        sample_rate: 0, // Wildcard
+        channels: 0,    // Wildcard
+        sample_format: SampleFormat::F32,
+    })
+}
+
+#[async_trait]
+impl ProcessorNode for AudioSpeechGateNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![raw_audio()],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "vad".to_string(),
+                accepts_types: vec![PacketType::Custom { type_id: VAD_EVENT_TYPE_ID.to_string() }],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![
+            OutputPin {
+                name: "out".to_string(),
+                // It outputs the same format it receives.
+                produces_type: raw_audio(),
+                cardinality: PinCardinality::Broadcast,
+            },
+            OutputPin {
+                name: "closed".to_string(),
+                produces_type: raw_audio(),
+                cardinality: PinCardinality::Broadcast,
+            },
+        ]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut vad_rx = context.inputs.remove("vad");
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut gate = Gate::new(self.config);
+
+        loop {
+            tokio::select! {
+                // Events first, so a speech_start queued alongside audio applies to it.
+                biased;
+                maybe_event = async { vad_rx.as_mut()?.recv().await }, if vad_rx.is_some() => {
+                    match maybe_event {
+                        Some(Packet::Custom(event)) => {
+                            if let Some(speaking) = vad_speech(&event) {
+                                gate.set_speaking(speaking);
+                            }
+                        }
+                        Some(_) => {}
+                        // Once the VAD ends, the gate keeps its last state.
+                        None => vad_rx = None,
+                    }
+                }
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let frames = match packet {
+                        Packet::Audio(frame) => match gate.route(frame) {
+                            Route::Open(frames) => frames,
+                            Route::Closed(frame) => {
+                                // `closed` is optional, so an unconnected pin isn't an error.
+                                let packet = Packet::Audio(frame);
+                                let _ = context.output_sender.send("closed", packet).await;
+                                stats.discarded();
+                                stats.maybe_send();
+                                continue;
+                            }
+                        },
+                        other => {
+                            if context.output_sender.send("out", other).await.is_err() {
+                                break;
+                            }
+                            stats.sent();
+                            continue;
+                        }
+                    };
+                    for frame in frames {
+                        if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&gate.config, &params).and_then(|config: AudioSpeechGateConfig| {
+                            config.validate()?;
+                            Ok(config)
+                        }) {
+                            Ok(config) => gate.set_config(config),
+                            Err(e) => {
+                                tracing::warn!("Rejected speech gate update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test assertions
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_audio_packet, create_test_context, extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::{CustomEncoding, CustomPacketData};
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 48_000;
+
+    /// A 20ms mono frame filled with `value`.
+    fn frame(value: f32) -> AudioFrame {
+        AudioFrame::new(RATE, 1, vec![value; 960])
+    }
+
+    fn is_open(route: &Route) -> bool {
+        matches!(route, Route::Open(_))
+    }
+
+    fn vad_event(event_type: &str) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: VAD_EVENT_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({ "event_type": event_type, "timestamp_ms": 0 }),
+            metadata: None,
+        }))
+    }
+
+    #[test]
+    fn test_gate_holds_open_for_hangover_after_speech_end() {
+        let mut gate = Gate::new(AudioSpeechGateConfig { hangover_ms: 60.0, pre_roll_ms: 0.0 });
+        assert!(!is_open(&gate.route(frame(0.1))));
+
+        gate.set_speaking(true);
+        assert!(is_open(&gate.route(frame(0.1))));
+        gate.set_speaking(false);
+
+        // Three 20ms frames of hangover, then closed.
+        for _ in 0..3 {
+            assert!(is_open(&gate.route(frame(0.1))));
+        }
+        assert!(!is_open(&gate.route(frame(0.1))));
+
+        // A repeated speech_end doesn't restart the hangover.
+        gate.set_speaking(false);
+        assert!(!is_open(&gate.route(frame(0.1))));
+    }
+
+    #[test]
+    fn test_pre_roll_keeps_latest_closed_audio() {
+        let mut gate = Gate::new(AudioSpeechGateConfig { hangover_ms: 0.0, pre_roll_ms: 40.0 });
+        for value in [0.1, 0.2, 0.3, 0.4] {
+            assert!(!is_open(&gate.route(frame(value))));
+        }
+
+        gate.set_speaking(true);
+        let Route::Open(frames) = gate.route(frame(0.5)) else { panic!("gate should be open") };
+        let firsts: Vec<f32> = frames.iter().map(|f| f.samples()[0]).collect();
+        assert_eq!(firsts, vec![0.3, 0.4, 0.5]);
+
+        // The pre-roll is only sent once.
+        let Route::Open(frames) = gate.route(frame(0.6)) else { panic!("gate should be open") };
+        assert_eq!(frames.len(), 1);
+    }
+
+    #[test]
+    fn test_validation_and_updates() {
+        assert!(AudioSpeechGateConfig::default().validate().is_ok());
+        assert!(AudioSpeechGateConfig { hangover_ms: -1.0, ..Default::default() }
+            .validate()
+            .is_err());
+
+        let updated: AudioSpeechGateConfig = merge_params(
+            &AudioSpeechGateConfig::default(),
+            &serde_json::json!({"hangover_ms": 800.0}),
+        )
+        .unwrap();
+        assert!((updated.hangover_ms - 800.0).abs() < f32::EPSILON);
+        assert!((updated.pre_roll_ms - 200.0).abs() < f32::EPSILON);
+
+        let factory = AudioSpeechGateNode::factory();
+        assert!(factory(Some(&serde_json::json!({"pre_roll_ms": 5000.0}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_routes_audio_on_vad_events() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let (vad_tx, vad_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx), ("vad".to_string(), vad_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = AudioSpeechGateNode::factory()(Some(&serde_json::json!({
+            "hangover_ms": 0.0, "pre_roll_ms": 0.0
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        input_tx.send(create_test_audio_packet(RATE, 1, 960, 0.1)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        vad_tx.send(vad_event("speech_start")).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 1, 960, 0.2)).await.unwrap();
+        input_tx.send(create_test_audio_packet(RATE, 1, 960, 0.3)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        vad_tx.send(vad_event("speech_end")).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        input_tx.send(create_test_audio_packet(RATE, 1, 960, 0.4)).await.unwrap();
+        drop(input_tx);
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        // Collecting drains the mock sender, so read both pins from one collection
+        let packets = mock_sender.collect_packets().await;
+        let first = |name: &str| -> Vec<f32> {
+            packets
+                .iter()
+                .filter(|(_, pin, _)| pin == name)
+                .map(|(_, _, p)| extract_audio_data(p).unwrap()[0])
+                .collect()
+        };
+        assert_eq!(first("out"), vec![0.2, 0.3]);
+        assert_eq!(first("closed"), vec![0.1, 0.4]);
+    }
+}
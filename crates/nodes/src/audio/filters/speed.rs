@@ -0,0 +1,606 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Speed node - Tempo change without pitch shift (time-stretching)
+//!
+//! Plays raw audio `rate` times faster (or slower below 1.0) while keeping its pitch, using
+//! WSOLA (waveform-similarity overlap-add): the output is built from Hann-windowed segments
+//! of the input taken every `rate` half-windows, each nudged by up to `search_ms` to where
+//! it lines up best with the previous one, so voices stay free of phasing artifacts.
+//!
+//! The rate can be changed while running, e.g. to let a listener speed TTS up. Output
+//! frames have the size of the input frames and timestamps continue from the first input
+//! timestamp on the stretched timeline. The node holds about one window of audio, which is
+//! flushed when the input ends, so a whole file comes out `1 / rate` times as long.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+const MIN_RATE: f32 = 0.25;
+const MAX_RATE: f32 = 4.0;
+
+/// Configuration for the AudioSpeedNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioSpeedConfig {
+    /// Playback speed: 2.0 plays twice as fast, 0.5 at half speed, 1.0 leaves the tempo as is
+    #[schemars(range(min = 0.25, max = 4.0), extend("tunable" = true))]
+    pub rate: f32,
+    /// Length of the overlapping segments, in milliseconds. Longer windows suit music,
+    /// shorter ones keep speech crisper.
+    #[schemars(range(min = 10.0, max = 100.0))]
+    pub window_ms: f32,
+    /// How far a segment may be moved to line up with the previous one, in milliseconds
+    #[schemars(range(min = 0.0, max = 30.0))]
+    pub search_ms: f32,
+}
+
+impl Default for AudioSpeedConfig {
+    fn default() -> Self {
+        Self { rate: 1.0, window_ms: 30.0, search_ms: 10.0 }
+    }
+}
+
+impl AudioSpeedConfig {
+    pub(super) fn validate(&self) -> Result<(), String> {
+        if !(MIN_RATE..=MAX_RATE).contains(&self.rate) {
+            return Err(format!("rate must be within {MIN_RATE}-{MAX_RATE}, got {}", self.rate));
+        }
+        if !(10.0..=100.0).contains(&self.window_ms) {
+            return Err(format!("window_ms must be within 10-100, got {}", self.window_ms));
+        }
+        if !(0.0..=30.0).contains(&self.search_ms) {
+            return Err(format!("search_ms must be within 0-30, got {}", self.search_ms));
+        }
+        Ok(())
+    }
+}
+
+/// Number of frames `ms` milliseconds last at `sample_rate`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Validated to at most 100 ms
+fn ms_to_frames(ms: f32, sample_rate: u32) -> usize {
+    (f64::from(ms) * f64::from(sample_rate) / 1000.0).round() as usize
+}
+
+/// WSOLA time-stretcher for one stream of interleaved audio.
+pub(super) struct Stretcher {
+    pub(super) sample_rate: u32,
+    pub(super) channels: usize,
+    /// Output hop in frames; windows are twice as long
+    hop: usize,
+    /// Search radius in frames
+    search: usize,
+    rate: f64,
+    /// Periodic Hann window, which sums to one at 50% overlap
+    window: Vec<f32>,
+    /// Input not needed by past segments, interleaved; `input[0]` is frame `base` of the stream
+    input: Vec<f32>,
+    base: usize,
+    /// Frames received so far
+    total_in: usize,
+    /// Where the next segment would start without alignment, in input frames
+    nominal: f64,
+    /// Start of the previous segment
+    prev: Option<usize>,
+    /// Overlap-add buffer of one window
+    overlap: Vec<f32>,
+    /// Finished output, interleaved
+    output: Vec<f32>,
+}
+
+impl Stretcher {
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub(super) fn new(sample_rate: u32, channels: usize, config: &AudioSpeedConfig) -> Self {
+        let channels = channels.max(1);
+        let hop = ms_to_frames(config.window_ms / 2.0, sample_rate).max(1);
+        let len = hop * 2;
+        let window = (0..len)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * i as f64 / len as f64;
+                0.5f64.mul_add(-phase.cos(), 0.5) as f32
+            })
+            .collect();
+        Self {
+            sample_rate,
+            channels,
+            hop,
+            search: ms_to_frames(config.search_ms, sample_rate),
+            rate: f64::from(config.rate),
+            window,
+            input: Vec::new(),
+            base: 0,
+            total_in: 0,
+            nominal: 0.0,
+            prev: None,
+            overlap: vec![0.0; len * channels],
+            output: Vec::new(),
+        }
+    }
+
+    pub(super) fn set_rate(&mut self, rate: f32) {
+        self.rate = f64::from(rate);
+    }
+
+    /// Nearest frame to the nominal position of the next segment.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Never negative
+    const fn center(&self) -> usize {
+        self.nominal.round() as usize
+    }
+
+    /// Input frames needed before the next segment can be placed.
+    fn needed(&self) -> usize {
+        let end = self.prev.map_or(0, |prev| (self.center() + self.search).max(prev + self.hop));
+        end + self.hop * 2
+    }
+
+    /// Mono mixdown of `len` frames starting at stream frame `start`.
+    fn mono(&self, start: usize, len: usize) -> Vec<f32> {
+        let from = (start - self.base) * self.channels;
+        self.input[from..from + len * self.channels]
+            .chunks_exact(self.channels)
+            .map(|frame| frame.iter().sum())
+            .collect()
+    }
+
+    /// Start of the segment within the search range that best continues the previous one.
+    fn align(&self, prev: usize) -> usize {
+        let center = self.center();
+        let lo = center.saturating_sub(self.search).max(self.base);
+        let hi = center + self.search;
+        // What the previous segment would have continued with, over the overlapping half
+        let target = self.mono(prev + self.hop, self.hop);
+        let candidates = self.mono(lo, hi - lo + self.hop);
+
+        let score = |offset: usize| {
+            let segment = &candidates[offset..offset + self.hop];
+            let (dot, energy) =
+                segment.iter().zip(&target).fold((0.0f64, 0.0f64), |(dot, energy), (x, t)| {
+                    (dot + f64::from(x * t), energy + f64::from(x * x))
+                });
+            dot / (energy + 1e-9).sqrt()
+        };
+        // Ties go to the nominal position, so silence and steady input are left in place
+        let mut best = center.max(lo);
+        let mut best_score = score(best - lo);
+        for start in lo..=hi {
+            let candidate = score(start - lo);
+            if candidate > best_score {
+                best = start;
+                best_score = candidate;
+            }
+        }
+        best
+    }
+
+    /// Places one segment and moves a hop of finished audio to the output.
+    #[allow(clippy::cast_precision_loss)]
+    fn step(&mut self) {
+        let start = self.prev.map_or(0, |prev| self.align(prev));
+        let len = self.hop * 2;
+        let from = (start - self.base) * self.channels;
+        let segment = &self.input[from..from + len * self.channels];
+        for (i, (frame, out)) in segment
+            .chunks_exact(self.channels)
+            .zip(self.overlap.chunks_exact_mut(self.channels))
+            .enumerate()
+        {
+            // The first segment has nothing to fade in against
+            let w = if self.prev.is_none() && i < self.hop { 1.0 } else { self.window[i] };
+            for (o, x) in out.iter_mut().zip(frame) {
+                *o += x * w;
+            }
+        }
+
+        let hop_samples = self.hop * self.channels;
+        self.output.extend_from_slice(&self.overlap[..hop_samples]);
+        self.overlap.copy_within(hop_samples.., 0);
+        let tail = self.overlap.len() - hop_samples;
+        self.overlap[tail..].fill(0.0);
+
+        self.prev = Some(start);
+        self.nominal += self.hop as f64 * self.rate;
+        // Later segments start no earlier than the search range or the previous continuation
+        let keep_from = self.center().saturating_sub(self.search).min(start + self.hop);
+        if keep_from > self.base {
+            self.input.drain(..(keep_from - self.base) * self.channels);
+            self.base = keep_from;
+        }
+    }
+
+    /// Stretches interleaved `samples`, adding what is finished to the output.
+    pub(super) fn process(&mut self, samples: &[f32]) {
+        self.input.extend_from_slice(samples);
+        self.total_in += samples.len() / self.channels;
+        while self.needed() <= self.total_in {
+            self.step();
+        }
+    }
+
+    /// Takes `frames` frames of finished output, if there are that many.
+    fn take(&mut self, frames: usize) -> Option<Vec<f32>> {
+        let samples = frames * self.channels;
+        (frames > 0 && self.output.len() >= samples).then(|| self.output.drain(..samples).collect())
+    }
+
+    /// Takes all finished output.
+    pub(super) fn take_all(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Finishes the stream: the rest of the output, as if the input continued with silence.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(super) fn drain(&mut self) -> Vec<f32> {
+        let owed = ((self.total_in as f64 - self.nominal) / self.rate).round().max(0.0) as usize;
+        let total = self.output.len() + owed * self.channels;
+        while self.output.len() < total {
+            let missing = self.needed().saturating_sub(self.total_in);
+            self.process(&vec![0.0; missing * self.channels]);
+        }
+        self.output.truncate(total);
+        std::mem::take(&mut self.output)
+    }
+}
+
+/// A node that changes the tempo of raw audio without changing its pitch.
+///
+/// Typical uses are speeding up or slowing down TTS output on request, and playing
+/// recordings back faster in oneshot conversion pipelines.
+pub struct AudioSpeedNode {
+    config: AudioSpeedConfig,
+}
+
+impl AudioSpeedNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioSpeedConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid speed configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+/// Timestamps stretched output on a timeline starting at the first input timestamp.
+struct OutputClock {
+    start_us: Option<u64>,
+    frames: u64,
+}
+
+impl OutputClock {
+    fn metadata(&mut self, frame: &AudioFrame) -> Option<PacketMetadata> {
+        let start_us = self.start_us?;
+        let timestamp_us = start_us + self.frames * 1_000_000 / u64::from(frame.sample_rate);
+        self.frames += frame.num_frames() as u64;
+        Some(PacketMetadata {
+            timestamp_us: Some(timestamp_us),
+            duration_us: frame.duration_us(),
+            sequence: None,
+        })
+    }
+}
+
+/// Sends interleaved `samples` as one frame.
+async fn send_audio(
+    context: &mut NodeContext,
+    stats: &mut NodeStatsTracker,
+    stretcher: &Stretcher,
+    clock: &mut OutputClock,
+    samples: Vec<f32>,
+) -> bool {
+    #[allow(clippy::cast_possible_truncation)] // Channel counts come from a u16
+    let channels = stretcher.channels as u16;
+    let mut frame = AudioFrame::new(stretcher.sample_rate, channels, samples);
+    frame.metadata = clock.metadata(&frame);
+    if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+        return false;
+    }
+    stats.sent();
+    true
+}
+
+#[async_trait]
+impl ProcessorNode for AudioSpeedNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut config = self.config;
+        let mut stretcher: Option<Stretcher> = None;
+        let mut clock = OutputClock { start_us: None, frames: 0 };
+        // Output frames match the size of the last input frame
+        let mut chunk_frames = 0;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    if frame.sample_rate == 0 || frame.channels == 0 {
+                        stats.discarded();
+                        continue;
+                    }
+                    let channels = usize::from(frame.channels);
+                    let current = match &mut stretcher {
+                        Some(current)
+                            if current.sample_rate == frame.sample_rate
+                                && current.channels == channels => current,
+                        _ => {
+                            if stretcher.is_some() {
+                                tracing::info!(
+                                    "Audio format changed to {} Hz, {} channels; restarting the stretcher",
+                                    frame.sample_rate,
+                                    channels
+                                );
+                            }
+                            clock = OutputClock {
+                                start_us: frame.metadata.as_ref().and_then(|m| m.timestamp_us),
+                                frames: 0,
+                            };
+                            stretcher.insert(Stretcher::new(frame.sample_rate, channels, &config))
+                        },
+                    };
+                    chunk_frames = frame.num_frames();
+                    current.process(&frame.samples);
+                    while let Some(samples) = current.take(chunk_frames) {
+                        if !send_audio(&mut context, &mut stats, current, &mut clock, samples).await {
+                            tracing::debug!("Output channel closed, stopping node");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                    }
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&config, &params).and_then(|updated: AudioSpeedConfig| {
+                            updated.validate()?;
+                            // Everything but the rate is fixed once running
+                            if (AudioSpeedConfig { rate: config.rate, ..updated }) != config {
+                                return Err(
+                                    "window_ms and search_ms cannot be changed while running"
+                                        .to_string(),
+                                );
+                            }
+                            Ok(updated)
+                        }) {
+                            Ok(updated) => {
+                                if let Some(stretcher) = &mut stretcher {
+                                    stretcher.set_rate(updated.rate);
+                                }
+                                config = updated;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected speed update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => {
+                        stretcher = None;
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Some(stretcher) = stretcher.as_mut() {
+            let samples = stretcher.drain();
+            for chunk in samples.chunks(chunk_frames.max(1) * stretcher.channels) {
+                if !send_audio(&mut context, &mut stats, stretcher, &mut clock, chunk.to_vec())
+                    .await
+                {
+                    tracing::debug!("Output channel closed while flushing the stretcher");
+                    break;
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss, clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    const RATE: u32 = 8000;
+
+    fn config(rate: f32) -> AudioSpeedConfig {
+        AudioSpeedConfig { rate, ..AudioSpeedConfig::default() }
+    }
+
+    /// Deterministic broadband signal, so no two offsets line up equally well.
+    fn noise(frames: usize) -> Vec<f32> {
+        let mut state = 0x2545_f491_u32;
+        (0..frames)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32).mul_add(2.0, -1.0)
+            })
+            .collect()
+    }
+
+    fn stretch(rate: f32, input: &[f32], chunk: usize) -> Vec<f32> {
+        let mut stretcher = Stretcher::new(RATE, 1, &config(rate));
+        let mut output = Vec::new();
+        for samples in input.chunks(chunk) {
+            stretcher.process(samples);
+            while let Some(ready) = stretcher.take(chunk) {
+                output.extend(ready);
+            }
+        }
+        output.extend(stretcher.drain());
+        output
+    }
+
+    /// Zero crossings per second, a rough pitch estimate for a pure tone.
+    fn crossings_per_second(samples: &[f32]) -> f32 {
+        let crossings = samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+        crossings as f32 * RATE as f32 / samples.len() as f32
+    }
+
+    #[test]
+    fn test_unit_rate_is_transparent() {
+        let input = noise(4000);
+        let output = stretch(1.0, &input, 160);
+        assert_eq!(output.len(), input.len());
+        for (out, inp) in output.iter().zip(&input) {
+            assert!((out - inp).abs() < 1e-5, "{out} != {inp}");
+        }
+    }
+
+    #[test]
+    fn test_rate_changes_duration_but_not_pitch() {
+        let tone: Vec<f32> = (0..16_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / RATE as f32).sin())
+            .collect();
+        let original = crossings_per_second(&tone);
+
+        for (rate, expected_len) in [(2.0, 8000), (0.5, 32_000), (1.5, 10_667)] {
+            let output = stretch(rate, &tone, 160);
+            assert_eq!(output.len(), expected_len, "rate {rate}");
+            // Skip the edges, where the first and last windows fade against silence
+            let body = &output[800..output.len() - 800];
+            let pitch = crossings_per_second(body);
+            assert!(
+                (pitch - original).abs() < original * 0.02,
+                "rate {rate}: {pitch} vs {original}"
+            );
+            let peak = body.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!(peak > 0.9 && peak < 1.1, "rate {rate}: peak {peak}");
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioSpeedConfig::default().validate().is_ok());
+        assert!(config(0.1).validate().is_err());
+        assert!(config(5.0).validate().is_err());
+        assert!(AudioSpeedConfig { window_ms: 5.0, ..config(1.0) }.validate().is_err());
+        assert!(AudioSpeedConfig { search_ms: 50.0, ..config(1.0) }.validate().is_err());
+        assert!(AudioSpeedNode::factory()(Some(&serde_json::json!({"rate": 1.25}))).is_ok());
+
+        let updated: AudioSpeedConfig =
+            merge_params(&config(1.0), &serde_json::json!({"rate": 2.0})).unwrap();
+        assert_eq!(updated, config(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_node_stretches_with_timestamps_and_tunable_rate() {
+        let (input_tx, input_rx) = mpsc::channel(100);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (mut context, mock_sender, mut state_rx) = create_test_context(inputs, 100);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+
+        let params = serde_json::json!({"rate": 2.0});
+        let node = AudioSpeedNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let send = |i: u64| {
+            let metadata = PacketMetadata {
+                timestamp_us: Some(1_000_000 + i * 20_000),
+                duration_us: Some(20_000),
+                sequence: Some(i),
+            };
+            Packet::Audio(AudioFrame::with_metadata(RATE, 1, noise(160), Some(metadata)))
+        };
+        for i in 0..50 {
+            input_tx.send(send(i)).await.unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        // Window sizes are fixed once running; the rate is not
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"window_ms": 50.0})))
+            .await
+            .unwrap();
+        control_tx
+            .send(NodeControlMessage::UpdateParams(serde_json::json!({"rate": 1.0})))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        for i in 50..100 {
+            input_tx.send(send(i)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let total: usize = packets.iter().map(|p| extract_audio_data(p).unwrap().len()).sum();
+        // One second at double speed, then one second as is
+        let expected = 8000 / 2 + 8000;
+        // Audio still held in the window when the rate changes plays at the new rate
+        assert!(total.abs_diff(expected) <= 400, "{total} frames, expected about {expected}");
+
+        let timestamps: Vec<u64> = packets
+            .iter()
+            .map(|p| match p {
+                Packet::Audio(frame) => frame.metadata.as_ref().unwrap().timestamp_us.unwrap(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(timestamps[0], 1_000_000);
+        assert!(timestamps.windows(2).all(|w| w[1] - w[0] == 20_000));
+    }
+}
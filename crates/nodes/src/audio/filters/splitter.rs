@@ -0,0 +1,545 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Splitter node - Fans one audio stream out to several outputs, each in its own format
+//!
+//! Every entry of `outputs` becomes a pin `out_0`, `out_1`, ... that receives the input
+//! converted to that entry's `sample_rate` and `channels`; fields left unset pass through
+//! unchanged. This lets a single microphone feed e.g. a 48 kHz stereo Opus encoder and a
+//! 16 kHz mono speech recognizer without a chain of resamplers and channel mixers.
+//!
+//! Channels are remixed between the standard layouts (see [`crate::audio::layout`]) before
+//! resampling, so downmixed branches resample fewer channels. Non-audio packets are copied
+//! to every output. An output whose downstream closes stops receiving packets; the node
+//! stops once all of them are closed.
+
+use crate::audio::layout::Remix;
+use async_trait::async_trait;
+use rubato::{FastFixedIn, Resampler};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Most outputs a splitter can have.
+const MAX_OUTPUTS: usize = 16;
+
+/// Most channels an output can be converted to.
+const MAX_CHANNELS: u16 = 32;
+
+/// Highest sample rate an output can be converted to, in Hz.
+const MAX_SAMPLE_RATE: u32 = 384_000;
+
+/// Input consumed per resampling step, in milliseconds.
+const CHUNK_MS: u32 = 20;
+
+/// Format of one output; unset fields follow the input.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct SplitterOutput {
+    /// Sample rate to resample to, in Hz
+    #[schemars(range(min = 1, max = 384_000))]
+    pub sample_rate: Option<u32>,
+    /// Number of channels to up- or downmix to
+    #[schemars(range(min = 1, max = 32))]
+    pub channels: Option<u16>,
+}
+
+/// Configuration for the AudioSplitterNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct AudioSplitterConfig {
+    /// One entry per output pin, in order (`out_0`, `out_1`, ...)
+    pub outputs: Vec<SplitterOutput>,
+}
+
+impl Default for AudioSplitterConfig {
+    fn default() -> Self {
+        Self { outputs: vec![SplitterOutput::default(); 2] }
+    }
+}
+
+impl AudioSplitterConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_OUTPUTS).contains(&self.outputs.len()) {
+            return Err(format!(
+                "outputs must have 1-{MAX_OUTPUTS} entries, got {}",
+                self.outputs.len()
+            ));
+        }
+        for (index, output) in self.outputs.iter().enumerate() {
+            if output.sample_rate.is_some_and(|rate| !(1..=MAX_SAMPLE_RATE).contains(&rate)) {
+                return Err(format!(
+                    "outputs[{index}].sample_rate must be within 1-{MAX_SAMPLE_RATE}"
+                ));
+            }
+            if output.channels.is_some_and(|channels| !(1..=MAX_CHANNELS).contains(&channels)) {
+                return Err(format!("outputs[{index}].channels must be within 1-{MAX_CHANNELS}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Name of the output pin at `index`.
+fn pin_name(index: usize) -> String {
+    format!("out_{index}")
+}
+
+/// Streaming sample rate conversion of interleaved audio in fixed input chunks.
+struct ChunkResampler {
+    resampler: FastFixedIn<f32>,
+    /// Output frames per input frame
+    ratio: f64,
+    channels: usize,
+    chunk_frames: usize,
+    /// Interleaved input not yet resampled
+    pending: Vec<f32>,
+}
+
+impl ChunkResampler {
+    fn new(from: u32, to: u32, channels: usize) -> Result<Self, StreamKitError> {
+        let chunk_frames = (from * CHUNK_MS / 1000).max(1) as usize;
+        let ratio = f64::from(to) / f64::from(from);
+        let resampler = FastFixedIn::<f32>::new(
+            ratio,
+            1.0,
+            rubato::PolynomialDegree::Linear,
+            chunk_frames,
+            channels,
+        )
+        .map_err(|e| StreamKitError::Runtime(format!("Failed to create resampler: {e}")))?;
+        Ok(Self { resampler, ratio, channels, chunk_frames, pending: Vec::new() })
+    }
+
+    /// Resamples every complete chunk of `pending` plus `input`, returning interleaved output.
+    fn push(&mut self, input: &[f32]) -> Result<Vec<f32>, StreamKitError> {
+        self.pending.extend_from_slice(input);
+        let chunk_samples = self.chunk_frames * self.channels;
+        let mut output = Vec::new();
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= chunk_samples {
+            let planar = self.deinterleave(&self.pending[consumed..consumed + chunk_samples]);
+            let resampled = self
+                .resampler
+                .process(&planar, None)
+                .map_err(|e| StreamKitError::Runtime(format!("Resampling failed: {e}")))?;
+            self.interleave_into(&resampled, &mut output);
+            consumed += chunk_samples;
+        }
+        self.pending.drain(..consumed);
+        Ok(output)
+    }
+
+    /// Resamples what is left of the input. The last chunk is padded with silence, which is
+    /// cut off again so the output lasts as long as the input did.
+    fn flush(&mut self) -> Result<Vec<f32>, StreamKitError> {
+        let mut output = Vec::new();
+        if self.pending.is_empty() {
+            return Ok(output);
+        }
+        let planar = self.deinterleave(&self.pending);
+        let resampled = self
+            .resampler
+            .process_partial(Some(&planar), None)
+            .map_err(|e| StreamKitError::Runtime(format!("Resampling failed: {e}")))?;
+        self.interleave_into(&resampled, &mut output);
+
+        let pending_frames = self.pending.len() / self.channels;
+        // Rounded frame counts of a single chunk fit any integer type
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let frames = (pending_frames as f64 * self.ratio).round() as usize;
+        output.truncate(frames * self.channels);
+        self.pending.clear();
+        Ok(output)
+    }
+
+    fn deinterleave(&self, samples: &[f32]) -> Vec<Vec<f32>> {
+        (0..self.channels)
+            .map(|ch| samples.iter().skip(ch).step_by(self.channels).copied().collect())
+            .collect()
+    }
+
+    fn interleave_into(&self, planar: &[Vec<f32>], output: &mut Vec<f32>) {
+        let frames = planar.first().map_or(0, Vec::len);
+        output.reserve(frames * self.channels);
+        for frame in 0..frames {
+            output.extend(planar.iter().take(self.channels).map(|channel| channel[frame]));
+        }
+    }
+}
+
+/// Conversion of the input to one output's format.
+struct Branch {
+    pin: String,
+    target: SplitterOutput,
+    /// Input sample rate and channel count the conversion was set up for
+    input: Option<(u32, u16)>,
+    /// Remix to the output channel count, unless it passes channels through
+    remix: Option<Remix>,
+    resampler: Option<ChunkResampler>,
+    /// Timestamp and sequence of the next resampled frame
+    next_timestamp_us: Option<u64>,
+    next_sequence: u64,
+    open: bool,
+}
+
+impl Branch {
+    fn new(index: usize, target: SplitterOutput) -> Self {
+        Self {
+            pin: pin_name(index),
+            target,
+            input: None,
+            remix: None,
+            resampler: None,
+            next_timestamp_us: None,
+            next_sequence: 0,
+            open: true,
+        }
+    }
+
+    fn output_rate(&self, input_rate: u32) -> u32 {
+        self.target.sample_rate.unwrap_or(input_rate)
+    }
+
+    fn output_channels(&self, input_channels: u16) -> u16 {
+        self.target.channels.unwrap_or(input_channels)
+    }
+
+    /// Sets up the conversion for `frame`'s format, returning what the previous conversion
+    /// still held if the format changed.
+    fn configure(&mut self, frame: &AudioFrame) -> Result<Option<AudioFrame>, StreamKitError> {
+        let format = (frame.sample_rate, frame.channels);
+        if self.input == Some(format) {
+            return Ok(None);
+        }
+        let flushed = self.flush()?;
+
+        let channels = self.output_channels(frame.channels);
+        let remix = Remix::new(frame.channels, channels);
+        self.remix = (!remix.is_identity()).then_some(remix);
+        let rate = self.output_rate(frame.sample_rate);
+        self.resampler = if rate == frame.sample_rate {
+            None
+        } else {
+            Some(ChunkResampler::new(frame.sample_rate, rate, usize::from(channels))?)
+        };
+        self.input = Some(format);
+        self.next_timestamp_us = frame.metadata.as_ref().and_then(|m| m.timestamp_us);
+        Ok(flushed)
+    }
+
+    /// Converts `frame` to the output format; `None` while the resampler is filling up.
+    fn convert(&mut self, frame: &AudioFrame) -> Result<Option<AudioFrame>, StreamKitError> {
+        if self.remix.is_none() && self.resampler.is_none() {
+            return Ok(Some(frame.clone()));
+        }
+        let remixed = self.remix.as_ref().map(|remix| remix.apply(frame.samples()));
+        let samples = remixed.as_deref().unwrap_or_else(|| frame.samples());
+        let channels = self.output_channels(frame.channels);
+        let Some(resampler) = self.resampler.as_mut() else {
+            return Ok(Some(AudioFrame::with_metadata(
+                frame.sample_rate,
+                channels,
+                samples.to_vec(),
+                frame.metadata.clone(),
+            )));
+        };
+        let output = resampler.push(samples)?;
+        Ok(self.resampled_frame(output))
+    }
+
+    /// Frames to send for `frame`: the remainder of a previous format, if it changed, and
+    /// `frame` converted.
+    fn process(&mut self, frame: &AudioFrame) -> Result<Vec<AudioFrame>, StreamKitError> {
+        let flushed = self.configure(frame)?;
+        let converted = self.convert(frame)?;
+        Ok(flushed.into_iter().chain(converted).collect())
+    }
+
+    /// Converts whatever the resampler still holds.
+    fn flush(&mut self) -> Result<Option<AudioFrame>, StreamKitError> {
+        let Some(resampler) = self.resampler.as_mut() else { return Ok(None) };
+        let output = resampler.flush()?;
+        Ok(self.resampled_frame(output))
+    }
+
+    /// Wraps resampled samples in a frame, continuing the branch's own timeline.
+    fn resampled_frame(&mut self, samples: Vec<f32>) -> Option<AudioFrame> {
+        let (input_rate, input_channels) = self.input?;
+        if samples.is_empty() {
+            return None;
+        }
+        let rate = self.output_rate(input_rate);
+        let channels = self.output_channels(input_channels);
+        let frames = (samples.len() / usize::from(channels)) as u64;
+        let duration_us = frames * 1_000_000 / u64::from(rate);
+        let metadata = PacketMetadata {
+            timestamp_us: self.next_timestamp_us,
+            duration_us: Some(duration_us),
+            sequence: Some(self.next_sequence),
+        };
+        self.next_sequence += 1;
+        if let Some(timestamp_us) = self.next_timestamp_us.as_mut() {
+            *timestamp_us += duration_us;
+        }
+        Some(AudioFrame::with_metadata(rate, channels, samples, Some(metadata)))
+    }
+}
+
+/// Sends `packet` on `branch`'s pin, closing the branch if its downstream is gone.
+async fn send(
+    context: &mut NodeContext,
+    stats: &mut NodeStatsTracker,
+    branch: &mut Branch,
+    packet: Packet,
+) {
+    if context.output_sender.send(&branch.pin, packet).await.is_err() {
+        tracing::debug!(pin = %branch.pin, "Splitter output closed");
+        branch.open = false;
+    } else {
+        stats.sent();
+    }
+}
+
+/// A node that duplicates an audio stream to several outputs, converting each to its own
+/// sample rate and channel count.
+pub struct AudioSplitterNode {
+    config: AudioSplitterConfig,
+}
+
+impl AudioSplitterNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioSplitterConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid splitter configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioSplitterNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        self.config
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| OutputPin {
+                name: pin_name(index),
+                produces_type: PacketType::RawAudio(AudioFormat {
+                    sample_rate: output.sample_rate.unwrap_or(0),
+                    channels: output.channels.unwrap_or(0),
+                    sample_format: SampleFormat::F32,
+                }),
+                cardinality: PinCardinality::Broadcast,
+            })
+            .collect()
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut branches: Vec<Branch> = self
+            .config
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(index, output)| Branch::new(index, *output))
+            .collect();
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Packet::Audio(frame) = packet else {
+                        for branch in branches.iter_mut().filter(|b| b.open) {
+                            send(&mut context, &mut stats, branch, packet.clone()).await;
+                        }
+                        if branches.iter().all(|b| !b.open) {
+                            break;
+                        }
+                        continue;
+                    };
+                    if frame.channels == 0 || frame.sample_rate == 0 {
+                        stats.discarded();
+                        continue;
+                    }
+
+                    for branch in branches.iter_mut().filter(|b| b.open) {
+                        let frames = match branch.process(&frame) {
+                            Ok(frames) => frames,
+                            Err(e) => {
+                                stats.errored();
+                                stats.force_send();
+                                state_helpers::emit_failed(&context.state_tx, &node_name, e.to_string());
+                                return Err(e);
+                            }
+                        };
+                        for out in frames {
+                            send(&mut context, &mut stats, branch, Packet::Audio(out)).await;
+                        }
+                    }
+                    if branches.iter().all(|b| !b.open) {
+                        break;
+                    }
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(_) => {
+                        tracing::warn!("audio::splitter outputs can't change while running");
+                        stats.errored();
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        for branch in branches.iter_mut().filter(|b| b.open) {
+            match branch.flush() {
+                Ok(Some(frame)) => {
+                    send(&mut context, &mut stats, branch, Packet::Audio(frame)).await;
+                },
+                Ok(None) => {},
+                Err(e) => {
+                    tracing::warn!(pin = %branch.pin, error = %e, "Failed to flush splitter output");
+                    stats.errored();
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    fn config(params: serde_json::Value) -> AudioSplitterConfig {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn test_validation_and_pins() {
+        assert!(AudioSplitterConfig::default().validate().is_ok());
+        assert!(config(serde_json::json!({"outputs": []})).validate().is_err());
+        assert!(config(serde_json::json!({"outputs": [{"channels": 0}]})).validate().is_err());
+        assert!(config(serde_json::json!({"outputs": [{"sample_rate": 0}]})).validate().is_err());
+
+        let params = serde_json::json!({"outputs": [{}, {"sample_rate": 16000, "channels": 1}]});
+        let node = AudioSplitterNode::factory()(Some(&params)).unwrap();
+        let pins = node.output_pins();
+        assert_eq!(pins.len(), 2);
+        assert_eq!(pins[1].name, "out_1");
+        assert_eq!(
+            pins[1].produces_type,
+            PacketType::RawAudio(AudioFormat {
+                sample_rate: 16000,
+                channels: 1,
+                sample_format: SampleFormat::F32
+            })
+        );
+    }
+
+    #[test]
+    fn test_resampler_keeps_duration() {
+        let mut resampler = ChunkResampler::new(48_000, 16_000, 1).unwrap();
+        let mut output = resampler.push(&vec![0.5; 4_800]).unwrap();
+        output.extend(resampler.push(&vec![0.5; 500]).unwrap());
+        output.extend(resampler.flush().unwrap());
+        // 5300 input frames at a third of the rate, give or take the interpolation delay
+        assert!(output.len().abs_diff(5_300 / 3) <= 4, "got {} frames", output.len());
+    }
+
+    #[tokio::test]
+    async fn test_node_converts_each_output() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let params = serde_json::json!({"outputs": [{}, {"sample_rate": 16000, "channels": 1}]});
+        let node = AudioSplitterNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 100 ms of 48 kHz stereo, left at 0.2 and right at 0.6
+        for _ in 0..5 {
+            let samples = [0.2, 0.6].repeat(960);
+            input_tx.send(Packet::Audio(AudioFrame::new(48_000, 2, samples))).await.unwrap();
+        }
+        input_tx.send(Packet::Text("marker".into())).await.unwrap();
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.collect_packets().await;
+        let frames = |pin: &str| -> Vec<AudioFrame> {
+            packets
+                .iter()
+                .filter(|(_, p, _)| p == pin)
+                .filter_map(|(_, _, packet)| match packet {
+                    Packet::Audio(frame) => Some(frame.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let texts = packets.iter().filter(|(_, _, p)| matches!(p, Packet::Text(_))).count();
+        assert_eq!(texts, 2);
+
+        let passthrough = frames("out_0");
+        assert_eq!(passthrough.len(), 5);
+        assert!(passthrough.iter().all(|f| f.sample_rate == 48_000 && f.channels == 2));
+
+        let speech = frames("out_1");
+        assert!(speech.iter().all(|f| f.sample_rate == 16_000 && f.channels == 1));
+        let samples: Vec<f32> = speech.iter().flat_map(|f| f.samples().to_vec()).collect();
+        assert!(samples.len().abs_diff(1_600) <= 4, "got {} samples", samples.len());
+        assert!(samples[10..samples.len() - 10].iter().all(|s| (s - 0.4).abs() < 1e-4));
+        let sequences: Vec<_> =
+            speech.iter().map(|f| f.metadata.as_ref().unwrap().sequence.unwrap()).collect();
+        assert_eq!(sequences, (0..speech.len() as u64).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,414 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! VU meter node - Pass-through level metering for live UIs
+//!
+//! Audio leaves the node untouched. Along the way it tracks, per channel, the peak since the
+//! last report and an RMS level smoothed over `window_ms`, and reports both as `meter.level`
+//! telemetry events. Reports are throttled on the wall clock like node stats, so an unpaced
+//! oneshot pipeline doesn't flood the telemetry bus; a final report is sent when the input ends.
+//!
+//! With `emit_packets` on, the same readings are also sent as `audio::meter/level@1` Custom
+//! packets on a `levels` pin, for pipelines that route them to a client.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, CustomEncoding, CustomPacketData, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Telemetry event type of a level report.
+pub const METER_EVENT_TYPE: &str = "meter.level";
+
+/// Custom packet type of a level report on the `levels` pin.
+pub const METER_LEVEL_TYPE_ID: &str = "audio::meter/level@1";
+
+/// Level reported for digital silence, in dBFS.
+const FLOOR_DB: f32 = -100.0;
+
+/// Configuration for the AudioVuMeterNode
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(default)]
+pub struct AudioVuMeterConfig {
+    /// Time constant of the RMS level, in milliseconds (300 matches a classic VU meter)
+    #[schemars(range(min = 1.0, max = 10000.0), extend("tunable" = true))]
+    pub window_ms: f32,
+    /// Minimum wall-clock time between two level reports, in milliseconds
+    #[schemars(range(min = 20, max = 60000), extend("tunable" = true))]
+    pub interval_ms: u64,
+    /// Also send each report as an `audio::meter/level@1` Custom packet on the `levels` pin
+    pub emit_packets: bool,
+}
+
+impl Default for AudioVuMeterConfig {
+    fn default() -> Self {
+        Self { window_ms: 300.0, interval_ms: 100, emit_packets: false }
+    }
+}
+
+impl AudioVuMeterConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(1.0..=10_000.0).contains(&self.window_ms) {
+            return Err(format!("window_ms must be within 1-10000, got {}", self.window_ms));
+        }
+        if !(20..=60_000).contains(&self.interval_ms) {
+            return Err(format!("interval_ms must be within 20-60000, got {}", self.interval_ms));
+        }
+        Ok(())
+    }
+}
+
+/// Converts a linear amplitude to dBFS, clamped to [`FLOOR_DB`] and rounded to 0.1 dB.
+fn to_db(amplitude: f32) -> f32 {
+    let db = 20.0 * amplitude.max(f32::MIN_POSITIVE).log10();
+    (db.max(FLOOR_DB) * 10.0).round() / 10.0
+}
+
+/// One level report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MeterLevels {
+    /// Highest sample magnitude per channel since the previous report, in dBFS
+    pub peak_db: Vec<f32>,
+    /// Smoothed RMS level per channel, in dBFS
+    pub rms_db: Vec<f32>,
+    /// Whether any sample reached full scale since the previous report
+    pub clipped: bool,
+    /// Stream position of the last metered sample
+    pub position_ms: u64,
+}
+
+/// Per-channel peak and RMS ballistics for one stream of interleaved audio.
+struct LevelMeter {
+    sample_rate: u32,
+    channels: usize,
+    /// Smoothing coefficient of the mean square, per sample
+    coeff: f32,
+    mean_square: Vec<f32>,
+    peak: Vec<f32>,
+    clipped: bool,
+    frames_seen: u64,
+}
+
+impl LevelMeter {
+    fn new(sample_rate: u32, channels: usize, window_ms: f32) -> Self {
+        let channels = channels.max(1);
+        let mut meter = Self {
+            sample_rate,
+            channels,
+            coeff: 0.0,
+            mean_square: vec![0.0; channels],
+            peak: vec![0.0; channels],
+            clipped: false,
+            frames_seen: 0,
+        };
+        meter.set_window(window_ms);
+        meter
+    }
+
+    /// One-pole smoothing with a time constant of `window_ms`.
+    #[allow(clippy::cast_possible_truncation)] // The coefficient is within 0-1
+    fn set_window(&mut self, window_ms: f32) {
+        let samples = f64::from(window_ms) * f64::from(self.sample_rate) / 1000.0;
+        self.coeff = (1.0 - (-1.0 / samples.max(1.0)).exp()) as f32;
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(self.channels) {
+            for ((&sample, mean_square), peak) in
+                frame.iter().zip(&mut self.mean_square).zip(&mut self.peak)
+            {
+                let magnitude = sample.abs();
+                *peak = peak.max(magnitude);
+                *mean_square += sample.mul_add(sample, -*mean_square) * self.coeff;
+            }
+            self.frames_seen += 1;
+        }
+        self.clipped |= self.peak.iter().any(|&p| p >= 1.0);
+    }
+
+    /// Current levels; starts a new peak-hold period.
+    fn report(&mut self) -> MeterLevels {
+        let levels = MeterLevels {
+            peak_db: self.peak.iter().map(|&p| to_db(p)).collect(),
+            rms_db: self.mean_square.iter().map(|&ms| to_db(ms.sqrt())).collect(),
+            clipped: self.clipped,
+            position_ms: self.frames_seen * 1000 / u64::from(self.sample_rate),
+        };
+        self.peak.fill(0.0);
+        self.clipped = false;
+        levels
+    }
+}
+
+/// A pass-through node that meters raw audio for live level meters.
+///
+/// Reports per-channel peak and RMS levels as `meter.level` telemetry, and optionally as
+/// `audio::meter/level@1` packets on the `levels` pin.
+pub struct AudioVuMeterNode {
+    config: AudioVuMeterConfig,
+}
+
+impl AudioVuMeterNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AudioVuMeterConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid VU meter configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn levels_packet(levels: &MeterLevels) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: METER_LEVEL_TYPE_ID.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!(levels),
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(levels.position_ms * 1000),
+                duration_us: None,
+                sequence: None,
+            }),
+        }))
+    }
+}
+
+/// Sends a report to telemetry and, while enabled, the `levels` pin.
+async fn report(
+    context: &mut NodeContext,
+    telemetry: &streamkit_core::telemetry::TelemetryEmitter,
+    meter: &mut LevelMeter,
+    emit_packets: &mut bool,
+) {
+    let levels = meter.report();
+    telemetry.emit(METER_EVENT_TYPE, serde_json::json!(levels));
+    if *emit_packets
+        && context
+            .output_sender
+            .send("levels", AudioVuMeterNode::levels_packet(&levels))
+            .await
+            .is_err()
+    {
+        // Metering must not stall the audio path, so an unconnected or closed pin just
+        // turns the packets off.
+        tracing::debug!("Levels pin is not connected, no longer sending level packets");
+        *emit_packets = false;
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AudioVuMeterNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                sample_rate: 0, // Wildcard
+                channels: 0,    // Wildcard
+                sample_format: SampleFormat::F32,
+            })],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        let mut pins = vec![OutputPin {
+            name: "out".to_string(),
+            // It outputs the same format it receives.
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: 0,
+                channels: 0,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }];
+        if self.config.emit_packets {
+            pins.push(OutputPin {
+                name: "levels".to_string(),
+                produces_type: PacketType::Custom { type_id: METER_LEVEL_TYPE_ID.to_string() },
+                cardinality: PinCardinality::Broadcast,
+            });
+        }
+        pins
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let telemetry = context.telemetry_emitter();
+        let mut config = self.config;
+        let mut emit_packets = config.emit_packets;
+        let mut meter: Option<LevelMeter> = None;
+        let mut last_report = Instant::now();
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    if let Packet::Audio(ref frame) = packet {
+                        if frame.sample_rate > 0 && frame.channels > 0 {
+                            let channels = usize::from(frame.channels);
+                            let meter = match &mut meter {
+                                Some(m) if m.sample_rate == frame.sample_rate && m.channels == channels => m,
+                                slot => slot.insert(LevelMeter::new(frame.sample_rate, channels, config.window_ms)),
+                            };
+                            meter.push(frame.samples());
+                            if last_report.elapsed() >= Duration::from_millis(config.interval_ms) {
+                                last_report = Instant::now();
+                                report(&mut context, &telemetry, meter, &mut emit_packets).await;
+                            }
+                        }
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        match merge_params(&config, &params).and_then(|updated: AudioVuMeterConfig| {
+                            updated.validate()?;
+                            if updated.emit_packets != config.emit_packets {
+                                return Err("emit_packets can't be changed while running".to_string());
+                            }
+                            Ok(updated)
+                        }) {
+                            Ok(updated) => {
+                                if let Some(meter) = &mut meter {
+                                    meter.set_window(updated.window_ms);
+                                }
+                                config = updated;
+                            }
+                            Err(e) => {
+                                tracing::warn!("Rejected VU meter update: {}", e);
+                                stats.errored();
+                            }
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        // Report the tail, so even input shorter than one interval is metered.
+        if let Some(meter) = &mut meter {
+            report(&mut context, &telemetry, meter, &mut emit_packets).await;
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::cast_precision_loss, clippy::float_cmp)] // Levels are rounded to 0.1 dB
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+        extract_audio_data,
+    };
+    use std::collections::HashMap;
+    use std::f32::consts::TAU;
+    use streamkit_core::types::AudioFrame;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_sine_levels_per_channel() {
+        // Left: full-scale-ish 1 kHz sine at -6 dBFS peak, right: silence
+        let rate = 48_000;
+        let samples: Vec<f32> = (0..rate)
+            .flat_map(|n| [0.5 * (TAU * 1000.0 * n as f32 / rate as f32).sin(), 0.0])
+            .collect();
+        let mut meter = LevelMeter::new(rate, 2, 100.0);
+        meter.push(&samples);
+        let levels = meter.report();
+
+        assert!((levels.peak_db[0] + 6.0).abs() < 0.1, "{levels:?}");
+        // A sine's RMS sits 3 dB below its peak
+        assert!((levels.rms_db[0] + 9.0).abs() < 0.2, "{levels:?}");
+        assert_eq!(levels.peak_db[1], FLOOR_DB);
+        assert_eq!(levels.rms_db[1], FLOOR_DB);
+        assert!(!levels.clipped);
+        assert_eq!(levels.position_ms, 1000);
+    }
+
+    #[test]
+    fn test_peak_is_held_until_reported() {
+        let mut meter = LevelMeter::new(1000, 1, 300.0);
+        meter.push(&[0.1, 1.0, 0.1]);
+        let first = meter.report();
+        assert_eq!(first.peak_db, vec![0.0]);
+        assert!(first.clipped);
+
+        meter.push(&[0.1; 10]);
+        let second = meter.report();
+        assert_eq!(second.peak_db, vec![-20.0]);
+        assert!(!second.clipped);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AudioVuMeterConfig::default().validate().is_ok());
+        let factory = AudioVuMeterNode::factory();
+        assert!(factory(Some(&serde_json::json!({"interval_ms": 5}))).is_err());
+        assert!(factory(Some(&serde_json::json!({"window_ms": 0.0}))).is_err());
+
+        let pins = factory(Some(&serde_json::json!({"emit_packets": true}))).unwrap().output_pins();
+        assert_eq!(pins.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["out", "levels"]);
+    }
+
+    #[tokio::test]
+    async fn test_node_passes_audio_and_reports_levels() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let params = serde_json::json!({"emit_packets": true});
+        let node = AudioVuMeterNode::factory()(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let frame = AudioFrame::new(1000, 1, vec![0.25; 20]);
+        input_tx.send(Packet::Audio(frame)).await.unwrap();
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.collect_packets().await;
+        let on_pin = |name: &str| -> Vec<&Packet> {
+            packets.iter().filter(|(_, pin, _)| pin == name).map(|(_, _, p)| p).collect()
+        };
+        let audio = on_pin("out");
+        assert_eq!(extract_audio_data(audio[0]).unwrap().to_vec(), vec![0.25; 20]);
+
+        // Input shorter than the interval still gets the final report
+        let levels = on_pin("levels");
+        assert_eq!(levels.len(), 1);
+        let Packet::Custom(custom) = levels[0] else { panic!("expected a Custom packet") };
+        assert_eq!(custom.type_id, METER_LEVEL_TYPE_ID);
+        assert_eq!(custom.data["peak_db"], serde_json::json!([-12.0]));
+        assert_eq!(custom.data["position_ms"], 20);
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! DTMF generator node - Renders digit strings as dual-tone telephone signalling
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Type id of the digit events emitted by `audio::analysis::dtmf_detect`, also accepted here.
+pub const DTMF_DIGIT_TYPE_ID: &str = "audio::dtmf/digit@1";
+
+/// Low-group (row) frequencies in Hz.
+pub const ROW_FREQS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+/// High-group (column) frequencies in Hz.
+pub const COL_FREQS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+/// Keypad layout indexed by `[row][column]`.
+pub const KEYPAD: [[char; 4]; 4] =
+    [['1', '2', '3', 'A'], ['4', '5', '6', 'B'], ['7', '8', '9', 'C'], ['*', '0', '#', 'D']];
+
+/// Returns the (row, column) frequencies for a DTMF digit (`0-9`, `*`, `#`, `A-D`).
+pub fn tone_frequencies(digit: char) -> Option<(f32, f32)> {
+    let digit = digit.to_ascii_uppercase();
+    KEYPAD.iter().enumerate().find_map(|(row, keys)| {
+        keys.iter().position(|&k| k == digit).map(|col| (ROW_FREQS[row], COL_FREQS[col]))
+    })
+}
+
+/// Configuration for the DtmfGeneratorNode
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DtmfGeneratorConfig {
+    /// Output sample rate in Hz
+    #[schemars(range(min = 8000))]
+    pub sample_rate: u32,
+    /// Output channel count (the tone is identical on every channel)
+    #[schemars(range(min = 1))]
+    pub channels: u16,
+    /// Duration of each tone in milliseconds
+    #[schemars(range(min = 1))]
+    pub tone_ms: u32,
+    /// Silence after each tone in milliseconds
+    pub gap_ms: u32,
+    /// Silence inserted for a `,` in the digit string, in milliseconds
+    pub pause_ms: u32,
+    /// Level of each of the two tones in dBFS
+    #[schemars(range(max = 0.0))]
+    pub level_db: f32,
+    /// Output frame duration in milliseconds
+    #[schemars(range(min = 1))]
+    pub frame_ms: u32,
+}
+
+impl Default for DtmfGeneratorConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 48000,
+            channels: 1,
+            tone_ms: 100,
+            gap_ms: 60,
+            pause_ms: 500,
+            level_db: -10.0,
+            frame_ms: 20,
+        }
+    }
+}
+
+impl DtmfGeneratorConfig {
+    fn samples_for_ms(&self, ms: u32) -> usize {
+        (u64::from(self.sample_rate) * u64::from(ms) / 1000) as usize
+    }
+
+    /// Renders a digit string as mono samples. Characters other than DTMF digits and `,`
+    /// are skipped and returned.
+    // Sample rates and tone lengths are far below f32's exact integer range.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn synthesize(&self, digits: &str) -> (Vec<f32>, Vec<char>) {
+        // Short ramps at tone edges avoid audible clicks.
+        const RAMP_MS: u32 = 2;
+
+        let amplitude = 10f32.powf(self.level_db / 20.0);
+        let tone_len = self.samples_for_ms(self.tone_ms);
+        let ramp_len = self.samples_for_ms(RAMP_MS).min(tone_len / 2).max(1);
+        let rate = self.sample_rate as f32;
+
+        let mut samples = Vec::new();
+        let mut skipped = Vec::new();
+        for c in digits.chars() {
+            if c == ',' {
+                samples.resize(samples.len() + self.samples_for_ms(self.pause_ms), 0.0);
+                continue;
+            }
+            let Some((low, high)) = tone_frequencies(c) else {
+                if !c.is_whitespace() {
+                    skipped.push(c);
+                }
+                continue;
+            };
+            samples.extend((0..tone_len).map(|n| {
+                let t = n as f32 / rate;
+                let edge = n.min(tone_len - 1 - n);
+                let envelope = if edge < ramp_len { edge as f32 / ramp_len as f32 } else { 1.0 };
+                amplitude * envelope * ((TAU * low * t).sin() + (TAU * high * t).sin())
+            }));
+            samples.resize(samples.len() + self.samples_for_ms(self.gap_ms), 0.0);
+        }
+        (samples, skipped)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.sample_rate < 8000 {
+            return Err(format!("sample_rate must be at least 8000, got {}", self.sample_rate));
+        }
+        if self.channels == 0 || self.tone_ms == 0 || self.frame_ms == 0 {
+            return Err("channels, tone_ms and frame_ms must be greater than 0".to_string());
+        }
+        if !self.level_db.is_finite() || self.level_db > 0.0 {
+            return Err(format!("level_db must be at most 0, got {}", self.level_db));
+        }
+        Ok(())
+    }
+}
+
+/// A node that turns digit strings into DTMF tones.
+///
+/// Accepts Text packets (e.g. `"123#"`, with `,` for a pause) and `audio::dtmf/digit@1`
+/// events from `audio::analysis::dtmf_detect`, so detected digits can be regenerated.
+/// Audio is only emitted while there are digits to play; the last frame is padded with silence.
+pub struct DtmfGeneratorNode {
+    config: DtmfGeneratorConfig,
+}
+
+impl DtmfGeneratorNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: DtmfGeneratorConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid DTMF generator configuration: {e}"))
+            })?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn digits_from_packet(packet: &Packet) -> Option<String> {
+        match packet {
+            Packet::Text(text) => Some(text.to_string()),
+            Packet::Custom(custom) if custom.type_id == DTMF_DIGIT_TYPE_ID => {
+                custom.data.get("digit").and_then(serde_json::Value::as_str).map(String::from)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for DtmfGeneratorNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![
+                PacketType::Text,
+                PacketType::Custom { type_id: DTMF_DIGIT_TYPE_ID.to_string() },
+            ],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: self.config.sample_rate,
+                channels: self.config.channels,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let channels = usize::from(self.config.channels);
+        let frame_len = self.config.samples_for_ms(self.config.frame_ms).max(1);
+        let frame_duration_us = u64::from(self.config.frame_ms) * 1000;
+        let mut sequence = 0u64;
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    let Some(digits) = Self::digits_from_packet(&packet) else {
+                        stats.discarded();
+                        continue;
+                    };
+
+                    let (mono, skipped) = self.config.synthesize(&digits);
+                    if !skipped.is_empty() {
+                        tracing::warn!(?skipped, "DtmfGeneratorNode skipped non-DTMF characters");
+                    }
+                    for chunk in mono.chunks(frame_len) {
+                        let mut samples = Vec::with_capacity(frame_len * channels);
+                        for &s in chunk {
+                            samples.extend(std::iter::repeat_n(s, channels));
+                        }
+                        samples.resize(frame_len * channels, 0.0);
+
+                        let metadata = PacketMetadata {
+                            timestamp_us: Some(sequence * frame_duration_us),
+                            duration_us: Some(frame_duration_us),
+                            sequence: Some(sequence),
+                        };
+                        sequence += 1;
+                        let frame = AudioFrame::with_metadata(
+                            self.config.sample_rate,
+                            self.config.channels,
+                            samples,
+                            Some(metadata),
+                        );
+                        if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                            return Ok(());
+                        }
+                        stats.sent();
+                    }
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_frequencies() {
+        assert_eq!(tone_frequencies('1'), Some((697.0, 1209.0)));
+        assert_eq!(tone_frequencies('#'), Some((941.0, 1477.0)));
+        assert_eq!(tone_frequencies('d'), Some((941.0, 1633.0)));
+        assert_eq!(tone_frequencies('x'), None);
+    }
+
+    #[test]
+    fn test_synthesize_layout() {
+        let config = DtmfGeneratorConfig { sample_rate: 8000, ..Default::default() };
+        let (samples, skipped) = config.synthesize("1, 2x");
+        // Two tones with their gaps plus one pause; the space is ignored
+        assert_eq!(samples.len(), 2 * (800 + 480) + 4000);
+        assert_eq!(skipped, vec!['x']);
+        assert!(samples.iter().all(|s| s.abs() <= 2.0 * 10f32.powf(-0.5)));
+        assert!(samples[800..1280].iter().all(|&s| s == 0.0));
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio generator nodes, which synthesize audio from control input rather than transform it.
+
+use streamkit_core::NodeRegistry;
+
+#[cfg(feature = "dtmf")]
+pub mod dtmf;
+#[cfg(feature = "audio_signal")]
+pub mod signal;
+
+/// Registers all available audio generator nodes with the engine's registry.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+#[cfg_attr(
+    not(any(feature = "dtmf", feature = "audio_signal")),
+    allow(unused_variables, clippy::missing_const_for_fn)
+)]
+pub fn register_audio_generators(registry: &mut NodeRegistry) {
+    #[cfg(feature = "dtmf")]
+    {
+        use dtmf::{DtmfGeneratorConfig, DtmfGeneratorNode};
+        use schemars::schema_for;
+        let factory = DtmfGeneratorNode::factory();
+        registry.register_dynamic_with_description(
+            "audio::generators::dtmf",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(DtmfGeneratorConfig))
+                .expect("DtmfGeneratorConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "generators".to_string()],
+            false,
+            "Renders digit strings as DTMF telephone tones. Accepts Text packets such as \
+             \"123#\" (with ',' for a pause) or digit events from audio::analysis::dtmf_detect, \
+             for IVR prompts and signalling to telephony systems.",
+        );
+    }
+
+    #[cfg(feature = "audio_signal")]
+    {
+        use schemars::schema_for;
+        use signal::{SignalSourceNode, SilenceConfig, ToneConfig};
+        let factory = SignalSourceNode::silence_factory();
+        registry.register_dynamic_with_description(
+            "audio::silence",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(SilenceConfig))
+                .expect("SilenceConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "generators".to_string()],
+            false,
+            "Emits silent F32 audio frames, paced in real time or as fast as possible, for a \
+             set duration or until stopped. Use it as a test source or to keep a mixer input \
+             fed while the real one is idle.",
+        );
+
+        let factory = SignalSourceNode::tone_factory();
+        registry.register_dynamic_with_description(
+            "audio::tone",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(ToneConfig))
+                .expect("ToneConfig schema should serialize to JSON"),
+            vec!["audio".to_string(), "generators".to_string()],
+            false,
+            "Emits a sine tone as F32 audio frames, paced in real time or as fast as possible, \
+             for a set duration or until stopped. Frequency and level can be tuned while \
+             running. Useful as a test signal for pipelines and devices.",
+        );
+    }
+}
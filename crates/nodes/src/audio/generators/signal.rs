@@ -0,0 +1,492 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Signal source nodes - Silence and sine tones with no input
+//!
+//! `audio::silence` and `audio::tone` emit F32 frames of a fixed format, either for a set
+//! duration or until the pipeline stops. They stand in for real sources when testing a
+//! pipeline, and keep a mixer input fed when the real one goes idle.
+//!
+//! With `realtime` on (the default) frames are paced to the wall clock, as a capture device
+//! would deliver them; with it off they are emitted as fast as downstream accepts them, which
+//! suits oneshot pipelines that render a fixed `duration_ms`.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{
+    AudioFormat, AudioFrame, Packet, PacketMetadata, PacketType, SampleFormat,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Configuration for `audio::silence`
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct SilenceConfig {
+    /// Output sample rate in Hz
+    #[schemars(range(min = 8000))]
+    pub sample_rate: u32,
+    /// Output channel count
+    #[schemars(range(min = 1))]
+    pub channels: u16,
+    /// Output frame duration in milliseconds
+    #[schemars(range(min = 1))]
+    pub frame_ms: u32,
+    /// Total duration in milliseconds; runs until the pipeline stops when unset
+    pub duration_ms: Option<u64>,
+    /// Pace frames to the wall clock instead of emitting them as fast as possible
+    pub realtime: bool,
+}
+
+impl Default for SilenceConfig {
+    fn default() -> Self {
+        Self { sample_rate: 48000, channels: 1, frame_ms: 20, duration_ms: None, realtime: true }
+    }
+}
+
+/// Configuration for `audio::tone`
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct ToneConfig {
+    /// Tone frequency in Hz, below half the sample rate
+    #[schemars(range(min = 1.0), extend("tunable" = true))]
+    pub frequency_hz: f32,
+    /// Tone level in dBFS
+    #[schemars(range(max = 0.0), extend("tunable" = true))]
+    pub level_db: f32,
+    /// Output sample rate in Hz
+    #[schemars(range(min = 8000))]
+    pub sample_rate: u32,
+    /// Output channel count (the tone is identical on every channel)
+    #[schemars(range(min = 1))]
+    pub channels: u16,
+    /// Output frame duration in milliseconds
+    #[schemars(range(min = 1))]
+    pub frame_ms: u32,
+    /// Total duration in milliseconds; runs until the pipeline stops when unset
+    pub duration_ms: Option<u64>,
+    /// Pace frames to the wall clock instead of emitting them as fast as possible
+    pub realtime: bool,
+}
+
+impl Default for ToneConfig {
+    fn default() -> Self {
+        Self {
+            frequency_hz: 440.0,
+            level_db: -20.0,
+            sample_rate: 48000,
+            channels: 1,
+            frame_ms: 20,
+            duration_ms: None,
+            realtime: true,
+        }
+    }
+}
+
+impl ToneConfig {
+    fn validate(&self) -> Result<(), String> {
+        validate_format(self.sample_rate, self.channels, self.frame_ms)?;
+        // Compare in f64: the Nyquist frequency of any u32 rate is exact there.
+        let nyquist = f64::from(self.sample_rate) / 2.0;
+        if !self.frequency_hz.is_finite()
+            || self.frequency_hz <= 0.0
+            || f64::from(self.frequency_hz) >= nyquist
+        {
+            return Err(format!(
+                "frequency_hz must be above 0 and below {nyquist}, got {}",
+                self.frequency_hz
+            ));
+        }
+        if !self.level_db.is_finite() || self.level_db > 0.0 {
+            return Err(format!("level_db must be at most 0, got {}", self.level_db));
+        }
+        Ok(())
+    }
+}
+
+fn validate_format(sample_rate: u32, channels: u16, frame_ms: u32) -> Result<(), String> {
+    if sample_rate < 8000 {
+        return Err(format!("sample_rate must be at least 8000, got {sample_rate}"));
+    }
+    if channels == 0 || frame_ms == 0 {
+        return Err("channels and frame_ms must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Output format and length shared by both signals.
+#[derive(Debug, Clone, Copy)]
+struct Timing {
+    sample_rate: u32,
+    channels: u16,
+    frame_ms: u32,
+    duration_ms: Option<u64>,
+    realtime: bool,
+}
+
+impl Timing {
+    /// Sample frames in the first `ms` milliseconds; rounding down per boundary keeps
+    /// frame lengths from drifting at rates that don't divide evenly (e.g. 22.05 kHz, 1 ms).
+    fn frames_until_ms(&self, ms: u64) -> u64 {
+        ms * u64::from(self.sample_rate) / 1000
+    }
+
+    /// Sample frames in total, if the duration is bounded.
+    fn total_frames(&self) -> Option<u64> {
+        self.duration_ms.map(|ms| self.frames_until_ms(ms))
+    }
+
+    /// Start position and length of frame `index`, or `None` once the duration is reached.
+    #[allow(clippy::cast_possible_truncation)] // One frame is at most a few seconds of audio
+    fn frame_span(&self, index: u64) -> Option<(u64, usize)> {
+        let start = self.frames_until_ms(index * u64::from(self.frame_ms));
+        let mut end = self.frames_until_ms((index + 1) * u64::from(self.frame_ms));
+        if let Some(total) = self.total_frames() {
+            end = end.min(total);
+        }
+        (end > start).then(|| (start, (end - start) as usize))
+    }
+
+    fn frames_to_us(&self, frames: u64) -> u64 {
+        frames * 1_000_000 / u64::from(self.sample_rate)
+    }
+}
+
+/// Phase-continuous sine oscillator; frequency and level changes take effect without clicks.
+#[derive(Debug)]
+struct Oscillator {
+    sample_rate: f64,
+    /// Position within the current cycle, in cycles
+    phase: f64,
+    frequency_hz: f64,
+    /// Target amplitude
+    amplitude: f32,
+    /// Amplitude at the end of the last rendered block, ramped to `amplitude` in the next
+    rendered_amplitude: f32,
+}
+
+impl Oscillator {
+    fn new(config: &ToneConfig) -> Self {
+        let mut osc = Self {
+            sample_rate: f64::from(config.sample_rate),
+            phase: 0.0,
+            frequency_hz: 0.0,
+            amplitude: 0.0,
+            rendered_amplitude: 0.0,
+        };
+        osc.set_config(config);
+        osc.rendered_amplitude = osc.amplitude;
+        osc
+    }
+
+    fn set_config(&mut self, config: &ToneConfig) {
+        self.frequency_hz = f64::from(config.frequency_hz);
+        self.amplitude = 10f32.powf(config.level_db / 20.0);
+    }
+
+    /// Fills `out` (interleaved, `channels` wide), ramping a level change over the block.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)] // Audio precision
+    fn render(&mut self, out: &mut [f32], channels: usize) {
+        let step = self.frequency_hz / self.sample_rate;
+        let frames = out.len() / channels;
+        let from = self.rendered_amplitude;
+        let ramp = (self.amplitude - from) / frames.max(1) as f32;
+        for (n, frame) in out.chunks_exact_mut(channels).enumerate() {
+            let amplitude = from + ramp * (n + 1) as f32;
+            let sample = amplitude * (std::f64::consts::TAU * self.phase).sin() as f32;
+            frame.fill(sample);
+            self.phase = (self.phase + step).fract();
+        }
+        self.rendered_amplitude = self.amplitude;
+    }
+}
+
+enum Signal {
+    Silence,
+    Tone(ToneConfig),
+}
+
+/// A source node emitting silence or a sine tone.
+pub struct SignalSourceNode {
+    timing: Timing,
+    signal: Signal,
+}
+
+impl SignalSourceNode {
+    pub fn silence_factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: SilenceConfig = config_helpers::parse_config_optional(params)?;
+            validate_format(config.sample_rate, config.channels, config.frame_ms).map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid silence configuration: {e}"))
+            })?;
+            Ok(Box::new(Self {
+                timing: Timing {
+                    sample_rate: config.sample_rate,
+                    channels: config.channels,
+                    frame_ms: config.frame_ms,
+                    duration_ms: config.duration_ms,
+                    realtime: config.realtime,
+                },
+                signal: Signal::Silence,
+            }))
+        })
+    }
+
+    pub fn tone_factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: ToneConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid tone configuration: {e}"))
+            })?;
+            Ok(Box::new(Self {
+                timing: Timing {
+                    sample_rate: config.sample_rate,
+                    channels: config.channels,
+                    frame_ms: config.frame_ms,
+                    duration_ms: config.duration_ms,
+                    realtime: config.realtime,
+                },
+                signal: Signal::Tone(config),
+            }))
+        })
+    }
+
+    /// Applies a tunable update to the tone; format and timing fields can't change.
+    fn apply_update(&mut self, osc: Option<&mut Oscillator>, params: &serde_json::Value) {
+        let Signal::Tone(config) = &mut self.signal else {
+            tracing::debug!("audio::silence has no tunable params, ignoring update");
+            return;
+        };
+        let updated = merge_params(config, params).and_then(|updated: ToneConfig| {
+            updated.validate()?;
+            if updated.sample_rate != config.sample_rate
+                || updated.channels != config.channels
+                || updated.frame_ms != config.frame_ms
+                || updated.duration_ms != config.duration_ms
+                || updated.realtime != config.realtime
+            {
+                return Err("only frequency_hz and level_db can change at runtime".to_string());
+            }
+            Ok(updated)
+        });
+        match updated {
+            Ok(updated) => {
+                if let Some(osc) = osc {
+                    osc.set_config(&updated);
+                }
+                *config = updated;
+            },
+            Err(e) => tracing::warn!("Rejected tone update: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for SignalSourceNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::RawAudio(AudioFormat {
+                sample_rate: self.timing.sample_rate,
+                channels: self.timing.channels,
+                sample_format: SampleFormat::F32,
+            }),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(mut self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes wait for the pipeline's Start signal before emitting
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::UpdateParams(params)) => self.apply_update(None, &params),
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::Shutdown) | None => return Ok(()),
+            }
+        }
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let timing = self.timing;
+        let channels = usize::from(timing.channels);
+        let mut osc = match &self.signal {
+            Signal::Tone(config) => Some(Oscillator::new(config)),
+            Signal::Silence => None,
+        };
+
+        // Burst on missed ticks so a stalled consumer doesn't make the stream fall behind
+        // the wall clock for good.
+        let period = Duration::from_millis(u64::from(timing.frame_ms));
+        let mut ticker = tokio::time::interval_at(Instant::now(), period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+        let mut index = 0u64;
+        let reason = loop {
+            let Some((start, len)) = timing.frame_span(index) else { break "completed" };
+            if let Some(token) = &context.cancellation_token {
+                if token.is_cancelled() {
+                    break "cancelled";
+                }
+            }
+
+            if timing.realtime {
+                // Oneshot pipelines drop the control channel once started; keep ticking then.
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    Some(msg) = context.control_rx.recv() => {
+                        match msg {
+                            NodeControlMessage::UpdateParams(params) => {
+                                self.apply_update(osc.as_mut(), &params);
+                            }
+                            NodeControlMessage::Query { kind, reply, .. } => {
+                                reject_query(&kind, reply);
+                            }
+                            NodeControlMessage::Start => {}
+                            NodeControlMessage::Shutdown => break "shutdown",
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                // Unpaced: only look at control messages between frames.
+                match context.control_rx.try_recv() {
+                    Ok(NodeControlMessage::UpdateParams(params)) => {
+                        self.apply_update(osc.as_mut(), &params);
+                    },
+                    Ok(NodeControlMessage::Query { kind, reply, .. }) => {
+                        reject_query(&kind, reply);
+                    },
+                    Ok(NodeControlMessage::Start)
+                    | Err(TryRecvError::Empty | TryRecvError::Disconnected) => {},
+                    Ok(NodeControlMessage::Shutdown) => break "shutdown",
+                }
+            }
+
+            let mut samples = vec![0.0; len * channels];
+            if let Some(osc) = &mut osc {
+                osc.render(&mut samples, channels);
+            }
+            let timestamp_us = timing.frames_to_us(start);
+            let metadata = PacketMetadata {
+                timestamp_us: Some(timestamp_us),
+                duration_us: Some(timing.frames_to_us(start + len as u64) - timestamp_us),
+                sequence: Some(index),
+            };
+            let frame = AudioFrame::with_metadata(
+                timing.sample_rate,
+                timing.channels,
+                samples,
+                Some(metadata),
+            );
+            if context.output_sender.send("out", Packet::Audio(frame)).await.is_err() {
+                tracing::debug!("Output channel closed, stopping node");
+                break "output_closed";
+            }
+            stats.sent();
+            stats.maybe_send();
+            index += 1;
+        };
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::float_cmp)] // Both channels carry the same samples
+mod tests {
+    use super::*;
+
+    fn timing(sample_rate: u32, frame_ms: u32, duration_ms: Option<u64>) -> Timing {
+        Timing { sample_rate, channels: 1, frame_ms, duration_ms, realtime: false }
+    }
+
+    #[test]
+    fn test_frame_spans_cover_duration_exactly() {
+        // 22.05 kHz doesn't divide into 1 ms frames; the spans must still tile the stream.
+        let t = timing(22050, 1, Some(105));
+        let spans: Vec<_> = (0..).map_while(|i| t.frame_span(i)).collect();
+        assert_eq!(spans.len(), 105);
+        assert_eq!(spans.iter().map(|&(_, len)| len as u64).sum::<u64>(), 2315);
+        assert!(spans.windows(2).all(|w| w[0].0 + w[0].1 as u64 == w[1].0));
+
+        // A duration that isn't a whole number of frames ends with a short frame
+        let t = timing(48000, 20, Some(50));
+        assert_eq!(t.frame_span(2), Some((1920, 480)));
+        assert_eq!(t.frame_span(3), None);
+        assert_eq!(timing(48000, 20, None).frame_span(1_000_000), Some((960_000_000, 960)));
+    }
+
+    #[test]
+    fn test_tone_is_phase_continuous_across_frames() {
+        let config = ToneConfig { frequency_hz: 1000.0, level_db: 0.0, ..Default::default() };
+        let mut osc = Oscillator::new(&config);
+        let mut block = vec![0.0; 96];
+        osc.render(&mut block[..48], 2);
+        osc.render(&mut block[48..], 2);
+        // 1 kHz at 48 kHz: one full cycle over the 48 stereo frames, channels identical
+        assert!(block.chunks_exact(2).all(|f| f[0] == f[1]));
+        assert!(block[0].abs() < 1e-6);
+        assert!((block[24] - 1.0).abs() < 1e-3, "quarter cycle should peak, got {}", block[24]);
+        assert!(block.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_tone_validation() {
+        assert!(ToneConfig::default().validate().is_ok());
+        let above_nyquist =
+            ToneConfig { frequency_hz: 8000.0, sample_rate: 16000, ..Default::default() };
+        assert!(above_nyquist.validate().is_err());
+        assert!(ToneConfig { level_db: 3.0, ..Default::default() }.validate().is_err());
+        assert!(ToneConfig { channels: 0, ..Default::default() }.validate().is_err());
+
+        let merged: ToneConfig =
+            merge_params(&ToneConfig::default(), &serde_json::json!({"frequency_hz": 1000.0}))
+                .unwrap();
+        assert!((merged.frequency_hz - 1000.0).abs() < f32::EPSILON);
+        assert_eq!(merged.sample_rate, 48000);
+    }
+
+    #[tokio::test]
+    async fn test_unpaced_silence_renders_duration() {
+        use crate::test_utils::{create_test_context, extract_audio_data};
+        use std::collections::HashMap;
+
+        let (mut context, mock_sender, _state_rx) = create_test_context(HashMap::new(), 10);
+        let (control_tx, control_rx) = tokio::sync::mpsc::channel(10);
+        context.control_rx = control_rx;
+        let node = (SignalSourceNode::silence_factory())(Some(&serde_json::json!({
+            "channels": 2, "duration_ms": 50, "realtime": false,
+        })))
+        .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        control_tx.send(NodeControlMessage::Start).await.unwrap();
+        handle.await.unwrap().unwrap();
+
+        let packets = mock_sender.get_packets_for_pin("out").await;
+        let lens: Vec<usize> =
+            packets.iter().map(|p| extract_audio_data(p).unwrap().len()).collect();
+        assert_eq!(lens, vec![1920, 1920, 960]);
+        assert!(packets.iter().all(|p| extract_audio_data(p).unwrap().iter().all(|&s| s == 0.0)));
+    }
+}
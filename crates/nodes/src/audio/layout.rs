@@ -0,0 +1,255 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Speaker layouts implied by a frame's channel count, and remixing between them.
+//!
+//! Audio frames only carry a channel count, so up to eight channels are taken to be the
+//! common layout of that size in WAVE/SMPTE order (FL, FR, FC, LFE, BL, BR, SL, SR), which is
+//! what the WAV, FLAC, Vorbis and AAC decoders produce. Larger counts have no known layout
+//! and are remixed by mapping channels cyclically.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+/// Position of a loudspeaker relative to the listener.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speaker {
+    /// Horizontal angle in degrees clockwise from straight ahead (90 is hard right, -90 hard left)
+    pub azimuth: f32,
+    /// Vertical angle in degrees above the horizontal plane
+    pub elevation: f32,
+    /// Low-frequency effects channel, which has no meaningful position
+    pub lfe: bool,
+}
+
+const fn speaker(azimuth: f32) -> Speaker {
+    Speaker { azimuth, elevation: 0.0, lfe: false }
+}
+
+const C: Speaker = speaker(0.0);
+const FL: Speaker = speaker(-30.0);
+const FR: Speaker = speaker(30.0);
+const LFE: Speaker = Speaker { azimuth: 0.0, elevation: 0.0, lfe: true };
+const SL: Speaker = speaker(-90.0);
+const SR: Speaker = speaker(90.0);
+const BC: Speaker = speaker(180.0);
+
+const QUAD: [Speaker; 4] = [FL, FR, speaker(-135.0), speaker(135.0)];
+const SURROUND_5_0: [Speaker; 5] = [FL, FR, C, speaker(-110.0), speaker(110.0)];
+const SURROUND_5_1: [Speaker; 6] = [FL, FR, C, LFE, speaker(-110.0), speaker(110.0)];
+const SURROUND_6_1: [Speaker; 7] = [FL, FR, C, LFE, BC, SL, SR];
+const SURROUND_7_1: [Speaker; 8] = [FL, FR, C, LFE, speaker(-150.0), speaker(150.0), SL, SR];
+
+/// The speakers of the standard layout with `channels` channels, in channel order.
+pub const fn speakers(channels: u16) -> Option<&'static [Speaker]> {
+    Some(match channels {
+        1 => &[C],
+        2 => &[FL, FR],
+        3 => &[FL, FR, C],
+        4 => &QUAD,
+        5 => &SURROUND_5_0,
+        6 => &SURROUND_5_1,
+        7 => &SURROUND_6_1,
+        8 => &SURROUND_7_1,
+        _ => return None,
+    })
+}
+
+/// Short name of the layout with `channels` channels, for logs and errors.
+pub const fn layout_name(channels: u16) -> &'static str {
+    match channels {
+        1 => "mono",
+        2 => "stereo",
+        3 => "3.0",
+        4 => "quad",
+        5 => "5.0",
+        6 => "5.1",
+        7 => "6.1",
+        8 => "7.1",
+        _ => "unknown",
+    }
+}
+
+/// Difference between two azimuths, in degrees (0-180).
+fn angle_between(a: f32, b: f32) -> f32 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+/// A mixing matrix converting interleaved audio from one channel count to another.
+///
+/// - Mono is duplicated to both front speakers (or sent to the centre when there is one)
+/// - Multichannel down to stereo follows ITU-R BS.775: centre and surrounds at -3 dB, no LFE
+/// - Anything down to mono averages the non-LFE channels
+/// - Other known layouts send each speaker to the same position, or else the nearest speaker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Remix {
+    from: usize,
+    to: usize,
+    /// `to` rows of `from` coefficients
+    matrix: Vec<f32>,
+}
+
+impl Remix {
+    #[allow(clippy::cast_precision_loss)] // Channel counts are tiny
+    pub fn new(from: u16, to: u16) -> Self {
+        let (from_len, to_len) = (usize::from(from.max(1)), usize::from(to.max(1)));
+        let mut matrix = vec![0.0; from_len * to_len];
+        let mut set = |out: usize, input: usize, gain: f32| matrix[out * from_len + input] = gain;
+
+        match (speakers(from), speakers(to)) {
+            _ if from_len == to_len => (0..from_len).for_each(|ch| set(ch, ch, 1.0)),
+            (Some(source), Some(_)) if to_len == 1 => {
+                let gain = 1.0 / source.iter().filter(|s| !s.lfe).count() as f32;
+                for (input, _) in source.iter().enumerate().filter(|(_, s)| !s.lfe) {
+                    set(0, input, gain);
+                }
+            },
+            (Some(_), Some(target)) if from_len == 1 => {
+                if let Some(centre) = target.iter().position(|s| *s == C) {
+                    set(centre, 0, 1.0);
+                } else {
+                    set(0, 0, 1.0);
+                    set(1, 0, 1.0);
+                }
+            },
+            (Some(source), Some(_)) if to_len == 2 => {
+                for (input, s) in source.iter().enumerate().filter(|(_, s)| !s.lfe) {
+                    let front = s.azimuth.abs() <= 60.0;
+                    let gain = if front { 1.0 } else { FRAC_1_SQRT_2 };
+                    if angle_between(s.azimuth, 0.0).rem_euclid(180.0) < f32::EPSILON {
+                        set(0, input, FRAC_1_SQRT_2 * gain);
+                        set(1, input, FRAC_1_SQRT_2 * gain);
+                    } else {
+                        set(usize::from(s.azimuth > 0.0), input, gain);
+                    }
+                }
+            },
+            (Some(source), Some(target)) => {
+                for (input, s) in source.iter().enumerate() {
+                    let candidates = target.iter().enumerate().filter(|(_, t)| t.lfe == s.lfe);
+                    let nearest = candidates.min_by(|(_, a), (_, b)| {
+                        angle_between(a.azimuth, s.azimuth)
+                            .total_cmp(&angle_between(b.azimuth, s.azimuth))
+                    });
+                    if let Some((out, _)) = nearest {
+                        set(out, input, 1.0);
+                    }
+                }
+            },
+            _ => (0..to_len).for_each(|out| set(out, out % from_len, 1.0)),
+        }
+
+        Self { from: from_len, to: to_len, matrix }
+    }
+
+    /// A remix with explicit coefficients: one row per output channel, each holding a gain
+    /// per input channel. Rows must be non-empty and all of the same length.
+    pub fn from_matrix(rows: &[Vec<f32>]) -> Self {
+        let from = rows.first().map_or(1, Vec::len).max(1);
+        let matrix = rows
+            .iter()
+            .flat_map(|row| (0..from).map(|input| row.get(input).copied().unwrap_or(0.0)))
+            .collect();
+        Self { from, to: rows.len().max(1), matrix }
+    }
+
+    /// Number of input channels.
+    pub const fn inputs(&self) -> usize {
+        self.from
+    }
+
+    /// Number of output channels.
+    pub const fn outputs(&self) -> usize {
+        self.to
+    }
+
+    /// Whether the remix passes every channel through unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.from == self.to
+            && self.matrix.chunks_exact(self.from).enumerate().all(|(out, row)| {
+                row.iter().enumerate().all(|(input, &gain)| {
+                    let expected = if input == out { 1.0 } else { 0.0 };
+                    (gain - expected).abs() < f32::EPSILON
+                })
+            })
+    }
+
+    /// Mixes interleaved `input` into interleaved `output`, adding to what is already there,
+    /// over as many frames as both hold.
+    pub fn accumulate(&self, input: &[f32], output: &mut [f32]) {
+        let frames = input.chunks_exact(self.from).zip(output.chunks_exact_mut(self.to));
+        for (source, target) in frames {
+            for (out, row) in target.iter_mut().zip(self.matrix.chunks_exact(self.from)) {
+                *out += row.iter().zip(source).map(|(gain, s)| gain * s).sum::<f32>();
+            }
+        }
+    }
+
+    /// Returns interleaved `input` remixed to the target channel count.
+    pub fn apply(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = vec![0.0; input.len() / self.from * self.to];
+        self.accumulate(input, &mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn matrix(from: u16, to: u16) -> Vec<f32> {
+        Remix::new(from, to).matrix
+    }
+
+    #[test]
+    fn test_mono_and_stereo_conversions() {
+        assert_eq!(matrix(1, 2), vec![1.0, 1.0]);
+        assert_eq!(matrix(2, 1), vec![0.5, 0.5]);
+        assert_eq!(matrix(2, 2), vec![1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(Remix::new(1, 2).apply(&[0.25, 0.5]), vec![0.25, 0.25, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_surround_downmix_to_stereo() {
+        let h = FRAC_1_SQRT_2;
+        // FL FR FC LFE BL BR
+        assert_eq!(matrix(6, 2), vec![1.0, 0.0, h, 0.0, h, 0.0, 0.0, 1.0, h, 0.0, 0.0, h]);
+        // A back centre is split between both sides and lowered by 3 dB on top.
+        let stereo = Remix::new(7, 2).apply(&[0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+        assert!((stereo[0] - 0.5).abs() < 1e-6 && (stereo[1] - 0.5).abs() < 1e-6);
+        // Mono from 5.1 leaves out the LFE.
+        assert_eq!(matrix(6, 1), vec![0.2, 0.2, 0.2, 0.0, 0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_between_surround_layouts() {
+        // Mono goes to the centre, stereo to the front pair.
+        assert_eq!(matrix(1, 6), vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+        let upmix = Remix::new(2, 6).apply(&[0.1, 0.2]);
+        assert_eq!(upmix, vec![0.1, 0.2, 0.0, 0.0, 0.0, 0.0]);
+
+        // 7.1 folds its sides and backs into the 5.1 surrounds and keeps the LFE.
+        let down = Remix::new(8, 6).apply(&[0.0, 0.0, 0.0, 0.5, 0.1, 0.2, 0.3, 0.4]);
+        assert!((down[3] - 0.5).abs() < 1e-6);
+        assert!((down[4] - 0.4).abs() < 1e-6 && (down[5] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unknown_layouts_map_cyclically() {
+        assert!(speakers(12).is_none());
+        assert_eq!(layout_name(12), "unknown");
+        assert_eq!(Remix::new(2, 12).apply(&[0.1, 0.2])[..4], [0.1, 0.2, 0.1, 0.2]);
+        assert!(Remix::new(3, 3).is_identity());
+    }
+
+    #[test]
+    fn test_explicit_matrix() {
+        // Swap a stereo pair and add a mono sum as a third channel.
+        let remix = Remix::from_matrix(&[vec![0.0, 1.0], vec![1.0, 0.0], vec![0.5, 0.5]]);
+        assert_eq!((remix.inputs(), remix.outputs()), (2, 3));
+        assert_eq!(remix.apply(&[0.2, 0.4]), vec![0.4, 0.2, 0.3]);
+        assert!(!Remix::from_matrix(&[vec![0.0, 1.0], vec![1.0, 0.0]]).is_identity());
+    }
+}
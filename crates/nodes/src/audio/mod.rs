@@ -4,11 +4,23 @@
 
 //! This module contains all built-in audio node implementations and their registration logic.
 
+// The DSP code writes `a * b + c` in per-sample loops on purpose: `mul_add` falls back to a
+// slow libm call on targets built without FMA, which includes the default x86-64 target.
+#![allow(clippy::suboptimal_flops)]
+
 use streamkit_core::NodeRegistry;
 
+pub mod analysis;
 pub mod codecs;
+#[cfg(feature = "audio_device")]
+pub mod device;
+#[cfg(feature = "audio_edit")]
+pub mod edit;
 pub mod filters;
+pub mod generators;
+pub mod layout;
 pub mod pacer;
+pub mod simd;
 
 use schemars::schema_for;
 
@@ -22,6 +34,12 @@ pub fn register_audio_nodes(registry: &mut NodeRegistry) {
     // Call the registration functions from the submodules.
     filters::register_audio_filters(registry);
     codecs::register_audio_codecs(registry);
+    analysis::register_audio_analysis(registry);
+    generators::register_audio_generators(registry);
+    #[cfg(feature = "audio_device")]
+    device::register_audio_device_nodes(registry);
+    #[cfg(feature = "audio_edit")]
+    edit::register_audio_edit(registry);
 
     // Register audio pacer
     #[cfg(feature = "audio_pacer")]
@@ -9,7 +9,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::time::Duration;
-use streamkit_core::control::NodeControlMessage;
+use streamkit_core::control::{reject_query, NodeControlMessage};
 use streamkit_core::types::{AudioFormat, AudioFrame, Packet, PacketType, SampleFormat};
 use streamkit_core::{
     config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
@@ -22,6 +22,7 @@ use tokio::time::{Instant, Interval, MissedTickBehavior};
 #[serde(default)]
 pub struct AudioPacerConfig {
     /// Playback speed multiplier (1.0 = real-time, 2.0 = 2x speed, 0.5 = half speed)
+    #[schemars(extend("tunable" = true))]
     pub speed: f32,
     /// Maximum number of audio frames to buffer internally
     /// Default: 32 frames (~640ms of audio at 20ms/frame)
@@ -394,6 +395,16 @@ impl ProcessorNode for AudioPacerNode {
                             tracing::info!("AudioPacerNode received shutdown signal");
                             break;
                         }
+                        NodeControlMessage::Query { kind, reply, .. } => {
+                            if kind == "buffer_fill" {
+                                let _ = reply.send(Ok(serde_json::json!({
+                                    "queued": audio_queue.len(),
+                                    "capacity": self.buffer_size,
+                                })));
+                            } else {
+                                reject_query(&kind, reply);
+                            }
+                        }
                     }
                 }
 
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! ADTS and AudioSpecificConfig helpers shared by the containers that carry AAC.
+
+/// AAC sampling frequencies by ADTS/AudioSpecificConfig index.
+pub const AAC_SAMPLE_RATES: [u32; 13] =
+    [96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350];
+
+/// Parsed fields of an ADTS header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdtsHeader {
+    /// AAC object type (profile + 1)
+    pub object_type: u8,
+    pub sample_rate_index: u8,
+    pub channel_config: u8,
+    pub header_len: usize,
+    /// Header plus payload
+    pub frame_len: usize,
+}
+
+/// Parses the ADTS header at the start of `data`.
+pub fn parse_adts_header(data: &[u8]) -> Option<AdtsHeader> {
+    if data.len() < 7 || data[0] != 0xFF || data[1] & 0xF6 != 0xF0 {
+        return None;
+    }
+    let protection_absent = data[1] & 0x01 == 1;
+    let frame_len = (usize::from(data[3] & 0x03) << 11)
+        | (usize::from(data[4]) << 3)
+        | usize::from(data[5] >> 5);
+    let header_len = if protection_absent { 7 } else { 9 };
+    if frame_len < header_len {
+        return None;
+    }
+    Some(AdtsHeader {
+        object_type: (data[2] >> 6) + 1,
+        sample_rate_index: (data[2] >> 2) & 0x0F,
+        channel_config: ((data[2] & 0x01) << 2) | (data[3] >> 6),
+        header_len,
+        frame_len,
+    })
+}
+
+/// Two-byte AudioSpecificConfig for the given object type, rate index and channel layout.
+pub const fn audio_specific_config(
+    object_type: u8,
+    sample_rate_index: u8,
+    channel_config: u8,
+) -> [u8; 2] {
+    [
+        (object_type << 3) | (sample_rate_index >> 1),
+        ((sample_rate_index & 0x01) << 7) | (channel_config << 3),
+    ]
+}
+
+/// Index of `sample_rate` in [`AAC_SAMPLE_RATES`].
+pub fn aac_sample_rate_index(sample_rate: u32) -> Option<u8> {
+    AAC_SAMPLE_RATES.iter().position(|&r| r == sample_rate).and_then(|i| u8::try_from(i).ok())
+}
+
+/// Builds the 7-byte ADTS header for an access unit of `payload_len` bytes.
+pub fn adts_header(asc: &[u8], payload_len: usize) -> Result<[u8; 7], String> {
+    if asc.len() < 2 {
+        return Err("AudioSpecificConfig is too short".to_string());
+    }
+    let object_type = asc[0] >> 3;
+    let sample_rate_index = ((asc[0] & 0x07) << 1) | (asc[1] >> 7);
+    let channel_config = (asc[1] >> 3) & 0x0F;
+    if !(1..=4).contains(&object_type) || sample_rate_index > 12 || channel_config > 7 {
+        return Err(format!(
+            "AudioSpecificConfig cannot be expressed as ADTS (object type {object_type}, \
+             rate index {sample_rate_index}, channels {channel_config})"
+        ));
+    }
+    let frame_len = payload_len + 7;
+    if frame_len > 0x1FFF {
+        return Err(format!("AAC access unit of {payload_len} bytes is too large for ADTS"));
+    }
+    let frame_len = u16::try_from(frame_len).map_err(|e| e.to_string())?;
+    let [len_hi, len_lo] = frame_len.to_be_bytes();
+    Ok([
+        0xFF,
+        0xF1,
+        ((object_type - 1) << 6) | (sample_rate_index << 2) | (channel_config >> 2),
+        ((channel_config & 0x03) << 6) | (len_hi >> 3),
+        (len_hi << 5) | (len_lo >> 3),
+        ((len_lo & 0x07) << 5) | 0x1F,
+        0xFC,
+    ])
+}
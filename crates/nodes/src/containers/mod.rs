@@ -7,8 +7,12 @@
 use streamkit_core::NodeRegistry;
 
 // Declare the submodules for each container format.
+pub(crate) mod adts;
+pub mod mp4;
+pub mod mpegts;
 pub mod ogg;
 pub mod wav;
+#[cfg(feature = "webm")]
 pub mod webm;
 
 // Integration tests for container nodes
@@ -18,7 +22,10 @@ mod tests;
 /// Registers all available container nodes with the engine's registry.
 pub fn register_container_nodes(registry: &mut NodeRegistry) {
     // Call the registration function from each submodule.
+    mp4::register_mp4_nodes(registry);
+    mpegts::register_mpegts_nodes(registry);
     ogg::register_ogg_nodes(registry);
     wav::register_wav_nodes(registry);
+    #[cfg(feature = "webm")]
     webm::register_webm_nodes(registry);
 }
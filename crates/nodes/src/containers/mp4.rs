@@ -0,0 +1,2292 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! MP4 container nodes.
+//!
+//! The muxer writes fragmented MP4 (fMP4): an init segment (`ftyp` + `moov` with empty sample
+//! tables) followed by self-contained `moof` + `mdat` fragments. Each fragment is emitted as soon
+//! as it is complete, so memory stays bounded by the fragment size no matter how long the
+//! stream runs, and the output can be fed straight to MSE/HLS/DASH consumers.
+//!
+//! Chapter markers from `audio::analysis::chapter_detect` can be fed to the muxer's `chapters`
+//! pin. The `moov` box is already out by the time markers arrive, so each one is written as an
+//! `emsg` event message box (version 1, absolute presentation time) in front of the next
+//! fragment, with [`CHAPTER_SCHEME_URI`] as its scheme and the marker JSON as its payload.
+//!
+//! The demuxer reads fragmented MP4 and "faststart" MP4 (`moov` before `mdat`) from a byte
+//! stream without seeking, and emits the samples of one track. Files with the `moov` box at
+//! the end cannot be demuxed without buffering the whole `mdat` and are rejected.
+//!
+//! Supported sample formats: Opus and AAC audio, VP9 and AV1 video.
+
+use async_trait::async_trait;
+use bytes::{Buf, Bytes, BytesMut};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{CustomPacketData, Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use tokio::sync::mpsc;
+
+use crate::audio::analysis::CHAPTER_MARKER_TYPE_ID;
+
+use super::adts::{
+    aac_sample_rate_index, adts_header, audio_specific_config, parse_adts_header, AAC_SAMPLE_RATES,
+};
+
+// --- MP4 Constants ---
+
+/// `scheme_id_uri` of the `emsg` boxes carrying chapter markers.
+pub const CHAPTER_SCHEME_URI: &str = "urn:streamkit:chapter:1";
+/// Input pin receiving chapter markers, when enabled.
+const CHAPTERS_PIN: &str = "chapters";
+/// Merged-input index of the chapters pin, past any track index.
+const CHAPTERS_INPUT: usize = usize::MAX;
+
+/// Default target duration of a muxed fragment.
+const DEFAULT_FRAGMENT_DURATION_MS: u64 = 1000;
+/// Default cap on buffered sample bytes before a fragment is forced out.
+const DEFAULT_MAX_FRAGMENT_BYTES: usize = 8 * 1024 * 1024;
+/// Upper bound for `max_fragment_bytes`, keeping box sizes and data offsets within 32 bits.
+const MAX_FRAGMENT_BYTES_LIMIT: usize = 256 * 1024 * 1024;
+/// Largest `moov`/`moof` the demuxer buffers in full.
+const MAX_METADATA_BOX_SIZE: u64 = 64 * 1024 * 1024;
+/// Opus encoder delay written to `dOps` (matches the WebM muxer).
+const OPUS_PRESKIP_SAMPLES: u16 = 312;
+/// Media timescale for video tracks.
+const VIDEO_TIMESCALE: u32 = 90_000;
+/// Samples per AAC-LC access unit.
+const AAC_FRAME_SAMPLES: u64 = 1024;
+/// Fallback frame durations (in track ticks) when packets carry no timing metadata:
+/// 20 ms Opus frames and 30 fps video.
+const DEFAULT_OPUS_FRAME_TICKS: u64 = 960;
+const DEFAULT_VIDEO_FRAME_TICKS: u64 = 3000;
+
+/// `trun` sample flags: a sync sample that depends on no other sample.
+const SAMPLE_FLAGS_SYNC: u32 = 0x0200_0000;
+/// `trun` sample flags: a non-sync sample that depends on others.
+const SAMPLE_FLAGS_NON_SYNC: u32 = 0x0101_0000;
+
+/// Codec of an MP4 track.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Mp4Codec {
+    /// Opus audio (`Opus` sample entry)
+    #[default]
+    Opus,
+    /// AAC-LC audio (`mp4a` sample entry). Input may be ADTS-framed or raw access units.
+    Aac,
+    /// VP9 video (`vp09` sample entry)
+    Vp9,
+    /// AV1 video (`av01` sample entry), one temporal unit per packet
+    Av1,
+}
+
+impl Mp4Codec {
+    const fn is_video(self) -> bool {
+        matches!(self, Self::Vp9 | Self::Av1)
+    }
+
+    const fn fourcc(self) -> &'static [u8; 4] {
+        match self {
+            Self::Opus => b"Opus",
+            Self::Aac => b"mp4a",
+            Self::Vp9 => b"vp09",
+            Self::Av1 => b"av01",
+        }
+    }
+
+    const fn from_fourcc(fourcc: [u8; 4]) -> Option<Self> {
+        match &fourcc {
+            b"Opus" => Some(Self::Opus),
+            b"mp4a" => Some(Self::Aac),
+            b"vp09" => Some(Self::Vp9),
+            b"av01" => Some(Self::Av1),
+            _ => None,
+        }
+    }
+
+    /// Packet type carried on pins for this codec.
+    const fn packet_type(self) -> PacketType {
+        match self {
+            Self::Opus => PacketType::OpusAudio,
+            Self::Aac | Self::Vp9 | Self::Av1 => PacketType::Binary,
+        }
+    }
+}
+
+// --- Box writing ---
+
+/// Appends ISO BMFF boxes to a buffer, patching each box size when it is closed.
+#[derive(Default)]
+struct BoxWriter {
+    buf: Vec<u8>,
+    open: Vec<usize>,
+}
+
+impl BoxWriter {
+    fn begin(&mut self, fourcc: &[u8]) {
+        self.open.push(self.buf.len());
+        self.u32(0);
+        self.bytes(fourcc);
+    }
+
+    fn begin_full(&mut self, fourcc: &[u8], version: u8, flags: u32) {
+        self.begin(fourcc);
+        self.u32((u32::from(version) << 24) | (flags & 0x00FF_FFFF));
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // Boxes are bounded by MAX_FRAGMENT_BYTES_LIMIT
+    fn end(&mut self) {
+        if let Some(start) = self.open.pop() {
+            let size = (self.buf.len() - start) as u32;
+            self.buf[start..start + 4].copy_from_slice(&size.to_be_bytes());
+        }
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn bytes(&mut self, v: &[u8]) {
+        self.buf.extend_from_slice(v);
+    }
+
+    fn zeros(&mut self, n: usize) {
+        self.buf.resize(self.buf.len() + n, 0);
+    }
+
+    const fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Unity transformation matrix used by `mvhd` and `tkhd`.
+    fn matrix(&mut self) {
+        for v in [0x0001_0000, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000] {
+            self.u32(v);
+        }
+    }
+}
+
+// --- Codec helpers ---
+
+/// Whether a VP9 frame is a key frame, from its uncompressed header.
+const fn vp9_is_keyframe(data: &[u8]) -> bool {
+    let Some(&first) = data.first() else {
+        return false;
+    };
+    if first >> 6 != 0b10 {
+        return false;
+    }
+    let profile = (((first >> 4) & 0x01) << 1) | ((first >> 5) & 0x01);
+    // Bits after the profile: [reserved_zero if profile 3], show_existing_frame, frame_type
+    let mut bit = if profile == 3 { 2 } else { 3 };
+    let show_existing_frame = (first >> bit) & 0x01 == 1;
+    if show_existing_frame {
+        return false;
+    }
+    bit -= 1;
+    (first >> bit) & 0x01 == 0
+}
+
+/// Reads a LEB128 value as used for AV1 OBU sizes. Returns the value and its length.
+fn read_leb128(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in data.iter().take(8).enumerate() {
+        value |= u64::from(byte & 0x7F) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Drops temporal delimiter OBUs from an AV1 temporal unit, as MP4 samples must not carry
+/// them, and reports whether the unit carries a sequence header.
+///
+/// Encoders repeat the sequence header on key frames, so its presence marks a sync sample.
+fn av1_prepare_sample(data: &Bytes) -> (Bytes, bool) {
+    const OBU_SEQUENCE_HEADER: u8 = 1;
+    const OBU_TEMPORAL_DELIMITER: u8 = 2;
+
+    let mut pos = 0;
+    let mut has_sequence_header = false;
+    let mut delimiters = Vec::new();
+    while pos < data.len() {
+        let header = data[pos];
+        let obu_type = (header >> 3) & 0x0F;
+        let header_len = if header & 0x04 != 0 { 2 } else { 1 };
+        let end = if header & 0x02 != 0 {
+            let Some((size, leb_len)) = data.get(pos + header_len..).and_then(read_leb128) else {
+                break;
+            };
+            usize::try_from(size).map_or(data.len(), |size| {
+                (pos + header_len + leb_len).saturating_add(size).min(data.len())
+            })
+        } else {
+            data.len()
+        };
+        match obu_type {
+            OBU_SEQUENCE_HEADER => has_sequence_header = true,
+            OBU_TEMPORAL_DELIMITER => delimiters.push(pos..end),
+            _ => {},
+        }
+        pos = end;
+    }
+
+    if delimiters.is_empty() {
+        return (data.clone(), has_sequence_header);
+    }
+    let mut stripped = Vec::with_capacity(data.len());
+    let mut last = 0;
+    for range in delimiters {
+        stripped.extend_from_slice(&data[last..range.start]);
+        last = range.end;
+    }
+    stripped.extend_from_slice(&data[last..]);
+    (Bytes::from(stripped), has_sequence_header)
+}
+
+fn us_to_ticks(us: u64, timescale: u32) -> u64 {
+    u64::try_from((u128::from(us) * u128::from(timescale) + 500_000) / 1_000_000)
+        .unwrap_or(u64::MAX)
+}
+
+fn ticks_to_us(ticks: u64, timescale: u32) -> u64 {
+    if timescale == 0 {
+        return 0;
+    }
+    u64::try_from(u128::from(ticks) * 1_000_000 / u128::from(timescale)).unwrap_or(u64::MAX)
+}
+
+// --- MP4 Muxer ---
+
+/// One track of the MP4 muxer.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct Mp4TrackConfig {
+    /// Codec of the samples arriving on this track's input pin
+    pub codec: Mp4Codec,
+    /// Audio sample rate in Hz. AAC takes it from the ADTS headers when present.
+    pub sample_rate: u32,
+    /// Number of audio channels. AAC takes it from the ADTS headers when present.
+    pub channels: u16,
+    /// Video width in pixels
+    pub width: u16,
+    /// Video height in pixels
+    pub height: u16,
+    /// Codec profile (VP9 profile or AV1 `seq_profile`)
+    pub profile: u8,
+    /// Codec level (VP9 level × 10, e.g. 31 for 3.1, or AV1 `seq_level_idx`)
+    pub level: u8,
+}
+
+impl Default for Mp4TrackConfig {
+    fn default() -> Self {
+        Self {
+            codec: Mp4Codec::Opus,
+            sample_rate: 48000,
+            channels: 2,
+            width: 1280,
+            height: 720,
+            profile: 0,
+            level: 31,
+        }
+    }
+}
+
+impl Mp4TrackConfig {
+    fn validate(&self) -> Result<(), String> {
+        match self.codec {
+            Mp4Codec::Opus if !(1..=2).contains(&self.channels) => {
+                Err(format!("Opus tracks support 1 or 2 channels, got {}", self.channels))
+            },
+            Mp4Codec::Aac if aac_sample_rate_index(self.sample_rate).is_none() => {
+                Err(format!("Unsupported AAC sample rate: {}", self.sample_rate))
+            },
+            Mp4Codec::Aac if !(1..=7).contains(&self.channels) => {
+                Err(format!("AAC tracks support 1 to 7 channels, got {}", self.channels))
+            },
+            Mp4Codec::Vp9 | Mp4Codec::Av1 if self.width == 0 || self.height == 0 => {
+                Err("Video tracks need a non-zero width and height".to_string())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// `codecs` parameter for the MIME type (RFC 6381).
+    fn codecs_string(&self) -> String {
+        match self.codec {
+            Mp4Codec::Opus => "opus".to_string(),
+            Mp4Codec::Aac => "mp4a.40.2".to_string(),
+            Mp4Codec::Vp9 => format!("vp09.{:02}.{:02}.08", self.profile, self.level),
+            Mp4Codec::Av1 => format!("av01.{}.{:02}M.08", self.profile, self.level),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct Mp4MuxerConfig {
+    /// Tracks to mux, in order. With one track the input pin is `in`; with several they are
+    /// `in_0`, `in_1`, ... in the same order.
+    pub tracks: Vec<Mp4TrackConfig>,
+    /// Target fragment duration in milliseconds. With a video track, fragments start at the
+    /// first key frame after this duration.
+    pub fragment_duration_ms: u64,
+    /// Emit a fragment early once this many sample bytes are buffered (default: 8 MiB).
+    pub max_fragment_bytes: usize,
+    /// Add a `chapters` input pin for `audio::chapter/marker@1` packets and write each marker
+    /// as an `emsg` box ahead of the next fragment.
+    pub chapters: bool,
+}
+
+impl Default for Mp4MuxerConfig {
+    fn default() -> Self {
+        Self {
+            tracks: vec![Mp4TrackConfig::default()],
+            fragment_duration_ms: DEFAULT_FRAGMENT_DURATION_MS,
+            max_fragment_bytes: DEFAULT_MAX_FRAGMENT_BYTES,
+            chapters: false,
+        }
+    }
+}
+
+/// A sample waiting for the next fragment.
+struct PendingSample {
+    data: Bytes,
+    timestamp_us: Option<u64>,
+    duration_us: Option<u64>,
+    /// Best guess of the duration at arrival time, used to decide when to cut fragments.
+    estimated_us: u64,
+    keyframe: bool,
+}
+
+/// Per-track muxing state.
+struct MuxTrack {
+    config: Mp4TrackConfig,
+    timescale: u32,
+    /// AudioSpecificConfig for AAC tracks, refined from the first ADTS header.
+    asc: Option<[u8; 2]>,
+    pending: Vec<PendingSample>,
+    last_timestamp_us: Option<u64>,
+    /// Decode time of the next fragment, in track ticks.
+    next_decode_time: Option<u64>,
+    last_duration: u64,
+}
+
+impl MuxTrack {
+    fn new(config: Mp4TrackConfig) -> Self {
+        let timescale =
+            if config.codec.is_video() { VIDEO_TIMESCALE } else { config.sample_rate.max(1) };
+        let timescale = if config.codec == Mp4Codec::Opus { 48000 } else { timescale };
+        let asc = (config.codec == Mp4Codec::Aac).then(|| {
+            audio_specific_config(
+                2,
+                aac_sample_rate_index(config.sample_rate).unwrap_or(3),
+                u8::try_from(config.channels).unwrap_or(2),
+            )
+        });
+        Self {
+            config,
+            timescale,
+            asc,
+            pending: Vec::new(),
+            last_timestamp_us: None,
+            next_decode_time: None,
+            last_duration: 0,
+        }
+    }
+
+    const fn default_frame_ticks(&self) -> u64 {
+        match self.config.codec {
+            Mp4Codec::Opus => DEFAULT_OPUS_FRAME_TICKS,
+            Mp4Codec::Aac => AAC_FRAME_SAMPLES,
+            Mp4Codec::Vp9 | Mp4Codec::Av1 => DEFAULT_VIDEO_FRAME_TICKS,
+        }
+    }
+
+    /// Turns an input packet into a sample: strips ADTS/temporal delimiters and detects
+    /// sync samples.
+    fn prepare(&mut self, data: Bytes, metadata: Option<&PacketMetadata>) -> PendingSample {
+        let (data, keyframe) = match self.config.codec {
+            Mp4Codec::Opus => (data, true),
+            Mp4Codec::Aac => match parse_adts_header(&data) {
+                Some(header) => {
+                    if self.pending.is_empty() && self.next_decode_time.is_none() {
+                        self.asc = Some(audio_specific_config(
+                            header.object_type,
+                            header.sample_rate_index,
+                            header.channel_config,
+                        ));
+                        if let Some(&rate) =
+                            AAC_SAMPLE_RATES.get(usize::from(header.sample_rate_index))
+                        {
+                            self.timescale = rate;
+                        }
+                    }
+                    let end = header.frame_len.min(data.len());
+                    (data.slice(header.header_len..end), true)
+                },
+                None => (data, true),
+            },
+            Mp4Codec::Vp9 => {
+                let keyframe = vp9_is_keyframe(&data);
+                (data, keyframe)
+            },
+            Mp4Codec::Av1 => av1_prepare_sample(&data),
+        };
+
+        let timestamp_us = metadata.and_then(|m| m.timestamp_us);
+        let duration_us = metadata.and_then(|m| m.duration_us);
+        let estimated_us = duration_us
+            .or_else(|| {
+                timestamp_us.zip(self.last_timestamp_us).and_then(|(ts, last)| ts.checked_sub(last))
+            })
+            .unwrap_or_else(|| ticks_to_us(self.default_frame_ticks(), self.timescale));
+        if timestamp_us.is_some() {
+            self.last_timestamp_us = timestamp_us;
+        }
+
+        PendingSample { data, timestamp_us, duration_us, estimated_us, keyframe }
+    }
+
+    fn pending_duration_us(&self) -> u64 {
+        self.pending.iter().map(|s| s.estimated_us).sum()
+    }
+
+    /// Sample durations in track ticks for the pending samples.
+    fn pending_durations(&mut self) -> Vec<u64> {
+        let default = self.default_frame_ticks();
+        let mut durations = Vec::with_capacity(self.pending.len());
+        for (i, sample) in self.pending.iter().enumerate() {
+            let from_timestamps = sample
+                .timestamp_us
+                .zip(self.pending.get(i + 1).and_then(|next| next.timestamp_us))
+                .and_then(|(ts, next)| {
+                    us_to_ticks(next, self.timescale).checked_sub(us_to_ticks(ts, self.timescale))
+                });
+            let duration = from_timestamps
+                .or_else(|| sample.duration_us.map(|us| us_to_ticks(us, self.timescale)))
+                .filter(|&d| d > 0)
+                .unwrap_or(if self.last_duration > 0 { self.last_duration } else { default });
+            self.last_duration = duration;
+            durations.push(duration);
+        }
+        durations
+    }
+}
+
+fn write_init_segment(tracks: &[MuxTrack]) -> Vec<u8> {
+    let mut w = BoxWriter::default();
+
+    w.begin(b"ftyp");
+    w.bytes(b"iso6");
+    w.u32(0);
+    for brand in [b"iso6", b"mp41"] {
+        w.bytes(brand);
+    }
+    if tracks.iter().any(|t| t.config.codec == Mp4Codec::Av1) {
+        w.bytes(b"av01");
+    }
+    w.end();
+
+    w.begin(b"moov");
+    w.begin_full(b"mvhd", 0, 0);
+    w.u32(0); // creation_time
+    w.u32(0); // modification_time
+    w.u32(1000); // timescale
+    w.u32(0); // duration (unknown, fragmented)
+    w.u32(0x0001_0000); // rate 1.0
+    w.u16(0x0100); // volume 1.0
+    w.zeros(10);
+    w.matrix();
+    w.zeros(24); // pre_defined
+    w.u32(u32::try_from(tracks.len() + 1).unwrap_or(u32::MAX)); // next_track_ID
+    w.end();
+
+    for (index, track) in tracks.iter().enumerate() {
+        write_trak(&mut w, track, track_id(index));
+    }
+
+    w.begin(b"mvex");
+    for index in 0..tracks.len() {
+        w.begin_full(b"trex", 0, 0);
+        w.u32(track_id(index));
+        w.u32(1); // default_sample_description_index
+        w.u32(0); // default_sample_duration
+        w.u32(0); // default_sample_size
+        w.u32(0); // default_sample_flags
+        w.end();
+    }
+    w.end();
+    w.end(); // moov
+
+    w.buf
+}
+
+fn track_id(index: usize) -> u32 {
+    u32::try_from(index + 1).unwrap_or(u32::MAX)
+}
+
+fn write_trak(w: &mut BoxWriter, track: &MuxTrack, id: u32) {
+    let config = &track.config;
+    let video = config.codec.is_video();
+
+    w.begin(b"trak");
+    w.begin_full(b"tkhd", 0, 0x03); // enabled | in_movie
+    w.u32(0); // creation_time
+    w.u32(0); // modification_time
+    w.u32(id);
+    w.u32(0); // reserved
+    w.u32(0); // duration
+    w.zeros(8);
+    w.u16(0); // layer
+    w.u16(0); // alternate_group
+    w.u16(if video { 0 } else { 0x0100 }); // volume
+    w.u16(0);
+    w.matrix();
+    let (width, height) = if video { (config.width, config.height) } else { (0, 0) };
+    w.u32(u32::from(width) << 16);
+    w.u32(u32::from(height) << 16);
+    w.end();
+
+    w.begin(b"mdia");
+    w.begin_full(b"mdhd", 0, 0);
+    w.u32(0); // creation_time
+    w.u32(0); // modification_time
+    w.u32(track.timescale);
+    w.u32(0); // duration
+    w.u16(0x55C4); // language: und
+    w.u16(0);
+    w.end();
+
+    w.begin_full(b"hdlr", 0, 0);
+    w.u32(0); // pre_defined
+    w.bytes(if video { b"vide" } else { b"soun" });
+    w.zeros(12);
+    w.bytes(if video { b"VideoHandler\0" } else { b"SoundHandler\0" });
+    w.end();
+
+    w.begin(b"minf");
+    if video {
+        w.begin_full(b"vmhd", 0, 1);
+        w.zeros(8); // graphicsmode + opcolor
+    } else {
+        w.begin_full(b"smhd", 0, 0);
+        w.zeros(4); // balance + reserved
+    }
+    w.end();
+
+    w.begin(b"dinf");
+    w.begin_full(b"dref", 0, 0);
+    w.u32(1);
+    w.begin_full(b"url ", 0, 1); // media data is in this file
+    w.end();
+    w.end();
+    w.end();
+
+    w.begin(b"stbl");
+    w.begin_full(b"stsd", 0, 0);
+    w.u32(1);
+    write_sample_entry(w, track, id);
+    w.end();
+    for fourcc in [b"stts", b"stsc", b"stco"] {
+        w.begin_full(fourcc, 0, 0);
+        w.u32(0); // entry_count
+        w.end();
+    }
+    w.begin_full(b"stsz", 0, 0);
+    w.u32(0); // sample_size
+    w.u32(0); // sample_count
+    w.end();
+    w.end(); // stbl
+
+    w.end(); // minf
+    w.end(); // mdia
+    w.end(); // trak
+}
+
+fn write_sample_entry(w: &mut BoxWriter, track: &MuxTrack, id: u32) {
+    let config = &track.config;
+    w.begin(config.codec.fourcc());
+    w.zeros(6); // reserved
+    w.u16(1); // data_reference_index
+
+    if config.codec.is_video() {
+        w.zeros(16); // pre_defined + reserved
+        w.u16(config.width);
+        w.u16(config.height);
+        w.u32(0x0048_0000); // 72 dpi
+        w.u32(0x0048_0000);
+        w.u32(0); // reserved
+        w.u16(1); // frame_count
+        w.zeros(32); // compressorname
+        w.u16(0x0018); // depth
+        w.u16(0xFFFF); // pre_defined = -1
+    } else {
+        w.zeros(8); // reserved
+        w.u16(config.channels);
+        w.u16(16); // samplesize
+        w.u32(0); // pre_defined + reserved
+        w.u32(track.timescale.min(0xFFFF) << 16);
+    }
+
+    match config.codec {
+        Mp4Codec::Opus => {
+            w.begin(b"dOps");
+            w.u8(0); // version
+            w.u8(u8::try_from(config.channels).unwrap_or(2));
+            w.u16(OPUS_PRESKIP_SAMPLES);
+            w.u32(config.sample_rate);
+            w.u16(0); // output gain
+            w.u8(0); // channel mapping family 0
+            w.end();
+        },
+        Mp4Codec::Aac => {
+            let asc = track.asc.unwrap_or_default();
+            w.begin_full(b"esds", 0, 0);
+
+            // ES_Descriptor: ES_ID + flags, then the two nested descriptors below.
+            w.u8(0x03);
+            w.u8(3 + 19 + 3);
+            w.u16(u16::try_from(id).unwrap_or(0));
+            w.u8(0);
+
+            // DecoderConfigDescriptor: Audio ISO/IEC 14496-3, AudioStream.
+            w.u8(0x04);
+            w.u8(13 + 4);
+            w.u8(0x40);
+            w.u8(0x15);
+            w.zeros(3); // bufferSizeDB
+            w.u32(0); // maxBitrate
+            w.u32(0); // avgBitrate
+
+            // DecoderSpecificInfo: the two-byte AudioSpecificConfig.
+            w.u8(0x05);
+            w.u8(2);
+            w.bytes(&asc);
+
+            // SLConfigDescriptor: predefined MP4 layout.
+            w.u8(0x06);
+            w.u8(1);
+            w.u8(0x02);
+            w.end();
+        },
+        Mp4Codec::Vp9 => {
+            w.begin_full(b"vpcC", 1, 0);
+            w.u8(config.profile);
+            w.u8(config.level);
+            w.u8((8 << 4) | (1 << 1)); // 8-bit, 4:2:0 colocated, limited range
+            w.u8(1); // colour_primaries: BT.709
+            w.u8(1); // transfer_characteristics: BT.709
+            w.u8(1); // matrix_coefficients: BT.709
+            w.u16(0); // codecInitializationDataSize
+            w.end();
+        },
+        Mp4Codec::Av1 => {
+            w.begin(b"av1C");
+            w.u8(0x81); // marker + version 1
+            w.u8((config.profile << 5) | (config.level & 0x1F));
+            w.u8(0b0000_1100); // main tier, 8-bit, 4:2:0
+            w.u8(0); // no initial_presentation_delay
+                     // The sequence header travels in-band with each sync sample.
+            w.end();
+        },
+    }
+    w.end();
+}
+
+/// Writes a chapter marker as an `emsg` box (ISO/IEC 23009-1, version 1).
+///
+/// The presentation time is the marker's `start_ms` in a millisecond timescale, falling back
+/// to the packet timestamp. Returns `None` for markers with neither.
+fn write_chapter_emsg(marker: &CustomPacketData) -> Option<Vec<u8>> {
+    let start_ms = marker
+        .data
+        .get("start_ms")
+        .and_then(serde_json::Value::as_u64)
+        .or_else(|| marker.metadata.as_ref()?.timestamp_us.map(|us| us / 1000))?;
+    let index = marker.data.get("index").and_then(serde_json::Value::as_u64).unwrap_or_default();
+
+    let mut w = BoxWriter::default();
+    w.begin_full(b"emsg", 1, 0);
+    w.u32(1000); // timescale
+    w.u64(start_ms); // presentation_time
+    w.u32(u32::MAX); // event_duration (unknown)
+    w.u32(u32::try_from(index).unwrap_or(u32::MAX)); // id
+    w.bytes(CHAPTER_SCHEME_URI.as_bytes());
+    w.u8(0);
+    w.u8(0); // value (empty)
+    w.bytes(marker.data.to_string().as_bytes());
+    w.end();
+    Some(w.buf)
+}
+
+/// Writes one `moof` + `mdat` fragment with every track's pending samples.
+fn write_fragment(sequence: u32, tracks: &mut [MuxTrack]) -> Vec<u8> {
+    let mut w = BoxWriter::default();
+    let mut data_offset_fields = Vec::new();
+    let mut payloads = Vec::new();
+
+    w.begin(b"moof");
+    w.begin_full(b"mfhd", 0, 0);
+    w.u32(sequence);
+    w.end();
+
+    let mut mdat_offset = 0usize;
+    for (index, track) in tracks.iter_mut().enumerate() {
+        if track.pending.is_empty() {
+            continue;
+        }
+        let durations = track.pending_durations();
+        let decode_time = *track.next_decode_time.get_or_insert_with(|| {
+            track.pending[0].timestamp_us.map_or(0, |ts| us_to_ticks(ts, track.timescale))
+        });
+        track.next_decode_time = Some(decode_time + durations.iter().sum::<u64>());
+
+        w.begin(b"traf");
+        w.begin_full(b"tfhd", 0, 0x02_0000); // default-base-is-moof
+        w.u32(track_id(index));
+        w.end();
+        w.begin_full(b"tfdt", 1, 0);
+        w.u64(decode_time);
+        w.end();
+        // data-offset, sample-duration, sample-size, sample-flags
+        w.begin_full(b"trun", 0, 0x0001 | 0x0100 | 0x0200 | 0x0400);
+        w.u32(u32::try_from(track.pending.len()).unwrap_or(u32::MAX));
+        data_offset_fields.push((w.len(), mdat_offset));
+        w.u32(0); // data_offset, patched below
+        for (sample, duration) in track.pending.drain(..).zip(durations) {
+            w.u32(u32::try_from(duration).unwrap_or(u32::MAX));
+            w.u32(u32::try_from(sample.data.len()).unwrap_or(u32::MAX));
+            w.u32(if sample.keyframe { SAMPLE_FLAGS_SYNC } else { SAMPLE_FLAGS_NON_SYNC });
+            mdat_offset += sample.data.len();
+            payloads.push(sample.data);
+        }
+        w.end(); // trun
+        w.end(); // traf
+    }
+    w.end(); // moof
+
+    // Sample data starts right after the moof and the 8-byte mdat header.
+    let moof_len = w.len();
+    for (field, offset) in data_offset_fields {
+        let data_offset = u32::try_from(moof_len + 8 + offset).unwrap_or(u32::MAX);
+        w.buf[field..field + 4].copy_from_slice(&data_offset.to_be_bytes());
+    }
+
+    w.begin(b"mdat");
+    for payload in payloads {
+        w.bytes(&payload);
+    }
+    w.end();
+    w.buf
+}
+
+/// A node that muxes Opus/AAC audio and VP9/AV1 video into a fragmented MP4 stream.
+pub struct Mp4MuxerNode {
+    config: Mp4MuxerConfig,
+}
+
+impl Mp4MuxerNode {
+    /// Creates a new MP4 muxer node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no tracks are configured or a track's parameters are invalid.
+    pub fn new(config: Mp4MuxerConfig) -> Result<Self, StreamKitError> {
+        if config.tracks.is_empty() {
+            return Err(StreamKitError::Configuration(
+                "MP4 muxer needs at least one track".to_string(),
+            ));
+        }
+        if config.max_fragment_bytes == 0 || config.max_fragment_bytes > MAX_FRAGMENT_BYTES_LIMIT {
+            return Err(StreamKitError::Configuration(format!(
+                "max_fragment_bytes must be between 1 and {MAX_FRAGMENT_BYTES_LIMIT}"
+            )));
+        }
+        for (index, track) in config.tracks.iter().enumerate() {
+            track.validate().map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid MP4 track {index}: {e}"))
+            })?;
+        }
+        Ok(Self { config })
+    }
+
+    fn pin_name(&self, index: usize) -> String {
+        if self.config.tracks.len() == 1 {
+            "in".to_string()
+        } else {
+            format!("in_{index}")
+        }
+    }
+
+    fn mime_type(&self) -> String {
+        let kind =
+            if self.config.tracks.iter().any(|t| t.codec.is_video()) { "video" } else { "audio" };
+        let codecs: Vec<String> =
+            self.config.tracks.iter().map(Mp4TrackConfig::codecs_string).collect();
+        format!("{kind}/mp4; codecs=\"{}\"", codecs.join(","))
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for Mp4MuxerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        let mut pins: Vec<InputPin> = self
+            .config
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| InputPin {
+                name: self.pin_name(index),
+                accepts_types: vec![track.codec.packet_type()],
+                cardinality: PinCardinality::One,
+            })
+            .collect();
+        if self.config.chapters {
+            pins.push(InputPin {
+                name: CHAPTERS_PIN.to_string(),
+                accepts_types: vec![PacketType::Custom {
+                    type_id: CHAPTER_MARKER_TYPE_ID.to_string(),
+                }],
+                cardinality: PinCardinality::One,
+            });
+        }
+        pins
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        Some(self.mime_type())
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        tracing::info!("Mp4MuxerNode starting with {} track(s)", self.config.tracks.len());
+
+        // Merge the track inputs into one channel; each drainer ends when its input closes.
+        let (merged_tx, mut merged_rx) = mpsc::channel::<(usize, Packet)>(32);
+        let mut inputs = Vec::with_capacity(self.config.tracks.len() + 1);
+        for index in 0..self.config.tracks.len() {
+            inputs.push((index, context.take_input(&self.pin_name(index))?));
+        }
+        // The chapters pin may be left unconnected.
+        if let Some(chapters_rx) =
+            self.config.chapters.then(|| context.inputs.remove(CHAPTERS_PIN)).flatten()
+        {
+            inputs.push((CHAPTERS_INPUT, chapters_rx));
+        }
+        let mut drainers = Vec::with_capacity(inputs.len());
+        for (index, mut input_rx) in inputs {
+            let merged_tx = merged_tx.clone();
+            drainers.push(tokio::spawn(async move {
+                while let Some(packet) = input_rx.recv().await {
+                    if merged_tx.send((index, packet)).await.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+        drop(merged_tx);
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut tracks: Vec<MuxTrack> =
+            self.config.tracks.iter().cloned().map(MuxTrack::new).collect();
+        let reference =
+            self.config.tracks.iter().position(|t| t.codec.is_video()).unwrap_or_default();
+        let fragment_duration_us = self.config.fragment_duration_ms.saturating_mul(1000);
+        let content_type = self.mime_type();
+        let mut init_sent = false;
+        let mut sequence = 0u32;
+        let mut packet_count = 0u64;
+        let mut pending_bytes = 0usize;
+        let mut chapters: Vec<Vec<u8>> = Vec::new();
+        let mut reason = "input_closed";
+
+        loop {
+            let next = if let Some(token) = &context.cancellation_token {
+                tokio::select! {
+                    () = token.cancelled() => {
+                        reason = "cancelled";
+                        None
+                    },
+                    next = merged_rx.recv() => next,
+                }
+            } else {
+                merged_rx.recv().await
+            };
+            let Some((index, packet)) = next else { break };
+            if index == CHAPTERS_INPUT {
+                stats_tracker.received();
+                match &packet {
+                    Packet::Custom(marker) if marker.type_id == CHAPTER_MARKER_TYPE_ID => {
+                        if let Some(emsg) = write_chapter_emsg(marker) {
+                            chapters.push(emsg);
+                            continue;
+                        }
+                        tracing::warn!("Mp4MuxerNode received chapter marker without a start");
+                    },
+                    _ => tracing::warn!("Mp4MuxerNode received non-chapter packet, ignoring"),
+                }
+                stats_tracker.discarded();
+                continue;
+            }
+            let Packet::Binary { data, metadata, .. } = packet else {
+                tracing::warn!("Mp4MuxerNode received non-binary packet, ignoring");
+                stats_tracker.discarded();
+                continue;
+            };
+            packet_count += 1;
+            stats_tracker.received();
+
+            let sample = tracks[index].prepare(data, metadata.as_ref());
+
+            // Cut before the sample that would push the reference track past the target
+            // duration; with video, only before a key frame so each fragment can start playback.
+            let reference_full = tracks[reference].pending_duration_us() >= fragment_duration_us
+                && !tracks[reference].pending.is_empty();
+            let cut_here = index == reference
+                && reference_full
+                && (sample.keyframe || !tracks[reference].config.codec.is_video());
+            if cut_here {
+                sequence += 1;
+                if !flush_fragment(
+                    &mut context,
+                    &mut tracks,
+                    &mut chapters,
+                    sequence,
+                    &mut init_sent,
+                    &content_type,
+                    &mut stats_tracker,
+                )
+                .await
+                {
+                    reason = "output_closed";
+                    break;
+                }
+                pending_bytes = 0;
+            }
+
+            pending_bytes += sample.data.len();
+            tracks[index].pending.push(sample);
+
+            if pending_bytes >= self.config.max_fragment_bytes {
+                sequence += 1;
+                if !flush_fragment(
+                    &mut context,
+                    &mut tracks,
+                    &mut chapters,
+                    sequence,
+                    &mut init_sent,
+                    &content_type,
+                    &mut stats_tracker,
+                )
+                .await
+                {
+                    reason = "output_closed";
+                    break;
+                }
+                pending_bytes = 0;
+            }
+            stats_tracker.maybe_send();
+        }
+
+        for drainer in drainers {
+            drainer.abort();
+        }
+
+        // Finalize: whatever is still buffered becomes the last fragment.
+        if reason == "input_closed"
+            && (tracks.iter().any(|t| !t.pending.is_empty()) || !chapters.is_empty())
+        {
+            sequence += 1;
+            flush_fragment(
+                &mut context,
+                &mut tracks,
+                &mut chapters,
+                sequence,
+                &mut init_sent,
+                &content_type,
+                &mut stats_tracker,
+            )
+            .await;
+        }
+
+        stats_tracker.force_send();
+        tracing::info!("Mp4MuxerNode finished: {} packets in {} fragments", packet_count, sequence);
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Sends the init segment (once) and a fragment of everything pending, preceded by the
+/// pending chapter `emsg` boxes.
+///
+/// Returns `false` if the output channel closed.
+async fn flush_fragment(
+    context: &mut NodeContext,
+    tracks: &mut [MuxTrack],
+    chapters: &mut Vec<Vec<u8>>,
+    sequence: u32,
+    init_sent: &mut bool,
+    content_type: &str,
+    stats_tracker: &mut NodeStatsTracker,
+) -> bool {
+    if !*init_sent {
+        let init = Bytes::from(write_init_segment(tracks));
+        tracing::debug!("Sending MP4 init segment ({} bytes)", init.len());
+        if context
+            .output_sender
+            .send(
+                "out",
+                Packet::Binary {
+                    data: init,
+                    content_type: Some(Cow::Owned(content_type.to_string())),
+                    metadata: None,
+                },
+            )
+            .await
+            .is_err()
+        {
+            return false;
+        }
+        stats_tracker.sent();
+        *init_sent = true;
+    }
+
+    let mut fragment = chapters.drain(..).flatten().collect::<Vec<u8>>();
+    // Chapters arriving after the last sample still go out, without an empty fragment.
+    if tracks.iter().any(|t| !t.pending.is_empty()) {
+        fragment.extend(write_fragment(sequence, tracks));
+    }
+    let fragment = Bytes::from(fragment);
+    tracing::trace!("Sending MP4 fragment {} ({} bytes)", sequence, fragment.len());
+    if context
+        .output_sender
+        .send(
+            "out",
+            Packet::Binary {
+                data: fragment,
+                content_type: Some(Cow::Owned(content_type.to_string())),
+                metadata: None,
+            },
+        )
+        .await
+        .is_err()
+    {
+        return false;
+    }
+    stats_tracker.sent();
+    true
+}
+
+// --- MP4 Demuxer ---
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
+#[serde(default)]
+pub struct Mp4DemuxerConfig {
+    /// Codec of the track to extract; the first track with this codec is used.
+    /// Opus is emitted as Opus packets, AAC as ADTS frames, VP9/AV1 as raw samples.
+    pub codec: Mp4Codec,
+}
+
+/// Reads big-endian fields from a box payload.
+pub(crate) struct BoxReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BoxReader<'a> {
+    pub(crate) const fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.data.len());
+        let Some(end) = end else {
+            return Err("MP4 box is truncated".to_string());
+        };
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn skip(&mut self, n: usize) -> Result<(), String> {
+        self.take(n).map(|_| ())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, String> {
+        let b = self.take(8)?;
+        Ok(u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    /// Version and flags of a full box.
+    pub(crate) fn full_header(&mut self) -> Result<(u8, u32), String> {
+        let v = self.u32()?;
+        Ok(((v >> 24) as u8, v & 0x00FF_FFFF))
+    }
+
+    pub(crate) fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+}
+
+/// Iterates over the child boxes in `data`, yielding `(fourcc, payload)`.
+pub(crate) fn child_boxes(data: &[u8]) -> impl Iterator<Item = Result<([u8; 4], &[u8]), String>> {
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        if pos + 8 > data.len() {
+            return None;
+        }
+        let size = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        let fourcc = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let (header, size) = match size {
+            0 => (8, data.len() - pos),
+            1 => {
+                let Some(large) = data.get(pos + 8..pos + 16) else {
+                    return Some(Err("MP4 box is truncated".to_string()));
+                };
+                let mut b = [0u8; 8];
+                b.copy_from_slice(large);
+                (16, usize::try_from(u64::from_be_bytes(b)).unwrap_or(usize::MAX))
+            },
+            size => (8, size as usize),
+        };
+        if size < header || pos + size > data.len() {
+            pos = data.len();
+            return Some(Err(format!(
+                "Invalid size for MP4 box '{}'",
+                String::from_utf8_lossy(&fourcc)
+            )));
+        }
+        let payload = &data[pos + header..pos + size];
+        pos += size;
+        Some(Ok((fourcc, payload)))
+    })
+}
+
+pub(crate) fn find_child<'a>(data: &'a [u8], fourcc: &[u8]) -> Result<Option<&'a [u8]>, String> {
+    for child in child_boxes(data) {
+        let (kind, payload) = child?;
+        if kind[..] == *fourcc {
+            return Ok(Some(payload));
+        }
+    }
+    Ok(None)
+}
+
+/// A sample located in the byte stream.
+#[derive(Debug, Clone, Copy)]
+struct SampleLocation {
+    offset: u64,
+    size: u32,
+    decode_time: u64,
+    composition_offset: i64,
+    duration: u32,
+}
+
+/// The track being extracted.
+#[derive(Debug, Default)]
+struct DemuxTrack {
+    id: u32,
+    codec: Mp4Codec,
+    timescale: u32,
+    /// AudioSpecificConfig (AAC only)
+    asc: Vec<u8>,
+    default_duration: u32,
+    default_size: u32,
+    /// Decode time after the last known sample, for fragments without `tfdt`.
+    next_decode_time: u64,
+}
+
+/// Parses `moov`, returning the first track with `codec` and any samples listed in its
+/// sample tables (non-fragmented files).
+fn parse_moov(moov: &[u8], codec: Mp4Codec) -> Result<(DemuxTrack, Vec<SampleLocation>), String> {
+    let mut found_codecs = Vec::new();
+    let mut selected = None;
+    for child in child_boxes(moov) {
+        let (kind, trak) = child?;
+        if &kind != b"trak" {
+            continue;
+        }
+        let Some(tkhd) = find_child(trak, b"tkhd")? else { continue };
+        let mut r = BoxReader::new(tkhd);
+        let (version, _) = r.full_header()?;
+        r.skip(if version == 1 { 16 } else { 8 })?;
+        let id = r.u32()?;
+
+        let Some(mdia) = find_child(trak, b"mdia")? else { continue };
+        let Some(mdhd) = find_child(mdia, b"mdhd")? else { continue };
+        let mut r = BoxReader::new(mdhd);
+        let (version, _) = r.full_header()?;
+        r.skip(if version == 1 { 16 } else { 8 })?;
+        let timescale = r.u32()?;
+
+        let Some(stbl) = find_child(mdia, b"minf")?.map(|minf| find_child(minf, b"stbl")) else {
+            continue;
+        };
+        let Some(stbl) = stbl? else { continue };
+        let Some(stsd) = find_child(stbl, b"stsd")? else { continue };
+        let mut r = BoxReader::new(stsd);
+        r.full_header()?;
+        r.u32()?; // entry_count
+        let Some(entry) = child_boxes(r.remaining()).next().transpose()? else { continue };
+        let Some(track_codec) = Mp4Codec::from_fourcc(entry.0) else {
+            found_codecs.push(String::from_utf8_lossy(&entry.0).into_owned());
+            continue;
+        };
+        found_codecs.push(format!("{track_codec:?}").to_lowercase());
+        if track_codec != codec || selected.is_some() {
+            continue;
+        }
+
+        let asc = if codec == Mp4Codec::Aac { parse_esds_asc(entry.1)? } else { Vec::new() };
+        let samples = parse_sample_tables(stbl)?;
+        selected =
+            Some((DemuxTrack { id, codec, timescale, asc, ..DemuxTrack::default() }, samples));
+    }
+
+    selected.ok_or_else(|| {
+        format!(
+            "MP4 has no {} track (found: {})",
+            format!("{codec:?}").to_lowercase(),
+            if found_codecs.is_empty() { "none".to_string() } else { found_codecs.join(", ") }
+        )
+    })
+}
+
+/// Extracts the AudioSpecificConfig from an `mp4a` sample entry's `esds` box.
+fn parse_esds_asc(mp4a: &[u8]) -> Result<Vec<u8>, String> {
+    // AudioSampleEntry fields before the child boxes.
+    let children = mp4a.get(28..).ok_or("mp4a sample entry is truncated")?;
+    let esds = find_child(children, b"esds")?.ok_or("mp4a sample entry has no esds box")?;
+    let mut r = BoxReader::new(esds);
+    r.full_header()?;
+
+    // Walk the descriptor tree until DecoderSpecificInfo (tag 5).
+    while !r.remaining().is_empty() {
+        let tag = r.u8()?;
+        let mut len = 0usize;
+        for _ in 0..4 {
+            let b = r.u8()?;
+            len = (len << 7) | usize::from(b & 0x7F);
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        match tag {
+            0x03 => {
+                r.skip(2)?; // ES_ID
+                let flags = r.u8()?;
+                if flags & 0x80 != 0 {
+                    r.skip(2)?;
+                }
+                if flags & 0x40 != 0 {
+                    let url_len = r.u8()?;
+                    r.skip(usize::from(url_len))?;
+                }
+                if flags & 0x20 != 0 {
+                    r.skip(2)?;
+                }
+            },
+            0x04 => r.skip(13)?,
+            0x05 => return Ok(r.take(len)?.to_vec()),
+            _ => r.skip(len)?,
+        }
+    }
+    Err("esds box has no AudioSpecificConfig".to_string())
+}
+
+/// Expands `stts`/`ctts`/`stsc`/`stsz`/`stco` into sample locations, sorted by offset.
+fn parse_sample_tables(stbl: &[u8]) -> Result<Vec<SampleLocation>, String> {
+    let Some(stsz) = find_child(stbl, b"stsz")? else { return Ok(Vec::new()) };
+    let mut r = BoxReader::new(stsz);
+    r.full_header()?;
+    let uniform_size = r.u32()?;
+    let count = r.u32()? as usize;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let mut sizes = Vec::with_capacity(count.min(1 << 20));
+    for _ in 0..count {
+        sizes.push(if uniform_size == 0 { r.u32()? } else { uniform_size });
+    }
+
+    let chunk_offsets: Vec<u64> = if let Some(stco) = find_child(stbl, b"stco")? {
+        let mut r = BoxReader::new(stco);
+        r.full_header()?;
+        (0..r.u32()?).map(|_| r.u32().map(u64::from)).collect::<Result<_, _>>()?
+    } else if let Some(co64) = find_child(stbl, b"co64")? {
+        let mut r = BoxReader::new(co64);
+        r.full_header()?;
+        (0..r.u32()?).map(|_| r.u64()).collect::<Result<_, _>>()?
+    } else {
+        return Err("MP4 sample table has no chunk offsets".to_string());
+    };
+
+    let mut stsc_entries = Vec::new();
+    if let Some(stsc) = find_child(stbl, b"stsc")? {
+        let mut r = BoxReader::new(stsc);
+        r.full_header()?;
+        for _ in 0..r.u32()? {
+            let first_chunk = r.u32()?;
+            let samples_per_chunk = r.u32()?;
+            r.u32()?; // sample_description_index
+            stsc_entries.push((first_chunk, samples_per_chunk));
+        }
+    }
+
+    let mut durations = Vec::with_capacity(sizes.len());
+    if let Some(stts) = find_child(stbl, b"stts")? {
+        let mut r = BoxReader::new(stts);
+        r.full_header()?;
+        for _ in 0..r.u32()? {
+            let sample_count = r.u32()?;
+            let delta = r.u32()?;
+            for _ in 0..sample_count {
+                if durations.len() == sizes.len() {
+                    break;
+                }
+                durations.push(delta);
+            }
+        }
+    }
+
+    let mut composition_offsets = Vec::new();
+    if let Some(ctts) = find_child(stbl, b"ctts")? {
+        let mut r = BoxReader::new(ctts);
+        let (version, _) = r.full_header()?;
+        for _ in 0..r.u32()? {
+            let sample_count = r.u32()?;
+            let raw = r.u32()?;
+            let offset = if version == 1 { i64::from(raw.cast_signed()) } else { i64::from(raw) };
+            for _ in 0..sample_count {
+                if composition_offsets.len() == sizes.len() {
+                    break;
+                }
+                composition_offsets.push(offset);
+            }
+        }
+    }
+
+    let mut samples = Vec::with_capacity(sizes.len());
+    let mut sample_index = 0usize;
+    let mut decode_time = 0u64;
+    for (chunk_index, &chunk_offset) in chunk_offsets.iter().enumerate() {
+        let chunk_number = u32::try_from(chunk_index + 1).unwrap_or(u32::MAX);
+        let samples_per_chunk = stsc_entries
+            .iter()
+            .take_while(|(first_chunk, _)| *first_chunk <= chunk_number)
+            .last()
+            .map_or(1, |(_, n)| *n);
+        let mut offset = chunk_offset;
+        for _ in 0..samples_per_chunk {
+            let Some(&size) = sizes.get(sample_index) else { break };
+            let duration = durations.get(sample_index).copied().unwrap_or(0);
+            samples.push(SampleLocation {
+                offset,
+                size,
+                decode_time,
+                composition_offset: composition_offsets.get(sample_index).copied().unwrap_or(0),
+                duration,
+            });
+            offset += u64::from(size);
+            decode_time += u64::from(duration);
+            sample_index += 1;
+        }
+    }
+    samples.sort_by_key(|s| s.offset);
+    Ok(samples)
+}
+
+/// Parses a `moof` starting at absolute stream offset `moof_start`, returning the
+/// selected track's samples.
+fn parse_moof(
+    moof: &[u8],
+    moof_start: u64,
+    track: &mut DemuxTrack,
+) -> Result<Vec<SampleLocation>, String> {
+    let mut samples = Vec::new();
+    for child in child_boxes(moof) {
+        let (kind, traf) = child?;
+        if &kind != b"traf" {
+            continue;
+        }
+        let Some(tfhd) = find_child(traf, b"tfhd")? else { continue };
+        let mut r = BoxReader::new(tfhd);
+        let (_, flags) = r.full_header()?;
+        if r.u32()? != track.id {
+            continue;
+        }
+        let base_offset = if flags & 0x01 != 0 { r.u64()? } else { moof_start };
+        if flags & 0x02 != 0 {
+            r.u32()?; // sample_description_index
+        }
+        let default_duration = if flags & 0x08 != 0 { r.u32()? } else { track.default_duration };
+        let default_size = if flags & 0x10 != 0 { r.u32()? } else { track.default_size };
+        // default_sample_flags is the last field; sync flags are not needed for extraction.
+
+        if let Some(tfdt) = find_child(traf, b"tfdt")? {
+            let mut r = BoxReader::new(tfdt);
+            let (version, _) = r.full_header()?;
+            track.next_decode_time = if version == 1 { r.u64()? } else { u64::from(r.u32()?) };
+        }
+
+        let mut next_offset = base_offset;
+        for child in child_boxes(traf) {
+            let (kind, trun) = child?;
+            if &kind != b"trun" {
+                continue;
+            }
+            let mut r = BoxReader::new(trun);
+            let (version, flags) = r.full_header()?;
+            let count = r.u32()?;
+            if flags & 0x01 != 0 {
+                let data_offset = i64::from(r.u32()?.cast_signed());
+                next_offset = base_offset
+                    .checked_add_signed(data_offset)
+                    .ok_or("MP4 trun data offset is out of range")?;
+            }
+            if flags & 0x04 != 0 {
+                r.u32()?; // first_sample_flags
+            }
+            for _ in 0..count {
+                let duration = if flags & 0x100 != 0 { r.u32()? } else { default_duration };
+                let size = if flags & 0x200 != 0 { r.u32()? } else { default_size };
+                if flags & 0x400 != 0 {
+                    r.u32()?; // sample_flags
+                }
+                let composition_offset = if flags & 0x800 != 0 {
+                    let raw = r.u32()?;
+                    if version == 0 {
+                        i64::from(raw)
+                    } else {
+                        i64::from(raw.cast_signed())
+                    }
+                } else {
+                    0
+                };
+                samples.push(SampleLocation {
+                    offset: next_offset,
+                    size,
+                    decode_time: track.next_decode_time,
+                    composition_offset,
+                    duration,
+                });
+                next_offset += u64::from(size);
+                track.next_decode_time += u64::from(duration);
+            }
+        }
+    }
+    Ok(samples)
+}
+
+/// Where the demuxer is in the box stream.
+enum DemuxState {
+    /// Expecting a box header at the current position.
+    BoxHeader,
+    /// Skipping the rest of an uninteresting box.
+    Skip { until: u64 },
+    /// Inside an `mdat` ending at `end` (`None`: runs to end of stream).
+    Mdat { end: Option<u64> },
+}
+
+/// A demuxed sample ready to be emitted.
+struct DemuxedSample {
+    data: Bytes,
+    timestamp_us: u64,
+    duration_us: u64,
+}
+
+/// Incremental, non-seeking MP4 demuxer for one track.
+struct StreamDemuxer {
+    codec: Mp4Codec,
+    buf: BytesMut,
+    /// Absolute stream offset of `buf[0]`.
+    buf_start: u64,
+    state: DemuxState,
+    track: Option<DemuxTrack>,
+    samples: VecDeque<SampleLocation>,
+}
+
+impl StreamDemuxer {
+    fn new(codec: Mp4Codec) -> Self {
+        Self {
+            codec,
+            buf: BytesMut::new(),
+            buf_start: 0,
+            state: DemuxState::BoxHeader,
+            track: None,
+            samples: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    fn buf_end(&self) -> u64 {
+        self.buf_start + self.buf.len() as u64
+    }
+
+    /// Drops buffered bytes up to absolute offset `to`.
+    fn discard_to(&mut self, to: u64) {
+        let n = usize::try_from(to.saturating_sub(self.buf_start))
+            .unwrap_or(usize::MAX)
+            .min(self.buf.len());
+        self.buf.advance(n);
+        self.buf_start += n as u64;
+    }
+
+    /// Advances through the buffered bytes until a sample is complete or more input is needed.
+    fn next_sample(&mut self) -> Result<Option<DemuxedSample>, String> {
+        loop {
+            match self.state {
+                DemuxState::Skip { until } => {
+                    self.discard_to(until);
+                    if self.buf_start < until {
+                        return Ok(None);
+                    }
+                    self.state = DemuxState::BoxHeader;
+                },
+                DemuxState::BoxHeader => {
+                    if !self.read_box()? {
+                        return Ok(None);
+                    }
+                },
+                DemuxState::Mdat { end } => {
+                    if let Some(sample) = self.next_in_mdat(end)? {
+                        return Ok(Some(sample));
+                    }
+                    if end.is_some_and(|end| self.buf_start >= end) {
+                        self.state = DemuxState::BoxHeader;
+                    } else {
+                        return Ok(None);
+                    }
+                },
+            }
+        }
+    }
+
+    /// Handles the box at the current position. Returns `false` if more data is needed.
+    fn read_box(&mut self) -> Result<bool, String> {
+        if self.buf.len() < 8 {
+            return Ok(false);
+        }
+        let size32 =
+            u64::from(u32::from_be_bytes([self.buf[0], self.buf[1], self.buf[2], self.buf[3]]));
+        let fourcc = [self.buf[4], self.buf[5], self.buf[6], self.buf[7]];
+        let (header_len, size) = match size32 {
+            0 => (8u64, None),
+            1 => {
+                if self.buf.len() < 16 {
+                    return Ok(false);
+                }
+                let mut b = [0u8; 8];
+                b.copy_from_slice(&self.buf[8..16]);
+                (16, Some(u64::from_be_bytes(b)))
+            },
+            size => (8, Some(size)),
+        };
+        if size.is_some_and(|size| size < header_len) {
+            return Err(format!("Invalid size for MP4 box '{}'", String::from_utf8_lossy(&fourcc)));
+        }
+        let box_start = self.buf_start;
+
+        match &fourcc {
+            b"mdat" => {
+                if self.track.is_none() {
+                    return Err("MP4 'mdat' precedes 'moov'; streaming demux needs a fragmented \
+                                or faststart file"
+                        .to_string());
+                }
+                self.discard_to(box_start + header_len);
+                self.state = DemuxState::Mdat { end: size.map(|size| box_start + size) };
+            },
+            b"moov" | b"moof" => {
+                let Some(size) = size.filter(|&size| size <= MAX_METADATA_BOX_SIZE) else {
+                    return Err(format!(
+                        "MP4 '{}' box is too large to demux",
+                        String::from_utf8_lossy(&fourcc)
+                    ));
+                };
+                let size = usize::try_from(size).map_err(|e| e.to_string())?;
+                if self.buf.len() < size {
+                    return Ok(false);
+                }
+                let header = usize::try_from(header_len).map_err(|e| e.to_string())?;
+                let payload = self.buf.split_to(size).split_off(header);
+                self.buf_start += size as u64;
+                if &fourcc == b"moov" {
+                    self.on_moov(&payload)?;
+                } else {
+                    self.on_moof(&payload, box_start)?;
+                }
+            },
+            _ => match size {
+                Some(size) => self.state = DemuxState::Skip { until: box_start + size },
+                None => self.state = DemuxState::Skip { until: u64::MAX },
+            },
+        }
+        Ok(true)
+    }
+
+    fn on_moov(&mut self, moov: &[u8]) -> Result<(), String> {
+        let (mut track, samples) = parse_moov(moov, self.codec)?;
+        if let Some(trex) = find_child(moov, b"mvex")?.and_then(|mvex| {
+            child_boxes(mvex).find_map(|child| match child {
+                Ok((kind, trex))
+                    if &kind == b"trex" && trex.get(4..8) == Some(&track.id.to_be_bytes()[..]) =>
+                {
+                    Some(trex)
+                },
+                _ => None,
+            })
+        }) {
+            let mut r = BoxReader::new(trex);
+            r.full_header()?;
+            r.skip(8)?; // track_ID, default_sample_description_index
+            track.default_duration = r.u32()?;
+            track.default_size = r.u32()?;
+        }
+        tracing::info!(
+            "MP4 demuxer selected track {} ({:?}, timescale {}, {} indexed samples)",
+            track.id,
+            track.codec,
+            track.timescale,
+            samples.len()
+        );
+        self.samples.extend(samples);
+        self.track = Some(track);
+        Ok(())
+    }
+
+    fn on_moof(&mut self, moof: &[u8], moof_start: u64) -> Result<(), String> {
+        let Some(track) = self.track.as_mut() else {
+            return Err("MP4 'moof' precedes 'moov'".to_string());
+        };
+        let samples = parse_moof(moof, moof_start, track)?;
+        self.samples.extend(samples);
+        Ok(())
+    }
+
+    /// Emits the next selected-track sample inside the current `mdat`, skipping other bytes.
+    fn next_in_mdat(&mut self, end: Option<u64>) -> Result<Option<DemuxedSample>, String> {
+        let limit = end.unwrap_or(u64::MAX);
+        while let Some(&sample) = self.samples.front() {
+            if sample.offset < self.buf_start {
+                tracing::warn!(
+                    "MP4 sample at offset {} was already passed, dropping",
+                    sample.offset
+                );
+                self.samples.pop_front();
+                continue;
+            }
+            let sample_end = sample.offset + u64::from(sample.size);
+            if sample_end > limit {
+                // Belongs to a later mdat.
+                break;
+            }
+            self.discard_to(sample.offset);
+            if self.buf_end() < sample_end {
+                return Ok(None);
+            }
+            self.samples.pop_front();
+            let size = sample.size as usize;
+            let data = self.buf.split_to(size).freeze();
+            self.buf_start = sample_end;
+            return self.finish_sample(data, sample).map(Some);
+        }
+        self.discard_to(limit);
+        Ok(None)
+    }
+
+    fn finish_sample(&self, data: Bytes, sample: SampleLocation) -> Result<DemuxedSample, String> {
+        let track = self.track.as_ref().ok_or("MP4 sample without a track")?;
+        let presentation = sample.decode_time.saturating_add_signed(sample.composition_offset);
+        let data = if track.codec == Mp4Codec::Aac {
+            let header = adts_header(&track.asc, data.len())?;
+            let mut framed = Vec::with_capacity(header.len() + data.len());
+            framed.extend_from_slice(&header);
+            framed.extend_from_slice(&data);
+            Bytes::from(framed)
+        } else {
+            data
+        };
+        Ok(DemuxedSample {
+            data,
+            timestamp_us: ticks_to_us(presentation, track.timescale),
+            duration_us: ticks_to_us(u64::from(sample.duration), track.timescale),
+        })
+    }
+
+    /// Samples that were indexed but never reached before the stream ended.
+    fn missing_samples(&self) -> usize {
+        self.samples.len()
+    }
+}
+
+/// A node that extracts one Opus, AAC, VP9 or AV1 track from an MP4 byte stream.
+pub struct Mp4DemuxerNode {
+    config: Mp4DemuxerConfig,
+}
+
+impl Mp4DemuxerNode {
+    pub const fn new(config: Mp4DemuxerConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for Mp4DemuxerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Binary],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: self.config.codec.packet_type(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        tracing::info!("Mp4DemuxerNode starting ({:?} track)", self.config.codec);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut demuxer = StreamDemuxer::new(self.config.codec);
+        let mut sequence = 0u64;
+
+        'input: while let Some(packet) = context.recv_with_cancellation(&mut input_rx).await {
+            let Packet::Binary { data, .. } = packet else {
+                stats_tracker.discarded();
+                continue;
+            };
+            stats_tracker.received();
+            demuxer.push(&data);
+
+            loop {
+                let sample = match demuxer.next_sample() {
+                    Ok(Some(sample)) => sample,
+                    Ok(None) => break,
+                    Err(e) => {
+                        stats_tracker.errored();
+                        stats_tracker.force_send();
+                        let err_msg = format!("MP4 demux error: {e}");
+                        state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                        return Err(StreamKitError::Runtime(err_msg));
+                    },
+                };
+                sequence += 1;
+                let metadata = PacketMetadata {
+                    timestamp_us: Some(sample.timestamp_us),
+                    duration_us: Some(sample.duration_us),
+                    sequence: Some(sequence),
+                };
+                let packet = Packet::Binary {
+                    data: sample.data,
+                    content_type: None,
+                    metadata: Some(metadata),
+                };
+                if context.output_sender.send("out", packet).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping demuxer");
+                    break 'input;
+                }
+                stats_tracker.sent();
+            }
+            stats_tracker.maybe_send();
+        }
+
+        if demuxer.missing_samples() > 0 {
+            tracing::warn!(
+                "MP4 stream ended with {} indexed samples not received",
+                demuxer.missing_samples()
+            );
+        }
+        stats_tracker.force_send();
+        tracing::info!("Mp4DemuxerNode finished, extracted {} samples", sequence);
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mp4")]
+use schemars::schema_for;
+#[cfg(feature = "mp4")]
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the MP4 container nodes.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON or the default muxer config is
+/// invalid (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+#[cfg_attr(not(feature = "mp4"), allow(unused_variables, clippy::missing_const_for_fn))]
+pub fn register_mp4_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "mp4")]
+    {
+        let default_muxer = Mp4MuxerNode::new(Mp4MuxerConfig::default())
+            .expect("default MP4 muxer config should be valid");
+        registry.register_static_with_description(
+            "containers::mp4::muxer",
+            |params| {
+                let config = config_helpers::parse_config_with_context(params, "Mp4Muxer")?;
+                Ok(Box::new(Mp4MuxerNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(Mp4MuxerConfig))
+                .expect("Mp4MuxerConfig schema should serialize to JSON"),
+            StaticPins { inputs: default_muxer.input_pins(), outputs: default_muxer.output_pins() },
+            vec!["containers".to_string(), "mp4".to_string()],
+            false,
+            "Muxes Opus/AAC audio and VP9/AV1 video into fragmented MP4. \
+             Emits an init segment followed by moof/mdat fragments as they complete. \
+             With `chapters` enabled, chapter markers are embedded as emsg boxes.",
+        );
+
+        let default_demuxer = Mp4DemuxerNode::new(Mp4DemuxerConfig::default());
+        registry.register_static_with_description(
+            "containers::mp4::demuxer",
+            |params| {
+                let config = config_helpers::parse_config_optional(params)?;
+                Ok(Box::new(Mp4DemuxerNode::new(config)))
+            },
+            serde_json::to_value(schema_for!(Mp4DemuxerConfig))
+                .expect("Mp4DemuxerConfig schema should serialize to JSON"),
+            StaticPins {
+                inputs: default_demuxer.input_pins(),
+                outputs: default_demuxer.output_pins(),
+            },
+            vec!["containers".to_string(), "mp4".to_string()],
+            false,
+            "Demuxes fragmented or faststart MP4 and extracts one Opus, AAC, VP9 or AV1 track. \
+             Streams samples without buffering the whole file.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped,
+        create_test_binary_packet, create_test_context,
+    };
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn packet(data: Vec<u8>, timestamp_us: u64, duration_us: u64) -> Packet {
+        Packet::Binary {
+            data: Bytes::from(data),
+            content_type: None,
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(timestamp_us),
+                duration_us: Some(duration_us),
+                sequence: None,
+            }),
+        }
+    }
+
+    fn payload(index: usize, len: usize) -> Vec<u8> {
+        (0..len).map(|i| u8::try_from((index * 7 + i) % 251).unwrap()).collect()
+    }
+
+    /// Runs the demuxer over `stream` in small chunks and returns its output packets.
+    async fn demux(stream: &[u8], codec: Mp4Codec) -> Vec<Packet> {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let node = Mp4DemuxerNode::new(Mp4DemuxerConfig { codec });
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for chunk in stream.chunks(173) {
+            input_tx.send(create_test_binary_packet(chunk.to_vec())).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    fn concat(packets: &[Packet]) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for packet in packets {
+            let Packet::Binary { data, .. } = packet else { panic!("expected binary output") };
+            stream.extend_from_slice(data);
+        }
+        stream
+    }
+
+    fn sample_data(packet: &Packet) -> (&[u8], PacketMetadata) {
+        let Packet::Binary { data, metadata, .. } = packet else { panic!("expected binary") };
+        (data, metadata.clone().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_mp4_opus_fragments_stream_before_eof_and_round_trip() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let config = Mp4MuxerConfig { fragment_duration_ms: 500, ..Mp4MuxerConfig::default() };
+        let node = Mp4MuxerNode::new(config).unwrap();
+        assert_eq!(node.content_type().as_deref(), Some("audio/mp4; codecs=\"opus\""));
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 2 seconds of 20 ms packets.
+        let frames: Vec<Vec<u8>> = (0..100).map(|i| payload(i, 40 + i % 13)).collect();
+        for (i, frame) in frames.iter().take(30).enumerate() {
+            input_tx.send(packet(frame.clone(), i as u64 * 20_000, 20_000)).await.unwrap();
+        }
+
+        // The first fragment is out while the input is still open.
+        let mut output = Vec::new();
+        for _ in 0..2 {
+            let (_, pin, packet) = mock_sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+            assert_eq!(pin, "out");
+            output.push(packet);
+        }
+        assert_eq!(&concat(&output[..1])[4..8], b"ftyp");
+        assert_eq!(&concat(&output[1..])[4..8], b"moof");
+
+        for (i, frame) in frames.iter().enumerate().skip(30) {
+            input_tx.send(packet(frame.clone(), i as u64 * 20_000, 20_000)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        output.extend(mock_sender.get_packets_for_pin("out").await);
+        // Init segment + four 500 ms fragments.
+        assert_eq!(output.len(), 5);
+
+        let demuxed = demux(&concat(&output), Mp4Codec::Opus).await;
+        assert_eq!(demuxed.len(), frames.len());
+        for (i, (packet, frame)) in demuxed.iter().zip(&frames).enumerate() {
+            let (data, metadata) = sample_data(packet);
+            assert_eq!(data, frame.as_slice());
+            assert_eq!(metadata.timestamp_us, Some(i as u64 * 20_000));
+            assert_eq!(metadata.duration_us, Some(20_000));
+            assert_eq!(metadata.sequence, Some(i as u64 + 1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mp4_chapter_markers_become_emsg_boxes() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let (chapters_tx, chapters_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        inputs.insert("chapters".to_string(), chapters_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let config = Mp4MuxerConfig {
+            fragment_duration_ms: 500,
+            chapters: true,
+            ..Mp4MuxerConfig::default()
+        };
+        let node = Mp4MuxerNode::new(config).unwrap();
+        assert!(node.input_pins().iter().any(|pin| pin.name == "chapters"));
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        let marker = serde_json::json!({ "index": 2, "start_ms": 640, "title": "Chapter 2" });
+        chapters_tx
+            .send(Packet::Custom(std::sync::Arc::new(CustomPacketData {
+                type_id: CHAPTER_MARKER_TYPE_ID.to_string(),
+                encoding: streamkit_core::types::CustomEncoding::Json,
+                data: marker.clone(),
+                metadata: None,
+            })))
+            .await
+            .unwrap();
+        drop(chapters_tx);
+        let frames: Vec<Vec<u8>> = (0..50).map(|i| payload(i, 40)).collect();
+        for (i, frame) in frames.iter().enumerate() {
+            input_tx.send(packet(frame.clone(), i as u64 * 20_000, 20_000)).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let stream = concat(&mock_sender.get_packets_for_pin("out").await);
+        let emsgs: Vec<&[u8]> = child_boxes(&stream)
+            .map(Result::unwrap)
+            .filter(|(fourcc, _)| fourcc == b"emsg")
+            .map(|(_, payload)| payload)
+            .collect();
+        assert_eq!(emsgs.len(), 1);
+
+        let mut reader = BoxReader::new(emsgs[0]);
+        assert_eq!(reader.full_header().unwrap().0, 1);
+        assert_eq!(reader.u32().unwrap(), 1000);
+        assert_eq!(reader.u64().unwrap(), 640);
+        assert_eq!(reader.u32().unwrap(), u32::MAX);
+        assert_eq!(reader.u32().unwrap(), 2);
+        let rest = reader.remaining();
+        let scheme_end = CHAPTER_SCHEME_URI.len();
+        assert_eq!(&rest[..scheme_end], CHAPTER_SCHEME_URI.as_bytes());
+        assert_eq!(&rest[scheme_end..scheme_end + 2], &[0, 0]);
+        let data: serde_json::Value = serde_json::from_slice(&rest[scheme_end + 2..]).unwrap();
+        assert_eq!(data, marker);
+
+        // Demuxers skip the event boxes.
+        assert_eq!(demux(&stream, Mp4Codec::Opus).await.len(), frames.len());
+    }
+
+    #[tokio::test]
+    async fn test_mp4_aac_adts_round_trip() {
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let config = Mp4MuxerConfig {
+            tracks: vec![Mp4TrackConfig { codec: Mp4Codec::Aac, ..Mp4TrackConfig::default() }],
+            ..Mp4MuxerConfig::default()
+        };
+        let node = Mp4MuxerNode::new(config).unwrap();
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 44.1 kHz stereo ADTS overrides the configured 48 kHz.
+        let asc = audio_specific_config(2, 4, 2);
+        let frames: Vec<Vec<u8>> = (0..50)
+            .map(|i| {
+                let body = payload(i, 100 + i);
+                let mut frame = adts_header(&asc, body.len()).unwrap().to_vec();
+                frame.extend_from_slice(&body);
+                frame
+            })
+            .collect();
+        for frame in &frames {
+            input_tx.send(create_test_binary_packet(frame.clone())).await.unwrap();
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let output = mock_sender.get_packets_for_pin("out").await;
+        let demuxed = demux(&concat(&output), Mp4Codec::Aac).await;
+        assert_eq!(demuxed.len(), frames.len());
+        for (i, (packet, frame)) in demuxed.iter().zip(&frames).enumerate() {
+            let (data, metadata) = sample_data(packet);
+            assert_eq!(data, frame.as_slice());
+            assert_eq!(metadata.timestamp_us, Some(ticks_to_us(i as u64 * 1024, 44100)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mp4_vp9_and_opus_tracks() {
+        let (video_tx, video_rx) = mpsc::channel(10);
+        let (audio_tx, audio_rx) = mpsc::channel(10);
+        let mut inputs = HashMap::new();
+        inputs.insert("in_0".to_string(), video_rx);
+        inputs.insert("in_1".to_string(), audio_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+        let config = Mp4MuxerConfig {
+            tracks: vec![
+                Mp4TrackConfig { codec: Mp4Codec::Vp9, ..Mp4TrackConfig::default() },
+                Mp4TrackConfig::default(),
+            ],
+            fragment_duration_ms: 400,
+            ..Mp4MuxerConfig::default()
+        };
+        let node = Mp4MuxerNode::new(config).unwrap();
+        assert_eq!(
+            node.content_type().as_deref(),
+            Some("video/mp4; codecs=\"vp09.00.31.08,opus\"")
+        );
+        let pins: Vec<String> = node.input_pins().into_iter().map(|p| p.name).collect();
+        assert_eq!(pins, ["in_0", "in_1"]);
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        // 1 second at 30 fps with a key frame every 15 frames (0x82: key, 0x86: inter).
+        let video: Vec<Vec<u8>> = (0..30)
+            .map(|i| {
+                let mut frame = payload(i, 300);
+                frame[0] = if i % 15 == 0 { 0x82 } else { 0x86 };
+                frame
+            })
+            .collect();
+        let audio: Vec<Vec<u8>> = (0..50).map(|i| payload(i, 60)).collect();
+        let video_frames = video.clone();
+        let video_task = tokio::spawn(async move {
+            for (i, frame) in video_frames.into_iter().enumerate() {
+                video_tx.send(packet(frame, i as u64 * 100_000 / 3, 33_333)).await.unwrap();
+            }
+        });
+        for (i, frame) in audio.iter().enumerate() {
+            audio_tx.send(packet(frame.clone(), i as u64 * 20_000, 20_000)).await.unwrap();
+        }
+        drop(audio_tx);
+        video_task.await.unwrap();
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+
+        let stream = concat(&mock_sender.get_packets_for_pin("out").await);
+        let demuxed_video = demux(&stream, Mp4Codec::Vp9).await;
+        assert_eq!(demuxed_video.len(), video.len());
+        for (packet, frame) in demuxed_video.iter().zip(&video) {
+            assert_eq!(sample_data(packet).0, frame.as_slice());
+        }
+        assert_eq!(sample_data(&demuxed_video[15]).1.timestamp_us, Some(500_000));
+
+        let demuxed_audio = demux(&stream, Mp4Codec::Opus).await;
+        assert_eq!(demuxed_audio.len(), audio.len());
+        for (packet, frame) in demuxed_audio.iter().zip(&audio) {
+            assert_eq!(sample_data(packet).0, frame.as_slice());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mp4_demux_faststart_file() {
+        let samples: Vec<Vec<u8>> = (0..6).map(|i| payload(i, 20 + i)).collect();
+
+        // Two chunks of three samples each, stored after the moov.
+        let build = |mdat_start: u32| {
+            let mut w = BoxWriter::default();
+            w.begin(b"ftyp");
+            w.bytes(b"isom");
+            w.u32(0);
+            w.end();
+            w.begin(b"moov");
+            w.begin(b"trak");
+            w.begin_full(b"tkhd", 0, 3);
+            w.zeros(8);
+            w.u32(7); // track_ID
+            w.zeros(68);
+            w.end();
+            w.begin(b"mdia");
+            w.begin_full(b"mdhd", 0, 0);
+            w.zeros(8);
+            w.u32(48000);
+            w.zeros(8);
+            w.end();
+            w.begin(b"minf");
+            w.begin(b"stbl");
+            w.begin_full(b"stsd", 0, 0);
+            w.u32(1);
+            w.begin(b"Opus");
+            w.zeros(28);
+            w.end();
+            w.end();
+            w.begin_full(b"stts", 0, 0);
+            w.u32(1);
+            w.u32(6);
+            w.u32(960);
+            w.end();
+            w.begin_full(b"stsc", 0, 0);
+            w.u32(1);
+            w.u32(1);
+            w.u32(3);
+            w.u32(1);
+            w.end();
+            w.begin_full(b"stsz", 0, 0);
+            w.u32(0);
+            w.u32(6);
+            for sample in &samples {
+                w.u32(u32::try_from(sample.len()).unwrap());
+            }
+            w.end();
+            w.begin_full(b"stco", 0, 0);
+            w.u32(2);
+            let first_chunk: usize = samples[..3].iter().map(Vec::len).sum();
+            w.u32(mdat_start + 8);
+            w.u32(mdat_start + 8 + u32::try_from(first_chunk).unwrap());
+            w.end();
+            w.end(); // stbl
+            w.end(); // minf
+            w.end(); // mdia
+            w.end(); // trak
+            w.end(); // moov
+            w
+        };
+        let mdat_start = u32::try_from(build(0).len()).unwrap();
+        let mut w = build(mdat_start);
+        w.begin(b"mdat");
+        for sample in &samples {
+            w.bytes(sample);
+        }
+        w.end();
+
+        let demuxed = demux(&w.buf, Mp4Codec::Opus).await;
+        assert_eq!(demuxed.len(), samples.len());
+        for (i, (packet, sample)) in demuxed.iter().zip(&samples).enumerate() {
+            let (data, metadata) = sample_data(packet);
+            assert_eq!(data, sample.as_slice());
+            assert_eq!(metadata.timestamp_us, Some(i as u64 * 20_000));
+        }
+    }
+
+    #[test]
+    fn test_av1_temporal_delimiters_are_stripped() {
+        // Temporal delimiter, sequence header (2 bytes), frame (3 bytes); all with size fields.
+        let unit = Bytes::from_static(&[0x12, 0x00, 0x0A, 0x02, 0xAA, 0xBB, 0x32, 0x03, 1, 2, 3]);
+        let (sample, keyframe) = av1_prepare_sample(&unit);
+        assert!(keyframe);
+        assert_eq!(&sample[..], &unit[2..]);
+
+        let (sample, keyframe) = av1_prepare_sample(&unit.slice(6..));
+        assert!(!keyframe);
+        assert_eq!(&sample[..], &unit[6..]);
+    }
+}
@@ -0,0 +1,1033 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! MPEG-TS muxer node.
+//!
+//! Packetizes AAC, Opus and H.264 elementary streams into a single-program transport stream
+//! for SRT/UDP contribution and other broadcast tooling. PAT/PMT are repeated on a
+//! configurable interval (and ahead of every video key frame), and the PCR is carried on the
+//! video track when there is one, otherwise on the first track.
+//!
+//! Output is emitted in whole 188-byte TS packets, grouped in chunks of `chunk_packets`
+//! (7 × 188 = 1316 bytes by default, the usual UDP/SRT payload).
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use tokio::sync::mpsc;
+
+use super::adts::{aac_sample_rate_index, adts_header, audio_specific_config, parse_adts_header};
+
+// --- MPEG-TS Constants ---
+
+const TS_PACKET_SIZE: usize = 188;
+const TS_PAYLOAD_SIZE: usize = TS_PACKET_SIZE - 4;
+const SYNC_BYTE: u8 = 0x47;
+
+const PAT_PID: u16 = 0x0000;
+const PMT_PID: u16 = 0x1000;
+/// PID of the first elementary stream; later tracks follow sequentially.
+const FIRST_STREAM_PID: u16 = 0x0100;
+const PROGRAM_NUMBER: u16 = 1;
+const TRANSPORT_STREAM_ID: u16 = 1;
+
+/// 90 kHz system clock used for PTS/PCR bases.
+const CLOCK_HZ: u64 = 90_000;
+/// PTS lead over the PCR, giving decoders time to buffer (700 ms).
+const PTS_OFFSET_TICKS: u64 = 63_000;
+
+const DEFAULT_PSI_INTERVAL_MS: u64 = 100;
+const DEFAULT_PCR_INTERVAL_MS: u64 = 40;
+/// ISO/IEC 13818-1 requires a PCR at least every 100 ms.
+const MAX_PCR_INTERVAL_MS: u64 = 100;
+const DEFAULT_CHUNK_PACKETS: usize = 7;
+
+/// Fallback frame durations in 90 kHz ticks when packets carry no timing metadata.
+const DEFAULT_OPUS_FRAME_TICKS: u64 = 1800;
+const DEFAULT_VIDEO_FRAME_TICKS: u64 = 3000;
+const AAC_FRAME_SAMPLES: u64 = 1024;
+
+/// H.264 access unit delimiter NAL (primary_pic_type = any), which TS receivers expect at the
+/// start of each access unit.
+const H264_AUD: [u8; 6] = [0x00, 0x00, 0x00, 0x01, 0x09, 0xF0];
+
+/// Codec of an MPEG-TS elementary stream.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TsCodec {
+    /// AAC audio; ADTS frames pass through, raw access units get an ADTS header
+    Aac,
+    /// Opus audio (ETSI TS 102 366 Annex "Opus in MPEG-TS" mapping)
+    #[default]
+    Opus,
+    /// H.264 video in Annex B byte-stream format, one access unit per packet
+    H264,
+}
+
+impl TsCodec {
+    const fn is_video(self) -> bool {
+        matches!(self, Self::H264)
+    }
+
+    const fn stream_type(self) -> u8 {
+        match self {
+            Self::Aac => 0x0F,
+            // PES private data, identified by the registration descriptor
+            Self::Opus => 0x06,
+            Self::H264 => 0x1B,
+        }
+    }
+
+    const fn stream_id(self) -> u8 {
+        match self {
+            Self::Aac => 0xC0,
+            Self::Opus => 0xBD,
+            Self::H264 => 0xE0,
+        }
+    }
+
+    const fn packet_type(self) -> PacketType {
+        match self {
+            Self::Opus => PacketType::OpusAudio,
+            Self::Aac | Self::H264 => PacketType::Binary,
+        }
+    }
+}
+
+/// One elementary stream of the MPEG-TS muxer.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct TsTrackConfig {
+    /// Codec of the access units arriving on this track's input pin
+    pub codec: TsCodec,
+    /// Audio sample rate in Hz (AAC: used for raw access units without ADTS headers)
+    pub sample_rate: u32,
+    /// Number of audio channels (Opus: 1 or 2)
+    pub channels: u8,
+}
+
+impl Default for TsTrackConfig {
+    fn default() -> Self {
+        Self { codec: TsCodec::Opus, sample_rate: 48000, channels: 2 }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+#[serde(default)]
+pub struct MpegTsMuxerConfig {
+    /// Elementary streams, in order. With one track the input pin is `in`; with several they
+    /// are `in_0`, `in_1`, ... in the same order. Stream PIDs start at 0x100.
+    pub tracks: Vec<TsTrackConfig>,
+    /// How often PAT and PMT are repeated, in milliseconds of stream time
+    pub psi_interval_ms: u64,
+    /// Maximum spacing of PCR values, in milliseconds of stream time (at most 100)
+    pub pcr_interval_ms: u64,
+    /// Number of 188-byte TS packets per output packet (7 fits a 1316-byte UDP/SRT payload)
+    pub chunk_packets: usize,
+}
+
+impl Default for MpegTsMuxerConfig {
+    fn default() -> Self {
+        Self {
+            tracks: vec![TsTrackConfig::default()],
+            psi_interval_ms: DEFAULT_PSI_INTERVAL_MS,
+            pcr_interval_ms: DEFAULT_PCR_INTERVAL_MS,
+            chunk_packets: DEFAULT_CHUNK_PACKETS,
+        }
+    }
+}
+
+// --- Packet writing ---
+
+/// CRC-32/MPEG-2 as used by PSI sections.
+fn crc32_mpeg2(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 { (crc << 1) ^ 0x04C1_1DB7 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Encodes a 33-bit timestamp in the PES PTS layout with the given 4-bit prefix.
+fn pes_timestamp(prefix: u8, ts: u64) -> [u8; 5] {
+    // 33 bits split 3/15/15, each group followed by a marker bit
+    let hi = u8::try_from((ts >> 30) & 0x07).unwrap_or_default();
+    let mid = u16::try_from((ts >> 15) & 0x7FFF).unwrap_or_default();
+    let lo = u16::try_from(ts & 0x7FFF).unwrap_or_default();
+    let [m1, m2] = ((mid << 1) | 1).to_be_bytes();
+    let [l1, l2] = ((lo << 1) | 1).to_be_bytes();
+    [(prefix << 4) | (hi << 1) | 1, m1, m2, l1, l2]
+}
+
+/// Encodes a 27 MHz PCR value as the 6-byte adaptation field layout.
+const fn pcr_bytes(pcr: u64) -> [u8; 6] {
+    let base = (pcr / 300) & 0x1_FFFF_FFFF;
+    let ext = pcr % 300;
+    let packed = (base << 15) | (0x3F << 9) | ext;
+    let bytes = packed.to_be_bytes();
+    [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+}
+
+/// Appends TS packets to an output buffer, tracking a continuity counter per PID.
+struct TsWriter {
+    out: BytesMut,
+    pat_cc: u8,
+    pmt_cc: u8,
+}
+
+impl TsWriter {
+    fn new() -> Self {
+        Self { out: BytesMut::new(), pat_cc: 0, pmt_cc: 0 }
+    }
+
+    /// Writes one TS packet carrying as much of `payload` as fits and returns the number of
+    /// payload bytes consumed. Short payloads are padded with adaptation field stuffing.
+    fn write_packet(
+        &mut self,
+        pid: u16,
+        cc: &mut u8,
+        start: bool,
+        pcr: Option<u64>,
+        random_access: bool,
+        payload: &[u8],
+    ) -> usize {
+        // Adaptation field length byte + flags byte + optional PCR
+        let min_af = if pcr.is_some() {
+            8
+        } else if random_access {
+            2
+        } else {
+            0
+        };
+        let take = payload.len().min(TS_PAYLOAD_SIZE - min_af);
+        let af_total = TS_PAYLOAD_SIZE - take;
+
+        let [pid_hi, pid_lo] = pid.to_be_bytes();
+        let control = if af_total > 0 { 0x30 } else { 0x10 };
+        self.out.extend_from_slice(&[
+            SYNC_BYTE,
+            (u8::from(start) << 6) | (pid_hi & 0x1F),
+            pid_lo,
+            control | (*cc & 0x0F),
+        ]);
+        *cc = (*cc + 1) & 0x0F;
+
+        if af_total > 0 {
+            // af_total is at most TS_PAYLOAD_SIZE, so it fits a byte.
+            self.out.extend_from_slice(&[u8::try_from(af_total - 1).unwrap_or(u8::MAX)]);
+            if af_total > 1 {
+                let flags = (u8::from(random_access) << 6) | (u8::from(pcr.is_some()) << 4);
+                self.out.extend_from_slice(&[flags]);
+                let mut used = 2;
+                if let Some(pcr) = pcr {
+                    self.out.extend_from_slice(&pcr_bytes(pcr));
+                    used += 6;
+                }
+                self.out.resize(self.out.len() + (af_total - used), 0xFF);
+            }
+        }
+        self.out.extend_from_slice(&payload[..take]);
+        take
+    }
+
+    /// Writes a PSI section in a single TS packet (pointer field, section, 0xFF fill).
+    fn write_section(&mut self, pid: u16, section: &[u8]) {
+        let mut cc = if pid == PAT_PID { self.pat_cc } else { self.pmt_cc };
+        let [pid_hi, pid_lo] = pid.to_be_bytes();
+        self.out.extend_from_slice(&[SYNC_BYTE, 0x40 | (pid_hi & 0x1F), pid_lo, 0x10 | cc]);
+        cc = (cc + 1) & 0x0F;
+        if pid == PAT_PID {
+            self.pat_cc = cc;
+        } else {
+            self.pmt_cc = cc;
+        }
+        self.out.extend_from_slice(&[0x00]);
+        self.out.extend_from_slice(section);
+        self.out.resize(self.out.len() + (TS_PAYLOAD_SIZE - 1 - section.len()), 0xFF);
+    }
+
+    fn write_psi(&mut self, tracks: &[TsTrack], pcr_pid: u16) {
+        self.write_section(PAT_PID, &pat_section());
+        self.write_section(PMT_PID, &pmt_section(tracks, pcr_pid));
+    }
+
+    /// Writes a PES packet across as many TS packets as needed.
+    fn write_pes(&mut self, track: &mut TsTrack, pes: &[u8], pcr: Option<u64>, keyframe: bool) {
+        let mut offset = 0;
+        let mut first = true;
+        while offset < pes.len() {
+            let (pcr, random_access) = if first { (pcr, keyframe) } else { (None, false) };
+            offset += self.write_packet(
+                track.pid,
+                &mut track.cc,
+                first,
+                pcr,
+                random_access,
+                &pes[offset..],
+            );
+            first = false;
+        }
+    }
+}
+
+/// Wraps a PSI section body (from table_id_extension on) with its header and CRC.
+fn psi_section(table_id: u8, table_id_extension: u16, body: &[u8]) -> Vec<u8> {
+    // table_id_extension .. last_section_number (5 bytes) + body + CRC (4 bytes)
+    let section_length = u16::try_from(5 + body.len() + 4).unwrap_or(u16::MAX);
+    let mut section = Vec::with_capacity(3 + usize::from(section_length));
+    section.push(table_id);
+    section.extend_from_slice(&(0xB000 | section_length).to_be_bytes());
+    section.extend_from_slice(&table_id_extension.to_be_bytes());
+    section.extend_from_slice(&[0xC1, 0x00, 0x00]); // version 0, current, section 0 of 0
+    section.extend_from_slice(body);
+    let crc = crc32_mpeg2(&section);
+    section.extend_from_slice(&crc.to_be_bytes());
+    section
+}
+
+fn pat_section() -> Vec<u8> {
+    let mut body = Vec::with_capacity(4);
+    body.extend_from_slice(&PROGRAM_NUMBER.to_be_bytes());
+    body.extend_from_slice(&(0xE000 | PMT_PID).to_be_bytes());
+    psi_section(0x00, TRANSPORT_STREAM_ID, &body)
+}
+
+fn pmt_section(tracks: &[TsTrack], pcr_pid: u16) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&(0xE000 | pcr_pid).to_be_bytes());
+    body.extend_from_slice(&0xF000u16.to_be_bytes()); // no program descriptors
+    for track in tracks {
+        let descriptors: Vec<u8> = match track.codec {
+            TsCodec::Opus => vec![
+                // registration_descriptor: format_identifier "Opus"
+                0x05,
+                4,
+                b'O',
+                b'p',
+                b'u',
+                b's',
+                // extension_descriptor: Opus channel_config_code
+                0x7F,
+                2,
+                0x80,
+                track.channels,
+            ],
+            TsCodec::Aac | TsCodec::H264 => Vec::new(),
+        };
+        body.push(track.codec.stream_type());
+        body.extend_from_slice(&(0xE000 | track.pid).to_be_bytes());
+        let es_info_length = u16::try_from(descriptors.len()).unwrap_or_default();
+        body.extend_from_slice(&(0xF000 | es_info_length).to_be_bytes());
+        body.extend_from_slice(&descriptors);
+    }
+    psi_section(0x02, PROGRAM_NUMBER, &body)
+}
+
+/// Builds the PES packet for one access unit.
+fn pes_packet(codec: TsCodec, pts: u64, payload: &[u8]) -> Vec<u8> {
+    let header_data = pes_timestamp(0b0010, pts);
+    let pes_length = 3 + header_data.len() + payload.len();
+    // Video PES packets may be longer than the 16-bit length allows; 0 means "unbounded".
+    let length_field = if codec.is_video() { 0 } else { u16::try_from(pes_length).unwrap_or(0) };
+    let mut pes = Vec::with_capacity(6 + pes_length);
+    pes.extend_from_slice(&[0x00, 0x00, 0x01, codec.stream_id()]);
+    pes.extend_from_slice(&length_field.to_be_bytes());
+    // '10' marker, data_alignment_indicator; PTS only
+    pes.extend_from_slice(&[0x84, 0x80, 5]);
+    pes.extend_from_slice(&header_data);
+    pes.extend_from_slice(payload);
+    pes
+}
+
+/// Whether an Annex B H.264 access unit contains an IDR slice, and whether it already
+/// starts with an access unit delimiter.
+fn h264_scan(data: &[u8]) -> (bool, bool) {
+    let mut idr = false;
+    let mut first_nal = None;
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+            if let Some(&header) = data.get(i + 3) {
+                let nal_type = header & 0x1F;
+                first_nal.get_or_insert(nal_type);
+                idr |= nal_type == 5;
+            }
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    (idr, first_nal == Some(9))
+}
+
+fn us_to_clock(us: u64) -> u64 {
+    u64::try_from((u128::from(us) * u128::from(CLOCK_HZ) + 500_000) / 1_000_000).unwrap_or(u64::MAX)
+}
+
+// --- MPEG-TS Muxer ---
+
+/// Per-stream muxing state.
+struct TsTrack {
+    codec: TsCodec,
+    pid: u16,
+    cc: u8,
+    channels: u8,
+    /// AudioSpecificConfig used to frame raw AAC access units
+    asc: [u8; 2],
+    /// 90 kHz ticks per access unit when no timing metadata is available
+    default_duration: u64,
+    /// Decode time of the next access unit, in 90 kHz ticks (before the PTS offset)
+    next_dts: Option<u64>,
+}
+
+impl TsTrack {
+    fn new(index: usize, config: &TsTrackConfig) -> Self {
+        let pid = FIRST_STREAM_PID + u16::try_from(index).unwrap_or_default();
+        let default_duration = match config.codec {
+            TsCodec::Opus => DEFAULT_OPUS_FRAME_TICKS,
+            TsCodec::Aac => AAC_FRAME_SAMPLES * CLOCK_HZ / u64::from(config.sample_rate.max(1)),
+            TsCodec::H264 => DEFAULT_VIDEO_FRAME_TICKS,
+        };
+        Self {
+            codec: config.codec,
+            pid,
+            cc: 0,
+            channels: config.channels,
+            asc: audio_specific_config(
+                2,
+                aac_sample_rate_index(config.sample_rate).unwrap_or(3),
+                config.channels,
+            ),
+            default_duration,
+            next_dts: None,
+        }
+    }
+
+    /// Returns the decode time of an access unit and advances the track clock.
+    fn timestamp(&mut self, metadata: Option<&PacketMetadata>) -> u64 {
+        let dts = metadata
+            .and_then(|m| m.timestamp_us)
+            .map(us_to_clock)
+            .or(self.next_dts)
+            .unwrap_or_default();
+        let duration = metadata
+            .and_then(|m| m.duration_us)
+            .map(us_to_clock)
+            .filter(|&d| d > 0)
+            .unwrap_or(self.default_duration);
+        self.next_dts = Some(dts + duration);
+        dts
+    }
+
+    /// Converts an input packet into a PES payload, reporting whether it is a key frame.
+    fn elementary_payload(&self, data: &[u8]) -> Result<(Vec<u8>, bool), String> {
+        match self.codec {
+            TsCodec::Aac => {
+                if parse_adts_header(data).is_some() {
+                    Ok((data.to_vec(), true))
+                } else {
+                    let header = adts_header(&self.asc, data.len())?;
+                    let mut framed = Vec::with_capacity(header.len() + data.len());
+                    framed.extend_from_slice(&header);
+                    framed.extend_from_slice(data);
+                    Ok((framed, true))
+                }
+            },
+            TsCodec::Opus => {
+                // opus_control_header: 0x7FE0 prefix (no trim, no extension), then the
+                // access unit size as a run of 0xFF bytes plus a final remainder byte.
+                let mut payload = Vec::with_capacity(data.len() + 2 + data.len() / 255 + 1);
+                payload.extend_from_slice(&[0x7F, 0xE0]);
+                let mut size = data.len();
+                while size >= 255 {
+                    payload.push(0xFF);
+                    size -= 255;
+                }
+                payload.push(u8::try_from(size).unwrap_or_default());
+                payload.extend_from_slice(data);
+                Ok((payload, true))
+            },
+            TsCodec::H264 => {
+                let (idr, has_aud) = h264_scan(data);
+                let mut payload = Vec::with_capacity(data.len() + H264_AUD.len());
+                if !has_aud {
+                    payload.extend_from_slice(&H264_AUD);
+                }
+                payload.extend_from_slice(data);
+                Ok((payload, idr))
+            },
+        }
+    }
+}
+
+/// A node that muxes AAC, Opus and H.264 elementary streams into an MPEG transport stream.
+pub struct MpegTsMuxerNode {
+    config: MpegTsMuxerConfig,
+}
+
+impl MpegTsMuxerNode {
+    /// Creates a new MPEG-TS muxer node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no tracks are configured, intervals are out of range, or a track's
+    /// parameters are invalid.
+    pub fn new(config: MpegTsMuxerConfig) -> Result<Self, StreamKitError> {
+        if config.tracks.is_empty() {
+            return Err(StreamKitError::Configuration(
+                "MPEG-TS muxer needs at least one track".to_string(),
+            ));
+        }
+        if config.tracks.len() > 16 {
+            return Err(StreamKitError::Configuration(
+                "MPEG-TS muxer supports at most 16 tracks".to_string(),
+            ));
+        }
+        if config.psi_interval_ms == 0 {
+            return Err(StreamKitError::Configuration(
+                "psi_interval_ms must be greater than 0".to_string(),
+            ));
+        }
+        if !(1..=MAX_PCR_INTERVAL_MS).contains(&config.pcr_interval_ms) {
+            return Err(StreamKitError::Configuration(format!(
+                "pcr_interval_ms must be between 1 and {MAX_PCR_INTERVAL_MS}"
+            )));
+        }
+        if config.chunk_packets == 0 {
+            return Err(StreamKitError::Configuration(
+                "chunk_packets must be greater than 0".to_string(),
+            ));
+        }
+        for (index, track) in config.tracks.iter().enumerate() {
+            match track.codec {
+                TsCodec::Opus if !(1..=2).contains(&track.channels) => {
+                    return Err(StreamKitError::Configuration(format!(
+                        "Invalid MPEG-TS track {index}: Opus supports 1 or 2 channels, got {}",
+                        track.channels
+                    )));
+                },
+                TsCodec::Aac
+                    if aac_sample_rate_index(track.sample_rate).is_none()
+                        || !(1..=7).contains(&track.channels) =>
+                {
+                    return Err(StreamKitError::Configuration(format!(
+                        "Invalid MPEG-TS track {index}: unsupported AAC format {} Hz, {} channels",
+                        track.sample_rate, track.channels
+                    )));
+                },
+                _ => {},
+            }
+        }
+        Ok(Self { config })
+    }
+
+    fn pin_name(&self, index: usize) -> String {
+        if self.config.tracks.len() == 1 {
+            "in".to_string()
+        } else {
+            format!("in_{index}")
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for MpegTsMuxerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        self.config
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| InputPin {
+                name: self.pin_name(index),
+                accepts_types: vec![track.codec.packet_type()],
+                cardinality: PinCardinality::One,
+            })
+            .collect()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn content_type(&self) -> Option<String> {
+        Some("video/mp2t".to_string())
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        tracing::info!("MpegTsMuxerNode starting with {} track(s)", self.config.tracks.len());
+
+        // Merge the track inputs into one channel; each drainer ends when its input closes.
+        let (merged_tx, mut merged_rx) = mpsc::channel::<(usize, Packet)>(32);
+        let mut drainers = Vec::with_capacity(self.config.tracks.len());
+        for index in 0..self.config.tracks.len() {
+            let mut input_rx = context.take_input(&self.pin_name(index))?;
+            let merged_tx = merged_tx.clone();
+            drainers.push(tokio::spawn(async move {
+                while let Some(packet) = input_rx.recv().await {
+                    if merged_tx.send((index, packet)).await.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+        drop(merged_tx);
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let mut tracks: Vec<TsTrack> = self
+            .config
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(index, config)| TsTrack::new(index, config))
+            .collect();
+        let pcr_track = self.config.tracks.iter().position(|t| t.codec.is_video()).unwrap_or(0);
+        let pcr_pid = tracks[pcr_track].pid;
+        let psi_interval = self.config.psi_interval_ms * CLOCK_HZ / 1000;
+        let pcr_interval = self.config.pcr_interval_ms * CLOCK_HZ / 1000;
+        let chunk_bytes = self.config.chunk_packets * TS_PACKET_SIZE;
+
+        let mut writer = TsWriter::new();
+        let mut last_psi: Option<u64> = None;
+        let mut last_pcr: Option<u64> = None;
+        let mut packet_count = 0u64;
+        let mut reason = "input_closed";
+
+        loop {
+            let next = if let Some(token) = &context.cancellation_token {
+                tokio::select! {
+                    () = token.cancelled() => {
+                        reason = "cancelled";
+                        None
+                    },
+                    next = merged_rx.recv() => next,
+                }
+            } else {
+                merged_rx.recv().await
+            };
+            let Some((index, packet)) = next else { break };
+            let Packet::Binary { data, metadata, .. } = packet else {
+                tracing::warn!("MpegTsMuxerNode received non-binary packet, ignoring");
+                stats_tracker.discarded();
+                continue;
+            };
+            packet_count += 1;
+            stats_tracker.received();
+
+            let track = &mut tracks[index];
+            let dts = track.timestamp(metadata.as_ref());
+            let (payload, keyframe) = match track.elementary_payload(&data) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::warn!("Dropping {:?} access unit: {}", track.codec, e);
+                    stats_tracker.discarded();
+                    continue;
+                },
+            };
+            let codec = track.codec;
+
+            // PSI goes out on its interval and ahead of video key frames, so receivers can
+            // join at any random access point.
+            if last_psi.is_none_or(|last| dts.saturating_sub(last) >= psi_interval)
+                || (codec.is_video() && keyframe)
+            {
+                writer.write_psi(&tracks, pcr_pid);
+                last_psi = Some(dts);
+            }
+
+            let pcr = (index == pcr_track
+                && last_pcr.is_none_or(|last| dts.saturating_sub(last) >= pcr_interval))
+            .then(|| {
+                last_pcr = Some(dts);
+                dts * 300
+            });
+
+            let pes = pes_packet(codec, dts + PTS_OFFSET_TICKS, &payload);
+            writer.write_pes(&mut tracks[index], &pes, pcr, keyframe && codec.is_video());
+
+            while writer.out.len() >= chunk_bytes {
+                let chunk = writer.out.split_to(chunk_bytes).freeze();
+                if !send_chunk(&mut context, chunk, &mut stats_tracker).await {
+                    reason = "output_closed";
+                    break;
+                }
+            }
+            if reason == "output_closed" {
+                break;
+            }
+            stats_tracker.maybe_send();
+        }
+
+        for drainer in drainers {
+            drainer.abort();
+        }
+
+        if reason == "input_closed" && !writer.out.is_empty() {
+            let rest = writer.out.split().freeze();
+            send_chunk(&mut context, rest, &mut stats_tracker).await;
+        }
+
+        stats_tracker.force_send();
+        tracing::info!("MpegTsMuxerNode finished after {} access units", packet_count);
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Sends a chunk of whole TS packets. Returns `false` if the output channel closed.
+async fn send_chunk(
+    context: &mut NodeContext,
+    data: Bytes,
+    stats_tracker: &mut NodeStatsTracker,
+) -> bool {
+    let packet =
+        Packet::Binary { data, content_type: Some(Cow::Borrowed("video/mp2t")), metadata: None };
+    if context.output_sender.send("out", packet).await.is_err() {
+        tracing::debug!("Output channel closed, stopping MPEG-TS muxer");
+        return false;
+    }
+    stats_tracker.sent();
+    true
+}
+
+#[cfg(feature = "mpegts")]
+use schemars::schema_for;
+#[cfg(feature = "mpegts")]
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the MPEG-TS container nodes.
+///
+/// # Panics
+///
+/// Panics if config schemas cannot be serialized to JSON or the default muxer config is
+/// invalid (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization and default config should never fail
+#[cfg_attr(not(feature = "mpegts"), allow(unused_variables, clippy::missing_const_for_fn))]
+pub fn register_mpegts_nodes(registry: &mut NodeRegistry) {
+    #[cfg(feature = "mpegts")]
+    {
+        let default_muxer = MpegTsMuxerNode::new(MpegTsMuxerConfig::default())
+            .expect("default MPEG-TS muxer config should be valid");
+        registry.register_static_with_description(
+            "containers::mpegts::muxer",
+            |params| {
+                let config = config_helpers::parse_config_with_context(params, "MpegTsMuxer")?;
+                Ok(Box::new(MpegTsMuxerNode::new(config)?))
+            },
+            serde_json::to_value(schema_for!(MpegTsMuxerConfig))
+                .expect("MpegTsMuxerConfig schema should serialize to JSON"),
+            StaticPins { inputs: default_muxer.input_pins(), outputs: default_muxer.output_pins() },
+            vec!["containers".to_string(), "mpegts".to_string()],
+            false,
+            "Muxes AAC, Opus and H.264 into an MPEG transport stream with periodic PAT/PMT \
+             and PCR. Emits whole 188-byte TS packets for SRT/UDP contribution.",
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use std::collections::HashMap;
+
+    fn packet(data: Vec<u8>, timestamp_us: u64, duration_us: u64) -> Packet {
+        Packet::Binary {
+            data: Bytes::from(data),
+            content_type: None,
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(timestamp_us),
+                duration_us: Some(duration_us),
+                sequence: None,
+            }),
+        }
+    }
+
+    fn payload(index: usize, len: usize) -> Vec<u8> {
+        (0..len).map(|i| u8::try_from((index * 7 + i) % 251).unwrap()).collect()
+    }
+
+    /// A parsed TS packet header with its adaptation field and payload.
+    struct TsPacket<'a> {
+        pid: u16,
+        start: bool,
+        cc: u8,
+        adaptation: &'a [u8],
+        payload: &'a [u8],
+    }
+
+    fn parse_ts(stream: &[u8]) -> Vec<TsPacket<'_>> {
+        assert_eq!(stream.len() % TS_PACKET_SIZE, 0);
+        stream
+            .chunks(TS_PACKET_SIZE)
+            .map(|p| {
+                assert_eq!(p[0], SYNC_BYTE);
+                let has_af = p[3] & 0x20 != 0;
+                let af_len = if has_af { 1 + usize::from(p[4]) } else { 0 };
+                TsPacket {
+                    pid: u16::from_be_bytes([p[1] & 0x1F, p[2]]),
+                    start: p[1] & 0x40 != 0,
+                    cc: p[3] & 0x0F,
+                    adaptation: &p[4..4 + af_len],
+                    payload: &p[4 + af_len..],
+                }
+            })
+            .collect()
+    }
+
+    /// Reassembles the PES packets of `pid` into (PTS, payload) pairs.
+    fn pes_payloads(packets: &[TsPacket<'_>], pid: u16) -> Vec<(u64, Vec<u8>)> {
+        let mut pes_packets: Vec<Vec<u8>> = Vec::new();
+        for packet in packets.iter().filter(|p| p.pid == pid) {
+            if packet.start {
+                pes_packets.push(Vec::new());
+            }
+            pes_packets.last_mut().unwrap().extend_from_slice(packet.payload);
+        }
+        pes_packets
+            .into_iter()
+            .map(|pes| {
+                assert_eq!(&pes[..3], &[0, 0, 1]);
+                let header_len = 9 + usize::from(pes[8]);
+                let t = &pes[9..14];
+                let pts = (u64::from(t[0] >> 1 & 0x07) << 30)
+                    | (u64::from(u16::from_be_bytes([t[1], t[2]]) >> 1) << 15)
+                    | u64::from(u16::from_be_bytes([t[3], t[4]]) >> 1);
+                (pts, pes[header_len..].to_vec())
+            })
+            .collect()
+    }
+
+    fn pcr_of(packet: &TsPacket<'_>) -> Option<u64> {
+        let af = packet.adaptation;
+        if af.len() < 8 || af[1] & 0x10 == 0 {
+            return None;
+        }
+        let base = (u64::from(u32::from_be_bytes([af[2], af[3], af[4], af[5]])) << 1)
+            | u64::from(af[6] >> 7);
+        let ext = (u64::from(af[6] & 0x01) << 8) | u64::from(af[7]);
+        Some(base * 300 + ext)
+    }
+
+    /// Runs the muxer with `config`, feeding `inputs[i]` into the i-th track pin in turn.
+    async fn mux(config: MpegTsMuxerConfig, inputs: Vec<Vec<Packet>>) -> Vec<Packet> {
+        let node = MpegTsMuxerNode::new(config).unwrap();
+        let mut senders = Vec::new();
+        let mut input_map = HashMap::new();
+        for index in 0..inputs.len() {
+            let (tx, rx) = mpsc::channel(200);
+            input_map.insert(node.pin_name(index), rx);
+            senders.push(tx);
+        }
+        let (context, mock_sender, mut state_rx) = create_test_context(input_map, 10);
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for (tx, packets) in senders.iter().zip(inputs) {
+            for packet in packets {
+                tx.send(packet).await.unwrap();
+            }
+        }
+        drop(senders);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    fn concat(packets: &[Packet]) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for packet in packets {
+            let Packet::Binary { data, content_type, .. } = packet else {
+                panic!("expected binary output")
+            };
+            assert_eq!(content_type.as_deref(), Some("video/mp2t"));
+            stream.extend_from_slice(data);
+        }
+        stream
+    }
+
+    #[test]
+    fn test_mpegts_psi_sections_carry_valid_crc() {
+        // Running the CRC over a section including its CRC yields zero.
+        assert_eq!(crc32_mpeg2(&pat_section()), 0);
+        let track = TsTrack::new(0, &TsTrackConfig::default());
+        let pmt = pmt_section(&[track], FIRST_STREAM_PID);
+        assert_eq!(crc32_mpeg2(&pmt), 0);
+        // CRC-32/MPEG-2 check value.
+        assert_eq!(crc32_mpeg2(b"123456789"), 0x0376_E6E7);
+    }
+
+    #[tokio::test]
+    async fn test_mpegts_opus_stream() {
+        let frames: Vec<Vec<u8>> = (0..50).map(|i| payload(i, 40 + i * 7)).collect();
+        let packets = frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| packet(frame.clone(), i as u64 * 20_000, 20_000))
+            .collect();
+        let output = mux(MpegTsMuxerConfig::default(), vec![packets]).await;
+
+        // Every chunk but the last is exactly 7 TS packets.
+        let (last, full) = output.split_last().unwrap();
+        for chunk in full {
+            assert_eq!(concat(std::slice::from_ref(chunk)).len(), 7 * TS_PACKET_SIZE);
+        }
+        assert!(concat(std::slice::from_ref(last)).len() <= 7 * TS_PACKET_SIZE);
+
+        let stream = concat(&output);
+        let ts = parse_ts(&stream);
+        assert_eq!(ts[0].pid, PAT_PID);
+        assert_eq!(ts[1].pid, PMT_PID);
+
+        // PMT announces an Opus stream on the first stream PID.
+        let pmt = &ts[1].payload[1..];
+        assert_eq!(pmt[0], 0x02);
+        assert_eq!(u16::from_be_bytes([pmt[8] & 0x1F, pmt[9]]), FIRST_STREAM_PID);
+        assert_eq!(pmt[12], 0x06);
+        assert_eq!(&pmt[17..23], &[0x05, 4, b'O', b'p', b'u', b's']);
+        assert_eq!(&pmt[23..27], &[0x7F, 2, 0x80, 2]);
+
+        // One second of audio at a 100 ms interval repeats the PAT ten times.
+        assert_eq!(ts.iter().filter(|p| p.pid == PAT_PID).count(), 10);
+
+        // Continuity counters increment per PID.
+        let stream_packets: Vec<_> = ts.iter().filter(|p| p.pid == FIRST_STREAM_PID).collect();
+        for pair in stream_packets.windows(2) {
+            assert_eq!(pair[1].cc, (pair[0].cc + 1) & 0x0F);
+        }
+
+        // The first packet carries the PCR; audio is the only (and so the PCR) track.
+        assert_eq!(pcr_of(stream_packets[0]), Some(0));
+        let pcr_count = stream_packets.iter().filter_map(|p| pcr_of(p)).count();
+        assert_eq!(pcr_count, 25);
+
+        let pes = pes_payloads(&ts, FIRST_STREAM_PID);
+        assert_eq!(pes.len(), frames.len());
+        for (i, ((pts, data), frame)) in pes.iter().zip(&frames).enumerate() {
+            assert_eq!(*pts, PTS_OFFSET_TICKS + i as u64 * 1800);
+            assert_eq!(&data[..2], &[0x7F, 0xE0]);
+            let mut size = 0;
+            let mut offset = 2;
+            while data[offset] == 0xFF {
+                size += 255;
+                offset += 1;
+            }
+            size += usize::from(data[offset]);
+            assert_eq!(size, frame.len());
+            assert_eq!(&data[offset + 1..], frame.as_slice());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mpegts_h264_and_aac() {
+        let config = MpegTsMuxerConfig {
+            tracks: vec![
+                TsTrackConfig { codec: TsCodec::H264, ..TsTrackConfig::default() },
+                TsTrackConfig { codec: TsCodec::Aac, sample_rate: 48000, channels: 2 },
+            ],
+            ..MpegTsMuxerConfig::default()
+        };
+        // 30 fps video with an IDR every 15 frames.
+        let video: Vec<Packet> = (0..30)
+            .map(|i| {
+                let nal_type = if i % 15 == 0 { 0x65 } else { 0x41 };
+                let mut au = vec![0, 0, 0, 1, nal_type];
+                au.extend(payload(i, 500 + i * 31));
+                packet(au, i as u64 * 33_333, 33_333)
+            })
+            .collect();
+        // Raw AAC access units.
+        let audio_frames: Vec<Vec<u8>> = (0..40).map(|i| payload(i, 100 + i)).collect();
+        let audio: Vec<Packet> = audio_frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| packet(frame.clone(), i as u64 * 21_333, 21_333))
+            .collect();
+
+        let output = mux(config, vec![video, audio]).await;
+        let stream = concat(&output);
+        let ts = parse_ts(&stream);
+
+        let video_pid = FIRST_STREAM_PID;
+        let audio_pid = FIRST_STREAM_PID + 1;
+
+        // The PCR rides on the video track only.
+        assert!(ts.iter().filter(|p| p.pid == audio_pid).all(|p| pcr_of(p).is_none()));
+        let pcrs: Vec<u64> = ts.iter().filter(|p| p.pid == video_pid).filter_map(pcr_of).collect();
+        assert!(!pcrs.is_empty());
+        for pair in pcrs.windows(2) {
+            assert!(pair[1] - pair[0] <= 40 * 27_000 + 33_333 * 27);
+        }
+
+        // Key frames start with the random access indicator and are preceded by PSI.
+        let video_starts: Vec<usize> =
+            (0..ts.len()).filter(|&i| ts[i].pid == video_pid && ts[i].start).collect();
+        assert_eq!(video_starts.len(), 30);
+        for (frame, &index) in video_starts.iter().enumerate() {
+            let random_access = ts[index].adaptation.get(1).is_some_and(|f| f & 0x40 != 0);
+            assert_eq!(random_access, frame % 15 == 0, "frame {frame}");
+            if frame % 15 == 0 {
+                assert_eq!(ts[index - 1].pid, PMT_PID);
+                assert_eq!(ts[index - 2].pid, PAT_PID);
+            }
+        }
+
+        let video_pes = pes_payloads(&ts, video_pid);
+        assert_eq!(&video_pes[0].1[..6], &H264_AUD);
+        assert_eq!(video_pes[0].1[10], 0x65);
+
+        // Raw AAC is framed as ADTS.
+        let audio_pes = pes_payloads(&ts, audio_pid);
+        assert_eq!(audio_pes.len(), audio_frames.len());
+        for ((pts, data), frame) in audio_pes.iter().zip(&audio_frames) {
+            assert!(*pts >= PTS_OFFSET_TICKS);
+            let header = parse_adts_header(data).unwrap();
+            assert_eq!(header.sample_rate_index, 3);
+            assert_eq!(header.channel_config, 2);
+            assert_eq!(header.frame_len, data.len());
+            assert_eq!(&data[header.header_len..], frame.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_mpegts_rejects_invalid_config() {
+        assert!(MpegTsMuxerNode::new(MpegTsMuxerConfig {
+            tracks: Vec::new(),
+            ..MpegTsMuxerConfig::default()
+        })
+        .is_err());
+        assert!(MpegTsMuxerNode::new(MpegTsMuxerConfig {
+            pcr_interval_ms: 150,
+            ..MpegTsMuxerConfig::default()
+        })
+        .is_err());
+        assert!(MpegTsMuxerNode::new(MpegTsMuxerConfig {
+            tracks: vec![TsTrackConfig { codec: TsCodec::Aac, sample_rate: 12345, channels: 2 }],
+            ..MpegTsMuxerConfig::default()
+        })
+        .is_err());
+    }
+}
@@ -25,6 +25,17 @@ use tokio::io::duplex;
 /// Default page flush threshold for Ogg muxer (typical max Ogg page size)
 const DEFAULT_CHUNK_SIZE: usize = 65536;
 
+/// Start of a Vorbis identification header, the first packet of an Ogg/Vorbis stream.
+const VORBIS_IDENT_MAGIC: &[u8] = b"\x01vorbis";
+
+/// Vorbis header packet types (identification, comment, setup).
+const VORBIS_IDENT_HEADER: u8 = 1;
+const VORBIS_COMMENT_HEADER: u8 = 3;
+
+/// Length of a Vorbis identification header.
+#[cfg(feature = "symphonia")]
+const VORBIS_IDENT_LEN: usize = 30;
+
 // --- Ogg Muxer ---
 
 // A shared, thread-safe buffer that implements io::Write. This is used to
@@ -53,11 +64,93 @@ impl Write for SharedPacketBuffer {
     }
 }
 
-#[derive(Deserialize, Debug, Default, Clone, Copy, JsonSchema)]
+/// Codec carried in an Ogg stream.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum OggMuxerCodec {
+    /// Opus packets. The muxer writes the `OpusHead` and `OpusTags` headers itself.
     #[default]
     Opus,
+    /// Vorbis packets, starting with the three header packets from `audio::vorbis::encoder`.
+    Vorbis,
+}
+
+impl OggMuxerCodec {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Opus => "opus",
+            Self::Vorbis => "vorbis",
+        }
+    }
+
+    /// Packet type of the codec's packets on the node's pins.
+    const fn packet_type(self) -> PacketType {
+        match self {
+            Self::Opus => PacketType::OpusAudio,
+            Self::Vorbis => PacketType::Binary,
+        }
+    }
+
+    /// Codec of a stream, detected from its first packet.
+    fn detect(first_packet: &[u8]) -> Self {
+        if first_packet.starts_with(VORBIS_IDENT_MAGIC) {
+            Self::Vorbis
+        } else {
+            Self::Opus
+        }
+    }
+
+    /// Checks a stream's first packet against the configured codec.
+    fn check_stream(self, first_packet: &[u8]) -> Result<(), String> {
+        self.check(Self::detect(first_packet))
+    }
+
+    /// Checks the codec found in a stream against the configured one.
+    fn check(self, found: Self) -> Result<(), String> {
+        if found == self {
+            Ok(())
+        } else {
+            Err(format!(
+                "Ogg stream carries {} audio but the demuxer is configured for {}; \
+                 set `codec: {}`",
+                found.name(),
+                self.name(),
+                found.name()
+            ))
+        }
+    }
+}
+
+/// Header type of a Vorbis header packet, or `None` for audio packets.
+fn vorbis_header_type(data: &[u8]) -> Option<u8> {
+    // Header packets have an odd type byte followed by the codec name.
+    match data {
+        [kind, name @ ..] if kind & 1 == 1 && name.starts_with(b"vorbis") => Some(*kind),
+        _ => None,
+    }
+}
+
+/// Sample rate of a Vorbis stream, read from its identification header.
+fn vorbis_sample_rate(identification: &[u8]) -> Option<u32> {
+    identification.get(12..16)?.try_into().ok().map(u32::from_le_bytes)
+}
+
+/// Timing metadata for a packet covering samples `start..end` of a stream.
+fn sample_span_metadata(
+    start: u64,
+    end: u64,
+    sample_rate: u32,
+    sequence: u64,
+) -> streamkit_core::types::PacketMetadata {
+    let to_us = |samples: u64| {
+        let us = u128::from(samples) * 1_000_000 / u128::from(sample_rate);
+        u64::try_from(us).unwrap_or(u64::MAX)
+    };
+    streamkit_core::types::PacketMetadata {
+        timestamp_us: Some(to_us(start)),
+        duration_us: Some(to_us(end) - to_us(start)),
+        sequence: Some(sequence),
+    }
 }
 
 #[derive(Deserialize, Debug, JsonSchema)]
@@ -67,7 +160,8 @@ pub struct OggMuxerConfig {
     pub stream_serial: u32,
     // The codec being muxed, to handle headers correctly.
     pub codec: OggMuxerCodec,
-    /// Number of audio channels (1 for mono, 2 for stereo). Defaults to 1.
+    /// Number of audio channels (1 for mono, 2 for stereo) written to the Opus header.
+    /// Defaults to 1. Vorbis streams carry their own headers.
     pub channels: u8,
     /// The number of bytes to buffer before flushing to the output. Defaults to 65536.
     pub chunk_size: usize,
@@ -101,7 +195,7 @@ impl ProcessorNode for OggMuxerNode {
     fn input_pins(&self) -> Vec<InputPin> {
         vec![InputPin {
             name: "in".to_string(),
-            accepts_types: vec![PacketType::OpusAudio], // Accepts Opus for now
+            accepts_types: vec![self.config.codec.packet_type()],
             cardinality: PinCardinality::One,
         }]
     }
@@ -201,7 +295,12 @@ impl ProcessorNode for OggMuxerNode {
                     }
                     tracing::debug!("OpusTags written successfully");
                 },
+                OggMuxerCodec::Vorbis => {
+                    tracing::info!("Vorbis headers are taken from the input stream");
+                },
             }
+            // Sample rate of a Vorbis stream, known once its identification header arrives.
+            let mut vorbis_rate: Option<u32> = None;
 
             tracing::info!("Headers written, entering receive loop to process incoming packets");
             while let Some(packet) = context.recv_with_cancellation(&mut input_rx).await {
@@ -222,11 +321,26 @@ impl ProcessorNode for OggMuxerNode {
                     if self.is_first_packet {
                         self.is_first_packet = false;
                     }
-                    let pck_info = PacketWriteEndInfo::EndPage;
+                    let mut pck_info = PacketWriteEndInfo::EndPage;
 
                     // Calculate granule position from metadata if available, otherwise use packet count
                     // For Opus: granule position is at 48kHz sample rate
-                    if let Some(meta) = metadata {
+                    if self.config.codec == OggMuxerCodec::Vorbis {
+                        match vorbis_muxer_step(&data, metadata.as_ref(), &mut vorbis_rate) {
+                            Ok((info, granule_pos)) => {
+                                pck_info = info;
+                                if let Some(granule_pos) = granule_pos {
+                                    last_granule_pos = granule_pos;
+                                }
+                            },
+                            Err(err_msg) => {
+                                stats_tracker.errored();
+                                stats_tracker.force_send();
+                                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                                return Err(StreamKitError::Runtime(err_msg));
+                            },
+                        }
+                    } else if let Some(meta) = metadata {
                         if let Some(timestamp_us) = meta.timestamp_us {
                             // Convert timestamp from microseconds to 48kHz samples
                             last_granule_pos = (timestamp_us * 48000) / 1_000_000;
@@ -354,20 +468,92 @@ impl ProcessorNode for OggMuxerNode {
     }
 }
 
+/// Page placement and granule position of a Vorbis packet.
+///
+/// Header packets end their own pages (identification, setup) at granule 0. Audio packets
+/// with timing metadata end a page whose granule position is the sample count at the end of
+/// the packet; without timing they share the next page.
+fn vorbis_muxer_step(
+    data: &[u8],
+    metadata: Option<&streamkit_core::types::PacketMetadata>,
+    sample_rate: &mut Option<u32>,
+) -> Result<(PacketWriteEndInfo, Option<u64>), String> {
+    let header = vorbis_header_type(data);
+    if header == Some(VORBIS_IDENT_HEADER) {
+        *sample_rate = vorbis_sample_rate(data).filter(|&rate| rate > 0);
+        if sample_rate.is_none() {
+            return Err("Invalid Vorbis identification header".to_string());
+        }
+        return Ok((PacketWriteEndInfo::EndPage, Some(0)));
+    }
+    let Some(rate) = *sample_rate else {
+        return Err("Vorbis stream must start with its identification header".to_string());
+    };
+    match header {
+        Some(VORBIS_COMMENT_HEADER) => Ok((PacketWriteEndInfo::NormalPacket, None)),
+        Some(_) => Ok((PacketWriteEndInfo::EndPage, None)),
+        None => {
+            let end_us = metadata.and_then(|meta| Some(meta.timestamp_us? + meta.duration_us?));
+            Ok(end_us.map_or((PacketWriteEndInfo::NormalPacket, None), |end_us| {
+                let granule_pos = (u128::from(end_us) * u128::from(rate) + 500_000) / 1_000_000;
+                (PacketWriteEndInfo::EndPage, Some(u64::try_from(granule_pos).unwrap_or(u64::MAX)))
+            }))
+        },
+    }
+}
+
 // --- Ogg Demuxer ---
 
 #[derive(Deserialize, Debug, Default, JsonSchema)]
 #[serde(default)]
-pub struct OggDemuxerConfig {}
+pub struct OggDemuxerConfig {
+    /// Codec carried by the stream. Defaults to Opus.
+    pub codec: OggMuxerCodec,
+}
 
 /// A node that demuxes an Ogg container stream into its underlying compressed packets.
 pub struct OggDemuxerNode {
-    _config: OggDemuxerConfig,
+    config: OggDemuxerConfig,
 }
 
 impl OggDemuxerNode {
     pub const fn new(config: OggDemuxerConfig) -> Self {
-        Self { _config: config }
+        Self { config }
+    }
+}
+
+/// Timing of Vorbis packets, derived from the granule positions of their pages.
+#[derive(Default)]
+struct VorbisPageTiming {
+    sample_rate: u32,
+    /// Granule position at the start of the current page.
+    page_start: u64,
+}
+
+impl VorbisPageTiming {
+    /// Timing metadata for a packet. Granule positions only time whole pages, so packets
+    /// sharing a page with others are left untimed.
+    fn metadata(
+        &mut self,
+        packet: &ogg::Packet,
+        sequence: u64,
+    ) -> Option<streamkit_core::types::PacketMetadata> {
+        if let Some(header) = vorbis_header_type(&packet.data) {
+            if header == VORBIS_IDENT_HEADER {
+                self.sample_rate = vorbis_sample_rate(&packet.data).unwrap_or(0);
+            }
+            return None;
+        }
+        let granule_pos = packet.absgp_page();
+        // A page with no packet ending in it has no granule position (-1).
+        if !packet.last_in_page() || granule_pos == u64::MAX {
+            return None;
+        }
+        let start = std::mem::replace(&mut self.page_start, granule_pos);
+        if !packet.first_in_page() || self.sample_rate == 0 {
+            return None;
+        }
+        Some(sample_span_metadata(start, granule_pos.max(start), self.sample_rate, sequence))
     }
 }
 
@@ -384,7 +570,7 @@ impl ProcessorNode for OggDemuxerNode {
     fn output_pins(&self) -> Vec<OutputPin> {
         vec![OutputPin {
             name: "out".to_string(),
-            produces_type: PacketType::OpusAudio,
+            produces_type: self.config.codec.packet_type(),
             cardinality: PinCardinality::Broadcast,
         }]
     }
@@ -393,6 +579,7 @@ impl ProcessorNode for OggDemuxerNode {
         let node_name = context.output_sender.node_name().to_string();
         state_helpers::emit_initializing(&context.state_tx, &node_name);
         tracing::info!("OggDemuxerNode starting");
+        let codec = self.config.codec;
         state_helpers::emit_running(&context.state_tx, &node_name);
         let mut input_rx = context.take_input("in")?;
 
@@ -455,6 +642,7 @@ impl ProcessorNode for OggDemuxerNode {
         let mut last_granule_pos: Option<u64> = None;
         let mut packets_at_granule_pos = 0u64;
         let mut detected_frame_duration_us: Option<u64> = None;
+        let mut vorbis_timing = VorbisPageTiming::default();
 
         loop {
             let packet_result = if let Some(token) = &context.cancellation_token {
@@ -477,6 +665,15 @@ impl ProcessorNode for OggDemuxerNode {
                 Ok(packet) => {
                     packets_extracted += 1;
                     stats_tracker.received();
+                    if packets_extracted == 1 {
+                        if let Err(err_msg) = codec.check_stream(&packet.data) {
+                            stats_tracker.errored();
+                            stats_tracker.force_send();
+                            writer_task.abort();
+                            state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                            return Err(StreamKitError::Runtime(err_msg));
+                        }
+                    }
                     if packets_extracted.is_multiple_of(1000) {
                         tracing::debug!("OggDemuxer extracted {} packets", packets_extracted);
                     }
@@ -486,7 +683,9 @@ impl ProcessorNode for OggDemuxerNode {
 
                     // Calculate timing metadata from granule position
                     // For Opus (RFC 7845): granule position is at 48kHz sample rate
-                    let metadata = if granule_pos > 0 {
+                    let metadata = if codec == OggMuxerCodec::Vorbis {
+                        vorbis_timing.metadata(&packet, packets_extracted)
+                    } else if granule_pos > 0 {
                         let timestamp_us = (granule_pos * 1_000_000) / 48000;
 
                         // Determine packet duration
@@ -604,21 +803,57 @@ use crate::streaming_utils::StreamingReader;
 #[cfg(feature = "symphonia")]
 #[derive(Deserialize, Debug, Default, JsonSchema)]
 #[serde(default)]
-pub struct SymphoniaOggDemuxerConfig {}
+pub struct SymphoniaOggDemuxerConfig {
+    /// Codec carried by the stream. Defaults to Opus.
+    pub codec: OggMuxerCodec,
+}
 
 /// Symphonia-based Ogg demuxer node (more robust alternative to the ogg crate based one)
 #[cfg(feature = "symphonia")]
 pub struct SymphoniaOggDemuxerNode {
-    _config: SymphoniaOggDemuxerConfig,
+    config: SymphoniaOggDemuxerConfig,
 }
 
 #[cfg(feature = "symphonia")]
 impl SymphoniaOggDemuxerNode {
     pub const fn new(config: SymphoniaOggDemuxerConfig) -> Self {
-        Self { _config: config }
+        Self { config }
     }
 }
 
+/// Sample rate and the three header packets of a Vorbis track.
+///
+/// Symphonia's Ogg reader consumes the headers: the identification and setup headers are
+/// kept as the track's extra data, and the comment header is replaced by an empty one.
+#[cfg(feature = "symphonia")]
+fn vorbis_track_headers(
+    track: &symphonia::core::formats::Track,
+) -> Result<(u32, [Vec<u8>; 3]), String> {
+    let extra_data = track
+        .codec_params
+        .extra_data
+        .as_deref()
+        .filter(|data| data.len() > VORBIS_IDENT_LEN)
+        .ok_or("Vorbis track has no setup header")?;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .filter(|&rate| rate > 0)
+        .ok_or("Vorbis track has no sample rate")?;
+
+    let vendor = b"streamkit";
+    let mut comment = vec![VORBIS_COMMENT_HEADER];
+    comment.extend_from_slice(b"vorbis");
+    #[allow(clippy::cast_possible_truncation)] // A short constant.
+    comment.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    comment.extend_from_slice(vendor);
+    comment.extend_from_slice(&0_u32.to_le_bytes()); // No comments.
+    comment.push(1); // Framing bit.
+
+    let (identification, setup) = extra_data.split_at(VORBIS_IDENT_LEN);
+    Ok((sample_rate, [identification.to_vec(), comment, setup.to_vec()]))
+}
+
 #[cfg(feature = "symphonia")]
 #[async_trait]
 impl ProcessorNode for SymphoniaOggDemuxerNode {
@@ -633,7 +868,7 @@ impl ProcessorNode for SymphoniaOggDemuxerNode {
     fn output_pins(&self) -> Vec<OutputPin> {
         vec![OutputPin {
             name: "out".to_string(),
-            produces_type: PacketType::OpusAudio,
+            produces_type: self.config.codec.packet_type(),
             cardinality: PinCardinality::Broadcast,
         }]
     }
@@ -643,6 +878,7 @@ impl ProcessorNode for SymphoniaOggDemuxerNode {
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
         tracing::info!("SymphoniaOggDemuxerNode starting");
+        let codec = self.config.codec;
         let mut input_rx = context.take_input("in")?;
 
         // Create channels for streaming reader and results
@@ -691,6 +927,38 @@ impl ProcessorNode for SymphoniaOggDemuxerNode {
                         return;
                     },
                 };
+            let vorbis_track = format_reader.tracks().iter().find(|track| {
+                track.codec_params.codec == symphonia::core::codecs::CODEC_TYPE_VORBIS
+            });
+            let found =
+                if vorbis_track.is_some() { OggMuxerCodec::Vorbis } else { OggMuxerCodec::Opus };
+            let headers = codec
+                .check(found)
+                .and_then(|()| vorbis_track.map(vorbis_track_headers).transpose());
+            let vorbis_rate = match headers {
+                Ok(Some((sample_rate, headers))) => {
+                    // Send the headers on ahead of the audio packets.
+                    for header in headers {
+                        let packet = Packet::Binary {
+                            data: Bytes::from(header),
+                            content_type: None,
+                            metadata: None,
+                        };
+                        if result_tx.blocking_send(Ok(packet)).is_err() {
+                            return;
+                        }
+                    }
+                    Some(sample_rate)
+                },
+                Ok(None) => None,
+                Err(err_msg) => {
+                    state_helpers::emit_failed(&state_tx, &node_name, &err_msg);
+                    let _ = result_tx.blocking_send(Err(err_msg));
+                    return;
+                },
+            };
+            // End of the previous Vorbis packet, in samples.
+            let mut vorbis_end = 0u64;
             let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), stats_tx);
 
             state_helpers::emit_running(&state_tx, &node_name);
@@ -714,7 +982,18 @@ impl ProcessorNode for SymphoniaOggDemuxerNode {
                         stats_tracker.received();
 
                         // Extract timing metadata
-                        let metadata = if packet.ts() > 0 {
+                        let metadata = if let Some(sample_rate) = vorbis_rate {
+                            // The final packet's timestamp is not trimmed with its duration,
+                            // so keep each packet from starting before the previous one ends.
+                            let start = packet.ts().max(vorbis_end);
+                            vorbis_end = (packet.ts() + packet.dur()).max(start);
+                            Some(sample_span_metadata(
+                                start,
+                                vorbis_end,
+                                sample_rate,
+                                packets_extracted,
+                            ))
+                        } else if packet.ts() > 0 {
                             // Opus uses 48kHz timebase
                             let timestamp_us = (packet.ts() * 1_000_000) / 48000;
                             let duration_us = (packet.dur() * 1_000_000) / 48000;
@@ -882,7 +1161,23 @@ impl ProcessorNode for SymphoniaOggDemuxerNode {
 }
 
 use schemars::schema_for;
-use streamkit_core::{config_helpers, registry::StaticPins};
+use streamkit_core::config_helpers;
+
+/// Parses a node config, defaulting when no params are given (the registry builds a default
+/// instance to list a dynamic node's pins) but rejecting params that don't parse, so a
+/// misspelled codec is not silently read as Opus.
+fn parse_ogg_config<T>(
+    params: Option<&serde_json::Value>,
+    context: &str,
+) -> Result<T, StreamKitError>
+where
+    T: for<'de> Deserialize<'de> + Default,
+{
+    params.map_or_else(
+        || Ok(T::default()),
+        |params| config_helpers::parse_config_with_context(Some(params), context),
+    )
+}
 
 /// Registers the Ogg container nodes.
 ///
@@ -891,66 +1186,55 @@ use streamkit_core::{config_helpers, registry::StaticPins};
 /// Panics if config schemas cannot be serialized to JSON (should never happen).
 #[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
 pub fn register_ogg_nodes(registry: &mut NodeRegistry) {
+    // Pins follow the configured codec, so the nodes are registered with dynamic pins.
     #[cfg(feature = "ogg")]
     {
-        let default_muxer = OggMuxerNode::new(OggMuxerConfig::default());
-        registry.register_static_with_description(
+        registry.register_dynamic_with_description(
             "containers::ogg::muxer",
             |params| {
-                let config = config_helpers::parse_config_with_context(params, "OggMuxer")?;
+                let config = parse_ogg_config(params, "OggMuxer")?;
                 Ok(Box::new(OggMuxerNode::new(config)))
             },
             serde_json::to_value(schema_for!(OggMuxerConfig))
                 .expect("OggMuxerConfig schema should serialize to JSON"),
-            StaticPins { inputs: default_muxer.input_pins(), outputs: default_muxer.output_pins() },
             vec!["containers".to_string(), "ogg".to_string()],
             false,
-            "Muxes Opus audio packets into an Ogg container. \
-             Produces streamable Ogg/Opus output for playback or storage.",
+            "Muxes Opus or Vorbis audio packets into an Ogg container. \
+             Produces streamable Ogg/Opus or Ogg/Vorbis output for playback or storage.",
         );
     }
 
     // Use Symphonia-based demuxer if available, otherwise fall back to ogg crate
     #[cfg(feature = "symphonia")]
     {
-        let default_demuxer = SymphoniaOggDemuxerNode::new(SymphoniaOggDemuxerConfig::default());
-        registry.register_static_with_description(
+        registry.register_dynamic_with_description(
             "containers::ogg::demuxer",
             |params| {
-                let config = config_helpers::parse_config_optional(params)?;
+                let config = parse_ogg_config(params, "OggDemuxer")?;
                 Ok(Box::new(SymphoniaOggDemuxerNode::new(config)))
             },
             serde_json::to_value(schema_for!(SymphoniaOggDemuxerConfig))
                 .expect("SymphoniaOggDemuxerConfig schema should serialize to JSON"),
-            StaticPins {
-                inputs: default_demuxer.input_pins(),
-                outputs: default_demuxer.output_pins(),
-            },
             vec!["containers".to_string(), "ogg".to_string()],
             false,
-            "Demuxes Ogg containers to extract Opus audio packets. \
-             Accepts binary Ogg data and outputs Opus-encoded audio frames.",
+            "Demuxes Ogg containers to extract Opus or Vorbis audio packets. \
+             Accepts binary Ogg data and outputs the encoded packets, Vorbis headers first.",
         );
     }
     #[cfg(all(feature = "ogg", not(feature = "symphonia")))]
     {
-        let default_demuxer = OggDemuxerNode::new(OggDemuxerConfig::default());
-        registry.register_static_with_description(
+        registry.register_dynamic_with_description(
             "containers::ogg::demuxer",
             |params| {
-                let config = config_helpers::parse_config_optional(params)?;
+                let config = parse_ogg_config(params, "OggDemuxer")?;
                 Ok(Box::new(OggDemuxerNode::new(config)))
             },
             serde_json::to_value(schema_for!(OggDemuxerConfig))
                 .expect("OggDemuxerConfig schema should serialize to JSON"),
-            StaticPins {
-                inputs: default_demuxer.input_pins(),
-                outputs: default_demuxer.output_pins(),
-            },
             vec!["containers".to_string(), "ogg".to_string()],
             false,
-            "Demuxes Ogg containers to extract Opus audio packets. \
-             Accepts binary Ogg data and outputs Opus-encoded audio frames.",
+            "Demuxes Ogg containers to extract Opus or Vorbis audio packets. \
+             Accepts binary Ogg data and outputs the encoded packets, Vorbis headers first.",
         );
     }
 }
@@ -7,9 +7,10 @@
 #![allow(clippy::unwrap_used, clippy::expect_used, clippy::disallowed_macros)]
 
 use super::ogg::{OggDemuxerConfig, OggDemuxerNode, OggMuxerConfig, OggMuxerNode};
+#[cfg(feature = "webm")]
 use super::webm::{WebMMuxerConfig, WebMMuxerNode};
 use crate::test_utils::{
-    assert_state_initializing, assert_state_running, assert_state_stopped,
+    assert_state_failed, assert_state_initializing, assert_state_running, assert_state_stopped,
     create_test_binary_packet, create_test_context,
 };
 use bytes::Bytes;
@@ -222,6 +223,32 @@ async fn test_ogg_demuxer_multiple_chunks() {
     println!("✅ OGG demuxer handled chunked input, extracted {} packets", output_packets.len());
 }
 
+#[tokio::test]
+async fn test_ogg_demuxer_codec_mismatch() {
+    let (input_tx, input_rx) = mpsc::channel(10);
+    let mut inputs = HashMap::new();
+    inputs.insert("in".to_string(), input_rx);
+
+    let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+    // Configured for Opus, fed Vorbis
+    let node = OggDemuxerNode::new(OggDemuxerConfig::default());
+
+    let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+    assert_state_initializing(&mut state_rx).await;
+    assert_state_running(&mut state_rx).await;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/audio/silence_vorbis.ogg");
+    let ogg_data = std::fs::read(&path).unwrap();
+    input_tx.send(create_test_binary_packet(ogg_data)).await.unwrap();
+
+    assert_state_failed(&mut state_rx).await;
+    let err = node_handle.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("codec: vorbis"), "unexpected error: {err}");
+    assert!(mock_sender.get_packets_for_pin("out").await.is_empty());
+}
+
 #[tokio::test]
 async fn test_ogg_roundtrip() {
     // Test muxing and then demuxing
@@ -284,6 +311,7 @@ async fn test_ogg_roundtrip() {
     println!("✅ Demuxed {} Opus packets from muxed OGG", demuxed_packets.len());
 }
 
+#[cfg(feature = "webm")]
 #[tokio::test]
 async fn test_webm_muxer_basic() {
     let (input_tx, input_rx) = mpsc::channel(10);
@@ -342,6 +370,7 @@ async fn test_webm_muxer_basic() {
     println!("✅ WebM muxer produced {} output packets", output_packets.len());
 }
 
+#[cfg(feature = "webm")]
 #[tokio::test]
 async fn test_webm_muxer_multiple_packets() {
     let (input_tx, input_rx) = mpsc::channel(10);
@@ -389,6 +418,7 @@ async fn test_webm_muxer_multiple_packets() {
     );
 }
 
+#[cfg(feature = "webm")]
 #[tokio::test]
 async fn test_webm_sliding_window() {
     // Test that WebM muxer handles long streams with sliding window
@@ -430,3 +460,117 @@ async fn test_webm_sliding_window() {
         output_packets.len()
     );
 }
+
+/// Runs a node over `packets` and returns what it sent on its `out` pin.
+#[cfg(all(feature = "vorbis_encoder", feature = "symphonia"))]
+async fn run_node<N: ProcessorNode + 'static>(node: N, packets: Vec<Packet>) -> Vec<Packet> {
+    let (input_tx, input_rx) = mpsc::channel(packets.len().max(1));
+    let inputs = HashMap::from([("in".to_string(), input_rx)]);
+    let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+    let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+    for packet in packets {
+        input_tx.send(packet).await.unwrap();
+    }
+    drop(input_tx);
+    handle.await.unwrap().unwrap();
+    mock_sender.get_packets_for_pin("out").await
+}
+
+/// Decodes demuxed Vorbis packets, checking that the output is contiguous in time.
+#[cfg(all(feature = "vorbis_encoder", feature = "symphonia"))]
+async fn decode_vorbis(packets: Vec<Packet>) -> Vec<f32> {
+    use crate::audio::codecs::vorbis::{VorbisDecoderConfig, VorbisDecoderNode};
+
+    let node = VorbisDecoderNode::new(VorbisDecoderConfig::default()).unwrap();
+    let mut decoded = Vec::new();
+    let mut next_us = 0;
+    for packet in run_node(node, packets).await {
+        let Packet::Audio(frame) = packet else { panic!("expected audio output") };
+        if let Some(metadata) = &frame.metadata {
+            assert_eq!(metadata.timestamp_us, Some(next_us));
+            next_us += metadata.duration_us.unwrap();
+        }
+        decoded.extend_from_slice(frame.samples());
+    }
+    decoded
+}
+
+#[cfg(all(feature = "vorbis_encoder", feature = "symphonia"))]
+#[tokio::test]
+#[allow(clippy::cast_precision_loss)]
+async fn test_ogg_vorbis_roundtrip() {
+    use super::ogg::{OggMuxerCodec, SymphoniaOggDemuxerConfig, SymphoniaOggDemuxerNode};
+    use crate::audio::codecs::vorbis_encoder::{VorbisEncoderConfig, VorbisEncoderNode};
+    use streamkit_core::types::AudioFrame;
+
+    // 0.9 s of a 440 Hz tone, which ends partway into a block
+    let samples: Vec<f32> = (0..43_200)
+        .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48_000.0).sin() * 0.5)
+        .collect();
+    let frames =
+        samples.chunks(960).map(|chunk| Packet::Audio(AudioFrame::new(48_000, 1, chunk.to_vec())));
+    let encoded =
+        run_node(VorbisEncoderNode::new(VorbisEncoderConfig::default()).unwrap(), frames.collect())
+            .await;
+
+    let muxer = OggMuxerNode::new(OggMuxerConfig {
+        codec: OggMuxerCodec::Vorbis,
+        ..OggMuxerConfig::default()
+    });
+    let ogg = run_node(muxer, encoded.clone()).await;
+
+    let node = OggDemuxerNode::new(OggDemuxerConfig { codec: OggMuxerCodec::Vorbis });
+    let demuxed = run_node(node, ogg.clone()).await;
+    let node =
+        SymphoniaOggDemuxerNode::new(SymphoniaOggDemuxerConfig { codec: OggMuxerCodec::Vorbis });
+    let symphonia_demuxed = run_node(node, ogg).await;
+
+    // Every packet survives the container, audio packets with their timing. The muxer ends
+    // the stream with an empty packet.
+    let demuxed: Vec<Packet> = demuxed
+        .into_iter()
+        .filter(|packet| !matches!(packet, Packet::Binary { data, .. } if data.is_empty()))
+        .collect();
+    assert_eq!(demuxed.len(), encoded.len());
+    assert_eq!(symphonia_demuxed.len(), encoded.len());
+    for (original, packet) in encoded.iter().zip(&demuxed) {
+        let (
+            Packet::Binary { data: original, metadata: original_metadata, .. },
+            Packet::Binary { data, metadata, .. },
+        ) = (original, packet)
+        else {
+            panic!("expected binary packets");
+        };
+        assert_eq!(data, original);
+        assert_eq!(metadata.is_some(), original_metadata.is_some());
+    }
+
+    for packets in [demuxed, symphonia_demuxed] {
+        let decoded = decode_vorbis(packets).await;
+        assert_eq!(decoded.len(), samples.len());
+        let noise: f32 = samples.iter().zip(&decoded).map(|(a, b)| (a - b) * (a - b)).sum();
+        let signal: f32 = samples.iter().map(|a| a * a).sum();
+        assert!(signal / noise > 100.0, "SNR {:.1} dB", 10.0 * (signal / noise).log10());
+    }
+}
+
+#[cfg(all(feature = "vorbis_encoder", feature = "symphonia"))]
+#[tokio::test]
+async fn test_ogg_vorbis_demux_sample_file() {
+    use super::ogg::{OggMuxerCodec, SymphoniaOggDemuxerConfig, SymphoniaOggDemuxerNode};
+
+    // One second of 48 kHz mono silence in short blocks. Small chunks split the Ogg pages
+    // across input packets.
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/audio/silence_vorbis.ogg");
+    let chunks: Vec<Packet> = std::fs::read(&path)
+        .unwrap()
+        .chunks(100)
+        .map(|chunk| create_test_binary_packet(chunk.to_vec()))
+        .collect();
+    let demuxer =
+        SymphoniaOggDemuxerNode::new(SymphoniaOggDemuxerConfig { codec: OggMuxerCodec::Vorbis });
+    let decoded = decode_vorbis(run_node(demuxer, chunks).await).await;
+
+    assert_eq!(decoded.len(), 48_000);
+    assert!(decoded.iter().all(|s| s.abs() < 1e-6));
+}
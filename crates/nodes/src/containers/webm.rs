@@ -13,7 +13,7 @@ use streamkit_core::stats::NodeStatsTracker;
 use streamkit_core::types::{Packet, PacketType};
 use streamkit_core::{
     state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
-    StreamKitError,
+    StreamKitError, TrackKind,
 };
 use webm::mux::{AudioCodecId, SegmentBuilder, SegmentMode, Writer};
 
@@ -259,6 +259,9 @@ pub struct WebMMuxerConfig {
     pub chunk_size: usize,
     /// Streaming mode: "live" for real-time streaming (no duration), "file" for complete files with duration (default)
     pub streaming_mode: WebMStreamingMode,
+    /// Place frames on the pipeline's shared media clock instead of using packet timestamps
+    /// as-is. Corrects source drift and lets separately muxed tracks share one timeline.
+    pub clock_sync: bool,
 }
 
 impl Default for WebMMuxerConfig {
@@ -268,6 +271,7 @@ impl Default for WebMMuxerConfig {
             channels: 2,
             chunk_size: DEFAULT_CHUNK_SIZE,
             streaming_mode: WebMStreamingMode::default(),
+            clock_sync: false,
         }
     }
 }
@@ -385,6 +389,15 @@ impl ProcessorNode for WebMMuxerNode {
 
         let mut current_timestamp_ns = 0u64;
         let mut header_sent = false;
+        let track_clock = if self.config.clock_sync {
+            let clock = context.media_clock.as_ref();
+            if clock.is_none() {
+                tracing::warn!("clock_sync requested but no media clock is available");
+            }
+            clock.map(|c| c.register_track(format!("{node_name}/audio"), TrackKind::Audio))
+        } else {
+            None
+        };
 
         tracing::info!("WebM segment built, entering receive loop to process incoming packets");
         while let Some(packet) = context.recv_with_cancellation(&mut input_rx).await {
@@ -413,6 +426,9 @@ impl ProcessorNode for WebMMuxerNode {
                     // No metadata: fallback to assuming 20ms per packet
                     current_timestamp_ns = packet_count * 20_000_000;
                 }
+                if let Some(track_clock) = &track_clock {
+                    current_timestamp_ns = track_clock.observe(current_timestamp_ns / 1000) * 1000;
+                }
 
                 // For audio, all frames are effectively "keyframes" (can start playback from any point)
                 let is_keyframe = true;
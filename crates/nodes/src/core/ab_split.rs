@@ -0,0 +1,303 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! A/B split node - Routes packets to one of two outputs for live experiments
+//!
+//! Lets teams compare two processing chains (e.g. STT or translation models) on real traffic.
+//! Telemetry events carry the experiment name and arm so results can be grouped downstream.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::PacketType;
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// What a routing decision applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitBy {
+    /// Hash `key` once and send the whole stream to one arm (sticky per session)
+    #[default]
+    Key,
+    /// Spread individual packets across both arms in the configured ratio
+    Packet,
+}
+
+/// Configuration for the AbSplitNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AbSplitConfig {
+    /// Fraction of traffic routed to output `a` (0.0-1.0); the rest goes to `b`.
+    #[schemars(range(min = 0.0, max = 1.0), extend("tunable" = true))]
+    pub ratio_a: f64,
+    /// Whether the ratio applies to streams (by key hash) or to individual packets
+    pub split_by: SplitBy,
+    /// Key hashed in `key` mode. Defaults to the session ID, so each session lands on a
+    /// stable arm.
+    pub key: Option<String>,
+    /// Experiment name included in telemetry events
+    pub experiment: String,
+}
+
+impl Default for AbSplitConfig {
+    fn default() -> Self {
+        Self { ratio_a: 0.5, split_by: SplitBy::Key, key: None, experiment: "ab".to_string() }
+    }
+}
+
+/// Resolution of the ratio, in parts per `BUCKETS`.
+const BUCKETS: u64 = 10_000;
+
+/// Converts `ratio_a` to parts per `BUCKETS` so splitting uses exact integer arithmetic.
+fn ratio_to_buckets(ratio_a: f64) -> u64 {
+    // Float-to-int conversion is fine: the value is clamped to 0.0..=10_000.0
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let buckets = (ratio_a.clamp(0.0, 1.0) * 10_000.0).round() as u64;
+    buckets
+}
+
+/// FNV-1a, chosen for being stable across Rust versions and platforms so a key keeps its
+/// arm across server restarts.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Picks the arm for `key` given the share of traffic routed to `a`.
+fn arm_for_key(key: &str, ratio_a: f64) -> Arm {
+    if fnv1a(key) % BUCKETS < ratio_to_buckets(ratio_a) {
+        Arm::A
+    } else {
+        Arm::B
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arm {
+    A,
+    B,
+}
+
+impl Arm {
+    const fn pin(self) -> &'static str {
+        match self {
+            Self::A => "a",
+            Self::B => "b",
+        }
+    }
+}
+
+/// Deterministic weighted round-robin for `packet` mode: over any window the share of
+/// packets sent to `a` stays within one packet of `ratio_a`.
+#[derive(Debug, Default)]
+struct PacketSplitter {
+    credit: u64,
+}
+
+impl PacketSplitter {
+    fn next(&mut self, ratio_a: f64) -> Arm {
+        self.credit += ratio_to_buckets(ratio_a);
+        if self.credit >= BUCKETS {
+            self.credit -= BUCKETS;
+            Arm::A
+        } else {
+            Arm::B
+        }
+    }
+}
+
+/// A node that routes its input to output `a` or `b`.
+///
+/// In `key` mode (default) the arm is chosen once by hashing `key` (the session ID unless
+/// configured), so a whole session stays on one chain; changing `ratio_a` at runtime
+/// re-evaluates the assignment. In `packet` mode packets are interleaved across both arms
+/// in the configured ratio.
+///
+/// Emits `ab_split.assigned` telemetry whenever the stream's arm is (re)assigned in `key`
+/// mode, and `ab_split.summary` with per-arm packet counts on shutdown. Both carry the
+/// `experiment` name. Answers the `"stats"` query with the same counts.
+pub struct AbSplitNode {
+    config: AbSplitConfig,
+}
+
+impl AbSplitNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: AbSplitConfig = config_helpers::parse_config_optional(params)?;
+            if !(0.0..=1.0).contains(&config.ratio_a) {
+                return Err(StreamKitError::Configuration(
+                    "ratio_a must be between 0.0 and 1.0".to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for AbSplitNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        [Arm::A, Arm::B]
+            .into_iter()
+            .map(|arm| OutputPin {
+                name: arm.pin().to_string(),
+                produces_type: PacketType::Passthrough,
+                cardinality: PinCardinality::Broadcast,
+            })
+            .collect()
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        let mut input_rx = context.take_input("in")?;
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let experiment = self.config.experiment.clone();
+        let key = self
+            .config
+            .key
+            .clone()
+            .or_else(|| context.session_id.clone())
+            .unwrap_or_else(|| node_name.clone());
+        let mut ratio_a = self.config.ratio_a;
+        let mut splitter = PacketSplitter::default();
+        let mut counts = [0u64; 2];
+
+        let assign = |ratio_a: f64| {
+            let arm = arm_for_key(&key, ratio_a);
+            tracing::info!("AbSplitNode '{}' assigned arm '{}'", experiment, arm.pin());
+            telemetry.emit(
+                "ab_split.assigned",
+                serde_json::json!({ "experiment": experiment, "arm": arm.pin(), "ratio_a": ratio_a }),
+            );
+            arm
+        };
+        let mut sticky_arm = (self.config.split_by == SplitBy::Key).then(|| assign(ratio_a));
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+
+                    let arm = sticky_arm.unwrap_or_else(|| splitter.next(ratio_a));
+                    counts[usize::from(arm == Arm::B)] += 1;
+                    if context.output_sender.send(arm.pin(), packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        break;
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        let new_ratio = params.get("ratio_a").and_then(serde_json::Value::as_f64);
+                        match new_ratio {
+                            Some(r) if (0.0..=1.0).contains(&r) => {
+                                ratio_a = r;
+                                if sticky_arm.is_some() {
+                                    sticky_arm = Some(assign(ratio_a));
+                                }
+                            }
+                            Some(r) => tracing::warn!("AbSplitNode ignoring invalid ratio_a: {}", r),
+                            None => {}
+                        }
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } if kind == "stats" => {
+                        let _ = reply.send(Ok(serde_json::json!({
+                            "experiment": experiment,
+                            "a": counts[0],
+                            "b": counts[1],
+                        })));
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        telemetry.emit(
+            "ab_split.summary",
+            serde_json::json!({ "experiment": experiment, "a": counts[0], "b": counts[1] }),
+        );
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(AbSplitConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize AbSplitConfig schema");
+            return;
+        },
+    };
+
+    let factory = AbSplitNode::factory();
+    registry.register_dynamic_with_description(
+        "core::ab_split",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "routing".to_string()],
+        false,
+        "Routes packets to output `a` or `b` by a configurable ratio, either per stream \
+         (sticky key hash) or per packet. Use it to compare two processing chains on live traffic.",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_splitter_honours_ratio() {
+        let mut splitter = PacketSplitter::default();
+        let arms: Vec<Arm> = (0..10).map(|_| splitter.next(0.3)).collect();
+        assert_eq!(arms.iter().filter(|a| **a == Arm::A).count(), 3);
+
+        let mut splitter = PacketSplitter::default();
+        assert!((0..5).all(|_| splitter.next(0.0) == Arm::B));
+        assert!((0..5).all(|_| splitter.next(1.0) == Arm::A));
+    }
+
+    #[test]
+    fn test_key_assignment_is_stable_and_follows_ratio() {
+        assert_eq!(arm_for_key("session-1", 0.5), arm_for_key("session-1", 0.5));
+        assert_eq!(arm_for_key("session-1", 0.0), Arm::B);
+        assert_eq!(arm_for_key("session-1", 1.0), Arm::A);
+
+        let to_a = (0..1000).filter(|i| arm_for_key(&format!("s{i}"), 0.2) == Arm::A).count();
+        assert!((150..250).contains(&to_a), "expected ~200 keys on arm a, got {to_a}");
+    }
+
+    #[test]
+    fn test_fnv1a_reference_values() {
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,276 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Consent gate node - Blocks a branch until a consent event arrives
+//!
+//! Call-recording deployments must not persist audio before the caller agrees. Placing this
+//! node in front of a recorder drops everything until a `consent.granted` Custom packet is
+//! received on its `consent` pin, and closes again on `consent.revoked`.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{CustomPacketData, Packet, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Configuration for the ConsentGateNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ConsentGateConfig {
+    /// Custom packet `type_id` that opens the gate
+    pub granted_type_id: String,
+    /// Custom packet `type_id` that closes the gate again
+    pub revoked_type_id: String,
+}
+
+impl Default for ConsentGateConfig {
+    fn default() -> Self {
+        Self {
+            granted_type_id: "consent.granted".to_string(),
+            revoked_type_id: "consent.revoked".to_string(),
+        }
+    }
+}
+
+/// Consent tracking, separated from I/O.
+#[derive(Debug, Default)]
+struct ConsentState {
+    granted: bool,
+    /// Packets dropped while consent was missing
+    blocked: u64,
+}
+
+impl ConsentState {
+    /// Applies a consent event, returning the new state if it changed.
+    fn on_event(&mut self, config: &ConsentGateConfig, event: &CustomPacketData) -> Option<bool> {
+        let granted = if event.type_id == config.granted_type_id {
+            true
+        } else if event.type_id == config.revoked_type_id {
+            false
+        } else {
+            return None;
+        };
+        (self.granted != granted).then(|| {
+            self.granted = granted;
+            granted
+        })
+    }
+
+    /// Returns whether a media packet may pass, counting it otherwise.
+    const fn admit(&mut self) -> bool {
+        if !self.granted {
+            self.blocked += 1;
+        }
+        self.granted
+    }
+}
+
+/// A node that forwards `in` to `out` only while consent is granted.
+///
+/// The gate starts closed. A Custom packet with `granted_type_id` on the `consent` pin opens
+/// it and one with `revoked_type_id` closes it; other packets on that pin are ignored.
+/// Packets arriving while the gate is closed are dropped, never buffered, so nothing from
+/// before consent reaches the recording branch. Consent events take priority over queued
+/// media.
+///
+/// Every transition emits a `consent.granted` or `consent.revoked` telemetry event carrying
+/// the triggering packet's data, for audit trails. Answers the `"status"` query with the
+/// current state and the number of blocked packets.
+pub struct ConsentGateNode {
+    config: ConsentGateConfig,
+}
+
+impl ConsentGateNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: ConsentGateConfig = config_helpers::parse_config_optional(params)?;
+            if config.granted_type_id.is_empty() || config.granted_type_id == config.revoked_type_id
+            {
+                return Err(StreamKitError::Configuration(
+                    "granted_type_id must be non-empty and differ from revoked_type_id".to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for ConsentGateNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "consent".to_string(),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Passthrough,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut consent_rx = context.take_input("consent")?;
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut consent = ConsentState::default();
+        let mut consent_open = true;
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        loop {
+            tokio::select! {
+                biased;
+
+                maybe_event = consent_rx.recv(), if consent_open => {
+                    let Some(Packet::Custom(event)) = maybe_event else {
+                        // A closed consent input keeps the last decision in force.
+                        consent_open = maybe_event.is_some();
+                        continue;
+                    };
+                    if let Some(granted) = consent.on_event(&self.config, &event) {
+                        let event_type = if granted { "consent.granted" } else { "consent.revoked" };
+                        tracing::info!(blocked = consent.blocked, "ConsentGateNode: {}", event_type);
+                        telemetry.emit(event_type, serde_json::json!({ "data": event.data }));
+                    }
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                        let _ = reply.send(Ok(serde_json::json!({
+                            "granted": consent.granted,
+                            "blocked": consent.blocked,
+                        })));
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats.received();
+                    if !consent.admit() {
+                        stats.discarded();
+                        stats.maybe_send();
+                        continue;
+                    }
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(ConsentGateConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize ConsentGateConfig schema");
+            return;
+        },
+    };
+
+    let factory = ConsentGateNode::factory();
+    registry.register_dynamic_with_description(
+        "core::consent_gate",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "routing".to_string()],
+        false,
+        "Blocks packets on a branch until a consent.granted Custom event arrives on the \
+         consent pin, and blocks again on consent.revoked. Use it in front of recorders \
+         where call recording requires the caller's agreement.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test setup failures should panic
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_test_audio_packet, create_test_context};
+    use std::collections::HashMap;
+    use streamkit_core::types::CustomEncoding;
+    use tokio::sync::mpsc;
+
+    fn event(type_id: &str) -> CustomPacketData {
+        CustomPacketData {
+            type_id: type_id.to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({ "agent": "a-17" }),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_consent_transitions() {
+        let config = ConsentGateConfig::default();
+        let mut state = ConsentState::default();
+
+        assert!(!state.admit());
+        assert_eq!(state.on_event(&config, &event("vad/speech@1")), None);
+        assert_eq!(state.on_event(&config, &event("consent.granted")), Some(true));
+        assert_eq!(state.on_event(&config, &event("consent.granted")), None);
+        assert!(state.admit());
+        assert_eq!(state.on_event(&config, &event("consent.revoked")), Some(false));
+        assert!(!state.admit());
+        assert_eq!(state.blocked, 2);
+    }
+
+    async fn run_gate(consent: Vec<CustomPacketData>, media: usize) -> usize {
+        let (in_tx, in_rx) = mpsc::channel(16);
+        let (consent_tx, consent_rx) = mpsc::channel(16);
+        for event in consent {
+            consent_tx.send(Packet::Custom(Arc::new(event))).await.unwrap();
+        }
+        for _ in 0..media {
+            in_tx.send(create_test_audio_packet(48000, 1, 960, 0.1)).await.unwrap();
+        }
+        drop((in_tx, consent_tx));
+
+        let inputs =
+            HashMap::from([("in".to_string(), in_rx), ("consent".to_string(), consent_rx)]);
+        let (context, sender, _state_rx) = create_test_context(inputs, 10);
+        let node = (ConsentGateNode::factory())(None).unwrap();
+        node.run(context).await.unwrap();
+        sender.get_packets_for_pin("out").await.len()
+    }
+
+    #[tokio::test]
+    async fn test_gate_blocks_until_granted() {
+        assert_eq!(run_gate(vec![], 3).await, 0);
+        assert_eq!(run_gate(vec![event("consent.granted")], 3).await, 3);
+        assert_eq!(run_gate(vec![event("consent.granted"), event("consent.revoked")], 3).await, 0);
+    }
+}
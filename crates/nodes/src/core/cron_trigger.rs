@@ -0,0 +1,499 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Cron trigger node - Emits a packet on a cron schedule
+//!
+//! Lets long-lived sessions drive periodic work (flushing summaries, rotating recordings,
+//! health pings through a script node) from inside the graph.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{CustomEncoding, CustomPacketData, Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Type id of the Custom packets emitted in `custom` output mode.
+pub const CRON_TICK_TYPE_ID: &str = "core::cron/tick@1";
+
+/// How far ahead to look for the next matching time before treating a schedule as
+/// unsatisfiable (e.g. `0 0 30 2 *`). Eight years always contains a Feb 29.
+const MAX_SEARCH_DAYS: u64 = 366 * 8;
+
+/// Packet kind emitted on each tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CronOutput {
+    /// `core::cron/tick@1` Custom packet with the fire time, tick count and `payload`
+    #[default]
+    Custom,
+    /// Text packet containing `text` (or the RFC 3339 fire time when unset)
+    Text,
+}
+
+/// Configuration for the CronTriggerNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CronTriggerConfig {
+    /// Cron expression evaluated in UTC: `minute hour day-of-month month day-of-week`,
+    /// optionally prefixed with a seconds field. Fields accept `*`, numbers, ranges (`1-5`),
+    /// lists (`0,30`) and steps (`*/15`). Day-of-week is 0-7 with 0 and 7 both Sunday.
+    #[schemars(extend("tunable" = true))]
+    pub schedule: String,
+    /// Packet kind to emit
+    pub output: CronOutput,
+    /// Text to emit in `text` mode (defaults to the RFC 3339 fire time)
+    #[schemars(extend("tunable" = true))]
+    pub text: Option<String>,
+    /// Arbitrary JSON copied into each tick's `payload` in `custom` mode
+    #[schemars(extend("tunable" = true))]
+    pub payload: serde_json::Value,
+}
+
+impl Default for CronTriggerConfig {
+    fn default() -> Self {
+        Self {
+            schedule: "* * * * *".to_string(),
+            output: CronOutput::Custom,
+            text: None,
+            payload: serde_json::Value::Null,
+        }
+    }
+}
+
+/// A parsed cron expression.
+///
+/// Each field is a bitset of allowed values. As in classic cron, when both day-of-month
+/// and day-of-week are restricted a day matches if either one does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parses a 5-field (or 6-field, with leading seconds) cron expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first invalid field.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", fields.as_slice()),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(format!("expected 5 or 6 fields, got {n}")),
+        };
+
+        let days_of_week = parse_field(rest[4], 0, 7, "day-of-week")?;
+        // 7 is an alias for Sunday
+        let days_of_week = (days_of_week | (days_of_week >> 7)) & 0x7f;
+
+        Ok(Self {
+            seconds: parse_field(seconds, 0, 59, "second")?,
+            minutes: parse_field(rest[0], 0, 59, "minute")?,
+            hours: parse_field(rest[1], 0, 23, "hour")?,
+            days_of_month: parse_field(rest[2], 1, 31, "day-of-month")?,
+            months: parse_field(rest[3], 1, 12, "month")?,
+            days_of_week,
+            dom_restricted: !rest[2].starts_with('*'),
+            dow_restricted: !rest[4].starts_with('*'),
+        })
+    }
+
+    /// Returns the first matching time strictly after `after` (Unix seconds, UTC),
+    /// or `None` if the schedule never fires.
+    pub fn next_after(&self, after: u64) -> Option<u64> {
+        let start = after + 1;
+        let first_day = start / 86_400;
+        for day in first_day..first_day + MAX_SEARCH_DAYS {
+            let from = if day == first_day { start % 86_400 } else { 0 };
+            if self.matches_day(day) {
+                if let Some(secs) = self.first_time_of_day(from) {
+                    return Some(day * 86_400 + secs);
+                }
+            }
+        }
+        None
+    }
+
+    const fn matches_day(&self, day: u64) -> bool {
+        let (_, month, dom) = civil_from_days(day);
+        if !has(self.months, month) {
+            return false;
+        }
+        let weekday = (day + 4) % 7; // 1970-01-01 was a Thursday
+        let month_day_ok = has(self.days_of_month, dom);
+        let weekday_ok = has(self.days_of_week, weekday);
+        if self.dom_restricted && self.dow_restricted {
+            month_day_ok || weekday_ok
+        } else {
+            month_day_ok && weekday_ok
+        }
+    }
+
+    fn first_time_of_day(&self, from: u64) -> Option<u64> {
+        for hour in (from / 3600)..24 {
+            if !has(self.hours, hour) {
+                continue;
+            }
+            for minute in 0..60 {
+                if !has(self.minutes, minute) || (hour * 60 + minute + 1) * 60 <= from {
+                    continue;
+                }
+                for second in 0..60 {
+                    let secs = hour * 3600 + minute * 60 + second;
+                    if secs >= from && has(self.seconds, second) {
+                        return Some(secs);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+const fn has(set: u64, value: u64) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parses one cron field into a bitset of values in `min..=max`.
+fn parse_field(field: &str, min: u64, max: u64, name: &str) -> Result<u64, String> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u64 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("invalid step '{step}' in {name} field"))?;
+                (range, step)
+            },
+            None => (part, 1),
+        };
+
+        let parse_value = |v: &str| -> Result<u64, String> {
+            v.parse::<u64>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .ok_or_else(|| format!("{name} value '{v}' must be between {min} and {max}"))
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (parse_value(lo)?, parse_value(hi)?)
+        } else {
+            let value = parse_value(range)?;
+            // `5/15` means "from 5, every 15"
+            (value, if step > 1 { max } else { value })
+        };
+        if lo > hi {
+            return Err(format!("invalid range '{range}' in {name} field"));
+        }
+
+        let mut value = lo;
+        while value <= hi {
+            set |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(set)
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) civil date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm, restricted to dates after 1970.
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Formats Unix seconds as an RFC 3339 UTC timestamp.
+fn format_rfc3339(secs: u64) -> String {
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let tod = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        tod / 3600,
+        (tod / 60) % 60,
+        tod % 60
+    )
+}
+
+fn unix_now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+/// A source node that emits a packet each time a cron schedule fires.
+///
+/// Times are evaluated in UTC against the system clock. Ticks missed while the node was
+/// blocked on backpressure (or the host was suspended) are skipped rather than replayed.
+///
+/// The schedule, `text` and `payload` can be changed at runtime via `UpdateParams`.
+pub struct CronTriggerNode {
+    config: CronTriggerConfig,
+    schedule: CronSchedule,
+}
+
+impl CronTriggerNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: CronTriggerConfig = config_helpers::parse_config_optional(params)?;
+            let schedule = CronSchedule::parse(&config.schedule).map_err(|e| {
+                StreamKitError::Configuration(format!("Invalid cron schedule: {e}"))
+            })?;
+            Ok(Box::new(Self { config, schedule }))
+        })
+    }
+
+    fn output_type(&self) -> PacketType {
+        match self.config.output {
+            CronOutput::Custom => PacketType::Custom { type_id: CRON_TICK_TYPE_ID.to_string() },
+            CronOutput::Text => PacketType::Text,
+        }
+    }
+
+    fn tick_packet(&self, fired_at: u64, count: u64) -> Packet {
+        let metadata = PacketMetadata {
+            timestamp_us: Some(fired_at * 1_000_000),
+            duration_us: None,
+            sequence: Some(count),
+        };
+        match self.config.output {
+            CronOutput::Custom => Packet::Custom(Arc::new(CustomPacketData {
+                type_id: CRON_TICK_TYPE_ID.to_string(),
+                encoding: CustomEncoding::Json,
+                data: serde_json::json!({
+                    "schedule": self.config.schedule,
+                    "fired_at": format_rfc3339(fired_at),
+                    "fired_at_ms": fired_at * 1000,
+                    "count": count,
+                    "payload": self.config.payload,
+                }),
+                metadata: Some(metadata),
+            })),
+            CronOutput::Text => Packet::Text(
+                self.config.text.clone().unwrap_or_else(|| format_rfc3339(fired_at)).into(),
+            ),
+        }
+    }
+
+    fn apply_update(&mut self, params: &serde_json::Value) {
+        if let Some(schedule) = params.get("schedule").and_then(serde_json::Value::as_str) {
+            match CronSchedule::parse(schedule) {
+                Ok(parsed) => {
+                    tracing::info!("CronTriggerNode schedule updated to '{}'", schedule);
+                    self.config.schedule = schedule.to_string();
+                    self.schedule = parsed;
+                },
+                Err(e) => tracing::warn!("CronTriggerNode ignoring invalid schedule: {}", e),
+            }
+        }
+        if let Some(text) = params.get("text") {
+            self.config.text = text.as_str().map(ToString::to_string);
+        }
+        if let Some(payload) = params.get("payload") {
+            self.config.payload = payload.clone();
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for CronTriggerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: self.output_type(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(mut self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes wait for the pipeline's Start signal before emitting
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::UpdateParams(params)) => self.apply_update(&params),
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::Shutdown) | None => return Ok(()),
+            }
+        }
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut count = 0u64;
+        let mut last_fired = 0u64;
+
+        loop {
+            // Never fire the same second twice, even if the clock reads early after a sleep
+            let now = unix_now();
+            let Some(next) = self.schedule.next_after(now.as_secs().max(last_fired)) else {
+                state_helpers::emit_failed(
+                    &context.state_tx,
+                    &node_name,
+                    format!("Schedule '{}' never fires", self.config.schedule),
+                );
+                return Err(StreamKitError::Configuration(format!(
+                    "Cron schedule '{}' never fires",
+                    self.config.schedule
+                )));
+            };
+            let wait = Duration::from_secs(next).saturating_sub(now);
+
+            tokio::select! {
+                () = tokio::time::sleep(wait) => {
+                    last_fired = next;
+                    count += 1;
+                    let packet = self.tick_packet(next, count);
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        break;
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                }
+                msg = context.control_rx.recv() => match msg {
+                    Some(NodeControlMessage::UpdateParams(params)) => self.apply_update(&params),
+                    Some(NodeControlMessage::Query { kind, reply, .. }) => {
+                        reject_query(&kind, reply);
+                    }
+                    Some(NodeControlMessage::Start) => {}
+                    Some(NodeControlMessage::Shutdown) | None => break,
+                },
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(CronTriggerConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize CronTriggerConfig schema");
+            return;
+        },
+    };
+
+    let factory = CronTriggerNode::factory();
+    registry.register_dynamic_with_description(
+        "core::cron_trigger",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "timing".to_string()],
+        false,
+        "Emits a packet whenever a cron schedule (UTC) fires. Use it to trigger periodic \
+         work inside long-lived sessions, such as flushing summaries or health pings via a script node.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    // 2025-01-01T00:00:00Z, a Wednesday
+    const NEW_YEAR_2025: u64 = 1_735_689_600;
+
+    #[test]
+    fn test_civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(NEW_YEAR_2025 / 86_400), (2025, 1, 1));
+        assert_eq!(format_rfc3339(NEW_YEAR_2025 + 3_723), "2025-01-01T01:02:03Z");
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let s = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(s.minutes, (1 << 0) | (1 << 15) | (1 << 30) | (1 << 45));
+        assert_eq!(s.hours, ((1 << 18) - 1) & !((1 << 9) - 1));
+        assert_eq!(s.days_of_week, 0b0011_1110);
+
+        assert_eq!(CronSchedule::parse("0 0 * * 7").unwrap().days_of_week, 1);
+        assert_eq!(
+            CronSchedule::parse("5/20 * * * *").unwrap().minutes,
+            (1 << 5) | (1 << 25) | (1 << 45)
+        );
+
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("10-5 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        let every_minute = CronSchedule::parse("* * * * *").unwrap();
+        assert_eq!(every_minute.next_after(NEW_YEAR_2025), Some(NEW_YEAR_2025 + 60));
+        assert_eq!(every_minute.next_after(NEW_YEAR_2025 - 1), Some(NEW_YEAR_2025));
+
+        let every_second = CronSchedule::parse("* * * * * *").unwrap();
+        assert_eq!(every_second.next_after(NEW_YEAR_2025), Some(NEW_YEAR_2025 + 1));
+
+        // Weekdays at 09:30: Jan 1 2025 is a Wednesday
+        let weekday_morning = CronSchedule::parse("30 9 * * 1-5").unwrap();
+        assert_eq!(
+            weekday_morning.next_after(NEW_YEAR_2025),
+            Some(NEW_YEAR_2025 + 9 * 3600 + 1800)
+        );
+
+        // Saturday Jan 4 -> next weekday is Monday Jan 6
+        let sat = NEW_YEAR_2025 + 3 * 86_400 + 10 * 3600;
+        assert_eq!(
+            weekday_morning.next_after(sat),
+            Some(NEW_YEAR_2025 + 5 * 86_400 + 9 * 3600 + 1800)
+        );
+
+        // Leap day
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        let next = leap.next_after(NEW_YEAR_2025).unwrap();
+        assert_eq!(format_rfc3339(next), "2028-02-29T00:00:00Z");
+
+        assert_eq!(CronSchedule::parse("0 0 30 2 *").unwrap().next_after(NEW_YEAR_2025), None);
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // 1st of the month OR any Friday: Friday Jan 3 comes first
+        let s = CronSchedule::parse("0 12 1 * 5").unwrap();
+        assert_eq!(
+            s.next_after(NEW_YEAR_2025 + 13 * 3600),
+            Some(NEW_YEAR_2025 + 2 * 86_400 + 12 * 3600)
+        );
+    }
+}
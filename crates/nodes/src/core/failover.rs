@@ -0,0 +1,433 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Failover node - Forwards the highest-priority healthy input
+//!
+//! Enables redundant ingest (two MoQ relays, a backup file loop) by watching each input
+//! for packet gaps and switching to the next source when the active one stalls.
+
+use async_trait::async_trait;
+use futures::stream::{self, SelectAll, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Configuration for the FailoverNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FailoverConfig {
+    /// Number of inputs, named `in_0` (primary) through `in_{N-1}`.
+    /// Lower index = higher priority.
+    #[schemars(range(min = 2))]
+    pub num_inputs: usize,
+    /// An input is unhealthy once this long passes without a packet from it.
+    #[schemars(range(min = 1), extend("tunable" = true))]
+    pub gap_timeout_ms: u64,
+    /// How long a higher-priority input must stay healthy before traffic switches back
+    /// to it. Prevents flapping on a source that recovers intermittently.
+    #[schemars(extend("tunable" = true))]
+    pub recovery_ms: u64,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self { num_inputs: 2, gap_timeout_ms: 500, recovery_ms: 2000 }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SourceHealth {
+    last_packet: Option<Instant>,
+    /// Start of the current run of packets without a gap
+    healthy_since: Option<Instant>,
+    closed: bool,
+}
+
+impl SourceHealth {
+    fn is_healthy(&self, now: Instant, gap: Duration) -> bool {
+        !self.closed && self.last_packet.is_some_and(|t| now.duration_since(t) < gap)
+    }
+
+    fn healthy_for(&self, now: Instant, gap: Duration) -> Duration {
+        match self.healthy_since {
+            Some(since) if self.is_healthy(now, gap) => now.duration_since(since),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// A change of active input.
+#[derive(Debug, PartialEq, Eq)]
+struct Switch {
+    from: Option<usize>,
+    to: usize,
+    reason: &'static str,
+}
+
+/// Switching logic, separated from I/O so it can be exercised with synthetic clocks.
+#[derive(Debug)]
+struct FailoverState {
+    sources: Vec<SourceHealth>,
+    active: Option<usize>,
+    gap: Duration,
+    recovery: Duration,
+}
+
+impl FailoverState {
+    fn new(num_inputs: usize, gap: Duration, recovery: Duration) -> Self {
+        Self { sources: vec![SourceHealth::default(); num_inputs], active: None, gap, recovery }
+    }
+
+    fn on_packet(&mut self, index: usize, now: Instant) {
+        let source = &mut self.sources[index];
+        if !source.is_healthy(now, self.gap) {
+            source.healthy_since = Some(now);
+        }
+        source.last_packet = Some(now);
+    }
+
+    fn on_closed(&mut self, index: usize) {
+        self.sources[index].closed = true;
+    }
+
+    /// Re-evaluates which input should be active, returning the switch if one happened.
+    fn select(&mut self, now: Instant) -> Option<Switch> {
+        let current_ok = self.active.is_some_and(|a| self.sources[a].is_healthy(now, self.gap));
+
+        let candidate = if current_ok {
+            // Only preempt the active source for a higher-priority one that has been stable
+            // for the whole recovery window.
+            let active = self.active.unwrap_or(0);
+            (0..active).find(|&i| self.sources[i].healthy_for(now, self.gap) >= self.recovery)
+        } else {
+            (0..self.sources.len()).find(|&i| self.sources[i].is_healthy(now, self.gap))
+        };
+
+        match candidate {
+            Some(next) if Some(next) != self.active => {
+                let from = self.active.replace(next);
+                let reason = match from {
+                    None => "initial",
+                    Some(prev) if prev > next => "recovered",
+                    Some(prev) if self.sources[prev].closed => "closed",
+                    Some(_) => "gap",
+                };
+                Some(Switch { from, to: next, reason })
+            },
+            _ => None,
+        }
+    }
+
+    fn all_closed(&self) -> bool {
+        self.sources.iter().all(|s| s.closed)
+    }
+
+    fn status(&self, now: Instant) -> serde_json::Value {
+        let inputs: Vec<serde_json::Value> = self
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                serde_json::json!({
+                    "pin": format!("in_{i}"),
+                    "healthy": s.is_healthy(now, self.gap),
+                    "closed": s.closed,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "active": self.active.map(|i| format!("in_{i}")),
+            "inputs": inputs,
+        })
+    }
+}
+
+/// A node that forwards packets from the highest-priority healthy input.
+///
+/// Each input is healthy while packets keep arriving within `gap_timeout_ms`. When the
+/// active input stalls or closes, the node switches to the next healthy input in priority
+/// order; it returns to a higher-priority input once that input has been healthy for
+/// `recovery_ms`. Packets from inactive inputs are drained and discarded.
+///
+/// Every switch emits a `failover.switch` telemetry event, and `failover.all_down` fires when
+/// no input is healthy. While a backup is active the node reports `Degraded`.
+///
+/// Answers the `"status"` query with the active pin and per-input health.
+pub struct FailoverNode {
+    config: FailoverConfig,
+}
+
+impl FailoverNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: FailoverConfig = config_helpers::parse_config_optional(params)?;
+            if config.num_inputs < 2 {
+                return Err(StreamKitError::Configuration(
+                    "num_inputs must be at least 2".to_string(),
+                ));
+            }
+            if config.gap_timeout_ms == 0 {
+                return Err(StreamKitError::Configuration(
+                    "gap_timeout_ms must be greater than 0".to_string(),
+                ));
+            }
+            Ok(Box::new(Self { config }))
+        })
+    }
+
+    fn apply_update(state: &mut FailoverState, params: &serde_json::Value) {
+        if let Some(gap) = params.get("gap_timeout_ms").and_then(serde_json::Value::as_u64) {
+            if gap > 0 {
+                state.gap = Duration::from_millis(gap);
+            }
+        }
+        if let Some(recovery) = params.get("recovery_ms").and_then(serde_json::Value::as_u64) {
+            state.recovery = Duration::from_millis(recovery);
+        }
+    }
+}
+
+type InputStream = Pin<Box<dyn futures::Stream<Item = (usize, Option<Packet>)> + Send>>;
+
+#[async_trait]
+impl ProcessorNode for FailoverNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        (0..self.config.num_inputs)
+            .map(|i| InputPin {
+                name: format!("in_{i}"),
+                accepts_types: vec![PacketType::Any],
+                cardinality: PinCardinality::One,
+            })
+            .collect()
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Passthrough,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Merge all inputs into one stream tagged with the input index; each input ends
+        // with a `None` marker so closure counts as a health event.
+        let mut inputs: SelectAll<InputStream> = SelectAll::new();
+        for i in 0..self.config.num_inputs {
+            let rx = context.take_input(&format!("in_{i}"))?;
+            let packets =
+                stream::unfold(
+                    rx,
+                    |mut rx| async move { rx.recv().await.map(|packet| (packet, rx)) },
+                );
+            inputs.push(Box::pin(
+                packets.map(move |p| (i, Some(p))).chain(stream::once(async move { (i, None) })),
+            ));
+        }
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut failover = FailoverState::new(
+            self.config.num_inputs,
+            Duration::from_millis(self.config.gap_timeout_ms),
+            Duration::from_millis(self.config.recovery_ms),
+        );
+
+        // Periodic re-evaluation catches stalls even when no input delivers packets.
+        let mut health_check =
+            tokio::time::interval((failover.gap / 4).max(Duration::from_millis(10)));
+        health_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut all_down_reported = false;
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        loop {
+            let mut forward = None;
+            tokio::select! {
+                item = inputs.next() => match item {
+                    Some((i, Some(packet))) => {
+                        stats.received();
+                        failover.on_packet(i, Instant::now());
+                        forward = Some((i, packet));
+                    }
+                    Some((i, None)) => {
+                        tracing::info!("FailoverNode input in_{} closed", i);
+                        failover.on_closed(i);
+                        if failover.all_closed() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = health_check.tick() => {}
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::UpdateParams(params) => {
+                        Self::apply_update(&mut failover, &params);
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                        let _ = reply.send(Ok(failover.status(Instant::now())));
+                    }
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+
+            let now = Instant::now();
+            if let Some(switch) = failover.select(now) {
+                let from = switch.from.map(|p| format!("in_{p}"));
+                let active = switch.to;
+                tracing::info!(
+                    "FailoverNode switching {:?} -> in_{} ({})",
+                    from,
+                    active,
+                    switch.reason
+                );
+                telemetry.emit(
+                    "failover.switch",
+                    serde_json::json!({
+                        "from": from,
+                        "to": format!("in_{active}"),
+                        "reason": switch.reason,
+                    }),
+                );
+                if active == 0 {
+                    state_helpers::emit_running(&context.state_tx, &node_name);
+                } else {
+                    state_helpers::emit_degraded(
+                        &context.state_tx,
+                        &node_name,
+                        format!("Primary input unavailable, forwarding in_{active}"),
+                        Some(serde_json::json!({ "active": format!("in_{active}") })),
+                    );
+                }
+            }
+
+            if failover.sources.iter().any(|s| s.is_healthy(now, failover.gap)) {
+                all_down_reported = false;
+            } else if failover.active.is_some() && !all_down_reported {
+                tracing::warn!("FailoverNode: no healthy inputs");
+                telemetry.emit("failover.all_down", serde_json::json!({}));
+                all_down_reported = true;
+            }
+
+            if let Some((i, packet)) = forward {
+                if failover.active == Some(i) {
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        break;
+                    }
+                    stats.sent();
+                } else {
+                    stats.discarded();
+                }
+                stats.maybe_send();
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(FailoverConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize FailoverConfig schema");
+            return;
+        },
+    };
+
+    let factory = FailoverNode::factory();
+    registry.register_dynamic_with_description(
+        "core::failover",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "routing".to_string()],
+        false,
+        "Forwards packets from the highest-priority healthy input (in_0 is the primary). \
+         Switches to a backup when the active input stops delivering packets, and back once \
+         the primary has recovered. Useful for redundant ingest.",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAP: Duration = Duration::from_millis(500);
+    const RECOVERY: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_selects_primary_then_fails_over_on_gap() {
+        let t0 = Instant::now();
+        let mut state = FailoverState::new(2, GAP, RECOVERY);
+
+        state.on_packet(1, t0);
+        assert_eq!(state.select(t0), Some(Switch { from: None, to: 1, reason: "initial" }));
+
+        // Primary shows up: backup is healthy, so wait for the recovery window
+        state.on_packet(0, t0 + Duration::from_millis(100));
+        state.on_packet(1, t0 + Duration::from_millis(100));
+        assert_eq!(state.select(t0 + Duration::from_millis(100)), None);
+
+        let mut now = t0;
+        while now < t0 + Duration::from_millis(2100) {
+            now += Duration::from_millis(100);
+            state.on_packet(0, now);
+            state.on_packet(1, now);
+        }
+        assert_eq!(state.select(now), Some(Switch { from: Some(1), to: 0, reason: "recovered" }));
+
+        // Primary stalls while the backup keeps flowing
+        let stalled = now + GAP;
+        state.on_packet(1, stalled);
+        assert_eq!(state.select(stalled), Some(Switch { from: Some(0), to: 1, reason: "gap" }));
+    }
+
+    #[test]
+    fn test_closed_input_fails_over_immediately() {
+        let t0 = Instant::now();
+        let mut state = FailoverState::new(3, GAP, RECOVERY);
+        for i in 0..3 {
+            state.on_packet(i, t0);
+        }
+        state.select(t0);
+        assert_eq!(state.active, Some(0));
+
+        state.on_closed(0);
+        state.on_closed(1);
+        assert_eq!(state.select(t0), Some(Switch { from: Some(0), to: 2, reason: "closed" }));
+        assert!(!state.all_closed());
+    }
+
+    #[test]
+    fn test_no_switch_when_everything_is_down() {
+        let t0 = Instant::now();
+        let mut state = FailoverState::new(2, GAP, RECOVERY);
+        state.on_packet(0, t0);
+        state.select(t0);
+
+        let later = t0 + GAP * 4;
+        assert_eq!(state.select(later), None);
+        assert_eq!(state.active, Some(0));
+        assert_eq!(state.status(later)["inputs"][0]["healthy"], false);
+    }
+}
@@ -0,0 +1,454 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! I/O backends shared by the file read and write nodes.
+//!
+//! `tokio` is the portable default: every read or write hops through tokio's blocking pool.
+//! `io_uring` (Linux, `io_uring` feature) gives the file its own thread running a
+//! tokio-uring runtime, so reads and writes are submitted to the kernel ring instead of
+//! occupying blocking-pool threads. Chunks cross between that thread and the node over
+//! bounded channels, which keeps backpressure intact. Whenever the ring can't be used —
+//! another platform, the feature compiled out, or ring setup refused by the kernel or a
+//! seccomp policy — the node logs the reason and continues on the `tokio` backend.
+//!
+//! Both backends read straight into buffers that become the emitted chunks, so bytes are
+//! never copied between the read buffer and the packet. The `tokio` backend carves chunks
+//! from shared blocks and, on Linux, tells the kernel the file will be read sequentially,
+//! which widens its readahead for large inputs.
+//!
+//! `mmap` (Linux, reading only) maps the whole file and emits chunks as views of the
+//! mapping, so the page cache is the only copy of the data. It suits multi-gigabyte
+//! conversions where the file is not modified while it is read: a file truncated under
+//! the mapping makes later page faults fail with SIGBUS, which kills the process. Empty
+//! files and mapping failures fall back to `tokio`; file writers always use `tokio`.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Which I/O backend a file node uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileIoBackend {
+    /// tokio's file API (blocking thread pool). Works everywhere.
+    #[default]
+    Tokio,
+    /// io_uring via tokio-uring on a dedicated thread. Linux only; falls back to `tokio`.
+    IoUring,
+    /// Memory-maps the file and emits chunks as views of the mapping. Linux only, for
+    /// reading files that don't change while they are read; writers use `tokio`.
+    Mmap,
+}
+
+/// Minimum size of the block the `tokio` backend carves chunks from. Emitted chunks keep
+/// their block alive until every chunk cut from it has been dropped.
+pub(crate) const READ_BLOCK_SIZE: usize = 256 * 1024;
+
+/// Hints the kernel that `file` will be read front to back. Purely advisory.
+#[cfg(target_os = "linux")]
+fn advise_sequential(file: &tokio::fs::File, path: &str) {
+    if let Err(e) = rustix::fs::fadvise(file, 0, None, rustix::fs::Advice::Sequential) {
+        tracing::debug!("could not set sequential access hint for '{}': {}", path, e);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+const fn advise_sequential(_file: &tokio::fs::File, _path: &str) {}
+
+/// A file opened for sequential reading.
+pub enum FileSource {
+    Tokio {
+        file: tokio::fs::File,
+        buffer: BytesMut,
+        chunk_size: usize,
+    },
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    IoUring(tokio::sync::mpsc::Receiver<io::Result<Bytes>>),
+    /// The whole mapped file and the offset of the next chunk.
+    Mapped {
+        data: Bytes,
+        pos: usize,
+        chunk_size: usize,
+    },
+}
+
+impl FileSource {
+    /// Opens `path` for reading in chunks of at most `chunk_size` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from opening the file.
+    pub async fn open(path: &str, chunk_size: usize, backend: FileIoBackend) -> io::Result<Self> {
+        let chunk_size = chunk_size.max(1);
+        if backend == FileIoBackend::IoUring {
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            match uring::spawn_reader(path.into(), chunk_size).await {
+                Ok(chunks) => return Ok(Self::IoUring(chunks)),
+                Err(uring::StartError::Open(e)) => return Err(e),
+                Err(uring::StartError::Ring(e)) => {
+                    tracing::warn!("io_uring unavailable ({e}), reading '{path}' with tokio");
+                },
+            }
+            #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+            tracing::warn!("io_uring backend not available in this build, using tokio");
+        }
+        let file = tokio::fs::File::open(path).await?;
+        if backend == FileIoBackend::Mmap {
+            #[cfg(target_os = "linux")]
+            {
+                let std_file = file.into_std().await;
+                let mapped =
+                    tokio::task::spawn_blocking(move || (streamkit_mmap::map(&std_file), std_file))
+                        .await
+                        .map_err(io::Error::other)?;
+                match mapped {
+                    (Ok(Some(data)), _) => return Ok(Self::Mapped { data, pos: 0, chunk_size }),
+                    (Ok(None), std_file) => {
+                        tracing::debug!("'{path}' is empty, reading it with tokio");
+                        return Ok(Self::tokio(
+                            tokio::fs::File::from_std(std_file),
+                            path,
+                            chunk_size,
+                        ));
+                    },
+                    (Err(e), std_file) => {
+                        tracing::warn!("mmap failed ({e}), reading '{path}' with tokio");
+                        return Ok(Self::tokio(
+                            tokio::fs::File::from_std(std_file),
+                            path,
+                            chunk_size,
+                        ));
+                    },
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            tracing::warn!("mmap backend not available on this platform, using tokio");
+        }
+        Ok(Self::tokio(file, path, chunk_size))
+    }
+
+    fn tokio(file: tokio::fs::File, path: &str, chunk_size: usize) -> Self {
+        advise_sequential(&file, path);
+        let buffer = BytesMut::with_capacity(chunk_size.max(READ_BLOCK_SIZE));
+        Self::Tokio { file, buffer, chunk_size }
+    }
+
+    /// Returns the next chunk, or `None` at end of file.
+    ///
+    /// Cancel-safe with respect to data: a cancelled call never drops bytes that were
+    /// already handed out.
+    ///
+    /// # Errors
+    ///
+    /// Returns the underlying read error.
+    pub async fn next_chunk(&mut self) -> io::Result<Option<Bytes>> {
+        match self {
+            Self::Tokio { file, buffer, chunk_size } => {
+                if buffer.capacity() < *chunk_size {
+                    buffer.reserve((*chunk_size).max(READ_BLOCK_SIZE));
+                }
+                let n = file.read_buf(&mut buffer.limit(*chunk_size)).await?;
+                Ok((n > 0).then(|| buffer.split().freeze()))
+            },
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Self::IoUring(chunks) => chunks.recv().await.transpose(),
+            Self::Mapped { data, pos, chunk_size } => {
+                let end = data.len().min(*pos + *chunk_size);
+                let chunk = (*pos < end).then(|| data.slice(*pos..end));
+                *pos = end;
+                Ok(chunk)
+            },
+        }
+    }
+}
+
+/// A file opened (created or truncated) for sequential writing.
+pub enum FileSink {
+    Tokio(tokio::fs::File),
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    IoUring(uring::Writer),
+}
+
+impl FileSink {
+    /// Creates `path`, truncating it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from creating the file.
+    pub async fn create(path: &str, backend: FileIoBackend) -> io::Result<Self> {
+        if backend == FileIoBackend::IoUring {
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            match uring::spawn_writer(path.into()).await {
+                Ok(writer) => return Ok(Self::IoUring(writer)),
+                Err(uring::StartError::Open(e)) => return Err(e),
+                Err(uring::StartError::Ring(e)) => {
+                    tracing::warn!("io_uring unavailable ({e}), writing '{path}' with tokio");
+                },
+            }
+            #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+            tracing::warn!("io_uring backend not available in this build, using tokio");
+        }
+        Ok(Self::Tokio(tokio::fs::File::create(path).await?))
+    }
+
+    /// Writes all of `buffer` and leaves it empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the underlying write error.
+    pub async fn write_all(&mut self, buffer: &mut Vec<u8>) -> io::Result<()> {
+        match self {
+            Self::Tokio(file) => {
+                file.write_all(buffer).await?;
+                buffer.clear();
+                Ok(())
+            },
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Self::IoUring(writer) => {
+                let capacity = buffer.capacity();
+                writer.write(std::mem::replace(buffer, Vec::with_capacity(capacity))).await
+            },
+        }
+    }
+
+    /// Flushes outstanding writes and closes the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error hit while flushing or closing.
+    pub async fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Tokio(mut file) => file.flush().await,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Self::IoUring(writer) => writer.finish().await,
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod uring {
+    use bytes::{Bytes, BytesMut};
+    use std::io;
+    use std::path::PathBuf;
+    use tokio::sync::{mpsc, oneshot};
+
+    /// Chunks in flight between the ring thread and the node.
+    const CHANNEL_CAPACITY: usize = 4;
+
+    pub enum StartError {
+        /// The ring could not be set up; the caller should fall back to tokio.
+        Ring(io::Error),
+        /// The file itself could not be opened.
+        Open(io::Error),
+    }
+
+    /// Spawns the ring thread, runs `task` on it and waits for it to report startup.
+    async fn spawn<F, Fut>(name: &str, task: F) -> Result<(), StartError>
+    where
+        F: FnOnce(oneshot::Sender<Result<(), StartError>>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let (started_tx, started_rx) = oneshot::channel();
+        std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || match tokio_uring::Runtime::new(&tokio_uring::builder()) {
+                Ok(runtime) => runtime.block_on(task(started_tx)),
+                Err(e) => {
+                    let _ = started_tx.send(Err(StartError::Ring(e)));
+                },
+            })
+            .map_err(StartError::Ring)?;
+        started_rx
+            .await
+            .unwrap_or_else(|_| Err(StartError::Ring(io::Error::other("io_uring thread exited"))))
+    }
+
+    pub async fn spawn_reader(
+        path: PathBuf,
+        chunk_size: usize,
+    ) -> Result<mpsc::Receiver<io::Result<Bytes>>, StartError> {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        spawn("file-read-uring", move |started| async move {
+            let file = match tokio_uring::fs::File::open(&path).await {
+                Ok(file) => file,
+                Err(e) => {
+                    let _ = started.send(Err(StartError::Open(e)));
+                    return;
+                },
+            };
+            let _ = started.send(Ok(()));
+
+            let mut pos = 0u64;
+            loop {
+                let (result, buffer) = file.read_at(BytesMut::with_capacity(chunk_size), pos).await;
+                let chunk = match result {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        pos += n as u64;
+                        Ok(buffer.freeze())
+                    },
+                    Err(e) => Err(e),
+                };
+                let failed = chunk.is_err();
+                if tx.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+            let _ = file.close().await;
+        })
+        .await?;
+        Ok(rx)
+    }
+
+    pub struct Writer {
+        tx: mpsc::Sender<Vec<u8>>,
+        done: oneshot::Receiver<io::Result<()>>,
+    }
+
+    impl Writer {
+        pub async fn write(&mut self, buffer: Vec<u8>) -> io::Result<()> {
+            if self.tx.send(buffer).await.is_ok() {
+                return Ok(());
+            }
+            // The ring thread only stops taking buffers after a failed write.
+            match (&mut self.done).await {
+                Ok(Err(e)) => Err(e),
+                Ok(Ok(())) | Err(_) => Err(io::Error::other("io_uring writer stopped")),
+            }
+        }
+
+        pub async fn finish(self) -> io::Result<()> {
+            drop(self.tx);
+            self.done.await.unwrap_or_else(|_| Err(io::Error::other("io_uring writer stopped")))
+        }
+    }
+
+    pub async fn spawn_writer(path: PathBuf) -> Result<Writer, StartError> {
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(CHANNEL_CAPACITY);
+        let (done_tx, done) = oneshot::channel();
+        spawn("file-write-uring", move |started| async move {
+            let file = match tokio_uring::fs::File::create(&path).await {
+                Ok(file) => file,
+                Err(e) => {
+                    let _ = started.send(Err(StartError::Open(e)));
+                    return;
+                },
+            };
+            let _ = started.send(Ok(()));
+
+            let mut pos = 0u64;
+            let mut result = Ok(());
+            'buffers: while let Some(mut buffer) = rx.recv().await {
+                while !buffer.is_empty() {
+                    let (written, returned) = file.write_at(buffer, pos).await;
+                    buffer = returned;
+                    match written {
+                        Ok(0) => {
+                            result = Err(io::ErrorKind::WriteZero.into());
+                            break 'buffers;
+                        },
+                        Ok(n) => {
+                            pos += n as u64;
+                            buffer.drain(..n);
+                        },
+                        Err(e) => {
+                            result = Err(e);
+                            break 'buffers;
+                        },
+                    }
+                }
+            }
+            drop(rx);
+            let closed = file.close().await;
+            let _ = done_tx.send(result.and(closed));
+        })
+        .await?;
+        Ok(Writer { tx, done })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    async fn round_trip(backend: FileIoBackend) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("data.bin");
+        let path = path.to_str().unwrap();
+        let data: Vec<u8> = (0..100_000u32).map(|i| u8::try_from(i % 251).unwrap()).collect();
+
+        let mut sink = FileSink::create(path, backend).await.unwrap();
+        for chunk in data.chunks(7000) {
+            let mut buffer = chunk.to_vec();
+            sink.write_all(&mut buffer).await.unwrap();
+            assert!(buffer.is_empty());
+        }
+        sink.finish().await.unwrap();
+
+        let mut source = FileSource::open(path, 4096, backend).await.unwrap();
+        let mut read = Vec::new();
+        while let Some(chunk) = source.next_chunk().await.unwrap() {
+            assert!(chunk.len() <= 4096);
+            read.extend_from_slice(&chunk);
+        }
+        assert_eq!(read, data);
+    }
+
+    #[tokio::test]
+    async fn test_tokio_round_trip() {
+        round_trip(FileIoBackend::Tokio).await;
+    }
+
+    #[tokio::test]
+    async fn test_io_uring_round_trip() {
+        // Falls back to tokio where io_uring is unavailable, so this passes everywhere.
+        round_trip(FileIoBackend::IoUring).await;
+    }
+
+    #[tokio::test]
+    async fn test_mmap_round_trip() {
+        // Falls back to tokio off Linux, so this passes everywhere.
+        round_trip(FileIoBackend::Mmap).await;
+    }
+
+    #[tokio::test]
+    async fn test_mmap_chunks_are_views_of_one_mapping() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("data.bin");
+        std::fs::write(&path, vec![7u8; 10_000]).unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut source = FileSource::open(path, 4096, FileIoBackend::Mmap).await.unwrap();
+        let first = source.next_chunk().await.unwrap().unwrap();
+        let second = source.next_chunk().await.unwrap().unwrap();
+        let third = source.next_chunk().await.unwrap().unwrap();
+        assert!(source.next_chunk().await.unwrap().is_none());
+        assert_eq!((first.len(), second.len(), third.len()), (4096, 4096, 1808));
+        if cfg!(target_os = "linux") {
+            assert_eq!(first.as_ptr().wrapping_add(4096), second.as_ptr());
+        }
+
+        // Empty files can't be mapped and are read through tokio instead.
+        let empty = temp_dir.path().join("empty.bin");
+        std::fs::write(&empty, []).unwrap();
+        let mut source =
+            FileSource::open(empty.to_str().unwrap(), 4096, FileIoBackend::Mmap).await.unwrap();
+        assert!(source.next_chunk().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_io_uring_open_error_is_not_masked() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir.path().join("missing.bin");
+        let Err(err) =
+            FileSource::open(missing.to_str().unwrap(), 4096, FileIoBackend::IoUring).await
+        else {
+            panic!("opening a missing file should fail");
+        };
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -5,7 +5,6 @@
 //! File read node - Streams raw bytes from a file
 
 use async_trait::async_trait;
-use bytes::Bytes;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use streamkit_core::types::{Packet, PacketType};
@@ -13,16 +12,23 @@ use streamkit_core::{
     config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
     PinCardinality, ProcessorNode, StreamKitError,
 };
-use tokio::io::AsyncReadExt;
+
+use super::file_io::{FileIoBackend, FileSource};
 
 /// Configuration for the FileReadNode
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileReadConfig {
     /// Path to the file to read
+    #[schemars(extend("sensitive" = true, "enum_provider" = "readable_files"))]
     pub path: String,
     /// Size of chunks to read (default: 8192 bytes)
     #[serde(default = "default_chunk_size")]
     pub chunk_size: usize,
+    /// I/O backend (default: tokio). `io_uring` and `mmap` are Linux-only and fall back to
+    /// tokio when unavailable. `mmap` serves chunks straight from the page cache; use it only
+    /// for files that are not truncated while being read.
+    #[serde(default)]
+    pub backend: FileIoBackend,
 }
 
 const fn default_chunk_size() -> usize {
@@ -43,9 +49,12 @@ impl FileReadNode {
             // For dynamic nodes, allow None to create a default instance for pin inspection
             let config: FileReadConfig = if params.is_none() {
                 // Default config for pin inspection only
-                FileReadConfig { path: "/dev/null".to_string(), chunk_size: default_chunk_size() }
+                FileReadConfig {
+                    path: "/dev/null".to_string(),
+                    chunk_size: default_chunk_size(),
+                    backend: FileIoBackend::default(),
+                }
             } else {
-                tracing::debug!("FileReadNode factory received params: {:?}", params);
                 config_helpers::parse_config_required(params)?
             };
             tracing::debug!("FileReadNode created with path: {}", config.path);
@@ -74,14 +83,21 @@ impl ProcessorNode for FileReadNode {
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
         // Open the file
-        let mut file = tokio::fs::File::open(&self.config.path).await.map_err(|e| {
-            StreamKitError::Runtime(format!("Failed to open file '{}': {}", self.config.path, e))
-        })?;
+        let mut file =
+            FileSource::open(&self.config.path, self.config.chunk_size, self.config.backend)
+                .await
+                .map_err(|e| {
+                    StreamKitError::Runtime(format!(
+                        "Failed to open file '{}': {}",
+                        self.config.path, e
+                    ))
+                })?;
 
         tracing::info!(
-            "FileReadNode opened file: {} (chunk_size: {})",
+            "FileReadNode opened file: {} (chunk_size: {}, backend: {:?})",
             self.config.path,
-            self.config.chunk_size
+            self.config.chunk_size,
+            self.config.backend
         );
 
         // Source nodes emit Ready state and wait for Start signal
@@ -103,6 +119,11 @@ impl ProcessorNode for FileReadNode {
                     tracing::info!("FileReadNode received shutdown before start");
                     return Ok(());
                 },
+                Some(streamkit_core::control::NodeControlMessage::Query {
+                    kind, reply, ..
+                }) => {
+                    streamkit_core::control::reject_query(&kind, reply);
+                },
                 None => {
                     tracing::warn!("Control channel closed before start signal received");
                     return Ok(());
@@ -115,7 +136,6 @@ impl ProcessorNode for FileReadNode {
         let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
         let mut chunk_count = 0u64;
         let mut total_bytes = 0u64;
-        let mut buffer = vec![0u8; self.config.chunk_size];
 
         // Read file in chunks
         loop {
@@ -129,9 +149,9 @@ impl ProcessorNode for FileReadNode {
 
             // Use select! to check both file read AND control messages
             tokio::select! {
-                read_result = file.read(&mut buffer) => {
+                read_result = file.next_chunk() => {
                     match read_result {
-                        Ok(0) => {
+                        Ok(None) => {
                             // EOF reached
                             tracing::info!(
                                 "FileReadNode reached EOF after {} chunks ({} bytes)",
@@ -140,12 +160,11 @@ impl ProcessorNode for FileReadNode {
                             );
                             break;
                         }
-                        Ok(n) => {
+                        Ok(Some(chunk)) => {
                             chunk_count += 1;
-                            total_bytes += n as u64;
+                            total_bytes += chunk.len() as u64;
 
                             // Send chunk as Binary packet (no metadata - demuxers will add timing)
-                            let chunk = Bytes::copy_from_slice(&buffer[..n]);
                             if context
                                 .output_sender
                                 .send(
@@ -190,6 +209,9 @@ impl ProcessorNode for FileReadNode {
                         | streamkit_core::control::NodeControlMessage::Start => {
                             // Ignore param updates and start during file read - loop continues naturally
                         }
+                        streamkit_core::control::NodeControlMessage::Query { kind, reply, .. } => {
+                            streamkit_core::control::reject_query(&kind, reply);
+                        }
                     }
                 }
             }
@@ -204,20 +226,20 @@ impl ProcessorNode for FileReadNode {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
+    use super::super::file_io::READ_BLOCK_SIZE;
     use super::*;
     use std::collections::HashMap;
     use streamkit_core::node::RoutedPacketMessage;
     use streamkit_core::NodeStatsUpdate;
     use tokio::sync::mpsc;
 
-    #[tokio::test]
-    async fn test_file_read_node() {
-        // Create a temporary test file
-        let temp_dir = tempfile::tempdir().unwrap();
-        let file_path = temp_dir.path().join("test.bin");
-        let test_data = b"Hello, StreamKit! This is a test file.";
-        tokio::fs::write(&file_path, test_data).await.unwrap();
-
+    /// Runs a FileReadNode over `path` through its whole lifecycle and returns the chunks it
+    /// emitted.
+    async fn read_chunks(
+        path: &std::path::Path,
+        chunk_size: usize,
+        backend: FileIoBackend,
+    ) -> Vec<bytes::Bytes> {
         // Create test context
         let (mock_sender, mut packet_rx) = mpsc::channel::<RoutedPacketMessage>(10);
         let (control_tx, control_rx) = mpsc::channel(10);
@@ -241,13 +263,15 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create and run node
-        let config = FileReadConfig {
-            path: file_path.to_str().unwrap().to_string(),
-            chunk_size: 10, // Small chunks for testing
-        };
+        let config =
+            FileReadConfig { path: path.to_str().unwrap().to_string(), chunk_size, backend };
         let node = Box::new(FileReadNode { config });
 
         let node_handle = tokio::spawn(async move { node.run(context).await });
@@ -268,10 +292,10 @@ mod tests {
         assert!(matches!(state.state, streamkit_core::NodeState::Running));
 
         // Collect all packets
-        let mut collected_data = Vec::new();
+        let mut chunks = Vec::new();
         while let Some((_node, _pin, packet)) = packet_rx.recv().await {
             if let Packet::Binary { data, .. } = packet {
-                collected_data.extend_from_slice(&data);
+                chunks.push(data);
             }
         }
 
@@ -281,8 +305,38 @@ mod tests {
 
         // Wait for node to complete
         node_handle.await.unwrap().unwrap();
+        chunks
+    }
+
+    #[tokio::test]
+    async fn test_file_read_node() {
+        // Create a temporary test file
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.bin");
+        let test_data = b"Hello, StreamKit! This is a test file.";
+        tokio::fs::write(&file_path, test_data).await.unwrap();
+
+        // Small chunks for testing
+        let collected_data = read_chunks(&file_path, 10, FileIoBackend::Tokio).await.concat();
 
         // Verify data matches
         assert_eq!(collected_data, test_data);
     }
+
+    #[tokio::test]
+    async fn test_file_read_spans_read_blocks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("large.bin");
+        let test_data: Vec<u8> =
+            (0..3 * READ_BLOCK_SIZE + 123).map(|i| u8::try_from(i % 251).unwrap()).collect();
+        tokio::fs::write(&file_path, &test_data).await.unwrap();
+
+        // Chunks that don't divide the block size exercise the block refill path.
+        let chunk_size = 100_000;
+        for backend in [FileIoBackend::Tokio, FileIoBackend::IoUring] {
+            let chunks = read_chunks(&file_path, chunk_size, backend).await;
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty() && chunk.len() <= chunk_size));
+            assert_eq!(chunks.concat(), test_data, "backend {backend:?}");
+        }
+    }
 }
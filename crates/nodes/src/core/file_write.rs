@@ -12,16 +12,22 @@ use streamkit_core::{
     config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
     PinCardinality, ProcessorNode, StreamKitError,
 };
-use tokio::io::AsyncWriteExt;
+
+use super::file_io::{FileIoBackend, FileSink};
 
 /// Configuration for the FileWriteNode
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileWriteConfig {
     /// Path to the file to write
+    #[schemars(extend("sensitive" = true))]
     pub path: String,
     /// Size of buffer before writing to disk (default: 8192 bytes)
     #[serde(default = "default_chunk_size")]
     pub chunk_size: usize,
+    /// I/O backend (default: tokio). `io_uring` is Linux-only and falls back to tokio
+    /// when unavailable; `mmap` only applies to reading, so it writes with tokio.
+    #[serde(default)]
+    pub backend: FileIoBackend,
 }
 
 const fn default_chunk_size() -> usize {
@@ -40,7 +46,11 @@ impl FileWriteNode {
             // For dynamic nodes, allow None to create a default instance for pin inspection
             let config: FileWriteConfig = if params.is_none() {
                 // Default config for pin inspection only
-                FileWriteConfig { path: "/dev/null".to_string(), chunk_size: default_chunk_size() }
+                FileWriteConfig {
+                    path: "/dev/null".to_string(),
+                    chunk_size: default_chunk_size(),
+                    backend: FileIoBackend::default(),
+                }
             } else {
                 config_helpers::parse_config_required(params)?
             };
@@ -69,14 +79,19 @@ impl ProcessorNode for FileWriteNode {
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
         // Create/open the file for writing
-        let mut file = tokio::fs::File::create(&self.config.path).await.map_err(|e| {
-            StreamKitError::Runtime(format!("Failed to create file '{}': {}", self.config.path, e))
-        })?;
+        let mut file =
+            FileSink::create(&self.config.path, self.config.backend).await.map_err(|e| {
+                StreamKitError::Runtime(format!(
+                    "Failed to create file '{}': {}",
+                    self.config.path, e
+                ))
+            })?;
 
         tracing::info!(
-            "FileWriteNode opened file for writing: {} (chunk_size: {})",
+            "FileWriteNode opened file for writing: {} (chunk_size: {}, backend: {:?})",
             self.config.path,
-            self.config.chunk_size
+            self.config.chunk_size,
+            self.config.backend
         );
 
         state_helpers::emit_running(&context.state_tx, &node_name);
@@ -101,7 +116,7 @@ impl ProcessorNode for FileWriteNode {
 
                 // Write buffer to file when it reaches chunk_size
                 if buffer.len() >= self.config.chunk_size {
-                    if let Err(e) = file.write_all(&buffer).await {
+                    if let Err(e) = file.write_all(&mut buffer).await {
                         stats_tracker.errored();
                         stats_tracker.force_send();
                         state_helpers::emit_failed(
@@ -114,7 +129,6 @@ impl ProcessorNode for FileWriteNode {
                         )));
                     }
                     chunks_written += 1;
-                    buffer.clear();
                 }
 
                 stats_tracker.sent();
@@ -127,7 +141,7 @@ impl ProcessorNode for FileWriteNode {
 
         // Write any remaining buffered data
         if !buffer.is_empty() {
-            if let Err(e) = file.write_all(&buffer).await {
+            if let Err(e) = file.write_all(&mut buffer).await {
                 stats_tracker.errored();
                 stats_tracker.force_send();
                 state_helpers::emit_failed(
@@ -141,7 +155,7 @@ impl ProcessorNode for FileWriteNode {
         }
 
         // Flush and close the file
-        if let Err(e) = file.flush().await {
+        if let Err(e) = file.finish().await {
             tracing::error!("Failed to flush file: {}", e);
             stats_tracker.errored();
             reason = format!("flush_failed: {e}");
@@ -203,12 +217,17 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create and run node
         let config = FileWriteConfig {
             path: file_path.to_str().unwrap().to_string(),
             chunk_size: default_chunk_size(),
+            backend: FileIoBackend::Tokio,
         };
         let node = Box::new(FileWriteNode { config });
 
@@ -283,12 +302,17 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create and run node with small chunk size for testing
         let config = FileWriteConfig {
             path: file_path.to_str().unwrap().to_string(),
             chunk_size: 20, // Small chunks for testing
+            backend: FileIoBackend::Tokio,
         };
         let node = Box::new(FileWriteNode { config });
 
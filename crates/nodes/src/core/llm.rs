@@ -0,0 +1,633 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! LLM node - Chat completions against an OpenAI-compatible endpoint
+//!
+//! Voice agents need a language model between speech recognition and synthesis. This node
+//! keeps a bounded conversation history, sends each user turn to a chat completions endpoint
+//! (OpenAI, or a local server such as llama.cpp, vLLM or Ollama) and emits the reply as Text.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::script::{ScriptNode, ScriptSecret};
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Configuration for the LlmNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Chat completions URL of an OpenAI-compatible API
+    pub endpoint: String,
+    /// Model name sent with each request
+    pub model: String,
+    /// System prompt sent ahead of the conversation
+    #[schemars(extend("tunable" = true))]
+    pub system_prompt: String,
+    /// Name of a server secret (`[script.secrets]`) holding the API key, sent as a bearer
+    /// token. Leave unset for local servers that need no key.
+    pub api_key_secret: Option<String>,
+    /// Completed exchanges kept as context; 0 sends only the current turn
+    #[schemars(range(min = 0, max = 64))]
+    pub max_history_turns: usize,
+    /// Sampling temperature
+    #[schemars(range(min = 0.0, max = 2.0), extend("tunable" = true))]
+    pub temperature: f32,
+    /// Random seed sent with each request, for endpoints that support reproducible sampling;
+    /// a generated seed is used and reported in telemetry when unset
+    pub seed: Option<u64>,
+    /// Upper bound on the reply length in tokens
+    #[schemars(range(min = 1, max = 4096), extend("tunable" = true))]
+    pub max_tokens: u32,
+    /// Request timeout in milliseconds
+    #[schemars(range(min = 100, max = 120_000))]
+    pub timeout_ms: u64,
+    /// Text emitted in place of a reply when a request fails; nothing is emitted when unset
+    #[schemars(extend("tunable" = true))]
+    pub fallback_reply: Option<String>,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            system_prompt: "You are a helpful voice assistant. Keep responses brief and \
+                            conversational, suitable for speech."
+                .to_string(),
+            api_key_secret: None,
+            max_history_turns: 8,
+            temperature: 0.7,
+            seed: None,
+            max_tokens: 200,
+            timeout_ms: 15_000,
+            fallback_reply: None,
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Checks ranges and that the endpoint is an HTTP(S) URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first invalid field.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err(format!("endpoint must be an http(s) URL, got '{}'", self.endpoint));
+        }
+        if self.model.is_empty() {
+            return Err("model must not be empty".to_string());
+        }
+        if self.max_history_turns > 64 {
+            return Err("max_history_turns must be at most 64".to_string());
+        }
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err("temperature must be between 0.0 and 2.0".to_string());
+        }
+        if !(1..=4096).contains(&self.max_tokens) {
+            return Err("max_tokens must be between 1 and 4096".to_string());
+        }
+        if !(100..=120_000).contains(&self.timeout_ms) {
+            return Err("timeout_ms must be between 100 and 120000".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Bounded history of completed user/assistant exchanges.
+#[derive(Debug, Default)]
+struct Conversation {
+    turns: VecDeque<(String, String)>,
+}
+
+impl Conversation {
+    /// Builds the chat messages for a new user turn.
+    fn messages(&self, system_prompt: &str, user: &str) -> Vec<serde_json::Value> {
+        let mut messages = Vec::with_capacity(self.turns.len() * 2 + 2);
+        if !system_prompt.is_empty() {
+            messages.push(serde_json::json!({ "role": "system", "content": system_prompt }));
+        }
+        for (question, answer) in &self.turns {
+            messages.push(serde_json::json!({ "role": "user", "content": question }));
+            messages.push(serde_json::json!({ "role": "assistant", "content": answer }));
+        }
+        messages.push(serde_json::json!({ "role": "user", "content": user }));
+        messages
+    }
+
+    /// Records a completed exchange, dropping the oldest ones beyond `max_turns`.
+    fn record(&mut self, user: String, reply: String, max_turns: usize) {
+        self.turns.push_back((user, reply));
+        while self.turns.len() > max_turns {
+            self.turns.pop_front();
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        self.turns
+            .iter()
+            .map(|(user, assistant)| serde_json::json!({ "user": user, "assistant": assistant }))
+            .collect()
+    }
+}
+
+/// Extracts the user turn from a Text or Transcription packet.
+fn turn_text(packet: &Packet) -> Option<String> {
+    let text = match packet {
+        Packet::Text(text) => text.trim(),
+        Packet::Transcription(transcription) => transcription.text.trim(),
+        _ => return None,
+    };
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Pulls the assistant's reply out of a chat completions response body.
+fn parse_reply(body: &[u8]) -> Result<String, String> {
+    let response: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| format!("invalid JSON response: {e}"))?;
+    if let Some(message) = response.pointer("/error/message").and_then(|m| m.as_str()) {
+        return Err(format!("API error: {message}"));
+    }
+    let reply = response
+        .pointer("/choices/0/message/content")
+        .and_then(|c| c.as_str())
+        .ok_or_else(|| "response has no choices[0].message.content".to_string())?
+        .trim();
+    if reply.is_empty() {
+        return Err("empty reply".to_string());
+    }
+    Ok(reply.to_string())
+}
+
+/// Sends one chat completions request. Owns its inputs so the node can keep handling control
+/// messages while it is in flight.
+async fn complete(
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<Arc<str>>,
+    body: Vec<u8>,
+) -> Result<String, String> {
+    let mut request =
+        client.post(&endpoint).header(reqwest::header::CONTENT_TYPE, "application/json").body(body);
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| format!("request failed: {e}"))?;
+    let status = response.status();
+    let body = response.bytes().await.map_err(|e| format!("failed to read response: {e}"))?;
+    if !status.is_success() {
+        // Include the API's own error message when the body carries one
+        let detail = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.pointer("/error/message").and_then(|m| m.as_str()).map(str::to_string));
+        return Err(
+            detail.map_or_else(|| format!("HTTP {status}"), |m| format!("HTTP {status}: {m}"))
+        );
+    }
+    parse_reply(&body)
+}
+
+/// Conversation and tunable settings, separated from I/O.
+struct LlmState {
+    config: LlmConfig,
+    conversation: Conversation,
+    requests: u64,
+    failures: u64,
+}
+
+impl LlmState {
+    fn request_body(&self, user: &str) -> Vec<u8> {
+        let mut body = serde_json::json!({
+            "model": self.config.model,
+            "messages": self.conversation.messages(&self.config.system_prompt, user),
+            "temperature": self.config.temperature,
+            "max_tokens": self.config.max_tokens,
+        });
+        if let Some(seed) = self.config.seed {
+            body["seed"] = seed.into();
+        }
+        body.to_string().into_bytes()
+    }
+
+    fn apply_update(&mut self, params: &serde_json::Value) -> Result<(), String> {
+        let updated: LlmConfig = merge_params(&self.config, params)?;
+        updated.validate()?;
+        if updated.endpoint != self.config.endpoint
+            || updated.model != self.config.model
+            || updated.api_key_secret != self.config.api_key_secret
+            || updated.max_history_turns != self.config.max_history_turns
+            || updated.timeout_ms != self.config.timeout_ms
+            || updated.seed != self.config.seed
+        {
+            return Err("only system_prompt, temperature, max_tokens and fallback_reply can be \
+                        tuned while running"
+                .to_string());
+        }
+        self.config = updated;
+        Ok(())
+    }
+
+    /// Handles a control message, returning `true` on shutdown.
+    fn handle_control(&mut self, msg: NodeControlMessage, stats: &mut NodeStatsTracker) -> bool {
+        match msg {
+            NodeControlMessage::UpdateParams(params) => {
+                if let Err(e) = self.apply_update(&params) {
+                    tracing::warn!("Rejected LLM update: {}", e);
+                    stats.errored();
+                }
+            },
+            NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                let _ = reply.send(Ok(serde_json::json!({
+                    "model": self.config.model,
+                    "history_turns": self.conversation.turns.len(),
+                    "requests": self.requests,
+                    "failures": self.failures,
+                })));
+            },
+            NodeControlMessage::Query { kind, reply, .. } if kind == "history" => {
+                let _ = reply.send(Ok(self.conversation.to_json()));
+            },
+            NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+            NodeControlMessage::Start => {},
+            NodeControlMessage::Shutdown => return true,
+        }
+        false
+    }
+}
+
+/// A node that answers each user turn with a chat completion.
+///
+/// Every Text or Transcription packet on `in` is one user turn; empty turns are dropped. Turns
+/// are answered one at a time, in order, with the system prompt and up to
+/// `max_history_turns` earlier exchanges as context, and each reply is sent on `out` as Text.
+/// A failed request is logged and reported as an `llm.error` telemetry event; the turn is not
+/// added to the history and `fallback_reply`, when set, is emitted instead. Successful replies
+/// emit `llm.response` with the request latency.
+///
+/// Answers the `"status"` query with request counters and the `"history"` query with the
+/// retained exchanges.
+pub struct LlmNode {
+    config: LlmConfig,
+    api_key: Option<Arc<str>>,
+}
+
+impl LlmNode {
+    /// Creates a factory that resolves `api_key_secret` against the server's secrets.
+    ///
+    /// A secret scoped with `allowed_fetch_urls` is only accepted when the endpoint matches.
+    pub fn factory(
+        secrets: Arc<HashMap<String, ScriptSecret>>,
+    ) -> streamkit_core::node::NodeFactory {
+        Arc::new(move |params| {
+            let config: LlmConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(StreamKitError::Configuration)?;
+            let api_key = match config.api_key_secret.as_deref() {
+                Some(name) => {
+                    let Some(secret) = secrets.get(name) else {
+                        let mut available: Vec<&String> = secrets.keys().collect();
+                        available.sort();
+                        return Err(StreamKitError::Configuration(format!(
+                            "LLM node references unknown secret '{name}'. Available secrets: \
+                             {available:?}"
+                        )));
+                    };
+                    if !ScriptNode::is_secret_allowed_for_url(secret, &config.endpoint) {
+                        return Err(StreamKitError::Configuration(format!(
+                            "Secret '{name}' is not allowed for endpoint '{}'",
+                            config.endpoint
+                        )));
+                    }
+                    Some(Arc::from(secret.value.as_str()))
+                },
+                None => None,
+            };
+            Ok(Box::new(Self { config, api_key }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for LlmNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Transcription, PacketType::Text],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Text,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let client = reqwest::Client::builder()
+            // Security: don't follow redirects (the API key must not leak to another host).
+            .redirect(reqwest::redirect::Policy::none())
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_millis(self.config.timeout_ms))
+            .build()
+            .map_err(|e| {
+                let msg = format!("Failed to initialize HTTP client: {e}");
+                state_helpers::emit_failed(&context.state_tx, &node_name, msg.clone());
+                StreamKitError::Runtime(msg)
+            })?;
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut state = LlmState {
+            config: self.config,
+            conversation: Conversation::default(),
+            requests: 0,
+            failures: 0,
+        };
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        'run: loop {
+            tokio::select! {
+                Some(msg) = context.control_rx.recv() => {
+                    if state.handle_control(msg, &mut stats_tracker) {
+                        break;
+                    }
+                }
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    stats_tracker.received();
+                    let Some(user) = turn_text(&packet) else {
+                        stats_tracker.discarded();
+                        stats_tracker.maybe_send();
+                        continue;
+                    };
+
+                    state.requests += 1;
+                    let started = Instant::now();
+                    let mut request = std::pin::pin!(complete(
+                        client.clone(),
+                        state.config.endpoint.clone(),
+                        self.api_key.clone(),
+                        state.request_body(&user),
+                    ));
+                    let result = loop {
+                        tokio::select! {
+                            result = &mut request => break result,
+                            Some(msg) = context.control_rx.recv() => {
+                                if state.handle_control(msg, &mut stats_tracker) {
+                                    break 'run;
+                                }
+                            }
+                        }
+                    };
+                    let latency_ms =
+                        u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+                    let reply = match result {
+                        Ok(reply) => {
+                            telemetry.emit(
+                                "llm.response",
+                                serde_json::json!({
+                                    "model": state.config.model,
+                                    "latency_ms": latency_ms,
+                                    "input_chars": user.len(),
+                                    "output_chars": reply.len(),
+                                }),
+                            );
+                            let max_turns = state.config.max_history_turns;
+                            state.conversation.record(user, reply.clone(), max_turns);
+                            reply
+                        },
+                        Err(e) => {
+                            tracing::warn!(latency_ms, "LLM request failed: {}", e);
+                            state.failures += 1;
+                            stats_tracker.errored();
+                            telemetry.emit(
+                                "llm.error",
+                                serde_json::json!({ "error": e, "latency_ms": latency_ms }),
+                            );
+                            let Some(fallback) = state.config.fallback_reply.clone() else {
+                                stats_tracker.maybe_send();
+                                continue;
+                            };
+                            fallback
+                        },
+                    };
+
+                    let packet = Packet::Text(reply.into());
+                    if context.output_sender.send("out", packet).await.is_err() {
+                        tracing::debug!("Output channel closed, stopping node");
+                        state_helpers::emit_stopped(&context.state_tx, &node_name, "output_closed");
+                        return Ok(());
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                }
+            }
+        }
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+#[allow(clippy::implicit_hasher)] // Secrets are always built with the default hasher
+pub fn register(
+    registry: &mut streamkit_core::NodeRegistry,
+    secrets: &HashMap<String, ScriptSecret>,
+) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(LlmConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize LlmConfig schema");
+            return;
+        },
+    };
+
+    let factory = LlmNode::factory(Arc::new(secrets.clone()));
+    registry.register_dynamic_with_description(
+        "core::llm",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "ml".to_string()],
+        false,
+        "Answers each Text or Transcription turn with a chat completion from an \
+         OpenAI-compatible endpoint, keeping a bounded conversation history. \
+         Security: the endpoint receives conversation text and the configured API key.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test setup failures should panic
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_context;
+    use axum::{http::HeaderMap, routing::post, Json, Router};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_conversation_history_is_bounded() {
+        let mut conversation = Conversation::default();
+        conversation.record("one".to_string(), "1".to_string(), 2);
+        conversation.record("two".to_string(), "2".to_string(), 2);
+        conversation.record("three".to_string(), "3".to_string(), 2);
+
+        let messages = conversation.messages("be brief", "four");
+        let contents: Vec<&str> = messages.iter().map(|m| m["content"].as_str().unwrap()).collect();
+        assert_eq!(contents, ["be brief", "two", "2", "three", "3", "four"]);
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[5]["role"], "user");
+
+        // Without a system prompt only the conversation is sent
+        assert_eq!(Conversation::default().messages("", "hi").len(), 1);
+    }
+
+    #[test]
+    fn test_request_body_forwards_seed() {
+        let schema = serde_json::to_value(schemars::schema_for!(LlmConfig)).unwrap();
+        assert!(streamkit_core::seed::accepts_seed(&schema));
+
+        let mut state = LlmState {
+            config: LlmConfig::default(),
+            conversation: Conversation::default(),
+            requests: 0,
+            failures: 0,
+        };
+        let body: serde_json::Value = serde_json::from_slice(&state.request_body("hi")).unwrap();
+        assert!(body.get("seed").is_none());
+
+        state.config.seed = Some(42);
+        let body: serde_json::Value = serde_json::from_slice(&state.request_body("hi")).unwrap();
+        assert_eq!(body["seed"], 42);
+        assert!(state.apply_update(&serde_json::json!({ "seed": 7 })).is_err());
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let ok = br#"{"choices":[{"message":{"role":"assistant","content":" Hello! "}}]}"#;
+        assert_eq!(parse_reply(ok).unwrap(), "Hello!");
+
+        let error = br#"{"error":{"message":"Invalid API key"}}"#;
+        assert_eq!(parse_reply(error).unwrap_err(), "API error: Invalid API key");
+        assert!(parse_reply(br#"{"choices":[]}"#).is_err());
+        assert!(parse_reply(b"not json").is_err());
+    }
+
+    fn secret(value: &str, allowed_fetch_urls: &[&str]) -> ScriptSecret {
+        ScriptSecret {
+            value: value.to_string(),
+            allowed_fetch_urls: allowed_fetch_urls.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_factory_resolves_secret() {
+        let secrets = Arc::new(HashMap::from([(
+            "openai_key".to_string(),
+            secret("sk-test", &["https://api.openai.com/*"]),
+        )]));
+        let factory = LlmNode::factory(secrets);
+        assert!(factory(Some(&serde_json::json!({ "api_key_secret": "openai_key" }))).is_ok());
+        assert!(factory(Some(&serde_json::json!({ "api_key_secret": "missing" }))).is_err());
+        assert!(factory(Some(&serde_json::json!({ "endpoint": "ftp://example.com" }))).is_err());
+        // The secret is scoped to OpenAI and must not be sent elsewhere
+        let elsewhere = serde_json::json!({
+            "api_key_secret": "openai_key",
+            "endpoint": "https://llm.example.com/v1/chat/completions",
+        });
+        assert!(factory(Some(&elsewhere)).is_err());
+    }
+
+    /// Echoes the last user message and the number of messages received.
+    async fn echo_completion(
+        headers: HeaderMap,
+        Json(body): Json<serde_json::Value>,
+    ) -> Json<serde_json::Value> {
+        let messages = body["messages"].as_array().unwrap();
+        let last = messages.last().unwrap()["content"].as_str().unwrap();
+        let auth = headers.get("authorization").and_then(|v| v.to_str().ok()).unwrap_or("");
+        Json(serde_json::json!({
+            "choices": [{ "message": {
+                "role": "assistant",
+                "content": format!("{last} ({} messages, {auth})", messages.len()),
+            }}]
+        }))
+    }
+
+    async fn start_mock_llm() -> Option<String> {
+        let app = Router::new().route("/v1/chat/completions", post(echo_completion));
+        let listener = match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return None,
+            Err(e) => panic!("Failed to bind test HTTP listener: {e}"),
+        };
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Some(format!("http://{addr}/v1/chat/completions"))
+    }
+
+    #[tokio::test]
+    async fn test_llm_keeps_conversation_history() {
+        let Some(endpoint) = start_mock_llm().await else {
+            tracing::warn!("Skipping test_llm_keeps_conversation_history: bind not permitted");
+            return;
+        };
+
+        let (input_tx, input_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([("in".to_string(), input_rx)]);
+        let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+
+        let secrets = Arc::new(HashMap::from([("llm_key".to_string(), secret("sk-test", &[]))]));
+        let params = serde_json::json!({
+            "endpoint": endpoint,
+            "api_key_secret": "llm_key",
+            "system_prompt": "be brief",
+        });
+        let node = (LlmNode::factory(secrets))(Some(&params)).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+
+        input_tx.send(Packet::Text("hello".into())).await.unwrap();
+        input_tx.send(Packet::Text("   ".into())).await.unwrap();
+        input_tx.send(Packet::Text("and again".into())).await.unwrap();
+        drop(input_tx);
+        handle.await.unwrap().unwrap();
+
+        let replies: Vec<String> = mock_sender
+            .get_packets_for_pin("out")
+            .await
+            .into_iter()
+            .map(|packet| match packet {
+                Packet::Text(text) => text.to_string(),
+                other => panic!("Expected Text packet, got {other:?}"),
+            })
+            .collect();
+        // system + user, then system + first exchange + user; the blank turn is dropped
+        assert_eq!(
+            replies,
+            ["hello (2 messages, Bearer sk-test)", "and again (4 messages, Bearer sk-test)"]
+        );
+    }
+}
@@ -4,19 +4,29 @@
 
 use streamkit_core::{NodeRegistry, ProcessorNode};
 
+pub mod ab_split;
 pub mod bytes_input;
 pub mod bytes_output;
+pub mod consent_gate;
+pub mod cron_trigger;
+pub mod failover;
+pub mod file_io;
 pub mod file_read;
 pub mod file_write;
 pub mod json_serialize;
+#[cfg(feature = "llm")]
+pub mod llm;
 pub mod pacer;
 mod passthrough;
 #[cfg(feature = "script")]
 pub mod script;
+pub mod session_bridge;
 pub mod sink;
 pub mod telemetry_out;
 pub mod telemetry_tap;
 pub mod text_chunker;
+pub mod trim;
+pub mod turn_manager;
 use passthrough::PassthroughNode;
 use streamkit_core::registry::StaticPins;
 
@@ -36,6 +46,51 @@ pub fn register_core_nodes(
     global_script_allowlist: Option<Vec<script::AllowlistRule>>,
     secrets: std::collections::HashMap<String, script::ScriptSecret>,
 ) {
+    register_builtin_core_nodes(registry);
+
+    // --- Register LLM Node ---
+    #[cfg(feature = "llm")]
+    llm::register(registry, &secrets);
+
+    // --- Register Script Node ---
+    {
+        use schemars::schema_for;
+
+        // Convert global allowlist and secrets to GlobalScriptConfig
+        let global_config = global_script_allowlist.map(|allowlist| script::GlobalScriptConfig {
+            global_fetch_allowlist: allowlist,
+            secrets,
+        });
+
+        let factory = script::ScriptNode::factory(global_config);
+        registry.register_dynamic_with_description(
+            "core::script",
+            move |params| (factory)(params),
+            serde_json::to_value(schema_for!(script::ScriptConfig))
+                .expect("ScriptConfig schema should serialize to JSON"),
+            vec!["core".to_string(), "scripting".to_string()],
+            false,
+            "Execute custom JavaScript code for API integration, webhooks, text transformation, and dynamic routing. \
+             Provides a sandboxed QuickJS runtime with fetch() API support. \
+             See the [Script Node Guide](/guides/script-node/) for detailed usage.",
+        );
+    }
+}
+
+/// Registers all available core nodes with the engine's main registry (without script config).
+///
+/// Note: This does not register the special-purpose input/output nodes,
+/// as they are instantiated manually by the stateless runner.
+#[cfg(not(feature = "script"))]
+pub fn register_core_nodes(registry: &mut NodeRegistry) {
+    register_builtin_core_nodes(registry);
+
+    tracing::info!("Finished registering core nodes (without script).");
+}
+
+/// Registers the core nodes that do not depend on server script configuration.
+#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
+fn register_builtin_core_nodes(registry: &mut NodeRegistry) {
     // --- Register PassthroughNode ---
     #[cfg(feature = "passthrough")]
     {
@@ -148,34 +203,27 @@ pub fn register_core_nodes(
         );
     }
 
-    // --- Register Sink Node ---
-    sink::register(registry);
-
-    // --- Register Script Node ---
-    #[cfg(feature = "script")]
+    // --- Register Trim ---
     {
         use schemars::schema_for;
 
-        // Convert global allowlist and secrets to GlobalScriptConfig
-        let global_config = global_script_allowlist.map(|allowlist| script::GlobalScriptConfig {
-            global_fetch_allowlist: allowlist,
-            secrets,
-        });
-
-        let factory = script::ScriptNode::factory(global_config);
+        let factory = trim::TrimNode::factory();
         registry.register_dynamic_with_description(
-            "core::script",
+            "core::trim",
             move |params| (factory)(params),
-            serde_json::to_value(schema_for!(script::ScriptConfig))
-                .expect("ScriptConfig schema should serialize to JSON"),
-            vec!["core".to_string(), "scripting".to_string()],
+            serde_json::to_value(schema_for!(trim::TrimConfig))
+                .expect("TrimConfig schema should serialize to JSON"),
+            vec!["core".to_string(), "timing".to_string()],
             false,
-            "Execute custom JavaScript code for API integration, webhooks, text transformation, and dynamic routing. \
-             Provides a sandboxed QuickJS runtime with fetch() API support. \
-             See the [Script Node Guide](/guides/script-node/) for detailed usage.",
+            "Keeps only the packets inside a time window, by start/end offset or the final \
+             `last_ms` of the stream, optionally rebasing timestamps to zero. \
+             Packets without timing information (e.g. container headers) are dropped.",
         );
     }
 
+    // --- Register Sink Node ---
+    sink::register(registry);
+
     // --- Register TelemetryTap Node ---
     {
         use schemars::schema_for;
@@ -195,69 +243,22 @@ pub fn register_core_nodes(
 
     // --- Register TelemetryOut Node ---
     telemetry_out::register(registry);
-}
-
-/// Registers all available core nodes with the engine's main registry (without script config).
-///
-/// Note: This does not register the special-purpose input/output nodes,
-/// as they are instantiated manually by the stateless runner.
-///
-/// # Panics
-///
-/// Panics if config schemas cannot be serialized to JSON (should never happen).
-#[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
-#[cfg(not(feature = "script"))]
-pub fn register_core_nodes(registry: &mut NodeRegistry) {
-    // --- Register PassthroughNode ---
-    #[cfg(feature = "passthrough")]
-    {
-        use schemars::{schema_for, JsonSchema};
-        use serde::Deserialize;
-
-        #[derive(Deserialize, Debug, Default, JsonSchema)]
-        #[serde(default)]
-        pub struct PassthroughConfig {}
 
-        registry.register_static_with_description(
-            "core::passthrough",
-            PassthroughNode::new,
-            serde_json::to_value(schema_for!(PassthroughConfig))
-                .expect("PassthroughConfig schema should serialize to JSON"),
-            vec!["core".to_string()],
-            false,
-            "Passes packets through unchanged. Useful for testing or as a no-op placeholder.",
-        );
-    }
+    // --- Register CronTrigger Node ---
+    cron_trigger::register(registry);
 
-    // --- Register Other Core Nodes ---
-    text_chunker::register(registry);
-    bytes_input::register(registry);
-    bytes_output::register(registry);
-    json_serialize::register(registry);
-    pacer::register(registry);
-    file_read::register(registry);
-    file_write::register(registry);
-    sink::register(registry);
+    // --- Register Failover Node ---
+    failover::register(registry);
 
-    // --- Register TelemetryTap Node ---
-    {
-        use schemars::schema_for;
+    // --- Register AbSplit Node ---
+    ab_split::register(registry);
 
-        registry.register_dynamic_with_description(
-            "core::telemetry_tap",
-            telemetry_tap::create_telemetry_tap,
-            serde_json::to_value(schema_for!(telemetry_tap::TelemetryTapConfig))
-                .expect("TelemetryTapConfig schema should serialize to JSON"),
-            vec!["core".to_string(), "observability".to_string()],
-            false,
-            "Observes packets and emits telemetry events for debugging and timeline visualization. \
-             Packets pass through unchanged while side-effect telemetry is sent to the session bus. \
-             Useful for monitoring Transcription, Custom (VAD), and other packet types.",
-        );
-    }
+    // --- Register ConsentGate Node ---
+    consent_gate::register(registry);
 
-    // --- Register TelemetryOut Node ---
-    telemetry_out::register(registry);
+    // --- Register TurnManager Node ---
+    turn_manager::register(registry);
 
-    tracing::info!("Finished registering core nodes (without script).");
+    // --- Register Session Bridge Nodes ---
+    session_bridge::register(registry);
 }
@@ -9,7 +9,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::time::Duration;
-use streamkit_core::control::NodeControlMessage;
+use streamkit_core::control::{reject_query, NodeControlMessage};
 use streamkit_core::types::{AudioFrame, Packet, PacketType};
 use streamkit_core::{
     config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
@@ -22,6 +22,7 @@ use tokio::time::{Instant, MissedTickBehavior};
 #[serde(default)]
 pub struct PacerConfig {
     /// Playback speed multiplier (1.0 = real-time, 2.0 = 2x speed, 0.5 = half speed)
+    #[schemars(extend("tunable" = true))]
     pub speed: f32,
     /// Maximum number of packets to buffer internally (for backpressure control)
     /// Higher values = more memory, smoother pacing. Lower values = less memory, more backpressure.
@@ -350,6 +351,16 @@ impl ProcessorNode for PacerNode {
                             tracing::info!("PacerNode received shutdown signal");
                             break;
                         }
+                        NodeControlMessage::Query { kind, reply, .. } => {
+                            if kind == "buffer_fill" {
+                                let _ = reply.send(Ok(serde_json::json!({
+                                    "queued": packet_queue.len(),
+                                    "capacity": self.buffer_size,
+                                })));
+                            } else {
+                                reject_query(&kind, reply);
+                            }
+                        }
                     }
                 }
 
@@ -449,6 +460,10 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create node with very fast speed to minimize test time
@@ -13,13 +13,13 @@ use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 use streamkit_core::control::NodeControlMessage;
 use streamkit_core::stats::NodeStatsTracker;
-use streamkit_core::telemetry::{TelemetryEmitter, TelemetryEvent};
+use streamkit_core::telemetry::TelemetryEmitter;
 use streamkit_core::types::{Packet, PacketType};
 use streamkit_core::{
     config_helpers, state_helpers, InputPin, NodeContext, OutputPin, PinCardinality, ProcessorNode,
     StreamKitError,
 };
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::Semaphore;
 
 /// Maps a server-configured secret to an HTTP header for fetch() calls
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -261,7 +261,7 @@ impl ScriptNode {
         })
     }
 
-    fn is_secret_allowed_for_url(secret: &ScriptSecret, url: &str) -> bool {
+    pub(crate) fn is_secret_allowed_for_url(secret: &ScriptSecret, url: &str) -> bool {
         if secret.allowed_fetch_urls.is_empty() {
             return true;
         }
@@ -1097,14 +1097,10 @@ impl ScriptNode {
     async fn register_telemetry(
         &self,
         context: &rquickjs::AsyncContext,
-        telemetry_tx: Option<mpsc::Sender<TelemetryEvent>>,
-        node_id: String,
-        session_id: Option<String>,
+        telemetry: Option<TelemetryEmitter>,
     ) -> Result<(), StreamKitError> {
         let span_registry: SpanRegistry = Arc::new(Mutex::new(HashMap::new()));
-        let shared_emitter: Option<Arc<TelemetryEmitter>> = telemetry_tx.as_ref().map(|tx| {
-            Arc::new(TelemetryEmitter::new(node_id.clone(), session_id.clone(), Some(tx.clone())))
-        });
+        let shared_emitter: Option<Arc<TelemetryEmitter>> = telemetry.map(Arc::new);
 
         context
             .with(|ctx| {
@@ -1375,9 +1371,7 @@ impl ProcessorNode for ScriptNode {
             // Initialize Telemetry API (emit, startSpan, endSpan)
             self.register_telemetry(
                 &js_context,
-                context.telemetry_tx.clone(),
-                node_name.clone(),
-                context.session_id.clone(),
+                context.telemetry_tx.is_some().then(|| context.telemetry_emitter()),
             )
             .await?;
 
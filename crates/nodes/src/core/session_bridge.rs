@@ -0,0 +1,554 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Session bridge nodes - Tap packets and telemetry from another session
+//!
+//! `core::session_tap` receives a copy of everything a node in another session sends on one
+//! of its output pins; `core::session_telemetry_tap` receives that session's telemetry
+//! events as Custom packets. Neither touches the tapped session's graph: taps are attached
+//! by the server, are read-only, and drop packets rather than slowing the source down.
+//!
+//! The server only grants a tap if the role that created this session can access the
+//! target session (it owns it, or has `access_all_sessions`).
+//!
+//! Each session has its own timeline, so by default packet timestamps are mapped onto this
+//! session's media clock: the offset between the two is measured on the first packet and
+//! kept, preserving the source's spacing, so taps of several sessions line up with each
+//! other and with local sources.
+//!
+//! While the target session, node or pin doesn't exist (yet), or after it goes away, the
+//! node reports `Recovering` and retries every `retry_interval_ms`.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::session_bridge::get_session_bridge;
+use streamkit_core::telemetry::{TelemetryEvent, TELEMETRY_TYPE_ID};
+use streamkit_core::types::{Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    config_helpers, registry::StaticPins, state_helpers, stats::NodeStatsTracker, InputPin,
+    MediaClock, NodeContext, OutputPin, PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc;
+
+/// A jump between the source timeline and ours larger than this re-anchors the mapping.
+const MAX_DRIFT_US: i64 = 2_000_000;
+
+fn default_pin() -> String {
+    "out".to_string()
+}
+
+const fn default_retime() -> bool {
+    true
+}
+
+const fn default_retry_interval_ms() -> u64 {
+    1000
+}
+
+/// Configuration for the SessionTapNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionTapConfig {
+    /// Name or ID of the session to tap
+    pub session: String,
+    /// Node of the tapped session whose output is copied
+    pub node: String,
+    /// Output pin of that node
+    #[serde(default = "default_pin")]
+    pub pin: String,
+    /// Map timestamps onto this session's media clock
+    #[serde(default = "default_retime")]
+    pub retime: bool,
+    /// Delay between attempts to reach the session, node or pin, in milliseconds
+    #[serde(default = "default_retry_interval_ms")]
+    #[schemars(range(min = 100, max = 60000))]
+    pub retry_interval_ms: u64,
+}
+
+/// Configuration for the SessionTelemetryTapNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionTelemetryTapConfig {
+    /// Name or ID of the session to tap
+    pub session: String,
+    /// Event types to forward (`vad.*` style prefixes allowed); empty forwards all
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// IDs of the nodes whose events are forwarded; empty forwards all
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    /// Map timestamps onto this session's media clock
+    #[serde(default = "default_retime")]
+    pub retime: bool,
+    /// Delay between attempts to reach the session, in milliseconds
+    #[serde(default = "default_retry_interval_ms")]
+    #[schemars(range(min = 100, max = 60000))]
+    pub retry_interval_ms: u64,
+}
+
+fn validate_common(session: &str, retry_interval_ms: u64) -> Result<(), StreamKitError> {
+    if session.trim().is_empty() {
+        return Err(StreamKitError::Configuration("session must not be empty".to_string()));
+    }
+    if !(100..=60_000).contains(&retry_interval_ms) {
+        return Err(StreamKitError::Configuration(format!(
+            "retry_interval_ms must be within 100-60000, got {retry_interval_ms}"
+        )));
+    }
+    Ok(())
+}
+
+/// What a bridge node taps.
+#[derive(Debug, Clone)]
+enum TapSource {
+    Output { node: String, pin: String },
+    Telemetry { event_types: Vec<String>, nodes: Vec<String> },
+}
+
+/// An open tap on another session.
+enum Tap {
+    Output(mpsc::Receiver<Packet>),
+    Telemetry(mpsc::Receiver<TelemetryEvent>),
+}
+
+impl TapSource {
+    async fn connect(&self, session_id: &str, target: &str) -> Result<Tap, String> {
+        let bridge = get_session_bridge()
+            .ok_or_else(|| "Session bridging is not available on this server".to_string())?;
+        match self {
+            Self::Output { node, pin } => {
+                bridge.tap_output(session_id, target, node, pin).await.map(Tap::Output)
+            },
+            Self::Telemetry { .. } => {
+                bridge.tap_telemetry(session_id, target).await.map(Tap::Telemetry)
+            },
+        }
+    }
+
+    /// The bridge node's pins; they only depend on the kind of tap.
+    fn output_pins(&self) -> Vec<OutputPin> {
+        let produces_type = match self {
+            // The tapped pin's type is only known once packets arrive.
+            Self::Output { .. } => PacketType::Any,
+            Self::Telemetry { .. } => PacketType::Custom { type_id: TELEMETRY_TYPE_ID.to_string() },
+        };
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    fn accepts(&self, event: &TelemetryEvent) -> bool {
+        let Self::Telemetry { event_types, nodes } = self else { return true };
+        let type_ok = event_types.is_empty()
+            || event.event_type().is_some_and(|event_type| {
+                event_types.iter().any(|pattern| {
+                    pattern
+                        .strip_suffix('*')
+                        .map_or(pattern == event_type, |prefix| event_type.starts_with(prefix))
+                })
+            });
+        type_ok && (nodes.is_empty() || nodes.contains(&event.node_id))
+    }
+}
+
+impl Tap {
+    /// Next packet from the tapped session, or `None` once the tap has closed.
+    async fn recv(&mut self, source: &TapSource, target: &str) -> Option<Packet> {
+        match self {
+            Self::Output(rx) => rx.recv().await,
+            Self::Telemetry(rx) => loop {
+                let event = rx.recv().await?;
+                if source.accepts(&event) {
+                    return Some(telemetry_packet(event, target));
+                }
+            },
+        }
+    }
+}
+
+/// Wraps a telemetry event of the tapped session, recording where it came from.
+fn telemetry_packet(event: TelemetryEvent, target: &str) -> Packet {
+    let mut packet = event.packet;
+    if let Some(data) = packet.data.as_object_mut() {
+        data.insert("source_session".to_string(), serde_json::json!(target));
+        data.insert("source_node".to_string(), serde_json::json!(event.node_id));
+    }
+    Packet::Custom(Arc::new(packet))
+}
+
+fn metadata_mut(packet: &mut Packet) -> Option<&mut Option<PacketMetadata>> {
+    match packet {
+        Packet::Audio(frame) => Some(&mut frame.metadata),
+        Packet::Binary { metadata, .. } => Some(metadata),
+        Packet::Transcription(data) => Some(&mut Arc::make_mut(data).metadata),
+        Packet::Custom(data) => Some(&mut Arc::make_mut(data).metadata),
+        Packet::Text(_) => None,
+    }
+}
+
+/// Maps timestamps from the tapped session's timeline onto this session's clock.
+struct Retimer {
+    clock: Option<Arc<MediaClock>>,
+    started: Instant,
+    /// Our time minus the source's, in microseconds
+    offset_us: Option<i64>,
+}
+
+impl Retimer {
+    fn new(clock: Option<Arc<MediaClock>>) -> Self {
+        Self { clock, started: Instant::now(), offset_us: None }
+    }
+
+    fn now_us(&self) -> u64 {
+        self.clock.as_ref().map_or_else(
+            || u64::try_from(self.started.elapsed().as_micros()).unwrap_or(u64::MAX),
+            |clock| clock.now_us(),
+        )
+    }
+
+    /// Forgets the offset, e.g. after reconnecting to a source that may have restarted.
+    const fn reset(&mut self) {
+        self.offset_us = None;
+    }
+
+    /// Returns `source_us` on our timeline.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)] // Microsecond clocks fit i64
+    fn map(&mut self, source_us: u64) -> u64 {
+        let now = self.now_us() as i64;
+        let source = source_us as i64;
+        let offset = *self.offset_us.get_or_insert(now - source);
+        let mapped = source + offset;
+        if (mapped - now).abs() > MAX_DRIFT_US {
+            self.offset_us = Some(now - source);
+            return now.max(0) as u64;
+        }
+        mapped.max(0) as u64
+    }
+
+    /// Re-stamps a packet; packets without a timestamp are stamped with the current time.
+    fn apply(&mut self, packet: &mut Packet) {
+        let Some(metadata) = metadata_mut(packet) else { return };
+        match metadata {
+            Some(PacketMetadata { timestamp_us: Some(ts), .. }) => *ts = self.map(*ts),
+            Some(meta) => meta.timestamp_us = Some(self.now_us()),
+            None => {
+                *metadata = Some(PacketMetadata {
+                    timestamp_us: Some(self.now_us()),
+                    duration_us: None,
+                    sequence: None,
+                });
+            },
+        }
+    }
+}
+
+/// Outcome of waiting for the next packet or control message.
+enum Step {
+    Packet(Packet),
+    TapClosed,
+    Shutdown,
+}
+
+/// A source node that bridges packets from another session into this one.
+///
+/// The same node implements `core::session_tap` (a node's output pin) and
+/// `core::session_telemetry_tap` (the session's telemetry).
+pub struct SessionBridgeNode {
+    target: String,
+    source: TapSource,
+    retime: bool,
+    retry_interval: Duration,
+}
+
+impl SessionBridgeNode {
+    pub fn tap_factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: SessionTapConfig = config_helpers::parse_config_required(params)?;
+            validate_common(&config.session, config.retry_interval_ms)?;
+            if config.node.trim().is_empty() || config.pin.trim().is_empty() {
+                return Err(StreamKitError::Configuration(
+                    "node and pin must not be empty".to_string(),
+                ));
+            }
+            Ok(Box::new(Self {
+                target: config.session,
+                source: TapSource::Output { node: config.node, pin: config.pin },
+                retime: config.retime,
+                retry_interval: Duration::from_millis(config.retry_interval_ms),
+            }))
+        })
+    }
+
+    pub fn telemetry_factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: SessionTelemetryTapConfig = config_helpers::parse_config_required(params)?;
+            validate_common(&config.session, config.retry_interval_ms)?;
+            Ok(Box::new(Self {
+                target: config.session,
+                source: TapSource::Telemetry {
+                    event_types: config.event_types,
+                    nodes: config.nodes,
+                },
+                retime: config.retime,
+                retry_interval: Duration::from_millis(config.retry_interval_ms),
+            }))
+        })
+    }
+
+    /// Waits out the retry interval; returns `false` if the node is shut down meanwhile.
+    async fn wait_to_retry(&self, context: &mut NodeContext) -> bool {
+        let deadline = tokio::time::Instant::now() + self.retry_interval;
+        loop {
+            tokio::select! {
+                () = tokio::time::sleep_until(deadline) => return true,
+                msg = context.control_rx.recv() => match msg {
+                    Some(NodeControlMessage::Query { kind, reply, .. }) => {
+                        reject_query(&kind, reply);
+                    }
+                    Some(NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start) => {}
+                    Some(NodeControlMessage::Shutdown) | None => return false,
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for SessionBridgeNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        self.source.output_pins()
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::UpdateParams(_)) => {},
+                Some(NodeControlMessage::Shutdown) | None => return Ok(()),
+            }
+        }
+
+        let Some(session_id) = context.session_id.clone() else {
+            let reason = "Session bridging needs a dynamic session";
+            state_helpers::emit_failed(&context.state_tx, &node_name, reason);
+            return Err(StreamKitError::Configuration(reason.to_string()));
+        };
+
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut retimer = Retimer::new(context.media_clock.clone());
+
+        'connect: loop {
+            let mut tap = match self.source.connect(&session_id, &self.target).await {
+                Ok(tap) => tap,
+                Err(e) => {
+                    tracing::warn!("{}: cannot tap session '{}': {}", node_name, self.target, e);
+                    state_helpers::emit_recovering(
+                        &context.state_tx,
+                        &node_name,
+                        format!("{e}, retrying"),
+                        None,
+                    );
+                    if self.wait_to_retry(&mut context).await {
+                        continue;
+                    }
+                    break;
+                },
+            };
+            state_helpers::emit_running(&context.state_tx, &node_name);
+            retimer.reset();
+
+            loop {
+                let step = tokio::select! {
+                    packet = tap.recv(&self.source, &self.target) => {
+                        packet.map_or(Step::TapClosed, Step::Packet)
+                    }
+                    msg = context.control_rx.recv() => match msg {
+                        Some(NodeControlMessage::Query { kind, reply, .. }) => {
+                            reject_query(&kind, reply);
+                            continue;
+                        }
+                        Some(NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start) => {
+                            continue;
+                        }
+                        Some(NodeControlMessage::Shutdown) | None => Step::Shutdown,
+                    },
+                };
+                match step {
+                    Step::Packet(mut packet) => {
+                        stats.received();
+                        if self.retime {
+                            retimer.apply(&mut packet);
+                        }
+                        if context.output_sender.send("out", packet).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            break 'connect;
+                        }
+                        stats.sent();
+                        stats.maybe_send();
+                    },
+                    Step::TapClosed => {
+                        tracing::info!("{}: tap of session '{}' closed", node_name, self.target);
+                        state_helpers::emit_recovering(
+                            &context.state_tx,
+                            &node_name,
+                            format!("Session '{}' is no longer available, retrying", self.target),
+                            None,
+                        );
+                        if self.wait_to_retry(&mut context).await {
+                            continue 'connect;
+                        }
+                        break 'connect;
+                    },
+                    Step::Shutdown => break 'connect,
+                }
+            }
+        }
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let (tap_schema, telemetry_schema) = match (
+        serde_json::to_value(schema_for!(SessionTapConfig)),
+        serde_json::to_value(schema_for!(SessionTelemetryTapConfig)),
+    ) {
+        (Ok(tap), Ok(telemetry)) => (tap, telemetry),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!(error = %e, "Failed to serialize session bridge config schema");
+            return;
+        },
+    };
+
+    // The configs have no defaults, so pins can't come from a default instance.
+    let output_pins = TapSource::Output { node: String::new(), pin: String::new() }.output_pins();
+    let factory = SessionBridgeNode::tap_factory();
+    registry.register_static_with_description(
+        "core::session_tap",
+        move |params| (factory)(params),
+        tap_schema,
+        StaticPins { inputs: vec![], outputs: output_pins },
+        vec!["core".to_string(), "observability".to_string()],
+        false,
+        "Receives a copy of the packets a node in another session sends on one of its output \
+         pins, without changing that session's graph. Use it to monitor production sessions \
+         (e.g. listen to their audio) from a separate session. Requires access to the tapped \
+         session; timestamps are mapped onto this session's clock.",
+    );
+
+    let output_pins = TapSource::Telemetry { event_types: vec![], nodes: vec![] }.output_pins();
+    let factory = SessionBridgeNode::telemetry_factory();
+    registry.register_static_with_description(
+        "core::session_telemetry_tap",
+        move |params| (factory)(params),
+        telemetry_schema,
+        StaticPins { inputs: vec![], outputs: output_pins },
+        vec!["core".to_string(), "observability".to_string()],
+        false,
+        "Receives the telemetry events of another session as Custom packets, optionally \
+         filtered by event type and node, without changing that session's graph. Requires \
+         access to the tapped session; timestamps are mapped onto this session's clock.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use streamkit_core::types::AudioFrame;
+
+    fn telemetry_event(node_id: &str, event_type: &str) -> TelemetryEvent {
+        TelemetryEvent::new(
+            Some("prod".to_string()),
+            node_id.to_string(),
+            serde_json::json!({"event_type": event_type}),
+            1_000,
+        )
+    }
+
+    #[test]
+    fn test_retimer_keeps_spacing_and_reanchors_on_jumps() {
+        let mut retimer = Retimer::new(None);
+        let first = retimer.map(50_000_000);
+        assert!(first < 1_000_000, "first packet lands at our current time, got {first}");
+        assert_eq!(retimer.map(50_020_000), first + 20_000);
+
+        // The source restarted its timeline: map it to now instead of the far past.
+        let restarted = retimer.map(1_000);
+        assert!(restarted < 1_000_000);
+        assert_eq!(retimer.map(21_000), restarted + 20_000);
+    }
+
+    #[test]
+    fn test_retimer_stamps_packets_without_timestamps() {
+        let mut retimer = Retimer::new(None);
+        let mut packet = Packet::Audio(AudioFrame::new(48_000, 1, vec![0.0; 480]));
+        retimer.apply(&mut packet);
+        let Packet::Audio(frame) = &packet else { unreachable!() };
+        assert!(frame.metadata.as_ref().unwrap().timestamp_us.is_some());
+
+        let mut text = Packet::Text("hello".into());
+        retimer.apply(&mut text);
+    }
+
+    #[test]
+    fn test_telemetry_filters_and_source_fields() {
+        let source = TapSource::Telemetry {
+            event_types: vec!["vad.*".to_string(), "stt.result".to_string()],
+            nodes: vec![],
+        };
+        assert!(source.accepts(&telemetry_event("vad", "vad.start")));
+        assert!(source.accepts(&telemetry_event("stt", "stt.result")));
+        assert!(!source.accepts(&telemetry_event("stt", "stt.partial")));
+
+        let by_node =
+            TapSource::Telemetry { event_types: vec![], nodes: vec!["whisper".to_string()] };
+        assert!(by_node.accepts(&telemetry_event("whisper", "stt.result")));
+        assert!(!by_node.accepts(&telemetry_event("vad", "vad.start")));
+
+        let Packet::Custom(data) = telemetry_packet(telemetry_event("vad", "vad.start"), "prod")
+        else {
+            unreachable!()
+        };
+        assert_eq!(data.type_id, TELEMETRY_TYPE_ID);
+        assert_eq!(data.data["source_session"], "prod");
+        assert_eq!(data.data["source_node"], "vad");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let tap = SessionBridgeNode::tap_factory();
+        assert!(tap(None).is_err());
+        assert!(tap(Some(&serde_json::json!({"session": "prod"}))).is_err());
+        assert!(tap(Some(&serde_json::json!({"session": "", "node": "mixer"}))).is_err());
+        let node = tap(Some(&serde_json::json!({"session": "prod", "node": "mixer"}))).unwrap();
+        assert_eq!(node.output_pins()[0].produces_type, PacketType::Any);
+
+        let telemetry = SessionBridgeNode::telemetry_factory();
+        assert!(telemetry(Some(&serde_json::json!({"session": "prod", "retry_interval_ms": 1})))
+            .is_err());
+        let node = telemetry(Some(&serde_json::json!({"session": "prod"}))).unwrap();
+        assert_eq!(
+            node.output_pins()[0].produces_type,
+            PacketType::Custom { type_id: TELEMETRY_TYPE_ID.to_string() }
+        );
+    }
+}
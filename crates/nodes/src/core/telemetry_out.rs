@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use streamkit_core::telemetry::{TelemetryEmitter, TELEMETRY_TYPE_ID};
+use streamkit_core::telemetry::TELEMETRY_TYPE_ID;
 use streamkit_core::types::{CustomPacketData, Packet, PacketType, TranscriptionData};
 use streamkit_core::{
     state_helpers, InputPin, NodeContext, OutputPin, PinCardinality, ProcessorNode, StreamKitError,
@@ -163,11 +163,7 @@ impl ProcessorNode for TelemetryOutNode {
         let node_name = context.output_sender.node_name().to_string();
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
-        let mut telemetry = TelemetryEmitter::new(
-            node_name.clone(),
-            context.session_id.clone(),
-            context.telemetry_tx.clone(),
-        );
+        let telemetry = context.telemetry_emitter();
         telemetry.set_rate_limit(self.config.max_events_per_sec);
 
         let mut input_rx = context.take_input("in")?;
@@ -36,7 +36,6 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::time::Instant;
-use streamkit_core::telemetry::TelemetryEmitter;
 use streamkit_core::types::{Packet, PacketType, TranscriptionData};
 use streamkit_core::{
     state_helpers, InputPin, NodeContext, OutputPin, PinCardinality, ProcessorNode, StreamKitError,
@@ -221,11 +220,7 @@ impl ProcessorNode for TelemetryTapNode {
         state_helpers::emit_initializing(&context.state_tx, &node_name);
 
         // Create telemetry emitter
-        let mut telemetry = TelemetryEmitter::new(
-            node_name.clone(),
-            context.session_id.clone(),
-            context.telemetry_tx.clone(),
-        );
+        let telemetry = context.telemetry_emitter();
 
         // Configure rate limiting
         telemetry.set_rate_limit(self.config.max_events_per_sec);
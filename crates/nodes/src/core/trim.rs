@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Trim node - keeps only the packets that fall inside a time window
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use streamkit_core::types::{Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+
+/// Configuration for the TrimNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TrimConfig {
+    /// Start of the kept window in milliseconds, inclusive.
+    pub start_ms: u64,
+    /// End of the kept window in milliseconds, exclusive. Unbounded when unset.
+    pub end_ms: Option<u64>,
+    /// Keep only the final `last_ms` milliseconds of the stream instead of a fixed window.
+    /// Output is held back until the input ends. Cannot be combined with `start_ms`/`end_ms`.
+    pub last_ms: Option<u64>,
+    /// Shift kept timestamps so the window starts at zero. Default: true.
+    pub rebase: bool,
+}
+
+impl Default for TrimConfig {
+    fn default() -> Self {
+        Self { start_ms: 0, end_ms: None, last_ms: None, rebase: true }
+    }
+}
+
+impl TrimConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(last_ms) = self.last_ms {
+            if last_ms == 0 {
+                return Err("last_ms must be greater than 0".to_string());
+            }
+            if self.start_ms != 0 || self.end_ms.is_some() {
+                return Err("last_ms cannot be combined with start_ms or end_ms".to_string());
+            }
+        }
+        if self.end_ms.is_some_and(|end_ms| end_ms <= self.start_ms) {
+            return Err("end_ms must be greater than start_ms".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A node that cuts a time window out of a stream.
+///
+/// A packet's position is its `metadata.timestamp_us`. Audio frames without a timestamp are
+/// placed by the running duration of the frames before them. Packets with no timing at all,
+/// such as container header packets, are dropped: they cannot be placed in the window.
+///
+/// In window mode the node stops as soon as a packet at or past `end_ms` arrives, so upstream
+/// readers are not drained needlessly. In `last_ms` mode packets are buffered (bounded by the
+/// window length) and released once the input closes.
+pub struct TrimNode {
+    config: TrimConfig,
+}
+
+impl TrimNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            let config: TrimConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(StreamKitError::Configuration)?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+fn metadata(packet: &Packet) -> Option<&PacketMetadata> {
+    match packet {
+        Packet::Audio(frame) => frame.metadata.as_ref(),
+        Packet::Binary { metadata, .. } => metadata.as_ref(),
+        Packet::Transcription(data) => data.metadata.as_ref(),
+        Packet::Custom(data) => data.metadata.as_ref(),
+        Packet::Text(_) => None,
+    }
+}
+
+fn metadata_mut(packet: &mut Packet) -> Option<&mut PacketMetadata> {
+    match packet {
+        Packet::Audio(frame) => frame.metadata.as_mut(),
+        Packet::Binary { metadata, .. } => metadata.as_mut(),
+        Packet::Transcription(data) => Arc::make_mut(data).metadata.as_mut(),
+        Packet::Custom(data) => Arc::make_mut(data).metadata.as_mut(),
+        Packet::Text(_) => None,
+    }
+}
+
+/// Places packets on the stream's timeline.
+#[derive(Default)]
+struct Clock {
+    /// End of the latest audio frame, used for frames that carry no timestamp
+    audio_end_us: u64,
+}
+
+impl Clock {
+    /// Returns the packet's start and end in microseconds, or `None` if it has no timing.
+    fn place(&mut self, packet: &Packet) -> Option<(u64, u64)> {
+        let meta = metadata(packet);
+        let duration_us = meta.and_then(|m| m.duration_us).or_else(|| match packet {
+            Packet::Audio(frame) => frame.duration_us(),
+            _ => None,
+        });
+        let start_us = match (meta.and_then(|m| m.timestamp_us), packet) {
+            (Some(ts), _) => ts,
+            (None, Packet::Audio(_)) => self.audio_end_us,
+            (None, _) => return None,
+        };
+        let end_us = start_us.saturating_add(duration_us.unwrap_or(0));
+        if matches!(packet, Packet::Audio(_)) {
+            self.audio_end_us = end_us;
+        }
+        Some((start_us, end_us))
+    }
+}
+
+/// Moves the packet's timestamp `offset_us` earlier.
+fn rebase(packet: &mut Packet, offset_us: u64) {
+    if offset_us == 0 {
+        return;
+    }
+    if let Some(ts) = metadata_mut(packet).and_then(|m| m.timestamp_us.as_mut()) {
+        *ts = ts.saturating_sub(offset_us);
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for TrimNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Passthrough,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let start_us = self.config.start_ms.saturating_mul(1000);
+        let end_us = self.config.end_ms.map(|ms| ms.saturating_mul(1000));
+        let last_us = self.config.last_ms.map(|ms| ms.saturating_mul(1000));
+        let rebase_by = |cut_us: u64| if self.config.rebase { cut_us } else { 0 };
+
+        let mut clock = Clock::default();
+        // Tail buffer for `last_ms` mode: (start, packet), oldest first.
+        let mut tail: VecDeque<(u64, Packet)> = VecDeque::new();
+        let mut stream_end_us = 0u64;
+        let mut reason = "input_closed";
+
+        while let Some(mut packet) = context.recv_with_cancellation(&mut input_rx).await {
+            stats_tracker.received();
+            let Some((packet_start, packet_end)) = clock.place(&packet) else {
+                stats_tracker.discarded();
+                stats_tracker.maybe_send();
+                continue;
+            };
+
+            if let Some(last_us) = last_us {
+                stream_end_us = stream_end_us.max(packet_end);
+                tail.push_back((packet_start, packet));
+                let cut_us = stream_end_us.saturating_sub(last_us);
+                while tail.front().is_some_and(|(start, _)| *start < cut_us) {
+                    tail.pop_front();
+                    stats_tracker.discarded();
+                }
+                stats_tracker.maybe_send();
+                continue;
+            }
+
+            if end_us.is_some_and(|end_us| packet_start >= end_us) {
+                stats_tracker.discarded();
+                reason = "end_reached";
+                break;
+            }
+            if packet_start < start_us {
+                stats_tracker.discarded();
+                stats_tracker.maybe_send();
+                continue;
+            }
+
+            rebase(&mut packet, rebase_by(start_us));
+            if context.output_sender.send("out", packet).await.is_err() {
+                tracing::debug!("Output channel closed, stopping node");
+                reason = "output_closed";
+                break;
+            }
+            stats_tracker.sent();
+            stats_tracker.maybe_send();
+        }
+
+        if let Some(last_us) = last_us {
+            let cut_us = stream_end_us.saturating_sub(last_us);
+            tracing::debug!(packets = tail.len(), cut_us, "Releasing trimmed tail");
+            for (_, mut packet) in tail {
+                rebase(&mut packet, rebase_by(cut_us));
+                if context.output_sender.send("out", packet).await.is_err() {
+                    tracing::debug!("Output channel closed, stopping node");
+                    reason = "output_closed";
+                    break;
+                }
+                stats_tracker.sent();
+            }
+        }
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    fn timed_packet(timestamp_ms: u64) -> Packet {
+        Packet::Binary {
+            data: Bytes::from(timestamp_ms.to_le_bytes().to_vec()),
+            content_type: None,
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(timestamp_ms * 1000),
+                duration_us: Some(20_000),
+                sequence: None,
+            }),
+        }
+    }
+
+    fn timestamps_ms(packets: &[Packet]) -> Vec<u64> {
+        packets.iter().map(|p| metadata(p).unwrap().timestamp_us.unwrap() / 1000).collect()
+    }
+
+    async fn run_trim(config: TrimConfig, packets: Vec<Packet>) -> Vec<Packet> {
+        let (input_tx, input_rx) = mpsc::channel(packets.len() + 1);
+        let mut inputs = HashMap::new();
+        inputs.insert("in".to_string(), input_rx);
+        let (context, mock_sender, mut state_rx) = create_test_context(inputs, 10);
+
+        let node = (TrimNode::factory())(Some(&serde_json::to_value(config).unwrap())).unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        for packet in packets {
+            // The node may stop early once the window has passed.
+            let _ = input_tx.send(packet).await;
+        }
+        drop(input_tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+        mock_sender.get_packets_for_pin("out").await
+    }
+
+    #[tokio::test]
+    async fn keeps_window_and_rebases() {
+        let packets = (0..10).map(|i| timed_packet(i * 20)).collect();
+        let config = TrimConfig { start_ms: 40, end_ms: Some(100), ..TrimConfig::default() };
+        let out = run_trim(config, packets).await;
+        assert_eq!(timestamps_ms(&out), vec![0, 20, 40]);
+    }
+
+    #[tokio::test]
+    async fn keeps_original_timestamps_without_rebase() {
+        let packets = (0..10).map(|i| timed_packet(i * 20)).collect();
+        let config = TrimConfig { start_ms: 40, end_ms: Some(100), rebase: false, last_ms: None };
+        let out = run_trim(config, packets).await;
+        assert_eq!(timestamps_ms(&out), vec![40, 60, 80]);
+    }
+
+    #[tokio::test]
+    async fn keeps_last_window() {
+        let packets = (0..10).map(|i| timed_packet(i * 20)).collect();
+        let config = TrimConfig { last_ms: Some(60), ..TrimConfig::default() };
+        let out = run_trim(config, packets).await;
+        // The stream ends at 200ms, so the last 60ms starts at 140ms.
+        assert_eq!(timestamps_ms(&out), vec![0, 20, 40]);
+    }
+
+    #[tokio::test]
+    async fn drops_untimed_packets_and_places_untimed_audio() {
+        let header = Packet::Binary {
+            data: Bytes::from_static(b"OpusHead"),
+            content_type: None,
+            metadata: None,
+        };
+        // 10ms frames at 48kHz, no metadata
+        let audio = || crate::test_utils::create_test_audio_packet(48000, 1, 480, 0.5);
+        let packets = vec![header, audio(), audio(), audio(), audio()];
+        let config = TrimConfig { start_ms: 20, ..TrimConfig::default() };
+        let out = run_trim(config, packets).await;
+        assert_eq!(out.len(), 2);
+        assert!(out.iter().all(|p| matches!(p, Packet::Audio(_))));
+    }
+
+    #[test]
+    fn rejects_invalid_windows() {
+        let bad = [
+            TrimConfig { start_ms: 100, end_ms: Some(100), ..TrimConfig::default() },
+            TrimConfig { last_ms: Some(0), ..TrimConfig::default() },
+            TrimConfig { start_ms: 10, last_ms: Some(1000), ..TrimConfig::default() },
+        ];
+        for config in bad {
+            let params = serde_json::to_value(&config).unwrap();
+            assert!((TrimNode::factory())(Some(&params)).is_err(), "{config:?} was accepted");
+        }
+    }
+}
@@ -0,0 +1,552 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Turn manager node - Decides when the user's turn ends and when the agent must stop talking
+//!
+//! A voice agent that answers every transcript fragment talks over the caller, and one that
+//! keeps playing its reply while the caller speaks feels deaf. This node sits between speech
+//! recognition and the language model, and on the agent's speech path: it joins transcript
+//! fragments into one user turn once VAD reports enough silence, and drops the agent's audio
+//! when the caller starts speaking over it (barge-in).
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::config_helpers::merge_params;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::types::{AudioFormat, Packet, PacketType, SampleFormat};
+use streamkit_core::{
+    config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
+    PinCardinality, ProcessorNode, StreamKitError,
+};
+use tokio::sync::mpsc;
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// How often pending turns are checked against their end-of-turn deadline.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Configuration for the TurnManagerNode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TurnManagerConfig {
+    /// Silence after the user stops speaking before their turn is committed, in milliseconds
+    #[schemars(range(min = 0, max = 10_000), extend("tunable" = true))]
+    pub end_of_turn_ms: u64,
+    /// Drop the agent's speech when the user starts talking over it
+    #[schemars(extend("tunable" = true))]
+    pub barge_in: bool,
+    /// Custom packet `type_id` of the VAD events on `in_1`
+    pub vad_type_id: String,
+}
+
+impl Default for TurnManagerConfig {
+    fn default() -> Self {
+        Self {
+            end_of_turn_ms: 600,
+            barge_in: true,
+            vad_type_id: "plugin::native::vad/vad-event@1".to_string(),
+        }
+    }
+}
+
+impl TurnManagerConfig {
+    /// Checks ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first invalid field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.end_of_turn_ms > 10_000 {
+            return Err("end_of_turn_ms must be at most 10000".to_string());
+        }
+        if self.vad_type_id.is_empty() {
+            return Err("vad_type_id must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    const fn end_of_turn(&self) -> Duration {
+        Duration::from_millis(self.end_of_turn_ms)
+    }
+}
+
+/// Turn-taking state, separated from I/O.
+#[derive(Debug, Default)]
+struct TurnState {
+    /// Set once any VAD event arrived; without VAD, turns end on transcript silence alone
+    vad_seen: bool,
+    user_speaking: bool,
+    /// Transcript fragments of the turn in progress
+    pending: Vec<String>,
+    /// When the turn in progress may be committed
+    commit_at: Option<Instant>,
+    /// Estimated end of playback of the agent audio forwarded so far
+    agent_busy_until: Option<Instant>,
+    /// Agent audio is dropped until the next user turn is committed
+    interrupted: bool,
+    turns: u64,
+    barge_ins: u64,
+    dropped_frames: u64,
+}
+
+impl TurnState {
+    fn agent_speaking(&self, now: Instant) -> bool {
+        self.agent_busy_until.is_some_and(|until| until > now)
+    }
+
+    /// Applies a VAD event, returning `true` when it interrupted the agent.
+    fn on_vad(&mut self, event_type: &str, now: Instant, config: &TurnManagerConfig) -> bool {
+        self.vad_seen = true;
+        match event_type {
+            "speech_start" => {
+                self.user_speaking = true;
+                self.commit_at = None;
+                if config.barge_in && self.agent_speaking(now) && !self.interrupted {
+                    self.interrupted = true;
+                    self.agent_busy_until = None;
+                    self.barge_ins += 1;
+                    return true;
+                }
+            },
+            "speech_end" => {
+                self.user_speaking = false;
+                self.commit_at = Some(now + config.end_of_turn());
+            },
+            _ => {},
+        }
+        false
+    }
+
+    fn on_transcript(&mut self, text: &str, now: Instant, config: &TurnManagerConfig) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.pending.push(text.to_string());
+        // With VAD the deadline follows speech_end (a late transcript commits right away);
+        // without it, each fragment restarts the silence timer.
+        if !self.vad_seen {
+            self.commit_at = Some(now + config.end_of_turn());
+        }
+    }
+
+    /// Commits the pending turn once its deadline has passed and the user is silent.
+    fn poll(&mut self, now: Instant) -> Option<String> {
+        if self.user_speaking
+            || self.pending.is_empty()
+            || self.commit_at.is_none_or(|deadline| deadline > now)
+        {
+            return None;
+        }
+        self.commit_at = None;
+        self.interrupted = false;
+        self.turns += 1;
+        Some(std::mem::take(&mut self.pending).join(" "))
+    }
+
+    /// Accounts for a frame of agent audio, returning whether it may be played.
+    fn on_agent_audio(&mut self, duration: Duration, now: Instant) -> bool {
+        if self.interrupted {
+            self.dropped_frames += 1;
+            return false;
+        }
+        let start = self.agent_busy_until.filter(|until| *until > now).unwrap_or(now);
+        self.agent_busy_until = Some(start + duration);
+        true
+    }
+
+    fn status(&self, now: Instant) -> serde_json::Value {
+        serde_json::json!({
+            "user_speaking": self.user_speaking,
+            "agent_speaking": self.agent_speaking(now),
+            "interrupted": self.interrupted,
+            "pending_fragments": self.pending.len(),
+            "turns": self.turns,
+            "barge_ins": self.barge_ins,
+            "dropped_frames": self.dropped_frames,
+        })
+    }
+}
+
+/// Receives from an optional input, pending forever once it is gone.
+async fn recv_from(rx: &mut Option<mpsc::Receiver<Packet>>) -> Option<Packet> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// A node that manages turn-taking between a user and a voice agent.
+///
+/// Inputs are positional so they can be wired with a `needs` list:
+/// - `in_0`: Transcription or Text from speech recognition
+/// - `in_1`: VAD events (`speech_start` / `speech_end` Custom packets)
+/// - `in_2`: the agent's synthesized speech, usually connected with `mode: feedback`
+///
+/// Transcript fragments are joined into one user turn, which is sent as Text on `out` once
+/// the user has been silent for `end_of_turn_ms` after VAD's `speech_end`. Without VAD events
+/// the timer restarts on every fragment instead. Agent audio is forwarded on `audio`; when
+/// the user starts speaking while it is still playing and `barge_in` is on, the rest of that
+/// reply is dropped until the next user turn is committed. Playback is estimated from the
+/// forwarded frame durations, so place this node before the pacer.
+///
+/// Emits `turn.user` and `turn.barge_in` telemetry events and answers the `"status"` query.
+pub struct TurnManagerNode {
+    config: TurnManagerConfig,
+}
+
+impl TurnManagerNode {
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        Arc::new(|params| {
+            let config: TurnManagerConfig = config_helpers::parse_config_optional(params)?;
+            config.validate().map_err(StreamKitError::Configuration)?;
+            Ok(Box::new(Self { config }))
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for TurnManagerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "in_0".to_string(),
+                accepts_types: vec![PacketType::Transcription, PacketType::Text],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "in_1".to_string(),
+                accepts_types: vec![PacketType::Custom {
+                    type_id: self.config.vad_type_id.clone(),
+                }],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "in_2".to_string(),
+                accepts_types: vec![PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0, // Wildcard
+                    channels: 0,    // Wildcard
+                    sample_format: SampleFormat::F32,
+                })],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![
+            OutputPin {
+                name: "out".to_string(),
+                produces_type: PacketType::Text,
+                cardinality: PinCardinality::Broadcast,
+            },
+            OutputPin {
+                name: "audio".to_string(),
+                produces_type: PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0,
+                    channels: 0,
+                    sample_format: SampleFormat::F32,
+                }),
+                cardinality: PinCardinality::Broadcast,
+            },
+        ]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        // Unconnected inputs are simply absent: no VAD means no barge-in, no agent audio
+        // means the node only segments turns.
+        let mut transcript_rx = context.inputs.remove("in_0");
+        let mut vad_rx = context.inputs.remove("in_1");
+        let mut agent_rx = context.inputs.remove("in_2");
+
+        let telemetry = context.telemetry_emitter();
+        let mut stats = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut config = self.config;
+        let mut turns = TurnState::default();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let reason = loop {
+            if transcript_rx.is_none() && agent_rx.is_none() {
+                // Nothing left to forward; pending fragments can no longer form a reply.
+                break "input_closed";
+            }
+
+            let turn = tokio::select! {
+                biased;
+
+                Some(msg) = context.control_rx.recv() => {
+                    match msg {
+                        NodeControlMessage::UpdateParams(params) => {
+                            match merge_params(&config, &params).and_then(|updated| {
+                                updated.validate().map(|()| updated)
+                            }) {
+                                Ok(updated) if updated.vad_type_id == config.vad_type_id => {
+                                    config = updated;
+                                },
+                                Ok(_) => {
+                                    tracing::warn!(
+                                        "Rejected turn manager update: vad_type_id is fixed"
+                                    );
+                                    stats.errored();
+                                },
+                                Err(e) => {
+                                    tracing::warn!("Rejected turn manager update: {}", e);
+                                    stats.errored();
+                                },
+                            }
+                        },
+                        NodeControlMessage::Query { kind, reply, .. } if kind == "status" => {
+                            let _ = reply.send(Ok(turns.status(Instant::now())));
+                        },
+                        NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                        NodeControlMessage::Start => {},
+                        NodeControlMessage::Shutdown => break "shutdown",
+                    }
+                    None
+                }
+                maybe_event = recv_from(&mut vad_rx) => {
+                    match maybe_event {
+                        Some(Packet::Custom(event)) if event.type_id == config.vad_type_id => {
+                            stats.received();
+                            let event_type =
+                                event.data.get("event_type").and_then(|t| t.as_str()).unwrap_or("");
+                            if turns.on_vad(event_type, Instant::now(), &config) {
+                                tracing::debug!("TurnManagerNode: user barged in");
+                                telemetry.emit(
+                                    "turn.barge_in",
+                                    serde_json::json!({ "barge_ins": turns.barge_ins }),
+                                );
+                            }
+                        },
+                        Some(_) => {
+                            stats.received();
+                            stats.discarded();
+                        },
+                        None => vad_rx = None,
+                    }
+                    None
+                }
+                maybe_packet = recv_from(&mut transcript_rx) => {
+                    match maybe_packet {
+                        Some(Packet::Transcription(transcription)) => {
+                            stats.received();
+                            turns.on_transcript(&transcription.text, Instant::now(), &config);
+                        },
+                        Some(Packet::Text(text)) => {
+                            stats.received();
+                            turns.on_transcript(&text, Instant::now(), &config);
+                        },
+                        Some(_) => {
+                            stats.received();
+                            stats.discarded();
+                        },
+                        None => transcript_rx = None,
+                    }
+                    None
+                }
+                maybe_packet = recv_from(&mut agent_rx) => {
+                    let Some(packet) = maybe_packet else {
+                        agent_rx = None;
+                        continue;
+                    };
+                    stats.received();
+                    let Packet::Audio(frame) = &packet else {
+                        stats.discarded();
+                        continue;
+                    };
+                    let duration = Duration::from_micros(frame.duration_us().unwrap_or(0));
+                    if !turns.on_agent_audio(duration, Instant::now()) {
+                        stats.discarded();
+                        stats.maybe_send();
+                        continue;
+                    }
+                    if context.output_sender.send("audio", packet).await.is_err() {
+                        tracing::debug!("Audio output closed, stopping node");
+                        break "output_closed";
+                    }
+                    stats.sent();
+                    stats.maybe_send();
+                    None
+                }
+                _ = ticker.tick() => turns.poll(Instant::now()),
+            };
+
+            let Some(text) = turn else { continue };
+            tracing::debug!(turn = turns.turns, "TurnManagerNode: user turn committed");
+            telemetry.emit("turn.user", serde_json::json!({ "turn": turns.turns, "text": text }));
+            if context.output_sender.send("out", Packet::Text(text.into())).await.is_err() {
+                tracing::debug!("Turn output closed, stopping node");
+                break "output_closed";
+            }
+            stats.sent();
+            stats.maybe_send();
+        };
+
+        stats.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+pub fn register(registry: &mut streamkit_core::NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = match serde_json::to_value(schema_for!(TurnManagerConfig)) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize TurnManagerConfig schema");
+            return;
+        },
+    };
+
+    let factory = TurnManagerNode::factory();
+    registry.register_dynamic_with_description(
+        "core::turn_manager",
+        move |params| (factory)(params),
+        schema,
+        vec!["core".to_string(), "routing".to_string()],
+        false,
+        "Turn-taking for voice agents: joins transcript fragments (in_0) into one user turn \
+         once VAD (in_1) reports end_of_turn_ms of silence, and drops the agent's speech \
+         (in_2, forwarded on the audio pin) when the user barges in.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test setup failures should panic
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_test_audio_packet, create_test_context};
+    use std::collections::HashMap;
+    use streamkit_core::types::{CustomEncoding, CustomPacketData};
+
+    fn vad_event(event_type: &str) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: TurnManagerConfig::default().vad_type_id,
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({ "event_type": event_type, "timestamp_ms": 0 }),
+            metadata: None,
+        }))
+    }
+
+    #[test]
+    fn test_turn_commits_after_silence() {
+        let config = TurnManagerConfig::default();
+        let mut state = TurnState::default();
+        let t0 = Instant::now();
+
+        state.on_vad("speech_start", t0, &config);
+        state.on_transcript("what's the", t0, &config);
+        state.on_transcript("weather like", t0, &config);
+        // Still speaking: nothing is committed however long it takes
+        assert_eq!(state.poll(t0 + Duration::from_secs(5)), None);
+
+        let end = t0 + Duration::from_secs(5);
+        state.on_vad("speech_end", end, &config);
+        assert_eq!(state.poll(end + Duration::from_millis(300)), None);
+        assert_eq!(
+            state.poll(end + Duration::from_millis(600)).as_deref(),
+            Some("what's the weather like")
+        );
+        assert_eq!(state.turns, 1);
+
+        // A transcript arriving after the deadline commits on the next poll
+        let late = end + Duration::from_secs(2);
+        state.on_vad("speech_start", late, &config);
+        state.on_vad("speech_end", late, &config);
+        state.on_transcript("thanks", late + Duration::from_secs(1), &config);
+        assert_eq!(state.poll(late + Duration::from_secs(1)).as_deref(), Some("thanks"));
+    }
+
+    #[test]
+    fn test_turn_without_vad_uses_transcript_timer() {
+        let config = TurnManagerConfig { end_of_turn_ms: 500, ..Default::default() };
+        let mut state = TurnState::default();
+        let t0 = Instant::now();
+
+        state.on_transcript("hello", t0, &config);
+        state.on_transcript("there", t0 + Duration::from_millis(400), &config);
+        assert_eq!(state.poll(t0 + Duration::from_millis(600)), None);
+        assert_eq!(state.poll(t0 + Duration::from_millis(900)).as_deref(), Some("hello there"));
+    }
+
+    #[test]
+    fn test_barge_in_drops_agent_audio_until_next_turn() {
+        let config = TurnManagerConfig::default();
+        let mut state = TurnState::default();
+        let t0 = Instant::now();
+        let frame = Duration::from_millis(20);
+
+        // A burst of 50 frames (1s) queued ahead of playback
+        for _ in 0..50 {
+            assert!(state.on_agent_audio(frame, t0));
+        }
+        assert!(state.agent_speaking(t0 + Duration::from_millis(900)));
+
+        assert!(state.on_vad("speech_start", t0 + Duration::from_millis(200), &config));
+        assert!(!state.on_agent_audio(frame, t0 + Duration::from_millis(220)));
+        assert_eq!(state.barge_ins, 1);
+
+        state.on_transcript("stop", t0, &config);
+        let end = t0 + Duration::from_millis(400);
+        state.on_vad("speech_end", end, &config);
+        assert_eq!(state.poll(end + Duration::from_secs(1)).as_deref(), Some("stop"));
+        assert!(state.on_agent_audio(frame, end + Duration::from_secs(2)));
+
+        // Speech while the agent is silent, or with barge-in off, interrupts nothing
+        let quiet = end + Duration::from_secs(10);
+        assert!(!state.on_vad("speech_start", quiet, &config));
+        let config = TurnManagerConfig { barge_in: false, ..Default::default() };
+        let mut state = TurnState::default();
+        assert!(state.on_agent_audio(frame, t0));
+        assert!(!state.on_vad("speech_start", t0, &config));
+    }
+
+    #[tokio::test]
+    async fn test_turn_manager_node() {
+        let (transcript_tx, transcript_rx) = mpsc::channel(10);
+        let (vad_tx, vad_rx) = mpsc::channel(10);
+        let (agent_tx, agent_rx) = mpsc::channel(10);
+        let inputs = HashMap::from([
+            ("in_0".to_string(), transcript_rx),
+            ("in_1".to_string(), vad_rx),
+            ("in_2".to_string(), agent_rx),
+        ]);
+        let (context, mock_sender, _state_rx) = create_test_context(inputs, 10);
+        let node = (TurnManagerNode::factory())(Some(&serde_json::json!({ "end_of_turn_ms": 0 })))
+            .unwrap();
+        let handle = tokio::spawn(async move { node.run(context).await });
+
+        // Agent reply is playing (1s of audio) when the user starts speaking
+        for _ in 0..5 {
+            agent_tx.send(create_test_audio_packet(8000, 1, 1600, 0.5)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        vad_tx.send(vad_event("speech_start")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        agent_tx.send(create_test_audio_packet(8000, 1, 1600, 0.5)).await.unwrap();
+        transcript_tx.send(Packet::Text("wait a second".into())).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        vad_tx.send(vad_event("speech_end")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        drop((transcript_tx, vad_tx, agent_tx));
+        handle.await.unwrap().unwrap();
+
+        // Collecting drains the mock sender, so read both pins from one collection
+        let packets = mock_sender.collect_packets().await;
+        let turns: Vec<&Packet> =
+            packets.iter().filter(|(_, pin, _)| pin == "out").map(|(_, _, p)| p).collect();
+        assert_eq!(turns.len(), 1);
+        assert!(matches!(turns[0], Packet::Text(text) if &**text == "wait a second"));
+        // The frame sent after the barge-in was dropped
+        assert_eq!(packets.iter().filter(|(_, pin, _)| pin == "audio").count(), 5);
+    }
+}
@@ -27,11 +27,15 @@ pub fn register_nodes(
     global_script_allowlist: Option<Vec<core::script::AllowlistRule>>,
     secrets: std::collections::HashMap<String, core::script::ScriptSecret>,
 ) {
+    // Transport nodes only need the secret values (e.g. MoQ encryption keys).
+    let secret_values =
+        secrets.iter().map(|(name, secret)| (name.clone(), secret.value.clone())).collect();
+
     // Call the registration function for each feature module.
     core::register_core_nodes(registry, global_script_allowlist, secrets);
     audio::register_audio_nodes(registry);
     containers::register_container_nodes(registry);
-    transport::register_transport_nodes(registry);
+    transport::register_transport_nodes(registry, &secret_values);
     // video::register_video_nodes(registry);
 
     tracing::info!("Finished registering built-in nodes.");
@@ -44,7 +48,7 @@ pub fn register_nodes(registry: &mut NodeRegistry) {
     core::register_core_nodes(registry);
     audio::register_audio_nodes(registry);
     containers::register_container_nodes(registry);
-    transport::register_transport_nodes(registry);
+    transport::register_transport_nodes(registry, &std::collections::HashMap::new());
     // video::register_video_nodes(registry);
 
     tracing::info!("Finished registering built-in nodes.");
@@ -38,6 +38,10 @@ pub fn create_test_context(
         cancellation_token: None,
         pin_management_rx: Some(pin_mgmt_rx), // Provide channel for dynamic pins support
         audio_pool: None,
+        media_clock: None,
+        cost_meter: None,
+        telemetry_limits: None,
+        lookahead: None,
     };
 
     (context, mock_sender, state_rx)
@@ -0,0 +1,1295 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! HLS packager node.
+//!
+//! `transport::hls::packager` takes the byte stream of `containers::mp4::muxer` (fragmented
+//! MP4) or `containers::mpegts::muxer` and writes a rolling HLS rendition into a directory:
+//! media segments, `init.mp4` for fMP4, and a media playlist that is replaced atomically after
+//! every change, so a static file server never hands out a half-written playlist.
+//!
+//! The stream is first split into timed units: one `moof` + `mdat` fragment for fMP4, the
+//! packets between two PATs for TS. A segment is closed at the first unit that starts on a key
+//! frame once the target duration would be exceeded, so segment length follows the encoder's
+//! GOP. With `part_duration_ms` set, units are also grouped into LL-HLS partial segments and the
+//! playlist advertises them together with a preload hint for the next part.
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::io;
+use std::path::PathBuf;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use tokio::sync::mpsc;
+
+use crate::containers::mp4::{child_boxes, find_child, BoxReader};
+
+// --- HLS Constants ---
+
+const DEFAULT_PLAYLIST_NAME: &str = "index.m3u8";
+const DEFAULT_SEGMENT_PREFIX: &str = "segment";
+const DEFAULT_SEGMENT_DURATION_MS: u64 = 6000;
+const DEFAULT_WINDOW_SIZE: usize = 6;
+const INIT_SEGMENT_NAME: &str = "init.mp4";
+/// Partial segments are listed for segments this many target durations from the live edge.
+const PART_LISTING_TARGET_DURATIONS: u64 = 3;
+/// Largest MP4 box buffered while waiting for it to complete.
+const MAX_BOX_SIZE: usize = 256 * 1024 * 1024;
+
+const TS_PACKET_SIZE: usize = 188;
+const SYNC_BYTE: u8 = 0x47;
+const PAT_PID: u16 = 0x0000;
+const TS_CLOCK_HZ: u64 = 90_000;
+
+/// `trun` sample flags: `sample_is_non_sync_sample`.
+const SAMPLE_FLAGS_NON_SYNC: u32 = 0x0001_0000;
+
+/// Container format of the packager input.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HlsInputFormat {
+    /// Detect from the first byte of the stream (0x47 sync byte means MPEG-TS)
+    #[default]
+    Auto,
+    /// Fragmented MP4, as produced by `containers::mp4::muxer`
+    Fmp4,
+    /// MPEG-TS, as produced by `containers::mpegts::muxer`
+    Ts,
+}
+
+/// Configuration for the HLS packager.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct HlsPackagerConfig {
+    /// Directory the playlist and segments are written to (created if missing)
+    #[schemars(extend("sensitive" = true))]
+    pub directory: String,
+    /// File name of the media playlist
+    #[serde(default = "default_playlist_name")]
+    pub playlist_name: String,
+    /// File name prefix of media segments (`segment00042.m4s`, `segment00042.ts`)
+    #[serde(default = "default_segment_prefix")]
+    pub segment_prefix: String,
+    /// Container format of the input stream
+    #[serde(default)]
+    pub format: HlsInputFormat,
+    /// Target segment duration in milliseconds. Segments are cut on key frames, so actual
+    /// durations follow the input's key frame interval.
+    #[serde(default = "default_segment_duration_ms")]
+    #[schemars(range(min = 1))]
+    pub segment_duration_ms: u64,
+    /// Number of segments listed in the playlist. 0 keeps every segment and writes an EVENT
+    /// playlist.
+    #[serde(default = "default_window_size")]
+    pub window_size: usize,
+    /// Delete segments once they have been out of the playlist window for another full window
+    #[serde(default = "default_delete_old_segments")]
+    pub delete_old_segments: bool,
+    /// Low-latency HLS partial segment target in milliseconds (unset: no partial segments).
+    /// Input fragments should be no longer than this.
+    #[serde(default)]
+    pub part_duration_ms: Option<u64>,
+}
+
+fn default_playlist_name() -> String {
+    DEFAULT_PLAYLIST_NAME.to_string()
+}
+
+fn default_segment_prefix() -> String {
+    DEFAULT_SEGMENT_PREFIX.to_string()
+}
+
+const fn default_segment_duration_ms() -> u64 {
+    DEFAULT_SEGMENT_DURATION_MS
+}
+
+const fn default_window_size() -> usize {
+    DEFAULT_WINDOW_SIZE
+}
+
+const fn default_delete_old_segments() -> bool {
+    true
+}
+
+/// Checks that `name` is a single path component, so it cannot escape `directory`.
+fn validate_file_name(field: &str, name: &str) -> Result<(), StreamKitError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(StreamKitError::Configuration(format!(
+            "{field} must be a plain file name, got '{name}'"
+        )));
+    }
+    Ok(())
+}
+
+/// Formats microseconds as seconds with millisecond precision.
+fn seconds(us: u64) -> String {
+    format!("{}.{:03}", us / 1_000_000, us % 1_000_000 / 1000)
+}
+
+#[allow(clippy::cast_possible_truncation)] // Media timelines stay far below u64::MAX µs
+const fn ticks_to_us(ticks: u64, timescale: u64) -> u64 {
+    if timescale == 0 {
+        return 0;
+    }
+    (ticks as u128 * 1_000_000 / timescale as u128) as u64
+}
+
+// --- Stream splitting ---
+
+/// A timed piece of the input that is copied verbatim into segments.
+struct MediaUnit {
+    data: Vec<u8>,
+    duration_us: u64,
+    /// Playback can start here: a key frame, or any unit of an audio-only stream.
+    independent: bool,
+}
+
+enum UnitEvent {
+    /// fMP4 initialization segment (`ftyp` + `moov`).
+    Init(Vec<u8>),
+    Unit(MediaUnit),
+}
+
+/// The track whose timing and sync samples drive segmentation.
+struct ReferenceTrack {
+    id: u32,
+    timescale: u32,
+    is_video: bool,
+    default_duration: u32,
+    default_flags: u32,
+}
+
+/// Picks the first video track of `moov`, or the first track when there is no video.
+fn parse_reference_track(moov: &[u8]) -> Result<ReferenceTrack, String> {
+    let mut selected: Option<ReferenceTrack> = None;
+    for child in child_boxes(moov) {
+        let (kind, trak) = child?;
+        if &kind != b"trak" {
+            continue;
+        }
+        let Some(tkhd) = find_child(trak, b"tkhd")? else { continue };
+        let mut r = BoxReader::new(tkhd);
+        let (version, _) = r.full_header()?;
+        r.skip(if version == 1 { 16 } else { 8 })?;
+        let id = r.u32()?;
+
+        let Some(mdia) = find_child(trak, b"mdia")? else { continue };
+        let Some(mdhd) = find_child(mdia, b"mdhd")? else { continue };
+        let mut r = BoxReader::new(mdhd);
+        let (version, _) = r.full_header()?;
+        r.skip(if version == 1 { 16 } else { 8 })?;
+        let timescale = r.u32()?;
+
+        let is_video = match find_child(mdia, b"hdlr")? {
+            Some(hdlr) => {
+                let mut r = BoxReader::new(hdlr);
+                r.full_header()?;
+                r.u32()?; // pre_defined
+                r.take(4)? == b"vide"
+            },
+            None => false,
+        };
+        if selected.as_ref().is_none_or(|track| is_video && !track.is_video) {
+            selected = Some(ReferenceTrack {
+                id,
+                timescale,
+                is_video,
+                default_duration: 0,
+                default_flags: 0,
+            });
+        }
+    }
+    let mut track = selected.ok_or("fMP4 moov has no tracks")?;
+
+    if let Some(mvex) = find_child(moov, b"mvex")? {
+        for child in child_boxes(mvex) {
+            let (kind, trex) = child?;
+            if &kind != b"trex" {
+                continue;
+            }
+            let mut r = BoxReader::new(trex);
+            r.full_header()?;
+            if r.u32()? != track.id {
+                continue;
+            }
+            r.u32()?; // default_sample_description_index
+            track.default_duration = r.u32()?;
+            r.u32()?; // default_sample_size
+            track.default_flags = r.u32()?;
+        }
+    }
+    Ok(track)
+}
+
+/// Timing of the reference track within one `moof`.
+struct FragmentTiming {
+    decode_time: Option<u64>,
+    duration: u64,
+    starts_with_sync: bool,
+}
+
+fn parse_fragment_timing(
+    moof: &[u8],
+    track: &ReferenceTrack,
+) -> Result<Option<FragmentTiming>, String> {
+    for child in child_boxes(moof) {
+        let (kind, traf) = child?;
+        if &kind != b"traf" {
+            continue;
+        }
+        let Some(tfhd) = find_child(traf, b"tfhd")? else { continue };
+        let mut r = BoxReader::new(tfhd);
+        let (_, flags) = r.full_header()?;
+        if r.u32()? != track.id {
+            continue;
+        }
+        if flags & 0x01 != 0 {
+            r.u64()?; // base_data_offset
+        }
+        if flags & 0x02 != 0 {
+            r.u32()?; // sample_description_index
+        }
+        let default_duration = if flags & 0x08 != 0 { r.u32()? } else { track.default_duration };
+        if flags & 0x10 != 0 {
+            r.u32()?; // default_sample_size
+        }
+        let default_flags = if flags & 0x20 != 0 { r.u32()? } else { track.default_flags };
+
+        let decode_time = match find_child(traf, b"tfdt")? {
+            Some(tfdt) => {
+                let mut r = BoxReader::new(tfdt);
+                let (version, _) = r.full_header()?;
+                Some(if version == 1 { r.u64()? } else { u64::from(r.u32()?) })
+            },
+            None => None,
+        };
+
+        let mut duration = 0u64;
+        let mut first_flags = None;
+        for child in child_boxes(traf) {
+            let (kind, trun) = child?;
+            if &kind != b"trun" {
+                continue;
+            }
+            let mut r = BoxReader::new(trun);
+            let (_, flags) = r.full_header()?;
+            let count = r.u32()?;
+            if flags & 0x01 != 0 {
+                r.u32()?; // data_offset
+            }
+            let first_sample_flags = if flags & 0x04 != 0 { Some(r.u32()?) } else { None };
+            for index in 0..count {
+                duration += u64::from(if flags & 0x100 != 0 { r.u32()? } else { default_duration });
+                if flags & 0x200 != 0 {
+                    r.u32()?; // sample_size
+                }
+                let sample_flags = if flags & 0x400 != 0 { r.u32()? } else { default_flags };
+                let sample_flags = if index == 0 {
+                    first_sample_flags.unwrap_or(sample_flags)
+                } else {
+                    sample_flags
+                };
+                first_flags.get_or_insert(sample_flags);
+                if flags & 0x800 != 0 {
+                    r.u32()?; // sample_composition_time_offset
+                }
+            }
+        }
+        return Ok(Some(FragmentTiming {
+            decode_time,
+            duration,
+            starts_with_sync: first_flags.is_some_and(|f| f & SAMPLE_FLAGS_NON_SYNC == 0),
+        }));
+    }
+    Ok(None)
+}
+
+/// Splits a fragmented MP4 byte stream into the init segment and `moof` + `mdat` units.
+#[derive(Default)]
+struct Fmp4Splitter {
+    buf: BytesMut,
+    init: Vec<u8>,
+    track: Option<ReferenceTrack>,
+    /// Boxes collected for the unit being assembled (`styp`/`sidx`/`prft` travel with the
+    /// fragment that follows them).
+    pending: Vec<u8>,
+    timing: Option<FragmentTiming>,
+    /// Decode time after the last fragment, for fragments without `tfdt` or without samples of
+    /// the reference track.
+    next_decode_time: u64,
+}
+
+impl Fmp4Splitter {
+    /// Header and total size of the box at the start of the buffer, once it is complete.
+    fn next_box(&self) -> Result<Option<(usize, usize)>, String> {
+        let Some(header) = self.buf.get(..8) else { return Ok(None) };
+        let (header_len, size) =
+            match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+                0 => {
+                    return Err(
+                        "MP4 boxes that run to the end of the stream are not supported".into()
+                    )
+                },
+                1 => {
+                    let Some(large) = self.buf.get(8..16) else { return Ok(None) };
+                    let mut b = [0u8; 8];
+                    b.copy_from_slice(large);
+                    (16, usize::try_from(u64::from_be_bytes(b)).unwrap_or(usize::MAX))
+                },
+                size => (8, size as usize),
+            };
+        if size < header_len {
+            return Err("Invalid MP4 box size".to_string());
+        }
+        if size > MAX_BOX_SIZE {
+            return Err(format!("MP4 box of {size} bytes exceeds the {MAX_BOX_SIZE}-byte limit"));
+        }
+        Ok((self.buf.len() >= size).then_some((header_len, size)))
+    }
+
+    fn push(&mut self, data: &[u8], events: &mut Vec<UnitEvent>) -> Result<(), String> {
+        self.buf.extend_from_slice(data);
+        while let Some((header_len, size)) = self.next_box()? {
+            let mp4_box = self.buf.split_to(size);
+            let payload = &mp4_box[header_len..];
+            match &mp4_box[4..8] {
+                b"ftyp" => self.init.extend_from_slice(&mp4_box),
+                b"moov" => {
+                    self.track = Some(parse_reference_track(payload)?);
+                    self.init.extend_from_slice(&mp4_box);
+                    events.push(UnitEvent::Init(std::mem::take(&mut self.init)));
+                },
+                b"moof" => {
+                    let track =
+                        self.track.as_ref().ok_or("fMP4 fragment arrived before the moov box")?;
+                    self.timing = parse_fragment_timing(payload, track)?;
+                    self.pending.extend_from_slice(&mp4_box);
+                },
+                b"mdat" => {
+                    let Some(track) = self.track.as_ref() else {
+                        return Err("MP4 'mdat' arrived before 'moov'".to_string());
+                    };
+                    if !self.pending.windows(4).any(|w| w == b"moof") {
+                        return Err("MP4 'mdat' without 'moof'; the HLS packager needs \
+                                    fragmented MP4"
+                            .to_string());
+                    }
+                    self.pending.extend_from_slice(&mp4_box);
+                    let timing = self.timing.take();
+                    let start = timing
+                        .as_ref()
+                        .and_then(|t| t.decode_time)
+                        .unwrap_or(self.next_decode_time);
+                    let duration = timing.as_ref().map_or(0, |t| t.duration);
+                    self.next_decode_time = start + duration;
+                    let timescale = u64::from(track.timescale);
+                    events.push(UnitEvent::Unit(MediaUnit {
+                        data: std::mem::take(&mut self.pending),
+                        duration_us: ticks_to_us(duration, timescale),
+                        independent: !track.is_video || timing.is_some_and(|t| t.starts_with_sync),
+                    }));
+                },
+                _ => self.pending.extend_from_slice(&mp4_box),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the PMT PID of the first program in a PAT section.
+fn parse_pat(payload: &[u8]) -> Option<u16> {
+    let section = payload.get(1 + usize::from(*payload.first()?)..)?;
+    let section_len =
+        usize::from(u16::from_be_bytes([*section.get(1)?, *section.get(2)?]) & 0x0FFF);
+    // Program loop between the 8-byte header and the CRC.
+    let programs = section.get(8..(3 + section_len).checked_sub(4)?)?;
+    programs.chunks_exact(4).find_map(|p| {
+        let program_number = u16::from_be_bytes([p[0], p[1]]);
+        (program_number != 0).then(|| u16::from_be_bytes([p[2] & 0x1F, p[3]]))
+    })
+}
+
+/// Returns the reference PID of a PMT section and whether it is a video stream.
+fn parse_pmt(payload: &[u8]) -> Option<(u16, bool)> {
+    let section = payload.get(1 + usize::from(*payload.first()?)..)?;
+    let section_len =
+        usize::from(u16::from_be_bytes([*section.get(1)?, *section.get(2)?]) & 0x0FFF);
+    let end = (3 + section_len).checked_sub(4)?;
+    let info_len = usize::from(u16::from_be_bytes([*section.get(10)?, *section.get(11)?]) & 0x0FFF);
+    let mut pos = 12 + info_len;
+    let mut first = None;
+    while pos + 5 <= end {
+        let stream_type = *section.get(pos)?;
+        let pid = u16::from_be_bytes([*section.get(pos + 1)? & 0x1F, *section.get(pos + 2)?]);
+        let es_info_len = usize::from(
+            u16::from_be_bytes([*section.get(pos + 3)?, *section.get(pos + 4)?]) & 0x0FFF,
+        );
+        // MPEG-1/2, MPEG-4 part 2, H.264 and H.265 video.
+        if matches!(stream_type, 0x01 | 0x02 | 0x10 | 0x1B | 0x24) {
+            return Some((pid, true));
+        }
+        first.get_or_insert(pid);
+        pos += 5 + es_info_len;
+    }
+    first.map(|pid| (pid, false))
+}
+
+/// Decode timestamp (DTS, else PTS) of a PES packet header, in 90 kHz ticks.
+fn pes_timestamp(payload: &[u8]) -> Option<u64> {
+    if payload.get(..3)? != [0x00, 0x00, 0x01] {
+        return None;
+    }
+    let flags = *payload.get(7)?;
+    let field = match flags & 0xC0 {
+        0xC0 => payload.get(14..19)?,
+        0x80 => payload.get(9..14)?,
+        _ => return None,
+    };
+    Some(
+        (u64::from(field[0] & 0x0E) << 29)
+            | (u64::from(field[1]) << 22)
+            | (u64::from(field[2] & 0xFE) << 14)
+            | (u64::from(field[3]) << 7)
+            | (u64::from(field[4]) >> 1),
+    )
+}
+
+#[derive(Default)]
+struct TsUnit {
+    data: Vec<u8>,
+    /// First reference-PID timestamp in the unit, in 90 kHz ticks.
+    start: Option<u64>,
+    independent: bool,
+}
+
+impl TsUnit {
+    fn into_media_unit(self, end: u64) -> MediaUnit {
+        let start = self.start.unwrap_or(end);
+        MediaUnit {
+            data: self.data,
+            duration_us: ticks_to_us(end.saturating_sub(start), TS_CLOCK_HZ),
+            independent: self.independent,
+        }
+    }
+}
+
+/// Splits an MPEG-TS byte stream into units that start at a PAT.
+///
+/// A unit's duration is only known once the next unit's first timestamp arrives, so each unit
+/// is held back until then.
+#[derive(Default)]
+struct TsSplitter {
+    buf: BytesMut,
+    pmt_pid: Option<u16>,
+    reference_pid: Option<u16>,
+    has_video: bool,
+    current: TsUnit,
+    closed: Option<TsUnit>,
+    last_timestamp: Option<u64>,
+    last_delta: u64,
+}
+
+impl TsSplitter {
+    fn push(&mut self, data: &[u8], events: &mut Vec<UnitEvent>) -> Result<(), String> {
+        self.buf.extend_from_slice(data);
+        while self.buf.len() >= TS_PACKET_SIZE {
+            if self.buf[0] != SYNC_BYTE {
+                return Err("Lost MPEG-TS sync; input must be whole 188-byte packets".to_string());
+            }
+            let packet = self.buf.split_to(TS_PACKET_SIZE);
+            self.push_packet(&packet, events);
+        }
+        Ok(())
+    }
+
+    fn push_packet(&mut self, packet: &[u8], events: &mut Vec<UnitEvent>) {
+        let pid = u16::from_be_bytes([packet[1] & 0x1F, packet[2]]);
+        let unit_start = packet[1] & 0x40 != 0;
+        let has_adaptation = packet[3] & 0x20 != 0;
+        let payload = if packet[3] & 0x10 == 0 {
+            &[][..]
+        } else if has_adaptation {
+            packet.get(5 + usize::from(packet[4])..).unwrap_or_default()
+        } else {
+            &packet[4..]
+        };
+        let random_access = has_adaptation && packet[4] > 0 && packet[5] & 0x40 != 0;
+
+        if pid == PAT_PID && unit_start {
+            if let Some(pmt_pid) = parse_pat(payload) {
+                self.pmt_pid = Some(pmt_pid);
+            }
+            if self.current.start.is_some() {
+                self.closed = Some(std::mem::take(&mut self.current));
+            }
+        } else if Some(pid) == self.pmt_pid && unit_start && self.reference_pid.is_none() {
+            if let Some((reference_pid, is_video)) = parse_pmt(payload) {
+                self.reference_pid = Some(reference_pid);
+                self.has_video = is_video;
+            }
+        }
+        self.current.data.extend_from_slice(packet);
+
+        if Some(pid) != self.reference_pid || !unit_start {
+            return;
+        }
+        let Some(timestamp) = pes_timestamp(payload) else { return };
+        if let Some(last) = self.last_timestamp {
+            self.last_delta = timestamp.saturating_sub(last);
+        }
+        self.last_timestamp = Some(timestamp);
+        if self.current.start.is_none() {
+            self.current.start = Some(timestamp);
+            self.current.independent = !self.has_video || random_access;
+            if let Some(closed) = self.closed.take() {
+                events.push(UnitEvent::Unit(closed.into_media_unit(timestamp)));
+            }
+        }
+    }
+
+    fn finish(&mut self, events: &mut Vec<UnitEvent>) {
+        if !self.buf.is_empty() {
+            tracing::warn!("Dropping {} trailing bytes of a partial TS packet", self.buf.len());
+        }
+        let Some(last) = self.last_timestamp else { return };
+        let current = std::mem::take(&mut self.current);
+        let unit = match self.closed.take() {
+            // Trailing packets that never carried a timestamp of their own.
+            Some(mut closed) => {
+                closed.data.extend_from_slice(&current.data);
+                closed
+            },
+            None => current,
+        };
+        if unit.start.is_some() {
+            events.push(UnitEvent::Unit(unit.into_media_unit(last + self.last_delta)));
+        }
+    }
+}
+
+enum Splitter {
+    Detect,
+    Fmp4(Fmp4Splitter),
+    Ts(TsSplitter),
+}
+
+impl Splitter {
+    fn new(format: HlsInputFormat) -> Self {
+        match format {
+            HlsInputFormat::Auto => Self::Detect,
+            HlsInputFormat::Fmp4 => Self::Fmp4(Fmp4Splitter::default()),
+            HlsInputFormat::Ts => Self::Ts(TsSplitter::default()),
+        }
+    }
+
+    fn push(&mut self, data: &[u8], events: &mut Vec<UnitEvent>) -> Result<(), String> {
+        if matches!(self, Self::Detect) {
+            match data.first() {
+                None => return Ok(()),
+                Some(&SYNC_BYTE) => *self = Self::Ts(TsSplitter::default()),
+                Some(_) => *self = Self::Fmp4(Fmp4Splitter::default()),
+            }
+        }
+        match self {
+            Self::Detect => Ok(()),
+            Self::Fmp4(splitter) => splitter.push(data, events),
+            Self::Ts(splitter) => splitter.push(data, events),
+        }
+    }
+
+    fn finish(&mut self, events: &mut Vec<UnitEvent>) {
+        match self {
+            Self::Detect => {},
+            Self::Fmp4(splitter) => {
+                if !splitter.buf.is_empty() || !splitter.pending.is_empty() {
+                    tracing::warn!("fMP4 stream ended inside a fragment; dropping it");
+                }
+            },
+            Self::Ts(splitter) => splitter.finish(events),
+        }
+    }
+}
+
+// --- Segments and playlist ---
+
+struct Part {
+    name: String,
+    duration_us: u64,
+    independent: bool,
+}
+
+struct Segment {
+    sequence: u64,
+    duration_us: u64,
+    parts: Vec<Part>,
+}
+
+struct OpenPart {
+    data: Vec<u8>,
+    duration_us: u64,
+    independent: bool,
+}
+
+struct OpenSegment {
+    sequence: u64,
+    data: Vec<u8>,
+    duration_us: u64,
+    parts: Vec<Part>,
+    part: Option<OpenPart>,
+}
+
+/// Writes segments and keeps the media playlist up to date.
+struct HlsWriter {
+    directory: PathBuf,
+    playlist_name: String,
+    segment_prefix: String,
+    segment_target_us: u64,
+    part_target_us: Option<u64>,
+    window_size: usize,
+    delete_old_segments: bool,
+    /// Whether an init segment was written (fMP4 output).
+    has_init: bool,
+    /// Advertised `EXT-X-TARGETDURATION`; it may only grow when a segment runs long.
+    target_duration_secs: u64,
+    segments: VecDeque<Segment>,
+    open: Option<OpenSegment>,
+    next_sequence: u64,
+    warned_long_part: bool,
+}
+
+impl HlsWriter {
+    fn new(config: &HlsPackagerConfig) -> Self {
+        Self {
+            directory: PathBuf::from(&config.directory),
+            playlist_name: config.playlist_name.clone(),
+            segment_prefix: config.segment_prefix.clone(),
+            segment_target_us: config.segment_duration_ms * 1000,
+            part_target_us: config.part_duration_ms.map(|ms| ms * 1000),
+            window_size: config.window_size,
+            delete_old_segments: config.delete_old_segments,
+            has_init: false,
+            target_duration_secs: config.segment_duration_ms.div_ceil(1000),
+            segments: VecDeque::new(),
+            open: None,
+            next_sequence: 0,
+            warned_long_part: false,
+        }
+    }
+
+    const fn extension(&self) -> &'static str {
+        if self.has_init {
+            "m4s"
+        } else {
+            "ts"
+        }
+    }
+
+    fn segment_name(&self, sequence: u64) -> String {
+        format!("{}{sequence:05}.{}", self.segment_prefix, self.extension())
+    }
+
+    fn part_name(&self, sequence: u64, part: usize) -> String {
+        format!("{}{sequence:05}.{part}.{}", self.segment_prefix, self.extension())
+    }
+
+    async fn handle(&mut self, events: &mut Vec<UnitEvent>) -> io::Result<()> {
+        for event in events.drain(..) {
+            match event {
+                UnitEvent::Init(data) => {
+                    tokio::fs::write(self.directory.join(INIT_SEGMENT_NAME), data).await?;
+                    self.has_init = true;
+                },
+                UnitEvent::Unit(unit) => self.push_unit(unit).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn push_unit(&mut self, unit: MediaUnit) -> io::Result<()> {
+        let mut changed = false;
+        if let Some(open) = &self.open {
+            if unit.independent && open.duration_us + unit.duration_us > self.segment_target_us {
+                self.close_segment().await?;
+                changed = true;
+            }
+        }
+        if let (Some(part_target), Some(part)) =
+            (self.part_target_us, self.open.as_ref().and_then(|open| open.part.as_ref()))
+        {
+            if part.duration_us + unit.duration_us > part_target {
+                self.close_part().await?;
+                changed = true;
+            }
+        }
+
+        let open = self.open.get_or_insert_with(|| {
+            let sequence = self.next_sequence;
+            self.next_sequence += 1;
+            OpenSegment {
+                sequence,
+                data: Vec::new(),
+                duration_us: 0,
+                parts: Vec::new(),
+                part: None,
+            }
+        });
+        if self.part_target_us.is_some() {
+            let part = open.part.get_or_insert_with(|| OpenPart {
+                data: Vec::new(),
+                duration_us: 0,
+                independent: unit.independent,
+            });
+            part.data.extend_from_slice(&unit.data);
+            part.duration_us += unit.duration_us;
+        }
+        open.data.extend_from_slice(&unit.data);
+        open.duration_us += unit.duration_us;
+
+        if changed {
+            self.write_playlist(false).await?;
+        }
+        Ok(())
+    }
+
+    async fn close_part(&mut self) -> io::Result<()> {
+        let Some((sequence, index, part)) = self
+            .open
+            .as_mut()
+            .and_then(|open| Some((open.sequence, open.parts.len(), open.part.take()?)))
+        else {
+            return Ok(());
+        };
+        let name = self.part_name(sequence, index);
+        tokio::fs::write(self.directory.join(&name), &part.data).await?;
+        if self.part_target_us.is_some_and(|target| part.duration_us > target)
+            && !self.warned_long_part
+        {
+            tracing::warn!(
+                "HLS partial segment {name} lasts {} s, longer than the part target; use shorter \
+                 input fragments",
+                seconds(part.duration_us)
+            );
+            self.warned_long_part = true;
+        }
+        if let Some(open) = self.open.as_mut() {
+            open.parts.push(Part {
+                name,
+                duration_us: part.duration_us,
+                independent: part.independent,
+            });
+        }
+        Ok(())
+    }
+
+    async fn close_segment(&mut self) -> io::Result<()> {
+        self.close_part().await?;
+        let Some(open) = self.open.take() else { return Ok(()) };
+        tokio::fs::write(self.directory.join(self.segment_name(open.sequence)), &open.data).await?;
+
+        let rounded_secs = (open.duration_us + 500_000) / 1_000_000;
+        if rounded_secs > self.target_duration_secs {
+            tracing::warn!(
+                "HLS segment {} lasts {} s, longer than the target duration; raising \
+                 EXT-X-TARGETDURATION to {rounded_secs} (check the input key frame interval)",
+                open.sequence,
+                seconds(open.duration_us)
+            );
+            self.target_duration_secs = rounded_secs;
+        }
+        self.segments.push_back(Segment {
+            sequence: open.sequence,
+            duration_us: open.duration_us,
+            parts: open.parts,
+        });
+
+        // Players may still fetch segments listed in a playlist they loaded earlier, so deleted
+        // segments stay on disk for one more window after leaving the playlist.
+        if self.window_size == 0 {
+            return Ok(());
+        }
+        let keep = if self.delete_old_segments { self.window_size * 2 } else { self.window_size };
+        while self.segments.len() > keep {
+            let Some(segment) = self.segments.pop_front() else { break };
+            if self.delete_old_segments {
+                self.remove_file(&self.segment_name(segment.sequence)).await;
+                for part in &segment.parts {
+                    self.remove_file(&part.name).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn remove_file(&self, name: &str) {
+        if let Err(e) = tokio::fs::remove_file(self.directory.join(name)).await {
+            if e.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("Failed to delete old HLS file {name}: {e}");
+            }
+        }
+    }
+
+    fn render_playlist(&self, ended: bool) -> String {
+        let listed = if self.window_size == 0 {
+            0
+        } else {
+            self.segments.len().saturating_sub(self.window_size)
+        };
+        let listed: Vec<&Segment> = self.segments.iter().skip(listed).collect();
+
+        let version = if self.part_target_us.is_some() {
+            9
+        } else if self.has_init {
+            7
+        } else {
+            3
+        };
+        let mut lines = vec![
+            "#EXTM3U".to_string(),
+            format!("#EXT-X-VERSION:{version}"),
+            format!("#EXT-X-TARGETDURATION:{}", self.target_duration_secs),
+        ];
+        if let Some(part_target) = self.part_target_us {
+            lines
+                .push(format!("#EXT-X-SERVER-CONTROL:PART-HOLD-BACK={}", seconds(part_target * 3)));
+            lines.push(format!("#EXT-X-PART-INF:PART-TARGET={}", seconds(part_target)));
+        }
+        let first_sequence = listed
+            .first()
+            .map(|s| s.sequence)
+            .or_else(|| self.open.as_ref().map(|open| open.sequence))
+            .unwrap_or(self.next_sequence);
+        lines.push(format!("#EXT-X-MEDIA-SEQUENCE:{first_sequence}"));
+        if self.window_size == 0 {
+            lines.push("#EXT-X-PLAYLIST-TYPE:EVENT".to_string());
+        }
+        if self.has_init {
+            lines.push(format!("#EXT-X-MAP:URI=\"{INIT_SEGMENT_NAME}\""));
+        }
+
+        // Parts are only listed near the live edge.
+        let part_horizon_us = self.target_duration_secs * 1_000_000 * PART_LISTING_TARGET_DURATIONS;
+        let mut from_edge_us = self.open.as_ref().map_or(0, |open| open.duration_us)
+            + listed.iter().map(|s| s.duration_us).sum::<u64>();
+        for segment in listed {
+            if self.part_target_us.is_some() && from_edge_us <= part_horizon_us {
+                lines.extend(segment.parts.iter().map(part_line));
+            }
+            from_edge_us -= segment.duration_us;
+            lines.push(format!("#EXTINF:{},", seconds(segment.duration_us)));
+            lines.push(self.segment_name(segment.sequence));
+        }
+
+        if ended {
+            lines.push("#EXT-X-ENDLIST".to_string());
+        } else if self.part_target_us.is_some() {
+            if let Some(open) = &self.open {
+                lines.extend(open.parts.iter().map(part_line));
+            }
+            let (sequence, next_part) = self
+                .open
+                .as_ref()
+                .map_or((self.next_sequence, 0), |open| (open.sequence, open.parts.len()));
+            lines.push(format!(
+                "#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"{}\"",
+                self.part_name(sequence, next_part)
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    async fn write_playlist(&self, ended: bool) -> io::Result<()> {
+        let path = self.directory.join(&self.playlist_name);
+        let tmp = self.directory.join(format!(".{}.tmp", self.playlist_name));
+        tokio::fs::write(&tmp, self.render_playlist(ended)).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+
+    async fn finish(&mut self) -> io::Result<()> {
+        self.close_segment().await?;
+        self.write_playlist(true).await
+    }
+}
+
+fn part_line(part: &Part) -> String {
+    format!(
+        "#EXT-X-PART:DURATION={},URI=\"{}\"{}",
+        seconds(part.duration_us),
+        part.name,
+        if part.independent { ",INDEPENDENT=YES" } else { "" }
+    )
+}
+
+// --- Node ---
+
+/// Writes an fMP4 or MPEG-TS stream as a rolling HLS playlist and segments.
+pub struct HlsPackagerNode {
+    config: HlsPackagerConfig,
+}
+
+impl HlsPackagerNode {
+    /// Validates the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error for an empty directory, file names that are not a single
+    /// path component, or out-of-range durations.
+    pub fn new(config: HlsPackagerConfig) -> Result<Self, StreamKitError> {
+        if config.directory.is_empty() {
+            return Err(StreamKitError::Configuration("directory must not be empty".to_string()));
+        }
+        validate_file_name("playlist_name", &config.playlist_name)?;
+        validate_file_name("segment_prefix", &config.segment_prefix)?;
+        if config.segment_duration_ms == 0 {
+            return Err(StreamKitError::Configuration(
+                "segment_duration_ms must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(part_ms) = config.part_duration_ms {
+            if part_ms == 0 || part_ms >= config.segment_duration_ms {
+                return Err(StreamKitError::Configuration(
+                    "part_duration_ms must be greater than 0 and shorter than segment_duration_ms"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(Self { config })
+    }
+
+    async fn package(
+        &self,
+        context: &NodeContext,
+        input_rx: &mut mpsc::Receiver<Packet>,
+        stats_tracker: &mut NodeStatsTracker,
+    ) -> Result<u64, String> {
+        let mut splitter = Splitter::new(self.config.format);
+        let mut writer = HlsWriter::new(&self.config);
+        let mut events = Vec::new();
+
+        while let Some(packet) = context.recv_with_cancellation(input_rx).await {
+            let Packet::Binary { data, .. } = packet else {
+                stats_tracker.discarded();
+                continue;
+            };
+            stats_tracker.received();
+            splitter.push(&data, &mut events).map_err(|e| format!("HLS packaging error: {e}"))?;
+            writer.handle(&mut events).await.map_err(|e| format!("HLS write error: {e}"))?;
+            stats_tracker.sent();
+            stats_tracker.maybe_send();
+        }
+
+        splitter.finish(&mut events);
+        writer.handle(&mut events).await.map_err(|e| format!("HLS write error: {e}"))?;
+        writer.finish().await.map_err(|e| format!("HLS write error: {e}"))?;
+        Ok(writer.next_sequence)
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for HlsPackagerNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Binary],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        if let Err(e) = tokio::fs::create_dir_all(&self.config.directory).await {
+            let err_msg =
+                format!("Failed to create HLS directory '{}': {e}", self.config.directory);
+            state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+            return Err(StreamKitError::Runtime(err_msg));
+        }
+        tracing::info!(
+            "HlsPackagerNode writing {} to {} ({} ms segments, window {})",
+            self.config.playlist_name,
+            self.config.directory,
+            self.config.segment_duration_ms,
+            self.config.window_size
+        );
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        match self.package(&context, &mut input_rx, &mut stats_tracker).await {
+            Ok(segments) => {
+                stats_tracker.force_send();
+                tracing::info!("HlsPackagerNode finished after {} segments", segments);
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+                Ok(())
+            },
+            Err(err_msg) => {
+                stats_tracker.errored();
+                stats_tracker.force_send();
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                Err(StreamKitError::Runtime(err_msg))
+            },
+        }
+    }
+}
+
+use schemars::schema_for;
+use streamkit_core::{config_helpers, registry::StaticPins};
+
+/// Registers the HLS transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_hls_nodes(registry: &mut NodeRegistry) {
+    registry.register_static_with_description(
+        "transport::hls::packager",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(HlsPackagerNode::new(config)?))
+        },
+        serde_json::to_value(schema_for!(HlsPackagerConfig))
+            .expect("HlsPackagerConfig schema should serialize to JSON"),
+        StaticPins {
+            inputs: vec![InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::Binary],
+                cardinality: PinCardinality::One,
+            }],
+            outputs: vec![],
+        },
+        vec!["transport".to_string(), "hls".to_string()],
+        false,
+        "Packages fragmented MP4 or MPEG-TS into rolling HLS segments and an m3u8 playlist in a \
+         directory, with optional LL-HLS partial segments. \
+         Security: the server validates the directory against `security.allowed_write_paths`.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn config(directory: &Path) -> HlsPackagerConfig {
+        serde_json::from_value(serde_json::json!({ "directory": directory })).unwrap()
+    }
+
+    /// Runs the packager over `chunks` and returns once it has stopped.
+    async fn package(config: HlsPackagerConfig, chunks: Vec<Vec<u8>>) {
+        let node = HlsPackagerNode::new(config).unwrap();
+        let (tx, rx) = mpsc::channel(100);
+        let (context, _mock_sender, mut state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 10);
+        let node_handle = tokio::spawn(async move { Box::new(node).run(context).await });
+
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+        for chunk in chunks {
+            let packet =
+                Packet::Binary { data: Bytes::from(chunk), content_type: None, metadata: None };
+            tx.send(packet).await.unwrap();
+        }
+        drop(tx);
+        assert_state_stopped(&mut state_rx).await;
+        node_handle.await.unwrap().unwrap();
+    }
+
+    fn mp4_box(fourcc: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut out = u32::try_from(payload.len() + 8).unwrap().to_be_bytes().to_vec();
+        out.extend_from_slice(fourcc);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn full_box(fourcc: &[u8], version: u8, flags: u32, payload: &[u8]) -> Vec<u8> {
+        let mut body = ((u32::from(version) << 24) | flags).to_be_bytes().to_vec();
+        body.extend_from_slice(payload);
+        mp4_box(fourcc, &body)
+    }
+
+    /// `ftyp` + `moov` with an audio track (id 1) and a 90 kHz video track (id 2).
+    fn fmp4_init() -> Vec<u8> {
+        let trak = |id: u32, timescale: u32, handler: &[u8]| {
+            let tkhd = full_box(b"tkhd", 0, 3, &[&[0u8; 8][..], &id.to_be_bytes()].concat());
+            let mdhd = full_box(b"mdhd", 0, 0, &[&[0u8; 8][..], &timescale.to_be_bytes()].concat());
+            let hdlr = full_box(b"hdlr", 0, 0, &[&[0u8; 4][..], handler, &[0u8; 12]].concat());
+            mp4_box(b"trak", &[tkhd, mp4_box(b"mdia", &[mdhd, hdlr].concat())].concat())
+        };
+        let moov = [trak(1, 48_000, b"soun"), trak(2, 90_000, b"vide")].concat();
+        [mp4_box(b"ftyp", b"iso6\0\0\0\0iso6"), mp4_box(b"moov", &moov)].concat()
+    }
+
+    /// A one-second video fragment, optionally starting with a sync sample.
+    fn fmp4_fragment(index: u64, keyframe: bool) -> Vec<u8> {
+        let tfhd = full_box(b"tfhd", 0, 0x02_0000, &2u32.to_be_bytes());
+        let tfdt = full_box(b"tfdt", 1, 0, &(index * 90_000).to_be_bytes());
+        let sample_flags: u32 = if keyframe { 0x0200_0000 } else { 0x0101_0000 };
+        let trun_fields = [1u32, 0, 90_000, 4, sample_flags];
+        let trun_payload: Vec<u8> = trun_fields.iter().flat_map(|v| v.to_be_bytes()).collect();
+        let trun = full_box(b"trun", 0, 0x0001 | 0x0100 | 0x0200 | 0x0400, &trun_payload);
+        let moof = mp4_box(b"moof", &mp4_box(b"traf", &[tfhd, tfdt, trun].concat()));
+        [moof, mp4_box(b"mdat", &[1, 2, 3, 4])].concat()
+    }
+
+    #[tokio::test]
+    async fn test_hls_fmp4_segments_on_key_frames_and_rolls_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config(dir.path());
+        config.segment_duration_ms = 2000;
+        config.window_size = 2;
+
+        // Key frames every two seconds; split the stream at arbitrary byte boundaries.
+        let mut stream = fmp4_init();
+        for index in 0..10 {
+            stream.extend(fmp4_fragment(index, index % 2 == 0));
+        }
+        package(config, stream.chunks(100).map(<[u8]>::to_vec).collect()).await;
+
+        let playlist = std::fs::read_to_string(dir.path().join("index.m3u8")).unwrap();
+        assert_eq!(
+            playlist,
+            "#EXTM3U\n#EXT-X-VERSION:7\n#EXT-X-TARGETDURATION:2\n#EXT-X-MEDIA-SEQUENCE:3\n\
+             #EXT-X-MAP:URI=\"init.mp4\"\n#EXTINF:2.000,\nsegment00003.m4s\n\
+             #EXTINF:2.000,\nsegment00004.m4s\n#EXT-X-ENDLIST\n"
+        );
+        assert_eq!(std::fs::read(dir.path().join("init.mp4")).unwrap(), fmp4_init());
+        // Segment 0 left the window two segments ago; 1 and 2 are kept for late readers.
+        assert!(!dir.path().join("segment00000.m4s").exists());
+        assert!(dir.path().join("segment00001.m4s").exists());
+        let segment = std::fs::read(dir.path().join("segment00004.m4s")).unwrap();
+        assert_eq!(segment, [fmp4_fragment(8, true), fmp4_fragment(9, false)].concat());
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // Test values fit the TS header fields
+    fn ts_packet(pid: u16, random_access: bool, payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![SYNC_BYTE, 0x40 | (pid >> 8) as u8, pid as u8];
+        if random_access {
+            packet.extend_from_slice(&[0x30, 1, 0x40]);
+        } else {
+            packet.push(0x10);
+        }
+        packet.extend_from_slice(payload);
+        packet.resize(TS_PACKET_SIZE, 0xFF);
+        packet
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // Each byte takes masked bits of the PTS
+    fn pes_header(pts: u64) -> Vec<u8> {
+        vec![
+            0x00,
+            0x00,
+            0x01,
+            0xE0,
+            0x00,
+            0x00,
+            0x80,
+            0x80,
+            5,
+            0x21 | ((pts >> 29) as u8 & 0x0E),
+            (pts >> 22) as u8,
+            ((pts >> 14) as u8 & 0xFE) | 1,
+            (pts >> 7) as u8,
+            ((pts << 1) as u8 & 0xFE) | 1,
+        ]
+    }
+
+    /// H.264 transport stream with a PAT/PMT before every 100 ms access unit and a key frame
+    /// every second. CRCs are not checked by the packager and left zero.
+    fn ts_stream(units: u64) -> Vec<u8> {
+        let pat = [0u8, 0x00, 0xB0, 13, 0, 1, 0xC1, 0, 0, 0, 1, 0xF0, 0x00, 0, 0, 0, 0];
+        let pmt = [
+            0u8, 0x02, 0xB0, 18, 0, 1, 0xC1, 0, 0, 0xE1, 0x00, 0xF0, 0x00, 0x1B, 0xE1, 0x00, 0xF0,
+            0x00, 0, 0, 0, 0,
+        ];
+        let mut stream = Vec::new();
+        for index in 0..units {
+            stream.extend(ts_packet(0x0000, false, &pat));
+            stream.extend(ts_packet(0x1000, false, &pmt));
+            stream.extend(ts_packet(0x0100, index % 10 == 0, &pes_header(63_000 + index * 9000)));
+        }
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_hls_ts_low_latency_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config(dir.path());
+        config.segment_duration_ms = 2000;
+        config.part_duration_ms = Some(500);
+        config.window_size = 0;
+
+        package(config, ts_stream(50).chunks(1316).map(<[u8]>::to_vec).collect()).await;
+
+        let playlist = std::fs::read_to_string(dir.path().join("index.m3u8")).unwrap();
+        for expected in [
+            "#EXT-X-VERSION:9\n",
+            "#EXT-X-SERVER-CONTROL:PART-HOLD-BACK=1.500\n#EXT-X-PART-INF:PART-TARGET=0.500\n",
+            "#EXT-X-MEDIA-SEQUENCE:0\n#EXT-X-PLAYLIST-TYPE:EVENT\n",
+            "#EXT-X-PART:DURATION=0.500,URI=\"segment00002.0.ts\",INDEPENDENT=YES\n\
+             #EXT-X-PART:DURATION=0.500,URI=\"segment00002.1.ts\"\n",
+            "#EXTINF:2.000,\nsegment00001.ts\n#EXT-X-PART",
+            "#EXTINF:1.000,\nsegment00002.ts\n#EXT-X-ENDLIST\n",
+        ] {
+            assert!(playlist.contains(expected), "missing {expected:?} in\n{playlist}");
+        }
+        assert!(!playlist.contains("#EXT-X-MAP"));
+        assert!(!playlist.contains("PRELOAD-HINT"));
+        let part = std::fs::read(dir.path().join("segment00001.3.ts")).unwrap();
+        assert_eq!(part.len(), 5 * 3 * TS_PACKET_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_hls_live_playlist_hints_next_part() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config(dir.path());
+        config.segment_duration_ms = 1000;
+        config.part_duration_ms = Some(400);
+        let mut writer = HlsWriter::new(&config);
+
+        for _ in 0..13 {
+            let unit = MediaUnit { data: vec![0x47; 188], duration_us: 200_000, independent: true };
+            writer.push_unit(unit).await.unwrap();
+        }
+
+        let playlist = writer.render_playlist(false);
+        assert!(playlist.contains(
+            "#EXTINF:1.000,\nsegment00001.ts\n\
+             #EXT-X-PART:DURATION=0.400,URI=\"segment00002.0.ts\",INDEPENDENT=YES\n\
+             #EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"segment00002.1.ts\"\n"
+        ));
+        assert!(!playlist.contains("#EXT-X-ENDLIST"));
+    }
+
+    #[test]
+    fn test_hls_rejects_invalid_config() {
+        let dir = Path::new("/tmp/hls");
+        let mut escaping = config(dir);
+        escaping.segment_prefix = "../segment".to_string();
+        assert!(HlsPackagerNode::new(escaping).is_err());
+
+        let mut long_parts = config(dir);
+        long_parts.part_duration_ms = Some(long_parts.segment_duration_ms);
+        assert!(HlsPackagerNode::new(long_parts).is_err());
+
+        assert!(HlsPackagerNode::new(config(dir)).is_ok());
+    }
+}
@@ -11,6 +11,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 use std::time::Duration;
+use streamkit_core::redaction::redact_url;
 use streamkit_core::types::{Packet, PacketType};
 use streamkit_core::{
     config_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
@@ -21,6 +22,7 @@ use streamkit_core::{
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HttpPullConfig {
     /// URL to fetch (HTTP or HTTPS)
+    #[schemars(extend("sensitive" = true))]
     pub url: String,
     /// Size of chunks to read (default: 8192 bytes)
     #[serde(default = "default_chunk_size")]
@@ -90,13 +92,17 @@ impl HttpPullNode {
     ) -> Result<(), StreamKitError> {
         let client = Self::shared_http_client()?;
 
-        tracing::info!("Starting streaming GET request to {}", url);
+        tracing::info!("Starting streaming GET request to {}", redact_url(url));
 
         let response = match client.get(url).send().await {
             Ok(resp) => resp,
             Err(e) => {
                 stats_tracker.errored();
-                return Err(StreamKitError::Runtime(format!("HTTP request failed: {e}")));
+                // reqwest errors embed the full URL, which may carry credentials
+                return Err(StreamKitError::Runtime(format!(
+                    "HTTP request failed: {}",
+                    e.without_url()
+                )));
             },
         };
 
@@ -226,6 +232,11 @@ impl ProcessorNode for HttpPullNode {
                     tracing::info!("HttpPullNode received shutdown before start");
                     return Ok(());
                 },
+                Some(streamkit_core::control::NodeControlMessage::Query {
+                    kind, reply, ..
+                }) => {
+                    streamkit_core::control::reject_query(&kind, reply);
+                },
                 None => {
                     tracing::warn!("Control channel closed before start signal received");
                     return Ok(());
@@ -388,6 +399,10 @@ mod tests {
             cancellation_token: None,
             pin_management_rx: None, // Test contexts don't support dynamic pins
             audio_pool: None,
+            media_clock: None,
+            cost_meter: None,
+            telemetry_limits: None,
+            lookahead: None,
         };
 
         // Create and run node with small chunk size for testing
@@ -8,14 +8,60 @@ use streamkit_core::NodeRegistry;
 
 pub mod moq;
 
+#[cfg(feature = "hls")]
+pub mod hls;
+
 #[cfg(feature = "http")]
 pub mod http;
 
+#[cfg(feature = "rtp")]
+pub mod rtp;
+
+#[cfg(feature = "socket")]
+pub mod socket;
+
+#[cfg(feature = "srt")]
+pub mod srt;
+
+#[cfg(feature = "webrtc")]
+pub mod webrtc;
+
+#[cfg(feature = "ws")]
+pub mod ws;
+
 /// Registers all available transport nodes with the engine's registry.
-pub fn register_transport_nodes(registry: &mut NodeRegistry) {
+///
+/// `secrets` maps server secret names to their values (see `[script.secrets]`).
+#[allow(clippy::implicit_hasher)] // Secrets are always built with the default hasher
+pub fn register_transport_nodes(
+    registry: &mut NodeRegistry,
+    secrets: &std::collections::HashMap<String, String>,
+) {
     // Call the registration function from each submodule.
-    moq::register_moq_nodes(registry);
+    #[cfg(feature = "moq")]
+    moq::register_moq_nodes(registry, secrets);
+    // Only the MoQ nodes consume secrets so far
+    #[cfg(not(feature = "moq"))]
+    let _ = secrets;
+
+    #[cfg(feature = "hls")]
+    hls::register_hls_nodes(registry);
 
     #[cfg(feature = "http")]
     http::register_http_nodes(registry);
+
+    #[cfg(feature = "rtp")]
+    rtp::register_rtp_nodes(registry);
+
+    #[cfg(feature = "socket")]
+    socket::register_socket_nodes(registry);
+
+    #[cfg(feature = "srt")]
+    srt::register_srt_nodes(registry);
+
+    #[cfg(feature = "webrtc")]
+    webrtc::register_webrtc_nodes(registry);
+
+    #[cfg(feature = "ws")]
+    ws::register_ws_nodes(registry);
 }
@@ -0,0 +1,382 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! End-to-end payload encryption for MoQ broadcasts.
+//!
+//! Relays forward frames without being able to read them:
+//! - A key-encryption key (KEK) is derived per broadcast with HKDF-SHA256 from a server secret
+//!   (`[script.secrets]`) and the broadcast name.
+//! - The publisher encrypts each Opus frame with a random AES-256-GCM content key, prefixed by
+//!   `[key_id][nonce]`.
+//! - Content keys are wrapped with the KEK and published on the [`KEYS_TRACK`] control track.
+//!   Every key message lists all keys a subscriber currently needs, so late joiners only need
+//!   the latest one.
+//! - On rotation the next key is announced first and used only after [`ROTATION_GRACE`], giving
+//!   subscribers time to receive it.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::Duration;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf;
+use ring::rand::{SecureRandom, SystemRandom};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use streamkit_core::StreamKitError;
+
+/// Name of the control track carrying wrapped content keys.
+pub const KEYS_TRACK: &str = "e2ee/keys";
+
+/// How long a newly announced key is published before frames switch to it.
+pub const ROTATION_GRACE: Duration = Duration::from_secs(2);
+
+const KEY_MESSAGE_VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+const WRAPPED_KEY_LEN: usize = KEY_LEN + TAG_LEN;
+const KDF_SALT: &[u8] = b"streamkit-moq-e2ee-v1";
+/// Keys a subscriber keeps around for frames still in flight after a rotation.
+const MAX_SUBSCRIBER_KEYS: usize = 4;
+
+/// Payload encryption settings shared by the MoQ publisher and subscriber.
+#[derive(Deserialize, Debug, JsonSchema, Clone)]
+pub struct MoqEncryptionConfig {
+    /// Name of a server secret (`[script.secrets]`) used to derive the broadcast's
+    /// key-encryption key. Publisher and subscriber must use the same secret value.
+    pub secret: String,
+    /// Seconds between content key rotations (publisher only). Default: 300. 0 disables rotation.
+    #[serde(default = "default_rotation_secs")]
+    pub rotation_secs: u64,
+}
+
+const fn default_rotation_secs() -> u64 {
+    300
+}
+
+/// Resolves the secret named by `config` into key material.
+///
+/// # Errors
+///
+/// Returns a configuration error if the secret isn't defined on the server.
+#[allow(clippy::implicit_hasher)] // Secrets are always built with the default hasher
+pub fn resolve_secret(
+    config: Option<&MoqEncryptionConfig>,
+    secrets: &HashMap<String, String>,
+) -> Result<Option<Vec<u8>>, StreamKitError> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    secrets.get(&config.secret).map(|value| Some(value.as_bytes().to_vec())).ok_or_else(|| {
+        let mut available: Vec<&String> = secrets.keys().collect();
+        available.sort();
+        StreamKitError::Configuration(format!(
+            "MoQ encryption references unknown secret '{}'. Available secrets: {available:?}",
+            config.secret
+        ))
+    })
+}
+
+/// Why a frame or key message couldn't be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum E2eeError {
+    Malformed,
+    /// The frame uses a key that hasn't been received (yet)
+    UnknownKey(u8),
+    /// Authentication failed: wrong secret or tampered data
+    Decrypt,
+    Random,
+}
+
+impl fmt::Display for E2eeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("malformed encrypted payload"),
+            Self::UnknownKey(id) => write!(f, "no content key with id {id}"),
+            Self::Decrypt => f.write_str("decryption failed"),
+            Self::Random => f.write_str("random number generator failed"),
+        }
+    }
+}
+
+impl std::error::Error for E2eeError {}
+
+impl From<E2eeError> for StreamKitError {
+    fn from(e: E2eeError) -> Self {
+        Self::Runtime(format!("MoQ encryption: {e}"))
+    }
+}
+
+fn derive_kek(secret: &[u8], broadcast: &str) -> LessSafeKey {
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, KDF_SALT).extract(secret);
+    let info = [broadcast.as_bytes()];
+    // AES_256_GCM's key length is well within HKDF-SHA256's output limit.
+    #[allow(clippy::expect_used)]
+    let okm = prk.expand(&info, &AES_256_GCM).expect("HKDF output length is valid");
+    LessSafeKey::new(UnboundKey::from(okm))
+}
+
+fn aead_key(bytes: &[u8]) -> Result<LessSafeKey, E2eeError> {
+    UnboundKey::new(&AES_256_GCM, bytes).map(LessSafeKey::new).map_err(|_| E2eeError::Malformed)
+}
+
+fn random_nonce(rng: &SystemRandom) -> Result<[u8; NONCE_LEN], E2eeError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce).map_err(|_| E2eeError::Random)?;
+    Ok(nonce)
+}
+
+struct ContentKey {
+    id: u8,
+    raw: [u8; KEY_LEN],
+    key: LessSafeKey,
+}
+
+/// Publisher side: encrypts frames and produces key messages.
+pub struct FrameEncryptor {
+    rng: SystemRandom,
+    kek: LessSafeKey,
+    current: ContentKey,
+    pending: Option<ContentKey>,
+}
+
+impl FrameEncryptor {
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn new(secret: &[u8], broadcast: &str) -> Result<Self, E2eeError> {
+        let rng = SystemRandom::new();
+        let current = Self::generate(&rng, 0)?;
+        Ok(Self { rng, kek: derive_kek(secret, broadcast), current, pending: None })
+    }
+
+    fn generate(rng: &SystemRandom, id: u8) -> Result<ContentKey, E2eeError> {
+        let mut raw = [0u8; KEY_LEN];
+        rng.fill(&mut raw).map_err(|_| E2eeError::Random)?;
+        Ok(ContentKey { id, raw, key: aead_key(&raw)? })
+    }
+
+    /// Generates the next content key. Frames keep using the current key until
+    /// [`Self::activate_pending`]; publish [`Self::key_message`] in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn prepare_rotation(&mut self) -> Result<(), E2eeError> {
+        let id = self.current.id.wrapping_add(1);
+        self.pending = Some(Self::generate(&self.rng, id)?);
+        Ok(())
+    }
+
+    /// Switches frames to the pending key. Returns false if no rotation was prepared.
+    pub const fn activate_pending(&mut self) -> bool {
+        match self.pending.take() {
+            Some(next) => {
+                self.current = next;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Wraps the current key (and the pending one, if any) with the KEK.
+    ///
+    /// Layout: `version, count, count × (key_id, nonce, wrapped key + tag)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn key_message(&self) -> Result<Vec<u8>, E2eeError> {
+        let keys: Vec<&ContentKey> = std::iter::once(&self.current).chain(&self.pending).collect();
+        let mut message = Vec::with_capacity(2 + keys.len() * (1 + NONCE_LEN + WRAPPED_KEY_LEN));
+        message.push(KEY_MESSAGE_VERSION);
+        // At most two keys are listed.
+        #[allow(clippy::cast_possible_truncation)]
+        message.push(keys.len() as u8);
+        for key in keys {
+            let nonce = random_nonce(&self.rng)?;
+            let mut wrapped = key.raw.to_vec();
+            self.kek
+                .seal_in_place_append_tag(
+                    Nonce::assume_unique_for_key(nonce),
+                    Aad::from([key.id]),
+                    &mut wrapped,
+                )
+                .map_err(|_| E2eeError::Decrypt)?;
+            message.push(key.id);
+            message.extend_from_slice(&nonce);
+            message.extend_from_slice(&wrapped);
+        }
+        Ok(message)
+    }
+
+    /// Encrypts one frame as `key_id, nonce, ciphertext + tag`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, E2eeError> {
+        let nonce = random_nonce(&self.rng)?;
+        let mut body = plaintext.to_vec();
+        self.current
+            .key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from([self.current.id]),
+                &mut body,
+            )
+            .map_err(|_| E2eeError::Decrypt)?;
+
+        let mut frame = Vec::with_capacity(1 + NONCE_LEN + body.len());
+        frame.push(self.current.id);
+        frame.extend_from_slice(&nonce);
+        frame.extend_from_slice(&body);
+        Ok(frame)
+    }
+}
+
+/// Subscriber side: learns keys from key messages and decrypts frames.
+pub struct FrameDecryptor {
+    kek: LessSafeKey,
+    /// Most recently announced last
+    keys: VecDeque<(u8, LessSafeKey)>,
+}
+
+impl FrameDecryptor {
+    pub fn new(secret: &[u8], broadcast: &str) -> Self {
+        Self { kek: derive_kek(secret, broadcast), keys: VecDeque::new() }
+    }
+
+    /// Unwraps the keys in a key message. Returns how many were accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is malformed or wasn't wrapped with this broadcast's KEK.
+    pub fn apply_key_message(&mut self, message: &[u8]) -> Result<usize, E2eeError> {
+        let (&version, rest) = message.split_first().ok_or(E2eeError::Malformed)?;
+        let (&count, mut rest) = rest.split_first().ok_or(E2eeError::Malformed)?;
+        if version != KEY_MESSAGE_VERSION {
+            return Err(E2eeError::Malformed);
+        }
+
+        let entry_len = 1 + NONCE_LEN + WRAPPED_KEY_LEN;
+        let mut unwrapped = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            if rest.len() < entry_len {
+                return Err(E2eeError::Malformed);
+            }
+            let (entry, tail) = rest.split_at(entry_len);
+            rest = tail;
+
+            let id = entry[0];
+            let nonce = Nonce::try_assume_unique_for_key(&entry[1..=NONCE_LEN])
+                .map_err(|_| E2eeError::Malformed)?;
+            let mut wrapped = entry[1 + NONCE_LEN..].to_vec();
+            let raw = self
+                .kek
+                .open_in_place(nonce, Aad::from([id]), &mut wrapped)
+                .map_err(|_| E2eeError::Decrypt)?;
+            unwrapped.push((id, aead_key(raw)?));
+        }
+
+        let accepted = unwrapped.len();
+        for (id, key) in unwrapped {
+            self.keys.retain(|(existing, _)| *existing != id);
+            self.keys.push_back((id, key));
+        }
+        while self.keys.len() > MAX_SUBSCRIBER_KEYS {
+            self.keys.pop_front();
+        }
+        Ok(accepted)
+    }
+
+    /// Decrypts a frame produced by [`FrameEncryptor::encrypt`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame is malformed, uses an unknown key or fails authentication.
+    pub fn decrypt(&self, frame: &[u8]) -> Result<Vec<u8>, E2eeError> {
+        if frame.len() < 1 + NONCE_LEN + TAG_LEN {
+            return Err(E2eeError::Malformed);
+        }
+        let id = frame[0];
+        let (_, key) =
+            self.keys.iter().find(|(key_id, _)| *key_id == id).ok_or(E2eeError::UnknownKey(id))?;
+        let nonce = Nonce::try_assume_unique_for_key(&frame[1..=NONCE_LEN])
+            .map_err(|_| E2eeError::Malformed)?;
+        let mut body = frame[1 + NONCE_LEN..].to_vec();
+        let plaintext =
+            key.open_in_place(nonce, Aad::from([id]), &mut body).map_err(|_| E2eeError::Decrypt)?;
+        let len = plaintext.len();
+        body.truncate(len);
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Test assertions
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_across_rotation() {
+        let mut encryptor = FrameEncryptor::new(b"secret", "room-1").unwrap();
+        let mut decryptor = FrameDecryptor::new(b"secret", "room-1");
+
+        let frame = encryptor.encrypt(b"opus-frame").unwrap();
+        assert_eq!(decryptor.decrypt(&frame).unwrap_err(), E2eeError::UnknownKey(0));
+        assert_eq!(decryptor.apply_key_message(&encryptor.key_message().unwrap()).unwrap(), 1);
+        assert_eq!(decryptor.decrypt(&frame).unwrap(), b"opus-frame");
+
+        // The pending key is announced alongside the current one, before frames use it.
+        encryptor.prepare_rotation().unwrap();
+        assert_eq!(decryptor.apply_key_message(&encryptor.key_message().unwrap()).unwrap(), 2);
+        assert!(encryptor.activate_pending());
+        let rotated = encryptor.encrypt(b"next-frame").unwrap();
+        assert_eq!(rotated[0], 1);
+        assert_eq!(decryptor.decrypt(&rotated).unwrap(), b"next-frame");
+        // Frames still in flight with the old key remain readable.
+        assert_eq!(decryptor.decrypt(&frame).unwrap(), b"opus-frame");
+    }
+
+    #[test]
+    fn test_rejects_wrong_secret_broadcast_and_tampering() {
+        let encryptor = FrameEncryptor::new(b"secret", "room-1").unwrap();
+        let message = encryptor.key_message().unwrap();
+
+        assert_eq!(
+            FrameDecryptor::new(b"other", "room-1").apply_key_message(&message).unwrap_err(),
+            E2eeError::Decrypt
+        );
+        assert_eq!(
+            FrameDecryptor::new(b"secret", "room-2").apply_key_message(&message).unwrap_err(),
+            E2eeError::Decrypt
+        );
+        assert_eq!(
+            FrameDecryptor::new(b"secret", "room-1").apply_key_message(&message[..10]).unwrap_err(),
+            E2eeError::Malformed
+        );
+
+        let mut decryptor = FrameDecryptor::new(b"secret", "room-1");
+        decryptor.apply_key_message(&message).unwrap();
+        let mut frame = encryptor.encrypt(b"opus-frame").unwrap();
+        let last = frame.len() - 1;
+        frame[last] ^= 1;
+        assert_eq!(decryptor.decrypt(&frame).unwrap_err(), E2eeError::Decrypt);
+    }
+
+    #[test]
+    fn test_resolve_secret() {
+        let secrets = HashMap::from([("moq".to_string(), "value".to_string())]);
+        let config = MoqEncryptionConfig { secret: "moq".to_string(), rotation_secs: 300 };
+        assert_eq!(resolve_secret(Some(&config), &secrets).unwrap(), Some(b"value".to_vec()));
+        assert_eq!(resolve_secret(None, &secrets).unwrap(), None);
+
+        let missing = MoqEncryptionConfig { secret: "nope".to_string(), rotation_secs: 300 };
+        assert!(matches!(
+            resolve_secret(Some(&missing), &secrets),
+            Err(StreamKitError::Configuration(_))
+        ));
+    }
+}
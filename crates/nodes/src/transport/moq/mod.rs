@@ -12,11 +12,13 @@
 #![cfg(feature = "moq")]
 
 mod constants;
+pub mod e2ee;
 mod peer;
 mod pull;
 mod push;
 
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 // Re-export public types
 pub use peer::{MoqPeerConfig, MoqPeerNode};
@@ -47,19 +49,26 @@ fn shared_insecure_client() -> Result<moq_native::Client, StreamKitError> {
 
 /// Registers the MoQ transport nodes.
 ///
+/// `secrets` provides the key material for `encryption` on the publisher and subscriber.
+///
 /// # Panics
 ///
 /// Panics if config schemas cannot be serialized to JSON (should never happen).
 #[allow(clippy::expect_used)] // Schema serialization should never fail for valid types
-pub fn register_moq_nodes(registry: &mut NodeRegistry) {
+#[allow(clippy::implicit_hasher)] // Secrets are always built with the default hasher
+pub fn register_moq_nodes(registry: &mut NodeRegistry, secrets: &HashMap<String, String>) {
     #[cfg(feature = "moq")]
     {
+        let secrets = Arc::new(secrets.clone());
+
         let default_moq_pull = MoqPullNode::new(MoqPullConfig::default());
+        let pull_secrets = Arc::clone(&secrets);
         registry.register_static_with_description(
             "transport::moq::subscriber",
-            |params| {
-                let config = config_helpers::parse_config_required(params)?;
-                Ok(Box::new(MoqPullNode::new(config)))
+            move |params| {
+                let config: MoqPullConfig = config_helpers::parse_config_required(params)?;
+                let secret = e2ee::resolve_secret(config.encryption.as_ref(), &pull_secrets)?;
+                Ok(Box::new(MoqPullNode::new(config).with_encryption_secret(secret)))
             },
             serde_json::to_value(schema_for!(MoqPullConfig))
                 .expect("MoqPullConfig schema should serialize to JSON"),
@@ -74,11 +83,13 @@ pub fn register_moq_nodes(registry: &mut NodeRegistry) {
         );
 
         let default_moq_push = MoqPushNode::new(MoqPushConfig::default());
+        let push_secrets = Arc::clone(&secrets);
         registry.register_static_with_description(
             "transport::moq::publisher",
-            |params| {
-                let config = config_helpers::parse_config_required(params)?;
-                Ok(Box::new(MoqPushNode::new(config)))
+            move |params| {
+                let config: MoqPushConfig = config_helpers::parse_config_required(params)?;
+                let secret = e2ee::resolve_secret(config.encryption.as_ref(), &push_secrets)?;
+                Ok(Box::new(MoqPushNode::new(config).with_encryption_secret(secret)))
             },
             serde_json::to_value(schema_for!(MoqPushConfig))
                 .expect("MoqPushConfig schema should serialize to JSON"),
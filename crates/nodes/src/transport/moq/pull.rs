@@ -10,16 +10,21 @@ use moq_lite::coding::Decode;
 use moq_lite::AsPath;
 use schemars::JsonSchema;
 use serde::Deserialize;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
+use streamkit_core::redaction::redact_url;
 use streamkit_core::types::{Packet, PacketType};
 use streamkit_core::{
     state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin, PinCardinality,
     ProcessorNode, StreamKitError,
 };
 
+use super::e2ee::{self, E2eeError, FrameDecryptor, MoqEncryptionConfig};
+
 #[derive(Deserialize, Debug, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct MoqPullConfig {
+    #[schemars(extend("sensitive" = true))]
     pub url: String,
     pub broadcast: String,
     /// Batch window in milliseconds. If > 0, after receiving a frame the node will
@@ -27,6 +32,9 @@ pub struct MoqPullConfig {
     /// Default: 0 (no batching) - recommended because moq_lite's TrackConsumer::read()
     /// has internal allocation overhead that makes batching counterproductive.
     pub batch_ms: u64,
+    /// Decrypts frames from a publisher with `encryption` enabled (same secret).
+    /// Default: disabled.
+    pub encryption: Option<MoqEncryptionConfig>,
 }
 
 /// A node that connects to a MoQ server, subscribes to a broadcast,
@@ -43,6 +51,17 @@ pub struct MoqPullNode {
     config: MoqPullConfig,
     /// Dynamically discovered output pins (one per track)
     output_pins: Vec<OutputPin>,
+    /// Key material for `config.encryption`, resolved from the server's secrets
+    encryption_secret: Option<Vec<u8>>,
+}
+
+/// Aborts the wrapped task when the connection it belongs to ends.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 impl MoqPullNode {
@@ -55,9 +74,16 @@ impl MoqPullNode {
                 produces_type: PacketType::OpusAudio,
                 cardinality: PinCardinality::Broadcast,
             }],
+            encryption_secret: None,
         }
     }
 
+    #[must_use]
+    pub fn with_encryption_secret(mut self, secret: Option<Vec<u8>>) -> Self {
+        self.encryption_secret = secret;
+        self
+    }
+
     fn stable_out_pin() -> OutputPin {
         OutputPin {
             name: "out".to_string(),
@@ -99,7 +125,7 @@ impl ProcessorNode for MoqPullNode {
     ) -> Result<streamkit_core::pins::PinUpdate, StreamKitError> {
         tracing::info!(
             node_id = %ctx.node_id,
-            url = %self.config.url,
+            url = %redact_url(&self.config.url),
             broadcast = %self.config.broadcast,
             "MoqPullNode: Discovering tracks from broadcast catalog"
         );
@@ -145,7 +171,7 @@ impl ProcessorNode for MoqPullNode {
     async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
         let node_name = context.output_sender.node_name().to_string();
         state_helpers::emit_initializing(&context.state_tx, &node_name);
-        tracing::info!(url = %self.config.url, broadcast = %self.config.broadcast, "MoqPullNode starting");
+        tracing::info!(url = %redact_url(&self.config.url), broadcast = %self.config.broadcast, "MoqPullNode starting");
         state_helpers::emit_running(&context.state_tx, &node_name);
 
         let mut total_packet_count = 0;
@@ -240,6 +266,69 @@ impl MoqPullNode {
         Ok(payload.copy_to_bytes(payload.remaining()))
     }
 
+    /// Strips the hang header and, with encryption, decrypts the frame.
+    /// Returns `None` (after logging) for frames that must be dropped.
+    fn open_payload(
+        payload: bytes::Bytes,
+        decryptor: Option<&Mutex<FrameDecryptor>>,
+    ) -> Option<bytes::Bytes> {
+        let data = match Self::strip_hang_timestamp_header(payload) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to decode frame timestamp: {e}");
+                return None;
+            },
+        };
+        let Some(decryptor) = decryptor else {
+            return Some(data);
+        };
+        let decrypted = decryptor.lock().unwrap_or_else(PoisonError::into_inner).decrypt(&data);
+        match decrypted {
+            Ok(plaintext) => Some(bytes::Bytes::from(plaintext)),
+            Err(E2eeError::UnknownKey(id)) => {
+                // Expected until the first key message arrives.
+                tracing::debug!(key_id = id, "Dropping frame for unknown content key");
+                None
+            },
+            Err(e) => {
+                tracing::warn!("Failed to decrypt frame: {e}");
+                None
+            },
+        }
+    }
+
+    /// Feeds key messages from the broadcast's key track into `decryptor`.
+    fn spawn_key_reader(
+        broadcast: &moq_lite::BroadcastConsumer,
+        decryptor: Arc<Mutex<FrameDecryptor>>,
+    ) -> AbortOnDrop {
+        let mut keys = broadcast.subscribe_track(&moq_lite::Track {
+            name: e2ee::KEYS_TRACK.to_string(),
+            priority: 100,
+        });
+        AbortOnDrop(tokio::spawn(async move {
+            let mut current_group = None;
+            loop {
+                match Self::read_next_raw_moq(&mut keys, &mut current_group).await {
+                    Ok(Some(message)) => {
+                        let mut decryptor =
+                            decryptor.lock().unwrap_or_else(PoisonError::into_inner);
+                        match decryptor.apply_key_message(&message) {
+                            Ok(count) => tracing::debug!(count, "Received MoQ content keys"),
+                            Err(e) => tracing::warn!("Ignoring MoQ key message: {e}"),
+                        }
+                    },
+                    Ok(None) => break,
+                    Err(moq_lite::Error::Cancel) => {},
+                    Err(e) => {
+                        tracing::debug!(error = %e, "MoQ key track ended");
+                        break;
+                    },
+                }
+            }
+        }))
+    }
+
     async fn read_next_raw_moq(
         track_consumer: &mut moq_lite::TrackConsumer,
         current_group: &mut Option<moq_lite::GroupConsumer>,
@@ -276,7 +365,7 @@ impl MoqPullNode {
     /// This is used during initialization to create output pins dynamically.
     async fn discover_tracks(&self) -> Result<Vec<moq_lite::Track>, StreamKitError> {
         tracing::info!(
-            url = %self.config.url,
+            url = %redact_url(&self.config.url),
             broadcast = %self.config.broadcast,
             "Connecting to MoQ server to discover tracks"
         );
@@ -284,7 +373,8 @@ impl MoqPullNode {
         let url = self.config.url.parse().map_err(|e| {
             StreamKitError::Configuration(format!(
                 "Failed to parse MoQ URL '{}': {}",
-                self.config.url, e
+                redact_url(&self.config.url),
+                e
             ))
         })?;
 
@@ -427,7 +517,8 @@ impl MoqPullNode {
         let url = self.config.url.parse().map_err(|e| {
             StreamKitError::Configuration(format!(
                 "Failed to parse MoQ URL '{}': {}",
-                self.config.url, e
+                redact_url(&self.config.url),
+                e
             ))
         })?;
 
@@ -540,6 +631,15 @@ impl MoqPullNode {
         let mut track_consumer = broadcast.subscribe_track(audio_track);
         let mut current_group: Option<moq_lite::GroupConsumer> = None;
 
+        // With encryption, keys arrive on a control track; frames seen before the first key
+        // message are dropped.
+        let decryptor = self.encryption_secret.as_ref().map(|secret| {
+            Arc::new(Mutex::new(FrameDecryptor::new(secret, &self.config.broadcast)))
+        });
+        let _key_reader = decryptor
+            .as_ref()
+            .map(|decryptor| Self::spawn_key_reader(&broadcast, Arc::clone(decryptor)));
+
         let mut session_packet_count: u32 = 0;
         let mut consecutive_cancels: u32 = 0;
         let mut last_payload_at = tokio::time::Instant::now();
@@ -644,13 +744,10 @@ impl MoqPullNode {
                                 );
                             }
 
-                            let data = match Self::strip_hang_timestamp_header(payload) {
-                                Ok(data) => data,
-                                Err(e) => {
-                                    tracing::warn!("Failed to decode frame timestamp: {e}");
-                                    stats_tracker.discarded();
-                                    continue;
-                                },
+                            let Some(data) = Self::open_payload(payload, decryptor.as_deref())
+                            else {
+                                stats_tracker.discarded();
+                                continue;
                             };
                             let packet =
                                 Packet::Binary { data, content_type: None, metadata: None };
@@ -685,13 +782,10 @@ impl MoqPullNode {
                             );
                         }
 
-                        let data = match Self::strip_hang_timestamp_header(first_payload) {
-                            Ok(data) => data,
-                            Err(e) => {
-                                tracing::warn!("Failed to decode frame timestamp: {e}");
-                                stats_tracker.discarded();
-                                continue;
-                            },
+                        let Some(data) = Self::open_payload(first_payload, decryptor.as_deref())
+                        else {
+                            stats_tracker.discarded();
+                            continue;
                         };
 
                         let packet = Packet::Binary { data, content_type: None, metadata: None };
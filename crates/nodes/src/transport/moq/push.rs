@@ -7,15 +7,20 @@
 use async_trait::async_trait;
 use schemars::JsonSchema;
 use serde::Deserialize;
+use std::time::Duration;
+use streamkit_core::redaction::redact_url;
 use streamkit_core::types::{Packet, PacketType};
 use streamkit_core::{
     packet_helpers, state_helpers, stats::NodeStatsTracker, InputPin, NodeContext, OutputPin,
     PinCardinality, ProcessorNode, StreamKitError,
 };
 
+use super::e2ee::{self, FrameEncryptor, MoqEncryptionConfig};
+
 #[derive(Deserialize, Debug, JsonSchema, Clone)]
 #[serde(default)]
 pub struct MoqPushConfig {
+    #[schemars(extend("sensitive" = true))]
     pub url: String,
     pub broadcast: String,
     #[serde(default = "default_channels")]
@@ -34,6 +39,9 @@ pub struct MoqPushConfig {
     ///
     /// Default: 0 (no added delay).
     pub initial_delay_ms: u64,
+    /// Encrypts frames end-to-end so relays never see plaintext media.
+    /// Subscribers need the same secret. Default: disabled.
+    pub encryption: Option<MoqEncryptionConfig>,
 }
 
 const fn default_channels() -> u32 {
@@ -52,6 +60,7 @@ impl Default for MoqPushConfig {
             channels: 2,
             group_duration_ms: default_group_duration_ms(),
             initial_delay_ms: 0,
+            encryption: None,
         }
     }
 }
@@ -59,11 +68,19 @@ impl Default for MoqPushConfig {
 /// A node that receives Opus packets and publishes them to a MoQ broadcast.
 pub struct MoqPushNode {
     config: MoqPushConfig,
+    /// Key material for `config.encryption`, resolved from the server's secrets
+    encryption_secret: Option<Vec<u8>>,
 }
 
 impl MoqPushNode {
     pub const fn new(config: MoqPushConfig) -> Self {
-        Self { config }
+        Self { config, encryption_secret: None }
+    }
+
+    #[must_use]
+    pub fn with_encryption_secret(mut self, secret: Option<Vec<u8>>) -> Self {
+        self.encryption_secret = secret;
+        self
     }
 }
 
@@ -88,12 +105,13 @@ impl ProcessorNode for MoqPushNode {
         let url = match self.config.url.parse() {
             Ok(url) => url,
             Err(e) => {
-                let err_msg = format!("Failed to parse MoQ URL '{}': {}", self.config.url, e);
+                let err_msg =
+                    format!("Failed to parse MoQ URL '{}': {}", redact_url(&self.config.url), e);
                 state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
                 return Err(StreamKitError::Configuration(err_msg));
             },
         };
-        tracing::info!(url = %self.config.url, broadcast = %self.config.broadcast, "MoqPushNode starting");
+        tracing::info!(url = %redact_url(&self.config.url), broadcast = %self.config.broadcast, "MoqPushNode starting");
         tracing::info!(
             group_duration_ms = self.config.group_duration_ms,
             initial_delay_ms = self.config.initial_delay_ms,
@@ -189,6 +207,27 @@ impl ProcessorNode for MoqPushNode {
 
         tracing::info!("published catalog for broadcast");
 
+        // With encryption, wrapped content keys go on a control track next to the audio.
+        let mut encryption = match &self.encryption_secret {
+            Some(secret) => {
+                let encryptor = FrameEncryptor::new(secret, &self.config.broadcast)?;
+                let mut keys_producer = broadcast.create_track(moq_lite::Track {
+                    name: e2ee::KEYS_TRACK.to_string(),
+                    priority: 100,
+                });
+                keys_producer.write_frame(encryptor.key_message()?);
+                tracing::info!(broadcast = %self.config.broadcast, "End-to-end encryption enabled");
+                Some((encryptor, keys_producer))
+            },
+            None => None,
+        };
+        let rotation_secs = self.config.encryption.as_ref().map_or(0, |e| e.rotation_secs);
+        let mut rotation_timer = tokio::time::interval(Duration::from_secs(rotation_secs.max(1)));
+        rotation_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately; rotation starts one period in.
+        rotation_timer.tick().await;
+        let mut activate_at: Option<tokio::time::Instant> = None;
+
         state_helpers::emit_running(&context.state_tx, &node_name);
 
         let mut input_rx = context.take_input("in")?;
@@ -229,6 +268,20 @@ impl ProcessorNode for MoqPushNode {
                                 StreamKitError::Runtime("MoQ frame timestamp overflow".to_string())
                             })?;
 
+                            let data = match encryption.as_mut() {
+                                Some((encryptor, keys_producer)) => {
+                                    let now = tokio::time::Instant::now();
+                                    if activate_at.is_some_and(|at| now >= at) {
+                                        activate_at = None;
+                                        encryptor.activate_pending();
+                                        keys_producer.write_frame(encryptor.key_message()?);
+                                        tracing::debug!("Switched to rotated content key");
+                                    }
+                                    bytes::Bytes::from(encryptor.encrypt(&data)?)
+                                },
+                                None => data,
+                            };
+
                             let mut payload = hang::BufList::new();
                             payload.push_chunk(data);
 
@@ -252,6 +305,15 @@ impl ProcessorNode for MoqPushNode {
                     }
                     stats_tracker.maybe_send();
                 },
+                _ = rotation_timer.tick(), if rotation_secs > 0 && activate_at.is_none() => {
+                    if let Some((encryptor, keys_producer)) = encryption.as_mut() {
+                        // Announce the next key now; frames switch to it after the grace period.
+                        encryptor.prepare_rotation()?;
+                        keys_producer.write_frame(encryptor.key_message()?);
+                        activate_at = Some(tokio::time::Instant::now() + e2ee::ROTATION_GRACE);
+                        tracing::debug!("Announced next content key");
+                    }
+                },
                 Some(control_msg) = context.control_rx.recv() => {
                     match control_msg {
                         streamkit_core::control::NodeControlMessage::Shutdown => {
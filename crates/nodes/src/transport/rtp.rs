@@ -0,0 +1,1358 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! RTP input and output nodes.
+//!
+//! `transport::rtp::output` packetizes encoded frames into RTP over UDP, and
+//! `transport::rtp::input` receives one RTP stream, reorders it through a jitter buffer and
+//! reassembles the frames. Supported payload formats:
+//!
+//! - Opus (RFC 7587): one packet per frame, 48 kHz clock.
+//! - VP9 (RFC 9628): sent in non-flexible mode with a 15-bit picture ID; the receiver also
+//!   accepts flexible mode, layer indices and scalability structures. 90 kHz clock.
+//! - AV1 (AOM RTP payload format): length-prefixed OBU elements, 90 kHz clock. Frames enter
+//!   and leave the nodes as temporal units in the low-overhead bitstream format.
+//!
+//! Each node carries a single stream (one SSRC). RTCP is not implemented, and frames damaged
+//! by packet loss are dropped rather than emitted partially.
+
+use async_trait::async_trait;
+use bytes::{BufMut, Bytes, BytesMut};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketMetadata, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality,
+    ProcessorNode, StreamKitError,
+};
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+// --- RTP Constants ---
+
+const RTP_VERSION: u8 = 2;
+const RTP_HEADER_SIZE: usize = 12;
+/// Largest UDP payload over IPv4.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+pub(super) const DEFAULT_MTU: usize = 1200;
+/// Smallest MTU leaving room for the RTP header, a payload descriptor and some payload.
+const MIN_MTU: usize = 64;
+pub(super) const DEFAULT_JITTER_BUFFER_MS: u64 = 50;
+pub(super) const MAX_JITTER_BUFFER_MS: u64 = 5000;
+/// Packets held by the jitter buffer before the oldest is released regardless of its age.
+const MAX_JITTER_BUFFER_PACKETS: usize = 2048;
+/// Packets arriving this far behind the stream are taken as a sender restart (RFC 3550 A.1).
+const MAX_MISORDER: u64 = 100;
+
+/// Fallback frame durations in clock ticks when packets carry no timing metadata.
+const DEFAULT_OPUS_FRAME_TICKS: u64 = 960;
+const DEFAULT_VIDEO_FRAME_TICKS: u64 = 3000;
+
+const OBU_SEQUENCE_HEADER: u8 = 1;
+const OBU_TEMPORAL_DELIMITER: u8 = 2;
+const OBU_TILE_LIST: u8 = 8;
+const OBU_PADDING: u8 = 15;
+/// Temporal delimiter OBU with an empty payload, starting every reassembled AV1 temporal unit.
+const AV1_TEMPORAL_DELIMITER: [u8; 2] = [(OBU_TEMPORAL_DELIMITER << 3) | 0x02, 0x00];
+
+/// Payload format of an RTP stream.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RtpCodec {
+    /// Opus audio (RFC 7587), one Opus packet per RTP packet
+    #[default]
+    Opus,
+    /// VP9 video (RFC 9628), one frame per pipeline packet
+    Vp9,
+    /// AV1 video, one temporal unit per pipeline packet
+    Av1,
+}
+
+impl RtpCodec {
+    pub(super) const fn clock_rate(self) -> u64 {
+        match self {
+            Self::Opus => 48_000,
+            Self::Vp9 | Self::Av1 => 90_000,
+        }
+    }
+
+    /// Dynamic payload types commonly used for each codec by WebRTC stacks.
+    pub(super) const fn default_payload_type(self) -> u8 {
+        match self {
+            Self::Opus => 111,
+            Self::Vp9 => 98,
+            Self::Av1 => 45,
+        }
+    }
+
+    pub(super) const fn default_frame_ticks(self) -> u64 {
+        match self {
+            Self::Opus => DEFAULT_OPUS_FRAME_TICKS,
+            Self::Vp9 | Self::Av1 => DEFAULT_VIDEO_FRAME_TICKS,
+        }
+    }
+
+    pub(super) const fn packet_type(self) -> PacketType {
+        match self {
+            Self::Opus => PacketType::OpusAudio,
+            Self::Vp9 | Self::Av1 => PacketType::Binary,
+        }
+    }
+
+    pub(super) const fn is_video(self) -> bool {
+        matches!(self, Self::Vp9 | Self::Av1)
+    }
+}
+
+/// Configuration for `transport::rtp::output`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct RtpOutputConfig {
+    /// Destination `host:port` of the RTP stream
+    #[schemars(extend("sensitive" = true))]
+    pub remote_address: String,
+    /// Local `host:port` to send from (default: any interface, ephemeral port)
+    #[serde(default = "default_local_address")]
+    pub local_address: String,
+    /// Payload format of the frames arriving on `in`
+    #[serde(default)]
+    pub codec: RtpCodec,
+    /// RTP payload type (default: 111 for Opus, 98 for VP9, 45 for AV1)
+    #[serde(default)]
+    #[schemars(range(max = 127))]
+    pub payload_type: Option<u8>,
+    /// Synchronization source identifier (default: random)
+    #[serde(default)]
+    pub ssrc: Option<u32>,
+    /// Largest RTP packet to send, header included; video frames are fragmented to fit
+    #[serde(default = "default_mtu")]
+    #[schemars(range(min = 64, max = 65507))]
+    pub mtu: usize,
+}
+
+/// Configuration for `transport::rtp::input`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct RtpInputConfig {
+    /// Local `host:port` to receive the RTP stream on
+    pub bind_address: String,
+    /// Payload format of the stream
+    #[serde(default)]
+    pub codec: RtpCodec,
+    /// Only accept packets with this payload type (default: any)
+    #[serde(default)]
+    #[schemars(range(max = 127))]
+    pub payload_type: Option<u8>,
+    /// Only accept packets from this SSRC (default: the first SSRC received)
+    #[serde(default)]
+    pub ssrc: Option<u32>,
+    /// How long packets are held for reordering before a missing one is declared lost
+    #[serde(default = "default_jitter_buffer_ms")]
+    #[schemars(range(max = 5000))]
+    pub jitter_buffer_ms: u64,
+}
+
+fn default_local_address() -> String {
+    "0.0.0.0:0".to_string()
+}
+
+const fn default_mtu() -> usize {
+    DEFAULT_MTU
+}
+
+const fn default_jitter_buffer_ms() -> u64 {
+    DEFAULT_JITTER_BUFFER_MS
+}
+
+fn validate_payload_type(payload_type: Option<u8>) -> Result<(), StreamKitError> {
+    match payload_type {
+        Some(pt) if pt > 127 => Err(StreamKitError::Configuration(format!(
+            "payload_type must be at most 127, got {pt}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// --- Bitstream helpers ---
+
+pub(super) fn low_u16(value: u64) -> u16 {
+    u16::try_from(value & 0xFFFF).unwrap_or_default()
+}
+
+pub(super) fn low_u32(value: u64) -> u32 {
+    u32::try_from(value & 0xFFFF_FFFF).unwrap_or_default()
+}
+
+pub(super) fn us_to_ticks(us: u64, clock_rate: u64) -> u64 {
+    u64::try_from(u128::from(us) * u128::from(clock_rate) / 1_000_000).unwrap_or(u64::MAX)
+}
+
+fn ticks_to_us(ticks: u64, clock_rate: u64) -> u64 {
+    u64::try_from(u128::from(ticks) * 1_000_000 / u128::from(clock_rate)).unwrap_or(u64::MAX)
+}
+
+/// Random value for the SSRC and the initial sequence number and timestamp (RFC 3550 §5.1).
+pub(super) fn random_u32() -> u32 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    hasher.write_u128(nanos.as_nanos());
+    low_u32(hasher.finish())
+}
+
+/// Reads a LEB128 value as used for AV1 OBU sizes. Returns the value and its length.
+fn read_leb128(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in data.iter().take(8).enumerate() {
+        value |= u64::from(byte & 0x7F) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+fn write_leb128(out: &mut BytesMut, mut value: usize) {
+    loop {
+        let byte = u8::try_from(value & 0x7F).unwrap_or_default();
+        value >>= 7;
+        if value == 0 {
+            out.put_u8(byte);
+            return;
+        }
+        out.put_u8(byte | 0x80);
+    }
+}
+
+const fn leb128_size(value: usize) -> usize {
+    let mut size = 1;
+    let mut value = value >> 7;
+    while value != 0 {
+        size += 1;
+        value >>= 7;
+    }
+    size
+}
+
+/// Whether a VP9 frame is a key frame, from its uncompressed header.
+pub(super) const fn vp9_is_keyframe(data: &[u8]) -> bool {
+    let Some(&first) = data.first() else {
+        return false;
+    };
+    if first >> 6 != 0b10 {
+        return false;
+    }
+    let profile = (((first >> 4) & 0x01) << 1) | ((first >> 5) & 0x01);
+    // Bits after the profile: [reserved_zero if profile 3], show_existing_frame, frame_type
+    let bit = if profile == 3 { 2 } else { 3 };
+    (first >> bit) & 0x01 == 0 && (first >> (bit - 1)) & 0x01 == 0
+}
+
+/// Length of the VP9 payload descriptor at the start of `payload`, or `None` if truncated.
+fn vp9_descriptor_len(payload: &[u8]) -> Option<usize> {
+    let flags = *payload.first()?;
+    let (picture_id, inter_predicted, layer_indices, flexible, scalability) = (
+        flags & 0x80 != 0,
+        flags & 0x40 != 0,
+        flags & 0x20 != 0,
+        flags & 0x10 != 0,
+        flags & 0x02 != 0,
+    );
+    let mut pos = 1;
+    if picture_id {
+        // M bit: 15-bit picture ID
+        pos += if payload.get(pos)? & 0x80 != 0 { 2 } else { 1 };
+    }
+    if layer_indices {
+        // TL0PICIDX follows the layer indices in non-flexible mode
+        pos += if flexible { 1 } else { 2 };
+    }
+    if flexible && inter_predicted {
+        // Up to three reference indices, each flagging whether another follows (N bit)
+        for _ in 0..3 {
+            let diff = *payload.get(pos)?;
+            pos += 1;
+            if diff & 0x01 == 0 {
+                break;
+            }
+        }
+    }
+    if scalability {
+        let header = *payload.get(pos)?;
+        pos += 1;
+        let spatial_layers = usize::from(header >> 5) + 1;
+        if header & 0x10 != 0 {
+            // Y: width and height of each spatial layer
+            pos += 4 * spatial_layers;
+        }
+        if header & 0x08 != 0 {
+            // G: picture group description
+            let pictures = *payload.get(pos)?;
+            pos += 1;
+            for _ in 0..pictures {
+                let references = usize::from((payload.get(pos)? >> 2) & 0x03);
+                pos += 1 + references;
+            }
+        }
+    }
+    (pos <= payload.len()).then_some(pos)
+}
+
+/// Splits an AV1 temporal unit into OBUs without size fields, dropping those RTP does not
+/// carry (temporal delimiters, tile lists and padding), and reports whether it carries a
+/// sequence header, i.e. starts a new coded video sequence.
+fn av1_split_obus(data: &[u8]) -> (Vec<Vec<u8>>, bool) {
+    let mut obus = Vec::new();
+    let mut has_sequence_header = false;
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = data[pos];
+        let obu_type = (header >> 3) & 0x0F;
+        let header_len = if header & 0x04 != 0 { 2 } else { 1 };
+        let Some(header_bytes) = data.get(pos..pos + header_len) else { break };
+        let (start, end) = if header & 0x02 != 0 {
+            let Some((size, leb_len)) = data.get(pos + header_len..).and_then(read_leb128) else {
+                break;
+            };
+            let start = (pos + header_len + leb_len).min(data.len());
+            let end = usize::try_from(size)
+                .map_or(data.len(), |size| start.saturating_add(size).min(data.len()));
+            (start, end)
+        } else {
+            (pos + header_len, data.len())
+        };
+        match obu_type {
+            OBU_TEMPORAL_DELIMITER | OBU_TILE_LIST | OBU_PADDING => {},
+            _ => {
+                has_sequence_header |= obu_type == OBU_SEQUENCE_HEADER;
+                let mut obu = Vec::with_capacity(header_len + end - start);
+                obu.push(header & !0x02);
+                obu.extend_from_slice(&header_bytes[1..]);
+                obu.extend_from_slice(&data[start..end]);
+                obus.push(obu);
+            },
+        }
+        pos = end;
+    }
+    (obus, has_sequence_header)
+}
+
+/// Restores the size field of an OBU received without one, as the low-overhead bitstream
+/// format requires.
+fn av1_write_obu(out: &mut BytesMut, obu: &[u8]) {
+    let Some(&header) = obu.first() else { return };
+    if header & 0x02 != 0 {
+        out.extend_from_slice(obu);
+        return;
+    }
+    let header_len = if header & 0x04 != 0 { 2 } else { 1 };
+    let Some(extension) = obu.get(1..header_len) else { return };
+    out.put_u8(header | 0x02);
+    out.extend_from_slice(extension);
+    write_leb128(out, obu.len() - header_len);
+    out.extend_from_slice(&obu[header_len..]);
+}
+
+// --- RTP packets ---
+
+#[derive(Debug, Clone)]
+pub(super) struct RtpPacket {
+    pub(super) marker: bool,
+    pub(super) payload_type: u8,
+    pub(super) sequence: u16,
+    pub(super) timestamp: u32,
+    pub(super) ssrc: u32,
+    pub(super) payload: Bytes,
+}
+
+impl RtpPacket {
+    /// Parses an RTP packet, skipping CSRCs, header extensions and padding.
+    fn parse(data: &Bytes) -> Option<Self> {
+        if data.len() < RTP_HEADER_SIZE || data[0] >> 6 != RTP_VERSION {
+            return None;
+        }
+        let mut start = RTP_HEADER_SIZE + 4 * usize::from(data[0] & 0x0F);
+        if data[0] & 0x10 != 0 {
+            let extension = data.get(start..start + 4)?;
+            start += 4 + 4 * usize::from(u16::from_be_bytes([extension[2], extension[3]]));
+        }
+        let mut end = data.len();
+        if data[0] & 0x20 != 0 {
+            end = end.checked_sub(usize::from(*data.last()?))?;
+        }
+        if start > end {
+            return None;
+        }
+        Some(Self {
+            marker: data[1] & 0x80 != 0,
+            payload_type: data[1] & 0x7F,
+            sequence: u16::from_be_bytes([data[2], data[3]]),
+            timestamp: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            ssrc: u32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+            payload: data.slice(start..end),
+        })
+    }
+
+    fn write(&self, out: &mut BytesMut) {
+        out.put_u8(RTP_VERSION << 6);
+        out.put_u8((u8::from(self.marker) << 7) | self.payload_type);
+        out.put_u16(self.sequence);
+        out.put_u32(self.timestamp);
+        out.put_u32(self.ssrc);
+        out.extend_from_slice(&self.payload);
+    }
+}
+
+/// Splits frames into RTP payloads.
+pub(super) struct Packetizer {
+    codec: RtpCodec,
+    /// Largest payload after the RTP header
+    max_payload: usize,
+    /// VP9 picture ID, incremented per frame
+    picture_id: u16,
+}
+
+impl Packetizer {
+    pub(super) const fn new(codec: RtpCodec, mtu: usize, picture_id: u16) -> Self {
+        Self { codec, max_payload: mtu - RTP_HEADER_SIZE, picture_id: picture_id & 0x7FFF }
+    }
+
+    /// Returns the payloads of one frame in sending order. Empty frames yield no payloads.
+    pub(super) fn packetize(&mut self, frame: &Bytes) -> Vec<Bytes> {
+        if frame.is_empty() {
+            return Vec::new();
+        }
+        match self.codec {
+            RtpCodec::Opus => vec![frame.clone()],
+            RtpCodec::Vp9 => self.packetize_vp9(frame),
+            RtpCodec::Av1 => self.packetize_av1(frame),
+        }
+    }
+
+    fn packetize_vp9(&mut self, frame: &[u8]) -> Vec<Bytes> {
+        // Flags plus a 15-bit picture ID
+        const DESCRIPTOR_SIZE: usize = 3;
+
+        let inter_predicted = !vp9_is_keyframe(frame);
+        let picture_id = self.picture_id;
+        self.picture_id = (self.picture_id + 1) & 0x7FFF;
+
+        let chunks: Vec<&[u8]> = frame.chunks(self.max_payload - DESCRIPTOR_SIZE).collect();
+        let last = chunks.len() - 1;
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                // I | P | L | F | B | E | V | Z
+                let mut flags = 0x80;
+                if inter_predicted {
+                    flags |= 0x40;
+                }
+                if i == 0 {
+                    flags |= 0x08;
+                }
+                if i == last {
+                    flags |= 0x04;
+                }
+                let mut payload = BytesMut::with_capacity(DESCRIPTOR_SIZE + chunk.len());
+                payload.put_u8(flags);
+                payload.put_u16(0x8000 | picture_id);
+                payload.extend_from_slice(chunk);
+                payload.freeze()
+            })
+            .collect()
+    }
+
+    /// Packs OBUs as length-prefixed elements, fragmenting those that do not fit.
+    fn packetize_av1(&self, frame: &[u8]) -> Vec<Bytes> {
+        let (obus, new_sequence) = av1_split_obus(frame);
+        let mut payloads = Vec::new();
+        let mut elements = BytesMut::new();
+        let mut continues_previous = false;
+
+        let mut finish = |elements: &mut BytesMut, continues_next: bool| {
+            // Z | Y | W W | N | - - -
+            let mut header = 0u8;
+            if continues_previous {
+                header |= 0x80;
+            }
+            if continues_next {
+                header |= 0x40;
+            }
+            if payloads.is_empty() && new_sequence {
+                header |= 0x08;
+            }
+            let mut payload = BytesMut::with_capacity(1 + elements.len());
+            payload.put_u8(header);
+            payload.extend_from_slice(elements);
+            elements.clear();
+            payloads.push(payload.freeze());
+            continues_previous = continues_next;
+        };
+
+        for obu in &obus {
+            let mut rest = obu.as_slice();
+            while !rest.is_empty() {
+                let room = self.max_payload - 1 - elements.len();
+                if room <= leb128_size(room) {
+                    finish(&mut elements, false);
+                    continue;
+                }
+                let take = rest.len().min(room - leb128_size(room));
+                write_leb128(&mut elements, take);
+                elements.extend_from_slice(&rest[..take]);
+                rest = &rest[take..];
+                if !rest.is_empty() {
+                    finish(&mut elements, true);
+                }
+            }
+        }
+        if !elements.is_empty() {
+            finish(&mut elements, false);
+        }
+        payloads
+    }
+}
+
+/// Orders packets by extended sequence number, holding each for at most `latency` while
+/// waiting for the ones before it.
+pub(super) struct JitterBuffer {
+    latency: Duration,
+    packets: BTreeMap<u64, (Instant, RtpPacket)>,
+    /// Highest extended sequence number seen, for unwrapping 16-bit sequence numbers
+    highest: Option<u64>,
+    /// Extended sequence number of the next packet to release
+    next: Option<u64>,
+    /// Packets skipped as lost
+    pub(super) lost: u64,
+}
+
+impl JitterBuffer {
+    pub(super) const fn new(latency: Duration) -> Self {
+        Self { latency, packets: BTreeMap::new(), highest: None, next: None, lost: 0 }
+    }
+
+    fn extend_sequence(&mut self, sequence: u16) -> u64 {
+        // The first packet starts one cycle in, so early reordered packets do not underflow
+        let extended = self.highest.map_or_else(
+            || (1 << 16) | u64::from(sequence),
+            |highest| {
+                let delta =
+                    i16::from_ne_bytes(sequence.wrapping_sub(low_u16(highest)).to_ne_bytes());
+                highest.saturating_add_signed(i64::from(delta))
+            },
+        );
+        self.highest = self.highest.max(Some(extended));
+        extended
+    }
+
+    /// Buffers a packet. Returns `false` for duplicates and packets that arrive after their
+    /// turn was skipped.
+    pub(super) fn push(&mut self, packet: RtpPacket, now: Instant) -> bool {
+        let mut sequence = self.extend_sequence(packet.sequence);
+        if let Some(next) = self.next {
+            if sequence + MAX_MISORDER < next {
+                tracing::info!("RTP sequence jumped back, assuming the sender restarted");
+                *self = Self::new(self.latency);
+                sequence = self.extend_sequence(packet.sequence);
+            } else if sequence < next {
+                return false;
+            }
+        }
+        if self.packets.contains_key(&sequence) {
+            return false;
+        }
+        self.packets.insert(sequence, (now, packet));
+        true
+    }
+
+    /// Releases the next packet once it is in order, or once the oldest buffered packet has
+    /// waited `latency` and any packets missing before it are given up on.
+    pub(super) fn pop(&mut self, now: Instant) -> Option<(u64, RtpPacket)> {
+        let (&sequence, _) = self.packets.first_key_value()?;
+        let ready = self.next == Some(sequence)
+            || self.packets.len() > MAX_JITTER_BUFFER_PACKETS
+            || self.next_deadline().is_some_and(|deadline| deadline <= now);
+        if !ready {
+            return None;
+        }
+        let (sequence, (_, packet)) = self.packets.pop_first()?;
+        if let Some(next) = self.next {
+            self.lost += sequence - next;
+        }
+        self.next = Some(sequence + 1);
+        Some((sequence, packet))
+    }
+
+    /// When the oldest buffered packet stops waiting for missing ones.
+    pub(super) fn next_deadline(&self) -> Option<Instant> {
+        self.packets.values().map(|(arrival, _)| *arrival + self.latency).min()
+    }
+}
+
+/// A reassembled frame and its RTP timestamp.
+pub(super) struct Frame {
+    pub(super) data: Bytes,
+    pub(super) timestamp: u32,
+}
+
+/// Reassembles frames from in-order RTP packets.
+pub(super) struct Depacketizer {
+    codec: RtpCodec,
+    /// Extended sequence number of the last packet pushed
+    last_sequence: Option<u64>,
+    /// Whether a frame is being assembled; packets are skipped until the next frame starts
+    /// otherwise
+    in_frame: bool,
+    frame_timestamp: u32,
+    frame: BytesMut,
+    /// AV1 OBU whose fragments span packets
+    obu: Vec<u8>,
+}
+
+impl Depacketizer {
+    pub(super) fn new(codec: RtpCodec) -> Self {
+        Self {
+            codec,
+            last_sequence: None,
+            in_frame: false,
+            frame_timestamp: 0,
+            frame: BytesMut::new(),
+            obu: Vec::new(),
+        }
+    }
+
+    fn begin_frame(&mut self, timestamp: u32) {
+        self.in_frame = true;
+        self.frame_timestamp = timestamp;
+        self.frame.clear();
+        self.obu.clear();
+        if self.codec == RtpCodec::Av1 {
+            self.frame.extend_from_slice(&AV1_TEMPORAL_DELIMITER);
+        }
+    }
+
+    fn finish_frame(&mut self) -> Frame {
+        self.in_frame = false;
+        Frame { data: self.frame.split().freeze(), timestamp: self.frame_timestamp }
+    }
+
+    pub(super) fn push(&mut self, sequence: u64, packet: &RtpPacket) -> Option<Frame> {
+        let continuous = self.last_sequence.is_none_or(|last| sequence == last + 1);
+        self.last_sequence = Some(sequence);
+        match self.codec {
+            RtpCodec::Opus => {
+                Some(Frame { data: packet.payload.clone(), timestamp: packet.timestamp })
+            },
+            RtpCodec::Vp9 => self.push_vp9(packet, continuous),
+            RtpCodec::Av1 => self.push_av1(packet, continuous),
+        }
+    }
+
+    fn push_vp9(&mut self, packet: &RtpPacket, continuous: bool) -> Option<Frame> {
+        let payload = &packet.payload;
+        let Some(descriptor_len) = vp9_descriptor_len(payload) else {
+            self.in_frame = false;
+            return None;
+        };
+        let flags = payload[0];
+        if flags & 0x08 != 0 {
+            self.begin_frame(packet.timestamp);
+        } else if !self.in_frame || !continuous || packet.timestamp != self.frame_timestamp {
+            self.in_frame = false;
+            return None;
+        }
+        self.frame.extend_from_slice(&payload[descriptor_len..]);
+        (flags & 0x04 != 0).then(|| self.finish_frame())
+    }
+
+    fn push_av1(&mut self, packet: &RtpPacket, continuous: bool) -> Option<Frame> {
+        let payload = &packet.payload;
+        let &header = payload.first()?;
+        let (continues_previous, continues_next) = (header & 0x80 != 0, header & 0x40 != 0);
+        let element_count = usize::from((header >> 4) & 0x03);
+
+        if !self.in_frame || packet.timestamp != self.frame_timestamp {
+            // There is no start flag, so a temporal unit is only known to be whole if its
+            // first packet directly follows the previous one.
+            self.begin_frame(packet.timestamp);
+            self.in_frame = continuous && !continues_previous;
+        } else if !continuous {
+            self.in_frame = false;
+        }
+        if !self.in_frame {
+            return None;
+        }
+
+        let mut pos = 1;
+        let mut index = 0;
+        while pos < payload.len() {
+            index += 1;
+            let len = if index == element_count {
+                payload.len() - pos
+            } else {
+                let Some((len, leb_len)) = read_leb128(&payload[pos..]) else {
+                    self.in_frame = false;
+                    return None;
+                };
+                pos += leb_len;
+                usize::try_from(len).unwrap_or(usize::MAX)
+            };
+            let Some(element) = payload.get(pos..pos.saturating_add(len)) else {
+                self.in_frame = false;
+                return None;
+            };
+            pos += len;
+            if index == 1 && !continues_previous && !self.obu.is_empty() {
+                // The previous packet promised a continuation that never came
+                self.in_frame = false;
+                return None;
+            }
+            self.obu.extend_from_slice(element);
+            if pos < payload.len() || !continues_next {
+                let obu = std::mem::take(&mut self.obu);
+                if (obu.first().map_or(0, |h| (h >> 3) & 0x0F)) != OBU_TEMPORAL_DELIMITER {
+                    av1_write_obu(&mut self.frame, &obu);
+                }
+            }
+        }
+
+        if packet.marker {
+            if !self.obu.is_empty() {
+                self.in_frame = false;
+                return None;
+            }
+            return Some(self.finish_frame());
+        }
+        None
+    }
+}
+
+/// Unwraps 32-bit RTP timestamps into microseconds since the first frame.
+pub(super) struct TimestampUnwrapper {
+    clock_rate: u64,
+    first: Option<u64>,
+    last: u64,
+}
+
+impl TimestampUnwrapper {
+    pub(super) const fn new(clock_rate: u64) -> Self {
+        Self { clock_rate, first: None, last: 0 }
+    }
+
+    pub(super) fn micros(&mut self, timestamp: u32) -> u64 {
+        let extended = if self.first.is_some() {
+            let delta =
+                i32::from_ne_bytes(timestamp.wrapping_sub(low_u32(self.last)).to_ne_bytes());
+            self.last.saturating_add_signed(i64::from(delta))
+        } else {
+            (1 << 32) | u64::from(timestamp)
+        };
+        self.last = extended;
+        let first = *self.first.get_or_insert(extended);
+        ticks_to_us(extended.saturating_sub(first), self.clock_rate)
+    }
+}
+
+/// Waits for the Start signal; returns `false` if the node should stop instead.
+pub(super) async fn wait_for_start(context: &mut NodeContext) -> bool {
+    loop {
+        match context.control_rx.recv().await {
+            Some(NodeControlMessage::Start) => return true,
+            Some(NodeControlMessage::UpdateParams(_)) => {},
+            Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+            Some(NodeControlMessage::Shutdown) | None => return false,
+        }
+    }
+}
+
+// --- Output node ---
+
+/// Sends encoded frames as an RTP stream over UDP.
+pub struct RtpOutputNode {
+    config: RtpOutputConfig,
+}
+
+impl RtpOutputNode {
+    /// Creates an RTP output node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the payload type or MTU is out of range.
+    pub fn new(config: RtpOutputConfig) -> Result<Self, StreamKitError> {
+        validate_payload_type(config.payload_type)?;
+        if !(MIN_MTU..=MAX_DATAGRAM_SIZE).contains(&config.mtu) {
+            return Err(StreamKitError::Configuration(format!(
+                "mtu must be between {MIN_MTU} and {MAX_DATAGRAM_SIZE}, got {}",
+                config.mtu
+            )));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            // For dynamic nodes, allow None to create a default instance for pin inspection
+            let config = if params.is_none() {
+                RtpOutputConfig {
+                    remote_address: "127.0.0.1:5004".to_string(),
+                    local_address: default_local_address(),
+                    codec: RtpCodec::default(),
+                    payload_type: None,
+                    ssrc: None,
+                    mtu: DEFAULT_MTU,
+                }
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+
+    async fn connect(&self) -> Result<UdpSocket, String> {
+        let socket = UdpSocket::bind(&self.config.local_address).await.map_err(|e| {
+            format!("Failed to bind RTP socket to {}: {e}", self.config.local_address)
+        })?;
+        socket
+            .connect(&self.config.remote_address)
+            .await
+            .map_err(|e| format!("Failed to resolve RTP destination: {e}"))?;
+        Ok(socket)
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for RtpOutputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![self.config.codec.packet_type()],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let socket = match self.connect().await {
+            Ok(socket) => socket,
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        let codec = self.config.codec;
+        let payload_type = self.config.payload_type.unwrap_or_else(|| codec.default_payload_type());
+        let ssrc = self.config.ssrc.unwrap_or_else(random_u32);
+        tracing::info!(
+            "RtpOutputNode sending {:?} to {} (payload type {}, SSRC {:#010x})",
+            codec,
+            self.config.remote_address,
+            payload_type,
+            ssrc
+        );
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut sequence = low_u16(u64::from(random_u32()));
+        let timestamp_offset = random_u32();
+        let mut packetizer =
+            Packetizer::new(codec, self.config.mtu, low_u16(u64::from(random_u32())));
+        let mut next_ticks: Option<u64> = None;
+        let mut buffer = BytesMut::with_capacity(self.config.mtu);
+
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break };
+                    let Packet::Binary { data, metadata, .. } = packet else {
+                        tracing::warn!("RtpOutputNode received non-binary packet, ignoring");
+                        stats_tracker.discarded();
+                        continue;
+                    };
+                    stats_tracker.received();
+
+                    let clock_rate = codec.clock_rate();
+                    let ticks = metadata
+                        .as_ref()
+                        .and_then(|m| m.timestamp_us)
+                        .map(|us| us_to_ticks(us, clock_rate))
+                        .or(next_ticks)
+                        .unwrap_or_default();
+                    let duration = metadata
+                        .as_ref()
+                        .and_then(|m| m.duration_us)
+                        .map(|us| us_to_ticks(us, clock_rate))
+                        .filter(|&d| d > 0)
+                        .unwrap_or_else(|| codec.default_frame_ticks());
+                    next_ticks = Some(ticks + duration);
+                    let timestamp = timestamp_offset.wrapping_add(low_u32(ticks));
+
+                    let payloads = packetizer.packetize(&data);
+                    let last = payloads.len().saturating_sub(1);
+                    for (i, payload) in payloads.into_iter().enumerate() {
+                        let rtp = RtpPacket {
+                            marker: codec.is_video() && i == last,
+                            payload_type,
+                            sequence,
+                            timestamp,
+                            ssrc,
+                            payload,
+                        };
+                        sequence = sequence.wrapping_add(1);
+                        buffer.clear();
+                        rtp.write(&mut buffer);
+                        if let Err(e) = socket.send(&buffer).await {
+                            // Unreachable receivers surface as transient ICMP errors
+                            tracing::debug!("RtpOutputNode failed to send packet: {}", e);
+                            stats_tracker.errored();
+                        }
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                }
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {}
+                    NodeControlMessage::Shutdown => break,
+                },
+            }
+        }
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, "input_closed");
+        Ok(())
+    }
+}
+
+// --- Input node ---
+
+/// Receives an RTP stream over UDP and emits the reassembled frames.
+pub struct RtpInputNode {
+    config: RtpInputConfig,
+}
+
+impl RtpInputNode {
+    /// Creates an RTP input node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the payload type or jitter buffer is out of range.
+    pub fn new(config: RtpInputConfig) -> Result<Self, StreamKitError> {
+        validate_payload_type(config.payload_type)?;
+        if config.jitter_buffer_ms > MAX_JITTER_BUFFER_MS {
+            return Err(StreamKitError::Configuration(format!(
+                "jitter_buffer_ms must be at most {MAX_JITTER_BUFFER_MS}, got {}",
+                config.jitter_buffer_ms
+            )));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            // For dynamic nodes, allow None to create a default instance for pin inspection
+            let config = if params.is_none() {
+                RtpInputConfig {
+                    bind_address: "0.0.0.0:5004".to_string(),
+                    codec: RtpCodec::default(),
+                    payload_type: None,
+                    ssrc: None,
+                    jitter_buffer_ms: DEFAULT_JITTER_BUFFER_MS,
+                }
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+
+    /// Checks a packet against the payload type and SSRC filters, locking onto the first
+    /// SSRC when none is configured.
+    fn accepts(&self, packet: &RtpPacket, ssrc: &mut Option<u32>) -> bool {
+        if self.config.payload_type.is_some_and(|pt| pt != packet.payload_type) {
+            return false;
+        }
+        if let Some(expected) = *ssrc {
+            return expected == packet.ssrc;
+        }
+        tracing::info!("RtpInputNode receiving SSRC {:#010x}", packet.ssrc);
+        *ssrc = Some(packet.ssrc);
+        true
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for RtpInputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: self.config.codec.packet_type(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        let socket = match UdpSocket::bind(&self.config.bind_address).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                let err_msg =
+                    format!("Failed to bind RTP socket to {}: {e}", self.config.bind_address);
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        tracing::info!(
+            "RtpInputNode listening for {:?} on {} (jitter buffer {} ms)",
+            self.config.codec,
+            socket
+                .local_addr()
+                .map_or_else(|_| self.config.bind_address.clone(), |a| a.to_string()),
+            self.config.jitter_buffer_ms
+        );
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        if !wait_for_start(&mut context).await {
+            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+            return Ok(());
+        }
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let codec = self.config.codec;
+        let mut jitter_buffer =
+            JitterBuffer::new(Duration::from_millis(self.config.jitter_buffer_ms));
+        let mut depacketizer = Depacketizer::new(codec);
+        let mut timestamps = TimestampUnwrapper::new(codec.clock_rate());
+        let mut ssrc = self.config.ssrc;
+        let mut frame_count = 0u64;
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+        let reason = loop {
+            let deadline = jitter_buffer.next_deadline();
+            tokio::select! {
+                received = socket.recv(&mut buf) => match received {
+                    Ok(len) => {
+                        stats_tracker.received();
+                        let accepted = RtpPacket::parse(&Bytes::copy_from_slice(&buf[..len]))
+                            .filter(|packet| self.accepts(packet, &mut ssrc))
+                            .is_some_and(|packet| jitter_buffer.push(packet, Instant::now()));
+                        if !accepted {
+                            stats_tracker.discarded();
+                        }
+                    },
+                    Err(e) => {
+                        tracing::debug!("RtpInputNode failed to receive packet: {}", e);
+                        stats_tracker.errored();
+                    },
+                },
+                () = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                    if deadline.is_some() => {},
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+
+            let mut output_closed = false;
+            while let Some((sequence, packet)) = jitter_buffer.pop(Instant::now()) {
+                let Some(frame) = depacketizer.push(sequence, &packet) else { continue };
+                let metadata = PacketMetadata {
+                    timestamp_us: Some(timestamps.micros(frame.timestamp)),
+                    duration_us: None,
+                    sequence: Some(frame_count),
+                };
+                frame_count += 1;
+                let packet = Packet::Binary {
+                    data: frame.data,
+                    content_type: None,
+                    metadata: Some(metadata),
+                };
+                if context.output_sender.send("out", packet).await.is_err() {
+                    output_closed = true;
+                    break;
+                }
+                stats_tracker.sent();
+            }
+            if output_closed {
+                tracing::debug!("Output channel closed, stopping node");
+                break "output_closed";
+            }
+            stats_tracker.maybe_send();
+        };
+
+        stats_tracker.force_send();
+        tracing::info!(
+            "RtpInputNode stopped after {} frames ({} packets lost)",
+            frame_count,
+            jitter_buffer.lost
+        );
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Registers the RTP transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_rtp_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let factory = RtpInputNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::rtp::input",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(RtpInputConfig))
+            .expect("RtpInputConfig schema should serialize to JSON"),
+        vec!["transport".to_string(), "rtp".to_string()],
+        false,
+        "Receives an Opus, VP9 or AV1 RTP stream over UDP, reorders it through a jitter \
+         buffer and emits the reassembled frames. \
+         Security: binds a local UDP port; restrict it via role allowlists.",
+    );
+
+    let factory = RtpOutputNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::rtp::output",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(RtpOutputConfig))
+            .expect("RtpOutputConfig schema should serialize to JSON"),
+        vec!["transport".to_string(), "rtp".to_string()],
+        false,
+        "Sends Opus, VP9 or AV1 frames as an RTP stream over UDP, fragmenting video frames to \
+         the configured MTU. \
+         Security: sends to arbitrary network addresses; restrict it via role allowlists.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    fn rtp(sequence: u16, timestamp: u32, marker: bool, payload: Bytes) -> RtpPacket {
+        RtpPacket { marker, payload_type: 96, sequence, timestamp, ssrc: 1, payload }
+    }
+
+    fn roundtrip(packetizer: &mut Packetizer, frames: &[Vec<u8>]) -> Vec<Bytes> {
+        let mut depacketizer = Depacketizer::new(packetizer.codec);
+        let mut sequence = 0u64;
+        let mut out = Vec::new();
+        for (n, frame) in frames.iter().enumerate() {
+            let payloads = packetizer.packetize(&Bytes::from(frame.clone()));
+            let last = payloads.len() - 1;
+            for (i, payload) in payloads.into_iter().enumerate() {
+                assert!(payload.len() <= packetizer.max_payload);
+                let packet = rtp(low_u16(sequence), u32::try_from(n).unwrap(), i == last, payload);
+                sequence += 1;
+                out.extend(depacketizer.push(sequence, &packet).map(|frame| frame.data));
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_rtp_packet_roundtrip() {
+        let packet = rtp(65535, 0xDEAD_BEEF, true, Bytes::from_static(b"payload"));
+        let mut buffer = BytesMut::new();
+        packet.write(&mut buffer);
+        assert_eq!(buffer.len(), RTP_HEADER_SIZE + 7);
+
+        let parsed = RtpPacket::parse(&buffer.freeze()).unwrap();
+        assert!(parsed.marker);
+        assert_eq!(parsed.payload_type, 96);
+        assert_eq!(parsed.sequence, 65535);
+        assert_eq!(parsed.timestamp, 0xDEAD_BEEF);
+        assert_eq!(parsed.payload, Bytes::from_static(b"payload"));
+
+        // One CSRC, a one-word header extension and two bytes of padding
+        let mut data = vec![0xB1, 0x60, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3];
+        data.extend_from_slice(&[0, 0, 0, 9]);
+        data.extend_from_slice(&[0xBE, 0xDE, 0, 1, 1, 2, 3, 4]);
+        data.extend_from_slice(&[0xAA, 0xBB, 0, 2]);
+        let parsed = RtpPacket::parse(&Bytes::from(data)).unwrap();
+        assert_eq!(parsed.payload_type, 96);
+        assert_eq!(parsed.payload, Bytes::from_static(&[0xAA, 0xBB]));
+    }
+
+    #[test]
+    fn test_vp9_fragmentation_roundtrip() {
+        let mut packetizer = Packetizer::new(RtpCodec::Vp9, MIN_MTU, 0x7FFF);
+        // A key frame spanning several packets, then a one-packet inter frame
+        let key: Vec<u8> = std::iter::once(0x82).chain(0..200).collect();
+        let inter = vec![0x86, 1, 2, 3];
+        let payloads = packetizer.packetize(&Bytes::from(key.clone()));
+        assert_eq!(payloads[0][0], 0x88);
+        assert_eq!(payloads.last().unwrap()[0], 0x84);
+        assert_eq!(packetizer.packetize(&Bytes::from(inter.clone()))[0][0], 0xCC);
+
+        let mut packetizer = Packetizer::new(RtpCodec::Vp9, MIN_MTU, 0);
+        let frames = roundtrip(&mut packetizer, &[key.clone(), inter.clone()]);
+        assert_eq!(frames, vec![Bytes::from(key), Bytes::from(inter)]);
+    }
+
+    #[test]
+    fn test_av1_fragmentation_roundtrip() {
+        // Temporal delimiter, sequence header and a frame OBU too large for one packet
+        let mut unit = AV1_TEMPORAL_DELIMITER.to_vec();
+        unit.extend_from_slice(&[0x0A, 3, 0xAA, 0xBB, 0xCC]);
+        let mut frame_obu = BytesMut::new();
+        frame_obu.put_u8(0x32);
+        write_leb128(&mut frame_obu, 150);
+        frame_obu.extend(0..150u8);
+        unit.extend_from_slice(&frame_obu);
+
+        let mut packetizer = Packetizer::new(RtpCodec::Av1, MIN_MTU, 0);
+        let payloads = packetizer.packetize(&Bytes::from(unit.clone()));
+        assert!(payloads.len() > 2);
+        assert_eq!(payloads[0][0] & 0x08, 0x08, "first packet starts a coded video sequence");
+        assert_eq!(payloads[1][0] & 0xC0, 0xC0, "middle packets continue an OBU both ways");
+
+        let frames = roundtrip(&mut packetizer, &[unit.clone()]);
+        assert_eq!(frames, vec![Bytes::from(unit)]);
+    }
+
+    #[test]
+    fn test_depacketizer_drops_damaged_frames() {
+        let mut packetizer = Packetizer::new(RtpCodec::Vp9, MIN_MTU, 0);
+        let frame = vec![0x86; 140];
+        let payloads = packetizer.packetize(&Bytes::from(frame.clone()));
+        assert_eq!(payloads.len(), 3);
+
+        let mut depacketizer = Depacketizer::new(RtpCodec::Vp9);
+        // The middle packet of the first frame is lost
+        assert!(depacketizer.push(0, &rtp(0, 0, false, payloads[0].clone())).is_none());
+        assert!(depacketizer.push(2, &rtp(2, 0, true, payloads[2].clone())).is_none());
+        // The next frame is whole again
+        let mut frames = Vec::new();
+        for (i, payload) in
+            packetizer.packetize(&Bytes::from(frame.clone())).into_iter().enumerate()
+        {
+            let sequence = 3 + u64::try_from(i).unwrap();
+            frames.extend(depacketizer.push(sequence, &rtp(low_u16(sequence), 1, i == 2, payload)));
+        }
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data, Bytes::from(frame));
+    }
+
+    #[test]
+    fn test_jitter_buffer_reorders_and_skips_losses() {
+        let start = Instant::now();
+        let latency = Duration::from_millis(50);
+        let mut buffer = JitterBuffer::new(latency);
+        let packet = |sequence| rtp(sequence, 0, false, Bytes::new());
+
+        // Sequence numbers wrap around 65535 -> 0
+        assert!(buffer.push(packet(65534), start));
+        assert!(buffer.push(packet(0), start));
+        assert!(buffer.push(packet(65535), start));
+        assert!(!buffer.push(packet(0), start), "duplicate");
+        assert!(buffer.pop(start).is_none(), "first packet waits for reordering");
+
+        let released: Vec<u16> =
+            std::iter::from_fn(|| buffer.pop(start + latency)).map(|(_, p)| p.sequence).collect();
+        assert_eq!(released, vec![65534, 65535, 0]);
+
+        // Packet 1 is lost; 2 is released once it has waited out the latency
+        let later = start + latency * 2;
+        assert!(buffer.push(packet(2), later));
+        assert!(buffer.pop(later).is_none());
+        assert_eq!(buffer.next_deadline(), Some(later + latency));
+        assert_eq!(buffer.pop(later + latency).unwrap().1.sequence, 2);
+        assert_eq!(buffer.lost, 1);
+        assert!(!buffer.push(packet(1), later + latency), "too late");
+    }
+
+    #[tokio::test]
+    async fn test_rtp_output_to_input() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = receiver.local_addr().unwrap().to_string();
+        let config: RtpOutputConfig = serde_json::from_value(serde_json::json!({
+            "remote_address": address,
+            "local_address": "127.0.0.1:0",
+            "ssrc": 42
+        }))
+        .unwrap();
+        let node = RtpOutputNode::new(config).unwrap();
+
+        let (tx, rx) = mpsc::channel(10);
+        let (context, _sender, mut state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 10);
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        for i in 0..2u64 {
+            let metadata = PacketMetadata {
+                timestamp_us: Some(i * 20_000),
+                duration_us: None,
+                sequence: None,
+            };
+            let packet = Packet::Binary {
+                data: Bytes::from(vec![0xFC, u8::try_from(i).unwrap()]),
+                content_type: None,
+                metadata: Some(metadata),
+            };
+            tx.send(packet).await.unwrap();
+        }
+        drop(tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let mut buf = [0u8; 1500];
+        let mut packets = Vec::new();
+        for _ in 0..2 {
+            let len = receiver.recv(&mut buf).await.unwrap();
+            packets.push(RtpPacket::parse(&Bytes::copy_from_slice(&buf[..len])).unwrap());
+        }
+        assert_eq!(packets[0].ssrc, 42);
+        assert_eq!(packets[0].payload_type, 111);
+        assert_eq!(packets[1].sequence, packets[0].sequence.wrapping_add(1));
+        assert_eq!(packets[1].timestamp.wrapping_sub(packets[0].timestamp), 960);
+        assert_eq!(packets[1].payload, Bytes::from_static(&[0xFC, 1]));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config: RtpOutputConfig = serde_json::from_value(serde_json::json!({
+            "remote_address": "127.0.0.1:5004",
+            "mtu": 20
+        }))
+        .unwrap();
+        assert!(RtpOutputNode::new(config).is_err());
+
+        let config: RtpInputConfig = serde_json::from_value(serde_json::json!({
+            "bind_address": "127.0.0.1:0",
+            "payload_type": 200
+        }))
+        .unwrap();
+        assert!(RtpInputNode::new(config).is_err());
+    }
+}
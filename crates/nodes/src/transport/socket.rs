@@ -0,0 +1,926 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Raw UDP and TCP socket nodes.
+//!
+//! These nodes move opaque bytes between a pipeline and a socket, for custom hardware
+//! encoders, test harnesses and other tools that speak neither RTP, SRT nor HTTP:
+//!
+//! - `transport::socket::udp_in` emits one binary packet per received datagram.
+//! - `transport::socket::udp_out` sends each binary packet as datagrams of at most
+//!   `max_datagram_size` bytes.
+//! - `transport::socket::tcp_in` emits the received byte stream in chunks of up to
+//!   `chunk_size` bytes.
+//! - `transport::socket::tcp_out` writes binary packets to the byte stream.
+//!
+//! The TCP nodes either connect to `address` or listen on it and serve the first client that
+//! connects. No framing is added, so packet boundaries are not preserved over TCP.
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::net::SocketAddr;
+use std::time::Duration;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::registry::StaticPins;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality,
+    ProcessorNode, StreamKitError,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpSocket, TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+
+/// Largest UDP payload over IPv4.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+/// Fits an Ethernet MTU after the IPv4 and UDP headers.
+const DEFAULT_OUTPUT_DATAGRAM_SIZE: usize = 1472;
+const DEFAULT_CHUNK_SIZE: usize = 65_536;
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 5000;
+
+/// How a TCP node establishes its connection.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TcpMode {
+    /// Connect to a server at `address`
+    #[default]
+    Connect,
+    /// Listen on `address` and serve the first client that connects
+    Listen,
+}
+
+/// Configuration for `transport::socket::udp_in`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct UdpInputConfig {
+    /// Local `host:port` to receive datagrams on
+    pub bind_address: String,
+    /// Largest datagram accepted; longer datagrams are truncated by the OS
+    #[serde(default = "default_input_datagram_size")]
+    #[schemars(range(min = 1, max = 65507))]
+    pub max_datagram_size: usize,
+    /// Content type attached to the emitted packets, e.g. `video/mp2t`
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+/// Configuration for `transport::socket::udp_out`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct UdpOutputConfig {
+    /// Destination `host:port`
+    #[schemars(extend("sensitive" = true))]
+    pub remote_address: String,
+    /// Local `host:port` to send from (default: any interface, ephemeral port)
+    #[serde(default = "default_local_address")]
+    pub local_address: String,
+    /// Largest datagram sent; longer packets are split across several datagrams
+    #[serde(default = "default_output_datagram_size")]
+    #[schemars(range(min = 1, max = 65507))]
+    pub max_datagram_size: usize,
+}
+
+/// Configuration for `transport::socket::tcp_in`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct TcpInputConfig {
+    /// Whether to connect to `address` or listen on it
+    #[serde(default)]
+    pub mode: TcpMode,
+    /// Server `host:port` in connect mode, local `host:port` in listen mode
+    #[schemars(extend("sensitive" = true))]
+    pub address: String,
+    /// Largest chunk emitted per packet, in bytes
+    #[serde(default = "default_chunk_size")]
+    #[schemars(range(min = 1, max = 16_777_216))]
+    pub chunk_size: usize,
+    /// Kernel receive buffer size in bytes (default: OS default)
+    #[serde(default)]
+    pub socket_buffer_size: Option<u32>,
+    /// Content type attached to the emitted packets, e.g. `video/mp2t`
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// How long connect mode waits for the server before failing
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+}
+
+/// Configuration for `transport::socket::tcp_out`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct TcpOutputConfig {
+    /// Whether to connect to `address` or listen on it
+    #[serde(default)]
+    pub mode: TcpMode,
+    /// Server `host:port` in connect mode, local `host:port` in listen mode
+    #[schemars(extend("sensitive" = true))]
+    pub address: String,
+    /// Kernel send buffer size in bytes (default: OS default)
+    #[serde(default)]
+    pub socket_buffer_size: Option<u32>,
+    /// Send small writes immediately instead of coalescing them (disables Nagle's algorithm)
+    #[serde(default = "default_nodelay")]
+    pub nodelay: bool,
+    /// How long connect mode waits for the server before failing
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+}
+
+const fn default_input_datagram_size() -> usize {
+    MAX_DATAGRAM_SIZE
+}
+
+const fn default_output_datagram_size() -> usize {
+    DEFAULT_OUTPUT_DATAGRAM_SIZE
+}
+
+fn default_local_address() -> String {
+    "0.0.0.0:0".to_string()
+}
+
+const fn default_chunk_size() -> usize {
+    DEFAULT_CHUNK_SIZE
+}
+
+const fn default_nodelay() -> bool {
+    true
+}
+
+const fn default_connect_timeout_ms() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_MS
+}
+
+fn validate_datagram_size(size: usize) -> Result<(), StreamKitError> {
+    if (1..=MAX_DATAGRAM_SIZE).contains(&size) {
+        Ok(())
+    } else {
+        Err(StreamKitError::Configuration(format!(
+            "max_datagram_size must be between 1 and {MAX_DATAGRAM_SIZE}, got {size}"
+        )))
+    }
+}
+
+/// Waits for the Start signal; returns `false` if the node should stop instead.
+async fn wait_for_start(context: &mut NodeContext) -> bool {
+    loop {
+        match context.control_rx.recv().await {
+            Some(NodeControlMessage::Start) => return true,
+            Some(NodeControlMessage::UpdateParams(_)) => {},
+            Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+            Some(NodeControlMessage::Shutdown) | None => return false,
+        }
+    }
+}
+
+/// Handles control messages until Shutdown; never returns if the control channel closes.
+async fn wait_for_shutdown(control_rx: &mut mpsc::Receiver<NodeControlMessage>) {
+    while let Some(msg) = control_rx.recv().await {
+        match msg {
+            NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+            NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+            NodeControlMessage::Shutdown => return,
+        }
+    }
+    std::future::pending::<()>().await;
+}
+
+async fn resolve(address: &str) -> Result<SocketAddr, String> {
+    tokio::net::lookup_host(address)
+        .await
+        .map_err(|e| format!("Failed to resolve {address}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("{address} resolved to no address"))
+}
+
+/// Connects to or accepts a TCP peer as configured.
+async fn open_tcp(
+    mode: TcpMode,
+    address: &str,
+    socket_buffer_size: Option<u32>,
+    send_buffer: bool,
+    connect_timeout: Duration,
+) -> Result<TcpStream, String> {
+    let addr = resolve(address).await?;
+    let socket = if addr.is_ipv6() { TcpSocket::new_v6() } else { TcpSocket::new_v4() }
+        .map_err(|e| format!("Failed to create TCP socket: {e}"))?;
+    if let Some(size) = socket_buffer_size {
+        let result = if send_buffer {
+            socket.set_send_buffer_size(size)
+        } else {
+            socket.set_recv_buffer_size(size)
+        };
+        result.map_err(|e| format!("Failed to set socket buffer size: {e}"))?;
+    }
+
+    match mode {
+        TcpMode::Connect => tokio::time::timeout(connect_timeout, socket.connect(addr))
+            .await
+            .map_err(|_| {
+                format!(
+                    "Timed out connecting to {address} after {} ms",
+                    connect_timeout.as_millis()
+                )
+            })?
+            .map_err(|e| format!("Failed to connect to {address}: {e}")),
+        TcpMode::Listen => {
+            socket.set_reuseaddr(true).map_err(|e| format!("Failed to set SO_REUSEADDR: {e}"))?;
+            socket
+                .bind(addr)
+                .map_err(|e| format!("Failed to bind TCP socket to {address}: {e}"))?;
+            let listener: TcpListener =
+                socket.listen(1).map_err(|e| format!("Failed to listen on {address}: {e}"))?;
+            tracing::info!(
+                "Listening for a TCP client on {}",
+                listener.local_addr().map_or_else(|_| address.to_string(), |a| a.to_string())
+            );
+            // Accepted sockets inherit the listener's buffer sizes
+            let (stream, peer) =
+                listener.accept().await.map_err(|e| format!("Failed to accept TCP client: {e}"))?;
+            tracing::info!("Accepted TCP client {}", peer);
+            Ok(stream)
+        },
+    }
+}
+
+/// Opens the TCP connection, giving up if the node is shut down first. Returns `Ok(None)` on
+/// shutdown.
+async fn open_tcp_until_shutdown(
+    open: impl std::future::Future<Output = Result<TcpStream, String>>,
+    context: &mut NodeContext,
+) -> Result<Option<TcpStream>, String> {
+    tokio::select! {
+        result = open => result.map(Some),
+        () = wait_for_shutdown(&mut context.control_rx) => Ok(None),
+    }
+}
+
+// --- UDP input ---
+
+/// Emits each received UDP datagram as a binary packet.
+pub struct UdpInputNode {
+    config: UdpInputConfig,
+}
+
+impl UdpInputNode {
+    /// Creates a UDP input node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `max_datagram_size` is out of range.
+    pub fn new(config: UdpInputConfig) -> Result<Self, StreamKitError> {
+        validate_datagram_size(config.max_datagram_size)?;
+        Ok(Self { config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for UdpInputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![binary_output_pin()]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        let socket = match UdpSocket::bind(&self.config.bind_address).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                let err_msg =
+                    format!("Failed to bind UDP socket to {}: {e}", self.config.bind_address);
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        tracing::info!(
+            "UdpInputNode listening on {}",
+            socket
+                .local_addr()
+                .map_or_else(|_| self.config.bind_address.clone(), |a| a.to_string())
+        );
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        if !wait_for_start(&mut context).await {
+            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+            return Ok(());
+        }
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let content_type = self.config.content_type.clone().map(Cow::Owned);
+        let mut buf = BytesMut::new();
+
+        let reason = loop {
+            buf.reserve(self.config.max_datagram_size);
+            tokio::select! {
+                received = socket.recv_buf(&mut buf) => match received {
+                    Ok(_) => {
+                        stats_tracker.received();
+                        let packet = Packet::Binary {
+                            data: buf.split().freeze(),
+                            content_type: content_type.clone(),
+                            metadata: None,
+                        };
+                        if context.output_sender.send("out", packet).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            break "output_closed";
+                        }
+                        stats_tracker.sent();
+                    },
+                    Err(e) => {
+                        tracing::debug!("UdpInputNode failed to receive datagram: {}", e);
+                        stats_tracker.errored();
+                    },
+                },
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+            stats_tracker.maybe_send();
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+// --- UDP output ---
+
+/// Sends binary packets as UDP datagrams.
+pub struct UdpOutputNode {
+    config: UdpOutputConfig,
+}
+
+impl UdpOutputNode {
+    /// Creates a UDP output node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `max_datagram_size` is out of range.
+    pub fn new(config: UdpOutputConfig) -> Result<Self, StreamKitError> {
+        validate_datagram_size(config.max_datagram_size)?;
+        Ok(Self { config })
+    }
+
+    async fn connect(&self) -> Result<UdpSocket, String> {
+        let socket = UdpSocket::bind(&self.config.local_address).await.map_err(|e| {
+            format!("Failed to bind UDP socket to {}: {e}", self.config.local_address)
+        })?;
+        socket
+            .connect(&self.config.remote_address)
+            .await
+            .map_err(|e| format!("Failed to resolve UDP destination: {e}"))?;
+        Ok(socket)
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for UdpOutputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![binary_input_pin()]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let socket = match self.connect().await {
+            Ok(socket) => socket,
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        tracing::info!("UdpOutputNode sending to {}", self.config.remote_address);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let reason = loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else { break "input_closed" };
+                    let Packet::Binary { data, .. } = packet else {
+                        tracing::warn!("UdpOutputNode received non-binary packet, ignoring");
+                        stats_tracker.discarded();
+                        continue;
+                    };
+                    stats_tracker.received();
+                    for datagram in data.chunks(self.config.max_datagram_size) {
+                        if let Err(e) = socket.send(datagram).await {
+                            // Unreachable receivers surface as transient ICMP errors
+                            tracing::debug!("UdpOutputNode failed to send datagram: {}", e);
+                            stats_tracker.errored();
+                        }
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                },
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+// --- TCP input ---
+
+/// Emits the byte stream received over TCP as binary packets.
+pub struct TcpInputNode {
+    config: TcpInputConfig,
+}
+
+impl TcpInputNode {
+    /// Creates a TCP input node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `chunk_size` is out of range.
+    pub fn new(config: TcpInputConfig) -> Result<Self, StreamKitError> {
+        if !(1..=MAX_CHUNK_SIZE).contains(&config.chunk_size) {
+            return Err(StreamKitError::Configuration(format!(
+                "chunk_size must be between 1 and {MAX_CHUNK_SIZE}, got {}",
+                config.chunk_size
+            )));
+        }
+        Ok(Self { config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for TcpInputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![binary_output_pin()]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        if !wait_for_start(&mut context).await {
+            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+            return Ok(());
+        }
+
+        let open = open_tcp(
+            self.config.mode,
+            &self.config.address,
+            self.config.socket_buffer_size,
+            false,
+            Duration::from_millis(self.config.connect_timeout_ms),
+        );
+        let mut stream = match open_tcp_until_shutdown(open, &mut context).await {
+            Ok(Some(stream)) => stream,
+            Ok(None) => {
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                return Ok(());
+            },
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let content_type = self.config.content_type.clone().map(Cow::Owned);
+        let mut buf = BytesMut::new();
+
+        let reason = loop {
+            buf.resize(self.config.chunk_size, 0);
+            tokio::select! {
+                read = stream.read(&mut buf) => match read {
+                    Ok(0) => {
+                        tracing::info!("TcpInputNode peer closed the connection");
+                        break "peer_closed";
+                    },
+                    Ok(len) => {
+                        stats_tracker.received();
+                        let packet = Packet::Binary {
+                            data: buf.split_to(len).freeze(),
+                            content_type: content_type.clone(),
+                            metadata: None,
+                        };
+                        if context.output_sender.send("out", packet).await.is_err() {
+                            tracing::debug!("Output channel closed, stopping node");
+                            break "output_closed";
+                        }
+                        stats_tracker.sent();
+                    },
+                    Err(e) => {
+                        let err_msg = format!("TCP read failed: {e}");
+                        stats_tracker.force_send();
+                        state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                        return Err(StreamKitError::Runtime(err_msg));
+                    },
+                },
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+            stats_tracker.maybe_send();
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+// --- TCP output ---
+
+/// Writes binary packets to a TCP byte stream.
+pub struct TcpOutputNode {
+    config: TcpOutputConfig,
+}
+
+impl TcpOutputNode {
+    /// Creates a TCP output node.
+    ///
+    /// # Errors
+    ///
+    /// Currently infallible; kept fallible like the other transport constructors.
+    pub const fn new(config: TcpOutputConfig) -> Result<Self, StreamKitError> {
+        Ok(Self { config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for TcpOutputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![binary_input_pin()]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let open = open_tcp(
+            self.config.mode,
+            &self.config.address,
+            self.config.socket_buffer_size,
+            true,
+            Duration::from_millis(self.config.connect_timeout_ms),
+        );
+        let mut stream = match open_tcp_until_shutdown(open, &mut context).await {
+            Ok(Some(stream)) => stream,
+            Ok(None) => {
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                return Ok(());
+            },
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        if let Err(e) = stream.set_nodelay(self.config.nodelay) {
+            tracing::debug!("TcpOutputNode failed to set TCP_NODELAY: {}", e);
+        }
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+
+        let reason = loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else {
+                        let _ = stream.shutdown().await;
+                        break "input_closed";
+                    };
+                    let Packet::Binary { data, .. } = packet else {
+                        tracing::warn!("TcpOutputNode received non-binary packet, ignoring");
+                        stats_tracker.discarded();
+                        continue;
+                    };
+                    stats_tracker.received();
+                    if let Err(e) = stream.write_all(&data).await {
+                        tracing::info!("TcpOutputNode peer closed the connection: {}", e);
+                        stats_tracker.errored();
+                        break "peer_closed";
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                },
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+fn binary_input_pin() -> InputPin {
+    InputPin {
+        name: "in".to_string(),
+        accepts_types: vec![PacketType::Binary],
+        cardinality: PinCardinality::One,
+    }
+}
+
+fn binary_output_pin() -> OutputPin {
+    OutputPin {
+        name: "out".to_string(),
+        produces_type: PacketType::Binary,
+        cardinality: PinCardinality::Broadcast,
+    }
+}
+
+/// Registers the socket transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_socket_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let categories = || vec!["transport".to_string(), "socket".to_string()];
+    let source_pins = || StaticPins { inputs: vec![], outputs: vec![binary_output_pin()] };
+    let sink_pins = || StaticPins { inputs: vec![binary_input_pin()], outputs: vec![] };
+
+    registry.register_static_with_description(
+        "transport::socket::udp_in",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(UdpInputNode::new(config)?))
+        },
+        serde_json::to_value(schema_for!(UdpInputConfig))
+            .expect("UdpInputConfig schema should serialize to JSON"),
+        source_pins(),
+        categories(),
+        false,
+        "Receives UDP datagrams and emits each one as a binary packet. \
+         Security: binds a local UDP port; restrict it via role allowlists.",
+    );
+
+    registry.register_static_with_description(
+        "transport::socket::udp_out",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(UdpOutputNode::new(config)?))
+        },
+        serde_json::to_value(schema_for!(UdpOutputConfig))
+            .expect("UdpOutputConfig schema should serialize to JSON"),
+        sink_pins(),
+        categories(),
+        false,
+        "Sends binary packets as UDP datagrams, splitting packets larger than the configured \
+         datagram size. \
+         Security: sends to arbitrary network addresses; restrict it via role allowlists.",
+    );
+
+    registry.register_static_with_description(
+        "transport::socket::tcp_in",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(TcpInputNode::new(config)?))
+        },
+        serde_json::to_value(schema_for!(TcpInputConfig))
+            .expect("TcpInputConfig schema should serialize to JSON"),
+        source_pins(),
+        categories(),
+        false,
+        "Connects to a TCP server, or accepts one client, and emits the received byte stream \
+         as binary chunks. \
+         Security: binds local ports and contacts arbitrary addresses; restrict it via role \
+         allowlists.",
+    );
+
+    registry.register_static_with_description(
+        "transport::socket::tcp_out",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(TcpOutputNode::new(config)?))
+        },
+        serde_json::to_value(schema_for!(TcpOutputConfig))
+            .expect("TcpOutputConfig schema should serialize to JSON"),
+        sink_pins(),
+        categories(),
+        false,
+        "Connects to a TCP server, or accepts one client, and writes binary packets to the \
+         byte stream. \
+         Security: binds local ports and contacts arbitrary addresses; restrict it via role \
+         allowlists.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, assert_state_update,
+        create_test_context,
+    };
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use streamkit_core::NodeState;
+
+    fn binary(data: &'static [u8]) -> Packet {
+        Packet::Binary { data: Bytes::from_static(data), content_type: None, metadata: None }
+    }
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config: UdpOutputConfig = serde_json::from_value(serde_json::json!({
+            "remote_address": "127.0.0.1:5000",
+            "max_datagram_size": 70000
+        }))
+        .unwrap();
+        assert!(UdpOutputNode::new(config).is_err());
+
+        let config: TcpInputConfig = serde_json::from_value(serde_json::json!({
+            "address": "127.0.0.1:5000",
+            "chunk_size": 0
+        }))
+        .unwrap();
+        assert!(TcpInputNode::new(config).is_err());
+
+        let config: TcpOutputConfig =
+            serde_json::from_value(serde_json::json!({"address": "127.0.0.1:5000"})).unwrap();
+        assert_eq!(config.mode, TcpMode::Connect);
+        assert!(config.nodelay);
+    }
+
+    #[tokio::test]
+    async fn test_udp_output_splits_large_packets() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let config: UdpOutputConfig = serde_json::from_value(serde_json::json!({
+            "remote_address": receiver.local_addr().unwrap().to_string(),
+            "local_address": "127.0.0.1:0",
+            "max_datagram_size": 4
+        }))
+        .unwrap();
+        let node = UdpOutputNode::new(config).unwrap();
+
+        let (tx, rx) = mpsc::channel(10);
+        let (context, _sender, mut state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 10);
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        tx.send(binary(b"abcdefghij")).await.unwrap();
+        drop(tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let mut buf = [0u8; 16];
+        let mut datagrams = Vec::new();
+        for _ in 0..3 {
+            let len = receiver.recv(&mut buf).await.unwrap();
+            datagrams.push(buf[..len].to_vec());
+        }
+        assert_eq!(datagrams, vec![b"abcd".to_vec(), b"efgh".to_vec(), b"ij".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn test_udp_input_emits_datagrams() {
+        let address = format!("127.0.0.1:{}", free_port());
+        let config: UdpInputConfig = serde_json::from_value(serde_json::json!({
+            "bind_address": address,
+            "content_type": "video/mp2t"
+        }))
+        .unwrap();
+        let node = UdpInputNode::new(config).unwrap();
+
+        let (mut context, sender, mut state_rx) = create_test_context(HashMap::new(), 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_update(&mut state_rx, |s| matches!(s, NodeState::Ready), "Ready").await;
+        control_tx.send(NodeControlMessage::Start).await.unwrap();
+        assert_state_running(&mut state_rx).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.send_to(b"hello", &address).await.unwrap();
+        let (_, pin, packet) = sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(pin, "out");
+        let Packet::Binary { data, content_type, .. } = packet else { panic!("expected binary") };
+        assert_eq!(data, Bytes::from_static(b"hello"));
+        assert_eq!(content_type.as_deref(), Some("video/mp2t"));
+
+        control_tx.send(NodeControlMessage::Shutdown).await.unwrap();
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tcp_input_listen_emits_chunks() {
+        let address = format!("127.0.0.1:{}", free_port());
+        let config: TcpInputConfig = serde_json::from_value(serde_json::json!({
+            "mode": "listen",
+            "address": address,
+            "chunk_size": 4
+        }))
+        .unwrap();
+        let node = TcpInputNode::new(config).unwrap();
+
+        let (mut context, sender, mut state_rx) = create_test_context(HashMap::new(), 10);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        context.control_rx = control_rx;
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_update(&mut state_rx, |s| matches!(s, NodeState::Ready), "Ready").await;
+        control_tx.send(NodeControlMessage::Start).await.unwrap();
+
+        // The listener is bound after Start, so retry until it accepts
+        let mut client = loop {
+            if let Ok(stream) = TcpStream::connect(&address).await {
+                break stream;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_state_running(&mut state_rx).await;
+
+        client.write_all(b"abcdefghij").await.unwrap();
+        client.shutdown().await.unwrap();
+        drop(client);
+
+        let mut received = Vec::new();
+        while received.len() < 10 {
+            let (_, _, packet) = sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+            let Packet::Binary { data, .. } = packet else { panic!("expected binary") };
+            assert!(data.len() <= 4);
+            received.extend_from_slice(&data);
+        }
+        assert_eq!(received, b"abcdefghij");
+
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tcp_output_connect_writes_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config: TcpOutputConfig = serde_json::from_value(serde_json::json!({
+            "address": listener.local_addr().unwrap().to_string()
+        }))
+        .unwrap();
+        let node = TcpOutputNode::new(config).unwrap();
+
+        let (tx, rx) = mpsc::channel(10);
+        let (context, _sender, mut state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 10);
+        let handle = tokio::spawn(async move { Box::new(node).run(context).await });
+        let (mut server, _) = listener.accept().await.unwrap();
+        assert_state_initializing(&mut state_rx).await;
+        assert_state_running(&mut state_rx).await;
+
+        tx.send(binary(b"hello ")).await.unwrap();
+        tx.send(binary(b"world")).await.unwrap();
+        drop(tx);
+        assert_state_stopped(&mut state_rx).await;
+        handle.await.unwrap().unwrap();
+
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"hello world");
+    }
+}
@@ -0,0 +1,1152 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! SRT live-mode connections: the HSv5 handshake in caller, listener and rendezvous modes, then
+//! ARQ (ACK/NAK driven retransmission) and timestamp-based packet delivery (TSBPD).
+//!
+//! A connection owns no task. The node feeds it datagrams and timer ticks, and drains the
+//! packets whose delivery time has come.
+
+use super::crypto::{random_bytes, KmError, StreamKey};
+use super::packet::{
+    decode_stream_id, encode_stream_id, hs_req, parse_hs_req, seq_add, seq_offset, Control,
+    DataPacket, Extension, Handshake, Packet, EXT_HSREQ, EXT_HSRSP, EXT_KMREQ, EXT_KMRSP, EXT_SID,
+    HS_AGREEMENT, HS_CONCLUSION, HS_EXT_CONFIG, HS_EXT_HSREQ, HS_EXT_KMREQ, HS_INDUCTION,
+    HS_REJECTION_BASE, HS_WAVEAHAND, KEY_EVEN, REJ_BADSECRET, REJ_UNSECURE, REJ_VERSION, SEQ_MASK,
+    SRT_MAGIC,
+};
+use bytes::{Bytes, BytesMut};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// SRT version announced in HSREQ (1.5.0).
+const SRT_VERSION: u32 = 0x0001_0500;
+/// TSBPD send/receive, encryption, too-late packet drop, periodic NAK and retransmission flag.
+const HSREQ_FLAGS: u32 = 0x3F;
+/// UDT stream type carried by the induction request (datagram).
+const UDT_DGRAM: u16 = 2;
+
+const HANDSHAKE_INTERVAL: Duration = Duration::from_millis(250);
+const ACK_INTERVAL: Duration = Duration::from_millis(10);
+const MIN_NAK_INTERVAL: Duration = Duration::from_millis(20);
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(1);
+const PEER_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+const INITIAL_RTT: Duration = Duration::from_millis(100);
+
+const MTU: u32 = 1500;
+const FLOW_WINDOW: u32 = 8192;
+/// Largest payload of a data packet: seven MPEG-TS packets.
+pub(super) const MAX_PAYLOAD_SIZE: usize = 1316;
+/// Largest datagram accepted, leaving room for oversized peers.
+pub(super) const MAX_DATAGRAM_SIZE: usize = 1500;
+/// Packets kept for retransmission before the oldest are dropped.
+const MAX_SEND_BUFFER: usize = 8192;
+/// Loss ranges reported by one periodic NAK.
+const MAX_NAK_RANGES: usize = 128;
+const MAX_PENDING_ACKS: usize = 64;
+
+/// Settings shared by every mode.
+#[derive(Clone)]
+pub(super) struct SrtOptions {
+    pub latency: Duration,
+    pub passphrase: Option<String>,
+    pub key_length: usize,
+    pub stream_id: Option<String>,
+    pub connect_timeout: Duration,
+}
+
+impl SrtOptions {
+    fn latency_ms(&self) -> u16 {
+        u16::try_from(self.latency.as_millis()).unwrap_or(u16::MAX)
+    }
+}
+
+/// Handshake results a connection is built from.
+struct Negotiated {
+    peer: SocketAddr,
+    peer_id: u32,
+    send_isn: u32,
+    recv_isn: u32,
+    latency: Duration,
+    key: Option<StreamKey>,
+    stream_id: Option<String>,
+    /// Answer to a repeated handshake when the peer missed ours
+    handshake_response: Option<Bytes>,
+}
+
+fn random_u32() -> Result<u32, String> {
+    random_bytes::<4>().map(u32::from_be_bytes)
+}
+
+fn micros_u32(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros()).unwrap_or(u32::MAX)
+}
+
+fn encode(packet: &Packet) -> Bytes {
+    let mut out = BytesMut::new();
+    packet.write(&mut out);
+    out.freeze()
+}
+
+fn handshake_packet(dest: u32, handshake: Handshake) -> Bytes {
+    encode(&Packet::Control { timestamp: 0, dest, control: Control::Handshake(handshake) })
+}
+
+fn peer_ip(addr: SocketAddr) -> [u8; 16] {
+    let mut ip = [0u8; 16];
+    match addr {
+        SocketAddr::V4(v4) => ip[..4].copy_from_slice(&v4.ip().octets()),
+        SocketAddr::V6(v6) => ip.copy_from_slice(&v6.ip().octets()),
+    }
+    ip
+}
+
+const fn rejection_reason(kind: u32) -> Option<u32> {
+    if kind >= HS_REJECTION_BASE && kind < HS_AGREEMENT {
+        Some(kind - HS_REJECTION_BASE)
+    } else {
+        None
+    }
+}
+
+fn rejection_error(reason: u32) -> String {
+    match reason {
+        REJ_BADSECRET => "SRT peer rejected the connection: wrong passphrase".to_string(),
+        REJ_UNSECURE => {
+            "SRT peer rejected the connection: encryption is configured on one side only"
+                .to_string()
+        },
+        REJ_VERSION => "SRT peer rejected the connection: HSv5 required".to_string(),
+        other => format!("SRT peer rejected the connection (reason {other})"),
+    }
+}
+
+/// Latency both sides settle on: the largest delay either asked for.
+fn negotiated_latency(options: &SrtOptions, hs_ext: &[u8]) -> Duration {
+    let (recv, send) = parse_hs_req(hs_ext).unwrap_or_default();
+    options.latency.max(Duration::from_millis(u64::from(recv.max(send))))
+}
+
+fn base_handshake(options: &SrtOptions, own_id: u32, isn: u32, peer: SocketAddr) -> Handshake {
+    // Advertised as PBKEYLEN / 8
+    let encryption = if options.passphrase.is_some() {
+        u16::try_from(options.key_length / 8).unwrap_or_default()
+    } else {
+        0
+    };
+    Handshake {
+        version: 5,
+        encryption,
+        extension: 0,
+        initial_seq: isn,
+        mtu: MTU,
+        flow_window: FLOW_WINDOW,
+        kind: HS_CONCLUSION,
+        socket_id: own_id,
+        cookie: 0,
+        peer_ip: peer_ip(peer),
+        extensions: Vec::new(),
+    }
+}
+
+/// The conclusion sent by a caller or rendezvous initiator.
+fn conclusion_request(
+    options: &SrtOptions,
+    own_id: u32,
+    isn: u32,
+    cookie: u32,
+    peer: SocketAddr,
+    key: Option<&StreamKey>,
+) -> Result<Handshake, String> {
+    let mut handshake = base_handshake(options, own_id, isn, peer);
+    handshake.cookie = cookie;
+    handshake.extension = HS_EXT_HSREQ;
+    handshake.extensions.push(Extension {
+        kind: EXT_HSREQ,
+        data: hs_req(SRT_VERSION, HSREQ_FLAGS, options.latency_ms()),
+    });
+    if let (Some(key), Some(passphrase)) = (key, options.passphrase.as_deref()) {
+        handshake.extension |= HS_EXT_KMREQ;
+        handshake.extensions.push(Extension { kind: EXT_KMREQ, data: key.km_message(passphrase)? });
+    }
+    if let Some(stream_id) = &options.stream_id {
+        handshake.extension |= HS_EXT_CONFIG;
+        handshake.extensions.push(Extension { kind: EXT_SID, data: encode_stream_id(stream_id) });
+    }
+    Ok(handshake)
+}
+
+/// Checks the answer to our conclusion, returning the negotiated latency.
+fn check_conclusion_response(
+    options: &SrtOptions,
+    response: &Handshake,
+) -> Result<Duration, String> {
+    let hs_rsp = response.find(EXT_HSRSP).ok_or("SRT peer sent no HSRSP extension")?;
+    if options.passphrase.is_some() {
+        match response.find(EXT_KMRSP) {
+            // A single word is a key material state instead of the echoed message
+            Some(km) if km.len() > 4 => {},
+            _ => return Err(rejection_error(REJ_BADSECRET)),
+        }
+    }
+    Ok(negotiated_latency(options, hs_rsp))
+}
+
+/// Validates a conclusion from a caller or rendezvous initiator, building our response or
+/// returning a rejection reason.
+fn accept_conclusion(
+    options: &SrtOptions,
+    request: &Handshake,
+    own_id: u32,
+    peer: SocketAddr,
+) -> Result<(Handshake, Option<StreamKey>, Duration), u32> {
+    let hs_req_data = request.find(EXT_HSREQ).ok_or(REJ_VERSION)?;
+    let km_req = request.find(EXT_KMREQ);
+    let key = match (options.passphrase.as_deref(), km_req) {
+        (Some(passphrase), Some(km)) => {
+            Some(StreamKey::from_km_message(km, passphrase).map_err(|e| match e {
+                KmError::BadSecret | KmError::Malformed => REJ_BADSECRET,
+            })?)
+        },
+        (None, None) => None,
+        _ => return Err(REJ_UNSECURE),
+    };
+
+    let mut response = base_handshake(options, own_id, request.initial_seq, peer);
+    response.cookie = request.cookie;
+    response.extension = HS_EXT_HSREQ;
+    response.extensions.push(Extension {
+        kind: EXT_HSRSP,
+        data: hs_req(SRT_VERSION, HSREQ_FLAGS, options.latency_ms()),
+    });
+    if let Some(km) = km_req {
+        response.extension |= HS_EXT_KMREQ;
+        response.extensions.push(Extension { kind: EXT_KMRSP, data: km.to_vec() });
+    }
+    Ok((response, key, negotiated_latency(options, hs_req_data)))
+}
+
+fn rejection(
+    options: &SrtOptions,
+    request: &Handshake,
+    own_id: u32,
+    peer: SocketAddr,
+    reason: u32,
+) -> Bytes {
+    let mut handshake = base_handshake(options, own_id, request.initial_seq, peer);
+    handshake.kind = HS_REJECTION_BASE + reason;
+    handshake.cookie = request.cookie;
+    handshake_packet(request.socket_id, handshake)
+}
+
+enum Incoming {
+    /// Nothing arrived within a handshake interval
+    Timeout,
+    Packet(SocketAddr, Packet),
+}
+
+/// Waits up to one handshake interval for a packet, failing once `deadline` has passed.
+async fn recv_handshake(
+    socket: &UdpSocket,
+    buf: &mut [u8],
+    deadline: Option<Instant>,
+) -> Result<Incoming, String> {
+    let now = Instant::now();
+    if deadline.is_some_and(|deadline| now >= deadline) {
+        return Err("SRT connection timed out".to_string());
+    }
+    let wait = deadline.map_or(now + HANDSHAKE_INTERVAL, |d| d.min(now + HANDSHAKE_INTERVAL));
+    match tokio::time::timeout_at(wait, socket.recv_from(buf)).await {
+        Err(_) => Ok(Incoming::Timeout),
+        Ok(Err(e)) => Err(format!("Failed to receive SRT handshake: {e}")),
+        Ok(Ok((len, from))) => {
+            Ok(Packet::parse(&buf[..len]).map_or(Incoming::Timeout, |p| Incoming::Packet(from, p)))
+        },
+    }
+}
+
+async fn send_to(socket: &UdpSocket, data: &[u8], peer: SocketAddr) -> Result<(), String> {
+    socket
+        .send_to(data, peer)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send SRT handshake: {e}"))
+}
+
+/// Connects to a listener at `remote`.
+pub(super) async fn call(
+    socket: Arc<UdpSocket>,
+    remote: SocketAddr,
+    options: &SrtOptions,
+) -> Result<SrtConnection, String> {
+    let deadline = Instant::now() + options.connect_timeout;
+    let own_id = (random_u32()? & 0x3FFF_FFFF).max(1);
+    let isn = random_u32()? & SEQ_MASK;
+    let key =
+        options.passphrase.as_ref().map(|_| StreamKey::generate(options.key_length)).transpose()?;
+
+    let mut request = base_handshake(options, own_id, isn, remote);
+    request.version = 4;
+    request.encryption = 0;
+    request.extension = UDT_DGRAM;
+    request.kind = HS_INDUCTION;
+    let mut request = handshake_packet(0, request);
+    let mut concluding = false;
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+    send_to(&socket, &request, remote).await?;
+    loop {
+        let Incoming::Packet(from, packet) =
+            recv_handshake(&socket, &mut buf, Some(deadline)).await?
+        else {
+            send_to(&socket, &request, remote).await?;
+            continue;
+        };
+        let Packet::Control { control: Control::Handshake(response), .. } = packet else {
+            continue;
+        };
+        if from != remote {
+            continue;
+        }
+        if let Some(reason) = rejection_reason(response.kind) {
+            return Err(rejection_error(reason));
+        }
+        match (concluding, response.kind) {
+            (false, HS_INDUCTION) => {
+                if response.version < 5 || response.extension != SRT_MAGIC {
+                    return Err("SRT peer does not support HSv5".to_string());
+                }
+                let conclusion = conclusion_request(
+                    options,
+                    own_id,
+                    isn,
+                    response.cookie,
+                    remote,
+                    key.as_ref(),
+                )?;
+                request = handshake_packet(0, conclusion);
+                concluding = true;
+                send_to(&socket, &request, remote).await?;
+            },
+            (true, HS_CONCLUSION) => {
+                let latency = check_conclusion_response(options, &response)?;
+                return Ok(SrtConnection::new(
+                    socket,
+                    Negotiated {
+                        peer: remote,
+                        peer_id: response.socket_id,
+                        send_isn: isn,
+                        recv_isn: response.initial_seq,
+                        latency,
+                        key,
+                        stream_id: options.stream_id.clone(),
+                        handshake_response: None,
+                    },
+                ));
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Waits for one caller to connect.
+pub(super) async fn listen(
+    socket: Arc<UdpSocket>,
+    options: &SrtOptions,
+) -> Result<SrtConnection, String> {
+    let own_id = (random_u32()? & 0x3FFF_FFFF).max(1);
+    let cookie = random_u32()?;
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+    loop {
+        let Incoming::Packet(from, packet) = recv_handshake(&socket, &mut buf, None).await? else {
+            continue;
+        };
+        let Packet::Control { control: Control::Handshake(request), .. } = packet else {
+            continue;
+        };
+        match request.kind {
+            HS_INDUCTION => {
+                let mut response = base_handshake(options, own_id, request.initial_seq, from);
+                response.encryption = 0;
+                response.extension = SRT_MAGIC;
+                response.kind = HS_INDUCTION;
+                response.cookie = cookie;
+                send_to(&socket, &handshake_packet(request.socket_id, response), from).await?;
+            },
+            HS_CONCLUSION if request.cookie == cookie => {
+                if request.version < 5 {
+                    let reject = rejection(options, &request, own_id, from, REJ_VERSION);
+                    send_to(&socket, &reject, from).await?;
+                    continue;
+                }
+                match accept_conclusion(options, &request, own_id, from) {
+                    Ok((response, key, latency)) => {
+                        let stream_id = request.find(EXT_SID).map(decode_stream_id);
+                        tracing::info!(
+                            "SRT caller {} connected (stream ID {:?}, latency {} ms)",
+                            from,
+                            stream_id,
+                            latency.as_millis()
+                        );
+                        let response = handshake_packet(request.socket_id, response);
+                        send_to(&socket, &response, from).await?;
+                        return Ok(SrtConnection::new(
+                            socket,
+                            Negotiated {
+                                peer: from,
+                                peer_id: request.socket_id,
+                                send_isn: request.initial_seq,
+                                recv_isn: request.initial_seq,
+                                latency,
+                                key,
+                                stream_id,
+                                handshake_response: Some(response),
+                            },
+                        ));
+                    },
+                    Err(reason) => {
+                        tracing::warn!(
+                            "Rejecting SRT caller {}: {}",
+                            from,
+                            rejection_error(reason)
+                        );
+                        let reject = rejection(options, &request, own_id, from, reason);
+                        send_to(&socket, &reject, from).await?;
+                    },
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Connects to a peer that is connecting back to us at the same time.
+///
+/// Both sides wave with a random cookie; the larger cookie initiates the conclusion exchange.
+pub(super) async fn rendezvous(
+    socket: Arc<UdpSocket>,
+    remote: SocketAddr,
+    options: &SrtOptions,
+) -> Result<SrtConnection, String> {
+    let deadline = Instant::now() + options.connect_timeout;
+    let own_id = (random_u32()? & 0x3FFF_FFFF).max(1);
+    let isn = random_u32()? & SEQ_MASK;
+    let cookie = random_u32()?;
+    let key =
+        options.passphrase.as_ref().map(|_| StreamKey::generate(options.key_length)).transpose()?;
+
+    let mut wave = base_handshake(options, own_id, isn, remote);
+    wave.kind = HS_WAVEAHAND;
+    wave.cookie = cookie;
+    let mut request = handshake_packet(0, wave);
+    // The responder's connection, complete once the initiator confirms it
+    let mut responded: Option<Negotiated> = None;
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+    send_to(&socket, &request, remote).await?;
+    loop {
+        let Incoming::Packet(from, packet) =
+            recv_handshake(&socket, &mut buf, Some(deadline)).await?
+        else {
+            if responded.is_none() {
+                send_to(&socket, &request, remote).await?;
+            }
+            continue;
+        };
+        if from != remote {
+            continue;
+        }
+        let Packet::Control { control: Control::Handshake(handshake), .. } = packet else {
+            // Anything else means the initiator considers the connection established
+            if let Some(negotiated) = responded.take() {
+                return Ok(SrtConnection::new(socket, negotiated));
+            }
+            continue;
+        };
+        if let Some(reason) = rejection_reason(handshake.kind) {
+            return Err(rejection_error(reason));
+        }
+        match handshake.kind {
+            HS_WAVEAHAND if handshake.cookie == cookie => {
+                return Err("SRT rendezvous cookies collided; retry the connection".to_string());
+            },
+            HS_WAVEAHAND if cookie > handshake.cookie && responded.is_none() => {
+                let conclusion =
+                    conclusion_request(options, own_id, isn, cookie, remote, key.as_ref())?;
+                request = handshake_packet(handshake.socket_id, conclusion);
+                send_to(&socket, &request, remote).await?;
+            },
+            HS_CONCLUSION if handshake.find(EXT_HSREQ).is_some() => {
+                // We are the responder
+                if let Some(negotiated) = &responded {
+                    if let Some(response) = &negotiated.handshake_response {
+                        send_to(&socket, response, remote).await?;
+                    }
+                    continue;
+                }
+                match accept_conclusion(options, &handshake, own_id, remote) {
+                    Ok((mut response, key, latency)) => {
+                        response.initial_seq = isn;
+                        let response = handshake_packet(handshake.socket_id, response);
+                        send_to(&socket, &response, remote).await?;
+                        responded = Some(Negotiated {
+                            peer: remote,
+                            peer_id: handshake.socket_id,
+                            send_isn: isn,
+                            recv_isn: handshake.initial_seq,
+                            latency,
+                            key,
+                            stream_id: handshake.find(EXT_SID).map(decode_stream_id),
+                            handshake_response: Some(response),
+                        });
+                    },
+                    Err(reason) => {
+                        let reject = rejection(options, &handshake, own_id, remote, reason);
+                        send_to(&socket, &reject, remote).await?;
+                        return Err(rejection_error(reason));
+                    },
+                }
+            },
+            HS_CONCLUSION if handshake.find(EXT_HSRSP).is_some() => {
+                // We are the initiator and this is the response
+                let latency = check_conclusion_response(options, &handshake)?;
+                let mut agreement = base_handshake(options, own_id, isn, remote);
+                agreement.kind = HS_AGREEMENT;
+                agreement.cookie = cookie;
+                let agreement = handshake_packet(handshake.socket_id, agreement);
+                send_to(&socket, &agreement, remote).await?;
+                return Ok(SrtConnection::new(
+                    socket,
+                    Negotiated {
+                        peer: remote,
+                        peer_id: handshake.socket_id,
+                        send_isn: isn,
+                        recv_isn: handshake.initial_seq,
+                        latency,
+                        key,
+                        stream_id: options.stream_id.clone(),
+                        handshake_response: Some(agreement),
+                    },
+                ));
+            },
+            HS_AGREEMENT => {
+                if let Some(negotiated) = responded.take() {
+                    return Ok(SrtConnection::new(socket, negotiated));
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+fn data_packet(sent: &SentPacket, key: u8, dest: u32, retransmitted: bool) -> Packet {
+    Packet::Data(DataPacket {
+        seq: sent.seq,
+        msg_no: sent.msg_no,
+        key,
+        retransmitted,
+        timestamp: sent.timestamp,
+        dest,
+        payload: sent.payload.clone(),
+    })
+}
+
+struct SentPacket {
+    seq: u32,
+    msg_no: u32,
+    timestamp: u32,
+    sent_at: Instant,
+    /// When to send the packet again if no ACK has covered it by then
+    retransmit_at: Instant,
+    payload: Bytes,
+}
+
+/// An established SRT connection.
+pub(super) struct SrtConnection {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+    peer_id: u32,
+    start: Instant,
+    latency: Duration,
+    key: Option<StreamKey>,
+    stream_id: Option<String>,
+    handshake_response: Option<Bytes>,
+
+    // Sending
+    next_seq: u32,
+    next_msg_no: u32,
+    send_buffer: VecDeque<SentPacket>,
+
+    // Receiving, with sequence numbers extended to 64 bits
+    recv_isn: u32,
+    highest_seq: u32,
+    highest_ext: u64,
+    next_deliver: u64,
+    loss: BTreeSet<u64>,
+    /// Received packets by extended sequence number, with their unwrapped timestamps
+    buffer: BTreeMap<u64, (u64, Bytes)>,
+    /// Arrival time and timestamp of the first packet, anchoring delivery times
+    tsbpd_base: Option<(Instant, u64)>,
+    last_timestamp: u64,
+    dropped: u64,
+
+    // Acknowledgements
+    ack_no: u32,
+    last_ack_sent: u64,
+    pending_acks: VecDeque<(u32, Instant)>,
+    rtt: Duration,
+    rtt_var: Duration,
+    next_ack_at: Instant,
+    next_nak_at: Instant,
+
+    last_sent: Instant,
+    last_received: Instant,
+    closed: bool,
+}
+
+/// Extended sequence number of the initial sequence number, far enough from zero that packets
+/// before it never underflow.
+const EXT_BASE: u64 = 1 << 32;
+
+impl SrtConnection {
+    fn new(socket: Arc<UdpSocket>, negotiated: Negotiated) -> Self {
+        let now = Instant::now();
+        Self {
+            socket,
+            peer: negotiated.peer,
+            peer_id: negotiated.peer_id,
+            start: now,
+            latency: negotiated.latency,
+            key: negotiated.key,
+            stream_id: negotiated.stream_id,
+            handshake_response: negotiated.handshake_response,
+            next_seq: negotiated.send_isn,
+            next_msg_no: 1,
+            send_buffer: VecDeque::new(),
+            recv_isn: negotiated.recv_isn,
+            highest_seq: seq_add(negotiated.recv_isn, SEQ_MASK),
+            highest_ext: EXT_BASE - 1,
+            next_deliver: EXT_BASE,
+            loss: BTreeSet::new(),
+            buffer: BTreeMap::new(),
+            tsbpd_base: None,
+            last_timestamp: EXT_BASE,
+            dropped: 0,
+            ack_no: 0,
+            last_ack_sent: EXT_BASE,
+            pending_acks: VecDeque::new(),
+            rtt: INITIAL_RTT,
+            rtt_var: INITIAL_RTT / 2,
+            next_ack_at: now + ACK_INTERVAL,
+            next_nak_at: now + MIN_NAK_INTERVAL,
+            last_sent: now,
+            last_received: now,
+            closed: false,
+        }
+    }
+
+    pub const fn socket(&self) -> &Arc<UdpSocket> {
+        &self.socket
+    }
+
+    pub const fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+
+    pub fn stream_id(&self) -> Option<&str> {
+        self.stream_id.as_deref()
+    }
+
+    pub const fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Packets sent and not acknowledged yet.
+    pub fn unacknowledged(&self) -> usize {
+        self.send_buffer.len()
+    }
+
+    /// Packets dropped since the last call because they arrived too late or never did.
+    pub fn take_dropped(&mut self) -> u64 {
+        std::mem::take(&mut self.dropped)
+    }
+
+    fn timestamp(&self, now: Instant) -> u32 {
+        let micros = now.duration_since(self.start).as_micros() & u128::from(u32::MAX);
+        u32::try_from(micros).unwrap_or_default()
+    }
+
+    fn transmit(&mut self, packet: &Packet, now: Instant) {
+        if let Err(e) = self.socket.try_send_to(&encode(packet), self.peer) {
+            // Lost datagrams are recovered by retransmission
+            tracing::debug!("Failed to send SRT packet: {}", e);
+        }
+        self.last_sent = now;
+    }
+
+    fn send_control(&mut self, control: Control, now: Instant) {
+        let packet =
+            Packet::Control { timestamp: self.timestamp(now), dest: self.peer_id, control };
+        self.transmit(&packet, now);
+    }
+
+    /// Sends one payload of at most [`MAX_PAYLOAD_SIZE`] bytes.
+    pub fn send(&mut self, payload: &[u8], now: Instant) {
+        let seq = self.next_seq;
+        self.next_seq = seq_add(seq, 1);
+        let msg_no = self.next_msg_no;
+        self.next_msg_no = if msg_no >= 0x03FF_FFFF { 1 } else { msg_no + 1 };
+
+        let mut payload = payload.to_vec();
+        if let Some(key) = &self.key {
+            key.apply(seq, &mut payload);
+        }
+        let sent = SentPacket {
+            seq,
+            msg_no,
+            timestamp: self.timestamp(now),
+            sent_at: now,
+            retransmit_at: now + self.retransmit_timeout(),
+            payload: Bytes::from(payload),
+        };
+        self.transmit(&data_packet(&sent, self.key_flag(), self.peer_id, false), now);
+        self.send_buffer.push_back(sent);
+        if self.send_buffer.len() > MAX_SEND_BUFFER {
+            self.send_buffer.pop_front();
+        }
+    }
+
+    const fn key_flag(&self) -> u8 {
+        if self.key.is_some() {
+            KEY_EVEN
+        } else {
+            0
+        }
+    }
+
+    /// How long to wait for an ACK before retransmitting without a NAK, which recovers losses
+    /// at the end of a burst that the receiver cannot detect.
+    fn retransmit_timeout(&self) -> Duration {
+        self.rtt + self.rtt_var * 4 + ACK_INTERVAL
+    }
+
+    fn to_seq(&self, ext: u64) -> u32 {
+        let offset = ext.wrapping_sub(EXT_BASE) & u64::from(SEQ_MASK);
+        seq_add(self.recv_isn, u32::try_from(offset).unwrap_or_default())
+    }
+
+    /// First sequence number not received yet; everything before it is acknowledged.
+    fn ack_point(&self) -> u64 {
+        self.loss.first().copied().unwrap_or(self.highest_ext + 1).max(self.next_deliver)
+    }
+
+    fn send_nak(&mut self, now: Instant) {
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        let mut current: Option<(u64, u64)> = None;
+        for &ext in &self.loss {
+            match current {
+                Some((first, last)) if last + 1 == ext => current = Some((first, ext)),
+                _ => {
+                    if let Some((first, last)) = current.replace((ext, ext)) {
+                        ranges.push((self.to_seq(first), self.to_seq(last)));
+                    }
+                    if ranges.len() >= MAX_NAK_RANGES {
+                        current = None;
+                        break;
+                    }
+                },
+            }
+        }
+        if let Some((first, last)) = current {
+            ranges.push((self.to_seq(first), self.to_seq(last)));
+        }
+        if !ranges.is_empty() {
+            self.send_control(Control::Nak(ranges), now);
+        }
+    }
+
+    /// Handles one datagram received on the connection's socket.
+    pub fn handle_datagram(&mut self, from: SocketAddr, data: &[u8], now: Instant) {
+        if from != self.peer {
+            return;
+        }
+        let Some(packet) = Packet::parse(data) else { return };
+        self.last_received = now;
+        match packet {
+            Packet::Data(packet) => self.handle_data(&packet, now),
+            Packet::Control { control, .. } => self.handle_control(control, now),
+        }
+    }
+
+    fn handle_control(&mut self, control: Control, now: Instant) {
+        match control {
+            Control::Handshake(_) => {
+                // The peer missed the end of the handshake
+                if let Some(response) = self.handshake_response.clone() {
+                    if let Err(e) = self.socket.try_send_to(&response, self.peer) {
+                        tracing::debug!("Failed to resend SRT handshake: {}", e);
+                    }
+                }
+            },
+            Control::Ack { ack_no, last_ack, rtt_us, rtt_var_us } => {
+                while self.send_buffer.front().is_some_and(|p| seq_offset(p.seq, last_ack) > 0) {
+                    self.send_buffer.pop_front();
+                }
+                // The receiver measures the RTT; adopt its estimate for retransmission timing
+                if rtt_us > 0 {
+                    self.rtt = Duration::from_micros(u64::from(rtt_us));
+                    self.rtt_var = Duration::from_micros(u64::from(rtt_var_us));
+                }
+                if ack_no != 0 {
+                    self.send_control(Control::AckAck(ack_no), now);
+                }
+            },
+            Control::Nak(ranges) => {
+                let Some(front) = self.send_buffer.front().map(|p| p.seq) else { return };
+                let (key, dest) = (self.key_flag(), self.peer_id);
+                let retransmit_at = now + self.retransmit_timeout();
+                let mut packets = Vec::new();
+                for (first, last) in ranges {
+                    let start =
+                        usize::try_from(seq_offset(front, first).max(0)).unwrap_or_default();
+                    let end = usize::try_from(seq_offset(front, last)).unwrap_or_default();
+                    for sent in
+                        self.send_buffer.range_mut(start..=end.min(self.send_buffer.len() - 1))
+                    {
+                        sent.retransmit_at = retransmit_at;
+                        packets.push(data_packet(sent, key, dest, true));
+                    }
+                }
+                for packet in packets {
+                    self.transmit(&packet, now);
+                }
+            },
+            Control::AckAck(ack_no) => {
+                if let Some(pos) = self.pending_acks.iter().position(|&(no, _)| no == ack_no) {
+                    let sample = now.duration_since(self.pending_acks[pos].1);
+                    self.pending_acks.drain(..=pos);
+                    let deviation = sample.abs_diff(self.rtt);
+                    self.rtt_var = (self.rtt_var * 3 + deviation) / 4;
+                    self.rtt = (self.rtt * 7 + sample) / 8;
+                }
+            },
+            Control::Shutdown => self.closed = true,
+            Control::KeepAlive | Control::Other(_) => {},
+        }
+    }
+
+    fn handle_data(&mut self, packet: &DataPacket, now: Instant) {
+        let offset = seq_offset(self.highest_seq, packet.seq);
+        let ext = self.highest_ext.saturating_add_signed(offset);
+        if ext < self.next_deliver || self.buffer.contains_key(&ext) {
+            return;
+        }
+        if ext > self.highest_ext {
+            if ext > self.highest_ext + 1 {
+                self.loss.extend(self.highest_ext + 1..ext);
+                let first = self.to_seq(self.highest_ext + 1);
+                let last = self.to_seq(ext - 1);
+                self.send_control(Control::Nak(vec![(first, last)]), now);
+            }
+            self.highest_ext = ext;
+            self.highest_seq = packet.seq;
+        } else if !self.loss.remove(&ext) {
+            return;
+        }
+
+        let mut payload = packet.payload.to_vec();
+        match (&self.key, packet.key) {
+            (Some(key), flag) if flag != 0 => key.apply(packet.seq, &mut payload),
+            (None, 0) => {},
+            _ => {
+                tracing::debug!("Dropping SRT packet with unexpected encryption flag");
+                return;
+            },
+        }
+
+        let timestamp = self.unwrap_timestamp(packet.timestamp);
+        self.tsbpd_base.get_or_insert((now, timestamp));
+        self.buffer.insert(ext, (timestamp, Bytes::from(payload)));
+    }
+
+    fn unwrap_timestamp(&mut self, timestamp: u32) -> u64 {
+        let last = u32::try_from(self.last_timestamp & u64::from(u32::MAX)).unwrap_or_default();
+        let delta = i32::from_ne_bytes(timestamp.wrapping_sub(last).to_ne_bytes());
+        let unwrapped = self.last_timestamp.saturating_add_signed(i64::from(delta));
+        self.last_timestamp = self.last_timestamp.max(unwrapped);
+        unwrapped
+    }
+
+    fn delivery_time(&self, timestamp: u64) -> Option<Instant> {
+        let (arrival, first) = self.tsbpd_base?;
+        Some(arrival + Duration::from_micros(timestamp.saturating_sub(first)) + self.latency)
+    }
+
+    /// Returns the next payload whose delivery time has come, skipping lost packets that can
+    /// no longer arrive in time.
+    pub fn pop_ready(&mut self, now: Instant) -> Option<Bytes> {
+        let (&ext, &(timestamp, _)) = self.buffer.first_key_value()?;
+        if self.delivery_time(timestamp).is_none_or(|at| now < at) {
+            return None;
+        }
+        if ext > self.next_deliver {
+            // Too late for the missing packets
+            self.dropped += ext - self.next_deliver;
+            self.loss = self.loss.split_off(&ext);
+        }
+        self.next_deliver = ext + 1;
+        self.buffer.pop_first().map(|(_, (_, payload))| payload)
+    }
+
+    /// Returns the next buffered payload without waiting for its delivery time, for draining
+    /// the buffer once the peer has closed the connection.
+    pub fn pop_remaining(&mut self) -> Option<Bytes> {
+        // Every buffered packet is due within the latency of its arrival
+        self.pop_ready(Instant::now() + self.latency * 2)
+    }
+
+    /// Sends periodic ACKs, NAKs and keepalives and expires unacknowledged packets. Fails when
+    /// the peer has gone silent.
+    pub fn on_timer(&mut self, now: Instant) -> Result<(), String> {
+        if now >= self.next_ack_at {
+            let ack = self.ack_point();
+            if ack != self.last_ack_sent {
+                self.ack_no = self.ack_no.wrapping_add(1).max(1);
+                self.pending_acks.push_back((self.ack_no, now));
+                if self.pending_acks.len() > MAX_PENDING_ACKS {
+                    self.pending_acks.pop_front();
+                }
+                let control = Control::Ack {
+                    ack_no: self.ack_no,
+                    last_ack: self.to_seq(ack),
+                    rtt_us: micros_u32(self.rtt),
+                    rtt_var_us: micros_u32(self.rtt_var),
+                };
+                self.send_control(control, now);
+                self.last_ack_sent = ack;
+            }
+            self.next_ack_at = now + ACK_INTERVAL;
+        }
+        if now >= self.next_nak_at {
+            if !self.loss.is_empty() {
+                self.send_nak(now);
+            }
+            self.next_nak_at = now + (self.rtt + self.rtt_var * 4).max(MIN_NAK_INTERVAL);
+        }
+
+        // Retransmitting past this point cannot beat the receiver's delivery deadline
+        let drop_delay = self.latency * 5 / 4 + MIN_NAK_INTERVAL;
+        while self.send_buffer.front().is_some_and(|p| now.duration_since(p.sent_at) > drop_delay) {
+            self.send_buffer.pop_front();
+        }
+
+        let (key, dest) = (self.key_flag(), self.peer_id);
+        let retransmit_at = now + self.retransmit_timeout();
+        let mut packets = Vec::new();
+        for sent in self.send_buffer.iter_mut().filter(|p| p.retransmit_at <= now) {
+            sent.retransmit_at = retransmit_at;
+            packets.push(data_packet(sent, key, dest, true));
+        }
+        for packet in packets {
+            self.transmit(&packet, now);
+        }
+
+        if now.duration_since(self.last_sent) >= KEEPALIVE_INTERVAL {
+            self.send_control(Control::KeepAlive, now);
+        }
+        if now.duration_since(self.last_received) >= PEER_IDLE_TIMEOUT {
+            return Err("SRT peer timed out".to_string());
+        }
+        Ok(())
+    }
+
+    /// When [`Self::on_timer`] or [`Self::pop_ready`] next have work to do.
+    pub fn next_wakeup(&self) -> Instant {
+        let mut wakeup = self.next_ack_at.min(self.last_sent + KEEPALIVE_INTERVAL);
+        if !self.loss.is_empty() {
+            wakeup = wakeup.min(self.next_nak_at);
+        }
+        if let Some(at) = self.send_buffer.iter().map(|p| p.retransmit_at).min() {
+            wakeup = wakeup.min(at);
+        }
+        if let Some(at) =
+            self.buffer.first_key_value().and_then(|(_, &(ts, _))| self.delivery_time(ts))
+        {
+            wakeup = wakeup.min(at);
+        }
+        wakeup
+    }
+
+    /// Tells the peer the connection is over.
+    pub fn close(&mut self) {
+        if !self.closed {
+            self.send_control(Control::Shutdown, Instant::now());
+            self.closed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn options(passphrase: Option<&str>) -> SrtOptions {
+        SrtOptions {
+            latency: Duration::from_millis(100),
+            passphrase: passphrase.map(str::to_string),
+            key_length: 16,
+            stream_id: Some("live/test".to_string()),
+            connect_timeout: Duration::from_secs(2),
+        }
+    }
+
+    async fn socket() -> Arc<UdpSocket> {
+        Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap())
+    }
+
+    /// Drives a connection, sending `outgoing` one per millisecond, until `expected` payloads
+    /// are delivered and everything sent is acknowledged.
+    async fn drive(
+        mut connection: SrtConnection,
+        outgoing: Vec<Bytes>,
+        expected: usize,
+    ) -> Vec<Bytes> {
+        let socket = connection.socket().clone();
+        let mut outgoing = outgoing.into_iter();
+        let mut next_send = Instant::now();
+        let mut received = Vec::new();
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut sending = true;
+        while received.len() < expected || sending || connection.unacknowledged() > 0 {
+            assert!(Instant::now() < deadline, "timed out with {} payloads", received.len());
+            let wakeup = if sending {
+                connection.next_wakeup().min(next_send)
+            } else {
+                connection.next_wakeup()
+            };
+            tokio::select! {
+                Ok((len, from)) = socket.recv_from(&mut buf) => {
+                    connection.handle_datagram(from, &buf[..len], Instant::now());
+                },
+                () = tokio::time::sleep_until(wakeup) => {},
+            }
+            let now = Instant::now();
+            connection.on_timer(now).unwrap();
+            if sending && now >= next_send {
+                match outgoing.next() {
+                    Some(payload) => connection.send(&payload, now),
+                    None => sending = false,
+                }
+                next_send = now + Duration::from_millis(1);
+            }
+            while let Some(payload) = connection.pop_ready(now) {
+                received.push(payload);
+            }
+        }
+        received
+    }
+
+    fn payloads(count: u8) -> Vec<Bytes> {
+        (0..count).map(|i| Bytes::from(vec![i; MAX_PAYLOAD_SIZE])).collect()
+    }
+
+    /// Forwards datagrams between a caller and `target`, dropping every fifth original data
+    /// packet on its way to the target.
+    async fn lossy_proxy(target: SocketAddr) -> SocketAddr {
+        let proxy = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = proxy.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+            let mut client = None;
+            let mut data_packets = 0u32;
+            while let Ok((len, from)) = proxy.recv_from(&mut buf).await {
+                if from == target {
+                    if let Some(client) = client {
+                        let _ = proxy.send_to(&buf[..len], client).await;
+                    }
+                    continue;
+                }
+                client = Some(from);
+                if let Some(Packet::Data(packet)) = Packet::parse(&buf[..len]) {
+                    if !packet.retransmitted {
+                        data_packets += 1;
+                        if data_packets.is_multiple_of(5) {
+                            continue;
+                        }
+                    }
+                }
+                let _ = proxy.send_to(&buf[..len], target).await;
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_caller_listener_recovers_losses_with_encryption() {
+        let listener_socket = socket().await;
+        let target = listener_socket.local_addr().unwrap();
+        let proxy = lossy_proxy(target).await;
+
+        let listener = tokio::spawn(async move {
+            listen(listener_socket, &options(Some("a secret passphrase"))).await.unwrap()
+        });
+        let mut caller_options = options(Some("a secret passphrase"));
+        caller_options.latency = Duration::from_millis(150);
+        let caller = call(socket().await, proxy, &caller_options).await.unwrap();
+        let listener = listener.await.unwrap();
+
+        assert_eq!(listener.stream_id(), Some("live/test"));
+        assert_eq!(caller.latency(), Duration::from_millis(150));
+        assert_eq!(listener.latency(), Duration::from_millis(150));
+
+        let sent = payloads(100);
+        let receiving = tokio::spawn(drive(listener, Vec::new(), sent.len()));
+        drive(caller, sent.clone(), 0).await;
+        assert_eq!(receiving.await.unwrap(), sent);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passphrase_is_rejected() {
+        let listener_socket = socket().await;
+        let target = listener_socket.local_addr().unwrap();
+        let listener = tokio::spawn(async move {
+            listen(listener_socket, &options(Some("the right passphrase"))).await
+        });
+
+        let err =
+            call(socket().await, target, &options(Some("a wrong passphrase"))).await.err().unwrap();
+        assert!(err.contains("wrong passphrase"), "{err}");
+        let err = call(socket().await, target, &options(None)).await.err().unwrap();
+        assert!(err.contains("one side only"), "{err}");
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn test_rendezvous() {
+        let (a, b) = (socket().await, socket().await);
+        let (a_addr, b_addr) = (a.local_addr().unwrap(), b.local_addr().unwrap());
+        let side_a =
+            tokio::spawn(
+                async move { rendezvous(a, b_addr, &options(Some("shared secret!"))).await },
+            );
+        let b = rendezvous(b, a_addr, &options(Some("shared secret!"))).await.unwrap();
+        let a = side_a.await.unwrap().unwrap();
+
+        let sent = payloads(20);
+        let receiving = tokio::spawn(drive(b, Vec::new(), sent.len()));
+        drive(a, sent.clone(), 0).await;
+        assert_eq!(receiving.await.unwrap(), sent);
+    }
+}
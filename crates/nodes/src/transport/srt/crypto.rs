@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! SRT encryption, following the keying material format of the SRT specification.
+//!
+//! The caller generates a random stream key (SEK) and salt, and sends them in a keying material
+//! message with the SEK wrapped (RFC 3394) under a key derived from the passphrase
+//! (PBKDF2-HMAC-SHA1 over the last 8 salt bytes, 2048 iterations). Payloads are encrypted with
+//! AES-CTR, the IV mixing the salt with the packet sequence number.
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+
+const SALT_LEN: usize = 16;
+/// Trailing salt bytes used as the PBKDF2 salt.
+const PBKDF2_SALT_LEN: usize = 8;
+const PBKDF2_ITERATIONS: u32 = 2048;
+/// Initial value of RFC 3394 key wrapping.
+const KEY_WRAP_IV: u64 = 0xA6A6_A6A6_A6A6_A6A6;
+
+// Keying material message header
+const KM_VERSION_TYPE: u8 = 0x12; // S = 0, V = 1, PT = 2 (KMmsg)
+const KM_SIGNATURE: u16 = 0x2029;
+const KM_CIPHER_AES_CTR: u8 = 2;
+const KM_SE_MPEG_TS: u8 = 2;
+const KM_HEADER_LEN: usize = 16;
+
+/// Fills a buffer with cryptographically secure random bytes.
+pub(super) fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new().fill(&mut bytes).map_err(|_| "System RNG failure".to_string())?;
+    Ok(bytes)
+}
+
+enum BlockCipher {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl BlockCipher {
+    fn new(key: &[u8]) -> Option<Self> {
+        match key.len() {
+            16 => Aes128::new_from_slice(key).ok().map(Self::Aes128),
+            24 => Aes192::new_from_slice(key).ok().map(Self::Aes192),
+            32 => Aes256::new_from_slice(key).ok().map(Self::Aes256),
+            _ => None,
+        }
+    }
+
+    fn encrypt(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Self::Aes128(cipher) => cipher.encrypt_block(block),
+            Self::Aes192(cipher) => cipher.encrypt_block(block),
+            Self::Aes256(cipher) => cipher.encrypt_block(block),
+        }
+    }
+
+    fn decrypt(&self, block: &mut [u8; 16]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Self::Aes128(cipher) => cipher.decrypt_block(block),
+            Self::Aes192(cipher) => cipher.decrypt_block(block),
+            Self::Aes256(cipher) => cipher.decrypt_block(block),
+        }
+    }
+}
+
+/// Wraps `key` (a multiple of 8 bytes) under `kek` (RFC 3394).
+fn wrap_key(kek: &BlockCipher, key: &[u8]) -> Vec<u8> {
+    let mut registers: Vec<u64> = key
+        .chunks_exact(8)
+        .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap_or_default()))
+        .collect();
+    let n = registers.len() as u64;
+    let mut a = KEY_WRAP_IV;
+    for j in 0..6u64 {
+        for (i, register) in (1u64..).zip(registers.iter_mut()) {
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&a.to_be_bytes());
+            block[8..].copy_from_slice(&register.to_be_bytes());
+            kek.encrypt(&mut block);
+            a = u64::from_be_bytes(block[..8].try_into().unwrap_or_default()) ^ (n * j + i);
+            *register = u64::from_be_bytes(block[8..].try_into().unwrap_or_default());
+        }
+    }
+    std::iter::once(a).chain(registers).flat_map(u64::to_be_bytes).collect()
+}
+
+/// Unwraps a key wrapped by [`wrap_key`], or returns `None` if the integrity check fails
+/// (usually a wrong passphrase).
+fn unwrap_key(kek: &BlockCipher, wrapped: &[u8]) -> Option<Vec<u8>> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(8) {
+        return None;
+    }
+    let mut words = wrapped
+        .chunks_exact(8)
+        .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap_or_default()));
+    let mut a = words.next()?;
+    let mut registers: Vec<u64> = words.collect();
+    let n = registers.len() as u64;
+    for j in (0..6u64).rev() {
+        for (i, register) in (1u64..=n).rev().zip(registers.iter_mut().rev()) {
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&(a ^ (n * j + i)).to_be_bytes());
+            block[8..].copy_from_slice(&register.to_be_bytes());
+            kek.decrypt(&mut block);
+            a = u64::from_be_bytes(block[..8].try_into().unwrap_or_default());
+            *register = u64::from_be_bytes(block[8..].try_into().unwrap_or_default());
+        }
+    }
+    (a == KEY_WRAP_IV).then(|| registers.into_iter().flat_map(u64::to_be_bytes).collect())
+}
+
+fn derive_kek(passphrase: &str, salt: &[u8; SALT_LEN], key_len: usize) -> Option<BlockCipher> {
+    let mut kek = vec![0u8; key_len];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA1,
+        NonZeroU32::new(PBKDF2_ITERATIONS)?,
+        &salt[SALT_LEN - PBKDF2_SALT_LEN..],
+        passphrase.as_bytes(),
+        &mut kek,
+    );
+    BlockCipher::new(&kek)
+}
+
+/// Why a keying material message was not accepted.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum KmError {
+    /// The key does not unwrap with this passphrase
+    BadSecret,
+    Malformed,
+}
+
+/// The stream encryption key (SEK) with its salt.
+pub(super) struct StreamKey {
+    salt: [u8; SALT_LEN],
+    key: Vec<u8>,
+    cipher: BlockCipher,
+}
+
+impl StreamKey {
+    /// Generates a random key of `key_len` bytes (16, 24 or 32).
+    pub fn generate(key_len: usize) -> Result<Self, String> {
+        let salt = random_bytes::<SALT_LEN>()?;
+        let key = random_bytes::<32>()?[..key_len].to_vec();
+        let cipher = BlockCipher::new(&key).ok_or("Invalid SRT key length")?;
+        Ok(Self { salt, key, cipher })
+    }
+
+    /// Builds the keying material message carrying this key wrapped under `passphrase`.
+    pub fn km_message(&self, passphrase: &str) -> Result<Vec<u8>, String> {
+        let kek = derive_kek(passphrase, &self.salt, self.key.len())
+            .ok_or("Failed to derive the SRT key encryption key")?;
+        let mut message = Vec::with_capacity(KM_HEADER_LEN + SALT_LEN + self.key.len() + 8);
+        message.push(KM_VERSION_TYPE);
+        message.extend_from_slice(&KM_SIGNATURE.to_be_bytes());
+        message.push(super::packet::KEY_EVEN);
+        // KEKI
+        message.extend_from_slice(&[0; 4]);
+        message.extend_from_slice(&[KM_CIPHER_AES_CTR, 0, KM_SE_MPEG_TS, 0, 0, 0]);
+        message.push(u8::try_from(SALT_LEN / 4).unwrap_or_default());
+        message.push(u8::try_from(self.key.len() / 4).unwrap_or_default());
+        message.extend_from_slice(&self.salt);
+        message.extend_from_slice(&wrap_key(&kek, &self.key));
+        Ok(message)
+    }
+
+    /// Recovers the key from a keying material message.
+    pub fn from_km_message(message: &[u8], passphrase: &str) -> Result<Self, KmError> {
+        let header = message.get(..KM_HEADER_LEN).ok_or(KmError::Malformed)?;
+        if header[0] != KM_VERSION_TYPE
+            || header[1..3] != KM_SIGNATURE.to_be_bytes()
+            || header[8] != KM_CIPHER_AES_CTR
+        {
+            return Err(KmError::Malformed);
+        }
+        let salt_len = usize::from(header[14]) * 4;
+        let key_len = usize::from(header[15]) * 4;
+        // A single (even or odd) key; both would follow one another
+        let wrapped_len = if header[3] & 0b11 == 0b11 { 2 * key_len } else { key_len } + 8;
+        if salt_len != SALT_LEN || message.len() < KM_HEADER_LEN + SALT_LEN + wrapped_len {
+            return Err(KmError::Malformed);
+        }
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&message[KM_HEADER_LEN..KM_HEADER_LEN + SALT_LEN]);
+        let wrapped = &message[KM_HEADER_LEN + SALT_LEN..KM_HEADER_LEN + SALT_LEN + wrapped_len];
+
+        let kek = derive_kek(passphrase, &salt, key_len).ok_or(KmError::Malformed)?;
+        let keys = unwrap_key(&kek, wrapped).ok_or(KmError::BadSecret)?;
+        let key = keys[..key_len].to_vec();
+        let cipher = BlockCipher::new(&key).ok_or(KmError::Malformed)?;
+        Ok(Self { salt, key, cipher })
+    }
+
+    /// Encrypts or decrypts a payload in place (AES-CTR is symmetric).
+    pub fn apply(&self, seq: u32, payload: &mut [u8]) {
+        let mut iv = [0u8; 16];
+        iv[10..14].copy_from_slice(&seq.to_be_bytes());
+        for (byte, salt) in iv.iter_mut().zip(&self.salt[..14]) {
+            *byte ^= salt;
+        }
+        let counter = u128::from_be_bytes(iv);
+        for (i, chunk) in (0u128..).zip(payload.chunks_mut(16)) {
+            let mut block = counter.wrapping_add(i).to_be_bytes();
+            self.cipher.encrypt(&mut block);
+            for (byte, key) in chunk.iter_mut().zip(block) {
+                *byte ^= key;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_key_wrap_rfc3394_vector() {
+        let kek = BlockCipher::new(&hex("000102030405060708090A0B0C0D0E0F")).unwrap();
+        let key = hex("00112233445566778899AABBCCDDEEFF");
+        let wrapped = wrap_key(&kek, &key);
+        assert_eq!(wrapped, hex("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5"));
+        assert_eq!(unwrap_key(&kek, &wrapped).unwrap(), key);
+
+        let other = BlockCipher::new(&[0u8; 16]).unwrap();
+        assert!(unwrap_key(&other, &wrapped).is_none());
+    }
+
+    #[test]
+    fn test_km_message_roundtrip() {
+        let key = StreamKey::generate(32).unwrap();
+        let message = key.km_message("correct horse battery").unwrap();
+        assert_eq!(message.len(), KM_HEADER_LEN + SALT_LEN + 32 + 8);
+
+        let received = StreamKey::from_km_message(&message, "correct horse battery").unwrap();
+        assert_eq!(received.key, key.key);
+        assert_eq!(received.salt, key.salt);
+        assert!(matches!(
+            StreamKey::from_km_message(&message, "wrong passphrase"),
+            Err(KmError::BadSecret)
+        ));
+
+        let mut payload = vec![0x47u8; 1316];
+        key.apply(1234, &mut payload);
+        assert_ne!(payload, vec![0x47u8; 1316]);
+        received.apply(1234, &mut payload);
+        assert_eq!(payload, vec![0x47u8; 1316]);
+    }
+}
@@ -0,0 +1,656 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! SRT input and output nodes.
+//!
+//! `transport::srt::output` sends the byte stream of `containers::mpegts::muxer` over SRT, and
+//! `transport::srt::input` receives one and emits it in chunks of up to seven TS packets, ready
+//! for a demuxer or `transport::hls::packager`. Both nodes connect as caller, listener or
+//! rendezvous peer.
+//!
+//! The SRT implementation follows the protocol specification (draft-sharabayko-srt) and is
+//! only tested against itself, not against libsrt-based tools. It covers live mode: the HSv5
+//! handshake, ARQ with ACK/NAK driven retransmission, timestamp-based delivery with too-late
+//! packet drop after the negotiated latency, and AES-CTR encryption from a passphrase. Key
+//! rotation, file mode, packet filters (FEC) and connection bonding are not implemented. Each
+//! node carries one connection; a listener serves the first caller that completes the
+//! handshake.
+
+mod connection;
+mod crypto;
+mod packet;
+
+use async_trait::async_trait;
+use connection::{SrtConnection, SrtOptions, MAX_DATAGRAM_SIZE, MAX_PAYLOAD_SIZE};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::registry::StaticPins;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketType};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality,
+    ProcessorNode, StreamKitError,
+};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+const DEFAULT_LATENCY_MS: u64 = 120;
+const MAX_LATENCY_MS: u64 = 60_000;
+const DEFAULT_KEY_LENGTH: usize = 16;
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 3000;
+const MIN_PASSPHRASE_LENGTH: usize = 10;
+const MAX_PASSPHRASE_LENGTH: usize = 79;
+const MAX_STREAM_ID_LENGTH: usize = 512;
+
+/// How an SRT connection is established.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SrtMode {
+    /// Connect to a listener at `remote_address`
+    #[default]
+    Caller,
+    /// Wait on `local_address` for a caller
+    Listener,
+    /// Connect to a peer at `remote_address` that connects back at the same time, both sides
+    /// binding a known `local_address` (traverses NATs and firewalls)
+    Rendezvous,
+}
+
+/// Configuration for `transport::srt::input` and `transport::srt::output`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct SrtConfig {
+    /// How the connection is established
+    #[serde(default)]
+    pub mode: SrtMode,
+    /// Peer `host:port` (caller and rendezvous modes)
+    #[serde(default)]
+    #[schemars(extend("sensitive" = true))]
+    pub remote_address: Option<String>,
+    /// Local `host:port` to bind (listener and rendezvous modes; callers default to any
+    /// interface and an ephemeral port)
+    #[serde(default)]
+    pub local_address: Option<String>,
+    /// Receiver buffering delay; the peers use the larger of their two values
+    #[serde(default = "default_latency_ms")]
+    #[schemars(range(max = 60000))]
+    pub latency_ms: u64,
+    /// Encrypts the stream; both peers must use the same passphrase (10 to 79 characters)
+    #[serde(default)]
+    #[schemars(extend("sensitive" = true), length(min = 10, max = 79))]
+    pub passphrase: Option<String>,
+    /// AES key length in bytes when encrypting: 16, 24 or 32 (listeners use the caller's)
+    #[serde(default = "default_key_length")]
+    pub key_length: usize,
+    /// Stream ID sent to the listener in caller and rendezvous modes, e.g. `#!::r=live/feed`
+    #[serde(default)]
+    #[schemars(length(max = 512))]
+    pub stream_id: Option<String>,
+    /// How long caller and rendezvous modes try to connect before failing
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+}
+
+const fn default_latency_ms() -> u64 {
+    DEFAULT_LATENCY_MS
+}
+
+const fn default_key_length() -> usize {
+    DEFAULT_KEY_LENGTH
+}
+
+const fn default_connect_timeout_ms() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_MS
+}
+
+impl SrtConfig {
+    fn validate(&self) -> Result<(), StreamKitError> {
+        let error = |msg: String| Err(StreamKitError::Configuration(msg));
+        let needs_remote = matches!(self.mode, SrtMode::Caller | SrtMode::Rendezvous);
+        let needs_local = matches!(self.mode, SrtMode::Listener | SrtMode::Rendezvous);
+        if needs_remote && self.remote_address.is_none() {
+            return error(format!("remote_address is required in {:?} mode", self.mode));
+        }
+        if needs_local && self.local_address.is_none() {
+            return error(format!("local_address is required in {:?} mode", self.mode));
+        }
+        if self.latency_ms > MAX_LATENCY_MS {
+            return error(format!(
+                "latency_ms must be at most {MAX_LATENCY_MS}, got {}",
+                self.latency_ms
+            ));
+        }
+        if let Some(passphrase) = &self.passphrase {
+            if !(MIN_PASSPHRASE_LENGTH..=MAX_PASSPHRASE_LENGTH).contains(&passphrase.len()) {
+                return error(format!(
+                    "passphrase must be {MIN_PASSPHRASE_LENGTH} to {MAX_PASSPHRASE_LENGTH} \
+                     characters long"
+                ));
+            }
+        }
+        if ![16, 24, 32].contains(&self.key_length) {
+            return error(format!("key_length must be 16, 24 or 32, got {}", self.key_length));
+        }
+        if self.stream_id.as_ref().is_some_and(|id| id.len() > MAX_STREAM_ID_LENGTH) {
+            return error(format!("stream_id must be at most {MAX_STREAM_ID_LENGTH} bytes"));
+        }
+        Ok(())
+    }
+
+    fn options(&self) -> SrtOptions {
+        SrtOptions {
+            latency: Duration::from_millis(self.latency_ms),
+            passphrase: self.passphrase.clone(),
+            key_length: self.key_length,
+            stream_id: self.stream_id.clone(),
+            connect_timeout: Duration::from_millis(self.connect_timeout_ms),
+        }
+    }
+
+    async fn connect(&self) -> Result<SrtConnection, String> {
+        let remote = match &self.remote_address {
+            Some(address) => Some(resolve(address).await?),
+            None => None,
+        };
+        let local = self.local_address.clone().unwrap_or_else(|| {
+            let any = if remote.is_some_and(|r| r.is_ipv6()) { "[::]:0" } else { "0.0.0.0:0" };
+            any.to_string()
+        });
+        let socket = UdpSocket::bind(&local)
+            .await
+            .map_err(|e| format!("Failed to bind SRT socket to {local}: {e}"))?;
+        tracing::info!(
+            "SRT {:?} bound to {}",
+            self.mode,
+            socket.local_addr().map_or_else(|_| local.clone(), |a| a.to_string())
+        );
+
+        let socket = Arc::new(socket);
+        let options = self.options();
+        match (self.mode, remote) {
+            (SrtMode::Listener, _) => connection::listen(socket, &options).await,
+            (SrtMode::Caller, Some(remote)) => connection::call(socket, remote, &options).await,
+            (SrtMode::Rendezvous, Some(remote)) => {
+                connection::rendezvous(socket, remote, &options).await
+            },
+            (mode, None) => Err(format!("remote_address is required in {mode:?} mode")),
+        }
+    }
+}
+
+async fn resolve(address: &str) -> Result<SocketAddr, String> {
+    tokio::net::lookup_host(address)
+        .await
+        .map_err(|e| format!("Failed to resolve SRT peer {address}: {e}"))?
+        .next()
+        .ok_or_else(|| format!("SRT peer {address} resolved to no address"))
+}
+
+/// Handles control messages until Shutdown; never returns if the control channel closes.
+async fn wait_for_shutdown(control_rx: &mut mpsc::Receiver<NodeControlMessage>) {
+    while let Some(msg) = control_rx.recv().await {
+        match msg {
+            NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+            NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+            NodeControlMessage::Shutdown => return,
+        }
+    }
+    std::future::pending::<()>().await;
+}
+
+/// Connects, giving up if the node is shut down first. Returns `Ok(None)` on shutdown.
+async fn connect_until_shutdown(
+    config: &SrtConfig,
+    context: &mut NodeContext,
+) -> Result<Option<SrtConnection>, String> {
+    tokio::select! {
+        result = config.connect() => result.map(Some),
+        () = wait_for_shutdown(&mut context.control_rx) => Ok(None),
+    }
+}
+
+fn log_connected(node: &str, connection: &SrtConnection) {
+    tracing::info!(
+        "{} connected to {} (stream ID {:?}, latency {} ms)",
+        node,
+        connection.peer(),
+        connection.stream_id(),
+        connection.latency().as_millis()
+    );
+}
+
+// --- Input node ---
+
+/// Receives an SRT stream and emits its payload as MPEG-TS chunks.
+pub struct SrtInputNode {
+    config: SrtConfig,
+}
+
+impl SrtInputNode {
+    /// Creates an SRT input node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if an address required by the mode is missing or a value
+    /// is out of range.
+    pub fn new(config: SrtConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for SrtInputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: PacketType::Binary,
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::UpdateParams(_)) => {},
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::Shutdown) | None => {
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                    return Ok(());
+                },
+            }
+        }
+
+        let mut connection = match connect_until_shutdown(&self.config, &mut context).await {
+            Ok(Some(connection)) => connection,
+            Ok(None) => {
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                return Ok(());
+            },
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        log_connected("SrtInputNode", &connection);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let socket = connection.socket().clone();
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+        let reason = loop {
+            tokio::select! {
+                received = socket.recv_from(&mut buf) => match received {
+                    Ok((len, from)) => connection.handle_datagram(from, &buf[..len], Instant::now()),
+                    Err(e) => {
+                        tracing::debug!("SrtInputNode failed to receive packet: {}", e);
+                        stats_tracker.errored();
+                    },
+                },
+                () = tokio::time::sleep_until(connection.next_wakeup()) => {},
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+
+            let now = Instant::now();
+            if let Err(err_msg) = connection.on_timer(now) {
+                stats_tracker.force_send();
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            }
+            // Once the peer is gone, whatever is buffered is delivered right away
+            let peer_closed = connection.is_closed();
+            let mut output_closed = false;
+            while let Some(payload) =
+                if peer_closed { connection.pop_remaining() } else { connection.pop_ready(now) }
+            {
+                stats_tracker.received();
+                let packet = Packet::Binary {
+                    data: payload,
+                    content_type: Some(Cow::Borrowed("video/mp2t")),
+                    metadata: None,
+                };
+                if context.output_sender.send("out", packet).await.is_err() {
+                    output_closed = true;
+                    break;
+                }
+                stats_tracker.sent();
+            }
+            for _ in 0..connection.take_dropped() {
+                stats_tracker.discarded();
+            }
+            if output_closed {
+                tracing::debug!("Output channel closed, stopping node");
+                break "output_closed";
+            }
+            if peer_closed {
+                tracing::info!("SrtInputNode peer closed the connection");
+                break "peer_closed";
+            }
+            stats_tracker.maybe_send();
+        };
+
+        connection.close();
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+// --- Output node ---
+
+/// Sends a byte stream (normally MPEG-TS) over SRT.
+pub struct SrtOutputNode {
+    config: SrtConfig,
+}
+
+impl SrtOutputNode {
+    /// Creates an SRT output node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if an address required by the mode is missing or a value
+    /// is out of range.
+    pub fn new(config: SrtConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
+
+/// Lets the peer acknowledge (or give up on) what is still in flight before closing.
+async fn drain(connection: &mut SrtConnection, buf: &mut [u8]) {
+    let deadline = Instant::now() + connection.latency() * 2;
+    let socket = connection.socket().clone();
+    while connection.unacknowledged() > 0 && !connection.is_closed() {
+        let now = Instant::now();
+        if now >= deadline || connection.on_timer(now).is_err() {
+            break;
+        }
+        tokio::select! {
+            Ok((len, from)) = socket.recv_from(buf) => {
+                connection.handle_datagram(from, &buf[..len], Instant::now());
+            },
+            () = tokio::time::sleep_until(connection.next_wakeup().min(deadline)) => {},
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for SrtOutputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Binary],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let mut connection = match connect_until_shutdown(&self.config, &mut context).await {
+            Ok(Some(connection)) => connection,
+            Ok(None) => {
+                state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                return Ok(());
+            },
+            Err(err_msg) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            },
+        };
+        log_connected("SrtOutputNode", &connection);
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let socket = connection.socket().clone();
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+        let reason = loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else {
+                        drain(&mut connection, &mut buf).await;
+                        break "input_closed";
+                    };
+                    let Packet::Binary { data, .. } = packet else {
+                        tracing::warn!("SrtOutputNode received non-binary packet, ignoring");
+                        stats_tracker.discarded();
+                        continue;
+                    };
+                    stats_tracker.received();
+                    let now = Instant::now();
+                    for chunk in data.chunks(MAX_PAYLOAD_SIZE) {
+                        connection.send(chunk, now);
+                    }
+                    stats_tracker.sent();
+                },
+                received = socket.recv_from(&mut buf) => match received {
+                    Ok((len, from)) => connection.handle_datagram(from, &buf[..len], Instant::now()),
+                    Err(e) => {
+                        tracing::debug!("SrtOutputNode failed to receive packet: {}", e);
+                        stats_tracker.errored();
+                    },
+                },
+                () = tokio::time::sleep_until(connection.next_wakeup()) => {},
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+
+            if let Err(err_msg) = connection.on_timer(Instant::now()) {
+                stats_tracker.force_send();
+                state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                return Err(StreamKitError::Runtime(err_msg));
+            }
+            if connection.is_closed() {
+                tracing::info!("SrtOutputNode peer closed the connection");
+                break "peer_closed";
+            }
+            stats_tracker.maybe_send();
+        };
+
+        connection.close();
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Registers the SRT transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_srt_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = serde_json::to_value(schema_for!(SrtConfig))
+        .expect("SrtConfig schema should serialize to JSON");
+
+    registry.register_static_with_description(
+        "transport::srt::input",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(SrtInputNode::new(config)?))
+        },
+        schema.clone(),
+        StaticPins {
+            inputs: vec![],
+            outputs: vec![OutputPin {
+                name: "out".to_string(),
+                produces_type: PacketType::Binary,
+                cardinality: PinCardinality::Broadcast,
+            }],
+        },
+        vec!["transport".to_string(), "srt".to_string()],
+        false,
+        "Receives an SRT stream as caller, listener or rendezvous peer, with optional \
+         passphrase encryption, and emits its MPEG-TS payload after the negotiated latency. \
+         Security: binds a local UDP port and contacts arbitrary addresses; restrict it via \
+         role allowlists.",
+    );
+
+    registry.register_static_with_description(
+        "transport::srt::output",
+        |params| {
+            let config = config_helpers::parse_config_required(params)?;
+            Ok(Box::new(SrtOutputNode::new(config)?))
+        },
+        schema,
+        StaticPins {
+            inputs: vec![InputPin {
+                name: "in".to_string(),
+                accepts_types: vec![PacketType::Binary],
+                cardinality: PinCardinality::One,
+            }],
+            outputs: vec![],
+        },
+        vec!["transport".to_string(), "srt".to_string()],
+        false,
+        "Sends a byte stream such as MPEG-TS over SRT as caller, listener or rendezvous peer, \
+         retransmitting lost packets within the negotiated latency. \
+         Security: binds a local UDP port and contacts arbitrary addresses; restrict it via \
+         role allowlists.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, create_test_context,
+    };
+    use bytes::Bytes;
+    use std::collections::HashMap;
+
+    fn config(value: serde_json::Value) -> SrtConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(SrtOutputNode::new(config(serde_json::json!({}))).is_err());
+        assert!(SrtInputNode::new(config(serde_json::json!({"mode": "listener"}))).is_err());
+        assert!(SrtInputNode::new(config(serde_json::json!({
+            "mode": "rendezvous",
+            "remote_address": "192.0.2.1:9000",
+        })))
+        .is_err());
+        assert!(SrtOutputNode::new(config(serde_json::json!({
+            "remote_address": "192.0.2.1:9000",
+            "passphrase": "short",
+        })))
+        .is_err());
+        assert!(SrtOutputNode::new(config(serde_json::json!({
+            "remote_address": "192.0.2.1:9000",
+            "key_length": 20,
+        })))
+        .is_err());
+
+        let valid = config(serde_json::json!({
+            "mode": "listener",
+            "local_address": "127.0.0.1:9000",
+            "passphrase": "long enough passphrase",
+        }));
+        assert_eq!(valid.latency_ms, DEFAULT_LATENCY_MS);
+        assert_eq!(valid.key_length, DEFAULT_KEY_LENGTH);
+        assert!(SrtInputNode::new(valid).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_output_to_input_over_loopback() {
+        // Find a free port for the listener
+        let port = std::net::UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let address = format!("127.0.0.1:{port}");
+        let passphrase = "loopback passphrase";
+
+        let input = SrtInputNode::new(config(serde_json::json!({
+            "mode": "listener",
+            "local_address": address,
+            "latency_ms": 40,
+            "passphrase": passphrase,
+        })))
+        .unwrap();
+        let (mut input_context, input_sender, mut input_state_rx) =
+            create_test_context(HashMap::new(), 16);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        input_context.control_rx = control_rx;
+        let input_handle = tokio::spawn(async move { Box::new(input).run(input_context).await });
+        control_tx.send(NodeControlMessage::Start).await.unwrap();
+
+        let output = SrtOutputNode::new(config(serde_json::json!({
+            "remote_address": address,
+            "latency_ms": 40,
+            "passphrase": passphrase,
+        })))
+        .unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        let (output_context, _output_senders, mut output_state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 16);
+        let output_handle = tokio::spawn(async move { Box::new(output).run(output_context).await });
+        assert_state_initializing(&mut output_state_rx).await;
+        assert_state_running(&mut output_state_rx).await;
+
+        // Ten TS packets, split into a full chunk and a remainder
+        let stream: Vec<u8> =
+            (0..10).flat_map(|i| [0x47, i].into_iter().chain([0u8; 186])).collect();
+        tx.send(Packet::Binary {
+            data: Bytes::from(stream.clone()),
+            content_type: None,
+            metadata: None,
+        })
+        .await
+        .unwrap();
+        drop(tx);
+
+        let mut received = Vec::new();
+        while received.len() < stream.len() {
+            let (_, pin, packet) = input_sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+            assert_eq!(pin, "out");
+            let Packet::Binary { data, content_type, .. } = packet else {
+                panic!("expected binary packets");
+            };
+            assert_eq!(content_type.as_deref(), Some("video/mp2t"));
+            assert!(data.len() <= MAX_PAYLOAD_SIZE);
+            received.extend_from_slice(&data);
+        }
+        assert_eq!(received, stream);
+
+        assert_state_stopped(&mut output_state_rx).await;
+        output_handle.await.unwrap().unwrap();
+        // The output closing the connection stops the input
+        assert_state_initializing(&mut input_state_rx).await;
+        input_handle.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,396 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! SRT wire format: data packets, control packets and the HSv5 handshake with its extensions.
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+const HEADER_SIZE: usize = 16;
+/// Sequence numbers are 31 bits wide.
+pub(super) const SEQ_MASK: u32 = 0x7FFF_FFFF;
+const MSG_NO_MASK: u32 = 0x03FF_FFFF;
+
+/// `KK` flag of a data packet encrypted with the even key.
+pub(super) const KEY_EVEN: u8 = 0b01;
+
+const CONTROL_HANDSHAKE: u16 = 0x0000;
+const CONTROL_KEEPALIVE: u16 = 0x0001;
+const CONTROL_ACK: u16 = 0x0002;
+const CONTROL_NAK: u16 = 0x0003;
+const CONTROL_SHUTDOWN: u16 = 0x0005;
+const CONTROL_ACKACK: u16 = 0x0006;
+
+/// Receive buffer space advertised in ACKs, in packets.
+const ACK_AVAILABLE_BUFFER: u32 = 8192;
+
+/// Extension field of an induction response, announcing HSv5 support.
+pub(super) const SRT_MAGIC: u16 = 0x4A17;
+
+pub(super) const HS_WAVEAHAND: u32 = 0;
+pub(super) const HS_INDUCTION: u32 = 1;
+pub(super) const HS_CONCLUSION: u32 = 0xFFFF_FFFF;
+pub(super) const HS_AGREEMENT: u32 = 0xFFFF_FFFE;
+/// Handshake types from this value on reject the connection, carrying `1000 + reason`.
+pub(super) const HS_REJECTION_BASE: u32 = 1000;
+
+pub(super) const REJ_VERSION: u32 = 8;
+pub(super) const REJ_BADSECRET: u32 = 10;
+pub(super) const REJ_UNSECURE: u32 = 11;
+
+/// Handshake extension flags, set in the extension field of a conclusion.
+pub(super) const HS_EXT_HSREQ: u16 = 0x1;
+pub(super) const HS_EXT_KMREQ: u16 = 0x2;
+pub(super) const HS_EXT_CONFIG: u16 = 0x4;
+
+pub(super) const EXT_HSREQ: u16 = 1;
+pub(super) const EXT_HSRSP: u16 = 2;
+pub(super) const EXT_KMREQ: u16 = 3;
+pub(super) const EXT_KMRSP: u16 = 4;
+pub(super) const EXT_SID: u16 = 5;
+
+/// Offset between 31-bit sequence numbers `from` and `to`, taking wraparound into account.
+pub(super) fn seq_offset(from: u32, to: u32) -> i64 {
+    let diff = to.wrapping_sub(from) & SEQ_MASK;
+    if diff > SEQ_MASK / 2 {
+        i64::from(diff) - i64::from(SEQ_MASK) - 1
+    } else {
+        i64::from(diff)
+    }
+}
+
+pub(super) const fn seq_add(seq: u32, n: u32) -> u32 {
+    seq.wrapping_add(n) & SEQ_MASK
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DataPacket {
+    pub seq: u32,
+    pub msg_no: u32,
+    /// Encryption key flag (`KK`): 0 when the payload is in the clear
+    pub key: u8,
+    pub retransmitted: bool,
+    pub timestamp: u32,
+    pub dest: u32,
+    pub payload: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Extension {
+    pub kind: u16,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Handshake {
+    pub version: u32,
+    pub encryption: u16,
+    pub extension: u16,
+    pub initial_seq: u32,
+    pub mtu: u32,
+    pub flow_window: u32,
+    pub kind: u32,
+    pub socket_id: u32,
+    pub cookie: u32,
+    pub peer_ip: [u8; 16],
+    pub extensions: Vec<Extension>,
+}
+
+impl Handshake {
+    pub fn find(&self, kind: u16) -> Option<&[u8]> {
+        self.extensions.iter().find(|e| e.kind == kind).map(|e| e.data.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Control {
+    Handshake(Handshake),
+    KeepAlive,
+    /// Acknowledges every packet before `last_ack`, reporting the receiver's RTT estimate
+    Ack {
+        ack_no: u32,
+        last_ack: u32,
+        rtt_us: u32,
+        rtt_var_us: u32,
+    },
+    /// Inclusive ranges of lost sequence numbers
+    Nak(Vec<(u32, u32)>),
+    Shutdown,
+    AckAck(u32),
+    /// Control types this implementation ignores
+    Other(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Packet {
+    Data(DataPacket),
+    Control { timestamp: u32, dest: u32, control: Control },
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+impl Packet {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let word0 = read_u32(data, 0)?;
+        let info = read_u32(data, 4)?;
+        let timestamp = read_u32(data, 8)?;
+        let dest = read_u32(data, 12)?;
+        if word0 & 0x8000_0000 == 0 {
+            return Some(Self::Data(DataPacket {
+                seq: word0,
+                msg_no: info & MSG_NO_MASK,
+                key: u8::try_from((info >> 27) & 0b11).unwrap_or_default(),
+                retransmitted: info & (1 << 26) != 0,
+                timestamp,
+                dest,
+                payload: Bytes::copy_from_slice(&data[HEADER_SIZE..]),
+            }));
+        }
+
+        let cif = &data[HEADER_SIZE..];
+        let control = match u16::try_from((word0 >> 16) & 0x7FFF).unwrap_or_default() {
+            CONTROL_HANDSHAKE => Control::Handshake(parse_handshake(cif)?),
+            CONTROL_KEEPALIVE => Control::KeepAlive,
+            CONTROL_ACK => Control::Ack {
+                ack_no: info,
+                last_ack: read_u32(cif, 0)? & SEQ_MASK,
+                // Absent from light ACKs
+                rtt_us: read_u32(cif, 4).unwrap_or_default(),
+                rtt_var_us: read_u32(cif, 8).unwrap_or_default(),
+            },
+            CONTROL_NAK => {
+                let mut ranges = Vec::new();
+                let mut fields =
+                    cif.chunks_exact(4).map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]));
+                while let Some(word) = fields.next() {
+                    if word & 0x8000_0000 == 0 {
+                        ranges.push((word, word));
+                    } else {
+                        ranges.push((word & SEQ_MASK, fields.next()? & SEQ_MASK));
+                    }
+                }
+                Control::Nak(ranges)
+            },
+            CONTROL_SHUTDOWN => Control::Shutdown,
+            CONTROL_ACKACK => Control::AckAck(info),
+            other => Control::Other(other),
+        };
+        Some(Self::Control { timestamp, dest, control })
+    }
+
+    pub fn write(&self, out: &mut BytesMut) {
+        match self {
+            Self::Data(packet) => {
+                out.put_u32(packet.seq & SEQ_MASK);
+                // PP = 0b11 (solo packet), O = 0, KK, R, message number
+                out.put_u32(
+                    (0b11 << 30)
+                        | (u32::from(packet.key & 0b11) << 27)
+                        | (u32::from(packet.retransmitted) << 26)
+                        | (packet.msg_no & MSG_NO_MASK),
+                );
+                out.put_u32(packet.timestamp);
+                out.put_u32(packet.dest);
+                out.extend_from_slice(&packet.payload);
+            },
+            Self::Control { timestamp, dest, control } => {
+                let (kind, info) = match control {
+                    Control::Handshake(_) => (CONTROL_HANDSHAKE, 0),
+                    Control::KeepAlive => (CONTROL_KEEPALIVE, 0),
+                    Control::Ack { ack_no, .. } => (CONTROL_ACK, *ack_no),
+                    Control::Nak(_) => (CONTROL_NAK, 0),
+                    Control::Shutdown => (CONTROL_SHUTDOWN, 0),
+                    Control::AckAck(ack_no) => (CONTROL_ACKACK, *ack_no),
+                    Control::Other(kind) => (*kind, 0),
+                };
+                out.put_u32(0x8000_0000 | (u32::from(kind) << 16));
+                out.put_u32(info);
+                out.put_u32(*timestamp);
+                out.put_u32(*dest);
+                match control {
+                    Control::Handshake(handshake) => write_handshake(handshake, out),
+                    Control::Ack { last_ack, rtt_us, rtt_var_us, .. } => {
+                        out.put_u32(*last_ack);
+                        out.put_u32(*rtt_us);
+                        out.put_u32(*rtt_var_us);
+                        out.put_u32(ACK_AVAILABLE_BUFFER);
+                    },
+                    Control::Nak(ranges) => {
+                        for &(first, last) in ranges {
+                            if first == last {
+                                out.put_u32(first & SEQ_MASK);
+                            } else {
+                                out.put_u32(0x8000_0000 | (first & SEQ_MASK));
+                                out.put_u32(last & SEQ_MASK);
+                            }
+                        }
+                    },
+                    // libsrt pads these with one word
+                    Control::KeepAlive | Control::Shutdown | Control::AckAck(_) => out.put_u32(0),
+                    Control::Other(_) => {},
+                }
+            },
+        }
+    }
+}
+
+fn parse_handshake(cif: &[u8]) -> Option<Handshake> {
+    let mut peer_ip = [0u8; 16];
+    peer_ip.copy_from_slice(cif.get(32..48)?);
+    let mut extensions = Vec::new();
+    let mut pos = 48;
+    while pos + 4 <= cif.len() {
+        let kind = read_u16(cif, pos)?;
+        let len = usize::from(read_u16(cif, pos + 2)?) * 4;
+        let data = cif.get(pos + 4..pos + 4 + len)?;
+        extensions.push(Extension { kind, data: data.to_vec() });
+        pos += 4 + len;
+    }
+    Some(Handshake {
+        version: read_u32(cif, 0)?,
+        encryption: read_u16(cif, 4)?,
+        extension: read_u16(cif, 6)?,
+        initial_seq: read_u32(cif, 8)? & SEQ_MASK,
+        mtu: read_u32(cif, 12)?,
+        flow_window: read_u32(cif, 16)?,
+        kind: read_u32(cif, 20)?,
+        socket_id: read_u32(cif, 24)?,
+        cookie: read_u32(cif, 28)?,
+        peer_ip,
+        extensions,
+    })
+}
+
+fn write_handshake(handshake: &Handshake, out: &mut BytesMut) {
+    out.put_u32(handshake.version);
+    out.put_u16(handshake.encryption);
+    out.put_u16(handshake.extension);
+    out.put_u32(handshake.initial_seq);
+    out.put_u32(handshake.mtu);
+    out.put_u32(handshake.flow_window);
+    out.put_u32(handshake.kind);
+    out.put_u32(handshake.socket_id);
+    out.put_u32(handshake.cookie);
+    out.extend_from_slice(&handshake.peer_ip);
+    for extension in &handshake.extensions {
+        let words = extension.data.len().div_ceil(4);
+        out.put_u16(extension.kind);
+        out.put_u16(u16::try_from(words).unwrap_or(u16::MAX));
+        out.extend_from_slice(&extension.data);
+        out.put_bytes(0, words * 4 - extension.data.len());
+    }
+}
+
+/// HSREQ/HSRSP contents: SRT version, capability flags and both TSBPD delays.
+pub(super) fn hs_req(version: u32, flags: u32, latency_ms: u16) -> Vec<u8> {
+    let mut data = Vec::with_capacity(12);
+    data.extend_from_slice(&version.to_be_bytes());
+    data.extend_from_slice(&flags.to_be_bytes());
+    // Receiver delay in the upper half, sender delay in the lower half
+    data.extend_from_slice(&latency_ms.to_be_bytes());
+    data.extend_from_slice(&latency_ms.to_be_bytes());
+    data
+}
+
+/// Parses HSREQ/HSRSP contents into (receiver delay, sender delay) in milliseconds.
+pub(super) fn parse_hs_req(data: &[u8]) -> Option<(u16, u16)> {
+    Some((read_u16(data, 8)?, read_u16(data, 10)?))
+}
+
+/// Encodes a stream ID. libsrt byte-swaps every 32-bit word of it on the wire.
+pub(super) fn encode_stream_id(stream_id: &str) -> Vec<u8> {
+    let mut data = stream_id.as_bytes().to_vec();
+    data.resize(data.len().div_ceil(4) * 4, 0);
+    for word in data.chunks_exact_mut(4) {
+        word.reverse();
+    }
+    data
+}
+
+pub(super) fn decode_stream_id(data: &[u8]) -> String {
+    let mut bytes = data.to_vec();
+    for word in bytes.chunks_exact_mut(4) {
+        word.reverse();
+    }
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(packet: &Packet) -> Packet {
+        let mut out = BytesMut::new();
+        packet.write(&mut out);
+        Packet::parse(&out).unwrap()
+    }
+
+    #[test]
+    fn test_data_packet_roundtrip() {
+        let packet = Packet::Data(DataPacket {
+            seq: SEQ_MASK,
+            msg_no: 12,
+            key: KEY_EVEN,
+            retransmitted: true,
+            timestamp: 1_000_000,
+            dest: 0x1234,
+            payload: Bytes::from_static(&[0x47; 188]),
+        });
+        assert_eq!(roundtrip(&packet), packet);
+    }
+
+    #[test]
+    fn test_control_packets_roundtrip() {
+        let handshake = Handshake {
+            version: 5,
+            encryption: 2,
+            extension: HS_EXT_HSREQ | HS_EXT_CONFIG,
+            initial_seq: 42,
+            mtu: 1500,
+            flow_window: 8192,
+            kind: HS_CONCLUSION,
+            socket_id: 7,
+            cookie: 0xC00C_1E00,
+            peer_ip: [1; 16],
+            extensions: vec![
+                Extension { kind: EXT_HSREQ, data: hs_req(0x0001_0500, 0x3F, 120) },
+                Extension { kind: EXT_SID, data: encode_stream_id("live/feed") },
+            ],
+        };
+        for control in [
+            Control::Handshake(handshake),
+            Control::Ack { ack_no: 3, last_ack: 100, rtt_us: 20_000, rtt_var_us: 5000 },
+            Control::Nak(vec![(5, 5), (SEQ_MASK - 1, 2)]),
+            Control::AckAck(3),
+            Control::KeepAlive,
+            Control::Shutdown,
+        ] {
+            let packet = Packet::Control { timestamp: 9, dest: 7, control };
+            assert_eq!(roundtrip(&packet), packet);
+        }
+    }
+
+    #[test]
+    fn test_stream_id_and_sequence_helpers() {
+        let encoded = encode_stream_id("#!::r=live");
+        assert_eq!(&encoded[..4], b"::!#");
+        assert_eq!(encoded.len(), 12);
+        assert_eq!(decode_stream_id(&encoded), "#!::r=live");
+
+        assert_eq!(seq_offset(SEQ_MASK, 1), 2);
+        assert_eq!(seq_offset(1, SEQ_MASK), -2);
+        assert_eq!(seq_add(SEQ_MASK, 1), 0);
+    }
+}
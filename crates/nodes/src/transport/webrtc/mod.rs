@@ -0,0 +1,215 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! WebRTC transport nodes, signaled over WHIP and WHEP.
+//!
+//! `transport::webrtc::whip_ingest` lets a browser or encoder publish Opus audio and VP9 video
+//! into a session, and `transport::webrtc::whep_egress` plays a session's Opus/VP9 streams to
+//! browsers. The server hosts the HTTP signaling endpoints and forwards each SDP offer to the
+//! node through [`streamkit_core::webrtc_gateway`]; the node negotiates the peer connection
+//! itself and answers with all ICE candidates gathered (no trickle ICE).
+//!
+//! RTP payloads are (de)packetized with the same code as `transport::rtp`, so frames enter and
+//! leave these nodes in the formats the Opus decoder and the VP9 nodes already use.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::webrtc_gateway::{WebRtcAnswer, WebRtcRejection};
+use streamkit_core::{NodeRegistry, StreamKitError};
+use webrtc::api::interceptor_registry::register_default_interceptors;
+use webrtc::api::media_engine::{MediaEngine, MIME_TYPE_OPUS, MIME_TYPE_VP9};
+use webrtc::api::setting_engine::SettingEngine;
+use webrtc::api::{APIBuilder, API};
+use webrtc::ice_transport::ice_candidate_type::RTCIceCandidateType;
+use webrtc::ice_transport::ice_server::RTCIceServer;
+use webrtc::interceptor::registry::Registry;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtp_transceiver::rtp_codec::{
+    RTCRtpCodecCapability, RTCRtpCodecParameters, RTPCodecType,
+};
+use webrtc::rtp_transceiver::RTCPFeedback;
+
+use super::rtp::{random_u32, RtpCodec};
+
+pub mod whep;
+pub mod whip;
+
+/// Payload types offered to clients; the common choices of browser WebRTC stacks
+const OPUS_PAYLOAD_TYPE: u8 = 111;
+const VP9_PAYLOAD_TYPE: u8 = 98;
+
+/// How long ICE candidate gathering may take before the answer is sent without the rest
+const GATHER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Settings shared by both WebRTC nodes.
+#[derive(Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct WebRtcNetworkConfig {
+    /// STUN/TURN server URLs used to gather candidates, e.g. `stun:stun.l.google.com:19302`.
+    /// Leave empty when clients can reach the server's host addresses directly.
+    #[serde(default)]
+    pub ice_servers: Vec<String>,
+    /// Public IP addresses to advertise instead of the host's own, for servers behind a
+    /// static 1:1 NAT (typical for cloud VMs).
+    #[serde(default)]
+    pub nat_1to1_ips: Vec<String>,
+}
+
+impl WebRtcNetworkConfig {
+    /// Builds the WebRTC API with only the codecs the nodes can (de)packetize.
+    fn build_api(&self) -> Result<API, webrtc::Error> {
+        let mut media_engine = MediaEngine::default();
+        media_engine.register_codec(
+            RTCRtpCodecParameters {
+                capability: RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_OPUS.to_owned(),
+                    clock_rate: 48_000,
+                    channels: 2,
+                    sdp_fmtp_line: "minptime=10;useinbandfec=1".to_owned(),
+                    rtcp_feedback: vec![],
+                },
+                payload_type: OPUS_PAYLOAD_TYPE,
+                ..Default::default()
+            },
+            RTPCodecType::Audio,
+        )?;
+        let video_feedback = [("nack", ""), ("nack", "pli"), ("ccm", "fir")]
+            .into_iter()
+            .map(|(typ, parameter)| RTCPFeedback {
+                typ: typ.to_owned(),
+                parameter: parameter.to_owned(),
+            })
+            .collect();
+        media_engine.register_codec(
+            RTCRtpCodecParameters {
+                capability: RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_VP9.to_owned(),
+                    clock_rate: 90_000,
+                    channels: 0,
+                    sdp_fmtp_line: "profile-id=0".to_owned(),
+                    rtcp_feedback: video_feedback,
+                },
+                payload_type: VP9_PAYLOAD_TYPE,
+                ..Default::default()
+            },
+            RTPCodecType::Video,
+        )?;
+
+        // NACK responder/generator, RTCP reports and TWCC
+        let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+
+        let mut setting_engine = SettingEngine::default();
+        if !self.nat_1to1_ips.is_empty() {
+            setting_engine.set_nat_1to1_ips(self.nat_1to1_ips.clone(), RTCIceCandidateType::Host);
+        }
+
+        Ok(APIBuilder::new()
+            .with_media_engine(media_engine)
+            .with_interceptor_registry(registry)
+            .with_setting_engine(setting_engine)
+            .build())
+    }
+
+    fn rtc_configuration(&self) -> RTCConfiguration {
+        let ice_servers = if self.ice_servers.is_empty() {
+            vec![]
+        } else {
+            vec![RTCIceServer { urls: self.ice_servers.clone(), ..Default::default() }]
+        };
+        RTCConfiguration { ice_servers, ..Default::default() }
+    }
+}
+
+/// Builds the shared WebRTC API, reporting failures as node configuration errors.
+fn build_api(config: &WebRtcNetworkConfig) -> Result<Arc<API>, StreamKitError> {
+    config
+        .build_api()
+        .map(Arc::new)
+        .map_err(|e| StreamKitError::Configuration(format!("Failed to set up WebRTC: {e}")))
+}
+
+/// Maps a negotiated track to the payload format the nodes handle.
+const fn track_codec(kind: RTPCodecType) -> Option<RtpCodec> {
+    match kind {
+        RTPCodecType::Audio => Some(RtpCodec::Opus),
+        RTPCodecType::Video => Some(RtpCodec::Vp9),
+        RTPCodecType::Unspecified => None,
+    }
+}
+
+/// Opaque ID of a WHIP/WHEP resource, used in its `Location` URL.
+fn new_resource_id() -> String {
+    format!("{:08x}{:08x}", random_u32(), random_u32())
+}
+
+/// Answers an offer on a fresh peer connection, waiting for ICE gathering to finish so the
+/// answer carries every candidate.
+async fn negotiate(
+    pc: &RTCPeerConnection,
+    offer_sdp: String,
+    resource_id: String,
+) -> Result<WebRtcAnswer, WebRtcRejection> {
+    let offer = RTCSessionDescription::offer(offer_sdp)
+        .map_err(|e| WebRtcRejection::InvalidOffer(format!("Invalid SDP offer: {e}")))?;
+    pc.set_remote_description(offer)
+        .await
+        .map_err(|e| WebRtcRejection::InvalidOffer(format!("Unusable SDP offer: {e}")))?;
+    let answer = pc
+        .create_answer(None)
+        .await
+        .map_err(|e| WebRtcRejection::Failed(format!("Failed to create answer: {e}")))?;
+    let mut gathering_complete = pc.gathering_complete_promise().await;
+    pc.set_local_description(answer)
+        .await
+        .map_err(|e| WebRtcRejection::Failed(format!("Failed to apply answer: {e}")))?;
+    if tokio::time::timeout(GATHER_TIMEOUT, gathering_complete.recv()).await.is_err() {
+        tracing::warn!("ICE gathering timed out, answering with the candidates found so far");
+    }
+    let sdp = pc
+        .local_description()
+        .await
+        .ok_or_else(|| WebRtcRejection::Failed("No local description".to_string()))?
+        .sdp;
+    Ok(WebRtcAnswer { sdp, resource_id })
+}
+
+/// Registers the WebRTC transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schemas cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_webrtc_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let factory = whip::WhipIngestNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::webrtc::whip_ingest",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(whip::WhipIngestConfig))
+            .expect("WhipIngestConfig schema should serialize to JSON"),
+        vec!["transport".to_string(), "webrtc".to_string()],
+        false,
+        "Accepts one WebRTC publisher over WHIP at \
+         /api/v1/sessions/{session}/whip/{node} and emits its Opus audio and VP9 video. \
+         Dynamic sessions only. Security: opens UDP ports for ICE; publishing requires the \
+         modify_sessions permission.",
+    );
+
+    let factory = whep::WhepEgressNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::webrtc::whep_egress",
+        move |params| (factory)(params),
+        serde_json::to_value(schema_for!(whep::WhepEgressConfig))
+            .expect("WhepEgressConfig schema should serialize to JSON"),
+        vec!["transport".to_string(), "webrtc".to_string()],
+        false,
+        "Plays Opus audio and VP9 video to WebRTC viewers over WHEP at \
+         /api/v1/sessions/{session}/whep/{node}. Dynamic sessions only. \
+         Security: opens UDP ports for ICE; playback requires the list_sessions permission.",
+    );
+}
@@ -0,0 +1,505 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! `transport::webrtc::whep_egress`: plays the session's media to WHEP viewers.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketMetadata};
+use streamkit_core::webrtc_gateway::{
+    get_webrtc_gateway, WebRtcAnswer, WebRtcEndpointKind, WebRtcRejection, WebRtcRequest,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use tokio::sync::{mpsc, oneshot};
+use webrtc::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_VP9};
+use webrtc::api::API;
+use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use webrtc::track::track_local::track_local_static_rtp::TrackLocalStaticRTP;
+use webrtc::track::track_local::{TrackLocal, TrackLocalWriter};
+
+use super::{negotiate, new_resource_id, WebRtcNetworkConfig};
+use crate::transport::rtp::{
+    low_u16, low_u32, random_u32, us_to_ticks, vp9_is_keyframe, Packetizer, RtpCodec, DEFAULT_MTU,
+};
+
+const DEFAULT_MAX_VIEWERS: usize = 16;
+
+/// Configuration for `transport::webrtc::whep_egress`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct WhepEgressConfig {
+    /// ICE servers and NAT settings
+    #[serde(flatten)]
+    pub network: WebRtcNetworkConfig,
+    /// Viewers allowed at once; further offers are refused with 409 Conflict
+    #[serde(default = "default_max_viewers")]
+    pub max_viewers: usize,
+}
+
+impl Default for WhepEgressConfig {
+    fn default() -> Self {
+        Self { network: WebRtcNetworkConfig::default(), max_viewers: DEFAULT_MAX_VIEWERS }
+    }
+}
+
+const fn default_max_viewers() -> usize {
+    DEFAULT_MAX_VIEWERS
+}
+
+/// Progress of a viewer's peer connection, reported by its background tasks
+enum PeerEvent {
+    Negotiated { resource_id: Arc<str>, pc: Arc<RTCPeerConnection> },
+    Closed { resource_id: Arc<str> },
+}
+
+/// A connected (or connecting) viewer with its own tracks
+struct Viewer {
+    /// `None` until negotiation completes
+    pc: Option<Arc<RTCPeerConnection>>,
+    audio: Arc<TrackLocalStaticRTP>,
+    video: Arc<TrackLocalStaticRTP>,
+    /// Video is held back until a keyframe the viewer can start decoding from
+    awaiting_keyframe: bool,
+}
+
+impl Viewer {
+    fn close(self) {
+        if let Some(pc) = self.pc {
+            tokio::spawn(async move {
+                let _ = pc.close().await;
+            });
+        }
+    }
+}
+
+/// RTP state of one outgoing stream, shared by all viewers
+struct RtpStream {
+    codec: RtpCodec,
+    packetizer: Packetizer,
+    sequence: u16,
+    timestamp_offset: u32,
+    next_ticks: Option<u64>,
+}
+
+impl RtpStream {
+    fn new(codec: RtpCodec) -> Self {
+        Self {
+            codec,
+            packetizer: Packetizer::new(codec, DEFAULT_MTU, low_u16(u64::from(random_u32()))),
+            sequence: low_u16(u64::from(random_u32())),
+            timestamp_offset: random_u32(),
+            next_ticks: None,
+        }
+    }
+
+    /// Packetizes a frame, timed from its metadata or following on from the previous one.
+    fn packetize(
+        &mut self,
+        data: &Bytes,
+        metadata: Option<&PacketMetadata>,
+    ) -> Vec<webrtc::rtp::packet::Packet> {
+        let clock_rate = self.codec.clock_rate();
+        let ticks = metadata
+            .and_then(|m| m.timestamp_us)
+            .map(|us| us_to_ticks(us, clock_rate))
+            .or(self.next_ticks)
+            .unwrap_or_default();
+        let duration = metadata
+            .and_then(|m| m.duration_us)
+            .map(|us| us_to_ticks(us, clock_rate))
+            .filter(|&d| d > 0)
+            .unwrap_or_else(|| self.codec.default_frame_ticks());
+        self.next_ticks = Some(ticks + duration);
+        let timestamp = self.timestamp_offset.wrapping_add(low_u32(ticks));
+
+        let payloads = self.packetizer.packetize(data);
+        let last = payloads.len().saturating_sub(1);
+        payloads
+            .into_iter()
+            .enumerate()
+            .map(|(i, payload)| {
+                let header = webrtc::rtp::header::Header {
+                    version: 2,
+                    marker: self.codec.is_video() && i == last,
+                    // The tracks rewrite the payload type and SSRC for each viewer
+                    payload_type: self.codec.default_payload_type(),
+                    sequence_number: self.sequence,
+                    timestamp,
+                    ..Default::default()
+                };
+                self.sequence = self.sequence.wrapping_add(1);
+                webrtc::rtp::packet::Packet { header, payload }
+            })
+            .collect()
+    }
+}
+
+/// Plays Opus audio and VP9 video to WHEP viewers.
+pub struct WhepEgressNode {
+    config: WhepEgressConfig,
+}
+
+impl WhepEgressNode {
+    /// Creates a WHEP egress node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `max_viewers` is zero.
+    pub fn new(config: WhepEgressConfig) -> Result<Self, StreamKitError> {
+        if config.max_viewers == 0 {
+            return Err(StreamKitError::Configuration(
+                "max_viewers must be at least 1".to_string(),
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            let config = if params.is_none() {
+                WhepEgressConfig::default()
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+
+    /// Negotiates a viewer's peer connection in the background; the offer is answered from
+    /// there and the outcome reported through `events_tx`.
+    fn spawn_viewer(
+        &self,
+        api: &Arc<API>,
+        sdp: String,
+        resource_id: Arc<str>,
+        viewer: &Viewer,
+        response_tx: oneshot::Sender<Result<WebRtcAnswer, WebRtcRejection>>,
+        events_tx: mpsc::UnboundedSender<PeerEvent>,
+    ) {
+        let api = Arc::clone(api);
+        let rtc_config = self.config.network.rtc_configuration();
+        let tracks: [Arc<dyn TrackLocal + Send + Sync>; 2] =
+            [viewer.audio.clone(), viewer.video.clone()];
+        tokio::spawn(async move {
+            let result: Result<_, WebRtcRejection> = async {
+                let pc = Arc::new(api.new_peer_connection(rtc_config).await.map_err(|e| {
+                    WebRtcRejection::Failed(format!("Failed to create peer connection: {e}"))
+                })?);
+                for track in tracks {
+                    let sender = pc.add_track(track).await.map_err(|e| {
+                        WebRtcRejection::Failed(format!("Failed to add track: {e}"))
+                    })?;
+                    // RTCP has to be read for the interceptors (NACK, reports) to work
+                    tokio::spawn(async move {
+                        let mut buf = vec![0u8; 1500];
+                        while sender.read(&mut buf).await.is_ok() {}
+                    });
+                }
+                let state_events = events_tx.clone();
+                let state_resource = Arc::clone(&resource_id);
+                pc.on_peer_connection_state_change(Box::new(
+                    move |state: RTCPeerConnectionState| {
+                        tracing::debug!(resource_id = %state_resource, %state, "WHEP peer connection state");
+                        if matches!(
+                            state,
+                            RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed
+                        ) {
+                            let _ = state_events.send(PeerEvent::Closed {
+                                resource_id: Arc::clone(&state_resource),
+                            });
+                        }
+                        Box::pin(async {})
+                    },
+                ));
+                match negotiate(&pc, sdp, resource_id.to_string()).await {
+                    Ok(answer) => Ok((pc, answer)),
+                    Err(rejection) => {
+                        let _ = pc.close().await;
+                        Err(rejection)
+                    },
+                }
+            }
+            .await;
+
+            match result {
+                Ok((pc, answer)) => {
+                    if response_tx.send(Ok(answer)).is_ok() {
+                        let _ = events_tx.send(PeerEvent::Negotiated { resource_id, pc });
+                        return;
+                    }
+                    // The HTTP request timed out in the meantime
+                    let _ = pc.close().await;
+                },
+                Err(rejection) => {
+                    let _ = response_tx.send(Err(rejection));
+                },
+            }
+            let _ = events_tx.send(PeerEvent::Closed { resource_id });
+        });
+    }
+}
+
+fn track(mime_type: &str, clock_rate: u32, channels: u16, kind: &str) -> Arc<TrackLocalStaticRTP> {
+    Arc::new(TrackLocalStaticRTP::new(
+        RTCRtpCodecCapability {
+            mime_type: mime_type.to_owned(),
+            clock_rate,
+            channels,
+            ..Default::default()
+        },
+        kind.to_owned(),
+        "streamkit".to_owned(),
+    ))
+}
+
+#[async_trait]
+impl ProcessorNode for WhepEgressNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![
+            InputPin {
+                name: "audio".to_string(),
+                accepts_types: vec![RtpCodec::Opus.packet_type()],
+                cardinality: PinCardinality::One,
+            },
+            InputPin {
+                name: "video".to_string(),
+                accepts_types: vec![RtpCodec::Vp9.packet_type()],
+                cardinality: PinCardinality::One,
+            },
+        ]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    #[allow(clippy::cognitive_complexity)] // One select loop over signaling, media and control
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        // Either pin may be left unconnected for audio-only or video-only playback
+        let mut audio_rx = context.inputs.remove("audio");
+        let mut video_rx = context.inputs.remove("video");
+
+        let Some(session_id) = context.session_id.clone() else {
+            let err_msg = "whep_egress requires a session (dynamic pipelines only)";
+            state_helpers::emit_failed(&context.state_tx, &node_name, err_msg);
+            return Err(StreamKitError::Configuration(err_msg.to_string()));
+        };
+        let Some(gateway) = get_webrtc_gateway() else {
+            let err_msg = "WebRTC gateway not available; the server was built without WHIP/WHEP";
+            state_helpers::emit_failed(&context.state_tx, &node_name, err_msg);
+            return Err(StreamKitError::Runtime(err_msg.to_string()));
+        };
+        let api = match super::build_api(&self.config.network) {
+            Ok(api) => api,
+            Err(e) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &e.to_string());
+                return Err(e);
+            },
+        };
+        let mut request_rx = match gateway
+            .register_endpoint(session_id.clone(), node_name.clone(), WebRtcEndpointKind::Whep)
+            .await
+        {
+            Ok(rx) => rx,
+            Err(e) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &e);
+                return Err(StreamKitError::Runtime(e));
+            },
+        };
+        tracing::info!(
+            "WhepEgressNode serving viewers at /api/v1/sessions/{}/whep/{} (max {})",
+            session_id,
+            node_name,
+            self.config.max_viewers
+        );
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel::<PeerEvent>();
+        let mut viewers: HashMap<Arc<str>, Viewer> = HashMap::new();
+        let mut audio = RtpStream::new(RtpCodec::Opus);
+        let mut video = RtpStream::new(RtpCodec::Vp9);
+
+        let reason = loop {
+            if audio_rx.is_none() && video_rx.is_none() {
+                break "input_closed";
+            }
+            let (packet, is_video) = tokio::select! {
+                maybe_packet = async { audio_rx.as_mut()?.recv().await }, if audio_rx.is_some() => {
+                    let Some(packet) = maybe_packet else {
+                        audio_rx = None;
+                        continue;
+                    };
+                    (packet, false)
+                },
+                maybe_packet = async { video_rx.as_mut()?.recv().await }, if video_rx.is_some() => {
+                    let Some(packet) = maybe_packet else {
+                        video_rx = None;
+                        continue;
+                    };
+                    (packet, true)
+                },
+                Some(request) = request_rx.recv() => {
+                    match request {
+                        WebRtcRequest::Offer { sdp, response_tx } => {
+                            if viewers.len() >= self.config.max_viewers {
+                                let _ = response_tx.send(Err(WebRtcRejection::Busy(format!(
+                                    "Viewer limit ({}) reached",
+                                    self.config.max_viewers
+                                ))));
+                                continue;
+                            }
+                            let resource_id: Arc<str> = new_resource_id().into();
+                            let viewer = Viewer {
+                                pc: None,
+                                audio: track(MIME_TYPE_OPUS, 48_000, 2, "audio"),
+                                video: track(MIME_TYPE_VP9, 90_000, 0, "video"),
+                                awaiting_keyframe: true,
+                            };
+                            self.spawn_viewer(
+                                &api,
+                                sdp,
+                                Arc::clone(&resource_id),
+                                &viewer,
+                                response_tx,
+                                events_tx.clone(),
+                            );
+                            viewers.insert(resource_id, viewer);
+                        },
+                        WebRtcRequest::Terminate { resource_id, response_tx } => {
+                            let viewer = viewers.remove(resource_id.as_str());
+                            let known = viewer.is_some();
+                            if let Some(viewer) = viewer {
+                                tracing::info!(resource_id = %resource_id, "WHEP viewer left");
+                                viewer.close();
+                            }
+                            let _ = response_tx.send(known);
+                        },
+                    }
+                    continue;
+                },
+                Some(event) = events_rx.recv() => {
+                    match event {
+                        PeerEvent::Negotiated { resource_id, pc } => {
+                            if let Some(viewer) = viewers.get_mut(&resource_id) {
+                                tracing::info!(resource_id = %resource_id, viewers = viewers.len(), "WHEP viewer connected");
+                                viewer.pc = Some(pc);
+                            } else {
+                                // Terminated while negotiating
+                                tokio::spawn(async move {
+                                    let _ = pc.close().await;
+                                });
+                            }
+                        },
+                        PeerEvent::Closed { resource_id } => {
+                            if let Some(viewer) = viewers.remove(&resource_id) {
+                                tracing::info!(resource_id = %resource_id, "WHEP viewer disconnected");
+                                viewer.close();
+                            }
+                        },
+                    }
+                    continue;
+                },
+                Some(msg) = context.control_rx.recv() => {
+                    match msg {
+                        NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                        NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                        NodeControlMessage::Shutdown => break "shutdown",
+                    }
+                    continue;
+                },
+            };
+
+            let Packet::Binary { data, metadata, .. } = packet else {
+                tracing::warn!("WhepEgressNode received non-binary packet, ignoring");
+                stats_tracker.discarded();
+                continue;
+            };
+            stats_tracker.received();
+
+            let keyframe = is_video && vp9_is_keyframe(&data);
+            let stream = if is_video { &mut video } else { &mut audio };
+            let rtp_packets = stream.packetize(&data, metadata.as_ref());
+            for viewer in viewers.values_mut() {
+                if viewer.pc.is_none() {
+                    continue;
+                }
+                let track = if is_video {
+                    if viewer.awaiting_keyframe && !keyframe {
+                        continue;
+                    }
+                    viewer.awaiting_keyframe = false;
+                    &viewer.video
+                } else {
+                    &viewer.audio
+                };
+                for rtp in &rtp_packets {
+                    if let Err(e) = track.write_rtp(rtp).await {
+                        tracing::debug!("WhepEgressNode failed to send packet: {}", e);
+                        stats_tracker.errored();
+                    }
+                }
+            }
+            stats_tracker.sent();
+            stats_tracker.maybe_send();
+        };
+
+        for (_, viewer) in viewers.drain() {
+            viewer.close();
+        }
+        gateway.unregister_endpoint(&session_id, &node_name).await;
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtp_stream_timing() {
+        let mut stream = RtpStream::new(RtpCodec::Opus);
+        let first = stream.packetize(&Bytes::from_static(&[1, 2, 3]), None);
+        // No metadata: 20 ms frames back to back
+        let second = stream.packetize(&Bytes::from_static(&[4, 5, 6]), None);
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].header.timestamp.wrapping_sub(first[0].header.timestamp), 960);
+        assert_eq!(
+            second[0].header.sequence_number,
+            first[0].header.sequence_number.wrapping_add(1)
+        );
+        assert!(!first[0].header.marker);
+
+        let metadata =
+            PacketMetadata { timestamp_us: Some(1_000_000), duration_us: None, sequence: None };
+        let timed = stream.packetize(&Bytes::from_static(&[7]), Some(&metadata));
+        assert_eq!(timed[0].header.timestamp.wrapping_sub(first[0].header.timestamp), 48_000);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config: WhepEgressConfig =
+            serde_json::from_value(serde_json::json!({ "max_viewers": 4 })).unwrap();
+        assert_eq!(config.max_viewers, 4);
+        assert!(WhepEgressNode::new(config).is_ok());
+
+        let config = WhepEgressConfig { max_viewers: 0, ..WhepEgressConfig::default() };
+        assert!(WhepEgressNode::new(config).is_err());
+    }
+}
@@ -0,0 +1,511 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! `transport::webrtc::whip_ingest`: receives media from one WHIP publisher.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{Packet, PacketMetadata};
+use streamkit_core::webrtc_gateway::{
+    get_webrtc_gateway, WebRtcAnswer, WebRtcEndpointKind, WebRtcRejection, WebRtcRequest,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, OutputPin, PinCardinality, ProcessorNode,
+    StreamKitError,
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use webrtc::api::API;
+use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use webrtc::peer_connection::RTCPeerConnection;
+use webrtc::rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
+use webrtc::track::track_remote::TrackRemote;
+
+use super::{negotiate, new_resource_id, track_codec, WebRtcNetworkConfig};
+use crate::transport::rtp::{
+    wait_for_start, Depacketizer, JitterBuffer, RtpCodec, RtpPacket, TimestampUnwrapper,
+    DEFAULT_JITTER_BUFFER_MS, MAX_JITTER_BUFFER_MS,
+};
+
+/// RTP packets queued between the track readers and the node
+const MEDIA_CHANNEL_CAPACITY: usize = 1024;
+
+/// Minimum spacing of keyframe requests sent to the publisher
+const PLI_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration for `transport::webrtc::whip_ingest`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct WhipIngestConfig {
+    /// ICE servers and NAT settings
+    #[serde(flatten)]
+    pub network: WebRtcNetworkConfig,
+    /// How long to wait for reordered packets before skipping lost ones
+    #[serde(default = "default_jitter_buffer_ms")]
+    pub jitter_buffer_ms: u64,
+}
+
+impl Default for WhipIngestConfig {
+    fn default() -> Self {
+        Self { network: WebRtcNetworkConfig::default(), jitter_buffer_ms: DEFAULT_JITTER_BUFFER_MS }
+    }
+}
+
+const fn default_jitter_buffer_ms() -> u64 {
+    DEFAULT_JITTER_BUFFER_MS
+}
+
+/// An RTP packet read from one of the publisher's tracks
+struct TrackPacket {
+    resource_id: Arc<str>,
+    codec: RtpCodec,
+    packet: RtpPacket,
+}
+
+/// Progress of a publisher's peer connection, reported by its background tasks
+enum PeerEvent {
+    Negotiated { resource_id: Arc<str>, pc: Arc<RTCPeerConnection> },
+    NegotiationFailed { resource_id: Arc<str> },
+    Closed { resource_id: Arc<str> },
+}
+
+/// The connected (or connecting) publisher
+struct Publisher {
+    resource_id: Arc<str>,
+    /// `None` until negotiation completes
+    pc: Option<Arc<RTCPeerConnection>>,
+    video_ssrc: Option<u32>,
+    last_pli: Option<Instant>,
+}
+
+/// Reassembly state of one media kind
+struct MediaStream {
+    pin: &'static str,
+    codec: RtpCodec,
+    jitter_buffer: JitterBuffer,
+    depacketizer: Depacketizer,
+    timestamps: TimestampUnwrapper,
+    /// Frames emitted, across publishers
+    frames: u64,
+    /// Packets lost by previous publishers
+    lost: u64,
+}
+
+impl MediaStream {
+    fn new(pin: &'static str, codec: RtpCodec, latency: Duration) -> Self {
+        Self {
+            pin,
+            codec,
+            jitter_buffer: JitterBuffer::new(latency),
+            depacketizer: Depacketizer::new(codec),
+            timestamps: TimestampUnwrapper::new(codec.clock_rate()),
+            frames: 0,
+            lost: 0,
+        }
+    }
+
+    /// Starts over for a new publisher, whose sequence numbers and timestamps are unrelated
+    /// to the previous one's.
+    fn reset(&mut self, latency: Duration) {
+        self.lost += self.jitter_buffer.lost;
+        self.jitter_buffer = JitterBuffer::new(latency);
+        self.depacketizer = Depacketizer::new(self.codec);
+        self.timestamps = TimestampUnwrapper::new(self.codec.clock_rate());
+    }
+}
+
+/// Receives Opus audio and VP9 video from a WHIP publisher.
+pub struct WhipIngestNode {
+    config: WhipIngestConfig,
+}
+
+impl WhipIngestNode {
+    /// Creates a WHIP ingest node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the jitter buffer is out of range.
+    pub fn new(config: WhipIngestConfig) -> Result<Self, StreamKitError> {
+        if config.jitter_buffer_ms > MAX_JITTER_BUFFER_MS {
+            return Err(StreamKitError::Configuration(format!(
+                "jitter_buffer_ms must be at most {MAX_JITTER_BUFFER_MS}, got {}",
+                config.jitter_buffer_ms
+            )));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            let config = if params.is_none() {
+                WhipIngestConfig::default()
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+
+    /// Negotiates a publisher's peer connection in the background; the offer is answered
+    /// from there and the outcome reported through `events_tx`.
+    fn spawn_publisher(
+        &self,
+        api: &Arc<API>,
+        sdp: String,
+        resource_id: Arc<str>,
+        response_tx: oneshot::Sender<Result<WebRtcAnswer, WebRtcRejection>>,
+        media_tx: mpsc::Sender<TrackPacket>,
+        events_tx: mpsc::UnboundedSender<PeerEvent>,
+    ) {
+        let api = Arc::clone(api);
+        let rtc_config = self.config.network.rtc_configuration();
+        tokio::spawn(async move {
+            let pc = match api.new_peer_connection(rtc_config).await {
+                Ok(pc) => Arc::new(pc),
+                Err(e) => {
+                    let _ = response_tx.send(Err(WebRtcRejection::Failed(format!(
+                        "Failed to create peer connection: {e}"
+                    ))));
+                    let _ = events_tx.send(PeerEvent::NegotiationFailed { resource_id });
+                    return;
+                },
+            };
+
+            let track_resource = Arc::clone(&resource_id);
+            pc.on_track(Box::new(move |track: Arc<TrackRemote>, _, _| {
+                let media_tx = media_tx.clone();
+                let resource_id = Arc::clone(&track_resource);
+                Box::pin(async move {
+                    tokio::spawn(read_track(track, resource_id, media_tx));
+                })
+            }));
+            let state_events = events_tx.clone();
+            let state_resource = Arc::clone(&resource_id);
+            pc.on_peer_connection_state_change(Box::new(move |state: RTCPeerConnectionState| {
+                tracing::debug!(resource_id = %state_resource, %state, "WHIP peer connection state");
+                if matches!(state, RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed)
+                {
+                    let _ = state_events
+                        .send(PeerEvent::Closed { resource_id: Arc::clone(&state_resource) });
+                }
+                Box::pin(async {})
+            }));
+
+            match negotiate(&pc, sdp, resource_id.to_string()).await {
+                Ok(answer) => {
+                    if response_tx.send(Ok(answer)).is_ok() {
+                        let _ = events_tx.send(PeerEvent::Negotiated { resource_id, pc });
+                        return;
+                    }
+                    // The HTTP request timed out in the meantime
+                    let _ = pc.close().await;
+                },
+                Err(rejection) => {
+                    let _ = response_tx.send(Err(rejection));
+                    let _ = pc.close().await;
+                },
+            }
+            let _ = events_tx.send(PeerEvent::NegotiationFailed { resource_id });
+        });
+    }
+}
+
+/// Forwards a remote track's RTP packets to the node until the track ends.
+async fn read_track(
+    track: Arc<TrackRemote>,
+    resource_id: Arc<str>,
+    media_tx: mpsc::Sender<TrackPacket>,
+) {
+    let Some(codec) = track_codec(track.kind()) else { return };
+    tracing::info!(resource_id = %resource_id, ?codec, ssrc = track.ssrc(), "WHIP track started");
+    while let Ok((packet, _)) = track.read_rtp().await {
+        let packet = RtpPacket {
+            marker: packet.header.marker,
+            payload_type: packet.header.payload_type,
+            sequence: packet.header.sequence_number,
+            timestamp: packet.header.timestamp,
+            ssrc: packet.header.ssrc,
+            payload: packet.payload,
+        };
+        let resource_id = Arc::clone(&resource_id);
+        if media_tx.send(TrackPacket { resource_id, codec, packet }).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Asks the publisher for a keyframe, at most once per [`PLI_INTERVAL`].
+fn request_keyframe(publisher: &mut Publisher) {
+    let (Some(pc), Some(media_ssrc)) = (publisher.pc.clone(), publisher.video_ssrc) else {
+        return;
+    };
+    let now = Instant::now();
+    if publisher.last_pli.is_some_and(|last| now.duration_since(last) < PLI_INTERVAL) {
+        return;
+    }
+    publisher.last_pli = Some(now);
+    tokio::spawn(async move {
+        let pli = PictureLossIndication { sender_ssrc: 0, media_ssrc };
+        if let Err(e) = pc.write_rtcp(&[Box::new(pli)]).await {
+            tracing::debug!("Failed to send PLI: {}", e);
+        }
+    });
+}
+
+fn close_publisher(publisher: Publisher) {
+    if let Some(pc) = publisher.pc {
+        tokio::spawn(async move {
+            let _ = pc.close().await;
+        });
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for WhipIngestNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![
+            OutputPin {
+                name: "audio".to_string(),
+                produces_type: RtpCodec::Opus.packet_type(),
+                cardinality: PinCardinality::Broadcast,
+            },
+            OutputPin {
+                name: "video".to_string(),
+                produces_type: RtpCodec::Vp9.packet_type(),
+                cardinality: PinCardinality::Broadcast,
+            },
+        ]
+    }
+
+    #[allow(clippy::cognitive_complexity)] // One select loop over signaling, media and control
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        let Some(session_id) = context.session_id.clone() else {
+            let err_msg = "whip_ingest requires a session (dynamic pipelines only)";
+            state_helpers::emit_failed(&context.state_tx, &node_name, err_msg);
+            return Err(StreamKitError::Configuration(err_msg.to_string()));
+        };
+        let Some(gateway) = get_webrtc_gateway() else {
+            let err_msg = "WebRTC gateway not available; the server was built without WHIP/WHEP";
+            state_helpers::emit_failed(&context.state_tx, &node_name, err_msg);
+            return Err(StreamKitError::Runtime(err_msg.to_string()));
+        };
+        let api = match super::build_api(&self.config.network) {
+            Ok(api) => api,
+            Err(e) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &e.to_string());
+                return Err(e);
+            },
+        };
+        let mut request_rx = match gateway
+            .register_endpoint(session_id.clone(), node_name.clone(), WebRtcEndpointKind::Whip)
+            .await
+        {
+            Ok(rx) => rx,
+            Err(e) => {
+                state_helpers::emit_failed(&context.state_tx, &node_name, &e);
+                return Err(StreamKitError::Runtime(e));
+            },
+        };
+        tracing::info!(
+            "WhipIngestNode accepting publishers at /api/v1/sessions/{}/whip/{}",
+            session_id,
+            node_name
+        );
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        if !wait_for_start(&mut context).await {
+            gateway.unregister_endpoint(&session_id, &node_name).await;
+            state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+            return Ok(());
+        }
+        state_helpers::emit_running(&context.state_tx, &node_name);
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let (media_tx, mut media_rx) = mpsc::channel::<TrackPacket>(MEDIA_CHANNEL_CAPACITY);
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel::<PeerEvent>();
+        let mut publisher: Option<Publisher> = None;
+        let latency = Duration::from_millis(self.config.jitter_buffer_ms);
+        let mut audio = MediaStream::new("audio", RtpCodec::Opus, latency);
+        let mut video = MediaStream::new("video", RtpCodec::Vp9, latency);
+
+        let reason = loop {
+            let deadline =
+                [audio.jitter_buffer.next_deadline(), video.jitter_buffer.next_deadline()]
+                    .into_iter()
+                    .flatten()
+                    .min();
+            tokio::select! {
+                Some(request) = request_rx.recv() => match request {
+                    WebRtcRequest::Offer { sdp, response_tx } => {
+                        if publisher.is_some() {
+                            let _ = response_tx.send(Err(WebRtcRejection::Busy(
+                                "A publisher is already connected".to_string(),
+                            )));
+                            continue;
+                        }
+                        let resource_id: Arc<str> = new_resource_id().into();
+                        publisher = Some(Publisher {
+                            resource_id: Arc::clone(&resource_id),
+                            pc: None,
+                            video_ssrc: None,
+                            last_pli: None,
+                        });
+                        audio.reset(latency);
+                        video.reset(latency);
+                        self.spawn_publisher(
+                            &api,
+                            sdp,
+                            resource_id,
+                            response_tx,
+                            media_tx.clone(),
+                            events_tx.clone(),
+                        );
+                    },
+                    WebRtcRequest::Terminate { resource_id, response_tx } => {
+                        let known =
+                            publisher.as_ref().is_some_and(|p| *p.resource_id == *resource_id);
+                        if known {
+                            tracing::info!(resource_id = %resource_id, "WHIP publisher left");
+                            if let Some(publisher) = publisher.take() {
+                                close_publisher(publisher);
+                            }
+                        }
+                        let _ = response_tx.send(known);
+                    },
+                },
+                Some(event) = events_rx.recv() => match event {
+                    PeerEvent::Negotiated { resource_id, pc } => {
+                        match publisher.as_mut().filter(|p| p.resource_id == resource_id) {
+                            Some(current) => {
+                                tracing::info!(resource_id = %resource_id, "WHIP publisher connected");
+                                current.pc = Some(pc);
+                            },
+                            // Terminated while negotiating
+                            None => close_publisher(Publisher {
+                                resource_id,
+                                pc: Some(pc),
+                                video_ssrc: None,
+                                last_pli: None,
+                            }),
+                        }
+                    },
+                    PeerEvent::NegotiationFailed { resource_id } | PeerEvent::Closed { resource_id } => {
+                        if publisher.as_ref().is_some_and(|p| p.resource_id == resource_id) {
+                            tracing::info!(resource_id = %resource_id, "WHIP publisher disconnected");
+                            if let Some(publisher) = publisher.take() {
+                                close_publisher(publisher);
+                            }
+                        }
+                    },
+                },
+                Some(TrackPacket { resource_id, codec, packet }) = media_rx.recv() => {
+                    let Some(current) = publisher.as_mut().filter(|p| p.resource_id == resource_id) else {
+                        continue;
+                    };
+                    stats_tracker.received();
+                    let stream = if codec.is_video() {
+                        if current.video_ssrc.is_none() {
+                            current.video_ssrc = Some(packet.ssrc);
+                            request_keyframe(current);
+                        }
+                        &mut video
+                    } else {
+                        &mut audio
+                    };
+                    if !stream.jitter_buffer.push(packet, Instant::now()) {
+                        stats_tracker.discarded();
+                    }
+                },
+                () = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)),
+                    if deadline.is_some() => {},
+                Some(msg) = context.control_rx.recv() => match msg {
+                    NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+                    NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+                    NodeControlMessage::Shutdown => break "shutdown",
+                },
+            }
+
+            let mut output_closed = false;
+            for stream in [&mut audio, &mut video] {
+                let lost_before = stream.jitter_buffer.lost;
+                while let Some((sequence, packet)) = stream.jitter_buffer.pop(Instant::now()) {
+                    let Some(frame) = stream.depacketizer.push(sequence, &packet) else { continue };
+                    let metadata = PacketMetadata {
+                        timestamp_us: Some(stream.timestamps.micros(frame.timestamp)),
+                        duration_us: None,
+                        sequence: Some(stream.frames),
+                    };
+                    stream.frames += 1;
+                    let packet = Packet::Binary {
+                        data: frame.data,
+                        content_type: None,
+                        metadata: Some(metadata),
+                    };
+                    if context.output_sender.send(stream.pin, packet).await.is_err() {
+                        output_closed = true;
+                        break;
+                    }
+                    stats_tracker.sent();
+                }
+                // Frames dropped for packet loss leave the decoder without references
+                if stream.codec.is_video() && stream.jitter_buffer.lost > lost_before {
+                    if let Some(current) = publisher.as_mut() {
+                        request_keyframe(current);
+                    }
+                }
+            }
+            if output_closed {
+                tracing::debug!("Output channel closed, stopping node");
+                break "output_closed";
+            }
+            stats_tracker.maybe_send();
+        };
+
+        if let Some(publisher) = publisher.take() {
+            close_publisher(publisher);
+        }
+        gateway.unregister_endpoint(&session_id, &node_name).await;
+        stats_tracker.force_send();
+        tracing::info!(
+            "WhipIngestNode stopped after {} audio and {} video frames ({} packets lost)",
+            audio.frames,
+            video.frames,
+            audio.lost + audio.jitter_buffer.lost + video.lost + video.jitter_buffer.lost
+        );
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_validation() {
+        let config: WhipIngestConfig = serde_json::from_value(serde_json::json!({
+            "ice_servers": ["stun:stun.l.google.com:19302"],
+            "jitter_buffer_ms": 80
+        }))
+        .unwrap();
+        assert_eq!(config.network.ice_servers.len(), 1);
+        assert!(WhipIngestNode::new(config).is_ok());
+
+        let config = WhipIngestConfig { jitter_buffer_ms: 10_000, ..WhipIngestConfig::default() };
+        assert!(WhipIngestNode::new(config).is_err());
+    }
+}
@@ -0,0 +1,1067 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! WebSocket input and output nodes.
+//!
+//! `transport::ws::output` connects to a WebSocket endpoint and sends every packet it receives
+//! as one binary message, and `transport::ws::input` connects to an endpoint and emits the
+//! packets it is sent. Both nodes are clients: they dial `url` (`ws://` or `wss://`) with
+//! optional extra handshake headers such as `Authorization`, and reconnect with exponential
+//! backoff when the connection fails or drops.
+//!
+//! Binary messages carry one packet each, framed so the packet type and timing survive the
+//! trip:
+//!
+//! ```text
+//! version (u8) | kind (u8) | flags (u8) | timestamp_us? (u64) | duration_us? (u64) | sequence? (u64) | body
+//! ```
+//!
+//! Integers are big-endian. The optional metadata fields are present when flag bit `0x01`,
+//! `0x02` or `0x04` is set, respectively. The body depends on the kind:
+//!
+//! - `0` audio: sample rate (u32), channel count (u16), then interleaved little-endian `f32`
+//!   samples
+//! - `1` text: UTF-8
+//! - `2` transcription: `TranscriptionData` as JSON
+//! - `3` custom: `CustomPacketData` as JSON
+//! - `4` binary: content type length (u16) and content type (UTF-8, empty for none), then
+//!   the data
+//!
+//! The input node also accepts text messages and emits them as text packets, so plain
+//! WebSocket producers can feed a session without implementing the framing.
+
+use async_trait::async_trait;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use streamkit_core::control::{reject_query, NodeControlMessage};
+use streamkit_core::redaction::redact_url;
+use streamkit_core::stats::NodeStatsTracker;
+use streamkit_core::types::{
+    AudioFrame, CustomPacketData, Packet, PacketMetadata, PacketType, TranscriptionData,
+};
+use streamkit_core::{
+    config_helpers, state_helpers, InputPin, NodeContext, NodeRegistry, OutputPin, PinCardinality,
+    ProcessorNode, StreamKitError,
+};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const FRAME_VERSION: u8 = 1;
+const FRAME_HEADER_SIZE: usize = 3;
+
+const KIND_AUDIO: u8 = 0;
+const KIND_TEXT: u8 = 1;
+const KIND_TRANSCRIPTION: u8 = 2;
+const KIND_CUSTOM: u8 = 3;
+const KIND_BINARY: u8 = 4;
+
+const FLAG_TIMESTAMP: u8 = 0x01;
+const FLAG_DURATION: u8 = 0x02;
+const FLAG_SEQUENCE: u8 = 0x04;
+
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Configuration for `transport::ws::input` and `transport::ws::output`.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct WsConfig {
+    /// WebSocket endpoint to connect to (`ws://` or `wss://`)
+    #[schemars(extend("sensitive" = true))]
+    pub url: String,
+    /// Extra HTTP headers sent with the handshake, e.g. `Authorization`
+    #[serde(default)]
+    #[schemars(extend("sensitive" = true))]
+    pub headers: BTreeMap<String, String>,
+    /// Type of the packets emitted on `out` (input node only)
+    #[serde(default = "default_packet_type")]
+    pub packet_type: PacketType,
+    /// Reconnect when the connection cannot be established or drops
+    #[serde(default = "default_reconnect")]
+    pub reconnect: bool,
+    /// Delay before the first reconnection attempt; doubles after each failed attempt
+    #[serde(default = "default_initial_backoff_ms")]
+    #[schemars(range(min = 1))]
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the reconnection delay
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Consecutive failed attempts after which the node fails (0: retry forever)
+    #[serde(default)]
+    pub max_reconnect_attempts: u32,
+    /// How long a connection attempt, handshake included, may take
+    #[serde(default = "default_connect_timeout_ms")]
+    #[schemars(range(min = 1))]
+    pub connect_timeout_ms: u64,
+    /// Largest message accepted from the endpoint, in bytes
+    #[serde(default = "default_max_message_bytes")]
+    #[schemars(range(min = 1))]
+    pub max_message_bytes: usize,
+}
+
+const fn default_packet_type() -> PacketType {
+    PacketType::Binary
+}
+
+const fn default_reconnect() -> bool {
+    true
+}
+
+const fn default_initial_backoff_ms() -> u64 {
+    DEFAULT_INITIAL_BACKOFF_MS
+}
+
+const fn default_max_backoff_ms() -> u64 {
+    DEFAULT_MAX_BACKOFF_MS
+}
+
+const fn default_connect_timeout_ms() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_MS
+}
+
+const fn default_max_message_bytes() -> usize {
+    DEFAULT_MAX_MESSAGE_BYTES
+}
+
+impl WsConfig {
+    /// Default instance used for pin inspection of dynamic nodes.
+    fn placeholder() -> Self {
+        Self {
+            url: "ws://127.0.0.1:8080".to_string(),
+            headers: BTreeMap::new(),
+            packet_type: default_packet_type(),
+            reconnect: default_reconnect(),
+            initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MS,
+            max_reconnect_attempts: 0,
+            connect_timeout_ms: DEFAULT_CONNECT_TIMEOUT_MS,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+        }
+    }
+
+    fn validate(&self) -> Result<(), StreamKitError> {
+        let error = |msg: String| Err(StreamKitError::Configuration(msg));
+        if !self.url.starts_with("ws://") && !self.url.starts_with("wss://") {
+            return error("url must start with ws:// or wss://".to_string());
+        }
+        for (name, value) in &self.headers {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return error(format!("invalid header name {name:?}"));
+            }
+            // Values usually carry credentials, so only the header name is reported
+            if HeaderValue::from_str(value).is_err() {
+                return error(format!("invalid value for header {name:?}"));
+            }
+        }
+        if self.initial_backoff_ms == 0 {
+            return error("initial_backoff_ms must be greater than 0".to_string());
+        }
+        if self.max_backoff_ms < self.initial_backoff_ms {
+            return error(format!(
+                "max_backoff_ms must be at least initial_backoff_ms ({}), got {}",
+                self.initial_backoff_ms, self.max_backoff_ms
+            ));
+        }
+        if self.connect_timeout_ms == 0 {
+            return error("connect_timeout_ms must be greater than 0".to_string());
+        }
+        if self.max_message_bytes == 0 {
+            return error("max_message_bytes must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    async fn connect(&self) -> Result<WsStream, String> {
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| format!("Invalid WebSocket URL: {e}"))?;
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("Invalid header name {name:?}"))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| format!("Invalid value for header {name:?}"))?;
+            request.headers_mut().insert(name, value);
+        }
+        let ws_config = WebSocketConfig::default()
+            .max_message_size(Some(self.max_message_bytes))
+            .max_frame_size(Some(self.max_message_bytes));
+
+        let connect = tokio_tungstenite::connect_async_with_config(request, Some(ws_config), true);
+        match tokio::time::timeout(Duration::from_millis(self.connect_timeout_ms), connect).await {
+            Ok(Ok((stream, _response))) => Ok(stream),
+            Ok(Err(e)) => Err(format!("WebSocket connection failed: {e}")),
+            Err(_) => {
+                Err(format!("WebSocket connection timed out after {} ms", self.connect_timeout_ms))
+            },
+        }
+    }
+}
+
+// --- Framing ---
+
+fn put_metadata(out: &mut BytesMut, metadata: Option<&PacketMetadata>) {
+    let Some(metadata) = metadata else {
+        out.put_u8(0);
+        return;
+    };
+    let fields = [
+        (FLAG_TIMESTAMP, metadata.timestamp_us),
+        (FLAG_DURATION, metadata.duration_us),
+        (FLAG_SEQUENCE, metadata.sequence),
+    ];
+    out.put_u8(fields.iter().filter(|(_, value)| value.is_some()).fold(0, |f, (flag, _)| f | flag));
+    for value in fields.iter().filter_map(|(_, value)| *value) {
+        out.put_u64(value);
+    }
+}
+
+fn take_metadata(buf: &mut Bytes, flags: u8) -> Result<Option<PacketMetadata>, String> {
+    let mut field = |flag: u8| -> Result<Option<u64>, String> {
+        if flags & flag == 0 {
+            return Ok(None);
+        }
+        if buf.remaining() < 8 {
+            return Err("truncated packet metadata".to_string());
+        }
+        Ok(Some(buf.get_u64()))
+    };
+    let metadata = PacketMetadata {
+        timestamp_us: field(FLAG_TIMESTAMP)?,
+        duration_us: field(FLAG_DURATION)?,
+        sequence: field(FLAG_SEQUENCE)?,
+    };
+    Ok((flags & (FLAG_TIMESTAMP | FLAG_DURATION | FLAG_SEQUENCE) != 0).then_some(metadata))
+}
+
+/// Serializes a packet into one binary WebSocket message.
+fn encode_packet(packet: &Packet) -> Result<Bytes, String> {
+    let mut out = BytesMut::with_capacity(FRAME_HEADER_SIZE + 24 + packet.payload_size());
+    out.put_u8(FRAME_VERSION);
+    match packet {
+        Packet::Audio(frame) => {
+            out.put_u8(KIND_AUDIO);
+            put_metadata(&mut out, frame.metadata.as_ref());
+            out.put_u32(frame.sample_rate);
+            out.put_u16(frame.channels);
+            for &sample in frame.samples.as_slice() {
+                out.put_f32_le(sample);
+            }
+        },
+        Packet::Text(text) => {
+            out.put_u8(KIND_TEXT);
+            put_metadata(&mut out, None);
+            out.extend_from_slice(text.as_bytes());
+        },
+        Packet::Transcription(transcription) => {
+            out.put_u8(KIND_TRANSCRIPTION);
+            put_metadata(&mut out, None);
+            serde_json::to_writer((&mut out).writer(), transcription.as_ref())
+                .map_err(|e| format!("Failed to serialize transcription: {e}"))?;
+        },
+        Packet::Custom(custom) => {
+            out.put_u8(KIND_CUSTOM);
+            put_metadata(&mut out, None);
+            serde_json::to_writer((&mut out).writer(), custom.as_ref())
+                .map_err(|e| format!("Failed to serialize custom packet: {e}"))?;
+        },
+        Packet::Binary { data, content_type, metadata } => {
+            out.put_u8(KIND_BINARY);
+            put_metadata(&mut out, metadata.as_ref());
+            let content_type = content_type.as_deref().unwrap_or_default();
+            let len = u16::try_from(content_type.len())
+                .map_err(|_| "Content type too long to frame".to_string())?;
+            out.put_u16(len);
+            out.extend_from_slice(content_type.as_bytes());
+            out.extend_from_slice(data);
+        },
+    }
+    Ok(out.freeze())
+}
+
+/// Parses a binary WebSocket message produced by [`encode_packet`]. Binary payloads are
+/// returned as views into `data`.
+fn decode_packet(data: &Bytes) -> Result<Packet, String> {
+    if data.len() < FRAME_HEADER_SIZE {
+        return Err("truncated frame header".to_string());
+    }
+    let mut buf = data.clone();
+    let version = buf.get_u8();
+    if version != FRAME_VERSION {
+        return Err(format!("unsupported frame version {version}"));
+    }
+    let kind = buf.get_u8();
+    let flags = buf.get_u8();
+    let metadata = take_metadata(&mut buf, flags)?;
+
+    match kind {
+        KIND_AUDIO => {
+            if buf.remaining() < 6 {
+                return Err("truncated audio header".to_string());
+            }
+            let sample_rate = buf.get_u32();
+            let channels = buf.get_u16();
+            if sample_rate == 0 || channels == 0 {
+                return Err("audio frame with zero sample rate or channels".to_string());
+            }
+            if !buf.len().is_multiple_of(4) {
+                return Err("audio payload is not a whole number of samples".to_string());
+            }
+            let samples =
+                buf.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+            Ok(Packet::Audio(AudioFrame::with_metadata(sample_rate, channels, samples, metadata)))
+        },
+        KIND_TEXT => std::str::from_utf8(&buf)
+            .map(|text| Packet::Text(Arc::from(text)))
+            .map_err(|e| format!("text packet is not UTF-8: {e}")),
+        KIND_TRANSCRIPTION => serde_json::from_slice::<TranscriptionData>(&buf)
+            .map(|transcription| Packet::Transcription(Arc::new(transcription)))
+            .map_err(|e| format!("invalid transcription JSON: {e}")),
+        KIND_CUSTOM => serde_json::from_slice::<CustomPacketData>(&buf)
+            .map(|custom| Packet::Custom(Arc::new(custom)))
+            .map_err(|e| format!("invalid custom packet JSON: {e}")),
+        KIND_BINARY => {
+            if buf.remaining() < 2 {
+                return Err("truncated content type".to_string());
+            }
+            let len = usize::from(buf.get_u16());
+            if buf.remaining() < len {
+                return Err("truncated content type".to_string());
+            }
+            let content_type = std::str::from_utf8(&buf[..len])
+                .map_err(|e| format!("content type is not UTF-8: {e}"))?
+                .to_string();
+            buf.advance(len);
+            Ok(Packet::Binary {
+                data: buf,
+                content_type: (!content_type.is_empty()).then_some(Cow::Owned(content_type)),
+                metadata,
+            })
+        },
+        other => Err(format!("unknown packet kind {other}")),
+    }
+}
+
+// --- Connection management ---
+
+/// Exponential reconnection delay, reset once a connection is established.
+struct Backoff {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+    failures: u32,
+}
+
+impl Backoff {
+    const fn new(config: &WsConfig) -> Self {
+        let initial = Duration::from_millis(config.initial_backoff_ms);
+        Self {
+            initial,
+            max: Duration::from_millis(config.max_backoff_ms),
+            next: initial,
+            failures: 0,
+        }
+    }
+
+    /// Records a failure and returns how long to wait before the next attempt.
+    fn fail(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        self.failures = self.failures.saturating_add(1);
+        delay
+    }
+
+    const fn reset(&mut self) {
+        self.next = self.initial;
+        self.failures = 0;
+    }
+}
+
+/// Outcome of waiting out a reconnection delay.
+enum Retry {
+    Reconnect,
+    Shutdown,
+    /// The output node's input closed while it was disconnected.
+    InputClosed,
+    GiveUp(String),
+}
+
+/// Handles control messages until Shutdown; never returns if the control channel closes.
+async fn wait_for_shutdown(control_rx: &mut mpsc::Receiver<NodeControlMessage>) {
+    while let Some(msg) = control_rx.recv().await {
+        match msg {
+            NodeControlMessage::Query { kind, reply, .. } => reject_query(&kind, reply),
+            NodeControlMessage::UpdateParams(_) | NodeControlMessage::Start => {},
+            NodeControlMessage::Shutdown => return,
+        }
+    }
+    std::future::pending::<()>().await;
+}
+
+/// Connects, giving up if the node is shut down first. Returns `None` on shutdown.
+async fn connect_until_shutdown(
+    config: &WsConfig,
+    control_rx: &mut mpsc::Receiver<NodeControlMessage>,
+) -> Option<Result<WsStream, String>> {
+    tokio::select! {
+        result = config.connect() => Some(result),
+        () = wait_for_shutdown(control_rx) => None,
+    }
+}
+
+/// Reports a failed or lost connection and waits out the backoff delay.
+///
+/// While waiting, packets arriving on `input_rx` are dropped so a live stream does not
+/// resume with a backlog of stale packets.
+async fn wait_to_reconnect(
+    config: &WsConfig,
+    backoff: &mut Backoff,
+    context: &mut NodeContext,
+    mut input_rx: Option<&mut mpsc::Receiver<Packet>>,
+    stats_tracker: &mut NodeStatsTracker,
+    err_msg: String,
+) -> Retry {
+    if !config.reconnect {
+        return Retry::GiveUp(err_msg);
+    }
+    if config.max_reconnect_attempts > 0 && backoff.failures >= config.max_reconnect_attempts {
+        return Retry::GiveUp(format!(
+            "{err_msg} (gave up after {} attempts)",
+            config.max_reconnect_attempts
+        ));
+    }
+    let delay = backoff.fail();
+    let node_name = context.output_sender.node_name().to_string();
+    tracing::warn!("{}: {}, reconnecting in {} ms", node_name, err_msg, delay.as_millis());
+    let reason = format!("{err_msg}, reconnecting");
+    if config.max_reconnect_attempts > 0 {
+        state_helpers::emit_recovering_with_retry(
+            &context.state_tx,
+            &node_name,
+            reason,
+            backoff.failures,
+            config.max_reconnect_attempts,
+        );
+    } else {
+        state_helpers::emit_recovering(&context.state_tx, &node_name, reason, None);
+    }
+
+    let deadline = tokio::time::Instant::now() + delay;
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep_until(deadline) => return Retry::Reconnect,
+            () = wait_for_shutdown(&mut context.control_rx) => return Retry::Shutdown,
+            maybe_packet = async {
+                match input_rx.as_deref_mut() {
+                    Some(rx) => rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                if maybe_packet.is_none() {
+                    return Retry::InputClosed;
+                }
+                stats_tracker.discarded();
+            },
+        }
+    }
+}
+
+// --- Input node ---
+
+/// Receives packets from a WebSocket endpoint.
+pub struct WsInputNode {
+    config: WsConfig,
+}
+
+impl WsInputNode {
+    /// Creates a WebSocket input node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL or a header is invalid or a value is out of
+    /// range.
+    pub fn new(config: WsConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            // For dynamic nodes, allow None to create a default instance for pin inspection
+            let config = if params.is_none() {
+                WsConfig::placeholder()
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+}
+
+/// Why a connected session of the input node ended.
+enum InputSessionEnd {
+    Shutdown,
+    OutputClosed,
+    Lost(String),
+}
+
+impl WsInputNode {
+    async fn run_session(
+        stream: &mut WsStream,
+        context: &mut NodeContext,
+        stats_tracker: &mut NodeStatsTracker,
+    ) -> InputSessionEnd {
+        loop {
+            let packet = tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(Message::Binary(data))) => {
+                        stats_tracker.received();
+                        match decode_packet(&data) {
+                            Ok(packet) => packet,
+                            Err(e) => {
+                                tracing::warn!("WsInputNode dropping malformed message: {}", e);
+                                stats_tracker.discarded();
+                                continue;
+                            },
+                        }
+                    },
+                    Some(Ok(Message::Text(text))) => {
+                        stats_tracker.received();
+                        Packet::Text(Arc::from(text.as_str()))
+                    },
+                    Some(Ok(Message::Close(_))) | None => {
+                        return InputSessionEnd::Lost("Connection closed by peer".to_string());
+                    },
+                    // Pings are answered by tungstenite itself
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        stats_tracker.errored();
+                        return InputSessionEnd::Lost(format!("Connection lost: {e}"));
+                    },
+                },
+                () = wait_for_shutdown(&mut context.control_rx) => {
+                    let _ = stream.close(None).await;
+                    return InputSessionEnd::Shutdown;
+                },
+            };
+            if context.output_sender.send("out", packet).await.is_err() {
+                tracing::debug!("Output channel closed, stopping node");
+                let _ = stream.close(None).await;
+                return InputSessionEnd::OutputClosed;
+            }
+            stats_tracker.sent();
+            stats_tracker.maybe_send();
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for WsInputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![OutputPin {
+            name: "out".to_string(),
+            produces_type: self.config.packet_type.clone(),
+            cardinality: PinCardinality::Broadcast,
+        }]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+
+        // Source nodes emit Ready state and wait for Start signal
+        state_helpers::emit_ready(&context.state_tx, &node_name);
+        loop {
+            match context.control_rx.recv().await {
+                Some(NodeControlMessage::Start) => break,
+                Some(NodeControlMessage::UpdateParams(_)) => {},
+                Some(NodeControlMessage::Query { kind, reply, .. }) => reject_query(&kind, reply),
+                Some(NodeControlMessage::Shutdown) | None => {
+                    state_helpers::emit_stopped(&context.state_tx, &node_name, "shutdown");
+                    return Ok(());
+                },
+            }
+        }
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut backoff = Backoff::new(&self.config);
+        let url = redact_url(&self.config.url);
+
+        let reason = loop {
+            let err_msg = match connect_until_shutdown(&self.config, &mut context.control_rx).await
+            {
+                None => break "shutdown",
+                Some(Ok(mut stream)) => {
+                    tracing::info!("WsInputNode connected to {}", url);
+                    backoff.reset();
+                    state_helpers::emit_running(&context.state_tx, &node_name);
+                    match Self::run_session(&mut stream, &mut context, &mut stats_tracker).await {
+                        InputSessionEnd::Shutdown => break "shutdown",
+                        InputSessionEnd::OutputClosed => break "output_closed",
+                        InputSessionEnd::Lost(err_msg) if !self.config.reconnect => {
+                            tracing::info!("WsInputNode disconnected from {}: {}", url, err_msg);
+                            break "peer_closed";
+                        },
+                        InputSessionEnd::Lost(err_msg) => err_msg,
+                    }
+                },
+                Some(Err(err_msg)) => err_msg,
+            };
+            match wait_to_reconnect(
+                &self.config,
+                &mut backoff,
+                &mut context,
+                None,
+                &mut stats_tracker,
+                err_msg,
+            )
+            .await
+            {
+                Retry::Reconnect => {},
+                Retry::Shutdown | Retry::InputClosed => break "shutdown",
+                Retry::GiveUp(err_msg) => {
+                    stats_tracker.force_send();
+                    state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                    return Err(StreamKitError::Runtime(err_msg));
+                },
+            }
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+// --- Output node ---
+
+/// Sends packets to a WebSocket endpoint.
+pub struct WsOutputNode {
+    config: WsConfig,
+}
+
+impl WsOutputNode {
+    /// Creates a WebSocket output node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL or a header is invalid or a value is out of
+    /// range.
+    pub fn new(config: WsConfig) -> Result<Self, StreamKitError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+
+    pub fn factory() -> streamkit_core::node::NodeFactory {
+        std::sync::Arc::new(|params| {
+            // For dynamic nodes, allow None to create a default instance for pin inspection
+            let config = if params.is_none() {
+                WsConfig::placeholder()
+            } else {
+                config_helpers::parse_config_required(params)?
+            };
+            Ok(Box::new(Self::new(config)?))
+        })
+    }
+}
+
+/// Why a connected session of the output node ended.
+enum OutputSessionEnd {
+    Shutdown,
+    InputClosed,
+    Lost(String),
+}
+
+impl WsOutputNode {
+    async fn run_session(
+        stream: WsStream,
+        input_rx: &mut mpsc::Receiver<Packet>,
+        context: &mut NodeContext,
+        stats_tracker: &mut NodeStatsTracker,
+    ) -> OutputSessionEnd {
+        let (mut sink, mut source) = stream.split();
+        loop {
+            tokio::select! {
+                maybe_packet = input_rx.recv() => {
+                    let Some(packet) = maybe_packet else {
+                        let _ = sink.close().await;
+                        return OutputSessionEnd::InputClosed;
+                    };
+                    stats_tracker.received();
+                    let frame = match encode_packet(&packet) {
+                        Ok(frame) => frame,
+                        Err(e) => {
+                            tracing::warn!("WsOutputNode dropping packet: {}", e);
+                            stats_tracker.discarded();
+                            continue;
+                        },
+                    };
+                    if let Err(e) = sink.send(Message::Binary(frame)).await {
+                        stats_tracker.errored();
+                        return OutputSessionEnd::Lost(format!("Connection lost: {e}"));
+                    }
+                    stats_tracker.sent();
+                    stats_tracker.maybe_send();
+                },
+                // Incoming data messages are ignored; reading keeps pings answered and notices
+                // the peer going away
+                msg = source.next() => match msg {
+                    Some(Ok(Message::Close(_))) | None => {
+                        return OutputSessionEnd::Lost("Connection closed by peer".to_string());
+                    },
+                    Some(Ok(_)) => {},
+                    Some(Err(e)) => {
+                        stats_tracker.errored();
+                        return OutputSessionEnd::Lost(format!("Connection lost: {e}"));
+                    },
+                },
+                () = wait_for_shutdown(&mut context.control_rx) => {
+                    let _ = sink.close().await;
+                    return OutputSessionEnd::Shutdown;
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessorNode for WsOutputNode {
+    fn input_pins(&self) -> Vec<InputPin> {
+        vec![InputPin {
+            name: "in".to_string(),
+            accepts_types: vec![PacketType::Any],
+            cardinality: PinCardinality::One,
+        }]
+    }
+
+    fn output_pins(&self) -> Vec<OutputPin> {
+        vec![]
+    }
+
+    async fn run(self: Box<Self>, mut context: NodeContext) -> Result<(), StreamKitError> {
+        let node_name = context.output_sender.node_name().to_string();
+        state_helpers::emit_initializing(&context.state_tx, &node_name);
+        let mut input_rx = context.take_input("in")?;
+
+        let mut stats_tracker = NodeStatsTracker::new(node_name.clone(), context.stats_tx.clone());
+        let mut backoff = Backoff::new(&self.config);
+        let url = redact_url(&self.config.url);
+
+        let reason = loop {
+            let err_msg = match connect_until_shutdown(&self.config, &mut context.control_rx).await
+            {
+                None => break "shutdown",
+                Some(Ok(stream)) => {
+                    tracing::info!("WsOutputNode connected to {}", url);
+                    backoff.reset();
+                    state_helpers::emit_running(&context.state_tx, &node_name);
+                    match Self::run_session(stream, &mut input_rx, &mut context, &mut stats_tracker)
+                        .await
+                    {
+                        OutputSessionEnd::Shutdown => break "shutdown",
+                        OutputSessionEnd::InputClosed => break "input_closed",
+                        OutputSessionEnd::Lost(err_msg) if !self.config.reconnect => {
+                            tracing::info!("WsOutputNode disconnected from {}: {}", url, err_msg);
+                            break "peer_closed";
+                        },
+                        OutputSessionEnd::Lost(err_msg) => err_msg,
+                    }
+                },
+                Some(Err(err_msg)) => err_msg,
+            };
+            match wait_to_reconnect(
+                &self.config,
+                &mut backoff,
+                &mut context,
+                Some(&mut input_rx),
+                &mut stats_tracker,
+                err_msg,
+            )
+            .await
+            {
+                Retry::Reconnect => {},
+                Retry::Shutdown => break "shutdown",
+                Retry::InputClosed => break "input_closed",
+                Retry::GiveUp(err_msg) => {
+                    stats_tracker.force_send();
+                    state_helpers::emit_failed(&context.state_tx, &node_name, &err_msg);
+                    return Err(StreamKitError::Runtime(err_msg));
+                },
+            }
+        };
+
+        stats_tracker.force_send();
+        state_helpers::emit_stopped(&context.state_tx, &node_name, reason);
+        Ok(())
+    }
+}
+
+/// Registers the WebSocket transport nodes.
+///
+/// # Panics
+///
+/// Panics if the config schema cannot be serialized to JSON (should never happen).
+#[allow(clippy::expect_used)] // Schema serialization should never fail
+pub fn register_ws_nodes(registry: &mut NodeRegistry) {
+    use schemars::schema_for;
+
+    let schema = serde_json::to_value(schema_for!(WsConfig))
+        .expect("WsConfig schema should serialize to JSON");
+
+    let factory = WsInputNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::ws::input",
+        move |params| (factory)(params),
+        schema.clone(),
+        vec!["transport".to_string(), "ws".to_string()],
+        false,
+        "Connects to a WebSocket endpoint and emits the packets it sends, reconnecting with \
+         exponential backoff. Binary messages use StreamKit's packet framing; text messages \
+         become text packets. \
+         Security: contacts arbitrary network addresses; restrict it via role allowlists.",
+    );
+
+    let factory = WsOutputNode::factory();
+    registry.register_dynamic_with_description(
+        "transport::ws::output",
+        move |params| (factory)(params),
+        schema,
+        vec!["transport".to_string(), "ws".to_string()],
+        false,
+        "Connects to a WebSocket endpoint and sends each packet as a framed binary message, \
+         keeping its type and timing metadata. Packets arriving while reconnecting are dropped. \
+         Security: contacts arbitrary network addresses; restrict it via role allowlists.",
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        assert_state_initializing, assert_state_running, assert_state_stopped, assert_state_update,
+        create_test_context,
+    };
+    use std::collections::HashMap;
+    use streamkit_core::types::CustomEncoding;
+    use streamkit_core::NodeState;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    fn config(value: serde_json::Value) -> WsConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn roundtrip(packet: &Packet) -> Packet {
+        decode_packet(&encode_packet(packet).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_framing_roundtrip() {
+        let metadata =
+            PacketMetadata { timestamp_us: Some(20_000), duration_us: None, sequence: Some(7) };
+
+        let audio =
+            AudioFrame::with_metadata(48_000, 2, vec![0.5, -0.25, 1.0, 0.0], Some(metadata));
+        let Packet::Audio(frame) = roundtrip(&Packet::Audio(audio)) else { panic!("audio") };
+        assert_eq!((frame.sample_rate, frame.channels), (48_000, 2));
+        assert_eq!(frame.samples.as_slice(), &[0.5, -0.25, 1.0, 0.0]);
+        let metadata = frame.metadata.unwrap();
+        assert_eq!(metadata.timestamp_us, Some(20_000));
+        assert_eq!(metadata.duration_us, None);
+        assert_eq!(metadata.sequence, Some(7));
+
+        let Packet::Text(text) = roundtrip(&Packet::Text(Arc::from("héllo"))) else {
+            panic!("text")
+        };
+        assert_eq!(&*text, "héllo");
+
+        let custom = CustomPacketData {
+            type_id: "test/event@1".to_string(),
+            encoding: CustomEncoding::Json,
+            data: serde_json::json!({"level": 3}),
+            metadata: None,
+        };
+        let Packet::Custom(custom) = roundtrip(&Packet::Custom(Arc::new(custom))) else {
+            panic!("custom")
+        };
+        assert_eq!(custom.type_id, "test/event@1");
+        assert_eq!(custom.data["level"], 3);
+
+        let binary = Packet::Binary {
+            data: Bytes::from_static(b"\x00\x01\x02"),
+            content_type: Some(Cow::Borrowed("audio/ogg")),
+            metadata: None,
+        };
+        let Packet::Binary { data, content_type, metadata } = roundtrip(&binary) else {
+            panic!("binary")
+        };
+        assert_eq!(data, Bytes::from_static(b"\x00\x01\x02"));
+        assert_eq!(content_type.as_deref(), Some("audio/ogg"));
+        assert!(metadata.is_none());
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_frames() {
+        assert!(decode_packet(&Bytes::from_static(&[FRAME_VERSION, KIND_TEXT])).is_err());
+        assert!(decode_packet(&Bytes::from_static(&[9, KIND_TEXT, 0])).is_err());
+        assert!(decode_packet(&Bytes::from_static(&[FRAME_VERSION, 42, 0])).is_err());
+        // Timestamp flag set but no timestamp follows
+        assert!(decode_packet(&Bytes::from_static(&[FRAME_VERSION, KIND_BINARY, 0x01])).is_err());
+        // Content type longer than the remaining data
+        assert!(decode_packet(&Bytes::from_static(&[FRAME_VERSION, KIND_BINARY, 0, 0, 9, b'a']))
+            .is_err());
+        // Audio payload not a multiple of four bytes
+        let mut audio = vec![FRAME_VERSION, KIND_AUDIO, 0, 0, 0, 0xBB, 0x80, 0, 1];
+        audio.extend_from_slice(&[0, 0, 0]);
+        assert!(decode_packet(&Bytes::from(audio)).is_err());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::new(&config(serde_json::json!({
+            "url": "ws://127.0.0.1:1",
+            "initial_backoff_ms": 100,
+            "max_backoff_ms": 300,
+        })));
+        let delays: Vec<u128> = (0..4).map(|_| backoff.fail().as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 300, 300]);
+        assert_eq!(backoff.failures, 4);
+        backoff.reset();
+        assert_eq!(backoff.fail(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(
+            WsOutputNode::new(config(serde_json::json!({"url": "http://example.com"}))).is_err()
+        );
+        assert!(WsOutputNode::new(config(serde_json::json!({
+            "url": "ws://example.com",
+            "headers": {"bad header": "x"},
+        })))
+        .is_err());
+        assert!(WsInputNode::new(config(serde_json::json!({
+            "url": "ws://example.com",
+            "initial_backoff_ms": 1000,
+            "max_backoff_ms": 10,
+        })))
+        .is_err());
+
+        let valid = config(serde_json::json!({
+            "url": "wss://example.com/ingest",
+            "headers": {"Authorization": "Bearer token"},
+            "packet_type": "Text",
+        }));
+        assert!(valid.reconnect);
+        assert_eq!(valid.initial_backoff_ms, DEFAULT_INITIAL_BACKOFF_MS);
+        let node = WsInputNode::new(valid).unwrap();
+        assert_eq!(node.output_pins()[0].produces_type, PacketType::Text);
+    }
+
+    #[tokio::test]
+    async fn test_output_to_input_through_relay() {
+        // A relay that checks the auth header on the first connection (the output node) and
+        // forwards its messages to the second (the input node)
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let relay = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut authorization = None;
+            let mut producer = tokio_tungstenite::accept_hdr_async(
+                tcp,
+                |request: &Request, response: Response| {
+                    authorization = request.headers().get("authorization").cloned();
+                    Ok(response)
+                },
+            )
+            .await
+            .unwrap();
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut consumer = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            while let Some(Ok(msg)) = producer.next().await {
+                if msg.is_close() {
+                    break;
+                }
+                consumer.send(msg).await.unwrap();
+            }
+            consumer.close(None).await.unwrap();
+            authorization
+        });
+
+        let output = WsOutputNode::new(config(serde_json::json!({
+            "url": url,
+            "headers": {"Authorization": "Bearer secret"},
+            "reconnect": false,
+        })))
+        .unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        let (output_context, _output_sender, mut output_state_rx) =
+            create_test_context(HashMap::from([("in".to_string(), rx)]), 16);
+        let output_handle = tokio::spawn(async move { Box::new(output).run(output_context).await });
+        assert_state_initializing(&mut output_state_rx).await;
+        assert_state_running(&mut output_state_rx).await;
+
+        let input = WsInputNode::new(config(serde_json::json!({
+            "url": url,
+            "reconnect": false,
+        })))
+        .unwrap();
+        let (mut input_context, input_sender, mut input_state_rx) =
+            create_test_context(HashMap::new(), 16);
+        let (control_tx, control_rx) = mpsc::channel(10);
+        input_context.control_rx = control_rx;
+        let input_handle = tokio::spawn(async move { Box::new(input).run(input_context).await });
+        control_tx.send(NodeControlMessage::Start).await.unwrap();
+        assert_state_initializing(&mut input_state_rx).await;
+        assert_state_update(&mut input_state_rx, |s| matches!(s, NodeState::Ready), "Ready").await;
+        assert_state_running(&mut input_state_rx).await;
+
+        let metadata =
+            PacketMetadata { timestamp_us: Some(1_000), duration_us: Some(20_000), sequence: None };
+        tx.send(Packet::Binary {
+            data: Bytes::from_static(b"frame"),
+            content_type: None,
+            metadata: Some(metadata),
+        })
+        .await
+        .unwrap();
+        tx.send(Packet::Text(Arc::from("done"))).await.unwrap();
+        drop(tx);
+
+        let (_, pin, packet) = input_sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(pin, "out");
+        let Packet::Binary { data, metadata, .. } = packet else { panic!("expected binary") };
+        assert_eq!(data, Bytes::from_static(b"frame"));
+        assert_eq!(metadata.unwrap().duration_us, Some(20_000));
+        let (_, _, packet) = input_sender.recv_timeout(Duration::from_secs(5)).await.unwrap();
+        assert!(matches!(packet, Packet::Text(text) if &*text == "done"));
+
+        assert_state_stopped(&mut output_state_rx).await;
+        output_handle.await.unwrap().unwrap();
+        // The relay closing the connection stops the input, which does not reconnect
+        assert_state_stopped(&mut input_state_rx).await;
+        input_handle.await.unwrap().unwrap();
+        assert_eq!(relay.await.unwrap().unwrap(), "Bearer secret");
+    }
+}
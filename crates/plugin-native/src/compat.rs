@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Compatibility with plugins built against older versions of the native C ABI.
+//!
+//! The ABI is versioned semantically (see [`NativeAbiVersion`]). A host upgrade that only bumps
+//! the minor version must keep loading plugins compiled before it, so every difference between
+//! minor versions is resolved here rather than by rejecting the plugin:
+//!
+//! - Fields appended to `CNativePluginAPI` in a later minor version are absent from older
+//!   plugins' structs. They must only be read through an [`AbiAdapter`] accessor that falls back
+//!   to the older behaviour.
+//! - Host behaviour a plugin has to opt into (such as the `_sandbox` param, added in 1.1) is
+//!   only enabled for plugins whose version announces it.
+
+use anyhow::{anyhow, Result};
+use streamkit_plugin_sdk_native::types::NativeAbiVersion;
+
+/// How the host talks to one loaded plugin, given the ABI version it was built against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AbiAdapter {
+    plugin: NativeAbiVersion,
+}
+
+impl AbiAdapter {
+    /// Picks the adapter for a plugin announcing `raw_version` in `CNativePluginAPI::version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version predates the oldest supported layout, has a different
+    /// major version than the host, or is a newer minor version than the host knows.
+    pub fn negotiate(raw_version: u32) -> Result<Self> {
+        let host = NativeAbiVersion::CURRENT;
+        let plugin = NativeAbiVersion::decode(raw_version).ok_or_else(|| {
+            anyhow!(
+                "Plugin API version {raw_version} is no longer supported; host implements ABI {host}. \
+                 Rebuild the plugin against the current plugin SDK."
+            )
+        })?;
+
+        if !host.can_load(plugin) {
+            let hint = if plugin.major == host.major {
+                "Upgrade the host to load it."
+            } else {
+                "Rebuild the plugin against a plugin SDK with the same major ABI version."
+            };
+            return Err(anyhow!(
+                "Plugin ABI version mismatch: plugin was built against {plugin}, host implements {host}. {hint}"
+            ));
+        }
+
+        Ok(Self { plugin })
+    }
+
+    /// The ABI version the plugin was built against.
+    pub const fn plugin_version(self) -> NativeAbiVersion {
+        self.plugin
+    }
+
+    /// Whether the plugin predates the host's minor version and runs through compatibility shims.
+    pub fn is_legacy(self) -> bool {
+        self.plugin < NativeAbiVersion::CURRENT
+    }
+
+    /// Whether instances may be handed the host-reserved `_sandbox` param.
+    ///
+    /// Plugins built before 1.1 would treat it as an ordinary (and unknown) param, so they
+    /// are left unsandboxed.
+    pub fn passes_sandbox_param(self) -> bool {
+        self.plugin >= NativeAbiVersion::SANDBOX
+    }
+
+    /// Whether the plugin's metadata carries requirements and conflicts.
+    ///
+    /// The fields are appended to `CNodeMetadata` and absent from older plugins' structs.
+    pub fn reads_requirements(self) -> bool {
+        self.plugin >= NativeAbiVersion::REQUIREMENTS
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_plugins_need_no_shims() {
+        let adapter = AbiAdapter::negotiate(NativeAbiVersion::CURRENT.encode()).unwrap();
+        assert_eq!(adapter.plugin_version(), NativeAbiVersion::CURRENT);
+        assert!(!adapter.is_legacy());
+        assert!(adapter.passes_sandbox_param());
+        assert!(adapter.reads_requirements());
+    }
+
+    #[test]
+    fn test_legacy_plugins_keep_loading() {
+        let v3 = AbiAdapter::negotiate(3).unwrap();
+        assert_eq!(v3.plugin_version(), NativeAbiVersion::new(1, 0));
+        assert!(v3.is_legacy());
+        assert!(!v3.passes_sandbox_param());
+        assert!(!v3.reads_requirements());
+
+        let v4 = AbiAdapter::negotiate(4).unwrap();
+        assert_eq!(v4.plugin_version(), NativeAbiVersion::new(1, 1));
+        assert!(v4.passes_sandbox_param());
+    }
+
+    #[test]
+    fn test_incompatible_versions_are_rejected() {
+        let host = NativeAbiVersion::CURRENT;
+        assert!(AbiAdapter::negotiate(2).is_err());
+        assert!(AbiAdapter::negotiate(NativeAbiVersion::new(host.major + 1, 0).encode()).is_err());
+        assert!(AbiAdapter::negotiate(NativeAbiVersion::new(host.major, host.minor + 1).encode())
+            .is_err());
+    }
+}
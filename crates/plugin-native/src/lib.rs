@@ -7,6 +7,10 @@
 //! This crate provides the host-side runtime for loading and executing native plugins
 //! that use the C ABI interface.
 
+pub mod compat;
+pub mod placement;
+pub mod requirements;
+pub mod sandbox;
 pub mod wrapper;
 
 use anyhow::{anyhow, Context, Result};
@@ -14,16 +18,24 @@ use libloading::{Library, Symbol};
 use std::path::Path;
 use std::sync::Arc;
 use streamkit_core::{NodeRegistry, PinCardinality};
-use streamkit_plugin_sdk_native::types::{CNativePluginAPI, NATIVE_PLUGIN_API_VERSION};
+use streamkit_plugin_sdk_native::types::{CNativePluginAPI, NativeAbiVersion};
 use streamkit_plugin_sdk_native::{conversions, types::PLUGIN_API_SYMBOL};
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::compat::AbiAdapter;
+use crate::placement::{ComputePool, PLACEMENT_PARAM};
+use crate::requirements::PluginRequirements;
+use crate::sandbox::SandboxPool;
 
 /// A loaded native plugin
 #[derive(Clone)]
 pub struct LoadedNativePlugin {
     library: Arc<Library>,
     api: &'static CNativePluginAPI,
+    abi: AbiAdapter,
     metadata: PluginMetadata,
+    compute_pool: Arc<ComputePool>,
+    sandboxes: Option<Arc<SandboxPool>>,
 }
 
 /// Metadata extracted from a plugin
@@ -35,6 +47,8 @@ pub struct PluginMetadata {
     pub outputs: Vec<streamkit_core::OutputPin>,
     pub param_schema: serde_json::Value,
     pub categories: Vec<String>,
+    /// Empty for plugins built before ABI 1.2
+    pub requirements: PluginRequirements,
 }
 
 impl LoadedNativePlugin {
@@ -45,8 +59,9 @@ impl LoadedNativePlugin {
     /// Returns an error if:
     /// - The library file cannot be loaded
     /// - The plugin doesn't export the required API symbol
-    /// - The API version is incompatible
+    /// - The plugin's ABI version is incompatible with the host's
     /// - Plugin metadata is invalid or cannot be read
+    /// - The host lacks a shared library or feature the plugin requires
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
 
@@ -59,7 +74,10 @@ impl LoadedNativePlugin {
             Library::new(path).map_err(|e| {
                 let path_display = path.display();
                 // libloading::Error contains detailed information about what went wrong
-                anyhow!("Failed to load library '{path_display}': {e}.",)
+                requirements::missing_library_hint(&e.to_string(), path).map_or_else(
+                    || anyhow!("Failed to load library '{path_display}': {e}."),
+                    |hint| anyhow!("Failed to load library '{path_display}': {hint}. ({e})"),
+                )
             })?
         };
 
@@ -86,24 +104,38 @@ impl LoadedNativePlugin {
         // the lifetime of the loaded library, which we keep alive via Arc<Library>.
         let api = unsafe { &*api_ptr };
 
-        // Check API version compatibility
-        if api.version != NATIVE_PLUGIN_API_VERSION {
-            let plugin_version = api.version;
-            return Err(anyhow!(
-                "Plugin API version mismatch: plugin has {plugin_version}, host expects {NATIVE_PLUGIN_API_VERSION}"
-            ));
+        // Check ABI compatibility; older minor versions load through compatibility shims
+        let abi = AbiAdapter::negotiate(api.version)?;
+        if abi.is_legacy() {
+            warn!(
+                ?path,
+                plugin_abi = %abi.plugin_version(),
+                host_abi = %NativeAbiVersion::CURRENT,
+                "Plugin was built against an older ABI version; loading it in compatibility mode"
+            );
         }
 
         // Extract metadata
-        let metadata = Self::extract_metadata(api)?;
+        let metadata = Self::extract_metadata(api, abi)?;
+        metadata
+            .requirements
+            .verify_host()
+            .with_context(|| format!("Plugin '{}' cannot run on this host", metadata.kind))?;
 
         info!(kind = %metadata.kind, "Successfully loaded native plugin");
 
-        Ok(Self { library: Arc::new(library), api, metadata })
+        Ok(Self {
+            library: Arc::new(library),
+            api,
+            abi,
+            metadata,
+            compute_pool: Arc::new(ComputePool::default()),
+            sandboxes: None,
+        })
     }
 
     /// Extract metadata from the plugin
-    fn extract_metadata(api: &CNativePluginAPI) -> Result<PluginMetadata> {
+    fn extract_metadata(api: &CNativePluginAPI, abi: AbiAdapter) -> Result<PluginMetadata> {
         let c_metadata = (api.get_metadata)();
         if c_metadata.is_null() {
             return Err(anyhow!("Plugin metadata is null"));
@@ -197,21 +229,42 @@ impl LoadedNativePlugin {
         };
 
         // Extract categories
-        let mut categories = Vec::new();
         // SAFETY: The plugin provides a valid pointer and count for the categories array.
-        let c_categories =
-            unsafe { std::slice::from_raw_parts(c_meta.categories, c_meta.categories_count) };
-
-        for c_cat_ptr in c_categories {
-            // SAFETY: Each category pointer is a valid C string provided by the plugin.
-            let cat = unsafe {
-                conversions::c_str_to_string(*c_cat_ptr)
-                    .map_err(|e| anyhow!("Failed to read category: {e}"))?
-            };
-            categories.push(cat);
-        }
+        let categories = unsafe {
+            c_str_array(c_meta.categories, c_meta.categories_count)
+                .map_err(|e| anyhow!("Failed to read category: {e}"))?
+        };
 
-        Ok(PluginMetadata { kind, description, inputs, outputs, param_schema, categories })
+        // Extract requirements; the fields don't exist in older plugins' metadata
+        let requirements = if abi.reads_requirements() {
+            // SAFETY: Plugins built against ABI 1.2 or later provide valid pointers and counts
+            // for these arrays.
+            unsafe {
+                PluginRequirements {
+                    libraries: c_str_array(
+                        c_meta.requires_libraries,
+                        c_meta.requires_libraries_count,
+                    )
+                    .map_err(|e| anyhow!("Failed to read required library: {e}"))?,
+                    features: c_str_array(c_meta.requires_features, c_meta.requires_features_count)
+                        .map_err(|e| anyhow!("Failed to read required feature: {e}"))?,
+                    conflicts_with: c_str_array(c_meta.conflicts_with, c_meta.conflicts_with_count)
+                        .map_err(|e| anyhow!("Failed to read conflicting plugin kind: {e}"))?,
+                }
+            }
+        } else {
+            PluginRequirements::default()
+        };
+
+        Ok(PluginMetadata {
+            kind,
+            description,
+            inputs,
+            outputs,
+            param_schema,
+            categories,
+            requirements,
+        })
     }
 
     /// Get the plugin metadata
@@ -219,11 +272,32 @@ impl LoadedNativePlugin {
         &self.metadata
     }
 
+    /// The ABI version the plugin was built against
+    pub const fn abi_version(&self) -> NativeAbiVersion {
+        self.abi.plugin_version()
+    }
+
     /// Get the plugin API
     pub const fn api(&self) -> &'static CNativePluginAPI {
         self.api
     }
 
+    /// Places this plugin's instances through `compute_pool`, typically shared by every
+    /// native plugin so automatic placement sees all of them.
+    #[must_use]
+    pub fn with_compute_pool(mut self, compute_pool: Arc<ComputePool>) -> Self {
+        self.compute_pool = compute_pool;
+        self
+    }
+
+    /// Gives each instance its own working directory and filtered environment from
+    /// `sandboxes`. Plugins built against ABI 1.0 are left unsandboxed.
+    #[must_use]
+    pub fn with_sandboxes(mut self, sandboxes: Arc<SandboxPool>) -> Self {
+        self.sandboxes = Some(sandboxes);
+        self
+    }
+
     /// Get a reference to the loaded library
     pub const fn library(&self) -> &Arc<Library> {
         &self.library
@@ -243,14 +317,37 @@ impl LoadedNativePlugin {
         let wrapper = wrapper::NativeNodeWrapper::new(
             self.library.clone(),
             self.api,
+            self.abi,
             self.metadata.clone(),
             params,
+            &self.compute_pool,
+            self.sandboxes.as_deref(),
         )?;
 
         Ok(Box::new(wrapper))
     }
 }
 
+/// Reads an array of C strings, treating a null pointer as empty.
+///
+/// # Safety
+///
+/// Unless null, `ptr` must point to `count` valid C string pointers.
+unsafe fn c_str_array(
+    ptr: *const *const std::os::raw::c_char,
+    count: usize,
+) -> Result<Vec<String>, String> {
+    if ptr.is_null() {
+        return Ok(Vec::new());
+    }
+    // SAFETY: Guaranteed by the caller.
+    let ptrs = unsafe { std::slice::from_raw_parts(ptr, count) };
+    ptrs.iter()
+        // SAFETY: Each pointer is a valid C string, guaranteed by the caller.
+        .map(|p| unsafe { conversions::c_str_to_string(*p) })
+        .collect()
+}
+
 /// Register a list of native plugins with the node registry
 ///
 /// Returns the number of plugins registered
@@ -269,7 +366,12 @@ pub fn register_plugins(
         let metadata = plugin.metadata();
         let original_kind = metadata.kind.clone();
         let kind = namespaced_kind(&original_kind)?;
-        let param_schema = metadata.param_schema.clone();
+        let mut param_schema = metadata.param_schema.clone();
+        if let Some(properties) =
+            param_schema.get_mut("properties").and_then(serde_json::Value::as_object_mut)
+        {
+            properties.insert(PLACEMENT_PARAM.to_string(), placement::placement_param_schema());
+        }
         let categories = metadata.categories.clone();
         let inputs = metadata.inputs.clone();
         let outputs = metadata.outputs.clone();
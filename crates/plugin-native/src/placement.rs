@@ -0,0 +1,398 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! CPU placement for native plugin instances.
+//!
+//! By default every FFI call of a plugin instance runs on Tokio's blocking pool, so a heavy
+//! model may hop between sockets from one packet to the next and read its weights across the
+//! interconnect. A placed instance instead gets a dedicated worker thread pinned to a set of
+//! cores. The instance is created on that thread too, so with the kernel's first-touch policy
+//! the memory it allocates while loading lands on the same NUMA node.
+//!
+//! Placement comes from the host-reserved `_placement` param of a node (explicit cores or a
+//! NUMA node), or from the [`ComputePool`]'s automatic policy.
+
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use streamkit_core::StreamKitError;
+
+/// Host-reserved node param holding a [`PlacementRequest`]. It is stripped before the params
+/// reach the plugin.
+pub const PLACEMENT_PARAM: &str = "_placement";
+
+/// How instances without an explicit `_placement` param are placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlacementPolicy {
+    /// Leave instances on the shared blocking pool.
+    #[default]
+    None,
+    /// Pin each instance to the NUMA node running the fewest placed instances. A no-op on
+    /// single-node machines.
+    NumaSpread,
+}
+
+/// Explicit placement requested through the `_placement` param.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlacementRequest {
+    /// Pin to exactly these CPU cores.
+    Cores(Vec<usize>),
+    /// Pin to the cores of this NUMA node.
+    NumaNode(usize),
+}
+
+impl PlacementRequest {
+    fn from_value(value: &serde_json::Value) -> Result<Self, String> {
+        let obj = value.as_object().ok_or("expected an object")?;
+        if let Some(key) = obj.keys().find(|key| *key != "cores" && *key != "numa_node") {
+            return Err(format!("unknown field '{key}'"));
+        }
+        match (obj.get("cores"), obj.get("numa_node")) {
+            (Some(_), Some(_)) => Err("set either 'cores' or 'numa_node', not both".to_string()),
+            (Some(cores), None) => {
+                let cores = cores
+                    .as_array()
+                    .and_then(|cores| {
+                        cores
+                            .iter()
+                            .map(|core| core.as_u64().and_then(|c| usize::try_from(c).ok()))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .ok_or("'cores' must be an array of core indices")?;
+                if cores.is_empty() {
+                    return Err("'cores' must not be empty".to_string());
+                }
+                Ok(Self::Cores(cores))
+            },
+            (None, Some(node)) => node
+                .as_u64()
+                .and_then(|n| usize::try_from(n).ok())
+                .map(Self::NumaNode)
+                .ok_or_else(|| "'numa_node' must be a non-negative integer".to_string()),
+            (None, None) => Err("expected 'cores' or 'numa_node'".to_string()),
+        }
+    }
+}
+
+/// Removes the `_placement` param from `params`, returning the remaining params and the
+/// parsed request.
+///
+/// # Errors
+///
+/// Returns a configuration error if `_placement` is malformed.
+pub fn split_placement_param(
+    params: Option<&serde_json::Value>,
+) -> Result<(Option<serde_json::Value>, Option<PlacementRequest>), StreamKitError> {
+    let Some(params) = params else { return Ok((None, None)) };
+    let Some(request) = params.get(PLACEMENT_PARAM) else {
+        return Ok((Some(params.clone()), None));
+    };
+    let request = PlacementRequest::from_value(request).map_err(|e| {
+        StreamKitError::Configuration(format!("Invalid {PLACEMENT_PARAM} param: {e}"))
+    })?;
+    let mut params = params.clone();
+    if let Some(obj) = params.as_object_mut() {
+        obj.remove(PLACEMENT_PARAM);
+    }
+    Ok((Some(params), Some(request)))
+}
+
+/// JSON Schema of the `_placement` param, merged into every native plugin's param schema.
+pub fn placement_param_schema() -> serde_json::Value {
+    serde_json::json!({
+        "description": "Host CPU placement of this instance (not passed to the plugin). \
+                        Pins the instance to `cores` or to the cores of `numa_node`.",
+        "type": "object",
+        "properties": {
+            "cores": { "type": "array", "items": { "type": "integer", "minimum": 0 }, "minItems": 1 },
+            "numa_node": { "type": "integer", "minimum": 0 }
+        },
+        "additionalProperties": false
+    })
+}
+
+/// Parses a kernel CPU list such as `0-3,8-11`.
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for range in list.trim().split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+/// CPUs of each NUMA node, indexed by node id. Empty when the topology is unavailable.
+fn read_numa_topology() -> Vec<Vec<usize>> {
+    let mut nodes = Vec::new();
+    for node in 0.. {
+        let path = format!("/sys/devices/system/node/node{node}/cpulist");
+        let Ok(list) = std::fs::read_to_string(path) else { break };
+        nodes.push(parse_cpu_list(&list).unwrap_or_default());
+    }
+    nodes
+}
+
+/// Assigns CPU cores to native plugin instances and tracks how many run on each NUMA node.
+pub struct ComputePool {
+    policy: PlacementPolicy,
+    numa_nodes: Vec<Vec<usize>>,
+    /// Placed instances per NUMA node.
+    active: Arc<Mutex<Vec<usize>>>,
+}
+
+impl ComputePool {
+    /// Creates a pool for this machine's NUMA topology.
+    pub fn new(policy: PlacementPolicy) -> Self {
+        let numa_nodes = read_numa_topology();
+        if policy == PlacementPolicy::NumaSpread && numa_nodes.len() < 2 {
+            tracing::info!(
+                "NUMA spread placement requested on a machine with {} NUMA node(s); \
+                 native plugin instances stay on the shared blocking pool",
+                numa_nodes.len()
+            );
+        }
+        Self::with_topology(policy, numa_nodes)
+    }
+
+    fn with_topology(policy: PlacementPolicy, numa_nodes: Vec<Vec<usize>>) -> Self {
+        let active = Arc::new(Mutex::new(vec![0; numa_nodes.len()]));
+        Self { policy, numa_nodes, active }
+    }
+
+    pub const fn policy(&self) -> PlacementPolicy {
+        self.policy
+    }
+
+    /// Decides where a new instance runs; `None` leaves it on the shared blocking pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `request` names a NUMA node that does not exist.
+    pub fn place(
+        &self,
+        request: Option<PlacementRequest>,
+    ) -> Result<Option<Placement>, StreamKitError> {
+        let node = match request {
+            Some(PlacementRequest::Cores(cores)) => {
+                let node =
+                    self.numa_nodes.iter().position(|cpus| cores.iter().all(|c| cpus.contains(c)));
+                return Ok(Some(self.lease(cores, node)));
+            },
+            Some(PlacementRequest::NumaNode(node)) => {
+                if node >= self.numa_nodes.len() {
+                    return Err(StreamKitError::Configuration(format!(
+                        "Invalid {PLACEMENT_PARAM} param: NUMA node {node} does not exist \
+                         (this machine has {})",
+                        self.numa_nodes.len()
+                    )));
+                }
+                node
+            },
+            None if self.policy == PlacementPolicy::NumaSpread && self.numa_nodes.len() > 1 => {
+                let active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+                // Least loaded node; ties go to the lowest id.
+                (0..self.numa_nodes.len()).min_by_key(|&n| active[n]).unwrap_or(0)
+            },
+            None => return Ok(None),
+        };
+        Ok(Some(self.lease(self.numa_nodes[node].clone(), Some(node))))
+    }
+
+    fn lease(&self, cores: Vec<usize>, numa_node: Option<usize>) -> Placement {
+        if let Some(node) = numa_node {
+            self.active.lock().unwrap_or_else(PoisonError::into_inner)[node] += 1;
+        }
+        Placement { cores, numa_node, active: Arc::clone(&self.active) }
+    }
+}
+
+impl Default for ComputePool {
+    fn default() -> Self {
+        Self::new(PlacementPolicy::None)
+    }
+}
+
+/// Cores assigned to one instance. Releases its NUMA node slot when dropped.
+pub struct Placement {
+    cores: Vec<usize>,
+    numa_node: Option<usize>,
+    active: Arc<Mutex<Vec<usize>>>,
+}
+
+impl Placement {
+    pub fn cores(&self) -> &[usize] {
+        &self.cores
+    }
+
+    pub const fn numa_node(&self) -> Option<usize> {
+        self.numa_node
+    }
+}
+
+impl Drop for Placement {
+    fn drop(&mut self) {
+        if let Some(node) = self.numa_node {
+            let mut active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+            active[node] = active[node].saturating_sub(1);
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A dedicated thread, pinned to a [`Placement`], that runs an instance's FFI calls in order.
+pub(crate) struct PinnedWorker {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl PinnedWorker {
+    /// Starts the worker thread. It exits once the worker is dropped and queued jobs are done.
+    pub(crate) fn spawn(name: &str, placement: Placement) -> Result<Self, StreamKitError> {
+        let (jobs, rx) = mpsc::channel::<Job>();
+        std::thread::Builder::new()
+            .name(format!("skit-{name}"))
+            .spawn(move || {
+                if let Err(e) = pin_current_thread(placement.cores()) {
+                    tracing::warn!(
+                        cores = ?placement.cores(),
+                        "Failed to pin native plugin worker, running unpinned: {e}"
+                    );
+                }
+                while let Ok(job) = rx.recv() {
+                    job();
+                }
+                drop(placement);
+            })
+            .map_err(|e| {
+                StreamKitError::Runtime(format!("Failed to start native plugin worker: {e}"))
+            })?;
+        Ok(Self { jobs })
+    }
+
+    fn submit<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+        reply: impl FnOnce(R) + Send + 'static,
+    ) -> Result<(), StreamKitError> {
+        self.jobs
+            .send(Box::new(move || reply(f())))
+            .map_err(|_| StreamKitError::Runtime("Native plugin worker has stopped".to_string()))
+    }
+
+    /// Runs `f` on the worker thread, blocking the caller until it returns.
+    pub(crate) fn run_blocking<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<R, StreamKitError> {
+        let (tx, rx) = mpsc::sync_channel(1);
+        self.submit(f, move |result| {
+            let _ = tx.send(result);
+        })?;
+        rx.recv().map_err(|_| StreamKitError::Runtime("Native plugin worker panicked".to_string()))
+    }
+
+    /// Runs `f` on the worker thread.
+    pub(crate) async fn run<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<R, StreamKitError> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.submit(f, move |result| {
+            let _ = tx.send(result);
+        })?;
+        rx.await.map_err(|_| StreamKitError::Runtime("Native plugin worker panicked".to_string()))
+    }
+}
+
+/// Pins the calling thread to `cores`. Also used by the server's pinned Tokio runtimes.
+///
+/// # Errors
+///
+/// Returns an error if a core is out of range or the affinity cannot be set, and always on
+/// platforms other than Linux.
+pub fn pin_current_thread(cores: &[usize]) -> std::io::Result<()> {
+    use std::io;
+
+    #[cfg(target_os = "linux")]
+    {
+        use nix::sched::{sched_setaffinity, CpuSet};
+        use nix::unistd::Pid;
+
+        let mut set = CpuSet::new();
+        for &core in cores {
+            set.set(core).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("core {core}: {e}"))
+            })?;
+        }
+        sched_setaffinity(Pid::from_raw(0), &set).map_err(io::Error::from)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cores;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "CPU pinning is only supported on Linux"))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8-9\n"), Some(vec![0, 1, 2, 3, 8, 9]));
+        assert_eq!(parse_cpu_list("5"), Some(vec![5]));
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("a-b"), None);
+    }
+
+    #[test]
+    fn test_split_placement_param() {
+        let params = serde_json::json!({ "model": "base", "_placement": { "numa_node": 1 } });
+        let (params, request) = split_placement_param(Some(&params)).unwrap();
+        assert_eq!(params, Some(serde_json::json!({ "model": "base" })));
+        assert_eq!(request, Some(PlacementRequest::NumaNode(1)));
+
+        let both = serde_json::json!({ "_placement": { "numa_node": 1, "cores": [0] } });
+        assert!(split_placement_param(Some(&both)).is_err());
+        let empty = serde_json::json!({ "_placement": { "cores": [] } });
+        assert!(split_placement_param(Some(&empty)).is_err());
+    }
+
+    #[test]
+    fn test_numa_spread_balances_nodes() {
+        let pool =
+            ComputePool::with_topology(PlacementPolicy::NumaSpread, vec![vec![0, 1], vec![2, 3]]);
+        let first = pool.place(None).unwrap().unwrap();
+        let second = pool.place(None).unwrap().unwrap();
+        assert_eq!((first.numa_node(), second.numa_node()), (Some(0), Some(1)));
+        assert_eq!(second.cores(), [2, 3]);
+
+        // Releasing node 0 makes it the least loaded again.
+        drop(first);
+        assert_eq!(pool.place(None).unwrap().unwrap().numa_node(), Some(0));
+
+        // Explicit requests count towards their node.
+        let pinned = pool.place(Some(PlacementRequest::Cores(vec![3]))).unwrap().unwrap();
+        assert_eq!(pinned.numa_node(), Some(1));
+        assert!(pool.place(Some(PlacementRequest::NumaNode(2))).is_err());
+    }
+
+    #[test]
+    fn test_no_policy_leaves_instances_unplaced() {
+        let pool = ComputePool::with_topology(PlacementPolicy::None, vec![vec![0], vec![1]]);
+        assert!(pool.place(None).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_worker_runs_jobs_on_its_thread() {
+        let pool = ComputePool::with_topology(PlacementPolicy::None, vec![]);
+        let placement = pool.place(Some(PlacementRequest::Cores(vec![0]))).unwrap().unwrap();
+        let worker = PinnedWorker::spawn("test", placement).unwrap();
+
+        let name = || std::thread::current().name().map(str::to_string);
+        assert_eq!(worker.run_blocking(name).unwrap().as_deref(), Some("skit-test"));
+        assert_eq!(worker.run(name).await.unwrap().as_deref(), Some("skit-test"));
+    }
+}
@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Requirements native plugins declare in their metadata.
+//!
+//! A plugin that needs something the host lacks otherwise fails late and cryptically: inside
+//! `dlopen`, on the first instance that opens a GPU, or with an illegal instruction. Plugins
+//! built against ABI 1.2 or later declare the shared libraries they load, the host features
+//! they need and the plugin kinds they can't be loaded alongside, so the loader can refuse
+//! them up front with an error naming what is missing.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+/// Host features a plugin may require.
+pub const KNOWN_FEATURES: &[&str] = &["gpu", "sse4.2", "avx", "avx2", "avx512f", "fma", "neon"];
+
+/// Environment variable the dynamic loader searches for shared libraries.
+#[cfg(target_os = "macos")]
+const LIBRARY_PATH_VAR: &str = "DYLD_LIBRARY_PATH";
+#[cfg(windows)]
+const LIBRARY_PATH_VAR: &str = "PATH";
+#[cfg(not(any(target_os = "macos", windows)))]
+const LIBRARY_PATH_VAR: &str = "LD_LIBRARY_PATH";
+
+/// What a plugin declared it needs from the host, and what it can't coexist with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginRequirements {
+    /// Shared libraries, by the name passed to `dlopen`
+    pub libraries: Vec<String>,
+    /// Host features, see [`KNOWN_FEATURES`]
+    pub features: Vec<String>,
+    /// Plugin kinds, plain (`whisper`) or namespaced (`plugin::native::whisper`)
+    pub conflicts_with: Vec<String>,
+}
+
+impl PluginRequirements {
+    /// Checks the declared libraries and features against this host.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing every requirement the host doesn't meet.
+    pub fn verify_host(&self) -> Result<()> {
+        let mut unmet = Vec::new();
+        for feature in &self.features {
+            match host_has_feature(feature) {
+                Some(true) => {},
+                Some(false) => {
+                    unmet
+                        .push(format!("host feature '{feature}' is not available on this machine"));
+                },
+                None => unmet.push(format!(
+                    "unknown host feature '{feature}' (known features: {})",
+                    KNOWN_FEATURES.join(", ")
+                )),
+            }
+        }
+        for library in &self.libraries {
+            // SAFETY: Probing runs the library's initialisers, exactly as the plugin loading
+            // it would. The handle is closed again right away.
+            if let Err(e) = unsafe { libloading::Library::new(library) } {
+                unmet.push(format!(
+                    "shared library '{library}' could not be loaded ({e}); install it or add \
+                     its directory to {LIBRARY_PATH_VAR}"
+                ));
+            }
+        }
+
+        if unmet.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("requirements not met: {}", unmet.join("; ")))
+        }
+    }
+
+    /// Whether these requirements declare a conflict with the plugin registered as `kind`
+    /// (e.g. `plugin::native::whisper`) under its own name `original_kind`.
+    pub fn conflicts_with_kind(&self, kind: &str, original_kind: &str) -> bool {
+        self.conflicts_with.iter().any(|entry| {
+            if entry.contains("::") {
+                entry == kind
+            } else {
+                entry == original_kind
+            }
+        })
+    }
+}
+
+/// Whether the host has `feature`, or `None` if the feature is unknown.
+fn host_has_feature(feature: &str) -> Option<bool> {
+    let available = match feature.to_ascii_lowercase().as_str() {
+        "gpu" => {
+            Path::new("/proc/driver/nvidia/version").exists()
+                || Path::new("/dev/nvidiactl").exists()
+        },
+        #[cfg(target_arch = "x86_64")]
+        "sse4.2" => std::is_x86_feature_detected!("sse4.2"),
+        #[cfg(target_arch = "x86_64")]
+        "avx" => std::is_x86_feature_detected!("avx"),
+        #[cfg(target_arch = "x86_64")]
+        "avx2" => std::is_x86_feature_detected!("avx2"),
+        #[cfg(target_arch = "x86_64")]
+        "avx512f" => std::is_x86_feature_detected!("avx512f"),
+        #[cfg(target_arch = "x86_64")]
+        "fma" => std::is_x86_feature_detected!("fma"),
+        #[cfg(not(target_arch = "x86_64"))]
+        "sse4.2" | "avx" | "avx2" | "avx512f" | "fma" => false,
+        #[cfg(target_arch = "aarch64")]
+        "neon" => std::arch::is_aarch64_feature_detected!("neon"),
+        #[cfg(not(target_arch = "aarch64"))]
+        "neon" => false,
+        _ => return None,
+    };
+    Some(available)
+}
+
+/// Explains a `dlopen` failure of the plugin at `plugin_path` caused by a shared library it
+/// links against, or returns `None` if the error is about something else.
+pub fn missing_library_hint(error: &str, plugin_path: &Path) -> Option<String> {
+    let library = if let Some(idx) = error.find(": cannot open shared object file") {
+        // glibc: "<plugin>: libfoo.so.1: cannot open shared object file: ..."
+        error[..idx].rsplit([' ', '"', '\'']).next()?
+    } else {
+        // macOS: "Library not loaded: @rpath/libfoo.dylib"
+        let rest = &error[error.find("Library not loaded: ")? + "Library not loaded: ".len()..];
+        rest.split_whitespace().next()?.trim_start_matches("@rpath/")
+    };
+
+    let library = library.trim_end_matches(':');
+    let is_plugin_itself =
+        plugin_path.file_name().is_some_and(|name| Path::new(library).file_name() == Some(name));
+    if library.is_empty() || is_plugin_itself {
+        return None;
+    }
+    Some(format!(
+        "the plugin links against shared library '{library}', which was not found. Install it \
+         or add its directory to {LIBRARY_PATH_VAR}"
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unmet_requirements_are_all_reported() {
+        let requirements = PluginRequirements {
+            libraries: vec!["libstreamkit-test-missing.so.1".to_string()],
+            features: vec!["quantum".to_string()],
+            conflicts_with: Vec::new(),
+        };
+        let error = requirements.verify_host().unwrap_err().to_string();
+        assert!(error.contains("unknown host feature 'quantum'"), "{error}");
+        assert!(error.contains("'libstreamkit-test-missing.so.1' could not be loaded"), "{error}");
+        assert!(PluginRequirements::default().verify_host().is_ok());
+    }
+
+    #[test]
+    fn test_conflicts_match_plain_or_namespaced_kinds() {
+        let requirements = PluginRequirements {
+            conflicts_with: vec!["whisper".to_string(), "plugin::wasm::vad".to_string()],
+            ..Default::default()
+        };
+        assert!(requirements.conflicts_with_kind("plugin::native::whisper", "whisper"));
+        assert!(requirements.conflicts_with_kind("plugin::wasm::vad", "vad"));
+        assert!(!requirements.conflicts_with_kind("plugin::native::vad", "vad"));
+        assert!(!requirements.conflicts_with_kind("plugin::native::kokoro", "kokoro"));
+    }
+
+    #[test]
+    fn test_missing_library_hint() {
+        let plugin = Path::new("/plugins/libwhisper.so");
+        let glibc = "/plugins/libwhisper.so: libonnxruntime.so.1: cannot open shared object \
+                     file: No such file or directory";
+        let hint = missing_library_hint(glibc, plugin).unwrap();
+        assert!(hint.contains("'libonnxruntime.so.1'"), "{hint}");
+
+        let macos = "dlopen(/plugins/libwhisper.dylib, 0x0005): Library not loaded: \
+                     @rpath/libonnxruntime.dylib\n  Referenced from: ...";
+        let hint = missing_library_hint(macos, Path::new("/plugins/libwhisper.dylib")).unwrap();
+        assert!(hint.contains("'libonnxruntime.dylib'"), "{hint}");
+
+        let itself = "/plugins/libwhisper.so: cannot open shared object file: No such file";
+        assert!(missing_library_hint(itself, plugin).is_none());
+        assert!(missing_library_hint("invalid ELF header", plugin).is_none());
+    }
+}
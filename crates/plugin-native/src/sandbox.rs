@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Working directories and filtered environments for native plugin instances.
+//!
+//! Native plugins run in the server process, whose working directory and environment can't
+//! differ per instance. Instead, every instance of a plugin built against ABI 1.1 or
+//! later gets a fresh directory of its own and a copy of the environment reduced to a
+//! passthrough list, handed over in the host-reserved `_sandbox` param. The plugin SDK
+//! exposes both to the plugin (`Sandbox::work_dir`, `Sandbox::var`), so plugins that write
+//! temp files don't collide and don't see secrets meant for the server. The directory is
+//! removed once the instance is destroyed.
+//!
+//! Users can't set `_sandbox` themselves: it is rejected at creation and dropped from
+//! parameter updates.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use streamkit_core::StreamKitError;
+
+pub use streamkit_plugin_sdk_native::sandbox::SANDBOX_PARAM;
+
+/// Environment variables visible to sandboxed plugins unless configured otherwise.
+pub const DEFAULT_ENV_PASSTHROUGH: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "LC_*",
+    "TZ",
+    "CUDA_VISIBLE_DEVICES",
+    "NVIDIA_VISIBLE_DEVICES",
+];
+
+/// Whether `name` matches a passthrough entry; a trailing `*` matches any suffix.
+fn passes(name: &str, passthrough: &[String]) -> bool {
+    passthrough.iter().any(|pattern| {
+        pattern.strip_suffix('*').map_or(pattern == name, |prefix| name.starts_with(prefix))
+    })
+}
+
+/// Creates the sandboxes of native plugin instances below a common root directory.
+#[derive(Debug)]
+pub struct SandboxPool {
+    root: PathBuf,
+    env: BTreeMap<String, String>,
+    next_id: AtomicU64,
+}
+
+impl SandboxPool {
+    /// Sandboxes below `root`, exposing the server's environment variables that match
+    /// `env_passthrough`.
+    pub fn new(root: impl Into<PathBuf>, env_passthrough: &[String]) -> Self {
+        let env = std::env::vars().filter(|(name, _)| passes(name, env_passthrough)).collect();
+        Self { root: root.into(), env, next_id: AtomicU64::new(0) }
+    }
+
+    /// Directory holding the instances' working directories
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Creates the working directory of a new instance of plugin `kind`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a runtime error if the directory cannot be created.
+    pub fn create(&self, kind: &str) -> Result<InstanceSandbox, StreamKitError> {
+        let kind: String = kind
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let work_dir = self.root.join(format!("{kind}-{}-{id}", std::process::id()));
+
+        std::fs::create_dir_all(&self.root)
+            .and_then(|()| {
+                let mut builder = std::fs::DirBuilder::new();
+                #[cfg(unix)]
+                std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+                builder.create(&work_dir)
+            })
+            .map_err(|e| {
+                StreamKitError::Runtime(format!(
+                    "Failed to create plugin working directory {}: {e}",
+                    work_dir.display()
+                ))
+            })?;
+
+        let mut env = self.env.clone();
+        let tmp = work_dir.to_string_lossy().into_owned();
+        for name in ["TMPDIR", "TMP", "TEMP"] {
+            env.insert(name.to_string(), tmp.clone());
+        }
+        Ok(InstanceSandbox { work_dir, env })
+    }
+}
+
+/// The working directory and environment of one plugin instance. Dropping it deletes the
+/// directory.
+#[derive(Debug)]
+pub struct InstanceSandbox {
+    work_dir: PathBuf,
+    env: BTreeMap<String, String>,
+}
+
+impl InstanceSandbox {
+    /// The instance's working directory
+    pub fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    /// Value of the `_sandbox` param describing this sandbox to the plugin
+    pub fn param(&self) -> serde_json::Value {
+        serde_json::json!({ "work_dir": self.work_dir, "env": self.env })
+    }
+}
+
+impl Drop for InstanceSandbox {
+    fn drop(&mut self) {
+        match std::fs::remove_dir_all(&self.work_dir) {
+            Ok(()) => {},
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+            Err(e) => tracing::warn!(
+                work_dir = %self.work_dir.display(),
+                error = %e,
+                "Failed to remove plugin working directory"
+            ),
+        }
+    }
+}
+
+/// Adds the `_sandbox` param of `sandbox` to `params`, or just validates that the user
+/// didn't set it when there is no sandbox.
+///
+/// # Errors
+///
+/// Returns a configuration error if `params` already contains `_sandbox`.
+pub fn insert_sandbox_param(
+    params: Option<serde_json::Value>,
+    sandbox: Option<&InstanceSandbox>,
+) -> Result<Option<serde_json::Value>, StreamKitError> {
+    if params.as_ref().is_some_and(|p| p.get(SANDBOX_PARAM).is_some()) {
+        return Err(StreamKitError::Configuration(format!(
+            "The {SANDBOX_PARAM} param is reserved for the host"
+        )));
+    }
+    let Some(sandbox) = sandbox else { return Ok(params) };
+    Ok(match params {
+        None => Some(serde_json::json!({ SANDBOX_PARAM: sandbox.param() })),
+        Some(serde_json::Value::Object(mut obj)) => {
+            obj.insert(SANDBOX_PARAM.to_string(), sandbox.param());
+            Some(serde_json::Value::Object(obj))
+        },
+        // Not an object the plugin could tell apart from its own params; leave it alone.
+        Some(other) => Some(other),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn passthrough(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_env_passthrough_patterns() {
+        let list = passthrough(&["PATH", "LC_*"]);
+        assert!(passes("PATH", &list));
+        assert!(passes("LC_ALL", &list));
+        assert!(!passes("PATHEXT", &list));
+        assert!(!passes("AWS_SECRET_ACCESS_KEY", &list));
+    }
+
+    #[test]
+    fn test_instances_get_private_directories_removed_on_drop() {
+        let root = std::env::temp_dir().join(format!("skit-sandbox-test-{}", std::process::id()));
+        let pool = SandboxPool::new(&root, &passthrough(&["PATH"]));
+
+        let first = pool.create("whisper").unwrap();
+        let second = pool.create("whisper").unwrap();
+        assert_ne!(first.work_dir(), second.work_dir());
+        assert!(first.work_dir().is_dir());
+
+        let param = first.param();
+        assert_eq!(param["work_dir"], first.work_dir().to_string_lossy().as_ref());
+        assert_eq!(param["env"]["TMPDIR"], param["work_dir"]);
+        assert_eq!(param["env"].get("PATH").is_some(), std::env::var("PATH").is_ok());
+        assert!(param["env"].get("HOME").is_none());
+
+        let dir = first.work_dir().to_path_buf();
+        std::fs::write(dir.join("scratch.bin"), b"data").unwrap();
+        drop(first);
+        assert!(!dir.exists());
+        drop(second);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_sandbox_param_is_host_only() {
+        let user = serde_json::json!({ "model": "base", SANDBOX_PARAM: { "work_dir": "/" } });
+        assert!(insert_sandbox_param(Some(user), None).is_err());
+
+        let root = std::env::temp_dir().join(format!("skit-sandbox-param-{}", std::process::id()));
+        let sandbox = SandboxPool::new(&root, &[]).create("kokoro").unwrap();
+        let params =
+            insert_sandbox_param(Some(serde_json::json!({ "voice": "af" })), Some(&sandbox))
+                .unwrap()
+                .unwrap();
+        assert_eq!(params["voice"], "af");
+        assert_eq!(params[SANDBOX_PARAM], sandbox.param());
+        assert!(insert_sandbox_param(None, Some(&sandbox)).unwrap().is_some());
+        drop(sandbox);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -14,7 +14,8 @@ use std::ffi::{c_void, CString};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use streamkit_core::control::NodeControlMessage;
-use streamkit_core::telemetry::TelemetryEvent;
+use streamkit_core::cost::NodeMeter;
+use streamkit_core::telemetry::TelemetryEmitter;
 use streamkit_core::types::Packet;
 use streamkit_core::{
     InputPin, NodeContext, NodeState, NodeStateUpdate, OutputPin, ProcessorNode, StopReason,
@@ -22,10 +23,13 @@ use streamkit_core::{
 };
 use streamkit_plugin_sdk_native::{
     conversions,
-    types::{CNativePluginAPI, CPacket, CPluginHandle, CResult},
+    types::{CNativePluginAPI, CPacket, CPluginHandle, CResult, GPU_TIME_TELEMETRY_EVENT},
 };
 use tracing::{error, info, warn};
 
+use crate::compat::AbiAdapter;
+use crate::placement::{self, ComputePool, PinnedWorker, PLACEMENT_PARAM};
+use crate::sandbox::{self, InstanceSandbox, SandboxPool, SANDBOX_PARAM};
 use crate::PluginMetadata;
 
 struct InstanceState {
@@ -34,23 +38,31 @@ struct InstanceState {
     handle_addr: AtomicUsize,
     in_flight_calls: AtomicUsize,
     drop_requested: AtomicBool,
+    /// Removed once the last reference goes, i.e. after the instance has been destroyed
+    _sandbox: Option<InstanceSandbox>,
 }
 
 impl InstanceState {
-    fn new(library: Arc<Library>, api: &'static CNativePluginAPI, handle: CPluginHandle) -> Self {
+    fn new(
+        library: Arc<Library>,
+        api: &'static CNativePluginAPI,
+        handle: CPluginHandle,
+        sandbox: Option<InstanceSandbox>,
+    ) -> Self {
         Self {
             library,
             api_addr: std::ptr::from_ref(api) as usize,
             handle_addr: AtomicUsize::new(handle as usize),
             in_flight_calls: AtomicUsize::new(0),
             drop_requested: AtomicBool::new(false),
+            _sandbox: sandbox,
         }
     }
 
     const fn api(&self) -> &'static CNativePluginAPI {
         // SAFETY: api_addr was created from a valid &'static CNativePluginAPI reference.
         // The loaded library is kept alive by self.library (Arc<Library>) held by this state,
-        // which is itself held by any in-flight spawn_blocking tasks.
+        // which is itself held by any in-flight blocking calls.
         unsafe { &*(self.api_addr as *const CNativePluginAPI) }
     }
 
@@ -93,6 +105,50 @@ impl InstanceState {
         let api = self.api();
         (api.destroy_instance)(handle_addr as CPluginHandle);
     }
+
+    /// Forwards a runtime query to the plugin. Blocking; call through an [`Executor`].
+    fn query(&self, kind: &str, args: &serde_json::Value) -> Result<serde_json::Value, String> {
+        use streamkit_plugin_sdk_native::conversions;
+
+        let Some(query_fn) = self.api().query else {
+            return Err("Plugin does not support queries".to_string());
+        };
+        let kind_cstr = CString::new(kind).map_err(|e| format!("Invalid query kind: {e}"))?;
+        let args_json =
+            serde_json::to_string(args).map_err(|e| format!("Failed to serialize args: {e}"))?;
+        let args_cstr = CString::new(args_json).map_err(|e| format!("Invalid args string: {e}"))?;
+
+        let handle =
+            self.begin_call().ok_or_else(|| "Plugin instance is shutting down".to_string())?;
+        let _lib = Arc::clone(&self.library);
+        let mut response: *const std::os::raw::c_char = std::ptr::null();
+        let result = query_fn(handle, kind_cstr.as_ptr(), args_cstr.as_ptr(), &raw mut response);
+
+        // Copy borrowed strings out before releasing the instance.
+        let outcome = if !result.success {
+            Err(if result.error_message.is_null() {
+                "Query failed".to_string()
+            } else {
+                // SAFETY: The error_message pointer is provided by the plugin
+                // and is valid for the duration of this call.
+                unsafe { conversions::c_str_to_string(result.error_message) }
+                    .unwrap_or_else(|_| "Query failed".to_string())
+            })
+        } else if response.is_null() {
+            Ok(serde_json::Value::Null)
+        } else {
+            // SAFETY: On success the plugin points `response` at a borrowed C string
+            // that stays valid until its next call on this thread.
+            unsafe { conversions::c_str_to_string(response) }
+                .map_err(|e| format!("Invalid query response: {e}"))
+                .and_then(|json| {
+                    serde_json::from_str(&json).map_err(|e| format!("Invalid query response: {e}"))
+                })
+        };
+
+        self.finish_call();
+        outcome
+    }
 }
 
 /// C callback function for plugin logging
@@ -141,31 +197,81 @@ extern "C" fn plugin_log_callback(
     }
 }
 
+/// Where an instance's blocking FFI calls run.
+enum Executor {
+    /// Tokio's shared blocking pool.
+    BlockingPool,
+    /// A dedicated thread pinned by the compute pool.
+    Pinned(PinnedWorker),
+}
+
+impl Executor {
+    async fn run<R: Send + 'static>(
+        &self,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<R, StreamKitError> {
+        match self {
+            Self::BlockingPool => tokio::task::spawn_blocking(f)
+                .await
+                .map_err(|e| StreamKitError::Runtime(format!("Plugin call panicked: {e}"))),
+            Self::Pinned(worker) => worker.run(f).await,
+        }
+    }
+}
+
+/// Calls the plugin's `create_instance`, returning the handle as an address so it can cross
+/// threads.
+fn create_instance(api_addr: usize, params: Option<&CString>) -> usize {
+    // SAFETY: api_addr was created from a valid &'static CNativePluginAPI reference, and the
+    // caller keeps the library alive for the duration of the call.
+    let api = unsafe { &*(api_addr as *const CNativePluginAPI) };
+    let params_ptr = params.map_or(std::ptr::null(), |s| s.as_ptr());
+    (api.create_instance)(params_ptr, plugin_log_callback, std::ptr::null_mut()) as usize
+}
+
 /// Wrapper that implements ProcessorNode for native plugins
 pub struct NativeNodeWrapper {
     state: Arc<InstanceState>,
     metadata: PluginMetadata,
+    executor: Executor,
 }
 
 impl NativeNodeWrapper {
     /// Create a new native node wrapper
     ///
+    /// The host-reserved `_placement` param is consumed here and decides, together with the
+    /// compute pool's policy, whether the instance gets a pinned worker thread. With
+    /// `sandboxes`, plugins that support it also get their own working directory and
+    /// environment through the `_sandbox` param.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Parameter serialization to JSON fails
     /// - Parameter string contains null bytes
+    /// - The `_placement` param is invalid or the pinned worker cannot start
+    /// - The params set the host-reserved `_sandbox` param, or its directory cannot be created
     /// - Plugin fails to create an instance
     pub fn new(
         library: Arc<Library>,
         api: &'static CNativePluginAPI,
+        abi: AbiAdapter,
         metadata: PluginMetadata,
         params: Option<&serde_json::Value>,
+        compute_pool: &ComputePool,
+        sandboxes: Option<&SandboxPool>,
     ) -> Result<Self, StreamKitError> {
+        let (params, placement_request) = placement::split_placement_param(params)?;
+        let sandbox = sandboxes
+            .filter(|_| abi.passes_sandbox_param())
+            .map(|pool| pool.create(&metadata.kind))
+            .transpose()?;
+        let params = sandbox::insert_sandbox_param(params, sandbox.as_ref())?;
+
         // Convert params to JSON string if provided
         let params_json = params
             .map(|p| {
-                serde_json::to_string(p).map_err(|e| {
+                serde_json::to_string(&p).map_err(|e| {
                     StreamKitError::Configuration(format!("Failed to serialize params: {e}"))
                 })
             })
@@ -176,18 +282,37 @@ impl NativeNodeWrapper {
                 StreamKitError::Configuration(format!("Invalid params string: {e}"))
             })?;
 
-        let params_ptr = params_cstr.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
-
-        // Create plugin instance with logging callback
-        let handle = (api.create_instance)(params_ptr, plugin_log_callback, std::ptr::null_mut());
+        // Create plugin instance with logging callback. A placed instance is created on its
+        // worker thread so the memory it allocates while loading is local to its cores.
+        let api_addr = std::ptr::from_ref(api) as usize;
+        let (handle_addr, executor) = match compute_pool.place(placement_request)? {
+            Some(placement) => {
+                info!(
+                    kind = %metadata.kind,
+                    cores = ?placement.cores(),
+                    numa_node = ?placement.numa_node(),
+                    "Placing native plugin instance on a pinned worker"
+                );
+                let worker = PinnedWorker::spawn(&metadata.kind, placement)?;
+                let lib = Arc::clone(&library);
+                let handle_addr = worker.run_blocking(move || {
+                    let _lib = lib;
+                    create_instance(api_addr, params_cstr.as_ref())
+                })?;
+                (handle_addr, Executor::Pinned(worker))
+            },
+            None => (create_instance(api_addr, params_cstr.as_ref()), Executor::BlockingPool),
+        };
 
-        if handle.is_null() {
+        if handle_addr == 0 {
             return Err(StreamKitError::Configuration(
                 "Plugin failed to create instance".to_string(),
             ));
         }
 
-        Ok(Self { state: Arc::new(InstanceState::new(library, api, handle)), metadata })
+        let handle = handle_addr as CPluginHandle;
+        let state = InstanceState::new(library, api, handle, sandbox);
+        Ok(Self { state: Arc::new(state), metadata, executor })
     }
 }
 
@@ -236,6 +361,9 @@ impl ProcessorNode for NativeNodeWrapper {
         }
 
         let mut control_channel_open = true;
+        // Shared with the blocking FFI calls so plugin telemetry goes through the node's
+        // sampling and rate limits.
+        let telemetry = Arc::new(context.telemetry_emitter());
 
         // Main processing loop
         loop {
@@ -254,19 +382,22 @@ impl ProcessorNode for NativeNodeWrapper {
 
                 maybe_control = context.control_rx.recv(), if control_channel_open => {
                     match maybe_control {
-                        Some(NodeControlMessage::UpdateParams(params_value)) => {
+                        Some(NodeControlMessage::UpdateParams(mut params_value)) => {
+                            if params_value.as_object_mut().and_then(|p| p.remove(PLACEMENT_PARAM)).is_some() {
+                                warn!(node = %node_name, "{PLACEMENT_PARAM} cannot change at runtime; ignoring it");
+                            }
+                            if params_value.as_object_mut().and_then(|p| p.remove(SANDBOX_PARAM)).is_some() {
+                                warn!(node = %node_name, "{SANDBOX_PARAM} is reserved for the host; ignoring it");
+                            }
                             // Serialize params to JSON string
                             let params_json = serde_json::to_string(&params_value)
                                 .map_err(|e| StreamKitError::Configuration(format!("Failed to serialize params: {e}")))?;
                             let params_cstr = CString::new(params_json)
                                 .map_err(|e| StreamKitError::Configuration(format!("Invalid params string: {e}")))?;
 
-                            // Move the blocking FFI call to spawn_blocking
+                            // Move the blocking FFI call off the async runtime
                             let state = Arc::clone(&self.state);
-                            // spawn_blocking can only fail with JoinError if the task panics.
-                            // If that happens, it's a serious bug that should crash.
-                            #[allow(clippy::expect_used)]
-                            let error_msg = tokio::task::spawn_blocking(move || {
+                            let error_msg = self.executor.run(move || {
                                 let handle = state.begin_call()?;
 
                                 let _lib = Arc::clone(&state.library);
@@ -290,9 +421,7 @@ impl ProcessorNode for NativeNodeWrapper {
                                 state.finish_call();
                                 error
                             })
-                            .await
-                            // spawn_blocking only panics if the task panics, which indicates a serious bug
-                            .expect("Update params task panicked");
+                            .await?;
 
                             if let Some(err) = error_msg {
                                 warn!(node = %node_name, error = %err, "Parameter update failed");
@@ -301,6 +430,15 @@ impl ProcessorNode for NativeNodeWrapper {
                         Some(NodeControlMessage::Start) => {
                             // Native plugins don't implement ready/start lifecycle - ignore
                         }
+                        Some(NodeControlMessage::Query { kind, args, reply }) => {
+                            let state = Arc::clone(&self.state);
+                            let result = self
+                                .executor
+                                .run(move || state.query(&kind, &args))
+                                .await
+                                .unwrap_or_else(|e| Err(e.to_string()));
+                            let _ = reply.send(result);
+                        }
                         Some(NodeControlMessage::Shutdown) => {
                             tracing::info!("Native plugin received shutdown signal");
                             break;
@@ -318,12 +456,11 @@ impl ProcessorNode for NativeNodeWrapper {
 
                         // Call flush to process any remaining buffered data
                         let state = Arc::clone(&self.state);
-                        let telemetry_tx = context.telemetry_tx.clone();
-                        let session_id = context.session_id.clone();
+                        let telemetry = Arc::clone(&telemetry);
                         let node_id = node_name.clone();
+                        let cost_meter = context.cost_meter.clone();
 
-                        #[allow(clippy::expect_used)]
-                        let (outputs, error) = tokio::task::spawn_blocking(move || {
+                        let (outputs, error) = self.executor.run(move || {
                             let Some(handle) = state.begin_call() else {
                                 return (Vec::new(), None);
                             };
@@ -334,15 +471,16 @@ impl ProcessorNode for NativeNodeWrapper {
                             let mut callback_ctx = CallbackContext {
                                 output_packets: Vec::new(),
                                 error: None,
-                                telemetry_tx,
-                                session_id,
+                                telemetry,
                                 node_id,
+                                cost_meter: cost_meter.clone(),
                             };
 
                             let callback_data = (&raw mut callback_ctx).cast::<c_void>();
 
                             // Call plugin's flush function
                             tracing::info!("Calling api.flush()");
+                            let started = std::time::Instant::now();
                             let result = (api.flush)(
                                 handle,
                                 output_callback_shim,
@@ -350,6 +488,9 @@ impl ProcessorNode for NativeNodeWrapper {
                                 Some(telemetry_callback_shim),
                                 callback_data,
                             );
+                            if let Some(meter) = &cost_meter {
+                                meter.add_cpu_time(started.elapsed());
+                            }
                             tracing::info!(success = result.success, "Flush returned");
 
                             let error = if result.success {
@@ -370,8 +511,7 @@ impl ProcessorNode for NativeNodeWrapper {
                             state.finish_call();
                             (outputs, error)
                         })
-                        .await
-                        .expect("Plugin flush task panicked");
+                        .await?;
 
                         // Send flush outputs
                         for (pin, pkt) in outputs {
@@ -387,15 +527,12 @@ impl ProcessorNode for NativeNodeWrapper {
                         break;
                     };
 
-                    // Move the blocking FFI call to spawn_blocking to avoid blocking the async runtime
+                    // Move the blocking FFI call off the async runtime
                     let state = Arc::clone(&self.state);
-                    let telemetry_tx = context.telemetry_tx.clone();
-                    let session_id = context.session_id.clone();
+                    let telemetry = Arc::clone(&telemetry);
                     let node_id = node_name.clone();
-                    // spawn_blocking can only fail with JoinError if the task panics.
-                    // If that happens, it's a serious bug that should crash.
-                    #[allow(clippy::expect_used)]
-                    let (outputs, error) = tokio::task::spawn_blocking(move || {
+                    let cost_meter = context.cost_meter.clone();
+                    let (outputs, error) = self.executor.run(move || {
                         let Some(handle) = state.begin_call() else {
                             return (Vec::new(), None);
                         };
@@ -413,14 +550,15 @@ impl ProcessorNode for NativeNodeWrapper {
                         let mut callback_ctx = CallbackContext {
                             output_packets: Vec::new(),
                             error: None,
-                            telemetry_tx,
-                            session_id,
+                            telemetry,
                             node_id,
+                            cost_meter: cost_meter.clone(),
                         };
 
                         let callback_data = (&raw mut callback_ctx).cast::<c_void>();
 
-                        // Call plugin's process function (BLOCKING - but we're in spawn_blocking)
+                        // Call plugin's process function (BLOCKING - but we're off the async runtime)
+                        let started = std::time::Instant::now();
                         let result = (api.process_packet)(
                             handle,
                             pin_cstr.as_ptr(),
@@ -430,6 +568,10 @@ impl ProcessorNode for NativeNodeWrapper {
                             Some(telemetry_callback_shim),
                             callback_data,
                         );
+                        // Runs off the node's task, so the engine's poll timing doesn't see it
+                        if let Some(meter) = &cost_meter {
+                            meter.add_cpu_time(started.elapsed());
+                        }
 
                         // Check for errors
                         let error = if result.success {
@@ -452,9 +594,7 @@ impl ProcessorNode for NativeNodeWrapper {
                         state.finish_call();
                         (outputs, error)
                     })
-                    .await
-                    // spawn_blocking only panics if the task panics, which indicates a serious bug
-                    .expect("Plugin processing task panicked");
+                    .await?;
 
             // Now send outputs (after dropping c_packet and result)
             for (pin, pkt) in outputs {
@@ -512,9 +652,10 @@ impl Drop for NativeNodeWrapper {
 struct CallbackContext {
     output_packets: Vec<(String, Packet)>,
     error: Option<String>,
-    telemetry_tx: Option<tokio::sync::mpsc::Sender<TelemetryEvent>>,
-    session_id: Option<String>,
+    telemetry: Arc<TelemetryEmitter>,
     node_id: String,
+    /// Receives GPU time the plugin reports via [`GPU_TIME_TELEMETRY_EVENT`].
+    cost_meter: Option<Arc<NodeMeter>>,
 }
 
 /// C callback function for sending output packets
@@ -572,10 +713,6 @@ extern "C" fn telemetry_callback_shim(
     }
 
     let ctx = unsafe { &mut *user_data.cast::<CallbackContext>() };
-    let Some(ref tx) = ctx.telemetry_tx else {
-        return CResult::success();
-    };
-
     let event_type_str = match unsafe { conversions::c_str_to_string(event_type) } {
         Ok(s) => s,
         Err(e) => {
@@ -584,6 +721,20 @@ extern "C" fn telemetry_callback_shim(
         },
     };
 
+    if event_type_str == GPU_TIME_TELEMETRY_EVENT {
+        let bytes = if data_json.is_null() {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(data_json, data_len) }
+        };
+        charge_gpu_time(ctx, bytes);
+        return CResult::success();
+    }
+
+    if !ctx.telemetry.is_enabled() {
+        return CResult::success();
+    }
+
     let data_value = if data_json.is_null() || data_len == 0 {
         serde_json::Value::Object(serde_json::Map::new())
     } else {
@@ -616,21 +767,74 @@ extern "C" fn telemetry_callback_shim(
             .unwrap_or(0)
     });
 
-    let mut event_data = match data_value {
+    let event_data = match data_value {
         serde_json::Value::Object(map) => serde_json::Value::Object(map),
         other => serde_json::json!({ "value": other }),
     };
 
-    if let Some(obj) = event_data.as_object_mut() {
-        obj.insert("event_type".to_string(), serde_json::Value::String(event_type_str));
+    // Best-effort: sampled, rate-limited or full-channel drops are counted by the emitter.
+    ctx.telemetry.emit_at(&event_type_str, timestamp_us, event_data);
+
+    CResult::success()
+}
+
+/// Charges a plugin's GPU time report (`{"nanos": N}`) to the node's cost meter.
+///
+/// Reports are accounting data, not telemetry, so they are consumed here even when the
+/// node has no telemetry channel.
+fn charge_gpu_time(ctx: &CallbackContext, data_json: &[u8]) {
+    let Some(meter) = &ctx.cost_meter else {
+        return;
+    };
+    let nanos = serde_json::from_slice::<serde_json::Value>(data_json)
+        .ok()
+        .and_then(|v| v.get("nanos").and_then(serde_json::Value::as_u64));
+    if let Some(nanos) = nanos {
+        meter.add_gpu_time(std::time::Duration::from_nanos(nanos));
+    } else {
+        warn!(node = %ctx.node_id, "Ignoring malformed GPU time report");
     }
+}
 
-    let event =
-        TelemetryEvent::new(ctx.session_id.clone(), ctx.node_id.clone(), event_data, timestamp_us);
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn callback_context(cost_meter: Option<Arc<NodeMeter>>) -> CallbackContext {
+        CallbackContext {
+            output_packets: Vec::new(),
+            error: None,
+            telemetry: Arc::new(TelemetryEmitter::new("node".to_string(), None, None)),
+            node_id: "node".to_string(),
+            cost_meter,
+        }
+    }
 
-    if tx.try_send(event).is_err() {
-        // Drop silently: best-effort.
+    fn report(ctx: &mut CallbackContext, event_type: &str, data: &[u8]) {
+        let event_type = CString::new(event_type).unwrap();
+        let result = telemetry_callback_shim(
+            event_type.as_ptr(),
+            data.as_ptr(),
+            data.len(),
+            std::ptr::null(),
+            std::ptr::from_mut(ctx).cast::<c_void>(),
+        );
+        assert!(result.success);
     }
 
-    CResult::success()
+    #[test]
+    fn test_gpu_time_reports_charge_the_cost_meter() {
+        let meter = Arc::new(NodeMeter::default());
+        let mut ctx = callback_context(Some(Arc::clone(&meter)));
+
+        report(&mut ctx, GPU_TIME_TELEMETRY_EVENT, br#"{"nanos":1500000}"#);
+        report(&mut ctx, GPU_TIME_TELEMETRY_EVENT, br#"{"nanos":500000}"#);
+        report(&mut ctx, GPU_TIME_TELEMETRY_EVENT, br#"{"secs":1}"#);
+        report(&mut ctx, "plugin.other", br#"{"nanos":1000000}"#);
+
+        assert_eq!(meter.gpu_time(), Duration::from_millis(2));
+        assert_eq!(meter.cpu_time(), Duration::ZERO);
+    }
 }
@@ -10,12 +10,12 @@
 
 use anyhow::Result;
 use bindings::streamkit::plugin::host::LogLevel;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError};
 use streamkit_core::{NodeRegistry, StreamKitError};
 use tokio::sync::Mutex;
 use wasmtime::component::{Component, HasSelf, Linker, ResourceTable};
-use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder};
+use wasmtime::{Cache, CacheConfig, Config, Engine, Store, StoreLimits, StoreLimitsBuilder};
 use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
 
 mod bindings {
@@ -24,6 +24,10 @@ mod bindings {
         world: "plugin",
         imports: { default: async },
         exports: { default: async },
+        // Reuse wasmtime-wasi's I/O types; the serde derives below cannot apply to resources.
+        with: { "wasi:io": wasmtime_wasi::p2::bindings::io },
+        // Lets node metadata be stored in the on-disk metadata cache.
+        additional_derives: [serde::Serialize, serde::Deserialize],
     });
 }
 
@@ -32,7 +36,12 @@ pub use bindings::streamkit::plugin::types as wit_types;
 use bindings::Plugin;
 
 mod conversions;
+mod metadata_cache;
 mod wrapper;
+pub use metadata_cache::{file_digest, MetadataCache, METADATA_CACHE_FILE_NAME};
+
+/// Directory name used for the compilation cache when it lives next to the plugins.
+pub const COMPILATION_CACHE_DIR_NAME: &str = ".compiled-cache";
 pub use wrapper::WasmNodeWrapper;
 
 /// Configuration for the WASM plugin runtime
@@ -44,6 +53,11 @@ pub struct PluginRuntimeConfig {
     pub enable_simd: bool,
     /// Enable multi-threading (experimental)
     pub enable_threads: bool,
+    /// Directory for wasmtime's on-disk cache of compiled components (default: disabled).
+    ///
+    /// Entries are keyed by the wasmtime version, the engine's compiler settings and a hash of
+    /// the component, so a changed plugin or engine config simply misses the cache.
+    pub compilation_cache_dir: Option<PathBuf>,
 }
 
 impl Default for PluginRuntimeConfig {
@@ -52,6 +66,7 @@ impl Default for PluginRuntimeConfig {
             max_memory_bytes: 64 * 1024 * 1024, // 64MB
             enable_simd: true,
             enable_threads: false,
+            compilation_cache_dir: None,
         }
     }
 }
@@ -62,6 +77,7 @@ pub struct PluginRuntime {
     linker: Arc<Linker<HostState>>,
     #[allow(dead_code)] // Stored for potential future use
     config: PluginRuntimeConfig,
+    metadata_cache: Option<MetadataCache>,
 }
 
 impl PluginRuntime {
@@ -76,6 +92,9 @@ impl PluginRuntime {
         engine_config.async_support(true);
         engine_config.wasm_simd(config.enable_simd);
         engine_config.wasm_threads(config.enable_threads);
+        if let Some(dir) = &config.compilation_cache_dir {
+            engine_config.cache(compilation_cache(dir));
+        }
 
         let engine = Engine::new(&engine_config)?;
         let mut linker = Linker::new(&engine);
@@ -89,7 +108,17 @@ impl PluginRuntime {
             |s| s,
         )?;
 
-        Ok(Self { engine, linker: Arc::new(linker), config })
+        Ok(Self { engine, linker: Arc::new(linker), config, metadata_cache: None })
+    }
+
+    /// Cache extracted plugin metadata in the file at `path`.
+    ///
+    /// Plugins whose file is unchanged since their metadata was cached are loaded without
+    /// compiling them; compilation is deferred until a node of theirs first runs.
+    #[must_use]
+    pub fn with_metadata_cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.metadata_cache = Some(MetadataCache::open(path));
+        self
     }
 
     /// Load a single plugin from a WASM file
@@ -100,11 +129,25 @@ impl PluginRuntime {
     /// - The file cannot be read or parsed as a valid WASM component
     /// - The component's metadata cannot be extracted
     pub fn load_plugin(&self, path: &Path) -> Result<LoadedPlugin> {
-        let component = Component::from_file(&self.engine, path)
-            .map_err(|e| anyhow::anyhow!("Failed to load component from file: {e:#}"))?;
+        let bytes = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read component file: {e}"))?;
+        let digest = file_digest(&bytes);
+
+        let cached = self.metadata_cache.as_ref().and_then(|cache| cache.get(&digest));
+        let (component, metadata) = if let Some(metadata) = cached {
+            tracing::debug!(path = ?path, kind = %metadata.kind, "Using cached WASM plugin metadata");
+            (PluginComponent::deferred(path, digest, &self.engine), metadata)
+        } else {
+            let component = Component::new(&self.engine, &bytes)
+                .map_err(|e| anyhow::anyhow!("Failed to load component from file: {e:#}"))?;
 
-        // Extract metadata by instantiating temporarily
-        let metadata = self.extract_metadata(&component)?;
+            // Extract metadata by instantiating temporarily
+            let metadata = self.extract_metadata(&component)?;
+            if let Some(cache) = &self.metadata_cache {
+                cache.insert(digest, path, metadata.clone());
+            }
+            (PluginComponent::compiled(component, &self.engine), metadata)
+        };
 
         tracing::info!(
             path = ?path,
@@ -113,7 +156,7 @@ impl PluginRuntime {
         );
 
         Ok(LoadedPlugin {
-            component,
+            component: Arc::new(component),
             metadata,
             engine: self.engine.clone(),
             linker: Arc::clone(&self.linker),
@@ -194,9 +237,91 @@ impl PluginRuntime {
     }
 }
 
+/// Builds wasmtime's compilation cache in `dir`.
+///
+/// A cache that cannot be set up only costs compile time, so failures are logged and the
+/// engine runs without one.
+fn compilation_cache(dir: &Path) -> Option<Cache> {
+    let mut cache_config = CacheConfig::new();
+    cache_config.with_directory(dir);
+    match Cache::new(cache_config) {
+        Ok(cache) => {
+            tracing::debug!(dir = ?dir, "Using WASM compilation cache");
+            Some(cache)
+        },
+        Err(e) => {
+            tracing::warn!(dir = ?dir, error = %e, "WASM compilation cache disabled");
+            None
+        },
+    }
+}
+
+/// A plugin's component, compiled up front or on first use.
+pub struct PluginComponent {
+    compiled: std::sync::Mutex<Option<Component>>,
+    /// Where to compile from when not yet compiled, and the digest the file must still have.
+    source: Option<(PathBuf, String)>,
+    engine: Engine,
+}
+
+impl PluginComponent {
+    /// Wraps an already compiled component.
+    pub fn compiled(component: Component, engine: &Engine) -> Self {
+        Self {
+            compiled: std::sync::Mutex::new(Some(component)),
+            source: None,
+            engine: engine.clone(),
+        }
+    }
+
+    /// A component compiled from `path` when first needed, provided the file still hashes
+    /// to `digest`.
+    pub fn deferred(path: &Path, digest: String, engine: &Engine) -> Self {
+        Self {
+            compiled: std::sync::Mutex::new(None),
+            source: Some((path.to_path_buf(), digest)),
+            engine: engine.clone(),
+        }
+    }
+
+    /// Returns the compiled component, compiling it first if needed.
+    ///
+    /// This blocks for the duration of the compilation on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin file can no longer be read, no longer matches the
+    /// metadata it was registered with, or fails to compile.
+    #[allow(clippy::significant_drop_tightening)] // Held so concurrent callers compile once
+    pub fn get(&self) -> Result<Component> {
+        let mut compiled = self.compiled.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(component) = compiled.as_ref() {
+            return Ok(component.clone());
+        }
+        let Some((path, digest)) = &self.source else {
+            anyhow::bail!("plugin component has no source to compile from");
+        };
+
+        let bytes = std::fs::read(path).map_err(|e| {
+            anyhow::anyhow!("Failed to read component file {}: {e}", path.display())
+        })?;
+        if file_digest(&bytes) != *digest {
+            anyhow::bail!(
+                "plugin file {} changed since it was loaded; reload the plugin",
+                path.display()
+            );
+        }
+        tracing::debug!(path = ?path, "Compiling deferred WASM plugin component");
+        let component = Component::new(&self.engine, &bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to load component from file: {e:#}"))?;
+        *compiled = Some(component.clone());
+        Ok(component)
+    }
+}
+
 /// A loaded WASM plugin ready to create node instances
 pub struct LoadedPlugin {
-    component: Component,
+    component: Arc<PluginComponent>,
     metadata: wit_types::NodeMetadata,
     engine: Engine,
     linker: Arc<Linker<HostState>>,
@@ -221,7 +346,7 @@ impl LoadedPlugin {
         params: Option<&serde_json::Value>,
     ) -> Result<Box<dyn streamkit_core::ProcessorNode>, StreamKitError> {
         let node = WasmNodeWrapper::new(
-            self.component.clone(),
+            Arc::clone(&self.component),
             self.metadata.clone(),
             params.cloned(),
             self.engine.clone(),
@@ -340,6 +465,18 @@ pub fn register_plugins(registry: &mut NodeRegistry, plugins: Vec<LoadedPlugin>)
 
         let categories = metadata.categories.clone();
 
+        for custom_type in &metadata.custom_types {
+            match serde_json::from_str(&custom_type.schema) {
+                Ok(schema) => registry.register_custom_type_schema(&custom_type.type_id, schema),
+                Err(e) => tracing::warn!(
+                    kind = %kind,
+                    type_id = %custom_type.type_id,
+                    error = %e,
+                    "Ignoring invalid custom packet type schema"
+                ),
+            }
+        }
+
         // Create a factory that captures the plugin
         let plugin = Arc::new(plugin);
         registry.register_dynamic(
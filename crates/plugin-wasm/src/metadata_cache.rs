@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! On-disk cache of plugin metadata.
+//!
+//! Reading a plugin's metadata means compiling and instantiating its component, which
+//! dominates server startup once a few dozen plugins are installed. The cache maps the
+//! SHA-256 of each plugin file to the metadata it reported, so unchanged plugins are
+//! registered without touching wasmtime at all. A modified file hashes differently and is
+//! extracted again; its old entry is dropped at that point.
+
+use crate::wit_types::NodeMetadata;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// File name used for the cache when it lives next to the plugins it describes.
+pub const METADATA_CACHE_FILE_NAME: &str = ".metadata-cache.json";
+
+/// Bump when the cache layout or the WIT `node-metadata` record changes.
+const CACHE_FORMAT: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    format: u32,
+    /// Host crate version; metadata extracted by another host build is not trusted.
+    host_version: String,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    file_name: String,
+    metadata: NodeMetadata,
+}
+
+/// Plugin metadata keyed by the digest of the plugin file.
+pub struct MetadataCache {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl MetadataCache {
+    /// Opens the cache stored at `path`.
+    ///
+    /// A missing, unreadable or outdated cache file starts an empty cache; it is rewritten on
+    /// the next insert.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<CacheFile>(&bytes) {
+                Ok(file)
+                    if file.format == CACHE_FORMAT
+                        && file.host_version == env!("CARGO_PKG_VERSION") =>
+                {
+                    tracing::debug!(
+                        path = ?path,
+                        entries = file.entries.len(),
+                        "Loaded plugin metadata cache"
+                    );
+                    file.entries
+                },
+                Ok(_) => {
+                    tracing::info!(
+                        path = ?path,
+                        "Discarding plugin metadata cache written by another host version"
+                    );
+                    HashMap::new()
+                },
+                Err(e) => {
+                    tracing::warn!(path = ?path, error = %e, "Ignoring corrupt plugin metadata cache");
+                    HashMap::new()
+                },
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                tracing::warn!(path = ?path, error = %e, "Failed to read plugin metadata cache");
+                HashMap::new()
+            },
+        };
+        Self { path, entries: Mutex::new(entries) }
+    }
+
+    /// Returns the cached metadata for a plugin file with the given digest.
+    pub fn get(&self, digest: &str) -> Option<NodeMetadata> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.get(digest).map(|entry| entry.metadata.clone())
+    }
+
+    /// Records the metadata extracted from `plugin_path` and writes the cache back to disk.
+    ///
+    /// Entries previously recorded for a file with the same name are replaced. Failing to
+    /// persist the cache only costs a slower next startup, so it is logged rather than returned.
+    pub fn insert(&self, digest: String, plugin_path: &Path, metadata: NodeMetadata) {
+        let file_name = plugin_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let snapshot = {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            entries.retain(|_, entry| entry.file_name != file_name);
+            entries.insert(digest, CacheEntry { file_name, metadata });
+            CacheFile {
+                format: CACHE_FORMAT,
+                host_version: env!("CARGO_PKG_VERSION").to_string(),
+                entries: entries.clone(),
+            }
+        };
+
+        if let Err(e) = self.persist(&snapshot) {
+            tracing::warn!(path = ?self.path, error = %e, "Failed to write plugin metadata cache");
+        }
+    }
+
+    fn persist(&self, file: &CacheFile) -> std::io::Result<()> {
+        let json = serde_json::to_vec_pretty(file).map_err(std::io::Error::other)?;
+
+        // Write then rename so a crash mid-write never leaves a truncated cache behind.
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, &self.path)
+    }
+}
+
+/// Hex-encoded SHA-256 of a plugin file's contents.
+pub fn file_digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().fold(String::with_capacity(64), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn metadata(kind: &str) -> NodeMetadata {
+        NodeMetadata {
+            kind: kind.to_string(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            param_schema: "{}".to_string(),
+            categories: vec!["audio".to_string()],
+            custom_types: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(METADATA_CACHE_FILE_NAME);
+        let digest = file_digest(b"plugin v1");
+
+        MetadataCache::open(&path).insert(
+            digest.clone(),
+            &dir.path().join("gain.wasm"),
+            metadata("gain"),
+        );
+
+        let reopened = MetadataCache::open(&path);
+        assert_eq!(reopened.get(&digest).unwrap().kind, "gain");
+        assert!(reopened.get(&file_digest(b"plugin v2")).is_none());
+    }
+
+    #[test]
+    fn test_changed_file_replaces_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::open(dir.path().join(METADATA_CACHE_FILE_NAME));
+        let plugin_path = dir.path().join("gain.wasm");
+        let (v1, v2) = (file_digest(b"plugin v1"), file_digest(b"plugin v2"));
+
+        cache.insert(v1.clone(), &plugin_path, metadata("gain"));
+        cache.insert(v2.clone(), &plugin_path, metadata("gain2"));
+
+        assert!(cache.get(&v1).is_none());
+        assert_eq!(cache.get(&v2).unwrap().kind, "gain2");
+    }
+
+    #[test]
+    fn test_corrupt_cache_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(METADATA_CACHE_FILE_NAME);
+        std::fs::write(&path, b"not json").unwrap();
+
+        let cache = MetadataCache::open(&path);
+        let digest = file_digest(b"plugin");
+        assert!(cache.get(&digest).is_none());
+
+        cache.insert(digest.clone(), &dir.path().join("gain.wasm"), metadata("gain"));
+        assert!(MetadataCache::open(&path).get(&digest).is_some());
+    }
+
+    #[test]
+    fn test_file_digest_is_hex_sha256() {
+        assert_eq!(
+            file_digest(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -5,7 +5,7 @@
 //! WASM node wrapper that implements the ProcessorNode trait
 
 use crate::bindings::Plugin;
-use crate::{wit_types, HostState};
+use crate::{wit_types, HostState, PluginComponent};
 use async_trait::async_trait;
 use futures::future::poll_fn;
 use std::{sync::Arc, task::Poll};
@@ -21,7 +21,7 @@ use wasmtime_wasi::WasiCtx;
 
 /// Wraps a WASM component to implement the ProcessorNode trait
 pub struct WasmNodeWrapper {
-    component: wasmtime::component::Component,
+    component: Arc<PluginComponent>,
     metadata: wit_types::NodeMetadata,
     params: Option<serde_json::Value>,
     engine: Engine,
@@ -33,7 +33,7 @@ impl WasmNodeWrapper {
     // Cannot be const: wasmtime types (Component, Engine) and Arc are not const-constructible
     #[allow(clippy::missing_const_for_fn)]
     pub fn new(
-        component: wasmtime::component::Component,
+        component: Arc<PluginComponent>,
         metadata: wit_types::NodeMetadata,
         params: Option<serde_json::Value>,
         engine: Engine,
@@ -98,6 +98,29 @@ impl ProcessorNode for WasmNodeWrapper {
         let mut store = Store::new(&engine, host_state);
         store.limiter(|s| &mut s.limits);
 
+        // Plugins loaded from the metadata cache compile on first use, off the async runtime
+        let component = match tokio::task::spawn_blocking(move || component.get()).await {
+            Ok(Ok(component)) => component,
+            Ok(Err(e)) => {
+                let err = StreamKitError::Configuration(format!("Failed to compile plugin: {e:#}"));
+                emit_state(
+                    &state_tx_clone,
+                    &node_id,
+                    NodeState::Failed { reason: err.to_string() },
+                );
+                return Err(err);
+            },
+            Err(e) => {
+                let err = StreamKitError::Runtime(format!("Plugin compilation task failed: {e}"));
+                emit_state(
+                    &state_tx_clone,
+                    &node_id,
+                    NodeState::Failed { reason: err.to_string() },
+                );
+                return Err(err);
+            },
+        };
+
         // Instantiate the component
         let instance = match linker.instantiate_async(&mut store, &component).await {
             Ok(instance) => instance,
@@ -243,6 +266,11 @@ impl ProcessorNode for WasmNodeWrapper {
                             tracing::info!("WASM plugin received shutdown signal");
                             break;
                         }
+                        Some(NodeControlMessage::Query { reply, .. }) => {
+                            // The WIT interface has no query export yet
+                            let _ = reply
+                                .send(Err("WASM plugins do not support queries".to_string()));
+                        }
                         None => {
                             control_channel_open = false;
                         }
@@ -0,0 +1,276 @@
+#!/usr/bin/env bash
+# SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+#
+# SPDX-License-Identifier: MPL-2.0
+
+set -euo pipefail
+IFS=$'\n\t'
+
+REPO_DEFAULT="streamer45/streamkit"
+LABEL="dev.streamkit.skit"
+TAG=""
+REPO="$REPO_DEFAULT"
+INSTALL_PREFIX="${HOME}/.streamkit"
+LOG_DIR="${HOME}/Library/Logs/StreamKit"
+PLIST="${HOME}/Library/LaunchAgents/${LABEL}.plist"
+NO_START="0"
+UNINSTALL="0"
+PURGE="0"
+YES="0"
+
+usage() {
+	cat <<'EOF'
+Usage: install.sh [OPTIONS]
+
+Installs StreamKit from GitHub Releases and registers a launchd agent for the
+current user.
+
+Options:
+  --tag vX.Y.Z        Install a specific release tag (required unless --latest)
+  --latest            Install the latest GitHub Release
+  --repo owner/name   GitHub repo to install from (default: streamer45/streamkit)
+  --prefix PATH       Install prefix (default: ~/.streamkit)
+  --no-start          Only install files; don't load the agent
+  --uninstall         Uninstall StreamKit (removes agent and binaries)
+  --purge             With --uninstall: also remove config, plugins, and logs
+  -y, --yes           Skip confirmation prompts
+  -h, --help          Show help
+
+Examples:
+  ./install.sh --tag v0.2.0
+  ./install.sh --latest
+  ./install.sh --uninstall
+  ./install.sh --uninstall --purge
+EOF
+}
+
+need_cmd() {
+	command -v "$1" >/dev/null 2>&1 || {
+		echo "Missing required command: $1" >&2
+		exit 1
+	}
+}
+
+while [[ $# -gt 0 ]]; do
+	case "$1" in
+		--tag|--version)
+			TAG="${2:-}"
+			shift 2
+			;;
+		--latest)
+			TAG="__latest__"
+			shift
+			;;
+		--repo)
+			REPO="${2:-}"
+			shift 2
+			;;
+		--prefix)
+			INSTALL_PREFIX="${2:-}"
+			shift 2
+			;;
+		--no-start)
+			NO_START="1"
+			shift
+			;;
+		--uninstall)
+			UNINSTALL="1"
+			shift
+			;;
+		--purge)
+			PURGE="1"
+			shift
+			;;
+		-y|--yes)
+			YES="1"
+			shift
+			;;
+		-h|--help)
+			usage
+			exit 0
+			;;
+		*)
+			echo "Unknown argument: $1" >&2
+			usage >&2
+			exit 2
+			;;
+	esac
+done
+
+if [[ "$(uname -s)" != "Darwin" ]]; then
+	echo "This installer is for macOS; use deploy/systemd on Linux." >&2
+	exit 1
+fi
+
+if [[ "${EUID:-$(id -u)}" -eq 0 ]]; then
+	echo "Run as the user the agent should run for, not as root." >&2
+	exit 1
+fi
+
+domain="gui/$(id -u)"
+
+unload_agent() {
+	launchctl bootout "${domain}/${LABEL}" 2>/dev/null || true
+}
+
+do_uninstall() {
+	echo "This will remove:"
+	echo "  - StreamKit binaries (${INSTALL_PREFIX}/releases)"
+	echo "  - launchd agent (${PLIST})"
+	if [[ "$PURGE" == "1" ]]; then
+		echo "  - Configuration and plugins (${INSTALL_PREFIX})"
+		echo "  - Logs (${LOG_DIR})"
+	fi
+	echo ""
+	if [[ "$YES" != "1" ]]; then
+		read -r -p "Continue? [y/N] " confirm
+		if [[ ! "$confirm" =~ ^[Yy]$ ]]; then
+			echo "Aborted."
+			exit 0
+		fi
+	fi
+
+	echo ""
+	echo "Uninstalling StreamKit..."
+
+	unload_agent
+	rm -f "$PLIST"
+
+	if [[ "$PURGE" == "1" ]]; then
+		echo "Purging configuration, plugins and logs..."
+		rm -rf "$INSTALL_PREFIX" "$LOG_DIR"
+	else
+		rm -rf "${INSTALL_PREFIX}/releases"
+		rm -f "${INSTALL_PREFIX}/current" "${INSTALL_PREFIX}/skit" "${INSTALL_PREFIX}/skit-cli"
+		echo ""
+		echo "Note: Config and plugins (${INSTALL_PREFIX}) and logs (${LOG_DIR}) preserved."
+		echo "Use --purge to remove everything."
+	fi
+
+	echo "StreamKit uninstalled."
+	exit 0
+}
+
+if [[ "$UNINSTALL" == "1" ]]; then
+	do_uninstall
+fi
+
+need_cmd curl
+need_cmd tar
+need_cmd sed
+need_cmd shasum
+need_cmd launchctl
+
+arch="$(uname -m)"
+case "$arch" in
+	arm64) platform="macos-arm64" ;;
+	*)
+		echo "Unsupported architecture: ${arch}" >&2
+		exit 1
+		;;
+esac
+
+gh_api_latest_tag() {
+	need_cmd head
+	curl -fsSL "https://api.github.com/repos/${REPO}/releases/latest" \
+		| sed -n 's/^[[:space:]]*"tag_name":[[:space:]]*"\([^"]*\)".*/\1/p' \
+		| head -n 1
+}
+
+if [[ -z "$TAG" ]]; then
+	echo "Missing --tag (or use --latest)." >&2
+	exit 2
+fi
+
+if [[ "$TAG" == "__latest__" ]]; then
+	TAG="$(gh_api_latest_tag)"
+	if [[ -z "$TAG" ]]; then
+		echo "Failed to resolve latest release tag for ${REPO}." >&2
+		exit 1
+	fi
+fi
+
+asset="streamkit-${TAG}-${platform}.tar.gz"
+base_url="https://github.com/${REPO}/releases/download/${TAG}"
+tmp_dir="$(mktemp -d)"
+cleanup() { rm -rf "$tmp_dir"; }
+trap cleanup EXIT
+
+echo "Downloading ${asset} from ${REPO} (${TAG})..."
+curl -fsSL -o "${tmp_dir}/${asset}" "${base_url}/${asset}"
+curl -fsSL -o "${tmp_dir}/${asset}.sha256" "${base_url}/${asset}.sha256"
+
+(
+	cd "$tmp_dir"
+	shasum -a 256 -c "${asset}.sha256"
+)
+
+echo "Extracting..."
+tar -xzf "${tmp_dir}/${asset}" -C "$tmp_dir"
+bundle_dir="${tmp_dir}/streamkit-${TAG}"
+if [[ ! -d "$bundle_dir" ]]; then
+	echo "Unexpected tarball contents (missing $(basename "$bundle_dir")/ directory)." >&2
+	exit 1
+fi
+# Downloaded binaries are quarantined by Gatekeeper until the attribute is cleared
+xattr -dr com.apple.quarantine "$bundle_dir" 2>/dev/null || true
+
+mkdir -p "${INSTALL_PREFIX}/releases" "${INSTALL_PREFIX}/plugins" "$LOG_DIR"
+release_dir="${INSTALL_PREFIX}/releases/${TAG}"
+if [[ -e "$release_dir" ]]; then
+	echo "Release already installed at ${release_dir}."
+else
+	mv "$bundle_dir" "$release_dir"
+fi
+
+ln -sfn "$release_dir" "${INSTALL_PREFIX}/current"
+ln -sfn "current/skit" "${INSTALL_PREFIX}/skit"
+ln -sfn "current/skit-cli" "${INSTALL_PREFIX}/skit-cli"
+
+script_dir="$(CDPATH= cd -- "$(dirname -- "${BASH_SOURCE[0]}")" && pwd)"
+
+install_config() {
+	local dst="${INSTALL_PREFIX}/skit.toml"
+	if [[ -f "$dst" ]]; then
+		return
+	fi
+	if [[ -f "${script_dir}/skit.toml" ]]; then
+		install -m 0644 "${script_dir}/skit.toml" "$dst"
+		return
+	fi
+	cat >"$dst" <<'EOF'
+[server]
+address = "127.0.0.1:4545"
+
+[plugins]
+directory = "plugins"
+
+[log]
+console_enable = true
+file_enable = false
+console_level = "info"
+EOF
+	chmod 0644 "$dst"
+}
+
+install_agent() {
+	local template="${script_dir}/${LABEL}.plist"
+	if [[ ! -f "$template" ]]; then
+		template="${release_dir}/launchd/${LABEL}.plist"
+	fi
+	mkdir -p "$(dirname "$PLIST")"
+	sed -e "s|@PREFIX@|${INSTALL_PREFIX}|g" -e "s|@LOG_DIR@|${LOG_DIR}|g" "$template" >"$PLIST"
+	chmod 0644 "$PLIST"
+}
+
+install_config
+install_agent
+
+if [[ "$NO_START" == "1" ]]; then
+	echo "Installed. Start with: launchctl bootstrap ${domain} ${PLIST}"
+	exit 0
+fi
+
+unload_agent
+launchctl bootstrap "$domain" "$PLIST"
+launchctl print "${domain}/${LABEL}" | head -n 20 || true
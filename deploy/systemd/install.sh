@@ -186,6 +186,7 @@ need_cmd install
 arch="$(uname -m)"
 case "$arch" in
 	x86_64|amd64) platform="linux-x64" ;;
+	aarch64|arm64) platform="linux-arm64" ;;
 	*)
 		echo "Unsupported architecture: ${arch}" >&2
 		exit 1
@@ -300,6 +301,11 @@ install_config() {
 	if [[ -f "$dst" ]]; then
 		return
 	fi
+	# ARM64 boxes get the edge config (smaller queues, fewer threads, session caps)
+	if [[ "$platform" == "linux-arm64" && -f "${script_dir}/skit-edge.toml" ]]; then
+		install -m 0644 "${script_dir}/skit-edge.toml" "$dst"
+		return
+	fi
 	if [[ -f "${script_dir}/skit.toml" ]]; then
 		install -m 0644 "${script_dir}/skit.toml" "$dst"
 		return
@@ -0,0 +1,137 @@
+# SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+#
+# SPDX-License-Identifier: MPL-2.0
+
+<#
+.SYNOPSIS
+Installs StreamKit from GitHub Releases and registers it as a Windows service.
+
+.EXAMPLE
+.\install.ps1 -Tag v0.2.0
+
+.EXAMPLE
+.\install.ps1 -Latest
+
+.EXAMPLE
+.\install.ps1 -Uninstall -Purge
+#>
+
+[CmdletBinding()]
+param(
+    # Install a specific release tag (required unless -Latest)
+    [string]$Tag = "",
+    # Install the latest GitHub Release
+    [switch]$Latest,
+    # GitHub repo to install from
+    [string]$Repo = "streamer45/streamkit",
+    # Install prefix
+    [string]$Prefix = "$env:ProgramFiles\StreamKit",
+    # Config, plugins and logs
+    [string]$DataDir = "$env:ProgramData\StreamKit",
+    # Only install files; don't start the service
+    [switch]$NoStart,
+    # Uninstall StreamKit (removes service and binaries)
+    [switch]$Uninstall,
+    # With -Uninstall: also remove config, plugins and logs
+    [switch]$Purge
+)
+
+$ErrorActionPreference = "Stop"
+$ServiceName = "StreamKit"
+
+$principal = New-Object Security.Principal.WindowsPrincipal([Security.Principal.WindowsIdentity]::GetCurrent())
+if (-not $principal.IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)) {
+    Write-Error "Run from an elevated (Administrator) PowerShell."
+}
+
+$skit = Join-Path $Prefix "skit.exe"
+$config = Join-Path $DataDir "skit.toml"
+
+if ($Uninstall) {
+    if (Get-Service -Name $ServiceName -ErrorAction SilentlyContinue) {
+        Write-Host "Removing service..."
+        if (Test-Path $skit) {
+            & $skit service uninstall
+        } else {
+            Stop-Service -Name $ServiceName -ErrorAction SilentlyContinue
+            sc.exe delete $ServiceName | Out-Null
+        }
+    }
+    if (Test-Path $Prefix) {
+        Write-Host "Removing $Prefix..."
+        Remove-Item -Recurse -Force $Prefix
+    }
+    if ($Purge) {
+        if (Test-Path $DataDir) {
+            Write-Host "Removing $DataDir..."
+            Remove-Item -Recurse -Force $DataDir
+        }
+    } else {
+        Write-Host "Note: Config, plugins and logs ($DataDir) preserved. Use -Purge to remove them."
+    }
+    Write-Host "StreamKit uninstalled."
+    exit 0
+}
+
+if ($Latest) {
+    $release = Invoke-RestMethod -Uri "https://api.github.com/repos/$Repo/releases/latest"
+    $Tag = $release.tag_name
+}
+if (-not $Tag) {
+    Write-Error "Missing -Tag (or use -Latest)."
+}
+
+$asset = "streamkit-$Tag-windows-x64.zip"
+$baseUrl = "https://github.com/$Repo/releases/download/$Tag"
+$tmpDir = Join-Path ([IO.Path]::GetTempPath()) ([Guid]::NewGuid())
+New-Item -ItemType Directory -Path $tmpDir | Out-Null
+
+try {
+    Write-Host "Downloading $asset from $Repo ($Tag)..."
+    Invoke-WebRequest -Uri "$baseUrl/$asset" -OutFile (Join-Path $tmpDir $asset)
+    Invoke-WebRequest -Uri "$baseUrl/$asset.sha256" -OutFile (Join-Path $tmpDir "$asset.sha256")
+
+    $expected = (Get-Content (Join-Path $tmpDir "$asset.sha256")).Split(" ")[0].Trim().ToLower()
+    $actual = (Get-FileHash -Algorithm SHA256 (Join-Path $tmpDir $asset)).Hash.ToLower()
+    if ($expected -ne $actual) {
+        Write-Error "Checksum mismatch for $asset"
+    }
+
+    Write-Host "Extracting..."
+    Expand-Archive -Path (Join-Path $tmpDir $asset) -DestinationPath $tmpDir
+    $bundleDir = Join-Path $tmpDir "streamkit-$Tag"
+
+    # The service holds skit.exe open, so it has to stop before the files are replaced
+    if (Get-Service -Name $ServiceName -ErrorAction SilentlyContinue) {
+        Stop-Service -Name $ServiceName
+    }
+
+    New-Item -ItemType Directory -Force -Path $Prefix, (Join-Path $DataDir "plugins"), (Join-Path $DataDir "logs") | Out-Null
+    Copy-Item -Recurse -Force -Path (Join-Path $bundleDir "*") -Destination $Prefix
+
+    if (-not (Test-Path $config)) {
+        Copy-Item (Join-Path $PSScriptRoot "skit.toml") $config -ErrorAction SilentlyContinue
+        if (-not (Test-Path $config)) {
+            Copy-Item (Join-Path $Prefix "windows\skit.toml") $config
+        }
+    }
+} finally {
+    Remove-Item -Recurse -Force $tmpDir -ErrorAction SilentlyContinue
+}
+
+if (-not (Get-Service -Name $ServiceName -ErrorAction SilentlyContinue)) {
+    & $skit --config $config service install
+    if ($LASTEXITCODE -ne 0) {
+        Write-Error "Failed to register the service."
+    }
+    # Restart after crashes: 5s, 5s, then 30s, resetting the count after a day
+    sc.exe failure $ServiceName reset= 86400 actions= restart/5000/restart/5000/restart/30000 | Out-Null
+}
+
+if ($NoStart) {
+    Write-Host "Installed. Start with: Start-Service $ServiceName"
+    exit 0
+}
+
+Start-Service -Name $ServiceName
+Get-Service -Name $ServiceName
@@ -72,6 +72,9 @@ export default defineConfig({
 						{ label: 'Docker', slug: 'deployment/docker' },
 						{ label: 'GPU Setup', slug: 'deployment/gpu' },
 						{ label: 'systemd', slug: 'deployment/systemd' },
+						{ label: 'macOS (launchd)', slug: 'deployment/launchd' },
+						{ label: 'Windows Service', slug: 'deployment/windows' },
+						{ label: 'Raspberry Pi / ARM64', slug: 'deployment/raspberry-pi' },
 					],
 				},
 				{
@@ -295,7 +295,8 @@ static const CNativePluginAPI g_plugin_api = {
     .process_packet = gain_process_packet,
     .update_params = gain_update_params,
     .flush = gain_flush,
-    .destroy_instance = gain_destroy_instance
+    .destroy_instance = gain_destroy_instance,
+    .query = NULL
 };
 
 /* Export the plugin entry point */
@@ -9,7 +9,7 @@
  * Plugins must export a single symbol `streamkit_native_plugin_api` that
  * returns a pointer to a CNativePluginAPI struct.
  *
- * API Version: 2
+ * ABI Version: 1.2
  */
 
 #ifndef STREAMKIT_PLUGIN_H
@@ -27,8 +27,31 @@ extern "C" {
  * Constants
  * ============================================================================ */
 
-/** Current API version. Plugins and host check compatibility via this field. */
-#define STREAMKIT_NATIVE_PLUGIN_API_VERSION 2
+/**
+ * Semantic version of the C ABI.
+ *
+ * The major version changes only on breaking layout changes; the host refuses plugins
+ * built against another major. Minor versions are additive, and the host keeps loading
+ * plugins built against an older minor of its major.
+ *
+ * Since 1.1 the host passes plugins a host-reserved "_sandbox" object in the
+ * create_instance params, holding the instance's private "work_dir" and its filtered
+ * "env"; plugins must ignore it or use it, but never treat it as one of their own params.
+ * 1.0 plugins are still loaded, unsandboxed.
+ *
+ * Since 1.2 CNodeMetadata ends with the plugin's requirements and conflicts, which the
+ * host verifies before using the plugin.
+ */
+#define STREAMKIT_NATIVE_PLUGIN_ABI_MAJOR 1
+#define STREAMKIT_NATIVE_PLUGIN_ABI_MINOR 2
+
+/**
+ * Encoded ABI version plugins put in CNativePluginAPI.version: major << 16 | minor.
+ *
+ * Plain values 3 and 4 are accepted from older plugins as ABI 1.0 and 1.1.
+ */
+#define STREAMKIT_NATIVE_PLUGIN_API_VERSION \
+    (((uint32_t)STREAMKIT_NATIVE_PLUGIN_ABI_MAJOR << 16) | STREAMKIT_NATIVE_PLUGIN_ABI_MINOR)
 
 /* ============================================================================
  * Core Types
@@ -203,6 +226,13 @@ typedef struct CNodeMetadata {
     const char* param_schema;               /**< JSON Schema as string */
     const char* const* categories;          /**< Array of category strings */
     size_t categories_count;
+    /* ABI 1.2 and later; may be NULL with a zero count */
+    const char* const* requires_libraries;  /**< Shared libraries loaded at runtime (dlopen names) */
+    size_t requires_libraries_count;
+    const char* const* requires_features;   /**< Host features needed ("gpu", "avx2", ...) */
+    size_t requires_features_count;
+    const char* const* conflicts_with;      /**< Plugin kinds that can't be loaded alongside */
+    size_t conflicts_with_count;
 } CNodeMetadata;
 
 /* ============================================================================
@@ -228,10 +258,11 @@ typedef CResult (*COutputCallback)(const char* pin_name, const CPacket* packet,
  * The main plugin API structure.
  *
  * Plugins export a function `streamkit_native_plugin_api()` that returns
- * a pointer to this struct. All function pointers must be non-NULL.
+ * a pointer to this struct. All function pointers must be non-NULL, except
+ * `query`, which may be NULL if the plugin answers no queries.
  */
 typedef struct CNativePluginAPI {
-    /** API version for compatibility checking. Must be STREAMKIT_NATIVE_PLUGIN_API_VERSION */
+    /** Encoded ABI version for compatibility checking. Must be STREAMKIT_NATIVE_PLUGIN_API_VERSION */
     uint32_t version;
 
     /**
@@ -291,6 +322,19 @@ typedef struct CNativePluginAPI {
      * @param handle Plugin instance handle
      */
     void (*destroy_instance)(CPluginHandle handle);
+
+    /**
+     * Answer a runtime query from a client (may be NULL).
+     *
+     * @param handle   Plugin instance handle
+     * @param kind     Name of the query (e.g. "model_info")
+     * @param args     JSON string with query arguments (can be NULL)
+     * @param response Set to a JSON string on success. Owned by the plugin and
+     *                 must stay valid until the plugin's next call on this thread.
+     * @return         CResult indicating success or failure
+     */
+    CResult (*query)(CPluginHandle handle, const char* kind, const char* args,
+                     const char** response);
 } CNativePluginAPI;
 
 /* ============================================================================
@@ -107,6 +107,13 @@ impl NativeProcessorNode for GainPlugin {
         }
         Ok(())
     }
+
+    fn query(&mut self, kind: &str, _args: Value) -> Result<Value, String> {
+        match kind {
+            "gain" => Ok(serde_json::json!({ "linear": self.gain })),
+            other => Err(format!("Unsupported query '{other}'")),
+        }
+    }
 }
 
 /// Convert decibels to linear gain
@@ -115,6 +115,10 @@ void exports_streamkit_plugin_node_metadata(exports_streamkit_plugin_node_node_m
     plugin_string_dup(&metadata.categories.ptr[0], "audio");
     plugin_string_dup(&metadata.categories.ptr[1], "filters");
 
+    // No custom packet types
+    metadata.custom_types.len = 0;
+    metadata.custom_types.ptr = NULL;
+
     *ret = metadata;
 }
 
@@ -72,6 +72,7 @@ impl Guest for GainPlugin {
              }"#
             .to_string(),
             categories: vec!["audio".to_string(), "filters".to_string()],
+            custom_types: Vec::new(),
         }
     }
 }
@@ -0,0 +1,130 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Configuration structures for the fingerprint plugin
+
+use serde::{Deserialize, Serialize};
+
+/// A reference recording to detect in the stream
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReferenceConfig {
+    /// Identifier reported in match events
+    pub id: String,
+
+    /// Path to a JSON fingerprint file, either `{"fingerprints": [...]}` (the payload of
+    /// the plugin's own fingerprint packets) or a bare array of sub-fingerprints
+    pub path: String,
+}
+
+/// On-disk reference formats
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReferenceFile {
+    Object { fingerprints: Vec<u32> },
+    Array(Vec<u32>),
+}
+
+impl ReferenceConfig {
+    /// Reads the reference's sub-fingerprints from disk.
+    pub fn load(&self) -> Result<Vec<u32>, String> {
+        let contents = std::fs::read_to_string(&self.path).map_err(|e| {
+            format!("Failed to read reference '{}' ({}): {}", self.id, self.path, e)
+        })?;
+        let file: ReferenceFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid reference '{}' ({}): {}", self.id, self.path, e))?;
+        let fingerprints = match file {
+            ReferenceFile::Object { fingerprints } | ReferenceFile::Array(fingerprints) => {
+                fingerprints
+            },
+        };
+        if fingerprints.is_empty() {
+            return Err(format!("Reference '{}' ({}) is empty", self.id, self.path));
+        }
+        Ok(fingerprints)
+    }
+}
+
+/// Configuration for the fingerprint plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintConfig {
+    /// Reference recordings to match against
+    #[serde(default)]
+    pub references: Vec<ReferenceConfig>,
+
+    /// Maximum fraction of differing bits for a block to count as a match (0.0 - 1.0)
+    #[serde(default = "default_max_bit_error_rate")]
+    pub max_bit_error_rate: f32,
+
+    /// Length of audio compared per match decision, in milliseconds
+    #[serde(default = "default_block_ms")]
+    pub block_ms: u64,
+
+    /// Also report audio that repeats earlier audio of the same stream
+    #[serde(default)]
+    pub detect_loops: bool,
+
+    /// How far back loop detection looks, in seconds
+    #[serde(default = "default_loop_history_s")]
+    pub loop_history_s: u64,
+
+    /// Frames quieter than this never start a match
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: f32,
+
+    /// Emit the computed sub-fingerprints on `out`, e.g. to record new references
+    #[serde(default)]
+    pub emit_fingerprints: bool,
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            references: Vec::new(),
+            max_bit_error_rate: default_max_bit_error_rate(),
+            block_ms: default_block_ms(),
+            detect_loops: false,
+            loop_history_s: default_loop_history_s(),
+            silence_threshold_db: default_silence_threshold_db(),
+            emit_fingerprints: false,
+        }
+    }
+}
+
+impl FingerprintConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.max_bit_error_rate) {
+            return Err(format!(
+                "max_bit_error_rate must be between 0.0 and 1.0, got {}",
+                self.max_bit_error_rate
+            ));
+        }
+        if self.block_ms == 0 {
+            return Err("block_ms must be greater than 0".to_string());
+        }
+        if self.detect_loops && self.loop_history_s * 1000 <= self.block_ms {
+            return Err("loop_history_s must be longer than block_ms".to_string());
+        }
+        let mut ids = std::collections::HashSet::new();
+        if let Some(duplicate) = self.references.iter().find(|r| !ids.insert(r.id.as_str())) {
+            return Err(format!("Duplicate reference id '{}'", duplicate.id));
+        }
+        Ok(())
+    }
+}
+
+fn default_max_bit_error_rate() -> f32 {
+    0.35
+}
+
+fn default_block_ms() -> u64 {
+    3000
+}
+
+fn default_loop_history_s() -> u64 {
+    120
+}
+
+fn default_silence_threshold_db() -> f32 {
+    -60.0
+}
@@ -0,0 +1,320 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sub-fingerprint extraction
+//!
+//! Audio is reduced to ~5.5 kHz mono, analysed in overlapping 2048-sample frames and each
+//! frame is summarised as a 32-bit word. Bit `m` records whether the energy difference
+//! between bands `m` and `m + 1` grew or shrank compared to the previous frame, which makes
+//! the words robust to gain changes, mild EQ and lossy coding while staying cheap to compare
+//! with a popcount.
+
+use std::f32::consts::PI;
+
+/// Internal analysis rate in Hz.
+pub const ANALYSIS_RATE: u32 = 5512;
+/// Samples per analysis frame at [`ANALYSIS_RATE`].
+const FRAME_LEN: usize = 2048;
+/// Samples between consecutive frames at [`ANALYSIS_RATE`].
+pub const HOP_LEN: usize = 64;
+/// Number of bits per sub-fingerprint; one band more is needed to form the differences.
+const BITS: usize = 32;
+const LOW_HZ: f32 = 300.0;
+const HIGH_HZ: f32 = 2000.0;
+
+/// Converts a frame count into milliseconds of stream time.
+pub fn frames_to_ms(frames: u64) -> u64 {
+    frames * HOP_LEN as u64 * 1000 / u64::from(ANALYSIS_RATE)
+}
+
+/// Converts milliseconds into a frame count, rounding down but never below one frame.
+pub fn ms_to_frames(ms: u64) -> usize {
+    let frames = ms * u64::from(ANALYSIS_RATE) / (HOP_LEN as u64 * 1000);
+    usize::try_from(frames).unwrap_or(usize::MAX).max(1)
+}
+
+/// One analysed frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubFingerprint {
+    pub value: u32,
+    /// The frame was below the silence threshold. Silent frames still take part in
+    /// comparisons but are never used to look up candidates.
+    pub silent: bool,
+}
+
+/// In-place iterative radix-2 FFT over a fixed power-of-two size.
+struct Fft {
+    twiddles: Vec<(f32, f32)>,
+    bit_reverse: Vec<usize>,
+}
+
+impl Fft {
+    fn new(size: usize) -> Self {
+        let bits = size.trailing_zeros();
+        let bit_reverse =
+            (0..size).map(|i| i.reverse_bits() >> (usize::BITS - bits)).collect::<Vec<_>>();
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let angle = -2.0 * PI * k as f32 / size as f32;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        Self { twiddles, bit_reverse }
+    }
+
+    fn process(&self, re: &mut [f32], im: &mut [f32]) {
+        let size = re.len();
+        for (i, &j) in self.bit_reverse.iter().enumerate() {
+            if i < j {
+                re.swap(i, j);
+                im.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= size {
+            let half = len / 2;
+            let stride = size / len;
+            for start in (0..size).step_by(len) {
+                for k in 0..half {
+                    let (wr, wi) = self.twiddles[k * stride];
+                    let (a, b) = (start + k, start + k + half);
+                    let tr = re[b] * wr - im[b] * wi;
+                    let ti = re[b] * wi + im[b] * wr;
+                    re[b] = re[a] - tr;
+                    im[b] = im[a] - ti;
+                    re[a] += tr;
+                    im[a] += ti;
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+/// Streaming sub-fingerprint extractor.
+///
+/// Accepts interleaved audio at any sample rate and channel count and yields one
+/// [`SubFingerprint`] every [`HOP_LEN`] analysis samples once the first frame is full.
+pub struct Fingerprinter {
+    /// Input samples per analysis sample
+    step: f64,
+    /// Input position relative to the next analysis sample boundary
+    phase: f64,
+    acc: f32,
+    acc_count: u32,
+    /// Ring of the most recent `FRAME_LEN` analysis samples; `write` is the oldest slot
+    buffer: Vec<f32>,
+    write: usize,
+    filled: usize,
+    /// Analysis samples collected since the last frame was emitted
+    since_hop: usize,
+    window: Vec<f32>,
+    /// FFT bin range `[start, end)` per band
+    bands: Vec<(usize, usize)>,
+    fft: Fft,
+    re: Vec<f32>,
+    im: Vec<f32>,
+    previous: Option<[f32; BITS]>,
+    /// Mean-square level below which a frame is flagged silent
+    silence_power: f32,
+}
+
+impl Fingerprinter {
+    pub fn new(sample_rate: u32, silence_threshold_db: f32) -> Self {
+        let window = (0..FRAME_LEN)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME_LEN as f32).cos())
+            .collect();
+        let bin_hz = ANALYSIS_RATE as f32 / FRAME_LEN as f32;
+        let ratio = (HIGH_HZ / LOW_HZ).powf(1.0 / (BITS + 1) as f32);
+        let edges: Vec<usize> = (0..=BITS + 1)
+            .map(|i| (LOW_HZ * ratio.powi(i as i32) / bin_hz).round() as usize)
+            .collect();
+        let bands = edges.windows(2).map(|w| (w[0], w[1].max(w[0] + 1))).collect();
+
+        Self {
+            step: f64::from(sample_rate) / f64::from(ANALYSIS_RATE),
+            phase: 0.0,
+            acc: 0.0,
+            acc_count: 0,
+            buffer: vec![0.0; FRAME_LEN],
+            write: 0,
+            filled: 0,
+            since_hop: 0,
+            window,
+            bands,
+            fft: Fft::new(FRAME_LEN),
+            re: vec![0.0; FRAME_LEN],
+            im: vec![0.0; FRAME_LEN],
+            previous: None,
+            silence_power: 10f32.powf(silence_threshold_db / 10.0),
+        }
+    }
+
+    /// Feeds interleaved samples, appending any completed sub-fingerprints to `out`.
+    pub fn push(&mut self, samples: &[f32], channels: usize, out: &mut Vec<SubFingerprint>) {
+        let channels = channels.max(1);
+        let scale = 1.0 / channels as f32;
+        for frame in samples.chunks_exact(channels) {
+            self.acc += frame.iter().sum::<f32>() * scale;
+            self.acc_count += 1;
+            self.phase += 1.0;
+            if self.phase >= self.step {
+                self.phase -= self.step;
+                let sample = self.acc / self.acc_count as f32;
+                self.acc = 0.0;
+                self.acc_count = 0;
+                self.push_analysis_sample(sample, out);
+            }
+        }
+    }
+
+    fn push_analysis_sample(&mut self, sample: f32, out: &mut Vec<SubFingerprint>) {
+        self.buffer[self.write] = sample;
+        self.write = (self.write + 1) % FRAME_LEN;
+        self.filled = (self.filled + 1).min(FRAME_LEN);
+        self.since_hop += 1;
+        if self.filled == FRAME_LEN && self.since_hop >= HOP_LEN {
+            self.since_hop = 0;
+            if let Some(sub) = self.analyse() {
+                out.push(sub);
+            }
+        }
+    }
+
+    fn analyse(&mut self) -> Option<SubFingerprint> {
+        let mut power = 0.0;
+        let (newer, older) = self.buffer.split_at(self.write);
+        for (i, &sample) in older.iter().chain(newer).enumerate() {
+            power += sample * sample;
+            self.re[i] = sample * self.window[i];
+            self.im[i] = 0.0;
+        }
+        let silent = power / (FRAME_LEN as f32) < self.silence_power;
+
+        self.fft.process(&mut self.re, &mut self.im);
+        let mut energies = [0.0f32; BITS + 1];
+        for (energy, &(start, end)) in energies.iter_mut().zip(&self.bands) {
+            *energy = (start..end).map(|k| self.re[k] * self.re[k] + self.im[k] * self.im[k]).sum();
+        }
+        let mut diffs = [0.0f32; BITS];
+        for (m, diff) in diffs.iter_mut().enumerate() {
+            *diff = energies[m] - energies[m + 1];
+        }
+
+        // The first frame has nothing to compare against and only seeds the history.
+        let previous = self.previous.replace(diffs)?;
+        let value = diffs
+            .iter()
+            .zip(&previous)
+            .enumerate()
+            .fold(0u32, |acc, (m, (now, before))| if now > before { acc | (1 << m) } else { acc });
+        Some(SubFingerprint { value, silent })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic test signal: two modulated tones picked from `seed` plus LCG noise.
+    fn test_signal(sample_rate: u32, seconds: f32, seed: u32) -> Vec<f32> {
+        let low = 300.0 + (seed % 7) as f32 * 60.0;
+        let high = 900.0 + (seed % 11) as f32 * 90.0;
+        let mut state = seed;
+        let count = (sample_rate as f32 * seconds) as usize;
+        (0..count)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                let envelope = 0.6 + 0.4 * (2.0 * PI * low / 300.0 * t).sin();
+                let tones = (2.0 * PI * low * t).sin() * (2.0 * PI * 0.7 * t).cos()
+                    + 0.5 * (2.0 * PI * high * t).sin() * (2.0 * PI * 0.3 * t).sin();
+                0.3 * envelope * (tones + noise)
+            })
+            .collect()
+    }
+
+    fn fingerprint(samples: &[f32], sample_rate: u32) -> Vec<SubFingerprint> {
+        let mut fp = Fingerprinter::new(sample_rate, -60.0);
+        let mut out = Vec::new();
+        // Feed in odd-sized chunks to exercise the streaming path.
+        for chunk in samples.chunks(997) {
+            fp.push(chunk, 1, &mut out);
+        }
+        out
+    }
+
+    fn bit_error_rate(a: &[SubFingerprint], b: &[SubFingerprint]) -> f32 {
+        let errors: u32 = a.iter().zip(b).map(|(x, y)| (x.value ^ y.value).count_ones()).sum();
+        errors as f32 / (a.len().min(b.len()) * BITS) as f32
+    }
+
+    #[test]
+    fn test_frame_rate_is_independent_of_sample_rate() {
+        let at_48k = fingerprint(&test_signal(48000, 2.0, 1), 48000);
+        let at_16k = fingerprint(&test_signal(16000, 2.0, 1), 16000);
+        let expected = (2 * ANALYSIS_RATE as usize - FRAME_LEN) / HOP_LEN;
+        assert!(at_48k.len().abs_diff(expected) <= 1, "{} vs {expected}", at_48k.len());
+        assert!(at_16k.len().abs_diff(expected) <= 1, "{} vs {expected}", at_16k.len());
+    }
+
+    #[test]
+    fn test_robust_to_gain_and_noise() {
+        let clean = test_signal(48000, 3.0, 7);
+        let mut state = 99u32;
+        let degraded: Vec<f32> = clean
+            .iter()
+            .map(|s| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let noise = (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                0.5 * s + 0.01 * noise
+            })
+            .collect();
+        let other = test_signal(48000, 3.0, 1234);
+
+        let reference = fingerprint(&clean, 48000);
+        assert!(bit_error_rate(&reference, &fingerprint(&degraded, 48000)) < 0.2);
+        assert!(bit_error_rate(&reference, &fingerprint(&other, 48000)) > 0.35);
+    }
+
+    #[test]
+    fn test_silence_is_flagged() {
+        let subs = fingerprint(&vec![0.0; 48000], 48000);
+        assert!(!subs.is_empty());
+        assert!(subs.iter().all(|s| s.silent));
+    }
+
+    #[test]
+    fn test_detects_reference_in_noisy_stream() {
+        use crate::matcher::{Matcher, MatcherSettings, Reference, Source};
+
+        let jingle = test_signal(48000, 5.0, 42);
+        let fingerprints = fingerprint(&jingle, 48000).iter().map(|s| s.value).collect();
+
+        // The jingle starts off the analysis hop grid, quieter and with added noise.
+        let mut stream = test_signal(48000, 7.3, 3);
+        let mut state = 5u32;
+        stream.extend(jingle.iter().map(|s| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            0.7 * s + 0.02 * ((state >> 8) as f32 / (1u32 << 24) as f32 - 0.5)
+        }));
+        stream.extend(test_signal(48000, 4.0, 9));
+
+        let mut matcher = Matcher::new(
+            vec![Reference { id: "jingle".into(), fingerprints }],
+            MatcherSettings {
+                block_frames: ms_to_frames(3000),
+                max_bit_error_rate: 0.35,
+                loop_history_frames: 0,
+            },
+        );
+        let matches: Vec<_> =
+            fingerprint(&stream, 48000).into_iter().flat_map(|s| matcher.push(s)).collect();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].source, Source::Reference(0));
+        let start_ms = frames_to_ms(matches[0].stream_frame - matches[0].source_frame);
+        assert!(start_ms.abs_diff(7300) <= 20, "jingle found at {start_ms} ms");
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Main fingerprint node implementation
+
+use crate::config::FingerprintConfig;
+use crate::fingerprint::{frames_to_ms, ms_to_frames, Fingerprinter};
+use crate::matcher::{Match, Matcher, MatcherSettings, Reference, Source};
+use std::sync::Arc;
+use streamkit_plugin_sdk_native::prelude::*;
+use streamkit_plugin_sdk_native::streamkit_core::types::{
+    AudioFormat, CustomEncoding, CustomPacketData, PacketMetadata, SampleFormat,
+};
+
+const MATCH_TYPE_ID: &str = "plugin::native::fingerprint/match@1";
+const FINGERPRINTS_TYPE_ID: &str = "plugin::native::fingerprint/fingerprints@1";
+
+/// Sub-fingerprints per emitted fingerprint packet (~1 second of audio)
+const FINGERPRINT_CHUNK: usize = 86;
+
+/// Fingerprint plugin node
+pub struct FingerprintNode {
+    config: FingerprintConfig,
+    /// Created on the first audio frame, recreated when the input sample rate changes
+    fingerprinter: Option<(u32, Fingerprinter)>,
+    matcher: Matcher,
+    /// Sub-fingerprints not yet emitted when `emit_fingerprints` is on
+    unsent: Vec<u32>,
+    logger: Logger,
+}
+
+impl FingerprintNode {
+    fn build_matcher(config: &FingerprintConfig, logger: &Logger) -> Result<Matcher, String> {
+        let references = config
+            .references
+            .iter()
+            .map(|r| Ok(Reference { id: r.id.clone(), fingerprints: r.load()? }))
+            .collect::<Result<Vec<_>, String>>()?;
+        for reference in &references {
+            plugin_info!(
+                logger,
+                reference = %reference.id,
+                duration_ms = frames_to_ms(reference.fingerprints.len() as u64),
+                "Loaded fingerprint reference"
+            );
+        }
+
+        let loop_history_frames =
+            if config.detect_loops { ms_to_frames(config.loop_history_s * 1000) } else { 0 };
+        Ok(Matcher::new(
+            references,
+            MatcherSettings {
+                block_frames: ms_to_frames(config.block_ms),
+                max_bit_error_rate: config.max_bit_error_rate,
+                loop_history_frames,
+            },
+        ))
+    }
+
+    fn emit_match(&self, found: &Match, output: &OutputSender) -> Result<(), String> {
+        let stream_time_ms = frames_to_ms(found.stream_frame);
+        let source_time_ms = frames_to_ms(found.source_frame);
+        let mut data = serde_json::json!({
+            "stream_time_ms": stream_time_ms,
+            "duration_ms": frames_to_ms(found.frames as u64),
+            "bit_error_rate": found.bit_error_rate,
+        });
+        match found.source {
+            Source::Reference(index) => {
+                data["match_type"] = "reference".into();
+                data["reference_id"] = self.matcher.reference_id(index).into();
+                data["reference_time_ms"] = source_time_ms.into();
+            },
+            Source::History => {
+                data["match_type"] = "loop".into();
+                data["previous_time_ms"] = source_time_ms.into();
+            },
+        }
+
+        plugin_info!(self.logger, data = %data, "Fingerprint match");
+
+        // For linear pipelines (`steps:`), only the `out` pin is connected by default.
+        output.send("out", &Self::custom_packet(MATCH_TYPE_ID, data, stream_time_ms))
+    }
+
+    fn emit_fingerprints(&mut self, output: &OutputSender) -> Result<(), String> {
+        if self.unsent.is_empty() {
+            return Ok(());
+        }
+        let start_frame = self.matcher.next_frame() - self.unsent.len() as u64;
+        let start_ms = frames_to_ms(start_frame);
+        let data = serde_json::json!({
+            "start_ms": start_ms,
+            "fingerprints": std::mem::take(&mut self.unsent),
+        });
+        output.send("out", &Self::custom_packet(FINGERPRINTS_TYPE_ID, data, start_ms))
+    }
+
+    fn custom_packet(type_id: &str, data: serde_json::Value, timestamp_ms: u64) -> Packet {
+        Packet::Custom(Arc::new(CustomPacketData {
+            type_id: type_id.to_string(),
+            encoding: CustomEncoding::Json,
+            data,
+            metadata: Some(PacketMetadata {
+                timestamp_us: Some(timestamp_ms.saturating_mul(1000)),
+                duration_us: None,
+                sequence: None,
+            }),
+        }))
+    }
+
+    fn parse_config(params: Option<serde_json::Value>) -> Result<FingerprintConfig, String> {
+        let config: FingerprintConfig = if let Some(params) = params {
+            serde_json::from_value(params).map_err(|e| format!("Invalid configuration: {}", e))?
+        } else {
+            FingerprintConfig::default()
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl NativeProcessorNode for FingerprintNode {
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::builder("fingerprint")
+            .description(
+                "Computes noise-robust audio fingerprints and matches them against reference \
+                 recordings (ads, jingles, music) or earlier audio of the same stream (loops). \
+                 Emits a Custom match event per detected occurrence. Accepts any sample rate \
+                 and channel count.",
+            )
+            .input(
+                "in",
+                &[PacketType::RawAudio(AudioFormat {
+                    sample_rate: 0, // Wildcard
+                    channels: 0,
+                    sample_format: SampleFormat::F32,
+                })],
+            )
+            // `Custom` packets: match events (type_id: plugin::native::fingerprint/match@1)
+            // and, with `emit_fingerprints`, plugin::native::fingerprint/fingerprints@1.
+            .output("out", PacketType::Any)
+            .param_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "references": {
+                        "type": "array",
+                        "description": "Reference recordings to detect. Each file holds {\"fingerprints\": [...]} as emitted with emit_fingerprints, or a bare array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "id": { "type": "string", "description": "Identifier reported in match events" },
+                                "path": { "type": "string", "description": "Path to the JSON fingerprint file" }
+                            },
+                            "required": ["id", "path"]
+                        },
+                        "default": []
+                    },
+                    "max_bit_error_rate": {
+                        "type": "number",
+                        "minimum": 0.0,
+                        "maximum": 1.0,
+                        "description": "Maximum fraction of differing fingerprint bits for a match (lower = stricter)",
+                        "default": 0.35
+                    },
+                    "block_ms": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Length of audio compared per match decision (milliseconds)",
+                        "default": 3000
+                    },
+                    "detect_loops": {
+                        "type": "boolean",
+                        "description": "Also report audio repeating earlier audio of the same stream",
+                        "default": false
+                    },
+                    "loop_history_s": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "How far back loop detection looks (seconds)",
+                        "default": 120
+                    },
+                    "silence_threshold_db": {
+                        "type": "number",
+                        "description": "Frames quieter than this (dBFS) never start a match",
+                        "default": -60.0
+                    },
+                    "emit_fingerprints": {
+                        "type": "boolean",
+                        "description": "Emit computed fingerprints on 'out' (type_id: plugin::native::fingerprint/fingerprints@1), e.g. to record references",
+                        "default": false
+                    }
+                }
+            }))
+            .category("audio")
+            .category("analysis")
+            .build()
+    }
+
+    fn new(params: Option<serde_json::Value>, logger: Logger) -> Result<Self, String> {
+        plugin_info!(logger, "Initializing fingerprint plugin");
+
+        let config = Self::parse_config(params)?;
+        plugin_debug!(logger, config = ?config, "Parsed fingerprint configuration");
+        let matcher = Self::build_matcher(&config, &logger)?;
+
+        Ok(Self { config, fingerprinter: None, matcher, unsent: Vec::new(), logger })
+    }
+
+    fn process(&mut self, _pin: &str, packet: Packet, output: &OutputSender) -> Result<(), String> {
+        let Packet::Audio(frame) = packet else {
+            return Err("Fingerprint plugin only accepts audio packets".to_string());
+        };
+
+        let fingerprinter = match &mut self.fingerprinter {
+            Some((rate, fingerprinter)) if *rate == frame.sample_rate => fingerprinter,
+            slot => {
+                plugin_debug!(self.logger, sample_rate = frame.sample_rate, "Starting analysis");
+                let fingerprinter =
+                    Fingerprinter::new(frame.sample_rate, self.config.silence_threshold_db);
+                &mut slot.insert((frame.sample_rate, fingerprinter)).1
+            },
+        };
+        let mut subs = Vec::new();
+        fingerprinter.push(frame.samples(), usize::from(frame.channels), &mut subs);
+
+        for sub in subs {
+            for found in self.matcher.push(sub) {
+                self.emit_match(&found, output)?;
+            }
+            if self.config.emit_fingerprints {
+                self.unsent.push(sub.value);
+                if self.unsent.len() >= FINGERPRINT_CHUNK {
+                    self.emit_fingerprints(output)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update_params(&mut self, params: Option<serde_json::Value>) -> Result<(), String> {
+        let config = Self::parse_config(params)?;
+        plugin_info!(self.logger, "Updating fingerprint parameters; match state is reset");
+
+        // Rebuilding reloads the references; the stream clock restarts with the new matcher.
+        self.matcher = Self::build_matcher(&config, &self.logger)?;
+        self.fingerprinter = None;
+        self.unsent.clear();
+        self.config = config;
+        Ok(())
+    }
+
+    fn flush(&mut self, output: &OutputSender) -> Result<(), String> {
+        plugin_debug!(self.logger, "Flushing fingerprint plugin");
+        self.emit_fingerprints(output)
+    }
+
+    fn cleanup(&mut self) {
+        plugin_info!(self.logger, "Cleaning up fingerprint plugin");
+    }
+}
@@ -0,0 +1,22 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Audio fingerprinting plugin
+//!
+//! Computes compact, noise-robust fingerprints of the incoming audio and matches them against
+//! a configurable set of reference recordings, emitting Custom match events:
+//! - **Reference matches**: ad/jingle detection, music identification
+//! - **Loop matches**: audio repeating something heard earlier in the same stream
+//!
+//! The fingerprints themselves can also be emitted to record new references.
+
+mod config;
+mod fingerprint;
+mod fingerprint_node;
+mod matcher;
+
+use fingerprint_node::FingerprintNode;
+use streamkit_plugin_sdk_native::{native_plugin_entry, NativeProcessorNode};
+
+native_plugin_entry!(FingerprintNode);
@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Block matching of the live sub-fingerprint stream
+//!
+//! Every non-silent sub-fingerprint is looked up, along with its single-bit-flip neighbours,
+//! in an exact-value index of the references (and, with loop detection, of the stream's own
+//! recent history). Each hit proposes an alignment between the stream and the source; an alignment is confirmed once a whole block
+//! of consecutive sub-fingerprints compares below the bit error rate threshold.
+
+use crate::fingerprint::SubFingerprint;
+use std::collections::{HashMap, VecDeque};
+
+/// Index buckets larger than this are ignored; such values are too common to be informative.
+const MAX_BUCKET: usize = 64;
+
+/// A reference recording to detect.
+pub struct Reference {
+    pub id: String,
+    pub fingerprints: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// Index into the reference list
+    Reference(usize),
+    /// Earlier audio of the same stream
+    History,
+}
+
+/// A confirmed match of one block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub source: Source,
+    /// Stream frame where the matched block starts
+    pub stream_frame: u64,
+    /// Frame in the source (reference position or earlier stream frame) where it starts
+    pub source_frame: u64,
+    /// Number of frames compared
+    pub frames: usize,
+    pub bit_error_rate: f32,
+}
+
+pub struct MatcherSettings {
+    pub block_frames: usize,
+    pub max_bit_error_rate: f32,
+    /// Stream history kept for loop detection, in frames; zero disables loop detection
+    pub loop_history_frames: usize,
+}
+
+pub struct Matcher {
+    references: Vec<Reference>,
+    index: HashMap<u32, Vec<(usize, usize)>>,
+    settings: MatcherSettings,
+    /// Recent stream frames; `stream[0]` is frame `stream_start`
+    stream: VecDeque<SubFingerprint>,
+    stream_start: u64,
+    history_index: HashMap<u32, VecDeque<u64>>,
+    /// Candidate alignments (source position minus stream position) and the frame at which
+    /// they are abandoned if still unconfirmed
+    pending: HashMap<(Source, i64), u64>,
+    /// Last frame at which each source matched, to report a contiguous match only once
+    last_match: HashMap<Source, u64>,
+}
+
+impl Matcher {
+    pub fn new(references: Vec<Reference>, settings: MatcherSettings) -> Self {
+        let mut index: HashMap<u32, Vec<(usize, usize)>> = HashMap::new();
+        for (r, reference) in references.iter().enumerate() {
+            for (pos, &value) in reference.fingerprints.iter().enumerate() {
+                index.entry(value).or_default().push((r, pos));
+            }
+        }
+        Self {
+            references,
+            index,
+            settings,
+            stream: VecDeque::new(),
+            stream_start: 0,
+            history_index: HashMap::new(),
+            pending: HashMap::new(),
+            last_match: HashMap::new(),
+        }
+    }
+
+    pub fn reference_id(&self, index: usize) -> &str {
+        &self.references[index].id
+    }
+
+    /// Number of frames the next pushed sub-fingerprint will be assigned.
+    pub fn next_frame(&self) -> u64 {
+        self.stream_start + self.stream.len() as u64
+    }
+
+    fn detect_loops(&self) -> bool {
+        self.settings.loop_history_frames > 0
+    }
+
+    /// Appends one sub-fingerprint and returns the matches it completes.
+    pub fn push(&mut self, sub: SubFingerprint) -> Vec<Match> {
+        let frame = self.next_frame();
+        let block = self.settings.block_frames;
+        self.stream.push_back(sub);
+        let keep = self.settings.loop_history_frames.max(block);
+        while self.stream.len() > keep {
+            let Some(evicted) = self.stream.pop_front() else { break };
+            if let Some(frames) = self.history_index.get_mut(&evicted.value) {
+                // Frames are indexed in order, so the evicted one is at the front if present.
+                if frames.front() == Some(&self.stream_start) {
+                    frames.pop_front();
+                }
+                if frames.is_empty() {
+                    self.history_index.remove(&evicted.value);
+                }
+            }
+            self.stream_start += 1;
+        }
+
+        if !sub.silent {
+            let expiry = frame + 2 * block as u64;
+            for probe in probes(sub.value) {
+                if let Some(hits) = self.index.get(&probe).filter(|h| h.len() <= MAX_BUCKET) {
+                    for &(r, pos) in hits {
+                        let offset = pos as i64 - frame as i64;
+                        self.pending.entry((Source::Reference(r), offset)).or_insert(expiry);
+                    }
+                }
+                if !self.detect_loops() {
+                    continue;
+                }
+                let Some(earlier) = self.history_index.get(&probe) else { continue };
+                if earlier.len() <= MAX_BUCKET {
+                    for &f in earlier.iter().filter(|&&f| frame - f >= block as u64) {
+                        let offset = f as i64 - frame as i64;
+                        self.pending.entry((Source::History, offset)).or_insert(expiry);
+                    }
+                }
+            }
+            if self.detect_loops() {
+                self.history_index.entry(sub.value).or_default().push_back(frame);
+            }
+        }
+
+        let mut matches = Vec::new();
+        for ((source, offset), expiry) in std::mem::take(&mut self.pending) {
+            match self.check(source, offset, frame) {
+                Check::Matched(found) => {
+                    let previous = self.last_match.insert(source, frame);
+                    if previous.is_none_or(|p| frame - p > block as u64) {
+                        matches.push(found);
+                    }
+                },
+                Check::Wait if frame < expiry => {
+                    self.pending.insert((source, offset), expiry);
+                },
+                Check::Wait | Check::Impossible => {},
+            }
+        }
+        matches
+    }
+
+    fn source_value(&self, source: Source, frame: u64) -> u32 {
+        match source {
+            Source::Reference(r) => self.references[r].fingerprints[frame as usize],
+            Source::History => self.stream[(frame - self.stream_start) as usize].value,
+        }
+    }
+
+    /// Compares the block ending at `frame` with the source block at `frame + offset`.
+    fn check(&self, source: Source, offset: i64, frame: u64) -> Check {
+        let source_end = frame as i64 + offset;
+        let len = match source {
+            Source::Reference(r) => {
+                let reference_len = self.references[r].fingerprints.len();
+                if source_end < 0 || source_end as usize >= reference_len {
+                    return Check::Impossible;
+                }
+                self.settings.block_frames.min(reference_len)
+            },
+            Source::History => self.settings.block_frames,
+        };
+
+        let stream_first = (frame + 1).checked_sub(len as u64);
+        let source_first = (source_end + 1).checked_sub(len as i64).filter(|&f| f >= 0);
+        let (Some(stream_first), Some(source_first)) = (stream_first, source_first) else {
+            return Check::Wait;
+        };
+        let source_first = source_first as u64;
+        if stream_first < self.stream_start
+            || (source == Source::History && source_first < self.stream_start)
+        {
+            return Check::Impossible;
+        }
+
+        let mut errors = 0u32;
+        let mut audible = 0usize;
+        for i in 0..len as u64 {
+            let sub = self.stream[(stream_first + i - self.stream_start) as usize];
+            audible += usize::from(!sub.silent);
+            errors += (sub.value ^ self.source_value(source, source_first + i)).count_ones();
+        }
+        // Blocks that are mostly silence compare equal to any other silence.
+        if audible * 2 < len {
+            return Check::Wait;
+        }
+        let bit_error_rate = errors as f32 / (len * 32) as f32;
+        if bit_error_rate > self.settings.max_bit_error_rate {
+            return Check::Wait;
+        }
+        Check::Matched(Match {
+            source,
+            stream_frame: stream_first,
+            source_frame: source_first,
+            frames: len,
+            bit_error_rate,
+        })
+    }
+}
+
+/// The value itself followed by every value one bit away from it. Exact hits are rare on
+/// degraded audio, but a single near-exact hit is enough to propose the right alignment.
+fn probes(value: u32) -> impl Iterator<Item = u32> {
+    std::iter::once(value).chain((0..32).map(move |bit| value ^ (1 << bit)))
+}
+
+enum Check {
+    Matched(Match),
+    /// Not (yet) a match; keep the candidate until it expires
+    Wait,
+    /// The alignment falls outside the source or the retained history
+    Impossible,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(seed: u32, len: usize) -> Vec<u32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state
+            })
+            .collect()
+    }
+
+    fn audible(value: u32) -> SubFingerprint {
+        SubFingerprint { value, silent: false }
+    }
+
+    fn settings(loop_history_frames: usize) -> MatcherSettings {
+        MatcherSettings { block_frames: 32, max_bit_error_rate: 0.35, loop_history_frames }
+    }
+
+    #[test]
+    fn test_reference_matched_once_with_bit_errors() {
+        let jingle = noise(1, 100);
+        let reference = Reference { id: "jingle".into(), fingerprints: jingle.clone() };
+        let mut matcher = Matcher::new(vec![reference], settings(0));
+
+        // Unrelated audio, then the jingle with a few flipped bits in every third frame.
+        let mut stream = noise(2, 50);
+        stream.extend(
+            jingle.iter().enumerate().map(|(i, v)| if i % 3 == 0 { v ^ 0b1011 } else { *v }),
+        );
+        stream.extend(noise(3, 50));
+
+        let matches: Vec<Match> =
+            stream.into_iter().flat_map(|v| matcher.push(audible(v))).collect();
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].source, Source::Reference(0));
+        assert_eq!(matches[0].stream_frame, 50);
+        assert_eq!(matches[0].source_frame, 0);
+        assert!((matches[0].bit_error_rate - 33.0 / (32.0 * 32.0)).abs() < 1e-6);
+        assert_eq!(matcher.reference_id(0), "jingle");
+    }
+
+    #[test]
+    fn test_reference_matched_when_stream_joins_midway() {
+        let jingle = noise(4, 100);
+        let reference = Reference { id: "jingle".into(), fingerprints: jingle.clone() };
+        let mut matcher = Matcher::new(vec![reference], settings(0));
+
+        let matches: Vec<Match> =
+            jingle[40..].iter().flat_map(|&v| matcher.push(audible(v))).collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].stream_frame, matches[0].source_frame), (0, 40));
+    }
+
+    #[test]
+    fn test_loop_detected_in_history() {
+        let segment = noise(5, 40);
+        let mut stream = segment.clone();
+        stream.extend(noise(6, 60));
+        stream.extend(&segment);
+
+        let mut with_loops = Matcher::new(Vec::new(), settings(200));
+        let matches: Vec<Match> =
+            stream.iter().flat_map(|&v| with_loops.push(audible(v))).collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].source, Source::History);
+        assert_eq!((matches[0].stream_frame, matches[0].source_frame), (100, 0));
+
+        let mut without = Matcher::new(Vec::new(), settings(0));
+        assert!(stream.iter().all(|&v| without.push(audible(v)).is_empty()));
+    }
+
+    #[test]
+    fn test_silence_never_matches() {
+        let silence = vec![0u32; 100];
+        let reference = Reference { id: "quiet".into(), fingerprints: silence.clone() };
+        let mut matcher = Matcher::new(vec![reference], settings(200));
+        assert!(silence
+            .iter()
+            .all(|&value| matcher.push(SubFingerprint { value, silent: true }).is_empty()));
+    }
+}
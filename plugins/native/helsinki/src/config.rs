@@ -39,6 +39,18 @@ pub struct HelsinkiConfig {
     /// first-request latency spikes (e.g. CUDA kernel initialization).
     #[serde(default)]
     pub warmup: bool,
+
+    /// Sampling temperature; 0 decodes greedily (the default, fully deterministic)
+    #[serde(default)]
+    pub temperature: f64,
+
+    /// Nucleus sampling threshold, only used when `temperature` is above 0
+    #[serde(default)]
+    pub top_p: Option<f64>,
+
+    /// Seed for sampling, filled in by the host when unset
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 fn default_model_dir() -> String {
@@ -71,6 +83,9 @@ impl Default for HelsinkiConfig {
             device_index: 0,
             max_length: default_max_length(),
             warmup: false,
+            temperature: 0.0,
+            top_p: None,
+            seed: None,
         }
     }
 }
@@ -122,6 +137,18 @@ impl HelsinkiConfig {
             ));
         }
 
+        if !self.temperature.is_finite() || self.temperature < 0.0 {
+            return Err(format!(
+                "temperature must be at least 0, got {}",
+                self.temperature
+            ));
+        }
+        if let Some(top_p) = self.top_p {
+            if !(top_p > 0.0 && top_p <= 1.0) {
+                return Err(format!("top_p must be in (0, 1], got {}", top_p));
+            }
+        }
+
         Ok(())
     }
 
@@ -192,4 +219,21 @@ mod tests {
         };
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_validate_sampling() {
+        let config = HelsinkiConfig {
+            temperature: 0.7,
+            top_p: Some(0.9),
+            seed: Some(42),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let config = HelsinkiConfig {
+            top_p: Some(1.5),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
 }
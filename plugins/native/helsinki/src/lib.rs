@@ -141,7 +141,20 @@ impl NativeProcessorNode for HelsinkiPlugin {
                         "type": "boolean",
                         "description": "If true, run a small warmup translation during initialization to reduce first-request latency",
                         "default": false
-                    }
+                    },
+                    "temperature": {
+                        "type": "number",
+                        "description": "Sampling temperature; 0 decodes greedily",
+                        "default": 0.0,
+                        "minimum": 0.0
+                    },
+                    "top_p": {
+                        "type": ["number", "null"],
+                        "description": "Nucleus sampling threshold (only used when temperature is above 0)",
+                        "exclusiveMinimum": 0.0,
+                        "maximum": 1.0
+                    },
+                    "seed": streamkit_plugin_sdk_native::seed::schema_property()
                 }
             }))
             .category("ml")
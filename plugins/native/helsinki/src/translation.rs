@@ -6,11 +6,22 @@
 
 use std::sync::{Arc, Mutex};
 
-use candle_core::{IndexOp, Tensor, D};
+use candle_core::{IndexOp, Tensor};
+use candle_transformers::generation::LogitsProcessor;
 
 use crate::config::HelsinkiConfig;
 use crate::model::CachedTranslator;
 
+/// Builds the token sampler for one input.
+///
+/// Without a temperature the processor takes the argmax, so decoding stays greedy. Each input
+/// is sampled from a fresh RNG seeded with the node's `seed`, so the same text and seed give
+/// the same output.
+fn sampler(config: &HelsinkiConfig) -> LogitsProcessor {
+    let temperature = (config.temperature > 0.0).then_some(config.temperature);
+    LogitsProcessor::new(config.seed.unwrap_or(0), temperature, config.top_p)
+}
+
 /// Translate text using the cached translator.
 pub fn translate(
     translator: &Arc<Mutex<CachedTranslator>>,
@@ -60,6 +71,8 @@ pub fn translate(
 
     let mut decoder_input = vec![decoder_start_token_id];
 
+    let mut sampler = sampler(config);
+
     for step in 0..config.max_length {
         // When `use_cache` is enabled (default for Marian), Candle's attention layers maintain a KV
         // cache. To avoid duplicating cached keys/values (and shape mismatches in the causal mask),
@@ -84,14 +97,14 @@ pub fn translate(
             .i((.., seq_len - 1, ..))
             .map_err(|e| format!("Failed to slice logits: {}", e))?;
 
-        // Greedy sampling: take argmax
         let next_token = last_logits
-            .argmax(D::Minus1)
-            .map_err(|e| format!("Argmax failed: {}", e))?
             .squeeze(0)
-            .map_err(|e| format!("Squeeze failed: {}", e))?
-            .to_scalar::<u32>()
-            .map_err(|e| format!("to_scalar failed: {}", e))?;
+            .map_err(|e| format!("Squeeze failed: {}", e))
+            .and_then(|logits| {
+                sampler
+                    .sample(&logits)
+                    .map_err(|e| format!("Sampling failed: {}", e))
+            })?;
 
         // Check for EOS
         if next_token == eos_token_id || next_token == pad_token_id {
@@ -123,9 +136,11 @@ mod tests {
 
     use streamkit_plugin_sdk_native::prelude::{CLogCallback, CLogLevel, Logger};
 
+    use candle_core::{Device, Tensor};
+
     use crate::config::HelsinkiConfig;
     use crate::model::get_or_load_translator;
-    use crate::translation::translate;
+    use crate::translation::{sampler, translate};
 
     extern "C" fn test_log_callback(
         _level: CLogLevel,
@@ -135,6 +150,56 @@ mod tests {
     ) {
     }
 
+    /// Draws 32 tokens from a fixed, fairly flat distribution over 16 tokens.
+    fn draw(config: &HelsinkiConfig) -> Vec<u32> {
+        let logits: Vec<f32> = (0..16u8).map(|i| f32::from(i) * 0.1).collect();
+        let logits = Tensor::new(logits.as_slice(), &Device::Cpu).unwrap();
+        let mut sampler = sampler(config);
+        (0..32).map(|_| sampler.sample(&logits).unwrap()).collect()
+    }
+
+    #[test]
+    fn sampling_is_reproducible_with_seed() {
+        let config = HelsinkiConfig {
+            temperature: 1.0,
+            top_p: Some(0.95),
+            seed: Some(42),
+            ..Default::default()
+        };
+        assert_eq!(draw(&config), draw(&config));
+
+        let other = HelsinkiConfig {
+            seed: Some(43),
+            ..config.clone()
+        };
+        assert_ne!(draw(&config), draw(&other));
+
+        // Greedy decoding ignores the seed
+        let greedy = HelsinkiConfig::default();
+        assert!(draw(&greedy).iter().all(|&token| token == 15));
+    }
+
+    #[test]
+    #[ignore = "requires local model files in ./models (run `just download-helsinki-models`)"]
+    fn translate_sampled_is_reproducible_with_seed() {
+        let repo_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../..");
+        let model_dir = repo_root.join("models/opus-mt-en-es");
+        let logger = Logger::new(test_log_callback as CLogCallback, ptr::null_mut(), "helsinki");
+
+        let mut config = HelsinkiConfig::default();
+        config.model_dir = model_dir.to_string_lossy().to_string();
+        config.max_length = 64;
+        config.temperature = 1.2;
+        config.seed = Some(7);
+        config.validate().unwrap();
+
+        let translator = get_or_load_translator(&config, &logger).unwrap();
+        let text = "The weather is lovely today, so we are going for a walk in the park.";
+        let first = translate(&translator, text, &config).unwrap();
+        let second = translate(&translator, text, &config).unwrap();
+        assert_eq!(first, second);
+    }
+
     #[test]
     #[ignore = "requires local model files in ./models (run `just download-helsinki-models`)"]
     fn translate_smoke_en_es() {
@@ -462,9 +462,15 @@ impl SenseVoiceNode {
         }
 
         // Decode
+        let decode_started = std::time::Instant::now();
         unsafe {
             ffi::SherpaOnnxDecodeOfflineStream(self.recognizer.get(), stream);
         }
+        if self.config.execution_provider != "cpu" {
+            if let Err(e) = output.report_gpu_time(decode_started.elapsed()) {
+                plugin_warn!(self.logger, "Failed to report GPU time: {}", e);
+            }
+        }
 
         // Get result
         let result_ptr = unsafe { ffi::SherpaOnnxGetOfflineStreamResult(stream) };
@@ -0,0 +1,600 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::connection::{self, Command, ConnectionTask};
+use crate::error::{Error, Result};
+use crate::options::{ClientOptions, ConnectionState};
+use futures_util::Stream;
+use streamkit_api::{
+    ApiPipeline, BatchOperation, ConnectionMode, CostReport, Event, NodeControlMessage,
+    NodeDefinition, PermissionsInfo, RequestPayload, ResponsePayload, SessionFilter, SessionInfo,
+    SessionListQuery, SessionNode, UiMetadataTarget, ValidationError,
+};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use url::Url;
+
+/// Requests that can wait for a connection before callers feel backpressure.
+const COMMAND_QUEUE: usize = 256;
+
+/// A session returned by [`Client::create_session`].
+#[derive(Debug, Clone)]
+pub struct CreatedSession {
+    pub session_id: String,
+    pub name: Option<String>,
+    /// ISO 8601 formatted timestamp when the session was created
+    pub created_at: String,
+}
+
+/// One page of [`Client::list_sessions`] results.
+#[derive(Debug, Clone)]
+pub struct SessionPage {
+    pub sessions: Vec<SessionInfo>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Typed async client for the StreamKit control API.
+///
+/// All requests share one WebSocket, so a `Client` can be cloned freely and used from
+/// many tasks at once. The connection is re-established automatically when it drops
+/// (see [`ReconnectPolicy`](crate::ReconnectPolicy)); requests in flight at that moment
+/// fail with [`Error::Disconnected`] because the server may or may not have applied them.
+#[derive(Debug, Clone)]
+pub struct Client {
+    commands: mpsc::Sender<Command>,
+    events: broadcast::WeakSender<Event>,
+    state: watch::Receiver<ConnectionState>,
+    http_base: Url,
+    options: ClientOptions,
+}
+
+impl Client {
+    /// Connects with default [`ClientOptions`]. `server_url` may be the server's
+    /// `http(s)://` or `ws(s)://` address; the control path is filled in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the initial connection fails.
+    pub async fn connect(server_url: &str) -> Result<Self> {
+        Self::connect_with(server_url, ClientOptions::default()).await
+    }
+
+    /// Connects with the given options. Must be called within a Tokio runtime, which
+    /// also drives the connection's background task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the initial connection fails.
+    pub async fn connect_with(server_url: &str, options: ClientOptions) -> Result<Self> {
+        let ws_url = crate::url::control_ws_url(server_url)?;
+        let http_base = crate::url::http_base_url(server_url)?;
+        let (ws, format) = connection::connect(&ws_url, &options).await?;
+        tracing::debug!("Connected to {ws_url} using {format:?}");
+
+        let (commands_tx, commands_rx) = mpsc::channel(COMMAND_QUEUE);
+        let (events_tx, _) = broadcast::channel(options.event_buffer.max(1));
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+        let events = events_tx.downgrade();
+
+        let task = ConnectionTask {
+            url: ws_url,
+            options: options.clone(),
+            commands: commands_rx,
+            events: events_tx,
+            state: state_tx,
+        };
+        tokio::spawn(task.run(ws, format));
+
+        Ok(Self { commands: commands_tx, events, state: state_rx, http_base, options })
+    }
+
+    /// Current state of the control connection.
+    pub fn state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// A receiver that observes connection state changes, e.g. to resynchronise after
+    /// a reconnect (events emitted while disconnected are not replayed).
+    pub fn state_changes(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+
+    /// Base URL of the server's HTTP API, for endpoints not covered by this client
+    /// (oneshot processing, plugin upload, samples).
+    pub const fn http_base_url(&self) -> &Url {
+        &self.http_base
+    }
+
+    /// Subscribes to server events. Each call returns an independent stream that sees
+    /// events from the moment of subscription. A subscriber that falls more than
+    /// `event_buffer` events behind skips the oldest ones. The stream ends once the
+    /// client is closed.
+    pub fn events(&self) -> impl Stream<Item = Event> + Send + Unpin + 'static {
+        let receiver = self.events.upgrade().map(|sender| sender.subscribe());
+        Box::pin(futures_util::stream::unfold(receiver, |receiver| async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, Some(receiver))),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Event subscriber lagged, skipped {skipped} events");
+                    },
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Sends a request and waits for its response. Error responses are returned as
+    /// [`Error::Server`], read-only rejections as [`Error::ReadOnly`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server rejects the request, the connection drops before
+    /// the response arrives, or no response arrives within the request timeout.
+    pub async fn request(&self, payload: RequestPayload) -> Result<ResponsePayload> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let command = Command {
+            correlation_id: uuid::Uuid::new_v4().to_string(),
+            payload,
+            reply: Some(reply_tx),
+        };
+        let timeout = self.options.request_timeout;
+        tokio::time::timeout(timeout, async {
+            self.commands.send(command).await.map_err(|_| Error::Closed)?;
+            reply_rx.await.map_err(|_| Error::Closed)?
+        })
+        .await
+        .map_err(|_| Error::Timeout)?
+    }
+
+    /// Queues a request without waiting for a response, for requests the server doesn't
+    /// answer (e.g. `TuneNodeAsync`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Closed`] if the client is closed.
+    pub async fn send(&self, payload: RequestPayload) -> Result<()> {
+        let command =
+            Command { correlation_id: uuid::Uuid::new_v4().to_string(), payload, reply: None };
+        self.commands.send(command).await.map_err(|_| Error::Closed)
+    }
+
+    /// Flushes queued requests and closes the connection. The connection stays open while
+    /// other clones of this client are alive; this waits until they are gone too.
+    pub async fn close(self) {
+        let Self { commands, mut state, .. } = self;
+        drop(commands);
+        // An error means the connection task already exited.
+        let _ = state.wait_for(|s| *s == ConnectionState::Closed).await;
+    }
+
+    /// Creates a dynamic session.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn create_session(
+        &self,
+        name: Option<&str>,
+        tags: Vec<String>,
+    ) -> Result<CreatedSession> {
+        let tags = (!tags.is_empty()).then_some(tags);
+        match self
+            .request(RequestPayload::CreateSession {
+                name: name.map(str::to_string),
+                tags,
+                record: None,
+            })
+            .await?
+        {
+            ResponsePayload::SessionCreated { session_id, name, created_at } => {
+                Ok(CreatedSession { session_id, name, created_at })
+            },
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Destroys a session and all of its nodes.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn destroy_session(&self, session_id: &str) -> Result<()> {
+        match self
+            .request(RequestPayload::DestroySession { session_id: session_id.to_string() })
+            .await?
+        {
+            ResponsePayload::SessionDestroyed { .. } | ResponsePayload::Success => Ok(()),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Destroys every session matching `filter` (admin only) and returns their IDs. With
+    /// `dry_run` nothing is destroyed and the matching IDs are returned.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn destroy_sessions(
+        &self,
+        filter: SessionFilter,
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        match self.request(RequestPayload::DestroySessions { filter, dry_run }).await? {
+            ResponsePayload::SessionsDestroyed { session_ids, .. } => Ok(session_ids),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Lists sessions visible to this client's role. Without a query every session is
+    /// returned in one page.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn list_sessions(&self, query: Option<SessionListQuery>) -> Result<SessionPage> {
+        match self.request(RequestPayload::ListSessions { query }).await? {
+            ResponsePayload::SessionsListed { sessions, next_cursor } => {
+                Ok(SessionPage { sessions, next_cursor })
+            },
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Lists the node kinds available to this client's role, with their schemas.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn list_node_kinds(&self) -> Result<Vec<NodeDefinition>> {
+        match self.request(RequestPayload::ListNodes).await? {
+            ResponsePayload::NodesListed { nodes } => Ok(nodes),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Fetches a session's current pipeline, including node states.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn get_pipeline(&self, session_id: &str) -> Result<ApiPipeline> {
+        match self
+            .request(RequestPayload::GetPipeline { session_id: session_id.to_string() })
+            .await?
+        {
+            ResponsePayload::Pipeline { pipeline } => Ok(pipeline),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Fetches the resource usage a session has accumulated so far.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn session_cost(&self, session_id: &str) -> Result<CostReport> {
+        match self
+            .request(RequestPayload::GetSessionCost { session_id: session_id.to_string() })
+            .await?
+        {
+            ResponsePayload::SessionCost { report, .. } => Ok(report),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Adds a node to a session's pipeline.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn add_node(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        kind: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<()> {
+        self.expect_success(RequestPayload::AddNode {
+            session_id: session_id.to_string(),
+            node_id: node_id.to_string(),
+            kind: kind.to_string(),
+            params,
+            label: None,
+            notes: None,
+        })
+        .await
+    }
+
+    /// Removes a node (and its connections) from a session's pipeline.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn remove_node(&self, session_id: &str, node_id: &str) -> Result<()> {
+        self.expect_success(RequestPayload::RemoveNode {
+            session_id: session_id.to_string(),
+            node_id: node_id.to_string(),
+        })
+        .await
+    }
+
+    /// Connects `from_node.from_pin` to `to_node.to_pin`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn connect_nodes(
+        &self,
+        session_id: &str,
+        (from_node, from_pin): (&str, &str),
+        (to_node, to_pin): (&str, &str),
+        mode: ConnectionMode,
+    ) -> Result<()> {
+        self.expect_success(RequestPayload::Connect {
+            session_id: session_id.to_string(),
+            from_node: from_node.to_string(),
+            from_pin: from_pin.to_string(),
+            to_node: to_node.to_string(),
+            to_pin: to_pin.to_string(),
+            mode,
+        })
+        .await
+    }
+
+    /// Removes the connection from `from_node.from_pin` to `to_node.to_pin`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn disconnect_nodes(
+        &self,
+        session_id: &str,
+        (from_node, from_pin): (&str, &str),
+        (to_node, to_pin): (&str, &str),
+    ) -> Result<()> {
+        self.expect_success(RequestPayload::Disconnect {
+            session_id: session_id.to_string(),
+            from_node: from_node.to_string(),
+            from_pin: from_pin.to_string(),
+            to_node: to_node.to_string(),
+            to_pin: to_pin.to_string(),
+        })
+        .await
+    }
+
+    /// Creates an input pin on a node with dynamic pins (e.g. `audio::mixer`) and returns
+    /// its name. Without `pin_name` the node picks the next free name.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn add_pin(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        pin_name: Option<&str>,
+    ) -> Result<String> {
+        match self
+            .request(RequestPayload::AddPin {
+                session_id: session_id.to_string(),
+                node_id: node_id.to_string(),
+                pin_name: pin_name.map(str::to_string),
+            })
+            .await?
+        {
+            ResponsePayload::PinAdded { pin_name, .. } => Ok(pin_name),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Removes an input pin from a node with dynamic pins, along with its connections.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn remove_pin(&self, session_id: &str, node_id: &str, pin_name: &str) -> Result<()> {
+        self.expect_success(RequestPayload::RemovePin {
+            session_id: session_id.to_string(),
+            node_id: node_id.to_string(),
+            pin_name: pin_name.to_string(),
+        })
+        .await
+    }
+
+    /// Sends a control message to a node and waits until the server has accepted it.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn tune_node(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        message: NodeControlMessage,
+    ) -> Result<()> {
+        self.expect_success(RequestPayload::TuneNode {
+            session_id: session_id.to_string(),
+            node_id: node_id.to_string(),
+            message,
+        })
+        .await
+    }
+
+    /// Sends a control message to a node without waiting, for high-frequency updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Closed`] if the client is closed.
+    pub async fn tune_node_async(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        message: NodeControlMessage,
+    ) -> Result<()> {
+        self.send(RequestPayload::TuneNodeAsync {
+            session_id: session_id.to_string(),
+            node_id: node_id.to_string(),
+            message,
+        })
+        .await
+    }
+
+    /// Asks a running node for runtime information (`kind` is node-specific, e.g.
+    /// `"stats"`).
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn query_node(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        kind: &str,
+        args: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        match self
+            .request(RequestPayload::QueryNode {
+                session_id: session_id.to_string(),
+                node_id: node_id.to_string(),
+                kind: kind.to_string(),
+                args,
+            })
+            .await?
+        {
+            ResponsePayload::QueryResult { result, .. } => Ok(result),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Applies `params` to every node of `kind` in the sessions matching `filter` (admin
+    /// only) and returns the nodes tuned. With `dry_run` the matching nodes are returned
+    /// without being tuned.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn tune_nodes_by_kind(
+        &self,
+        kind: &str,
+        params: serde_json::Value,
+        filter: SessionFilter,
+        dry_run: bool,
+    ) -> Result<Vec<SessionNode>> {
+        match self
+            .request(RequestPayload::TuneNodesByKind {
+                kind: kind.to_string(),
+                params,
+                filter,
+                dry_run,
+            })
+            .await?
+        {
+            ResponsePayload::NodesTuned { nodes, .. } => Ok(nodes),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Replaces the `ui_metadata` stored on a node or connection (e.g. editor layout);
+    /// `None` clears it.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn set_ui_metadata(
+        &self,
+        session_id: &str,
+        target: UiMetadataTarget,
+        ui_metadata: Option<serde_json::Value>,
+    ) -> Result<()> {
+        self.expect_success(RequestPayload::SetUiMetadata {
+            session_id: session_id.to_string(),
+            target,
+            ui_metadata,
+        })
+        .await
+    }
+
+    /// Checks a batch of operations against a session without applying it. Returns the
+    /// problems found; an empty list means the batch would apply.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn validate_batch(
+        &self,
+        session_id: &str,
+        operations: Vec<BatchOperation>,
+    ) -> Result<Vec<ValidationError>> {
+        match self
+            .request(RequestPayload::ValidateBatch {
+                session_id: session_id.to_string(),
+                operations,
+            })
+            .await?
+        {
+            ResponsePayload::ValidationResult { errors } => Ok(errors),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Applies a batch of operations atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Server`] listing the failures if the batch was rejected; see also
+    /// [`Client::request`].
+    pub async fn apply_batch(
+        &self,
+        session_id: &str,
+        operations: Vec<BatchOperation>,
+    ) -> Result<()> {
+        match self
+            .request(RequestPayload::ApplyBatch { session_id: session_id.to_string(), operations })
+            .await?
+        {
+            ResponsePayload::BatchApplied { success: true, .. } => Ok(()),
+            ResponsePayload::BatchApplied { errors, .. } => Err(Error::Server(errors.join("; "))),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Returns this client's role name and what it is allowed to do.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn permissions(&self) -> Result<(String, PermissionsInfo)> {
+        match self.request(RequestPayload::GetPermissions).await? {
+            ResponsePayload::Permissions { role, permissions } => Ok((role, permissions)),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Enters or leaves read-only mode (admin only). While read-only, mutating requests
+    /// fail with [`Error::ReadOnly`] carrying `message` as the banner.
+    ///
+    /// # Errors
+    ///
+    /// See [`Client::request`].
+    pub async fn set_read_only(&self, enabled: bool, message: Option<&str>) -> Result<()> {
+        self.expect_success(RequestPayload::SetReadOnly {
+            enabled,
+            message: message.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn expect_success(&self, payload: RequestPayload) -> Result<()> {
+        match self.request(payload).await? {
+            ResponsePayload::Success => Ok(()),
+            other => Err(unexpected(&other)),
+        }
+    }
+}
+
+fn unexpected(payload: &ResponsePayload) -> Error {
+    Error::UnexpectedResponse(format!("{payload:?}"))
+}
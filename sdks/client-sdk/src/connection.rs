@@ -0,0 +1,328 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Background task that owns the control WebSocket.
+//!
+//! The task multiplexes every request of a [`Client`](crate::Client) over one connection,
+//! routes responses back to their callers by correlation ID, fans events out to
+//! subscribers, and re-establishes the connection with exponential backoff when it drops.
+
+use crate::error::{Error, Result};
+use crate::options::{ClientOptions, ConnectionState, TlsOptions};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use streamkit_api::{
+    Event, MessageType, Request, RequestPayload, Response, ResponsePayload, WireFormat,
+};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Where the task delivers the outcome of a request.
+pub type ReplySender = oneshot::Sender<Result<ResponsePayload>>;
+
+/// A request queued for sending. Fire-and-forget requests have no `reply`.
+pub struct Command {
+    pub correlation_id: String,
+    pub payload: RequestPayload,
+    pub reply: Option<ReplySender>,
+}
+
+impl Command {
+    /// The caller stopped waiting (timed out or was cancelled); sending is pointless.
+    fn is_abandoned(&self) -> bool {
+        self.reply.as_ref().is_some_and(oneshot::Sender::is_closed)
+    }
+}
+
+/// Opens the control WebSocket, offering the configured wire format's subprotocol.
+///
+/// Returns the format the server accepted; servers that don't echo a subprotocol speak JSON.
+pub async fn connect(url: &Url, options: &ClientOptions) -> Result<(WsStream, WireFormat)> {
+    let mut request = url.as_str().into_client_request()?;
+    let headers = request.headers_mut();
+    for (name, value) in &options.headers {
+        let name = tokio_tungstenite::tungstenite::http::HeaderName::try_from(name.as_str())
+            .map_err(|e| Error::InvalidUrl(format!("Invalid header name '{name}': {e}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| Error::InvalidUrl(format!("Invalid value for header '{name}': {e}")))?;
+        headers.insert(name, value);
+    }
+    if options.wire_format != WireFormat::Json {
+        headers.insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_static(options.wire_format.subprotocol()),
+        );
+    }
+
+    let connector = tls_connector(&options.tls)?;
+    let (ws, response) =
+        tokio_tungstenite::connect_async_tls_with_config(request, None, false, connector).await?;
+    let accepted = response
+        .headers()
+        .get("Sec-WebSocket-Protocol")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let format = WireFormat::negotiate(accepted.iter().map(String::as_str)).unwrap_or_default();
+    Ok((ws, format))
+}
+
+/// Builds a TLS connector for non-default [`TlsOptions`]; `None` uses the default connector.
+fn tls_connector(tls: &TlsOptions) -> Result<Option<Connector>> {
+    if tls.ca_certificate_pem.is_none()
+        && tls.client_certificate_pem.is_none()
+        && tls.client_key_pem.is_none()
+    {
+        return Ok(None);
+    }
+
+    let mut builder = native_tls::TlsConnector::builder();
+    if let Some(ca) = &tls.ca_certificate_pem {
+        let ca = native_tls::Certificate::from_pem(ca)
+            .map_err(|e| Error::Tls(format!("Invalid CA certificate: {e}")))?;
+        builder.add_root_certificate(ca);
+    }
+    match (&tls.client_certificate_pem, &tls.client_key_pem) {
+        (Some(cert), Some(key)) => {
+            let identity = native_tls::Identity::from_pkcs8(cert, key)
+                .map_err(|e| Error::Tls(format!("Invalid client certificate or key: {e}")))?;
+            builder.identity(identity);
+        },
+        (None, None) => {},
+        _ => {
+            return Err(Error::Tls(
+                "Client certificate and key must be configured together".to_string(),
+            ));
+        },
+    }
+    let connector = builder.build().map_err(|e| Error::Tls(e.to_string()))?;
+    Ok(Some(Connector::NativeTls(connector)))
+}
+
+fn encode(format: WireFormat, request: &Request) -> Result<WsMessage> {
+    match format {
+        WireFormat::Json => serde_json::to_string(request)
+            .map(|text| WsMessage::Text(text.into()))
+            .map_err(|e| Error::Codec(e.to_string())),
+        WireFormat::Msgpack => rmp_serde::to_vec_named(request)
+            .map(|bytes| WsMessage::Binary(bytes.into()))
+            .map_err(|e| Error::Codec(e.to_string())),
+    }
+}
+
+/// A decoded server message.
+enum Incoming {
+    Response(Response),
+    Event(Event),
+}
+
+/// Decodes a frame, or `None` for frames that aren't API messages (pings, unknown types).
+fn decode(msg: &WsMessage) -> Option<Incoming> {
+    let value: serde_json::Value = match msg {
+        WsMessage::Text(text) => serde_json::from_str(text).ok()?,
+        WsMessage::Binary(bytes) => rmp_serde::from_slice(bytes).ok()?,
+        _ => return None,
+    };
+    let decoded = match value.get("type").and_then(serde_json::Value::as_str) {
+        Some("response") => serde_json::from_value(value).map(Incoming::Response),
+        Some("event") => serde_json::from_value(value).map(Incoming::Event),
+        _ => return None,
+    };
+    decoded.map_err(|e| tracing::warn!("Failed to decode server message: {e}")).ok()
+}
+
+/// Why a connection ended.
+enum Exit {
+    /// Every client handle was dropped.
+    ClientDropped,
+    /// The connection failed or the server closed it.
+    ConnectionLost,
+}
+
+pub struct ConnectionTask {
+    pub url: Url,
+    pub options: ClientOptions,
+    pub commands: mpsc::Receiver<Command>,
+    pub events: broadcast::Sender<Event>,
+    pub state: watch::Sender<ConnectionState>,
+}
+
+impl ConnectionTask {
+    pub async fn run(mut self, mut ws: WsStream, mut format: WireFormat) {
+        let mut backlog = VecDeque::new();
+        loop {
+            let _ = self.state.send(ConnectionState::Connected);
+            let mut pending = HashMap::new();
+            let exit = self.serve(&mut ws, format, &mut backlog, &mut pending).await;
+            for (_, reply) in pending.drain() {
+                let _ = reply.send(Err(Error::Disconnected));
+            }
+            if matches!(exit, Exit::ClientDropped) {
+                let _ = ws.close(None).await;
+                break;
+            }
+
+            match self.reconnect(&mut backlog).await {
+                Some((new_ws, new_format)) => (ws, format) = (new_ws, new_format),
+                None => break,
+            }
+        }
+
+        let _ = self.state.send(ConnectionState::Closed);
+        for command in backlog {
+            if let Some(reply) = command.reply {
+                let _ = reply.send(Err(Error::Closed));
+            }
+        }
+    }
+
+    async fn serve(
+        &mut self,
+        ws: &mut WsStream,
+        format: WireFormat,
+        backlog: &mut VecDeque<Command>,
+        pending: &mut HashMap<String, ReplySender>,
+    ) -> Exit {
+        // Requests queued while reconnecting go out first, in order.
+        while let Some(command) = backlog.pop_front() {
+            if let Err(command) = send(ws, format, command, pending).await {
+                backlog.push_front(command);
+                return Exit::ConnectionLost;
+            }
+        }
+
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => {
+                    let Some(command) = command else {
+                        return Exit::ClientDropped;
+                    };
+                    if let Err(command) = send(ws, format, command, pending).await {
+                        backlog.push_front(command);
+                        return Exit::ConnectionLost;
+                    }
+                }
+                msg = ws.next() => match msg {
+                    Some(Ok(WsMessage::Close(_))) | None => return Exit::ConnectionLost,
+                    Some(Ok(msg)) => self.dispatch(&msg, pending),
+                    Some(Err(e)) => {
+                        tracing::warn!("Control WebSocket error: {e}");
+                        return Exit::ConnectionLost;
+                    }
+                },
+            }
+        }
+    }
+
+    fn dispatch(&self, msg: &WsMessage, pending: &mut HashMap<String, ReplySender>) {
+        match decode(msg) {
+            Some(Incoming::Response(response)) => {
+                let Some(reply) =
+                    response.correlation_id.as_ref().and_then(|cid| pending.remove(cid))
+                else {
+                    tracing::debug!("Dropping response without a pending request");
+                    return;
+                };
+                let result = match response.payload {
+                    ResponsePayload::Error { message } => Err(Error::Server(message)),
+                    ResponsePayload::ReadOnly { message, banner } => {
+                        Err(Error::ReadOnly { message, banner })
+                    },
+                    payload => Ok(payload),
+                };
+                let _ = reply.send(result);
+            },
+            // No subscribers is fine; events are simply not observed.
+            Some(Incoming::Event(event)) => {
+                let _ = self.events.send(event);
+            },
+            None => {},
+        }
+    }
+
+    /// Reconnects with exponential backoff. Requests submitted meanwhile are queued in
+    /// `backlog`. Returns `None` when the policy gives up or every client handle is dropped.
+    async fn reconnect(
+        &mut self,
+        backlog: &mut VecDeque<Command>,
+    ) -> Option<(WsStream, WireFormat)> {
+        let policy = self.options.reconnect.clone();
+        let mut delay = policy.initial_backoff;
+        let mut attempt = 0u32;
+        loop {
+            if policy.max_attempts.is_some_and(|max| attempt >= max) {
+                tracing::warn!("Giving up reconnecting to {} after {attempt} attempts", self.url);
+                return None;
+            }
+            attempt += 1;
+            let _ = self.state.send(ConnectionState::Reconnecting { attempt });
+
+            let sleep = tokio::time::sleep(delay);
+            tokio::pin!(sleep);
+            loop {
+                tokio::select! {
+                    () = &mut sleep => break,
+                    command = self.commands.recv() => match command {
+                        Some(command) => backlog.push_back(command),
+                        None => return None,
+                    },
+                }
+            }
+            backlog.retain(|command| !command.is_abandoned());
+
+            match connect(&self.url, &self.options).await {
+                Ok(connected) => {
+                    tracing::info!("Reconnected to {} after {attempt} attempt(s)", self.url);
+                    return Some(connected);
+                },
+                Err(e) => tracing::debug!("Reconnect attempt {attempt} failed: {e}"),
+            }
+            delay = delay.saturating_mul(2).min(policy.max_backoff);
+        }
+    }
+}
+
+/// Sends one command. On a transport failure the command is handed back so it can be
+/// retried after reconnecting; encoding failures are reported to the caller instead.
+async fn send(
+    ws: &mut WsStream,
+    format: WireFormat,
+    command: Command,
+    pending: &mut HashMap<String, ReplySender>,
+) -> std::result::Result<(), Command> {
+    if command.is_abandoned() {
+        return Ok(());
+    }
+    let request = Request {
+        message_type: MessageType::Request,
+        correlation_id: Some(command.correlation_id),
+        payload: command.payload,
+    };
+    let msg = match encode(format, &request) {
+        Ok(msg) => msg,
+        Err(e) => {
+            if let Some(reply) = command.reply {
+                let _ = reply.send(Err(e));
+            }
+            return Ok(());
+        },
+    };
+    // Rebuild the command only if the send fails; `request` owns the payload until then.
+    let correlation_id = request.correlation_id.clone().unwrap_or_default();
+    if let Err(e) = ws.send(msg).await {
+        tracing::warn!("Failed to send request: {e}");
+        return Err(Command { correlation_id, payload: request.payload, reply: command.reply });
+    }
+    if let Some(reply) = command.reply {
+        pending.retain(|_, reply| !reply.is_closed());
+        pending.insert(correlation_id, reply);
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use thiserror::Error;
+
+/// Errors returned by [`Client`](crate::Client) operations.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The server URL could not be parsed or uses an unsupported scheme.
+    #[error("Invalid server URL: {0}")]
+    InvalidUrl(String),
+
+    /// The WebSocket connection could not be established or failed while in use.
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    /// The TLS settings are invalid, e.g. a certificate or key could not be parsed.
+    #[error("TLS error: {0}")]
+    Tls(String),
+
+    /// A message could not be encoded or decoded in the negotiated wire format.
+    #[error("Codec error: {0}")]
+    Codec(String),
+
+    /// The server answered the request with an `error` response.
+    #[error("Server error: {0}")]
+    Server(String),
+
+    /// The server is in read-only mode and refused a mutating request. `banner` is the
+    /// operator's message, if one was set.
+    #[error("Server is read-only: {message}")]
+    ReadOnly { message: String, banner: Option<String> },
+
+    /// The server answered with a response of a different kind than the request expects.
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    /// No response arrived within the configured request timeout.
+    #[error("Request timed out")]
+    Timeout,
+
+    /// The connection dropped before the response arrived. The request may or may not
+    /// have been applied by the server.
+    #[error("Connection lost before a response was received")]
+    Disconnected,
+
+    /// The client gave up reconnecting and is no longer usable.
+    #[error("Client is closed")]
+    Closed,
+}
+
+/// Result alias for client operations.
+pub type Result<T> = std::result::Result<T, Error>;
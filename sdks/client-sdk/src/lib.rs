@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Typed async client for the StreamKit control API.
+//!
+//! [`Client`] keeps one WebSocket to the server's `/api/v1/control` endpoint and exposes
+//! the control plane as typed methods (sessions, nodes, connections, batches), plus a
+//! stream of server [`Event`]s. Correlation IDs, reconnection and the wire format are
+//! handled internally.
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use streamkit_client_sdk::{Client, ConnectionMode};
+//!
+//! # async fn run() -> streamkit_client_sdk::Result<()> {
+//! let client = Client::connect("http://127.0.0.1:4545").await?;
+//! let mut events = client.events();
+//!
+//! let session = client.create_session(Some("demo"), Vec::new()).await?;
+//! let id = &session.session_id;
+//! client.add_node(id, "gain", "audio::gain", Some(serde_json::json!({ "gain": 0.5 }))).await?;
+//! client.add_node(id, "sink", "core::passthrough", None).await?;
+//! client.connect_nodes(id, ("gain", "out"), ("sink", "in"), ConnectionMode::default()).await?;
+//!
+//! while let Some(event) = events.next().await {
+//!     println!("{:?}", event.payload);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod connection;
+mod error;
+mod options;
+pub mod url;
+
+pub use client::{Client, CreatedSession, SessionPage};
+pub use error::{Error, Result};
+pub use options::{ClientOptions, ConnectionState, ReconnectPolicy, TlsOptions};
+
+// Re-export the API types used in method signatures so callers don't need a direct
+// dependency on `streamkit-api`.
+pub use streamkit_api::{
+    ApiPipeline, BatchOperation, ConnectionMode, CostReport, Event, EventPayload,
+    NodeControlMessage, NodeDefinition, PermissionsInfo, RequestPayload, ResponsePayload,
+    SessionFilter, SessionInfo, SessionListQuery, SessionNode, UiMetadataTarget, ValidationError,
+    WireFormat,
+};
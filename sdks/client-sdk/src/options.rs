@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use std::time::Duration;
+use streamkit_api::WireFormat;
+
+/// How the client re-establishes a dropped connection.
+///
+/// Delays start at `initial_backoff` and double after each failed attempt, up to
+/// `max_backoff`.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Attempts before giving up; `None` retries forever. `Some(0)` disables reconnection.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// A policy that never reconnects; the client closes when the connection drops.
+    pub const fn disabled() -> Self {
+        Self { initial_backoff: Duration::ZERO, max_backoff: Duration::ZERO, max_attempts: Some(0) }
+    }
+}
+
+/// Settings for [`Client::connect_with`](crate::Client::connect_with).
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Encoding offered to the server. The server may fall back to JSON.
+    pub wire_format: WireFormat,
+    /// How long a request waits for its response before failing with
+    /// [`Error::Timeout`](crate::Error::Timeout).
+    pub request_timeout: Duration,
+    pub reconnect: ReconnectPolicy,
+    /// Extra HTTP headers sent with the WebSocket handshake, e.g. the role header
+    /// configured as `permissions.role_header` or credentials for an auth proxy.
+    pub headers: Vec<(String, String)>,
+    /// Events buffered per subscriber before the oldest are dropped.
+    pub event_buffer: usize,
+    /// Certificates for `https://`/`wss://` servers.
+    pub tls: TlsOptions,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            wire_format: WireFormat::Json,
+            request_timeout: Duration::from_secs(30),
+            reconnect: ReconnectPolicy::default(),
+            headers: Vec::new(),
+            event_buffer: 1024,
+            tls: TlsOptions::default(),
+        }
+    }
+}
+
+/// TLS settings for the control connection.
+///
+/// All certificates are PEM-encoded. Leave everything unset to verify the server against the
+/// system trust store without presenting a client certificate.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// Extra CA certificate trusted for the server certificate, e.g. a private CA.
+    pub ca_certificate_pem: Option<Vec<u8>>,
+    /// Client certificate presented for mutual TLS; requires `client_key_pem`.
+    pub client_certificate_pem: Option<Vec<u8>>,
+    /// PKCS#8 private key of `client_certificate_pem`.
+    pub client_key_pem: Option<Vec<u8>>,
+}
+
+/// State of the client's control connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// The connection dropped; `attempt` counts reconnect attempts so far. Requests made
+    /// in this state are queued and sent once reconnected.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Reconnection gave up or the client was dropped; every request now fails.
+    Closed,
+}
@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Conversions between the server base URL and the HTTP/WebSocket endpoints.
+
+use crate::error::{Error, Result};
+use url::Url;
+
+/// Path of the WebSocket control endpoint.
+pub const CONTROL_PATH: &str = "/api/v1/control";
+
+/// Returns the HTTP(S) base URL (no path, query or fragment) for a server URL given as
+/// `http(s)://` or `ws(s)://`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidUrl`] if the URL can't be parsed or uses another scheme.
+pub fn http_base_url(server_url: &str) -> Result<Url> {
+    let mut url = Url::parse(server_url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+    let scheme = match url.scheme() {
+        "http" | "ws" => "http",
+        "https" | "wss" => "https",
+        _ => return Err(Error::InvalidUrl("Server URL must be http(s) or ws(s)".to_string())),
+    };
+    set_scheme(&mut url, scheme)?;
+    url.set_path("");
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok(url)
+}
+
+/// Returns the WebSocket control endpoint for a server URL given as `http(s)://` or
+/// `ws(s)://`. Any path on the input is replaced by [`CONTROL_PATH`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidUrl`] if the URL can't be parsed or uses another scheme.
+pub fn control_ws_url(server_url: &str) -> Result<Url> {
+    let mut url = Url::parse(server_url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+    let scheme = match url.scheme() {
+        "http" | "ws" => "ws",
+        "https" | "wss" => "wss",
+        _ => return Err(Error::InvalidUrl("Server URL must be http(s) or ws(s)".to_string())),
+    };
+    set_scheme(&mut url, scheme)?;
+    url.set_path(CONTROL_PATH);
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok(url)
+}
+
+fn set_scheme(url: &mut Url, scheme: &str) -> Result<()> {
+    url.set_scheme(scheme)
+        .map_err(|()| Error::InvalidUrl(format!("Failed to convert server URL to {scheme}://")))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)] // Fixed, known-good URLs
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_conversion() {
+        assert_eq!(
+            control_ws_url("http://localhost:4545/ui?x=1").unwrap().as_str(),
+            "ws://localhost:4545/api/v1/control"
+        );
+        assert_eq!(
+            control_ws_url("wss://skit.example.com").unwrap().as_str(),
+            "wss://skit.example.com/api/v1/control"
+        );
+        assert_eq!(
+            http_base_url("wss://skit.example.com/api/v1/control").unwrap().as_str(),
+            "https://skit.example.com/"
+        );
+        assert!(matches!(control_ws_url("ftp://host"), Err(Error::InvalidUrl(_))));
+    }
+}
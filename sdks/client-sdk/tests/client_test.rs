@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Exercises the client against a scripted in-process WebSocket server.
+
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use streamkit_api::{MessageType, Request, RequestPayload, Response, ResponsePayload};
+use streamkit_client_sdk::{
+    Client, ClientOptions, ConnectionState, Error, Event, EventPayload, ReconnectPolicy,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::WebSocketStream;
+
+async fn bind() -> Option<TcpListener> {
+    match TcpListener::bind("127.0.0.1:0").await {
+        Ok(listener) => Some(listener),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => None,
+        Err(e) => panic!("Failed to bind mock server: {e}"),
+    }
+}
+
+async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+    let (stream, _) = listener.accept().await.unwrap();
+    tokio_tungstenite::accept_async(stream).await.unwrap()
+}
+
+async fn recv_request(ws: &mut WebSocketStream<TcpStream>) -> Request {
+    loop {
+        match ws.next().await.unwrap().unwrap() {
+            WsMessage::Text(text) => return serde_json::from_str(&text).unwrap(),
+            WsMessage::Close(_) => panic!("client closed the connection"),
+            _ => {},
+        }
+    }
+}
+
+async fn reply(ws: &mut WebSocketStream<TcpStream>, request: &Request, payload: ResponsePayload) {
+    let response = Response {
+        message_type: MessageType::Response,
+        correlation_id: request.correlation_id.clone(),
+        payload,
+    };
+    let text = serde_json::to_string(&response).unwrap();
+    ws.send(WsMessage::Text(text.into())).await.unwrap();
+}
+
+fn session_created(id: &str) -> ResponsePayload {
+    ResponsePayload::SessionCreated {
+        session_id: id.to_string(),
+        name: None,
+        created_at: "2025-01-01T00:00:00Z".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn responses_are_matched_by_correlation_id() {
+    let Some(listener) = bind().await else {
+        return;
+    };
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let first = recv_request(&mut ws).await;
+        let second = recv_request(&mut ws).await;
+
+        // An event first, then the responses in reverse order.
+        let event = Event {
+            message_type: MessageType::Event,
+            correlation_id: None,
+            payload: EventPayload::SessionDestroyed { session_id: "gone".to_string() },
+        };
+        let text = serde_json::to_string(&event).unwrap();
+        ws.send(WsMessage::Text(text.into())).await.unwrap();
+        for request in [&second, &first] {
+            let RequestPayload::CreateSession { name, .. } = &request.payload else {
+                panic!("unexpected request: {:?}", request.payload);
+            };
+            reply(&mut ws, request, session_created(name.as_deref().unwrap())).await;
+        }
+        let third = recv_request(&mut ws).await;
+        reply(&mut ws, &third, ResponsePayload::Error { message: "denied".to_string() }).await;
+        // Keep the connection open until the client is done.
+        let _ = ws.next().await;
+    });
+
+    let client = Client::connect(&url).await.unwrap();
+    let mut events = client.events();
+
+    let (a, b) = tokio::join!(client.create_session(Some("a"), Vec::new()), async {
+        // Make sure "a" goes out first.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        client.create_session(Some("b"), Vec::new()).await
+    });
+    assert_eq!(a.unwrap().session_id, "a");
+    assert_eq!(b.unwrap().session_id, "b");
+
+    let event = timeout(Duration::from_secs(5), events.next()).await.unwrap().unwrap();
+    let EventPayload::SessionDestroyed { session_id } = event.payload else {
+        panic!("unexpected event: {:?}", event.payload);
+    };
+    assert_eq!(session_id, "gone");
+
+    let err = client.destroy_session("x").await.unwrap_err();
+    assert!(matches!(err, Error::Server(ref message) if message == "denied"), "{err:?}");
+
+    drop(client);
+    timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn reconnects_after_connection_loss() {
+    let Some(listener) = bind().await else {
+        return;
+    };
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        // First connection: take one request and drop the socket without answering.
+        let mut ws = accept(&listener).await;
+        let _ = recv_request(&mut ws).await;
+        drop(ws);
+
+        let mut ws = accept(&listener).await;
+        let request = recv_request(&mut ws).await;
+        reply(&mut ws, &request, session_created("after-reconnect")).await;
+        let _ = ws.next().await;
+    });
+
+    let options = ClientOptions {
+        reconnect: ReconnectPolicy {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            max_attempts: Some(20),
+        },
+        ..ClientOptions::default()
+    };
+    let client = Client::connect_with(&url, options).await.unwrap();
+    let mut states = client.state_changes();
+
+    let err = client.create_session(None, Vec::new()).await.unwrap_err();
+    assert!(matches!(err, Error::Disconnected), "{err:?}");
+
+    timeout(Duration::from_secs(5), states.wait_for(|s| *s == ConnectionState::Connected))
+        .await
+        .unwrap()
+        .unwrap();
+    let session = client.create_session(None, Vec::new()).await.unwrap();
+    assert_eq!(session.session_id, "after-reconnect");
+
+    drop(client);
+    timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn gives_up_when_reconnection_is_disabled() {
+    let Some(listener) = bind().await else {
+        return;
+    };
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let ws = accept(&listener).await;
+        drop(ws);
+    });
+
+    let options =
+        ClientOptions { reconnect: ReconnectPolicy::disabled(), ..ClientOptions::default() };
+    let client = Client::connect_with(&url, options).await.unwrap();
+    let mut events = client.events();
+    server.await.unwrap();
+
+    let mut states = client.state_changes();
+    timeout(Duration::from_secs(5), states.wait_for(|s| *s == ConnectionState::Closed))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(client.list_sessions(None).await, Err(Error::Closed)));
+    assert!(events.next().await.is_none());
+}
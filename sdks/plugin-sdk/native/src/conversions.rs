@@ -446,7 +446,27 @@ pub fn error_to_c(msg: impl AsRef<str>) -> *const c_char {
         );
     }
 
-    let msg = msg.as_ref();
+    store_borrowed(&LAST_ERROR, msg.as_ref())
+}
+
+/// Convert a query response (JSON text) to a C string for returning across the C ABI.
+///
+/// Follows the same borrowed-pointer rules as [`error_to_c`], with its own slot so a
+/// response stays valid if an error message is produced afterwards.
+pub fn response_to_c(json: impl AsRef<str>) -> *const c_char {
+    thread_local! {
+        static LAST_RESPONSE: RefCell<CString> = RefCell::new(
+            CString::new("").unwrap_or_else(|_| unsafe { CString::from_vec_unchecked(vec![0]) })
+        );
+    }
+
+    store_borrowed(&LAST_RESPONSE, json.as_ref())
+}
+
+fn store_borrowed(
+    slot: &'static std::thread::LocalKey<RefCell<CString>>,
+    msg: &str,
+) -> *const c_char {
     let sanitized = if msg.contains('\0') { msg.replace('\0', " ") } else { msg.to_string() };
 
     // CString::new can only fail if there are interior null bytes. We sanitize them above,
@@ -454,7 +474,7 @@ pub fn error_to_c(msg: impl AsRef<str>) -> *const c_char {
     let c_str =
         CString::new(sanitized).unwrap_or_else(|_| unsafe { CString::from_vec_unchecked(vec![0]) });
 
-    LAST_ERROR.with(|slot| {
+    slot.with(|slot| {
         *slot.borrow_mut() = c_str;
         slot.borrow().as_ptr()
     })
@@ -505,6 +525,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_response_survives_later_error() {
+        let c_response = response_to_c(r#"{"queued":3}"#);
+        let _ = error_to_c("unrelated failure");
+        unsafe {
+            let result_cstr = CStr::from_ptr(c_response);
+            assert_eq!(result_cstr.to_string_lossy(), r#"{"queued":3}"#);
+        }
+    }
+
     #[test]
     fn test_string_to_c_requires_free() {
         let c_msg = string_to_c("hello");
@@ -45,6 +45,8 @@
 
 pub mod conversions;
 pub mod logger;
+pub mod sandbox;
+pub mod seed;
 pub mod types;
 
 use std::ffi::CString;
@@ -59,6 +61,7 @@ pub use types::*;
 /// Re-export commonly used types
 pub mod prelude {
     pub use crate::logger::Logger;
+    pub use crate::sandbox::Sandbox;
     pub use crate::types::{CLogCallback, CLogLevel};
     pub use crate::{
         native_plugin_entry, plugin_debug, plugin_error, plugin_info, plugin_log, plugin_trace,
@@ -76,6 +79,12 @@ pub struct NodeMetadata {
     pub outputs: Vec<OutputPin>,
     pub param_schema: serde_json::Value,
     pub categories: Vec<String>,
+    /// Shared libraries loaded at runtime, checked by the host before the plugin is used
+    pub requires_libraries: Vec<String>,
+    /// Host features needed, see [`NodeMetadataBuilder::requires_feature`]
+    pub requires_features: Vec<String>,
+    /// Plugin kinds that can't be loaded alongside this one
+    pub conflicts_with: Vec<String>,
 }
 
 impl NodeMetadata {
@@ -88,6 +97,9 @@ impl NodeMetadata {
             outputs: Vec::new(),
             param_schema: serde_json::json!({}),
             categories: Vec::new(),
+            requires_libraries: Vec::new(),
+            requires_features: Vec::new(),
+            conflicts_with: Vec::new(),
         }
     }
 }
@@ -100,6 +112,9 @@ pub struct NodeMetadataBuilder {
     outputs: Vec<OutputPin>,
     param_schema: serde_json::Value,
     categories: Vec<String>,
+    requires_libraries: Vec<String>,
+    requires_features: Vec<String>,
+    conflicts_with: Vec<String>,
 }
 
 impl NodeMetadataBuilder {
@@ -146,6 +161,30 @@ impl NodeMetadataBuilder {
         self
     }
 
+    /// Declare a shared library the plugin loads at runtime, by the name passed to `dlopen`
+    /// (e.g. `libcudart.so.12`). The host refuses to load the plugin if it can't be found.
+    #[must_use]
+    pub fn requires_library(mut self, library: &str) -> Self {
+        self.requires_libraries.push(library.to_string());
+        self
+    }
+
+    /// Declare a host feature the plugin needs: `gpu` (an NVIDIA driver), or a CPU feature
+    /// such as `avx2`, `avx512f`, `fma` or `neon`.
+    #[must_use]
+    pub fn requires_feature(mut self, feature: &str) -> Self {
+        self.requires_features.push(feature.to_string());
+        self
+    }
+
+    /// Declare a plugin kind that can't be loaded alongside this one, e.g. because both
+    /// bundle incompatible versions of the same library.
+    #[must_use]
+    pub fn conflicts_with(mut self, kind: &str) -> Self {
+        self.conflicts_with.push(kind.to_string());
+        self
+    }
+
     /// Build the metadata
     pub fn build(self) -> NodeMetadata {
         NodeMetadata {
@@ -155,6 +194,9 @@ impl NodeMetadataBuilder {
             outputs: self.outputs,
             param_schema: self.param_schema,
             categories: self.categories,
+            requires_libraries: self.requires_libraries,
+            requires_features: self.requires_features,
+            conflicts_with: self.conflicts_with,
         }
     }
 }
@@ -281,6 +323,24 @@ impl OutputSender {
             Err(error_msg)
         }
     }
+
+    /// Report time spent on a GPU so the host can charge it to this node's cost meter.
+    ///
+    /// The host already measures the wall time of each `process`/`flush` call as CPU time;
+    /// call this around the GPU portion (e.g. model inference on CUDA) of that work.
+    /// If the host doesn't provide a telemetry callback, this is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host telemetry callback reports an error.
+    pub fn report_gpu_time(&self, elapsed: std::time::Duration) -> Result<(), String> {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.emit_telemetry(
+            types::GPU_TIME_TELEMETRY_EVENT,
+            &serde_json::json!({ "nanos": nanos }),
+            None,
+        )
+    }
 }
 
 /// Trait that plugin authors implement
@@ -291,6 +351,9 @@ pub trait NativeProcessorNode: Sized + Send + 'static {
 
     /// Create a new instance of the node
     ///
+    /// The instance's working directory and environment are available through
+    /// [`Sandbox::current`](crate::sandbox::Sandbox::current) while this runs.
+    ///
     /// # Errors
     ///
     /// Returns an error if initialization fails (e.g., invalid parameters)
@@ -325,6 +388,18 @@ pub trait NativeProcessorNode: Sized + Send + 'static {
         Ok(())
     }
 
+    /// Answer a runtime query from a client (optional)
+    ///
+    /// `kind` names the information requested (e.g. `"model_info"`); `args` holds
+    /// query-specific arguments, or `null` when none were sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query kind is unsupported or cannot be answered
+    fn query(&mut self, kind: &str, _args: serde_json::Value) -> Result<serde_json::Value, String> {
+        Err(format!("Unsupported query '{kind}'"))
+    }
+
     /// Clean up resources (optional)
     fn cleanup(&mut self) {}
 }
@@ -443,6 +518,7 @@ macro_rules! native_plugin_entry {
             std::ffi::CString,
             Option<std::ffi::CString>,
             std::ffi::CString,
+            [(Vec<std::ffi::CString>, Vec<*const std::os::raw::c_char>); 3],
         )> = std::sync::OnceLock::new();
 
         #[no_mangle]
@@ -455,6 +531,7 @@ macro_rules! native_plugin_entry {
                 update_params: __plugin_update_params,
                 flush: __plugin_flush,
                 destroy_instance: __plugin_destroy_instance,
+                query: Some(__plugin_query),
             };
             &API
         }
@@ -657,6 +734,25 @@ macro_rules! native_plugin_entry {
                     let param_schema = std::ffi::CString::new(meta.param_schema.to_string())
                         .expect("Param schema JSON should not contain null bytes");
 
+                    // Convert requirements and conflicts
+                    let [libraries, features, conflicts] = [
+                        &meta.requires_libraries,
+                        &meta.requires_features,
+                        &meta.conflicts_with,
+                    ]
+                    .map(|entries| {
+                        let strings: Vec<std::ffi::CString> = entries
+                            .iter()
+                            .map(|entry| {
+                                std::ffi::CString::new(entry.as_str())
+                                    .expect("Requirement should not contain null bytes")
+                            })
+                            .collect();
+                        let ptrs: Vec<*const std::os::raw::c_char> =
+                            strings.iter().map(|s| s.as_ptr()).collect();
+                        (strings, ptrs)
+                    });
+
                     let c_metadata = $crate::types::CNodeMetadata {
                         kind: kind.as_ptr(),
                         description: description.as_ref().map_or(std::ptr::null(), |d| d.as_ptr()),
@@ -667,6 +763,12 @@ macro_rules! native_plugin_entry {
                         param_schema: param_schema.as_ptr(),
                         categories: category_ptrs.as_ptr(),
                         categories_count: category_ptrs.len(),
+                        requires_libraries: libraries.1.as_ptr(),
+                        requires_libraries_count: libraries.1.len(),
+                        requires_features: features.1.as_ptr(),
+                        requires_features_count: features.1.len(),
+                        conflicts_with: conflicts.1.as_ptr(),
+                        conflicts_with_count: conflicts.1.len(),
                     };
 
                     (
@@ -685,6 +787,7 @@ macro_rules! native_plugin_entry {
                         kind,
                         description,
                         param_schema,
+                        [libraries, features, conflicts],
                     )
                 });
 
@@ -697,7 +800,7 @@ macro_rules! native_plugin_entry {
             log_callback: $crate::types::CLogCallback,
             log_user_data: *mut std::os::raw::c_void,
         ) -> $crate::types::CPluginHandle {
-            let params_json = if params.is_null() {
+            let mut params_json = if params.is_null() {
                 None
             } else {
                 match unsafe { $crate::conversions::c_str_to_string(params) } {
@@ -709,11 +812,14 @@ macro_rules! native_plugin_entry {
                     Err(_) => return std::ptr::null_mut(),
                 }
             };
+            let sandbox = $crate::sandbox::Sandbox::take_from_params(&mut params_json);
 
             // Create logger for this plugin instance
             let logger = $crate::logger::Logger::new(log_callback, log_user_data, module_path!());
 
-            match <$plugin_type as $crate::NativeProcessorNode>::new(params_json, logger) {
+            match sandbox
+                .scope(|| <$plugin_type as $crate::NativeProcessorNode>::new(params_json, logger))
+            {
                 Ok(instance) => Box::into_raw(Box::new(instance)) as $crate::types::CPluginHandle,
                 Err(_) => std::ptr::null_mut(),
             }
@@ -807,6 +913,65 @@ macro_rules! native_plugin_entry {
             }
         }
 
+        extern "C" fn __plugin_query(
+            handle: $crate::types::CPluginHandle,
+            kind: *const std::os::raw::c_char,
+            args: *const std::os::raw::c_char,
+            response: *mut *const std::os::raw::c_char,
+        ) -> $crate::types::CResult {
+            if handle.is_null() || kind.is_null() || response.is_null() {
+                let err_msg = $crate::conversions::error_to_c("Invalid query arguments (null)");
+                return $crate::types::CResult::error(err_msg);
+            }
+
+            let instance = unsafe { &mut *(handle as *mut $plugin_type) };
+
+            let kind = match unsafe { $crate::conversions::c_str_to_string(kind) } {
+                Ok(s) => s,
+                Err(e) => {
+                    let err_msg =
+                        $crate::conversions::error_to_c(format!("Invalid query kind: {e}"));
+                    return $crate::types::CResult::error(err_msg);
+                },
+            };
+
+            let args_json = if args.is_null() {
+                serde_json::Value::Null
+            } else {
+                match unsafe { $crate::conversions::c_str_to_string(args) } {
+                    Ok(s) if s.is_empty() => serde_json::Value::Null,
+                    Ok(s) => match serde_json::from_str(&s) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            let err_msg =
+                                $crate::conversions::error_to_c(format!("Invalid args JSON: {e}"));
+                            return $crate::types::CResult::error(err_msg);
+                        },
+                    },
+                    Err(e) => {
+                        let err_msg =
+                            $crate::conversions::error_to_c(format!("Invalid args string: {e}"));
+                        return $crate::types::CResult::error(err_msg);
+                    },
+                }
+            };
+
+            let result = instance.query(&kind, args_json).and_then(|value| {
+                serde_json::to_string(&value)
+                    .map_err(|e| format!("Failed to serialize query response: {e}"))
+            });
+            match result {
+                Ok(json) => {
+                    unsafe { *response = $crate::conversions::response_to_c(json) };
+                    $crate::types::CResult::success()
+                },
+                Err(e) => {
+                    let err_msg = $crate::conversions::error_to_c(e);
+                    $crate::types::CResult::error(err_msg)
+                },
+            }
+        }
+
         extern "C" fn __plugin_flush(
             handle: $crate::types::CPluginHandle,
             callback: $crate::types::COutputCallback,
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Per-instance sandbox provided by the host
+//!
+//! Native plugins run inside the server process, so the process working directory and
+//! environment are shared with the server and every other plugin. Instead of touching
+//! them, the host gives each instance its own working directory and a filtered copy of the
+//! environment, delivered through the host-reserved `_sandbox` param. The generated
+//! `create_instance` export strips that param before it reaches
+//! [`NativeProcessorNode::new`](crate::NativeProcessorNode::new), and makes it available
+//! through [`Sandbox::current`] for the duration of that call.
+//!
+//! Plugins should write scratch files below [`Sandbox::work_dir`], read settings through
+//! [`Sandbox::var`] rather than `std::env::var`, and start helper processes with
+//! [`Sandbox::command`]. The directory is deleted when the instance is destroyed.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Host-reserved param carrying the instance's sandbox. Never passed on to the plugin.
+pub const SANDBOX_PARAM: &str = "_sandbox";
+
+thread_local! {
+    static CURRENT: RefCell<Option<Sandbox>> = const { RefCell::new(None) };
+}
+
+/// Working directory and environment of one plugin instance
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sandbox {
+    work_dir: Option<PathBuf>,
+    env: Option<BTreeMap<String, String>>,
+}
+
+impl Sandbox {
+    /// Removes the `_sandbox` param from `params`, returning the sandbox it describes.
+    ///
+    /// Without the param (an older host, or sandboxing disabled) the sandbox is empty and
+    /// falls back to the process-wide directory and environment. If `_sandbox` was the only
+    /// param, `params` becomes `None`, as if the node had been created without params.
+    pub fn take_from_params(params: &mut Option<serde_json::Value>) -> Self {
+        let Some(value) =
+            params.as_mut().and_then(|p| p.as_object_mut()).and_then(|p| p.remove(SANDBOX_PARAM))
+        else {
+            return Self::default();
+        };
+        if params.as_ref().and_then(|p| p.as_object()).is_some_and(serde_json::Map::is_empty) {
+            *params = None;
+        }
+        let work_dir = value.get("work_dir").and_then(|v| v.as_str()).map(PathBuf::from);
+        let env = value.get("env").and_then(|v| v.as_object()).map(|env| {
+            env.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect()
+        });
+        Self { work_dir, env }
+    }
+
+    /// The sandbox of the instance being created
+    ///
+    /// Only meaningful while [`NativeProcessorNode::new`](crate::NativeProcessorNode::new)
+    /// runs; keep a clone if it's needed later. Elsewhere this returns an empty sandbox.
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone().unwrap_or_default())
+    }
+
+    /// Runs `f` with `self` as the [`current`](Self::current) sandbox.
+    #[doc(hidden)]
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Reset(Option<Sandbox>);
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|current| *current.borrow_mut() = previous);
+            }
+        }
+
+        let _reset = Reset(CURRENT.with(|current| current.borrow_mut().replace(self)));
+        f()
+    }
+
+    /// Whether the host provided a sandbox
+    pub const fn is_sandboxed(&self) -> bool {
+        self.work_dir.is_some() || self.env.is_some()
+    }
+
+    /// Directory reserved for this instance, if the host provided one
+    pub fn work_dir(&self) -> Option<&Path> {
+        self.work_dir.as_deref()
+    }
+
+    /// Directory for temporary files: the instance's working directory, or the system
+    /// temporary directory when unsandboxed
+    pub fn temp_dir(&self) -> PathBuf {
+        self.work_dir.clone().unwrap_or_else(std::env::temp_dir)
+    }
+
+    /// Value of environment variable `name` as visible to this instance
+    ///
+    /// In a sandbox only the variables the host passes through are visible; otherwise this
+    /// reads the process environment.
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.env.as_ref().map_or_else(|| std::env::var(name).ok(), |env| env.get(name).cloned())
+    }
+
+    /// All environment variables visible to this instance
+    pub fn vars(&self) -> BTreeMap<String, String> {
+        self.env.clone().unwrap_or_else(|| std::env::vars().collect())
+    }
+
+    /// A command that runs `program` in the instance's working directory with only the
+    /// instance's environment
+    pub fn command(&self, program: impl AsRef<std::ffi::OsStr>) -> std::process::Command {
+        let mut command = std::process::Command::new(program);
+        if let Some(env) = &self.env {
+            command.env_clear().envs(env);
+        }
+        if let Some(work_dir) = &self.work_dir {
+            command.current_dir(work_dir);
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_param_is_stripped_and_scoped() {
+        let mut params = Some(serde_json::json!({
+            "model": "base",
+            "_sandbox": {"work_dir": "/srv/work/whisper-1", "env": {"LANG": "C.UTF-8"}},
+        }));
+        let sandbox = Sandbox::take_from_params(&mut params);
+        assert_eq!(params, Some(serde_json::json!({"model": "base"})));
+        assert_eq!(sandbox.work_dir(), Some(Path::new("/srv/work/whisper-1")));
+        assert_eq!(sandbox.var("LANG").as_deref(), Some("C.UTF-8"));
+        assert_eq!(sandbox.var("PATH"), None);
+
+        assert!(!Sandbox::current().is_sandboxed());
+        let seen = sandbox.clone().scope(Sandbox::current);
+        assert_eq!(seen, sandbox);
+        assert!(!Sandbox::current().is_sandboxed());
+    }
+
+    #[test]
+    fn test_sandbox_only_params_and_fallback() {
+        let mut params = Some(serde_json::json!({"_sandbox": {"work_dir": "/srv/work/vad-0"}}));
+        assert!(Sandbox::take_from_params(&mut params).work_dir().is_some());
+        assert_eq!(params, None);
+
+        let mut params = Some(serde_json::json!({"model": "base"}));
+        let sandbox = Sandbox::take_from_params(&mut params);
+        assert!(!sandbox.is_sandboxed());
+        assert_eq!(sandbox.temp_dir(), std::env::temp_dir());
+        assert_eq!(sandbox.var("PATH"), std::env::var("PATH").ok());
+        assert!(!Sandbox::take_from_params(&mut None).is_sandboxed());
+    }
+}
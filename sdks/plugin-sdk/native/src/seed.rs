@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Seed convention for plugins with stochastic output
+//!
+//! A plugin whose output depends on random sampling declares an optional integer `seed`
+//! param in its config schema (see [`schema_property`]). The host then always creates it
+//! with a seed, picking one when the pipeline leaves it unset, and records the effective
+//! seed in telemetry so a run can be reproduced by setting it explicitly.
+//!
+//! Plugins read the seed with [`from_params`] and use it to initialise their RNG or
+//! sampler. Backends that can't be seeded should not declare the param.
+
+use serde_json::Value;
+
+pub use streamkit_core::seed::SEED_PARAM;
+
+/// Reads the seed from the instance params.
+///
+/// Returns `None` when the plugin was created without one, which only happens if its
+/// schema doesn't declare `seed` or the host predates the convention.
+pub fn from_params(params: Option<&Value>) -> Option<u64> {
+    params.and_then(|p| p.get(SEED_PARAM)).and_then(Value::as_u64)
+}
+
+/// JSON schema of the `seed` property, for a plugin's `param_schema`.
+pub fn schema_property() -> Value {
+    serde_json::json!({
+        "type": ["integer", "null"],
+        "minimum": 0,
+        "description": "Random seed; a generated seed is used and reported in telemetry when unset"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_seed_is_read_from_params() {
+        assert_eq!(from_params(Some(&json!({ "seed": 7 }))), Some(7));
+        assert_eq!(from_params(Some(&json!({ "seed": null }))), None);
+        assert_eq!(from_params(None), None);
+    }
+
+    #[test]
+    fn test_schema_property_is_accepted_by_the_host() {
+        let schema = json!({ "properties": { SEED_PARAM: schema_property() } });
+        assert!(streamkit_core::seed::accepts_seed(&schema));
+    }
+}
@@ -9,8 +9,90 @@
 
 use std::os::raw::{c_char, c_void};
 
-/// API version number. Plugins and host check compatibility via this field.
-pub const NATIVE_PLUGIN_API_VERSION: u32 = 2;
+/// Major version of the native C ABI.
+///
+/// Bumped only for breaking changes (reordered or removed fields, changed signatures).
+/// The host refuses plugins built against a different major version.
+pub const NATIVE_PLUGIN_ABI_MAJOR: u16 = 1;
+
+/// Minor version of the native C ABI.
+///
+/// Bumped for additive changes: new fields appended to the end of [`CNativePluginAPI`], or new
+/// host behaviour a plugin opts into. The host keeps loading plugins built against any older
+/// minor version of the same major and adapts to them.
+///
+/// - 1.0: the layout formerly announced as API version 3.
+/// - 1.1: the plugin strips the host-reserved `_sandbox` param (see [`crate::sandbox`]) from its
+///   creation params. Formerly API version 4.
+/// - 1.2: [`CNodeMetadata`] gains the plugin's requirements (shared libraries, host features)
+///   and conflicting plugin kinds.
+pub const NATIVE_PLUGIN_ABI_MINOR: u16 = 2;
+
+/// Encoded ABI version plugins announce in [`CNativePluginAPI::version`].
+///
+/// See [`NativeAbiVersion::encode`] for the encoding.
+pub const NATIVE_PLUGIN_API_VERSION: u32 = NativeAbiVersion::CURRENT.encode();
+
+/// Semantic version of the native C ABI.
+///
+/// It is carried in the `version` field of [`CNativePluginAPI`] as `major << 16 | minor`.
+/// Values below `1 << 16` are the plain API versions used before the ABI was versioned
+/// semantically; [`NativeAbiVersion::decode`] maps the ones the host still supports onto
+/// their semantic equivalent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NativeAbiVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl NativeAbiVersion {
+    /// The ABI version this SDK builds plugins against.
+    pub const CURRENT: Self = Self::new(NATIVE_PLUGIN_ABI_MAJOR, NATIVE_PLUGIN_ABI_MINOR);
+
+    /// First ABI version whose plugins receive the `_sandbox` param.
+    pub const SANDBOX: Self = Self::new(1, 1);
+
+    /// First ABI version whose [`CNodeMetadata`] carries requirements and conflicts.
+    pub const REQUIREMENTS: Self = Self::new(1, 2);
+
+    /// Plain API versions from before semantic versioning, with their semantic equivalent.
+    const LEGACY: [(u32, Self); 2] = [(3, Self::new(1, 0)), (4, Self::new(1, 1))];
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// Packs the version into the `version` field of [`CNativePluginAPI`].
+    pub const fn encode(self) -> u32 {
+        ((self.major as u32) << 16) | self.minor as u32
+    }
+
+    /// Reads the `version` field of [`CNativePluginAPI`].
+    ///
+    /// Returns `None` for legacy plain versions that predate the oldest supported layout.
+    pub fn decode(raw: u32) -> Option<Self> {
+        if raw >> 16 == 0 {
+            return Self::LEGACY.iter().find(|(legacy, _)| *legacy == raw).map(|(_, v)| *v);
+        }
+        // Both halves fit in 16 bits by construction
+        #[allow(clippy::cast_possible_truncation)]
+        let (major, minor) = ((raw >> 16) as u16, (raw & 0xFFFF) as u16);
+        Some(Self::new(major, minor))
+    }
+
+    /// Whether a host implementing `self` can load a plugin built against `plugin`.
+    ///
+    /// The major versions must match and the plugin may not be newer than the host.
+    pub const fn can_load(self, plugin: Self) -> bool {
+        self.major == plugin.major && plugin.minor <= self.minor
+    }
+}
+
+impl std::fmt::Display for NativeAbiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
 
 /// Opaque handle to a plugin instance
 pub type CPluginHandle = *mut c_void;
@@ -182,6 +264,16 @@ pub struct CNodeMetadata {
     /// Array of category strings
     pub categories: *const *const c_char,
     pub categories_count: usize,
+    /// Shared libraries the plugin loads, as passed to `dlopen` (e.g. `libcudart.so.12`).
+    /// ABI 1.2 and later; the host checks them before using the plugin.
+    pub requires_libraries: *const *const c_char,
+    pub requires_libraries_count: usize,
+    /// Host features the plugin needs (e.g. `gpu`, `avx2`). ABI 1.2 and later.
+    pub requires_features: *const *const c_char,
+    pub requires_features_count: usize,
+    /// Plugin kinds that can't be loaded alongside this one. ABI 1.2 and later.
+    pub conflicts_with: *const *const c_char,
+    pub conflicts_with_count: usize,
 }
 
 /// Callback function type for sending output packets
@@ -200,11 +292,21 @@ pub type CTelemetryCallback = Option<
     extern "C" fn(*const c_char, *const u8, usize, *const CPacketMetadata, *mut c_void) -> CResult,
 >;
 
+/// Telemetry event type the host reserves for GPU time reports.
+///
+/// The host charges the `nanos` field of the payload to the node's cost meter instead of
+/// forwarding the event to the telemetry bus. Plugins should use
+/// [`OutputSender::report_gpu_time`](crate::OutputSender::report_gpu_time) rather than
+/// emitting it by hand.
+pub const GPU_TIME_TELEMETRY_EVENT: &str = "streamkit.cost.gpu_time";
+
 /// The main plugin API structure
 /// Plugins export a function that returns a pointer to this struct
 #[repr(C)]
 pub struct CNativePluginAPI {
-    /// API version for compatibility checking
+    /// Encoded ABI version for compatibility checking, see [`NativeAbiVersion`].
+    ///
+    /// Must stay the first field in every ABI version.
     pub version: u32,
 
     /// Get metadata about the node type
@@ -258,7 +360,51 @@ pub struct CNativePluginAPI {
     /// Destroy a plugin instance
     /// handle: Plugin instance handle
     pub destroy_instance: extern "C" fn(CPluginHandle),
+
+    /// Answer a runtime query (nullable; null means queries are unsupported)
+    /// handle: Plugin instance handle
+    /// kind: Name of the query
+    /// args: JSON string with query arguments (nullable)
+    /// response: Out-pointer set to a JSON string on success.
+    ///           Borrowed like `CResult::error_message`; copy it before the next call.
+    pub query: Option<
+        extern "C" fn(CPluginHandle, *const c_char, *const c_char, *mut *const c_char) -> CResult,
+    >,
 }
 
 /// Symbol name that plugins must export
 pub const PLUGIN_API_SYMBOL: &[u8] = b"streamkit_native_plugin_api\0";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abi_version_round_trips_through_encoding() {
+        let version = NativeAbiVersion::new(2, 7);
+        assert_eq!(version.encode(), 0x0002_0007);
+        assert_eq!(NativeAbiVersion::decode(version.encode()), Some(version));
+        assert_eq!(
+            NativeAbiVersion::decode(NATIVE_PLUGIN_API_VERSION),
+            Some(NativeAbiVersion::CURRENT)
+        );
+    }
+
+    #[test]
+    fn legacy_api_versions_map_to_semantic_versions() {
+        assert_eq!(NativeAbiVersion::decode(3), Some(NativeAbiVersion::new(1, 0)));
+        assert_eq!(NativeAbiVersion::decode(4), Some(NativeAbiVersion::new(1, 1)));
+        assert_eq!(NativeAbiVersion::decode(2), None);
+        assert_eq!(NativeAbiVersion::decode(5), None);
+    }
+
+    #[test]
+    fn host_loads_older_minors_of_its_major_only() {
+        let host = NativeAbiVersion::new(1, 3);
+        assert!(host.can_load(NativeAbiVersion::new(1, 0)));
+        assert!(host.can_load(NativeAbiVersion::new(1, 3)));
+        assert!(!host.can_load(NativeAbiVersion::new(1, 4)));
+        assert!(!host.can_load(NativeAbiVersion::new(2, 0)));
+        assert!(!host.can_load(NativeAbiVersion::new(0, 9)));
+    }
+}
@@ -51,6 +51,7 @@
 //!             outputs: vec![/* ... */],
 //!             param_schema: "{}".to_string(),
 //!             categories: vec!["audio".to_string()],
+//!             custom_types: vec![],
 //!         }
 //!     }
 //! }
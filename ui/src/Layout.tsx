@@ -12,7 +12,9 @@ import logo from './assets/logo.png';
 import { LayoutPresetButtons } from './components/LayoutPresetButtons';
 import { Button } from './components/ui/Button';
 import { useTheme, type ColorMode } from './context/ThemeContext';
+import { fetchConfig } from './services/config';
 import { LAYOUT_PRESETS, useLayoutStore, type LayoutPreset } from './stores/layoutStore';
+import { useMaintenanceStore } from './stores/maintenanceStore';
 
 const LayoutContainer = styled.div`
   display: flex;
@@ -181,6 +183,15 @@ const ItemLabel = styled.span`
   white-space: nowrap;
 `;
 
+const ReadOnlyBanner = styled.div`
+  padding: 6px 20px;
+  background-color: var(--sk-warning);
+  color: #1f1300;
+  font-size: 14px;
+  font-weight: 600;
+  text-align: center;
+`;
+
 const Main = styled.main`
   flex: 1;
   overflow: hidden;
@@ -200,6 +211,22 @@ const Layout: React.FC = () => {
     'focus-canvas',
     'inspector-focus',
   ];
+  const { readOnly, banner, setReadOnly } = useMaintenanceStore(
+    useShallow((state) => ({
+      readOnly: state.readOnly,
+      banner: state.banner,
+      setReadOnly: state.setReadOnly,
+    }))
+  );
+
+  // Later changes arrive as `readonlychanged` events
+  React.useEffect(() => {
+    fetchConfig()
+      .then((config) => setReadOnly(config.readOnly ?? false, config.banner))
+      .catch(() => {
+        // Config is optional here; the stream view reports fetch failures
+      });
+  }, [setReadOnly]);
 
   return (
     <LayoutContainer>
@@ -275,6 +302,11 @@ const Layout: React.FC = () => {
           </MobileNavControls>
         </NavControls>
       </Nav>
+      {readOnly && (
+        <ReadOnlyBanner role="status">
+          Read-only mode: changes are disabled{banner ? ` — ${banner}` : ''}
+        </ReadOnlyBanner>
+      )}
       <Main>
         <Outlet />
       </Main>
@@ -186,8 +186,10 @@ const NodePalette: React.FC<NodePaletteProps> = ({
     }
   };
 
+  // Deprecated kinds stay resolvable for existing pipelines but aren't offered for new ones.
   const sortedDefs = React.useMemo(
-    () => [...nodeDefinitions].sort((a, b) => a.kind.localeCompare(b.kind)),
+    () =>
+      nodeDefinitions.filter((def) => !def.deprecated).sort((a, b) => a.kind.localeCompare(b.kind)),
     [nodeDefinitions]
   );
 
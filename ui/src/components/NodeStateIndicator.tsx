@@ -248,6 +248,12 @@ const renderPacketStats = (stats?: NodeStats): React.ReactNode => {
           <span style={{ marginLeft: 8, color: 'var(--sk-text-muted)' }}>Out:</span>{' '}
           {formatNumber(stats.sent)} pkt ({sentPps} pps)
         </div>
+        {stats.latency_us !== null && stats.latency_us !== undefined && (
+          <div style={{ marginBottom: 2 }}>
+            <span style={{ color: 'var(--sk-text-muted)' }}>Latency:</span>{' '}
+            {(Number(stats.latency_us) / 1000).toFixed(1)} ms
+          </div>
+        )}
         {(stats.discarded > 0 || stats.errored > 0) && (
           <div style={{ marginTop: 2, color: 'var(--sk-warning)' }}>
             {stats.discarded > 0 &&
@@ -30,8 +30,8 @@ type EditorNodeData = {
   onLabelChange?: (nodeId: string, newLabel: string) => void;
 };
 
-type ConnectionMode = 'reliable' | 'best_effort';
-type NeedsDependency = string | { node: string; mode?: ConnectionMode };
+type ConnectionMode = 'reliable' | 'best_effort' | 'feedback';
+type NeedsDependency = string | { node: string; from_pin?: string; mode?: ConnectionMode };
 
 function orderNodeIdsTopDown(
   nodes: Array<Node<EditorNodeData>>,
@@ -107,7 +107,14 @@ function buildPipelineForYaml(
         const label = idToLabelMap.get(e.source);
         if (!label) return null;
         const mode = (e.data as { mode?: ConnectionMode } | undefined)?.mode;
-        return mode === 'best_effort' ? { node: label, mode } : label;
+        // Only non-default output pins need spelling out
+        const fromPin = e.sourceHandle && e.sourceHandle !== 'out' ? e.sourceHandle : undefined;
+        if (!fromPin && (!mode || mode === 'reliable')) return label;
+        return {
+          node: label,
+          ...(fromPin ? { from_pin: fromPin } : {}),
+          ...(mode && mode !== 'reliable' ? { mode } : {}),
+        };
       })
       .filter((v): v is NeedsDependency => v !== null);
 
@@ -30,6 +30,14 @@ interface JsonSchemaProperty {
   exclusiveMaximum?: number;
   multipleOf?: number;
   tunable?: boolean;
+  enum?: unknown[];
+  /** Current options of provider-backed params, resolved by the server (a hint, not enforced) */
+  enum_options?: EnumOption[];
+}
+
+interface EnumOption {
+  value: string;
+  label?: string;
 }
 
 interface JsonSchema {
@@ -86,6 +94,21 @@ const FormInput = styled.input`
   }
 `;
 
+const FormSelect = styled.select`
+  width: 100%;
+  max-width: 100%;
+  padding: 8px;
+  background: var(--sk-panel-bg);
+  border: 1px solid var(--sk-border);
+  border-radius: 4px;
+  color: var(--sk-text);
+  box-sizing: border-box;
+
+  &:focus-visible {
+    outline: 1px solid var(--sk-primary);
+  }
+`;
+
 const FormTextarea = styled.textarea`
   width: 100%;
   max-width: 100%;
@@ -198,6 +221,40 @@ const StringField: React.FC<{
   );
 };
 
+// Helper: Render a dropdown for string params with a list of options.
+// The current value is kept as an option even when the server no longer lists it
+// (e.g. an unplugged device), so opening the inspector never changes the config.
+const EnumField: React.FC<{
+  inputId: string;
+  value: unknown;
+  schema: JsonSchemaProperty;
+  options: EnumOption[];
+  readOnly: boolean;
+  onChange: (value: string | undefined) => void;
+}> = ({ inputId, value, schema, options, readOnly, onChange }) => {
+  const stringValue = typeof value === 'string' ? value : '';
+
+  return (
+    <FormSelect
+      id={inputId}
+      value={stringValue}
+      onChange={(e) => onChange(e.target.value === '' ? undefined : e.target.value)}
+      disabled={readOnly}
+      aria-label={schema.description}
+    >
+      <option value="">(default)</option>
+      {stringValue !== '' && !options.some((option) => option.value === stringValue) && (
+        <option value={stringValue}>{stringValue}</option>
+      )}
+      {options.map((option) => (
+        <option key={option.value} value={option.value}>
+          {option.label ?? option.value}
+        </option>
+      ))}
+    </FormSelect>
+  );
+};
+
 // Helper: Render number field
 const NumberField: React.FC<{
   inputId: string;
@@ -289,6 +346,24 @@ const InspectorPane: React.FC<InspectorPaneProps> = ({
     // In monitor view, disable non-tunable params (they can't be changed at runtime)
     const isDisabled = readOnly || (isMonitorView && !schema.tunable);
 
+    const enumOptions =
+      schema.enum_options ??
+      schema.enum
+        ?.filter((v): v is string => typeof v === 'string')
+        .map((v): EnumOption => ({ value: v }));
+    if (enumOptions && enumOptions.length > 0) {
+      return (
+        <EnumField
+          inputId={inputId}
+          value={currentValue}
+          schema={schema}
+          options={enumOptions}
+          readOnly={isDisabled}
+          onChange={(v) => handleInputChange(key, v)}
+        />
+      );
+    }
+
     switch (schema.type) {
       case 'string':
         return (
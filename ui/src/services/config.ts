@@ -9,6 +9,10 @@ import { getApiUrl } from './base';
  */
 export interface FrontendConfig {
   moqGatewayUrl?: string;
+  /** Server is in read-only maintenance mode */
+  readOnly?: boolean;
+  /** Operator message to show while read-only */
+  banner?: string;
 }
 
 /**
@@ -27,5 +31,7 @@ export async function fetchConfig(): Promise<FrontendConfig> {
   // Convert snake_case to camelCase
   return {
     moqGatewayUrl: data.moq_gateway_url,
+    readOnly: data.read_only,
+    banner: data.banner,
   };
 }
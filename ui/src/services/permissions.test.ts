@@ -74,6 +74,7 @@ describe('permissions', () => {
         upload_assets: true,
         delete_assets: false,
         access_all_sessions: true,
+        view_secrets: true,
       };
 
       // Mock successful fetch
@@ -141,6 +142,7 @@ describe('permissions', () => {
         upload_assets: false,
         delete_assets: false,
         access_all_sessions: false,
+        view_secrets: false,
       };
 
       global.fetch = vi.fn().mockResolvedValue({
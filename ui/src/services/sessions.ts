@@ -6,7 +6,7 @@
  * Service for managing sessions
  */
 
-import type { SessionInfo } from '@/types/types';
+import type { PipelineEstimate, SessionInfo } from '@/types/types';
 import { getLogger } from '@/utils/logger';
 
 import { getApiUrl } from './base';
@@ -90,3 +90,29 @@ export async function createSession(
 
   return result;
 }
+
+/**
+ * Estimates the resources a pipeline needs (CPU, memory, GPU, model downloads) before
+ * creating a session from it
+ * @param yaml - Pipeline definition in YAML format
+ * @returns A promise that resolves to the estimate
+ */
+export async function estimatePipeline(yaml: string): Promise<PipelineEstimate> {
+  const apiUrl = getApiUrl();
+  const endpoint = `${apiUrl}/api/v1/estimate`;
+
+  const response = await fetch(endpoint, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/json',
+    },
+    body: JSON.stringify({ yaml }),
+  });
+
+  if (!response.ok) {
+    const errorText = await response.text();
+    throw new Error(errorText || `Failed to estimate pipeline: ${response.statusText}`);
+  }
+
+  return response.json();
+}
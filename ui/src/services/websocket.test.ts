@@ -344,6 +344,60 @@ describe('WebSocketService', () => {
       });
     });
 
+    it('should re-fetch the pipeline when a snapshot shows drift', async () => {
+      service.subscribeToSession('session-1');
+      useSessionStore.getState().setPipeline('session-1', {
+        name: null,
+        description: null,
+        mode: 'dynamic',
+        nodes: {},
+        connections: [],
+      });
+
+      const ws = service['ws']! as unknown as MockWebSocket;
+      ws.send.mockClear();
+
+      // Local view has no nodes but the server reports two: an event was missed.
+      ws.simulateMessage(
+        JSON.stringify({
+          type: 'event',
+          payload: {
+            event: 'pipelinesnapshot',
+            session_id: 'session-1',
+            revision: 1,
+            hash: 'abc',
+            node_count: 2,
+            connection_count: 0,
+          },
+        })
+      );
+
+      const sendCalls = ws.send.mock.calls as string[][];
+      expect(sendCalls).toHaveLength(1);
+      const request = JSON.parse(sendCalls[0][0]);
+      expect(request.payload).toEqual({ action: 'getpipeline', session_id: 'session-1' });
+
+      const nodes = {
+        a: { kind: 'audio::gain', params: null, state: null },
+        b: { kind: 'audio::gain', params: null, state: null },
+      };
+      ws.simulateMessage(
+        JSON.stringify({
+          type: 'response',
+          correlation_id: request.correlation_id,
+          payload: {
+            action: 'pipeline',
+            pipeline: { name: null, description: null, mode: 'dynamic', nodes, connections: [] },
+          },
+        })
+      );
+      await Promise.resolve();
+      await Promise.resolve();
+
+      const pipeline = useSessionStore.getState().getSession('session-1')?.pipeline;
+      expect(Object.keys(pipeline?.nodes ?? {})).toEqual(['a', 'b']);
+    });
+
     it('should notify message handlers', () => {
       const handler = vi.fn();
       service.onMessage(handler);
@@ -4,6 +4,7 @@
 
 import { v4 as uuidv4 } from 'uuid';
 
+import { useMaintenanceStore } from '@/stores/maintenanceStore';
 import { useNodeParamsStore } from '@/stores/nodeParamsStore';
 import { useSessionStore } from '@/stores/sessionStore';
 import { useTelemetryStore, parseTelemetryEvent } from '@/stores/telemetryStore';
@@ -25,6 +26,7 @@ type NodeRemovedPayload = Extract<WsEventPayload, { event: 'noderemoved' }>;
 type ConnectionAddedPayload = Extract<WsEventPayload, { event: 'connectionadded' }>;
 type ConnectionRemovedPayload = Extract<WsEventPayload, { event: 'connectionremoved' }>;
 type NodeTelemetryPayload = Extract<WsEventPayload, { event: 'nodetelemetry' }>;
+type PipelineSnapshotPayload = Extract<WsEventPayload, { event: 'pipelinesnapshot' }>;
 
 interface PendingRequest {
   resolve: (response: Response) => void;
@@ -44,6 +46,8 @@ export class WebSocketService {
   private messageQueue: Request[] = [];
   private isIntentionallyClosed = false;
   private subscribedSessions: Set<string> = new Set();
+  private pipelineRevisions: Map<string, number> = new Map();
+  private resyncingSessions: Set<string> = new Set();
 
   constructor(url: string) {
     this.url = url;
@@ -195,6 +199,15 @@ export class WebSocketService {
       case 'nodetelemetry':
         this.handleNodeTelemetry(payload);
         break;
+      case 'pipelinesnapshot':
+        this.handlePipelineSnapshot(payload);
+        break;
+      case 'nodekinddeprecated':
+        logger.warn(`Node ${payload.node_id}: ${payload.message}`);
+        break;
+      case 'readonlychanged':
+        useMaintenanceStore.getState().setReadOnly(payload.enabled, payload.message);
+        break;
       default:
         break;
     }
@@ -202,6 +215,7 @@ export class WebSocketService {
 
   private handleSessionDestroyed(payload: SessionDestroyedPayload): void {
     this.subscribedSessions.delete(payload.session_id);
+    this.pipelineRevisions.delete(payload.session_id);
     useSessionStore.getState().clearSession(payload.session_id);
     useNodeParamsStore.getState().resetSession(payload.session_id);
     useTelemetryStore.getState().clearSession(payload.session_id);
@@ -234,10 +248,10 @@ export class WebSocketService {
   }
 
   private handleNodeAdded(payload: NodeAddedPayload): void {
-    const { session_id, node_id, kind, params } = payload;
+    const { session_id, node_id, kind, params, label, notes } = payload;
     useSessionStore
       .getState()
-      .addNode(session_id, node_id, { kind, params, state: 'Initializing' });
+      .addNode(session_id, node_id, { kind, params, label, notes, state: 'Initializing' });
   }
 
   private handleNodeRemoved(payload: NodeRemovedPayload): void {
@@ -269,6 +283,51 @@ export class WebSocketService {
     useTelemetryStore.getState().addEvent(telemetryEvent);
   }
 
+  // Compares the server's pipeline summary with the local view and re-fetches the full
+  // pipeline when a revision was skipped (missed events) or the counts no longer match.
+  private handlePipelineSnapshot(payload: PipelineSnapshotPayload): void {
+    const { session_id, node_count, connection_count } = payload;
+    if (!this.subscribedSessions.has(session_id)) return;
+
+    const revision = Number(payload.revision);
+    const lastRevision = this.pipelineRevisions.get(session_id);
+    this.pipelineRevisions.set(session_id, revision);
+
+    const pipeline = useSessionStore.getState().getSession(session_id)?.pipeline;
+    const missedEvents = lastRevision !== undefined && revision > lastRevision + 1;
+    const drifted =
+      !pipeline ||
+      Object.keys(pipeline.nodes).length !== node_count ||
+      pipeline.connections.length !== connection_count;
+
+    if (missedEvents || drifted) {
+      logger.info('Pipeline drift detected, re-fetching session:', session_id);
+      this.resyncPipeline(session_id);
+    }
+  }
+
+  private resyncPipeline(sessionId: string): void {
+    if (this.resyncingSessions.has(sessionId)) return;
+    this.resyncingSessions.add(sessionId);
+
+    this.send({
+      type: 'request' as MessageType,
+      correlation_id: uuidv4(),
+      payload: { action: 'getpipeline' as const, session_id: sessionId },
+    })
+      .then((response) => {
+        if (response.payload.action === 'pipeline' && this.subscribedSessions.has(sessionId)) {
+          useSessionStore.getState().setPipeline(sessionId, response.payload.pipeline);
+        }
+      })
+      .catch((error) => {
+        logger.warn('Failed to re-fetch pipeline:', error);
+      })
+      .finally(() => {
+        this.resyncingSessions.delete(sessionId);
+      });
+  }
+
   send(request: Request): Promise<Response> {
     return new Promise((resolve, reject) => {
       const correlationId = request.correlation_id || uuidv4();
@@ -353,6 +412,7 @@ export class WebSocketService {
 
   unsubscribeFromSession(sessionId: string): void {
     this.subscribedSessions.delete(sessionId);
+    this.pipelineRevisions.delete(sessionId);
     // Keep the session entry so the Monitor session list can display the latest known status
     // even when a session is not actively selected/subscribed.
     useSessionStore.getState().setConnected(sessionId, false);
@@ -0,0 +1,18 @@
+// SPDX-FileCopyrightText: © 2025 StreamKit Contributors
+//
+// SPDX-License-Identifier: MPL-2.0
+
+import { create } from 'zustand';
+
+interface MaintenanceStore {
+  /** Server rejects changes until an admin leaves read-only mode */
+  readOnly: boolean;
+  banner: string | null;
+  setReadOnly: (readOnly: boolean, banner?: string | null) => void;
+}
+
+export const useMaintenanceStore = create<MaintenanceStore>((set) => ({
+  readOnly: false,
+  banner: null,
+  setReadOnly: (readOnly, banner = null) => set({ readOnly, banner: readOnly ? banner : null }),
+}));
@@ -156,6 +156,8 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
           [nodeId]: {
             kind: nodeData.kind,
             params: nodeData.params,
+            label: nodeData.label,
+            notes: nodeData.notes,
             state: nodeData.state ?? null,
           },
         },
@@ -17,6 +17,8 @@ function makeSourceNodeDef(kind: string, producesType: PacketType): NodeDefiniti
     outputs: [{ name: 'out', produces_type: producesType, cardinality: 'Broadcast' }],
     categories: [],
     bidirectional: false,
+    deprecated: false,
+    aliases: [],
   };
   return nodeDef;
 }
@@ -29,6 +31,8 @@ function makeSinkNodeDef(kind: string, acceptsTypes: PacketType[] = ['Any']): No
     outputs: [],
     categories: [],
     bidirectional: false,
+    deprecated: false,
+    aliases: [],
   };
 }
 
@@ -84,6 +88,8 @@ nodes:
         ],
         categories: [],
         bidirectional: false,
+        deprecated: false,
+        aliases: [],
       },
       {
         kind: 'plugin::native::whisper',
@@ -100,6 +106,8 @@ nodes:
         outputs: [{ name: 'out', produces_type: 'Transcription', cardinality: 'Broadcast' }],
         categories: [],
         bidirectional: false,
+        deprecated: false,
+        aliases: [],
       },
     ];
 
@@ -24,7 +24,7 @@ import { canConnect, formatPacketType, resolveOutputType } from './packetTypes';
 
 export type EngineMode = 'oneshot' | 'dynamic';
 
-type ConnectionMode = 'reliable' | 'best_effort';
+type ConnectionMode = 'reliable' | 'best_effort' | 'feedback';
 
 type EditorNodeData = {
   label: string;
@@ -40,7 +40,7 @@ type EditorNodeData = {
   onLabelChange?: (nodeId: string, newLabel: string) => void;
 };
 
-type NeedsDependency = string | { node: string; mode?: ConnectionMode };
+type NeedsDependency = string | { node: string; from_pin?: string; mode?: ConnectionMode };
 
 type ImportedNodeConfig = {
   kind: string;
@@ -224,6 +224,7 @@ function validateConnectionCompatibility(
   sourceNode: Node<EditorNodeData>,
   targetNode: Node<EditorNodeData>,
   needsIndex: number,
+  fromPin: string | undefined,
   sourceLabel: string,
   targetLabel: string,
   nodes: Node<EditorNodeData>[],
@@ -238,8 +239,10 @@ function validateConnectionCompatibility(
     accepts_types: PacketType[];
   }>;
 
-  // Get default output pin (first output)
-  const sourceOutput = sourceOutputs[0];
+  // Use the named output pin, or the default (first) output
+  const sourceOutput = fromPin
+    ? sourceOutputs.find((output) => output.name === fromPin)
+    : sourceOutputs[0];
   // For nodes with multiple needs, use the input pin corresponding to the needs index
   const targetInput = targetInputs[needsIndex] || targetInputs[0];
 
@@ -274,6 +277,7 @@ function createEdgeForConnection(
   sourceId: string,
   targetId: string,
   needsIndex: number,
+  fromPin: string | undefined,
   mode: ConnectionMode | undefined,
   nodeByLabel: Map<string, Node<EditorNodeData>>,
   newEdges: Edge[]
@@ -287,7 +291,9 @@ function createEdgeForConnection(
     accepts_types: PacketType[];
   }>;
 
-  const sourceOutput = sourceOutputs[0];
+  const sourceOutput = fromPin
+    ? sourceOutputs.find((output) => output.name === fromPin)
+    : sourceOutputs[0];
   const targetInput = targetInputs[needsIndex] || targetInputs[0];
 
   if (!sourceOutput || !targetInput) return;
@@ -313,7 +319,7 @@ function createEdgeForConnection(
     targetHandle: targetHandleName,
     data: {
       resolvedType,
-      ...(mode === 'best_effort' ? { mode } : {}),
+      ...(mode && mode !== 'reliable' ? { mode } : {}),
     },
   });
 }
@@ -340,6 +346,7 @@ function createEdgesFromPipeline(
       needs.forEach((dep: NeedsDependency, needsIndex: number) => {
         const sourceLabel = typeof dep === 'string' ? dep : dep.node;
         const mode: ConnectionMode | undefined = typeof dep === 'string' ? undefined : dep.mode;
+        const fromPin = typeof dep === 'string' ? undefined : dep.from_pin;
 
         const sourceId = labelToIdMap.get(sourceLabel);
 
@@ -358,6 +365,7 @@ function createEdgesFromPipeline(
           sourceNode,
           targetNode,
           needsIndex,
+          fromPin,
           sourceLabel,
           label,
           Array.from(nodeByLabel.values()),
@@ -371,6 +379,7 @@ function createEdgesFromPipeline(
           sourceId,
           targetId,
           needsIndex,
+          fromPin,
           mode,
           nodeByLabel,
           newEdges